@@ -78,6 +78,27 @@ impl ControlPanel {
                 .service(endpoints::stats)
                 .service(endpoints::get_config)
                 .service(endpoints::set_config)
+                .service(endpoints::transfers)
+                .service(endpoints::initiate_transfer)
+                .service(endpoints::confirm_transfer)
+                .service(endpoints::cancel_transfer)
+                .service(endpoints::unwinds)
+                .service(endpoints::start_unwind)
+                .service(endpoints::cancel_unwind)
+                .service(endpoints::strategy_parameters)
+                .service(endpoints::set_strategy_parameters)
+                .service(endpoints::dump_reservations)
+                .service(endpoints::dump_book)
+                .service(endpoints::probe_book)
+                .service(endpoints::book_consistency)
+                .service(endpoints::latency_report)
+                .service(endpoints::search_symbols)
+                .service(endpoints::pull_quotes)
+                .service(endpoints::pull_triggers_report)
+                .service(endpoints::dump_timeouts)
+                .service(endpoints::dump_tasks)
+                .service(endpoints::export_support_bundle)
+                .service(endpoints::export_audit)
                 .service(
                     actix_files::Files::new("/", webui_dir)
                         .use_last_modified(true)
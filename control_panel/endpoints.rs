@@ -1,5 +1,6 @@
 use actix_web::{get, post, web, HttpResponse, Responder};
 use futures::FutureExt;
+use serde::Deserialize;
 
 use crate::control_panel::{send_request, DataWebMmbRpcClient};
 
@@ -41,3 +42,280 @@ pub(super) async fn set_config(body: web::Bytes, client: DataWebMmbRpcClient) ->
 pub(super) async fn stats(client: DataWebMmbRpcClient) -> impl Responder {
     send_request(client, |client| client.stats().boxed()).await
 }
+
+#[get("/transfers")]
+pub(super) async fn transfers(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.transfers().boxed()).await
+}
+
+#[post("/transfers")]
+pub(super) async fn initiate_transfer(
+    body: web::Bytes,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let request = match String::from_utf8((&body).to_vec()) {
+        Ok(request) => request,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Failed to convert transfer request({body:?}) to utf8 string: {err}",
+            ))
+        }
+    };
+
+    send_request(client, move |client| {
+        client.initiate_transfer(request.clone()).boxed()
+    })
+    .await
+}
+
+#[post("/transfers/{transfer_id}/confirm")]
+pub(super) async fn confirm_transfer(
+    transfer_id: web::Path<u64>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let transfer_id = transfer_id.into_inner();
+    send_request(client, move |client| {
+        client.confirm_transfer(transfer_id).boxed()
+    })
+    .await
+}
+
+#[post("/transfers/{transfer_id}/cancel")]
+pub(super) async fn cancel_transfer(
+    transfer_id: web::Path<u64>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let transfer_id = transfer_id.into_inner();
+    send_request(client, move |client| {
+        client.cancel_transfer(transfer_id).boxed()
+    })
+    .await
+}
+
+#[get("/unwinds")]
+pub(super) async fn unwinds(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.unwinds().boxed()).await
+}
+
+#[post("/unwinds")]
+pub(super) async fn start_unwind(body: web::Bytes, client: DataWebMmbRpcClient) -> impl Responder {
+    let request = match String::from_utf8((&body).to_vec()) {
+        Ok(request) => request,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Failed to convert unwind request({body:?}) to utf8 string: {err}",
+            ))
+        }
+    };
+
+    send_request(client, move |client| {
+        client.start_unwind(request.clone()).boxed()
+    })
+    .await
+}
+
+#[post("/unwinds/{unwind_id}/cancel")]
+pub(super) async fn cancel_unwind(
+    unwind_id: web::Path<u64>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let unwind_id = unwind_id.into_inner();
+    send_request(client, move |client| {
+        client.cancel_unwind(unwind_id).boxed()
+    })
+    .await
+}
+
+#[get("/strategy_parameters")]
+pub(super) async fn strategy_parameters(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.strategy_parameters().boxed()).await
+}
+
+#[post("/strategy_parameters")]
+pub(super) async fn set_strategy_parameters(
+    body: web::Bytes,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let parameters = match String::from_utf8((&body).to_vec()) {
+        Ok(parameters) => parameters,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!(
+                "Failed to convert strategy parameters({body:?}) to utf8 string: {err}",
+            ))
+        }
+    };
+
+    send_request(client, move |client| {
+        client.set_strategy_parameters(parameters.clone()).boxed()
+    })
+    .await
+}
+
+#[get("/diagnostics/reservations")]
+pub(super) async fn dump_reservations(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.dump_reservations().boxed()).await
+}
+
+const DEFAULT_ORDER_BOOK_DEPTH: usize = 10;
+
+#[derive(Deserialize)]
+pub(super) struct DumpBookQuery {
+    exchange_account_id: String,
+    currency_pair: String,
+    depth: Option<usize>,
+}
+
+#[get("/diagnostics/book")]
+pub(super) async fn dump_book(
+    query: web::Query<DumpBookQuery>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let DumpBookQuery {
+        exchange_account_id,
+        currency_pair,
+        depth,
+    } = query.into_inner();
+    let depth = depth.unwrap_or(DEFAULT_ORDER_BOOK_DEPTH);
+
+    send_request(client, move |client| {
+        client
+            .dump_book(exchange_account_id.clone(), currency_pair.clone(), depth)
+            .boxed()
+    })
+    .await
+}
+
+#[get("/diagnostics/probe_book")]
+pub(super) async fn probe_book(
+    query: web::Query<DumpBookQuery>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let DumpBookQuery {
+        exchange_account_id,
+        currency_pair,
+        depth,
+    } = query.into_inner();
+    let depth = depth.unwrap_or(DEFAULT_ORDER_BOOK_DEPTH);
+
+    send_request(client, move |client| {
+        client
+            .probe_book(exchange_account_id.clone(), currency_pair.clone(), depth)
+            .boxed()
+    })
+    .await
+}
+
+#[get("/diagnostics/book_consistency")]
+pub(super) async fn book_consistency(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.book_consistency().boxed()).await
+}
+
+#[get("/diagnostics/latency")]
+pub(super) async fn latency_report(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.latency_report().boxed()).await
+}
+
+#[derive(Deserialize)]
+pub(super) struct SearchSymbolsQuery {
+    query: String,
+}
+
+#[get("/symbols")]
+pub(super) async fn search_symbols(
+    query: web::Query<SearchSymbolsQuery>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let SearchSymbolsQuery { query } = query.into_inner();
+
+    send_request(client, move |client| {
+        client.search_symbols(query.clone()).boxed()
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub(super) struct PullQuotesQuery {
+    exchange_account_id: String,
+    currency_pair: Option<String>,
+    reason: String,
+}
+
+#[post("/pull_quotes")]
+pub(super) async fn pull_quotes(
+    query: web::Query<PullQuotesQuery>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let PullQuotesQuery {
+        exchange_account_id,
+        currency_pair,
+        reason,
+    } = query.into_inner();
+
+    send_request(client, move |client| {
+        client
+            .pull_quotes(
+                exchange_account_id.clone(),
+                currency_pair.clone(),
+                reason.clone(),
+            )
+            .boxed()
+    })
+    .await
+}
+
+#[get("/pull_triggers")]
+pub(super) async fn pull_triggers_report(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.pull_triggers_report().boxed()).await
+}
+
+#[get("/diagnostics/timeouts")]
+pub(super) async fn dump_timeouts(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.dump_timeouts().boxed()).await
+}
+
+#[get("/diagnostics/tasks")]
+pub(super) async fn dump_tasks(client: DataWebMmbRpcClient) -> impl Responder {
+    send_request(client, |client| client.dump_tasks().boxed()).await
+}
+
+const DEFAULT_SUPPORT_BUNDLE_LOG_LINES: usize = 1000;
+
+#[derive(Deserialize)]
+pub(super) struct SupportBundleQuery {
+    log_lines: Option<usize>,
+}
+
+#[post("/support_bundle")]
+pub(super) async fn export_support_bundle(
+    query: web::Query<SupportBundleQuery>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let log_lines = query
+        .into_inner()
+        .log_lines
+        .unwrap_or(DEFAULT_SUPPORT_BUNDLE_LOG_LINES);
+
+    send_request(client, move |client| {
+        client.export_support_bundle(log_lines).boxed()
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+pub(super) struct AuditExportQuery {
+    from: String,
+    to: String,
+}
+
+#[post("/audit_export")]
+pub(super) async fn export_audit(
+    query: web::Query<AuditExportQuery>,
+    client: DataWebMmbRpcClient,
+) -> impl Responder {
+    let AuditExportQuery { from, to } = query.into_inner();
+
+    send_request(client, move |client| {
+        client.export_audit(from.clone(), to.clone()).boxed()
+    })
+    .await
+}
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_utils::checked_decimal::CheckedDecimal;
 use mmb_utils::decimal_inverse_sign::DecimalInverseSign;
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::{nothing_to_do, DateTime};
@@ -30,10 +31,11 @@ use crate::misc::service_value_tree::ServiceValueTree;
 #[double]
 use crate::misc::time::time_manager;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
-use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
+use crate::settings::ReservationFeeSettings;
+use mmb_domain::exchanges::symbol::{BeforeAfter, ContractType, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId};
 use mmb_domain::order::snapshot::ReservationId;
-use mmb_domain::order::snapshot::{ClientOrderFillId, ClientOrderId, OrderSide};
+use mmb_domain::order::snapshot::{ClientOrderFillId, ClientOrderId, OrderRole, OrderSide};
 
 use super::balance_reservation_preset::BalanceReservationPreset;
 
@@ -57,6 +59,8 @@ pub(crate) struct BalanceReservationManager {
     pub balance_reservation_storage: BalanceReservationStorage,
 
     pub(crate) is_call_from_clone: bool,
+
+    reservation_fee: Option<ReservationFeeSettings>,
 }
 
 impl BalanceReservationManager {
@@ -71,9 +75,14 @@ impl BalanceReservationManager {
             ),
             balance_reservation_storage: BalanceReservationStorage::new(),
             is_call_from_clone: false,
+            reservation_fee: None,
         }
     }
 
+    pub fn set_reservation_fee(&mut self, reservation_fee: Option<ReservationFeeSettings>) {
+        self.reservation_fee = reservation_fee;
+    }
+
     pub fn exchanges_by_id(&self) -> &HashMap<ExchangeAccountId, Arc<Exchange>> {
         self.currency_pair_to_symbol_converter.exchanges_by_id()
     }
@@ -390,14 +399,18 @@ impl BalanceReservationManager {
 
                 explanation.with_reason(|| format!("free_amount_in_amount_currency_code with leverage and amount_multiplier = {free_amount_in_amount_currency_code}"));
 
-                let mut free_amount_in_currency_code = symbol
-                    .convert_amount_from_amount_currency_code(
+                let free_amount_in_currency_code = symbol
+                    .try_convert_amount_from_amount_currency_code(
                         currency_code,
                         free_amount_in_amount_currency_code,
                         price,
-                    );
-                free_amount_in_currency_code /= leverage;
-                free_amount_in_currency_code *= symbol.amount_multiplier;
+                    )
+                    .and_then(|x| x.try_div(leverage))
+                    .and_then(|x| x.try_mul(symbol.amount_multiplier))
+                    .map_err(|error| {
+                        log::error!("Failed to calculate free amount for {request:?}: {error:?}")
+                    })
+                    .ok()?;
 
                 explanation.with_reason(|| {
                     format!(
@@ -433,15 +446,20 @@ impl BalanceReservationManager {
             .get_by_balance_request(&request)
             .is_some()
         {
-            balance_in_currency_code = self.get_balance_with_applied_limits(
-                &request,
-                symbol.clone(),
-                side,
-                balance_in_currency_code,
-                price,
-                leverage,
-                explanation,
-            );
+            balance_in_currency_code = self
+                .get_balance_with_applied_limits(
+                    &request,
+                    symbol.clone(),
+                    side,
+                    balance_in_currency_code,
+                    price,
+                    leverage,
+                    explanation,
+                )
+                .map_err(|error| {
+                    log::error!("Failed to apply amount limit for {request:?}: {error:?}")
+                })
+                .ok()?;
         }
 
         explanation.with_reason(|| {
@@ -507,7 +525,7 @@ impl BalanceReservationManager {
         price: Price,
         leverage: Decimal,
         explanation: &mut Option<Explanation>,
-    ) -> Amount {
+    ) -> Result<Amount> {
         let position = self.get_position_values(
             request.configuration_descriptor,
             request.exchange_account_id,
@@ -549,19 +567,20 @@ impl BalanceReservationManager {
         });
 
         //AmountLimit is applied to full amount
-        balance_in_currency_code *= leverage;
-        balance_in_currency_code /= symbol.amount_multiplier;
+        balance_in_currency_code = balance_in_currency_code
+            .try_mul(leverage)?
+            .try_div(symbol.amount_multiplier)?;
         explanation.with_reason(|| {
             format!(
                 "balance_in_currency_code with leverage and multiplier: {balance_in_currency_code}"
             )
         });
 
-        let balance_in_amount_currency = symbol.convert_amount_into_amount_currency_code(
+        let balance_in_amount_currency = symbol.try_convert_amount_into_amount_currency_code(
             request.currency_code,
             balance_in_currency_code,
             price,
-        );
+        )?;
         explanation.with_reason(|| {
             format!("balance_in_amount_currency with leverage and multiplier: {balance_in_amount_currency}")
         });
@@ -572,18 +591,20 @@ impl BalanceReservationManager {
             format!("limited_balance_in_amount_currency: {limited_balance_in_amount_currency}")
         });
 
-        let mut limited_balance_in_currency_code = symbol.convert_amount_from_amount_currency_code(
-            request.currency_code,
-            limited_balance_in_amount_currency,
-            price,
-        );
+        let mut limited_balance_in_currency_code = symbol
+            .try_convert_amount_from_amount_currency_code(
+                request.currency_code,
+                limited_balance_in_amount_currency,
+                price,
+            )?;
         explanation.with_reason(|| {
             format!("limited_balance_in_currency_code: {limited_balance_in_currency_code}")
         });
 
         //converting back to pure balance
-        limited_balance_in_currency_code /= leverage;
-        limited_balance_in_currency_code *= symbol.amount_multiplier;
+        limited_balance_in_currency_code = limited_balance_in_currency_code
+            .try_div(leverage)?
+            .try_mul(symbol.amount_multiplier)?;
         explanation.with_reason(|| {
             format!("limited_balance_in_currency_code without leverage and multiplier: {limited_balance_in_currency_code}")
         });
@@ -592,7 +613,7 @@ impl BalanceReservationManager {
             log::warn!("Balance {limited_balance_in_currency_code} < 0 ({total_amount_limit_in_amount_currency} - ({reserved_amount_in_amount_currency} + {position_amount_in_amount_currency}) {balance_in_amount_currency} for {request:?} {symbol:?}");
         };
 
-        dec!(0).max(limited_balance_in_currency_code)
+        Ok(dec!(0).max(limited_balance_in_currency_code))
     }
 
     fn get_untouchable_amount(symbol: Arc<Symbol>, amount: Amount) -> Amount {
@@ -733,7 +754,7 @@ impl BalanceReservationManager {
                 reservation.symbol.clone(),
                 -cost,
                 reservation.price,
-            );
+            )?;
         }
 
         reservation.unreserved_amount += amount_diff_in_amount_currency;
@@ -812,21 +833,45 @@ impl BalanceReservationManager {
         if !symbol.is_derivative {
             bail!("restore_fill_amount_position is available only for derivative exchanges");
         }
+        self.set_fill_amount_position(exchange_account_id, symbol.currency_pair(), new_position);
+        Ok(())
+    }
+
+    /// Positions on derivative exchanges are restored from exchange positions, so for spot
+    /// markets position by fill amount can be restored only from account trades history
+    pub(crate) fn restore_fill_amount_position_from_history(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        new_position: Decimal,
+    ) -> Result<()> {
+        if symbol.is_derivative {
+            bail!("restore_fill_amount_position_from_history is available only for spot exchanges");
+        }
+        self.set_fill_amount_position(exchange_account_id, symbol.currency_pair(), new_position);
+        Ok(())
+    }
+
+    fn set_fill_amount_position(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+        new_position: Decimal,
+    ) {
         let previous_value = self
             .position_by_fill_amount_in_amount_currency
-            .get(exchange_account_id, symbol.currency_pair());
+            .get(exchange_account_id, currency_pair);
 
         let now = time_manager::now();
 
         self.position_by_fill_amount_in_amount_currency.set(
             exchange_account_id,
-            symbol.currency_pair(),
+            currency_pair,
             previous_value,
             new_position,
             None,
             now,
         );
-        Ok(())
     }
 
     pub fn get_last_position_change_before_period(
@@ -866,7 +911,7 @@ impl BalanceReservationManager {
         configuration_descriptor: ConfigurationDescriptor,
         exchange_account_id: ExchangeAccountId,
         symbol: Arc<Symbol>,
-    ) -> (Amount, CurrencyCode) {
+    ) -> Result<(Amount, CurrencyCode)> {
         let mut change_amount_in_currency = dec!(0);
 
         let currency_code = symbol.get_trade_code(side, before_after);
@@ -883,10 +928,13 @@ impl BalanceReservationManager {
                 symbol.clone(),
                 -fill_amount,
                 price,
-            );
+            )?;
 
-            change_amount_in_currency =
-                symbol.convert_amount_from_amount_currency_code(currency_code, fill_amount, price);
+            change_amount_in_currency = symbol.try_convert_amount_from_amount_currency_code(
+                currency_code,
+                fill_amount,
+                price,
+            )?;
         }
         if symbol.amount_currency_code == currency_code {
             let mut position_change = fill_amount;
@@ -911,16 +959,17 @@ impl BalanceReservationManager {
                     symbol.clone(),
                     diff_in_amount_currency,
                     price,
-                );
+                )?;
 
-                change_amount_in_currency = symbol.convert_amount_from_amount_currency_code(
+                change_amount_in_currency = symbol.try_convert_amount_from_amount_currency_code(
                     currency_code,
                     diff_in_amount_currency,
                     price,
-                );
+                )?;
 
-                // reversed derivative
-                if symbol.amount_currency_code == symbol.base_currency_code() {
+                // amount of inverse contracts is specified in quote currency,
+                // other contracts change position in base currency with reversed sign
+                if symbol.contract_type != ContractType::Inverse {
                     position_change.inverse_sign();
                 }
             }
@@ -934,7 +983,7 @@ impl BalanceReservationManager {
             );
             self.validate_position_and_limits(&request);
         }
-        (change_amount_in_currency, currency_code)
+        Ok((change_amount_in_currency, currency_code))
     }
 
     fn validate_position_and_limits(&self, request: &BalanceRequest) {
@@ -1011,7 +1060,7 @@ impl BalanceReservationManager {
         configuration_descriptor: ConfigurationDescriptor,
         exchange_account_id: ExchangeAccountId,
         symbol: Arc<Symbol>,
-    ) {
+    ) -> Result<()> {
         let leverage = self.get_leverage(exchange_account_id, symbol.currency_pair());
         if !symbol.is_derivative || symbol.balance_currency_code == Some(commission_currency_code) {
             let request = BalanceRequest::new(
@@ -1030,19 +1079,22 @@ impl BalanceReservationManager {
                 symbol.currency_pair(),
                 converted_commission_currency_code,
             );
-            let commission_in_amount_currency = symbol.convert_amount_into_amount_currency_code(
-                converted_commission_currency_code,
-                converted_commission_amount,
-                price,
-            );
+            let commission_in_amount_currency = symbol
+                .try_convert_amount_into_amount_currency_code(
+                    converted_commission_currency_code,
+                    converted_commission_amount,
+                    price,
+                )?;
             let res_commission_amount_in_amount_currency = commission_in_amount_currency / leverage;
             self.virtual_balance_holder.add_balance_by_symbol(
                 &request,
                 symbol,
                 -res_commission_amount_in_amount_currency,
                 price,
-            );
+            )?;
         }
+
+        Ok(())
     }
 
     pub fn approve_reservation(
@@ -1121,10 +1173,19 @@ impl BalanceReservationManager {
             // special case for derivatives because balance for AmountCurrency is auto-calculated
             if src_reservation.symbol.is_derivative {
                 // check if we have enough balance for the operation
-                let add_amount = src_reservation.convert_in_reservation_currency(amount_to_move);
-                let sub_amount = dst_reservation.convert_in_reservation_currency(amount_to_move);
-
-                let balance_diff_amount = add_amount - sub_amount;
+                let balance_diff_amount = match src_reservation
+                    .convert_in_reservation_currency(amount_to_move)
+                    .and_then(|add_amount| {
+                        let sub_amount =
+                            dst_reservation.convert_in_reservation_currency(amount_to_move)?;
+                        Ok(add_amount - sub_amount)
+                    }) {
+                    Ok(balance_diff_amount) => balance_diff_amount,
+                    Err(error) => {
+                        log::error!("Can't transfer {amount_to_move} from {src_reservation_id} to {dst_reservation_id}: {error:?}");
+                        return false;
+                    }
+                };
 
                 let available_balance = self
                     .try_get_available_balance(
@@ -1148,12 +1209,15 @@ impl BalanceReservationManager {
         }
 
         // we can safely move amount ignoring price because of check that have been done before
-        self.transfer_amount(
+        if let Err(error) = self.transfer_amount(
             src_reservation_id,
             dst_reservation_id,
             amount_to_move,
             client_order_id,
-        );
+        ) {
+            log::error!("Failed to transfer {amount_to_move} from {src_reservation_id} to {dst_reservation_id}: {error:?}");
+            return false;
+        }
         true
     }
 
@@ -1163,7 +1227,7 @@ impl BalanceReservationManager {
         dst_reservation_id: ReservationId,
         amount_to_move: Amount,
         client_order_id: &Option<ClientOrderId>,
-    ) {
+    ) -> Result<()> {
         let src_reservation = self.get_reservation_expected(src_reservation_id);
         let new_src_unreserved_amount = src_reservation.unreserved_amount - amount_to_move;
         log::info!("trying to update src unreserved amount for transfer: {src_reservation:?} {new_src_unreserved_amount} {client_order_id:?}");
@@ -1173,7 +1237,7 @@ impl BalanceReservationManager {
             client_order_id,
             true,
             dec!(0),
-        );
+        )?;
 
         let dst_reservation = self.get_reservation_expected(dst_reservation_id);
         let new_dst_unreserved_amount = dst_reservation.unreserved_amount + amount_to_move;
@@ -1184,9 +1248,10 @@ impl BalanceReservationManager {
             client_order_id,
             false,
             -src_cost_diff,
-        );
+        )?;
 
         log::info!("Successfully transferred {amount_to_move} from {src_reservation_id} to {dst_reservation_id}");
+        Ok(())
     }
 
     fn update_unreserved_amount_for_transfer(
@@ -1196,7 +1261,7 @@ impl BalanceReservationManager {
         client_order_id: &Option<ClientOrderId>,
         is_src_request: bool,
         target_cost_diff: Decimal,
-    ) -> Decimal {
+    ) -> Result<Decimal> {
         let approve_time = time_manager::now();
         let reservation = self.get_mut_reservation_expected(reservation_id);
         // we should check the case when we have insignificant calculation errors
@@ -1270,7 +1335,7 @@ impl BalanceReservationManager {
             buff_symbol,
             -cost_diff,
             buff_price,
-        );
+        )?;
         let reservation = self.get_mut_reservation_expected(reservation_id);
 
         reservation.cost += cost_diff;
@@ -1295,7 +1360,7 @@ impl BalanceReservationManager {
             reservation.price,
             reservation.amount,
         );
-        cost_diff
+        Ok(cost_diff)
     }
 
     pub fn try_reserve_multiple(
@@ -1324,7 +1389,10 @@ impl BalanceReservationManager {
         reserve_parameters: &ReserveParameters,
         explanation: &mut Option<Explanation>,
     ) -> Option<ReservationId> {
-        let can_reserve_result = self.can_reserve_core(reserve_parameters, explanation);
+        let can_reserve_result = self
+            .can_reserve_core(reserve_parameters, explanation)
+            .map_err(|error| log::error!("Failed to reserve {reserve_parameters:?}: {error:?}"))
+            .ok()?;
         if !can_reserve_result.can_reserve {
             log::info!(
                 "Failed to reserve {} {} {:?} {} {} {reserve_parameters:?}",
@@ -1388,45 +1456,47 @@ impl BalanceReservationManager {
         &self,
         reserve_parameters: &ReserveParameters,
         explanation: &mut Option<Explanation>,
-    ) -> CanReserveResult {
-        let preset = self.get_currency_code_and_reservation_amount(reserve_parameters, explanation);
+    ) -> Result<CanReserveResult> {
+        let preset =
+            self.get_currency_code_and_reservation_amount(reserve_parameters, explanation)?;
         //We set includeFreeAmount to false because we already took FreeAmount into consideration while calculating the preset
         //Otherwise we would count FreeAmount twice which is wrong
         let old_balance = self.get_available_balance(reserve_parameters, false, explanation);
 
         let preset_cost = preset.cost_in_reservation_currency_code;
+        let expected_fee = preset.expected_fee_in_reservation_currency_code;
 
         let new_balance = old_balance - preset_cost;
 
         explanation.with_reason(|| {
             format!(
-                "old_balance: {old_balance} preset_cost: {preset_cost} new_balance: {new_balance}"
+                "old_balance: {old_balance} preset_cost: {preset_cost} expected_fee: {expected_fee} new_balance: {new_balance}"
             )
         });
 
         let (can_reserve, potential_position) = self.can_reserve_with_limit(reserve_parameters);
 
         if !can_reserve {
-            return CanReserveResult {
+            return Ok(CanReserveResult {
                 can_reserve: false,
                 preset,
                 potential_position,
                 old_balance,
                 new_balance,
-            };
+            });
         }
 
         //Spot trading might need a more precise solution
         let rounded_balance = reserve_parameters
             .symbol
             .round_to_remove_amount_precision_error_expected(new_balance);
-        CanReserveResult {
+        Ok(CanReserveResult {
             can_reserve: rounded_balance >= dec!(0),
             preset,
             potential_position,
             old_balance,
             new_balance,
-        }
+        })
     }
 
     /// The sign of returned Decimal value calculate over ReserveParameters::order_side.
@@ -1495,7 +1565,7 @@ impl BalanceReservationManager {
         &self,
         reserve_parameters: &ReserveParameters,
         explanation: &mut Option<Explanation>,
-    ) -> BalanceReservationPreset {
+    ) -> Result<BalanceReservationPreset> {
         let price = reserve_parameters.price;
         let amount = reserve_parameters.amount;
         let symbol = reserve_parameters.symbol.clone();
@@ -1506,31 +1576,80 @@ impl BalanceReservationManager {
             .expect("failed to get exchange")
             .get_balance_reservation_currency_code(symbol.clone(), reserve_parameters.order_side);
 
-        let amount_in_reservation_currency_code = symbol.convert_amount_from_amount_currency_code(
-            reservation_currency_code,
-            amount,
-            price,
-        );
+        let amount_in_reservation_currency_code = symbol
+            .try_convert_amount_from_amount_currency_code(
+                reservation_currency_code,
+                amount,
+                price,
+            )?;
 
         let (cost_in_amount_currency_code, taken_free_amount) =
             self.calculate_reservation_cost(reserve_parameters);
-        let cost_in_reservation_currency_code = symbol.convert_amount_from_amount_currency_code(
-            reservation_currency_code,
-            cost_in_amount_currency_code,
-            price,
-        );
+        let cost_in_reservation_currency_code = symbol
+            .try_convert_amount_from_amount_currency_code(
+                reservation_currency_code,
+                cost_in_amount_currency_code,
+                price,
+            )?;
+
+        // expected fee is held by reservation along with order cost and released proportionally
+        let expected_fee_in_amount_currency_code =
+            self.calculate_expected_fee(reserve_parameters, reservation_currency_code);
+        let cost_in_amount_currency_code =
+            cost_in_amount_currency_code + expected_fee_in_amount_currency_code;
+        let expected_fee_in_reservation_currency_code = symbol
+            .try_convert_amount_from_amount_currency_code(
+                reservation_currency_code,
+                expected_fee_in_amount_currency_code,
+                price,
+            )?;
+        let cost_in_reservation_currency_code =
+            cost_in_reservation_currency_code + expected_fee_in_reservation_currency_code;
 
         explanation.with_reason(|| {
-            format!("cost_in_reservation_currency_code: {cost_in_reservation_currency_code} taken_free_amount: {taken_free_amount}")
+            format!("cost_in_reservation_currency_code: {cost_in_reservation_currency_code} taken_free_amount: {taken_free_amount} expected_fee_in_reservation_currency_code: {expected_fee_in_reservation_currency_code}")
         });
 
-        BalanceReservationPreset::new(
+        Ok(BalanceReservationPreset::new(
             reservation_currency_code,
             amount_in_reservation_currency_code,
             taken_free_amount,
             cost_in_reservation_currency_code,
             cost_in_amount_currency_code,
-        )
+            expected_fee_in_reservation_currency_code,
+        ))
+    }
+
+    /// Expected fee in amount currency code with safety margin if fee is paid from reserved currency.
+    /// Taker fee is expected if order role is unknown. Maker rebate isn't taken into account
+    /// because it's received only after fill
+    fn calculate_expected_fee(
+        &self,
+        reserve_parameters: &ReserveParameters,
+        reservation_currency_code: CurrencyCode,
+    ) -> Amount {
+        let Some(reservation_fee) = &self.reservation_fee else {
+            return dec!(0);
+        };
+
+        let symbol = &reserve_parameters.symbol;
+        if symbol.get_commission_currency_code(reserve_parameters.order_side)
+            != reservation_currency_code
+        {
+            return dec!(0);
+        }
+
+        let fee_rate = self
+            .exchanges_by_id()
+            .get(&reserve_parameters.exchange_account_id)
+            .expect("failed to get exchange")
+            .expected_fee_rate(reserve_parameters.order_role.unwrap_or(OrderRole::Taker))
+            .max(dec!(0));
+
+        reserve_parameters.amount
+            * symbol.amount_multiplier
+            * fee_rate
+            * (dec!(1) + reservation_fee.safety_margin)
     }
 
     fn calculate_reservation_cost(
@@ -1563,6 +1682,37 @@ impl BalanceReservationManager {
         )
     }
 
+    /// Difference of not approved amount of reservation after price change
+    /// in reservation currency and in amount currency
+    fn get_reservation_amount_diff(
+        reservation: &BalanceReservation,
+        new_raw_rest_amount: Amount,
+        new_price: Price,
+    ) -> Result<(Amount, Amount)> {
+        let symbol = &reservation.symbol;
+        let new_rest_amount_in_reservation_currency = symbol
+            .try_convert_amount_from_amount_currency_code(
+                reservation.reservation_currency_code,
+                new_raw_rest_amount,
+                new_price,
+            )?;
+        let not_approved_amount_in_reservation_currency =
+            reservation.convert_in_reservation_currency(reservation.not_approved_amount)?;
+        let reservation_amount_diff_in_reservation_currency =
+            new_rest_amount_in_reservation_currency - not_approved_amount_in_reservation_currency;
+
+        let reservation_amount_diff = symbol.try_convert_amount_into_amount_currency_code(
+            reservation.reservation_currency_code,
+            reservation_amount_diff_in_reservation_currency,
+            new_price,
+        )?;
+
+        Ok((
+            reservation_amount_diff_in_reservation_currency,
+            reservation_amount_diff,
+        ))
+    }
+
     pub fn try_update_reservation_price(
         &mut self,
         reservation_id: ReservationId,
@@ -1590,17 +1740,14 @@ impl BalanceReservationManager {
             .sum();
 
         let new_raw_rest_amount = reservation.amount - approved_sum;
-        let new_rest_amount_in_reservation_currency =
-            reservation.symbol.convert_amount_from_amount_currency_code(
-                reservation.reservation_currency_code,
-                new_raw_rest_amount,
-                new_price,
-            );
-        let not_approved_amount_in_reservation_currency =
-            reservation.convert_in_reservation_currency(reservation.not_approved_amount);
-
-        let reservation_amount_diff_in_reservation_currency =
-            new_rest_amount_in_reservation_currency - not_approved_amount_in_reservation_currency;
+        let (reservation_amount_diff_in_reservation_currency, reservation_amount_diff) =
+            match Self::get_reservation_amount_diff(reservation, new_raw_rest_amount, new_price) {
+                Ok(diffs) => diffs,
+                Err(error) => {
+                    log::error!("Failed to update reservation {reservation_id} with price {new_price}: {error:?}");
+                    return false;
+                }
+            };
 
         let old_balance = self
             .try_get_available_balance(
@@ -1641,13 +1788,6 @@ impl BalanceReservationManager {
 
         let reservation = self.get_mut_reservation_expected(reservation_id);
         reservation.price = new_price;
-
-        let reservation_amount_diff = reservation.symbol.convert_amount_into_amount_currency_code(
-            reservation.reservation_currency_code,
-            reservation_amount_diff_in_reservation_currency,
-            reservation.price,
-        );
-
         reservation.unreserved_amount -= reservation_amount_diff; // it will be compensated later
 
         self.add_reserved_amount(
@@ -1686,8 +1826,13 @@ impl BalanceReservationManager {
         reserve_parameters: &ReserveParameters,
         explanation: &mut Option<Explanation>,
     ) -> bool {
-        self.can_reserve_core(reserve_parameters, explanation)
-            .can_reserve
+        match self.can_reserve_core(reserve_parameters, explanation) {
+            Ok(can_reserve_result) => can_reserve_result.can_reserve,
+            Err(error) => {
+                log::error!("Failed to check reservation {reserve_parameters:?}: {error:?}");
+                false
+            }
+        }
     }
 
     pub fn get_available_leveraged_balance(
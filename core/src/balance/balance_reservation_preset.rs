@@ -9,6 +9,8 @@ pub(crate) struct BalanceReservationPreset {
     pub(crate) taken_free_amount_in_amount_currency_code: Amount,
     pub(crate) cost_in_reservation_currency_code: Decimal,
     pub(crate) cost_in_amount_currency_code: Decimal,
+    /// Fee which is expected to be paid from reserved balance, it's already included in costs
+    pub(crate) expected_fee_in_reservation_currency_code: Decimal,
 }
 
 impl BalanceReservationPreset {
@@ -18,6 +20,7 @@ impl BalanceReservationPreset {
         taken_free_amount_in_amount_currency_code: Amount,
         cost_in_reservation_currency_code: Decimal,
         cost_in_amount_currency_code: Decimal,
+        expected_fee_in_reservation_currency_code: Decimal,
     ) -> Self {
         Self {
             reservation_currency_code,
@@ -25,6 +28,7 @@ impl BalanceReservationPreset {
             taken_free_amount_in_amount_currency_code,
             cost_in_reservation_currency_code,
             cost_in_amount_currency_code,
+            expected_fee_in_reservation_currency_code,
         }
     }
 }
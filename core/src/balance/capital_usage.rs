@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::Amount;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::balance::manager::balance_request::BalanceRequest;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+#[derive(Debug, Default, Clone)]
+struct CapitalUsageState {
+    reserved: Amount,
+    peak_reserved: Amount,
+    idle: Amount,
+    peak_utilization: Decimal,
+    turnover: Amount,
+    balance_change: Amount,
+}
+
+/// Capital usage of strategy on market in currency `currency_code`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CapitalUsage {
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub currency_code: CurrencyCode,
+    /// Currently reserved amount
+    pub reserved: Amount,
+    /// Maximal reserved amount since start, considered as capital allocated to strategy
+    pub peak_reserved: Amount,
+    /// Available for reservation but not reserved amount on the last sample
+    pub idle: Amount,
+    /// Maximal share of reserved amount in reserved and idle amount
+    pub peak_utilization: Decimal,
+    /// Filled volume
+    pub turnover: Amount,
+    /// Balance change caused by fills including commission
+    pub balance_change: Amount,
+    /// Balance change relative to allocated capital
+    pub return_on_allocated_capital: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapitalUsageReport {
+    pub time: DateTime,
+    pub items: Vec<CapitalUsage>,
+}
+
+impl_event!(CapitalUsageReport, "capital_usage_reports");
+
+/// Accumulates capital usage by strategies from reservations and fills
+#[derive(Debug, Default, Clone)]
+pub(crate) struct CapitalUsageTracker {
+    states: HashMap<BalanceRequest, CapitalUsageState>,
+}
+
+impl CapitalUsageTracker {
+    pub fn register_turnover(&mut self, request: BalanceRequest, turnover: Amount) {
+        self.states.entry(request).or_default().turnover += turnover.abs();
+    }
+
+    pub fn register_balance_changes(&mut self, changes: HashMap<BalanceRequest, Amount>) {
+        for (request, change) in changes {
+            self.states.entry(request).or_default().balance_change += change;
+        }
+    }
+
+    /// Update reserved and idle amounts. Requests without reservation are considered fully idle
+    pub fn sample(
+        &mut self,
+        reserved_by_request: HashMap<BalanceRequest, Amount>,
+        get_available_balance: impl Fn(&BalanceRequest) -> Option<Amount>,
+    ) {
+        for request in reserved_by_request.keys() {
+            self.states.entry(request.clone()).or_default();
+        }
+
+        for (request, state) in &mut self.states {
+            let reserved = reserved_by_request
+                .get(request)
+                .copied()
+                .unwrap_or_default();
+
+            state.reserved = reserved;
+            state.peak_reserved = state.peak_reserved.max(reserved);
+
+            if let Some(available) = get_available_balance(request) {
+                state.idle = available.max(Decimal::ZERO);
+            }
+
+            let total = reserved + state.idle;
+            if !total.is_zero() {
+                state.peak_utilization = state.peak_utilization.max(reserved / total);
+            }
+        }
+    }
+
+    pub fn report(&self, time: DateTime) -> CapitalUsageReport {
+        let items = self
+            .states
+            .iter()
+            .map(|(request, state)| CapitalUsage {
+                configuration_descriptor: request.configuration_descriptor,
+                exchange_account_id: request.exchange_account_id,
+                currency_pair: request.currency_pair,
+                currency_code: request.currency_code,
+                reserved: state.reserved,
+                peak_reserved: state.peak_reserved,
+                idle: state.idle,
+                peak_utilization: state.peak_utilization,
+                turnover: state.turnover,
+                balance_change: state.balance_change,
+                return_on_allocated_capital: match state.peak_reserved.is_zero() {
+                    true => Decimal::ZERO,
+                    false => state.balance_change / state.peak_reserved,
+                },
+            })
+            .collect();
+
+        CapitalUsageReport { time, items }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_utils::hashmap;
+    use rust_decimal_macros::dec;
+
+    fn request(currency_code: &str) -> BalanceRequest {
+        BalanceRequest::new(
+            ConfigurationDescriptor::new("test_strategy".into(), "test_key".into()),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            currency_code.into(),
+        )
+    }
+
+    #[test]
+    fn peak_values_are_kept_after_unreserve() {
+        let mut tracker = CapitalUsageTracker::default();
+        let usdt = request("usdt");
+
+        tracker.sample(hashmap![usdt => dec!(40)], |_| Some(dec!(60)));
+        tracker.sample(HashMap::new(), |_| Some(dec!(100)));
+
+        let report = tracker.report(Utc::now());
+        let item = &report.items[0];
+        assert_eq!(item.reserved, dec!(0));
+        assert_eq!(item.peak_reserved, dec!(40));
+        assert_eq!(item.idle, dec!(100));
+        assert_eq!(item.peak_utilization, dec!(0.4));
+    }
+
+    #[test]
+    fn return_on_allocated_capital() {
+        let mut tracker = CapitalUsageTracker::default();
+        let usdt = request("usdt");
+
+        tracker.sample(hashmap![usdt.clone() => dec!(200)], |_| None);
+        tracker.register_turnover(usdt.clone(), dec!(-150));
+        tracker.register_balance_changes(hashmap![usdt.clone() => dec!(-150)]);
+        tracker.register_turnover(usdt.clone(), dec!(160));
+        tracker.register_balance_changes(hashmap![usdt => dec!(160)]);
+
+        let report = tracker.report(Utc::now());
+        let item = &report.items[0];
+        assert_eq!(item.turnover, dec!(310));
+        assert_eq!(item.balance_change, dec!(10));
+        assert_eq!(item.return_on_allocated_capital, dec!(0.05));
+    }
+}
@@ -1,6 +1,6 @@
 use mockall_double::double;
 
-use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::exchanges::symbol::{ContractType, Symbol};
 use mmb_domain::order::fill::OrderFill;
 use mmb_domain::order::snapshot::{OrderSide, OrderSnapshot};
 use std::sync::Arc;
@@ -57,8 +57,8 @@ impl BalanceChangesCalculator {
         let order_side = order.header.side;
         let exchange_account_id = order.header.exchange_account_id;
 
-        let (new_base_amount, new_quote_amount) = if !symbol.is_derivative {
-            match order_side {
+        let (new_base_amount, new_quote_amount) = match symbol.contract_type {
+            ContractType::Spot => match order_side {
                 OrderSide::Sell => (
                     -filled_amount,
                     symbol.convert_amount_from_amount_currency_code(
@@ -75,55 +75,50 @@ impl BalanceChangesCalculator {
                         price,
                     ),
                 ),
-            }
-        } else {
-            let balance_currency_code = symbol
-                .balance_currency_code
-                .expect("Balance currency code isn't set");
-
-            if balance_currency_code == symbol.base_currency_code {
-                match order_side {
-                    OrderSide::Sell => (
-                        symbol.convert_amount_from_amount_currency_code(
-                            symbol.base_currency_code(),
-                            -filled_amount,
-                            price,
-                        ) - commission_amount,
-                        filled_amount,
-                    ),
-                    OrderSide::Buy => (
-                        symbol.convert_amount_from_amount_currency_code(
-                            symbol.base_currency_code(),
-                            filled_amount,
-                            price,
-                        ) - commission_amount,
-                        -filled_amount,
-                    ),
-                }
-            } else if balance_currency_code == symbol.quote_currency_code {
-                match order_side {
-                    OrderSide::Sell => (
+            },
+            ContractType::Inverse => match order_side {
+                OrderSide::Sell => (
+                    symbol.convert_amount_from_amount_currency_code(
+                        symbol.base_currency_code(),
                         -filled_amount,
-                        symbol.convert_amount_from_amount_currency_code(
-                            symbol.quote_currency_code(),
-                            filled_amount,
-                            price,
-                        ) - commission_amount,
-                    ),
-                    OrderSide::Buy => (
+                        price,
+                    ) - commission_amount,
+                    filled_amount,
+                ),
+                OrderSide::Buy => (
+                    symbol.convert_amount_from_amount_currency_code(
+                        symbol.base_currency_code(),
                         filled_amount,
-                        symbol.convert_amount_from_amount_currency_code(
-                            symbol.quote_currency_code(),
-                            -filled_amount,
-                            price,
-                        ) - commission_amount,
-                    ),
-                }
-            } else {
-                panic!(
-                    "BalanceChangesCalculator::get_balance_changes_calculator_results: balance_currency_code({}) is wrong.",
-                    balance_currency_code
-                )
+                        price,
+                    ) - commission_amount,
+                    -filled_amount,
+                ),
+            },
+            ContractType::Linear => match order_side {
+                OrderSide::Sell => (
+                    -filled_amount,
+                    symbol.convert_amount_from_amount_currency_code(
+                        symbol.quote_currency_code(),
+                        filled_amount,
+                        price,
+                    ) - commission_amount,
+                ),
+                OrderSide::Buy => (
+                    filled_amount,
+                    symbol.convert_amount_from_amount_currency_code(
+                        symbol.quote_currency_code(),
+                        -filled_amount,
+                        price,
+                    ) - commission_amount,
+                ),
+            },
+            ContractType::Quanto => {
+                return self.get_quanto_balance_changes(
+                    configuration_descriptor,
+                    order,
+                    order_fill,
+                    symbol,
+                );
             }
         };
 
@@ -152,4 +147,48 @@ impl BalanceChangesCalculator {
             exchange_account_id.exchange_id,
         )
     }
+
+    /// Quanto contracts change position in base currency, but are settled in balance currency
+    /// which is neither base nor quote currency
+    fn get_quanto_balance_changes(
+        &self,
+        configuration_descriptor: ConfigurationDescriptor,
+        order: &OrderSnapshot,
+        order_fill: &OrderFill,
+        symbol: Arc<Symbol>,
+    ) -> BalanceChangesCalculatorResult {
+        let price = order_fill.price();
+        let exchange_account_id = order.header.exchange_account_id;
+        let settlement_currency_code = symbol.pnl_currency_code();
+        let contract_value = symbol.contract_value(order_fill.amount(), price);
+
+        let (position_change, settlement_change) = match order.header.side {
+            OrderSide::Sell => (-order_fill.amount(), contract_value),
+            OrderSide::Buy => (order_fill.amount(), -contract_value),
+        };
+
+        let mut res_balance_changes = ServiceValueTree::default();
+        for (currency_code, balance_change) in [
+            (symbol.base_currency_code(), position_change),
+            (
+                settlement_currency_code,
+                settlement_change - order_fill.commission_amount(),
+            ),
+        ] {
+            let request = BalanceRequest::new(
+                configuration_descriptor,
+                exchange_account_id,
+                symbol.currency_pair(),
+                currency_code,
+            );
+            res_balance_changes.set_by_balance_request(&request, balance_change);
+        }
+
+        BalanceChangesCalculatorResult::new(
+            res_balance_changes,
+            settlement_currency_code,
+            price,
+            exchange_account_id.exchange_id,
+        )
+    }
 }
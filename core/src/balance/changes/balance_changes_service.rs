@@ -1,8 +1,10 @@
 use std::{sync::Arc, time::Duration};
 
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_domain::events::CashFlowEvent;
 use mmb_domain::order::fill::OrderFill;
 use mmb_domain::order::snapshot::{ClientOrderFillId, OrderSnapshot};
+use mmb_domain::reporting_precision::ReportingPrecision;
 use mmb_utils::{
     cancellation_token::CancellationToken,
     infrastructure::SpawnFutureFlags,
@@ -37,6 +39,7 @@ use super::{
 enum BalanceChangeServiceEvent {
     OnTimer,
     BalanceChange(BalanceChange),
+    CashFlow(CashFlow),
 }
 
 #[derive(Debug)]
@@ -60,6 +63,24 @@ impl BalanceChange {
     }
 }
 
+#[derive(Debug)]
+struct CashFlow {
+    pub cash_flow: CashFlowEvent,
+    pub change_date: DateTime,
+}
+
+/// Service name of configuration descriptor which cash flows are accounted under
+pub const CASH_FLOW_SERVICE_NAME: &str = "CashFlow";
+
+/// Cash flow isn't related to any strategy, so it is accounted once per market account
+/// regardless of how many strategies trade the market
+fn cash_flow_configuration_descriptor(cash_flow: &CashFlowEvent) -> ConfigurationDescriptor {
+    ConfigurationDescriptor::new(
+        CASH_FLOW_SERVICE_NAME.into(),
+        cash_flow.market_account_id().market_id().into(),
+    )
+}
+
 pub struct BalanceChangesService {
     usd_converter: UsdConverter,
     rx_event: mpsc::Receiver<BalanceChangeServiceEvent>,
@@ -69,6 +90,7 @@ pub struct BalanceChangesService {
     balance_changes_calculator: BalanceChangesCalculator,
     lifetime_manager: Arc<AppLifetimeManager>,
     event_recorder: Arc<EventRecorder>,
+    reporting_precision: ReportingPrecision,
 }
 
 impl BalanceChangesService {
@@ -78,6 +100,7 @@ impl BalanceChangesService {
         usd_converter: UsdConverter,
         lifetime_manager: Arc<AppLifetimeManager>,
         event_recorder: Arc<EventRecorder>,
+        reporting_precision: ReportingPrecision,
     ) -> Arc<Self> {
         let (tx_event, rx_event) = mpsc::channel(20_000);
         let balance_changes_accumulators =
@@ -95,6 +118,7 @@ impl BalanceChangesService {
             ),
             lifetime_manager: lifetime_manager.clone(),
             event_recorder,
+            reporting_precision,
         });
 
         let on_timer_tick = {
@@ -146,6 +170,10 @@ impl BalanceChangesService {
                     self.handle_balance_change_event(event, cancellation_token.clone())
                         .await;
                 }
+                BalanceChangeServiceEvent::CashFlow(event) => {
+                    self.handle_cash_flow_event(event, cancellation_token.clone())
+                        .await;
+                }
                 BalanceChangeServiceEvent::OnTimer => {
                     self.profit_loss_stopper_service
                         .check_for_limit(&self.usd_converter, cancellation_token.clone())
@@ -186,7 +214,7 @@ impl BalanceChangesService {
             }
 
             self.event_recorder
-                .save(profit_loss_balance_change)
+                .save(profit_loss_balance_change.rounded(&self.reporting_precision))
                 .expect("Failure save profit_loss_balance_change");
         }
         self.profit_loss_stopper_service
@@ -194,6 +222,68 @@ impl BalanceChangesService {
             .await;
     }
 
+    async fn handle_cash_flow_event(&self, event: CashFlow, cancellation_token: CancellationToken) {
+        let cash_flow = &event.cash_flow;
+        let usd_change = match self
+            .usd_converter
+            .convert_amount(
+                cash_flow.currency_code,
+                cash_flow.amount,
+                cancellation_token.clone(),
+            )
+            .await
+        {
+            Some(usd_change) => usd_change,
+            None => {
+                log::error!(
+                    "Failed to convert {:?} cash flow {} {} on {} to USD, it isn't included into PnL",
+                    cash_flow.kind,
+                    cash_flow.amount,
+                    cash_flow.currency_code,
+                    cash_flow.market_account_id()
+                );
+                return;
+            }
+        };
+
+        let profit_loss_balance_change = ProfitLossBalanceChange::from_cash_flow(
+            cash_flow_configuration_descriptor(cash_flow),
+            cash_flow,
+            event.change_date,
+            usd_change,
+        );
+
+        for accumulator in self.balance_changes_accumulators.iter() {
+            accumulator.add_balance_change(&profit_loss_balance_change);
+        }
+
+        self.event_recorder
+            .save(profit_loss_balance_change.rounded(&self.reporting_precision))
+            .expect("Failure save profit_loss_balance_change");
+
+        self.profit_loss_stopper_service
+            .check_for_limit(&self.usd_converter, cancellation_token)
+            .await;
+    }
+
+    /// Funding payments and accrued fees are included into PnL the same way as fills
+    pub fn add_cash_flow(&self, cash_flow: &CashFlowEvent) {
+        if self
+            .lifetime_manager
+            .stop_token()
+            .is_cancellation_requested()
+        {
+            log::error!("BalanceChangesService::add_cash_flow() not available because cancellation was requested on the CancellationToken");
+            return;
+        }
+
+        self.tx_event
+            .send_expected(BalanceChangeServiceEvent::CashFlow(CashFlow {
+                cash_flow: cash_flow.clone(),
+                change_date: time_manager::now(),
+            }));
+    }
+
     pub fn add_balance_change(
         &self,
         configuration_descriptor: ConfigurationDescriptor,
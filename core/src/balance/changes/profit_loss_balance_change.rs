@@ -3,9 +3,11 @@ use std::fmt::{Display, Formatter};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use mmb_database::impl_event;
+use mmb_domain::events::CashFlowEvent;
 use mmb_domain::market::{CurrencyCode, ExchangeId, MarketAccountId};
 use mmb_domain::order::snapshot::ClientOrderFillId;
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::reporting_precision::ReportingPrecision;
 use mmb_utils::DateTime;
 use mmb_utils::{impl_u64_id, time::get_atomic_current_secs};
 use once_cell::sync::Lazy;
@@ -60,12 +62,42 @@ impl ProfitLossBalanceChange {
         }
     }
 
+    pub fn from_cash_flow(
+        configuration_descriptor: ConfigurationDescriptor,
+        cash_flow: &CashFlowEvent,
+        change_date: DateTime,
+        usd_balance_change: Amount,
+    ) -> Self {
+        Self {
+            id: ProfitLossBalanceChangeId::generate(),
+            // cash flow isn't related to any fill, so it gets its own unique id
+            client_order_fill_id: ClientOrderFillId::unique_id(),
+            change_date,
+            configuration_descriptor,
+            exchange_id: cash_flow.exchange_account_id.exchange_id,
+            market_account_id: cash_flow.market_account_id(),
+            currency_code: cash_flow.currency_code,
+            balance_change: cash_flow.amount,
+            usd_price: usd_balance_change / cash_flow.amount,
+            usd_balance_change,
+        }
+    }
+
     pub fn with_portion(&self, portion: Decimal) -> ProfitLossBalanceChange {
         let mut item = self.clone();
         item.balance_change *= portion;
         item.usd_balance_change *= portion;
         item
     }
+
+    /// Copy with amounts rounded for reporting, calculations should use original values
+    pub fn rounded(&self, precision: &ReportingPrecision) -> ProfitLossBalanceChange {
+        let mut item = self.clone();
+        item.balance_change = precision.round_amount(self.currency_code, self.balance_change);
+        item.usd_price = precision.round_default(self.usd_price);
+        item.usd_balance_change = precision.round_usd(self.usd_balance_change);
+        item
+    }
 }
 
 #[cfg(test)]
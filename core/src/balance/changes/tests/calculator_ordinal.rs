@@ -107,6 +107,56 @@ mod tests {
         assert_eq!(actual_quote_balance_changed, quote_balance_changed);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn simple_sell_base_currency_with_maker_rebate() {
+        /*
+         * /// Just sell some base amount with rebate (negative commission) in quote ///
+         * Currency pair: Base/Quote
+         * Amount currency code: Base
+         * Commission currency code: Quote
+         */
+        let mut test_obj = TestBase::new(false, false);
+
+        let price_base_quote = dec!(1.232);
+        let amount_in_base = dec!(14);
+        let amount_in_quote = amount_in_base * price_base_quote;
+        let filled_amount_in_base = amount_in_base;
+        let rebate_amount_in_quote = amount_in_quote * dec!(0.0001);
+
+        let order = TestBase::create_order_with_commission_amount(
+            TestBase::exchange_account_id_1(),
+            TestBase::currency_pair(),
+            OrderSide::Sell,
+            price_base_quote,
+            amount_in_base,
+            filled_amount_in_base,
+            TestBase::quote(),
+            -rebate_amount_in_quote,
+        );
+
+        // Expected
+        let base_balance_changed = -amount_in_base;
+        let quote_balance_changed = amount_in_quote + rebate_amount_in_quote;
+
+        // Actual
+        test_obj.calculate_balance_changes(vec![&order]).await;
+
+        let actual_base_balance_changed = test_obj.get_actual_balance_change(
+            TestBase::exchange_account_id_1(),
+            TestBase::currency_pair(),
+            TestBase::base(),
+        );
+
+        let actual_quote_balance_changed = test_obj.get_actual_balance_change(
+            TestBase::exchange_account_id_1(),
+            TestBase::currency_pair(),
+            TestBase::quote(),
+        );
+
+        assert_eq!(actual_base_balance_changed, base_balance_changed);
+        assert_eq!(actual_quote_balance_changed, quote_balance_changed);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn two_directions_amount_in_base_but_sell_by_equal_price_and_amount_nullable_commission(
     ) {
@@ -1,5 +1,6 @@
 use mmb_utils::DateTime;
 
+use mmb_domain::decimal_serialization::decimal;
 use mmb_domain::order::snapshot::Amount;
 use mmb_domain::order::snapshot::ClientOrderId;
 use serde::Serialize;
@@ -9,8 +10,10 @@ pub struct ApprovedPart {
     _approve_time: DateTime,
     _client_order_id: ClientOrderId,
     /// Order amount in current CurrencyCode
+    #[serde(with = "decimal")]
     pub(crate) amount: Amount,
     pub(crate) is_canceled: bool,
+    #[serde(with = "decimal")]
     pub(crate) unreserved_amount: Amount,
 }
 
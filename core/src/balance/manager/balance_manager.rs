@@ -2,16 +2,22 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::balance::balance_reservation_manager::BalanceReservationManager;
+use crate::balance::capital_usage::{CapitalUsageReport, CapitalUsageTracker};
 use crate::balance::changes::balance_changes_service::BalanceChangesService;
+use crate::balance::manager::balance_request::BalanceRequest;
 use crate::balance::manager::balance_reservation::BalanceReservation;
 use crate::balance::manager::balances::Balances;
 use crate::balance::manager::position_change::PositionChange;
+use crate::balance::manager::two_sided_reservation::{QuoteLevel, TwoSidedReservation};
 use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
+use crate::exchanges::general::exchange::Exchange;
 use crate::explanation::Explanation;
 use crate::misc::reserve_parameters::ReserveParameters;
 use crate::misc::service_value_tree::ServiceValueTree;
+use crate::misc::time::time_manager;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
-use mmb_domain::events::ExchangeBalancesAndPositions;
+use crate::settings::ReservationFeeSettings;
+use mmb_domain::events::{CashFlowEvent, ExchangeBalancesAndPositions};
 use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId};
 use mmb_domain::order::fill::OrderFill;
@@ -52,6 +58,7 @@ pub struct BalanceManager {
     position_differs_times_in_row_by_exchange_id:
         HashMap<ExchangeAccountId, HashMap<CurrencyPair, u32>>,
     event_recorder: Option<Arc<EventRecorder>>,
+    capital_usage: CapitalUsageTracker,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -77,6 +84,7 @@ impl BalanceManager {
             balance_changes_service: None,
             position_differs_times_in_row_by_exchange_id: Default::default(),
             event_recorder,
+            capital_usage: Default::default(),
         }))
     }
 
@@ -127,6 +135,15 @@ impl BalanceManager {
             .get_reservation_ids()
     }
 
+    pub fn get_reservations(&self) -> Vec<(ReservationId, BalanceReservation)> {
+        self.balance_reservation_manager
+            .balance_reservation_storage
+            .get_all_raw_reservations()
+            .iter()
+            .map(|(id, reservation)| (*id, reservation.clone()))
+            .collect()
+    }
+
     #[cfg(test)]
     pub(crate) fn restore_balance_state_with_reservations_handling(
         &mut self,
@@ -326,6 +343,16 @@ impl BalanceManager {
         Ok(())
     }
 
+    pub fn restore_fill_amount_position_from_history(
+        &mut self,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        position: Decimal,
+    ) -> Result<()> {
+        self.balance_reservation_manager
+            .restore_fill_amount_position_from_history(exchange_account_id, symbol, position)
+    }
+
     pub fn update_exchange_balance(
         &mut self,
         exchange_account_id: ExchangeAccountId,
@@ -372,7 +399,7 @@ impl BalanceManager {
                 filtered_exchange_balances.get_mut(&reservation.reservation_currency_code)
             {
                 *filtered_exchange_balance -=
-                    reservation.convert_in_reservation_currency(not_approved_amount_cost);
+                    reservation.convert_in_reservation_currency(not_approved_amount_cost)?;
             }
         }
 
@@ -423,7 +450,7 @@ impl BalanceManager {
 
             balance += reservation.convert_in_reservation_currency(
                 reservation.get_proportional_cost_amount(reservation.not_approved_amount)?,
-            );
+            )?;
         }
         Ok(balances_dict)
     }
@@ -554,13 +581,25 @@ impl BalanceManager {
             .balance_reservation_manager
             .currency_pair_to_symbol_converter
             .get_symbol(exchange_account_id, order_snapshot.header.currency_pair);
-        self.handle_order_fill(
+        self.register_fill_capital_usage(
+            configuration_descriptor,
+            &symbol,
+            order_snapshot,
+            order_fill,
+        );
+        if let Err(error) = self.handle_order_fill(
             configuration_descriptor,
             exchange_account_id,
             symbol,
             order_snapshot,
             order_fill,
-        );
+        ) {
+            // balance will be actualized with the next balance update from exchange
+            log::error!(
+                "Failed to handle fill {order_fill:?} of order {}: {error:?}",
+                order_snapshot.header.client_order_id
+            );
+        }
         self.save_balances();
 
         if let Some(balance_changes_service) = &self.balance_changes_service {
@@ -572,6 +611,102 @@ impl BalanceManager {
         }
     }
 
+    pub fn cash_flow_received(&self, cash_flow: &CashFlowEvent) {
+        // Balance itself will be actualized with the next balance update from exchange
+        if let Some(balance_changes_service) = &self.balance_changes_service {
+            balance_changes_service.add_cash_flow(cash_flow);
+        }
+    }
+
+    fn register_fill_capital_usage(
+        &mut self,
+        configuration_descriptor: ConfigurationDescriptor,
+        symbol: &Symbol,
+        order_snapshot: &OrderSnapshot,
+        order_fill: &OrderFill,
+    ) {
+        let exchange_account_id = order_snapshot.header.exchange_account_id;
+        let request = |currency_code| {
+            BalanceRequest::new(
+                configuration_descriptor,
+                exchange_account_id,
+                symbol.currency_pair(),
+                currency_code,
+            )
+        };
+
+        let quote_amount = match symbol.try_convert_amount_from_amount_currency_code(
+            symbol.quote_currency_code(),
+            order_fill.amount(),
+            order_fill.price(),
+        ) {
+            Ok(quote_amount) => quote_amount,
+            Err(error) => {
+                log::error!("Failed to register capital usage of fill {order_fill:?}: {error:?}");
+                return;
+            }
+        };
+        self.capital_usage
+            .register_turnover(request(symbol.quote_currency_code()), quote_amount);
+
+        let mut changes = HashMap::new();
+        if !symbol.is_derivative {
+            let (base_change, quote_change) = match order_snapshot.header.side {
+                OrderSide::Buy => (order_fill.amount(), -quote_amount),
+                OrderSide::Sell => (-order_fill.amount(), quote_amount),
+            };
+            changes.insert(request(symbol.base_currency_code()), base_change);
+            changes.insert(request(symbol.quote_currency_code()), quote_change);
+        }
+        *changes
+            .entry(request(order_fill.commission_currency_code()))
+            .or_default() -= order_fill.commission_amount();
+
+        self.capital_usage.register_balance_changes(changes);
+    }
+
+    /// Sample reserved and idle capital of strategies and save capital usage report
+    pub fn update_capital_usage(&mut self) -> CapitalUsageReport {
+        let reserved_by_request = self
+            .balance_reservation_manager
+            .get_state()
+            .reserved_amount
+            .map(|x| x.get_as_balances())
+            .unwrap_or_default();
+
+        let balance_reservation_manager = &self.balance_reservation_manager;
+        let last_order_fills = &self.last_order_fills;
+        self.capital_usage.sample(reserved_by_request, |request| {
+            let market_account_id =
+                MarketAccountId::new(request.exchange_account_id, request.currency_pair);
+            let price = last_order_fills.get(&market_account_id)?.price();
+            let symbol = balance_reservation_manager
+                .currency_pair_to_symbol_converter
+                .get_symbol(request.exchange_account_id, request.currency_pair);
+
+            balance_reservation_manager.try_get_available_balance_with_unknown_side(
+                request.configuration_descriptor,
+                request.exchange_account_id,
+                symbol,
+                request.currency_code,
+                price,
+            )
+        });
+
+        let report = self.capital_usage.report(time_manager::now());
+        if let Some(event_recorder) = &self.event_recorder {
+            event_recorder
+                .save(report.clone())
+                .expect("Failure save capital usage report");
+        }
+
+        report
+    }
+
+    pub fn get_capital_usage_report(&self) -> CapitalUsageReport {
+        self.capital_usage.report(time_manager::now())
+    }
+
     fn handle_order_fill(
         &mut self,
         configuration_descriptor: ConfigurationDescriptor,
@@ -579,7 +714,7 @@ impl BalanceManager {
         symbol: Arc<Symbol>,
         order_snapshot: &OrderSnapshot,
         order_fill: &OrderFill,
-    ) {
+    ) -> Result<()> {
         let (amount_in_before_trade_currency_code, currency_code_before_trade) = self
             .balance_reservation_manager
             .handle_position_fill_amount_change(
@@ -591,7 +726,7 @@ impl BalanceManager {
                 configuration_descriptor,
                 exchange_account_id,
                 symbol.clone(),
-            );
+            )?;
 
         let (amount_in_after_trade_currency_code, currency_code_after_trade) = self
             .balance_reservation_manager
@@ -604,7 +739,7 @@ impl BalanceManager {
                 configuration_descriptor,
                 exchange_account_id,
                 symbol.clone(),
-            );
+            )?;
 
         self.balance_reservation_manager
             .handle_position_fill_amount_change_commission(
@@ -616,7 +751,7 @@ impl BalanceManager {
                 configuration_descriptor,
                 exchange_account_id,
                 symbol.clone(),
-            );
+            )?;
 
         self.update_last_order_fill(
             exchange_account_id,
@@ -648,6 +783,7 @@ impl BalanceManager {
             currency_code_after_trade,
             amount_in_after_trade_currency_code
         );
+        Ok(())
     }
 
     fn update_last_order_fill(
@@ -687,6 +823,18 @@ impl BalanceManager {
         }
     }
 
+    /// Return unfilled part of canceled order to not approved amount of reservation,
+    /// so it can be approved by another order
+    pub fn cancel_approved_reservation(
+        &mut self,
+        reservation_id: ReservationId,
+        client_order_id: &ClientOrderId,
+    ) {
+        self.balance_reservation_manager
+            .cancel_approved_reservation(reservation_id, client_order_id);
+        self.save_balances();
+    }
+
     pub fn get_reservation(&self, reservation_id: ReservationId) -> Option<&BalanceReservation> {
         self.balance_reservation_manager
             .get_reservation(reservation_id)
@@ -826,6 +974,45 @@ impl BalanceManager {
         None
     }
 
+    /// Reserve balance for all levels of bid and ask ladders at once.
+    /// If any level can't be funded nothing is reserved
+    pub fn try_reserve_two_sided(
+        &mut self,
+        configuration_descriptor: ConfigurationDescriptor,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        bid_levels: &[QuoteLevel],
+        ask_levels: &[QuoteLevel],
+        explanation: &mut Option<Explanation>,
+    ) -> Option<TwoSidedReservation> {
+        let reserve_parameters = bid_levels
+            .iter()
+            .map(|level| (OrderSide::Buy, level))
+            .chain(ask_levels.iter().map(|level| (OrderSide::Sell, level)))
+            .map(|(side, level)| {
+                ReserveParameters::new(
+                    configuration_descriptor,
+                    exchange_account_id,
+                    symbol.clone(),
+                    side,
+                    level.price,
+                    level.amount,
+                )
+            })
+            .collect_vec();
+
+        let mut reservation_ids = self
+            .balance_reservation_manager
+            .try_reserve_multiple(&reserve_parameters, explanation)?;
+        self.save_balances();
+
+        let asks = reservation_ids.split_off(bid_levels.len());
+        Some(TwoSidedReservation {
+            bids: reservation_ids,
+            asks,
+        })
+    }
+
     pub fn can_reserve(
         &self,
         reserve_parameters: &ReserveParameters,
@@ -874,11 +1061,15 @@ impl BalanceManager {
             Some(balance) => {
                 let currency_code = symbol.get_trade_code(side, BeforeAfter::Before);
                 let balance_in_amount_currency_code = symbol
-                    .convert_amount_into_amount_currency_code(
+                    .try_convert_amount_into_amount_currency_code(
                         currency_code,
                         balance,
                         price_quote_to_base,
-                    );
+                    )
+                    .map_err(|error| {
+                        log::error!("Failed to convert balance {balance} {currency_code} for {exchange_account_id} with price {price_quote_to_base}: {error:?}")
+                    })
+                    .ok()?;
                 Some(symbol.round_to_remove_amount_precision_error_expected(
                     balance_in_amount_currency_code,
                 ))
@@ -980,6 +1171,11 @@ impl BalanceManager {
         );
     }
 
+    pub fn set_reservation_fee(&mut self, reservation_fee: Option<ReservationFeeSettings>) {
+        self.balance_reservation_manager
+            .set_reservation_fee(reservation_fee);
+    }
+
     pub fn set_balance_changes_service(&mut self, service: Arc<BalanceChangesService>) {
         self.balance_changes_service = Some(service);
     }
@@ -998,32 +1194,8 @@ impl BalanceManager {
             .cloned()
             .collect_vec();
 
-        let update_actions = exchanges.iter().map(|exchange| {
-            let this = this.clone();
-            let cancellation_token = cancellation_token.clone();
-
-            async move {
-                let run = async move {
-                    let balances_and_positions = exchange
-                        .get_balance(cancellation_token.clone())
-                        .await
-                        .with_context(|| {
-                            format!("failed get_balance for {}", exchange.exchange_account_id)
-                        })?;
-
-                    this.lock()
-                        .update_exchange_balance(
-                            exchange.exchange_account_id,
-                            &balances_and_positions,
-                        )
-                        .context("failed to update exchange balance")
-                };
-
-                match run.await {
-                    Ok(()) => nothing_to_do(),
-                    Err(err) => log::error!("{err:?}"),
-                }
-            }
+        let update_actions = exchanges.into_iter().map(|exchange| {
+            Self::update_balance_for_exchange(this.clone(), exchange, cancellation_token.clone())
         });
 
         join_all(update_actions).await;
@@ -1031,6 +1203,30 @@ impl BalanceManager {
         log::trace!("Balance update finished")
     }
 
+    pub async fn update_balance_for_exchange(
+        this: Arc<Mutex<Self>>,
+        exchange: Arc<Exchange>,
+        cancellation_token: CancellationToken,
+    ) {
+        let run = async move {
+            let balances_and_positions = exchange
+                .get_balance(cancellation_token)
+                .await
+                .with_context(|| {
+                    format!("failed get_balance for {}", exchange.exchange_account_id)
+                })?;
+
+            this.lock()
+                .update_exchange_balance(exchange.exchange_account_id, &balances_and_positions)
+                .context("failed to update exchange balance")
+        };
+
+        match run.await {
+            Ok(()) => nothing_to_do(),
+            Err(err) => log::error!("{err:?}"),
+        }
+    }
+
     // TODO: should be implemented
     // public void ExecuteTransaction(Action action)
     // {
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use crate::balance::manager::approved_part::ApprovedPart;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_domain::decimal_serialization::decimal;
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::CurrencyCode;
 use mmb_domain::market::ExchangeAccountId;
@@ -22,16 +23,23 @@ pub struct BalanceReservation {
     pub exchange_account_id: ExchangeAccountId,
     pub symbol: Arc<Symbol>,
     pub order_side: OrderSide,
+    #[serde(with = "decimal")]
     pub price: Price,
+    #[serde(with = "decimal")]
     pub amount: Amount,
+    #[serde(with = "decimal")]
     pub taken_free_amount: Amount,
+    /// Cost in amount currency code including expected fee if it's paid from reserved currency
+    #[serde(with = "decimal")]
     pub cost: Decimal,
 
     /// CurrencyCode in which we take away amount
     pub reservation_currency_code: CurrencyCode,
+    #[serde(with = "decimal")]
     pub unreserved_amount: Amount,
 
     /// Not approved amount in AmountCurrencyCode
+    #[serde(with = "decimal")]
     pub not_approved_amount: Amount,
     pub approved_parts: HashMap<ClientOrderId, ApprovedPart>,
 }
@@ -83,8 +91,8 @@ impl BalanceReservation {
     pub(crate) fn convert_in_reservation_currency(
         &self,
         amount_in_current_currency: Amount,
-    ) -> Amount {
-        self.symbol.convert_amount_from_amount_currency_code(
+    ) -> Result<Amount> {
+        self.symbol.try_convert_amount_from_amount_currency_code(
             self.reservation_currency_code,
             amount_in_current_currency,
             self.price,
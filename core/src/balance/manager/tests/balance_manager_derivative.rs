@@ -7,7 +7,7 @@ use rust_decimal_macros::dec;
 use uuid::Uuid;
 
 use mmb_domain::exchanges::symbol::{Precision, Symbol};
-use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::market::{CurrencyCode, ExchangeAccountId};
 use mmb_domain::order::fill::{OrderFill, OrderFillType};
 use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{OrderFillRole, OrderSide};
@@ -53,14 +53,14 @@ impl BalanceManagerDerivative {
 
     #[allow(clippy::type_complexity)]
     fn create_balance_manager(
-        is_reversed: bool,
+        symbol: Symbol,
     ) -> (
         Arc<Symbol>,
         Arc<Mutex<BalanceManager>>,
         HashMap<ExchangeAccountId, Arc<Exchange>>,
     ) {
         let (symbol, exchanges_by_id) =
-            BalanceManagerDerivative::create_balance_manager_ctor_parameters(is_reversed);
+            BalanceManagerDerivative::create_balance_manager_ctor_parameters(symbol);
         let currency_pair_to_symbol_converter =
             CurrencyPairToSymbolConverter::new(exchanges_by_id.clone());
 
@@ -68,19 +68,36 @@ impl BalanceManagerDerivative {
         (symbol, balance_manager, exchanges_by_id)
     }
 
-    fn create_balance_manager_ctor_parameters(
-        is_reversed: bool,
-    ) -> (Arc<Symbol>, HashMap<ExchangeAccountId, Arc<Exchange>>) {
-        let base = BalanceManagerBase::eth();
-        let quote = BalanceManagerBase::btc();
-
+    /// Inverse contract if not reversed, otherwise linear contract
+    fn create_symbol(is_reversed: bool) -> Symbol {
         let (balance, amount) = if is_reversed {
             (BalanceManagerBase::btc(), BalanceManagerBase::eth())
         } else {
             (BalanceManagerBase::eth(), BalanceManagerBase::btc())
         };
 
-        let mut symbol = Symbol::new(
+        let mut symbol = Self::create_symbol_by_currency_codes(amount, balance);
+        if is_reversed {
+            symbol.amount_multiplier = dec!(0.001);
+        }
+        symbol
+    }
+
+    /// Quanto contract with amount in base currency and balance in currency outside of pair
+    fn create_quanto_symbol() -> Symbol {
+        let mut symbol = Self::create_symbol_by_currency_codes(
+            BalanceManagerBase::eth(),
+            BalanceManagerBase::bnb(),
+        );
+        symbol.amount_multiplier = dec!(0.001);
+        symbol
+    }
+
+    fn create_symbol_by_currency_codes(amount: CurrencyCode, balance: CurrencyCode) -> Symbol {
+        let base = BalanceManagerBase::eth();
+        let quote = BalanceManagerBase::btc();
+
+        Symbol::new(
             true,
             base.as_str().into(),
             base,
@@ -95,10 +112,12 @@ impl BalanceManagerDerivative {
             Some(balance),
             Precision::ByTick { tick: dec!(0.1) },
             Precision::ByTick { tick: dec!(0.001) },
-        );
-        if is_reversed {
-            symbol.amount_multiplier = dec!(0.001);
-        }
+        )
+    }
+
+    fn create_balance_manager_ctor_parameters(
+        symbol: Symbol,
+    ) -> (Arc<Symbol>, HashMap<ExchangeAccountId, Arc<Exchange>>) {
         let symbol = Arc::from(symbol);
         let exchange_1 = get_test_exchange_with_symbol_and_id(
             symbol.clone(),
@@ -120,8 +139,12 @@ impl BalanceManagerDerivative {
     }
 
     fn new(is_reversed: bool) -> Self {
+        Self::new_by_symbol(Self::create_symbol(is_reversed))
+    }
+
+    fn new_by_symbol(symbol: Symbol) -> Self {
         let (symbol, balance_manager, exchanges_by_id) =
-            BalanceManagerDerivative::create_balance_manager(is_reversed);
+            BalanceManagerDerivative::create_balance_manager(symbol);
         let mut balance_manager_base = BalanceManagerBase::new();
         balance_manager_base.set_balance_manager(balance_manager);
         balance_manager_base.set_symbol(symbol);
@@ -233,6 +256,7 @@ mod tests {
     use crate::balance::manager::tests::balance_manager_base::BalanceManagerBase;
     use crate::explanation::Explanation;
     use crate::infrastructure::init_lifetime_manager;
+    use mmb_domain::exchanges::symbol::{ContractType, Symbol};
     use mmb_domain::market::CurrencyCode;
 
     use mmb_domain::order::pool::OrdersPool;
@@ -554,6 +578,134 @@ mod tests {
         );
     }
 
+    fn create_quanto_test_obj(bnb_amount: Amount) -> BalanceManagerDerivative {
+        init_lifetime_manager();
+
+        let test_object = BalanceManagerDerivative::new_by_symbol(
+            BalanceManagerDerivative::create_quanto_symbol(),
+        );
+
+        let exchange_account_id = test_object.balance_manager_base.exchange_account_id_1;
+        BalanceManagerBase::update_balance(
+            &mut test_object.balance_manager(),
+            exchange_account_id,
+            hashmap![BalanceManagerBase::bnb() => bnb_amount],
+        );
+        test_object
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn reservation_with_zero_price_should_fail_without_panic() {
+        init_logger();
+        let test_object =
+            create_test_obj_by_currency_code(BalanceManagerBase::eth(), dec!(100), false);
+
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Buy,
+            dec!(0),
+            dec!(5),
+        );
+
+        assert!(!test_object
+            .balance_manager()
+            .can_reserve(&reserve_parameters, &mut None));
+        assert!(test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_symbols_have_expected_contract_types() {
+        let contract_type = |symbol: Symbol| symbol.contract_type;
+
+        assert_eq!(
+            contract_type(BalanceManagerDerivative::create_symbol(false)),
+            ContractType::Inverse
+        );
+        assert_eq!(
+            contract_type(BalanceManagerDerivative::create_symbol(true)),
+            ContractType::Linear
+        );
+        assert_eq!(
+            contract_type(BalanceManagerDerivative::create_quanto_symbol()),
+            ContractType::Quanto
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn quanto_reservation_should_use_balance_currency_like_linear() {
+        init_logger();
+        let linear = create_test_obj_by_currency_code(BalanceManagerBase::btc(), dec!(100), true);
+        let quanto = create_quanto_test_obj(dec!(100));
+        let price = BalanceManagerDerivative::price();
+
+        for test_object in [&linear, &quanto] {
+            let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+                OrderSide::Sell,
+                price,
+                dec!(5),
+            );
+            test_object
+                .balance_manager()
+                .try_reserve(&reserve_parameters, &mut None)
+                .expect("in test");
+        }
+
+        assert_eq!(
+            quanto
+                .balance_manager_base
+                .get_balance_by_currency_code(BalanceManagerBase::bnb(), price)
+                .expect("in test"),
+            (dec!(100) - dec!(5) * BalanceManagerDerivative::reversed_price_x_multiplier())
+                * dec!(0.95)
+        );
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            assert_eq!(
+                quanto
+                    .balance_manager_base
+                    .get_balance_by_trade_side(side, price),
+                linear
+                    .balance_manager_base
+                    .get_balance_by_trade_side(side, price),
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(OrderSide::Buy)]
+    #[case(OrderSide::Sell)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn fill_should_change_quanto_position(#[case] order_side: OrderSide) {
+        init_logger();
+        let test_object = create_quanto_test_obj(dec!(100));
+
+        let mut order = test_object
+            .balance_manager_base
+            .create_order(order_side, ReservationId::generate());
+        order.add_fill(BalanceManagerDerivative::create_order_fill(
+            dec!(0.1),
+            dec!(1),
+            dec!(0.1),
+            dec!(0),
+            true,
+        ));
+
+        let configuration_descriptor = test_object.balance_manager_base.configuration_descriptor;
+        test_object
+            .balance_manager()
+            .order_was_filled(configuration_descriptor, &order);
+
+        assert_eq!(
+            test_object.balance_manager().get_position(
+                test_object.balance_manager_base.exchange_account_id_1,
+                test_object.balance_manager_base.symbol().currency_pair(),
+                order_side,
+            ),
+            dec!(1)
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn fill_buy_should_commission_should_be_deducted_from_balance() {
         init_logger();
@@ -2040,8 +2192,10 @@ mod tests {
         let reservation_1 = balance_manager
             .get_reservation_expected(reservation_id_1)
             .clone();
-        let balance_1 =
-            initial_balance - reservation_1.convert_in_reservation_currency(reservation_1.amount);
+        let balance_1 = initial_balance
+            - reservation_1
+                .convert_in_reservation_currency(reservation_1.amount)
+                .expect("in test");
         assert_eq!(
             test_object
                 .balance_manager()
@@ -2058,8 +2212,10 @@ mod tests {
         let reservation_2 = balance_manager
             .get_reservation_expected(reservation_id_2)
             .clone();
-        let balance_2 =
-            balance_1 - reservation_2.convert_in_reservation_currency(reservation_2.amount);
+        let balance_2 = balance_1
+            - reservation_2
+                .convert_in_reservation_currency(reservation_2.amount)
+                .expect("in test");
         assert_eq!(
             test_object
                 .balance_manager()
@@ -2074,8 +2230,12 @@ mod tests {
             &None
         ));
 
-        let add = reservation_1.convert_in_reservation_currency(amount_to_transfer);
-        let sub = reservation_2.convert_in_reservation_currency(amount_to_transfer);
+        let add = reservation_1
+            .convert_in_reservation_currency(amount_to_transfer)
+            .expect("in test");
+        let sub = reservation_2
+            .convert_in_reservation_currency(amount_to_transfer)
+            .expect("in test");
         assert_eq!(
             test_object
                 .balance_manager()
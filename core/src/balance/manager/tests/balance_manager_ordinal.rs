@@ -156,13 +156,16 @@ mod tests {
     use crate::balance::manager::balance_manager::BalanceManager;
     use crate::balance::manager::position_change::PositionChange;
     use crate::balance::manager::tests::balance_manager_base::BalanceManagerBase;
+    use crate::balance::manager::two_sided_reservation::{QuoteLevel, TwoSidedReservation};
     use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairToSymbolConverter;
     use crate::misc::reserve_parameters::ReserveParameters;
+    use crate::settings::ReservationFeeSettings;
     use mmb_domain::exchanges::symbol::{Precision, Symbol};
     use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
     use mmb_domain::order::pool::OrdersPool;
     use mmb_domain::order::snapshot::{
-        ClientOrderFillId, ClientOrderId, OrderSide, OrderSnapshot, OrderStatus, ReservationId,
+        ClientOrderFillId, ClientOrderId, OrderRole, OrderSide, OrderSnapshot, OrderStatus,
+        ReservationId,
     };
 
     use super::BalanceManagerOrdinal;
@@ -568,6 +571,95 @@ mod tests {
         assert!(reservation.approved_parts.is_empty());
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn try_reserve_buy_with_expected_fee() {
+        init_logger();
+        let mut test_object = create_test_obj_by_currency_code(BalanceManagerBase::btc(), dec!(1));
+        test_object
+            .balance_manager()
+            .set_reservation_fee(Some(ReservationFeeSettings {
+                safety_margin: dec!(0.5),
+            }));
+
+        // taker fee 0.2% with margin for whole balance can't be paid
+        let reserve_parameters = test_object.balance_manager_base.create_reserve_parameters(
+            OrderSide::Buy,
+            dec!(0.2),
+            dec!(5),
+        );
+        assert!(test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .is_none());
+
+        // maker fee 0.1% with margin: 4.99 * 0.2 + 4.99 * 0.001 * 1.5 * 0.2 = 0.999497
+        let reserve_parameters = test_object
+            .balance_manager_base
+            .create_reserve_parameters(OrderSide::Buy, dec!(0.2), dec!(4.99))
+            .with_order_role(OrderRole::Maker);
+        let reservation_id = test_object
+            .balance_manager()
+            .try_reserve(&reserve_parameters, &mut None)
+            .expect("in test");
+
+        // expected fee is held along with order cost
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(0.000503))
+        );
+
+        // 0.005 * 0.2 = 0.001 fits in balance without fee only
+        let small_reserve_parameters = test_object
+            .balance_manager_base
+            .create_reserve_parameters(OrderSide::Buy, dec!(0.2), dec!(0.005))
+            .with_order_role(OrderRole::Maker);
+        assert!(test_object
+            .balance_manager()
+            .try_reserve(&small_reserve_parameters, &mut None)
+            .is_none());
+
+        let order = test_object
+            .balance_manager_base
+            .create_order(OrderSide::Buy, reservation_id);
+        let client_order_id = order.header.client_order_id.clone();
+        test_object.balance_manager().approve_reservation(
+            reservation_id,
+            &client_order_id,
+            dec!(4.99),
+        );
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(0.000503))
+        );
+
+        // fee is released proportionally with unreserved amount
+        test_object
+            .balance_manager()
+            .unreserve_by_client_order_id(reservation_id, client_order_id.clone(), dec!(2.495))
+            .expect("in test");
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(0.5002515))
+        );
+
+        test_object
+            .balance_manager()
+            .unreserve_by_client_order_id(reservation_id, client_order_id, dec!(2.495))
+            .expect("in test");
+        assert_eq!(
+            test_object
+                .balance_manager()
+                .get_balance_by_reserve_parameters(&reserve_parameters),
+            Some(dec!(1))
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn try_reserve_sell_not_enough_balance() {
         init_logger();
@@ -911,6 +1003,99 @@ mod tests {
         assert!(reservation.approved_parts.is_empty());
     }
 
+    fn try_reserve_two_sided(
+        test_object: &BalanceManagerOrdinal,
+        bid_levels: &[QuoteLevel],
+        ask_levels: &[QuoteLevel],
+    ) -> Option<TwoSidedReservation> {
+        let base = &test_object.balance_manager_base;
+        test_object.balance_manager().try_reserve_two_sided(
+            base.configuration_descriptor,
+            base.exchange_account_id_1,
+            base.symbol(),
+            bid_levels,
+            ask_levels,
+            &mut None,
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn try_reserve_two_sided_not_enough_balance_for_one_level() {
+        init_logger();
+        let test_object = create_eth_btc_test_obj(dec!(1), dec!(5));
+
+        let bid_levels = [
+            QuoteLevel::new(dec!(0.2), dec!(2)),
+            QuoteLevel::new(dec!(0.19), dec!(3)),
+        ];
+        let ask_levels = [
+            QuoteLevel::new(dec!(0.21), dec!(2)),
+            QuoteLevel::new(dec!(0.22), dec!(3.5)),
+        ];
+
+        assert!(try_reserve_two_sided(&test_object, &bid_levels, &ask_levels).is_none());
+
+        let base = &test_object.balance_manager_base;
+        assert_eq!(
+            base.get_balance_by_trade_side(OrderSide::Buy, dec!(0.2)),
+            Some(dec!(1))
+        );
+        assert_eq!(
+            base.get_balance_by_trade_side(OrderSide::Sell, dec!(0.2)),
+            Some(dec!(5))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    pub async fn try_reserve_two_sided_enough_balance() {
+        init_logger();
+        let test_object = create_eth_btc_test_obj(dec!(1), dec!(5));
+
+        let bid_levels = [
+            QuoteLevel::new(dec!(0.2), dec!(2)),
+            QuoteLevel::new(dec!(0.19), dec!(3)),
+        ];
+        let ask_levels = [
+            QuoteLevel::new(dec!(0.21), dec!(2)),
+            QuoteLevel::new(dec!(0.22), dec!(3)),
+        ];
+
+        let reservation =
+            try_reserve_two_sided(&test_object, &bid_levels, &ask_levels).expect("in test");
+        assert_eq!(reservation.bids.len(), 2);
+        assert_eq!(reservation.asks.len(), 2);
+
+        let base = &test_object.balance_manager_base;
+        assert_eq!(
+            base.get_balance_by_trade_side(OrderSide::Buy, dec!(0.2)),
+            Some(dec!(0.03))
+        );
+        assert_eq!(
+            base.get_balance_by_trade_side(OrderSide::Sell, dec!(0.2)),
+            Some(dec!(0))
+        );
+
+        let balance_manager = test_object.balance_manager();
+        for (reservation_id, side, level) in reservation
+            .bids
+            .iter()
+            .zip(&bid_levels)
+            .map(|(id, level)| (id, OrderSide::Buy, level))
+            .chain(
+                reservation
+                    .asks
+                    .iter()
+                    .zip(&ask_levels)
+                    .map(|(id, level)| (id, OrderSide::Sell, level)),
+            )
+        {
+            let reservation = balance_manager.get_reservation_expected(*reservation_id);
+            assert_eq!(reservation.order_side, side);
+            assert_eq!(reservation.price, level.price);
+            assert_eq!(reservation.amount, level.amount);
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     pub async fn try_reserve_three_not_enough_balance_for_1() {
         init_logger();
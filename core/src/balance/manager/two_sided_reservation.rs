@@ -0,0 +1,21 @@
+use mmb_domain::order::snapshot::{Amount, Price, ReservationId};
+
+/// Price level of quote ladder which should be funded by reservation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteLevel {
+    pub price: Price,
+    pub amount: Amount,
+}
+
+impl QuoteLevel {
+    pub fn new(price: Price, amount: Amount) -> Self {
+        Self { price, amount }
+    }
+}
+
+/// Reservations for full quote ladder on both sides. Ids are in the same order as requested levels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwoSidedReservation {
+    pub bids: Vec<ReservationId>,
+    pub asks: Vec<ReservationId>,
+}
@@ -2,6 +2,7 @@ pub(crate) mod balance_position_model;
 pub(crate) mod balance_reservation_manager;
 pub(crate) mod balance_reservation_preset;
 pub(crate) mod balance_reservation_storage;
+pub mod capital_usage;
 pub(crate) mod changes;
 pub mod manager;
 pub(crate) mod virtual_balance_holder;
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use anyhow::Result;
+
 use crate::balance::manager::balance_request::BalanceRequest;
 use crate::exchanges::general::exchange::Exchange;
 use crate::explanation::{Explanation, OptionExplanationAddReasonExt};
@@ -92,13 +94,13 @@ impl VirtualBalanceHolder {
         symbol: Arc<Symbol>,
         diff_in_amount_currency: Amount,
         price: Price,
-    ) {
+    ) -> Result<()> {
         if !symbol.is_derivative {
-            let diff_in_request_currency = symbol.convert_amount_from_amount_currency_code(
+            let diff_in_request_currency = symbol.try_convert_amount_from_amount_currency_code(
                 request.currency_code,
                 diff_in_amount_currency,
                 price,
-            );
+            )?;
             self.add_balance(request, diff_in_request_currency);
         } else {
             let balance_currency_code_request = BalanceRequest::new(
@@ -109,16 +111,19 @@ impl VirtualBalanceHolder {
                     .balance_currency_code
                     .expect("symbol.balance_currency_code should be non None"),
             );
-            let diff_in_balance_currency_code = symbol.convert_amount_from_amount_currency_code(
-                balance_currency_code_request.currency_code,
-                diff_in_amount_currency,
-                price,
-            );
+            let diff_in_balance_currency_code = symbol
+                .try_convert_amount_from_amount_currency_code(
+                    balance_currency_code_request.currency_code,
+                    diff_in_amount_currency,
+                    price,
+                )?;
             self.add_balance(
                 &balance_currency_code_request,
                 diff_in_balance_currency_code,
             );
         }
+
+        Ok(())
     }
 
     pub fn get_virtual_balance(
@@ -159,11 +164,16 @@ impl VirtualBalanceHolder {
 
             explanation.with_reason(|| format!("get_virtual_balance balance_currency_code_balance_diff = {balance_currency_code_balance_diff}"));
 
-            let cur_balance_diff = symbol.convert_amount_from_balance_currency_code(
-                balance_request.currency_code,
-                balance_currency_code_balance_diff,
-                price,
-            );
+            let cur_balance_diff = symbol
+                .try_convert_amount_from_balance_currency_code(
+                    balance_request.currency_code,
+                    balance_currency_code_balance_diff,
+                    price,
+                )
+                .map_err(|error| {
+                    log::error!("Failed to convert balance diff {balance_currency_code_balance_diff} for {balance_request:?} with price {price}: {error:?}")
+                })
+                .ok()?;
 
             explanation.with_reason(|| {
                 format!("get_virtual_balance current_balance_diff = {cur_balance_diff}")
@@ -194,11 +204,16 @@ impl VirtualBalanceHolder {
                 .expect("failed to get exchange balance: balance_currency_code should be non None"),
         )?;
 
-        Some(symbol.convert_amount_from_balance_currency_code(
-            currency_code,
-            balance_currency_code_balance,
-            price,
-        ))
+        symbol
+            .try_convert_amount_from_balance_currency_code(
+                currency_code,
+                balance_currency_code_balance,
+                price,
+            )
+            .map_err(|error| {
+                log::error!("Failed to convert balance {balance_currency_code_balance} into {currency_code} for {exchange_account_id} with price {price}: {error:?}")
+            })
+            .ok()
     }
 
     fn get_raw_exchange_balance(
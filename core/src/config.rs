@@ -12,6 +12,7 @@ use toml_edit::{value, ArrayOfTables, Document, Table};
 pub static EXCHANGE_ACCOUNT_ID: &str = "exchange_account_id";
 pub static API_KEY: &str = "api_key";
 pub static SECRET_KEY: &str = "secret_key";
+pub static PASSPHRASE: &str = "passphrase";
 pub static CONFIG_PATH: &str = "config.toml";
 pub static CREDENTIALS_PATH: &str = "credentials.toml";
 
@@ -81,16 +82,20 @@ pub fn save_settings(settings: &str, config_path: &str, credentials_path: &str)
         let (exchange_account_id, api_key, secret_key) = get_credentials_data(exchange_settings)
             .ok_or_else(|| anyhow!("Unable to get credentials data for exchange"))?;
 
-        let creds = hashmap![
+        let mut creds = hashmap![
             API_KEY => api_key,
             SECRET_KEY => secret_key
         ];
+        if let Some(passphrase) = exchange_settings.get(PASSPHRASE).and_then(|v| v.as_str()) {
+            creds.insert(PASSPHRASE, passphrase.to_owned());
+        }
 
         credentials_per_exchange.insert(exchange_account_id, creds);
 
         // Remove credentials from main config
         let _ = exchange_settings.remove(API_KEY);
         let _ = exchange_settings.remove(SECRET_KEY);
+        let _ = exchange_settings.remove(PASSPHRASE);
     }
 
     let serialized_creds = toml_edit::ser::to_string(&credentials_per_exchange)?;
@@ -145,6 +150,15 @@ fn parse_toml_settings(settings: &str, credentials: &str) -> Result<Document> {
 
             exchange.insert(API_KEY, value(api_key));
             exchange.insert(SECRET_KEY, value(secret_key));
+
+            // Passphrase is optional because it's required only by some exchanges
+            if let Some(passphrase) = credentials
+                .get(exchange_account_id)
+                .and_then(|v| v.get(PASSPHRASE))
+                .and_then(|v| v.as_str())
+            {
+                exchange.insert(PASSPHRASE, value(passphrase));
+            }
         }
     }
 
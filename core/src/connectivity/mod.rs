@@ -1,7 +1,9 @@
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 use thiserror::Error;
 use url::Url;
 
+mod proxy;
 mod websocket;
 mod websocket_connection;
 
@@ -17,11 +19,15 @@ pub enum ConnectivityError {
     SecondaryConnectorIsNotPresent,
     #[error("not connected")]
     NotConnected,
+    #[error("connection wasn't established in `{0:?}`")]
+    Timeout(Duration),
+    #[error("failed to ping socket: `{0}`")]
+    FailedToPing(tokio_tungstenite::tungstenite::Error),
 }
 
 pub type Result<T> = std::result::Result<T, ConnectivityError>;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum WebSocketRole {
     Main,
     Secondary,
@@ -36,15 +42,64 @@ impl Display for WebSocketRole {
     }
 }
 
+/// Application level message which exchange requires to receive periodically,
+/// otherwise it closes websocket connection regardless of protocol pings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketKeepAlive {
+    pub message: String,
+    pub interval: Duration,
+}
+
+/// Compression of binary websocket frames, decompressed frames are forwarded as text messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebSocketCompression {
+    Gzip,
+}
+
 #[derive(Debug, Clone)]
 pub struct WebSocketParams {
     url: Url,
+    /// Custom connector for connection via proxy, default one is used if not set
+    connector: Option<NetworkConnector>,
+    keep_alive: Option<WebSocketKeepAlive>,
+    /// Additional headers of handshake request, e.g. for authentication of connection
+    headers: Vec<(String, String)>,
+    /// Binary frames are ignored if not set
+    compression: Option<WebSocketCompression>,
 }
 
 impl WebSocketParams {
     pub fn new(url: Url) -> Self {
-        WebSocketParams { url }
+        WebSocketParams {
+            url,
+            connector: None,
+            keep_alive: None,
+            headers: Vec::new(),
+            compression: None,
+        }
+    }
+
+    pub fn with_connector(mut self, connector: NetworkConnector) -> Self {
+        self.connector = Some(connector);
+        self
+    }
+
+    pub fn with_keep_alive(mut self, keep_alive: WebSocketKeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_compression(mut self, compression: WebSocketCompression) -> Self {
+        self.compression = Some(compression);
+        self
     }
 }
 
+pub use proxy::{NetworkConnector, Proxy};
 pub use websocket::{websocket_open, WsSender};
+pub use websocket_connection::{measure_round_trip, WebSocketRoundTrip};
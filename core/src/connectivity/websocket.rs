@@ -1,9 +1,10 @@
-use super::websocket_connection::open_connection;
+use super::websocket_connection::{open_connection, LastFrameTime};
 use super::{ConnectivityError, Result, WebSocketParams, WebSocketRole};
 use crate::infrastructure::spawn_future;
 use futures::FutureExt;
 use mmb_domain::market::ExchangeAccountId;
 use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
 use tokio::sync::mpsc;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_util::sync::{CancellationToken, DropGuard as CancellationTokenDropGuard};
@@ -13,6 +14,8 @@ pub struct WsSender {
     main_sender: mpsc::UnboundedSender<Message>,
     /// Secondary websocket connection sender
     secondary_sender: Option<mpsc::UnboundedSender<Message>>,
+    /// Time of last frame received over main websocket connection
+    last_frame_time: LastFrameTime,
     /// Cancellation token for service futures
     _cancel: CancellationTokenDropGuard,
 }
@@ -34,6 +37,28 @@ impl WsSender {
             .send(Message::Text(msg))
             .map_err(|_| ConnectivityError::NotConnected)
     }
+
+    /// Time of last frame received over main websocket, including pongs, so it is updated
+    /// even if there is no market activity when exchange answers pings
+    pub fn last_frame_time(&self) -> Option<DateTime> {
+        *self.last_frame_time.lock()
+    }
+}
+
+#[cfg(test)]
+impl WsSender {
+    /// Sender without connection, messages of main websocket are received by returned receiver
+    pub(crate) fn new_for_test() -> (Self, mpsc::UnboundedReceiver<Message>) {
+        let (main_sender, main_receiver) = mpsc::unbounded_channel();
+        let sender = WsSender {
+            main_sender,
+            secondary_sender: None,
+            last_frame_time: LastFrameTime::default(),
+            _cancel: CancellationToken::new().drop_guard(),
+        };
+
+        (sender, main_receiver)
+    }
 }
 
 pub async fn websocket_open(
@@ -57,17 +82,20 @@ async fn connect_both_parallel(
     exchange_account_id: ExchangeAccountId,
 ) -> Result<(WsSender, mpsc::UnboundedReceiver<String>)> {
     let cancel = CancellationToken::new();
+    let last_frame_time = LastFrameTime::default();
     let (main, secondary) = tokio::join!(
         open_connection(
             exchange_account_id,
             WebSocketRole::Main,
             main,
+            last_frame_time.clone(),
             cancel.clone()
         ),
         open_connection(
             exchange_account_id,
             WebSocketRole::Secondary,
             secondary,
+            LastFrameTime::default(),
             cancel.clone()
         )
     );
@@ -79,6 +107,7 @@ async fn connect_both_parallel(
             let sender = WsSender {
                 main_sender: main.0,
                 secondary_sender: Some(secondary.0),
+                last_frame_time,
                 _cancel: cancel.drop_guard(),
             };
             spawn_future(
@@ -127,16 +156,19 @@ async fn connect_only_main(
     exchange_account_id: ExchangeAccountId,
 ) -> Result<(WsSender, mpsc::UnboundedReceiver<String>)> {
     let cancel = CancellationToken::new();
+    let last_frame_time = LastFrameTime::default();
     let (tx, rx) = open_connection(
         exchange_account_id,
         WebSocketRole::Main,
         params,
+        last_frame_time.clone(),
         cancel.clone(),
     )
     .await?;
     let sender = WsSender {
         main_sender: tx,
         secondary_sender: None,
+        last_frame_time,
         _cancel: cancel.drop_guard(),
     };
     Ok((sender, rx))
@@ -1,16 +1,31 @@
-use super::{ConnectivityError, Result, WebSocketParams, WebSocketRole};
+use super::{
+    ConnectivityError, NetworkConnector, Result, WebSocketCompression, WebSocketKeepAlive,
+    WebSocketParams, WebSocketRole,
+};
 use crate::infrastructure::spawn_future_ok;
+use crate::misc::time::time_manager;
+use flate2::read::GzDecoder;
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
+use hyper::http::header::{HeaderName, HeaderValue};
+use hyper::http::uri::InvalidUri;
 use mmb_domain::market::ExchangeAccountId;
 use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
 use std::fmt::Formatter;
+use std::io::Read;
+use std::sync::Arc;
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
-use tokio::time::{timeout, timeout_at, Duration, Instant};
+use tokio::time::{interval_at, timeout, timeout_at, Duration, Instant, Interval};
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::{Request, Response};
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::{client_async_tls, connect_async, MaybeTlsStream, WebSocketStream};
 use tokio_util::sync::CancellationToken;
+use url::Url;
 
 /// Time interval between heartbeat pings are sent
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
@@ -25,6 +40,9 @@ const WRITE_DEADLINE_TIMEOUT: Duration = Duration::from_secs(1);
 
 const PING_MESSAGE: &[u8; 9] = b"heartbeat";
 
+/// Time of last frame received over connection, including pongs in reply to heartbeat pings
+pub(crate) type LastFrameTime = Arc<Mutex<Option<DateTime>>>;
+
 type TrySendResult = std::result::Result<(), mpsc::error::TrySendError<Message>>;
 
 /// Compound log records key
@@ -50,6 +68,8 @@ struct WriterHandle {
     internal_rx: mpsc::Receiver<Message>,
     /// User's input channel
     writer_rx: mpsc::UnboundedReceiver<Message>,
+    /// Application level keep-alive messages required by exchange
+    keep_alive: Option<KeepAliveTimer>,
     /// Cancellation token.
     ///
     /// This one is bidirectional: we use it to trigger signal and to wait for the signal from
@@ -102,6 +122,11 @@ impl WriterHandle {
                         }
                     }
                 },
+
+                msg = KeepAliveTimer::tick(&mut self.keep_alive) => {
+                    log::trace!("Websocket {} writer sends keep-alive message", self.meta);
+                    msg
+                }
             };
 
             tokio::select! {
@@ -152,6 +177,32 @@ impl WriterHandle {
     }
 }
 
+struct KeepAliveTimer {
+    interval: Interval,
+    message: String,
+}
+
+impl KeepAliveTimer {
+    fn new(keep_alive: WebSocketKeepAlive) -> Self {
+        KeepAliveTimer {
+            // first message is sent after interval, not right after connection
+            interval: interval_at(Instant::now() + keep_alive.interval, keep_alive.interval),
+            message: keep_alive.message,
+        }
+    }
+
+    /// Never completes if keep-alive isn't required
+    async fn tick(timer: &mut Option<Self>) -> Message {
+        match timer {
+            Some(timer) => {
+                timer.interval.tick().await;
+                Message::Text(timer.message.clone())
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
+
 /// Websocket reader
 struct ReaderHandle {
     /// WS reader handle
@@ -162,6 +213,10 @@ struct ReaderHandle {
     reader_tx: mpsc::UnboundedSender<String>,
     /// Channel to `WriterHandle`
     internal_tx: mpsc::Sender<Message>,
+    /// Compression of binary messages which are forwarded to the user after decompression
+    compression: Option<WebSocketCompression>,
+    /// Shared with the user to detect stalled connection
+    last_frame_time: LastFrameTime,
     /// Cancellation token.
     ///
     /// This one is bidirectional: we use it to trigger signal and to wait for the signal from
@@ -227,6 +282,7 @@ impl ReaderHandle {
 
             // received message processing
             receive_ts = Instant::now();
+            *self.last_frame_time.lock() = Some(time_manager::now());
             next_heartbeat_ts = receive_ts + HEARTBEAT_INTERVAL;
 
             match msg {
@@ -239,10 +295,27 @@ impl ReaderHandle {
                         return;
                     }
                 }
-                Message::Binary(bytes) => log::trace!(
-                    "Websocket {} reader received binary message: {bytes:x?}",
-                    self.meta,
-                ),
+                Message::Binary(bytes) => match self.compression {
+                    Some(compression) => match decompress(compression, &bytes) {
+                        Ok(text) => {
+                            if self.forward_message(text).is_err() {
+                                log::trace!(
+                                    "Websocket {} reader failed to forward message, exiting",
+                                    self.meta
+                                );
+                                return;
+                            }
+                        }
+                        Err(e) => log::error!(
+                            "Websocket {} reader failed to decompress binary message: {e:?}",
+                            self.meta
+                        ),
+                    },
+                    None => log::trace!(
+                        "Websocket {} reader received binary message: {bytes:x?}",
+                        self.meta,
+                    ),
+                },
                 Message::Ping(msg) => {
                     if (self.send_pong(Message::Pong(msg))).is_err() {
                         log::trace!(
@@ -292,6 +365,15 @@ impl ReaderHandle {
     }
 }
 
+fn decompress(compression: WebSocketCompression, bytes: &[u8]) -> std::io::Result<String> {
+    let mut text = String::new();
+    match compression {
+        WebSocketCompression::Gzip => GzDecoder::new(bytes).read_to_string(&mut text)?,
+    };
+
+    Ok(text)
+}
+
 /// Open WebSocket connection.
 ///
 /// Provided cancellation token can be used to shutdown service futures instantly.
@@ -302,14 +384,13 @@ pub async fn open_connection(
     exchange_account_id: ExchangeAccountId,
     role: WebSocketRole,
     params: WebSocketParams,
+    last_frame_time: LastFrameTime,
     cancel: CancellationToken,
 ) -> Result<(
     mpsc::UnboundedSender<Message>,
     mpsc::UnboundedReceiver<String>,
 )> {
-    let (ws_stream, _) = connect_async(params.url.clone())
-        .await
-        .map_err(|e| ConnectivityError::FailedToConnect(role, params.url.to_string(), e))?;
+    let ws_stream = connect(role, &params).await?;
 
     let meta = Meta(exchange_account_id, role);
 
@@ -323,6 +404,7 @@ pub async fn open_connection(
         meta,
         internal_rx,
         writer_rx,
+        keep_alive: params.keep_alive.map(KeepAliveTimer::new),
         cancel: cancel.clone(),
     };
 
@@ -331,6 +413,8 @@ pub async fn open_connection(
         meta,
         internal_tx,
         reader_tx,
+        compression: params.compression,
+        last_frame_time,
         cancel,
     };
 
@@ -347,3 +431,123 @@ pub async fn open_connection(
 
     Ok((writer_tx, reader_rx))
 }
+
+/// Time of establishing WebSocket connection and round trip of ping over it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebSocketRoundTrip {
+    /// Duration of TCP connection, TLS and WebSocket handshakes
+    pub connect: Duration,
+    /// Time between sending ping and receiving pong
+    pub ping: Duration,
+}
+
+/// Open temporary WebSocket connection and measure round trip time of ping over it.
+/// Connection is closed after receiving pong
+pub async fn measure_round_trip(
+    role: WebSocketRole,
+    params: &WebSocketParams,
+    max_duration: Duration,
+) -> Result<WebSocketRoundTrip> {
+    timeout(max_duration, measure_round_trip_inner(role, params))
+        .await
+        .map_err(|_| ConnectivityError::Timeout(max_duration))?
+}
+
+async fn measure_round_trip_inner(
+    role: WebSocketRole,
+    params: &WebSocketParams,
+) -> Result<WebSocketRoundTrip> {
+    let connect_started = Instant::now();
+    let mut ws_stream = connect(role, params).await?;
+    let connect_duration = connect_started.elapsed();
+
+    let ping_started = Instant::now();
+    ws_stream
+        .send(Message::Ping(PING_MESSAGE.to_vec()))
+        .await
+        .map_err(ConnectivityError::FailedToPing)?;
+
+    while let Some(msg) = ws_stream.next().await {
+        // exchange can send greeting messages or own pings before pong
+        if let Message::Pong(payload) = msg.map_err(ConnectivityError::FailedToPing)? {
+            if payload == PING_MESSAGE {
+                let ping_duration = ping_started.elapsed();
+                let _ = ws_stream.close(None).await;
+
+                return Ok(WebSocketRoundTrip {
+                    connect: connect_duration,
+                    ping: ping_duration,
+                });
+            }
+        }
+    }
+
+    Err(ConnectivityError::NotConnected)
+}
+
+async fn connect(
+    role: WebSocketRole,
+    params: &WebSocketParams,
+) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let connection = match create_request(params) {
+        Err(err) => Err(err),
+        Ok(request) => match &params.connector {
+            None => connect_async(request).await,
+            Some(connector) => connect_with_connector(connector, &params.url, request).await,
+        },
+    };
+    let (ws_stream, _) = connection
+        .map_err(|e| ConnectivityError::FailedToConnect(role, params.url.to_string(), e))?;
+
+    Ok(ws_stream)
+}
+
+/// Handshake request with additional headers from params
+fn create_request(params: &WebSocketParams) -> tungstenite::Result<Request> {
+    let mut request = params.url.as_str().into_client_request()?;
+    for (name, value) in &params.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+        let value =
+            HeaderValue::from_str(value).map_err(|e| tungstenite::Error::HttpFormat(e.into()))?;
+        request.headers_mut().insert(name, value);
+    }
+
+    Ok(request)
+}
+
+async fn connect_with_connector(
+    connector: &NetworkConnector,
+    url: &Url,
+    request: Request,
+) -> tungstenite::Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)> {
+    let uri = url
+        .as_str()
+        .parse()
+        .map_err(|e: InvalidUri| tungstenite::Error::HttpFormat(e.into()))?;
+
+    let stream = connector.connect(uri).await?;
+    stream.set_nodelay(true)?;
+
+    client_async_tls(request, stream).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    #[test]
+    fn decompress_gzip_message() {
+        let message = r#"{"ping":1492420473027}"#;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(message.as_bytes()).expect("in test");
+        let bytes = encoder.finish().expect("in test");
+
+        let text = decompress(WebSocketCompression::Gzip, &bytes).expect("in test");
+
+        assert_eq!(text, message);
+    }
+}
@@ -1,6 +1,6 @@
-use crate::database::events::recorder::save_batch;
+use crate::database::events::recorder::save_to_db;
 use crate::exchanges::timeouts::timeout_manager;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use mmb_database::postgres_db::events::InsertEvent;
 use mmb_database::postgres_db::PgPool;
@@ -17,6 +17,7 @@ use tokio::task::spawn_blocking;
 const BUFFER_SIZE: usize = 16384;
 const EVENTS_FILE_PREFIX: &str = "events_";
 const NOT_FINISHED_FILED_PREFIX: &str = "writing_yet_";
+const BROKEN_FILE_PREFIX: &str = "broken_";
 
 fn get_postponed_events_dir(
     postponed_events_dir_from_settings: Option<PathBuf>,
@@ -63,27 +64,119 @@ impl PostponedEventsFileFormat {
     }
 }
 
+/// Depth of postponed events backlog which is waiting for restoring to database
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboxBacklog {
+    /// Count of postponed events files, every file contains single batch of events
+    pub files: usize,
+    /// Total size of postponed events files in bytes
+    pub size: u64,
+    /// Count of events dropped because outbox size limit was reached
+    pub dropped_events: u64,
+}
+
+#[derive(Debug, Default)]
+struct OutboxState {
+    /// If true, events are postponed without trying to save them to database
+    /// until all postponed events are restored, so events order is kept
+    is_active: tokio::sync::Mutex<bool>,
+    backlog: parking_lot::Mutex<OutboxBacklog>,
+}
+
+/// Durable local outbox of events: every batch which can't be saved to database is appended
+/// as a separate file to postponed events dir and replayed in order when connectivity returns
 #[derive(Debug, Clone)]
 pub(crate) struct EventRecorderFallback {
     postponed_events_dir: Arc<Path>,
+    max_size: Option<u64>,
+    state: Arc<OutboxState>,
 }
 
 impl EventRecorderFallback {
     /// EventRecorder's fallback handlers
     /// postponed_events_dir: postponed events director from settings if exists
-    pub fn new(postponed_events_dir: Option<PathBuf>) -> Result<Self> {
+    /// max_size: max total size of postponed events files in bytes, unlimited if not set
+    pub fn new(postponed_events_dir: Option<PathBuf>, max_size: Option<u64>) -> Result<Self> {
+        let postponed_events_dir = init_postponed_events_dir(postponed_events_dir)?;
+
+        // Events left from previous launch should be restored before new ones
+        let backlog = calculate_backlog(&postponed_events_dir)?;
+        let state = OutboxState {
+            is_active: tokio::sync::Mutex::new(backlog.files > 0),
+            backlog: parking_lot::Mutex::new(backlog),
+        };
+
         Ok(Self {
-            postponed_events_dir: init_postponed_events_dir(postponed_events_dir)?,
+            postponed_events_dir,
+            max_size,
+            state: Arc::new(state),
         })
     }
 
+    pub(crate) fn backlog(&self) -> OutboxBacklog {
+        *self.state.backlog.lock()
+    }
+
+    /// Saves events to database if outbox isn't active, otherwise or if database is unavailable
+    /// events are postponed to file
+    pub(crate) async fn save_or_postpone(
+        &self,
+        pool: &PgPool,
+        table_name: &str,
+        events: Vec<InsertEvent>,
+    ) -> Result<()> {
+        let mut is_active = self.state.is_active.lock().await;
+        let events = match *is_active {
+            true => events,
+            false => match save_to_db(pool, table_name, events).await {
+                Ok(()) => return Ok(()),
+                Err(not_written_events) => {
+                    log::warn!("Database is unavailable, events are postponed to outbox until connectivity returns");
+                    *is_active = true;
+                    not_written_events
+                }
+            },
+        };
+
+        self.save_to_file(table_name.to_string(), events).await
+    }
+
+    /// Stops postponing new events if all postponed events are restored to database
+    pub(crate) async fn deactivate_if_restored(&self) -> Result<()> {
+        let mut is_active = self.state.is_active.lock().await;
+        if !*is_active
+            || !self
+                .get_existing_postponed_events_file_names()
+                .await?
+                .is_empty()
+        {
+            return Ok(());
+        }
+
+        *is_active = false;
+        log::info!("All postponed events are restored, events are saved to database again");
+
+        Ok(())
+    }
+
     pub(crate) async fn save_to_file(
         &self,
         table_name: String,
         not_written_events: Vec<InsertEvent>,
     ) -> Result<()> {
+        if let Some(max_size) = self.max_size {
+            let mut backlog = self.state.backlog.lock();
+            if backlog.size >= max_size {
+                backlog.dropped_events += not_written_events.len() as u64;
+                bail!(
+                    "Outbox size limit {max_size} bytes is reached, {} events of {table_name} are dropped",
+                    not_written_events.len()
+                );
+            }
+        }
+
         let postponed_events_dir = self.postponed_events_dir.clone();
-        spawn_blocking(move || -> Result<()> {
+        let file_size = spawn_blocking(move || -> Result<u64> {
             let now = timeout_manager::now();
 
             let file_names = FileNames::from_date(now);
@@ -101,6 +194,14 @@ impl EventRecorderFallback {
                 )
             })?;
 
+            let file = buf_writer
+                .into_inner()
+                .context("failed flushing postponed events file")?;
+            let file_size = file
+                .metadata()
+                .context("can't get size of postponed events file")?
+                .len();
+
             let finished_file_path = postponed_events_dir.join(&file_names.finished);
             fs::rename(not_finished_file_path, finished_file_path).with_context(|| {
                 format!(
@@ -109,10 +210,14 @@ impl EventRecorderFallback {
                 )
             })?;
 
-            Ok(())
+            Ok(file_size)
         })
         .await??;
 
+        let mut backlog = self.state.backlog.lock();
+        backlog.files += 1;
+        backlog.size += file_size;
+
         Ok(())
     }
 
@@ -129,14 +234,22 @@ impl EventRecorderFallback {
         .await?
     }
 
+    /// Restores postponed events files to database in specified order.
+    /// Restoring stops on the first file which can't be saved, so events order is kept.
+    /// Returns true if all files are restored
     pub(crate) async fn try_restore_to_db_postponed_events(
         &self,
         pool: &PgPool,
         file_names: &[OsString],
-    ) {
+    ) -> bool {
         for file_name in file_names {
             let file_path = self.postponed_events_dir.join(file_name);
 
+            let file_size = tokio::fs::metadata(&file_path)
+                .await
+                .map(|metadata| metadata.len())
+                .unwrap_or_default();
+
             let PostponedEventsFileFormat {
                 table_name, events, ..
             } = match load_from_file(file_path.clone()).await {
@@ -144,22 +257,64 @@ impl EventRecorderFallback {
                 Err(err) => {
                     let path = file_path.display();
                     log::error!("failed load postponed events file {path} with error: {err:?}");
+
+                    // Broken file is kept for investigation but excluded from restoring
+                    let broken_file_path = self.postponed_events_dir.join(format!(
+                        "{BROKEN_FILE_PREFIX}{}",
+                        file_name.to_string_lossy()
+                    ));
+                    match tokio::fs::rename(&file_path, broken_file_path).await {
+                        Ok(()) => self.remove_from_backlog(file_size),
+                        Err(err) => {
+                            log::error!(
+                                "failed renaming broken postponed events file {path}: {err}"
+                            );
+                            return false;
+                        }
+                    }
                     continue;
                 }
             };
 
-            match save_batch(pool, &table_name, events, self).await {
-                Err(err) => log::error!("failed resaving batch of events to db: {err}"),
-                Ok(()) => tokio::fs::remove_file(file_path)
-                    .await
-                    .unwrap_or_else(|err| {
-                        log::error!("failed removing file from postponed events: {err}")
-                    }),
+            if save_to_db(pool, &table_name, events).await.is_err() {
+                log::error!("failed resaving postponed events to db, restoring is postponed");
+                return false;
+            }
+
+            match tokio::fs::remove_file(file_path).await {
+                Ok(()) => self.remove_from_backlog(file_size),
+                Err(err) => {
+                    log::error!("failed removing file from postponed events: {err}");
+                    return false;
+                }
             }
         }
+
+        true
+    }
+
+    fn remove_from_backlog(&self, file_size: u64) {
+        let mut backlog = self.state.backlog.lock();
+        backlog.files = backlog.files.saturating_sub(1);
+        backlog.size = backlog.size.saturating_sub(file_size);
     }
 }
 
+fn calculate_backlog(postponed_events_dir: &Path) -> Result<OutboxBacklog> {
+    let mut backlog = OutboxBacklog::default();
+    for file_name in fs::read_dir(postponed_events_dir)
+        .context("can't read postponed events dir")?
+        .filter_map(select_events_file_names)
+    {
+        let metadata = fs::metadata(postponed_events_dir.join(file_name))
+            .context("can't get size of postponed events file")?;
+        backlog.files += 1;
+        backlog.size += metadata.len();
+    }
+
+    Ok(backlog)
+}
+
 struct FileNames {
     not_finished: String,
     finished: String,
@@ -220,7 +375,7 @@ async fn load_from_file(path: PathBuf) -> Result<PostponedEventsFileFormat> {
 #[cfg(test)]
 mod tests {
     use crate::database::events::recorder::fallback::{
-        load_from_file, EventRecorderFallback, PostponedEventsFileFormat,
+        load_from_file, EventRecorderFallback, OutboxBacklog, PostponedEventsFileFormat,
     };
     use bb8_postgres::bb8::PooledConnection;
     use bb8_postgres::PostgresConnectionManager;
@@ -231,7 +386,10 @@ mod tests {
     use mmb_utils::DateTime;
     use scopeguard::defer;
     use serde::{Deserialize, Serialize};
-    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use std::{env, fs};
+    use tokio::time::sleep;
     use tokio_postgres::NoTls;
 
     const TABLE_NAME: &str = "fallback_events";
@@ -247,6 +405,21 @@ mod tests {
         TestEvent { date: Utc::now() }
     }
 
+    fn test_event_data() -> InsertEvent {
+        InsertEvent {
+            version: 0,
+            json: test_event().get_json().expect("in test"),
+        }
+    }
+
+    /// Separate dir for every test, so tests don't see postponed events of each other
+    fn test_postponed_events_dir(test_name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("postponed_events_{test_name}"));
+        // files can be left by previous failed run
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
     async fn init_test() -> PgPoolMutex {
         let pool_mutex = PgPoolMutex::create(&get_database_url(), 1).await;
         let connection = pool_mutex.pool.get_connection_expected().await;
@@ -272,7 +445,7 @@ mod tests {
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn save_files_on_fallback() {
         // arrange
-        let fallback = EventRecorderFallback::new(None).expect("in test");
+        let fallback = EventRecorderFallback::new(None, None).expect("in test");
         defer! {
             fs::remove_dir_all(fallback.clone().postponed_events_dir).expect("clear postponed events dir");
         };
@@ -324,7 +497,7 @@ mod tests {
     async fn restore_postponed_events_in_db() {
         let pool_mutex = init_test().await;
 
-        let fallback = EventRecorderFallback::new(None).expect("in test");
+        let fallback = EventRecorderFallback::new(None, None).expect("in test");
         defer! {
             fs::remove_dir_all(fallback.clone().postponed_events_dir).expect("clear postponed events dir");
         };
@@ -372,4 +545,98 @@ mod tests {
         let json: serde_json::Value = rows[0].get("json");
         pretty_assertions::assert_eq!(json, test_event_data.json);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn drop_events_when_max_size_is_reached() {
+        let dir = test_postponed_events_dir("max_size");
+        let fallback = EventRecorderFallback::new(Some(dir), Some(1)).expect("in test");
+        defer! {
+            fs::remove_dir_all(fallback.clone().postponed_events_dir).expect("clear postponed events dir");
+        };
+
+        // limit is checked before saving, so the first file is saved even if it exceeds the limit
+        fallback
+            .save_to_file(TABLE_NAME.to_string(), vec![test_event_data()])
+            .await
+            .expect("in test");
+
+        let result = fallback
+            .save_to_file(
+                TABLE_NAME.to_string(),
+                vec![test_event_data(), test_event_data()],
+            )
+            .await;
+        assert!(result.is_err());
+
+        let backlog = fallback.backlog();
+        assert_eq!(backlog.files, 1);
+        assert_eq!(backlog.dropped_events, 2);
+
+        let file_names = fallback
+            .get_existing_postponed_events_file_names()
+            .await
+            .expect("in test");
+        assert_eq!(file_names.len(), 1, "dropped events shouldn't be saved");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn restore_postponed_events_in_order_and_deactivate_outbox() {
+        let pool_mutex = init_test().await;
+
+        let dir = test_postponed_events_dir("restore_in_order");
+        let previous_launch_fallback =
+            EventRecorderFallback::new(Some(dir.clone()), None).expect("in test");
+        defer! {
+            fs::remove_dir_all(previous_launch_fallback.clone().postponed_events_dir).expect("clear postponed events dir");
+        };
+
+        let first_event = test_event_data();
+        let second_event = test_event_data();
+        for event in [&first_event, &second_event] {
+            previous_launch_fallback
+                .save_to_file(TABLE_NAME.to_string(), vec![event.clone()])
+                .await
+                .expect("in test");
+            // file names are based on time, so they have to differ
+            sleep(Duration::from_millis(1)).await;
+        }
+
+        // events left from previous launch are restored before new ones
+        let fallback = EventRecorderFallback::new(Some(dir), None).expect("in test");
+        assert!(*fallback.state.is_active.lock().await);
+        assert_eq!(fallback.backlog().files, 2);
+
+        fallback.deactivate_if_restored().await.expect("in test");
+        assert!(
+            *fallback.state.is_active.lock().await,
+            "outbox should be active while postponed events aren't restored"
+        );
+
+        let mut file_names = fallback
+            .get_existing_postponed_events_file_names()
+            .await
+            .expect("in test");
+        file_names.sort();
+        assert!(
+            fallback
+                .try_restore_to_db_postponed_events(&pool_mutex.pool, &file_names)
+                .await
+        );
+        assert_eq!(fallback.backlog(), OutboxBacklog::default());
+
+        fallback.deactivate_if_restored().await.expect("in test");
+        assert!(!*fallback.state.is_active.lock().await);
+
+        let connection = pool_mutex.pool.get_connection_expected().await;
+        let rows = connection
+            .query(&format!("SELECT * FROM {TABLE_NAME} ORDER BY id"), &[])
+            .await
+            .expect("select events");
+
+        let jsons = rows
+            .iter()
+            .map(|row| row.get::<_, serde_json::Value>("json"))
+            .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(jsons, vec![first_event.json, second_event.json]);
+    }
 }
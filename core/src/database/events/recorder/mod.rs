@@ -1,5 +1,7 @@
 mod fallback;
 
+pub use crate::database::events::recorder::fallback::OutboxBacklog;
+
 use crate::database::events::recorder::fallback::EventRecorderFallback;
 use crate::infrastructure::spawn_future;
 use anyhow::{bail, Context, Result};
@@ -33,27 +35,32 @@ pub struct EventRecorder {
     data_tx: mpsc::Sender<(TableName, InsertEvent)>,
     shutdown_signal_tx: mpsc::UnboundedSender<()>,
     shutdown_rx: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+    fallback: Option<EventRecorderFallback>,
 }
 
 impl EventRecorder {
     pub async fn start(
         pool: Option<PgPool>,
         postponed_events_dir: Option<PathBuf>,
+        postponed_events_max_size: Option<u64>,
     ) -> Result<Arc<EventRecorder>> {
         let (data_tx, data_rx) = mpsc::channel(20_000);
         let (shutdown_signal_tx, shutdown_signal_rx) = mpsc::unbounded_channel();
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
-        match pool {
+        let fallback = match pool {
             None => {
                 let _ = shutdown_tx.send(Ok(()));
                 print_info(
                     "EventRecorder is not started because `database_url` is not set in settings",
                 );
+
+                None
             }
             Some(pool) => {
-                let fallback = EventRecorderFallback::new(postponed_events_dir)
-                    .context("failed creation EventRecorderFallback")?;
+                let fallback =
+                    EventRecorderFallback::new(postponed_events_dir, postponed_events_max_size)
+                        .context("failed creation EventRecorderFallback")?;
 
                 let _ = spawn_future(
                     "start db event recorder",
@@ -69,16 +76,19 @@ impl EventRecorder {
                 let _ = spawn_future(
                     "start postponed events restoring",
                     SpawnFutureFlags::DENY_CANCELLATION | SpawnFutureFlags::STOP_BY_TOKEN,
-                    start_postponed_events_restoring(pool, fallback),
+                    start_postponed_events_restoring(pool, fallback.clone()),
                 );
                 print_info("EventRecorder started");
+
+                Some(fallback)
             }
-        }
+        };
 
         Ok(Arc::new(Self {
             data_tx,
             shutdown_signal_tx,
             shutdown_rx: Mutex::new(Some(shutdown_rx)),
+            fallback,
         }))
     }
 
@@ -100,6 +110,19 @@ impl EventRecorder {
         Ok(())
     }
 
+    /// Events queue is full, so events aren't saved in time and new events are rejected
+    pub fn is_stalled(&self) -> bool {
+        !self.data_tx.is_closed() && self.data_tx.capacity() == 0
+    }
+
+    /// Depth of events backlog postponed while database is unavailable
+    pub fn outbox_backlog(&self) -> OutboxBacklog {
+        self.fallback
+            .as_ref()
+            .map(|fallback| fallback.backlog())
+            .unwrap_or_default()
+    }
+
     pub async fn flush_and_stop(&self) -> Result<()> {
         let _ = self.shutdown_signal_tx.send(());
         let receiver = self.shutdown_rx.lock().take();
@@ -120,24 +143,35 @@ async fn start_postponed_events_restoring(
     loop {
         let _ = interval.tick().await;
 
-        let mut file_names = fallback
-            .get_existing_postponed_events_file_names()
-            .await
-            .context("can't get existing postponed events files")?;
+        // Events can be postponed during restoring, so restoring is repeated until outbox is empty
+        loop {
+            let mut file_names = fallback
+                .get_existing_postponed_events_file_names()
+                .await
+                .context("can't get existing postponed events files")?;
+
+            if file_names.is_empty() {
+                fallback
+                    .deactivate_if_restored()
+                    .await
+                    .context("can't deactivate outbox of postponed events")?;
+                break;
+            }
 
-        if file_names.is_empty() {
-            // nothing to restore
-            continue;
-        }
+            if !pool.is_connection_health().await {
+                let backlog = fallback.backlog();
+                log::warn!("Database is unavailable, postponed events backlog: {backlog:?}");
+                break;
+            }
 
-        if !pool.is_connection_health().await {
-            continue;
+            file_names.sort();
+            if !fallback
+                .try_restore_to_db_postponed_events(&pool, &file_names)
+                .await
+            {
+                break;
+            }
         }
-
-        file_names.sort();
-        fallback
-            .try_restore_to_db_postponed_events(&pool, &file_names)
-            .await;
     }
 }
 
@@ -233,6 +267,21 @@ async fn save_batch(
     events: Vec<InsertEvent>,
     fallback: &EventRecorderFallback,
 ) -> Result<()> {
+    let saving_result = fallback.save_or_postpone(pool, table_name, events).await;
+
+    if let Err(err) = saving_result {
+        log::error!("Can't save to file not written events in EventRecorderFallback: {err:?}");
+    };
+
+    Ok(())
+}
+
+/// Saves events to database. Returns not written events if database is unavailable
+pub(crate) async fn save_to_db(
+    pool: &PgPool,
+    table_name: &'_ str,
+    events: Vec<InsertEvent>,
+) -> Result<(), Vec<InsertEvent>> {
     match save_events_batch(pool, table_name, &events).await {
         Ok(()) => return Ok(()),
         Err(err) => log::error!("Failed to save batch of events with error: {err:?}"),
@@ -241,28 +290,22 @@ async fn save_batch(
     let (saving_result, not_written_events) =
         save_events_one_by_one(pool, table_name, events).await;
     match saving_result {
-        Ok(()) => if !not_written_events.is_empty() {},
+        Ok(()) => {
+            // Events rejected by database one by one can't be saved by retrying
+            if !not_written_events.is_empty() {
+                log::error!(
+                    "{} events of {table_name} were rejected by database",
+                    not_written_events.len()
+                );
+            }
+
+            Ok(())
+        }
         Err(err) => {
             log::error!("Failed to save events one by one with error: {err:?}");
-            save_to_file(table_name, not_written_events, fallback).await;
+            Err(not_written_events)
         }
     }
-
-    Ok(())
-}
-
-async fn save_to_file(
-    table_name: &str,
-    not_written_events: Vec<InsertEvent>,
-    fallback: &EventRecorderFallback,
-) {
-    let saving_result = fallback
-        .save_to_file(table_name.to_string(), not_written_events)
-        .await;
-
-    if let Err(err) = saving_result {
-        log::error!("Can't save to file not written events in EventRecorderFallback: {err:?}");
-    };
 }
 
 #[cfg(test)]
@@ -330,7 +373,7 @@ mod tests {
     async fn save_1_event() {
         let pool_mutex = init_test().await;
 
-        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None)
+        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None, None)
             .await
             .expect("in test");
 
@@ -358,7 +401,9 @@ mod tests {
         let person = test_person();
 
         // act
-        let event_recorder = EventRecorder::start(None, None).await.expect("in test");
+        let event_recorder = EventRecorder::start(None, None, None)
+            .await
+            .expect("in test");
 
         event_recorder.save(person).expect("in test");
 
@@ -381,7 +426,7 @@ mod tests {
         let person = test_person();
 
         // act
-        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None)
+        let event_recorder = EventRecorder::start(Some(pool_mutex.pool.clone()), None, None)
             .await
             .expect("in test");
         let connection = pool_mutex.pool.get_connection_expected().await;
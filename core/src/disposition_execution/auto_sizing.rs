@@ -0,0 +1,141 @@
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::order::snapshot::Amount;
+use rust_decimal::Decimal;
+
+use crate::disposition_execution::TradingContextBySide;
+
+/// Total amount of quotes of side. Balance reserved by not finished orders of the side isn't
+/// available anymore, so it's added back to avoid shrinking of quotes after their placing
+pub(super) fn side_capacity(
+    available_balance: Amount,
+    remaining_amount: Amount,
+    utilization: Decimal,
+    symbol: &Symbol,
+) -> Amount {
+    symbol.amount_round(
+        (available_balance + remaining_amount) * utilization,
+        Round::Floor,
+    )
+}
+
+/// Replace max amount of side by capacity and split it equally between quotes of the side
+pub(super) fn size_by_capacity(
+    by_side: &mut TradingContextBySide,
+    capacity: Amount,
+    symbol: &Symbol,
+) {
+    by_side.max_amount = capacity;
+
+    let quotes_count = by_side
+        .estimating
+        .iter()
+        .filter(|x| x.value.is_some())
+        .count();
+    if quotes_count == 0 {
+        return;
+    }
+
+    let quote_amount = symbol.amount_round(capacity / Decimal::from(quotes_count), Round::Floor);
+    for estimating in &mut by_side.estimating {
+        let (trade_cycle, explanation) = estimating.as_mut_all();
+        let Some(trade_cycle) = trade_cycle else {
+            continue;
+        };
+
+        trade_cycle.disposition.order.amount = quote_amount;
+        explanation.add_reason(format!(
+            "Amount {quote_amount} is sized by capacity {capacity} of side split between {quotes_count} quotes"
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::{TradeCycle, TradeDisposition};
+    use crate::explanation::WithExplanation;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+    use mmb_domain::order::snapshot::{OrderRole, OrderSide};
+    use rust_decimal_macros::dec;
+
+    fn symbol() -> Symbol {
+        let base = "btc".into();
+        let quote = "usdt".into();
+        Symbol::new(
+            false,
+            "btc".into(),
+            base,
+            "usdt".into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        )
+    }
+
+    fn quote(price: Decimal) -> WithExplanation<Option<TradeCycle>> {
+        let market_account_id = MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        );
+
+        WithExplanation {
+            value: Some(TradeCycle {
+                order_role: OrderRole::Maker,
+                strategy_name: "test".to_string(),
+                metadata: Default::default(),
+                disposition: TradeDisposition::new(
+                    market_account_id,
+                    OrderSide::Buy,
+                    price,
+                    dec!(5),
+                ),
+            }),
+            explanation: Default::default(),
+        }
+    }
+
+    #[test]
+    fn capacity_includes_balance_reserved_by_own_orders() {
+        assert_eq!(
+            side_capacity(dec!(1.5), dec!(0.5), dec!(0.5), &symbol()),
+            dec!(1)
+        );
+        assert_eq!(
+            side_capacity(dec!(1), dec!(0), dec!(0.3333), &symbol()),
+            dec!(0.333)
+        );
+    }
+
+    #[test]
+    fn capacity_is_split_between_quotes() {
+        let mut by_side = TradingContextBySide {
+            max_amount: dec!(10),
+            estimating: vec![
+                quote(dec!(100)),
+                WithExplanation {
+                    value: None,
+                    explanation: Default::default(),
+                },
+                quote(dec!(99)),
+            ],
+        };
+
+        size_by_capacity(&mut by_side, dec!(1.001), &symbol());
+
+        assert_eq!(by_side.max_amount, dec!(1.001));
+        let amounts: Vec<_> = by_side
+            .estimating
+            .iter()
+            .map(|x| x.value.as_ref().map(|x| x.disposition.amount()))
+            .collect();
+        assert_eq!(amounts, vec![Some(dec!(0.5)), None, Some(dec!(0.5))]);
+    }
+}
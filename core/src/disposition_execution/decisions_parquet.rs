@@ -0,0 +1,232 @@
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::disposition_execution::feature_recorder::{DecisionOutcome, LabeledDecision};
+
+enum Column {
+    Int64(Vec<i64>),
+    Double(Vec<Option<f64>>),
+    Utf8(Vec<String>),
+}
+
+/// Flat schema of labeled decision: features are followed by outcome columns of every horizon.
+/// Signals of strategy have variable count of levels, so they are written as JSON
+fn message_type(horizons_secs: &[u64]) -> String {
+    let outcome_fields = horizons_secs
+        .iter()
+        .map(|horizon| {
+            format!(
+                "optional double buy_filled_amount_{horizon}s;
+                optional double sell_filled_amount_{horizon}s;
+                optional double mid_price_{horizon}s;
+                optional double pnl_{horizon}s;"
+            )
+        })
+        .join("\n");
+
+    format!(
+        "message labeled_decision {{
+            required int64 time (TIMESTAMP_MILLIS);
+            required binary intent_id (UTF8);
+            required binary strategy (UTF8);
+            required binary configuration_key (UTF8);
+            required binary exchange_account_id (UTF8);
+            required binary currency_pair (UTF8);
+            optional double top_bid;
+            optional double top_ask;
+            optional double mid_price;
+            optional double spread;
+            optional double top_imbalance;
+            optional double position;
+            optional double volatility;
+            required binary signals (UTF8);
+            {outcome_fields}
+        }}"
+    )
+}
+
+fn double(value: Option<Decimal>) -> Option<f64> {
+    value.and_then(|x| x.to_f64())
+}
+
+fn columns(horizons_secs: &[u64], decisions: &[LabeledDecision]) -> Result<Vec<Column>> {
+    let features = decisions.iter().map(|x| &x.features).collect_vec();
+    let utf8 =
+        |f: &dyn Fn(&LabeledDecision) -> String| Column::Utf8(decisions.iter().map(f).collect());
+    let double_column = |f: &dyn Fn(&LabeledDecision) -> Option<Decimal>| {
+        Column::Double(decisions.iter().map(|x| double(f(x))).collect())
+    };
+
+    let signals = features
+        .iter()
+        .map(|x| serde_json::to_string(&x.signals))
+        .collect::<Result<_, _>>()
+        .context("Failed to serialize decision signals")?;
+
+    let mut columns = vec![
+        Column::Int64(features.iter().map(|x| x.time.timestamp_millis()).collect()),
+        utf8(&|x| x.features.intent_id.to_string()),
+        utf8(&|x| x.features.configuration_descriptor.service_name.to_string()),
+        utf8(&|x| {
+            x.features
+                .configuration_descriptor
+                .service_configuration_key
+                .to_string()
+        }),
+        utf8(&|x| x.features.exchange_account_id.to_string()),
+        utf8(&|x| x.features.currency_pair.to_string()),
+        double_column(&|x| x.features.book.top_bid),
+        double_column(&|x| x.features.book.top_ask),
+        double_column(&|x| x.features.book.mid_price),
+        double_column(&|x| x.features.book.spread),
+        double_column(&|x| x.features.book.top_imbalance),
+        double_column(&|x| Some(x.features.position)),
+        double_column(&|x| x.features.volatility),
+        Column::Utf8(signals),
+    ];
+
+    for horizon_secs in horizons_secs {
+        let outcome = |x: &LabeledDecision| -> Option<DecisionOutcome> {
+            x.outcomes
+                .iter()
+                .find(|outcome| outcome.horizon_secs == *horizon_secs)
+                .cloned()
+        };
+        columns.push(double_column(&|x| outcome(x).map(|x| x.buy_filled_amount)));
+        columns.push(double_column(&|x| outcome(x).map(|x| x.sell_filled_amount)));
+        columns.push(double_column(&|x| outcome(x).and_then(|x| x.mid_price)));
+        columns.push(double_column(&|x| outcome(x).and_then(|x| x.pnl)));
+    }
+
+    Ok(columns)
+}
+
+/// Writes labeled decisions to single Parquet file as one row group
+pub(crate) fn write_labeled_decisions(
+    path: &Path,
+    horizons_secs: &[u64],
+    decisions: &[LabeledDecision],
+) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    }
+
+    let schema = parse_message_type(&message_type(horizons_secs))
+        .context("Failed to build schema of labeled decisions")?;
+    let properties = WriterProperties::builder().build();
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+    let mut writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))?;
+    let mut row_group_writer = writer.next_row_group()?;
+    for column in columns(horizons_secs, decisions)? {
+        let mut column_writer = row_group_writer
+            .next_column()?
+            .context("Schema of labeled decisions doesn't match columns")?;
+
+        match column {
+            Column::Int64(values) => {
+                column_writer
+                    .typed::<Int64Type>()
+                    .write_batch(&values, None, None)?;
+            }
+            Column::Double(values) => {
+                let definition_levels = values.iter().map(|x| x.is_some() as i16).collect_vec();
+                let values = values.into_iter().flatten().collect_vec();
+                column_writer.typed::<DoubleType>().write_batch(
+                    &values,
+                    Some(&definition_levels),
+                    None,
+                )?;
+            }
+            Column::Utf8(values) => {
+                let values = values
+                    .into_iter()
+                    .map(|x| ByteArray::from(x.into_bytes()))
+                    .collect_vec();
+                column_writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, None, None)?;
+            }
+        }
+        column_writer.close()?;
+    }
+    row_group_writer.close()?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::feature_recorder::{BookFeatures, DecisionFeatures};
+    use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::snapshot::IntentId;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn schema_has_outcome_columns_of_every_horizon() {
+        let schema = parse_message_type(&message_type(&[10, 60])).expect("in test");
+
+        let fields = schema
+            .get_fields()
+            .iter()
+            .map(|x| x.name().to_string())
+            .collect_vec();
+        assert_eq!(fields.len(), 14 + 2 * 4);
+        assert_eq!(fields[14], "buy_filled_amount_10s");
+        assert_eq!(fields[21], "pnl_60s");
+    }
+
+    #[test]
+    fn labeled_decisions_are_written_as_rows() {
+        let decision = LabeledDecision {
+            features: DecisionFeatures {
+                time: Utc::now(),
+                intent_id: IntentId::generate(),
+                configuration_descriptor: ConfigurationDescriptor::new(
+                    "test_strategy".into(),
+                    "test_key".into(),
+                ),
+                exchange_account_id: ExchangeAccountId::new("Binance", 0),
+                currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+                book: BookFeatures::default(),
+                position: dec!(1),
+                volatility: None,
+                signals: vec![],
+            },
+            outcomes: vec![DecisionOutcome {
+                horizon_secs: 10,
+                buy_filled_amount: dec!(1),
+                sell_filled_amount: dec!(0),
+                mid_price: Some(dec!(100)),
+                pnl: None,
+            }],
+        };
+        let path = std::env::temp_dir()
+            .join("decisions_parquet_test")
+            .join("labeled_decisions.parquet");
+
+        write_labeled_decisions(&path, &[10], &[decision.clone(), decision]).expect("in test");
+
+        let file = File::open(&path).expect("in test");
+        let reader = SerializedFileReader::new(file).expect("in test");
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let _ = fs::remove_file(path);
+    }
+}
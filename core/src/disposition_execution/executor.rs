@@ -1,8 +1,9 @@
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use chrono::Utc;
+use enum_map::EnumMap;
 use itertools::Itertools;
 use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
 use mmb_utils::{nothing_to_do, DateTime};
@@ -11,16 +12,34 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use tokio::sync::{broadcast, oneshot};
 
+use crate::disposition_execution::auto_sizing::{side_capacity, size_by_capacity};
+use crate::disposition_execution::decisions_parquet::write_labeled_decisions;
+use crate::disposition_execution::feature_recorder::{
+    BookFeatures, FeatureRecorder, LabeledDecision,
+};
+use crate::disposition_execution::protective_orders::{
+    ModificationState, PendingModification, ProtectiveOrder, ProtectiveOrderUpdate,
+    ProtectiveOrders,
+};
 use crate::disposition_execution::strategy::DispositionStrategy;
 use crate::disposition_execution::trading_context_calculation::calculate_trading_context;
+use crate::disposition_execution::warm_up::WarmUp;
 use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::order::amend::AmendOrderResult;
 use crate::exchanges::general::request_type::RequestType;
 use crate::explanation::{Explanation, WithExplanation};
 use crate::lifecycle::trading_engine::{EngineContext, Service};
+use crate::misc::market_data_mode::{widen_spread, MarketDataMode};
+use crate::misc::pull_triggers::{index_gap_rate, PullCause, VolatilityWindow};
 use crate::misc::reserve_parameters::ReserveParameters;
 use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::settings::{
+    AutoSizingSettings, DegradedModeSettings, FeatureRecorderSettings, MaxOrderAgeSettings,
+    ProtectiveOrdersSettings, RefreshLevelSettings,
+};
 use crate::{
-    disposition_execution::trade_limit::is_enough_amount_and_cost, infrastructure::spawn_future,
+    disposition_execution::trade_limit::is_enough_amount_and_cost,
+    infrastructure::{spawn_future, spawn_future_standalone},
 };
 use crate::{
     disposition_execution::{
@@ -32,10 +51,10 @@ use chrono::Duration;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::CurrencyPair;
-use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
-use mmb_domain::order::event::OrderEventType;
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId, MarketId};
+use mmb_domain::order::event::{OrderEvent, OrderEventType};
 use mmb_domain::order::pool::OrderRef;
-use mmb_domain::order::snapshot::{Amount, Price, UserOrder};
+use mmb_domain::order::snapshot::{Amount, IntentId, Price, ReservationId, UserOrder};
 use mmb_domain::order::snapshot::{
     ClientOrderId, OrderHeader, OrderSide, OrderSnapshot, OrderStatus,
 };
@@ -45,6 +64,10 @@ static DISPOSITION_EXECUTOR: &str = "DispositionExecutor";
 static DISPOSITION_EXECUTOR_REQUESTS_GROUP: &str = "DispositionExecutorRG";
 const ALLOWED_AMOUNT_DEVIATION_RATE: Decimal = dec!(0.001);
 const GROUP_REQUESTS_COUNT: usize = 4;
+const MAX_ORDER_AGE_CHECK_PERIOD: std::time::Duration = std::time::Duration::from_secs(1);
+/// Holds of quoting by pull triggers fired outside of executor are noticed at least this often,
+/// because market events can be absent, e.g. during connectivity flap
+const PULL_TRIGGERS_CHECK_PERIOD: std::time::Duration = std::time::Duration::from_millis(200);
 
 struct DisplaySmallOrder {
     price: Decimal,
@@ -70,9 +93,24 @@ impl DispositionExecutorService {
         exchange_account_id: ExchangeAccountId,
         currency_pair: CurrencyPair,
         strategy: Box<dyn DispositionStrategy>,
+        refresh_level: Option<RefreshLevelSettings>,
+        feature_recorder: Option<FeatureRecorderSettings>,
+        protective_orders: Option<ProtectiveOrdersSettings>,
+        degraded_mode: Option<DegradedModeSettings>,
+        max_order_age: Option<MaxOrderAgeSettings>,
+        auto_sizing: Option<AutoSizingSettings>,
         cancellation_token: CancellationToken,
         statistics: Arc<StatisticService>,
-    ) -> Arc<Self> {
+    ) -> Result<Arc<Self>> {
+        // Fail before start instead of failing at the first order attempt
+        validate_required_capabilities(&engine_ctx, exchange_account_id, strategy.as_ref())
+            .context("Strategy can't be started on configured exchanges")?;
+        if let Some(auto_sizing) = &auto_sizing {
+            auto_sizing
+                .validate()
+                .context("Invalid auto sizing settings of strategy")?;
+        }
+
         let (work_finished_sender, receiver) = oneshot::channel();
 
         let action = async move {
@@ -83,6 +121,12 @@ impl DispositionExecutorService {
                 exchange_account_id,
                 currency_pair,
                 strategy,
+                refresh_level,
+                feature_recorder,
+                protective_orders,
+                degraded_mode,
+                max_order_age,
+                auto_sizing,
                 work_finished_sender,
                 cancellation_token,
                 statistics,
@@ -96,12 +140,55 @@ impl DispositionExecutorService {
             action,
         );
 
-        Arc::new(DispositionExecutorService {
+        Ok(Arc::new(DispositionExecutorService {
             work_finished_receiver: Mutex::new(Some(receiver)),
-        })
+        }))
     }
 }
 
+/// Checks capabilities required by strategy on its trading exchange and exchanges
+/// of subscribed markets, error lists all missing capabilities by exchanges
+fn validate_required_capabilities(
+    engine_ctx: &EngineContext,
+    exchange_account_id: ExchangeAccountId,
+    strategy: &dyn DispositionStrategy,
+) -> Result<()> {
+    let exchange_account_ids = std::iter::once(exchange_account_id)
+        .chain(
+            strategy
+                .subscribed_markets()
+                .into_iter()
+                .map(|x| x.exchange_account_id),
+        )
+        .unique();
+
+    let mut missing_by_exchanges = Vec::new();
+    for exchange_account_id in exchange_account_ids {
+        let required = strategy.required_capabilities(exchange_account_id);
+        if required.is_empty() {
+            continue;
+        }
+
+        let exchange = engine_ctx
+            .exchanges
+            .get(&exchange_account_id)
+            .with_context(|| format!("Exchange {exchange_account_id} isn't configured"))?;
+        let missing = exchange.missing_capabilities(&required);
+        if !missing.is_empty() {
+            missing_by_exchanges.push(format!("{exchange_account_id}: {missing:?}"));
+        }
+    }
+
+    if !missing_by_exchanges.is_empty() {
+        bail!(
+            "Strategy requires capabilities which aren't supported by exchanges: {}",
+            missing_by_exchanges.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
 impl Service for DispositionExecutorService {
     fn name(&self) -> &str {
         DISPOSITION_EXECUTOR
@@ -125,9 +212,22 @@ struct DispositionExecutor {
     local_snapshots_service: LocalSnapshotsService,
     orders_state: OrdersState,
     strategy: Box<dyn DispositionStrategy>,
+    refresh_level: Option<RefreshLevelSettings>,
+    feature_recorder: Option<FeatureRecorder>,
+    protective_orders: Option<ProtectiveOrders>,
+    degraded_mode: Option<DegradedModeSettings>,
+    max_order_age: Option<MaxOrderAgeSettings>,
+    auto_sizing: Option<AutoSizingSettings>,
+    max_amount_by_side: EnumMap<OrderSide, Amount>,
     work_finished_sender: Option<oneshot::Sender<Result<()>>>,
     cancellation_token: CancellationToken,
     statistics: Arc<StatisticService>,
+    warm_up: WarmUp,
+    /// Set if volatility pull trigger is enabled
+    volatility_window: Option<VolatilityWindow>,
+    is_quoting_held: bool,
+    /// Name of executor in liveness registry
+    subsystem_name: String,
 }
 
 impl DispositionExecutor {
@@ -139,6 +239,12 @@ impl DispositionExecutor {
         exchange_account_id: ExchangeAccountId,
         currency_pair: CurrencyPair,
         strategy: Box<dyn DispositionStrategy>,
+        refresh_level: Option<RefreshLevelSettings>,
+        feature_recorder: Option<FeatureRecorderSettings>,
+        protective_orders: Option<ProtectiveOrdersSettings>,
+        degraded_mode: Option<DegradedModeSettings>,
+        max_order_age: Option<MaxOrderAgeSettings>,
+        auto_sizing: Option<AutoSizingSettings>,
         work_finished_sender: oneshot::Sender<Result<()>>,
         cancellation_token: CancellationToken,
         statistics: Arc<StatisticService>,
@@ -149,6 +255,26 @@ impl DispositionExecutor {
             .expect("Target exchange should exists")
             .get_symbol(currency_pair)
             .expect("Currency pair symbol should exists for target trading place");
+        let contract_type = symbol.contract_type;
+
+        let protective_orders = protective_orders.map(|settings| {
+            let trigger_price_type = engine_ctx
+                .exchanges
+                .get(&exchange_account_id)
+                .expect("Target exchange should exists")
+                .available_trigger_price_type(settings.trigger_price_type);
+            ProtectiveOrders::new(settings, contract_type, trigger_price_type)
+        });
+
+        engine_ctx
+            .strategy_parameters
+            .register(strategy.tunable_parameters());
+
+        let volatility_window = engine_ctx
+            .pull_triggers
+            .settings()
+            .volatility
+            .map(|x| VolatilityWindow::new(x.window()));
 
         DispositionExecutor {
             engine_ctx,
@@ -158,24 +284,55 @@ impl DispositionExecutor {
             symbol,
             orders_state: OrdersState::new(),
             strategy,
+            refresh_level,
+            feature_recorder: feature_recorder.as_ref().map(FeatureRecorder::new),
+            protective_orders,
+            degraded_mode,
+            max_order_age,
+            auto_sizing,
+            max_amount_by_side: EnumMap::default(),
             work_finished_sender: Some(work_finished_sender),
             cancellation_token,
             statistics,
+            warm_up: WarmUp::default(),
+            volatility_window,
+            is_quoting_held: false,
+            subsystem_name: format!("disposition_executor_{exchange_account_id}_{currency_pair}"),
         }
     }
 
     pub async fn start(&mut self) -> Result<()> {
         let mut trading_context: Option<TradingContext> = None;
+        let mut max_order_age_timer = tokio::time::interval(MAX_ORDER_AGE_CHECK_PERIOD);
+        let mut pull_triggers_timer = tokio::time::interval(PULL_TRIGGERS_CHECK_PERIOD);
 
         loop {
             let event = tokio::select! {
                 event_res = self.events_receiver.recv() => event_res.map_err(|e| anyhow!("Error during receiving event in DispositionExecutor::start(). Error: {e}."))?,
+                _ = max_order_age_timer.tick(), if self.max_order_age.is_some() => {
+                    if self.cancel_expired_orders(now()) {
+                        // quotes should be placed again by the next trading context calculation
+                        trading_context = None;
+                    }
+                    continue;
+                }
+                _ = pull_triggers_timer.tick() => {
+                    if self.hold_quoting(now()) {
+                        trading_context = None;
+                    }
+                    continue;
+                }
                 _ = self.cancellation_token.when_cancelled() => {
+                    self.flush_labeled_decisions();
                     let _ = self.work_finished_sender.take().ok_or_else(|| anyhow!("Can't take `work_finished_sender` in DispositionExecutor"))?.send(Ok(()));
                     return Ok(());
                 }
             };
 
+            self.engine_ctx
+                .liveness_registry
+                .register_activity(&self.subsystem_name);
+
             self.handle_event(&event, &mut trading_context)?;
         }
     }
@@ -186,18 +343,28 @@ impl DispositionExecutor {
         last_trading_context: &mut Option<TradingContext>,
     ) -> Result<()> {
         let now = now();
+        self.apply_strategy_parameters(now);
+        // retry update of protective orders deferred by creation or modification in progress
+        self.update_protective_orders(now);
+
         let need_recalculate_trading_context = self.prepare_estimate_trading_context(event, now);
 
         match event {
             ExchangeEvent::OrderBookEvent(order_book_event) => {
                 let _ = self.local_snapshots_service.update(order_book_event);
+                self.check_pull_triggers(event, now);
             }
+            ExchangeEvent::MarkPrice(_) => self.check_pull_triggers(event, now),
             ExchangeEvent::OrderEvent(order_event) => {
                 let order = &order_event.order;
                 if order.order_type().is_external_order() {
                     return Ok(());
                 }
 
+                if self.is_protective_order(order) {
+                    return self.handle_protective_order_event(order_event, now);
+                }
+
                 match order_event.event_type {
                     OrderEventType::CreateOrderSucceeded => nothing_to_do(),
                     OrderEventType::CreateOrderFailed => {
@@ -213,6 +380,12 @@ impl DispositionExecutor {
                             "Started handling event OrderFilled {} in DispositionExecutor",
                             cloned_order.client_order_id()
                         );
+                        if self.has_price_slot(order) {
+                            self.register_fill_for_features(cloned_order, now);
+                            self.register_fill_for_protection(cloned_order);
+                            self.update_protective_orders(now);
+                        }
+
                         let price_slot = self.get_price_slot(order);
                         if let Some(price_slot) = price_slot {
                             self.engine_ctx.balance_manager.lock().order_was_filled(
@@ -225,6 +398,7 @@ impl DispositionExecutor {
                             }
 
                             self.handle_order_fill(cloned_order, price_slot)?;
+                            self.refresh_price_slot_after_fill(price_slot, now)?;
                         }
                         log::trace!(
                             "Finished handling event OrderFilled {} in DispositionExecutor",
@@ -240,6 +414,7 @@ impl DispositionExecutor {
                         if let Some(price_slot) = price_slot {
                             self.handle_order_fill(cloned_order, price_slot)?;
                             self.finish_order(order, price_slot)?;
+                            self.refresh_price_slot_after_fill(price_slot, now)?;
                         }
                         log::trace!(
                             "Finished handling event OrderCompleted {} in DispositionExecutor",
@@ -266,11 +441,18 @@ impl DispositionExecutor {
 
                         // TODO save state to Database
                     }
+                    // Price slot is already released by CancelOrderSucceeded of expired order
+                    OrderEventType::Expired => nothing_to_do(),
+                    OrderEventType::AmendOrderSucceeded | OrderEventType::AmendOrderFailed => {
+                        nothing_to_do()
+                    }
                 }
             }
             _ => nothing_to_do(),
         };
 
+        self.label_decisions(now);
+
         let mut new_trading_context = estimate_trading_context(
             need_recalculate_trading_context,
             event,
@@ -279,19 +461,628 @@ impl DispositionExecutor {
             now,
         )?;
 
+        if new_trading_context.is_some() && !self.is_warmed_up(now) {
+            new_trading_context = None;
+        }
+
+        if let Some(degraded_mode) = self.degraded_mode {
+            if self.is_market_data_degraded() {
+                match degraded_mode.widen_spread_rate {
+                    Some(rate) => {
+                        if let Some(trading_context) = &mut new_trading_context {
+                            widen_spread(trading_context, rate, &self.symbol);
+                        }
+                    }
+                    None => new_trading_context = None,
+                }
+            }
+        }
+
+        if self.hold_quoting(now) {
+            new_trading_context = None;
+        }
+
+        if let Some(auto_sizing) = self.auto_sizing {
+            if let Some(trading_context) = &mut new_trading_context {
+                self.size_by_balance(trading_context, auto_sizing);
+            }
+        }
+
         if last_trading_context == &mut new_trading_context {
             return Ok(());
         }
 
-        self.synchronize_price_slots_for_trading_context(&mut new_trading_context, now)?;
+        let intent_id = IntentId::generate();
+        self.synchronize_price_slots_for_trading_context(&mut new_trading_context, intent_id, now)?;
+        self.record_decision(&new_trading_context, intent_id, now);
         *last_trading_context = new_trading_context;
 
         Ok(())
     }
 
+    /// Apply parameters changed through control panel. Called before handling of event,
+    /// so strategy never sees partially applied changes during decision cycle
+    fn apply_strategy_parameters(&mut self, now: DateTime) {
+        let strategy_parameters = self.engine_ctx.strategy_parameters.clone();
+        let Some(changes) = strategy_parameters.take_pending_changes() else {
+            return;
+        };
+
+        if let Err(err) = self.strategy.set_parameters(&changes) {
+            log::error!("Failed to apply strategy parameters {changes:?}: {err:?}");
+            return;
+        }
+
+        for config_change in strategy_parameters.commit(&changes, now) {
+            log::info!(
+                "Strategy parameter {} is changed from {} to {}",
+                config_change.parameter,
+                config_change.old_value,
+                config_change.new_value
+            );
+
+            self.engine_ctx
+                .event_recorder
+                .save(config_change)
+                .expect("Failed to save config change");
+        }
+    }
+
+    fn is_warmed_up(&mut self, now: DateTime) -> bool {
+        if self.warm_up.is_completed() {
+            return true;
+        }
+
+        let data_readiness = self.strategy.data_readiness(now);
+        let progress = self.warm_up.update(data_readiness).map(|x| x.to_vec());
+        let is_completed = self.warm_up.is_completed();
+
+        if let Some(progress) = progress {
+            let market_account_id =
+                MarketAccountId::new(self.exchange_account_id, self.symbol.currency_pair());
+
+            match is_completed {
+                true => log::info!("Warm-up of strategy on {market_account_id} is completed"),
+                false => log::trace!("Warm-up of strategy on {market_account_id}: {progress:?}"),
+            }
+
+            self.statistics
+                .register_warm_up_progress(market_account_id, progress);
+        }
+
+        is_completed
+    }
+
+    fn is_market_data_degraded(&self) -> bool {
+        let market_account_id =
+            MarketAccountId::new(self.exchange_account_id, self.symbol.currency_pair());
+        self.engine_ctx.market_data_modes.mode(market_account_id) == MarketDataMode::Degraded
+    }
+
+    fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.exchange_account_id, self.symbol.currency_pair())
+    }
+
+    fn mid_price(&self) -> Option<Price> {
+        let prices = self
+            .local_snapshots_service
+            .get_snapshot(self.market_account_id().market_id())?
+            .get_top_prices();
+        Some((prices.top_ask? + prices.top_bid?) * dec!(0.5))
+    }
+
+    /// Fire pull triggers by conditions of market of the executor
+    fn check_pull_triggers(&mut self, event: &ExchangeEvent, now: DateTime) {
+        let market_account_id = self.market_account_id();
+        let settings = self.engine_ctx.pull_triggers.settings();
+        let (volatility, index_price_gap) = (settings.volatility, settings.index_price_gap);
+        let mid_price = self.mid_price();
+
+        let cause = match event {
+            ExchangeEvent::OrderBookEvent(x) if x.market_account_id() == market_account_id => {
+                let (Some(volatility), Some(window), Some(mid_price)) =
+                    (volatility, &mut self.volatility_window, mid_price)
+                else {
+                    return;
+                };
+
+                let move_rate = window.update(now, mid_price);
+                if move_rate <= volatility.max_move_rate {
+                    return;
+                }
+
+                window.clear();
+                PullCause::VolatilitySpike { move_rate }
+            }
+            ExchangeEvent::MarkPrice(x) if x.market_account_id() == market_account_id => {
+                let Some(gap_rate) = x
+                    .index_price
+                    .zip(mid_price)
+                    .and_then(|(index_price, mid_price)| index_gap_rate(mid_price, index_price))
+                else {
+                    return;
+                };
+
+                match index_price_gap {
+                    Some(settings) if gap_rate > settings.max_gap_rate => {
+                        PullCause::IndexPriceGap { gap_rate }
+                    }
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+
+        // otherwise every update of volatile market would extend the hold
+        let pull_triggers = &self.engine_ctx.pull_triggers;
+        if pull_triggers.hold_until(market_account_id, now).is_none() {
+            let _ = pull_triggers.pull(
+                self.exchange_account_id,
+                Some(market_account_id.currency_pair),
+                cause,
+                now,
+            );
+        }
+    }
+
+    /// Cancel all quotes while quoting of market or of strategy on it is held by pull trigger.
+    /// Returns true if it's held
+    fn hold_quoting(&mut self, now: DateTime) -> bool {
+        let market_account_id = self.market_account_id();
+        let configuration_descriptor = self.strategy.configuration_descriptor();
+        let hold_until = self.engine_ctx.pull_triggers.strategy_hold_until(
+            market_account_id,
+            configuration_descriptor.service_name.as_str(),
+            now,
+        );
+
+        match (hold_until, self.is_quoting_held) {
+            (Some(hold_until), false) => log::warn!(
+                "Quoting on {market_account_id} is held by pull trigger until {hold_until}"
+            ),
+            (None, true) => log::info!(
+                "Quoting on {market_account_id} is resumed after cool-down of pull trigger"
+            ),
+            _ => {}
+        }
+        self.is_quoting_held = hold_until.is_some();
+
+        if self.is_quoting_held {
+            self.pull_quotes();
+        }
+
+        self.is_quoting_held
+    }
+
+    fn pull_quotes(&self) {
+        for (_, state_by_side) in self.orders_state.by_side.iter() {
+            for price_slot in state_by_side.traverse_price_slots() {
+                let mut composite_order = price_slot.order.borrow_mut();
+                if composite_order
+                    .orders
+                    .values()
+                    .all(|x| x.is_cancellation_requested)
+                {
+                    continue;
+                }
+
+                let mut explanation = Explanation::default();
+                self.start_cancelling_all_orders(
+                    "quoting is held by pull trigger",
+                    &mut composite_order,
+                    &mut explanation,
+                );
+                log::info!(
+                    "Quotes of price slot {} are pulled: {explanation:?}",
+                    price_slot.id
+                );
+            }
+        }
+    }
+
+    fn record_decision(
+        &mut self,
+        trading_context: &Option<TradingContext>,
+        intent_id: IntentId,
+        now: DateTime,
+    ) {
+        let (feature_recorder, trading_context) =
+            match (&mut self.feature_recorder, trading_context) {
+                (Some(feature_recorder), Some(trading_context)) => {
+                    (feature_recorder, trading_context)
+                }
+                _ => return,
+            };
+
+        let book = self
+            .local_snapshots_service
+            .get_snapshot(MarketId::new(
+                self.exchange_account_id.exchange_id,
+                self.symbol.currency_pair(),
+            ))
+            .map(BookFeatures::from_snapshot)
+            .unwrap_or_default();
+
+        let position = self.engine_ctx.balance_manager.lock().get_position(
+            self.exchange_account_id,
+            self.symbol.currency_pair(),
+            OrderSide::Buy,
+        );
+
+        feature_recorder.record_decision(
+            now,
+            intent_id,
+            self.strategy.configuration_descriptor(),
+            self.exchange_account_id,
+            self.symbol.currency_pair(),
+            book,
+            position,
+            trading_context,
+        );
+    }
+
+    fn register_fill_for_features(&mut self, cloned_order: &OrderSnapshot, now: DateTime) {
+        let Some(feature_recorder) = &mut self.feature_recorder else {
+            return;
+        };
+
+        if let Some(fill) = cloned_order.fills.fills.last() {
+            feature_recorder.register_fill(now, cloned_order.side(), fill.price(), fill.amount());
+        }
+    }
+
+    fn is_protective_order(&mut self, order: &OrderRef) -> bool {
+        let Some(protective_orders) = &mut self.protective_orders else {
+            return false;
+        };
+
+        // replacement of amended protective order is protective since its first event
+        protective_orders.adopt_replacement(order);
+        protective_orders.is_protective_order(&order.client_order_id())
+    }
+
+    fn handle_protective_order_event(
+        &mut self,
+        order_event: &OrderEvent,
+        now: DateTime,
+    ) -> Result<()> {
+        let client_order_id = order_event.order.client_order_id();
+
+        match order_event.event_type {
+            OrderEventType::OrderFilled { ref cloned_order } => {
+                self.engine_ctx
+                    .balance_manager
+                    .lock()
+                    .order_was_filled(self.strategy.configuration_descriptor(), cloned_order);
+
+                self.register_fill_for_features(cloned_order, now);
+                self.register_fill_for_protection(cloned_order);
+            }
+            OrderEventType::CreateOrderFailed => {
+                log::error!("Failed to create protective order {client_order_id} on {}, position of strategy isn't protected", self.exchange_account_id);
+            }
+            _ => nothing_to_do(),
+        }
+
+        self.update_protective_orders(now);
+
+        Ok(())
+    }
+
+    fn register_fill_for_protection(&mut self, cloned_order: &OrderSnapshot) {
+        let Some(protective_orders) = &mut self.protective_orders else {
+            return;
+        };
+
+        if let Some(fill) = cloned_order.fills.fills.last() {
+            protective_orders.register_fill(cloned_order.side(), fill.price(), fill.amount());
+        }
+    }
+
+    /// Releases reservations of finished protective orders and amends, cancels or creates
+    /// protective orders if position was changed since last update
+    fn update_protective_orders(&mut self, now: DateTime) {
+        let Some(protective_orders) = &mut self.protective_orders else {
+            return;
+        };
+
+        let finished_orders = protective_orders.take_finished_orders();
+        let updates = match protective_orders.needs_update() {
+            true => protective_orders.start_update(&self.symbol),
+            false => Vec::new(),
+        };
+
+        for (order, reservation_id) in finished_orders {
+            self.release_protective_reservation(&order, reservation_id);
+        }
+
+        for update in updates {
+            match update {
+                ProtectiveOrderUpdate::Modify {
+                    order,
+                    reservation_id,
+                    price,
+                    amount,
+                    modification,
+                } => {
+                    self.modify_protective_order(order, reservation_id, price, amount, modification)
+                }
+                ProtectiveOrderUpdate::Create(protective_order) => {
+                    self.create_protective_order(protective_order, now)
+                }
+                ProtectiveOrderUpdate::Cancel(order) => self.cancel_protective_order(order),
+            }
+        }
+    }
+
+    fn create_protective_order(&mut self, protective_order: ProtectiveOrder, now: DateTime) {
+        let min_amount = self
+            .symbol
+            .get_min_amount(protective_order.price)
+            .expect("We can't trade if we can't calculate min amount for order");
+        if protective_order.amount < min_amount {
+            log::warn!(
+                "Can't create protective order for amount {} < min amount {min_amount} on {}",
+                protective_order.amount,
+                self.exchange_account_id
+            );
+            return;
+        }
+
+        let configuration_descriptor = self.strategy.configuration_descriptor();
+        let reserve_parameters = ReserveParameters::new(
+            configuration_descriptor,
+            self.exchange_account_id,
+            self.symbol.clone(),
+            protective_order.side,
+            protective_order.price,
+            protective_order.amount,
+        );
+        let mut explanation = None;
+        let Some(reservation_id) = self
+            .engine_ctx
+            .balance_manager
+            .lock()
+            .try_reserve(&reserve_parameters, &mut explanation)
+        else {
+            log::warn!(
+                "Can't reserve balance for protective order {:?} of {} on {}, position of strategy isn't protected",
+                protective_order.user_order,
+                protective_order.amount,
+                self.exchange_account_id
+            );
+            return;
+        };
+
+        let exchange = self.exchange();
+        let order_header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            self.exchange_account_id,
+            self.symbol.currency_pair(),
+            protective_order.side,
+            protective_order.amount,
+            protective_order.user_order,
+            Some(reservation_id),
+            None,
+            configuration_descriptor.service_name.to_string(),
+        );
+
+        let order = exchange.orders.add_simple_initial(
+            &order_header,
+            now,
+            exchange.exchange_client.get_initial_extension_data(),
+        );
+        if let Some(protective_orders) = &mut self.protective_orders {
+            protective_orders.add_active_order(order, reservation_id);
+        }
+
+        let cancellation_token = self.cancellation_token.clone();
+        let action = async move {
+            exchange
+                .create_order(&order_header, None, cancellation_token)
+                .await?;
+
+            Ok(())
+        };
+        spawn_future(
+            "create_order for protective order",
+            SpawnFutureFlags::empty(),
+            action,
+        );
+    }
+
+    /// Amends protective order keeping its reservation, which is extended if amount grows
+    fn modify_protective_order(
+        &self,
+        order: OrderRef,
+        reservation_id: ReservationId,
+        price: Price,
+        amount: Amount,
+        modification: PendingModification,
+    ) {
+        let amount_increase = amount - order.amount();
+        let new_amount = if amount_increase.is_zero() {
+            None
+        } else if amount_increase.is_sign_positive()
+            && !self.extend_protective_reservation(&order, reservation_id, price, amount_increase)
+        {
+            log::warn!(
+                "Can't reserve balance to increase protective order {} by {amount_increase} on {}, only its price is modified",
+                order.client_order_id(),
+                self.exchange_account_id
+            );
+            None
+        } else {
+            Some(amount)
+        };
+
+        let exchange = self.exchange();
+        let cancellation_token = self.cancellation_token.clone();
+        let action = async move {
+            let result = match order.user_order() {
+                Some(user_order) => {
+                    let user_order = user_order.with_price(price);
+                    exchange
+                        .amend_order(&order, user_order, new_amount, cancellation_token)
+                        .await
+                }
+                None => Err(anyhow!(
+                    "Protective order {} isn't user order",
+                    order.client_order_id()
+                )),
+            };
+
+            let replacement = match &result {
+                Ok(AmendOrderResult::Replaced(new_order)) => Some(new_order.clone()),
+                _ => None,
+            };
+            *modification.lock() = ModificationState::Finished { replacement };
+
+            result.map(|_| ())
+        };
+        spawn_future(
+            "amend_order for protective order",
+            SpawnFutureFlags::empty(),
+            action,
+        );
+    }
+
+    /// Reserves increase of protective order amount and moves it to the reservation of the order
+    fn extend_protective_reservation(
+        &self,
+        order: &OrderRef,
+        reservation_id: ReservationId,
+        price: Price,
+        amount_increase: Amount,
+    ) -> bool {
+        let reserve_parameters = ReserveParameters::new(
+            self.strategy.configuration_descriptor(),
+            self.exchange_account_id,
+            self.symbol.clone(),
+            order.side(),
+            price,
+            amount_increase,
+        );
+
+        let mut balance_manager = self.engine_ctx.balance_manager.lock();
+        let Some(increase_reservation_id) =
+            balance_manager.try_reserve(&reserve_parameters, &mut None)
+        else {
+            return false;
+        };
+
+        let is_transferred = balance_manager.try_transfer_reservation(
+            increase_reservation_id,
+            reservation_id,
+            amount_increase,
+            &None,
+        );
+        if balance_manager
+            .get_reservation(increase_reservation_id)
+            .is_some()
+        {
+            if let Err(error) = balance_manager.unreserve_rest(increase_reservation_id) {
+                log::error!(
+                    "Failed to unreserve rest of reservation {increase_reservation_id}: {error:?}"
+                );
+            }
+        }
+
+        is_transferred
+    }
+
+    fn cancel_protective_order(&self, order: OrderRef) {
+        let exchange = self.exchange();
+        let cancellation_token = self.cancellation_token.clone();
+        let action = async move {
+            exchange
+                .wait_cancel_order(order, None, false, cancellation_token)
+                .await
+        };
+        spawn_future(
+            "Start wait_cancel_order for protective order",
+            SpawnFutureFlags::empty(),
+            action,
+        );
+    }
+
+    /// Fills of protective orders are registered on every fill event,
+    /// so only unfilled rest of reservation is released
+    fn release_protective_reservation(&self, order: &OrderRef, reservation_id: ReservationId) {
+        let mut balance_manager = self.engine_ctx.balance_manager.lock();
+        if balance_manager.get_reservation(reservation_id).is_none() {
+            return;
+        }
+
+        if order.status() == OrderStatus::Canceled {
+            balance_manager.cancel_approved_reservation(reservation_id, &order.client_order_id());
+        }
+
+        if let Err(error) = balance_manager.unreserve_rest(reservation_id) {
+            log::error!(
+                "Failed to release reservation {reservation_id} of protective order {} on {}: {error:?}",
+                order.client_order_id(),
+                self.exchange_account_id
+            );
+        }
+    }
+
+    fn label_decisions(&mut self, now: DateTime) {
+        let Some(feature_recorder) = &mut self.feature_recorder else {
+            return;
+        };
+
+        let mid_price = self
+            .local_snapshots_service
+            .get_snapshot(MarketId::new(
+                self.exchange_account_id.exchange_id,
+                self.symbol.currency_pair(),
+            ))
+            .and_then(|snapshot| BookFeatures::from_snapshot(snapshot).mid_price);
+
+        if let Some(labeled) = feature_recorder.label_decisions(now, mid_price, &self.symbol) {
+            self.write_labeled_decisions(labeled, now);
+        }
+    }
+
+    /// Writes batch of labeled decisions to Parquet file outside of executor thread
+    fn write_labeled_decisions(&self, labeled: Vec<LabeledDecision>, now: DateTime) {
+        let Some(feature_recorder) = &self.feature_recorder else {
+            return;
+        };
+
+        let path = feature_recorder.file_path(&self.strategy.configuration_descriptor(), now);
+        let horizons_secs = feature_recorder.horizons_secs().to_vec();
+        let action = async move { write_labeled_decisions(&path, &horizons_secs, &labeled) };
+        let _ =
+            spawn_future_standalone("write labeled decisions", SpawnFutureFlags::empty(), action);
+    }
+
+    /// Labeled decisions collected since the last written batch are written on stop
+    fn flush_labeled_decisions(&mut self) {
+        let Some(feature_recorder) = &mut self.feature_recorder else {
+            return;
+        };
+
+        let labeled = feature_recorder.take_labeled();
+        if labeled.is_empty() {
+            return;
+        }
+
+        let path = feature_recorder.file_path(&self.strategy.configuration_descriptor(), now());
+        if let Err(err) = write_labeled_decisions(&path, feature_recorder.horizons_secs(), &labeled)
+        {
+            log::error!(
+                "Unable to write labeled decisions to {}: {err:?}",
+                path.display()
+            );
+        }
+    }
+
     fn synchronize_price_slots_for_trading_context(
         &mut self,
         trading_context: &mut Option<TradingContext>,
+        intent_id: IntentId,
         now: DateTime,
     ) -> Result<()> {
         let trading_context = match trading_context {
@@ -299,6 +1090,10 @@ impl DispositionExecutor {
             Some(v) => v,
         };
 
+        for (side, trading_context_by_side) in trading_context.by_side.iter() {
+            self.max_amount_by_side[side] = trading_context_by_side.max_amount;
+        }
+
         for (side, state_by_side) in self.orders_state.by_side.iter() {
             let trading_context_by_side = &mut trading_context.by_side[side];
 
@@ -306,6 +1101,7 @@ impl DispositionExecutor {
                 &state_by_side.slots,
                 &mut trading_context_by_side.estimating[..],
                 trading_context_by_side.max_amount,
+                intent_id,
                 now,
             )?
         }
@@ -313,6 +1109,7 @@ impl DispositionExecutor {
         let explanations = trading_context.get_explanations(
             self.exchange_account_id.exchange_id,
             self.symbol.currency_pair(),
+            intent_id,
         );
 
         self.engine_ctx
@@ -328,6 +1125,7 @@ impl DispositionExecutor {
         slots: &[PriceSlot],
         estimating: &mut [WithExplanation<Option<TradeCycle>>],
         max_amount: Decimal,
+        intent_id: IntentId,
         now: DateTime,
     ) -> Result<()> {
         if slots.len() != estimating.len() {
@@ -340,7 +1138,14 @@ impl DispositionExecutor {
 
             let (trade_cycle, explanation) = with_explanation.as_mut_all();
 
-            self.synchronize_price_slot(trade_cycle, price_slot, max_amount, now, explanation)?;
+            self.synchronize_price_slot(
+                trade_cycle,
+                price_slot,
+                max_amount,
+                intent_id,
+                now,
+                explanation,
+            )?;
         }
 
         Ok(())
@@ -351,6 +1156,7 @@ impl DispositionExecutor {
         new_estimating: &Option<TradeCycle>,
         price_slot: &PriceSlot,
         max_amount: Decimal,
+        intent_id: IntentId,
         now: DateTime,
         explanation: &mut Explanation,
     ) -> Result<()> {
@@ -456,6 +1262,7 @@ impl DispositionExecutor {
                 price_slot,
                 new_estimating,
                 max_amount,
+                intent_id,
                 now,
                 explanation,
             )?;
@@ -472,6 +1279,7 @@ impl DispositionExecutor {
                     price_slot,
                     new_estimating,
                     max_amount,
+                    intent_id,
                     now,
                     explanation,
                 )?;
@@ -496,6 +1304,133 @@ impl DispositionExecutor {
         Ok(())
     }
 
+    /// Re-places filled part of quote at the last strategy-provided level of the price slot
+    fn refresh_price_slot_after_fill(&self, price_slot: &PriceSlot, now: DateTime) -> Result<()> {
+        let refresh_level = match self.refresh_level {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+
+        let cooldown = Duration::milliseconds(refresh_level.cooldown_ms as i64);
+        let last_estimating = match estimating_to_refresh(price_slot, cooldown, now) {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+
+        log::trace!("Refreshing price slot {} after fill", price_slot.id);
+
+        let mut explanation = Explanation::default();
+        explanation.add_reason("Refreshing level after fill");
+
+        let max_amount = self.max_amount_by_side[last_estimating.disposition.side()];
+        // re-quote continues decision which the slot estimating is taken from
+        let intent_id = price_slot
+            .intent_id
+            .get()
+            .unwrap_or_else(IntentId::generate);
+        self.synchronize_price_slot(
+            &Some(last_estimating),
+            price_slot,
+            max_amount,
+            intent_id,
+            now,
+            &mut explanation,
+        )?;
+        price_slot.last_refresh_time.set(Some(now));
+
+        Ok(())
+    }
+
+    /// Size quotes of every side by part of balance available for the side, so deposits and
+    /// withdrawals change quotes without editing of strategy settings
+    fn size_by_balance(
+        &self,
+        trading_context: &mut TradingContext,
+        auto_sizing: AutoSizingSettings,
+    ) {
+        for (side, by_side) in trading_context.by_side.iter_mut() {
+            let price = by_side
+                .estimating
+                .iter()
+                .find_map(|x| x.value.as_ref().map(|x| x.disposition.price()));
+            let Some(price) = price else {
+                continue;
+            };
+
+            let available_balance = self
+                .engine_ctx
+                .balance_manager
+                .lock()
+                .get_balance_by_side(
+                    self.strategy.configuration_descriptor(),
+                    self.exchange_account_id,
+                    self.symbol.clone(),
+                    side,
+                    price,
+                )
+                .unwrap_or_else(|| {
+                    log::warn!(
+                        "Balance of {side:?} side isn't available for auto sizing on {}",
+                        self.exchange_account_id
+                    );
+                    dec!(0)
+                });
+
+            let remaining_amount = self.orders_state.by_side[side].calc_total_remaining_amount();
+            let capacity = side_capacity(
+                available_balance,
+                remaining_amount,
+                auto_sizing.utilization(side),
+                &self.symbol,
+            );
+            size_by_capacity(by_side, capacity, &self.symbol);
+        }
+    }
+
+    /// Cancel orders which rest longer than max order age. Returns true if any order is canceled
+    fn cancel_expired_orders(&self, now: DateTime) -> bool {
+        let max_order_age = match self.max_order_age {
+            None => return false,
+            Some(v) => Duration::milliseconds(v.max_age_ms as i64),
+        };
+
+        let mut is_any_order_expired = false;
+        for (_, state_by_side) in self.orders_state.by_side.iter() {
+            for price_slot in state_by_side.traverse_price_slots() {
+                let mut composite_order = price_slot.order.borrow_mut();
+                let expired_orders = composite_order
+                    .orders
+                    .values_mut()
+                    .filter(|or| {
+                        !or.is_cancellation_requested
+                            && now - or.order.fn_ref(|x| x.init_time()) >= max_order_age
+                    })
+                    .collect_vec();
+
+                if expired_orders.is_empty() {
+                    continue;
+                }
+                is_any_order_expired = true;
+
+                let mut explanation = Explanation::default();
+                self.start_cancelling_orders_with_cause(
+                    &format!(
+                        "orders are older than {} ms",
+                        max_order_age.num_milliseconds()
+                    ),
+                    expired_orders.into_iter(),
+                    &mut explanation,
+                );
+                log::info!(
+                    "Expired orders of price slot {} are cancelled: {explanation:?}",
+                    price_slot.id
+                );
+            }
+        }
+
+        is_any_order_expired
+    }
+
     fn start_cancelling_all_orders(
         &self,
         cause: &str,
@@ -584,6 +1519,7 @@ impl DispositionExecutor {
         price_slot: &PriceSlot,
         new_estimating: &TradeCycle,
         max_amount: Decimal,
+        intent_id: IntentId,
         now: DateTime,
         explanation: &mut Explanation,
     ) -> Result<()> {
@@ -599,6 +1535,22 @@ impl DispositionExecutor {
             return log_trace(msg, explanation);
         }
 
+        if let Some(max_ratio) = self
+            .engine_ctx
+            .core_settings
+            .order_to_trade_ratio
+            .as_ref()
+            .and_then(|x| x.max_ratio)
+        {
+            let ratio = self
+                .statistics
+                .order_to_trade_ratio(new_disposition.market_account_id());
+            if ratio > max_ratio {
+                let msg = format!("Finished `try_create_order` because order to trade ratio {ratio} exceeds limit {max_ratio}");
+                return log_trace(msg, explanation);
+            }
+        }
+
         let new_order_amount = self.calculate_new_order_amount(
             new_disposition.market_account_id(),
             side,
@@ -641,7 +1593,8 @@ impl DispositionExecutor {
             new_disposition.side(),
             new_disposition.price(),
             new_order_amount,
-        );
+        )
+        .with_order_role(new_estimating.order_role);
 
         let reservation_id;
         *explanation = {
@@ -697,6 +1650,7 @@ impl DispositionExecutor {
         }
 
         *price_slot.estimating.borrow_mut() = Some(Box::new(new_estimating.clone()));
+        price_slot.intent_id.set(Some(intent_id));
 
         let order_header = OrderHeader::with_user_order(
             new_client_order_id.clone(),
@@ -708,6 +1662,14 @@ impl DispositionExecutor {
             Some(reservation_id),
             None,
             new_estimating.strategy_name.clone(),
+        )
+        .with_intent_id(intent_id)
+        .with_metadata(new_estimating.metadata.clone());
+
+        self.engine_ctx.liveness_registry.register_orders_scope(
+            &self.subsystem_name,
+            self.market_account_id(),
+            &order_header.strategy_name,
         );
 
         let exchange = self.exchange();
@@ -801,6 +1763,12 @@ impl DispositionExecutor {
         new_amount
     }
 
+    fn has_price_slot(&self, order: &OrderRef) -> bool {
+        self.orders_state.by_side[order.side()]
+            .find_price_slot(order)
+            .is_some()
+    }
+
     fn get_price_slot(&self, order: &OrderRef) -> Option<&PriceSlot> {
         let header = order.header();
         let price_slot = self.orders_state.by_side[header.side].find_price_slot(order);
@@ -921,6 +1889,26 @@ fn estimate_trading_context(
     ))
 }
 
+/// Last strategy-provided level of price slot to re-place filled part of quote at.
+/// `None` if there is no level or cooldown since the previous re-quote isn't over
+fn estimating_to_refresh(
+    price_slot: &PriceSlot,
+    cooldown: Duration,
+    now: DateTime,
+) -> Option<TradeCycle> {
+    if let Some(last_refresh_time) = price_slot.last_refresh_time.get() {
+        if now < last_refresh_time + cooldown {
+            log::trace!(
+                "Skipped refreshing price slot {} because of cooldown",
+                price_slot.id
+            );
+            return None;
+        }
+    }
+
+    price_slot.estimating.borrow().as_deref().cloned()
+}
+
 fn get_cancelling_orders<'a>(
     order_records: impl Iterator<Item = &'a mut OrderRecord>,
     desired_amount: Amount,
@@ -971,3 +1959,108 @@ fn log_trace(msg: impl AsRef<str>, explanation: &mut Explanation) -> Result<()>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::{PriceSlotId, TradeDisposition};
+    use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::{OrderMetadata, OrderRole};
+
+    const COOLDOWN_MS: i64 = 500;
+
+    fn market_account_id() -> MarketAccountId {
+        MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        )
+    }
+
+    fn estimating() -> TradeCycle {
+        TradeCycle {
+            order_role: OrderRole::Maker,
+            strategy_name: "test".to_owned(),
+            metadata: OrderMetadata::default(),
+            disposition: TradeDisposition::new(
+                market_account_id(),
+                OrderSide::Buy,
+                dec!(100),
+                dec!(1),
+            ),
+        }
+    }
+
+    /// Price slot quoted by `estimating()` with order which is filled by `filled_amount`
+    fn quoted_price_slot(filled_amount: Amount) -> PriceSlot {
+        let price_slot = PriceSlot::new(PriceSlotId::new("test".to_owned(), 0), OrderSide::Buy);
+        *price_slot.estimating.borrow_mut() = Some(Box::new(estimating()));
+
+        let market_account_id = market_account_id();
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            market_account_id.exchange_account_id,
+            market_account_id.currency_pair,
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(100)),
+            None,
+            None,
+            "test".to_owned(),
+        );
+        let order = OrdersPool::new().add_simple_initial(&header, now(), None);
+        order.fn_mut(|x| x.fills.filled_amount = filled_amount);
+        price_slot.add_order(OrderSide::Buy, dec!(100), order, RequestGroupId::generate());
+
+        price_slot
+    }
+
+    #[test]
+    fn filled_part_of_partially_filled_quote_is_requoted() {
+        let price_slot = quoted_price_slot(dec!(0.3));
+
+        let estimating =
+            estimating_to_refresh(&price_slot, Duration::milliseconds(COOLDOWN_MS), now())
+                .expect("in test");
+
+        // re-quote is placed at the same level, so only filled part is added to the slot
+        assert_eq!(estimating, self::estimating());
+        let remaining_amount = price_slot.order.borrow().remaining_amount();
+        assert_eq!(remaining_amount, dec!(0.7));
+        assert_eq!(
+            estimating.disposition.amount() - remaining_amount,
+            dec!(0.3)
+        );
+    }
+
+    #[test]
+    fn requote_is_skipped_during_cooldown() {
+        let price_slot = quoted_price_slot(dec!(0.3));
+        let cooldown = Duration::milliseconds(COOLDOWN_MS);
+        let refresh_time = now();
+        price_slot.last_refresh_time.set(Some(refresh_time));
+
+        let during_cooldown = refresh_time + Duration::milliseconds(COOLDOWN_MS - 1);
+        assert_eq!(
+            estimating_to_refresh(&price_slot, cooldown, during_cooldown),
+            None
+        );
+
+        let after_cooldown = refresh_time + cooldown;
+        assert_eq!(
+            estimating_to_refresh(&price_slot, cooldown, after_cooldown),
+            Some(estimating())
+        );
+    }
+
+    #[test]
+    fn price_slot_without_level_is_not_requoted() {
+        let price_slot = quoted_price_slot(dec!(0.3));
+        *price_slot.estimating.borrow_mut() = None;
+
+        assert_eq!(
+            estimating_to_refresh(&price_slot, Duration::milliseconds(COOLDOWN_MS), now()),
+            None
+        );
+    }
+}
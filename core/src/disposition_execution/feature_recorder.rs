@@ -0,0 +1,617 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use chrono::Duration;
+use itertools::Itertools;
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, IntentId, OrderSide, Price};
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use mmb_utils::DateTime;
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::Serialize;
+
+use crate::disposition_execution::TradingContext;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use crate::settings::FeatureRecorderSettings;
+
+/// Order book features on the moment of decision
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BookFeatures {
+    pub top_bid: Option<Price>,
+    pub top_ask: Option<Price>,
+    pub mid_price: Option<Price>,
+    pub spread: Option<Price>,
+    /// (bid amount - ask amount) / (bid amount + ask amount) on top levels
+    pub top_imbalance: Option<Decimal>,
+}
+
+impl BookFeatures {
+    pub fn from_snapshot(snapshot: &LocalOrderBookSnapshot) -> Self {
+        let top_bid = snapshot.get_top_bid();
+        let top_ask = snapshot.get_top_ask();
+
+        let (mid_price, spread, top_imbalance) = match (top_bid, top_ask) {
+            (Some((bid, bid_amount)), Some((ask, ask_amount))) => {
+                let total_amount = bid_amount + ask_amount;
+                let top_imbalance = match total_amount.is_zero() {
+                    true => None,
+                    false => Some((bid_amount - ask_amount) / total_amount),
+                };
+                (
+                    Some((bid + ask) / Decimal::TWO),
+                    Some(ask - bid),
+                    top_imbalance,
+                )
+            }
+            _ => (None, None, None),
+        };
+
+        BookFeatures {
+            top_bid: top_bid.map(|(price, _)| price),
+            top_ask: top_ask.map(|(price, _)| price),
+            mid_price,
+            spread,
+            top_imbalance,
+        }
+    }
+}
+
+/// Level of trading context provided by strategy
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionSignal {
+    pub side: OrderSide,
+    pub level: usize,
+    pub price: Option<Price>,
+    pub amount: Option<Amount>,
+}
+
+/// Strategy context on the moment when strategy changed its trading context
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionFeatures {
+    pub time: DateTime,
+    pub intent_id: IntentId,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub book: BookFeatures,
+    pub position: Decimal,
+    /// Standard deviation of relative mid price changes between last decisions
+    pub volatility: Option<Decimal>,
+    pub signals: Vec<DecisionSignal>,
+}
+
+/// Outcome of decision after horizon has passed
+#[derive(Debug, Clone, Serialize)]
+pub struct DecisionOutcome {
+    pub horizon_secs: u64,
+    pub buy_filled_amount: Amount,
+    pub sell_filled_amount: Amount,
+    pub mid_price: Option<Price>,
+    /// Mark-to-market PnL of fills since decision and of position held on the moment of decision
+    /// in PnL currency of symbol (base currency for inverse contracts)
+    pub pnl: Option<Decimal>,
+}
+
+/// Decision features with outcomes on all configured horizons, ready for ML training
+#[derive(Debug, Clone, Serialize)]
+pub struct LabeledDecision {
+    pub features: DecisionFeatures,
+    pub outcomes: Vec<DecisionOutcome>,
+}
+
+struct RecordedFill {
+    time: DateTime,
+    /// Signed amount (positive for buys)
+    amount: Amount,
+    price: Price,
+}
+
+struct PendingDecision {
+    features: DecisionFeatures,
+    /// Sequence number of the first fill after decision
+    first_fill_number: u64,
+    outcomes: Vec<DecisionOutcome>,
+}
+
+impl PendingDecision {
+    fn outcome<'a>(
+        &self,
+        horizon_secs: u64,
+        fills: impl Iterator<Item = &'a RecordedFill>,
+        mid_price: Option<Price>,
+        symbol: &Symbol,
+    ) -> DecisionOutcome {
+        let horizon_end = self.features.time + Duration::seconds(horizon_secs as i64);
+        let fills = fills.take_while(|x| x.time <= horizon_end).collect_vec();
+
+        let buy_filled_amount = fills
+            .iter()
+            .filter(|x| x.amount.is_sign_positive())
+            .map(|x| x.amount)
+            .sum();
+        let sell_filled_amount = fills
+            .iter()
+            .filter(|x| x.amount.is_sign_negative())
+            .map(|x| -x.amount)
+            .sum();
+
+        let pnl =
+            mid_price
+                .zip(self.features.book.mid_price)
+                .map(|(mid_price, decision_mid_price)| {
+                    let fills_pnl = fills
+                        .iter()
+                        .map(|x| symbol.position_pnl(x.amount, x.price, mid_price))
+                        .sum::<Decimal>();
+
+                    fills_pnl
+                        + symbol.position_pnl(self.features.position, decision_mid_price, mid_price)
+                });
+
+        DecisionOutcome {
+            horizon_secs,
+            buy_filled_amount,
+            sell_filled_amount,
+            mid_price,
+            pnl,
+        }
+    }
+}
+
+/// Collects strategy decisions and labels them with fills and PnL over configured horizons
+pub(crate) struct FeatureRecorder {
+    horizons_secs: Vec<u64>,
+    volatility_window: usize,
+    max_pending_decisions: usize,
+    decisions_per_file: usize,
+    output_dir: PathBuf,
+    /// Decisions waiting for outcomes ordered by time
+    pending: VecDeque<PendingDecision>,
+    /// Fills since the oldest pending decision
+    fills: VecDeque<RecordedFill>,
+    /// Sequence number of the front of `fills`
+    first_fill_number: u64,
+    labeled: Vec<LabeledDecision>,
+    mid_prices: VecDeque<Price>,
+}
+
+impl FeatureRecorder {
+    pub fn new(settings: &FeatureRecorderSettings) -> Self {
+        let mut horizons_secs = settings.horizons_secs.clone();
+        horizons_secs.sort_unstable();
+        horizons_secs.dedup();
+
+        FeatureRecorder {
+            horizons_secs,
+            volatility_window: settings.volatility_window,
+            max_pending_decisions: settings.max_pending_decisions,
+            decisions_per_file: settings.decisions_per_file,
+            output_dir: settings.output_dir.clone(),
+            pending: VecDeque::new(),
+            fills: VecDeque::new(),
+            first_fill_number: 0,
+            labeled: Vec::new(),
+            mid_prices: VecDeque::new(),
+        }
+    }
+
+    pub fn horizons_secs(&self) -> &[u64] {
+        &self.horizons_secs
+    }
+
+    /// Path of Parquet file for batch of labeled decisions of strategy completed at `time`
+    pub fn file_path(
+        &self,
+        configuration_descriptor: &ConfigurationDescriptor,
+        time: DateTime,
+    ) -> PathBuf {
+        self.output_dir.join(format!(
+            "{}_{}_{}.parquet",
+            configuration_descriptor.service_name,
+            configuration_descriptor.service_configuration_key,
+            time.format("%Y%m%d_%H%M%S_%3f")
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_decision(
+        &mut self,
+        time: DateTime,
+        intent_id: IntentId,
+        configuration_descriptor: ConfigurationDescriptor,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+        book: BookFeatures,
+        position: Decimal,
+        trading_context: &TradingContext,
+    ) {
+        if let Some(mid_price) = book.mid_price {
+            self.mid_prices.push_back(mid_price);
+            while self.mid_prices.len() > self.volatility_window + 1 {
+                let _ = self.mid_prices.pop_front();
+            }
+        }
+
+        let signals = trading_context
+            .by_side
+            .iter()
+            .flat_map(|(side, ctx)| {
+                ctx.estimating
+                    .iter()
+                    .enumerate()
+                    .map(move |(level, estimating)| DecisionSignal {
+                        side,
+                        level,
+                        price: estimating.value.as_ref().map(|x| x.disposition.price()),
+                        amount: estimating.value.as_ref().map(|x| x.disposition.amount()),
+                    })
+            })
+            .collect();
+
+        let features = DecisionFeatures {
+            time,
+            intent_id,
+            configuration_descriptor,
+            exchange_account_id,
+            currency_pair,
+            book,
+            position,
+            volatility: self.volatility(),
+            signals,
+        };
+
+        if self.pending.len() >= self.max_pending_decisions {
+            log::warn!(
+                "Too many decisions wait for outcomes on {exchange_account_id} {currency_pair}, the oldest one is dropped"
+            );
+            let _ = self.pending.pop_front();
+            self.remove_unused_fills();
+        }
+
+        self.pending.push_back(PendingDecision {
+            features,
+            first_fill_number: self.first_fill_number + self.fills.len() as u64,
+            outcomes: Vec::with_capacity(self.horizons_secs.len()),
+        });
+    }
+
+    pub fn register_fill(&mut self, time: DateTime, side: OrderSide, price: Price, amount: Amount) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let amount = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => -amount,
+        };
+        self.fills.push_back(RecordedFill {
+            time,
+            amount,
+            price,
+        });
+    }
+
+    /// Labels pending decisions which horizons have passed.
+    /// Returns batch of labeled decisions when `decisions_per_file` of them are collected.
+    /// PnL is calculated in PnL currency of symbol according to its contract type
+    pub fn label_decisions(
+        &mut self,
+        now: DateTime,
+        mid_price: Option<Price>,
+        symbol: &Symbol,
+    ) -> Option<Vec<LabeledDecision>> {
+        let min_horizon =
+            Duration::seconds(self.horizons_secs.first().copied().unwrap_or(0) as i64);
+        for decision in &mut self.pending {
+            // decisions are ordered by time, so horizons of next ones haven't passed too
+            if decision.features.time + min_horizon > now {
+                break;
+            }
+
+            let fills_start = decision
+                .first_fill_number
+                .saturating_sub(self.first_fill_number) as usize;
+            while let Some(&horizon_secs) = self.horizons_secs.get(decision.outcomes.len()) {
+                let horizon = Duration::seconds(horizon_secs as i64);
+                if decision.features.time + horizon > now {
+                    break;
+                }
+
+                let fills = self.fills.range(fills_start..);
+                let outcome = decision.outcome(horizon_secs, fills, mid_price, symbol);
+                decision.outcomes.push(outcome);
+            }
+        }
+
+        while let Some(decision) = self.pending.front() {
+            if decision.outcomes.len() < self.horizons_secs.len() {
+                break;
+            }
+
+            let decision = self
+                .pending
+                .pop_front()
+                .expect("pending decision should exist");
+            self.labeled.push(LabeledDecision {
+                features: decision.features,
+                outcomes: decision.outcomes,
+            });
+        }
+        self.remove_unused_fills();
+
+        (self.labeled.len() >= self.decisions_per_file).then(|| self.take_labeled())
+    }
+
+    /// Takes all labeled decisions which weren't returned as batch yet
+    pub fn take_labeled(&mut self) -> Vec<LabeledDecision> {
+        std::mem::take(&mut self.labeled)
+    }
+
+    fn remove_unused_fills(&mut self) {
+        let used_from = self
+            .pending
+            .front()
+            .map_or(self.first_fill_number + self.fills.len() as u64, |x| {
+                x.first_fill_number
+            });
+
+        while self.first_fill_number < used_from && self.fills.pop_front().is_some() {
+            self.first_fill_number += 1;
+        }
+    }
+
+    fn volatility(&self) -> Option<Decimal> {
+        let returns = self
+            .mid_prices
+            .iter()
+            .zip(self.mid_prices.iter().skip(1))
+            .filter(|(prev, _)| !prev.is_zero())
+            .map(|(prev, next)| (next - prev) / prev)
+            .collect::<Vec<_>>();
+
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let count = Decimal::from(returns.len());
+        let mean = returns.iter().sum::<Decimal>() / count;
+        let variance = returns
+            .iter()
+            .map(|x| (x - mean) * (x - mean))
+            .sum::<Decimal>()
+            / count;
+
+        variance.sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::TradingContextBySide;
+    use chrono::Utc;
+    use mmb_domain::exchanges::symbol::Precision;
+    use rust_decimal_macros::dec;
+
+    fn symbol() -> Symbol {
+        let base = "btc".into();
+        let quote = "usdt".into();
+        Symbol::new(
+            false,
+            "btc".into(),
+            base,
+            "usdt".into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        )
+    }
+
+    fn recorder(horizons_secs: Vec<u64>) -> FeatureRecorder {
+        FeatureRecorder::new(&FeatureRecorderSettings {
+            horizons_secs,
+            volatility_window: 10,
+            output_dir: "labeled_decisions".into(),
+            decisions_per_file: 1,
+            max_pending_decisions: 3,
+        })
+    }
+
+    fn book(mid_price: Price) -> BookFeatures {
+        BookFeatures {
+            top_bid: Some(mid_price - dec!(1)),
+            top_ask: Some(mid_price + dec!(1)),
+            mid_price: Some(mid_price),
+            spread: Some(dec!(2)),
+            top_imbalance: None,
+        }
+    }
+
+    fn record(recorder: &mut FeatureRecorder, time: DateTime, mid_price: Price, position: Decimal) {
+        let empty_ctx = TradingContextBySide {
+            max_amount: dec!(0),
+            estimating: vec![],
+        };
+
+        recorder.record_decision(
+            time,
+            IntentId::generate(),
+            ConfigurationDescriptor::new("test_strategy".into(), "test_key".into()),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            book(mid_price),
+            position,
+            &TradingContext::new(empty_ctx.clone(), empty_ctx),
+        );
+    }
+
+    #[test]
+    fn decision_is_labeled_after_all_horizons() {
+        let mut recorder = recorder(vec![60, 10]);
+        let now = Utc::now();
+        record(&mut recorder, now, dec!(100), dec!(1));
+
+        let fill_time = now + Duration::seconds(5);
+        recorder.register_fill(fill_time, OrderSide::Buy, dec!(99), dec!(2));
+        let labeled =
+            recorder.label_decisions(now + Duration::seconds(10), Some(dec!(101)), &symbol());
+        assert!(labeled.is_none());
+
+        let fill_time = now + Duration::seconds(30);
+        recorder.register_fill(fill_time, OrderSide::Sell, dec!(102), dec!(1));
+        let labeled = recorder
+            .label_decisions(now + Duration::seconds(60), Some(dec!(103)), &symbol())
+            .expect("in test");
+        assert_eq!(labeled.len(), 1);
+
+        let outcomes = &labeled[0].outcomes;
+        assert_eq!(outcomes[0].horizon_secs, 10);
+        // 2 * (101 - 99) + 1 * (101 - 100)
+        assert_eq!(outcomes[0].pnl, Some(dec!(5)));
+        assert_eq!(outcomes[1].horizon_secs, 60);
+        assert_eq!(outcomes[1].sell_filled_amount, dec!(1));
+        // 2 * (103 - 99) - 1 * (103 - 102) + 1 * (103 - 100)
+        assert_eq!(outcomes[1].pnl, Some(dec!(10)));
+    }
+
+    #[test]
+    fn volatility_is_calculated_by_last_mid_prices() {
+        let mut recorder = recorder(vec![10]);
+        let now = Utc::now();
+        record(&mut recorder, now, dec!(100), dec!(0));
+        record(&mut recorder, now, dec!(110), dec!(0));
+        assert_eq!(recorder.pending[1].features.volatility, None);
+
+        record(&mut recorder, now, dec!(99), dec!(0));
+        // returns are 0.1 and -0.1
+        let volatility = recorder.pending[2].features.volatility;
+        assert_eq!(volatility.map(|x| x.round_dp(10)), Some(dec!(0.1)));
+    }
+
+    #[test]
+    fn fills_after_horizon_are_not_in_outcome() {
+        let mut recorder = recorder(vec![10]);
+        let now = Utc::now();
+        record(&mut recorder, now, dec!(100), dec!(0));
+
+        recorder.register_fill(
+            now + Duration::seconds(5),
+            OrderSide::Buy,
+            dec!(99),
+            dec!(2),
+        );
+        recorder.register_fill(
+            now + Duration::seconds(15),
+            OrderSide::Buy,
+            dec!(98),
+            dec!(1),
+        );
+        let labeled = recorder
+            .label_decisions(now + Duration::seconds(20), Some(dec!(101)), &symbol())
+            .expect("in test");
+
+        let outcome = &labeled[0].outcomes[0];
+        assert_eq!(outcome.buy_filled_amount, dec!(2));
+        assert_eq!(outcome.pnl, Some(dec!(4)));
+        assert!(recorder.fills.is_empty());
+    }
+
+    #[test]
+    fn decision_sees_only_fills_after_it() {
+        let mut recorder = recorder(vec![10]);
+        let now = Utc::now();
+        record(&mut recorder, now, dec!(100), dec!(0));
+        recorder.register_fill(
+            now + Duration::seconds(1),
+            OrderSide::Buy,
+            dec!(99),
+            dec!(2),
+        );
+        record(
+            &mut recorder,
+            now + Duration::seconds(2),
+            dec!(100),
+            dec!(0),
+        );
+        recorder.register_fill(
+            now + Duration::seconds(3),
+            OrderSide::Sell,
+            dec!(101),
+            dec!(1),
+        );
+
+        let labeled = recorder
+            .label_decisions(now + Duration::seconds(20), Some(dec!(100)), &symbol())
+            .expect("in test");
+
+        assert_eq!(labeled.len(), 2);
+        let outcome = &labeled[0].outcomes[0];
+        assert_eq!(outcome.buy_filled_amount, dec!(2));
+        assert_eq!(outcome.sell_filled_amount, dec!(1));
+        let outcome = &labeled[1].outcomes[0];
+        assert_eq!(outcome.buy_filled_amount, dec!(0));
+        assert_eq!(outcome.sell_filled_amount, dec!(1));
+    }
+
+    #[test]
+    fn oldest_pending_decision_is_dropped_over_limit() {
+        let mut recorder = recorder(vec![10]);
+        let now = Utc::now();
+        for secs in 0..4 {
+            record(
+                &mut recorder,
+                now + Duration::seconds(secs),
+                dec!(100),
+                dec!(0),
+            );
+            recorder.register_fill(
+                now + Duration::seconds(secs),
+                OrderSide::Buy,
+                dec!(99),
+                dec!(1),
+            );
+        }
+
+        assert_eq!(recorder.pending.len(), 3);
+        assert_eq!(
+            recorder.pending[0].features.time,
+            now + Duration::seconds(1)
+        );
+        // fills before the oldest pending decision aren't kept
+        assert_eq!(recorder.fills.len(), 3);
+    }
+
+    #[test]
+    fn labeled_decisions_are_returned_by_batches() {
+        let mut recorder = FeatureRecorder::new(&FeatureRecorderSettings {
+            horizons_secs: vec![10],
+            volatility_window: 10,
+            output_dir: "labeled_decisions".into(),
+            decisions_per_file: 2,
+            max_pending_decisions: 10,
+        });
+        let now = Utc::now();
+        record(&mut recorder, now, dec!(100), dec!(0));
+        record(
+            &mut recorder,
+            now + Duration::seconds(5),
+            dec!(100),
+            dec!(0),
+        );
+
+        let labeled = recorder.label_decisions(now + Duration::seconds(10), None, &symbol());
+        assert!(labeled.is_none());
+
+        let labeled = recorder.label_decisions(now + Duration::seconds(15), None, &symbol());
+        assert_eq!(labeled.map(|x| x.len()), Some(2));
+        assert!(recorder.take_labeled().is_empty());
+    }
+}
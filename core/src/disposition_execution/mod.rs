@@ -1,7 +1,15 @@
+mod auto_sizing;
+mod decisions_parquet;
 pub mod executor;
+pub mod feature_recorder;
+pub mod protective_orders;
 pub mod strategy;
+pub mod strategy_context;
+pub mod testing;
 pub mod trade_limit;
 mod trading_context_calculation;
+pub mod tunable_parameters;
+pub mod warm_up;
 
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 use crate::explanation::{Explanation, ExplanationSet, PriceLevelExplanation, WithExplanation};
@@ -9,11 +17,12 @@ use enum_map::{enum_map, EnumMap};
 use itertools::Itertools;
 use mmb_domain::market::{CurrencyPair, ExchangeAccountId, ExchangeId, MarketAccountId, MarketId};
 use mmb_domain::order::pool::OrderRef;
-use mmb_domain::order::snapshot::{Amount, Price};
-use mmb_domain::order::snapshot::{ClientOrderId, OrderRole, OrderSide};
+use mmb_domain::order::snapshot::{Amount, IntentId, Price};
+use mmb_domain::order::snapshot::{ClientOrderId, OrderMetadata, OrderRole, OrderSide};
+use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
@@ -107,6 +116,8 @@ impl TradeDisposition {
 pub struct TradeCycle {
     pub order_role: OrderRole,
     pub strategy_name: String,
+    /// Custom data which is stored in header of order created for disposition
+    pub metadata: OrderMetadata,
     pub disposition: TradeDisposition,
 }
 
@@ -147,6 +158,7 @@ impl TradingContext {
         &self,
         exchange_id: ExchangeId,
         currency_pair: CurrencyPair,
+        intent_id: IntentId,
     ) -> ExplanationSet {
         let explanations = self
             .by_side
@@ -155,7 +167,7 @@ impl TradingContext {
             .flat_map(|x| x.estimating.iter().map(to_price_level_explanation))
             .collect_vec();
 
-        ExplanationSet::new(exchange_id, currency_pair, explanations)
+        ExplanationSet::new(exchange_id, currency_pair, intent_id, explanations)
     }
 }
 
@@ -281,14 +293,20 @@ pub struct PriceSlot {
     pub id: PriceSlotId,
     pub estimating: RefCell<Option<Box<TradeCycle>>>,
     pub order: RefCell<CompositeOrder>,
+    /// Time of last re-quote after fill
+    pub last_refresh_time: Cell<Option<DateTime>>,
+    /// Strategy decision which current estimating is taken from
+    pub intent_id: Cell<Option<IntentId>>,
 }
 
 impl PriceSlot {
-    fn new(id: PriceSlotId, side: OrderSide) -> Self {
+    pub fn new(id: PriceSlotId, side: OrderSide) -> Self {
         PriceSlot {
             id,
             estimating: RefCell::new(None),
             order: RefCell::new(CompositeOrder::new(side)),
+            last_refresh_time: Cell::new(None),
+            intent_id: Cell::new(None),
         }
     }
 
@@ -0,0 +1,604 @@
+use std::sync::Arc;
+
+use mmb_domain::exchanges::symbol::{ContractType, Round, Symbol};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderSide, OrderStatus, Price, ReservationId, TriggerPriceType,
+    UserOrder,
+};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+
+use crate::settings::ProtectiveOrdersSettings;
+
+/// Protective order required for current net position
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProtectiveOrder {
+    pub side: OrderSide,
+    pub amount: Amount,
+    pub price: Price,
+    pub user_order: UserOrder,
+}
+
+/// Active orders are amended within their kind, so stop-loss order never becomes take-profit one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProtectiveOrderKind {
+    StopLoss,
+    TakeProfit,
+}
+
+impl ProtectiveOrderKind {
+    fn of(user_order: Option<UserOrder>) -> Self {
+        match user_order {
+            Some(UserOrder::StopLoss { .. }) => ProtectiveOrderKind::StopLoss,
+            _ => ProtectiveOrderKind::TakeProfit,
+        }
+    }
+}
+
+/// State of protective order modification which is executed outside of executor
+#[derive(Debug, Clone)]
+pub(crate) enum ModificationState {
+    InProgress,
+    /// Emulated modification replaces the order by new one with the same reservation
+    Finished {
+        replacement: Option<OrderRef>,
+    },
+}
+
+pub(crate) type PendingModification = Arc<Mutex<ModificationState>>;
+
+/// Change of protective orders required to protect current net position
+pub(crate) enum ProtectiveOrderUpdate {
+    /// Amend active order to new price (stop price for stop-loss) and whole amount of order
+    Modify {
+        order: OrderRef,
+        reservation_id: ReservationId,
+        price: Price,
+        amount: Amount,
+        modification: PendingModification,
+    },
+    Create(ProtectiveOrder),
+    Cancel(OrderRef),
+}
+
+/// Protective order with balance reserved for it. Reservation is kept through modifications
+/// of the order and is released when the last order of the lineage is finished
+struct ActiveProtectiveOrder {
+    kind: ProtectiveOrderKind,
+    order: OrderRef,
+    reservation_id: ReservationId,
+    modification: Option<PendingModification>,
+}
+
+impl ActiveProtectiveOrder {
+    /// Applies result of finished modification, returns `true` if modification is still in progress
+    fn sync_modification(&mut self) -> bool {
+        let Some(modification) = &self.modification else {
+            return false;
+        };
+
+        let replacement = match &*modification.lock() {
+            ModificationState::InProgress => return true,
+            ModificationState::Finished { replacement } => replacement.clone(),
+        };
+        if let Some(replacement) = replacement {
+            self.order = replacement;
+        }
+        self.modification = None;
+        false
+    }
+
+    /// Order can't be modified until it's created on exchange and previous modification is finished
+    fn is_busy(&mut self) -> bool {
+        self.sync_modification() || self.order.status() != OrderStatus::Created
+    }
+}
+
+/// Tracks net position of strategy by fills and keeps stop-loss (and optionally take-profit) orders
+/// which close this position at configured distance from average entry price
+pub(crate) struct ProtectiveOrders {
+    settings: ProtectiveOrdersSettings,
+    contract_type: ContractType,
+    /// Trigger of stop-loss orders available on target exchange
+    trigger_price_type: TriggerPriceType,
+    /// Signed net position: positive for long, negative for short
+    position: Amount,
+    entry_price: Price,
+    /// Net position for which active protective orders were placed
+    protected_position: Amount,
+    active_orders: Vec<ActiveProtectiveOrder>,
+    /// Protective orders which aren't required anymore and can still receive fills
+    /// until cancellation is finished
+    cancelling_orders: Vec<ActiveProtectiveOrder>,
+}
+
+impl ProtectiveOrders {
+    pub fn new(
+        settings: ProtectiveOrdersSettings,
+        contract_type: ContractType,
+        trigger_price_type: TriggerPriceType,
+    ) -> Self {
+        ProtectiveOrders {
+            settings,
+            contract_type,
+            trigger_price_type,
+            position: Decimal::ZERO,
+            entry_price: Decimal::ZERO,
+            protected_position: Decimal::ZERO,
+            active_orders: Vec::new(),
+            cancelling_orders: Vec::new(),
+        }
+    }
+
+    fn all_orders_mut(&mut self) -> impl Iterator<Item = &mut ActiveProtectiveOrder> {
+        self.active_orders
+            .iter_mut()
+            .chain(self.cancelling_orders.iter_mut())
+    }
+
+    pub fn is_protective_order(&self, client_order_id: &ClientOrderId) -> bool {
+        self.active_orders
+            .iter()
+            .chain(&self.cancelling_orders)
+            .any(|x| &x.order.client_order_id() == client_order_id)
+    }
+
+    /// Order which replaces protective order during emulated modification becomes protective
+    /// as soon as its first event is received, so its fills aren't missed
+    pub fn adopt_replacement(&mut self, order: &OrderRef) {
+        let Some(replaced_order_id) = order.header().replaced_order_id else {
+            return;
+        };
+
+        if let Some(active_order) = self
+            .all_orders_mut()
+            .find(|x| x.order.client_order_id() == replaced_order_id)
+        {
+            active_order.order = order.clone();
+        }
+    }
+
+    pub fn register_fill(&mut self, side: OrderSide, price: Price, amount: Amount) {
+        let signed_amount = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => -amount,
+        };
+        let new_position = self.position + signed_amount;
+
+        self.entry_price = if new_position.is_zero() {
+            Decimal::ZERO
+        } else if self.position.is_zero()
+            || self.position.is_sign_positive() != new_position.is_sign_positive()
+        {
+            // position was opened or flipped by this fill
+            price
+        } else if new_position.abs() > self.position.abs() {
+            match self.contract_type {
+                // amount of inverse contracts is specified in quote currency, so average entry price is harmonic
+                ContractType::Inverse => {
+                    new_position / (self.position / self.entry_price + signed_amount / price)
+                }
+                _ => (self.entry_price * self.position + price * signed_amount) / new_position,
+            }
+        } else {
+            // partial close doesn't change entry price of the rest of position
+            self.entry_price
+        };
+
+        self.position = new_position;
+    }
+
+    pub fn needs_update(&self) -> bool {
+        self.position != self.protected_position
+    }
+
+    /// Removes finished protective orders and returns them with their reservations to release.
+    /// Order cancelled by emulated modification isn't finished until its replacement is known
+    pub fn take_finished_orders(&mut self) -> Vec<(OrderRef, ReservationId)> {
+        let mut finished_orders = Vec::new();
+        for orders in [&mut self.active_orders, &mut self.cancelling_orders] {
+            orders.retain_mut(|x| {
+                if x.sync_modification() || !x.order.is_finished() {
+                    return true;
+                }
+
+                finished_orders.push((x.order.clone(), x.reservation_id));
+                false
+            });
+        }
+
+        finished_orders
+    }
+
+    /// Plans changes of protective orders for current position: active orders of the same kind
+    /// and side are amended, other ones are cancelled and missing ones are created.
+    /// Nothing is planned while any active order is being created or modified,
+    /// so the update is retried on next event
+    pub fn start_update(&mut self, symbol: &Symbol) -> Vec<ProtectiveOrderUpdate> {
+        if self.active_orders.iter_mut().any(|x| x.is_busy()) {
+            return Vec::new();
+        }
+
+        self.protected_position = self.position;
+
+        let mut required_orders = self.required_orders(symbol);
+        let mut updates = Vec::new();
+        for mut active_order in std::mem::take(&mut self.active_orders) {
+            let required_position = required_orders.iter().position(|x| {
+                ProtectiveOrderKind::of(Some(x.user_order)) == active_order.kind
+                    && x.side == active_order.order.side()
+            });
+            let Some(required_position) = required_position else {
+                updates.push(ProtectiveOrderUpdate::Cancel(active_order.order.clone()));
+                self.cancelling_orders.push(active_order);
+                continue;
+            };
+
+            let required_order = required_orders.swap_remove(required_position);
+            let order = active_order.order.clone();
+            let amount = order.filled_amount() + required_order.amount;
+            if amount != order.amount() || required_order.price != current_price(&order) {
+                let modification = Arc::new(Mutex::new(ModificationState::InProgress));
+                active_order.modification = Some(modification.clone());
+                updates.push(ProtectiveOrderUpdate::Modify {
+                    order,
+                    reservation_id: active_order.reservation_id,
+                    price: required_order.price,
+                    amount,
+                    modification,
+                });
+            }
+            self.active_orders.push(active_order);
+        }
+
+        updates.extend(
+            required_orders
+                .into_iter()
+                .map(ProtectiveOrderUpdate::Create),
+        );
+        updates
+    }
+
+    pub fn add_active_order(&mut self, order: OrderRef, reservation_id: ReservationId) {
+        self.active_orders.push(ActiveProtectiveOrder {
+            kind: ProtectiveOrderKind::of(order.user_order()),
+            order,
+            reservation_id,
+            modification: None,
+        });
+    }
+
+    /// Protective orders for current position, nothing is required if position is flat
+    pub fn required_orders(&self, symbol: &Symbol) -> Vec<ProtectiveOrder> {
+        if self.position.is_zero() {
+            return Vec::new();
+        }
+
+        let amount = symbol.amount_round(self.position.abs(), Round::Floor);
+        let (side, stop_price, take_profit_price) = match self.position.is_sign_positive() {
+            true => (
+                OrderSide::Sell,
+                self.entry_price * (Decimal::ONE - self.settings.stop_loss_distance),
+                self.settings
+                    .take_profit_distance
+                    .map(|x| self.entry_price * (Decimal::ONE + x)),
+            ),
+            false => (
+                OrderSide::Buy,
+                self.entry_price * (Decimal::ONE + self.settings.stop_loss_distance),
+                self.settings
+                    .take_profit_distance
+                    .map(|x| self.entry_price * (Decimal::ONE - x)),
+            ),
+        };
+
+        let stop_price = symbol.price_round(stop_price, Round::ToNearest);
+        let mut orders = vec![ProtectiveOrder {
+            side,
+            amount,
+            price: stop_price,
+            user_order: UserOrder::StopLoss {
+                stop_price,
+                trigger_price_type: self.trigger_price_type,
+            },
+        }];
+
+        if let Some(take_profit_price) = take_profit_price {
+            let price = symbol.price_round(take_profit_price, Round::ToNearest);
+            orders.push(ProtectiveOrder {
+                side,
+                amount,
+                price,
+                user_order: UserOrder::limit(price),
+            });
+        }
+
+        orders
+    }
+}
+
+/// Stop price of stop-loss order or limit price of take-profit order
+fn current_price(order: &OrderRef) -> Price {
+    match order.user_order() {
+        Some(UserOrder::StopLoss { stop_price, .. }) => stop_price,
+        _ => order.price(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::OrderHeader;
+    use rust_decimal_macros::dec;
+
+    fn symbol() -> Symbol {
+        let base = "btc".into();
+        let quote = "usdt".into();
+        Symbol::new(
+            false,
+            "btc".into(),
+            base,
+            "usdt".into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        )
+    }
+
+    fn protective_orders(take_profit_distance: Option<Decimal>) -> ProtectiveOrders {
+        protective_orders_for_contract(take_profit_distance, ContractType::Spot)
+    }
+
+    fn protective_orders_for_contract(
+        take_profit_distance: Option<Decimal>,
+        contract_type: ContractType,
+    ) -> ProtectiveOrders {
+        ProtectiveOrders::new(
+            ProtectiveOrdersSettings {
+                stop_loss_distance: dec!(0.1),
+                take_profit_distance,
+                trigger_price_type: TriggerPriceType::Mark,
+            },
+            contract_type,
+            TriggerPriceType::Mark,
+        )
+    }
+
+    #[test]
+    fn stop_loss_for_long_position_by_average_entry_price() {
+        let mut protective_orders = protective_orders(None);
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        protective_orders.register_fill(OrderSide::Buy, dec!(200), dec!(1));
+        assert!(protective_orders.needs_update());
+
+        let orders = protective_orders.required_orders(&symbol());
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].side, OrderSide::Sell);
+        assert_eq!(orders[0].amount, dec!(2));
+        assert!(matches!(
+            orders[0].user_order,
+            UserOrder::StopLoss {
+                trigger_price_type: TriggerPriceType::Mark,
+                ..
+            }
+        ));
+        assert_eq!(orders[0].price, dec!(135));
+    }
+
+    #[test]
+    fn entry_price_of_inverse_contracts_is_harmonic_average() {
+        let mut protective_orders = protective_orders_for_contract(None, ContractType::Inverse);
+        protective_orders.register_fill(OrderSide::Buy, dec!(20000), dec!(1000));
+        protective_orders.register_fill(OrderSide::Buy, dec!(25000), dec!(1000));
+
+        // entry price is 2000 / (1000 / 20000 + 1000 / 25000) = 22222.22
+        let orders = protective_orders.required_orders(&symbol());
+        assert_eq!(orders[0].price, dec!(20000));
+    }
+
+    #[test]
+    fn stop_loss_and_take_profit_for_short_position() {
+        let mut protective_orders = protective_orders(Some(dec!(0.2)));
+        protective_orders.register_fill(OrderSide::Sell, dec!(100), dec!(1));
+        // partial close keeps entry price
+        protective_orders.register_fill(OrderSide::Buy, dec!(90), dec!(0.5));
+
+        let orders = protective_orders.required_orders(&symbol());
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].side, OrderSide::Buy);
+        assert_eq!(orders[0].amount, dec!(0.5));
+        assert_eq!(orders[0].price, dec!(110));
+        assert!(matches!(orders[1].user_order, UserOrder::Limit { .. }));
+        assert_eq!(orders[1].price, dec!(80));
+    }
+
+    #[test]
+    fn nothing_is_required_on_flat_position() {
+        let mut protective_orders = protective_orders(Some(dec!(0.2)));
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        let _ = protective_orders.start_update(&symbol());
+        assert!(!protective_orders.needs_update());
+
+        protective_orders.register_fill(OrderSide::Sell, dec!(90), dec!(1));
+        assert!(protective_orders.needs_update());
+        assert!(protective_orders.required_orders(&symbol()).is_empty());
+    }
+
+    fn created_order(orders_pool: &OrdersPool, protective_order: ProtectiveOrder) -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Binance", 0),
+            symbol().currency_pair(),
+            protective_order.side,
+            protective_order.amount,
+            protective_order.user_order,
+            Some(ReservationId::generate()),
+            None,
+            "test".to_owned(),
+        );
+        let order = orders_pool.add_simple_initial(&header, Utc::now(), None);
+        order.fn_mut(|x| x.set_status(OrderStatus::Created, Utc::now()));
+        order
+    }
+
+    /// Protects position by created orders as executor does
+    fn protect_position(
+        protective_orders: &mut ProtectiveOrders,
+        orders_pool: &OrdersPool,
+    ) -> Vec<OrderRef> {
+        let mut orders = Vec::new();
+        for update in protective_orders.start_update(&symbol()) {
+            let ProtectiveOrderUpdate::Create(protective_order) = update else {
+                panic!("Only creation is expected");
+            };
+            let order = created_order(orders_pool, protective_order);
+            let reservation_id = order.header().reservation_id.expect("in test");
+            protective_orders.add_active_order(order.clone(), reservation_id);
+            orders.push(order);
+        }
+        orders
+    }
+
+    #[test]
+    fn active_orders_are_modified_on_position_change() {
+        let orders_pool = OrdersPool::new();
+        let mut protective_orders = protective_orders(Some(dec!(0.2)));
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        let orders = protect_position(&mut protective_orders, &orders_pool);
+        assert_eq!(orders.len(), 2);
+
+        protective_orders.register_fill(OrderSide::Buy, dec!(200), dec!(1));
+        let updates = protective_orders.start_update(&symbol());
+
+        assert_eq!(updates.len(), 2);
+        for (update, order) in updates.iter().zip(&orders) {
+            let ProtectiveOrderUpdate::Modify {
+                order: modified_order,
+                reservation_id,
+                amount,
+                ..
+            } = update
+            else {
+                panic!("Modification is expected");
+            };
+            assert_eq!(modified_order.client_order_id(), order.client_order_id());
+            assert_eq!(Some(*reservation_id), order.header().reservation_id);
+            assert_eq!(*amount, dec!(2));
+        }
+        let ProtectiveOrderUpdate::Modify { price, .. } = &updates[0] else {
+            panic!("Modification is expected");
+        };
+        assert_eq!(*price, dec!(135));
+    }
+
+    #[test]
+    fn update_is_deferred_while_modification_is_in_progress() {
+        let orders_pool = OrdersPool::new();
+        let mut protective_orders = protective_orders(None);
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        let orders = protect_position(&mut protective_orders, &orders_pool);
+
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        let updates = protective_orders.start_update(&symbol());
+        let Some(ProtectiveOrderUpdate::Modify { modification, .. }) = updates.first() else {
+            panic!("Modification is expected");
+        };
+
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        assert!(protective_orders.start_update(&symbol()).is_empty());
+        assert!(protective_orders.needs_update());
+
+        // emulated modification cancels order and replaces it by new one
+        orders[0].fn_mut(|x| x.set_status(OrderStatus::Canceled, Utc::now()));
+        let replacement = created_order(
+            &orders_pool,
+            ProtectiveOrder {
+                side: OrderSide::Sell,
+                amount: dec!(2),
+                price: dec!(90),
+                user_order: UserOrder::stop_loss(dec!(90)),
+            },
+        );
+        assert!(protective_orders.take_finished_orders().is_empty());
+
+        *modification.lock() = ModificationState::Finished {
+            replacement: Some(replacement.clone()),
+        };
+        assert!(protective_orders.take_finished_orders().is_empty());
+        assert!(protective_orders.is_protective_order(&replacement.client_order_id()));
+        assert!(!protective_orders.is_protective_order(&orders[0].client_order_id()));
+
+        let updates = protective_orders.start_update(&symbol());
+        assert!(matches!(
+            updates.as_slice(),
+            [ProtectiveOrderUpdate::Modify { amount, .. }] if *amount == dec!(3)
+        ));
+    }
+
+    #[test]
+    fn orders_are_cancelled_and_released_on_flat_position() {
+        let orders_pool = OrdersPool::new();
+        let mut protective_orders = protective_orders(None);
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        let orders = protect_position(&mut protective_orders, &orders_pool);
+
+        protective_orders.register_fill(OrderSide::Sell, dec!(100), dec!(1));
+        let updates = protective_orders.start_update(&symbol());
+        let [ProtectiveOrderUpdate::Cancel(cancelled_order)] = updates.as_slice() else {
+            panic!("Cancellation is expected");
+        };
+        assert_eq!(
+            cancelled_order.client_order_id(),
+            orders[0].client_order_id()
+        );
+        assert!(protective_orders.is_protective_order(&orders[0].client_order_id()));
+        assert!(protective_orders.take_finished_orders().is_empty());
+
+        orders[0].fn_mut(|x| x.set_status(OrderStatus::Canceled, Utc::now()));
+        let finished_orders = protective_orders.take_finished_orders();
+        assert_eq!(finished_orders.len(), 1);
+        assert_eq!(
+            Some(finished_orders[0].1),
+            orders[0].header().reservation_id
+        );
+        assert!(!protective_orders.is_protective_order(&orders[0].client_order_id()));
+    }
+
+    #[test]
+    fn replacement_is_adopted_by_its_first_event() {
+        let orders_pool = OrdersPool::new();
+        let mut protective_orders = protective_orders(None);
+        protective_orders.register_fill(OrderSide::Buy, dec!(100), dec!(1));
+        let orders = protect_position(&mut protective_orders, &orders_pool);
+
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Binance", 0),
+            symbol().currency_pair(),
+            OrderSide::Sell,
+            dec!(1),
+            UserOrder::stop_loss(dec!(80)),
+            orders[0].header().reservation_id,
+            None,
+            "test".to_owned(),
+        )
+        .with_replaced_order_id(orders[0].client_order_id());
+        let replacement = orders_pool.add_simple_initial(&header, Utc::now(), None);
+
+        protective_orders.adopt_replacement(&replacement);
+        assert!(protective_orders.is_protective_order(&replacement.client_order_id()));
+        assert!(!protective_orders.is_protective_order(&orders[0].client_order_id()));
+    }
+}
@@ -1,14 +1,19 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use mmb_utils::DateTime;
+use rust_decimal::Decimal;
 
+use crate::disposition_execution::tunable_parameters::TunableParameter;
+use crate::disposition_execution::warm_up::DataReadiness;
 use crate::disposition_execution::{PriceSlot, TradingContext};
+use crate::exchanges::general::features::ExchangeCapability;
 use crate::explanation::Explanation;
 use crate::order_book::local_snapshot_service::LocalSnapshotsService;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
 use mmb_domain::events::ExchangeEvent;
-use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
 use mmb_domain::order::snapshot::OrderSnapshot;
 use mmb_utils::cancellation_token::CancellationToken;
 
@@ -30,4 +35,36 @@ pub trait DispositionStrategy: Send + Sync + 'static {
     ) -> Result<()>;
 
     fn configuration_descriptor(&self) -> ConfigurationDescriptor;
+
+    /// Markets which events strategy needs in addition to its trading market (e.g. markets for hedging
+    /// or reference prices). Events of other markets aren't delivered to strategy
+    fn subscribed_markets(&self) -> Vec<MarketAccountId> {
+        Vec::new()
+    }
+
+    /// Capabilities which strategy requires from exchange, it's called for the trading exchange
+    /// and exchanges of subscribed markets. Strategy isn't started if some of them aren't supported
+    fn required_capabilities(
+        &self,
+        _exchange_account_id: ExchangeAccountId,
+    ) -> Vec<ExchangeCapability> {
+        Vec::new()
+    }
+
+    /// Readiness of data which strategy depends on. Order intents of the strategy are withheld
+    /// by disposition executor until all dependencies are ready
+    fn data_readiness(&self, _now: DateTime) -> Vec<DataReadiness> {
+        Vec::new()
+    }
+
+    /// Parameters which can be changed at runtime through control panel
+    fn tunable_parameters(&self) -> Vec<TunableParameter> {
+        Vec::new()
+    }
+
+    /// Apply values of tunable parameters which are already validated against declared type and range.
+    /// Called by disposition executor between decision cycles with all requested changes at once
+    fn set_parameters(&mut self, _parameters: &HashMap<String, Decimal>) -> Result<()> {
+        bail!("Strategy doesn't support tunable parameters")
+    }
 }
@@ -0,0 +1,132 @@
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::explanation::Explanation;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::misc::market_data_mode::MarketDataMode;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use itertools::Itertools;
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use std::sync::Arc;
+
+/// Access to engine state needed by strategies
+pub trait StrategyContext: Send + Sync {
+    fn symbol(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Arc<Symbol>>;
+
+    /// Range of order prices which won't be rejected by exchange price filters at the moment,
+    /// quotes should be clamped into it (see `Symbol::clamp_price`)
+    fn allowed_price_band(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> (Option<Price>, Option<Price>);
+
+    /// `Degraded` while only REST data is available for the market, so prices may be stale
+    fn market_data_mode(&self, market_account_id: MarketAccountId) -> MarketDataMode;
+
+    /// Limit of position changing by strategy on the market
+    fn set_target_amount_limit(
+        &self,
+        configuration_descriptor: ConfigurationDescriptor,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        limit: Amount,
+    );
+
+    /// Leveraged balance in amount currency available for a new order on specified side,
+    /// taking into account not finished orders
+    fn available_leveraged_balance(
+        &self,
+        configuration_descriptor: ConfigurationDescriptor,
+        side: OrderSide,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        price: Price,
+        explanation: &mut Option<Explanation>,
+    ) -> Option<Amount>;
+}
+
+impl StrategyContext for EngineContext {
+    fn symbol(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Arc<Symbol>> {
+        let exchange = self.exchanges.get(&exchange_account_id)?;
+        let symbol = exchange.symbols.get(&currency_pair)?.clone();
+        Some(symbol)
+    }
+
+    fn allowed_price_band(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> (Option<Price>, Option<Price>) {
+        self.exchanges
+            .get(&exchange_account_id)
+            .and_then(|exchange| exchange.allowed_price_band(currency_pair).ok())
+            .unwrap_or_default()
+    }
+
+    fn market_data_mode(&self, market_account_id: MarketAccountId) -> MarketDataMode {
+        self.market_data_modes.mode(market_account_id)
+    }
+
+    fn set_target_amount_limit(
+        &self,
+        configuration_descriptor: ConfigurationDescriptor,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        limit: Amount,
+    ) {
+        self.balance_manager.lock().set_target_amount_limit(
+            configuration_descriptor,
+            exchange_account_id,
+            symbol,
+            limit,
+        );
+    }
+
+    fn available_leveraged_balance(
+        &self,
+        configuration_descriptor: ConfigurationDescriptor,
+        side: OrderSide,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        price: Price,
+        explanation: &mut Option<Explanation>,
+    ) -> Option<Amount> {
+        // TODO: delete deep_clone
+        let orders = self
+            .exchanges
+            .iter()
+            .flat_map(|x| {
+                x.orders
+                    .not_finished
+                    .iter()
+                    .map(|y| y.clone())
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
+            self.balance_manager.clone(),
+            Some(&mut orders.iter()),
+        )
+        .expect("StrategyContext::available_leveraged_balance: failed to clone and subtract not approved data for BalanceManager");
+
+        let balance_manager = balance_manager.lock();
+        balance_manager.get_leveraged_balance_in_amount_currency_code(
+            configuration_descriptor,
+            side,
+            exchange_account_id,
+            symbol,
+            price,
+            explanation,
+        )
+    }
+}
@@ -0,0 +1,600 @@
+//! Support for unit tests of strategies.
+//!
+//! `StrategyTestHarness` drives a strategy the same way as disposition executor does, but without
+//! exchanges, balance manager and the rest of the engine: test feeds scripted order book snapshots
+//! and fills and asserts on trading contexts (order intents) returned by the strategy.
+//! Warm-up, degraded mode and orders synchronization of disposition executor aren't simulated.
+
+use crate::disposition_execution::strategy::DispositionStrategy;
+use crate::disposition_execution::strategy_context::StrategyContext;
+use crate::disposition_execution::{PriceSlot, PriceSlotId, TradeDisposition, TradingContext};
+use crate::explanation::Explanation;
+use crate::misc::market_data_mode::MarketDataMode;
+use crate::misc::time::time_manager;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use chrono::Duration;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::fill::{OrderFill, OrderFillType};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderFillRole, OrderOptions, OrderRole, OrderSide, OrderSnapshot,
+    OrderStatus, Price, SortedOrderData,
+};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const TEST_STRATEGY_NAME: &str = "StrategyTestHarness";
+
+/// Runs strategy on scripted events and records trading contexts calculated by it
+pub struct StrategyTestHarness<S: DispositionStrategy> {
+    strategy: Box<S>,
+    exchange_account_id: ExchangeAccountId,
+    local_snapshots_service: LocalSnapshotsService,
+    buy_price_slot: PriceSlot,
+    sell_price_slot: PriceSlot,
+    now: DateTime,
+    trading_contexts: Vec<Option<TradingContext>>,
+}
+
+impl<S: DispositionStrategy> StrategyTestHarness<S> {
+    pub fn new(strategy: Box<S>, exchange_account_id: ExchangeAccountId) -> Self {
+        StrategyTestHarness {
+            strategy,
+            exchange_account_id,
+            local_snapshots_service: LocalSnapshotsService::default(),
+            buy_price_slot: PriceSlot::new(
+                PriceSlotId::new(TEST_STRATEGY_NAME.into(), 0),
+                OrderSide::Buy,
+            ),
+            sell_price_slot: PriceSlot::new(
+                PriceSlotId::new(TEST_STRATEGY_NAME.into(), 0),
+                OrderSide::Sell,
+            ),
+            now: time_manager::now(),
+            trading_contexts: Vec::new(),
+        }
+    }
+
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    pub fn strategy_mut(&mut self) -> &mut S {
+        &mut self.strategy
+    }
+
+    pub fn local_snapshots_service(&self) -> &LocalSnapshotsService {
+        &self.local_snapshots_service
+    }
+
+    /// Time passed to strategy. Scripted events are created at this time
+    pub fn now(&self) -> DateTime {
+        self.now
+    }
+
+    pub fn set_now(&mut self, now: DateTime) {
+        self.now = now;
+    }
+
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.now = self.now + duration;
+    }
+
+    /// Handle event like disposition executor: order book events update local snapshots and
+    /// trading context is recalculated only on order book and liquidation price events.
+    /// Returns recalculated trading context
+    pub fn feed_event(&mut self, event: &ExchangeEvent) -> Option<TradingContext> {
+        let need_recalculate_trading_context = match event {
+            ExchangeEvent::OrderBookEvent(order_book_event) => {
+                let _ = self.local_snapshots_service.update(order_book_event);
+                true
+            }
+            ExchangeEvent::LiquidationPrice(_) => true,
+            _ => false,
+        };
+
+        if !need_recalculate_trading_context {
+            return None;
+        }
+
+        let mut explanation = Explanation::default();
+        let trading_context = self.strategy.calculate_trading_context(
+            event,
+            self.now,
+            &self.local_snapshots_service,
+            &mut explanation,
+        );
+        self.trading_contexts.push(trading_context.clone());
+
+        trading_context
+    }
+
+    /// Feed full order book snapshot of the market
+    pub fn feed_order_book(
+        &mut self,
+        currency_pair: CurrencyPair,
+        bids: &[(Price, Amount)],
+        asks: &[(Price, Amount)],
+    ) -> Option<TradingContext> {
+        let event = order_book_snapshot_event(
+            MarketAccountId::new(self.exchange_account_id, currency_pair),
+            bids,
+            asks,
+            self.now,
+        );
+
+        self.feed_event(&event)
+    }
+
+    /// Pass order with fills to strategy like disposition executor does for orders of strategy
+    pub fn feed_fill(&mut self, order: OrderSnapshot) -> anyhow::Result<()> {
+        let price_slot = match order.side() {
+            OrderSide::Buy => &self.buy_price_slot,
+            OrderSide::Sell => &self.sell_price_slot,
+        };
+
+        self.strategy.handle_order_fill(
+            &Arc::new(order),
+            price_slot,
+            self.exchange_account_id,
+            CancellationToken::default(),
+        )
+    }
+
+    /// All trading contexts calculated by strategy in order of feeding events
+    pub fn trading_contexts(&self) -> &[Option<TradingContext>] {
+        &self.trading_contexts
+    }
+
+    pub fn last_trading_context(&self) -> Option<&TradingContext> {
+        self.trading_contexts.last()?.as_ref()
+    }
+
+    /// Order intents on specified side from the last calculated trading context
+    pub fn last_dispositions(&self, side: OrderSide) -> Vec<TradeDisposition> {
+        self.last_trading_context()
+            .map(|trading_context| {
+                trading_context.by_side[side]
+                    .estimating
+                    .iter()
+                    .filter_map(|x| x.value.as_ref().map(|cycle| cycle.disposition.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// `StrategyContext` with state specified by test instead of engine
+#[derive(Default)]
+pub struct TestStrategyContext {
+    symbols: Mutex<HashMap<MarketAccountId, Arc<Symbol>>>,
+    available_balances: Mutex<HashMap<(MarketAccountId, OrderSide), Amount>>,
+    price_bands: Mutex<HashMap<MarketAccountId, (Option<Price>, Option<Price>)>>,
+    market_data_modes: Mutex<HashMap<MarketAccountId, MarketDataMode>>,
+    target_amount_limits: Mutex<HashMap<MarketAccountId, Amount>>,
+}
+
+impl TestStrategyContext {
+    pub fn add_symbol(&self, exchange_account_id: ExchangeAccountId, symbol: Arc<Symbol>) {
+        let market_account_id = MarketAccountId::new(exchange_account_id, symbol.currency_pair());
+        self.symbols.lock().insert(market_account_id, symbol);
+    }
+
+    pub fn set_available_balance(
+        &self,
+        market_account_id: MarketAccountId,
+        side: OrderSide,
+        amount: Amount,
+    ) {
+        self.available_balances
+            .lock()
+            .insert((market_account_id, side), amount);
+    }
+
+    pub fn set_price_band(
+        &self,
+        market_account_id: MarketAccountId,
+        price_band: (Option<Price>, Option<Price>),
+    ) {
+        self.price_bands
+            .lock()
+            .insert(market_account_id, price_band);
+    }
+
+    pub fn set_market_data_mode(&self, market_account_id: MarketAccountId, mode: MarketDataMode) {
+        self.market_data_modes
+            .lock()
+            .insert(market_account_id, mode);
+    }
+
+    /// Limit which was set by strategy through `StrategyContext::set_target_amount_limit`
+    pub fn target_amount_limit(&self, market_account_id: MarketAccountId) -> Option<Amount> {
+        self.target_amount_limits
+            .lock()
+            .get(&market_account_id)
+            .copied()
+    }
+}
+
+impl StrategyContext for TestStrategyContext {
+    fn symbol(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> Option<Arc<Symbol>> {
+        self.symbols
+            .lock()
+            .get(&MarketAccountId::new(exchange_account_id, currency_pair))
+            .cloned()
+    }
+
+    fn allowed_price_band(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+    ) -> (Option<Price>, Option<Price>) {
+        self.price_bands
+            .lock()
+            .get(&MarketAccountId::new(exchange_account_id, currency_pair))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn market_data_mode(&self, market_account_id: MarketAccountId) -> MarketDataMode {
+        self.market_data_modes
+            .lock()
+            .get(&market_account_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn set_target_amount_limit(
+        &self,
+        _configuration_descriptor: ConfigurationDescriptor,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        limit: Amount,
+    ) {
+        let market_account_id = MarketAccountId::new(exchange_account_id, symbol.currency_pair());
+        self.target_amount_limits
+            .lock()
+            .insert(market_account_id, limit);
+    }
+
+    fn available_leveraged_balance(
+        &self,
+        _configuration_descriptor: ConfigurationDescriptor,
+        side: OrderSide,
+        exchange_account_id: ExchangeAccountId,
+        symbol: Arc<Symbol>,
+        _price: Price,
+        _explanation: &mut Option<Explanation>,
+    ) -> Option<Amount> {
+        let market_account_id = MarketAccountId::new(exchange_account_id, symbol.currency_pair());
+        self.available_balances
+            .lock()
+            .get(&(market_account_id, side))
+            .copied()
+    }
+}
+
+/// Builder of spot or derivative `Symbol` with tick precisions typical for crypto exchanges
+pub struct SymbolBuilder {
+    base: &'static str,
+    quote: &'static str,
+    is_derivative: bool,
+    price_tick: Price,
+    amount_tick: Amount,
+    min_amount: Option<Amount>,
+    min_cost: Option<Price>,
+}
+
+impl SymbolBuilder {
+    pub fn new(base: &'static str, quote: &'static str) -> Self {
+        SymbolBuilder {
+            base,
+            quote,
+            is_derivative: false,
+            price_tick: dec!(0.01),
+            amount_tick: dec!(0.001),
+            min_amount: Some(dec!(0.001)),
+            min_cost: None,
+        }
+    }
+
+    pub fn derivative(mut self) -> Self {
+        self.is_derivative = true;
+        self
+    }
+
+    pub fn price_tick(mut self, price_tick: Price) -> Self {
+        self.price_tick = price_tick;
+        self
+    }
+
+    pub fn amount_tick(mut self, amount_tick: Amount) -> Self {
+        self.amount_tick = amount_tick;
+        self
+    }
+
+    pub fn min_amount(mut self, min_amount: Option<Amount>) -> Self {
+        self.min_amount = min_amount;
+        self
+    }
+
+    pub fn min_cost(mut self, min_cost: Option<Price>) -> Self {
+        self.min_cost = min_cost;
+        self
+    }
+
+    pub fn build(self) -> Arc<Symbol> {
+        Arc::new(Symbol::new(
+            self.is_derivative,
+            self.base.into(),
+            self.base.into(),
+            self.quote.into(),
+            self.quote.into(),
+            None,
+            None,
+            self.min_amount,
+            None,
+            self.min_cost,
+            self.base.into(),
+            None,
+            Precision::ByTick {
+                tick: self.price_tick,
+            },
+            Precision::ByTick {
+                tick: self.amount_tick,
+            },
+        ))
+    }
+}
+
+/// Builder of limit `OrderSnapshot` created on exchange, optionally with fills
+pub struct OrderSnapshotBuilder {
+    order: OrderSnapshot,
+}
+
+impl OrderSnapshotBuilder {
+    pub fn limit(
+        market_account_id: MarketAccountId,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+    ) -> Self {
+        let mut order = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(price),
+            Some(OrderRole::Maker),
+            market_account_id.exchange_account_id,
+            market_account_id.currency_pair,
+            amount,
+            side,
+            None,
+            TEST_STRATEGY_NAME,
+        );
+        order.set_status(OrderStatus::Created, time_manager::now());
+
+        OrderSnapshotBuilder { order }
+    }
+
+    pub fn role(mut self, role: OrderRole) -> Self {
+        self.order.props.role = Some(role);
+        self
+    }
+
+    /// Add fill with zero commission. Order becomes completed when it's filled fully
+    pub fn fill(mut self, price: Price, amount: Amount) -> Self {
+        let role = match self.order.props.role {
+            Some(OrderRole::Taker) => OrderFillRole::Taker,
+            _ => OrderFillRole::Maker,
+        };
+        let commission_currency_code = self.order.currency_pair().to_codes().quote;
+
+        let now = time_manager::now();
+        self.order.add_fill(OrderFill::new(
+            Uuid::new_v4(),
+            None,
+            now,
+            OrderFillType::UserTrade,
+            None,
+            price,
+            amount,
+            price * amount,
+            role,
+            commission_currency_code,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            commission_currency_code,
+            Decimal::ZERO,
+            Decimal::ZERO,
+            true,
+            None,
+            Some(self.order.side()),
+        ));
+
+        if self.order.fills.filled_amount >= self.order.amount() {
+            self.order.set_status(OrderStatus::Completed, now);
+        }
+
+        self
+    }
+
+    pub fn build(self) -> OrderSnapshot {
+        self.order
+    }
+}
+
+/// Event with full order book snapshot of the market
+pub fn order_book_snapshot_event(
+    market_account_id: MarketAccountId,
+    bids: &[(Price, Amount)],
+    asks: &[(Price, Amount)],
+    time: DateTime,
+) -> ExchangeEvent {
+    let order_book_data = OrderBookData::new(
+        asks.iter().copied().collect::<SortedOrderData>(),
+        bids.iter().copied().collect::<SortedOrderData>(),
+    );
+
+    ExchangeEvent::OrderBookEvent(OrderBookEvent::new(
+        time,
+        market_account_id.exchange_account_id,
+        market_account_id.currency_pair,
+        String::default(),
+        EventType::Snapshot,
+        Arc::new(order_book_data),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::{TradeCycle, TradingContextBySide};
+    use crate::explanation::WithExplanation;
+
+    /// Quotes on top of order book and remembers filled amount
+    struct TopOfBookStrategy {
+        market_account_id: MarketAccountId,
+        context: Arc<dyn StrategyContext>,
+        filled_amount: Mutex<Amount>,
+    }
+
+    impl TopOfBookStrategy {
+        fn context_by_side(
+            &self,
+            side: OrderSide,
+            local_snapshots_service: &LocalSnapshotsService,
+        ) -> Option<TradingContextBySide> {
+            let snapshot =
+                local_snapshots_service.get_snapshot(self.market_account_id.market_id())?;
+            let price = snapshot.get_top(side)?.0;
+            let symbol = self.context.symbol(
+                self.market_account_id.exchange_account_id,
+                self.market_account_id.currency_pair,
+            )?;
+            let amount = self.context.available_leveraged_balance(
+                self.configuration_descriptor(),
+                side,
+                self.market_account_id.exchange_account_id,
+                symbol,
+                price,
+                &mut None,
+            )?;
+
+            Some(TradingContextBySide {
+                max_amount: amount,
+                estimating: vec![WithExplanation {
+                    value: Some(TradeCycle {
+                        order_role: OrderRole::Maker,
+                        strategy_name: "TopOfBookStrategy".into(),
+                        metadata: Default::default(),
+                        disposition: TradeDisposition::new(
+                            self.market_account_id,
+                            side,
+                            price,
+                            amount,
+                        ),
+                    }),
+                    explanation: Explanation::default(),
+                }],
+            })
+        }
+    }
+
+    impl DispositionStrategy for TopOfBookStrategy {
+        fn calculate_trading_context(
+            &mut self,
+            _event: &ExchangeEvent,
+            _now: DateTime,
+            local_snapshots_service: &LocalSnapshotsService,
+            _explanation: &mut Explanation,
+        ) -> Option<TradingContext> {
+            Some(TradingContext::new(
+                self.context_by_side(OrderSide::Buy, local_snapshots_service)?,
+                self.context_by_side(OrderSide::Sell, local_snapshots_service)?,
+            ))
+        }
+
+        fn handle_order_fill(
+            &self,
+            cloned_order: &Arc<OrderSnapshot>,
+            _price_slot: &PriceSlot,
+            _target_eai: ExchangeAccountId,
+            _cancellation_token: CancellationToken,
+        ) -> anyhow::Result<()> {
+            *self.filled_amount.lock() += cloned_order.fills.filled_amount;
+            Ok(())
+        }
+
+        fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+            ConfigurationDescriptor::new("TopOfBookStrategy".into(), "test".into())
+        }
+    }
+
+    #[test]
+    fn strategy_quotes_on_scripted_order_book_and_handles_fills() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let symbol = SymbolBuilder::new("btc", "usdt").build();
+        let market_account_id = MarketAccountId::new(exchange_account_id, symbol.currency_pair());
+
+        let context = Arc::new(TestStrategyContext::default());
+        context.add_symbol(exchange_account_id, symbol.clone());
+        context.set_available_balance(market_account_id, OrderSide::Buy, dec!(2));
+        context.set_available_balance(market_account_id, OrderSide::Sell, dec!(1));
+
+        let strategy = Box::new(TopOfBookStrategy {
+            market_account_id,
+            context: context.clone(),
+            filled_amount: Mutex::new(Amount::ZERO),
+        });
+        let mut harness = StrategyTestHarness::new(strategy, exchange_account_id);
+
+        let trading_context = harness.feed_order_book(
+            symbol.currency_pair(),
+            &[(dec!(100), dec!(1)), (dec!(99), dec!(3))],
+            &[(dec!(101), dec!(2))],
+        );
+        assert!(trading_context.is_some());
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Buy),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Buy,
+                dec!(100),
+                dec!(2)
+            )]
+        );
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Sell),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Sell,
+                dec!(101),
+                dec!(1)
+            )]
+        );
+
+        let order =
+            OrderSnapshotBuilder::limit(market_account_id, OrderSide::Buy, dec!(100), dec!(2))
+                .fill(dec!(100), dec!(0.5))
+                .fill(dec!(100), dec!(1.5))
+                .build();
+        assert_eq!(order.status(), OrderStatus::Completed);
+
+        harness.feed_fill(order).expect("in test");
+        assert_eq!(*harness.strategy().filled_amount.lock(), dec!(2));
+        assert_eq!(harness.trading_contexts().len(), 1);
+    }
+}
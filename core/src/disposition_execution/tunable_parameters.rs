@@ -0,0 +1,218 @@
+use anyhow::{bail, Context, Result};
+use mmb_database::impl_event;
+use mmb_domain::decimal_serialization::{decimal, option_decimal};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParameterType {
+    Decimal,
+    Integer,
+}
+
+/// Parameter of strategy which can be changed at runtime through control panel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TunableParameter {
+    pub name: String,
+    pub parameter_type: ParameterType,
+    #[serde(default, with = "option_decimal")]
+    pub min: Option<Decimal>,
+    #[serde(default, with = "option_decimal")]
+    pub max: Option<Decimal>,
+    /// Current value of parameter
+    #[serde(with = "decimal")]
+    pub value: Decimal,
+}
+
+impl TunableParameter {
+    pub fn new(name: impl Into<String>, parameter_type: ParameterType, value: Decimal) -> Self {
+        Self {
+            name: name.into(),
+            parameter_type,
+            min: None,
+            max: None,
+            value,
+        }
+    }
+
+    pub fn with_range(mut self, min: Option<Decimal>, max: Option<Decimal>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    fn validate(&self, value: Decimal) -> Result<()> {
+        if self.parameter_type == ParameterType::Integer && !value.fract().is_zero() {
+            bail!("Parameter {} should be integer, got {value}", self.name);
+        }
+
+        if let Some(min) = self.min {
+            if value < min {
+                bail!("Parameter {} should be >= {min}, got {value}", self.name);
+            }
+        }
+
+        if let Some(max) = self.max {
+            if value > max {
+                bail!("Parameter {} should be <= {max}, got {value}", self.name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Record of config audit trail about parameter changed at runtime
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub time: DateTime,
+    pub parameter: String,
+    pub old_value: Decimal,
+    pub new_value: Decimal,
+}
+
+impl_event!(ConfigChange, "config_changes");
+
+/// Tunable parameters declared by strategy and changes requested through control panel.
+/// Requested changes are validated immediately and applied by disposition executor
+/// between decision cycles all at once
+#[derive(Default)]
+pub struct StrategyParameters {
+    parameters: RwLock<Vec<TunableParameter>>,
+    pending_changes: Mutex<HashMap<String, Decimal>>,
+}
+
+impl StrategyParameters {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub(crate) fn register(&self, parameters: Vec<TunableParameter>) {
+        *self.parameters.write() = parameters;
+        self.pending_changes.lock().clear();
+    }
+
+    pub fn parameters(&self) -> Vec<TunableParameter> {
+        self.parameters.read().clone()
+    }
+
+    /// Validate requested values and queue them for applying. If any value is invalid nothing is queued
+    pub fn request_change(&self, changes: HashMap<String, Decimal>) -> Result<()> {
+        if changes.is_empty() {
+            bail!("No parameters are specified");
+        }
+
+        let parameters = self.parameters.read();
+        for (name, value) in &changes {
+            parameters
+                .iter()
+                .find(|x| &x.name == name)
+                .with_context(|| format!("Parameter {name} isn't tunable"))?
+                .validate(*value)?;
+        }
+
+        self.pending_changes.lock().extend(changes);
+
+        Ok(())
+    }
+
+    pub(crate) fn take_pending_changes(&self) -> Option<HashMap<String, Decimal>> {
+        let mut pending_changes = self.pending_changes.lock();
+        if pending_changes.is_empty() {
+            return None;
+        }
+
+        Some(std::mem::take(&mut *pending_changes))
+    }
+
+    /// Update current values of parameters after changes are applied by strategy
+    pub(crate) fn commit(
+        &self,
+        changes: &HashMap<String, Decimal>,
+        now: DateTime,
+    ) -> Vec<ConfigChange> {
+        let mut parameters = self.parameters.write();
+        parameters
+            .iter_mut()
+            .filter_map(|parameter| {
+                let new_value = *changes.get(&parameter.name)?;
+                let old_value = std::mem::replace(&mut parameter.value, new_value);
+                Some(ConfigChange {
+                    time: now,
+                    parameter: parameter.name.clone(),
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn strategy_parameters() -> Arc<StrategyParameters> {
+        let strategy_parameters = StrategyParameters::new();
+        strategy_parameters.register(vec![
+            TunableParameter::new("spread", ParameterType::Decimal, dec!(1))
+                .with_range(Some(dec!(0)), None),
+            TunableParameter::new("levels", ParameterType::Integer, dec!(3))
+                .with_range(Some(dec!(1)), Some(dec!(10))),
+        ]);
+        strategy_parameters
+    }
+
+    #[test]
+    fn invalid_change_rejects_whole_request() {
+        let strategy_parameters = strategy_parameters();
+
+        for levels in [dec!(2.5), dec!(0), dec!(11)] {
+            let result = strategy_parameters.request_change(HashMap::from([
+                ("spread".to_owned(), dec!(2)),
+                ("levels".to_owned(), levels),
+            ]));
+            assert!(result.is_err(), "levels {levels}");
+        }
+
+        let result =
+            strategy_parameters.request_change(HashMap::from([("skew".to_owned(), dec!(0.5))]));
+        assert!(result.is_err());
+
+        assert_eq!(strategy_parameters.take_pending_changes(), None);
+    }
+
+    #[test]
+    fn applied_changes_update_current_values() {
+        let strategy_parameters = strategy_parameters();
+
+        strategy_parameters
+            .request_change(HashMap::from([("spread".to_owned(), dec!(2))]))
+            .expect("in test");
+        strategy_parameters
+            .request_change(HashMap::from([("levels".to_owned(), dec!(5))]))
+            .expect("in test");
+
+        let changes = strategy_parameters.take_pending_changes().expect("in test");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(strategy_parameters.take_pending_changes(), None);
+
+        let config_changes = strategy_parameters.commit(&changes, Utc::now());
+        assert_eq!(config_changes.len(), 2);
+        assert_eq!(config_changes[0].old_value, dec!(1));
+        assert_eq!(config_changes[0].new_value, dec!(2));
+
+        let values = strategy_parameters
+            .parameters()
+            .into_iter()
+            .map(|x| x.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![dec!(2), dec!(5)]);
+    }
+}
@@ -0,0 +1,111 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Readiness of data which strategy needs to accumulate before quoting
+/// (e.g. candles history, index price, funding rate)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataReadiness {
+    pub name: String,
+    /// Accumulated part of required data from 0 to 1
+    pub progress: Decimal,
+}
+
+impl DataReadiness {
+    pub fn new(name: impl Into<String>, progress: Decimal) -> Self {
+        Self {
+            name: name.into(),
+            progress: progress.clamp(dec!(0), dec!(1)),
+        }
+    }
+
+    pub fn from_count(name: impl Into<String>, accumulated: usize, required: usize) -> Self {
+        let progress = match required {
+            0 => dec!(1),
+            _ => Decimal::from(accumulated) / Decimal::from(required),
+        };
+
+        Self::new(name, progress)
+    }
+
+    pub fn from_flag(name: impl Into<String>, is_ready: bool) -> Self {
+        Self::new(name, if is_ready { dec!(1) } else { dec!(0) })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.progress >= dec!(1)
+    }
+}
+
+/// Gate for order intents of strategy which are withheld until all declared data dependencies are ready.
+/// Once warm-up is completed it isn't checked anymore
+#[derive(Default)]
+pub(crate) struct WarmUp {
+    is_completed: bool,
+    last_progress: Vec<DataReadiness>,
+}
+
+impl WarmUp {
+    pub fn is_completed(&self) -> bool {
+        self.is_completed
+    }
+
+    /// Update readiness of data dependencies. Returns progress if it is changed since last update
+    pub fn update(&mut self, dependencies: Vec<DataReadiness>) -> Option<&[DataReadiness]> {
+        if self.is_completed {
+            return None;
+        }
+
+        self.is_completed = dependencies.iter().all(|x| x.is_ready());
+
+        if self.last_progress == dependencies && !self.is_completed {
+            return None;
+        }
+
+        self.last_progress = dependencies;
+        Some(&self.last_progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warm_up_is_completed_when_all_dependencies_are_ready() {
+        let mut warm_up = WarmUp::default();
+
+        let progress = vec![
+            DataReadiness::from_count("candles", 5, 20),
+            DataReadiness::from_flag("index_price", true),
+        ];
+        assert!(warm_up.update(progress.clone()).is_some());
+        assert!(!warm_up.is_completed());
+
+        // unchanged progress isn't reported again
+        assert!(warm_up.update(progress).is_none());
+
+        let progress = vec![
+            DataReadiness::from_count("candles", 25, 20),
+            DataReadiness::from_flag("index_price", true),
+        ];
+        assert_eq!(
+            warm_up.update(progress).map(|x| x[0].progress),
+            Some(dec!(1))
+        );
+        assert!(warm_up.is_completed());
+
+        // data may become unavailable later, but warm-up stays completed
+        assert!(warm_up
+            .update(vec![DataReadiness::from_flag("index_price", false)])
+            .is_none());
+        assert!(warm_up.is_completed());
+    }
+
+    #[test]
+    fn strategy_without_dependencies_does_not_need_warm_up() {
+        let mut warm_up = WarmUp::default();
+        assert!(warm_up.update(Vec::new()).is_some());
+        assert!(warm_up.is_completed());
+    }
+}
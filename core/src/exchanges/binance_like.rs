@@ -0,0 +1,187 @@
+//! Helpers shared by exchanges with Binance-like REST API (Binance spot, MEXC etc.):
+//! HMAC-SHA256 query signature, `{"code", "msg"}` errors and order sides and statuses
+use crate::exchanges::rest_client::{RestResponse, UriBuilder};
+use crate::exchanges::traits::ExchangeError;
+use anyhow::{Context, Result};
+use hmac::digest::generic_array;
+use hmac::digest::generic_array::GenericArray;
+use hmac::{Hmac, Mac};
+use mmb_domain::market::ExchangeErrorType;
+use mmb_domain::order::snapshot::{OrderSide, OrderStatus};
+use mmb_utils::time::get_current_milliseconds;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::fmt::{Display, Formatter};
+
+pub const LISTEN_KEY: &str = "listenKey";
+
+/// Adds hex encoded HMAC-SHA256 of query as last `signature` parameter
+pub fn write_signature_to_builder(secret_key: &str, builder: &mut UriBuilder) {
+    let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+        .expect("Unable to calculate hmac for request signature");
+    hmac.update(builder.query());
+
+    let hmac_bytes = hmac.finalize().into_bytes();
+
+    // hex representation of signature have double size of input data
+    builder.ensure_free_size(hmac_bytes.len() * 2);
+
+    struct HexAdapter<'a> {
+        bytes: &'a GenericArray<u8, generic_array::typenum::U32>,
+    }
+    impl<'a> Display for HexAdapter<'a> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:x}", self.bytes)
+        }
+    }
+
+    let hexer = HexAdapter { bytes: &hmac_bytes };
+    builder.add_kv("signature", hexer);
+}
+
+pub fn add_authentification(secret_key: &str, builder: &mut UriBuilder) {
+    let time_stamp = get_current_milliseconds();
+    builder.add_kv("timestamp", time_stamp);
+
+    write_signature_to_builder(secret_key, builder);
+}
+
+/// Errors are returned as `{"code": -2011, "msg": "Unknown order sent."}`,
+/// so `ExchangeError` is created with unknown type which should be clarified by exchange
+pub fn check_rest_error(response: &RestResponse) -> Result<(), ExchangeError> {
+    //Binance is a little inconsistent: for failed responses sometimes they include
+    //only code or only success:false but sometimes both
+    if !(response.content.contains(r#""success":false"#) || response.content.contains(r#""code""#))
+    {
+        return Ok(());
+    }
+
+    #[derive(Deserialize)]
+    struct Error {
+        msg: String,
+        code: i64,
+    }
+
+    let error: Error = serde_json::from_str(&response.content).map_err(|err| {
+        ExchangeError::parsing(format!(
+            "Unable to parse response.content: {err:?}\n{}",
+            response.content
+        ))
+    })?;
+
+    Err(ExchangeError::new(
+        ExchangeErrorType::Unknown,
+        error.msg,
+        Some(error.code),
+    ))
+}
+
+pub fn parse_listen_key(response: &RestResponse) -> Result<String> {
+    #[derive(Deserialize)]
+    struct ListenKey {
+        #[serde(rename = "listenKey")]
+        listen_key: String,
+    }
+
+    let data: ListenKey =
+        serde_json::from_str(&response.content).context("Unable to parse listen key response")?;
+
+    Ok(data.listen_key)
+}
+
+pub fn parse_server_time(response: &RestResponse) -> Result<i64> {
+    #[derive(Deserialize)]
+    struct ServerTime {
+        #[serde(rename = "serverTime")]
+        time: i64,
+    }
+
+    let server_time_struct: ServerTime =
+        serde_json::from_str(&response.content).context("Failed to parse get time response")?;
+    Ok(server_time_struct.time)
+}
+
+pub fn get_server_order_side(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "BUY",
+        OrderSide::Sell => "SELL",
+    }
+}
+
+pub fn get_local_order_side(side: &str) -> OrderSide {
+    match side {
+        "BUY" => OrderSide::Buy,
+        "SELL" => OrderSide::Sell,
+        _ => panic!("Unexpected order side"),
+    }
+}
+
+pub fn get_local_order_status(status: &str) -> OrderStatus {
+    match status {
+        "NEW" | "PARTIALLY_FILLED" => OrderStatus::Created,
+        "FILLED" => OrderStatus::Completed,
+        "PENDING_CANCEL" => OrderStatus::Canceling,
+        // MEXC reports canceled partially filled orders separately
+        "CANCELED" | "PARTIALLY_CANCELED" | "EXPIRED" | "REJECTED" => OrderStatus::Canceled,
+        _ => panic!("Unexpected order status"),
+    }
+}
+
+/// Response of `/api/v3/account`
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub struct SpotAccountInfo<'a> {
+    pub balances: Vec<SpotBalance<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpotBalance<'a> {
+    pub asset: &'a str,
+    pub free: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+
+    #[test]
+    fn generate_signature() {
+        // All values and strings gotten from binanсe API example
+        let secret_key = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
+
+        let mut builder = UriBuilder::from_path("/test");
+        builder.add_kv("symbol", "LTCBTC");
+        builder.add_kv("side", "BUY");
+        builder.add_kv("type", "LIMIT");
+        builder.add_kv("timeInForce", "GTC");
+        builder.add_kv("quantity", "1");
+        builder.add_kv("price", "0");
+        builder.add_kv("recvWindow", "5000");
+        builder.add_kv("timestamp", "1499827319559");
+        write_signature_to_builder(secret_key, &mut builder);
+
+        let query = builder.query();
+
+        let expected = b"76f4fcd9c09d7969fcf97254950d690077f0fe090ea68ec7601a69ff36acd34b";
+
+        //expected that signature was last parameter
+        let signature_value = query.split_at(query.len() - expected.len()).1;
+
+        assert_eq!(signature_value, expected);
+    }
+
+    #[test]
+    fn error_with_code_and_msg() {
+        let response = RestResponse::new(
+            r#"{"code":-2011,"msg":"Unknown order sent."}"#.to_owned(),
+            StatusCode::BAD_REQUEST,
+        );
+
+        let error = check_rest_error(&response).expect_err("in test");
+
+        assert_eq!(error.message, "Unknown order sent.");
+        assert_eq!(error.code, Some(-2011));
+    }
+}
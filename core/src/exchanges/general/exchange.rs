@@ -1,23 +1,27 @@
 use super::polling_timeout_manager::PollingTimeoutManager;
 use crate::balance::manager::balance_manager::BalanceManager;
 use crate::connectivity::{
-    websocket_open, ConnectivityError, WebSocketParams, WebSocketRole, WsSender,
+    websocket_open, ConnectivityError, NetworkConnector, WebSocketParams, WebSocketRole, WsSender,
 };
 use crate::database::events::recorder::EventRecorder;
 use crate::exchanges::block_reasons::WEBSOCKET_DISCONNECTED;
 use crate::exchanges::exchange_blocker::{BlockType, ExchangeBlocker};
-use crate::exchanges::general::features::ExchangeFeatures;
+use crate::exchanges::general::features::{ExchangeCapability, ExchangeFeatures};
 use crate::exchanges::general::order::cancel::CancelOrderResult;
 use crate::exchanges::general::order::create::CreateOrderResult;
 use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::general::websocket_shards::WebSocketShards;
 use crate::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::exchanges::traits::{ExchangeClient, ExchangeError};
 use crate::infrastructure::spawn_future;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use crate::math::ConvertPercentToRate;
+use crate::misc::limit_collar::LimitCollar;
 use crate::misc::time::time_manager;
 use crate::orders::buffered_fills::buffered_canceled_orders_manager::BufferedCanceledOrdersManager;
 use crate::orders::buffered_fills::buffered_fills_manager::BufferedFillsManager;
+use crate::settings::OperationPoliciesSettings;
 use anyhow::{bail, Context, Result};
 use dashmap::DashMap;
 use function_name::named;
@@ -37,9 +41,9 @@ use mmb_domain::order::event::OrderEvent;
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::pool::OrdersPool;
-use mmb_domain::order::snapshot::OrderSide;
 use mmb_domain::order::snapshot::{Amount, Price};
-use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId};
+use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId, TriggerPriceType};
+use mmb_domain::order::snapshot::{OrderRole, OrderSide};
 use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
@@ -47,6 +51,7 @@ use mmb_utils::send_expected::SendExpectedByRef;
 use mmb_utils::{nothing_to_do, DateTime};
 use parking_lot::Mutex;
 use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::Serialize;
 use std::fmt::Debug;
 use std::ops::DerefMut;
@@ -54,7 +59,8 @@ use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 use tokio::sync::{broadcast, oneshot};
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
+use url::Url;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum RequestResult<T> {
@@ -95,6 +101,8 @@ pub struct Exchange {
     pub currencies: Mutex<Vec<CurrencyCode>>,
     pub leverage_by_currency_pair: DashMap<CurrencyPair, Decimal>,
     pub order_book_top: DashMap<CurrencyPair, OrderBookTop>,
+    /// Markets which market orders are converted to marketable limit orders
+    pub(super) limit_collars: DashMap<CurrencyPair, LimitCollar>,
     pub exchange_client: BoxExchangeClient,
     pub(super) features: ExchangeFeatures,
     pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
@@ -108,6 +116,8 @@ pub struct Exchange {
     pub(super) orders_created_events: DashMap<ClientOrderId, oneshot::Sender<()>>,
     pub(super) last_trades_update_time: DashMap<MarketId, DateTime>,
     pub(super) last_trades: DashMap<MarketId, Trade>,
+    /// Last trade id received in trades feed, used to detect missed prints
+    pub(super) last_tape_trade_ids: DashMap<MarketId, u64>,
     pub(super) timeout_manager: Arc<TimeoutManager>,
     pub(crate) balance_manager: Mutex<Option<Weak<Mutex<BalanceManager>>>>,
     pub(super) buffered_fills_manager: Mutex<BufferedFillsManager>,
@@ -131,7 +141,11 @@ pub struct Exchange {
         ),
     >,
     exchange_blocker: Weak<ExchangeBlocker>,
-    ws_sender: Mutex<Option<WsSender>>,
+    /// Used to spawn background operations of exchange from `&self` methods
+    pub(super) weak_self: Weak<Exchange>,
+    pub(super) ws_sender: Mutex<Option<WsSender>>,
+    pub(super) websocket_shards: Mutex<WebSocketShards>,
+    last_websocket_message_time: Mutex<Option<DateTime>>,
     auto_reconnect: AtomicBool,
 
     // Temporary fix before integration ExchangeBlocker to wait_order_finish/wait_cancel_order fallbacks #641
@@ -139,6 +153,7 @@ pub struct Exchange {
     // Equal 0 by default in case if we cannot get exchange server time
     server_time_latency: AtomicI64,
     pub event_recorder: Arc<EventRecorder>,
+    pub(super) operation_policies: OperationPoliciesSettings,
 }
 
 pub type BoxExchangeClient = Box<dyn ExchangeClient + Send + Sync + 'static>;
@@ -157,6 +172,7 @@ impl Exchange {
         exchange_blocker: Weak<ExchangeBlocker>,
         commission: Commission,
         event_recorder: Arc<EventRecorder>,
+        operation_policies: OperationPoliciesSettings,
     ) -> Arc<Self> {
         let polling_timeout_manager = PollingTimeoutManager::new(timeout_arguments);
 
@@ -169,6 +185,8 @@ impl Exchange {
                 exchange_client,
                 orders,
                 ws_sender: Default::default(),
+                websocket_shards: Default::default(),
+                last_websocket_message_time: Default::default(),
                 order_creation_events: DashMap::new(),
                 order_cancellation_events: DashMap::new(),
                 lifetime_manager,
@@ -186,16 +204,20 @@ impl Exchange {
                 orders_finish_events: DashMap::new(),
                 orders_created_events: DashMap::new(),
                 leverage_by_currency_pair: DashMap::new(),
+                limit_collars: DashMap::new(),
                 last_trades_update_time: DashMap::new(),
                 last_trades: DashMap::new(),
+                last_tape_trade_ids: DashMap::new(),
                 balance_manager: Mutex::new(None),
                 buffered_fills_manager: Default::default(),
                 exchange_blocker,
+                weak_self: e.clone(),
                 buffered_canceled_orders_manager: Default::default(),
                 auto_reconnect: AtomicBool::new(false),
                 timeout,
                 server_time_latency: Default::default(),
                 event_recorder,
+                operation_policies,
             }
         })
     }
@@ -240,6 +262,14 @@ impl Exchange {
             }
         }));
 
+        exchange_client.set_handle_cash_flow_callback(Box::new({
+            let exchange_weak = exchange_weak.clone();
+            move |cash_flow| match exchange_weak.upgrade() {
+                Some(exchange) => exchange.handle_cash_flow(cash_flow),
+                None => log::info!("Unable to upgrade weak reference to Exchange instance"),
+            }
+        }));
+
         exchange_client.set_send_websocket_message_callback(Box::new({
             let exchange_weak = exchange_weak.clone();
             move |role, message| {
@@ -265,7 +295,8 @@ impl Exchange {
         }))
     }
 
-    fn on_websocket_message(&self, msg: &str) {
+    pub(super) fn on_websocket_message(&self, msg: &str) {
+        *self.last_websocket_message_time.lock() = Some(time_manager::now());
         self.maybe_log_websocket_message(msg);
 
         if let Err(error) = self.exchange_client.on_websocket_message(msg) {
@@ -364,10 +395,37 @@ impl Exchange {
         self.connect_ws().await
     }
 
+    pub fn is_websocket_connected(&self) -> bool {
+        self.ws_sender.lock().is_some()
+    }
+
+    pub fn last_websocket_message_time(&self) -> Option<DateTime> {
+        *self.last_websocket_message_time.lock()
+    }
+
+    pub fn is_websocket_enabled(&self) -> bool {
+        self.exchange_client
+            .is_websocket_enabled(WebSocketRole::Main)
+    }
+
+    /// Exchange answers heartbeat pings of websocket connection with pongs
+    pub fn supports_websocket_ping_pong(&self) -> bool {
+        self.features.websocket_options.supports_ping_pong
+    }
+
+    /// Time of last frame received over main websocket connection, including pongs
+    pub fn last_websocket_frame_time(&self) -> Option<DateTime> {
+        self.ws_sender
+            .lock()
+            .as_ref()
+            .and_then(|x| x.last_frame_time())
+    }
+
     pub async fn disconnect_ws(&self) {
         // prevent auto reconnect
         self.auto_reconnect.store(false, Ordering::SeqCst);
         self.ws_sender.lock().take();
+        self.websocket_shards.lock().reset();
     }
 
     pub async fn connect_ws(self: &Arc<Self>) -> Result<()> {
@@ -375,7 +433,7 @@ impl Exchange {
         self.on_connecting();
         // do connect
         match self.connect_internal().await {
-            Ok(reader) => {
+            Ok((reader, extra_shards)) => {
                 // enable auto reconnect after first success
                 self.auto_reconnect.store(true, Ordering::SeqCst);
                 spawn_future(
@@ -383,6 +441,7 @@ impl Exchange {
                     SpawnFutureFlags::STOP_BY_TOKEN,
                     Self::reader_future(Arc::downgrade(self), reader),
                 );
+                self.start_websocket_shards(extra_shards);
                 self.on_connected();
                 Ok(())
             }
@@ -417,9 +476,17 @@ impl Exchange {
     }
 
     /// Actual connect function, all internal work here.
+    /// Returns reader of connections and market data shards which should be served
+    /// by additional connections
     async fn connect_internal(
         self: &Arc<Self>,
-    ) -> Result<tokio::sync::mpsc::UnboundedReceiver<String>, ConnectivityError> {
+    ) -> Result<
+        (
+            tokio::sync::mpsc::UnboundedReceiver<String>,
+            Vec<Vec<SpecificCurrencyPair>>,
+        ),
+        ConnectivityError,
+    > {
         log::info!("Websocket: Connecting on {}", self.exchange_account_id);
 
         if !self
@@ -434,12 +501,56 @@ impl Exchange {
             ));
         };
 
-        let main = self
-            .get_websocket_params(WebSocketRole::Main)
-            .await
-            .map_err(|e| {
-                ConnectivityError::FailedToGetParams(WebSocketRole::Main, e.to_string())
-            })?;
+        // first shard of market data is served by main connection
+        let mut shards = self.market_data_shards().into_iter();
+        let main_shard = shards.next();
+
+        let policy = self.operation_policies.websocket_connect;
+        let mut attempt = 1;
+        let (tx, rx) = loop {
+            let open_result = self
+                .try_open_websocket(main_shard.as_deref(), policy.timeout())
+                .await;
+            let error = match open_result {
+                Ok(connection) => break connection,
+                Err(err) => err,
+            };
+
+            if attempt >= policy.max_attempts {
+                return Err(error);
+            }
+
+            log::warn!(
+                "Websocket: failed to connect on {} on attempt {attempt}: {error}",
+                self.exchange_account_id
+            );
+            attempt += 1;
+            sleep(policy.retry_delay()).await;
+        };
+
+        if let Some(currency_pairs) = &main_shard {
+            self.subscribe_market_data_shard(&tx, currency_pairs)
+                .map_err(|e| {
+                    ConnectivityError::FailedToGetParams(WebSocketRole::Main, e.to_string())
+                })?;
+        }
+
+        self.ws_sender.lock().replace(tx);
+        Ok((rx, shards.collect()))
+    }
+
+    /// Websocket parameters are requested on every attempt, because URL can contain
+    /// one-time token which is already used or expired after failed attempt
+    async fn try_open_websocket(
+        self: &Arc<Self>,
+        main_shard: Option<&[SpecificCurrencyPair]>,
+        open_timeout: Duration,
+    ) -> Result<(WsSender, tokio::sync::mpsc::UnboundedReceiver<String>), ConnectivityError> {
+        let main = match main_shard {
+            Some(currency_pairs) => self.get_market_data_websocket_params(currency_pairs).await,
+            None => self.get_websocket_params(WebSocketRole::Main).await,
+        }
+        .map_err(|e| ConnectivityError::FailedToGetParams(WebSocketRole::Main, e.to_string()))?;
 
         let secondary = if self
             .exchange_client
@@ -459,9 +570,12 @@ impl Exchange {
             );
             None
         };
-        let (tx, rx) = websocket_open(self.exchange_account_id, main, secondary).await?;
-        self.ws_sender.lock().replace(tx);
-        Ok(rx)
+
+        let open_fut = websocket_open(self.exchange_account_id, main, secondary);
+        match timeout(open_timeout, open_fut).await {
+            Ok(connection) => connection,
+            Err(_) => Err(ConnectivityError::Timeout(open_timeout)),
+        }
     }
 
     fn forward_websocket_message(&self, role: WebSocketRole, msg: String) -> Result<()> {
@@ -485,12 +599,60 @@ impl Exchange {
         Ok(())
     }
 
+    /// Arm dead man's switch of exchange. Returns `false` if exchange doesn't support it
+    pub async fn cancel_all_after(
+        &self,
+        timeout: Duration,
+        cancellation_token: CancellationToken,
+    ) -> Result<bool> {
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::CancelAllAfter,
+                None,
+                cancellation_token,
+            )
+            .await;
+
+        match self.exchange_client.cancel_all_after(timeout).await {
+            None => Ok(false),
+            Some(result) => result.map(|_| true),
+        }
+    }
+
     pub async fn get_websocket_params(
         self: &Arc<Self>,
         role: WebSocketRole,
     ) -> Result<WebSocketParams> {
         let ws_url = self.exchange_client.create_ws_url(role).await?;
-        Ok(WebSocketParams::new(ws_url))
+        self.websocket_params_by_url(role, ws_url)
+    }
+
+    pub(super) fn websocket_params_by_url(
+        &self,
+        role: WebSocketRole,
+        ws_url: Url,
+    ) -> Result<WebSocketParams> {
+        let mut params = WebSocketParams::new(ws_url);
+        if let Some(keep_alive) = self.exchange_client.get_websocket_keep_alive(role) {
+            params = params.with_keep_alive(keep_alive);
+        }
+
+        let headers = self.exchange_client.get_websocket_headers(role)?;
+        if !headers.is_empty() {
+            params = params.with_headers(headers);
+        }
+
+        if let Some(compression) = self.exchange_client.get_websocket_compression(role) {
+            params = params.with_compression(compression);
+        }
+
+        let network_settings = &self.exchange_client.get_settings().network;
+        if !network_settings.is_custom_connection() {
+            return Ok(params);
+        }
+
+        Ok(params.with_connector(NetworkConnector::new(network_settings)?))
     }
 
     pub(crate) fn add_event_on_order_change(
@@ -506,6 +668,13 @@ impl Exchange {
             let _ = self.orders.not_finished.remove(&order.client_order_id());
         }
 
+        if let OrderEventType::OrderFilled { .. }
+        | OrderEventType::OrderCompleted { .. }
+        | OrderEventType::CancelOrderSucceeded = event_type
+        {
+            self.cancel_oco_sibling(order);
+        }
+
         let event = ExchangeEvent::OrderEvent(OrderEvent::new(order.clone(), event_type));
         self.events_channel
             .send(event)
@@ -528,7 +697,7 @@ impl Exchange {
             }
             Ok(orders) => {
                 tokio::select! {
-                    _ = self.cancel_orders(orders.clone(), cancellation_token.clone()) => nothing_to_do(),
+                    _ = self.cancel_orders_by_info(orders.clone(), cancellation_token.clone()) => nothing_to_do(),
                     _ = cancellation_token.when_cancelled() => {
                         log::error!(
                             "Opened orders canceling for exchange account id {} was interrupted by CancellationToken for list of orders {:?}",
@@ -674,6 +843,106 @@ impl Exchange {
         }
     }
 
+    /// Set leverage on exchange and use it for balance reservations of the currency pair
+    pub async fn set_leverage(
+        &self,
+        currency_pair: CurrencyPair,
+        leverage: Decimal,
+        cancellation_token: CancellationToken,
+    ) -> Result<Decimal> {
+        if !self.exchange_client.get_settings().is_margin_trading {
+            bail!("Impossible to set leverage for non-derivative market");
+        }
+
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::SetLeverage,
+                None,
+                cancellation_token,
+            )
+            .await;
+
+        let leverage = match self
+            .exchange_client
+            .set_leverage(currency_pair, leverage)
+            .await
+        {
+            None => bail!(
+                "Setting leverage isn't supported on {}",
+                self.exchange_account_id
+            ),
+            Some(leverage) => leverage?,
+        };
+
+        log::info!(
+            "Leverage {leverage} is set for {} {currency_pair}",
+            self.exchange_account_id
+        );
+        self.leverage_by_currency_pair
+            .insert(currency_pair, leverage);
+
+        Ok(leverage)
+    }
+
+    /// Borrow currency on margin account of exchange
+    pub async fn borrow(
+        &self,
+        currency_code: CurrencyCode,
+        amount: Amount,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::Borrow,
+                None,
+                cancellation_token,
+            )
+            .await;
+
+        match self.exchange_client.borrow(currency_code, amount).await {
+            None => bail!("Borrowing isn't supported on {}", self.exchange_account_id),
+            Some(result) => result?,
+        }
+
+        log::info!(
+            "Borrowed {amount} {currency_code} on {}",
+            self.exchange_account_id
+        );
+
+        Ok(())
+    }
+
+    /// Repay borrowed currency on margin account of exchange
+    pub async fn repay(
+        &self,
+        currency_code: CurrencyCode,
+        amount: Amount,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::Repay,
+                None,
+                cancellation_token,
+            )
+            .await;
+
+        match self.exchange_client.repay(currency_code, amount).await {
+            None => bail!("Repaying isn't supported on {}", self.exchange_account_id),
+            Some(result) => result?,
+        }
+
+        log::info!(
+            "Repaid {amount} {currency_code} on {}",
+            self.exchange_account_id
+        );
+
+        Ok(())
+    }
+
     fn update_positions_leverage(&self, positions: &[DerivativePosition]) {
         for position in positions {
             if let Some(mut leverage) = self
@@ -714,7 +983,12 @@ impl Exchange {
         self: &Arc<Self>,
         cancellation_token: CancellationToken,
     ) -> Result<ExchangeBalancesAndPositions> {
-        for retry_attempt in 1..=5 {
+        let policy = self.operation_policies.balance_fetch;
+        for retry_attempt in 1..=policy.max_attempts {
+            if retry_attempt > 1 {
+                sleep(policy.retry_delay()).await;
+            }
+
             self.timeout_manager
                 .reserve_when_available(
                     self.exchange_account_id,
@@ -723,7 +997,21 @@ impl Exchange {
                     cancellation_token.clone(),
                 )
                 .await;
-            match self.exchange_client.get_balance_and_positions().await {
+
+            let balance_fut = self.exchange_client.get_balance_and_positions();
+            let balance_res = match timeout(policy.timeout(), balance_fut).await {
+                Ok(res) => res,
+                Err(_) => {
+                    print_warn(
+                        retry_attempt,
+                        function_name!(),
+                        &self.exchange_account_id,
+                        format!("timeout {:?} is over", policy.timeout()),
+                    );
+                    continue;
+                }
+            };
+            match balance_res {
                 Ok(balance_and_positions) => {
                     if let Some(positions) = &balance_and_positions.positions {
                         self.update_positions_leverage(positions);
@@ -795,6 +1083,31 @@ impl Exchange {
         self.timeout
     }
 
+    /// Fee rate expected for order with specified role
+    pub fn expected_fee_rate(&self, order_role: OrderRole) -> Decimal {
+        self.commission
+            .get_commission(order_role)
+            .fee
+            .percent_to_rate()
+    }
+
+    /// Preferred trigger price type for conditional orders if it's supported by exchange, otherwise last price
+    pub fn available_trigger_price_type(&self, preferred: TriggerPriceType) -> TriggerPriceType {
+        match self
+            .features
+            .order_features
+            .supports_trigger_price_type(preferred)
+        {
+            true => preferred,
+            false => TriggerPriceType::Last,
+        }
+    }
+
+    /// Capabilities required by strategy which aren't supported by exchange
+    pub fn missing_capabilities(&self, required: &[ExchangeCapability]) -> Vec<ExchangeCapability> {
+        self.features.missing_capabilities(required)
+    }
+
     pub fn get_symbol(&self, currency_pair: CurrencyPair) -> Result<Arc<Symbol>> {
         self.symbols
             .get(&currency_pair)
@@ -807,6 +1120,25 @@ impl Exchange {
             .map(|pair| pair.value().clone())
     }
 
+    /// Reference price for exchange price filters: mid price of order book top
+    pub fn reference_price(&self, currency_pair: CurrencyPair) -> Option<Price> {
+        let order_book_top = self.order_book_top.get(&currency_pair)?;
+        match (&order_book_top.bid, &order_book_top.ask) {
+            (Some(bid), Some(ask)) => Some((bid.price + ask.price) / dec!(2)),
+            (Some(level), None) | (None, Some(level)) => Some(level.price),
+            (None, None) => None,
+        }
+    }
+
+    /// Range of order prices which won't be rejected by exchange price filters
+    pub fn allowed_price_band(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<(Option<Price>, Option<Price>)> {
+        let symbol = self.get_symbol(currency_pair)?;
+        Ok(symbol.allowed_price_band(self.reference_price(currency_pair)))
+    }
+
     pub fn update_server_time_latency(&self, latency: i64) {
         self.server_time_latency.store(latency, Ordering::SeqCst)
     }
@@ -852,7 +1184,7 @@ pub fn get_specific_currency_pair_for_tests(
 }
 
 fn print_warn(
-    retry_attempt: i32,
+    retry_attempt: u32,
     fn_name: &str,
     exchange_account_id: &ExchangeAccountId,
     error: impl Debug,
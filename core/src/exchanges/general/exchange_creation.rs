@@ -1,22 +1,36 @@
 use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
 use crate::database::events::recorder::EventRecorder;
-use crate::exchanges::exchange_blocker::ExchangeBlocker;
+use crate::exchanges::block_reasons::EXCHANGE_INITIALIZATION;
+use crate::exchanges::exchange_blocker::{BlockType, ExchangeBlocker};
+use crate::infrastructure::spawn_future;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::lifecycle::launcher::EngineBuildConfig;
-use crate::settings::ExchangeSettings;
+use crate::settings::{
+    CurrencyPairSetting, ExchangeInitializationSettings, ExchangeSettings,
+    OperationPoliciesSettings,
+};
 use crate::{
     exchanges::{
         general::exchange::Exchange,
+        timeouts::requests_state_file::{restore_requests, start_saving_requests},
         timeouts::requests_timeout_manager_factory::RequestsTimeoutManagerFactory,
+        timeouts::shared_rate_budget::start_shared_rate_budget,
         timeouts::timeout_manager::TimeoutManager,
     },
     settings::CoreSettings,
 };
+use anyhow::{Context, Result};
+use futures::future::{ready, BoxFuture};
+use futures::FutureExt;
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::exchanges::commission::Commission;
+use mmb_domain::market::ExchangeAccountId;
 use mmb_domain::order::pool::OrdersPool;
+use mmb_utils::infrastructure::SpawnFutureFlags;
 use tokio::sync::broadcast;
+use tokio::time::{sleep, timeout};
 
 pub fn create_timeout_manager(
     core_settings: &CoreSettings,
@@ -36,14 +50,31 @@ pub fn create_timeout_manager(
                 exchange_account_id,
             );
 
+            if let Some(shared_rate_budget) = &exchange_settings.shared_rate_budget {
+                start_shared_rate_budget(
+                    exchange_account_id,
+                    &exchange_settings.api_key,
+                    shared_rate_budget.clone(),
+                    request_timeout_manager.clone(),
+                );
+            }
+
             (exchange_account_id, request_timeout_manager)
         })
         .collect();
 
-    TimeoutManager::new(request_timeout_managers)
+    let timeout_manager = TimeoutManager::new(request_timeout_managers);
+
+    if let Some(state_settings) = &core_settings.timeout_manager_state {
+        restore_requests(&timeout_manager, &state_settings.path);
+        start_saving_requests(timeout_manager.clone(), state_settings.clone());
+    }
+
+    timeout_manager
 }
 
-pub async fn create_exchange(
+#[allow(clippy::too_many_arguments)]
+pub fn create_exchange(
     user_settings: &ExchangeSettings,
     build_settings: &EngineBuildConfig,
     events_channel: broadcast::Sender<ExchangeEvent>,
@@ -51,6 +82,7 @@ pub async fn create_exchange(
     timeout_manager: Arc<TimeoutManager>,
     exchange_blocker: Weak<ExchangeBlocker>,
     event_recorder: Arc<EventRecorder>,
+    operation_policies: OperationPoliciesSettings,
 ) -> Arc<Exchange> {
     let exchange_account_id = user_settings.exchange_account_id;
     let exchange_client_builder =
@@ -65,7 +97,7 @@ pub async fn create_exchange(
         orders.clone(),
     );
 
-    let exchange = Exchange::new(
+    Exchange::new(
         exchange_account_id,
         exchange_client.client,
         orders,
@@ -77,10 +109,254 @@ pub async fn create_exchange(
         exchange_blocker,
         Commission::default(),
         event_recorder,
-    );
+        operation_policies,
+    )
+}
 
-    exchange.build_symbols(&user_settings.currency_pairs).await;
+async fn initialize_exchange(
+    exchange: Arc<Exchange>,
+    currency_pairs: Option<Vec<CurrencyPairSetting>>,
+) -> Result<()> {
+    exchange.try_build_symbols(&currency_pairs).await?;
     exchange.exchange_client.initialized(exchange.clone()).await;
 
-    exchange
+    Ok(())
+}
+
+/// Result of waiting for exchange initialization during launch
+enum LaunchInitialization {
+    Initialized,
+    /// Initialization of optional exchange which should be continued in background
+    Postponed(BoxFuture<'static, Result<()>>),
+}
+
+/// Initialize exchange (request symbols, etc.) waiting for it no longer than
+/// `init_settings.time_limit()`. Required exchange that isn't initialized stops the launch.
+/// Optional exchange is blocked and its initialization is continued in background until success
+pub async fn initialize_exchange_with_timeout(
+    exchange: Arc<Exchange>,
+    user_settings: &ExchangeSettings,
+    init_settings: &ExchangeInitializationSettings,
+    exchange_blocker: Weak<ExchangeBlocker>,
+) {
+    let exchange_account_id = exchange.exchange_account_id;
+    log::info!("Initialization of exchange {exchange_account_id} started");
+
+    let started_at = Instant::now();
+    let initialization =
+        initialize_exchange(exchange.clone(), user_settings.currency_pairs.clone()).boxed();
+
+    let pending_initialization = match wait_initialization(
+        exchange_account_id,
+        initialization,
+        user_settings.is_optional,
+        init_settings.time_limit(user_settings.is_optional),
+    )
+    .await
+    {
+        LaunchInitialization::Initialized => {
+            log::info!(
+                "Exchange {exchange_account_id} initialized in {:?}",
+                started_at.elapsed()
+            );
+            return;
+        }
+        LaunchInitialization::Postponed(initialization) => initialization,
+    };
+
+    log::warn!(
+        "Optional exchange {exchange_account_id} wasn't initialized in {:?}. Launch continues while it is blocked and initialized in background",
+        started_at.elapsed()
+    );
+
+    exchange_blocker
+        .upgrade()
+        .expect("ExchangeBlocker should exist during launch")
+        .block(
+            exchange_account_id,
+            EXCHANGE_INITIALIZATION,
+            BlockType::Manual,
+        );
+
+    let _ = spawn_future(
+        &format!("Background initialization of exchange {exchange_account_id}"),
+        SpawnFutureFlags::STOP_BY_TOKEN,
+        initialize_in_background(
+            exchange,
+            user_settings.currency_pairs.clone(),
+            pending_initialization,
+            init_settings.retry_delay(),
+            exchange_blocker,
+        ),
+    );
+}
+
+async fn initialize_in_background(
+    exchange: Arc<Exchange>,
+    currency_pairs: Option<Vec<CurrencyPairSetting>>,
+    initialization: BoxFuture<'static, Result<()>>,
+    retry_delay: Duration,
+    exchange_blocker: Weak<ExchangeBlocker>,
+) -> Result<()> {
+    let exchange_account_id = exchange.exchange_account_id;
+    retry_initialization(exchange_account_id, initialization, retry_delay, || {
+        initialize_exchange(exchange.clone(), currency_pairs.clone()).boxed()
+    })
+    .await;
+
+    log::info!("Exchange {exchange_account_id} initialized in background");
+
+    if exchange.is_websocket_connected() {
+        // websocket was connected before symbols were known, so subscriptions should be renewed
+        exchange
+            .reconnect_ws()
+            .await
+            .with_context(|| format!("Unable to reconnect websocket of {exchange_account_id}"))?;
+    }
+
+    if let Some(exchange_blocker) = exchange_blocker.upgrade() {
+        exchange_blocker.unblock(exchange_account_id, EXCHANGE_INITIALIZATION);
+    }
+
+    Ok(())
+}
+
+async fn wait_initialization(
+    exchange_account_id: ExchangeAccountId,
+    mut initialization: BoxFuture<'static, Result<()>>,
+    is_optional: bool,
+    time_limit: Option<Duration>,
+) -> LaunchInitialization {
+    let result = match time_limit {
+        Some(time_limit) => timeout(time_limit, &mut initialization).await,
+        None => Ok((&mut initialization).await),
+    };
+
+    match result {
+        Ok(Ok(())) => LaunchInitialization::Initialized,
+        Ok(Err(error)) if is_optional => LaunchInitialization::Postponed(ready(Err(error)).boxed()),
+        Ok(Err(error)) => panic!("Unable to initialize exchange {exchange_account_id}: {error:?}"),
+        Err(_) if is_optional => LaunchInitialization::Postponed(initialization),
+        Err(_) => panic!("Exchange {exchange_account_id} wasn't initialized in {time_limit:?}"),
+    }
+}
+
+async fn retry_initialization(
+    exchange_account_id: ExchangeAccountId,
+    mut initialization: BoxFuture<'static, Result<()>>,
+    retry_delay: Duration,
+    mut next_attempt: impl FnMut() -> BoxFuture<'static, Result<()>>,
+) {
+    while let Err(error) = initialization.await {
+        log::warn!("Initialization of exchange {exchange_account_id} failed, retry in {retry_delay:?}: {error:?}");
+        sleep(retry_delay).await;
+        initialization = next_attempt();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use futures::future::pending;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn exchange_account_id() -> ExchangeAccountId {
+        ExchangeAccountId::new("Binance", 0)
+    }
+
+    fn failed_initialization() -> BoxFuture<'static, Result<()>> {
+        async { bail!("Symbols aren't received") }.boxed()
+    }
+
+    #[test]
+    fn only_optional_exchange_has_time_limit_by_default() {
+        let settings = ExchangeInitializationSettings::default();
+        assert_eq!(settings.time_limit(true), Some(Duration::from_secs(120)));
+        assert_eq!(settings.time_limit(false), None);
+
+        let settings = ExchangeInitializationSettings {
+            required_timeout_secs: Some(600),
+            ..Default::default()
+        };
+        assert_eq!(settings.time_limit(false), Some(Duration::from_secs(600)));
+    }
+
+    #[tokio::test]
+    async fn optional_exchange_initialization_is_postponed_on_timeout() {
+        let initialization = pending().boxed();
+
+        let result = wait_initialization(
+            exchange_account_id(),
+            initialization,
+            true,
+            Some(Duration::from_millis(10)),
+        )
+        .await;
+
+        assert!(matches!(result, LaunchInitialization::Postponed(_)));
+    }
+
+    #[tokio::test]
+    async fn optional_exchange_initialization_is_postponed_on_error() {
+        let result = wait_initialization(
+            exchange_account_id(),
+            failed_initialization(),
+            true,
+            Some(Duration::from_secs(1)),
+        )
+        .await;
+
+        let LaunchInitialization::Postponed(initialization) = result else {
+            panic!("Initialization should be postponed");
+        };
+        assert!(initialization.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn required_exchange_is_waited_without_time_limit() {
+        let initialization = async {
+            sleep(Duration::from_millis(50)).await;
+            Ok(())
+        }
+        .boxed();
+
+        let result = wait_initialization(exchange_account_id(), initialization, false, None).await;
+
+        assert!(matches!(result, LaunchInitialization::Initialized));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "wasn't initialized")]
+    async fn required_exchange_stops_launch_on_timeout() {
+        let _ = wait_initialization(
+            exchange_account_id(),
+            pending().boxed(),
+            false,
+            Some(Duration::from_millis(10)),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn background_initialization_is_retried_until_success() {
+        let attempts = Arc::new(AtomicUsize::new(1));
+
+        retry_initialization(
+            exchange_account_id(),
+            failed_initialization(),
+            Duration::from_millis(1),
+            || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                if attempt < 3 {
+                    failed_initialization()
+                } else {
+                    ready(Ok(())).boxed()
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
 }
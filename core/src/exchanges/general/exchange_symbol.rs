@@ -1,3 +1,4 @@
+use anyhow::{bail, Result};
 use dashmap::DashMap;
 use itertools::Itertools;
 use mmb_domain::market::CurrencyCode;
@@ -5,16 +6,27 @@ use mmb_utils::infrastructure::WithExpect;
 use rust_decimal_macros::dec;
 use std::collections::HashSet;
 use std::sync::Arc;
+use tokio::time::{sleep, timeout};
 
 use crate::settings::CurrencyPairSetting;
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::market::{CurrencyId, ExchangeAccountId};
+use mmb_domain::market::{CurrencyAliases, CurrencyId, ExchangeAccountId};
 
 use super::exchange::Exchange;
 
 impl Exchange {
     pub async fn build_symbols(&self, currency_pair_settings: &Option<Vec<CurrencyPairSetting>>) {
-        let exchange_symbols = &self.request_symbols_with_retries().await;
+        self.try_build_symbols(currency_pair_settings)
+            .await
+            .unwrap_or_else(|error| panic!("{error:?}"));
+    }
+
+    /// Same as `build_symbols` but returns error instead of panic if symbols can't be requested from exchange
+    pub async fn try_build_symbols(
+        &self,
+        currency_pair_settings: &Option<Vec<CurrencyPairSetting>>,
+    ) -> Result<()> {
+        let exchange_symbols = &self.request_symbols_with_retries().await?;
 
         let supported_currencies = get_supported_currencies(exchange_symbols);
         self.setup_supported_currencies(supported_currencies);
@@ -34,27 +46,35 @@ impl Exchange {
         self.setup_symbols(get_symbols(
             currency_pairs,
             exchange_symbols,
+            &self.exchange_client.get_currency_aliases(),
             self.exchange_account_id,
         ));
+
+        Ok(())
     }
 
-    async fn request_symbols_with_retries(&self) -> Vec<Arc<Symbol>> {
-        const MAX_RETRIES: u8 = 5;
-        for retry in 0..=MAX_RETRIES {
-            match self.exchange_client.build_all_symbols().await {
-                Ok(result_symbols) => return result_symbols,
-                Err(error) => {
-                    let error_message = format!(
+    async fn request_symbols_with_retries(&self) -> Result<Vec<Arc<Symbol>>> {
+        let policy = self.operation_policies.symbols_fetch;
+        for attempt in 1..=policy.max_attempts {
+            let error_message =
+                match timeout(policy.timeout(), self.exchange_client.build_all_symbols()).await {
+                    Ok(Ok(result_symbols)) => return Ok(result_symbols),
+                    Ok(Err(error)) => format!(
                         "Unable to get symbol for {}: {error:?}",
                         self.exchange_account_id
-                    );
-
-                    if retry < MAX_RETRIES {
-                        log::warn!("{error_message}");
-                    } else {
-                        panic!("{error_message}");
-                    }
-                }
+                    ),
+                    Err(_) => format!(
+                        "Unable to get symbol for {}: timeout {:?} is over",
+                        self.exchange_account_id,
+                        policy.timeout()
+                    ),
+                };
+
+            if attempt < policy.max_attempts {
+                log::warn!("{error_message}");
+                sleep(policy.retry_delay()).await;
+            } else {
+                bail!("{error_message}");
             }
         }
 
@@ -107,17 +127,21 @@ fn get_supported_currencies(symbols: &[Arc<Symbol>]) -> DashMap<CurrencyCode, Cu
 fn get_symbols(
     currency_pairs: &[CurrencyPairSetting],
     exchange_symbols: &[Arc<Symbol>],
+    currency_aliases: &CurrencyAliases,
     exchange_account_id: ExchangeAccountId,
 ) -> Vec<Arc<Symbol>> {
     currency_pairs
         .iter()
-        .filter_map(|x| get_matched_currency_pair(x, exchange_symbols, exchange_account_id))
+        .filter_map(|x| {
+            get_matched_currency_pair(x, exchange_symbols, currency_aliases, exchange_account_id)
+        })
         .collect()
 }
 
 fn get_matched_currency_pair(
     currency_pair_setting: &CurrencyPairSetting,
     exchange_symbols: &[Arc<Symbol>],
+    currency_aliases: &CurrencyAliases,
     exchange_account_id: ExchangeAccountId,
 ) -> Option<Arc<Symbol>> {
     // currency pair symbol and currency pairs from settings should match 1 to 1
@@ -128,7 +152,9 @@ fn get_matched_currency_pair(
                 symbol.currency_pair().as_str() == currency_pair
             }
             CurrencyPairSetting::Ordinary { base, quote } => {
-                symbol.base_currency_code == *base && symbol.quote_currency_code == *quote
+                // symbols are built with unified currency codes, so settings should be unified too
+                symbol.base_currency_code == currency_aliases.unify(*base)
+                    && symbol.quote_currency_code == currency_aliases.unify(*quote)
             }
         })
         .take(2)
@@ -1,4 +1,5 @@
 use mmb_domain::events::AllowedEventSourceType;
+use mmb_domain::order::snapshot::TriggerPriceType;
 
 #[derive(Debug)]
 pub enum OpenOrdersType {
@@ -42,7 +43,7 @@ pub struct WebSocketOptions {
     pub execution_notification: bool,
     /// Is order cancellation result able to receive
     pub cancellation_notification: bool,
-    // TODO Not used, is it redundant?
+    /// Exchange answers websocket pings with pongs, so quiet connection is still alive
     pub supports_ping_pong: bool,
     // TODO Used in exchange inner not in core, is it redundant?
     pub supports_subscription_response: bool,
@@ -83,9 +84,20 @@ pub struct OrderFeatures {
     /// Stop loss orders are supported
     // TODO Flag is not used in core, is it redundant?
     pub supports_stop_loss_order: bool,
+    /// Conditional orders can be triggered by mark price
+    pub supports_mark_price_trigger: bool,
+    /// Conditional orders can be triggered by index price
+    pub supports_index_price_trigger: bool,
+    /// Active orders can be amended in place, otherwise amendment is emulated
+    /// by cancellation and creation of new order
+    pub supports_amend_order: bool,
+    /// Iceberg orders with display amount are supported, otherwise they are emulated
+    /// by sequential orders of display amount
+    pub supports_iceberg_order: bool,
 }
 
 impl OrderFeatures {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         maker_only: bool,
         supports_get_order_info_by_client_order_id: bool,
@@ -94,6 +106,10 @@ impl OrderFeatures {
         order_was_completed_error_for_cancellation: bool,
         supports_already_cancelled_order: bool,
         supports_stop_loss_order: bool,
+        supports_mark_price_trigger: bool,
+        supports_index_price_trigger: bool,
+        supports_amend_order: bool,
+        supports_iceberg_order: bool,
     ) -> Self {
         Self {
             maker_only,
@@ -103,10 +119,36 @@ impl OrderFeatures {
             order_was_completed_error_for_cancellation,
             supports_already_cancelled_order,
             supports_stop_loss_order,
+            supports_mark_price_trigger,
+            supports_index_price_trigger,
+            supports_amend_order,
+            supports_iceberg_order,
+        }
+    }
+
+    /// Last price trigger is supported by all exchanges with conditional orders
+    pub fn supports_trigger_price_type(&self, trigger_price_type: TriggerPriceType) -> bool {
+        match trigger_price_type {
+            TriggerPriceType::Last => true,
+            TriggerPriceType::Mark => self.supports_mark_price_trigger,
+            TriggerPriceType::Index => self.supports_index_price_trigger,
         }
     }
 }
 
+/// Capability of exchange which strategy can require to work correctly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExchangeCapability {
+    /// Maker only (post-only) orders
+    MakerOnly,
+    /// Stop-loss orders
+    StopOrders,
+    /// Amendment of active orders in place
+    AmendOrder,
+    /// Fills are received via websocket
+    WebSocketFills,
+}
+
 #[derive(Default)]
 pub struct OrderTradeOption {
     /// Get trades result contain timestamp
@@ -120,6 +162,9 @@ pub struct OrderTradeOption {
     pub supports_tick_direction: bool,
     // TODO Repeats supports_trade_time functional and used only in tests. Is it redundant?
     pub supports_my_trades_from_time: bool,
+    /// Ids of trades feed are sequential numbers, so missed trades can be detected by id gaps
+    /// and backfilled with `ExchangeClient::get_trades_from_id`
+    pub supports_trades_gap_detection: bool,
 }
 
 pub struct ExchangeFeatures {
@@ -171,4 +216,63 @@ impl ExchangeFeatures {
             allowed_cancel_event_source_type,
         }
     }
+
+    pub fn supports(&self, capability: ExchangeCapability) -> bool {
+        match capability {
+            ExchangeCapability::MakerOnly => self.order_features.maker_only,
+            ExchangeCapability::StopOrders => self.order_features.supports_stop_loss_order,
+            ExchangeCapability::AmendOrder => self.order_features.supports_amend_order,
+            ExchangeCapability::WebSocketFills => self.websocket_options.execution_notification,
+        }
+    }
+
+    /// Required capabilities which aren't supported by exchange
+    pub fn missing_capabilities(&self, required: &[ExchangeCapability]) -> Vec<ExchangeCapability> {
+        required
+            .iter()
+            .copied()
+            .filter(|&capability| !self.supports(capability))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_capabilities_are_listed() {
+        let features = ExchangeFeatures::new(
+            OpenOrdersType::AllCurrencyPair,
+            RestFillsFeatures::default(),
+            OrderFeatures {
+                maker_only: true,
+                ..OrderFeatures::default()
+            },
+            OrderTradeOption::default(),
+            WebSocketOptions {
+                execution_notification: true,
+                ..WebSocketOptions::default()
+            },
+            false,
+            AllowedEventSourceType::default(),
+            AllowedEventSourceType::default(),
+            AllowedEventSourceType::default(),
+        );
+
+        let missing = features.missing_capabilities(&[
+            ExchangeCapability::MakerOnly,
+            ExchangeCapability::StopOrders,
+            ExchangeCapability::AmendOrder,
+            ExchangeCapability::WebSocketFills,
+        ]);
+
+        assert_eq!(
+            missing,
+            vec![
+                ExchangeCapability::StopOrders,
+                ExchangeCapability::AmendOrder
+            ]
+        );
+    }
 }
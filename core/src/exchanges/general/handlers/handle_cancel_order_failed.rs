@@ -1,5 +1,5 @@
 use crate::exchanges::general::exchange::Exchange;
-use crate::exchanges::general::handlers::should_ignore_event;
+use crate::exchanges::general::handlers::should_ignore_failure_event;
 use crate::exchanges::traits::ExchangeError;
 use chrono::Utc;
 use function_name::named;
@@ -28,7 +28,7 @@ impl Exchange {
         );
 
         let allowed_cancel_event_source_type = self.features.allowed_cancel_event_source_type;
-        if should_ignore_event(allowed_cancel_event_source_type, event_source_type) {
+        if should_ignore_failure_event(allowed_cancel_event_source_type, event_source_type) {
             return;
         }
 
@@ -0,0 +1,45 @@
+use mmb_domain::events::{CashFlowEvent, ExchangeEvent};
+
+use crate::exchanges::general::exchange::Exchange;
+
+impl Exchange {
+    pub fn handle_cash_flow(&self, cash_flow: CashFlowEvent) {
+        if cash_flow.amount.is_zero() {
+            return;
+        }
+
+        if !self.symbols.contains_key(&cash_flow.currency_pair) {
+            log::warn!(
+                "Unknown currency pair {} for {:?} cash flow on {}",
+                cash_flow.currency_pair,
+                cash_flow.kind,
+                self.exchange_account_id
+            );
+            return;
+        }
+
+        // Cash flow is included into PnL here rather than by strategies,
+        // so it is accounted once even if no one or several strategies trade the market
+        let balance_manager = self
+            .balance_manager
+            .lock()
+            .as_ref()
+            .and_then(|x| x.upgrade());
+        match balance_manager {
+            Some(balance_manager) => balance_manager.lock().cash_flow_received(&cash_flow),
+            None => log::warn!(
+                "BalanceManager isn't available to include {:?} cash flow on {} into PnL",
+                cash_flow.kind,
+                self.exchange_account_id
+            ),
+        }
+
+        self.events_channel
+            .send(ExchangeEvent::CashFlow(cash_flow.clone()))
+            .expect("Unable to send cash flow event. Probably receiver is already dropped");
+
+        self.event_recorder
+            .save(cash_flow)
+            .expect("Failure save cash_flow_event");
+    }
+}
@@ -9,7 +9,7 @@ use mmb_domain::exchanges::commission::Percent;
 use mmb_domain::exchanges::symbol::{Round, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
 use mmb_domain::order::event::OrderEventType;
-use mmb_domain::order::fill::{OrderFill, OrderFillType};
+use mmb_domain::order::fill::{FillBookContext, OrderFill, OrderFillType};
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{Amount, OrderOptions, Price};
 use mmb_domain::order::snapshot::{ClientOrderFillId, OrderRole};
@@ -480,6 +480,14 @@ impl Exchange {
         }
     }
 
+    fn get_fill_book_context(&self, currency_pair: CurrencyPair) -> Option<FillBookContext> {
+        let order_book_top = self.order_book_top.get(&currency_pair)?;
+        Some(FillBookContext::new(
+            order_book_top.bid.as_ref().map(|x| x.price),
+            order_book_top.ask.as_ref().map(|x| x.price),
+        ))
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn add_fill(
         &self,
@@ -507,8 +515,10 @@ impl Exchange {
         let expected_converted_commission_amount =
             last_fill_amount_in_converted_commission_currency_code * expected_commission_rate;
 
+        // Referral reward is a share of paid fee, so there is no reward for maker rebate
         let referral_reward = self.commission.get_commission(order_role).referral_reward;
-        let referral_reward_amount = commission_amount * referral_reward.percent_to_rate();
+        let referral_reward_amount =
+            commission_amount.max(dec!(0)) * referral_reward.percent_to_rate();
 
         let rounded_fill_price = symbol.price_round(last_fill_price, Round::ToNearest);
 
@@ -516,7 +526,7 @@ impl Exchange {
         let side = order_ref.side();
         let exchange_order_id = order_ref.exchange_order_id();
 
-        let order_fill = OrderFill::new(
+        let mut order_fill = OrderFill::new(
             Uuid::new_v4(),
             Some(ClientOrderFillId::unique_id()),
             Utc::now(),
@@ -536,6 +546,8 @@ impl Exchange {
             None,
             Some(side),
         );
+        order_fill.set_book_context(self.get_fill_book_context(order_ref.currency_pair()));
+        order_fill.set_intent_id(order_ref.header().intent_id);
 
         log::info!(
             "Adding a fill {} {trade_id:?} {client_order_id} {exchange_order_id:?} {order_fill:?}",
@@ -746,11 +758,11 @@ mod test {
     use mmb_domain::market::CurrencyCode;
     use mmb_domain::order::fill::OrderFill;
     use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::{IntentId, OrderType, UserOrder};
     use mmb_domain::order::snapshot::{
         OrderFillRole, OrderFills, OrderHeader, OrderSimpleProps, OrderStatusHistory,
         SystemInternalOrderProps,
     };
-    use mmb_domain::order::snapshot::{OrderType, UserOrder};
     use serde_json::json;
     use uuid::Uuid;
 
@@ -2070,6 +2082,52 @@ mod test {
         assert_eq!(fill.commission_amount(), right_value);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn fill_is_linked_to_intent_of_order() {
+        let (exchange, _event_receiver) = get_test_exchange(false);
+
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let mut fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(trade_id_from_str("test_trade_id")),
+            client_order_id: None,
+            exchange_order_id: ExchangeOrderId::new("".into()),
+            fill_price: dec!(0.8),
+            fill_amount: FillAmount::Incremental {
+                fill_amount: dec!(5),
+                total_filled_amount: None,
+            },
+            order_role: Some(OrderRole::Taker),
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: None,
+        };
+
+        let intent_id = IntentId::generate();
+        let mut order = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(dec!(0.8)),
+            Some(OrderRole::Taker),
+            exchange.exchange_account_id,
+            currency_pair,
+            dec!(12),
+            OrderSide::Buy,
+            None,
+            "FromTest",
+        );
+        order.header.intent_id = Some(intent_id);
+
+        let order_ref = OrdersPool::new().add_snapshot_initial(&order);
+        exchange.create_and_add_order_fill(&mut fill_event, &order_ref);
+
+        let (fills, _) = order_ref.get_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].intent_id(), Some(intent_id));
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn take_roll_from_order_if_not_specified() {
         let (exchange, _event_receiver) = get_test_exchange(false);
@@ -2689,6 +2747,28 @@ mod test {
 
             Ok(())
         }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn via_negative_commission_rate() -> Result<()> {
+            let (exchange, _event_receiver) = get_test_exchange(true);
+
+            let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+            let symbol = exchange.get_symbol(currency_pair)?;
+            let commission_amount = Exchange::get_commission_amount(
+                None,
+                None,
+                dec!(-0.0001),
+                dec!(5),
+                dec!(0.8),
+                CurrencyCode::new("PHB"),
+                &symbol,
+            );
+
+            let right_value = dec!(-0.01) / dec!(100) * dec!(5) / dec!(0.8);
+            assert_eq!(commission_amount, right_value);
+
+            Ok(())
+        }
     }
 
     mod add_fill {
@@ -2874,6 +2954,58 @@ mod test {
 
             Ok(())
         }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn no_referral_reward_for_maker_rebate() -> Result<()> {
+            let (exchange, _event_receiver) = get_test_exchange(false);
+
+            let client_order_id = ClientOrderId::unique_id();
+            let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+            let order_side = OrderSide::Buy;
+            let order_role = OrderRole::Maker;
+            let order_amount = dec!(12);
+            let fill_price = dec!(0.8);
+
+            let order_ref = create_order_ref(
+                &client_order_id,
+                Some(order_role),
+                exchange.exchange_account_id,
+                currency_pair,
+                fill_price,
+                order_amount,
+                order_side,
+            );
+
+            let trade_id = Some(trade_id_from_str("test trade_id"));
+            let symbol = exchange.get_symbol(currency_pair)?;
+            let converted_commission_currency_code =
+                symbol.get_commission_currency_code(order_side);
+            let commission_amount = dec!(-0.0005);
+
+            exchange.add_fill(
+                &trade_id,
+                true,
+                OrderFillType::UserTrade,
+                &symbol,
+                &order_ref,
+                converted_commission_currency_code,
+                dec!(5),
+                dec!(0.8),
+                dec!(4.0),
+                dec!(0.001),
+                commission_amount,
+                order_role,
+                CurrencyCode::new("PHB"),
+                commission_amount,
+            );
+
+            let fill = order_ref.get_fills().0.last().cloned().expect("in test");
+
+            assert_eq!(fill.commission_amount(), commission_amount);
+            assert_eq!(fill.referral_reward_amount(), dec!(0));
+
+            Ok(())
+        }
     }
 
     mod check_fill_amounts_conformity {
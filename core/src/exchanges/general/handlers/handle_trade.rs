@@ -1,11 +1,29 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use mmb_database::impl_event;
 use mmb_domain::events::{ExchangeEvent, Trade, TradesEvent};
 use mmb_domain::market::CurrencyPair;
-use mmb_domain::market::MarketId;
+use mmb_domain::market::{ExchangeAccountId, MarketId};
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use serde::Serialize;
 
 use crate::exchanges::{general::exchange::Exchange, timeouts::timeout_manager};
+use crate::infrastructure::spawn_future;
+
+#[derive(Debug, Serialize)]
+struct TradesGap {
+    exchange_account_id: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    first_missed_trade_id: u64,
+    last_missed_trade_id: u64,
+}
+
+impl_event!(TradesGap, "trades_gaps");
 
 impl Exchange {
-    pub fn handle_trade(&self, currency_pair: CurrencyPair, trade: Trade) {
+    pub fn handle_trade(self: &Arc<Self>, currency_pair: CurrencyPair, trade: Trade) {
         if !self.exchange_client.get_settings().subscribe_to_market_data {
             return;
         }
@@ -30,6 +48,10 @@ impl Exchange {
             );
         }
 
+        if self.features.trade_option.supports_trades_gap_detection {
+            self.detect_trades_gap(market_id, currency_pair, &trades_event.trades);
+        }
+
         if self.exchange_client.get_settings().request_trades {
             let should_add_event = if let Some(last_trade) = self.last_trades.get_mut(&market_id) {
                 let trades = &mut trades_event.trades;
@@ -53,6 +75,10 @@ impl Exchange {
             }
         }
 
+        self.send_trades_event(trades_event);
+    }
+
+    fn send_trades_event(&self, trades_event: TradesEvent) {
         self.events_channel
             .send(ExchangeEvent::Trades(trades_event.clone()))
             .expect("Unable to send trades event. Probably receiver is already dropped");
@@ -61,4 +87,257 @@ impl Exchange {
             .save(trades_event)
             .expect("Failure save trades_event");
     }
+
+    fn detect_trades_gap(
+        self: &Arc<Self>,
+        market_id: MarketId,
+        currency_pair: CurrencyPair,
+        trades: &[Trade],
+    ) {
+        let mut trade_ids = Vec::with_capacity(trades.len());
+        for trade in trades {
+            match trade.trade_id.number() {
+                Some(trade_id) => trade_ids.push(trade_id),
+                None => {
+                    log::warn!(
+                        "Unable to detect trades gap by not number trade id {} on {market_id}",
+                        trade.trade_id
+                    );
+                    return;
+                }
+            }
+        }
+
+        let last_trade_id = self.last_tape_trade_ids.get(&market_id).map(|x| *x);
+        for missed_trade_ids in missed_trade_ids_in_batch(last_trade_id, &trade_ids) {
+            let gap = TradesGap {
+                exchange_account_id: self.exchange_account_id,
+                currency_pair,
+                first_missed_trade_id: *missed_trade_ids.start(),
+                last_missed_trade_id: *missed_trade_ids.end(),
+            };
+            log::warn!("Detected gap in trades feed: {gap:?}");
+
+            self.backfill_trades(currency_pair, missed_trade_ids);
+
+            if let Err(err) = self.event_recorder.save(gap) {
+                log::error!("Failure save trades gap: {err:?}");
+            }
+        }
+
+        // already received trades don't move last trade id back
+        if let Some(&newest_trade_id) = trade_ids.iter().max() {
+            if last_trade_id.map_or(true, |x| newest_trade_id > x) {
+                self.last_tape_trade_ids.insert(market_id, newest_trade_id);
+            }
+        }
+    }
+
+    fn backfill_trades(
+        self: &Arc<Self>,
+        currency_pair: CurrencyPair,
+        missed_trade_ids: RangeInclusive<u64>,
+    ) {
+        let first_missed_trade_id = *missed_trade_ids.start();
+        let last_missed_trade_id = *missed_trade_ids.end();
+        let exchange_account_id = self.exchange_account_id;
+        let action = format!("Backfill trades {first_missed_trade_id}..={last_missed_trade_id} for {exchange_account_id} {currency_pair}");
+        let self_weak = Arc::downgrade(self);
+        let future = async move {
+            let exchange = match self_weak.upgrade() {
+                Some(exchange) => exchange,
+                None => return Ok(()),
+            };
+
+            let trades = match exchange
+                .exchange_client
+                .get_trades_from_id(currency_pair, first_missed_trade_id)
+                .await
+            {
+                None => {
+                    log::warn!("Trades backfill isn't supported on {exchange_account_id}");
+                    return Ok(());
+                }
+                Some(trades) => trades?,
+            };
+
+            let trades = select_missed_trades(trades, &missed_trade_ids);
+
+            log::info!(
+                "Backfilled {} trades of {} missed for {exchange_account_id} {currency_pair}",
+                trades.len(),
+                last_missed_trade_id - first_missed_trade_id + 1
+            );
+
+            if trades.is_empty() {
+                return Ok(());
+            }
+
+            exchange.send_trades_event(TradesEvent {
+                exchange_account_id,
+                currency_pair,
+                trades,
+                receipt_time: timeout_manager::now(),
+            });
+
+            Ok(())
+        };
+        spawn_future(&action, SpawnFutureFlags::STOP_BY_TOKEN, future);
+    }
+}
+
+/// Ids of trades missed between last received trade and the new one
+fn missed_trade_ids(last_trade_id: u64, trade_id: u64) -> Option<RangeInclusive<u64>> {
+    (trade_id > last_trade_id + 1).then(|| last_trade_id + 1..=trade_id - 1)
+}
+
+/// Ids of trades missed before and between trades of batch. Exchanges send batches ordered
+/// from oldest to newest trade or vice versa, so ids are walked in ascending order
+fn missed_trade_ids_in_batch(
+    last_trade_id: Option<u64>,
+    trade_ids: &[u64],
+) -> Vec<RangeInclusive<u64>> {
+    let mut missed = Vec::new();
+    let mut last_trade_id = last_trade_id;
+    for trade_id in trade_ids.iter().copied().sorted_unstable() {
+        if let Some(last_trade_id) = last_trade_id {
+            if trade_id <= last_trade_id {
+                // trade was already received
+                continue;
+            }
+
+            missed.extend(missed_trade_ids(last_trade_id, trade_id));
+        }
+
+        last_trade_id = Some(trade_id);
+    }
+
+    missed
+}
+
+fn select_missed_trades(trades: Vec<Trade>, missed_trade_ids: &RangeInclusive<u64>) -> Vec<Trade> {
+    trades
+        .into_iter()
+        .filter(|x| {
+            x.trade_id
+                .number()
+                .map_or(false, |trade_id| missed_trade_ids.contains(&trade_id))
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use crate::infrastructure::init_lifetime_manager;
+    use mmb_domain::events::TradeId;
+    use mmb_domain::order::snapshot::OrderSide;
+    use rust_decimal_macros::dec;
+
+    fn trade(trade_id: TradeId) -> Trade {
+        Trade {
+            trade_id,
+            price: dec!(100),
+            quantity: dec!(1),
+            side: OrderSide::Buy,
+            transaction_time: timeout_manager::now(),
+        }
+    }
+
+    fn trades(ids: &[u64]) -> Vec<Trade> {
+        ids.iter().map(|&id| trade(TradeId::Number(id))).collect()
+    }
+
+    #[test]
+    fn missed_trade_ids_between_sequential_trades() {
+        assert_eq!(missed_trade_ids(10, 11), None);
+        assert_eq!(missed_trade_ids(10, 12), Some(11..=11));
+        assert_eq!(missed_trade_ids(10, 15), Some(11..=14));
+    }
+
+    #[test]
+    fn missed_trade_ids_in_ascending_and_descending_batches() {
+        assert_eq!(missed_trade_ids_in_batch(None, &[5, 6, 8]), vec![7..=7]);
+        assert_eq!(
+            missed_trade_ids_in_batch(Some(2), &[5, 6, 8]),
+            vec![3..=4, 7..=7]
+        );
+        assert_eq!(
+            missed_trade_ids_in_batch(Some(2), &[8, 6, 5]),
+            vec![3..=4, 7..=7]
+        );
+        assert_eq!(missed_trade_ids_in_batch(Some(8), &[8, 7, 6]), vec![]);
+    }
+
+    #[test]
+    fn only_missed_trades_are_backfilled() {
+        let mut received = trades(&[10, 11, 12, 14, 15]);
+        received.push(trade(TradeId::String("13".into())));
+
+        let backfilled = select_missed_trades(received, &(11..=14));
+
+        let ids = backfilled.iter().map(|x| x.trade_id.number()).collect_vec();
+        assert_eq!(ids, vec![Some(11), Some(12), Some(14)]);
+    }
+
+    #[tokio::test]
+    async fn last_trade_id_is_tracked_through_gaps() {
+        let _ = init_lifetime_manager();
+        let (exchange, _rx) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let market_id = MarketId::new(exchange.exchange_account_id.exchange_id, currency_pair);
+
+        exchange.detect_trades_gap(market_id, currency_pair, &trades(&[1, 2]));
+        assert_eq!(
+            exchange.last_tape_trade_ids.get(&market_id).map(|x| *x),
+            Some(2)
+        );
+
+        // gap 3..=4 is backfilled in background, newest received trade stays the last one
+        exchange.detect_trades_gap(market_id, currency_pair, &trades(&[5]));
+        assert_eq!(
+            exchange.last_tape_trade_ids.get(&market_id).map(|x| *x),
+            Some(5)
+        );
+
+        // already received trades don't move last trade id back
+        exchange.detect_trades_gap(market_id, currency_pair, &trades(&[3, 4]));
+        assert_eq!(
+            exchange.last_tape_trade_ids.get(&market_id).map(|x| *x),
+            Some(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn newest_trade_of_descending_batch_is_the_last_one() {
+        let _ = init_lifetime_manager();
+        let (exchange, _rx) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let market_id = MarketId::new(exchange.exchange_account_id.exchange_id, currency_pair);
+
+        exchange.detect_trades_gap(market_id, currency_pair, &trades(&[2, 1]));
+        assert_eq!(
+            exchange.last_tape_trade_ids.get(&market_id).map(|x| *x),
+            Some(2)
+        );
+
+        exchange.detect_trades_gap(market_id, currency_pair, &trades(&[5, 4, 3]));
+        assert_eq!(
+            exchange.last_tape_trade_ids.get(&market_id).map(|x| *x),
+            Some(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn gap_detection_is_skipped_for_string_trade_ids() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let currency_pair = CurrencyPair::from_codes("PHB".into(), "BTC".into());
+        let market_id = MarketId::new(exchange.exchange_account_id.exchange_id, currency_pair);
+
+        let trades = [trade(TradeId::String("abc".into()))];
+        exchange.detect_trades_gap(market_id, currency_pair, &trades);
+
+        assert!(exchange.last_tape_trade_ids.get(&market_id).is_none());
+    }
 }
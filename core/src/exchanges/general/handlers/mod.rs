@@ -2,6 +2,7 @@ use mmb_domain::events::{AllowedEventSourceType, EventSourceType};
 
 pub mod handle_cancel_order_failed;
 pub mod handle_cancel_order_succeeded;
+pub mod handle_cash_flow;
 pub mod handle_order_filled;
 pub mod handle_trade;
 
@@ -15,6 +16,65 @@ pub(crate) fn should_ignore_event(
     match allowed_event_source_type {
         FallbackOnly if source_type != RestFallback => true,
         NonFallback if source_type != Rest && source_type != WebSocket => true,
+        WebSocketOnly if source_type != WebSocket => true,
         _ => false,
     }
 }
+
+/// The same as `should_ignore_event` but for events about failed requests
+pub(crate) fn should_ignore_failure_event(
+    allowed_event_source_type: AllowedEventSourceType,
+    source_type: EventSourceType,
+) -> bool {
+    match allowed_event_source_type {
+        AllowedEventSourceType::WebSocketOnly => source_type == EventSourceType::Rpc,
+        _ => should_ignore_event(allowed_event_source_type, source_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn websocket_only_ignores_success_events_not_from_websocket() {
+        use AllowedEventSourceType::WebSocketOnly;
+
+        assert!(!should_ignore_event(
+            WebSocketOnly,
+            EventSourceType::WebSocket
+        ));
+        assert!(should_ignore_event(WebSocketOnly, EventSourceType::Rest));
+        assert!(should_ignore_event(
+            WebSocketOnly,
+            EventSourceType::RestFallback
+        ));
+
+        assert!(!should_ignore_failure_event(
+            WebSocketOnly,
+            EventSourceType::Rest
+        ));
+        assert!(!should_ignore_failure_event(
+            WebSocketOnly,
+            EventSourceType::RestFallback
+        ));
+        assert!(should_ignore_failure_event(
+            WebSocketOnly,
+            EventSourceType::Rpc
+        ));
+    }
+
+    #[test]
+    fn failure_events_for_other_source_types() {
+        use AllowedEventSourceType::*;
+
+        for source_type in [EventSourceType::Rest, EventSourceType::RestFallback] {
+            for allowed in [All, FallbackOnly, NonFallback] {
+                assert_eq!(
+                    should_ignore_failure_event(allowed, source_type),
+                    should_ignore_event(allowed, source_type)
+                );
+            }
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub mod handlers;
 pub mod order;
 pub mod polling_timeout_manager;
 pub mod request_type;
+pub mod websocket_shards;
 
 #[cfg(test)]
 pub mod test_helper;
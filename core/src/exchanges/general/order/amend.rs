@@ -0,0 +1,466 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::order::event::OrderEventType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderHeader, OrderStatus, Price, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::traits::ExchangeError;
+
+#[derive(Debug, Clone)]
+pub enum AmendOrderResult {
+    /// Exchange amended the order in place, so it keeps its ids
+    Amended(OrderRef),
+    /// Exchange doesn't support amendment, so the order was cancelled
+    /// and its remaining amount was placed as a new order
+    Replaced(OrderRef),
+}
+
+impl AmendOrderResult {
+    /// Active order with new parameters
+    pub fn order(&self) -> &OrderRef {
+        match self {
+            AmendOrderResult::Amended(order) | AmendOrderResult::Replaced(order) => order,
+        }
+    }
+}
+
+impl Exchange {
+    /// Change price, trigger parameters and/or amount of active order. Order kind can't be changed.
+    /// New amount is the whole amount of order including already filled part.
+    /// Native amendment keeps ids of order, otherwise the order is replaced by cancellation
+    /// and creation of new order with the same reservation, which refers to the replaced order
+    /// by `replaced_order_id`.
+    /// Increase of amount is always emulated because approved reservation of order can't grow
+    pub async fn amend_order(
+        &self,
+        order: &OrderRef,
+        new_user_order: UserOrder,
+        new_amount: Option<Amount>,
+        cancellation_token: CancellationToken,
+    ) -> Result<AmendOrderResult> {
+        let client_order_id = order.client_order_id();
+        let user_order = order.user_order().with_context(|| {
+            format!(
+                "Order {client_order_id} on {} isn't user order",
+                self.exchange_account_id
+            )
+        })?;
+        if !user_order.is_amendable_to(&new_user_order) {
+            bail!(
+                "Order {client_order_id} {user_order:?} can't be amended to {new_user_order:?} on {}",
+                self.exchange_account_id
+            );
+        }
+
+        if let Some(new_amount) = new_amount {
+            let filled_amount = order.filled_amount();
+            if new_amount <= filled_amount {
+                bail!(
+                    "New amount {new_amount} of order {client_order_id} should be greater than filled amount {filled_amount} on {}",
+                    self.exchange_account_id
+                );
+            }
+        }
+
+        if order.is_finished() {
+            bail!(
+                "Order {client_order_id} on {} is already finished",
+                self.exchange_account_id
+            );
+        }
+
+        let exchange_order_id = order.exchange_order_id().with_context(|| {
+            format!(
+                "Order {client_order_id} isn't created yet on {}",
+                self.exchange_account_id
+            )
+        })?;
+
+        let is_amount_increased = new_amount.map_or(false, |amount| amount > order.amount());
+        if !is_amount_increased
+            && self
+                .try_amend_order_natively(
+                    order,
+                    user_order,
+                    &exchange_order_id,
+                    new_user_order,
+                    new_amount,
+                    cancellation_token.clone(),
+                )
+                .await?
+        {
+            return Ok(AmendOrderResult::Amended(order.clone()));
+        }
+
+        let new_order = self
+            .replace_order(order, new_user_order, new_amount, cancellation_token)
+            .await?;
+
+        Ok(AmendOrderResult::Replaced(new_order))
+    }
+
+    /// Returns `false` if exchange doesn't support such amendment of orders
+    async fn try_amend_order_natively(
+        &self,
+        order: &OrderRef,
+        user_order: UserOrder,
+        exchange_order_id: &ExchangeOrderId,
+        new_user_order: UserOrder,
+        new_amount: Option<Amount>,
+        cancellation_token: CancellationToken,
+    ) -> Result<bool> {
+        let client_order_id = order.client_order_id();
+        let old_price = user_order.reservation_price();
+        let new_price = new_user_order.reservation_price();
+        let old_amount = order.amount();
+
+        if let Some(new_price) = new_price {
+            self.update_reservation_price(order, new_price)?;
+        }
+
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::AmendOrder,
+                None,
+                cancellation_token,
+            )
+            .await;
+
+        let result = self
+            .request_amend_order(order, exchange_order_id, new_user_order, new_amount)
+            .await;
+
+        match result {
+            Some(Ok(new_exchange_order_id)) => {
+                if new_exchange_order_id != *exchange_order_id {
+                    self.rebind_exchange_order_id(order, exchange_order_id, new_exchange_order_id);
+                }
+            }
+            Some(Err(error)) => {
+                self.restore_reservation_price(order, new_price, old_price);
+                self.add_event_on_order_change(order, OrderEventType::AmendOrderFailed)?;
+                bail!(
+                    "Failed to amend order {client_order_id} {exchange_order_id:?} on {}: {error:?}",
+                    self.exchange_account_id
+                );
+            }
+            None => {
+                self.restore_reservation_price(order, new_price, old_price);
+                return Ok(false);
+            }
+        }
+
+        order.fn_mut(|x| {
+            if let UserOrder::Limit { price, .. } = new_user_order {
+                x.internal_props.modified_price = Some(price);
+            }
+            x.internal_props.amended_user_order = Some(new_user_order);
+            if new_amount.is_some() {
+                x.internal_props.modified_amount = new_amount;
+            }
+        });
+        self.add_event_on_order_change(order, OrderEventType::AmendOrderSucceeded)?;
+
+        if let (Some(new_amount), Some(reservation_id)) =
+            (new_amount, order.header().reservation_id)
+        {
+            if new_amount < old_amount {
+                self.with_balance_manager(|balance_manager| {
+                    balance_manager.unreserve_by_client_order_id(
+                        reservation_id,
+                        client_order_id.clone(),
+                        old_amount - new_amount,
+                    )
+                })
+                .transpose()?;
+            }
+        }
+
+        log::info!(
+            "Order {client_order_id} {exchange_order_id:?} is amended to {new_user_order:?} amount {new_amount:?} on {}",
+            self.exchange_account_id
+        );
+
+        Ok(true)
+    }
+
+    /// Price of limit order and amount are changed by modification of order, trigger parameters
+    /// of conditional order are changed by its amendment.
+    /// Returns exchange order id of amended order or `None` if exchange doesn't support it
+    async fn request_amend_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_user_order: UserOrder,
+        new_amount: Option<Amount>,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        let exchange_client = &self.exchange_client;
+        let result = match new_user_order {
+            UserOrder::Limit { price, .. } => {
+                let new_price = (price != order.price()).then_some(price);
+                exchange_client
+                    .modify_order(order, exchange_order_id, new_price, new_amount)
+                    .await
+            }
+            _ if new_amount.is_none() => {
+                return exchange_client
+                    .amend_conditional_order(order, exchange_order_id, &new_user_order)
+                    .await
+            }
+            UserOrder::StopLoss { stop_price, .. } => {
+                exchange_client
+                    .modify_order(order, exchange_order_id, Some(stop_price), new_amount)
+                    .await
+            }
+            _ => None,
+        };
+
+        result.map(|result| result.map(|_| exchange_order_id.clone()))
+    }
+
+    /// Order is found by its new exchange order id after atomic replacement on exchange.
+    /// Fills of the new order could be received before response on amendment
+    fn rebind_exchange_order_id(
+        &self,
+        order: &OrderRef,
+        old_exchange_order_id: &ExchangeOrderId,
+        new_exchange_order_id: ExchangeOrderId,
+    ) {
+        let client_order_id = order.client_order_id();
+        log::info!(
+            "Order {client_order_id} {old_exchange_order_id:?} is replaced by {new_exchange_order_id:?} on {}",
+            self.exchange_account_id
+        );
+
+        order.fn_mut(|x| x.props.exchange_order_id = Some(new_exchange_order_id.clone()));
+        self.orders
+            .cache_by_exchange_id
+            .remove(old_exchange_order_id);
+        self.orders
+            .cache_by_exchange_id
+            .insert(new_exchange_order_id.clone(), order.clone());
+
+        self.handle_buffered_fills(&client_order_id, &new_exchange_order_id);
+    }
+
+    async fn replace_order(
+        &self,
+        order: &OrderRef,
+        new_user_order: UserOrder,
+        new_amount: Option<Amount>,
+        cancellation_token: CancellationToken,
+    ) -> Result<OrderRef> {
+        let client_order_id = order.client_order_id();
+        log::info!(
+            "Order {client_order_id} is amended by replacement on {}",
+            self.exchange_account_id
+        );
+
+        let header = order.header();
+        let old_amount = order.amount();
+        let new_amount = new_amount.unwrap_or(old_amount);
+
+        if let Some(reservation_id) = header.reservation_id {
+            let amount_increase = new_amount - old_amount;
+            let not_approved_amount = self
+                .with_balance_manager(|balance_manager| {
+                    balance_manager
+                        .get_reservation(reservation_id)
+                        .map(|reservation| reservation.not_approved_amount)
+                })
+                .flatten();
+            if let Some(not_approved_amount) = not_approved_amount {
+                if amount_increase > not_approved_amount {
+                    bail!(
+                        "Reservation {reservation_id} of order {client_order_id} can't cover increase of amount by {amount_increase} on {}",
+                        self.exchange_account_id
+                    );
+                }
+            }
+        }
+
+        self.wait_cancel_order(order.clone(), None, true, cancellation_token.clone())
+            .await?;
+
+        // Order could be triggered and filled while cancellation was in progress
+        if order.status() != OrderStatus::Canceled {
+            bail!(
+                "Order {client_order_id} was finished with status {:?} before replacement on {}",
+                order.status(),
+                self.exchange_account_id
+            );
+        }
+
+        if let Some(reservation_id) = header.reservation_id {
+            self.with_balance_manager(|balance_manager| {
+                balance_manager.cancel_approved_reservation(reservation_id, &client_order_id)
+            });
+            if let Some(new_price) = new_user_order.reservation_price() {
+                self.update_reservation_price(order, new_price)?;
+            }
+
+            if new_amount < old_amount {
+                self.with_balance_manager(|balance_manager| {
+                    balance_manager.unreserve(reservation_id, old_amount - new_amount)
+                })
+                .transpose()?;
+            }
+        }
+
+        let mut new_header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            header.exchange_account_id,
+            header.currency_pair,
+            header.side,
+            new_amount - order.filled_amount(),
+            new_user_order,
+            header.reservation_id,
+            header.signal_id.clone(),
+            header.strategy_name.clone(),
+        )
+        .with_replaced_order_id(client_order_id);
+
+        if let Some(intent_id) = header.intent_id {
+            new_header = new_header.with_intent_id(intent_id);
+        }
+
+        self.create_order(&new_header, None, cancellation_token)
+            .await
+    }
+
+    fn update_reservation_price(&self, order: &OrderRef, new_price: Price) -> Result<()> {
+        let reservation_id = match order.header().reservation_id {
+            Some(reservation_id) => reservation_id,
+            None => return Ok(()),
+        };
+
+        let is_updated = self
+            .with_balance_manager(|balance_manager| {
+                balance_manager.try_update_reservation(reservation_id, new_price)
+            })
+            .unwrap_or(true);
+        if !is_updated {
+            bail!(
+                "Unable to update reservation {reservation_id} of order {} to price {new_price} on {}",
+                order.client_order_id(),
+                self.exchange_account_id
+            );
+        }
+
+        Ok(())
+    }
+
+    fn restore_reservation_price(
+        &self,
+        order: &OrderRef,
+        new_price: Option<Price>,
+        old_price: Option<Price>,
+    ) {
+        let (Some(_), Some(old_price)) = (new_price, old_price) else {
+            return;
+        };
+
+        if let Err(error) = self.update_reservation_price(order, old_price) {
+            log::error!("Failed to restore reservation price after order amendment: {error:?}");
+        }
+    }
+
+    /// Returns `None` if balance manager isn't set up, so there is no reservation accounting
+    fn with_balance_manager<T>(&self, action: impl FnOnce(&mut BalanceManager) -> T) -> Option<T> {
+        let balance_manager = self.balance_manager.lock().as_ref()?.upgrade()?;
+        let mut balance_manager = balance_manager.lock();
+        Some(action(&mut balance_manager))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use chrono::Utc;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::snapshot::{OrderSide, TriggerPriceType};
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn amend_order_rejects_invalid_amendment() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let create_order = |user_order| {
+            let header = OrderHeader::with_user_order(
+                ClientOrderId::unique_id(),
+                exchange.exchange_account_id,
+                CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+                OrderSide::Sell,
+                dec!(1),
+                user_order,
+                None,
+                None,
+                "test".to_owned(),
+            );
+            exchange
+                .orders
+                .add_simple_initial(&header, Utc::now(), None)
+        };
+        let amend_order = |order, new_user_order, new_amount| {
+            exchange.amend_order(order, new_user_order, new_amount, CancellationToken::new())
+        };
+
+        let limit_order = create_order(UserOrder::limit(dec!(10)));
+        let result = amend_order(&limit_order, UserOrder::stop_loss(dec!(9)), None).await;
+        assert!(result.is_err());
+
+        let result = amend_order(&limit_order, UserOrder::maker_only(dec!(9)), None).await;
+        assert!(result.is_err());
+
+        let result = amend_order(&limit_order, UserOrder::limit(dec!(10)), Some(dec!(0))).await;
+        assert!(result.is_err());
+
+        let stop_loss_order = create_order(UserOrder::stop_loss(dec!(10)));
+        let trailing_stop = UserOrder::TrailingStop {
+            trailing_delta: dec!(1),
+            stop_price: None,
+            trigger_price_type: TriggerPriceType::Last,
+        };
+        let result = amend_order(&stop_loss_order, trailing_stop, None).await;
+        assert!(result.is_err());
+
+        // stop-loss order can be amended after it's created on exchange
+        let result = amend_order(&stop_loss_order, UserOrder::stop_loss(dec!(9)), None).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn modified_price_and_amount_override_header() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            OrderSide::Sell,
+            dec!(1),
+            UserOrder::limit(dec!(10)),
+            None,
+            None,
+            "test".to_owned(),
+        );
+        let order = exchange
+            .orders
+            .add_simple_initial(&header, Utc::now(), None);
+
+        order.fn_mut(|x| {
+            x.internal_props.modified_price = Some(dec!(11));
+            x.internal_props.modified_amount = Some(dec!(2));
+        });
+
+        assert_eq!(order.price(), dec!(11));
+        assert_eq!(order.amount(), dec!(2));
+        assert_eq!(order.deep_clone().price(), dec!(11));
+        assert_eq!(order.header().amount, dec!(1));
+    }
+}
@@ -0,0 +1,293 @@
+use anyhow::{bail, Result};
+use futures::future::join_all;
+use itertools::Itertools;
+use mmb_domain::events::EventSourceType;
+use mmb_domain::market::ExchangeErrorType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{OrderHeader, OrderStatus};
+use mmb_utils::cancellation_token::CancellationToken;
+
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::exchange::RequestResult::{Error, Success};
+use crate::exchanges::general::order::create::CreateOrderResult;
+use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
+use crate::exchanges::traits::ExchangeError;
+use crate::misc::time::time_manager;
+
+/// Order which should be created as a part of batch
+#[derive(Debug, Clone)]
+pub struct OrderCreating {
+    pub header: OrderHeader,
+    /// Group of requests which are already reserved for the order,
+    /// otherwise creation request is reserved in timeout manager by batch itself
+    pub pre_reservation_group_id: Option<RequestGroupId>,
+}
+
+impl OrderCreating {
+    pub fn new(header: OrderHeader) -> Self {
+        Self {
+            header,
+            pre_reservation_group_id: None,
+        }
+    }
+}
+
+impl Exchange {
+    /// Create several orders at once. Exchanges with bulk endpoint get orders in batches
+    /// of max supported size, otherwise orders are created by concurrent single requests,
+    /// which rate is limited by timeout manager. Returns results in the same order as orders
+    pub async fn create_orders_batch(
+        &self,
+        orders_creating: Vec<OrderCreating>,
+        cancellation_token: CancellationToken,
+    ) -> Vec<Result<OrderRef>> {
+        let max_batch_size = self
+            .exchange_client
+            .max_orders_batch_size()
+            .filter(|&size| size > 1);
+
+        match max_batch_size {
+            Some(max_batch_size) => {
+                self.create_orders_by_batches(&orders_creating, max_batch_size, cancellation_token)
+                    .await
+            }
+            None => {
+                self.create_orders_by_single_requests(&orders_creating, cancellation_token)
+                    .await
+            }
+        }
+    }
+
+    async fn create_orders_by_batches(
+        &self,
+        orders_creating: &[OrderCreating],
+        max_batch_size: usize,
+        cancellation_token: CancellationToken,
+    ) -> Vec<Result<OrderRef>> {
+        let mut results = Vec::with_capacity(orders_creating.len());
+        for batch in orders_creating.chunks(max_batch_size) {
+            let mut batch_results = batch.iter().map(|_| None).collect_vec();
+            let mut orders = Vec::with_capacity(batch.len());
+            for (index, order_creating) in batch.iter().enumerate() {
+                let header = self.apply_limit_collar(&order_creating.header);
+                let header = header.as_ref();
+                let validation_result = self
+                    .validate_batch_order_header(header)
+                    .and_then(|_| self.validate_order_header(header));
+                match validation_result {
+                    Ok(()) => orders.push(self.orders.add_simple_initial(
+                        header,
+                        time_manager::now(),
+                        self.exchange_client.get_initial_extension_data(),
+                    )),
+                    Err(error) => batch_results[index] = Some(Err(error)),
+                }
+            }
+
+            if !orders.is_empty() {
+                let created_results = self
+                    .request_orders_batch(&orders, cancellation_token.clone())
+                    .await;
+
+                for (order, created_result) in orders.iter().zip(created_results) {
+                    let index = batch
+                        .iter()
+                        .position(|x| x.header.client_order_id == order.client_order_id())
+                        .expect("order is created from batch");
+                    let result = self
+                        .handle_batch_order_created(
+                            order,
+                            created_result,
+                            batch[index].pre_reservation_group_id,
+                            cancellation_token.clone(),
+                        )
+                        .await;
+                    batch_results[index] = Some(result);
+                }
+            }
+
+            results.extend(
+                batch_results
+                    .into_iter()
+                    .map(|result| result.expect("every order of batch has result")),
+            );
+        }
+
+        results
+    }
+
+    /// Returns result for every order, so failure of the whole request fails all orders
+    async fn request_orders_batch(
+        &self,
+        orders: &[OrderRef],
+        cancellation_token: CancellationToken,
+    ) -> Vec<CreateOrderResult> {
+        log::info!(
+            "Submitting batch of orders {} on {}",
+            orders.iter().map(|x| x.client_order_id()).join(", "),
+            self.exchange_account_id
+        );
+
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::CreateOrdersBatch,
+                None,
+                cancellation_token,
+            )
+            .await;
+
+        match self.exchange_client.create_orders_batch(orders).await {
+            Ok(created_results) if created_results.len() == orders.len() => created_results,
+            Ok(created_results) => {
+                // Some orders could be created, so their status should be checked
+                let error = ExchangeError::parsing(format!(
+                    "Batch response contains {} results for {} orders",
+                    created_results.len(),
+                    orders.len()
+                ));
+                vec![CreateOrderResult::failed(error, EventSourceType::Rest); orders.len()]
+            }
+            Err(error) => {
+                vec![CreateOrderResult::failed(error, EventSourceType::Rest); orders.len()]
+            }
+        }
+    }
+
+    fn validate_batch_order_header(&self, header: &OrderHeader) -> Result<()> {
+        if header.exchange_account_id != self.exchange_account_id {
+            bail!(
+                "Order {} of batch belongs to {} instead of {}",
+                header.client_order_id,
+                header.exchange_account_id,
+                self.exchange_account_id
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn handle_batch_order_created(
+        &self,
+        order: &OrderRef,
+        created_result: CreateOrderResult,
+        pre_reservation_group_id: Option<RequestGroupId>,
+        cancellation_token: CancellationToken,
+    ) -> Result<OrderRef> {
+        let client_order_id = order.client_order_id();
+        match created_result.outcome {
+            Success(exchange_order_id) => self.handle_create_order_succeeded(
+                self.exchange_account_id,
+                &client_order_id,
+                &exchange_order_id,
+                created_result.source_type,
+            )?,
+            Error(exchange_error)
+                if exchange_error.error_type == ExchangeErrorType::ParsingError =>
+            {
+                self.check_order_creation(
+                    order.clone(),
+                    Some(exchange_error),
+                    pre_reservation_group_id,
+                    cancellation_token.clone(),
+                )
+                .await;
+            }
+            Error(exchange_error) => {
+                self.handle_create_order_failed(
+                    &client_order_id,
+                    &exchange_error,
+                    created_result.source_type,
+                )?;
+                bail!(
+                    "Failed to create order {client_order_id} by batch on {}: {}",
+                    self.exchange_account_id,
+                    exchange_error.message
+                );
+            }
+        }
+
+        if matches!(
+            order.status(),
+            OrderStatus::Creating | OrderStatus::FailedToCreate
+        ) {
+            bail!(
+                "Order {client_order_id} of batch isn't created on {}, status {:?}",
+                self.exchange_account_id,
+                order.status()
+            );
+        }
+
+        self.handle_created_order(order, pre_reservation_group_id, cancellation_token)
+            .await
+            .unwrap_or_else(|err| log::error!("failed handle_created_order: {err}"));
+
+        Ok(order.clone())
+    }
+
+    async fn create_orders_by_single_requests(
+        &self,
+        orders_creating: &[OrderCreating],
+        cancellation_token: CancellationToken,
+    ) -> Vec<Result<OrderRef>> {
+        let create_orders = orders_creating.iter().map(|order_creating| {
+            let cancellation_token = cancellation_token.clone();
+            async move {
+                self.validate_batch_order_header(&order_creating.header)?;
+
+                if order_creating.pre_reservation_group_id.is_none() {
+                    self.timeout_manager
+                        .reserve_when_available(
+                            self.exchange_account_id,
+                            RequestType::CreateOrder,
+                            None,
+                            cancellation_token.clone(),
+                        )
+                        .await;
+                }
+
+                self.create_order(
+                    &order_creating.header,
+                    order_creating.pre_reservation_group_id,
+                    cancellation_token,
+                )
+                .await
+            }
+        });
+
+        join_all(create_orders).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn orders_of_other_exchange_account_are_rejected() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            ExchangeAccountId::new("Binance", 5),
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            OrderSide::Sell,
+            dec!(1),
+            UserOrder::limit(dec!(10)),
+            None,
+            None,
+            "test".to_owned(),
+        );
+
+        let results = exchange
+            .create_orders_batch(vec![OrderCreating::new(header)], CancellationToken::new())
+            .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+}
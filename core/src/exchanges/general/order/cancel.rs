@@ -189,7 +189,7 @@ impl Exchange {
         }
     }
 
-    pub(crate) async fn cancel_orders(
+    pub(crate) async fn cancel_orders_by_info(
         &self,
         orders: Vec<OrderInfo>,
         cancellation_token: CancellationToken,
@@ -219,7 +219,7 @@ impl Exchange {
 
         if !not_found_orders.is_empty() {
             log::error!(
-                "`cancel_orders` was received for an orders which are not in the system {}: {}",
+                "`cancel_orders_by_info` was received for an orders which are not in the system {}: {}",
                 self.exchange_account_id,
                 not_found_orders.iter().join(", "),
             );
@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use futures::future::join_all;
+use itertools::Itertools;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderSide};
+use mmb_utils::cancellation_token::CancellationToken;
+
+use crate::exchanges::general::exchange::Exchange;
+
+/// Selection of not finished orders for cancellation, order should meet all specified conditions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrdersFilter {
+    pub client_order_ids: Option<HashSet<ClientOrderId>>,
+    pub currency_pair: Option<CurrencyPair>,
+    pub side: Option<OrderSide>,
+    pub strategy_name: Option<String>,
+}
+
+impl OrdersFilter {
+    pub fn by_ids(client_order_ids: impl IntoIterator<Item = ClientOrderId>) -> Self {
+        OrdersFilter {
+            client_order_ids: Some(client_order_ids.into_iter().collect()),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_currency_pair(currency_pair: CurrencyPair) -> Self {
+        OrdersFilter {
+            currency_pair: Some(currency_pair),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_side(side: OrderSide) -> Self {
+        OrdersFilter {
+            side: Some(side),
+            ..Default::default()
+        }
+    }
+
+    pub fn by_strategy(strategy_name: impl Into<String>) -> Self {
+        OrdersFilter {
+            strategy_name: Some(strategy_name.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_currency_pair(mut self, currency_pair: CurrencyPair) -> Self {
+        self.currency_pair = Some(currency_pair);
+        self
+    }
+
+    pub fn with_side(mut self, side: OrderSide) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    pub fn matches(&self, order: &OrderRef) -> bool {
+        let header = order.header();
+        self.client_order_ids
+            .as_ref()
+            .map_or(true, |ids| ids.contains(&header.client_order_id))
+            && self
+                .currency_pair
+                .map_or(true, |x| x == header.currency_pair)
+            && self.side.map_or(true, |x| x == header.side)
+            && self
+                .strategy_name
+                .as_ref()
+                .map_or(true, |x| *x == header.strategy_name)
+    }
+}
+
+impl Exchange {
+    /// Cancel all not finished orders matching the filter and wait for their finishing.
+    /// Cancellation requests are sent concurrently, so their rate is limited by timeout manager.
+    /// Returns result of cancellation for every matched order
+    pub async fn cancel_orders(
+        &self,
+        filter: &OrdersFilter,
+        cancellation_token: CancellationToken,
+    ) -> Vec<Result<OrderRef>> {
+        let orders = self
+            .orders
+            .not_finished
+            .iter()
+            .filter(|x| filter.matches(x.value()))
+            .map(|x| x.value().clone())
+            .collect_vec();
+
+        if let Some(client_order_ids) = &filter.client_order_ids {
+            let not_found_ids = client_order_ids
+                .iter()
+                .filter(|id| !orders.iter().any(|x| x.client_order_id() == **id))
+                .join(", ");
+            if !not_found_ids.is_empty() {
+                log::warn!(
+                    "Orders {not_found_ids} for cancellation are already finished or don't match filter on {}",
+                    self.exchange_account_id
+                );
+            }
+        }
+
+        log::info!(
+            "Cancelling orders {} by filter {filter:?} on {}",
+            orders.iter().map(|x| x.client_order_id()).join(", "),
+            self.exchange_account_id
+        );
+
+        let cancel_orders = orders.into_iter().map(|order| {
+            let cancellation_token = cancellation_token.clone();
+            async move {
+                self.wait_cancel_order(order.clone(), None, true, cancellation_token)
+                    .await?;
+                Ok(order)
+            }
+        });
+
+        join_all(cancel_orders).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use chrono::Utc;
+    use mmb_domain::order::snapshot::{OrderHeader, UserOrder};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn filter_matches_orders_by_all_conditions() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let btc_pair = CurrencyPair::from_codes("btc".into(), "usdt".into());
+        let eth_pair = CurrencyPair::from_codes("eth".into(), "usdt".into());
+        let create_order = |currency_pair, side| {
+            let header = OrderHeader::with_user_order(
+                ClientOrderId::unique_id(),
+                exchange.exchange_account_id,
+                currency_pair,
+                side,
+                dec!(1),
+                UserOrder::limit(dec!(10)),
+                None,
+                None,
+                "test".to_owned(),
+            );
+            exchange
+                .orders
+                .add_simple_initial(&header, Utc::now(), None)
+        };
+
+        let btc_buy = create_order(btc_pair, OrderSide::Buy);
+        let btc_sell = create_order(btc_pair, OrderSide::Sell);
+        let eth_buy = create_order(eth_pair, OrderSide::Buy);
+
+        let filter = OrdersFilter::by_currency_pair(btc_pair);
+        assert!(filter.matches(&btc_buy));
+        assert!(filter.matches(&btc_sell));
+        assert!(!filter.matches(&eth_buy));
+
+        let filter = filter.with_side(OrderSide::Buy);
+        assert!(filter.matches(&btc_buy));
+        assert!(!filter.matches(&btc_sell));
+
+        let filter = OrdersFilter::by_ids([eth_buy.client_order_id()]);
+        assert!(filter.matches(&eth_buy));
+        assert!(!filter.matches(&btc_buy));
+
+        let filter = OrdersFilter::by_strategy("test").with_currency_pair(eth_pair);
+        assert!(filter.matches(&eth_buy));
+        assert!(!filter.matches(&btc_buy));
+        assert!(!OrdersFilter::by_strategy("other").matches(&eth_buy));
+
+        assert!(OrdersFilter::default().matches(&btc_sell));
+    }
+
+    #[tokio::test]
+    async fn orders_are_not_cancelled_by_filter_without_matches() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let filter = OrdersFilter::by_ids([ClientOrderId::unique_id()]);
+
+        let results = exchange
+            .cancel_orders(&filter, CancellationToken::new())
+            .await;
+
+        assert!(results.is_empty());
+    }
+}
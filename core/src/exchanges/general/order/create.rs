@@ -1,5 +1,5 @@
 use crate::exchanges::general::exchange::RequestResult::{Error, Success};
-use crate::exchanges::general::handlers::should_ignore_event;
+use crate::exchanges::general::handlers::{should_ignore_event, should_ignore_failure_event};
 use crate::exchanges::general::request_type::RequestType;
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 use crate::exchanges::traits::ExchangeError;
@@ -14,7 +14,7 @@ use mmb_domain::market::{ExchangeAccountId, ExchangeErrorType};
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{
-    ClientOrderId, ExchangeOrderId, OrderHeader, OrderInfo, OrderStatus, OrderType,
+    ClientOrderId, ExchangeOrderId, OrderHeader, OrderInfo, OrderOptions, OrderStatus, OrderType,
 };
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::time::ToStdExpected;
@@ -55,8 +55,13 @@ impl Exchange {
     ) -> Result<OrderRef> {
         use AllowedEventSourceType::*;
 
+        let order_header = self.apply_limit_collar(order_header);
+        let order_header = order_header.as_ref();
+
         log::info!("Submitting order {order_header:?}");
 
+        self.validate_order_header(order_header)?;
+
         let order = self.orders.add_simple_initial(
             order_header,
             time_manager::now(),
@@ -67,7 +72,7 @@ impl Exchange {
 
         let create_order_fut = self.create_order_base(&order, linked_ct.clone());
 
-        let duration = Duration::from_secs(5 * 60);
+        let duration = self.operation_policies.create_order.timeout();
         let poll_creation_fut = {
             let order = order.clone();
             let linked_ct = linked_ct.clone();
@@ -163,7 +168,7 @@ impl Exchange {
                     handle_poll_creation_order_res(&order, poll_result, linked_ct)?;
                 }
             }
-            NonFallback => {
+            NonFallback | WebSocketOnly => {
                 let created_order_result = create_order_fut.await;
                 handle_create_order_res(
                     self,
@@ -184,7 +189,54 @@ impl Exchange {
         Ok(order)
     }
 
-    async fn handle_created_order(
+    /// Check that order can be created on the exchange before sending request
+    pub(super) fn validate_order_header(&self, order_header: &OrderHeader) -> Result<()> {
+        if let OrderOptions::User(user_order) = &order_header.options {
+            if let Some(trigger_price_type) = user_order.trigger_price_type() {
+                if !self
+                    .features
+                    .order_features
+                    .supports_trigger_price_type(trigger_price_type)
+                {
+                    bail!(
+                        "Trigger by {trigger_price_type:?} price isn't supported on {} for order {}",
+                        self.exchange_account_id,
+                        order_header.client_order_id
+                    );
+                }
+            }
+        }
+
+        if order_header.display_amount.is_some()
+            && !self.features.order_features.supports_iceberg_order
+        {
+            bail!(
+                "Iceberg orders aren't supported on {}, order {} should be emulated by `create_iceberg_order`",
+                self.exchange_account_id,
+                order_header.client_order_id
+            );
+        }
+
+        if order_header.order_type == OrderType::Limit {
+            if let Some(price) = order_header.source_price {
+                let currency_pair = order_header.currency_pair;
+                if let Some(symbol) = self.symbols.get(&currency_pair) {
+                    symbol
+                        .validate_price(price, self.reference_price(currency_pair))
+                        .with_context(|| {
+                            format!(
+                                "Order {} on {} violates exchange price filters",
+                                order_header.client_order_id, self.exchange_account_id
+                            )
+                        })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(super) async fn handle_created_order(
         &self,
         order: &OrderRef,
         pre_reservation_group_id: Option<RequestGroupId>,
@@ -267,7 +319,7 @@ impl Exchange {
         Ok(())
     }
 
-    async fn check_order_creation(
+    pub(super) async fn check_order_creation(
         &self,
         order: OrderRef,
         error: Option<ExchangeError>,
@@ -515,7 +567,7 @@ impl Exchange {
     }
 
     #[named]
-    fn handle_create_order_failed(
+    pub(crate) fn handle_create_order_failed(
         &self,
         client_order_id: &ClientOrderId,
         exchange_error: &ExchangeError,
@@ -528,7 +580,8 @@ impl Exchange {
             exchange_error,
         );
 
-        if should_ignore_event(self.features.allowed_create_event_source_type, source_type) {
+        if should_ignore_failure_event(self.features.allowed_create_event_source_type, source_type)
+        {
             return Ok(());
         }
 
@@ -651,6 +704,28 @@ impl Exchange {
         }
     }
 
+    /// Fills received before the order got its exchange order id
+    pub(crate) fn handle_buffered_fills(
+        &self,
+        client_order_id: &ClientOrderId,
+        exchange_order_id: &ExchangeOrderId,
+    ) {
+        let mut buffered_fills_manager = self.buffered_fills_manager.lock();
+        if let Some(buffered_fills) = buffered_fills_manager.get_fills(exchange_order_id) {
+            log::trace!(
+                "Found buffered fills for an order {client_order_id} {exchange_order_id} on {}:\n{buffered_fills:?}",
+                self.exchange_account_id,
+            );
+
+            for buffered_fill in buffered_fills {
+                let mut fill_event = buffered_fill.to_fill_event_data(client_order_id.clone());
+                self.handle_order_filled(&mut fill_event);
+            }
+
+            buffered_fills_manager.remove_fills(exchange_order_id);
+        }
+    }
+
     fn react_on_status_when_succeed(
         &self,
         order: &OrderRef,
@@ -722,22 +797,7 @@ impl Exchange {
 
                 self.add_event_on_order_change(order, OrderEventType::CreateOrderSucceeded)?;
 
-                let mut buffered_fills_manager = self.buffered_fills_manager.lock();
-                if let Some(buffered_fills) = buffered_fills_manager.get_fills(exchange_order_id) {
-                    log::trace!(
-                        "Found buffered fills for an order {client_order_id} {exchange_order_id} on {}:\n{buffered_fills:?}",
-                        self.exchange_account_id,
-                    );
-
-                    for buffered_fill in buffered_fills {
-                        let mut fill_event =
-                            buffered_fill.to_fill_event_data(client_order_id.clone());
-                        self.handle_order_filled(&mut fill_event);
-                    }
-
-                    buffered_fills_manager.remove_fills(exchange_order_id);
-                }
-                drop(buffered_fills_manager);
+                self.handle_buffered_fills(&client_order_id, exchange_order_id);
 
                 let mut buffered_canceled_orders_manager =
                     self.buffered_canceled_orders_manager.lock();
@@ -8,17 +8,18 @@ use mmb_domain::market::CurrencyCode;
 use mmb_domain::order::fill::OrderFillType;
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::{Amount, Price};
-use mmb_domain::order::snapshot::{ExchangeOrderId, OrderRole};
+use mmb_domain::order::snapshot::{ExchangeOrderId, OrderRole, OrderSide};
 use mmb_utils::DateTime;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderTrade {
     pub exchange_order_id: ExchangeOrderId,
     pub trade_id: TradeId,
     pub datetime: DateTime,
     pub price: Price,
     pub amount: Amount,
+    pub side: OrderSide,
     pub order_role: OrderRole,
     pub fee_currency_code: CurrencyCode,
     pub fee_rate: Option<Price>,
@@ -34,6 +35,7 @@ impl OrderTrade {
         datetime: DateTime,
         price: Price,
         amount: Amount,
+        side: OrderSide,
         order_role: OrderRole,
         fee_currency_code: CurrencyCode,
         fee_rate: Option<Price>,
@@ -46,6 +48,7 @@ impl OrderTrade {
             datetime,
             price,
             amount,
+            side,
             order_role,
             fee_currency_code,
             fee_rate,
@@ -0,0 +1,155 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderHeader, OrderStatus, OrderType};
+use mmb_utils::cancellation_token::CancellationToken;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use crate::exchanges::general::exchange::Exchange;
+
+fn validate_iceberg_header(header: &OrderHeader) -> Result<()> {
+    let display_amount = header.display_amount.with_context(|| {
+        format!(
+            "Display amount isn't specified for iceberg order {}",
+            header.client_order_id
+        )
+    })?;
+
+    if header.order_type != OrderType::Limit {
+        bail!(
+            "Iceberg order {} should be limit order, but it is {:?}",
+            header.client_order_id,
+            header.order_type
+        );
+    }
+
+    if display_amount <= dec!(0) || display_amount >= header.amount {
+        bail!(
+            "Display amount {display_amount} of iceberg order {} should be positive and less than amount {}",
+            header.client_order_id,
+            header.amount
+        );
+    }
+
+    Ok(())
+}
+
+impl Exchange {
+    /// Create iceberg order which shows only display amount in order book.
+    /// Exchanges without native iceberg orders are emulated by sequential child orders:
+    /// next slice is posted once the visible child order is filled, until the whole amount is done.
+    /// Returns all created orders. Emulation stops when a child order is canceled
+    /// or the operation is cancelled by token, active child order is canceled in the latter case
+    pub async fn create_iceberg_order(
+        self: Arc<Self>,
+        header: &OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<Vec<OrderRef>> {
+        validate_iceberg_header(header)?;
+
+        if self.features.order_features.supports_iceberg_order {
+            let order = self.create_order(header, None, cancellation_token).await?;
+            return Ok(vec![order]);
+        }
+
+        let display_amount = header.display_amount.expect("checked in validation");
+        log::info!(
+            "Iceberg orders aren't supported on {}, order {} is emulated by slices of {display_amount}",
+            self.exchange_account_id,
+            header.client_order_id
+        );
+
+        let mut child_orders = Vec::new();
+        let mut filled_amount = dec!(0);
+        while filled_amount < header.amount {
+            let child_header = OrderHeader::with_options(
+                ClientOrderId::unique_id(),
+                header.exchange_account_id,
+                header.currency_pair,
+                header.side,
+                display_amount.min(header.amount - filled_amount),
+                header.options.clone(),
+                header.reservation_id,
+                header.signal_id.clone(),
+                header.strategy_name.clone(),
+            );
+
+            let child_order = self
+                .create_order(&child_header, None, cancellation_token.clone())
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to create slice of iceberg order {} on {}",
+                        header.client_order_id, self.exchange_account_id
+                    )
+                })?;
+            child_orders.push(child_order.clone());
+
+            self.clone()
+                .wait_order_finish(&child_order, None, cancellation_token.clone())
+                .await?;
+
+            if cancellation_token.is_cancellation_requested() && !child_order.is_finished() {
+                let stop_token = self.lifetime_manager.stop_token();
+                self.wait_cancel_order(child_order.clone(), None, true, stop_token)
+                    .await?;
+            }
+
+            filled_amount += child_order.filled_amount();
+
+            if child_order.status() != OrderStatus::Completed {
+                log::info!(
+                    "Iceberg order {} is stopped on {} after slice {} with status {:?}, filled {filled_amount} of {}",
+                    header.client_order_id,
+                    self.exchange_account_id,
+                    child_order.client_order_id(),
+                    child_order.status(),
+                    header.amount
+                );
+                break;
+            }
+        }
+
+        Ok(child_orders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::snapshot::{OrderSide, UserOrder};
+
+    #[tokio::test]
+    async fn create_iceberg_order_rejects_invalid_display_amount() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let header = |user_order| {
+            OrderHeader::with_user_order(
+                ClientOrderId::unique_id(),
+                exchange.exchange_account_id,
+                CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+                OrderSide::Sell,
+                dec!(10),
+                user_order,
+                None,
+                None,
+                "test".to_owned(),
+            )
+        };
+
+        for header in [
+            header(UserOrder::limit(dec!(1))),
+            header(UserOrder::limit(dec!(1))).with_display_amount(dec!(10)),
+            header(UserOrder::limit(dec!(1))).with_display_amount(dec!(0)),
+            header(UserOrder::Market).with_display_amount(dec!(1)),
+        ] {
+            let result = exchange
+                .clone()
+                .create_iceberg_order(&header, CancellationToken::new())
+                .await;
+            assert!(result.is_err());
+        }
+        assert!(exchange.orders.cache_by_client_id.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::exchanges::general::exchange::{Exchange, OrderBookTop};
+use crate::misc::limit_collar::LimitCollar;
+use crate::settings::LimitCollarSettings;
+use mmb_domain::exchanges::symbol::Round;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::snapshot::{OrderHeader, OrderOptions, OrderSide, OrderType, UserOrder};
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::time::Duration;
+
+impl Exchange {
+    /// Enable conversion of market orders of the market to marketable limit orders
+    pub fn set_limit_collar(&self, settings: LimitCollarSettings) {
+        let _ = self
+            .limit_collars
+            .insert(settings.currency_pair, LimitCollar::new(settings));
+    }
+
+    pub(crate) fn update_limit_collar(
+        &self,
+        currency_pair: CurrencyPair,
+        order_book_top: &OrderBookTop,
+    ) {
+        let Some(mut limit_collar) = self.limit_collars.get_mut(&currency_pair) else {
+            return;
+        };
+
+        if let (Some(ask), Some(bid)) = (&order_book_top.ask, &order_book_top.bid) {
+            limit_collar.update((ask.price + bid.price) / Decimal::TWO);
+        }
+    }
+
+    /// Market order is replaced by limit order with collar price if collar is set for its market.
+    /// Order stays market one while middle price of market isn't known yet
+    pub(super) fn apply_limit_collar<'a>(&self, header: &'a OrderHeader) -> Cow<'a, OrderHeader> {
+        if !matches!(header.options, OrderOptions::User(UserOrder::Market)) {
+            return Cow::Borrowed(header);
+        }
+
+        let Some(limit_collar) = self.limit_collars.get(&header.currency_pair) else {
+            return Cow::Borrowed(header);
+        };
+
+        let (Some(price), Some(symbol)) = (
+            limit_collar.price(header.side),
+            self.symbols.get(&header.currency_pair),
+        ) else {
+            return Cow::Borrowed(header);
+        };
+
+        // rounding keeps price inside the collar
+        let price = match header.side {
+            OrderSide::Buy => symbol.price_round(price, Round::Floor),
+            OrderSide::Sell => symbol.price_round(price, Round::Ceiling),
+        };
+
+        log::info!(
+            "Market order {} on {} {} is converted to limit order with collar price {price}",
+            header.client_order_id,
+            self.exchange_account_id,
+            header.currency_pair
+        );
+
+        let mut header = header.clone();
+        header.options = OrderOptions::limit(price);
+        header.order_type = OrderType::Limit;
+        header.source_price = Some(price);
+        if let (None, Some(expire_after_ms)) =
+            (header.expire_after, limit_collar.settings().expire_after_ms)
+        {
+            header.expire_after = Some(Duration::from_millis(expire_after_ms));
+        }
+
+        Cow::Owned(header)
+    }
+}
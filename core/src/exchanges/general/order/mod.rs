@@ -1,8 +1,16 @@
+pub mod amend;
+pub mod batch;
 pub mod cancel;
+pub mod cancel_by_filter;
 pub mod create;
 pub mod create_websocket_based;
 pub mod get_info;
 pub mod get_open_orders;
 pub mod get_order_trades;
+pub mod iceberg;
+pub mod limit_collar;
+pub mod oco;
+pub mod reconcile_fills;
+pub mod resolve_stuck;
 pub mod wait_cancel;
 pub mod wait_finish;
@@ -0,0 +1,389 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::events::EventSourceType;
+use mmb_domain::order::pool::{OcoOrderGroup, OrderRef};
+use mmb_domain::order::snapshot::{OrderHeader, OrderStatus, OrderType};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::request_type::RequestType;
+use crate::infrastructure::spawn_future;
+use crate::misc::time::time_manager;
+
+/// Orders of created one-cancels-other group
+#[derive(Debug, Clone)]
+pub struct OcoOrders {
+    pub limit_order: OrderRef,
+    pub stop_order: OrderRef,
+}
+
+fn validate_oco_headers(limit_header: &OrderHeader, stop_header: &OrderHeader) -> Result<()> {
+    if limit_header.order_type != OrderType::Limit {
+        bail!(
+            "Order {} of OCO group should be limit order, but it is {:?}",
+            limit_header.client_order_id,
+            limit_header.order_type
+        );
+    }
+
+    if stop_header.order_type != OrderType::StopLoss {
+        bail!(
+            "Order {} of OCO group should be stop-loss order, but it is {:?}",
+            stop_header.client_order_id,
+            stop_header.order_type
+        );
+    }
+
+    if limit_header.exchange_account_id != stop_header.exchange_account_id
+        || limit_header.currency_pair != stop_header.currency_pair
+        || limit_header.side != stop_header.side
+    {
+        bail!(
+            "Orders {} and {} of OCO group should have the same exchange account, currency pair and side",
+            limit_header.client_order_id,
+            stop_header.client_order_id
+        );
+    }
+
+    Ok(())
+}
+
+impl Exchange {
+    /// Create limit and stop-loss orders linked as one-cancels-other: when one of them
+    /// is filled or canceled, the other one is canceled. Exchanges without native OCO orders
+    /// are emulated by separate orders which siblings are canceled by core
+    pub async fn create_oco_order(
+        &self,
+        limit_header: &OrderHeader,
+        stop_header: &OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<OcoOrders> {
+        validate_oco_headers(limit_header, stop_header)?;
+
+        log::info!("Submitting OCO orders {limit_header:?} {stop_header:?}");
+
+        let limit_order = self.orders.add_simple_initial(
+            limit_header,
+            time_manager::now(),
+            self.exchange_client.get_initial_extension_data(),
+        );
+        let stop_order = self.orders.add_simple_initial(
+            stop_header,
+            time_manager::now(),
+            self.exchange_client.get_initial_extension_data(),
+        );
+
+        self.timeout_manager
+            .reserve_when_available(
+                self.exchange_account_id,
+                RequestType::CreateOcoOrder,
+                None,
+                cancellation_token.clone(),
+            )
+            .await;
+
+        match self
+            .exchange_client
+            .create_oco_order(&limit_order, &stop_order)
+            .await
+        {
+            Some(Ok((limit_exchange_order_id, stop_exchange_order_id))) => {
+                let _ = self.orders.add_oco_group(OcoOrderGroup {
+                    limit_order: limit_header.client_order_id.clone(),
+                    stop_order: stop_header.client_order_id.clone(),
+                    is_native: true,
+                });
+
+                for (order, exchange_order_id) in [
+                    (&limit_order, &limit_exchange_order_id),
+                    (&stop_order, &stop_exchange_order_id),
+                ] {
+                    self.handle_create_order_succeeded(
+                        self.exchange_account_id,
+                        &order.client_order_id(),
+                        exchange_order_id,
+                        EventSourceType::Rest,
+                    )?;
+                }
+
+                log::info!(
+                    "Created OCO orders {} {limit_exchange_order_id:?} and {} {stop_exchange_order_id:?} on {}",
+                    limit_header.client_order_id,
+                    stop_header.client_order_id,
+                    self.exchange_account_id
+                );
+
+                Ok(OcoOrders {
+                    limit_order,
+                    stop_order,
+                })
+            }
+            Some(Err(error)) => {
+                for order in [&limit_order, &stop_order] {
+                    self.handle_create_order_failed(
+                        &order.client_order_id(),
+                        &error,
+                        EventSourceType::Rest,
+                    )?;
+                }
+
+                bail!(
+                    "Failed to create OCO orders {} and {} on {}: {error:?}",
+                    limit_header.client_order_id,
+                    stop_header.client_order_id,
+                    self.exchange_account_id
+                )
+            }
+            None => {
+                self.create_emulated_oco_order(limit_header, stop_header, cancellation_token)
+                    .await
+            }
+        }
+    }
+
+    async fn create_emulated_oco_order(
+        &self,
+        limit_header: &OrderHeader,
+        stop_header: &OrderHeader,
+        cancellation_token: CancellationToken,
+    ) -> Result<OcoOrders> {
+        log::info!(
+            "OCO orders aren't supported on {}, orders {} and {} are linked by core",
+            self.exchange_account_id,
+            limit_header.client_order_id,
+            stop_header.client_order_id
+        );
+
+        let limit_order = self
+            .create_order(limit_header, None, cancellation_token.clone())
+            .await?;
+
+        let stop_order = match self
+            .create_order(stop_header, None, cancellation_token.clone())
+            .await
+        {
+            Ok(stop_order) => stop_order,
+            Err(error) => {
+                if let Err(cancel_error) = self
+                    .wait_cancel_order(limit_order.clone(), None, true, cancellation_token)
+                    .await
+                {
+                    log::error!(
+                        "Unable to cancel order {} of OCO group with failed stop order on {}: {cancel_error:?}",
+                        limit_header.client_order_id,
+                        self.exchange_account_id
+                    );
+                }
+
+                return Err(error).with_context(|| {
+                    format!(
+                        "Failed to create stop order {} of OCO group on {}",
+                        stop_header.client_order_id, self.exchange_account_id
+                    )
+                });
+            }
+        };
+
+        let _ = self.orders.add_oco_group(OcoOrderGroup {
+            limit_order: limit_header.client_order_id.clone(),
+            stop_order: stop_header.client_order_id.clone(),
+            is_native: false,
+        });
+
+        // Limit order could be filled or canceled while stop order was creating
+        if limit_order.is_finished() || !limit_order.filled_amount().is_zero() {
+            self.cancel_oco_sibling(&limit_order);
+        }
+
+        Ok(OcoOrders {
+            limit_order,
+            stop_order,
+        })
+    }
+
+    /// Cancel sibling of order from OCO group after the order was filled or canceled.
+    /// Siblings of native OCO orders are canceled by exchange, so group is only forgotten
+    pub(crate) fn cancel_oco_sibling(&self, order: &OrderRef) {
+        let sibling = match self.take_oco_sibling_to_cancel(order) {
+            None => return,
+            Some(sibling) => sibling,
+        };
+
+        let client_order_id = order.client_order_id();
+        let sibling_id = sibling.client_order_id();
+        log::info!(
+            "Order {client_order_id} of OCO group is finished, canceling sibling {sibling_id} on {}",
+            self.exchange_account_id
+        );
+
+        let weak_self = self.weak_self.clone();
+        let cancellation_token = self.lifetime_manager.stop_token();
+        let action = format!(
+            "Canceling sibling {sibling_id} of OCO order {client_order_id} on {}",
+            self.exchange_account_id
+        );
+        let _ = spawn_future(&action, SpawnFutureFlags::STOP_BY_TOKEN, async move {
+            match weak_self.upgrade() {
+                Some(exchange) => {
+                    exchange
+                        .wait_cancel_order(sibling, None, true, cancellation_token)
+                        .await
+                }
+                None => Ok(()),
+            }
+        });
+    }
+
+    /// Forget OCO group of the order and return its sibling if it should be canceled by core
+    fn take_oco_sibling_to_cancel(&self, order: &OrderRef) -> Option<OrderRef> {
+        let client_order_id = order.client_order_id();
+        let group = self.orders.get_oco_group(&client_order_id)?;
+        self.orders.remove_oco_group(&group);
+
+        if group.is_native {
+            return None;
+        }
+
+        let sibling_id = group
+            .sibling(&client_order_id)
+            .expect("Order should be member of its OCO group");
+        let sibling = match self.orders.cache_by_client_id.get(sibling_id) {
+            Some(sibling) => sibling.clone(),
+            None => {
+                log::error!(
+                    "Sibling {sibling_id} of OCO order {client_order_id} isn't found in orders pool of {}",
+                    self.exchange_account_id
+                );
+                return None;
+            }
+        };
+
+        if sibling.is_finished() || sibling.status() == OrderStatus::Canceling {
+            return None;
+        }
+
+        Some(sibling)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::snapshot::{ClientOrderId, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn create_oco_order_rejects_different_sides() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let header = |side, user_order| {
+            OrderHeader::with_user_order(
+                ClientOrderId::unique_id(),
+                exchange.exchange_account_id,
+                CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+                side,
+                dec!(1),
+                user_order,
+                None,
+                None,
+                "test".to_owned(),
+            )
+        };
+
+        let limit_header = header(OrderSide::Sell, UserOrder::limit(dec!(10)));
+        let stop_header = header(OrderSide::Buy, UserOrder::stop_loss(dec!(9)));
+        let result = exchange
+            .create_oco_order(&limit_header, &stop_header, CancellationToken::new())
+            .await;
+        assert!(result.is_err());
+
+        let result = exchange
+            .create_oco_order(&stop_header, &limit_header, CancellationToken::new())
+            .await;
+        assert!(result.is_err());
+        assert!(exchange.orders.cache_by_client_id.is_empty());
+    }
+
+    fn add_oco_group(exchange: &Exchange, is_native: bool) -> (OrderRef, OrderRef) {
+        let order = |user_order| {
+            let header = OrderHeader::with_user_order(
+                ClientOrderId::unique_id(),
+                exchange.exchange_account_id,
+                CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+                OrderSide::Sell,
+                dec!(1),
+                user_order,
+                None,
+                None,
+                "test".to_owned(),
+            );
+            exchange
+                .orders
+                .add_simple_initial(&header, time_manager::now(), None)
+        };
+
+        let limit_order = order(UserOrder::limit(dec!(10)));
+        let stop_order = order(UserOrder::stop_loss(dec!(9)));
+        let _ = exchange.orders.add_oco_group(OcoOrderGroup {
+            limit_order: limit_order.client_order_id(),
+            stop_order: stop_order.client_order_id(),
+            is_native,
+        });
+
+        (limit_order, stop_order)
+    }
+
+    #[tokio::test]
+    async fn emulated_oco_sibling_is_canceled_after_fill_or_cancel() {
+        let (exchange, _rx) = get_test_exchange(false);
+
+        let (filled_limit_order, stop_order) = add_oco_group(&exchange, false);
+        filled_limit_order.fn_mut(|x| x.set_status(OrderStatus::Completed, time_manager::now()));
+        let sibling = exchange
+            .take_oco_sibling_to_cancel(&filled_limit_order)
+            .expect("in test");
+        assert_eq!(sibling.client_order_id(), stop_order.client_order_id());
+        assert!(exchange.orders.oco_groups.is_empty());
+
+        let (limit_order, canceled_stop_order) = add_oco_group(&exchange, false);
+        canceled_stop_order.fn_mut(|x| x.set_status(OrderStatus::Canceled, time_manager::now()));
+        let sibling = exchange
+            .take_oco_sibling_to_cancel(&canceled_stop_order)
+            .expect("in test");
+        assert_eq!(sibling.client_order_id(), limit_order.client_order_id());
+        assert!(exchange.orders.oco_groups.is_empty());
+
+        // Sibling of the forgotten group isn't canceled twice
+        assert!(exchange
+            .take_oco_sibling_to_cancel(&canceled_stop_order)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn finished_emulated_oco_sibling_is_not_canceled() {
+        let (exchange, _rx) = get_test_exchange(false);
+
+        let (limit_order, stop_order) = add_oco_group(&exchange, false);
+        for order in [&limit_order, &stop_order] {
+            order.fn_mut(|x| x.set_status(OrderStatus::Canceled, time_manager::now()));
+        }
+
+        assert!(exchange.take_oco_sibling_to_cancel(&limit_order).is_none());
+        assert!(exchange.orders.oco_groups.is_empty());
+    }
+
+    #[tokio::test]
+    async fn native_oco_sibling_is_left_to_exchange() {
+        let (exchange, _rx) = get_test_exchange(false);
+
+        let (limit_order, stop_order) = add_oco_group(&exchange, true);
+        limit_order.fn_mut(|x| x.set_status(OrderStatus::Completed, time_manager::now()));
+
+        assert!(exchange.take_oco_sibling_to_cancel(&limit_order).is_none());
+        assert!(exchange
+            .orders
+            .get_oco_group(&stop_order.client_order_id())
+            .is_none());
+    }
+}
@@ -0,0 +1,233 @@
+use crate::exchanges::general::exchange::{Exchange, RequestResult};
+use crate::exchanges::general::features::RestFillsType;
+use crate::exchanges::general::order::get_order_trades::OrderTrade;
+use crate::exchanges::general::request_type::RequestType;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use mmb_domain::order::pool::OrderRef;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+
+impl Exchange {
+    /// Request account trades since `from` via REST and handle trades of known orders which
+    /// are absent in fills of orders (e.g. because websocket message was lost) as fills
+    /// from REST fallback. Returns count of handled missed fills
+    pub async fn reconcile_fills(
+        &self,
+        from: DateTime,
+        cancellation_token: CancellationToken,
+    ) -> Result<usize> {
+        if !matches!(
+            self.features.rest_fills_features.fills_type,
+            RestFillsType::MyTrades
+        ) {
+            return Ok(0);
+        }
+
+        // only markets with orders created since `from` can have fills to reconcile
+        let currency_pairs = self
+            .orders
+            .cache_by_client_id
+            .iter()
+            .filter(|x| x.fn_ref(|x| x.props.init_time >= from))
+            .map(|x| x.currency_pair())
+            .unique()
+            .collect_vec();
+
+        let mut missed_fills_count = 0;
+        for currency_pair in currency_pairs {
+            let Some(symbol) = self.symbols.get(&currency_pair).map(|x| x.clone()) else {
+                continue;
+            };
+
+            self.timeout_manager
+                .reserve_when_available(
+                    self.exchange_account_id,
+                    RequestType::GetOrderTrades,
+                    None,
+                    cancellation_token.clone(),
+                )
+                .await
+                .into_result()?;
+
+            let trades = match self
+                .exchange_client
+                .get_my_trades(&symbol, Some(from))
+                .await
+            {
+                RequestResult::Success(trades) => trades,
+                RequestResult::Error(error) => {
+                    return Err(error).with_context(|| {
+                        format!(
+                            "Unable to get trades for {} {currency_pair} to reconcile fills",
+                            self.exchange_account_id
+                        )
+                    })
+                }
+            };
+
+            for trade in &trades {
+                let Some(order) = self
+                    .orders
+                    .cache_by_exchange_id
+                    .get(&trade.exchange_order_id)
+                    .map(|x| x.clone())
+                else {
+                    continue;
+                };
+
+                if !is_fill_missed(&order, trade) {
+                    continue;
+                }
+
+                log::warn!(
+                    "Fill {:?} of order {} {} on {} was missed, it's handled from REST trades",
+                    trade.trade_id,
+                    order.client_order_id(),
+                    trade.exchange_order_id,
+                    self.exchange_account_id
+                );
+
+                self.handle_order_filled_for_rest_fallback(&order, trade);
+                missed_fills_count += 1;
+            }
+        }
+
+        Ok(missed_fills_count)
+    }
+}
+
+fn is_fill_missed(order: &OrderRef, trade: &OrderTrade) -> bool {
+    order.fn_ref(|x| {
+        !x.fills
+            .fills
+            .iter()
+            .any(|fill| fill.trade_id() == Some(&trade.trade_id))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::{
+        get_test_client, get_test_exchange_with_rest_fills_type, get_test_symbol,
+    };
+    use crate::misc::time::time_manager;
+    use mmb_domain::events::TradeId;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order::fill::OrderFillType;
+    use mmb_domain::order::snapshot::{
+        Amount, ClientOrderId, ExchangeOrderId, OrderOptions, OrderRole, OrderSide, OrderSnapshot,
+        OrderStatus,
+    };
+    use rust_decimal_macros::dec;
+    use std::sync::Arc;
+
+    fn get_exchange(rest_fills_type: RestFillsType) -> Arc<Exchange> {
+        let symbol = get_test_symbol(false, "PHB", "BTC", "PHB");
+        let exchange_account_id = ExchangeAccountId::new("local_exchange_account_id", 0);
+        let (exchange, _rx) =
+            get_test_exchange_with_rest_fills_type(symbol, exchange_account_id, rest_fills_type);
+        exchange
+    }
+
+    fn add_order(exchange: &Exchange, exchange_order_id: &str) -> OrderRef {
+        let mut snapshot = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(dec!(10)),
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            dec!(1),
+            OrderSide::Buy,
+            None,
+            "test",
+        );
+        let exchange_order_id = ExchangeOrderId::from(exchange_order_id);
+        snapshot.props.exchange_order_id = Some(exchange_order_id.clone());
+        snapshot.set_status(OrderStatus::Created, time_manager::now());
+
+        let order = exchange.orders.add_snapshot_initial(&snapshot);
+        let _ = exchange
+            .orders
+            .cache_by_exchange_id
+            .insert(exchange_order_id, order.clone());
+
+        order
+    }
+
+    fn trade(exchange_order_id: &str, trade_id: u64, amount: Amount) -> OrderTrade {
+        OrderTrade::new(
+            ExchangeOrderId::from(exchange_order_id),
+            TradeId::Number(trade_id),
+            time_manager::now(),
+            dec!(10),
+            amount,
+            OrderSide::Buy,
+            OrderRole::Maker,
+            "BTC".into(),
+            Some(dec!(0.1)),
+            None,
+            OrderFillType::UserTrade,
+        )
+    }
+
+    #[tokio::test]
+    async fn fill_is_missed_until_its_trade_is_handled() {
+        let exchange = get_exchange(RestFillsType::MyTrades);
+        let order = add_order(&exchange, "1");
+        let handled_trade = trade("1", 1, dec!(0.3));
+        let other_trade = trade("1", 2, dec!(0.3));
+
+        assert!(is_fill_missed(&order, &handled_trade));
+
+        exchange.handle_order_filled_for_rest_fallback(&order, &handled_trade);
+
+        assert!(!is_fill_missed(&order, &handled_trade));
+        assert!(is_fill_missed(&order, &other_trade));
+    }
+
+    #[tokio::test]
+    async fn missed_fills_of_known_orders_are_reconciled() {
+        let exchange = get_exchange(RestFillsType::MyTrades);
+        let order = add_order(&exchange, "1");
+        let from = time_manager::now() - chrono::Duration::minutes(1);
+
+        exchange.handle_order_filled_for_rest_fallback(&order, &trade("1", 1, dec!(0.3)));
+        *get_test_client(&exchange).my_trades.lock() = vec![
+            trade("1", 1, dec!(0.3)),
+            trade("1", 2, dec!(0.2)),
+            trade("unknown", 3, dec!(0.5)),
+        ];
+
+        let missed_fills_count = exchange
+            .reconcile_fills(from, CancellationToken::default())
+            .await
+            .expect("in test");
+        assert_eq!(missed_fills_count, 1);
+        assert_eq!(order.filled_amount(), dec!(0.5));
+
+        // reconciled fills aren't handled twice
+        let missed_fills_count = exchange
+            .reconcile_fills(from, CancellationToken::default())
+            .await
+            .expect("in test");
+        assert_eq!(missed_fills_count, 0);
+        assert_eq!(order.filled_amount(), dec!(0.5));
+    }
+
+    #[tokio::test]
+    async fn fills_are_not_reconciled_without_rest_trades() {
+        let exchange = get_exchange(RestFillsType::GetOrderInfo);
+        let order = add_order(&exchange, "1");
+        *get_test_client(&exchange).my_trades.lock() = vec![trade("1", 1, dec!(0.3))];
+
+        let from = time_manager::now() - chrono::Duration::minutes(1);
+        let missed_fills_count = exchange
+            .reconcile_fills(from, CancellationToken::default())
+            .await
+            .expect("in test");
+        assert_eq!(missed_fills_count, 0);
+        assert!(order.filled_amount().is_zero());
+    }
+}
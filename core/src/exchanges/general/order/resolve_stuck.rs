@@ -0,0 +1,57 @@
+use crate::exchanges::general::exchange::Exchange;
+use anyhow::{bail, Result};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::OrderStatus;
+use mmb_utils::cancellation_token::CancellationToken;
+use std::time::Duration;
+use tokio::time::timeout;
+
+impl Exchange {
+    /// Request order info via REST for order which stays in `Creating` or `Canceling` status too long
+    /// and update order status according to it. Returns error if order status wasn't resolved in `resolve_timeout`
+    pub async fn resolve_stuck_order(
+        &self,
+        order: &OrderRef,
+        resolve_timeout: Duration,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let stuck_status = order.status();
+        let client_order_id = order.client_order_id();
+
+        let resolving = async {
+            match stuck_status {
+                OrderStatus::Creating => {
+                    self.check_order_creation(order.clone(), None, None, cancellation_token)
+                        .await;
+
+                    Ok(())
+                }
+                OrderStatus::Canceling => {
+                    self.check_order_cancellation_status(order, None, None, cancellation_token)
+                        .await
+                }
+                _ => Ok(()),
+            }
+        };
+
+        match timeout(resolve_timeout, resolving).await {
+            Ok(result) => result?,
+            Err(_) => bail!("Resolving status of order {client_order_id} on {} wasn't finished in {resolve_timeout:?}", self.exchange_account_id),
+        }
+
+        let status = order.status();
+        if status == stuck_status {
+            bail!(
+                "Order {client_order_id} on {} is still in status {status:?} after requesting order info",
+                self.exchange_account_id
+            );
+        }
+
+        log::info!(
+            "Stuck order {client_order_id} on {} was resolved from status {stuck_status:?} to {status:?}",
+            self.exchange_account_id
+        );
+
+        Ok(())
+    }
+}
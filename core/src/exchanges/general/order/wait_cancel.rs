@@ -19,12 +19,9 @@ use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::nothing_to_do;
 use mmb_utils::time::ToStdExpected;
 use scopeguard;
-use std::time::Duration;
 use tokio::sync::broadcast;
 use tokio::time::{sleep, timeout};
 
-const CANCEL_DELAY: Duration = Duration::from_secs(10);
-
 impl Exchange {
     pub async fn wait_cancel_order(
         &self,
@@ -114,21 +111,28 @@ impl Exchange {
                 order_is_finished_token.clone(),
             );
 
-            let duration = Duration::from_secs(3 * 60 * 60);
+            let duration = self.operation_policies.cancel_order.timeout();
             async move {
                 timeout(duration, poll_fut).await.unwrap_or_else(|_| bail!("Time in form of {duration:?} is over, but future `poll wait cancel order` is not completed yet"))
             }
         };
 
+        // cancellation found by fallback polling would be ignored for `WebSocketOnly`
         let is_poll_enabled = self.features.websocket_options.cancellation_notification
-            && self.features.allowed_cancel_event_source_type
-                != AllowedEventSourceType::NonFallback;
+            && !matches!(
+                self.features.allowed_cancel_event_source_type,
+                AllowedEventSourceType::NonFallback | AllowedEventSourceType::WebSocketOnly
+            );
 
         pin_mut!(poll_cancellation_fut);
 
+        let max_attempts = self.operation_policies.cancel_order.max_attempts;
         let mut attempt_number = 0;
         while !cancellation_token.is_cancellation_requested() {
             attempt_number += 1;
+            if attempt_number > max_attempts {
+                bail!("Cancellation of order {client_order_id} {exchange_order_id:?} {} reached maximum attempts {max_attempts}", self.exchange_account_id);
+            }
 
             let log_event_level = match attempt_number == 1 {
                 true => log::Level::Trace,
@@ -170,7 +174,7 @@ impl Exchange {
                             continue;
                         }
                     }
-                    _ = sleep(CANCEL_DELAY) => {
+                    _ = sleep(self.operation_policies.cancel_order.retry_delay()) => {
                         if self.features.allowed_cancel_event_source_type != AllowedEventSourceType::All {
                             bail!("Order was expected to cancel explicitly via Rest or Web Socket but got timeout instead")
                         }
@@ -304,7 +308,7 @@ impl Exchange {
         Ok(())
     }
 
-    async fn check_order_cancellation_status(
+    pub(super) async fn check_order_cancellation_status(
         &self,
         order: &OrderRef,
         exchange_error: Option<ExchangeError>,
@@ -1,4 +1,4 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use chrono::Utc;
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
@@ -417,33 +417,7 @@ impl Exchange {
             RequestType::GetOrderInfo => {
                 let order_info = match self.get_order_info(order).await {
                     Ok(order_info) => {
-                        let exchange_order_id = order.exchange_order_id().with_context(|| {
-                        "No exchange_order_id in order while handle_order_filled_for_restfallback"
-                    })?;
-
-                        let commission_currency_code = order_info
-                            .commission_currency_code
-                            .clone()
-                            .map(|currency_code| CurrencyCode::new(&currency_code));
-
-                        let mut fill_event = FillEvent {
-                            source_type: EventSourceType::RestFallback,
-                            trade_id: None,
-                            client_order_id: Some(order.client_order_id()),
-                            exchange_order_id,
-                            fill_price: order_info.average_fill_price,
-                            fill_amount: FillAmount::Total {
-                                total_filled_amount: order_info.filled_amount,
-                            },
-                            order_role: None,
-                            commission_currency_code,
-                            commission_rate: order_info.commission_rate,
-                            commission_amount: order_info.commission_amount,
-                            fill_type: OrderFillType::UserTrade,
-                            special_order_data: None,
-                            fill_date: None,
-                        };
-                        self.handle_order_filled(&mut fill_event);
+                        self.handle_order_filled_by_order_info(order, &order_info)?;
 
                         RequestResult::Success(order_info)
                     }
@@ -459,6 +433,112 @@ impl Exchange {
         }
     }
 
+    fn handle_order_filled_by_order_info(
+        &self,
+        order: &OrderRef,
+        order_info: &OrderInfo,
+    ) -> Result<()> {
+        let exchange_order_id = order.exchange_order_id().with_context(|| {
+            "No exchange_order_id in order while handle_order_filled_for_restfallback"
+        })?;
+
+        let commission_currency_code = order_info
+            .commission_currency_code
+            .clone()
+            .map(|currency_code| CurrencyCode::new(&currency_code));
+
+        let mut fill_event = FillEvent {
+            source_type: EventSourceType::RestFallback,
+            trade_id: None,
+            client_order_id: Some(order.client_order_id()),
+            exchange_order_id,
+            fill_price: order_info.average_fill_price,
+            fill_amount: FillAmount::Total {
+                total_filled_amount: order_info.filled_amount,
+            },
+            order_role: None,
+            commission_currency_code,
+            commission_rate: order_info.commission_rate,
+            commission_amount: order_info.commission_amount,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: None,
+        };
+        self.handle_order_filled(&mut fill_event);
+
+        Ok(())
+    }
+
+    /// Finish order which was closed on exchange while engine was stopped through usual order
+    /// events. Fills missed during this time are requested from exchange and reconciled with
+    /// fills restored from database
+    pub(crate) async fn finish_recovered_order(
+        &self,
+        order: &OrderRef,
+        order_info: &OrderInfo,
+    ) -> Result<()> {
+        let client_order_id = order.client_order_id();
+        let exchange_order_id = &order_info.exchange_order_id;
+
+        if order.status() == OrderStatus::Creating {
+            self.handle_create_order_succeeded(
+                self.exchange_account_id,
+                &client_order_id,
+                exchange_order_id,
+                EventSourceType::RestFallback,
+            )?;
+        }
+
+        match self.features.rest_fills_features.fills_type {
+            RestFillsType::MyTrades => {
+                let symbol = self.get_symbol(order.currency_pair())?;
+                let result = self
+                    .check_order_fills_using_request_type(
+                        order,
+                        &symbol,
+                        RequestType::GetOrderTrades,
+                        None,
+                        self.lifetime_manager.stop_token(),
+                    )
+                    .await?;
+
+                if let Some(error) = result.get_error() {
+                    bail!("Unable to get trades of recovered order {client_order_id} on {}: {error:?}", self.exchange_account_id);
+                }
+            }
+            RestFillsType::None | RestFillsType::GetOrderInfo => {
+                if !order_info.filled_amount.is_zero() {
+                    self.handle_order_filled_by_order_info(order, order_info)?;
+                }
+            }
+        }
+
+        if order.is_finished() {
+            return Ok(());
+        }
+
+        ensure!(
+            order_info.order_status == OrderStatus::Canceled,
+            "Not all fills of completed recovered order {client_order_id} are received on {}",
+            self.exchange_account_id
+        );
+
+        self.handle_cancel_order_succeeded(
+            Some(&client_order_id),
+            exchange_order_id,
+            Some(order_info.filled_amount),
+            EventSourceType::RestFallback,
+        );
+
+        ensure!(
+            order.is_finished(),
+            "Cancellation of recovered order {client_order_id} isn't handled on {}",
+            self.exchange_account_id
+        );
+
+        Ok(())
+    }
+
     pub(crate) fn handle_order_filled_for_rest_fallback(
         &self,
         order: &OrderRef,
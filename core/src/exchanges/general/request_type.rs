@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum RequestType {
     CreateOrder,
     CancelOrder,
@@ -21,5 +23,12 @@ pub enum RequestType {
     GetLastPrints,
     GetProfileId,
     GetMyTrades,
+    GetServerTime,
     SetLeverage,
+    AmendOrder,
+    CreateOcoOrder,
+    CreateOrdersBatch,
+    Borrow,
+    Repay,
+    CancelAllAfter,
 }
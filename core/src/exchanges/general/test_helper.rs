@@ -11,7 +11,7 @@ use crate::{
             exchange::Exchange,
             features::{
                 ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption,
-                RestFillsFeatures, WebSocketOptions,
+                RestFillsFeatures, RestFillsType, WebSocketOptions,
             },
         },
         timeouts::{
@@ -31,12 +31,14 @@ use mmb_domain::events::{AllowedEventSourceType, ExchangeBalancesAndPositions, E
 use mmb_domain::exchanges::commission::{Commission, CommissionForType};
 use mmb_domain::exchanges::symbol::{BeforeAfter, Precision, Symbol};
 use mmb_domain::market::{
-    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, SpecificCurrencyPair,
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, ExchangeErrorType,
+    SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, OrderOptions, Price};
 use mmb_domain::order::snapshot::{ClientOrderId, OrderInfo, OrderRole, OrderSide, OrderSnapshot};
 use mmb_domain::position::{ActivePosition, ClosedPosition};
+use parking_lot::Mutex;
 use rust_decimal_macros::dec;
 use tokio::sync::broadcast;
 use url::Url;
@@ -50,11 +52,18 @@ use crate::exchanges::timeouts::requests_timeout_manager_factory::RequestsTimeou
 use crate::exchanges::traits::{
     ExchangeError, HandleMetricsCb, HandleOrderFilledCb, SendWebsocketMessageCb,
 };
+use crate::settings::OperationPoliciesSettings;
 use mmb_utils::{cancellation_token::CancellationToken, hashmap, DateTime};
 
 use super::order::get_order_trades::OrderTrade;
 
-pub struct TestClient;
+#[derive(Default)]
+pub struct TestClient {
+    /// Orders info returned by client order id, other orders aren't found on exchange
+    pub(crate) order_infos: DashMap<ClientOrderId, OrderInfo>,
+    /// Account trades returned for any symbol
+    pub(crate) my_trades: Mutex<Vec<OrderTrade>>,
+}
 
 #[async_trait]
 impl ExchangeClient for TestClient {
@@ -85,8 +94,17 @@ impl ExchangeClient for TestClient {
         unimplemented!("doesn't need in UT")
     }
 
-    async fn get_order_info(&self, _order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
-        unimplemented!("doesn't need in UT")
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        self.order_infos
+            .get(&order.client_order_id())
+            .map(|x| x.clone())
+            .ok_or_else(|| {
+                ExchangeError::new(
+                    ExchangeErrorType::OrderNotFound,
+                    "Order isn't found".to_owned(),
+                    None,
+                )
+            })
     }
 
     async fn close_position(
@@ -110,7 +128,7 @@ impl ExchangeClient for TestClient {
         _symbol: &Symbol,
         _last_date_time: Option<DateTime>,
     ) -> RequestResult<Vec<OrderTrade>> {
-        unimplemented!("doesn't need in UT")
+        RequestResult::Success(self.my_trades.lock().clone())
     }
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
@@ -165,6 +183,16 @@ impl Support for TestClient {
         unimplemented!("doesn't need in UT")
     }
 
+    fn create_market_data_subscribe_messages(
+        &self,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<Vec<String>> {
+        Ok(currency_pairs
+            .iter()
+            .map(|currency_pair| format!("subscribe {currency_pair}"))
+            .collect())
+    }
+
     fn get_specific_currency_pair(&self, _currency_pair: CurrencyPair) -> SpecificCurrencyPair {
         unimplemented!("doesn't need in UT")
     }
@@ -208,8 +236,23 @@ pub(crate) fn get_test_exchange_by_currency_codes_and_amount_code(
     quote_currency_code: &str,
     amount_currency_code: &str,
 ) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    let symbol = get_test_symbol(
+        is_derivative,
+        base_currency_code,
+        quote_currency_code,
+        amount_currency_code,
+    );
+    get_test_exchange_with_symbol(symbol)
+}
+
+pub(crate) fn get_test_symbol(
+    is_derivative: bool,
+    base_currency_code: &str,
+    quote_currency_code: &str,
+    amount_currency_code: &str,
+) -> Arc<Symbol> {
     let price_tick = dec!(0.1);
-    let symbol = Arc::new(Symbol::new(
+    Arc::new(Symbol::new(
         is_derivative,
         base_currency_code.into(),
         base_currency_code.into(),
@@ -224,8 +267,7 @@ pub(crate) fn get_test_exchange_by_currency_codes_and_amount_code(
         None,
         Precision::ByTick { tick: price_tick },
         Precision::ByTick { tick: dec!(0) },
-    ));
-    get_test_exchange_with_symbol(symbol)
+    ))
 }
 
 pub(crate) fn get_test_exchange_by_currency_codes(
@@ -255,11 +297,19 @@ pub(crate) fn get_test_exchange_with_symbol(
 pub(crate) fn get_test_exchange_with_symbol_and_id(
     symbol: Arc<Symbol>,
     exchange_account_id: ExchangeAccountId,
+) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
+    get_test_exchange_with_rest_fills_type(symbol, exchange_account_id, RestFillsType::None)
+}
+
+pub(crate) fn get_test_exchange_with_rest_fills_type(
+    symbol: Arc<Symbol>,
+    exchange_account_id: ExchangeAccountId,
+    rest_fills_type: RestFillsType,
 ) -> (Arc<Exchange>, broadcast::Receiver<ExchangeEvent>) {
     let lifetime_manager = AppLifetimeManager::new(CancellationToken::new());
     let (tx, rx) = broadcast::channel(10);
 
-    let exchange_client = Box::new(TestClient);
+    let exchange_client = Box::<TestClient>::default();
     let referral_reward = dec!(40);
     let commission = Commission::new(
         CommissionForType::new(dec!(0.1), referral_reward),
@@ -275,7 +325,7 @@ pub(crate) fn get_test_exchange_with_symbol_and_id(
     let timeout_managers = hashmap![exchange_account_id => request_timeout_manager];
     let timeout_manager = TimeoutManager::new(timeout_managers);
     let event_recorder =
-        block_on(EventRecorder::start(None, None)).expect("Failure start EventRecorder");
+        block_on(EventRecorder::start(None, None, None)).expect("Failure start EventRecorder");
 
     let exchange = Exchange::new(
         exchange_account_id,
@@ -283,7 +333,7 @@ pub(crate) fn get_test_exchange_with_symbol_and_id(
         OrdersPool::new(),
         ExchangeFeatures::new(
             OpenOrdersType::AllCurrencyPair,
-            RestFillsFeatures::default(),
+            RestFillsFeatures::new(rest_fills_type),
             OrderFeatures {
                 supports_get_order_info_by_client_order_id: true,
                 ..OrderFeatures::default()
@@ -302,6 +352,7 @@ pub(crate) fn get_test_exchange_with_symbol_and_id(
         Arc::downgrade(&exchange_blocker),
         commission,
         event_recorder,
+        OperationPoliciesSettings::default(),
     );
 
     exchange
@@ -356,3 +407,11 @@ pub(crate) fn try_add_snapshot_by_exchange_id(exchange: &Exchange, order_ref: &O
             .insert(exchange_order_id, order_ref.clone());
     }
 }
+
+pub(crate) fn get_test_client(exchange: &Exchange) -> &TestClient {
+    exchange
+        .exchange_client
+        .as_any()
+        .downcast_ref::<TestClient>()
+        .expect("in test")
+}
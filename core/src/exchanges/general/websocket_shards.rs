@@ -0,0 +1,360 @@
+use crate::connectivity::{websocket_open, WebSocketParams, WebSocketRole, WsSender};
+use crate::exchanges::general::exchange::Exchange;
+use crate::infrastructure::spawn_future;
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use mmb_domain::market::SpecificCurrencyPair;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout};
+
+/// Additional connections of main websocket which receive market data of currency pairs
+/// exceeding subscriptions limit of single connection
+#[derive(Default)]
+pub(crate) struct WebSocketShards {
+    /// Incremented on every reconnection of exchange, so readers of previous connections stop
+    generation: u64,
+    senders: HashMap<usize, WsSender>,
+}
+
+impl WebSocketShards {
+    /// Close all shard connections
+    pub(super) fn reset(&mut self) -> u64 {
+        self.generation += 1;
+        self.senders.clear();
+        self.generation
+    }
+}
+
+/// Split currency pairs into minimal count of evenly filled shards
+fn split_into_shards<T>(items: Vec<T>, max_per_shard: usize) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+
+    let max_per_shard = max_per_shard.max(1);
+    let shards_count = (items.len() + max_per_shard - 1) / max_per_shard;
+    let shard_size = (items.len() + shards_count - 1) / shards_count;
+
+    items
+        .into_iter()
+        .chunks(shard_size)
+        .into_iter()
+        .map(|chunk| chunk.collect())
+        .collect()
+}
+
+impl Exchange {
+    /// Market data subscriptions distributed across connections of main websocket,
+    /// empty if all of them can be served by single connection
+    pub(super) fn market_data_shards(&self) -> Vec<Vec<SpecificCurrencyPair>> {
+        let max_currency_pairs = match self.exchange_client.max_websocket_currency_pairs() {
+            Some(max_currency_pairs) => max_currency_pairs,
+            None => return Vec::new(),
+        };
+
+        let currency_pairs = self
+            .symbols
+            .iter()
+            .map(|symbol| {
+                self.exchange_client
+                    .get_specific_currency_pair(*symbol.key())
+            })
+            // stable order, so same currency pairs get to same shards after reconnection
+            .sorted_by(|left, right| left.as_str().cmp(right.as_str()))
+            .collect_vec();
+
+        if currency_pairs.len() <= max_currency_pairs {
+            return Vec::new();
+        }
+
+        let shards = split_into_shards(currency_pairs, max_currency_pairs);
+        log::info!(
+            "Market data of {} is sharded across {} websocket connections",
+            self.exchange_account_id,
+            shards.len()
+        );
+        shards
+    }
+
+    pub(super) async fn get_market_data_websocket_params(
+        &self,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<WebSocketParams> {
+        let ws_url = self
+            .exchange_client
+            .create_market_data_ws_url(currency_pairs)
+            .await?;
+        self.websocket_params_by_url(WebSocketRole::Main, ws_url)
+    }
+
+    /// Open additional connections for market data shards. Every shard is reconnected
+    /// independently, and if it can't be reconnected, whole websocket is reconnected
+    /// to rebalance subscriptions
+    pub(super) fn start_websocket_shards(self: &Arc<Self>, shards: Vec<Vec<SpecificCurrencyPair>>) {
+        let generation = self.websocket_shards.lock().reset();
+
+        // index 0 is used by main connection
+        for (shard_index, currency_pairs) in (1..).zip(shards) {
+            spawn_future(
+                &format!(
+                    "Exchange account id {} websocket shard {shard_index}",
+                    self.exchange_account_id
+                ),
+                SpawnFutureFlags::STOP_BY_TOKEN,
+                Self::websocket_shard_future(
+                    Arc::downgrade(self),
+                    generation,
+                    shard_index,
+                    currency_pairs,
+                ),
+            );
+        }
+    }
+
+    async fn websocket_shard_future(
+        instance: Weak<Self>,
+        generation: u64,
+        shard_index: usize,
+        currency_pairs: Vec<SpecificCurrencyPair>,
+    ) -> Result<()> {
+        loop {
+            let mut reader = match instance.upgrade() {
+                Some(exchange) => {
+                    match exchange
+                        .open_websocket_shard(generation, shard_index, &currency_pairs)
+                        .await
+                    {
+                        Ok(Some(reader)) => reader,
+                        // exchange was reconnected, so shards are recreated
+                        Ok(None) => return Ok(()),
+                        Err(error) => {
+                            exchange.rebalance_websocket_shards(generation, shard_index, error);
+                            return Ok(());
+                        }
+                    }
+                }
+                None => return Ok(()),
+            };
+
+            while let Some(msg) = reader.recv().await {
+                match instance.upgrade() {
+                    Some(exchange) => exchange.on_websocket_message(&msg),
+                    None => return Ok(()),
+                }
+            }
+
+            match instance.upgrade() {
+                Some(exchange) if exchange.is_current_shards_generation(generation) => {
+                    log::warn!(
+                        "Websocket shard {shard_index} of {} disconnected, reconnecting",
+                        exchange.exchange_account_id
+                    );
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Returns `None` if connection is outdated because exchange was reconnected
+    async fn open_websocket_shard(
+        &self,
+        generation: u64,
+        shard_index: usize,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<Option<mpsc::UnboundedReceiver<String>>> {
+        let policy = self.operation_policies.websocket_connect;
+        let mut attempt = 1;
+        loop {
+            if !self.is_current_shards_generation(generation) {
+                return Ok(None);
+            }
+
+            let error = match self
+                .try_open_websocket_shard(currency_pairs, policy.timeout())
+                .await
+            {
+                Ok((sender, reader)) => {
+                    let mut shards = self.websocket_shards.lock();
+                    if shards.generation != generation {
+                        return Ok(None);
+                    }
+
+                    shards.senders.insert(shard_index, sender);
+                    return Ok(Some(reader));
+                }
+                Err(error) => error,
+            };
+
+            if attempt >= policy.max_attempts {
+                return Err(error);
+            }
+
+            log::warn!(
+                "Websocket: failed to connect shard {shard_index} on {} on attempt {attempt}: {error:?}",
+                self.exchange_account_id
+            );
+            attempt += 1;
+            sleep(policy.retry_delay()).await;
+        }
+    }
+
+    async fn try_open_websocket_shard(
+        &self,
+        currency_pairs: &[SpecificCurrencyPair],
+        open_timeout: std::time::Duration,
+    ) -> Result<(WsSender, mpsc::UnboundedReceiver<String>)> {
+        let params = self
+            .get_market_data_websocket_params(currency_pairs)
+            .await?;
+
+        let connection = timeout(
+            open_timeout,
+            websocket_open(self.exchange_account_id, params, None),
+        )
+        .await
+        .with_context(|| format!("Connection wasn't established in {open_timeout:?}"))??;
+
+        self.subscribe_market_data_shard(&connection.0, currency_pairs)?;
+
+        Ok(connection)
+    }
+
+    /// Subscribe connection of shard to market data for exchanges which subscribe by messages
+    pub(super) fn subscribe_market_data_shard(
+        &self,
+        sender: &WsSender,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<()> {
+        let messages = self
+            .exchange_client
+            .create_market_data_subscribe_messages(currency_pairs)?;
+
+        for message in messages {
+            sender.send_main(message)?;
+        }
+
+        Ok(())
+    }
+
+    /// Close main connection, so whole websocket is reconnected by usual auto reconnection
+    /// and currency pairs are distributed across new connections
+    fn rebalance_websocket_shards(
+        &self,
+        generation: u64,
+        shard_index: usize,
+        error: anyhow::Error,
+    ) {
+        if !self.is_current_shards_generation(generation) {
+            return;
+        }
+
+        log::error!(
+            "Unable to reconnect websocket shard {shard_index} of {}, reconnecting all connections: {error:?}",
+            self.exchange_account_id
+        );
+        self.ws_sender.lock().take();
+    }
+
+    fn is_current_shards_generation(&self, generation: u64) -> bool {
+        self.websocket_shards.lock().generation == generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use anyhow::anyhow;
+    use rstest::rstest;
+    use tokio_tungstenite::tungstenite::Message;
+
+    fn connected_test_exchange() -> Arc<Exchange> {
+        let (exchange, _) = get_test_exchange(false);
+        let (sender, _) = WsSender::new_for_test();
+        exchange.ws_sender.lock().replace(sender);
+        exchange
+    }
+
+    #[rstest]
+    #[case(0, 10, vec![])]
+    #[case(5, 10, vec![5])]
+    #[case(10, 10, vec![10])]
+    #[case(11, 10, vec![6, 5])]
+    #[case(25, 10, vec![9, 9, 7])]
+    #[case(3, 0, vec![1, 1, 1])]
+    fn split_into_even_shards(
+        #[case] items_count: usize,
+        #[case] max_per_shard: usize,
+        #[case] expected_sizes: Vec<usize>,
+    ) {
+        let items = (0..items_count).collect_vec();
+
+        let shards = split_into_shards(items.clone(), max_per_shard);
+
+        assert_eq!(shards.iter().map(|x| x.len()).collect_vec(), expected_sizes);
+        assert_eq!(shards.into_iter().flatten().collect_vec(), items);
+    }
+
+    #[test]
+    fn shards_are_filled_evenly_without_exceeding_limit() {
+        for items_count in 1..50 {
+            for max_per_shard in 1..12 {
+                let shards = split_into_shards((0..items_count).collect_vec(), max_per_shard);
+
+                let sizes = shards.iter().map(|x| x.len()).collect_vec();
+                let min_shards_count = (items_count + max_per_shard - 1) / max_per_shard;
+                assert_eq!(sizes.len(), min_shards_count);
+                assert!(sizes.iter().all(|&size| size <= max_per_shard));
+                let (min, max) = sizes.iter().minmax().into_option().expect("in test");
+                assert!(max - min <= 1, "uneven shards {sizes:?}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn dead_shard_reconnects_whole_websocket() {
+        let exchange = connected_test_exchange();
+        let generation = exchange.websocket_shards.lock().reset();
+
+        exchange.rebalance_websocket_shards(generation, 1, anyhow!("test"));
+
+        // main connection is closed, so auto reconnection redistributes currency pairs
+        assert!(!exchange.is_websocket_connected());
+    }
+
+    #[tokio::test]
+    async fn dead_shard_of_previous_connection_is_ignored() {
+        let exchange = connected_test_exchange();
+        let outdated_generation = exchange.websocket_shards.lock().reset();
+        // exchange is reconnected and shards are recreated
+        let _ = exchange.websocket_shards.lock().reset();
+
+        exchange.rebalance_websocket_shards(outdated_generation, 1, anyhow!("test"));
+        assert!(exchange.is_websocket_connected());
+
+        let reader = exchange
+            .open_websocket_shard(outdated_generation, 1, &["btcusdt".into()])
+            .await
+            .expect("in test");
+        assert!(reader.is_none(), "outdated shard shouldn't be reopened");
+    }
+
+    #[tokio::test]
+    async fn shard_is_subscribed_to_its_currency_pairs() {
+        let (exchange, _) = get_test_exchange(false);
+        let (sender, mut receiver) = WsSender::new_for_test();
+
+        exchange
+            .subscribe_market_data_shard(&sender, &["btcusdt".into(), "ethusdt".into()])
+            .expect("in test");
+
+        for expected in ["subscribe btcusdt", "subscribe ethusdt"] {
+            let message = receiver.try_recv().expect("in test");
+            assert_eq!(message, Message::Text(expected.to_owned()));
+        }
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -10,31 +10,56 @@ use tokio::sync::{broadcast, oneshot};
 
 use crate::exchanges::general::exchange::{Exchange, OrderBookTop, PriceLevel};
 use crate::lifecycle::trading_engine::Service;
+use crate::misc::strategy_events_router::StrategyEventsRouter;
+use crate::order_book::feed_arbiter::FeedArbiter;
 use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::services::heartbeat::LivenessRegistry;
+use crate::settings::RedundantFeedSettings;
 use mmb_domain::events::ExchangeEvent;
-use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::market::{ExchangeAccountId, MarketId};
 use mmb_domain::order::event::OrderEventType;
 use mmb_domain::order::snapshot::OrderType;
 use mmb_domain::order_book::event::OrderBookEvent;
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+
+/// Name of events loop which its activity is registered by in `LivenessRegistry`
+pub(crate) const INTERNAL_EVENTS_LOOP_NAME: &str = "InternalEventsLoop";
 
 pub(crate) struct InternalEventsLoop {
     work_finished_receiver: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+    /// Order books of all markets, kept for order book tops of exchanges and diagnostics
+    local_snapshots_service: Mutex<LocalSnapshotsService>,
+    /// Order book events of markets with redundant feeds are replaced by arbitrated ones
+    feed_arbiter: Mutex<FeedArbiter>,
 }
 
 impl InternalEventsLoop {
-    pub(crate) fn new() -> Arc<Self> {
+    pub(crate) fn new(redundant_feeds: &[RedundantFeedSettings]) -> Arc<Self> {
         Arc::new(InternalEventsLoop {
             work_finished_receiver: Default::default(),
+            local_snapshots_service: Default::default(),
+            feed_arbiter: Mutex::new(FeedArbiter::new(redundant_feeds)),
         })
     }
 
+    pub(crate) fn order_book_snapshot(
+        &self,
+        market_id: MarketId,
+    ) -> Option<LocalOrderBookSnapshot> {
+        self.local_snapshots_service
+            .lock()
+            .get_snapshot(market_id)
+            .cloned()
+    }
+
     pub async fn start(
         self: Arc<Self>,
         mut events_receiver: broadcast::Receiver<ExchangeEvent>,
         exchanges_map: HashMap<ExchangeAccountId, Arc<Exchange>>,
+        liveness_registry: Arc<LivenessRegistry>,
+        strategy_events_router: Arc<StrategyEventsRouter>,
         cancellation_token: CancellationToken,
     ) -> Result<()> {
-        let mut local_snapshots_service = LocalSnapshotsService::default();
         let (work_finished_sender, receiver) = oneshot::channel();
         *self.work_finished_receiver.lock() = Some(receiver);
 
@@ -47,11 +72,25 @@ impl InternalEventsLoop {
                 }
             };
 
+            liveness_registry.register_activity(self.name());
+
+            let event = match event {
+                ExchangeEvent::OrderBookEvent(order_book_event) => {
+                    match self.feed_arbiter.lock().arbitrate(order_book_event) {
+                        Some(order_book_event) => ExchangeEvent::OrderBookEvent(order_book_event),
+                        None => continue,
+                    }
+                }
+                event => event,
+            };
+
+            strategy_events_router.route(&event);
+
             match event {
                 ExchangeEvent::OrderBookEvent(ref order_book_event) => {
                     update_order_book_top_for_exchange(
                         order_book_event,
-                        &mut local_snapshots_service,
+                        &mut self.local_snapshots_service.lock(),
                         &exchanges_map,
                     )
                 }
@@ -81,7 +120,10 @@ impl InternalEventsLoop {
                 }
                 ExchangeEvent::BalanceUpdate(_) => {}
                 ExchangeEvent::LiquidationPrice(_) => {}
+                ExchangeEvent::MarkPrice(_) => {}
                 ExchangeEvent::Trades(_) => {}
+                ExchangeEvent::CashFlow(_) => {}
+                ExchangeEvent::Heartbeat(_) => {}
             }
         }
     }
@@ -108,6 +150,7 @@ fn update_order_book_top_for_exchange(
         exchanges_map
             .get(&market_account_id.exchange_account_id)
             .map(|exchange| {
+                exchange.update_limit_collar(market_account_id.currency_pair, &order_book_top);
                 exchange
                     .order_book_top
                     .insert(market_account_id.currency_pair, order_book_top)
@@ -117,7 +160,7 @@ fn update_order_book_top_for_exchange(
 
 impl Service for InternalEventsLoop {
     fn name(&self) -> &str {
-        "InternalEventsLoop"
+        INTERNAL_EVENTS_LOOP_NAME
     }
 
     fn graceful_shutdown(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>> {
@@ -1,3 +1,4 @@
+pub mod binance_like;
 pub mod block_reasons;
 pub mod common;
 pub mod exchange_blocker;
@@ -1,7 +1,8 @@
+use crate::connectivity::NetworkConnector;
 use crate::exchanges::traits::ExchangeError;
+use crate::settings::NetworkSettings;
 use anyhow::Result;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use hyper::client::HttpConnector;
 use hyper::http::request::Builder;
 use hyper::http::uri::{Parts, PathAndQuery};
 use hyper::{Body, Client, Error, Method, Request, Response, StatusCode, Uri};
@@ -24,6 +25,17 @@ pub trait RestHeaders {
         uri: &Uri,
         request_type: RequestType,
     ) -> Builder;
+
+    /// Used for requests with body. Should be overridden by exchanges which sign request body
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        _body: &[u8],
+    ) -> Builder {
+        self.add_specific_headers(builder, uri, request_type)
+    }
 }
 
 #[derive(Default)]
@@ -205,7 +217,7 @@ pub struct RestClient<
     ErrHandler: ErrorHandler + Send + Sync + 'static,
     SpecHeaders: RestHeaders + Send + Sync + 'static,
 > {
-    client: Client<HttpsConnector<HttpConnector>>,
+    client: Client<HttpsConnector<NetworkConnector>>,
     error_handler: ErrorHandlerData<ErrHandler>,
     headers: SpecHeaders,
 }
@@ -218,8 +230,17 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
     RestClient<ErrHandler, SpecHeaders>
 {
     pub fn new(error_handler: ErrorHandlerData<ErrHandler>, headers: SpecHeaders) -> Self {
+        Self::with_network_settings(error_handler, headers, &NetworkSettings::default())
+    }
+
+    /// Network settings should be validated before, client creation panics on invalid proxy url
+    pub fn with_network_settings(
+        error_handler: ErrorHandlerData<ErrHandler>,
+        headers: SpecHeaders,
+        network_settings: &NetworkSettings,
+    ) -> Self {
         Self {
-            client: create_client(),
+            client: create_client(network_settings),
             error_handler,
             headers,
         }
@@ -305,7 +326,12 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
         let request_type = RequestType::Post;
         let req = self
             .headers
-            .add_specific_headers(builder, &uri, request_type)
+            .add_specific_headers_with_body(
+                builder,
+                &uri,
+                request_type,
+                query.as_deref().unwrap_or_default(),
+            )
             .uri(uri)
             .header(hyper::header::CONNECTION, KEEP_ALIVE)
             .body(match query {
@@ -393,14 +419,28 @@ impl<ErrHandler: ErrorHandler + Send + Sync + 'static, SpecHeaders: RestHeaders
     }
 }
 
-fn create_client() -> Client<HttpsConnector<HttpConnector>> {
-    let https = HttpsConnectorBuilder::new()
+fn create_client(network_settings: &NetworkSettings) -> Client<HttpsConnector<NetworkConnector>> {
+    let connector =
+        NetworkConnector::new(network_settings).expect("Unable to create network connector");
+
+    let https_builder = HttpsConnectorBuilder::new()
         .with_native_roots()
-        .https_only()
-        .enable_http1()
-        .enable_http2()
-        .build();
-    Client::builder().build::<_, Body>(https)
+        .https_only();
+    let https = match network_settings.http2_only {
+        true => https_builder.enable_http2().wrap_connector(connector),
+        false => https_builder
+            .enable_http1()
+            .enable_http2()
+            .wrap_connector(connector),
+    };
+
+    let mut client_builder = Client::builder();
+    client_builder.http2_only(network_settings.http2_only);
+    if let Some(max_idle) = network_settings.pool_max_idle_per_host {
+        client_builder.pool_max_idle_per_host(max_idle);
+    }
+
+    client_builder.build::<_, Body>(https)
 }
 
 pub struct UriBuilder {
@@ -1,8 +1,11 @@
 pub mod inner_request_manager;
 pub mod more_or_equals_available_requests_count_trigger_scheduler;
+pub mod polling_scheduler;
 pub mod pre_reserved_group;
 pub mod request;
+pub mod requests_state_file;
 pub mod requests_timeout_manager;
 pub mod requests_timeout_manager_factory;
+pub mod shared_rate_budget;
 pub mod timeout_manager;
 pub mod triggers;
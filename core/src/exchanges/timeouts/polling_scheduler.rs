@@ -0,0 +1,146 @@
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::infrastructure::{FutureOutcome, SpawnFutureFlags};
+use rand::Rng;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
+use crate::infrastructure::spawn_future;
+use crate::settings::PollingSchedulerSettings;
+
+/// Fractional parts of multiples of golden ratio are spread evenly over [0, 1)
+/// for any number of multiples, so phases of polls don't collide however many tasks are scheduled
+const GOLDEN_RATIO_FRACTION: f64 = 0.618_033_988_749_895;
+
+/// Central scheduler of periodic REST polls (balances, order info and others).
+/// Polls with equal periods are smeared across the period by distinct phases, every period
+/// is randomly deviated by jitter, and polls of exchange are postponed while its rate limit
+/// budget is mostly used, so periodic requests don't form synchronized bursts
+pub struct PollingScheduler {
+    settings: PollingSchedulerSettings,
+    timeout_manager: Arc<TimeoutManager>,
+    scheduled_tasks_count: AtomicUsize,
+}
+
+impl PollingScheduler {
+    pub fn new(
+        settings: PollingSchedulerSettings,
+        timeout_manager: Arc<TimeoutManager>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            timeout_manager,
+            scheduled_tasks_count: AtomicUsize::new(0),
+        })
+    }
+
+    /// Repeat `action` endlessly with jittered `period`. Polls of specified exchange account
+    /// are coordinated with its requests timeout manager
+    pub fn spawn_polling<F, Fut>(
+        self: &Arc<Self>,
+        name: &str,
+        exchange_account_id: Option<ExchangeAccountId>,
+        period: Duration,
+        action: F,
+    ) -> tokio::task::JoinHandle<FutureOutcome>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let task_index = self.scheduled_tasks_count.fetch_add(1, Ordering::Relaxed);
+        let phase = phase_offset(task_index, period);
+
+        let this = self.clone();
+        let name = name.to_owned();
+        let polling = async move {
+            sleep(phase).await;
+
+            let mut postponed_ticks = 0;
+            loop {
+                if this.should_postpone(exchange_account_id, postponed_ticks) {
+                    postponed_ticks += 1;
+                    log::info!("Polling {name} is postponed because of low rate limit budget");
+                } else {
+                    postponed_ticks = 0;
+                    action().await;
+                }
+
+                let jitter = rand::thread_rng().gen_range(-1.0..=1.0);
+                sleep(jittered_period(
+                    period,
+                    this.settings.jitter_percent,
+                    jitter,
+                ))
+                .await;
+            }
+        };
+
+        spawn_future(
+            &format!("Polling {name}"),
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            async move {
+                polling.await;
+                Ok(())
+            },
+        )
+    }
+
+    fn should_postpone(
+        &self,
+        exchange_account_id: Option<ExchangeAccountId>,
+        postponed_ticks: u32,
+    ) -> bool {
+        let exchange_account_id = match exchange_account_id {
+            Some(exchange_account_id) => exchange_account_id,
+            None => return false,
+        };
+
+        postponed_ticks < self.settings.max_postponed_ticks
+            && self
+                .timeout_manager
+                .available_requests_percent(exchange_account_id)
+                < self.settings.min_available_requests_percent as usize
+    }
+}
+
+fn phase_offset(task_index: usize, period: Duration) -> Duration {
+    period.mul_f64((task_index as f64 * GOLDEN_RATIO_FRACTION).fract())
+}
+
+/// `jitter` is a random value in range [-1, 1]
+fn jittered_period(period: Duration, jitter_percent: u8, jitter: f64) -> Duration {
+    period.mul_f64(1.0 + jitter * jitter_percent as f64 / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_are_spread_over_period() {
+        let period = Duration::from_secs(60);
+        let mut phases = (0..5)
+            .map(|index| phase_offset(index, period))
+            .collect::<Vec<_>>();
+        phases.sort();
+
+        assert_eq!(phases[0], Duration::ZERO);
+        assert!(phases.iter().all(|phase| *phase < period));
+        // 5 phases leave no gap longer than 2 / 5 of period
+        for pair in phases.windows(2) {
+            assert!(pair[1] - pair[0] <= period.mul_f64(0.4));
+        }
+    }
+
+    #[test]
+    fn period_is_deviated_by_jitter() {
+        let period = Duration::from_secs(10);
+        assert_eq!(jittered_period(period, 20, 0.0), period);
+        assert_eq!(jittered_period(period, 20, 1.0), Duration::from_secs(12));
+        assert_eq!(jittered_period(period, 20, -1.0), Duration::from_secs(8));
+        assert_eq!(jittered_period(period, 0, 1.0), period);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
+use serde::Serialize;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 #[allow(dead_code)]
 pub struct PreReservedGroup {
     pub(crate) id: RequestGroupId,
@@ -1,9 +1,10 @@
 use mmb_utils::DateTime;
+use serde::{Deserialize, Serialize};
 
 use crate::exchanges::general::request_type::RequestType;
 use crate::exchanges::timeouts::requests_timeout_manager::RequestGroupId;
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Request {
     pub(crate) request_type: RequestType,
     pub(crate) allowed_start_time: DateTime,
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::task::spawn_blocking;
+use tokio::time::sleep;
+
+use crate::exchanges::timeouts::request::Request;
+use crate::exchanges::timeouts::timeout_manager::{now, TimeoutManager};
+use crate::infrastructure::spawn_future_ok;
+use crate::settings::TimeoutManagerStateSettings;
+
+/// Requests of exchange account made during the last period of its rate limit
+#[derive(Debug, Serialize, Deserialize)]
+struct RecentRequests {
+    exchange_account_id: ExchangeAccountId,
+    requests: Vec<Request>,
+}
+
+fn load(path: &Path) -> Result<Vec<RecentRequests>> {
+    let content = fs::read(path)
+        .with_context(|| format!("can't read timeout manager state file {}", path.display()))?;
+    serde_json::from_slice(&content)
+        .with_context(|| format!("can't parse timeout manager state file {}", path.display()))
+}
+
+/// State is written to temporary file first, so state file isn't broken if process is killed
+fn save(path: &Path, recent_requests: &[RecentRequests]) -> Result<()> {
+    let content = serde_json::to_vec(recent_requests)?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)
+        .with_context(|| format!("can't write file {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("can't rename {} to {}", tmp_path.display(), path.display()))
+}
+
+fn collect_recent_requests(timeout_manager: &TimeoutManager) -> Vec<RecentRequests> {
+    let current_time = now();
+    timeout_manager
+        .requests_timeout_managers()
+        .map(|(&exchange_account_id, manager)| RecentRequests {
+            exchange_account_id,
+            requests: manager.recent_requests(current_time),
+        })
+        .collect()
+}
+
+/// Restore requests saved before restart. Missing or broken state file isn't an error,
+/// engine starts with empty budget as without persistence
+pub(crate) fn restore_requests(timeout_manager: &TimeoutManager, path: &Path) {
+    if !path.exists() {
+        log::info!(
+            "Timeout manager state file {} doesn't exist, nothing to restore",
+            path.display()
+        );
+        return;
+    }
+
+    let recent_requests = match load(path) {
+        Ok(recent_requests) => recent_requests,
+        Err(error) => {
+            log::warn!("Unable to restore timeout manager state: {error:?}");
+            return;
+        }
+    };
+
+    let current_time = now();
+    for RecentRequests {
+        exchange_account_id,
+        requests,
+    } in recent_requests
+    {
+        match timeout_manager.requests_timeout_manager(exchange_account_id) {
+            Some(manager) => manager.restore_requests(requests, current_time),
+            None => log::info!("Requests of {exchange_account_id} aren't restored because exchange account isn't configured"),
+        }
+    }
+}
+
+pub(crate) fn start_saving_requests(
+    timeout_manager: Arc<TimeoutManager>,
+    settings: TimeoutManagerStateSettings,
+) {
+    let action = async move {
+        loop {
+            sleep(settings.save_period()).await;
+
+            let recent_requests = collect_recent_requests(&timeout_manager);
+            let path = settings.path.clone();
+            let result = spawn_blocking(move || save(&path, &recent_requests)).await;
+
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => log::warn!("Unable to save timeout manager state: {error:?}"),
+                Err(error) => log::warn!("Saving timeout manager state panicked: {error:?}"),
+            }
+        }
+    };
+
+    let _ = spawn_future_ok(
+        "Saving timeout manager state",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        action,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::request_type::RequestType;
+    use crate::exchanges::timeouts::requests_timeout_manager_factory::{
+        RequestTimeoutArguments, RequestsTimeoutManagerFactory,
+    };
+    use std::collections::HashMap;
+
+    fn timeout_manager(exchange_account_id: ExchangeAccountId) -> Arc<TimeoutManager> {
+        let requests_timeout_manager = RequestsTimeoutManagerFactory::from_requests_per_period(
+            RequestTimeoutArguments::from_requests_per_minute(5),
+            exchange_account_id,
+        );
+
+        TimeoutManager::new(HashMap::from([(
+            exchange_account_id,
+            requests_timeout_manager,
+        )]))
+    }
+
+    #[test]
+    fn requests_are_restored_from_state_file() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let path = std::env::temp_dir().join(format!(
+            "timeout_manager_state_{}.json",
+            uuid::Uuid::new_v4()
+        ));
+
+        let timeout_manager_before_restart = timeout_manager(exchange_account_id);
+        for _ in 0..3 {
+            assert!(timeout_manager_before_restart
+                .try_reserve_instant(exchange_account_id, RequestType::CreateOrder));
+        }
+        save(
+            &path,
+            &collect_recent_requests(&timeout_manager_before_restart),
+        )
+        .expect("in test");
+
+        let timeout_manager = timeout_manager(exchange_account_id);
+        restore_requests(&timeout_manager, &path);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            timeout_manager
+                .requests_timeout_manager(exchange_account_id)
+                .expect("in test")
+                .used_requests_count(now()),
+            3
+        );
+    }
+}
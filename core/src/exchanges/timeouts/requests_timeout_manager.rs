@@ -7,6 +7,7 @@ use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::{FutureOutcome, SpawnFutureFlags};
 use mmb_utils::{DateTime, OPERATION_CANCELED_MSG};
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
 use tokio::time::timeout;
 use uuid::Uuid;
@@ -22,7 +23,7 @@ use crate::{exchanges::general::request_type::RequestType, infrastructure::spawn
 use mmb_domain::market::ExchangeAccountId;
 use mmb_utils::time::ToStdExpected;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct RequestGroupId(Uuid);
 
 impl RequestGroupId {
@@ -39,6 +40,18 @@ impl Display for RequestGroupId {
 
 pub struct RequestsTimeoutManager {
     inner: Mutex<InnerRequestsTimeoutManager>,
+    /// Rate limit of exchange, own limit can be less if it is shared with other processes
+    requests_limit: usize,
+}
+
+/// Reserved requests and groups of exchange account at the moment
+#[derive(Clone, Serialize)]
+pub struct RequestsTimeoutState {
+    pub exchange_account_id: ExchangeAccountId,
+    pub requests_per_period: usize,
+    pub period_duration_ms: i64,
+    pub requests: Vec<Request>,
+    pub pre_reserved_groups: Vec<PreReservedGroup>,
 }
 
 impl RequestsTimeoutManager {
@@ -65,6 +78,7 @@ impl RequestsTimeoutManager {
 
         Arc::new(Self {
             inner: Mutex::new(inner),
+            requests_limit: requests_per_period,
         })
     }
 
@@ -382,6 +396,86 @@ impl RequestsTimeoutManager {
     pub fn get_period_duration(&self) -> std::time::Duration {
         self.inner.lock().get_period_duration().to_std_expected()
     }
+
+    /// Count of own requests in current period including scheduled ones
+    pub fn used_requests_count(&self, current_time: DateTime) -> usize {
+        let mut inner = self.inner.lock();
+        let current_time = inner.get_non_decreasing_time(current_time);
+        inner.remove_outdated_requests(current_time);
+
+        inner.requests.len()
+    }
+
+    /// Requests of current period, they are saved to be restored after restart
+    pub fn recent_requests(&self, current_time: DateTime) -> Vec<Request> {
+        let mut inner = self.inner.lock();
+        let current_time = inner.get_non_decreasing_time(current_time);
+        inner.remove_outdated_requests(current_time);
+
+        inner.requests.clone()
+    }
+
+    /// Restore requests made before restart, so they are counted in the current period.
+    /// Pre-reserved groups aren't restored, so restored requests don't belong to any group
+    pub fn restore_requests(&self, requests: Vec<Request>, current_time: DateTime) {
+        let mut inner = self.inner.lock();
+        let current_time = inner.get_non_decreasing_time(current_time);
+
+        for request in requests {
+            let _ = inner.add_request(request.request_type, request.allowed_start_time, None);
+        }
+        inner.remove_outdated_requests(current_time);
+
+        log::info!(
+            "{} requests of {} are restored",
+            inner.requests.len(),
+            inner.exchange_account_id
+        );
+    }
+
+    /// Restrict own requests to the part of exchange rate limit which isn't used
+    /// by other processes sharing API key, but no more than `max_share_percent` of it
+    pub fn apply_external_usage(&self, external_requests_count: usize, max_share_percent: u8) {
+        let max_share = self.requests_limit * max_share_percent as usize / 100;
+        let requests_per_period = self
+            .requests_limit
+            .saturating_sub(external_requests_count)
+            .min(max_share)
+            // at least one request should be available to not block requests forever
+            .max(1);
+
+        let mut inner = self.inner.lock();
+        if inner.requests_per_period != requests_per_period {
+            log::trace!(
+                "Requests limit of {} is changed to {requests_per_period} of {} because of {external_requests_count} requests of other processes",
+                inner.exchange_account_id,
+                self.requests_limit
+            );
+            inner.requests_per_period = requests_per_period;
+        }
+    }
+
+    /// Percent of requests of current period which are still available for reservation
+    pub fn available_requests_percent(&self, current_time: DateTime) -> usize {
+        let mut inner = self.inner.lock();
+        let current_time = inner.get_non_decreasing_time(current_time);
+        inner.remove_outdated_requests(current_time);
+
+        let available_requests_count = inner.get_available_requests_count_at_present(current_time);
+        available_requests_count * 100 / inner.requests_per_period.max(1)
+    }
+
+    pub fn state(&self) -> RequestsTimeoutState {
+        let inner = self.inner.lock();
+
+        RequestsTimeoutState {
+            exchange_account_id: inner.exchange_account_id,
+            requests_per_period: inner.requests_per_period,
+            period_duration_ms: inner.period_duration.num_milliseconds(),
+            requests: inner.requests.clone(),
+            pre_reserved_groups: inner.pre_reserved_groups.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
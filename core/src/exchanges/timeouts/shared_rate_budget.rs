@@ -0,0 +1,268 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+use uuid::Uuid;
+
+use crate::exchanges::timeouts::requests_timeout_manager::RequestsTimeoutManager;
+use crate::infrastructure::spawn_future_ok;
+use crate::settings::SharedRateBudgetSettings;
+
+/// Publishes usage of the process and returns sum of fresh usages of other processes.
+/// Usage is stored as `{requests_count}:{update_time_ms}` in hash field of the process
+const SYNC_USAGE_SCRIPT: &str = r#"
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2] .. ':' .. ARGV[3])
+redis.call('PEXPIRE', KEYS[1], ARGV[4])
+local others = 0
+local entries = redis.call('HGETALL', KEYS[1])
+for i = 1, #entries, 2 do
+    if entries[i] ~= ARGV[1] then
+        local usage, time = string.match(entries[i + 1], '(%d+):(%d+)')
+        if usage and tonumber(ARGV[3]) - tonumber(time) <= tonumber(ARGV[4]) then
+            others = others + tonumber(usage)
+        end
+    end
+end
+return others
+"#;
+
+/// Usage of process is ignored if it isn't updated during so many sync periods
+const STALE_SYNC_PERIODS: u64 = 3;
+
+/// Minimal Redis connection which is enough for evaluating of scripts with integer result
+struct RedisConnection {
+    stream: BufReader<TcpStream>,
+}
+
+impl RedisConnection {
+    async fn connect(address: &str) -> Result<Self> {
+        let stream = TcpStream::connect(address)
+            .await
+            .with_context(|| format!("Unable to connect to Redis {address}"))?;
+
+        Ok(Self {
+            stream: BufReader::new(stream),
+        })
+    }
+
+    async fn eval_integer(&mut self, script: &str, keys: &[&str], args: &[&str]) -> Result<i64> {
+        let keys_count = keys.len().to_string();
+        let mut parts = vec!["EVAL", script, &keys_count];
+        parts.extend_from_slice(keys);
+        parts.extend_from_slice(args);
+
+        self.stream
+            .get_mut()
+            .write_all(&encode_command(&parts))
+            .await
+            .context("Unable to send command to Redis")?;
+
+        let mut reply = String::new();
+        let read_count = self
+            .stream
+            .read_line(&mut reply)
+            .await
+            .context("Unable to read reply of Redis")?;
+        if read_count == 0 {
+            bail!("Redis connection is closed");
+        }
+
+        parse_integer_reply(&reply)
+    }
+}
+
+fn encode_command(parts: &[&str]) -> Vec<u8> {
+    let mut command = format!("*{}\r\n", parts.len());
+    for part in parts {
+        command.push_str(&format!("${}\r\n{part}\r\n", part.len()));
+    }
+
+    command.into_bytes()
+}
+
+fn parse_integer_reply(reply: &str) -> Result<i64> {
+    let reply = reply.trim_end();
+    match reply.split_at(reply.len().min(1)) {
+        (":", value) => value
+            .parse()
+            .with_context(|| format!("Unable to parse integer reply of Redis {reply}")),
+        ("-", error) => bail!("Redis error: {error}"),
+        _ => bail!("Unexpected reply of Redis: {reply}"),
+    }
+}
+
+/// Processes using the same API key get the same budget name without extra configuration
+fn default_budget_key(exchange_account_id: ExchangeAccountId, api_key: &str) -> String {
+    let api_key_hash = Sha256::digest(api_key.as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    format!(
+        "mmb:rate_budget:{}:{api_key_hash}",
+        exchange_account_id.exchange_id
+    )
+}
+
+/// Synchronization of exchange rate limit with other processes sharing API key.
+/// If Redis is unavailable, the last applied limit is kept
+struct SharedRateBudget {
+    exchange_account_id: ExchangeAccountId,
+    settings: SharedRateBudgetSettings,
+    budget_key: String,
+    instance_id: String,
+    requests_timeout_manager: Arc<RequestsTimeoutManager>,
+    connection: Option<RedisConnection>,
+}
+
+impl SharedRateBudget {
+    async fn run(mut self) {
+        log::info!(
+            "Rate limit of {} is shared by budget {}",
+            self.exchange_account_id,
+            self.budget_key
+        );
+
+        loop {
+            let sync_result = timeout(self.settings.sync_period(), self.sync())
+                .await
+                .unwrap_or_else(|_| bail!("Synchronization timeout is exceeded"));
+
+            if let Err(error) = sync_result {
+                self.connection = None;
+                log::warn!(
+                    "Unable to sync shared rate budget {} of {}: {error:?}",
+                    self.budget_key,
+                    self.exchange_account_id
+                );
+            }
+
+            sleep(self.settings.sync_period()).await;
+        }
+    }
+
+    async fn sync(&mut self) -> Result<()> {
+        let used_requests_count = self
+            .requests_timeout_manager
+            .used_requests_count(Utc::now());
+
+        let connection = match &mut self.connection {
+            Some(connection) => connection,
+            None => self
+                .connection
+                .insert(RedisConnection::connect(&self.settings.redis_address).await?),
+        };
+
+        let stale_timeout_ms = self.settings.sync_period_ms * STALE_SYNC_PERIODS;
+        let external_requests_count = connection
+            .eval_integer(
+                SYNC_USAGE_SCRIPT,
+                &[&self.budget_key],
+                &[
+                    &self.instance_id,
+                    &used_requests_count.to_string(),
+                    &Utc::now().timestamp_millis().to_string(),
+                    &stale_timeout_ms.to_string(),
+                ],
+            )
+            .await?;
+
+        self.requests_timeout_manager.apply_external_usage(
+            external_requests_count.max(0) as usize,
+            self.settings.max_share_percent,
+        );
+
+        Ok(())
+    }
+}
+
+pub(crate) fn start_shared_rate_budget(
+    exchange_account_id: ExchangeAccountId,
+    api_key: &str,
+    settings: SharedRateBudgetSettings,
+    requests_timeout_manager: Arc<RequestsTimeoutManager>,
+) {
+    let budget_key = settings
+        .key
+        .clone()
+        .unwrap_or_else(|| default_budget_key(exchange_account_id, api_key));
+
+    let shared_rate_budget = SharedRateBudget {
+        exchange_account_id,
+        settings,
+        budget_key,
+        instance_id: Uuid::new_v4().to_string(),
+        requests_timeout_manager,
+        connection: None,
+    };
+
+    let _ = spawn_future_ok(
+        &format!("Shared rate budget of {exchange_account_id}"),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        shared_rate_budget.run(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::timeouts::requests_timeout_manager_factory::{
+        RequestTimeoutArguments, RequestsTimeoutManagerFactory,
+    };
+
+    #[test]
+    fn redis_command_is_encoded() {
+        let command = encode_command(&["EVAL", "return 1", "0"]);
+        assert_eq!(
+            String::from_utf8(command).expect("in test"),
+            "*3\r\n$4\r\nEVAL\r\n$8\r\nreturn 1\r\n$1\r\n0\r\n"
+        );
+    }
+
+    #[test]
+    fn redis_reply_is_parsed() {
+        assert_eq!(parse_integer_reply(":42\r\n").expect("in test"), 42);
+        assert!(parse_integer_reply("-ERR unknown command\r\n").is_err());
+        assert!(parse_integer_reply("+OK\r\n").is_err());
+        assert!(parse_integer_reply("").is_err());
+    }
+
+    #[test]
+    fn own_limit_is_reduced_by_external_usage() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let requests_timeout_manager = RequestsTimeoutManagerFactory::from_requests_per_period(
+            RequestTimeoutArguments::from_requests_per_minute(100),
+            exchange_account_id,
+        );
+        let requests_per_period =
+            |manager: &RequestsTimeoutManager| manager.state().requests_per_period;
+
+        requests_timeout_manager.apply_external_usage(30, 100);
+        assert_eq!(requests_per_period(&requests_timeout_manager), 70);
+
+        requests_timeout_manager.apply_external_usage(10, 50);
+        assert_eq!(requests_per_period(&requests_timeout_manager), 50);
+
+        requests_timeout_manager.apply_external_usage(150, 100);
+        assert_eq!(requests_per_period(&requests_timeout_manager), 1);
+    }
+
+    #[test]
+    fn budget_key_depends_on_api_key_only() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        assert_ne!(
+            default_budget_key(exchange_account_id, "key1"),
+            default_budget_key(exchange_account_id, "key2")
+        );
+        assert_eq!(
+            default_budget_key(exchange_account_id, "key1"),
+            default_budget_key(ExchangeAccountId::new("Binance", 1), "key1")
+        );
+    }
+}
@@ -16,7 +16,7 @@ use chrono::Utc;
 
 use crate::exchanges::general::request_type::RequestType;
 use crate::exchanges::timeouts::requests_timeout_manager::{
-    RequestGroupId, RequestsTimeoutManager,
+    RequestGroupId, RequestsTimeoutManager, RequestsTimeoutState,
 };
 use mmb_domain::market::ExchangeAccountId;
 
@@ -121,6 +121,33 @@ impl TimeoutManager {
             .with_expect(|| format!("Can't find timeout manger for {exchange_account_id}"))
             .get_period_duration()
     }
+
+    pub fn available_requests_percent(&self, exchange_account_id: ExchangeAccountId) -> usize {
+        self.inner
+            .get(&exchange_account_id)
+            .with_expect(|| format!("Can't find timeout manger for {exchange_account_id}"))
+            .available_requests_percent(now())
+    }
+
+    pub fn requests_timeout_manager(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> Option<&Arc<RequestsTimeoutManager>> {
+        self.inner.get(&exchange_account_id)
+    }
+
+    pub fn requests_timeout_managers(
+        &self,
+    ) -> impl Iterator<Item = (&ExchangeAccountId, &Arc<RequestsTimeoutManager>)> {
+        self.inner.iter()
+    }
+
+    /// States of requests timeout managers of all exchange accounts
+    pub fn state(&self) -> Vec<RequestsTimeoutState> {
+        let mut states = self.inner.values().map(|x| x.state()).collect::<Vec<_>>();
+        states.sort_by_key(|x| x.exchange_account_id.to_string());
+        states
+    }
 }
 
 pub fn now() -> DateTime {
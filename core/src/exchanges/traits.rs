@@ -3,7 +3,7 @@ use super::{
     general::order::get_order_trades::OrderTrade,
     timeouts::requests_timeout_manager_factory::RequestTimeoutArguments,
 };
-use crate::connectivity::WebSocketRole;
+use crate::connectivity::{WebSocketCompression, WebSocketKeepAlive, WebSocketRole};
 use crate::exchanges::general::exchange::BoxExchangeClient;
 use crate::exchanges::general::exchange::{Exchange, RequestResult};
 use crate::exchanges::general::features::ExchangeFeatures;
@@ -15,21 +15,28 @@ use crate::settings::ExchangeSettings;
 use anyhow::Result;
 use async_trait::async_trait;
 use dashmap::DashMap;
-use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions, MetricsEventInfo};
+use mmb_domain::events::{
+    CashFlowEvent, EventSourceType, ExchangeBalancesAndPositions, MetricsEventInfo,
+};
 use mmb_domain::events::{ExchangeEvent, Trade};
 use mmb_domain::exchanges::symbol::{BeforeAfter, Symbol};
-use mmb_domain::market::CurrencyId;
+use mmb_domain::market::{CurrencyAliases, CurrencyId};
 use mmb_domain::market::{
     CurrencyCode, CurrencyPair, ExchangeAccountId, ExchangeErrorType, ExchangeId,
     SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
-use mmb_domain::order::snapshot::Price;
+use mmb_domain::order::snapshot::{Amount, Price};
 use mmb_domain::order::snapshot::{
-    ClientOrderId, ExchangeOrderId, OrderInfo, OrderInfoExtensionData, OrderSide,
+    ClientOrderId, ExchangeOrderId, OrderInfo, OrderInfoExtensionData, OrderSide, UserOrder,
 };
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_domain::transfer::{
+    DepositAddress, ExternalTransferInfo, NetworkStatus, WithdrawalRequest,
+};
 use mmb_utils::DateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::sync::Arc;
@@ -142,6 +149,148 @@ pub trait ExchangeClient: Support {
     /// Need for server time latency calculating
     /// Should return server time with millis accuracy
     async fn get_server_time(&self) -> Option<Result<i64>>;
+
+    /// Set leverage for derivative symbol. Returns leverage applied by exchange
+    /// Should return `None` if exchange doesn't support it
+    async fn set_leverage(
+        &self,
+        _currency_pair: CurrencyPair,
+        _leverage: Decimal,
+    ) -> Option<Result<Decimal>> {
+        None
+    }
+
+    /// Borrow currency on margin account
+    /// Should return `None` if exchange doesn't support it
+    async fn borrow(&self, _currency_code: CurrencyCode, _amount: Amount) -> Option<Result<()>> {
+        None
+    }
+
+    /// Repay borrowed currency on margin account, accrued interest is repaid first
+    /// Should return `None` if exchange doesn't support it
+    async fn repay(&self, _currency_code: CurrencyCode, _amount: Amount) -> Option<Result<()>> {
+        None
+    }
+
+    /// Change trigger parameters of active conditional order keeping its client order id.
+    /// Returns exchange order id of amended order, which is new if exchange replaces the order
+    /// atomically instead of changing it in place
+    /// Should return `None` if exchange doesn't support it
+    async fn amend_conditional_order(
+        &self,
+        _order: &OrderRef,
+        _exchange_order_id: &ExchangeOrderId,
+        _new_user_order: &UserOrder,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        None
+    }
+
+    /// Max count of orders which can be created by one request of `create_orders_batch`
+    /// Should return `None` if exchange doesn't support batch creation of orders
+    fn max_orders_batch_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Create several orders by one request, results should be in the same order as orders
+    async fn create_orders_batch(
+        &self,
+        _orders: &[OrderRef],
+    ) -> Result<Vec<CreateOrderResult>, ExchangeError> {
+        Err(ExchangeError::unknown(
+            "Batch creation of orders isn't supported",
+        ))
+    }
+
+    /// Change price and/or amount of active limit or stop-loss order keeping its ids.
+    /// Price of stop-loss order is its stop price
+    /// Should return `None` if exchange doesn't support it
+    async fn modify_order(
+        &self,
+        _order: &OrderRef,
+        _exchange_order_id: &ExchangeOrderId,
+        _new_price: Option<Price>,
+        _new_amount: Option<Amount>,
+    ) -> Option<Result<(), ExchangeError>> {
+        None
+    }
+
+    /// Create limit and stop orders linked as one-cancels-other, returns their exchange order ids
+    /// Should return `None` if exchange doesn't support it
+    async fn create_oco_order(
+        &self,
+        _limit_order: &OrderRef,
+        _stop_order: &OrderRef,
+    ) -> Option<Result<(ExchangeOrderId, ExchangeOrderId), ExchangeError>> {
+        None
+    }
+
+    /// Request public trades starting from specified trade id, used to backfill missed prints
+    /// Should return `None` if exchange doesn't support it
+    async fn get_trades_from_id(
+        &self,
+        _currency_pair: CurrencyPair,
+        _from_trade_id: u64,
+    ) -> Option<Result<Vec<Trade>>> {
+        None
+    }
+
+    /// Request snapshot of order book with at least `depth` levels on every side by REST,
+    /// used to check consistency of locally maintained order book
+    /// Should return `None` if exchange doesn't support it
+    async fn get_order_book_snapshot(
+        &self,
+        _currency_pair: CurrencyPair,
+        _depth: usize,
+    ) -> Option<Result<OrderBookData>> {
+        None
+    }
+
+    /// Arm dead man's switch of exchange: all orders are cancelled by exchange itself
+    /// if switch isn't re-armed during `timeout`. Zero `timeout` disarms the switch
+    /// Should return `None` if exchange doesn't support it
+    async fn cancel_all_after(&self, _timeout: Duration) -> Option<Result<()>> {
+        None
+    }
+
+    /// Methods for moving inventory between exchanges.
+    /// Should return `None` if exchange doesn't support it
+    async fn get_network_status(
+        &self,
+        _currency_code: CurrencyCode,
+        _network: &str,
+    ) -> Option<Result<NetworkStatus>> {
+        None
+    }
+
+    async fn get_deposit_address(
+        &self,
+        _currency_code: CurrencyCode,
+        _network: &str,
+    ) -> Option<Result<DepositAddress>> {
+        None
+    }
+
+    /// Returns id of created withdrawal
+    async fn withdraw(&self, _request: &WithdrawalRequest) -> Option<Result<String>> {
+        None
+    }
+
+    async fn get_withdrawal(
+        &self,
+        _currency_code: CurrencyCode,
+        _withdrawal_id: &str,
+    ) -> Option<Result<ExternalTransferInfo>> {
+        None
+    }
+
+    /// Should return `Ok(None)` if deposit with specified transaction id isn't visible on exchange yet
+    async fn get_deposit(
+        &self,
+        _currency_code: CurrencyCode,
+        _tx_id: &str,
+    ) -> Option<Result<Option<ExternalTransferInfo>>> {
+        None
+    }
 }
 
 pub type OrderCreatedCb =
@@ -158,6 +307,8 @@ pub type SendWebsocketMessageCb = Box<dyn Fn(WebSocketRole, String) -> Result<()
 
 pub type HandleMetricsCb = Box<dyn Fn(MetricsEventInfo) + Send + Sync>;
 
+pub type HandleCashFlowCb = Box<dyn Fn(CashFlowEvent) + Send + Sync>;
+
 #[async_trait]
 pub trait Support: Send + Sync {
     /// Needed to call the `downcast_ref` method
@@ -181,12 +332,62 @@ pub trait Support: Send + Sync {
 
     fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb);
 
+    /// Should be implemented for exchanges with funding payments or periodically accrued fees
+    fn set_handle_cash_flow_callback(&mut self, _callback: HandleCashFlowCb) {}
+
     fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>);
 
     fn is_websocket_enabled(&self, role: WebSocketRole) -> bool;
 
+    /// Called before every connection attempt, so URL can be fetched dynamically
+    /// (e.g. with one-time connection token requested from REST API)
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url>;
 
+    /// Called after `create_ws_url`, so keep-alive parameters can be taken from the same
+    /// response as dynamic URL
+    fn get_websocket_keep_alive(&self, _role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        None
+    }
+
+    /// Additional headers of websocket handshake, called before every connection attempt,
+    /// so headers can be signed with fresh nonce
+    fn get_websocket_headers(&self, _role: WebSocketRole) -> Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+
+    /// Compression of binary websocket messages, they are decompressed before
+    /// `on_websocket_message`. Binary messages are ignored if it isn't set
+    fn get_websocket_compression(&self, _role: WebSocketRole) -> Option<WebSocketCompression> {
+        None
+    }
+
+    /// Maximum count of currency pairs which market data can be received by single connection
+    /// of main websocket. Traded currency pairs exceeding the limit are sharded across several
+    /// connections, so main websocket should receive market data only
+    fn max_websocket_currency_pairs(&self) -> Option<usize> {
+        None
+    }
+
+    /// Url of main websocket connection which receives market data of specified currency pairs.
+    /// Ordinary url of main websocket is used by default, so exchanges which subscribe
+    /// by messages only need `create_market_data_subscribe_messages`
+    async fn create_market_data_ws_url(
+        &self,
+        _currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<Url> {
+        self.create_ws_url(WebSocketRole::Main).await
+    }
+
+    /// Messages sent over connection of market data shard right after it's opened to subscribe
+    /// to specified currency pairs. While subscriptions are sharded, `on_connected` shouldn't
+    /// subscribe to market data of all traded currency pairs
+    fn create_market_data_subscribe_messages(
+        &self,
+        _currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
     fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair;
 
     fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode>;
@@ -207,6 +408,11 @@ pub trait Support: Send + Sync {
 
     fn get_settings(&self) -> &ExchangeSettings;
 
+    /// Aliases for unification of exchange currency codes, configured aliases override connector defaults
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        CurrencyAliases::new(&[], &self.get_settings().currency_aliases)
+    }
+
     fn get_initial_extension_data(&self) -> Option<Box<dyn OrderInfoExtensionData>> {
         None
     }
@@ -0,0 +1,456 @@
+use anyhow::{anyhow, bail, Context, Result};
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderSide, Price, ReservationId, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::order::amend::AmendOrderResult;
+use crate::execution_algorithms::parent_order::{ParentOrder, ParentOrderStatus};
+use crate::execution_algorithms::trigger::{update_prices, MarketPrices};
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+pub const CHASE_ALGORITHM: &str = "Chase";
+
+#[derive(Debug, Clone)]
+pub struct ChaseParameters {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    pub amount: Amount,
+    /// Order is kept so many price ticks inside the spread from the best price of its side,
+    /// 0 means joining the best price
+    pub ticks_inside: u32,
+    /// Maximum distance of order price from its initial price,
+    /// order stays at the limit if market moves farther
+    pub max_chase_distance: Price,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub strategy_name: String,
+}
+
+impl ChaseParameters {
+    fn validate(&self) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!(
+                "Amount of chase should be positive, but it is {}",
+                self.amount
+            );
+        }
+
+        if self.max_chase_distance < dec!(0) {
+            bail!(
+                "Max chase distance shouldn't be negative, but it is {}",
+                self.max_chase_distance
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Price of chasing order following the best price of its side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Chase {
+    side: OrderSide,
+    tick: Price,
+    ticks_inside: u32,
+    max_chase_distance: Price,
+    start_price: Option<Price>,
+}
+
+impl Chase {
+    fn new(side: OrderSide, tick: Price, ticks_inside: u32, max_chase_distance: Price) -> Self {
+        Chase {
+            side,
+            tick,
+            ticks_inside,
+            max_chase_distance,
+            start_price: None,
+        }
+    }
+
+    /// Price `ticks_inside` ticks inside the spread from the best price of order side.
+    /// Price never crosses the opposite side of order book, so order stays resting,
+    /// and never moves farther than max chase distance from the first price
+    fn target_price(&mut self, prices: &MarketPrices) -> Option<Price> {
+        let offset = self.tick * Decimal::from(self.ticks_inside);
+        let price = match self.side {
+            OrderSide::Buy => {
+                let price = prices.best_bid? + offset;
+                prices
+                    .best_ask
+                    .map_or(price, |ask| price.min(ask - self.tick))
+            }
+            OrderSide::Sell => {
+                let price = prices.best_ask? - offset;
+                prices
+                    .best_bid
+                    .map_or(price, |bid| price.max(bid + self.tick))
+            }
+        };
+
+        let start_price = *self.start_price.get_or_insert(price);
+        Some(match self.side {
+            OrderSide::Buy => price.min(start_price + self.max_chase_distance),
+            OrderSide::Sell => price.max(start_price - self.max_chase_distance),
+        })
+    }
+
+    /// The worst price order can be moved to, `None` until the first price is known
+    fn limit_price(&self) -> Option<Price> {
+        let start_price = self.start_price?;
+        Some(match self.side {
+            OrderSide::Buy => start_price + self.max_chase_distance,
+            OrderSide::Sell => (start_price - self.max_chase_distance).max(self.tick),
+        })
+    }
+}
+
+/// Child orders of chase
+struct ChaseOrders<'a> {
+    engine_context: &'a EngineContext,
+    exchange: &'a Arc<Exchange>,
+    symbol: &'a Arc<Symbol>,
+    parent_order: &'a ParentOrder,
+    configuration_descriptor: ConfigurationDescriptor,
+    strategy_name: &'a str,
+}
+
+impl ChaseOrders<'_> {
+    /// Balance is reserved once at the limit price of chase, so moving of order
+    /// never needs more balance than reserved
+    fn reserve(&self, limit_price: Price) -> Result<ReservationId> {
+        let exchange_account_id = self.parent_order.exchange_account_id();
+        let amount = self.parent_order.remaining_amount();
+        let reserve_parameters = ReserveParameters::new(
+            self.configuration_descriptor,
+            exchange_account_id,
+            self.symbol.clone(),
+            self.parent_order.side(),
+            limit_price,
+            amount,
+        );
+
+        self.engine_context
+            .balance_manager
+            .lock()
+            .try_reserve(&reserve_parameters, &mut None)
+            .with_context(|| {
+                format!("Can't reserve balance for chase of {amount} on {exchange_account_id}")
+            })
+    }
+
+    async fn create(&self, price: Price, reservation_id: ReservationId) -> Result<OrderRef> {
+        let amount = self
+            .symbol
+            .amount_round(self.parent_order.remaining_amount(), Round::Floor);
+        let min_amount = self.symbol.get_min_amount(price)?;
+        if amount < min_amount {
+            bail!("Remaining amount {amount} of chase is less than min amount {min_amount}");
+        }
+
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            self.parent_order.exchange_account_id(),
+            self.parent_order.currency_pair(),
+            self.parent_order.side(),
+            amount,
+            UserOrder::limit(price),
+            Some(reservation_id),
+            None,
+            self.strategy_name.to_owned(),
+        );
+
+        let stop_token = self.engine_context.lifetime_manager.stop_token();
+        let order = self
+            .exchange
+            .create_order(&header, None, stop_token)
+            .await
+            .with_context(|| {
+                format!("Failed to create order of chase {}", self.parent_order.id())
+            })?;
+        self.parent_order.add_child_order(order.clone());
+
+        Ok(order)
+    }
+
+    /// Move order to new price by amendment or cancel-replace if exchange can't amend orders
+    async fn move_order(&self, order: &OrderRef, price: Price) -> Result<OrderRef> {
+        let user_order = order
+            .user_order()
+            .context("Order of chase should be user order")?
+            .with_price(price);
+        let stop_token = self.engine_context.lifetime_manager.stop_token();
+        match self
+            .exchange
+            .amend_order(order, user_order, None, stop_token)
+            .await?
+        {
+            AmendOrderResult::Amended(order) => Ok(order),
+            AmendOrderResult::Replaced(order) => {
+                self.parent_order.add_child_order(order.clone());
+                Ok(order)
+            }
+        }
+    }
+
+    async fn cancel(&self, order: &OrderRef) -> Result<()> {
+        if order.is_finished() {
+            return Ok(());
+        }
+
+        let stop_token = self.engine_context.lifetime_manager.stop_token();
+        self.exchange
+            .wait_cancel_order(order.clone(), None, true, stop_token)
+            .await
+    }
+
+    /// Reservations of replaced orders are released by replacement,
+    /// so only the last order is reported as finished
+    fn release_reservation(
+        &self,
+        order: Option<&OrderRef>,
+        reservation_id: ReservationId,
+    ) -> Result<()> {
+        let mut balance_manager = self.engine_context.balance_manager.lock();
+        if let Some(order) = order {
+            balance_manager.order_was_finished(self.configuration_descriptor, &order.deep_clone());
+        }
+
+        if balance_manager.get_reservation(reservation_id).is_some() {
+            balance_manager.unreserve_rest(reservation_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Start chase: limit order is kept at the best price of its side (or `ticks_inside` ticks
+/// inside the spread) and moved after the market until it is filled. Order is moved by
+/// modification if exchange supports it, otherwise by cancel-replace. Market isn't chased
+/// farther than `max_chase_distance` from the initial price of order.
+/// Returns parent order for progress tracking, cancellation token stops chasing and
+/// cancels the order on exchange
+pub fn start_chase(
+    engine_context: Arc<EngineContext>,
+    parameters: ChaseParameters,
+    cancellation_token: CancellationToken,
+) -> Result<Arc<ParentOrder>> {
+    parameters.validate()?;
+    let exchange = engine_context
+        .exchanges
+        .get(&parameters.exchange_account_id)
+        .with_context(|| format!("Exchange {} isn't found", parameters.exchange_account_id))?
+        .clone();
+    let symbol = exchange.get_symbol(parameters.currency_pair)?;
+
+    let parent_order = ParentOrder::new(
+        CHASE_ALGORITHM,
+        parameters.exchange_account_id,
+        parameters.currency_pair,
+        parameters.side,
+        parameters.amount,
+    );
+
+    let market_account_id =
+        MarketAccountId::new(parameters.exchange_account_id, parameters.currency_pair);
+    let mut events = engine_context
+        .strategy_events_router
+        .subscribe([market_account_id]);
+    let action_name = format!(
+        "Chase {} with max distance {}",
+        parent_order.id(),
+        parameters.max_chase_distance
+    );
+    let parent = parent_order.clone();
+    let action = async move {
+        let chase_orders = ChaseOrders {
+            engine_context: &engine_context,
+            exchange: &exchange,
+            symbol: &symbol,
+            parent_order: &parent,
+            configuration_descriptor: parameters.configuration_descriptor,
+            strategy_name: &parameters.strategy_name,
+        };
+
+        let mut chase = Chase::new(
+            parameters.side,
+            symbol.price_precision.get_tick(),
+            parameters.ticks_inside,
+            parameters.max_chase_distance,
+        );
+        let mut prices = MarketPrices::default();
+        let mut local_snapshots = LocalSnapshotsService::default();
+        let mut reservation_id: Option<ReservationId> = None;
+        let mut order: Option<OrderRef> = None;
+        let result = loop {
+            if parent.remaining_amount().is_zero() {
+                break Ok(ParentOrderStatus::Completed);
+            }
+
+            let event = tokio::select! {
+                event = events.recv() => event,
+                _ = cancellation_token.when_cancelled() => {
+                    break Ok(ParentOrderStatus::Finished);
+                }
+            };
+
+            let event = match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped_count)) => {
+                    log::warn!("Chase missed {skipped_count} events, prices can be stale");
+                    continue;
+                }
+                Err(RecvError::Closed) => break Err(anyhow!("Events channel of chase is closed")),
+            };
+
+            if !update_prices(&mut prices, &mut local_snapshots, market_account_id, &event) {
+                continue;
+            }
+
+            let Some(price) = chase.target_price(&prices) else {
+                continue;
+            };
+            let price = symbol.price_round(price, Round::ToNearest);
+
+            let reservation_id = match reservation_id {
+                Some(reservation_id) => reservation_id,
+                None => {
+                    let limit_price = chase.limit_price().expect("start price is set");
+                    match chase_orders.reserve(limit_price) {
+                        Ok(id) => *reservation_id.insert(id),
+                        Err(error) => break Err(error),
+                    }
+                }
+            };
+
+            match order.clone() {
+                Some(current) if !current.is_finished() => {
+                    if current.price() == price {
+                        continue;
+                    }
+
+                    match chase_orders.move_order(&current, price).await {
+                        Ok(moved) => order = Some(moved),
+                        Err(error) => log::warn!(
+                            "Failed to move order of chase {} to price {price}: {error:?}",
+                            parent.id()
+                        ),
+                    }
+                }
+                _ => match chase_orders.create(price, reservation_id).await {
+                    Ok(created) => order = Some(created),
+                    Err(error) => break Err(error),
+                },
+            }
+        };
+
+        if let Some(order) = &order {
+            if let Err(error) = chase_orders.cancel(order).await {
+                log::error!("Failed to cancel order of chase {}: {error:?}", parent.id());
+            }
+        }
+
+        if let Some(reservation_id) = reservation_id {
+            chase_orders.release_reservation(order.as_ref(), reservation_id)?;
+        }
+
+        let status = match result {
+            Ok(ParentOrderStatus::Finished) if parent.remaining_amount().is_zero() => {
+                ParentOrderStatus::Completed
+            }
+            Ok(status) => status,
+            Err(_) => ParentOrderStatus::Failed,
+        };
+        parent.set_status(status);
+        log::info!("Chase is finished {:?}", parent.progress());
+
+        result.map(|_| ())
+    };
+
+    let _ = spawn_future(&action_name, SpawnFutureFlags::STOP_BY_TOKEN, action);
+
+    Ok(parent_order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(best_bid: Price, best_ask: Price) -> MarketPrices {
+        MarketPrices {
+            last_trade: None,
+            mark: None,
+            best_bid: Some(best_bid),
+            best_ask: Some(best_ask),
+        }
+    }
+
+    #[test]
+    fn buy_order_chases_rising_bid_up_to_max_distance() {
+        let mut chase = Chase::new(OrderSide::Buy, dec!(0.1), 1, dec!(1));
+
+        assert_eq!(
+            chase.target_price(&prices(dec!(100), dec!(101))),
+            Some(dec!(100.1))
+        );
+        assert_eq!(chase.limit_price(), Some(dec!(101.1)));
+        assert_eq!(
+            chase.target_price(&prices(dec!(100.5), dec!(101))),
+            Some(dec!(100.6))
+        );
+        assert_eq!(
+            chase.target_price(&prices(dec!(105), dec!(106))),
+            Some(dec!(101.1))
+        );
+        assert_eq!(
+            chase.target_price(&prices(dec!(99), dec!(100))),
+            Some(dec!(99.1))
+        );
+    }
+
+    #[test]
+    fn sell_order_chases_falling_ask() {
+        let mut chase = Chase::new(OrderSide::Sell, dec!(0.1), 0, dec!(2));
+
+        assert_eq!(
+            chase.target_price(&prices(dec!(99), dec!(100))),
+            Some(dec!(100))
+        );
+        assert_eq!(
+            chase.target_price(&prices(dec!(98), dec!(99))),
+            Some(dec!(99))
+        );
+        assert_eq!(
+            chase.target_price(&prices(dec!(90), dec!(91))),
+            Some(dec!(98))
+        );
+        assert_eq!(chase.limit_price(), Some(dec!(98)));
+    }
+
+    #[test]
+    fn order_doesnt_cross_spread() {
+        let mut chase = Chase::new(OrderSide::Buy, dec!(0.1), 5, dec!(10));
+
+        assert_eq!(
+            chase.target_price(&prices(dec!(100), dec!(100.2))),
+            Some(dec!(100.1))
+        );
+        assert_eq!(
+            chase.target_price(&prices(dec!(100), dec!(100.1))),
+            Some(dec!(100))
+        );
+    }
+}
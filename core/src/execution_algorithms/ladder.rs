@@ -0,0 +1,339 @@
+use anyhow::{bail, Context, Result};
+use futures::future::join_all;
+use itertools::Itertools;
+use mmb_domain::exchanges::symbol::Round;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderSide, Price, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+use crate::exchanges::general::order::batch::OrderCreating;
+use crate::execution_algorithms::parent_order::{ParentOrder, ParentOrderStatus};
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+pub const LADDER_ALGORITHM: &str = "Ladder";
+
+/// Distribution of ladder amount between its orders from the first price to the last one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LadderDistribution {
+    /// Amounts of orders grow linearly: the last order is `orders_count` times the first one
+    Linear,
+    /// Amount of every next order is `ratio` times amount of previous one,
+    /// ratio 1 splits amount evenly
+    Geometric { ratio: Decimal },
+}
+
+#[derive(Debug, Clone)]
+pub struct LadderParameters {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    /// Total amount of all orders of ladder
+    pub amount: Amount,
+    /// Price of the first order
+    pub start_price: Price,
+    /// Price of the last order
+    pub end_price: Price,
+    pub orders_count: u32,
+    pub distribution: LadderDistribution,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub strategy_name: String,
+}
+
+impl LadderParameters {
+    fn validate(&self) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!(
+                "Amount of ladder should be positive, but it is {}",
+                self.amount
+            );
+        }
+
+        if self.orders_count == 0 {
+            bail!("Orders count of ladder should be positive");
+        }
+
+        if self.start_price <= dec!(0) || self.end_price <= dec!(0) {
+            bail!(
+                "Prices of ladder should be positive, but they are {} and {}",
+                self.start_price,
+                self.end_price
+            );
+        }
+
+        if let LadderDistribution::Geometric { ratio } = self.distribution {
+            if ratio <= dec!(0) {
+                bail!("Ratio of geometric ladder should be positive, but it is {ratio}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prices and amounts of ladder orders before rounding by symbol
+fn ladder_levels(parameters: &LadderParameters) -> Vec<(Price, Amount)> {
+    let count = parameters.orders_count;
+    let price_step = match count {
+        1 => dec!(0),
+        _ => (parameters.end_price - parameters.start_price) / Decimal::from(count - 1),
+    };
+
+    let weights = (0..count)
+        .scan(dec!(1), |weight, index| {
+            let current = match parameters.distribution {
+                LadderDistribution::Linear => Decimal::from(index + 1),
+                LadderDistribution::Geometric { ratio } => {
+                    let current = *weight;
+                    *weight *= ratio;
+                    current
+                }
+            };
+            Some(current)
+        })
+        .collect_vec();
+    let weights_sum = weights.iter().sum::<Decimal>();
+
+    weights
+        .into_iter()
+        .enumerate()
+        .map(|(index, weight)| {
+            let price = parameters.start_price + price_step * Decimal::from(index);
+            (price, parameters.amount * weight / weights_sum)
+        })
+        .collect()
+}
+
+/// Place `orders_count` limit orders between two prices at once. Balance for the whole ladder
+/// is reserved by single reservation at the highest price of ladder, so ladder isn't placed
+/// if balance isn't enough for all its orders. Returns parent order which groups orders of ladder
+/// for progress tracking and cancellation by `cancel_ladder`.
+/// Rest of reservation is released when all orders of ladder are finished
+pub async fn place_ladder(
+    engine_context: Arc<EngineContext>,
+    parameters: LadderParameters,
+    cancellation_token: CancellationToken,
+) -> Result<Arc<ParentOrder>> {
+    parameters.validate()?;
+
+    let exchange_account_id = parameters.exchange_account_id;
+    let exchange = engine_context
+        .exchanges
+        .get(&exchange_account_id)
+        .with_context(|| format!("Exchange {exchange_account_id} isn't found"))?
+        .clone();
+    let symbol = exchange.get_symbol(parameters.currency_pair)?;
+
+    let levels = ladder_levels(&parameters)
+        .into_iter()
+        .map(|(price, amount)| {
+            let price = symbol.price_round(price, Round::ToNearest);
+            let amount = symbol.amount_round(amount, Round::Floor);
+            let min_amount = symbol.get_min_amount(price)?;
+            if amount < min_amount {
+                bail!("Amount {amount} of ladder order at price {price} is less than min amount {min_amount}");
+            }
+
+            Ok((price, amount))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let total_amount = levels.iter().map(|(_, amount)| amount).sum::<Amount>();
+    let reservation_price = levels
+        .iter()
+        .map(|&(price, _)| price)
+        .max()
+        .expect("ladder has at least one order");
+    let reserve_parameters = ReserveParameters::new(
+        parameters.configuration_descriptor,
+        exchange_account_id,
+        symbol.clone(),
+        parameters.side,
+        reservation_price,
+        total_amount,
+    );
+    let reservation_id = engine_context
+        .balance_manager
+        .lock()
+        .try_reserve(&reserve_parameters, &mut None)
+        .with_context(|| {
+            format!("Can't reserve balance for ladder of {total_amount} on {exchange_account_id}")
+        })?;
+
+    let parent_order = ParentOrder::new(
+        LADDER_ALGORITHM,
+        exchange_account_id,
+        parameters.currency_pair,
+        parameters.side,
+        total_amount,
+    );
+
+    let orders_creating = levels
+        .iter()
+        .map(|&(price, amount)| {
+            OrderCreating::new(OrderHeader::with_user_order(
+                ClientOrderId::unique_id(),
+                exchange_account_id,
+                parameters.currency_pair,
+                parameters.side,
+                amount,
+                UserOrder::limit(price),
+                Some(reservation_id),
+                None,
+                parameters.strategy_name.clone(),
+            ))
+        })
+        .collect_vec();
+
+    for result in exchange
+        .create_orders_batch(orders_creating, cancellation_token)
+        .await
+    {
+        match result {
+            Ok(order) => parent_order.add_child_order(order),
+            Err(error) => log::warn!(
+                "Failed to create order of ladder {}: {error:?}",
+                parent_order.id()
+            ),
+        }
+    }
+
+    let child_orders = parent_order.child_orders();
+    if child_orders.is_empty() {
+        parent_order.set_status(ParentOrderStatus::Failed);
+        engine_context
+            .balance_manager
+            .lock()
+            .unreserve_rest(reservation_id)?;
+        bail!("No one order of ladder {} is created", parent_order.id());
+    }
+
+    log::info!("Ladder is placed {:?}", parent_order.progress());
+
+    let action_name = format!("Waiting finish of ladder {}", parent_order.id());
+    let parent = parent_order.clone();
+    let action = async move {
+        let stop_token = engine_context.lifetime_manager.stop_token();
+        join_all(child_orders.iter().map(|order| {
+            exchange
+                .clone()
+                .wait_order_finish(order, None, stop_token.clone())
+        }))
+        .await;
+
+        let mut balance_manager = engine_context.balance_manager.lock();
+        for order in &child_orders {
+            balance_manager
+                .order_was_finished(parameters.configuration_descriptor, &order.deep_clone());
+        }
+
+        if balance_manager.get_reservation(reservation_id).is_some() {
+            balance_manager.unreserve_rest(reservation_id)?;
+        }
+
+        let status = match parent.remaining_amount().is_zero() {
+            true => ParentOrderStatus::Completed,
+            false => ParentOrderStatus::Finished,
+        };
+        parent.set_status(status);
+        log::info!("Ladder is finished {:?}", parent.progress());
+
+        Ok(())
+    };
+
+    let _ = spawn_future(&action_name, SpawnFutureFlags::STOP_BY_TOKEN, action);
+
+    Ok(parent_order)
+}
+
+/// Cancel all not finished orders of ladder
+pub async fn cancel_ladder(engine_context: &EngineContext, ladder: &ParentOrder) -> Result<()> {
+    let exchange = engine_context
+        .exchanges
+        .get(&ladder.exchange_account_id())
+        .with_context(|| format!("Exchange {} isn't found", ladder.exchange_account_id()))?
+        .clone();
+
+    let stop_token = engine_context.lifetime_manager.stop_token();
+    let cancellations = ladder
+        .child_orders()
+        .into_iter()
+        .filter(|order| !order.is_finished())
+        .map(|order| exchange.wait_cancel_order(order, None, true, stop_token.clone()));
+
+    join_all(cancellations)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()
+        .with_context(|| format!("Failed to cancel orders of ladder {}", ladder.id()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(distribution: LadderDistribution) -> LadderParameters {
+        LadderParameters {
+            exchange_account_id: ExchangeAccountId::new("Binance", 0),
+            currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            side: OrderSide::Buy,
+            amount: dec!(12),
+            start_price: dec!(100),
+            end_price: dec!(97),
+            orders_count: 3,
+            distribution,
+            configuration_descriptor: ConfigurationDescriptor::new("test".into(), "test".into()),
+            strategy_name: "test".to_owned(),
+        }
+    }
+
+    #[test]
+    fn linear_ladder() {
+        assert_eq!(
+            ladder_levels(&parameters(LadderDistribution::Linear)),
+            vec![
+                (dec!(100), dec!(2)),
+                (dec!(98.5), dec!(4)),
+                (dec!(97), dec!(6))
+            ]
+        );
+    }
+
+    #[test]
+    fn geometric_ladder() {
+        let levels = ladder_levels(&parameters(LadderDistribution::Geometric {
+            ratio: dec!(0.5),
+        }));
+
+        let amounts = levels
+            .iter()
+            .map(|(_, amount)| amount.round_dp(10))
+            .collect_vec();
+        assert_eq!(
+            amounts,
+            vec![
+                (dec!(48) / dec!(7)).round_dp(10),
+                (dec!(24) / dec!(7)).round_dp(10),
+                (dec!(12) / dec!(7)).round_dp(10)
+            ]
+        );
+    }
+
+    #[test]
+    fn single_order_ladder_is_placed_at_start_price() {
+        let mut parameters = parameters(LadderDistribution::Linear);
+        parameters.orders_count = 1;
+
+        assert_eq!(ladder_levels(&parameters), vec![(dec!(100), dec!(12))]);
+    }
+}
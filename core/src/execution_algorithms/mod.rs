@@ -0,0 +1,8 @@
+pub mod chase;
+pub mod ladder;
+pub mod parent_order;
+pub(crate) mod slice;
+pub mod trailing_stop;
+pub mod trigger;
+pub mod twap;
+pub mod vwap;
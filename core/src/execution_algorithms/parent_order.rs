@@ -0,0 +1,150 @@
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, OrderSide, Price};
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ParentOrderStatus {
+    Executing,
+    /// Whole amount is filled
+    Completed,
+    /// Execution is stopped by cancellation or end of time window before the whole amount is filled
+    Finished,
+    Failed,
+}
+
+impl ParentOrderStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, ParentOrderStatus::Executing)
+    }
+}
+
+/// Aggregate progress of parent order over all its child orders
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParentOrderProgress {
+    pub id: ClientOrderId,
+    pub algorithm: &'static str,
+    pub status: ParentOrderStatus,
+    pub amount: Amount,
+    pub filled_amount: Amount,
+    /// Volume weighted price of all fills of child orders
+    pub average_price: Option<Price>,
+    pub child_orders_count: usize,
+}
+
+struct ParentOrderState {
+    status: ParentOrderStatus,
+    child_orders: Vec<OrderRef>,
+}
+
+/// Order executed by execution algorithm through child orders posted on exchange
+pub struct ParentOrder {
+    id: ClientOrderId,
+    algorithm: &'static str,
+    exchange_account_id: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    side: OrderSide,
+    amount: Amount,
+    state: Mutex<ParentOrderState>,
+}
+
+impl ParentOrder {
+    pub fn new(
+        algorithm: &'static str,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+        side: OrderSide,
+        amount: Amount,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            id: ClientOrderId::unique_id(),
+            algorithm,
+            exchange_account_id,
+            currency_pair,
+            side,
+            amount,
+            state: Mutex::new(ParentOrderState {
+                status: ParentOrderStatus::Executing,
+                child_orders: Vec::new(),
+            }),
+        })
+    }
+
+    pub fn id(&self) -> &ClientOrderId {
+        &self.id
+    }
+
+    pub fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    pub fn currency_pair(&self) -> CurrencyPair {
+        self.currency_pair
+    }
+
+    pub fn side(&self) -> OrderSide {
+        self.side
+    }
+
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    pub fn status(&self) -> ParentOrderStatus {
+        self.state.lock().status
+    }
+
+    pub fn child_orders(&self) -> Vec<OrderRef> {
+        self.state.lock().child_orders.clone()
+    }
+
+    pub fn filled_amount(&self) -> Amount {
+        self.state
+            .lock()
+            .child_orders
+            .iter()
+            .map(|order| order.filled_amount())
+            .sum()
+    }
+
+    pub fn remaining_amount(&self) -> Amount {
+        (self.amount - self.filled_amount()).max(dec!(0))
+    }
+
+    pub fn average_price(&self) -> Option<Price> {
+        let (cost, filled_amount) = self
+            .state
+            .lock()
+            .child_orders
+            .iter()
+            .flat_map(|order| order.get_fills().0)
+            .fold((dec!(0), dec!(0)), |(cost, amount), fill| {
+                (cost + fill.price() * fill.amount(), amount + fill.amount())
+            });
+
+        (!filled_amount.is_zero()).then(|| cost / filled_amount)
+    }
+
+    pub fn progress(&self) -> ParentOrderProgress {
+        ParentOrderProgress {
+            id: self.id.clone(),
+            algorithm: self.algorithm,
+            status: self.status(),
+            amount: self.amount,
+            filled_amount: self.filled_amount(),
+            average_price: self.average_price(),
+            child_orders_count: self.state.lock().child_orders.len(),
+        }
+    }
+
+    pub(crate) fn add_child_order(&self, order: OrderRef) {
+        self.state.lock().child_orders.push(order);
+    }
+
+    pub(crate) fn set_status(&self, status: ParentOrderStatus) {
+        self.state.lock().status = status;
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, Price, ReservationId, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::exchanges::general::exchange::Exchange;
+use crate::execution_algorithms::parent_order::ParentOrder;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::misc::reserve_parameters::ReserveParameters;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+/// Child order of parent order posted for one slice of execution algorithm
+pub(crate) struct SliceOrder<'a> {
+    pub engine_context: &'a EngineContext,
+    pub exchange: &'a Arc<Exchange>,
+    pub symbol: &'a Arc<Symbol>,
+    pub parent_order: &'a ParentOrder,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub strategy_name: &'a str,
+    pub price: Price,
+}
+
+impl SliceOrder<'_> {
+    /// Post limit child order with balance reserved for it and wait until it is finished
+    /// or `slice_duration` is elapsed, in the latter case the child order is canceled.
+    /// Returns `None` if amount is too small for an order or balance can't be reserved,
+    /// so the amount should be carried over to next slices
+    pub async fn execute(
+        &self,
+        amount: Amount,
+        slice_duration: Duration,
+        cancellation_token: CancellationToken,
+    ) -> Result<Option<OrderRef>> {
+        let amount = self.symbol.amount_round(amount, Round::Floor);
+        let min_amount = self.symbol.get_min_amount(self.price)?;
+        if amount.is_zero() || amount < min_amount {
+            log::trace!(
+                "Slice {amount} of parent order {} is less than min amount {min_amount}",
+                self.parent_order.id()
+            );
+            return Ok(None);
+        }
+
+        let exchange_account_id = self.parent_order.exchange_account_id();
+        let reserve_parameters = ReserveParameters::new(
+            self.configuration_descriptor,
+            exchange_account_id,
+            self.symbol.clone(),
+            self.parent_order.side(),
+            self.price,
+            amount,
+        );
+        let reservation_id = match self
+            .engine_context
+            .balance_manager
+            .lock()
+            .try_reserve(&reserve_parameters, &mut None)
+        {
+            Some(reservation_id) => reservation_id,
+            None => {
+                log::warn!(
+                    "Can't reserve balance for slice {amount} of parent order {} on {exchange_account_id}",
+                    self.parent_order.id()
+                );
+                return Ok(None);
+            }
+        };
+
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            exchange_account_id,
+            self.parent_order.currency_pair(),
+            self.parent_order.side(),
+            amount,
+            UserOrder::limit(self.price),
+            Some(reservation_id),
+            None,
+            self.strategy_name.to_owned(),
+        );
+
+        let order = match self
+            .exchange
+            .create_order(&header, None, cancellation_token.clone())
+            .await
+        {
+            Ok(order) => order,
+            Err(error) => {
+                self.release_reservation(None, reservation_id)?;
+                return Err(error).with_context(|| {
+                    format!(
+                        "Failed to create child order of parent order {}",
+                        self.parent_order.id()
+                    )
+                });
+            }
+        };
+        self.parent_order.add_child_order(order.clone());
+
+        let wait_finish =
+            self.exchange
+                .clone()
+                .wait_order_finish(&order, None, cancellation_token.clone());
+        let _ = timeout(slice_duration, wait_finish).await;
+
+        if !order.is_finished() {
+            let stop_token = self.engine_context.lifetime_manager.stop_token();
+            self.exchange
+                .wait_cancel_order(order.clone(), None, true, stop_token)
+                .await?;
+        }
+
+        self.release_reservation(Some(&order), reservation_id)?;
+
+        Ok(Some(order))
+    }
+
+    fn release_reservation(
+        &self,
+        order: Option<&OrderRef>,
+        reservation_id: ReservationId,
+    ) -> Result<()> {
+        let mut balance_manager = self.engine_context.balance_manager.lock();
+        if let Some(order) = order {
+            balance_manager.order_was_finished(self.configuration_descriptor, &order.deep_clone());
+        }
+
+        if balance_manager.get_reservation(reservation_id).is_some() {
+            balance_manager.unreserve_rest(reservation_id)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,503 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderSide, Price, TriggerPriceType, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::features::ExchangeCapability;
+use crate::exchanges::general::order::amend::AmendOrderResult;
+use crate::execution_algorithms::parent_order::{
+    ParentOrder, ParentOrderProgress, ParentOrderStatus,
+};
+use crate::execution_algorithms::trigger::{update_prices, MarketPrices};
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+
+pub const TRAILING_STOP_ALGORITHM: &str = "TrailingStop";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TrailingStopExecution {
+    /// Stop-loss order is kept on exchange and moved after the best price,
+    /// so position is protected even if engine is disconnected
+    ExchangeStop,
+    /// Market order is sent when price retraces by trailing delta,
+    /// for exchanges without stop orders
+    MarketOrder,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrailingStopParameters {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    /// Side of closing order: sell for long position and buy for short one
+    pub side: OrderSide,
+    pub amount: Amount,
+    /// Distance of stop price from the best price reached since start
+    pub trailing_delta: Price,
+    pub execution: TrailingStopExecution,
+    pub strategy_name: String,
+}
+
+impl TrailingStopParameters {
+    fn validate(&self, exchange: &Exchange) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!(
+                "Amount of trailing stop should be positive, but it is {}",
+                self.amount
+            );
+        }
+
+        if self.trailing_delta <= dec!(0) {
+            bail!(
+                "Trailing delta of trailing stop should be positive, but it is {}",
+                self.trailing_delta
+            );
+        }
+
+        if self.execution == TrailingStopExecution::ExchangeStop
+            && !exchange
+                .missing_capabilities(&[ExchangeCapability::StopOrders])
+                .is_empty()
+        {
+            bail!(
+                "Exchange {} doesn't support stop orders, trailing stop should be executed by market order",
+                self.exchange_account_id
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Stop price following the best price of market for closing order side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Trail {
+    side: OrderSide,
+    trailing_delta: Price,
+    best_price: Option<Price>,
+}
+
+impl Trail {
+    fn new(side: OrderSide, trailing_delta: Price) -> Self {
+        Trail {
+            side,
+            trailing_delta,
+            best_price: None,
+        }
+    }
+
+    /// Closing sell order is executed by bids and closing buy order by asks
+    fn reference_price(&self, prices: &MarketPrices) -> Option<Price> {
+        match self.side {
+            OrderSide::Sell => prices.best_bid,
+            OrderSide::Buy => prices.best_ask,
+        }
+    }
+
+    /// Move the best price by reference price and return the current stop price
+    fn update(&mut self, price: Price) -> Price {
+        let best_price = match (self.side, self.best_price) {
+            (OrderSide::Sell, Some(best_price)) => best_price.max(price),
+            (OrderSide::Buy, Some(best_price)) => best_price.min(price),
+            (_, None) => price,
+        };
+        self.best_price = Some(best_price);
+
+        match self.side {
+            OrderSide::Sell => best_price - self.trailing_delta,
+            OrderSide::Buy => best_price + self.trailing_delta,
+        }
+    }
+
+    /// Price retraced from the best price by trailing delta
+    fn is_triggered(&self, price: Price, stop_price: Price) -> bool {
+        match self.side {
+            OrderSide::Sell => price <= stop_price,
+            OrderSide::Buy => price >= stop_price,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TrailingStopStatus {
+    /// Stop price follows the market
+    Tracking,
+    /// Price retraced by trailing delta and closing order is executed
+    Fired,
+    /// Tracking is stopped by cancellation before stop is fired
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrailingStopProgress {
+    pub status: TrailingStopStatus,
+    pub execution: TrailingStopExecution,
+    pub best_price: Option<Price>,
+    pub stop_price: Option<Price>,
+    pub order: ParentOrderProgress,
+}
+
+struct TrailingStopState {
+    status: TrailingStopStatus,
+    best_price: Option<Price>,
+    stop_price: Option<Price>,
+}
+
+/// Stop order which price follows the market at trailing delta from the best price
+pub struct TrailingStop {
+    execution: TrailingStopExecution,
+    parent_order: Arc<ParentOrder>,
+    state: Mutex<TrailingStopState>,
+}
+
+impl TrailingStop {
+    fn new(execution: TrailingStopExecution, parent_order: Arc<ParentOrder>) -> Arc<Self> {
+        Arc::new(Self {
+            execution,
+            parent_order,
+            state: Mutex::new(TrailingStopState {
+                status: TrailingStopStatus::Tracking,
+                best_price: None,
+                stop_price: None,
+            }),
+        })
+    }
+
+    pub fn parent_order(&self) -> &Arc<ParentOrder> {
+        &self.parent_order
+    }
+
+    pub fn status(&self) -> TrailingStopStatus {
+        self.state.lock().status
+    }
+
+    pub fn progress(&self) -> TrailingStopProgress {
+        let state = self.state.lock();
+        TrailingStopProgress {
+            status: state.status,
+            execution: self.execution,
+            best_price: state.best_price,
+            stop_price: state.stop_price,
+            order: self.parent_order.progress(),
+        }
+    }
+
+    fn set_status(&self, status: TrailingStopStatus) {
+        self.state.lock().status = status;
+    }
+
+    fn set_prices(&self, best_price: Option<Price>, stop_price: Price) {
+        let mut state = self.state.lock();
+        state.best_price = best_price;
+        state.stop_price = Some(stop_price);
+    }
+
+    fn finish(&self, status: TrailingStopStatus) {
+        self.set_status(status);
+        let parent_order_status = match status {
+            TrailingStopStatus::Failed => ParentOrderStatus::Failed,
+            _ if self.parent_order.remaining_amount().is_zero() => ParentOrderStatus::Completed,
+            _ => ParentOrderStatus::Finished,
+        };
+        self.parent_order.set_status(parent_order_status);
+        log::info!("Trailing stop is finished {:?}", self.progress());
+    }
+}
+
+/// Closing orders of trailing stop
+struct StopOrders<'a> {
+    engine_context: &'a EngineContext,
+    exchange: &'a Arc<Exchange>,
+    symbol: &'a Symbol,
+    parent_order: &'a ParentOrder,
+    strategy_name: &'a str,
+}
+
+impl StopOrders<'_> {
+    /// Orders close existing position, so balance isn't reserved for them
+    async fn create(&self, user_order: UserOrder) -> Result<OrderRef> {
+        let header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            self.parent_order.exchange_account_id(),
+            self.parent_order.currency_pair(),
+            self.parent_order.side(),
+            self.symbol
+                .amount_round(self.parent_order.remaining_amount(), Round::Floor),
+            user_order,
+            None,
+            None,
+            self.strategy_name.to_owned(),
+        );
+
+        let stop_token = self.engine_context.lifetime_manager.stop_token();
+        let order = self
+            .exchange
+            .create_order(&header, None, stop_token)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to create order of trailing stop {}",
+                    self.parent_order.id()
+                )
+            })?;
+        self.parent_order.add_child_order(order.clone());
+
+        Ok(order)
+    }
+
+    async fn cancel(&self, order: &OrderRef) -> Result<()> {
+        if order.is_finished() {
+            return Ok(());
+        }
+
+        let stop_token = self.engine_context.lifetime_manager.stop_token();
+        self.exchange
+            .wait_cancel_order(order.clone(), None, true, stop_token)
+            .await
+    }
+
+    /// Move stop order on exchange to new stop price by amendment, so the order keeps its ids
+    /// on exchanges which support it. Returns `None` if the previous stop order is already filled
+    async fn move_stop(
+        &self,
+        stop_order: Option<OrderRef>,
+        stop_price: Price,
+    ) -> Result<Option<OrderRef>> {
+        let trigger_price_type = self
+            .exchange
+            .available_trigger_price_type(TriggerPriceType::Last);
+        let user_order = UserOrder::StopLoss {
+            stop_price,
+            trigger_price_type,
+        };
+
+        let Some(stop_order) = stop_order else {
+            return self.create(user_order).await.map(Some);
+        };
+
+        if !stop_order.is_finished() {
+            let stop_token = self.engine_context.lifetime_manager.stop_token();
+            let result = self
+                .exchange
+                .amend_order(&stop_order, user_order, None, stop_token)
+                .await;
+            match result {
+                Ok(AmendOrderResult::Amended(order)) => return Ok(Some(order)),
+                Ok(AmendOrderResult::Replaced(order)) => {
+                    self.parent_order.add_child_order(order.clone());
+                    return Ok(Some(order));
+                }
+                // stop order could be triggered while it was amended
+                Err(error) if stop_order.is_finished() => log::info!(
+                    "Stop order {} of trailing stop {} is finished before amendment: {error:?}",
+                    stop_order.client_order_id(),
+                    self.parent_order.id()
+                ),
+                Err(error) => return Err(error),
+            }
+        }
+
+        if self.parent_order.remaining_amount().is_zero() {
+            return Ok(None);
+        }
+
+        self.create(user_order).await.map(Some)
+    }
+}
+
+/// Start trailing stop: stop price follows the best price of market at trailing delta and
+/// closing order is executed when price retraces by the delta. Prices are tracked by order book
+/// kept locally from events routed to strategies. Depending on execution, stop-loss order is
+/// moved on exchange after every change of stop price or market order is sent on retracement,
+/// so trailing stops are available on exchanges without native ones.
+/// Returns trailing stop for progress tracking, cancellation token stops tracking and
+/// cancels stop order on exchange
+pub fn start_trailing_stop(
+    engine_context: Arc<EngineContext>,
+    parameters: TrailingStopParameters,
+    cancellation_token: CancellationToken,
+) -> Result<Arc<TrailingStop>> {
+    let exchange = engine_context
+        .exchanges
+        .get(&parameters.exchange_account_id)
+        .with_context(|| format!("Exchange {} isn't found", parameters.exchange_account_id))?
+        .clone();
+    parameters.validate(&exchange)?;
+    let symbol = exchange.get_symbol(parameters.currency_pair)?;
+
+    let parent_order = ParentOrder::new(
+        TRAILING_STOP_ALGORITHM,
+        parameters.exchange_account_id,
+        parameters.currency_pair,
+        parameters.side,
+        parameters.amount,
+    );
+    let trailing_stop = TrailingStop::new(parameters.execution, parent_order);
+
+    let market_account_id =
+        MarketAccountId::new(parameters.exchange_account_id, parameters.currency_pair);
+    let mut events = engine_context
+        .strategy_events_router
+        .subscribe([market_account_id]);
+    let action_name = format!(
+        "Trailing stop {} with delta {}",
+        trailing_stop.parent_order.id(),
+        parameters.trailing_delta
+    );
+    let stop = trailing_stop.clone();
+    let action = async move {
+        let stop_orders = StopOrders {
+            engine_context: &engine_context,
+            exchange: &exchange,
+            symbol: &symbol,
+            parent_order: &stop.parent_order,
+            strategy_name: &parameters.strategy_name,
+        };
+
+        let mut trail = Trail::new(parameters.side, parameters.trailing_delta);
+        let mut prices = MarketPrices::default();
+        let mut local_snapshots = LocalSnapshotsService::default();
+        let mut stop_order: Option<OrderRef> = None;
+        loop {
+            if stop.parent_order.remaining_amount().is_zero() {
+                stop.finish(TrailingStopStatus::Fired);
+                return Ok(());
+            }
+
+            let event = tokio::select! {
+                event = events.recv() => event,
+                _ = cancellation_token.when_cancelled() => {
+                    if let Some(stop_order) = &stop_order {
+                        stop_orders.cancel(stop_order).await?;
+                    }
+                    stop.finish(TrailingStopStatus::Cancelled);
+                    return Ok(());
+                }
+            };
+
+            let event = match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped_count)) => {
+                    log::warn!("Trailing stop missed {skipped_count} events, prices can be stale");
+                    continue;
+                }
+                Err(RecvError::Closed) => {
+                    stop.finish(TrailingStopStatus::Failed);
+                    bail!("Events channel of trailing stop is closed");
+                }
+            };
+
+            if !update_prices(&mut prices, &mut local_snapshots, market_account_id, &event) {
+                continue;
+            }
+
+            let Some(price) = trail.reference_price(&prices) else {
+                continue;
+            };
+            let stop_price = symbol.price_round(trail.update(price), Round::ToNearest);
+            stop.set_prices(trail.best_price, stop_price);
+
+            let result = match parameters.execution {
+                TrailingStopExecution::MarketOrder => {
+                    if !trail.is_triggered(price, stop_price) {
+                        continue;
+                    }
+
+                    log::info!(
+                        "Trailing stop {} is fired at price {price} by stop price {stop_price}",
+                        stop.parent_order.id()
+                    );
+                    stop_orders.create(UserOrder::Market).await.map(|_| ())
+                }
+                TrailingStopExecution::ExchangeStop => {
+                    let current_stop_price =
+                        stop_order.as_ref().and_then(|x| match x.user_order() {
+                            Some(UserOrder::StopLoss { stop_price, .. }) => Some(stop_price),
+                            _ => None,
+                        });
+                    if current_stop_price == Some(stop_price) {
+                        continue;
+                    }
+
+                    stop_orders
+                        .move_stop(stop_order.take(), stop_price)
+                        .await
+                        .map(|order| stop_order = order)
+                }
+            };
+
+            if let Err(error) = result {
+                stop.finish(TrailingStopStatus::Failed);
+                return Err(error);
+            }
+
+            if parameters.execution == TrailingStopExecution::MarketOrder {
+                stop.finish(TrailingStopStatus::Fired);
+                return Ok(());
+            }
+        }
+    };
+
+    let _ = spawn_future(&action_name, SpawnFutureFlags::STOP_BY_TOKEN, action);
+
+    Ok(trailing_stop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sell_stop_follows_rising_price() {
+        let mut trail = Trail::new(OrderSide::Sell, dec!(10));
+
+        assert_eq!(trail.update(dec!(100)), dec!(90));
+        assert_eq!(trail.update(dec!(120)), dec!(110));
+        assert_eq!(trail.update(dec!(115)), dec!(110));
+        assert_eq!(trail.best_price, Some(dec!(120)));
+        assert!(!trail.is_triggered(dec!(115), dec!(110)));
+        assert!(trail.is_triggered(dec!(110), dec!(110)));
+    }
+
+    #[test]
+    fn buy_stop_follows_falling_price() {
+        let mut trail = Trail::new(OrderSide::Buy, dec!(5));
+
+        assert_eq!(trail.update(dec!(100)), dec!(105));
+        assert_eq!(trail.update(dec!(90)), dec!(95));
+        assert_eq!(trail.update(dec!(94)), dec!(95));
+        assert!(!trail.is_triggered(dec!(94), dec!(95)));
+        assert!(trail.is_triggered(dec!(96), dec!(95)));
+    }
+
+    #[test]
+    fn reference_price_is_taken_from_side_of_execution() {
+        let prices = MarketPrices {
+            last_trade: Some(dec!(100)),
+            mark: None,
+            best_bid: Some(dec!(99)),
+            best_ask: Some(dec!(101)),
+        };
+
+        assert_eq!(
+            Trail::new(OrderSide::Sell, dec!(1)).reference_price(&prices),
+            Some(dec!(99))
+        );
+        assert_eq!(
+            Trail::new(OrderSide::Buy, dec!(1)).reference_price(&prices),
+            Some(dec!(101))
+        );
+    }
+}
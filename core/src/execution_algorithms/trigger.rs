@@ -0,0 +1,451 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::execution_algorithms::parent_order::{
+    ParentOrder, ParentOrderProgress, ParentOrderStatus,
+};
+use crate::execution_algorithms::slice::SliceOrder;
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::misc::time::time_manager;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+pub const TRIGGER_ALGORITHM: &str = "Trigger";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TriggerPriceSource {
+    LastTrade,
+    Mark,
+    BestBid,
+    BestAsk,
+    /// Middle between best bid and best ask
+    Mid,
+}
+
+/// Condition of market which fires trigger order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TriggerCondition {
+    /// Price reaches level or goes above it
+    PriceAbove {
+        source: TriggerPriceSource,
+        level: Price,
+    },
+    /// Price reaches level or goes below it
+    PriceBelow {
+        source: TriggerPriceSource,
+        level: Price,
+    },
+    /// Difference between best ask and best bid exceeds specified value
+    SpreadAbove { spread: Price },
+}
+
+impl TriggerCondition {
+    fn is_met(&self, prices: &MarketPrices) -> bool {
+        match *self {
+            TriggerCondition::PriceAbove { source, level } => {
+                prices.get(source).map_or(false, |price| price >= level)
+            }
+            TriggerCondition::PriceBelow { source, level } => {
+                prices.get(source).map_or(false, |price| price <= level)
+            }
+            TriggerCondition::SpreadAbove { spread } => prices
+                .spread()
+                .map_or(false, |market_spread| market_spread > spread),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let value = match *self {
+            TriggerCondition::PriceAbove { level, .. } => level,
+            TriggerCondition::PriceBelow { level, .. } => level,
+            TriggerCondition::SpreadAbove { spread } => spread,
+        };
+
+        if value <= dec!(0) {
+            bail!("Value of trigger condition {self:?} should be positive");
+        }
+
+        Ok(())
+    }
+}
+
+/// Last known prices of market observed from events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MarketPrices {
+    pub last_trade: Option<Price>,
+    pub mark: Option<Price>,
+    pub best_bid: Option<Price>,
+    pub best_ask: Option<Price>,
+}
+
+impl MarketPrices {
+    fn get(&self, source: TriggerPriceSource) -> Option<Price> {
+        match source {
+            TriggerPriceSource::LastTrade => self.last_trade,
+            TriggerPriceSource::Mark => self.mark,
+            TriggerPriceSource::BestBid => self.best_bid,
+            TriggerPriceSource::BestAsk => self.best_ask,
+            TriggerPriceSource::Mid => self
+                .best_bid
+                .zip(self.best_ask)
+                .map(|(bid, ask)| (bid + ask) / dec!(2)),
+        }
+    }
+
+    fn spread(&self) -> Option<Price> {
+        self.best_bid.zip(self.best_ask).map(|(bid, ask)| ask - bid)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TriggerParameters {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    pub amount: Amount,
+    pub condition: TriggerCondition,
+    /// Price of limit order submitted when condition fires, so it is the worst price of execution
+    pub limit_price: Price,
+    /// Time since firing after which not filled part of submitted order is canceled
+    pub order_lifetime: Duration,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub strategy_name: String,
+}
+
+impl TriggerParameters {
+    fn validate(&self) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!(
+                "Amount of trigger order should be positive, but it is {}",
+                self.amount
+            );
+        }
+
+        if self.limit_price <= dec!(0) {
+            bail!(
+                "Limit price of trigger order should be positive, but it is {}",
+                self.limit_price
+            );
+        }
+
+        if self.order_lifetime.is_zero() {
+            bail!("Order lifetime of trigger order should be positive");
+        }
+
+        self.condition.validate()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TriggerStatus {
+    /// Condition isn't met yet
+    Waiting,
+    /// Condition is met and order is submitted
+    Fired,
+    /// Waiting is stopped by cancellation before condition is met
+    Cancelled,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TriggerOrderProgress {
+    pub condition: TriggerCondition,
+    pub status: TriggerStatus,
+    pub fired_time: Option<DateTime>,
+    /// Market prices at the moment of firing
+    pub fired_prices: Option<MarketPrices>,
+    pub order: ParentOrderProgress,
+}
+
+struct TriggerState {
+    status: TriggerStatus,
+    fired_time: Option<DateTime>,
+    fired_prices: Option<MarketPrices>,
+}
+
+/// Order which is submitted to exchange only when condition on market fires
+pub struct TriggerOrder {
+    condition: TriggerCondition,
+    parent_order: Arc<ParentOrder>,
+    state: Mutex<TriggerState>,
+}
+
+impl TriggerOrder {
+    fn new(condition: TriggerCondition, parent_order: Arc<ParentOrder>) -> Arc<Self> {
+        Arc::new(Self {
+            condition,
+            parent_order,
+            state: Mutex::new(TriggerState {
+                status: TriggerStatus::Waiting,
+                fired_time: None,
+                fired_prices: None,
+            }),
+        })
+    }
+
+    pub fn condition(&self) -> TriggerCondition {
+        self.condition
+    }
+
+    pub fn parent_order(&self) -> &Arc<ParentOrder> {
+        &self.parent_order
+    }
+
+    pub fn status(&self) -> TriggerStatus {
+        self.state.lock().status
+    }
+
+    pub fn progress(&self) -> TriggerOrderProgress {
+        let state = self.state.lock();
+        TriggerOrderProgress {
+            condition: self.condition,
+            status: state.status,
+            fired_time: state.fired_time,
+            fired_prices: state.fired_prices,
+            order: self.parent_order.progress(),
+        }
+    }
+
+    fn set_status(&self, status: TriggerStatus) {
+        self.state.lock().status = status;
+    }
+
+    fn fire(&self, prices: MarketPrices) {
+        let mut state = self.state.lock();
+        state.status = TriggerStatus::Fired;
+        state.fired_time = Some(time_manager::now());
+        state.fired_prices = Some(prices);
+    }
+}
+
+/// Apply market event to observed prices.
+/// Returns `true` if any price of the market is changed
+pub(super) fn update_prices(
+    prices: &mut MarketPrices,
+    local_snapshots: &mut LocalSnapshotsService,
+    market_account_id: MarketAccountId,
+    event: &ExchangeEvent,
+) -> bool {
+    let previous_prices = *prices;
+    match event {
+        ExchangeEvent::OrderBookEvent(order_book_event)
+            if order_book_event.market_account_id() == market_account_id =>
+        {
+            if local_snapshots.update(order_book_event).is_some() {
+                let snapshot = local_snapshots.get_snapshot_expected(market_account_id.market_id());
+                prices.best_bid = snapshot.get_top_bid().map(|(price, _)| price);
+                prices.best_ask = snapshot.get_top_ask().map(|(price, _)| price);
+            }
+        }
+        ExchangeEvent::Trades(trades_event)
+            if trades_event.exchange_account_id == market_account_id.exchange_account_id
+                && trades_event.currency_pair == market_account_id.currency_pair =>
+        {
+            if let Some(trade) = trades_event.trades.last() {
+                prices.last_trade = Some(trade.price);
+            }
+        }
+        ExchangeEvent::MarkPrice(mark_price_event)
+            if mark_price_event.market_account_id() == market_account_id =>
+        {
+            prices.mark = Some(mark_price_event.mark_price);
+        }
+        _ => {}
+    }
+
+    *prices != previous_prices
+}
+
+/// Start watching market events for trigger order: limit order is submitted only when
+/// condition on prices of market is met, so stop-like behavior is available on exchanges
+/// without native conditional orders. Order book is tracked locally from events routed to
+/// strategies. Not filled part of submitted order is canceled after its lifetime.
+/// Returns trigger order for progress tracking, cancellation token stops waiting of condition
+pub fn start_trigger(
+    engine_context: Arc<EngineContext>,
+    parameters: TriggerParameters,
+    cancellation_token: CancellationToken,
+) -> Result<Arc<TriggerOrder>> {
+    parameters.validate()?;
+
+    let exchange = engine_context
+        .exchanges
+        .get(&parameters.exchange_account_id)
+        .with_context(|| format!("Exchange {} isn't found", parameters.exchange_account_id))?
+        .clone();
+    let symbol = exchange.get_symbol(parameters.currency_pair)?;
+
+    let parent_order = ParentOrder::new(
+        TRIGGER_ALGORITHM,
+        parameters.exchange_account_id,
+        parameters.currency_pair,
+        parameters.side,
+        parameters.amount,
+    );
+    let trigger_order = TriggerOrder::new(parameters.condition, parent_order);
+
+    let market_account_id =
+        MarketAccountId::new(parameters.exchange_account_id, parameters.currency_pair);
+    let mut events = engine_context
+        .strategy_events_router
+        .subscribe([market_account_id]);
+    let action_name = format!(
+        "Trigger order {} waiting for {:?}",
+        trigger_order.parent_order.id(),
+        parameters.condition
+    );
+    let trigger = trigger_order.clone();
+    let action = async move {
+        let mut prices = MarketPrices::default();
+        let mut local_snapshots = LocalSnapshotsService::default();
+        loop {
+            let event = tokio::select! {
+                event = events.recv() => event,
+                _ = cancellation_token.when_cancelled() => {
+                    trigger.set_status(TriggerStatus::Cancelled);
+                    trigger.parent_order.set_status(ParentOrderStatus::Finished);
+                    log::info!("Trigger order is cancelled {:?}", trigger.progress());
+                    return Ok(());
+                }
+            };
+
+            let event = match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped_count)) => {
+                    log::warn!("Trigger order missed {skipped_count} events, prices can be stale");
+                    continue;
+                }
+                Err(RecvError::Closed) => {
+                    trigger.set_status(TriggerStatus::Failed);
+                    trigger.parent_order.set_status(ParentOrderStatus::Failed);
+                    bail!("Events channel of trigger order is closed");
+                }
+            };
+
+            let is_changed =
+                update_prices(&mut prices, &mut local_snapshots, market_account_id, &event);
+            if is_changed && trigger.condition.is_met(&prices) {
+                break;
+            }
+        }
+
+        trigger.fire(prices);
+        log::info!(
+            "Trigger order {} fired by {:?} at prices {prices:?}",
+            trigger.parent_order.id(),
+            trigger.condition
+        );
+
+        let parent = &trigger.parent_order;
+        let slice_order = SliceOrder {
+            engine_context: &engine_context,
+            exchange: &exchange,
+            symbol: &symbol,
+            parent_order: parent,
+            configuration_descriptor: parameters.configuration_descriptor,
+            strategy_name: &parameters.strategy_name,
+            price: parameters.limit_price,
+        };
+
+        let slice_result = slice_order
+            .execute(
+                parameters.amount,
+                parameters.order_lifetime,
+                engine_context.lifetime_manager.stop_token(),
+            )
+            .await;
+        let order = match slice_result {
+            Ok(order) => order,
+            Err(error) => {
+                trigger.set_status(TriggerStatus::Failed);
+                parent.set_status(ParentOrderStatus::Failed);
+                return Err(error);
+            }
+        };
+
+        if order.is_none() {
+            trigger.set_status(TriggerStatus::Failed);
+            parent.set_status(ParentOrderStatus::Failed);
+            bail!(
+                "Order of trigger order {} isn't submitted because of too small amount or insufficient balance",
+                parent.id()
+            );
+        }
+
+        let status = match parent.remaining_amount().is_zero() {
+            true => ParentOrderStatus::Completed,
+            false => ParentOrderStatus::Finished,
+        };
+        parent.set_status(status);
+        log::info!("Trigger order is finished {:?}", trigger.progress());
+
+        Ok(())
+    };
+
+    let _ = spawn_future(&action_name, SpawnFutureFlags::STOP_BY_TOKEN, action);
+
+    Ok(trigger_order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(last_trade: Price, best_bid: Price, best_ask: Price) -> MarketPrices {
+        MarketPrices {
+            last_trade: Some(last_trade),
+            mark: None,
+            best_bid: Some(best_bid),
+            best_ask: Some(best_ask),
+        }
+    }
+
+    #[test]
+    fn price_conditions_fire_on_reaching_level() {
+        let above = TriggerCondition::PriceAbove {
+            source: TriggerPriceSource::LastTrade,
+            level: dec!(100),
+        };
+        let below = TriggerCondition::PriceBelow {
+            source: TriggerPriceSource::Mid,
+            level: dec!(90),
+        };
+
+        assert!(!above.is_met(&prices(dec!(99), dec!(98), dec!(99))));
+        assert!(above.is_met(&prices(dec!(100), dec!(98), dec!(99))));
+        assert!(!below.is_met(&prices(dec!(100), dec!(90), dec!(91))));
+        assert!(below.is_met(&prices(dec!(100), dec!(89), dec!(91))));
+    }
+
+    #[test]
+    fn conditions_without_prices_are_not_met() {
+        let empty_prices = MarketPrices::default();
+
+        assert!(!TriggerCondition::PriceBelow {
+            source: TriggerPriceSource::Mark,
+            level: dec!(100),
+        }
+        .is_met(&empty_prices));
+        assert!(!TriggerCondition::SpreadAbove { spread: dec!(1) }.is_met(&empty_prices));
+    }
+
+    #[test]
+    fn spread_condition_fires_on_wide_spread() {
+        let condition = TriggerCondition::SpreadAbove { spread: dec!(2) };
+
+        assert!(!condition.is_met(&prices(dec!(100), dec!(99), dec!(101))));
+        assert!(condition.is_met(&prices(dec!(100), dec!(99), dec!(101.5))));
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+use crate::execution_algorithms::parent_order::{ParentOrder, ParentOrderStatus};
+use crate::execution_algorithms::slice::SliceOrder;
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+pub const TWAP_ALGORITHM: &str = "TWAP";
+
+#[derive(Debug, Clone)]
+pub struct TwapParameters {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    pub amount: Amount,
+    /// Price of child limit orders, so it is the worst price of execution
+    pub limit_price: Price,
+    /// Time window of execution
+    pub duration: Duration,
+    pub slices_count: u32,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub strategy_name: String,
+}
+
+impl TwapParameters {
+    fn validate(&self) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!(
+                "Amount of TWAP order should be positive, but it is {}",
+                self.amount
+            );
+        }
+
+        if self.slices_count == 0 {
+            bail!("Slices count of TWAP order should be positive");
+        }
+
+        if self.duration.is_zero() {
+            bail!("Duration of TWAP order should be positive");
+        }
+
+        Ok(())
+    }
+}
+
+/// Amount which should be filled in total by the end of slice with index `slice_index`
+fn twap_target_amount(amount: Amount, slices_count: u32, slice_index: u32) -> Amount {
+    amount * Decimal::from(slice_index + 1) / Decimal::from(slices_count)
+}
+
+/// Start time-weighted average price execution of parent order: time window is split into
+/// equal slices and every slice posts limit child order to reach evenly growing target amount.
+/// Child order is canceled at the end of its slice, so unfilled amount is carried over
+/// to the next slices. Returns parent order for progress tracking
+pub fn start_twap(
+    engine_context: Arc<EngineContext>,
+    parameters: TwapParameters,
+    cancellation_token: CancellationToken,
+) -> Result<Arc<ParentOrder>> {
+    parameters.validate()?;
+
+    let exchange = engine_context
+        .exchanges
+        .get(&parameters.exchange_account_id)
+        .with_context(|| format!("Exchange {} isn't found", parameters.exchange_account_id))?
+        .clone();
+    let symbol = exchange.get_symbol(parameters.currency_pair)?;
+
+    let parent_order = ParentOrder::new(
+        TWAP_ALGORITHM,
+        parameters.exchange_account_id,
+        parameters.currency_pair,
+        parameters.side,
+        parameters.amount,
+    );
+
+    let action_name = format!("TWAP execution of parent order {}", parent_order.id());
+    let parent = parent_order.clone();
+    let action = async move {
+        let slice_order = SliceOrder {
+            engine_context: &engine_context,
+            exchange: &exchange,
+            symbol: &symbol,
+            parent_order: &parent,
+            configuration_descriptor: parameters.configuration_descriptor,
+            strategy_name: &parameters.strategy_name,
+            price: parameters.limit_price,
+        };
+
+        let start_time = Instant::now();
+        let slice_duration = parameters.duration / parameters.slices_count;
+        for slice_index in 0..parameters.slices_count {
+            if cancellation_token.is_cancellation_requested() {
+                break;
+            }
+
+            let target_amount =
+                twap_target_amount(parameters.amount, parameters.slices_count, slice_index);
+            let slice_amount = target_amount - parent.filled_amount();
+            let slice_end = start_time + slice_duration * (slice_index + 1);
+
+            if slice_amount > dec!(0) {
+                let slice_result = slice_order
+                    .execute(
+                        slice_amount,
+                        slice_end.saturating_duration_since(Instant::now()),
+                        cancellation_token.clone(),
+                    )
+                    .await;
+
+                if let Err(error) = slice_result {
+                    parent.set_status(ParentOrderStatus::Failed);
+                    return Err(error);
+                }
+            }
+
+            log::info!("TWAP progress {:?}", parent.progress());
+            sleep_until(slice_end).await;
+        }
+
+        let status = match parent.remaining_amount().is_zero() {
+            true => ParentOrderStatus::Completed,
+            false => ParentOrderStatus::Finished,
+        };
+        parent.set_status(status);
+        log::info!("TWAP execution is finished {:?}", parent.progress());
+
+        Ok(())
+    };
+
+    let _ = spawn_future(&action_name, SpawnFutureFlags::STOP_BY_TOKEN, action);
+
+    Ok(parent_order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_amount_grows_evenly() {
+        let targets = (0..4)
+            .map(|slice_index| twap_target_amount(dec!(10), 4, slice_index))
+            .collect::<Vec<_>>();
+
+        assert_eq!(targets, vec![dec!(2.5), dec!(5), dec!(7.5), dec!(10)]);
+    }
+}
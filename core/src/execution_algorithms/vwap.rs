@@ -0,0 +1,246 @@
+use anyhow::{bail, Context, Result};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep_until, timeout_at, Instant};
+
+use crate::execution_algorithms::parent_order::{ParentOrder, ParentOrderStatus};
+use crate::execution_algorithms::slice::SliceOrder;
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::EngineContext;
+use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+
+pub const VWAP_ALGORITHM: &str = "VWAP";
+
+#[derive(Debug, Clone)]
+pub struct VwapParameters {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub side: OrderSide,
+    pub amount: Amount,
+    /// Price of child limit orders, so it is the worst price of execution
+    pub limit_price: Price,
+    /// Part of market traded volume which child orders should take, in range (0, 1]
+    pub participation_rate: Decimal,
+    /// Period of traded volume observation, child order of every period is sized
+    /// by volume traded during the previous one
+    pub slice_period: Duration,
+    /// Time since start when the whole amount should be executed. The last slice posts
+    /// all remaining amount regardless of traded volume
+    pub deadline: Duration,
+    pub configuration_descriptor: ConfigurationDescriptor,
+    pub strategy_name: String,
+}
+
+impl VwapParameters {
+    fn validate(&self) -> Result<()> {
+        if self.amount <= dec!(0) {
+            bail!(
+                "Amount of VWAP order should be positive, but it is {}",
+                self.amount
+            );
+        }
+
+        if self.participation_rate <= dec!(0) || self.participation_rate > dec!(1) {
+            bail!(
+                "Participation rate of VWAP order should be in range (0, 1], but it is {}",
+                self.participation_rate
+            );
+        }
+
+        if self.slice_period.is_zero() || self.deadline < self.slice_period {
+            bail!(
+                "Slice period {:?} of VWAP order should be positive and not greater than deadline {:?}",
+                self.slice_period,
+                self.deadline
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn vwap_slice_amount(
+    traded_volume: Amount,
+    participation_rate: Decimal,
+    remaining_amount: Amount,
+    is_last_slice: bool,
+) -> Amount {
+    match is_last_slice {
+        true => remaining_amount,
+        false => (traded_volume * participation_rate).min(remaining_amount),
+    }
+}
+
+/// Sum traded volume of market from trades stream until specified time
+async fn observe_traded_volume(
+    events: &mut broadcast::Receiver<ExchangeEvent>,
+    exchange_account_id: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    until: Instant,
+) -> Amount {
+    let mut traded_volume = dec!(0);
+    loop {
+        match timeout_at(until, events.recv()).await {
+            Err(_) => return traded_volume,
+            Ok(Ok(ExchangeEvent::Trades(trades_event)))
+                if trades_event.exchange_account_id == exchange_account_id
+                    && trades_event.currency_pair == currency_pair =>
+            {
+                traded_volume += trades_event
+                    .trades
+                    .iter()
+                    .map(|trade| trade.quantity)
+                    .sum::<Amount>();
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(RecvError::Lagged(skipped_count))) => {
+                log::warn!("VWAP missed {skipped_count} events, traded volume is underestimated")
+            }
+            Ok(Err(RecvError::Closed)) => {
+                sleep_until(until).await;
+                return traded_volume;
+            }
+        }
+    }
+}
+
+/// Start volume-weighted average price execution of parent order: every slice period
+/// child limit order is sized as participation rate of market volume traded
+/// during the previous period, while volume of the current period is observed from trades stream.
+/// Child order is canceled at the end of its period, and the rest of amount is posted
+/// by the last slice before deadline. Returns parent order for progress tracking
+pub fn start_vwap(
+    engine_context: Arc<EngineContext>,
+    parameters: VwapParameters,
+    cancellation_token: CancellationToken,
+) -> Result<Arc<ParentOrder>> {
+    parameters.validate()?;
+
+    let exchange = engine_context
+        .exchanges
+        .get(&parameters.exchange_account_id)
+        .with_context(|| format!("Exchange {} isn't found", parameters.exchange_account_id))?
+        .clone();
+    let symbol = exchange.get_symbol(parameters.currency_pair)?;
+
+    let parent_order = ParentOrder::new(
+        VWAP_ALGORITHM,
+        parameters.exchange_account_id,
+        parameters.currency_pair,
+        parameters.side,
+        parameters.amount,
+    );
+
+    let mut events = engine_context.get_events_channel();
+    let action_name = format!("VWAP execution of parent order {}", parent_order.id());
+    let parent = parent_order.clone();
+    let action = async move {
+        let slice_order = SliceOrder {
+            engine_context: &engine_context,
+            exchange: &exchange,
+            symbol: &symbol,
+            parent_order: &parent,
+            configuration_descriptor: parameters.configuration_descriptor,
+            strategy_name: &parameters.strategy_name,
+            price: parameters.limit_price,
+        };
+
+        let deadline = Instant::now() + parameters.deadline;
+        let mut traded_volume = dec!(0);
+        while !cancellation_token.is_cancellation_requested() {
+            let remaining_amount = parent.remaining_amount();
+            let now = Instant::now();
+            if remaining_amount.is_zero() || now >= deadline {
+                break;
+            }
+
+            let slice_end = (now + parameters.slice_period).min(deadline);
+            let slice_amount = vwap_slice_amount(
+                traded_volume,
+                parameters.participation_rate,
+                remaining_amount,
+                slice_end == deadline,
+            );
+
+            let execute_slice = async {
+                match slice_amount > dec!(0) {
+                    true => {
+                        slice_order
+                            .execute(slice_amount, slice_end - now, cancellation_token.clone())
+                            .await
+                    }
+                    false => Ok(None),
+                }
+            };
+            let observe_volume = observe_traded_volume(
+                &mut events,
+                parameters.exchange_account_id,
+                parameters.currency_pair,
+                slice_end,
+            );
+
+            let (slice_result, observed_volume) = tokio::join!(execute_slice, observe_volume);
+            if let Err(error) = slice_result {
+                parent.set_status(ParentOrderStatus::Failed);
+                return Err(error);
+            }
+
+            traded_volume = observed_volume;
+            log::info!(
+                "VWAP progress {:?}, traded volume of market {traded_volume}",
+                parent.progress()
+            );
+        }
+
+        let status = match parent.remaining_amount().is_zero() {
+            true => ParentOrderStatus::Completed,
+            false => ParentOrderStatus::Finished,
+        };
+        parent.set_status(status);
+        log::info!("VWAP execution is finished {:?}", parent.progress());
+
+        Ok(())
+    };
+
+    let _ = spawn_future(&action_name, SpawnFutureFlags::STOP_BY_TOKEN, action);
+
+    Ok(parent_order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_amount_follows_traded_volume() {
+        assert_eq!(
+            vwap_slice_amount(dec!(100), dec!(0.1), dec!(50), false),
+            dec!(10)
+        );
+        assert_eq!(
+            vwap_slice_amount(dec!(1000), dec!(0.1), dec!(50), false),
+            dec!(50)
+        );
+        assert_eq!(
+            vwap_slice_amount(dec!(0), dec!(0.1), dec!(50), false),
+            dec!(0)
+        );
+    }
+
+    #[test]
+    fn last_slice_takes_remaining_amount() {
+        assert_eq!(
+            vwap_slice_amount(dec!(100), dec!(0.1), dec!(50), true),
+            dec!(50)
+        );
+    }
+}
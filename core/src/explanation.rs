@@ -1,7 +1,7 @@
 use mmb_database::impl_event;
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::market::ExchangeId;
-use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order::snapshot::{Amount, IntentId, Price};
 use serde::Serialize;
 use std::fmt::{Debug, Formatter};
 
@@ -139,6 +139,7 @@ pub struct PriceLevelExplanation<'a> {
 pub struct ExplanationSet<'a> {
     exchange_id: ExchangeId,
     currency_pair: CurrencyPair,
+    intent_id: IntentId,
     set: Vec<PriceLevelExplanation<'a>>,
 }
 
@@ -146,11 +147,13 @@ impl<'a> ExplanationSet<'a> {
     pub fn new(
         exchange_id: ExchangeId,
         currency_pair: CurrencyPair,
+        intent_id: IntentId,
         set: Vec<PriceLevelExplanation<'a>>,
     ) -> Self {
         Self {
             exchange_id,
             currency_pair,
+            intent_id,
             set,
         }
     }
@@ -29,6 +29,7 @@ pub mod statistic_service;
 pub mod config;
 pub mod database;
 pub mod disposition_execution;
+pub mod execution_algorithms;
 pub mod explanation;
 pub mod lifecycle;
 pub mod math;
@@ -6,7 +6,9 @@ use crate::exchanges::general::currency_pair_to_symbol_converter::CurrencyPairTo
 use crate::exchanges::general::exchange::Exchange;
 use crate::exchanges::general::exchange_creation::create_exchange;
 use crate::exchanges::general::exchange_creation::create_timeout_manager;
+use crate::exchanges::general::exchange_creation::initialize_exchange_with_timeout;
 use crate::exchanges::internal_events_loop::InternalEventsLoop;
+use crate::exchanges::timeouts::polling_scheduler::PollingScheduler;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::exchanges::traits::ExchangeClientBuilder;
 use crate::infrastructure::spawn_future;
@@ -27,6 +29,7 @@ use mmb_database::postgres_db::PgPool;
 use mmb_domain::events::{ExchangeEvent, ExchangeEvents, CHANNEL_MAX_EVENTS_COUNT};
 use mmb_domain::market::ExchangeAccountId;
 use mmb_domain::market::ExchangeId;
+use mmb_domain::market::MarketAccountId;
 use mmb_utils::infrastructure::{init_infrastructure, SpawnFutureFlags};
 use mmb_utils::logger::print_info;
 use mmb_utils::nothing_to_do;
@@ -45,9 +48,27 @@ use tokio::time::timeout;
 use uuid::Uuid;
 
 use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
+use crate::misc::time::time_manager;
+use crate::services::account_history_import::AccountHistoryImportService;
 use crate::services::cleanup_database::CleanupDatabaseService;
+use crate::services::compliance_export::ComplianceExportService;
+use crate::services::dead_man_switch::DeadManSwitchService;
+use crate::services::diagnostics::DiagnosticsService;
+use crate::services::event_stream::EventStreamService;
 use crate::services::exchange_time_latency::ExchangeTimeLatencyService;
+use crate::services::fill_reconciliation::FillReconciliationService;
+use crate::services::heartbeat::HeartbeatService;
+use crate::services::inventory_transfer::InventoryTransferService;
+use crate::services::latency_probe::LatencyProbeService;
 use crate::services::live_ranges::LiveRangesService;
+use crate::services::market_data_recorder::MarketDataRecorderService;
+use crate::services::order_expiration::OrderExpirationService;
+use crate::services::orders_recovery::OrdersRecoveryService;
+use crate::services::position_unwind::PositionUnwindService;
+use crate::services::stuck_orders_watchdog::StuckOrdersWatchdogService;
+use crate::services::summary_report::SummaryReportService;
+use crate::services::support_bundle::SupportBundleService;
+use crate::services::trading_day_rollover::TradingDayRolloverService;
 
 pub struct EngineBuildConfig {
     pub supported_exchange_clients: HashMap<ExchangeId, Box<dyn ExchangeClientBuilder + 'static>>,
@@ -151,9 +172,16 @@ where
         }
     };
 
+    settings
+        .core
+        .validate()
+        .context("core settings validation failed")?;
+
     let (events_sender, events_receiver) = broadcast::channel(CHANNEL_MAX_EVENTS_COUNT);
 
     let timeout_manager = create_timeout_manager(&settings.core, build_settings);
+    let polling_scheduler =
+        PollingScheduler::new(settings.core.polling.clone(), timeout_manager.clone());
 
     let exchange_account_ids = settings
         .core
@@ -178,9 +206,18 @@ where
         (None, None)
     };
 
-    let event_recorder = EventRecorder::start(pool.clone(), postponed_events_dir)
-        .await
-        .expect("can't start EventRecorder");
+    let postponed_events_max_size = settings
+        .core
+        .database
+        .as_ref()
+        .and_then(|db| db.postponed_events_max_size);
+    let event_recorder = EventRecorder::start(
+        pool.clone(),
+        postponed_events_dir,
+        postponed_events_max_size,
+    )
+    .await
+    .expect("can't start EventRecorder");
 
     let exchanges = create_exchanges(
         &settings.core,
@@ -207,12 +244,9 @@ where
         currency_pair_to_symbol_converter,
         Some(event_recorder.clone()),
     );
-
-    BalanceManager::update_balances_for_exchanges(
-        balance_manager.clone(),
-        lifetime_manager.stop_token(),
-    )
-    .await;
+    balance_manager
+        .lock()
+        .set_reservation_fee(settings.core.reservation_fee);
 
     for exchange in &exchanges_map {
         exchange
@@ -220,7 +254,46 @@ where
             .setup_balance_manager(balance_manager.clone())
     }
 
-    start_updating_balances(&lifetime_manager, &balance_manager);
+    if settings.core.is_recorder() {
+        log::info!("TradingEngine is started in recorder role: balances and orders aren't managed");
+    } else {
+        BalanceManager::update_balances_for_exchanges(
+            balance_manager.clone(),
+            lifetime_manager.stop_token(),
+        )
+        .await;
+
+        if let (Some(pool), Some(import_settings)) = (&pool, &settings.core.account_history_import)
+        {
+            AccountHistoryImportService::new(
+                exchanges_map.clone(),
+                balance_manager.clone(),
+                event_recorder.clone(),
+                pool.clone(),
+                import_settings.clone(),
+            )
+            .run()
+            .await;
+        }
+
+        if let (Some(pool), Some(recovery_settings)) = (&pool, &settings.core.orders_recovery) {
+            OrdersRecoveryService::new(
+                exchanges_map.clone(),
+                pool.clone(),
+                recovery_settings.clone(),
+            )
+            .run()
+            .await;
+        }
+
+        start_updating_balances(
+            &lifetime_manager,
+            &balance_manager,
+            &exchanges_map,
+            &polling_scheduler,
+        );
+        start_capital_usage_reports(&balance_manager);
+    }
 
     let (finish_graceful_shutdown_tx, finish_graceful_shutdown_rx) = oneshot::channel();
 
@@ -231,6 +304,7 @@ where
         finish_graceful_shutdown_tx,
         exchange_blocker,
         timeout_manager,
+        polling_scheduler,
         lifetime_manager.clone(),
         balance_manager,
         event_recorder,
@@ -249,20 +323,39 @@ where
 fn start_updating_balances(
     lifetime_manager: &Arc<AppLifetimeManager>,
     balance_manager: &Arc<Mutex<BalanceManager>>,
+    exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+    polling_scheduler: &Arc<PollingScheduler>,
 ) {
+    for exchange in exchanges.iter().map(|x| x.value().clone()) {
+        let exchange_account_id = exchange.exchange_account_id;
+        let balance_manager = balance_manager.clone();
+        let stop_token = lifetime_manager.stop_token();
+        let _ = polling_scheduler.spawn_polling(
+            &format!("Update balances {exchange_account_id}"),
+            Some(exchange_account_id),
+            Duration::from_secs(60),
+            move || {
+                BalanceManager::update_balance_for_exchange(
+                    balance_manager.clone(),
+                    exchange.clone(),
+                    stop_token.clone(),
+                )
+            },
+        );
+    }
+}
+
+fn start_capital_usage_reports(balance_manager: &Arc<Mutex<BalanceManager>>) {
+    let balance_manager = balance_manager.clone();
     spawn_by_timer(
-        "Update balances",
+        "Capital usage report",
         Duration::from_secs(60),
         Duration::from_secs(60),
         SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
-        {
+        move || {
             let balance_manager = balance_manager.clone();
-            let stop_token = lifetime_manager.stop_token();
-            move || {
-                BalanceManager::update_balances_for_exchanges(
-                    balance_manager.clone(),
-                    stop_token.clone(),
-                )
+            async move {
+                balance_manager.lock().update_capital_usage();
             }
         },
     );
@@ -283,15 +376,64 @@ fn run_services<'a, StrategySettings>(
 where
     StrategySettings: Clone + Debug + Deserialize<'a> + Serialize,
 {
-    let internal_events_loop = InternalEventsLoop::new();
+    let internal_events_loop = start_internal_events_loop(
+        &engine_context,
+        events_receiver,
+        exchanges_map.into_iter().collect(),
+    );
+
+    let inventory_transfer_service = InventoryTransferService::new(
+        engine_context.exchanges.clone(),
+        engine_context.event_recorder.clone(),
+        settings.core.inventory_transfer.clone(),
+        engine_context.lifetime_manager.stop_token(),
+    );
     engine_context
         .shutdown_service
-        .register_core_service(internal_events_loop.clone());
+        .register_core_service(inventory_transfer_service.clone());
+
+    let position_unwind_service = PositionUnwindService::new(
+        engine_context.exchanges.clone(),
+        engine_context.event_recorder.clone(),
+        settings.core.position_unwind.clone(),
+        engine_context.lifetime_manager.stop_token(),
+    );
+    engine_context
+        .shutdown_service
+        .register_core_service(position_unwind_service.clone());
+
+    let diagnostics_service = DiagnosticsService::new(
+        engine_context.exchanges.clone(),
+        engine_context.balance_manager.clone(),
+        engine_context.timeout_manager.clone(),
+        internal_events_loop.clone(),
+    );
+
+    start_sampling_book_consistency(&engine_context, &settings.core, &diagnostics_service);
+
+    let latency_probe_service = start_latency_probe(&engine_context, &settings.core);
+
+    let engine_settings = load_pretty_settings(init_user_settings);
+    let support_bundle_service = SupportBundleService::new(
+        diagnostics_service.clone(),
+        engine_context.statistic_service.clone(),
+        engine_settings.clone(),
+    );
+    let compliance_export_service = ComplianceExportService::new(engine_context.exchanges.clone());
 
     let control_panel = CoreApi::create_and_start(
         engine_context.lifetime_manager.clone(),
-        load_pretty_settings(init_user_settings),
+        engine_settings,
         engine_context.statistic_service.clone(),
+        inventory_transfer_service,
+        position_unwind_service,
+        engine_context.strategy_parameters.clone(),
+        diagnostics_service,
+        support_bundle_service,
+        compliance_export_service,
+        engine_context.pull_triggers.clone(),
+        latency_probe_service,
+        settings.core.decimal_serialization,
     )
     .expect("Unable to start control panel");
     engine_context
@@ -302,40 +444,8 @@ where
         .shutdown_service
         .register_core_service(cleanup_orders_service.clone());
 
-    let _ = spawn_future(
-        "internal_events_loop start",
-        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
-        internal_events_loop.start(
-            events_receiver,
-            exchanges_map.into_iter().collect(),
-            engine_context.lifetime_manager.stop_token(),
-        ),
-    );
-
     if let Some(data_services) = data_services {
-        engine_context
-            .shutdown_service
-            .register_core_service(data_services.live_range_service.clone());
-
-        let _ = spawn_by_timer(
-            "live ranges",
-            Duration::ZERO,
-            Duration::from_secs(1),
-            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
-            move || data_services.live_range_service.clone().push(),
-        );
-
-        engine_context
-            .shutdown_service
-            .register_core_service(data_services.cleanup_database_service.clone());
-
-        let _ = spawn_by_timer(
-            "cleanup database",
-            Duration::ZERO,
-            Duration::from_secs(60 * 60), // one hour
-            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
-            move || data_services.cleanup_database_service.clone().run(),
-        );
+        start_data_services(&engine_context, data_services);
     }
 
     let cleanup_orders_service_weak = Arc::downgrade(&cleanup_orders_service);
@@ -356,6 +466,105 @@ where
         },
     );
 
+    start_stuck_orders_watchdog(&engine_context, &settings.core);
+
+    let order_expiration_service = Arc::new(OrderExpirationService::new(
+        engine_context.exchanges.clone(),
+        engine_context.lifetime_manager.stop_token(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(order_expiration_service.clone());
+
+    let order_expiration_service_weak = Arc::downgrade(&order_expiration_service);
+
+    let _ = spawn_by_timer(
+        "cancel_expired_orders",
+        Duration::ZERO,
+        Duration::from_secs(1),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let order_expiration_service_weak = order_expiration_service_weak.clone();
+
+            async move {
+                if let Some(order_expiration_service) = order_expiration_service_weak.upgrade() {
+                    order_expiration_service.cancel_expired_orders().await
+                }
+            }
+        },
+    );
+
+    start_fill_reconciliation(&engine_context, &settings.core);
+    start_updating_market_data_modes(&engine_context, &settings.core);
+    start_checking_connectivity_flaps(&engine_context, &settings.core);
+    start_event_stream(&engine_context, &settings.core);
+    start_dead_man_switch(&engine_context, &settings.core);
+
+    start_heartbeat(&engine_context, &settings.core);
+
+    let summary_report_service = Arc::new(SummaryReportService::new(
+        engine_context.exchanges.clone(),
+        engine_context.balance_manager.clone(),
+        engine_context.statistic_service.clone(),
+        engine_context.event_recorder.clone(),
+        settings.core.stuck_orders_watchdog.clone(),
+        settings.core.reporting_precision.clone(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(summary_report_service.clone());
+
+    let summary_report_service_weak = Arc::downgrade(&summary_report_service);
+
+    let _ = spawn_by_timer(
+        "send_summary_report",
+        Duration::ZERO,
+        settings.core.summary_report.period(),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let summary_report_service_weak = summary_report_service_weak.clone();
+
+            async move {
+                if let Some(summary_report_service) = summary_report_service_weak.upgrade() {
+                    summary_report_service.send_report().await
+                }
+            }
+        },
+    );
+
+    let trading_day_rollover_service = Arc::new(TradingDayRolloverService::new(
+        &settings.core.trading_day,
+        engine_context.statistic_service.clone(),
+        summary_report_service,
+        engine_context.event_recorder.clone(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(trading_day_rollover_service.clone());
+
+    let trading_day_rollover_service_weak = Arc::downgrade(&trading_day_rollover_service);
+
+    let _ = spawn_by_timer(
+        "check_trading_day_rollover",
+        settings.core.trading_day.check_period(),
+        settings.core.trading_day.check_period(),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let trading_day_rollover_service_weak = trading_day_rollover_service_weak.clone();
+
+            async move {
+                if let Some(trading_day_rollover_service) =
+                    trading_day_rollover_service_weak.upgrade()
+                {
+                    trading_day_rollover_service.check_rollover().await
+                }
+            }
+        },
+    );
+
     engine_context
         .shutdown_service
         .register_core_service(exchange_time_latency_service.clone());
@@ -376,6 +585,379 @@ where
     TradingEngine::new(engine_context, settings, finish_graceful_shutdown_rx)
 }
 
+/// Starts only market data handling and its recording without order management and strategies
+fn run_recorder_services<StrategySettings>(
+    engine_context: Arc<EngineContext>,
+    events_receiver: broadcast::Receiver<ExchangeEvent>,
+    settings: AppSettings<StrategySettings>,
+    exchanges_map: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    finish_graceful_shutdown_rx: oneshot::Receiver<ActionAfterGracefulShutdown>,
+    data_services: Option<DataServices>,
+) -> TradingEngine<StrategySettings>
+where
+    StrategySettings: Clone,
+{
+    if settings.core.database.is_none() {
+        log::warn!("Database isn't set in settings, so market data of recorder role isn't saved");
+    }
+
+    let market_data_recorder_service = MarketDataRecorderService::new(
+        engine_context.event_recorder.clone(),
+        engine_context.liveness_registry.clone(),
+    );
+    engine_context
+        .shutdown_service
+        .register_core_service(market_data_recorder_service.clone());
+
+    let _ = spawn_future(
+        "market_data_recorder start",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        market_data_recorder_service.start(
+            engine_context.get_events_channel(),
+            engine_context.lifetime_manager.stop_token(),
+        ),
+    );
+
+    let _ = start_internal_events_loop(
+        &engine_context,
+        events_receiver,
+        exchanges_map.into_iter().collect(),
+    );
+
+    if let Some(data_services) = data_services {
+        start_data_services(&engine_context, data_services);
+    }
+
+    start_updating_market_data_modes(&engine_context, &settings.core);
+    start_heartbeat(&engine_context, &settings.core);
+
+    log::info!("TradingEngine started in recorder role");
+    TradingEngine::new(engine_context, settings, finish_graceful_shutdown_rx)
+}
+
+fn start_internal_events_loop(
+    engine_context: &Arc<EngineContext>,
+    events_receiver: broadcast::Receiver<ExchangeEvent>,
+    exchanges_map: HashMap<ExchangeAccountId, Arc<Exchange>>,
+) -> Arc<InternalEventsLoop> {
+    let internal_events_loop =
+        InternalEventsLoop::new(&engine_context.core_settings.redundant_feeds);
+    engine_context
+        .shutdown_service
+        .register_core_service(internal_events_loop.clone());
+
+    let _ = spawn_future(
+        "internal_events_loop start",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        internal_events_loop.clone().start(
+            events_receiver,
+            exchanges_map,
+            engine_context.liveness_registry.clone(),
+            engine_context.strategy_events_router.clone(),
+            engine_context.lifetime_manager.stop_token(),
+        ),
+    );
+
+    internal_events_loop
+}
+
+fn start_data_services(engine_context: &Arc<EngineContext>, data_services: DataServices) {
+    engine_context
+        .shutdown_service
+        .register_core_service(data_services.live_range_service.clone());
+
+    let _ = spawn_by_timer(
+        "live ranges",
+        Duration::ZERO,
+        Duration::from_secs(1),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || data_services.live_range_service.clone().push(),
+    );
+
+    engine_context
+        .shutdown_service
+        .register_core_service(data_services.cleanup_database_service.clone());
+
+    let _ = spawn_by_timer(
+        "cleanup database",
+        Duration::ZERO,
+        Duration::from_secs(60 * 60), // one hour
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || data_services.cleanup_database_service.clone().run(),
+    );
+}
+
+fn start_updating_market_data_modes(
+    engine_context: &Arc<EngineContext>,
+    core_settings: &CoreSettings,
+) {
+    let market_data_modes = engine_context.market_data_modes.clone();
+    let exchanges = engine_context.exchanges.clone();
+    let stale_timeout = core_settings.market_data_mode.stale_timeout();
+
+    let _ = spawn_by_timer(
+        "update_market_data_modes",
+        core_settings.market_data_mode.check_period(),
+        core_settings.market_data_mode.check_period(),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            market_data_modes.update(&exchanges, stale_timeout);
+            futures::future::ready(())
+        },
+    );
+}
+
+fn start_event_stream(engine_context: &Arc<EngineContext>, core_settings: &CoreSettings) {
+    let Some(event_stream_settings) = core_settings.event_stream.clone() else {
+        return;
+    };
+
+    let event_stream_service = EventStreamService::new(event_stream_settings);
+    engine_context
+        .shutdown_service
+        .register_core_service(event_stream_service.clone());
+
+    let _ = spawn_future(
+        "event_stream start",
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        event_stream_service.start(
+            engine_context.get_events_channel(),
+            engine_context.lifetime_manager.stop_token(),
+        ),
+    );
+}
+
+fn start_checking_connectivity_flaps(
+    engine_context: &Arc<EngineContext>,
+    core_settings: &CoreSettings,
+) {
+    if core_settings.pull_triggers.connectivity_flap.is_none() {
+        return;
+    }
+
+    let pull_triggers = engine_context.pull_triggers.clone();
+    let exchanges = engine_context.exchanges.clone();
+    let _ = spawn_by_timer(
+        "check_connectivity_flaps",
+        core_settings.market_data_mode.check_period(),
+        core_settings.market_data_mode.check_period(),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            pull_triggers.check_connectivity(&exchanges, time_manager::now());
+            futures::future::ready(())
+        },
+    );
+}
+
+fn start_sampling_book_consistency(
+    engine_context: &Arc<EngineContext>,
+    core_settings: &CoreSettings,
+    diagnostics_service: &Arc<DiagnosticsService>,
+) {
+    let depth = core_settings.book_consistency.depth;
+    for market in &core_settings.book_consistency.markets {
+        let market_account_id =
+            MarketAccountId::new(market.exchange_account_id, market.currency_pair);
+        let diagnostics_service_weak = Arc::downgrade(diagnostics_service);
+
+        let _ = engine_context.polling_scheduler.spawn_polling(
+            &format!("sample_book_consistency_{market_account_id}"),
+            Some(market_account_id.exchange_account_id),
+            core_settings.book_consistency.period(),
+            move || {
+                let diagnostics_service_weak = diagnostics_service_weak.clone();
+
+                async move {
+                    if let Some(diagnostics_service) = diagnostics_service_weak.upgrade() {
+                        diagnostics_service
+                            .sample_book_consistency(market_account_id, depth)
+                            .await
+                    }
+                }
+            },
+        );
+    }
+}
+
+fn start_latency_probe(
+    engine_context: &Arc<EngineContext>,
+    core_settings: &CoreSettings,
+) -> Option<Arc<LatencyProbeService>> {
+    let settings = core_settings.latency_probe.clone()?;
+    let period = settings.period();
+
+    let latency_probe_service = Arc::new(LatencyProbeService::new(
+        engine_context.exchanges.clone(),
+        engine_context.event_recorder.clone(),
+        engine_context.timeout_manager.clone(),
+        settings,
+        engine_context.lifetime_manager.stop_token(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(latency_probe_service.clone());
+
+    for exchange_account_id in engine_context.exchanges.iter().map(|x| *x.key()) {
+        let latency_probe_service_weak = Arc::downgrade(&latency_probe_service);
+
+        let _ = engine_context.polling_scheduler.spawn_polling(
+            &format!("probe_latency_{exchange_account_id}"),
+            Some(exchange_account_id),
+            period,
+            move || {
+                let latency_probe_service_weak = latency_probe_service_weak.clone();
+
+                async move {
+                    if let Some(latency_probe_service) = latency_probe_service_weak.upgrade() {
+                        latency_probe_service.probe(exchange_account_id).await
+                    }
+                }
+            },
+        );
+    }
+
+    Some(latency_probe_service)
+}
+
+fn start_stuck_orders_watchdog(engine_context: &Arc<EngineContext>, core_settings: &CoreSettings) {
+    let Some(watchdog_settings) = &core_settings.stuck_orders_watchdog else {
+        return;
+    };
+
+    let stuck_orders_watchdog_service = Arc::new(StuckOrdersWatchdogService::new(
+        engine_context.exchanges.clone(),
+        watchdog_settings.clone(),
+        engine_context.lifetime_manager.stop_token(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(stuck_orders_watchdog_service.clone());
+
+    let stuck_orders_watchdog_service_weak = Arc::downgrade(&stuck_orders_watchdog_service);
+
+    let _ = engine_context.polling_scheduler.spawn_polling(
+        "check_stuck_orders",
+        None,
+        watchdog_settings.check_period(),
+        move || {
+            let stuck_orders_watchdog_service_weak = stuck_orders_watchdog_service_weak.clone();
+
+            async move {
+                if let Some(stuck_orders_watchdog_service) =
+                    stuck_orders_watchdog_service_weak.upgrade()
+                {
+                    stuck_orders_watchdog_service.check_stuck_orders().await
+                }
+            }
+        },
+    );
+}
+
+fn start_fill_reconciliation(engine_context: &Arc<EngineContext>, core_settings: &CoreSettings) {
+    let Some(fill_reconciliation_settings) = &core_settings.fill_reconciliation else {
+        return;
+    };
+
+    let fill_reconciliation_service = Arc::new(FillReconciliationService::new(
+        engine_context.exchanges.clone(),
+        fill_reconciliation_settings.clone(),
+        engine_context.lifetime_manager.stop_token(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(fill_reconciliation_service.clone());
+
+    let fill_reconciliation_service_weak = Arc::downgrade(&fill_reconciliation_service);
+
+    let _ = engine_context.polling_scheduler.spawn_polling(
+        "reconcile_fills",
+        None,
+        fill_reconciliation_settings.period(),
+        move || {
+            let fill_reconciliation_service_weak = fill_reconciliation_service_weak.clone();
+
+            async move {
+                if let Some(fill_reconciliation_service) =
+                    fill_reconciliation_service_weak.upgrade()
+                {
+                    fill_reconciliation_service.reconcile_fills().await
+                }
+            }
+        },
+    );
+}
+
+fn start_dead_man_switch(engine_context: &Arc<EngineContext>, core_settings: &CoreSettings) {
+    let Some(dead_man_switch_settings) = &core_settings.dead_man_switch else {
+        return;
+    };
+
+    let dead_man_switch_service = Arc::new(DeadManSwitchService::new(
+        engine_context.exchanges.clone(),
+        engine_context.liveness_registry.clone(),
+        dead_man_switch_settings.clone(),
+        engine_context.pull_triggers.clone(),
+        engine_context.lifetime_manager.stop_token(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(dead_man_switch_service.clone());
+
+    let dead_man_switch_service_weak = Arc::downgrade(&dead_man_switch_service);
+
+    let _ = spawn_by_timer(
+        "check_dead_man_switch",
+        Duration::ZERO,
+        dead_man_switch_settings.period(),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let dead_man_switch_service_weak = dead_man_switch_service_weak.clone();
+
+            async move {
+                if let Some(dead_man_switch_service) = dead_man_switch_service_weak.upgrade() {
+                    dead_man_switch_service.check().await
+                }
+            }
+        },
+    );
+}
+
+fn start_heartbeat(engine_context: &Arc<EngineContext>, core_settings: &CoreSettings) {
+    let heartbeat_service = Arc::new(HeartbeatService::new(
+        engine_context.get_events_sender(),
+        engine_context.exchanges.clone(),
+        engine_context.event_recorder.clone(),
+        engine_context.liveness_registry.clone(),
+        core_settings.heartbeat.clone(),
+    ));
+
+    engine_context
+        .shutdown_service
+        .register_core_service(heartbeat_service.clone());
+
+    let heartbeat_service_weak = Arc::downgrade(&heartbeat_service);
+
+    let _ = spawn_by_timer(
+        "send_heartbeat",
+        core_settings.heartbeat.period(),
+        core_settings.heartbeat.period(),
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let heartbeat_service_weak = heartbeat_service_weak.clone();
+
+            async move {
+                if let Some(heartbeat_service) = heartbeat_service_weak.upgrade() {
+                    heartbeat_service.send_heartbeat().await
+                }
+            }
+        },
+    );
+}
+
 pub(crate) fn unwrap_or_handle_panic<T>(
     action_outcome: Result<T, Box<dyn Any + Send>>,
     message_template: &'static str,
@@ -496,17 +1078,28 @@ where
     ));
 
     let action_outcome = panic::catch_unwind(AssertUnwindSafe(|| {
-        run_services(
-            engine_context.clone(),
-            events_receiver,
-            settings,
-            exchanges_map,
-            init_user_settings,
-            finish_graceful_shutdown_rx,
-            cleanup_orders_service,
-            data_services,
-            exchange_time_latency_service,
-        )
+        if settings.core.is_recorder() {
+            run_recorder_services(
+                engine_context.clone(),
+                events_receiver,
+                settings,
+                exchanges_map,
+                finish_graceful_shutdown_rx,
+                data_services,
+            )
+        } else {
+            run_services(
+                engine_context.clone(),
+                events_receiver,
+                settings,
+                exchanges_map,
+                init_user_settings,
+                finish_graceful_shutdown_rx,
+                cleanup_orders_service,
+                data_services,
+                exchange_time_latency_service,
+            )
+        }
     }));
 
     let message_template = "Panic happened during TradingEngine creation";
@@ -530,16 +1123,50 @@ pub async fn create_exchanges(
     exchange_blocker: Weak<ExchangeBlocker>,
     event_recorder: Arc<EventRecorder>,
 ) -> Vec<Arc<Exchange>> {
-    join_all(core_settings.exchanges.iter().map(|x| {
-        create_exchange(
-            x,
-            build_settings,
-            events_channel.clone(),
-            lifetime_manager.clone(),
-            timeout_manager.clone(),
-            exchange_blocker.clone(),
-            event_recorder.clone(),
-        )
-    }))
-    .await
+    let exchanges = core_settings
+        .exchanges
+        .iter()
+        .map(|x| {
+            create_exchange(
+                x,
+                build_settings,
+                events_channel.clone(),
+                lifetime_manager.clone(),
+                timeout_manager.clone(),
+                exchange_blocker.clone(),
+                event_recorder.clone(),
+                core_settings.operation_policies.clone(),
+            )
+        })
+        .collect_vec();
+
+    for limit_collar in &core_settings.limit_collars {
+        match exchanges
+            .iter()
+            .find(|x| x.exchange_account_id == limit_collar.exchange_account_id)
+        {
+            Some(exchange) => exchange.set_limit_collar(limit_collar.clone()),
+            None => log::warn!(
+                "Limit collar is set for unknown exchange account {}",
+                limit_collar.exchange_account_id
+            ),
+        }
+    }
+
+    join_all(
+        exchanges
+            .iter()
+            .zip(&core_settings.exchanges)
+            .map(|(exchange, x)| {
+                initialize_exchange_with_timeout(
+                    exchange.clone(),
+                    x,
+                    &core_settings.exchange_initialization,
+                    exchange_blocker.clone(),
+                )
+            }),
+    )
+    .await;
+
+    exchanges
 }
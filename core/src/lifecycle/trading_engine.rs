@@ -3,16 +3,24 @@ use crate::balance::manager::balance_manager::BalanceManager;
 use crate::database::events::recorder::EventRecorder;
 use crate::disposition_execution::executor::DispositionExecutorService;
 use crate::disposition_execution::strategy::DispositionStrategy;
+use crate::disposition_execution::tunable_parameters::StrategyParameters;
 use crate::exchanges::block_reasons;
 use crate::exchanges::exchange_blocker::BlockType;
 use crate::exchanges::exchange_blocker::ExchangeBlocker;
 use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::timeouts::polling_scheduler::PollingScheduler;
 use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
 use crate::infrastructure::unset_lifetime_manager;
 use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
 use crate::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use crate::lifecycle::shutdown::ShutdownService;
+use crate::misc::conflated_events_receiver::ConflatedEventsReceiver;
+use crate::misc::market_data_mode::MarketDataModes;
+use crate::misc::pull_triggers::PullTriggers;
+use crate::misc::strategy_bus::StrategyBus;
+use crate::misc::strategy_events_router::StrategyEventsRouter;
 use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use crate::services::heartbeat::LivenessRegistry;
 use crate::settings::DispositionStrategySettings;
 use crate::settings::{AppSettings, CoreSettings};
 use crate::statistic_service::{StatisticEventHandler, StatisticService};
@@ -21,7 +29,7 @@ use dashmap::DashMap;
 use futures::future::join_all;
 use futures::FutureExt;
 use mmb_domain::events::{ExchangeEvent, ExchangeEvents};
-use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId, MarketId};
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::logger::print_info;
@@ -51,9 +59,21 @@ pub struct EngineContext {
     pub exchange_blocker: Arc<ExchangeBlocker>,
     pub lifetime_manager: Arc<AppLifetimeManager>,
     pub timeout_manager: Arc<TimeoutManager>,
+    /// Periodic REST polls with jitter coordinated with rate limits
+    pub polling_scheduler: Arc<PollingScheduler>,
     pub balance_manager: Arc<Mutex<BalanceManager>>,
     pub event_recorder: Arc<EventRecorder>,
     pub statistic_service: Arc<StatisticService>,
+    pub strategy_parameters: Arc<StrategyParameters>,
+    pub liveness_registry: Arc<LivenessRegistry>,
+    /// Per-strategy channels with events of subscribed markets only
+    pub strategy_events_router: Arc<StrategyEventsRouter>,
+    /// Typed messages between strategies
+    pub strategy_bus: Arc<StrategyBus>,
+    /// Availability of websocket market data by exchange accounts
+    pub market_data_modes: Arc<MarketDataModes>,
+    /// Holds of quoting by fired pull triggers
+    pub pull_triggers: Arc<PullTriggers>,
     is_graceful_shutdown_started: AtomicBool,
     exchange_events: ExchangeEvents,
     finish_graceful_shutdown_sender: Mutex<Option<oneshot::Sender<ActionAfterGracefulShutdown>>>,
@@ -68,11 +88,15 @@ impl EngineContext {
         finish_graceful_shutdown_sender: oneshot::Sender<ActionAfterGracefulShutdown>,
         exchange_blocker: Arc<ExchangeBlocker>,
         timeout_manager: Arc<TimeoutManager>,
+        polling_scheduler: Arc<PollingScheduler>,
         lifetime_manager: Arc<AppLifetimeManager>,
         balance_manager: Arc<Mutex<BalanceManager>>,
         event_recorder: Arc<EventRecorder>,
     ) -> Arc<Self> {
-        let statistic_service = StatisticService::new();
+        let statistic_service = StatisticService::new(
+            core_settings.order_to_trade_ratio.as_ref(),
+            core_settings.reporting_precision.clone(),
+        );
         let engine_context = Arc::new(EngineContext {
             core_settings,
             exchanges,
@@ -80,9 +104,19 @@ impl EngineContext {
             exchange_blocker,
             lifetime_manager: lifetime_manager.clone(),
             timeout_manager,
+            polling_scheduler,
             balance_manager,
+            strategy_bus: StrategyBus::new(event_recorder.clone()),
+            pull_triggers: PullTriggers::new(
+                core_settings.pull_triggers.clone(),
+                event_recorder.clone(),
+            ),
             event_recorder,
             statistic_service,
+            strategy_parameters: StrategyParameters::new(),
+            liveness_registry: Default::default(),
+            strategy_events_router: Default::default(),
+            market_data_modes: Default::default(),
             is_graceful_shutdown_started: Default::default(),
             exchange_events,
             finish_graceful_shutdown_sender: Mutex::new(Some(finish_graceful_shutdown_sender)),
@@ -121,6 +155,41 @@ impl EngineContext {
         self.shutdown_service.user_lvl_shutdown().await;
         self.exchange_blocker.stop_blocker().await;
 
+        // orders and positions aren't managed in recorder role
+        if !self.core_settings.is_recorder() {
+            self.cancel_orders_and_close_positions().await;
+        }
+
+        self.shutdown_service.core_lvl_shutdown().await;
+
+        match timeout(Duration::from_secs(5), self.event_recorder.flush_and_stop()).await {
+            Err(_) => log::error!("In graceful shutdown EventRecorder::flush_and_stop() was not finished during 5 seconds"),
+            Ok(Err(err)) => log::error!("In graceful shutdown error from EventRecorder::flush_and_stop(): {err:?}"),
+            Ok(Ok(())) => nothing_to_do(),
+        }
+
+        let disconnect_websockets = self
+            .exchanges
+            .iter()
+            .map(|exchange| async move { exchange.clone().disconnect_ws().await });
+        join_all(disconnect_websockets).await;
+
+        self.finish_graceful_shutdown_sender
+            .lock()
+            .take()
+            .expect("'finish_graceful_shutdown_sender' should exists in EngineContext")
+            .send_expected(action);
+
+        if let ActionAfterGracefulShutdown::Restart = action {
+            futures_cancellation_token.cancel();
+        }
+
+        unset_lifetime_manager();
+
+        print_info("Graceful shutdown finished");
+    }
+
+    async fn cancel_orders_and_close_positions(&self) {
         let cancellation_token = CancellationToken::default();
         const TIMEOUT: Duration = Duration::from_secs(5);
 
@@ -155,39 +224,23 @@ impl EngineContext {
                 );
             }
         }
-
-        self.shutdown_service.core_lvl_shutdown().await;
-
-        match timeout(Duration::from_secs(5), self.event_recorder.flush_and_stop()).await {
-            Err(_) => log::error!("In graceful shutdown EventRecorder::flush_and_stop() was not finished during 5 seconds"),
-            Ok(Err(err)) => log::error!("In graceful shutdown error from EventRecorder::flush_and_stop(): {err:?}"),
-            Ok(Ok(())) => nothing_to_do(),
-        }
-
-        let disconnect_websockets = self
-            .exchanges
-            .iter()
-            .map(|exchange| async move { exchange.clone().disconnect_ws().await });
-        join_all(disconnect_websockets).await;
-
-        self.finish_graceful_shutdown_sender
-            .lock()
-            .take()
-            .expect("'finish_graceful_shutdown_sender' should exists in EngineContext")
-            .send_expected(action);
-
-        if let ActionAfterGracefulShutdown::Restart = action {
-            futures_cancellation_token.cancel();
-        }
-
-        unset_lifetime_manager();
-
-        print_info("Graceful shutdown finished");
     }
 
     pub fn get_events_channel(&self) -> broadcast::Receiver<ExchangeEvent> {
         self.exchange_events.get_events_channel()
     }
+
+    pub fn get_events_sender(&self) -> broadcast::Sender<ExchangeEvent> {
+        self.exchange_events.get_events_sender()
+    }
+
+    /// Events receiver for slow consumers with conflated order book updates
+    pub fn get_conflated_events_receiver(&self) -> ConflatedEventsReceiver {
+        ConflatedEventsReceiver::new(
+            self.get_events_channel(),
+            self.core_settings.market_data_conflation.interval(),
+        )
+    }
 }
 
 async fn cancel_opened_orders(
@@ -271,30 +324,66 @@ impl<StrategySettings: Clone> TradingEngine<StrategySettings> {
     }
 
     /// Starts `DispositionExecutor` trading pattern assumes that orders will be placed
-    /// on the exchange almost all the time
-    pub fn start_disposition_executor(&self, strategy: Box<dyn DispositionStrategy>)
+    /// on the exchange almost all the time. Fails if strategy can't be started
+    /// on configured exchanges
+    pub fn start_disposition_executor(&self, strategy: Box<dyn DispositionStrategy>) -> Result<()>
     where
         StrategySettings: DispositionStrategySettings,
     {
         let ctx = self.context();
         let settings = self.settings();
 
-        let statistics =
-            StatisticEventHandler::new(ctx.get_events_channel(), ctx.statistic_service.clone());
+        if settings.core.is_recorder() {
+            log::warn!(
+                "DispositionExecutor isn't started because TradingEngine is in recorder role"
+            );
+            return Ok(());
+        }
+
+        let statistics = StatisticEventHandler::new(
+            ctx.get_conflated_events_receiver(),
+            ctx.statistic_service.clone(),
+        );
 
         let base_settings = &settings.strategy;
+
+        let mut local_snapshots_service = LocalSnapshotsService::default();
+        if let Some(bucket_size) = base_settings.order_book_price_bucket() {
+            let market_id = MarketId::new(
+                base_settings.exchange_account_id().exchange_id,
+                base_settings.currency_pair(),
+            );
+            local_snapshots_service.add_aggregation(market_id, bucket_size);
+        }
+
+        let market_account_id = MarketAccountId::new(
+            base_settings.exchange_account_id(),
+            base_settings.currency_pair(),
+        );
+        let events_receiver = ctx
+            .strategy_events_router
+            .subscribe(std::iter::once(market_account_id).chain(strategy.subscribed_markets()));
+
         let disposition_executor_service = DispositionExecutorService::new(
             ctx.clone(),
-            ctx.get_events_channel(),
-            LocalSnapshotsService::default(),
+            events_receiver,
+            local_snapshots_service,
             base_settings.exchange_account_id(),
             base_settings.currency_pair(),
             strategy,
+            base_settings.refresh_level_on_fill(),
+            base_settings.feature_recorder(),
+            base_settings.protective_orders(),
+            base_settings.degraded_mode(),
+            base_settings.max_order_age(),
+            base_settings.auto_sizing(),
             ctx.lifetime_manager.stop_token(),
             statistics.stats.clone(),
-        );
+        )?;
 
         ctx.shutdown_service
             .register_user_service(disposition_executor_service);
+
+        Ok(())
     }
 }
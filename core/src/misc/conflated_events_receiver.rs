@@ -0,0 +1,233 @@
+use crate::infrastructure::spawn_future;
+use crate::order_book::local_snapshot_service::LocalSnapshotsService;
+use anyhow::{bail, Result};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::MarketId;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, Notify};
+use tokio::time::{sleep_until, Instant};
+
+#[derive(Default)]
+struct ConflationState {
+    events: VecDeque<ExchangeEvent>,
+    snapshots: LocalSnapshotsService,
+    /// Last order book event by market which state isn't delivered to consumer yet
+    pending_order_books: HashMap<MarketId, OrderBookEvent>,
+    last_delivery_times: HashMap<MarketId, Instant>,
+    is_closed: bool,
+}
+
+impl ConflationState {
+    fn push(&mut self, event: ExchangeEvent) {
+        match event {
+            ExchangeEvent::OrderBookEvent(order_book_event) => {
+                if let Some(market_account_id) = self.snapshots.update(&order_book_event) {
+                    let _ = self
+                        .pending_order_books
+                        .insert(market_account_id.market_id(), order_book_event);
+                }
+            }
+            event => self.events.push_back(event),
+        }
+    }
+
+    /// Returns snapshot of market which can be delivered now or time when it will be possible
+    fn take_order_book(
+        &mut self,
+        now: Instant,
+        min_interval: Duration,
+    ) -> Result<OrderBookEvent, Option<Instant>> {
+        let mut next_delivery_time: Option<Instant> = None;
+        let mut ready_market_id = None;
+        for market_id in self.pending_order_books.keys() {
+            let delivery_time = self
+                .last_delivery_times
+                .get(market_id)
+                .map_or(now, |x| *x + min_interval);
+
+            if delivery_time <= now {
+                ready_market_id = Some(*market_id);
+                break;
+            }
+
+            next_delivery_time =
+                Some(next_delivery_time.map_or(delivery_time, |x| x.min(delivery_time)));
+        }
+
+        let market_id = ready_market_id.ok_or(next_delivery_time)?;
+        let last_event = self
+            .pending_order_books
+            .remove(&market_id)
+            .expect("Market should be pending");
+        let snapshot = self.snapshots.get_snapshot_expected(market_id);
+        let _ = self.last_delivery_times.insert(market_id, now);
+
+        Ok(OrderBookEvent::new(
+            snapshot.last_update_time,
+            last_event.exchange_account_id,
+            last_event.currency_pair,
+            "conflated".to_owned(),
+            EventType::Snapshot,
+            Arc::new(OrderBookData::new(
+                snapshot.asks.clone(),
+                snapshot.bids.clone(),
+            )),
+        ))
+    }
+}
+
+/// Receiver of exchange events for consumers which can't keep up with every order book update
+/// (visualization, statistics). Order book events are coalesced to the latest state of each market
+/// and delivered as snapshots not more often than once per `min_interval` for a market.
+/// Other events (orders, fills, etc.) are delivered unconflated in the original order
+pub struct ConflatedEventsReceiver {
+    state: Arc<Mutex<ConflationState>>,
+    notify: Arc<Notify>,
+    min_interval: Duration,
+}
+
+impl ConflatedEventsReceiver {
+    pub fn new(
+        events_receiver: broadcast::Receiver<ExchangeEvent>,
+        min_interval: Duration,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(ConflationState::default()));
+        let notify = Arc::new(Notify::new());
+
+        let _ = spawn_future(
+            "Conflate exchange events",
+            SpawnFutureFlags::STOP_BY_TOKEN,
+            collect_events(events_receiver, state.clone(), notify.clone()),
+        );
+
+        Self {
+            state,
+            notify,
+            min_interval,
+        }
+    }
+
+    pub async fn recv(&mut self) -> Result<ExchangeEvent> {
+        loop {
+            let next_delivery_time = {
+                let mut state = self.state.lock();
+                if let Some(event) = state.events.pop_front() {
+                    return Ok(event);
+                }
+
+                match state.take_order_book(Instant::now(), self.min_interval) {
+                    Ok(order_book_event) => {
+                        return Ok(ExchangeEvent::OrderBookEvent(order_book_event))
+                    }
+                    Err(_) if state.is_closed => bail!("Events channel is closed"),
+                    Err(next_delivery_time) => next_delivery_time,
+                }
+            };
+
+            match next_delivery_time {
+                Some(next_delivery_time) => tokio::select! {
+                    _ = self.notify.notified() => {},
+                    _ = sleep_until(next_delivery_time) => {},
+                },
+                None => self.notify.notified().await,
+            }
+        }
+    }
+}
+
+async fn collect_events(
+    mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+    state: Arc<Mutex<ConflationState>>,
+    notify: Arc<Notify>,
+) -> Result<()> {
+    loop {
+        match events_receiver.recv().await {
+            Ok(event) => state.lock().push(event),
+            Err(RecvError::Lagged(skipped_count)) => {
+                log::warn!("Conflated events receiver skipped {skipped_count} events")
+            }
+            Err(RecvError::Closed) => {
+                state.lock().is_closed = true;
+                notify.notify_one();
+                return Ok(());
+            }
+        }
+
+        notify.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::events::HeartbeatEvent;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order_book_data;
+    use rust_decimal_macros::dec;
+
+    fn order_book_event(event_type: EventType, data: OrderBookData) -> ExchangeEvent {
+        ExchangeEvent::OrderBookEvent(OrderBookEvent::new(
+            Utc::now(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            "".to_owned(),
+            event_type,
+            Arc::new(data),
+        ))
+    }
+
+    #[test]
+    fn order_book_updates_are_coalesced_to_latest_snapshot() {
+        let mut state = ConflationState::default();
+        state.push(order_book_event(
+            EventType::Snapshot,
+            order_book_data![dec!(10) => dec!(1), ; dec!(9) => dec!(1),],
+        ));
+        state.push(order_book_event(
+            EventType::Update,
+            order_book_data![dec!(10) => dec!(0), dec!(11) => dec!(2), ;],
+        ));
+        state.push(ExchangeEvent::Heartbeat(HeartbeatEvent {
+            time: Utc::now(),
+            subsystems: vec![],
+        }));
+        state.push(order_book_event(
+            EventType::Update,
+            order_book_data![; dec!(9) => dec!(3),],
+        ));
+
+        assert_eq!(state.events.len(), 1);
+
+        let now = Instant::now();
+        let min_interval = Duration::from_secs(1);
+        let event = state.take_order_book(now, min_interval).expect("in test");
+        assert!(matches!(event.event_type, EventType::Snapshot));
+        assert_eq!(
+            *event.data,
+            order_book_data![dec!(11) => dec!(2), ; dec!(9) => dec!(3),]
+        );
+
+        // nothing is pending
+        assert_eq!(state.take_order_book(now, min_interval).err(), Some(None));
+
+        state.push(order_book_event(
+            EventType::Update,
+            order_book_data![dec!(12) => dec!(1), ;],
+        ));
+        assert_eq!(
+            state.take_order_book(now, min_interval).err(),
+            Some(Some(now + min_interval))
+        );
+        assert!(state
+            .take_order_book(now + min_interval, min_interval)
+            .is_ok());
+    }
+}
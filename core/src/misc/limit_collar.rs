@@ -0,0 +1,114 @@
+use crate::settings::LimitCollarSettings;
+use mmb_domain::order::snapshot::{OrderSide, Price};
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::VecDeque;
+
+/// Volatility estimator of market which calculates limit price of collared market orders
+pub(crate) struct LimitCollar {
+    settings: LimitCollarSettings,
+    mid_prices: VecDeque<Price>,
+}
+
+impl LimitCollar {
+    pub fn new(settings: LimitCollarSettings) -> Self {
+        LimitCollar {
+            settings,
+            mid_prices: VecDeque::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &LimitCollarSettings {
+        &self.settings
+    }
+
+    pub fn update(&mut self, mid_price: Price) {
+        self.mid_prices.push_back(mid_price);
+        while self.mid_prices.len() > self.settings.window {
+            let _ = self.mid_prices.pop_front();
+        }
+    }
+
+    /// Standard deviation of middle prices in window relative to their mean
+    fn volatility(&self) -> Option<Decimal> {
+        if self.mid_prices.len() < 2 {
+            return None;
+        }
+
+        let count = Decimal::from(self.mid_prices.len());
+        let mean = self.mid_prices.iter().sum::<Decimal>() / count;
+        if mean <= Decimal::ZERO {
+            return None;
+        }
+
+        let variance = self
+            .mid_prices
+            .iter()
+            .map(|x| (x - mean) * (x - mean))
+            .sum::<Decimal>()
+            / count;
+
+        variance.sqrt().map(|x| x / mean)
+    }
+
+    /// Distance of collar price from middle price relative to it
+    pub fn rate(&self) -> Decimal {
+        let min_rate = self.settings.min_rate;
+        self.volatility()
+            .map_or(min_rate, |x| x * self.settings.sigmas)
+            .clamp(min_rate, self.settings.max_rate.max(min_rate))
+    }
+
+    /// Limit price of market order, `None` until middle price is known
+    pub fn price(&self, side: OrderSide) -> Option<Price> {
+        let mid_price = *self.mid_prices.back()?;
+        let rate = self.rate();
+        Some(match side {
+            OrderSide::Buy => mid_price * (Decimal::ONE + rate),
+            OrderSide::Sell => mid_price * (Decimal::ONE - rate),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use rust_decimal_macros::dec;
+
+    fn collar(max_rate: Decimal) -> LimitCollar {
+        LimitCollar::new(LimitCollarSettings {
+            exchange_account_id: ExchangeAccountId::new("Binance", 0),
+            currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            window: 2,
+            sigmas: dec!(3),
+            min_rate: dec!(0.001),
+            max_rate,
+            expire_after_ms: None,
+        })
+    }
+
+    #[test]
+    fn collar_is_derived_from_volatility() {
+        let mut collar = collar(dec!(0.1));
+        assert_eq!(collar.price(OrderSide::Buy), None);
+
+        // minimal rate is used until volatility is estimated
+        collar.update(dec!(100));
+        assert_eq!(collar.price(OrderSide::Buy), Some(dec!(100.1)));
+
+        // price 100 is out of window, deviation is 1 relative to mean 100
+        collar.update(dec!(99));
+        collar.update(dec!(101));
+        assert_eq!(collar.rate(), dec!(0.03));
+        assert_eq!(collar.price(OrderSide::Buy), Some(dec!(104.03)));
+        assert_eq!(collar.price(OrderSide::Sell), Some(dec!(97.97)));
+    }
+
+    #[test]
+    fn collar_is_limited_by_max_rate() {
+        let mut collar = collar(dec!(0.02));
+        collar.update(dec!(99));
+        collar.update(dec!(101));
+        assert_eq!(collar.rate(), dec!(0.02));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::disposition_execution::TradingContext;
+use crate::exchanges::general::exchange::Exchange;
+use crate::misc::time::time_manager;
+use dashmap::DashMap;
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::OrderSide;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum MarketDataMode {
+    /// Market data is received from websocket in real time
+    #[default]
+    Normal,
+    /// Websocket is disconnected or silent, so only REST data is available and prices may be stale
+    Degraded,
+}
+
+/// Tracks availability of websocket market data of exchanges, so strategies can stop quoting
+/// confidently on stale prices while exchange works only through REST
+#[derive(Default)]
+pub struct MarketDataModes {
+    modes: DashMap<ExchangeAccountId, MarketDataMode>,
+}
+
+impl MarketDataModes {
+    pub fn mode(&self, market_account_id: MarketAccountId) -> MarketDataMode {
+        self.modes
+            .get(&market_account_id.exchange_account_id)
+            .map(|x| *x)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn update(
+        &self,
+        exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+        stale_timeout: Duration,
+    ) {
+        let stale_time = time_manager::now()
+            - chrono::Duration::from_std(stale_timeout)
+                .expect("Unable to convert stale_timeout of market data mode settings");
+
+        for exchange in exchanges.iter() {
+            let exchange_account_id = *exchange.key();
+            let mode = detect_mode(
+                exchange.is_websocket_connected(),
+                exchange.last_websocket_message_time(),
+                stale_time,
+            );
+
+            let prev_mode = self
+                .modes
+                .insert(exchange_account_id, mode)
+                .unwrap_or_default();
+            match (prev_mode, mode) {
+                (MarketDataMode::Normal, MarketDataMode::Degraded) => log::warn!(
+                    "Websocket market data of {exchange_account_id} is unavailable, switched to degraded mode"
+                ),
+                (MarketDataMode::Degraded, MarketDataMode::Normal) => log::info!(
+                    "Websocket market data of {exchange_account_id} is restored, switched to normal mode"
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn detect_mode(
+    is_websocket_connected: bool,
+    last_websocket_message_time: Option<DateTime>,
+    stale_time: DateTime,
+) -> MarketDataMode {
+    match last_websocket_message_time {
+        Some(time) if is_websocket_connected && time >= stale_time => MarketDataMode::Normal,
+        _ => MarketDataMode::Degraded,
+    }
+}
+
+/// Moves quotes of trading context away from strategy prices by `rate` relative to price
+pub(crate) fn widen_spread(trading_context: &mut TradingContext, rate: Decimal, symbol: &Symbol) {
+    for (side, by_side) in trading_context.by_side.iter_mut() {
+        for estimating in &mut by_side.estimating {
+            let (trade_cycle, explanation) = estimating.as_mut_all();
+            let Some(trade_cycle) = trade_cycle else { continue; };
+
+            let order = &mut trade_cycle.disposition.order;
+            order.price = match side {
+                OrderSide::Buy => {
+                    symbol.price_round(order.price * (Decimal::ONE - rate), Round::Floor)
+                }
+                OrderSide::Sell => {
+                    symbol.price_round(order.price * (Decimal::ONE + rate), Round::Ceiling)
+                }
+            };
+            explanation.add_reason(format!(
+                "Price is moved away by rate {rate} because market data is degraded"
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disposition_execution::{TradeCycle, TradeDisposition, TradingContextBySide};
+    use crate::explanation::WithExplanation;
+    use chrono::Utc;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::snapshot::{OrderRole, Price};
+    use rstest::rstest;
+    use rust_decimal_macros::dec;
+
+    #[rstest]
+    #[case::fresh_messages(true, Some(10), MarketDataMode::Normal)]
+    #[case::stale_messages(true, Some(40), MarketDataMode::Degraded)]
+    #[case::no_messages(true, None, MarketDataMode::Degraded)]
+    #[case::disconnected(false, Some(10), MarketDataMode::Degraded)]
+    fn detect_market_data_mode(
+        #[case] is_websocket_connected: bool,
+        #[case] last_message_ago_secs: Option<i64>,
+        #[case] expected: MarketDataMode,
+    ) {
+        let now = Utc::now();
+        let last_message_time =
+            last_message_ago_secs.map(|secs| now - chrono::Duration::seconds(secs));
+        let stale_time = now - chrono::Duration::seconds(30);
+
+        assert_eq!(
+            detect_mode(is_websocket_connected, last_message_time, stale_time),
+            expected
+        );
+    }
+
+    fn side_context(
+        market_account_id: MarketAccountId,
+        side: OrderSide,
+        price: Price,
+    ) -> TradingContextBySide {
+        TradingContextBySide {
+            max_amount: dec!(1),
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role: OrderRole::Maker,
+                    strategy_name: "test".to_string(),
+                    metadata: Default::default(),
+                    disposition: TradeDisposition::new(market_account_id, side, price, dec!(1)),
+                }),
+                explanation: Default::default(),
+            }],
+        }
+    }
+
+    fn price(trading_context: &TradingContext, side: OrderSide) -> Price {
+        trading_context.by_side[side].estimating[0]
+            .value
+            .as_ref()
+            .expect("trade cycle should exist")
+            .disposition
+            .order
+            .price
+    }
+
+    #[test]
+    fn widen_spread_moves_prices_away() {
+        let base = "btc".into();
+        let quote = "usdt".into();
+        let symbol = Symbol::new(
+            false,
+            "btc".into(),
+            base,
+            "usdt".into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        );
+        let market_account_id = MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes(base, quote),
+        );
+
+        let mut trading_context = TradingContext::new(
+            side_context(market_account_id, OrderSide::Buy, dec!(1000)),
+            side_context(market_account_id, OrderSide::Sell, dec!(1001)),
+        );
+        widen_spread(&mut trading_context, dec!(0.0011), &symbol);
+
+        assert_eq!(price(&trading_context, OrderSide::Buy), dec!(998.9));
+        assert_eq!(price(&trading_context, OrderSide::Sell), dec!(1002.2));
+    }
+}
@@ -1,6 +1,15 @@
+pub mod conflated_events_receiver;
+pub(crate) mod limit_collar;
+pub mod market_data_mode;
+pub(crate) mod order_to_trade_ratio;
+pub(crate) mod performance_attribution;
 pub(crate) mod position_helper;
 pub(crate) mod price_source_model;
+pub mod pull_triggers;
 pub mod reserve_parameters;
 pub(crate) mod service_value_tree;
+pub mod strategy_bus;
+pub mod strategy_events_router;
 pub mod time;
+pub mod trading_day;
 pub mod traits;
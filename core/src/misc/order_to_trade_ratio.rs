@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use chrono::Duration;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum OrderActivity {
+    /// Order creation, amend or cancellation
+    OrderRequest,
+    Trade,
+}
+
+/// Counts order requests and trades over a rolling time window
+#[derive(Debug)]
+pub(crate) struct OrderToTradeRatioTracker {
+    window: Duration,
+    activities: VecDeque<(DateTime, OrderActivity)>,
+    order_requests_count: u64,
+    trades_count: u64,
+}
+
+impl OrderToTradeRatioTracker {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            activities: VecDeque::new(),
+            order_requests_count: 0,
+            trades_count: 0,
+        }
+    }
+
+    pub fn register_order_request(&mut self, now: DateTime) {
+        self.order_requests_count += 1;
+        self.activities
+            .push_back((now, OrderActivity::OrderRequest));
+        self.remove_outdated(now);
+    }
+
+    pub fn register_trade(&mut self, now: DateTime) {
+        self.trades_count += 1;
+        self.activities.push_back((now, OrderActivity::Trade));
+        self.remove_outdated(now);
+    }
+
+    /// Ratio of order requests to trades inside the window.
+    /// If there were no trades, the ratio is calculated as if there was a single one
+    pub fn ratio(&mut self, now: DateTime) -> Decimal {
+        self.remove_outdated(now);
+
+        Decimal::from(self.order_requests_count) / Decimal::from(self.trades_count.max(1))
+    }
+
+    fn remove_outdated(&mut self, now: DateTime) {
+        let window_start = now - self.window;
+        while let Some((time, activity)) = self.activities.front() {
+            if *time >= window_start {
+                break;
+            }
+
+            match activity {
+                OrderActivity::OrderRequest => self.order_requests_count -= 1,
+                OrderActivity::Trade => self.trades_count -= 1,
+            }
+            let _ = self.activities.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn time(seconds: u32) -> DateTime {
+        Utc.ymd(2022, 11, 1).and_hms(0, 0, seconds)
+    }
+
+    #[test]
+    fn ratio_without_trades_equals_order_requests_count() {
+        let mut tracker = OrderToTradeRatioTracker::new(Duration::seconds(10));
+        tracker.register_order_request(time(0));
+        tracker.register_order_request(time(1));
+        tracker.register_order_request(time(2));
+
+        assert_eq!(tracker.ratio(time(3)), dec!(3));
+    }
+
+    #[test]
+    fn ratio_with_trades() {
+        let mut tracker = OrderToTradeRatioTracker::new(Duration::seconds(10));
+        (0..5).for_each(|x| tracker.register_order_request(time(x)));
+        tracker.register_trade(time(5));
+        tracker.register_trade(time(6));
+
+        assert_eq!(tracker.ratio(time(7)), dec!(2.5));
+    }
+
+    #[test]
+    fn outdated_activities_are_excluded() {
+        let mut tracker = OrderToTradeRatioTracker::new(Duration::seconds(10));
+        (0..4).for_each(|x| tracker.register_order_request(time(x)));
+        tracker.register_trade(time(12));
+        tracker.register_order_request(time(13));
+
+        assert_eq!(tracker.ratio(time(13)), dec!(2));
+        assert_eq!(tracker.ratio(time(30)), dec!(0));
+    }
+}
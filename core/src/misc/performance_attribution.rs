@@ -0,0 +1,410 @@
+use std::collections::{HashMap, HashSet};
+
+use mmb_domain::events::CashFlowKind;
+use mmb_domain::market::MarketAccountId;
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_domain::reporting_precision::ReportingPrecision;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Split of strategy PnL on market in quote currency
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceAttribution {
+    /// Gain of fills relative to benchmark price at the moment of fill
+    pub spread_capture: Decimal,
+    /// Revaluation of held position by moves of benchmark price
+    pub inventory_pnl: Decimal,
+    /// Paid fees, maker rebates decrease them
+    pub fees: Decimal,
+    /// Received funding payments, paid ones are negative
+    pub funding: Decimal,
+    pub total_pnl: Decimal,
+    /// Filled volume in quote currency
+    pub turnover: Decimal,
+    /// Position of strategy at the end of period
+    pub position: Amount,
+}
+
+impl PerformanceAttribution {
+    fn add_spread_capture(&mut self, value: Decimal) {
+        self.spread_capture += value;
+        self.total_pnl += value;
+    }
+
+    fn add_inventory_pnl(&mut self, value: Decimal) {
+        self.inventory_pnl += value;
+        self.total_pnl += value;
+    }
+
+    fn add_fees(&mut self, value: Decimal) {
+        self.fees += value;
+        self.total_pnl -= value;
+    }
+
+    fn add_funding(&mut self, value: Decimal) {
+        self.funding += value;
+        self.total_pnl += value;
+    }
+
+    fn rounded(&self, market_account_id: MarketAccountId, precision: &ReportingPrecision) -> Self {
+        let base = market_account_id.currency_pair.to_codes().base;
+        Self {
+            spread_capture: precision.round_default(self.spread_capture),
+            inventory_pnl: precision.round_default(self.inventory_pnl),
+            fees: precision.round_default(self.fees),
+            funding: precision.round_default(self.funding),
+            total_pnl: precision.round_default(self.total_pnl),
+            turnover: precision.round_default(self.turnover),
+            position: precision.round_amount(base, self.position),
+        }
+    }
+
+    /// Attribution for next trading day: PnL is reset, position is kept
+    fn start_next_day(&self) -> Self {
+        Self {
+            position: self.position,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct AttributionState {
+    position: Amount,
+    /// Benchmark price the position was revalued last time
+    last_price: Option<Price>,
+    since_start: PerformanceAttribution,
+    daily: PerformanceAttribution,
+}
+
+impl AttributionState {
+    fn update(&mut self, update: impl Fn(&mut PerformanceAttribution)) {
+        update(&mut self.since_start);
+        update(&mut self.daily);
+    }
+
+    fn revalue(&mut self, price: Price) {
+        if let Some(last_price) = self.last_price {
+            let pnl = self.position * (price - last_price);
+            if !pnl.is_zero() {
+                self.update(|x| x.add_inventory_pnl(pnl));
+            }
+        }
+
+        self.last_price = Some(price);
+    }
+}
+
+/// Attributes PnL of strategies to spread capture, inventory revaluation, fees and funding.
+/// Benchmark price of market is index price if exchange sends it, otherwise mid price of order book
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct PerformanceAttributionTracker {
+    /// Attribution states by strategy name and market
+    strategies: HashMap<String, HashMap<MarketAccountId, AttributionState>>,
+    benchmark_prices: HashMap<MarketAccountId, Price>,
+    /// Markets with index price, mid prices of order books are ignored for them
+    index_price_markets: HashSet<MarketAccountId>,
+}
+
+impl PerformanceAttributionTracker {
+    pub fn update_index_price(&mut self, market_account_id: MarketAccountId, price: Price) {
+        let _ = self.index_price_markets.insert(market_account_id);
+        self.update_benchmark_price(market_account_id, price);
+    }
+
+    pub fn update_mid_price(&mut self, market_account_id: MarketAccountId, price: Price) {
+        if !self.index_price_markets.contains(&market_account_id) {
+            self.update_benchmark_price(market_account_id, price);
+        }
+    }
+
+    fn update_benchmark_price(&mut self, market_account_id: MarketAccountId, price: Price) {
+        let _ = self.benchmark_prices.insert(market_account_id, price);
+
+        for markets in self.strategies.values_mut() {
+            if let Some(state) = markets.get_mut(&market_account_id) {
+                state.revalue(price);
+            }
+        }
+    }
+
+    /// Register fill with fee in quote currency. If there is no benchmark price yet,
+    /// mid price at the moment of fill or fill price is used
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_fill(
+        &mut self,
+        strategy_name: &str,
+        market_account_id: MarketAccountId,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        fee: Decimal,
+        mid_price: Option<Price>,
+    ) {
+        let _ = self
+            .strategies
+            .entry(strategy_name.to_owned())
+            .or_default()
+            .entry(market_account_id)
+            .or_default();
+
+        let benchmark_price = match self.index_price_markets.contains(&market_account_id) {
+            true => self.benchmark_prices.get(&market_account_id).copied(),
+            false => mid_price.or_else(|| self.benchmark_prices.get(&market_account_id).copied()),
+        }
+        .unwrap_or(price);
+        self.update_benchmark_price(market_account_id, benchmark_price);
+
+        let (spread_capture, position_change) = match side {
+            OrderSide::Buy => ((benchmark_price - price) * amount, amount),
+            OrderSide::Sell => ((price - benchmark_price) * amount, -amount),
+        };
+
+        let state = self.state_mut(strategy_name, market_account_id);
+        state.position += position_change;
+        let position = state.position;
+        state.update(|x| {
+            x.add_spread_capture(spread_capture);
+            x.add_fees(fee);
+            x.turnover += price * amount;
+            x.position = position;
+        });
+    }
+
+    /// Cash flow of market in quote currency is split between strategies by their positions.
+    /// Accrued fees are attributed to fees
+    pub fn register_cash_flow(
+        &mut self,
+        market_account_id: MarketAccountId,
+        kind: CashFlowKind,
+        amount: Amount,
+    ) {
+        let states = self
+            .strategies
+            .values_mut()
+            .filter_map(|markets| markets.get_mut(&market_account_id))
+            .filter(|state| !state.position.is_zero())
+            .collect::<Vec<_>>();
+
+        let total_position: Decimal = states.iter().map(|x| x.position.abs()).sum();
+        if total_position.is_zero() {
+            log::warn!("Cash flow {kind:?} {amount} on {market_account_id} isn't attributed to strategies because there are no positions");
+            return;
+        }
+
+        for state in states {
+            let share = amount * state.position.abs() / total_position;
+            match kind {
+                CashFlowKind::Funding => state.update(|x| x.add_funding(share)),
+                CashFlowKind::AccruedFee => state.update(|x| x.add_fees(-share)),
+            }
+        }
+    }
+
+    pub fn benchmark_price(&self, market_account_id: MarketAccountId) -> Option<Price> {
+        self.benchmark_prices.get(&market_account_id).copied()
+    }
+
+    fn state_mut(
+        &mut self,
+        strategy_name: &str,
+        market_account_id: MarketAccountId,
+    ) -> &mut AttributionState {
+        self.strategies
+            .get_mut(strategy_name)
+            .and_then(|markets| markets.get_mut(&market_account_id))
+            .expect("Attribution state should be created before update")
+    }
+
+    /// Reset daily attribution and return attribution of finished trading day
+    pub fn start_trading_day(
+        &mut self,
+    ) -> HashMap<String, HashMap<MarketAccountId, PerformanceAttribution>> {
+        let daily = self.collect(|state| &state.daily);
+        for markets in self.strategies.values_mut() {
+            for state in markets.values_mut() {
+                state.daily = state.daily.start_next_day();
+            }
+        }
+
+        daily
+    }
+
+    fn collect(
+        &self,
+        get: impl Fn(&AttributionState) -> &PerformanceAttribution,
+    ) -> HashMap<String, HashMap<MarketAccountId, PerformanceAttribution>> {
+        self.strategies
+            .iter()
+            .map(|(strategy_name, markets)| {
+                let markets = markets
+                    .iter()
+                    .map(|(market_account_id, state)| (*market_account_id, get(state).clone()))
+                    .collect();
+                (strategy_name.clone(), markets)
+            })
+            .collect()
+    }
+
+    /// Copy of tracker with attribution rounded for reporting
+    pub fn rounded(&self, precision: &ReportingPrecision) -> Self {
+        let strategies = self
+            .strategies
+            .iter()
+            .map(|(strategy_name, markets)| {
+                let markets = markets
+                    .iter()
+                    .map(|(market_account_id, state)| {
+                        let state = AttributionState {
+                            since_start: state.since_start.rounded(*market_account_id, precision),
+                            daily: state.daily.rounded(*market_account_id, precision),
+                            ..state.clone()
+                        };
+                        (*market_account_id, state)
+                    })
+                    .collect();
+                (strategy_name.clone(), markets)
+            })
+            .collect();
+
+        Self {
+            strategies,
+            ..self.clone()
+        }
+    }
+}
+
+/// Round attribution of strategies for reporting
+pub(crate) fn round_attribution(
+    attribution: HashMap<String, HashMap<MarketAccountId, PerformanceAttribution>>,
+    precision: &ReportingPrecision,
+) -> HashMap<String, HashMap<MarketAccountId, PerformanceAttribution>> {
+    attribution
+        .into_iter()
+        .map(|(strategy_name, markets)| {
+            let markets = markets
+                .into_iter()
+                .map(|(market_account_id, x)| {
+                    (market_account_id, x.rounded(market_account_id, precision))
+                })
+                .collect();
+            (strategy_name, markets)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use rust_decimal_macros::dec;
+
+    const STRATEGY: &str = "test_strategy";
+
+    fn market_account_id() -> MarketAccountId {
+        MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        )
+    }
+
+    fn attribution(tracker: &PerformanceAttributionTracker) -> PerformanceAttribution {
+        tracker.strategies[STRATEGY][&market_account_id()]
+            .since_start
+            .clone()
+    }
+
+    #[test]
+    fn split_pnl_of_round_trip() {
+        let mut tracker = PerformanceAttributionTracker::default();
+        let market = market_account_id();
+
+        // Bought 2 below mid, then mid moved up by 10 and sold 1 above mid
+        tracker.update_mid_price(market, dec!(100));
+        tracker.register_fill(
+            STRATEGY,
+            market,
+            OrderSide::Buy,
+            dec!(99),
+            dec!(2),
+            dec!(0.1),
+            Some(dec!(100)),
+        );
+        tracker.update_mid_price(market, dec!(110));
+        tracker.register_fill(
+            STRATEGY,
+            market,
+            OrderSide::Sell,
+            dec!(111),
+            dec!(2),
+            dec!(-0.05),
+            Some(dec!(110)),
+        );
+
+        let attribution = attribution(&tracker);
+        assert_eq!(attribution.spread_capture, dec!(4));
+        assert_eq!(attribution.inventory_pnl, dec!(20));
+        assert_eq!(attribution.fees, dec!(0.05));
+        assert_eq!(attribution.position, dec!(0));
+        // Cash flow of fills: -198 + 222 - fees
+        assert_eq!(attribution.total_pnl, dec!(23.95));
+    }
+
+    #[test]
+    fn index_price_is_preferred_to_mid_price() {
+        let mut tracker = PerformanceAttributionTracker::default();
+        let market = market_account_id();
+
+        tracker.update_index_price(market, dec!(100));
+        tracker.update_mid_price(market, dec!(105));
+        tracker.register_fill(
+            STRATEGY,
+            market,
+            OrderSide::Buy,
+            dec!(101),
+            dec!(1),
+            dec!(0),
+            Some(dec!(105)),
+        );
+
+        assert_eq!(attribution(&tracker).spread_capture, dec!(-1));
+        assert_eq!(tracker.benchmark_price(market), Some(dec!(100)));
+    }
+
+    #[test]
+    fn cash_flows_are_split_by_positions() {
+        let mut tracker = PerformanceAttributionTracker::default();
+        let market = market_account_id();
+        let other_strategy = "other_strategy";
+
+        tracker.update_index_price(market, dec!(100));
+        for (strategy_name, amount) in [(STRATEGY, dec!(3)), (other_strategy, dec!(1))] {
+            tracker.register_fill(
+                strategy_name,
+                market,
+                OrderSide::Buy,
+                dec!(100),
+                amount,
+                dec!(0),
+                None,
+            );
+        }
+        tracker.register_cash_flow(market, CashFlowKind::Funding, dec!(-8));
+        tracker.register_cash_flow(market, CashFlowKind::AccruedFee, dec!(-0.4));
+
+        assert_eq!(attribution(&tracker).funding, dec!(-6));
+        assert_eq!(
+            tracker.strategies[other_strategy][&market]
+                .since_start
+                .funding,
+            dec!(-2)
+        );
+
+        let daily = tracker.start_trading_day();
+        assert_eq!(daily[STRATEGY][&market].fees, dec!(0.3));
+        assert_eq!(daily[STRATEGY][&market].total_pnl, dec!(-6.3));
+        let next_day = tracker.start_trading_day();
+        assert_eq!(next_day[STRATEGY][&market].total_pnl, dec!(0));
+        assert_eq!(next_day[STRATEGY][&market].position, dec!(3));
+    }
+}
@@ -0,0 +1,385 @@
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+use crate::settings::{ConnectivityFlapTriggerSettings, PullTriggersSettings};
+use dashmap::DashMap;
+use mmb_database::impl_event;
+use mmb_domain::decimal_serialization::decimal;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::Price;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// Count of last trigger events kept for report
+const MAX_RECENT_EVENTS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum PullCause {
+    VolatilitySpike {
+        #[serde(with = "decimal")]
+        move_rate: Decimal,
+    },
+    IndexPriceGap {
+        #[serde(with = "decimal")]
+        gap_rate: Decimal,
+    },
+    ExternalSignal {
+        reason: String,
+    },
+    ConnectivityFlap {
+        disconnects: usize,
+    },
+    StrategyStalled {
+        subsystem: String,
+    },
+}
+
+impl Display for PullCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PullCause::VolatilitySpike { move_rate } => {
+                write!(f, "volatility spike with price move rate {move_rate}")
+            }
+            PullCause::IndexPriceGap { gap_rate } => {
+                write!(f, "gap with index price at rate {gap_rate}")
+            }
+            PullCause::ExternalSignal { reason } => write!(f, "external signal '{reason}'"),
+            PullCause::ConnectivityFlap { disconnects } => {
+                write!(f, "connectivity flap with {disconnects} disconnects")
+            }
+            PullCause::StrategyStalled { subsystem } => write!(f, "stall of {subsystem}"),
+        }
+    }
+}
+
+/// Firing of pull trigger. Currency pair isn't set if all markets of exchange account are affected,
+/// strategy name isn't set if all strategies of market are affected
+#[derive(Debug, Clone, Serialize)]
+pub struct PullTriggerEvent {
+    pub time: DateTime,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: Option<CurrencyPair>,
+    pub strategy_name: Option<String>,
+    pub cause: PullCause,
+    pub hold_until: DateTime,
+}
+
+impl_event!(PullTriggerEvent, "pull_trigger_events");
+
+/// Holds of quoting by fired pull triggers. Disposition executors cancel all quotes of market
+/// while it's held and check market conditions of their own markets for triggers
+pub struct PullTriggers {
+    settings: PullTriggersSettings,
+    event_recorder: Arc<EventRecorder>,
+    /// Time until quoting is held by market or by whole exchange account
+    holds: Mutex<HashMap<(ExchangeAccountId, Option<CurrencyPair>), DateTime>>,
+    /// Time until quoting of single strategy on market is held
+    strategy_holds: Mutex<HashMap<(MarketAccountId, String), DateTime>>,
+    recent_events: Mutex<VecDeque<PullTriggerEvent>>,
+    disconnects: Mutex<HashMap<ExchangeAccountId, DisconnectsWindow>>,
+}
+
+impl PullTriggers {
+    pub fn new(settings: PullTriggersSettings, event_recorder: Arc<EventRecorder>) -> Arc<Self> {
+        Arc::new(PullTriggers {
+            settings,
+            event_recorder,
+            holds: Default::default(),
+            strategy_holds: Default::default(),
+            recent_events: Default::default(),
+            disconnects: Default::default(),
+        })
+    }
+
+    pub fn settings(&self) -> &PullTriggersSettings {
+        &self.settings
+    }
+
+    /// Hold quoting of market or of all markets of exchange account for cool-down period
+    pub fn pull(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: Option<CurrencyPair>,
+        cause: PullCause,
+        now: DateTime,
+    ) -> PullTriggerEvent {
+        let hold_until = now + self.settings.cool_down();
+        let event = PullTriggerEvent {
+            time: now,
+            exchange_account_id,
+            currency_pair,
+            strategy_name: None,
+            cause,
+            hold_until,
+        };
+
+        let scope = match currency_pair {
+            Some(currency_pair) => format!("{exchange_account_id} {currency_pair}"),
+            None => format!("all markets of {exchange_account_id}"),
+        };
+
+        let _ = self
+            .holds
+            .lock()
+            .insert((exchange_account_id, currency_pair), hold_until);
+
+        self.record(event, &scope)
+    }
+
+    /// Hold quoting of single strategy on market for cool-down period, other strategies of market
+    /// keep quoting
+    pub fn pull_strategy(
+        &self,
+        market_account_id: MarketAccountId,
+        strategy_name: &str,
+        cause: PullCause,
+        now: DateTime,
+    ) -> PullTriggerEvent {
+        let hold_until = now + self.settings.cool_down();
+        let event = PullTriggerEvent {
+            time: now,
+            exchange_account_id: market_account_id.exchange_account_id,
+            currency_pair: Some(market_account_id.currency_pair),
+            strategy_name: Some(strategy_name.to_owned()),
+            cause,
+            hold_until,
+        };
+
+        let _ = self
+            .strategy_holds
+            .lock()
+            .insert((market_account_id, strategy_name.to_owned()), hold_until);
+
+        self.record(event, &format!("{strategy_name} on {market_account_id}"))
+    }
+
+    fn record(&self, event: PullTriggerEvent, scope: &str) -> PullTriggerEvent {
+        log::warn!(
+            "Quotes of {scope} are pulled until {} because of {}",
+            event.hold_until,
+            event.cause
+        );
+
+        let mut recent_events = self.recent_events.lock();
+        recent_events.push_back(event.clone());
+        while recent_events.len() > MAX_RECENT_EVENTS {
+            let _ = recent_events.pop_front();
+        }
+        drop(recent_events);
+
+        if let Err(err) = self.event_recorder.save(event.clone()) {
+            log::error!("Failed to save pull trigger event for {scope}: {err:?}");
+        }
+
+        event
+    }
+
+    /// Returns `None` if quoting of market isn't held at the moment
+    pub fn hold_until(
+        &self,
+        market_account_id: MarketAccountId,
+        now: DateTime,
+    ) -> Option<DateTime> {
+        let holds = self.holds.lock();
+        let exchange_account_id = market_account_id.exchange_account_id;
+        [Some(market_account_id.currency_pair), None]
+            .into_iter()
+            .filter_map(|currency_pair| holds.get(&(exchange_account_id, currency_pair)))
+            .filter(|&&hold_until| hold_until > now)
+            .max()
+            .cloned()
+    }
+
+    /// Returns `None` if quoting of strategy on market isn't held at the moment. Holds of whole
+    /// market are taken into account too
+    pub fn strategy_hold_until(
+        &self,
+        market_account_id: MarketAccountId,
+        strategy_name: &str,
+        now: DateTime,
+    ) -> Option<DateTime> {
+        let strategy_hold_until = self
+            .strategy_holds
+            .lock()
+            .get(&(market_account_id, strategy_name.to_owned()))
+            .filter(|&&hold_until| hold_until > now)
+            .cloned();
+
+        self.hold_until(market_account_id, now)
+            .max(strategy_hold_until)
+    }
+
+    /// Last fired triggers, the newest first
+    pub fn report(&self) -> Vec<PullTriggerEvent> {
+        self.recent_events.lock().iter().rev().cloned().collect()
+    }
+
+    /// Detect flapping websocket connections of exchanges, should be called periodically
+    pub(crate) fn check_connectivity(
+        &self,
+        exchanges: &DashMap<ExchangeAccountId, Arc<Exchange>>,
+        now: DateTime,
+    ) {
+        let Some(settings) = self.settings.connectivity_flap else {
+            return;
+        };
+
+        let mut flapping = Vec::new();
+        {
+            let mut disconnects = self.disconnects.lock();
+            for exchange in exchanges.iter() {
+                let exchange_account_id = *exchange.key();
+                let window = disconnects.entry(exchange_account_id).or_default();
+                if let Some(count) = window.update(exchange.is_websocket_connected(), now, settings)
+                {
+                    flapping.push((exchange_account_id, count));
+                }
+            }
+        }
+
+        for (exchange_account_id, disconnects) in flapping {
+            let _ = self.pull(
+                exchange_account_id,
+                None,
+                PullCause::ConnectivityFlap { disconnects },
+                now,
+            );
+        }
+    }
+}
+
+/// Disconnections of websocket of exchange account over sliding window
+#[derive(Default)]
+struct DisconnectsWindow {
+    was_connected: bool,
+    disconnects: VecDeque<DateTime>,
+}
+
+impl DisconnectsWindow {
+    /// Returns count of disconnects if it reaches maximum. Window is cleared after that,
+    /// so the trigger fires again only after new disconnects
+    fn update(
+        &mut self,
+        is_connected: bool,
+        now: DateTime,
+        settings: ConnectivityFlapTriggerSettings,
+    ) -> Option<usize> {
+        if self.was_connected && !is_connected {
+            self.disconnects.push_back(now);
+        }
+        self.was_connected = is_connected;
+
+        let window_start = now - settings.window();
+        while self
+            .disconnects
+            .front()
+            .map_or(false, |&x| x < window_start)
+        {
+            let _ = self.disconnects.pop_front();
+        }
+
+        let count = self.disconnects.len();
+        if count < settings.max_disconnects {
+            return None;
+        }
+
+        self.disconnects.clear();
+        Some(count)
+    }
+}
+
+/// Mid prices of market over sliding window
+pub(crate) struct VolatilityWindow {
+    window: chrono::Duration,
+    prices: VecDeque<(DateTime, Price)>,
+}
+
+impl VolatilityWindow {
+    pub fn new(window: chrono::Duration) -> Self {
+        VolatilityWindow {
+            window,
+            prices: VecDeque::new(),
+        }
+    }
+
+    /// Add price and return range of prices in window relative to its minimal price
+    pub fn update(&mut self, time: DateTime, price: Price) -> Decimal {
+        self.prices.push_back((time, price));
+
+        let window_start = time - self.window;
+        while self
+            .prices
+            .front()
+            .map_or(false, |&(x, _)| x < window_start)
+        {
+            let _ = self.prices.pop_front();
+        }
+
+        let prices = self.prices.iter().map(|&(_, price)| price);
+        match (prices.clone().min(), prices.max()) {
+            (Some(min), Some(max)) if min > Decimal::ZERO => (max - min) / min,
+            _ => Decimal::ZERO,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.prices.clear();
+    }
+}
+
+/// Deviation of price of market from index price relative to index price
+pub(crate) fn index_gap_rate(price: Price, index_price: Price) -> Option<Decimal> {
+    (index_price > Decimal::ZERO).then(|| (price - index_price).abs() / index_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn time(seconds: i64) -> DateTime {
+        Utc.timestamp_opt(seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn volatility_is_measured_over_window() {
+        let mut window = VolatilityWindow::new(chrono::Duration::seconds(10));
+
+        assert_eq!(window.update(time(0), dec!(100)), dec!(0));
+        assert_eq!(window.update(time(5), dec!(102)), dec!(0.02));
+        assert_eq!(window.update(time(8), dec!(101)), dec!(0.02));
+        // price 100 is out of window
+        assert_eq!(window.update(time(11), dec!(102)), dec!(1) / dec!(101));
+    }
+
+    #[test]
+    fn index_gap_is_relative_to_index_price() {
+        assert_eq!(index_gap_rate(dec!(99), dec!(100)), Some(dec!(0.01)));
+        assert_eq!(index_gap_rate(dec!(102), dec!(100)), Some(dec!(0.02)));
+        assert_eq!(index_gap_rate(dec!(102), dec!(0)), None);
+    }
+
+    #[test]
+    fn connectivity_flap_fires_after_max_disconnects_in_window() {
+        let settings = ConnectivityFlapTriggerSettings {
+            window_secs: 60,
+            max_disconnects: 2,
+        };
+        let mut window = DisconnectsWindow::default();
+
+        assert_eq!(window.update(true, time(0), settings), None);
+        assert_eq!(window.update(false, time(1), settings), None);
+        assert_eq!(window.update(false, time(2), settings), None);
+        assert_eq!(window.update(true, time(3), settings), None);
+        // the first disconnect is out of window
+        assert_eq!(window.update(false, time(70), settings), None);
+        assert_eq!(window.update(true, time(71), settings), None);
+        assert_eq!(window.update(false, time(72), settings), Some(2));
+        assert_eq!(window.update(true, time(73), settings), None);
+    }
+}
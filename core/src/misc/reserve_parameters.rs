@@ -6,7 +6,7 @@ use crate::balance::manager::balance_reservation::BalanceReservation;
 use crate::service_configuration::configuration_descriptor::ConfigurationDescriptor;
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::ExchangeAccountId;
-use mmb_domain::order::snapshot::OrderSide;
+use mmb_domain::order::snapshot::{OrderRole, OrderSide};
 
 #[derive(Clone, Hash, Debug, Eq, PartialEq)]
 pub struct ReserveParameters {
@@ -16,6 +16,8 @@ pub struct ReserveParameters {
     pub(crate) symbol: Arc<Symbol>,
     pub(crate) exchange_account_id: ExchangeAccountId,
     pub(crate) configuration_descriptor: ConfigurationDescriptor,
+    /// Expected role of order for fee estimation, taker fee is expected if it's unknown
+    pub(crate) order_role: Option<OrderRole>,
 }
 
 impl ReserveParameters {
@@ -34,9 +36,15 @@ impl ReserveParameters {
             order_side,
             price,
             amount,
+            order_role: None,
         }
     }
 
+    pub fn with_order_role(mut self, order_role: OrderRole) -> Self {
+        self.order_role = Some(order_role);
+        self
+    }
+
     pub fn from_reservation(reservation: &BalanceReservation, amount: Amount) -> Self {
         ReserveParameters::new(
             reservation.configuration_descriptor,
@@ -59,6 +67,7 @@ impl ReserveParameters {
             order_side: reservation.order_side,
             price,
             amount,
+            order_role: None,
         }
     }
 }
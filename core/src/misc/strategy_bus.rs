@@ -0,0 +1,286 @@
+use crate::database::events::recorder::EventRecorder;
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use mmb_database::impl_event;
+use mmb_utils::DateTime;
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+type AnyMessage = Arc<dyn Any + Send + Sync>;
+
+/// Typed message which strategies exchange via `StrategyBus`,
+/// e.g. arbitrage strategy announces inventory needs to market maker strategy.
+/// Every message type is a separate topic
+pub trait BusMessage: Serialize + Send + Sync + 'static {
+    /// Name of topic for recording and logs, should be unique among message types
+    const TOPIC: &'static str;
+}
+
+/// Published message saved to database for replay and debugging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyBusRecord {
+    pub time: DateTime,
+    pub topic: String,
+    /// Name of strategy which published message
+    pub publisher: String,
+    pub message: serde_json::Value,
+}
+
+impl_event!(StrategyBusRecord, "strategy_bus_messages");
+
+/// Receiving side of subscription to messages of type `T`.
+/// Subscription is removed from bus after receiver is dropped
+pub struct BusReceiver<T> {
+    receiver: mpsc::Receiver<AnyMessage>,
+    _message_type: PhantomData<T>,
+}
+
+impl<T: BusMessage> BusReceiver<T> {
+    /// Returns `None` if bus is dropped
+    pub async fn recv(&mut self) -> Option<Arc<T>> {
+        let message = self.receiver.recv().await?;
+        Some(Self::downcast(message))
+    }
+
+    /// Returns `None` if there are no pending messages
+    pub fn try_recv(&mut self) -> Option<Arc<T>> {
+        self.receiver.try_recv().ok().map(Self::downcast)
+    }
+
+    fn downcast(message: AnyMessage) -> Arc<T> {
+        // subscriptions are stored by type id of message, so other types can't be received
+        message
+            .downcast::<T>()
+            .unwrap_or_else(|_| panic!("Unexpected message type in topic {}", T::TOPIC))
+    }
+}
+
+/// Lightweight pub/sub bus for communication between strategies.
+/// Every subscriber has its own bounded queue, so slow subscriber makes publisher wait
+/// (`publish`) or fail (`try_publish`) instead of unlimited growth of memory.
+/// All published messages are recorded by `EventRecorder`
+pub struct StrategyBus {
+    subscriptions: RwLock<HashMap<TypeId, Vec<mpsc::Sender<AnyMessage>>>>,
+    event_recorder: Arc<EventRecorder>,
+}
+
+impl StrategyBus {
+    pub fn new(event_recorder: Arc<EventRecorder>) -> Arc<Self> {
+        Arc::new(Self {
+            subscriptions: Default::default(),
+            event_recorder,
+        })
+    }
+
+    /// Subscribe to messages of type `T`, `capacity` is maximum count of messages
+    /// in queue of subscriber before backpressure is applied to publishers
+    pub fn subscribe<T: BusMessage>(&self, capacity: usize) -> BusReceiver<T> {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        self.subscriptions
+            .write()
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(sender);
+
+        BusReceiver {
+            receiver,
+            _message_type: PhantomData,
+        }
+    }
+
+    /// Publish message waiting for free space in queues of all subscribers
+    pub async fn publish<T: BusMessage>(&self, publisher: &str, message: T) -> Result<()> {
+        self.record(publisher, &message)?;
+
+        let message: AnyMessage = Arc::new(message);
+        for sender in self.get_senders::<T>() {
+            // error means that subscriber is dropped right now
+            let _ = sender.send(message.clone()).await;
+        }
+
+        Ok(())
+    }
+
+    /// Publish message without waiting. Message is delivered to subscribers with free space
+    /// in queues and error is returned if queue of some subscriber is full
+    pub fn try_publish<T: BusMessage>(&self, publisher: &str, message: T) -> Result<()> {
+        self.record(publisher, &message)?;
+        self.send_without_waiting(message)
+    }
+
+    /// Publish previously recorded message again without recording it
+    pub fn replay<T: BusMessage + DeserializeOwned>(
+        &self,
+        record: &StrategyBusRecord,
+    ) -> Result<()> {
+        if record.topic != T::TOPIC {
+            bail!(
+                "Unable to replay message of topic {} as {}",
+                record.topic,
+                T::TOPIC
+            );
+        }
+
+        let message: T = serde_json::from_value(record.message.clone())
+            .with_context(|| format!("Unable to deserialize message of topic {}", T::TOPIC))?;
+        self.send_without_waiting(message)
+    }
+
+    fn send_without_waiting<T: BusMessage>(&self, message: T) -> Result<()> {
+        let message: AnyMessage = Arc::new(message);
+        let mut full_queues_count = 0;
+        for sender in self.get_senders::<T>() {
+            if let Err(mpsc::error::TrySendError::Full(_)) = sender.try_send(message.clone()) {
+                full_queues_count += 1;
+            }
+        }
+
+        if full_queues_count > 0 {
+            bail!(
+                "Message of topic {} isn't delivered to {full_queues_count} subscribers with full queues",
+                T::TOPIC
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Senders of alive subscriptions, subscriptions of dropped receivers are removed
+    fn get_senders<T: BusMessage>(&self) -> Vec<mpsc::Sender<AnyMessage>> {
+        let type_id = TypeId::of::<T>();
+        let has_closed_subscriptions = match self.subscriptions.read().get(&type_id) {
+            None => return Vec::new(),
+            Some(senders) => senders.iter().any(|x| x.is_closed()),
+        };
+
+        if has_closed_subscriptions {
+            if let Some(senders) = self.subscriptions.write().get_mut(&type_id) {
+                senders.retain(|x| !x.is_closed());
+            }
+        }
+
+        self.subscriptions
+            .read()
+            .get(&type_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn record<T: BusMessage>(&self, publisher: &str, message: &T) -> Result<()> {
+        let record = StrategyBusRecord {
+            time: Utc::now(),
+            topic: T::TOPIC.to_owned(),
+            publisher: publisher.to_owned(),
+            message: serde_json::to_value(message)
+                .with_context(|| format!("Unable to serialize message of topic {}", T::TOPIC))?,
+        };
+
+        if let Err(err) = self.event_recorder.save(record) {
+            // recording is needed only for debugging, so message is published anyway
+            log::warn!("Failed to record message of topic {}: {err:?}", T::TOPIC);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::order::snapshot::Amount;
+    use rust_decimal_macros::dec;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    struct InventoryNeed {
+        currency: String,
+        amount: Amount,
+    }
+
+    impl BusMessage for InventoryNeed {
+        const TOPIC: &'static str = "inventory_need";
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Pause;
+
+    impl BusMessage for Pause {
+        const TOPIC: &'static str = "pause";
+    }
+
+    async fn create_bus() -> Arc<StrategyBus> {
+        StrategyBus::new(
+            EventRecorder::start(None, None, None)
+                .await
+                .expect("Failure start EventRecorder"),
+        )
+    }
+
+    fn inventory_need() -> InventoryNeed {
+        InventoryNeed {
+            currency: "btc".to_owned(),
+            amount: dec!(1.5),
+        }
+    }
+
+    #[tokio::test]
+    async fn deliver_messages_by_topics() {
+        let bus = create_bus().await;
+        let mut first = bus.subscribe::<InventoryNeed>(10);
+        let mut second = bus.subscribe::<InventoryNeed>(10);
+        let mut pause = bus.subscribe::<Pause>(10);
+
+        bus.publish("arbitrage", inventory_need())
+            .await
+            .expect("in test");
+
+        assert_eq!(first.try_recv().as_deref(), Some(&inventory_need()));
+        assert_eq!(second.try_recv().as_deref(), Some(&inventory_need()));
+        assert!(pause.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn try_publish_fails_on_full_queue() {
+        let bus = create_bus().await;
+        let mut receiver = bus.subscribe::<InventoryNeed>(1);
+
+        bus.try_publish("arbitrage", inventory_need())
+            .expect("in test");
+        assert!(bus.try_publish("arbitrage", inventory_need()).is_err());
+
+        assert!(receiver.try_recv().is_some());
+        assert!(receiver.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn remove_subscription_after_receiver_dropped() {
+        let bus = create_bus().await;
+        drop(bus.subscribe::<Pause>(1));
+
+        bus.try_publish("arbitrage", Pause).expect("in test");
+
+        assert!(bus.subscriptions.read()[&TypeId::of::<Pause>()].is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_recorded_message() {
+        let bus = create_bus().await;
+        let mut receiver = bus.subscribe::<InventoryNeed>(10);
+        let record = StrategyBusRecord {
+            time: Utc::now(),
+            topic: InventoryNeed::TOPIC.to_owned(),
+            publisher: "arbitrage".to_owned(),
+            message: serde_json::to_value(inventory_need()).expect("in test"),
+        };
+
+        assert!(bus.replay::<Pause>(&record).is_err());
+        bus.replay::<InventoryNeed>(&record).expect("in test");
+
+        assert_eq!(receiver.try_recv().as_deref(), Some(&inventory_need()));
+    }
+}
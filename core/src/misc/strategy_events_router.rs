@@ -0,0 +1,169 @@
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use tokio::sync::broadcast;
+
+/// Capacity of strategy channel. It's less than capacity of common events channel,
+/// because strategy receives only events of subscribed markets
+const STRATEGY_CHANNEL_MAX_EVENTS_COUNT: usize = 50_000;
+
+struct Subscription {
+    markets: HashSet<MarketAccountId>,
+    events_sender: broadcast::Sender<ExchangeEvent>,
+}
+
+impl Subscription {
+    fn is_interested(&self, event: &ExchangeEvent) -> bool {
+        match event_target(event) {
+            EventTarget::All => true,
+            EventTarget::ExchangeAccount(exchange_account_id) => self
+                .markets
+                .iter()
+                .any(|x| x.exchange_account_id == exchange_account_id),
+            EventTarget::Market(market_account_id) => self.markets.contains(&market_account_id),
+        }
+    }
+}
+
+enum EventTarget {
+    All,
+    ExchangeAccount(ExchangeAccountId),
+    Market(MarketAccountId),
+}
+
+fn event_target(event: &ExchangeEvent) -> EventTarget {
+    match event {
+        ExchangeEvent::OrderBookEvent(x) => {
+            EventTarget::Market(MarketAccountId::new(x.exchange_account_id, x.currency_pair))
+        }
+        ExchangeEvent::OrderEvent(x) => EventTarget::Market(x.order.header().market_account_id()),
+        ExchangeEvent::LiquidationPrice(x) => {
+            EventTarget::Market(MarketAccountId::new(x.exchange_account_id, x.currency_pair))
+        }
+        ExchangeEvent::MarkPrice(x) => EventTarget::Market(x.market_account_id()),
+        ExchangeEvent::Trades(x) => {
+            EventTarget::Market(MarketAccountId::new(x.exchange_account_id, x.currency_pair))
+        }
+        ExchangeEvent::CashFlow(x) => EventTarget::Market(x.market_account_id()),
+        ExchangeEvent::BalanceUpdate(x) => EventTarget::ExchangeAccount(x.exchange_account_id),
+        ExchangeEvent::Heartbeat(_) => EventTarget::All,
+    }
+}
+
+/// Routes exchange events from internal events loop into separate channel of every strategy,
+/// so strategy is woken up only by events of markets it's subscribed to.
+/// Events without market (balances, heartbeats) are delivered to strategies of related exchange account or to all
+#[derive(Default)]
+pub struct StrategyEventsRouter {
+    subscriptions: RwLock<Vec<Subscription>>,
+}
+
+impl StrategyEventsRouter {
+    pub fn subscribe(
+        &self,
+        markets: impl IntoIterator<Item = MarketAccountId>,
+    ) -> broadcast::Receiver<ExchangeEvent> {
+        let (events_sender, events_receiver) =
+            broadcast::channel(STRATEGY_CHANNEL_MAX_EVENTS_COUNT);
+        self.subscriptions.write().push(Subscription {
+            markets: markets.into_iter().collect(),
+            events_sender,
+        });
+
+        events_receiver
+    }
+
+    pub(crate) fn route(&self, event: &ExchangeEvent) {
+        let mut has_closed_subscriptions = false;
+        for subscription in self.subscriptions.read().iter() {
+            if subscription.events_sender.receiver_count() == 0 {
+                has_closed_subscriptions = true;
+                continue;
+            }
+
+            if subscription.is_interested(event) {
+                // error means that strategy is stopped and its receiver is already dropped
+                let _ = subscription.events_sender.send(event.clone());
+            }
+        }
+
+        if has_closed_subscriptions {
+            self.subscriptions
+                .write()
+                .retain(|x| x.events_sender.receiver_count() > 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::events::{CashFlowEvent, CashFlowKind, HeartbeatEvent};
+    use mmb_domain::market::CurrencyPair;
+    use rust_decimal_macros::dec;
+
+    fn cash_flow(market_account_id: MarketAccountId) -> ExchangeEvent {
+        ExchangeEvent::CashFlow(CashFlowEvent {
+            exchange_account_id: market_account_id.exchange_account_id,
+            currency_pair: market_account_id.currency_pair,
+            currency_code: "usdt".into(),
+            kind: CashFlowKind::Funding,
+            amount: dec!(1),
+            transaction_time: Utc::now(),
+        })
+    }
+
+    #[test]
+    fn route_events_by_subscribed_markets() {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        let btc_market = MarketAccountId::new(
+            exchange_account_id,
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        );
+        let eth_market = MarketAccountId::new(
+            exchange_account_id,
+            CurrencyPair::from_codes("eth".into(), "usdt".into()),
+        );
+
+        let router = StrategyEventsRouter::default();
+        let mut btc_receiver = router.subscribe([btc_market]);
+        let mut eth_receiver = router.subscribe([eth_market]);
+
+        router.route(&cash_flow(btc_market));
+        router.route(&ExchangeEvent::Heartbeat(HeartbeatEvent {
+            time: Utc::now(),
+            subsystems: Vec::new(),
+        }));
+
+        assert!(matches!(
+            btc_receiver.try_recv(),
+            Ok(ExchangeEvent::CashFlow(_))
+        ));
+        assert!(matches!(
+            btc_receiver.try_recv(),
+            Ok(ExchangeEvent::Heartbeat(_))
+        ));
+        assert!(matches!(
+            eth_receiver.try_recv(),
+            Ok(ExchangeEvent::Heartbeat(_))
+        ));
+        assert!(eth_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn remove_subscription_after_receiver_dropped() {
+        let market = MarketAccountId::new(
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        );
+
+        let router = StrategyEventsRouter::default();
+        drop(router.subscribe([market]));
+
+        router.route(&cash_flow(market));
+
+        assert!(router.subscriptions.read().is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{Duration, TimeZone, Timelike, Utc};
+use mmb_utils::DateTime;
+
+use crate::settings::TradingDaySettings;
+
+const SECONDS_IN_DAY: i64 = 24 * 60 * 60;
+
+/// Calculates boundaries of trading days in session timezone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradingDayBoundary {
+    /// Shift of UTC timestamp after which trading day starts at midnight
+    shift_secs: i64,
+}
+
+impl TradingDayBoundary {
+    pub fn new(settings: &TradingDaySettings) -> Self {
+        let start_secs = settings.start_time.num_seconds_from_midnight() as i64;
+        Self {
+            shift_secs: settings.utc_offset_mins as i64 * 60 - start_secs,
+        }
+    }
+
+    /// Start of trading day which contains specified time
+    pub fn day_start(&self, time: DateTime) -> DateTime {
+        let shifted_secs = time.timestamp() + self.shift_secs;
+        let day_start_secs = shifted_secs - shifted_secs.rem_euclid(SECONDS_IN_DAY);
+        Utc.timestamp(day_start_secs - self.shift_secs, 0)
+    }
+
+    pub fn next_day_start(&self, time: DateTime) -> DateTime {
+        self.day_start(time) + Duration::days(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use rstest::rstest;
+
+    fn utc(hour: u32, min: u32) -> DateTime {
+        Utc.ymd(2022, 12, 30).and_hms(hour, min, 0)
+    }
+
+    #[rstest]
+    #[case::utc_midnight(0, (0, 0), utc(15, 30), utc(0, 0))]
+    #[case::exactly_on_boundary(0, (0, 0), utc(0, 0), utc(0, 0))]
+    #[case::positive_offset_before_local_midnight(480, (0, 0), utc(15, 30), utc(16, 0) - Duration::days(1))]
+    #[case::positive_offset_after_local_midnight(480, (0, 0), utc(16, 30), utc(16, 0))]
+    #[case::negative_offset(-300, (0, 0), utc(3, 0), utc(5, 0) - Duration::days(1))]
+    #[case::custom_start_time(0, (22, 0), utc(21, 59), utc(22, 0) - Duration::days(1))]
+    #[case::custom_start_time_with_offset(-300, (17, 0), utc(22, 0), utc(22, 0))]
+    fn day_start(
+        #[case] utc_offset_mins: i32,
+        #[case] start_time: (u32, u32),
+        #[case] time: DateTime,
+        #[case] expected: DateTime,
+    ) {
+        let boundary = TradingDayBoundary::new(&TradingDaySettings {
+            utc_offset_mins,
+            start_time: NaiveTime::from_hms(start_time.0, start_time.1, 0),
+            ..Default::default()
+        });
+
+        assert_eq!(boundary.day_start(time), expected);
+        assert_eq!(boundary.next_day_start(time), expected + Duration::days(1));
+    }
+}
@@ -0,0 +1,364 @@
+use crate::settings::RedundantFeedSettings;
+use mmb_domain::market::MarketAccountId;
+use mmb_domain::order::snapshot::Price;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::DateTime;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Feed {
+    Primary,
+    Backup,
+}
+
+#[derive(Default)]
+struct FeedState {
+    /// Order book built from events of the feed only
+    snapshot: Option<LocalOrderBookSnapshot>,
+    last_event_time: Option<DateTime>,
+}
+
+impl FeedState {
+    fn middle_price(&self) -> Option<Price> {
+        let prices = self.snapshot.as_ref()?.get_top_prices();
+        Some((prices.top_ask? + prices.top_bid?) * dec!(0.5))
+    }
+}
+
+struct ArbitratedMarket {
+    settings: RedundantFeedSettings,
+    divergence_timeout: chrono::Duration,
+    failover_timeout: chrono::Duration,
+    primary: FeedState,
+    backup: FeedState,
+    /// Sequence id of the last forwarded event
+    last_forwarded_id: Option<u64>,
+    last_forwarded_feed: Option<Feed>,
+    diverged_since: Option<DateTime>,
+    is_divergence_reported: bool,
+}
+
+impl ArbitratedMarket {
+    fn new(settings: RedundantFeedSettings) -> Self {
+        let to_chrono = |duration| {
+            chrono::Duration::from_std(duration)
+                .expect("Unable to convert timeout of redundant feed settings")
+        };
+
+        Self {
+            divergence_timeout: to_chrono(settings.divergence_timeout()),
+            failover_timeout: to_chrono(settings.failover_timeout()),
+            settings,
+            primary: FeedState::default(),
+            backup: FeedState::default(),
+            last_forwarded_id: None,
+            last_forwarded_feed: None,
+            diverged_since: None,
+            is_divergence_reported: false,
+        }
+    }
+
+    fn feed_state(&mut self, feed: Feed) -> &mut FeedState {
+        match feed {
+            Feed::Primary => &mut self.primary,
+            Feed::Backup => &mut self.backup,
+        }
+    }
+
+    fn handle(&mut self, feed: Feed, event: OrderBookEvent) -> Option<OrderBookEvent> {
+        let now = event.creation_time;
+        let state = self.feed_state(feed);
+        state.last_event_time = Some(now);
+        match event.event_type {
+            EventType::Snapshot => state.snapshot = Some(event.to_orderbook_snapshot()),
+            EventType::Update => match &mut state.snapshot {
+                Some(snapshot) => snapshot.apply_update(&event.data, now),
+                // update can't be applied before the first snapshot of the feed
+                None => return None,
+            },
+        }
+
+        self.check_divergence(now);
+
+        let event_id = event.event_id().parse::<u64>().ok();
+        if !self.should_forward(feed, event_id, now) {
+            return None;
+        }
+
+        if event_id.is_some() {
+            self.last_forwarded_id = event_id;
+        }
+
+        // updates of other feed can't be applied to forwarded book, so whole book is forwarded
+        let (event_type, data) = match event.event_type {
+            EventType::Update if self.last_forwarded_feed != Some(feed) => {
+                let snapshot = self
+                    .feed_state(feed)
+                    .snapshot
+                    .as_ref()
+                    .expect("Snapshot of feed should exist after applying update");
+                let data = OrderBookData::new(snapshot.asks.clone(), snapshot.bids.clone());
+                (EventType::Snapshot, Arc::new(data))
+            }
+            event_type => (event_type, event.data.clone()),
+        };
+        self.last_forwarded_feed = Some(feed);
+
+        Some(OrderBookEvent::new(
+            event.creation_time,
+            self.settings.exchange_account_id,
+            self.settings.currency_pair,
+            event.event_id().to_owned(),
+            event_type,
+            data,
+        ))
+    }
+
+    /// Events with sequence ids are forwarded from whichever feed delivers them first.
+    /// Without ids events of primary feed are forwarded while it isn't silent
+    fn should_forward(&self, feed: Feed, event_id: Option<u64>, now: DateTime) -> bool {
+        match (event_id, self.last_forwarded_id) {
+            (Some(event_id), Some(last_forwarded_id)) => event_id > last_forwarded_id,
+            (Some(_), None) => true,
+            (None, _) => match feed {
+                Feed::Primary => true,
+                Feed::Backup => self
+                    .primary
+                    .last_event_time
+                    .map_or(true, |time| now - time > self.failover_timeout),
+            },
+        }
+    }
+
+    fn check_divergence(&mut self, now: DateTime) {
+        let (primary_price, backup_price) =
+            match (self.primary.middle_price(), self.backup.middle_price()) {
+                (Some(primary), Some(backup)) if !backup.is_zero() => (primary, backup),
+                _ => return,
+            };
+
+        let divergence_percent = ((primary_price - backup_price) / backup_price).abs() * dec!(100);
+        let market_account_id = MarketAccountId::new(
+            self.settings.exchange_account_id,
+            self.settings.currency_pair,
+        );
+
+        if divergence_percent <= self.settings.max_divergence_percent {
+            if self.is_divergence_reported {
+                log::info!("Redundant feeds of {market_account_id} converged");
+            }
+
+            self.diverged_since = None;
+            self.is_divergence_reported = false;
+            return;
+        }
+
+        let diverged_since = *self.diverged_since.get_or_insert(now);
+        if !self.is_divergence_reported && now - diverged_since > self.divergence_timeout {
+            self.is_divergence_reported = true;
+            log::error!(
+                "Redundant feeds of {market_account_id} diverged by {divergence_percent:.4}% since {diverged_since}: middle price of primary feed {primary_price}, of backup feed {} {backup_price}",
+                self.settings.backup_exchange_account_id
+            );
+        }
+    }
+}
+
+/// Arbiter of redundant order book feeds of critical markets. Events of both feeds are applied
+/// to separate local books, and whichever update arrives first is forwarded as event
+/// of the primary market, so order books which strategies quote against survive failure
+/// of single connection. Events of backup market aren't forwarded
+#[derive(Default)]
+pub(crate) struct FeedArbiter {
+    markets: Vec<ArbitratedMarket>,
+    feeds: HashMap<MarketAccountId, (usize, Feed)>,
+}
+
+impl FeedArbiter {
+    pub(crate) fn new(settings: &[RedundantFeedSettings]) -> Self {
+        let mut arbiter = FeedArbiter::default();
+        for (index, settings) in settings.iter().enumerate() {
+            let market_account_id = |exchange_account_id| {
+                MarketAccountId::new(exchange_account_id, settings.currency_pair)
+            };
+            arbiter.feeds.insert(
+                market_account_id(settings.exchange_account_id),
+                (index, Feed::Primary),
+            );
+            arbiter.feeds.insert(
+                market_account_id(settings.backup_exchange_account_id),
+                (index, Feed::Backup),
+            );
+            arbiter
+                .markets
+                .push(ArbitratedMarket::new(settings.clone()));
+        }
+
+        arbiter
+    }
+
+    /// Returns event which should be handled instead of received one,
+    /// `None` if received event is outdated or belongs to feed which isn't forwarded now
+    pub(crate) fn arbitrate(&mut self, event: OrderBookEvent) -> Option<OrderBookEvent> {
+        match self.feeds.get(&event.market_account_id()) {
+            Some(&(index, feed)) => self.markets[index].handle(feed, event),
+            None => Some(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+    use mmb_domain::order_book_data;
+    use rust_decimal_macros::dec;
+
+    fn primary() -> ExchangeAccountId {
+        ExchangeAccountId::new("Binance", 0)
+    }
+
+    fn backup() -> ExchangeAccountId {
+        ExchangeAccountId::new("Binance", 1)
+    }
+
+    fn currency_pair() -> CurrencyPair {
+        CurrencyPair::from_codes("btc".into(), "usdt".into())
+    }
+
+    fn arbiter() -> FeedArbiter {
+        FeedArbiter::new(&[RedundantFeedSettings {
+            exchange_account_id: primary(),
+            currency_pair: currency_pair(),
+            backup_exchange_account_id: backup(),
+            max_divergence_percent: dec!(1),
+            divergence_timeout_ms: 1000,
+            failover_timeout_ms: 2000,
+        }])
+    }
+
+    fn event(
+        exchange_account_id: ExchangeAccountId,
+        time: DateTime,
+        event_id: &str,
+        event_type: EventType,
+        data: OrderBookData,
+    ) -> OrderBookEvent {
+        OrderBookEvent::new(
+            time,
+            exchange_account_id,
+            currency_pair(),
+            event_id.to_owned(),
+            event_type,
+            Arc::new(data),
+        )
+    }
+
+    #[test]
+    fn first_arrived_update_is_forwarded_as_primary_market() {
+        let mut arbiter = arbiter();
+        let now = Utc::now();
+        let book = || order_book_data![dec!(101) => dec!(1), ; dec!(100) => dec!(1),];
+
+        let forwarded = arbiter
+            .arbitrate(event(backup(), now, "1", EventType::Snapshot, book()))
+            .expect("in test");
+        assert_eq!(forwarded.exchange_account_id, primary());
+
+        // the same update from primary feed is duplicate
+        let forwarded = arbiter.arbitrate(event(primary(), now, "1", EventType::Snapshot, book()));
+        assert!(forwarded.is_none());
+
+        // primary feed is ahead now, its update is forwarded as whole book
+        let forwarded = arbiter
+            .arbitrate(event(
+                primary(),
+                now,
+                "2",
+                EventType::Update,
+                order_book_data![dec!(101) => dec!(0), dec!(102) => dec!(2), ;],
+            ))
+            .expect("in test");
+        assert!(matches!(forwarded.event_type, EventType::Snapshot));
+        assert_eq!(
+            *forwarded.data,
+            order_book_data![dec!(102) => dec!(2), ; dec!(100) => dec!(1),]
+        );
+
+        let other_market = event(
+            ExchangeAccountId::new("Binance", 2),
+            now,
+            "1",
+            EventType::Snapshot,
+            book(),
+        );
+        assert!(arbiter.arbitrate(other_market).is_some());
+    }
+
+    #[test]
+    fn backup_feed_without_sequence_ids_is_used_when_primary_is_silent() {
+        let mut arbiter = arbiter();
+        let now = Utc::now();
+        let book = || order_book_data![dec!(101) => dec!(1), ; dec!(100) => dec!(1),];
+
+        assert!(arbiter
+            .arbitrate(event(primary(), now, "", EventType::Snapshot, book()))
+            .is_some());
+        assert!(arbiter
+            .arbitrate(event(backup(), now, "", EventType::Snapshot, book()))
+            .is_none());
+
+        let later = now + chrono::Duration::seconds(3);
+        let forwarded = arbiter
+            .arbitrate(event(backup(), later, "", EventType::Snapshot, book()))
+            .expect("in test");
+        assert_eq!(forwarded.exchange_account_id, primary());
+    }
+
+    #[test]
+    fn divergence_is_reported_after_timeout() {
+        let mut arbiter = arbiter();
+        let now = Utc::now();
+
+        arbiter.arbitrate(event(
+            primary(),
+            now,
+            "1",
+            EventType::Snapshot,
+            order_book_data![dec!(101) => dec!(1), ; dec!(100) => dec!(1),],
+        ));
+        arbiter.arbitrate(event(
+            backup(),
+            now,
+            "1",
+            EventType::Snapshot,
+            order_book_data![dec!(111) => dec!(1), ; dec!(110) => dec!(1),],
+        ));
+        assert!(arbiter.markets[0].diverged_since.is_some());
+        assert!(!arbiter.markets[0].is_divergence_reported);
+
+        let later = now + chrono::Duration::seconds(2);
+        arbiter.arbitrate(event(
+            backup(),
+            later,
+            "2",
+            EventType::Update,
+            order_book_data![dec!(112) => dec!(1), ;],
+        ));
+        assert!(arbiter.markets[0].is_divergence_reported);
+
+        arbiter.arbitrate(event(
+            backup(),
+            later,
+            "3",
+            EventType::Snapshot,
+            order_book_data![dec!(101) => dec!(1), ; dec!(100) => dec!(1),],
+        ));
+        assert!(arbiter.markets[0].diverged_since.is_none());
+    }
+}
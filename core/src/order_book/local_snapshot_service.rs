@@ -1,4 +1,6 @@
 use mmb_domain::market::{MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::Price;
+use mmb_domain::order_book::aggregated_order_book::AggregatedOrderBook;
 use mmb_domain::order_book::event;
 use mmb_domain::order_book::local_order_book_snapshot::{LocalOrderBookSnapshot, ResultAskBidFix};
 use mmb_utils::infrastructure::WithExpect;
@@ -7,11 +9,43 @@ use std::collections::HashMap;
 /// Produce and actualize current logical state of order book snapshot according to logical time of handled order book events
 pub struct LocalSnapshotsService {
     local_snapshots: HashMap<MarketId, LocalOrderBookSnapshot>,
+    aggregated_order_books: HashMap<MarketId, Vec<AggregatedOrderBook>>,
 }
 
 impl LocalSnapshotsService {
     pub fn new(local_snapshots: HashMap<MarketId, LocalOrderBookSnapshot>) -> Self {
-        Self { local_snapshots }
+        Self {
+            local_snapshots,
+            aggregated_order_books: HashMap::new(),
+        }
+    }
+
+    /// Start maintaining order book of market aggregated by price buckets of specified size
+    pub fn add_aggregation(&mut self, market_id: MarketId, bucket_size: Price) {
+        let aggregated_order_books = self.aggregated_order_books.entry(market_id).or_default();
+        if aggregated_order_books
+            .iter()
+            .any(|x| x.bucket_size() == bucket_size)
+        {
+            return;
+        }
+
+        let aggregated_order_book = match self.local_snapshots.get(&market_id) {
+            Some(snapshot) => AggregatedOrderBook::from_snapshot(snapshot, bucket_size),
+            None => AggregatedOrderBook::new(bucket_size),
+        };
+        aggregated_order_books.push(aggregated_order_book);
+    }
+
+    pub fn get_aggregated_order_book(
+        &self,
+        market_id: MarketId,
+        bucket_size: Price,
+    ) -> Option<&AggregatedOrderBook> {
+        self.aggregated_order_books
+            .get(&market_id)?
+            .iter()
+            .find(|x| x.bucket_size() == bucket_size)
     }
 
     pub fn get_snapshot(&self, market_id: MarketId) -> Option<&LocalOrderBookSnapshot> {
@@ -40,6 +74,10 @@ impl LocalSnapshotsService {
                     log::warn!("On {market_account_id} orderbook top asks {top_ask} and bids {top_bid} was crossed (fixed now {})", snapshot.get_top_prices())
                 }
 
+                for aggregated_order_book in self.aggregated_order_books_mut(market_id) {
+                    aggregated_order_book.rebuild(&snapshot);
+                }
+
                 self.local_snapshots.insert(market_id, snapshot);
 
                 Some(market_account_id)
@@ -47,12 +85,26 @@ impl LocalSnapshotsService {
             event::EventType::Update => match self.local_snapshots.get_mut(&market_id) {
                 None => None,
                 Some(snapshot) => {
+                    let aggregated_order_books = self
+                        .aggregated_order_books
+                        .get_mut(&market_id)
+                        .map(|x| x.as_mut_slice())
+                        .unwrap_or_default();
+
+                    for aggregated_order_book in aggregated_order_books.iter_mut() {
+                        aggregated_order_book.apply_update(snapshot, &event.data);
+                    }
+
                     snapshot.apply_update(&event.data, event.creation_time);
 
                     if let ResultAskBidFix::Fixed { top_ask, top_bid } =
                         snapshot.fix_asks_bids_if_needed()
                     {
-                        log::warn!("On {market_account_id} orderbook top asks {top_ask} and bids {top_bid} was crossed (fixed now {})", snapshot.get_top_prices())
+                        log::warn!("On {market_account_id} orderbook top asks {top_ask} and bids {top_bid} was crossed (fixed now {})", snapshot.get_top_prices());
+
+                        for aggregated_order_book in aggregated_order_books {
+                            aggregated_order_book.rebuild(snapshot);
+                        }
                     }
 
                     Some(market_account_id)
@@ -60,6 +112,16 @@ impl LocalSnapshotsService {
             },
         }
     }
+
+    fn aggregated_order_books_mut(
+        &mut self,
+        market_id: MarketId,
+    ) -> impl Iterator<Item = &mut AggregatedOrderBook> {
+        self.aggregated_order_books
+            .get_mut(&market_id)
+            .into_iter()
+            .flatten()
+    }
 }
 
 impl Default for LocalSnapshotsService {
@@ -1 +1,2 @@
+pub(crate) mod feed_arbiter;
 pub mod local_snapshot_service;
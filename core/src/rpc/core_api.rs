@@ -1,10 +1,19 @@
 use anyhow::Result;
+use mmb_domain::decimal_serialization::DecimalSerialization;
 use parking_lot::Mutex;
 use tokio::sync::{mpsc, oneshot};
 
 use crate::lifecycle::app_lifetime_manager::{ActionAfterGracefulShutdown, AppLifetimeManager};
 use std::sync::Arc;
 
+use crate::disposition_execution::tunable_parameters::StrategyParameters;
+use crate::misc::pull_triggers::PullTriggers;
+use crate::services::compliance_export::ComplianceExportService;
+use crate::services::diagnostics::DiagnosticsService;
+use crate::services::inventory_transfer::InventoryTransferService;
+use crate::services::latency_probe::LatencyProbeService;
+use crate::services::position_unwind::PositionUnwindService;
+use crate::services::support_bundle::SupportBundleService;
 use crate::{lifecycle::trading_engine::Service, statistic_service::StatisticService};
 
 use super::{
@@ -23,10 +32,20 @@ pub(crate) struct CoreApi {
 }
 
 impl CoreApi {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn create_and_start(
         lifetime_manager: Arc<AppLifetimeManager>,
         engine_settings: String,
         statistics: Arc<StatisticService>,
+        inventory_transfer: Arc<InventoryTransferService>,
+        position_unwind: Arc<PositionUnwindService>,
+        strategy_parameters: Arc<StrategyParameters>,
+        diagnostics: Arc<DiagnosticsService>,
+        support_bundle: Arc<SupportBundleService>,
+        compliance_export: Arc<ComplianceExportService>,
+        pull_triggers: Arc<PullTriggers>,
+        latency_probe: Option<Arc<LatencyProbeService>>,
+        decimal_serialization: DecimalSerialization,
     ) -> Result<Arc<Self>> {
         let (server_stopper_tx, server_stopper_rx) =
             mpsc::channel::<ActionAfterGracefulShutdown>(10);
@@ -38,6 +57,15 @@ impl CoreApi {
         } = crate_server_and_channels(RpcImpl::new(
             server_stopper_tx.clone(),
             statistics,
+            inventory_transfer,
+            position_unwind,
+            strategy_parameters,
+            diagnostics,
+            support_bundle,
+            compliance_export,
+            pull_triggers,
+            latency_probe,
+            decimal_serialization,
             engine_settings,
         ));
 
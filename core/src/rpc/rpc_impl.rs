@@ -1,12 +1,27 @@
-use jsonrpc_core::Result;
-use mmb_rpc::rest_api::server_side_error;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use jsonrpc_core::{BoxFuture, Result};
+use mmb_domain::decimal_serialization::DecimalSerialization;
 use mmb_rpc::rest_api::MmbRpc;
+use mmb_rpc::rest_api::{server_side_error, server_side_error_with_details};
 use parking_lot::Mutex;
+use serde::Serialize;
 use tokio::sync::mpsc;
 
+use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::disposition_execution::tunable_parameters::StrategyParameters;
 use crate::lifecycle::app_lifetime_manager::ActionAfterGracefulShutdown;
+use crate::misc::pull_triggers::{PullCause, PullTriggers};
+use crate::misc::time::time_manager;
+use crate::services::compliance_export::ComplianceExportService;
+use crate::services::diagnostics::DiagnosticsService;
+use crate::services::inventory_transfer::{InventoryTransferService, TransferRequest};
+use crate::services::latency_probe::LatencyProbeService;
+use crate::services::position_unwind::{PositionUnwindService, UnwindRequest};
+use crate::services::support_bundle::SupportBundleService;
 use crate::statistic_service::StatisticService;
 use mmb_rpc::rest_api::ErrorCode;
 
@@ -17,21 +32,56 @@ use super::common::set_config;
 pub struct RpcImpl {
     server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
     statistics: Arc<StatisticService>,
+    inventory_transfer: Arc<InventoryTransferService>,
+    position_unwind: Arc<PositionUnwindService>,
+    strategy_parameters: Arc<StrategyParameters>,
+    diagnostics: Arc<DiagnosticsService>,
+    support_bundle: Arc<SupportBundleService>,
+    compliance_export: Arc<ComplianceExportService>,
+    pull_triggers: Arc<PullTriggers>,
+    /// Not set if latency probe is disabled in settings
+    latency_probe: Option<Arc<LatencyProbeService>>,
+    /// Format of decimal values in all responses
+    decimal_serialization: DecimalSerialization,
     engine_settings: String,
 }
 
 impl RpcImpl {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         server_stopper_tx: Arc<Mutex<Option<mpsc::Sender<ActionAfterGracefulShutdown>>>>,
         statistics: Arc<StatisticService>,
+        inventory_transfer: Arc<InventoryTransferService>,
+        position_unwind: Arc<PositionUnwindService>,
+        strategy_parameters: Arc<StrategyParameters>,
+        diagnostics: Arc<DiagnosticsService>,
+        support_bundle: Arc<SupportBundleService>,
+        compliance_export: Arc<ComplianceExportService>,
+        pull_triggers: Arc<PullTriggers>,
+        latency_probe: Option<Arc<LatencyProbeService>>,
+        decimal_serialization: DecimalSerialization,
         engine_settings: String,
     ) -> Self {
         Self {
             server_stopper_tx,
             statistics,
+            inventory_transfer,
+            position_unwind,
+            strategy_parameters,
+            diagnostics,
+            support_bundle,
+            compliance_export,
+            pull_triggers,
+            latency_probe,
+            decimal_serialization,
             engine_settings,
         }
     }
+
+    fn to_json(&self, value: &impl Serialize) -> serde_json::Result<String> {
+        self.decimal_serialization
+            .scope(|| serde_json::to_string(value))
+    }
 }
 
 impl MmbRpc for RpcImpl {
@@ -54,16 +104,257 @@ impl MmbRpc for RpcImpl {
     }
 
     fn stats(&self) -> Result<String> {
-        let json_statistic = serde_json::to_string(&self.statistics.statistic_service_state)
-            .map_err(|err| {
-                log::warn!(
-                    "Failed to convert {:?} to string: {}",
-                    self.statistics,
-                    err.to_string()
-                );
-                server_side_error(ErrorCode::FailedToSaveNewConfig)
-            })?;
+        let statistic = self.statistics.rounded_state();
+        let json_statistic = self.to_json(&statistic).map_err(|err| {
+            log::warn!(
+                "Failed to convert {:?} to string: {}",
+                self.statistics,
+                err.to_string()
+            );
+            server_side_error(ErrorCode::FailedToSaveNewConfig)
+        })?;
 
         Ok(json_statistic)
     }
+
+    fn transfers(&self) -> Result<String> {
+        self.to_json(&self.inventory_transfer.transfers())
+            .map_err(|err| transfer_request_error(err.into()))
+    }
+
+    fn initiate_transfer(&self, request: String) -> Result<String> {
+        let request = serde_json::from_str::<TransferRequest>(&request)
+            .map_err(|err| transfer_request_error(err.into()))?;
+
+        let transfer_id = self
+            .inventory_transfer
+            .initiate(request)
+            .map_err(transfer_request_error)?;
+
+        Ok(format!(
+            "Transfer {transfer_id} is initiated and waits for confirmation"
+        ))
+    }
+
+    fn confirm_transfer(&self, transfer_id: u64) -> Result<String> {
+        self.inventory_transfer
+            .confirm(transfer_id)
+            .map_err(transfer_request_error)?;
+
+        Ok(format!("Transfer {transfer_id} is confirmed and started"))
+    }
+
+    fn cancel_transfer(&self, transfer_id: u64) -> Result<String> {
+        self.inventory_transfer
+            .cancel(transfer_id)
+            .map_err(transfer_request_error)?;
+
+        Ok(format!("Transfer {transfer_id} is cancelled"))
+    }
+
+    fn unwinds(&self) -> Result<String> {
+        self.to_json(&self.position_unwind.unwinds())
+            .map_err(|err| unwind_request_error(err.into()))
+    }
+
+    fn start_unwind(&self, request: String) -> Result<String> {
+        let request = serde_json::from_str::<UnwindRequest>(&request)
+            .map_err(|err| unwind_request_error(err.into()))?;
+
+        let unwind_id = self
+            .position_unwind
+            .start(request)
+            .map_err(unwind_request_error)?;
+
+        Ok(format!("Unwind {unwind_id} is started"))
+    }
+
+    fn cancel_unwind(&self, unwind_id: u64) -> Result<String> {
+        self.position_unwind
+            .cancel(unwind_id)
+            .map_err(unwind_request_error)?;
+
+        Ok(format!("Unwind {unwind_id} is cancelled"))
+    }
+
+    fn strategy_parameters(&self) -> Result<String> {
+        self.to_json(&self.strategy_parameters.parameters())
+            .map_err(|err| strategy_parameters_error(err.into()))
+    }
+
+    fn set_strategy_parameters(&self, parameters: String) -> Result<String> {
+        let parameters = serde_json::from_str::<HashMap<String, Decimal>>(&parameters)
+            .map_err(|err| strategy_parameters_error(err.into()))?;
+
+        self.strategy_parameters
+            .request_change(parameters)
+            .map_err(strategy_parameters_error)?;
+
+        Ok("Strategy parameters are accepted and will be applied before next decision".into())
+    }
+
+    fn dump_reservations(&self) -> Result<String> {
+        self.to_json(&self.diagnostics.reservations())
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn dump_book(
+        &self,
+        exchange_account_id: String,
+        currency_pair: String,
+        depth: usize,
+    ) -> Result<String> {
+        let order_book = self
+            .diagnostics
+            .order_book(&exchange_account_id, &currency_pair, depth)
+            .map_err(diagnostics_error)?;
+
+        self.to_json(&order_book)
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn probe_book(
+        &self,
+        exchange_account_id: String,
+        currency_pair: String,
+        depth: usize,
+    ) -> BoxFuture<Result<String>> {
+        let diagnostics = self.diagnostics.clone();
+        let decimal_serialization = self.decimal_serialization;
+        Box::pin(async move {
+            let report = diagnostics
+                .probe_book(&exchange_account_id, &currency_pair, depth)
+                .await
+                .map_err(diagnostics_error)?;
+
+            decimal_serialization
+                .scope(|| serde_json::to_string(&report))
+                .map_err(|err| diagnostics_error(err.into()))
+        })
+    }
+
+    fn book_consistency(&self) -> Result<String> {
+        self.to_json(&self.diagnostics.book_consistency_reports())
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn latency_report(&self) -> Result<String> {
+        let latency_probe = self.latency_probe.as_ref().ok_or_else(|| {
+            diagnostics_error(anyhow::anyhow!("Latency probe isn't enabled in settings"))
+        })?;
+
+        self.to_json(&latency_probe.report())
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn search_symbols(&self, query: String) -> Result<String> {
+        let matches = self
+            .diagnostics
+            .search_symbols(&query)
+            .map_err(diagnostics_error)?;
+
+        self.to_json(&matches)
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn pull_quotes(
+        &self,
+        exchange_account_id: String,
+        currency_pair: Option<String>,
+        reason: String,
+    ) -> Result<String> {
+        let (exchange_account_id, currency_pair) = match currency_pair {
+            Some(currency_pair) => {
+                let (exchange, currency_pair) = self
+                    .diagnostics
+                    .find_market(&exchange_account_id, &currency_pair)
+                    .map_err(pull_quotes_error)?;
+                (exchange.exchange_account_id, Some(currency_pair))
+            }
+            None => {
+                let exchange_account_id = exchange_account_id.parse().map_err(|err| {
+                    pull_quotes_error(anyhow::anyhow!(
+                        "Invalid exchange account id {exchange_account_id}: {err:?}"
+                    ))
+                })?;
+                (exchange_account_id, None)
+            }
+        };
+
+        let event = self.pull_triggers.pull(
+            exchange_account_id,
+            currency_pair,
+            PullCause::ExternalSignal { reason },
+            time_manager::now(),
+        );
+
+        self.to_json(&event)
+            .map_err(|err| pull_quotes_error(err.into()))
+    }
+
+    fn pull_triggers_report(&self) -> Result<String> {
+        self.to_json(&self.pull_triggers.report())
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn dump_timeouts(&self) -> Result<String> {
+        self.to_json(&self.diagnostics.timeouts())
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn dump_tasks(&self) -> Result<String> {
+        self.to_json(&self.diagnostics.tasks())
+            .map_err(|err| diagnostics_error(err.into()))
+    }
+
+    fn export_support_bundle(&self, log_lines: usize) -> Result<String> {
+        let path = self
+            .support_bundle
+            .export(log_lines)
+            .map_err(diagnostics_error)?;
+
+        Ok(path.display().to_string())
+    }
+
+    fn export_audit(&self, from: String, to: String) -> Result<String> {
+        let from = parse_audit_time(&from).map_err(audit_export_error)?;
+        let to = parse_audit_time(&to).map_err(audit_export_error)?;
+        let export = self
+            .compliance_export
+            .export(from, to)
+            .map_err(audit_export_error)?;
+
+        self.to_json(&export)
+            .map_err(|err| audit_export_error(err.into()))
+    }
+}
+
+fn transfer_request_error(error: anyhow::Error) -> jsonrpc_core::Error {
+    server_side_error_with_details(ErrorCode::TransferRequestFailed, &format!("{error:#}"))
+}
+
+fn unwind_request_error(error: anyhow::Error) -> jsonrpc_core::Error {
+    server_side_error_with_details(ErrorCode::UnwindRequestFailed, &format!("{error:#}"))
+}
+
+fn parse_audit_time(time: &str) -> anyhow::Result<mmb_utils::DateTime> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|x| x.with_timezone(&Utc))
+        .with_context(|| format!("Unable to parse time '{time}' of audit range"))
+}
+
+fn pull_quotes_error(error: anyhow::Error) -> jsonrpc_core::Error {
+    server_side_error_with_details(ErrorCode::PullQuotesFailed, &format!("{error:#}"))
+}
+
+fn audit_export_error(error: anyhow::Error) -> jsonrpc_core::Error {
+    server_side_error_with_details(ErrorCode::AuditExportFailed, &format!("{error:#}"))
+}
+
+fn strategy_parameters_error(error: anyhow::Error) -> jsonrpc_core::Error {
+    server_side_error_with_details(ErrorCode::StrategyParametersRejected, &format!("{error:#}"))
+}
+
+fn diagnostics_error(error: anyhow::Error) -> jsonrpc_core::Error {
+    server_side_error_with_details(ErrorCode::DiagnosticsFailed, &format!("{error:#}"))
 }
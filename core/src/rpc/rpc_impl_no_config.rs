@@ -1,4 +1,4 @@
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
 use mmb_rpc::rest_api::MmbRpc;
 use mmb_utils::send_expected::SendExpectedByRef;
 use parking_lot::Mutex;
@@ -52,4 +52,103 @@ impl MmbRpc for RpcImplNoConfig {
     fn stats(&self) -> Result<String> {
         Ok(CONFIG_IS_NOT_SET.into())
     }
+
+    fn transfers(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn initiate_transfer(&self, _request: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn confirm_transfer(&self, _transfer_id: u64) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn cancel_transfer(&self, _transfer_id: u64) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn unwinds(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn start_unwind(&self, _request: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn cancel_unwind(&self, _unwind_id: u64) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn strategy_parameters(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn set_strategy_parameters(&self, _parameters: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn dump_reservations(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn dump_book(
+        &self,
+        _exchange_account_id: String,
+        _currency_pair: String,
+        _depth: usize,
+    ) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn probe_book(
+        &self,
+        _exchange_account_id: String,
+        _currency_pair: String,
+        _depth: usize,
+    ) -> BoxFuture<Result<String>> {
+        Box::pin(futures::future::ready(Ok(CONFIG_IS_NOT_SET.into())))
+    }
+
+    fn book_consistency(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn latency_report(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn search_symbols(&self, _query: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn pull_quotes(
+        &self,
+        _exchange_account_id: String,
+        _currency_pair: Option<String>,
+        _reason: String,
+    ) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn pull_triggers_report(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn dump_timeouts(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn dump_tasks(&self) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn export_support_bundle(&self, _log_lines: usize) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
+
+    fn export_audit(&self, _from: String, _to: String) -> Result<String> {
+        Ok(CONFIG_IS_NOT_SET.into())
+    }
 }
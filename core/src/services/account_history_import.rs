@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use itertools::Itertools;
+use mmb_database::impl_event;
+use mmb_database::postgres_db::account_history::{
+    is_account_history_imported, save_account_history_imported,
+};
+use mmb_database::postgres_db::PgPool;
+use mmb_domain::events::ExchangeBalance;
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderSide};
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::{Exchange, RequestResult};
+use crate::exchanges::general::order::get_order_trades::OrderTrade;
+use crate::misc::time::time_manager;
+use crate::settings::AccountHistoryImportSettings;
+
+#[derive(Debug, Serialize)]
+struct AccountHistoryTrade<'a> {
+    exchange_account_id: ExchangeAccountId,
+    currency_pair: CurrencyPair,
+    trade: &'a OrderTrade,
+}
+
+impl_event!(AccountHistoryTrade<'_>, "account_history_trades");
+
+/// Imports recent account trades from exchange REST API on first launch against already active
+/// account, so local database and positions by fill amount start from actual state instead of flat
+pub struct AccountHistoryImportService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: Arc<Mutex<BalanceManager>>,
+    event_recorder: Arc<EventRecorder>,
+    pool: PgPool,
+    settings: AccountHistoryImportSettings,
+}
+
+impl AccountHistoryImportService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        event_recorder: Arc<EventRecorder>,
+        pool: PgPool,
+        settings: AccountHistoryImportSettings,
+    ) -> Self {
+        Self {
+            exchanges,
+            balance_manager,
+            event_recorder,
+            pool,
+            settings,
+        }
+    }
+
+    pub async fn run(&self) {
+        for exchange in self.exchanges.iter() {
+            let exchange_account_id = *exchange.key();
+            if let Err(err) = self.import_for_exchange(exchange.value()).await {
+                log::error!("Failed to import account history for {exchange_account_id}: {err:?}");
+            }
+        }
+    }
+
+    async fn import_for_exchange(&self, exchange: &Arc<Exchange>) -> Result<()> {
+        let exchange_account_id = exchange.exchange_account_id;
+        if is_account_history_imported(&self.pool, &exchange_account_id.to_string()).await? {
+            log::info!("Account history for {exchange_account_id} was imported earlier");
+            return Ok(());
+        }
+
+        let depth = chrono::Duration::hours(self.settings.depth_hours as i64);
+        let from_datetime = time_manager::now() - depth;
+
+        let symbols = exchange
+            .symbols
+            .iter()
+            .map(|x| x.value().clone())
+            .collect::<Vec<_>>();
+
+        let mut is_all_imported = true;
+        let mut window_positions = HashMap::new();
+        for symbol in &symbols {
+            let currency_pair = symbol.currency_pair();
+            let trades = match exchange
+                .exchange_client
+                .get_my_trades(symbol, Some(from_datetime))
+                .await
+            {
+                RequestResult::Success(trades) => trades,
+                RequestResult::Error(err) => {
+                    log::warn!("Unable to get account trades for {exchange_account_id} {currency_pair}: {err:?}");
+                    is_all_imported = false;
+                    continue;
+                }
+            };
+
+            for trade in &trades {
+                self.event_recorder
+                    .save(AccountHistoryTrade {
+                        exchange_account_id,
+                        currency_pair,
+                        trade,
+                    })
+                    .context("Failed to save imported account trade")?;
+            }
+
+            log::info!(
+                "Imported {} account trades for {exchange_account_id} {currency_pair}",
+                trades.len()
+            );
+
+            window_positions.insert(currency_pair, position_by_trades(&trades));
+        }
+
+        let balances = exchange
+            .exchange_client
+            .get_balance_and_positions()
+            .await
+            .context("Failed to get balances for seeding positions")?
+            .balances;
+
+        let positions = seed_spot_positions(&symbols, &balances, &window_positions);
+        {
+            let mut balance_manager = self.balance_manager.lock();
+            for (symbol, position) in positions {
+                balance_manager.restore_fill_amount_position_from_history(
+                    exchange_account_id,
+                    symbol,
+                    position,
+                )?;
+            }
+        }
+
+        if is_all_imported {
+            save_account_history_imported(&self.pool, &exchange_account_id.to_string()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn position_by_trades(trades: &[OrderTrade]) -> Amount {
+    trades
+        .iter()
+        .map(|trade| match trade.side {
+            OrderSide::Buy => trade.amount,
+            OrderSide::Sell => -trade.amount,
+        })
+        .sum()
+}
+
+/// Starting positions of spot markets. Trades history covers only `depth_hours`, so position is
+/// taken from holdings of amount currency when it is traded on a single market of the account.
+/// Otherwise holdings can't be split between markets and sum of imported trades is used
+fn seed_spot_positions(
+    symbols: &[Arc<Symbol>],
+    balances: &[ExchangeBalance],
+    window_positions: &HashMap<CurrencyPair, Amount>,
+) -> Vec<(Arc<Symbol>, Amount)> {
+    let spot_symbols = symbols.iter().filter(|x| !x.is_derivative).collect_vec();
+    let markets_by_currency = spot_symbols
+        .iter()
+        .counts_by(|symbol| symbol.amount_currency_code);
+
+    spot_symbols
+        .into_iter()
+        .filter_map(|symbol| {
+            let currency_code = symbol.amount_currency_code;
+            let holdings = balances
+                .iter()
+                .find(|x| x.currency_code == currency_code)
+                .map(|x| x.balance);
+
+            let position = match holdings {
+                Some(holdings) if markets_by_currency[&currency_code] == 1 => holdings,
+                _ => {
+                    let position = *window_positions.get(&symbol.currency_pair())?;
+                    log::warn!(
+                        "Position of {} is seeded from account trades for last imported period only",
+                        symbol.currency_pair()
+                    );
+                    position
+                }
+            };
+            Some((symbol.clone(), position))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::events::TradeId;
+    use mmb_domain::exchanges::symbol::Precision;
+    use mmb_domain::order::fill::OrderFillType;
+    use mmb_domain::order::snapshot::{ExchangeOrderId, OrderRole};
+    use rust_decimal_macros::dec;
+
+    fn symbol(base: &str, quote: &str) -> Arc<Symbol> {
+        let (base, quote) = (base.into(), quote.into());
+        Arc::new(Symbol::new(
+            false,
+            base.as_str().into(),
+            base,
+            quote.as_str().into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick { tick: dec!(0.1) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ))
+    }
+
+    fn trade(side: OrderSide, amount: Amount) -> OrderTrade {
+        OrderTrade::new(
+            ExchangeOrderId::new("1".into()),
+            TradeId::Number(1),
+            time_manager::now(),
+            dec!(100),
+            amount,
+            side,
+            OrderRole::Maker,
+            "BTC".into(),
+            None,
+            None,
+            OrderFillType::UserTrade,
+        )
+    }
+
+    fn balance(currency_code: &str, balance: Amount) -> ExchangeBalance {
+        ExchangeBalance {
+            currency_code: currency_code.into(),
+            balance,
+        }
+    }
+
+    #[test]
+    fn position_by_trades_is_signed_sum_of_amounts() {
+        let trades = [
+            trade(OrderSide::Buy, dec!(2)),
+            trade(OrderSide::Sell, dec!(0.5)),
+            trade(OrderSide::Buy, dec!(1)),
+        ];
+
+        assert_eq!(position_by_trades(&trades), dec!(2.5));
+    }
+
+    #[test]
+    fn position_is_seeded_from_holdings_beyond_imported_period() {
+        let btc_usdt = symbol("BTC", "USDT");
+        // trades of imported period cover only part of accumulated holdings
+        let window_positions = HashMap::from([(btc_usdt.currency_pair(), dec!(0.5))]);
+        let balances = [balance("BTC", dec!(3)), balance("USDT", dec!(1000))];
+
+        let positions = seed_spot_positions(&[btc_usdt.clone()], &balances, &window_positions);
+
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].0.currency_pair(), btc_usdt.currency_pair());
+        assert_eq!(positions[0].1, dec!(3));
+    }
+
+    #[test]
+    fn position_of_currency_shared_by_markets_is_seeded_from_trades() {
+        let btc_usdt = symbol("BTC", "USDT");
+        let btc_eth = symbol("BTC", "ETH");
+        let eth_usdt = symbol("ETH", "USDT");
+        let window_positions = HashMap::from([(btc_usdt.currency_pair(), dec!(0.5))]);
+        let balances = [balance("BTC", dec!(3)), balance("ETH", dec!(7))];
+
+        let positions = seed_spot_positions(
+            &[btc_usdt.clone(), btc_eth, eth_usdt.clone()],
+            &balances,
+            &window_positions,
+        );
+
+        let positions = positions
+            .into_iter()
+            .map(|(symbol, position)| (symbol.currency_pair(), position))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(
+            positions,
+            HashMap::from([
+                (btc_usdt.currency_pair(), dec!(0.5)),
+                (eth_usdt.currency_pair(), dec!(7)),
+            ])
+        );
+    }
+}
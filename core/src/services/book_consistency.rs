@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use mmb_domain::decimal_serialization::{decimal, option_decimal};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::DateTime;
+use serde::Serialize;
+
+/// Divergence of one side of local order book from order book snapshot received by REST
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SideDivergence {
+    /// Count of levels of REST snapshot
+    pub compared_levels: usize,
+    /// Levels of REST snapshot which are absent in local order book
+    pub missing_levels: usize,
+    /// Levels of local order book inside price range of REST snapshot which are absent in it
+    pub extra_levels: usize,
+    /// Levels with the same price and different amount
+    pub amount_mismatches: usize,
+    /// Maximum absolute difference of amount on the same price level
+    #[serde(with = "decimal")]
+    pub max_amount_difference: Amount,
+    #[serde(with = "option_decimal")]
+    pub local_best_price: Option<Price>,
+    #[serde(with = "option_decimal")]
+    pub rest_best_price: Option<Price>,
+}
+
+impl SideDivergence {
+    /// Levels of both sides should be sorted from the best price.
+    /// Local levels beyond the worst level of REST snapshot aren't compared because
+    /// REST snapshot is limited by depth
+    fn new(
+        local_levels: impl Iterator<Item = (Price, Amount)>,
+        rest_levels: &[(Price, Amount)],
+        is_asks: bool,
+    ) -> Self {
+        let mut local_levels = local_levels.peekable();
+        let local_best_price = local_levels.peek().map(|(price, _)| *price);
+
+        let worst_rest_price = rest_levels.last().map(|(price, _)| *price);
+        let local_levels: HashMap<Price, Amount> = local_levels
+            .take_while(|(price, _)| match worst_rest_price {
+                Some(worst_price) if is_asks => *price <= worst_price,
+                Some(worst_price) => *price >= worst_price,
+                None => true,
+            })
+            .collect();
+
+        let mut divergence = SideDivergence {
+            compared_levels: rest_levels.len(),
+            local_best_price,
+            rest_best_price: rest_levels.first().map(|(price, _)| *price),
+            ..Default::default()
+        };
+
+        for (price, rest_amount) in rest_levels {
+            match local_levels.get(price) {
+                None => divergence.missing_levels += 1,
+                Some(local_amount) if local_amount != rest_amount => {
+                    divergence.amount_mismatches += 1;
+                    divergence.max_amount_difference = divergence
+                        .max_amount_difference
+                        .max((local_amount - rest_amount).abs());
+                }
+                Some(_) => {}
+            }
+        }
+
+        divergence.extra_levels = local_levels
+            .keys()
+            .filter(|price| {
+                !rest_levels
+                    .iter()
+                    .any(|(rest_price, _)| rest_price == *price)
+            })
+            .count();
+
+        divergence
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.missing_levels == 0
+            && self.extra_levels == 0
+            && self.amount_mismatches == 0
+            && self.local_best_price == self.rest_best_price
+    }
+}
+
+/// Result of level-by-level comparison of local order book with REST depth snapshot of market.
+/// Books are taken at slightly different moments, so small divergence is expected
+/// on fast markets, while persistent divergence means that local order book is broken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BookConsistencyReport {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub time: DateTime,
+    pub local_update_time: DateTime,
+    pub asks: SideDivergence,
+    pub bids: SideDivergence,
+}
+
+impl BookConsistencyReport {
+    pub(crate) fn new(
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+        time: DateTime,
+        local_snapshot: &LocalOrderBookSnapshot,
+        rest_snapshot: &OrderBookData,
+        depth: usize,
+    ) -> Self {
+        let rest_asks = take_levels(rest_snapshot.asks.iter(), depth);
+        let rest_bids = take_levels(rest_snapshot.bids.iter().rev(), depth);
+
+        BookConsistencyReport {
+            exchange_account_id,
+            currency_pair,
+            time,
+            local_update_time: local_snapshot.last_update_time,
+            asks: SideDivergence::new(
+                copied_levels(local_snapshot.get_asks_price_levels()),
+                &rest_asks,
+                true,
+            ),
+            bids: SideDivergence::new(
+                copied_levels(local_snapshot.get_bids_price_levels()),
+                &rest_bids,
+                false,
+            ),
+        }
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.asks.is_consistent() && self.bids.is_consistent()
+    }
+}
+
+fn copied_levels<'a>(
+    levels: impl Iterator<Item = (&'a Price, &'a Amount)>,
+) -> impl Iterator<Item = (Price, Amount)> {
+    levels.map(|(price, amount)| (*price, *amount))
+}
+
+fn take_levels<'a>(
+    levels: impl Iterator<Item = (&'a Price, &'a Amount)>,
+    depth: usize,
+) -> Vec<(Price, Amount)> {
+    copied_levels(levels).take(depth).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn equal_sides_are_consistent() {
+        let levels = [(dec!(10), dec!(1)), (dec!(11), dec!(2))];
+
+        let divergence = SideDivergence::new(levels.into_iter(), &levels, true);
+
+        assert!(divergence.is_consistent());
+        assert_eq!(divergence.compared_levels, 2);
+    }
+
+    #[test]
+    fn local_levels_beyond_rest_depth_are_not_compared() {
+        let local_levels = [(dec!(10), dec!(1)), (dec!(9), dec!(2)), (dec!(8), dec!(3))];
+        let rest_levels = [(dec!(10), dec!(1)), (dec!(9), dec!(2))];
+
+        let divergence = SideDivergence::new(local_levels.into_iter(), &rest_levels, false);
+
+        assert!(divergence.is_consistent());
+    }
+
+    #[test]
+    fn divergence_of_levels_is_counted() {
+        let local_levels = [
+            (dec!(10), dec!(1)),
+            (dec!(10.5), dec!(4)),
+            (dec!(11), dec!(5)),
+        ];
+        let rest_levels = [
+            (dec!(10.2), dec!(1)),
+            (dec!(10.5), dec!(2)),
+            (dec!(11), dec!(5.5)),
+        ];
+
+        let divergence = SideDivergence::new(local_levels.into_iter(), &rest_levels, true);
+
+        assert_eq!(
+            divergence,
+            SideDivergence {
+                compared_levels: 3,
+                missing_levels: 1,
+                extra_levels: 1,
+                amount_mismatches: 2,
+                max_amount_difference: dec!(2),
+                local_best_price: Some(dec!(10)),
+                rest_best_price: Some(dec!(10.2)),
+            }
+        );
+        assert!(!divergence.is_consistent());
+    }
+}
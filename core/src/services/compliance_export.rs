@@ -0,0 +1,351 @@
+use crate::exchanges::general::exchange::Exchange;
+use anyhow::{ensure, Context, Result};
+use dashmap::DashMap;
+use mmb_domain::events::TradeId;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, ExchangeId};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, IntentId, OrderFillRole, OrderSide, OrderSnapshot,
+    OrderStatus, OrderType, Price,
+};
+use mmb_utils::DateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const AUDIT_EXPORTS_DIR: &str = "audit_exports";
+/// Previous hash of the first record of chain
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const FILE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AuditEventType {
+    Created,
+    StatusChanged,
+    Filled,
+}
+
+/// Single order event of audit file. Decision ids link event to strategy decision
+/// and external signal which the order is created by
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEvent {
+    pub time: DateTime,
+    pub event_type: AuditEventType,
+    pub exchange_id: ExchangeId,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub client_order_id: ClientOrderId,
+    pub exchange_order_id: Option<ExchangeOrderId>,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub status: Option<OrderStatus>,
+    pub price: Option<Price>,
+    pub amount: Option<Amount>,
+    pub trade_id: Option<TradeId>,
+    pub role: Option<OrderFillRole>,
+    pub strategy_name: String,
+    pub intent_id: Option<IntentId>,
+    pub signal_id: Option<String>,
+}
+
+impl AuditEvent {
+    fn new(order: &OrderSnapshot, time: DateTime, event_type: AuditEventType) -> Self {
+        let header = &order.header;
+        AuditEvent {
+            time,
+            event_type,
+            exchange_id: header.exchange_account_id.exchange_id,
+            exchange_account_id: header.exchange_account_id,
+            currency_pair: header.currency_pair,
+            client_order_id: header.client_order_id.clone(),
+            exchange_order_id: order.props.exchange_order_id.clone(),
+            side: header.side,
+            order_type: header.order_type,
+            status: None,
+            price: None,
+            amount: None,
+            trade_id: None,
+            role: None,
+            strategy_name: header.strategy_name.clone(),
+            intent_id: header.intent_id,
+            signal_id: header.signal_id.clone(),
+        }
+    }
+
+    /// Creation, status changes and fills of order in chronological order
+    fn from_order(order: &OrderSnapshot) -> Vec<Self> {
+        let mut events = vec![AuditEvent {
+            price: order.header.source_price,
+            amount: Some(order.header.amount),
+            ..AuditEvent::new(order, order.props.init_time, AuditEventType::Created)
+        }];
+
+        events.extend(
+            order
+                .status_history
+                .changes()
+                .map(|(status, time)| AuditEvent {
+                    status: Some(status),
+                    ..AuditEvent::new(order, time, AuditEventType::StatusChanged)
+                }),
+        );
+
+        events.extend(order.fills.fills.iter().map(|fill| {
+            let event = AuditEvent::new(order, fill.receive_time(), AuditEventType::Filled);
+            AuditEvent {
+                price: Some(fill.price()),
+                amount: Some(fill.amount()),
+                trade_id: fill.trade_id().cloned(),
+                role: Some(fill.role()),
+                intent_id: fill.intent_id().or(event.intent_id),
+                ..event
+            }
+        }));
+
+        events.sort_by_key(|x| x.time);
+        events
+    }
+}
+
+/// Line of audit file. Every record contains hash of previous one, so change, removal
+/// or reordering of any record breaks the chain from that record to the end of file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AuditRecord {
+    sequence: u64,
+    /// `AuditEvent` is kept as JSON value, so verification hashes the same text
+    /// without deserializing of domain types
+    event: serde_json::Value,
+    prev_hash: String,
+    hash: String,
+}
+
+fn record_hash(sequence: u64, prev_hash: &str, event: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(sequence.to_string().as_bytes());
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(event.to_string().as_bytes());
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn chain_records(events: &[AuditEvent]) -> Result<Vec<AuditRecord>> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+    events
+        .iter()
+        .enumerate()
+        .map(|(sequence, event)| {
+            let sequence = sequence as u64;
+            let event = serde_json::to_value(event).context("Unable to serialize audit event")?;
+            let hash = record_hash(sequence, &prev_hash, &event);
+            let record = AuditRecord {
+                sequence,
+                event,
+                prev_hash: std::mem::replace(&mut prev_hash, hash.clone()),
+                hash,
+            };
+            Ok(record)
+        })
+        .collect()
+}
+
+/// Check hash chain of records and return hash of the last record
+fn verify_records(records: impl Iterator<Item = Result<AuditRecord>>) -> Result<String> {
+    let mut prev_hash = GENESIS_HASH.to_string();
+    for (expected_sequence, record) in records.enumerate() {
+        let record = record?;
+        let sequence = record.sequence;
+        ensure!(
+            sequence == expected_sequence as u64,
+            "Record {sequence} is found instead of {expected_sequence}"
+        );
+        ensure!(
+            record.prev_hash == prev_hash,
+            "Previous hash of record {sequence} doesn't match hash of previous record"
+        );
+        ensure!(
+            record_hash(sequence, &prev_hash, &record.event) == record.hash,
+            "Hash of record {sequence} doesn't match its content"
+        );
+
+        prev_hash = record.hash;
+    }
+
+    Ok(prev_hash)
+}
+
+/// Verify hash chain of audit file and return hash of its last record
+pub fn verify_audit_file(path: &Path) -> Result<String> {
+    let file = File::open(path)
+        .with_context(|| format!("Unable to open audit file {}", path.display()))?;
+
+    verify_records(BufReader::new(file).lines().map(|line| {
+        let line = line.context("Unable to read audit file")?;
+        serde_json::from_str(&line).context("Unable to parse audit record")
+    }))
+}
+
+/// Result of audit export, last hash can be kept separately to prove completeness of file
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditExport {
+    pub path: PathBuf,
+    pub records_count: usize,
+    pub last_hash: String,
+}
+
+/// Writes order events of requested time range to hash chained JSON lines file
+/// for compliance reviews. Orders are taken from orders pools of exchanges,
+/// so range is limited by orders which are kept in memory
+pub struct ComplianceExportService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+}
+
+impl ComplianceExportService {
+    pub(crate) fn new(exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>) -> Arc<Self> {
+        Arc::new(ComplianceExportService { exchanges })
+    }
+
+    /// Write events of orders happened in range `[from, to)` into `AUDIT_EXPORTS_DIR`
+    pub fn export(&self, from: DateTime, to: DateTime) -> Result<AuditExport> {
+        ensure!(
+            from < to,
+            "Start of audit range {from} should be before end {to}"
+        );
+
+        let mut events: Vec<_> = self
+            .exchanges
+            .iter()
+            .flat_map(|exchange| {
+                exchange
+                    .orders
+                    .cache_by_client_id
+                    .iter()
+                    .flat_map(|order| AuditEvent::from_order(&order.deep_clone()))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|x| from <= x.time && x.time < to)
+            .collect();
+        events.sort_by(|a, b| {
+            a.time
+                .cmp(&b.time)
+                .then_with(|| a.client_order_id.as_str().cmp(b.client_order_id.as_str()))
+        });
+
+        let records = chain_records(&events)?;
+
+        fs::create_dir_all(AUDIT_EXPORTS_DIR)
+            .with_context(|| format!("Unable to create directory {AUDIT_EXPORTS_DIR}"))?;
+        let path = Path::new(AUDIT_EXPORTS_DIR).join(format!(
+            "audit_{}_{}.jsonl",
+            from.format(FILE_TIME_FORMAT),
+            to.format(FILE_TIME_FORMAT)
+        ));
+
+        write_records(&path, &records)
+            .with_context(|| format!("Unable to write audit file {}", path.display()))?;
+
+        let last_hash = records
+            .last()
+            .map_or_else(|| GENESIS_HASH.to_string(), |x| x.hash.clone());
+        log::info!(
+            "Audit file with {} records is exported to {}, last hash {last_hash}",
+            records.len(),
+            path.display()
+        );
+
+        Ok(AuditExport {
+            path,
+            records_count: records.len(),
+            last_hash,
+        })
+    }
+}
+
+fn write_records(path: &Path, records: &[AuditRecord]) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for record in records {
+        serde_json::to_writer(&mut writer, record)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    fn event(seconds: i64, price: Price) -> AuditEvent {
+        let exchange_account_id = ExchangeAccountId::new("Binance", 0);
+        AuditEvent {
+            time: Utc.timestamp_opt(seconds, 0).unwrap(),
+            event_type: AuditEventType::Filled,
+            exchange_id: exchange_account_id.exchange_id,
+            exchange_account_id,
+            currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            client_order_id: ClientOrderId::new("test".into()),
+            exchange_order_id: None,
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            status: None,
+            price: Some(price),
+            amount: Some(dec!(0.1)),
+            trade_id: None,
+            role: Some(OrderFillRole::Maker),
+            strategy_name: "test".to_string(),
+            intent_id: None,
+            signal_id: Some("signal".to_string()),
+        }
+    }
+
+    fn records() -> Vec<AuditRecord> {
+        chain_records(&[
+            event(1, dec!(100)),
+            event(2, dec!(101)),
+            event(3, dec!(102)),
+        ])
+        .expect("chained records")
+    }
+
+    fn verify(records: Vec<AuditRecord>) -> Result<String> {
+        // records are verified after round trip through text like in audit file
+        verify_records(records.into_iter().map(|x| {
+            let line = serde_json::to_string(&x)?;
+            Ok(serde_json::from_str(&line)?)
+        }))
+    }
+
+    #[test]
+    fn chain_of_unchanged_records_is_valid() {
+        let records = records();
+        let last_hash = records.last().expect("last record").hash.clone();
+
+        assert_eq!(records[0].prev_hash, GENESIS_HASH);
+        assert_eq!(records[1].prev_hash, records[0].hash);
+        assert_eq!(verify(records).expect("valid chain"), last_hash);
+    }
+
+    #[test]
+    fn altered_record_breaks_chain() {
+        let mut records = records();
+        records[1].event["price"] = serde_json::json!("99");
+
+        assert!(verify(records).is_err());
+    }
+
+    #[test]
+    fn removed_record_breaks_chain() {
+        let mut records = records();
+        records.remove(1);
+
+        assert!(verify(records).is_err());
+    }
+}
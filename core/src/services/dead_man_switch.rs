@@ -0,0 +1,309 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::order::cancel_by_filter::OrdersFilter;
+use crate::exchanges::internal_events_loop::INTERNAL_EVENTS_LOOP_NAME;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::pull_triggers::{PullCause, PullTriggers};
+use crate::misc::time::time_manager;
+use crate::services::heartbeat::LivenessRegistry;
+use crate::settings::DeadManSwitchSettings;
+use dashmap::{DashMap, DashSet};
+use futures::future::join_all;
+use itertools::Itertools;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot::Receiver;
+
+/// Cancels all orders if engine stops working. Two levels of protection are used:
+/// - cancel-all-after switch of exchange is re-armed periodically, so exchange cancels orders
+///   itself if whole process hangs or loses connection;
+/// - internal watchdog cancels all orders if events loop is stalled while process is alive,
+///   re-arming of exchange switches is stopped until events loop recovers.
+///
+/// If strategy stall timeout is set, stall of a single disposition executor cancels only orders
+/// of its strategies on its market and pauses quoting of these strategies by pull trigger
+pub struct DeadManSwitchService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    liveness_registry: Arc<LivenessRegistry>,
+    settings: DeadManSwitchSettings,
+    /// Exchanges which don't support cancel-all-after, switch isn't armed for them
+    unsupported_exchanges: DashSet<ExchangeAccountId>,
+    is_triggered: AtomicBool,
+    pull_triggers: Arc<PullTriggers>,
+    /// Executors which orders are cancelled because of stall
+    stalled_subsystems: DashSet<String>,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for DeadManSwitchService {
+    fn name(&self) -> &str {
+        "DeadManSwitchService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl DeadManSwitchService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        liveness_registry: Arc<LivenessRegistry>,
+        settings: DeadManSwitchSettings,
+        pull_triggers: Arc<PullTriggers>,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        Self {
+            exchanges,
+            liveness_registry,
+            settings,
+            unsupported_exchanges: DashSet::new(),
+            is_triggered: AtomicBool::new(false),
+            pull_triggers,
+            stalled_subsystems: DashSet::new(),
+            cancellation_token,
+        }
+    }
+
+    pub async fn check(self: Arc<Self>) {
+        let last_activity_time = self
+            .liveness_registry
+            .last_activity_time(INTERNAL_EVENTS_LOOP_NAME);
+
+        if !is_stalled(
+            last_activity_time,
+            time_manager::now(),
+            self.settings.stall_timeout(),
+        ) {
+            if self.is_triggered.swap(false, Ordering::SeqCst) {
+                log::info!("Events loop is recovered, dead man's switch is re-armed");
+            }
+
+            self.arm_exchange_switches().await;
+            self.check_strategies().await;
+            return;
+        }
+
+        if self.is_triggered.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        log::error!("Events loop is stalled since {last_activity_time:?}, cancelling all orders");
+        join_all(self.exchanges.iter().map(|x| cancel_all_orders(x.clone()))).await;
+    }
+
+    /// Cancel orders of strategies of stalled executors and pause quoting of these strategies on
+    /// their markets. Quoting is resumed after cool-down of pause, recovery of executor doesn't
+    /// extend it
+    async fn check_strategies(&self) {
+        let Some(stall_timeout) = self.settings.strategy_stall_timeout() else {
+            return;
+        };
+
+        let now = time_manager::now();
+        for (subsystem, scope) in self.liveness_registry.orders_scopes() {
+            let market_account_id = scope.market_account_id;
+            let last_activity_time = self.liveness_registry.last_activity_time(&subsystem);
+            if !is_stalled(last_activity_time, now, stall_timeout) {
+                if self.stalled_subsystems.remove(&subsystem).is_some() {
+                    log::info!("{subsystem} is recovered, quoting of its strategies on {market_account_id} is resumed after cool-down");
+                }
+                continue;
+            }
+
+            if !self.stalled_subsystems.insert(subsystem.clone()) {
+                continue;
+            }
+
+            log::error!(
+                "{subsystem} is stalled since {last_activity_time:?}, cancelling orders of strategies {:?} on {market_account_id}",
+                scope.strategy_names
+            );
+            for strategy_name in &scope.strategy_names {
+                let _ = self.pull_triggers.pull_strategy(
+                    market_account_id,
+                    strategy_name,
+                    PullCause::StrategyStalled {
+                        subsystem: subsystem.clone(),
+                    },
+                    now,
+                );
+            }
+
+            let Some(exchange) = self
+                .exchanges
+                .get(&market_account_id.exchange_account_id)
+                .map(|x| x.clone())
+            else {
+                continue;
+            };
+
+            for strategy_name in scope.strategy_names {
+                let filter = OrdersFilter::by_strategy(strategy_name)
+                    .with_currency_pair(market_account_id.currency_pair);
+                for result in exchange
+                    .cancel_orders(&filter, self.cancellation_token.clone())
+                    .await
+                {
+                    if let Err(err) = result {
+                        log::error!("Failed to cancel order of stalled strategy on {market_account_id}: {err:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    async fn arm_exchange_switches(&self) {
+        let timeout = self.settings.timeout();
+        let exchanges = self
+            .exchanges
+            .iter()
+            .filter(|x| !self.unsupported_exchanges.contains(x.key()))
+            .map(|x| x.value().clone())
+            .collect_vec();
+
+        let cancellation_token = &self.cancellation_token;
+        let actions = exchanges.into_iter().map(|exchange| async move {
+            let result = exchange
+                .cancel_all_after(timeout, cancellation_token.clone())
+                .await;
+            (exchange.exchange_account_id, result)
+        });
+
+        for (exchange_account_id, result) in join_all(actions).await {
+            match result {
+                Ok(true) => {}
+                Ok(false) => {
+                    log::warn!("Cancel-all-after isn't supported on {exchange_account_id}, orders are protected by internal watchdog only");
+                    let _ = self.unsupported_exchanges.insert(exchange_account_id);
+                }
+                Err(err) => {
+                    if !self.cancellation_token.is_cancellation_requested() {
+                        log::error!(
+                            "Failed to arm dead man's switch on {exchange_account_id}: {err:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn cancel_all_orders(exchange: Arc<Exchange>) {
+    let currency_pairs = exchange
+        .orders
+        .not_finished
+        .iter()
+        .map(|x| x.currency_pair())
+        .unique()
+        .collect_vec();
+
+    for currency_pair in currency_pairs {
+        if let Err(err) = exchange.cancel_all_orders(currency_pair).await {
+            log::error!(
+                "Failed to cancel all orders of {currency_pair} on {}: {err:?}",
+                exchange.exchange_account_id
+            );
+        }
+    }
+}
+
+/// Events loop which hasn't started yet isn't considered stalled
+fn is_stalled(
+    last_activity_time: Option<DateTime>,
+    now: DateTime,
+    stall_timeout: Duration,
+) -> bool {
+    let Some(last_activity_time) = last_activity_time else {
+        return false;
+    };
+
+    chrono::Duration::from_std(stall_timeout)
+        .map(|stall_timeout| last_activity_time + stall_timeout < now)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::get_test_exchange;
+    use crate::settings::PullTriggersSettings;
+    use chrono::Utc;
+    use mmb_domain::market::{CurrencyPair, MarketAccountId};
+
+    #[test]
+    fn events_loop_is_stalled_after_timeout() {
+        let now = Utc::now();
+        let stall_timeout = Duration::from_secs(30);
+
+        assert!(!is_stalled(None, now, stall_timeout));
+        assert!(!is_stalled(
+            Some(now - chrono::Duration::seconds(10)),
+            now,
+            stall_timeout
+        ));
+        assert!(is_stalled(
+            Some(now - chrono::Duration::seconds(31)),
+            now,
+            stall_timeout
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_stalled_strategy_is_paused_once() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let market_account_id = MarketAccountId::new(
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("phb".into(), "btc".into()),
+        );
+        let pull_triggers = PullTriggers::new(
+            PullTriggersSettings::default(),
+            exchange.event_recorder.clone(),
+        );
+        let liveness_registry = Arc::new(LivenessRegistry::default());
+        liveness_registry.register_activity("executor");
+        liveness_registry.register_orders_scope("executor", market_account_id, "stalled");
+
+        let exchanges = DashMap::new();
+        let _ = exchanges.insert(exchange.exchange_account_id, exchange.clone());
+        let service = DeadManSwitchService::new(
+            exchanges,
+            liveness_registry.clone(),
+            DeadManSwitchSettings {
+                timeout_secs: 60,
+                period_secs: 10,
+                stall_timeout_secs: 30,
+                strategy_stall_timeout_secs: Some(1),
+            },
+            pull_triggers.clone(),
+            CancellationToken::new(),
+        );
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        service.check_strategies().await;
+
+        let now = time_manager::now();
+        let is_held = |strategy_name| {
+            pull_triggers
+                .strategy_hold_until(market_account_id, strategy_name, now)
+                .is_some()
+        };
+        assert!(is_held("stalled"));
+        assert!(!is_held("other"));
+        assert!(pull_triggers.hold_until(market_account_id, now).is_none());
+        assert_eq!(pull_triggers.report().len(), 1);
+
+        // stall is handled only once
+        service.check_strategies().await;
+        assert_eq!(pull_triggers.report().len(), 1);
+
+        // recovery doesn't extend pause
+        liveness_registry.register_activity("executor");
+        service.check_strategies().await;
+        assert!(service.stalled_subsystems.is_empty());
+        assert_eq!(pull_triggers.report().len(), 1);
+    }
+}
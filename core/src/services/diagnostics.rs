@@ -0,0 +1,331 @@
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::balance::manager::balance_reservation::BalanceReservation;
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::internal_events_loop::InternalEventsLoop;
+use crate::exchanges::timeouts::requests_timeout_manager::RequestsTimeoutState;
+use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
+use crate::misc::time::time_manager;
+use crate::services::book_consistency::BookConsistencyReport;
+use crate::services::symbol_search::{search_symbols, SymbolMatch, SymbolQuery};
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use mmb_domain::decimal_serialization::{decimal, decimal_levels};
+use mmb_domain::market::{
+    CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId, MarketId,
+};
+use mmb_domain::order::snapshot::{Amount, OrderSnapshot, Price, ReservationId};
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::spawned_futures::{spawned_futures, SpawnedFutureInfo};
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReservationDump {
+    pub reservation_id: ReservationId,
+    pub reservation: BalanceReservation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceDump {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_code: CurrencyCode,
+    #[serde(with = "decimal")]
+    pub balance: Amount,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderBookDump {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub last_update_time: DateTime,
+    /// Best asks first
+    #[serde(with = "decimal_levels")]
+    pub asks: Vec<(Price, Amount)>,
+    /// Best bids first
+    #[serde(with = "decimal_levels")]
+    pub bids: Vec<(Price, Amount)>,
+}
+
+impl OrderBookDump {
+    fn new(
+        exchange_account_id: ExchangeAccountId,
+        currency_pair: CurrencyPair,
+        snapshot: &LocalOrderBookSnapshot,
+        depth: usize,
+    ) -> Self {
+        OrderBookDump {
+            exchange_account_id,
+            currency_pair,
+            last_update_time: snapshot.last_update_time,
+            asks: take_levels(snapshot.get_asks_price_levels(), depth),
+            bids: take_levels(snapshot.get_bids_price_levels(), depth),
+        }
+    }
+}
+
+fn take_levels<'a>(
+    levels: impl Iterator<Item = (&'a Price, &'a Amount)>,
+    depth: usize,
+) -> Vec<(Price, Amount)> {
+    levels
+        .take(depth)
+        .map(|(price, amount)| (*price, *amount))
+        .collect()
+}
+
+/// Collects internal state of engine for inspection by operator via control panel
+pub struct DiagnosticsService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: Arc<Mutex<BalanceManager>>,
+    timeout_manager: Arc<TimeoutManager>,
+    internal_events_loop: Arc<InternalEventsLoop>,
+    /// Last reports of periodic sampling of order books consistency
+    book_consistency_reports: Mutex<HashMap<MarketAccountId, BookConsistencyReport>>,
+}
+
+impl DiagnosticsService {
+    pub(crate) fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        timeout_manager: Arc<TimeoutManager>,
+        internal_events_loop: Arc<InternalEventsLoop>,
+    ) -> Arc<Self> {
+        Arc::new(DiagnosticsService {
+            exchanges,
+            balance_manager,
+            timeout_manager,
+            internal_events_loop,
+            book_consistency_reports: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn reservations(&self) -> Vec<ReservationDump> {
+        self.balance_manager
+            .lock()
+            .get_reservations()
+            .into_iter()
+            .map(|(reservation_id, reservation)| ReservationDump {
+                reservation_id,
+                reservation,
+            })
+            .collect()
+    }
+
+    /// Last balances received from exchanges
+    pub fn balances(&self) -> Vec<BalanceDump> {
+        let balances = self.balance_manager.lock().get_balances();
+
+        balances
+            .balances_by_exchange_id
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(exchange_account_id, balances)| {
+                balances
+                    .into_iter()
+                    .map(move |(currency_code, balance)| BalanceDump {
+                        exchange_account_id,
+                        currency_code,
+                        balance,
+                    })
+            })
+            .collect()
+    }
+
+    /// Not finished orders of all exchange accounts
+    pub fn open_orders(&self) -> Vec<OrderSnapshot> {
+        self.exchanges
+            .iter()
+            .flat_map(|exchange| {
+                exchange
+                    .orders
+                    .not_finished
+                    .iter()
+                    .map(|order| order.deep_clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Top `depth` levels of local order book of market
+    pub fn order_book(
+        &self,
+        exchange_account_id: &str,
+        currency_pair: &str,
+        depth: usize,
+    ) -> Result<OrderBookDump> {
+        let (exchange, currency_pair) = self.find_market(exchange_account_id, currency_pair)?;
+        let exchange_account_id = exchange.exchange_account_id;
+
+        let market_id = MarketId::new(exchange_account_id.exchange_id, currency_pair);
+        let snapshot = self
+            .internal_events_loop
+            .order_book_snapshot(market_id)
+            .with_context(|| format!("There is no order book for {market_id}"))?;
+
+        Ok(OrderBookDump::new(
+            exchange_account_id,
+            currency_pair,
+            &snapshot,
+            depth,
+        ))
+    }
+
+    /// Compare top `depth` levels of local order book of market with snapshot requested by REST
+    pub async fn probe_book(
+        &self,
+        exchange_account_id: &str,
+        currency_pair: &str,
+        depth: usize,
+    ) -> Result<BookConsistencyReport> {
+        let (exchange, currency_pair) = self.find_market(exchange_account_id, currency_pair)?;
+        self.probe_market_book(&exchange, currency_pair, depth)
+            .await
+    }
+
+    /// Probe order book of market and keep report for `book_consistency_reports`
+    pub(crate) async fn sample_book_consistency(
+        &self,
+        market_account_id: MarketAccountId,
+        depth: usize,
+    ) {
+        let exchange = match self.exchanges.get(&market_account_id.exchange_account_id) {
+            Some(exchange) => exchange.clone(),
+            None => {
+                log::error!(
+                    "Exchange {} for sampling order book consistency is not found",
+                    market_account_id.exchange_account_id
+                );
+                return;
+            }
+        };
+
+        match self
+            .probe_market_book(&exchange, market_account_id.currency_pair, depth)
+            .await
+        {
+            Ok(report) => {
+                if !report.is_consistent() {
+                    log::warn!(
+                        "Local order book of {market_account_id} diverges from REST snapshot: {report:?}"
+                    );
+                }
+                self.book_consistency_reports
+                    .lock()
+                    .insert(market_account_id, report);
+            }
+            Err(err) => {
+                log::warn!("Failed to probe order book consistency of {market_account_id}: {err:?}")
+            }
+        }
+    }
+
+    /// Last reports of periodic sampling of order books consistency
+    pub fn book_consistency_reports(&self) -> Vec<BookConsistencyReport> {
+        self.book_consistency_reports
+            .lock()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    async fn probe_market_book(
+        &self,
+        exchange: &Exchange,
+        currency_pair: CurrencyPair,
+        depth: usize,
+    ) -> Result<BookConsistencyReport> {
+        let exchange_account_id = exchange.exchange_account_id;
+        self.timeout_manager
+            .reserve_when_available(
+                exchange_account_id,
+                RequestType::GetOrderBook,
+                None,
+                CancellationToken::default(),
+            )
+            .await;
+
+        let rest_snapshot = exchange
+            .exchange_client
+            .get_order_book_snapshot(currency_pair, depth)
+            .await
+            .with_context(|| {
+                format!("Order book snapshot by REST isn't supported on {exchange_account_id}")
+            })?
+            .with_context(|| {
+                format!(
+                    "Failed to get order book snapshot of {currency_pair} on {exchange_account_id}"
+                )
+            })?;
+
+        // Local order book is taken after response to reduce difference of moments of books
+        let market_id = MarketId::new(exchange_account_id.exchange_id, currency_pair);
+        let local_snapshot = self
+            .internal_events_loop
+            .order_book_snapshot(market_id)
+            .with_context(|| format!("There is no order book for {market_id}"))?;
+
+        Ok(BookConsistencyReport::new(
+            exchange_account_id,
+            currency_pair,
+            time_manager::now(),
+            &local_snapshot,
+            &rest_snapshot,
+            depth,
+        ))
+    }
+
+    /// Markets of all exchange accounts matching fuzzy currency pair like "btc-usdt" or "XBTUSD"
+    pub fn search_symbols(&self, query: &str) -> Result<Vec<SymbolMatch>> {
+        let query = SymbolQuery::new(query)?;
+        let mut exchanges = self
+            .exchanges
+            .iter()
+            .map(|x| x.value().clone())
+            .collect::<Vec<_>>();
+        exchanges.sort_by_key(|x| x.exchange_account_id.to_string());
+
+        Ok(exchanges
+            .iter()
+            .flat_map(|exchange| search_symbols(exchange, &query))
+            .collect())
+    }
+
+    pub(crate) fn find_market(
+        &self,
+        exchange_account_id: &str,
+        currency_pair: &str,
+    ) -> Result<(Arc<Exchange>, CurrencyPair)> {
+        let exchange_account_id = exchange_account_id
+            .parse::<ExchangeAccountId>()
+            .map_err(|err| anyhow!("Invalid exchange account id {exchange_account_id}: {err:?}"))?;
+        let exchange = self
+            .exchanges
+            .get(&exchange_account_id)
+            .with_context(|| format!("Exchange {exchange_account_id} is not found"))?
+            .clone();
+
+        let currency_pair = exchange
+            .symbols
+            .iter()
+            .map(|x| *x.key())
+            .find(|x| x.as_str().eq_ignore_ascii_case(currency_pair))
+            .with_context(|| {
+                format!("Currency pair {currency_pair} is not found on {exchange_account_id}")
+            })?;
+
+        Ok((exchange, currency_pair))
+    }
+
+    pub fn timeouts(&self) -> Vec<RequestsTimeoutState> {
+        self.timeout_manager.state()
+    }
+
+    pub fn tasks(&self) -> Vec<SpawnedFutureInfo> {
+        spawned_futures()
+    }
+}
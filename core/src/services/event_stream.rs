@@ -0,0 +1,557 @@
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::{EventStreamSettings, EventStreamSink};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Method, Request};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use mmb_domain::events::{BalanceUpdateEvent, ExchangeEvent, TradeId};
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::event::{OrderEvent, OrderEventType};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, IntentId, OrderFillRole, OrderMetadata, OrderSide,
+    OrderSnapshot, OrderStatus, OrderType, Price,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, oneshot};
+
+/// Version of payloads, it's increased on incompatible changes of message structures
+pub const STREAM_SCHEMA_VERSION: u32 = 1;
+/// NATS server closes connection if client doesn't answer its pings
+const KEEP_ALIVE_PERIOD: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FillMessage {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub client_order_id: ClientOrderId,
+    pub exchange_order_id: Option<ExchangeOrderId>,
+    pub trade_id: Option<TradeId>,
+    pub side: OrderSide,
+    pub role: OrderFillRole,
+    pub price: Price,
+    pub amount: Amount,
+    pub commission_currency_code: CurrencyCode,
+    pub commission_amount: Decimal,
+    pub strategy_name: String,
+    pub intent_id: Option<IntentId>,
+    pub metadata: OrderMetadata,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderMessage {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub client_order_id: ClientOrderId,
+    pub exchange_order_id: Option<ExchangeOrderId>,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub status: OrderStatus,
+    pub price: Option<Price>,
+    pub amount: Amount,
+    pub filled_amount: Amount,
+    pub strategy_name: String,
+    pub intent_id: Option<IntentId>,
+    pub metadata: OrderMetadata,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrencyBalance {
+    pub currency_code: CurrencyCode,
+    pub balance: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceMessage {
+    pub exchange_account_id: ExchangeAccountId,
+    pub balances: Vec<CurrencyBalance>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum StreamPayload {
+    Fill(FillMessage),
+    Order(OrderMessage),
+    Balance(BalanceMessage),
+}
+
+impl StreamPayload {
+    fn topic_suffix(&self) -> &'static str {
+        match self {
+            StreamPayload::Fill(_) => "fills",
+            StreamPayload::Order(_) => "orders",
+            StreamPayload::Balance(_) => "balances",
+        }
+    }
+
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        match self {
+            StreamPayload::Fill(x) => x.exchange_account_id,
+            StreamPayload::Order(x) => x.exchange_account_id,
+            StreamPayload::Balance(x) => x.exchange_account_id,
+        }
+    }
+}
+
+/// Message published to external topic
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamMessage {
+    pub schema_version: u32,
+    pub time: DateTime,
+    #[serde(flatten)]
+    pub payload: StreamPayload,
+}
+
+impl StreamMessage {
+    fn new(time: DateTime, payload: StreamPayload) -> Self {
+        StreamMessage {
+            schema_version: STREAM_SCHEMA_VERSION,
+            time,
+            payload,
+        }
+    }
+}
+
+fn order_message(order: &OrderSnapshot) -> OrderMessage {
+    OrderMessage {
+        exchange_account_id: order.header.exchange_account_id,
+        currency_pair: order.header.currency_pair,
+        client_order_id: order.header.client_order_id.clone(),
+        exchange_order_id: order.props.exchange_order_id.clone(),
+        side: order.header.side,
+        order_type: order.header.order_type,
+        status: order.props.status,
+        price: order.header.source_price,
+        amount: order.header.amount,
+        filled_amount: order.fills.filled_amount,
+        strategy_name: order.header.strategy_name.clone(),
+        intent_id: order.header.intent_id,
+        metadata: order.header.metadata.clone(),
+    }
+}
+
+/// Every fill raises `OrderFilled` event, so only the last fill of order is new
+fn fill_message(order: &OrderSnapshot) -> Option<FillMessage> {
+    let fill = order.fills.fills.last()?;
+    Some(FillMessage {
+        exchange_account_id: order.header.exchange_account_id,
+        currency_pair: order.header.currency_pair,
+        client_order_id: order.header.client_order_id.clone(),
+        exchange_order_id: order.props.exchange_order_id.clone(),
+        trade_id: fill.trade_id().cloned(),
+        side: order.header.side,
+        role: fill.role(),
+        price: fill.price(),
+        amount: fill.amount(),
+        commission_currency_code: fill.commission_currency_code(),
+        commission_amount: fill.commission_amount(),
+        strategy_name: order.header.strategy_name.clone(),
+        intent_id: fill.intent_id().or(order.header.intent_id),
+        metadata: order.header.metadata.clone(),
+    })
+}
+
+fn balance_message(event: &BalanceUpdateEvent) -> BalanceMessage {
+    BalanceMessage {
+        exchange_account_id: event.exchange_account_id,
+        balances: event
+            .balances_and_positions
+            .balances
+            .iter()
+            .map(|x| CurrencyBalance {
+                currency_code: x.currency_code,
+                balance: x.balance,
+            })
+            .collect(),
+    }
+}
+
+/// Normalized messages of engine event, events which aren't streamed give no messages
+fn to_messages(event: &ExchangeEvent, now: DateTime) -> Vec<StreamMessage> {
+    let payloads = match event {
+        ExchangeEvent::OrderEvent(OrderEvent { order, event_type }) => match event_type {
+            OrderEventType::OrderFilled { cloned_order } => {
+                let mut payloads = vec![StreamPayload::Order(order_message(cloned_order))];
+                payloads.extend(fill_message(cloned_order).map(StreamPayload::Fill));
+                payloads
+            }
+            OrderEventType::OrderCompleted { cloned_order } => {
+                vec![StreamPayload::Order(order_message(cloned_order))]
+            }
+            _ => vec![StreamPayload::Order(order_message(&order.deep_clone()))],
+        },
+        ExchangeEvent::BalanceUpdate(balance_update) => {
+            vec![StreamPayload::Balance(balance_message(balance_update))]
+        }
+        _ => vec![],
+    };
+
+    payloads
+        .into_iter()
+        .map(|payload| StreamMessage::new(now, payload))
+        .collect()
+}
+
+#[async_trait]
+trait StreamPublisher: Send {
+    /// Messages with the same key keep their order in partitioned topics
+    async fn publish(&mut self, topic: &str, key: &str, message: &serde_json::Value) -> Result<()>;
+
+    async fn keep_alive(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Kafka producer through Confluent REST Proxy, so no native Kafka client is needed
+struct KafkaRestPublisher {
+    url: String,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl KafkaRestPublisher {
+    fn new(url: &str) -> Self {
+        let https = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        KafkaRestPublisher {
+            url: url.trim_end_matches('/').to_owned(),
+            client: Client::builder().build(https),
+        }
+    }
+}
+
+fn kafka_rest_body(key: &str, message: &serde_json::Value) -> String {
+    serde_json::json!({ "records": [{ "key": key, "value": message }] }).to_string()
+}
+
+#[async_trait]
+impl StreamPublisher for KafkaRestPublisher {
+    async fn publish(&mut self, topic: &str, key: &str, message: &serde_json::Value) -> Result<()> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{}/topics/{topic}", self.url))
+            .header("Content-Type", "application/vnd.kafka.json.v2+json")
+            .body(Body::from(kafka_rest_body(key, message)))?;
+
+        let response = self
+            .client
+            .request(request)
+            .await
+            .context("Unable to send request to Kafka REST Proxy")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            bail!(
+                "Kafka REST Proxy responded with {status}: {}",
+                String::from_utf8_lossy(&body)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Minimal NATS connection which is enough for publishing without acknowledgements
+struct NatsConnection {
+    stream: TcpStream,
+    /// Incomplete line of server messages
+    pending: String,
+}
+
+impl NatsConnection {
+    async fn connect(address: &str) -> Result<Self> {
+        let mut stream = TcpStream::connect(address)
+            .await
+            .with_context(|| format!("Unable to connect to NATS {address}"))?;
+        stream
+            .write_all(b"CONNECT {\"verbose\":false,\"pedantic\":false,\"name\":\"mmb\"}\r\n")
+            .await
+            .context("Unable to send CONNECT to NATS")?;
+
+        Ok(NatsConnection {
+            stream,
+            pending: String::new(),
+        })
+    }
+
+    /// Answer pings of server and fail on its errors without waiting for new data
+    async fn handle_server_messages(&mut self) -> Result<()> {
+        let mut buffer = [0u8; 4096];
+        loop {
+            match self.stream.try_read(&mut buffer) {
+                Ok(0) => bail!("NATS connection is closed"),
+                Ok(count) => self
+                    .pending
+                    .push_str(&String::from_utf8_lossy(&buffer[..count])),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err).context("Unable to read from NATS"),
+            }
+        }
+
+        while let Some(end) = self.pending.find("\r\n") {
+            let line: String = self.pending.drain(..end + 2).collect();
+            if let Some(reply) = parse_server_line(&line)? {
+                self.stream.write_all(reply).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns reply which server expects
+fn parse_server_line(line: &str) -> Result<Option<&'static [u8]>> {
+    let line = line.trim_end();
+    if line == "PING" {
+        return Ok(Some(b"PONG\r\n"));
+    }
+    if let Some(error) = line.strip_prefix("-ERR") {
+        bail!("NATS error:{error}");
+    }
+
+    // INFO, +OK and PONG need no reply
+    Ok(None)
+}
+
+fn encode_nats_publish(subject: &str, payload: &str) -> Vec<u8> {
+    format!("PUB {subject} {}\r\n{payload}\r\n", payload.len()).into_bytes()
+}
+
+struct NatsPublisher {
+    address: String,
+    /// Connection is established again after failure
+    connection: Option<NatsConnection>,
+}
+
+impl NatsPublisher {
+    async fn connection(&mut self) -> Result<&mut NatsConnection> {
+        if self.connection.is_none() {
+            self.connection = Some(NatsConnection::connect(&self.address).await?);
+        }
+
+        Ok(self.connection.as_mut().expect("connection is set above"))
+    }
+
+    async fn send(&mut self, data: Option<&[u8]>) -> Result<()> {
+        let connection = self.connection().await?;
+        let result: Result<()> = async {
+            connection.handle_server_messages().await?;
+            if let Some(data) = data {
+                connection.stream.write_all(data).await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        if result.is_err() {
+            self.connection = None;
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl StreamPublisher for NatsPublisher {
+    async fn publish(
+        &mut self,
+        topic: &str,
+        _key: &str,
+        message: &serde_json::Value,
+    ) -> Result<()> {
+        let data = encode_nats_publish(topic, &message.to_string());
+        self.send(Some(&data)).await
+    }
+
+    async fn keep_alive(&mut self) -> Result<()> {
+        self.send(None).await
+    }
+}
+
+/// Publishes normalized fill, order and balance events to external Kafka or NATS topics,
+/// so downstream systems can consume activity of engine without access to its database.
+/// Message which failed to be published is retried once with new connection and dropped after
+pub struct EventStreamService {
+    settings: EventStreamSettings,
+    work_finished_receiver: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+}
+
+impl EventStreamService {
+    pub fn new(settings: EventStreamSettings) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            work_finished_receiver: Default::default(),
+        })
+    }
+
+    pub async fn start(
+        self: Arc<Self>,
+        mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let (work_finished_sender, receiver) = oneshot::channel();
+        *self.work_finished_receiver.lock() = Some(receiver);
+
+        let mut publisher: Box<dyn StreamPublisher> = match &self.settings.sink {
+            EventStreamSink::KafkaRest { url } => Box::new(KafkaRestPublisher::new(url)),
+            EventStreamSink::Nats { address } => Box::new(NatsPublisher {
+                address: address.clone(),
+                connection: None,
+            }),
+        };
+        let mut keep_alive_timer = tokio::time::interval(KEEP_ALIVE_PERIOD);
+
+        loop {
+            let event = tokio::select! {
+                event_res = events_receiver.recv() => match event_res {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("EventStreamService skipped {skipped} events because of slow publishing");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => {
+                        let _ = work_finished_sender.send(Ok(()));
+                        return Ok(());
+                    }
+                },
+                _ = keep_alive_timer.tick() => {
+                    if let Err(err) = publisher.keep_alive().await {
+                        log::warn!("Connection of event stream isn't alive: {err:?}");
+                    }
+                    continue;
+                }
+                _ = cancellation_token.when_cancelled() => {
+                    let _ = work_finished_sender.send(Ok(()));
+                    return Ok(());
+                }
+            };
+
+            for message in to_messages(&event, time_manager::now()) {
+                self.publish(publisher.as_mut(), &message).await;
+            }
+        }
+    }
+
+    async fn publish(&self, publisher: &mut dyn StreamPublisher, message: &StreamMessage) {
+        let topic = format!(
+            "{}.{}",
+            self.settings.topic_prefix,
+            message.payload.topic_suffix()
+        );
+        let key = message.payload.exchange_account_id().to_string();
+        let value = match serde_json::to_value(message) {
+            Ok(value) => value,
+            Err(err) => {
+                log::error!("Unable to serialize stream message {message:?}: {err:?}");
+                return;
+            }
+        };
+
+        if let Err(err) = publisher.publish(&topic, &key, &value).await {
+            log::warn!("Failed to publish message to {topic}, retrying: {err:?}");
+            if let Err(err) = publisher.publish(&topic, &key, &value).await {
+                log::error!("Message {value} isn't published to {topic}: {err:?}");
+            }
+        }
+    }
+}
+
+impl Service for EventStreamService {
+    fn name(&self) -> &str {
+        "EventStreamService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>> {
+        let work_finished_receiver = self.work_finished_receiver.lock().take();
+        if work_finished_receiver.is_none() {
+            log::warn!("'work_finished_receiver' wasn't created when started graceful shutdown in EventStreamService");
+        }
+
+        work_finished_receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::events::{ExchangeBalance, ExchangeBalancesAndPositions};
+    use mmb_domain::order::snapshot::OrderOptions;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn balance_update_is_streamed_with_schema_version() {
+        let event = ExchangeEvent::BalanceUpdate(BalanceUpdateEvent {
+            exchange_account_id: ExchangeAccountId::new("Binance", 0),
+            balances_and_positions: ExchangeBalancesAndPositions {
+                balances: vec![ExchangeBalance {
+                    currency_code: "btc".into(),
+                    balance: dec!(1.5),
+                }],
+                positions: None,
+            },
+        });
+
+        let messages = to_messages(&event, time_manager::now());
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].payload.topic_suffix(), "balances");
+
+        let value = serde_json::to_value(&messages[0]).expect("serialized message");
+        assert_eq!(value["schema_version"], STREAM_SCHEMA_VERSION);
+        assert_eq!(value["type"], "balance");
+        assert_eq!(value["data"]["balances"][0]["balance"], "1.5");
+    }
+
+    #[test]
+    fn order_metadata_is_streamed() {
+        let mut order = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(dec!(100)),
+            None,
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            dec!(1),
+            OrderSide::Buy,
+            None,
+            "test",
+        );
+        let _ = order
+            .header
+            .metadata
+            .insert("model_version".to_owned(), serde_json::json!("v2"));
+
+        let value = serde_json::to_value(order_message(&order)).expect("serialized message");
+        assert_eq!(value["metadata"]["model_version"], "v2");
+    }
+
+    #[test]
+    fn nats_protocol() {
+        assert_eq!(
+            encode_nats_publish("mmb.fills", "{\"a\":1}"),
+            b"PUB mmb.fills 7\r\n{\"a\":1}\r\n".to_vec()
+        );
+        assert_eq!(
+            parse_server_line("PING\r\n").expect("ping"),
+            Some(&b"PONG\r\n"[..])
+        );
+        assert_eq!(parse_server_line("+OK\r\n").expect("ok"), None);
+        assert!(parse_server_line("-ERR 'Authorization Violation'\r\n").is_err());
+    }
+
+    #[test]
+    fn kafka_rest_body_contains_keyed_record() {
+        let body = kafka_rest_body("Binance_0", &serde_json::json!({ "a": 1 }));
+        assert_eq!(body, r#"{"records":[{"key":"Binance_0","value":{"a":1}}]}"#);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::FillReconciliationSettings;
+use dashmap::DashMap;
+use futures::future::join_all;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::cancellation_token::CancellationToken;
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+
+/// Periodically compares account trades received via REST with fills of orders in orders pools
+/// and handles trades missed over websocket as fills, so positions and balances don't drift
+pub struct FillReconciliationService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    settings: FillReconciliationSettings,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for FillReconciliationService {
+    fn name(&self) -> &str {
+        "FillReconciliationService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl FillReconciliationService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        settings: FillReconciliationSettings,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        Self {
+            exchanges,
+            settings,
+            cancellation_token,
+        }
+    }
+
+    pub async fn reconcile_fills(self: Arc<Self>) {
+        let from = time_manager::now() - self.settings.depth();
+
+        let exchanges = self
+            .exchanges
+            .iter()
+            .map(|x| x.value().clone())
+            .collect::<Vec<_>>();
+
+        let cancellation_token = &self.cancellation_token;
+        let reconciliations = exchanges.iter().map(|exchange| async move {
+            let result = exchange
+                .reconcile_fills(from, cancellation_token.create_linked_token())
+                .await;
+
+            match result {
+                Ok(0) => log::trace!("No missed fills on {}", exchange.exchange_account_id),
+                Ok(count) => log::warn!(
+                    "{count} missed fills on {} are reconciled",
+                    exchange.exchange_account_id
+                ),
+                Err(error) => {
+                    if !cancellation_token.is_cancellation_requested() {
+                        log::error!(
+                            "Failed to reconcile fills on {}: {error:?}",
+                            exchange.exchange_account_id
+                        );
+                    }
+                }
+            }
+        });
+
+        join_all(reconciliations).await;
+    }
+}
@@ -0,0 +1,246 @@
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::HeartbeatSettings;
+use dashmap::DashMap;
+use mmb_domain::events::{ExchangeEvent, HeartbeatEvent, SubsystemLiveness};
+use mmb_domain::market::{ExchangeAccountId, MarketAccountId};
+use mmb_utils::send_expected::SendExpectedByRef;
+use mmb_utils::DateTime;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::sync::oneshot::Receiver;
+
+/// Market and strategies of orders created by subsystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrdersScope {
+    pub market_account_id: MarketAccountId,
+    pub strategy_names: HashSet<String>,
+}
+
+/// Last activity time of subsystems which process events in loops (events loop, disposition executor, etc.)
+#[derive(Default)]
+pub struct LivenessRegistry {
+    last_activity_times: DashMap<String, DateTime>,
+    /// Orders of subsystems which create them, so only orders of stalled subsystem can be cancelled
+    orders_scopes: DashMap<String, OrdersScope>,
+}
+
+impl LivenessRegistry {
+    pub fn register_activity(&self, subsystem: &str) {
+        let now = time_manager::now();
+        match self.last_activity_times.get_mut(subsystem) {
+            Some(mut last_activity_time) => *last_activity_time = now,
+            None => {
+                let _ = self.last_activity_times.insert(subsystem.to_owned(), now);
+            }
+        }
+    }
+
+    pub fn last_activity_time(&self, subsystem: &str) -> Option<DateTime> {
+        self.last_activity_times.get(subsystem).map(|x| *x)
+    }
+
+    pub fn register_orders_scope(
+        &self,
+        subsystem: &str,
+        market_account_id: MarketAccountId,
+        strategy_name: &str,
+    ) {
+        if let Some(scope) = self.orders_scopes.get(subsystem) {
+            if scope.strategy_names.contains(strategy_name) {
+                return;
+            }
+        }
+
+        let _ = self
+            .orders_scopes
+            .entry(subsystem.to_owned())
+            .or_insert_with(|| OrdersScope {
+                market_account_id,
+                strategy_names: HashSet::new(),
+            })
+            .strategy_names
+            .insert(strategy_name.to_owned());
+    }
+
+    pub fn orders_scopes(&self) -> Vec<(String, OrdersScope)> {
+        self.orders_scopes
+            .iter()
+            .map(|x| (x.key().clone(), x.value().clone()))
+            .collect()
+    }
+}
+
+/// Periodically sends `HeartbeatEvent` with liveness of subsystems to the events channel.
+/// Subsystems listening the events channel register activity on receiving heartbeat too,
+/// so stalled subsystem is detected even if there is no market activity
+pub struct HeartbeatService {
+    events_sender: broadcast::Sender<ExchangeEvent>,
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    event_recorder: Arc<EventRecorder>,
+    liveness_registry: Arc<LivenessRegistry>,
+    settings: HeartbeatSettings,
+}
+
+impl Service for HeartbeatService {
+    fn name(&self) -> &str {
+        "HeartbeatService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl HeartbeatService {
+    pub fn new(
+        events_sender: broadcast::Sender<ExchangeEvent>,
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        event_recorder: Arc<EventRecorder>,
+        liveness_registry: Arc<LivenessRegistry>,
+        settings: HeartbeatSettings,
+    ) -> Self {
+        Self {
+            events_sender,
+            exchanges,
+            event_recorder,
+            liveness_registry,
+            settings,
+        }
+    }
+
+    pub async fn send_heartbeat(self: Arc<Self>) {
+        let now = time_manager::now();
+        let deadline = now
+            - chrono::Duration::from_std(self.settings.stall_timeout())
+                .expect("Unable to convert stall_timeout of heartbeat settings");
+
+        let mut subsystems = self
+            .liveness_registry
+            .last_activity_times
+            .iter()
+            .map(|x| subsystem_liveness(x.key().clone(), Some(*x.value()), deadline))
+            .collect::<Vec<_>>();
+
+        subsystems.push(SubsystemLiveness {
+            name: "event_recorder".to_owned(),
+            is_alive: !self.event_recorder.is_stalled(),
+            last_activity_time: None,
+        });
+
+        let outbox_backlog = self.event_recorder.outbox_backlog();
+        if outbox_backlog != Default::default() {
+            log::warn!("Event recorder has postponed events backlog: {outbox_backlog:?}");
+        }
+
+        for exchange in self.exchanges.iter() {
+            if !exchange.is_websocket_enabled() {
+                continue;
+            }
+
+            subsystems.push(websocket_liveness(
+                format!("websocket_{}", exchange.exchange_account_id),
+                exchange.is_websocket_connected(),
+                exchange.supports_websocket_ping_pong(),
+                exchange.last_websocket_frame_time(),
+                deadline,
+            ));
+        }
+
+        subsystems.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let event = HeartbeatEvent {
+            time: now,
+            subsystems,
+        };
+
+        for stalled in event.stalled_subsystems() {
+            log::warn!(
+                "Subsystem {} is stalled, last activity time: {:?}",
+                stalled.name,
+                stalled.last_activity_time
+            );
+        }
+
+        self.events_sender
+            .send_expected(ExchangeEvent::Heartbeat(event));
+    }
+}
+
+fn subsystem_liveness(
+    name: String,
+    last_activity_time: Option<DateTime>,
+    deadline: DateTime,
+) -> SubsystemLiveness {
+    SubsystemLiveness {
+        name,
+        is_alive: last_activity_time.map_or(false, |x| x >= deadline),
+        last_activity_time,
+    }
+}
+
+/// Websocket can be quiet if there is no market activity, so it is alive while connected.
+/// If exchange answers pings, frames (at least pongs) should be received before deadline too
+fn websocket_liveness(
+    name: String,
+    is_connected: bool,
+    supports_ping_pong: bool,
+    last_frame_time: Option<DateTime>,
+    deadline: DateTime,
+) -> SubsystemLiveness {
+    let mut liveness = subsystem_liveness(name, last_frame_time, deadline);
+    liveness.is_alive = is_connected && (!supports_ping_pong || liveness.is_alive);
+    liveness
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn subsystem_without_activity_after_deadline_is_stalled() {
+        let now = Utc::now();
+        let deadline = now - Duration::seconds(30);
+
+        let alive = subsystem_liveness("a".to_owned(), Some(now), deadline);
+        assert!(alive.is_alive);
+
+        let stalled =
+            subsystem_liveness("b".to_owned(), Some(now - Duration::minutes(1)), deadline);
+        assert!(!stalled.is_alive);
+
+        let never_active = subsystem_liveness("c".to_owned(), None, deadline);
+        assert!(!never_active.is_alive);
+    }
+
+    #[test]
+    fn websocket_liveness_is_based_on_connection_and_pongs() {
+        let now = Utc::now();
+        let deadline = now - Duration::seconds(30);
+        let stale = Some(now - Duration::minutes(1));
+        let liveness = |is_connected, supports_ping_pong, last_frame_time| {
+            websocket_liveness(
+                "ws".to_owned(),
+                is_connected,
+                supports_ping_pong,
+                last_frame_time,
+                deadline,
+            )
+            .is_alive
+        };
+
+        assert!(liveness(true, true, Some(now)));
+        assert!(!liveness(true, true, stale));
+        assert!(!liveness(true, true, None));
+        assert!(!liveness(false, true, Some(now)));
+
+        // without pongs quiet connection is alive until it is dropped
+        assert!(liveness(true, false, stale));
+        assert!(liveness(true, false, None));
+        assert!(!liveness(false, false, Some(now)));
+    }
+}
@@ -0,0 +1,652 @@
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::InventoryTransferSettings;
+use anyhow::{bail, ensure, Context, Result};
+use dashmap::DashMap;
+use mmb_database::impl_event;
+use mmb_domain::decimal_serialization::decimal;
+use mmb_domain::market::{CurrencyCode, ExchangeAccountId};
+use mmb_domain::order::snapshot::Amount;
+use mmb_domain::transfer::{ExternalTransferStatus, WithdrawalRequest};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+use tokio::time::{sleep, Instant};
+
+pub type TransferId = u64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferRequest {
+    pub from_exchange_account_id: ExchangeAccountId,
+    pub to_exchange_account_id: ExchangeAccountId,
+    pub currency_code: CurrencyCode,
+    #[serde(with = "decimal")]
+    pub amount: Amount,
+    /// Network (chain) used for moving currency, e.g. "ERC20"
+    pub network: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state")]
+pub enum TransferState {
+    /// Waiting for explicit confirmation of operator
+    AwaitingConfirmation,
+    /// Checking networks availability and requesting withdrawal on source exchange
+    Withdrawing,
+    /// Waiting till withdrawal is broadcasted to the network
+    WithdrawalPending {
+        withdrawal_id: String,
+    },
+    /// Waiting till deposit is credited on target exchange
+    DepositPending {
+        withdrawal_id: String,
+        tx_id: String,
+    },
+    Completed {
+        withdrawal_id: String,
+        tx_id: String,
+    },
+    Cancelled {
+        reason: String,
+    },
+    /// If transfer failed after withdrawal was requested, funds can be in flight and need manual handling
+    Failed {
+        reason: String,
+    },
+}
+
+/// Supervised transfer of inventory between exchanges
+#[derive(Debug, Clone, Serialize)]
+pub struct InventoryTransfer {
+    pub id: TransferId,
+    pub request: TransferRequest,
+    pub state: TransferState,
+    pub creation_time: DateTime,
+    pub update_time: DateTime,
+}
+
+impl_event!(InventoryTransfer, "inventory_transfers");
+
+impl InventoryTransfer {
+    fn set_state(&mut self, state: TransferState, now: DateTime) {
+        self.state = state;
+        self.update_time = now;
+    }
+
+    fn confirm(&mut self, now: DateTime) -> Result<()> {
+        ensure!(
+            self.state == TransferState::AwaitingConfirmation,
+            "Transfer {} can't be confirmed in state {:?}",
+            self.id,
+            self.state
+        );
+
+        self.set_state(TransferState::Withdrawing, now);
+        Ok(())
+    }
+
+    fn cancel(&mut self, reason: String, now: DateTime) -> Result<()> {
+        ensure!(
+            self.state == TransferState::AwaitingConfirmation,
+            "Transfer {} can't be cancelled in state {:?}",
+            self.id,
+            self.state
+        );
+
+        self.set_state(TransferState::Cancelled { reason }, now);
+        Ok(())
+    }
+
+    fn withdrawal_requested(&mut self, withdrawal_id: String, now: DateTime) -> Result<()> {
+        ensure!(
+            self.state == TransferState::Withdrawing,
+            "Withdrawal of transfer {} can't be requested in state {:?}",
+            self.id,
+            self.state
+        );
+
+        self.set_state(TransferState::WithdrawalPending { withdrawal_id }, now);
+        Ok(())
+    }
+
+    fn withdrawal_broadcasted(&mut self, tx_id: String, now: DateTime) -> Result<()> {
+        let withdrawal_id = match &self.state {
+            TransferState::WithdrawalPending { withdrawal_id } => withdrawal_id.clone(),
+            state => bail!(
+                "Withdrawal of transfer {} can't be broadcasted in state {state:?}",
+                self.id
+            ),
+        };
+
+        self.set_state(
+            TransferState::DepositPending {
+                withdrawal_id,
+                tx_id,
+            },
+            now,
+        );
+        Ok(())
+    }
+
+    fn deposit_credited(&mut self, now: DateTime) -> Result<()> {
+        let (withdrawal_id, tx_id) = match &self.state {
+            TransferState::DepositPending {
+                withdrawal_id,
+                tx_id,
+            } => (withdrawal_id.clone(), tx_id.clone()),
+            state => bail!(
+                "Deposit of transfer {} can't be credited in state {state:?}",
+                self.id
+            ),
+        };
+
+        self.set_state(
+            TransferState::Completed {
+                withdrawal_id,
+                tx_id,
+            },
+            now,
+        );
+        Ok(())
+    }
+}
+
+/// Moves inventory between exchanges: withdraws currency on source exchange, monitors it in the network
+/// and confirms deposit on target exchange. Transfer is started only after explicit confirmation of operator
+pub struct InventoryTransferService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    event_recorder: Arc<EventRecorder>,
+    settings: InventoryTransferSettings,
+    transfers: Mutex<BTreeMap<TransferId, InventoryTransfer>>,
+    last_transfer_id: AtomicU64,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for InventoryTransferService {
+    fn name(&self) -> &str {
+        "InventoryTransferService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl InventoryTransferService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        event_recorder: Arc<EventRecorder>,
+        settings: InventoryTransferSettings,
+        cancellation_token: CancellationToken,
+    ) -> Arc<Self> {
+        // ids are started from current time for keeping them unique between engine restarts
+        let first_transfer_id = time_manager::now().timestamp_millis() as u64;
+
+        Arc::new(Self {
+            exchanges,
+            event_recorder,
+            settings,
+            transfers: Mutex::new(BTreeMap::new()),
+            last_transfer_id: AtomicU64::new(first_transfer_id),
+            cancellation_token,
+        })
+    }
+
+    pub fn transfers(&self) -> Vec<InventoryTransfer> {
+        self.transfers.lock().values().cloned().collect()
+    }
+
+    /// Register transfer which waits for confirmation of operator
+    pub fn initiate(self: &Arc<Self>, request: TransferRequest) -> Result<TransferId> {
+        ensure!(
+            request.amount > dec!(0),
+            "Amount of transfer should be positive"
+        );
+        ensure!(
+            request.from_exchange_account_id != request.to_exchange_account_id,
+            "Source and target exchanges of transfer should be different"
+        );
+        for exchange_account_id in [
+            request.from_exchange_account_id,
+            request.to_exchange_account_id,
+        ] {
+            ensure!(
+                self.exchanges.contains_key(&exchange_account_id),
+                "Exchange {exchange_account_id} isn't found"
+            );
+        }
+
+        let id = self.last_transfer_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let now = time_manager::now();
+        let transfer = InventoryTransfer {
+            id,
+            request,
+            state: TransferState::AwaitingConfirmation,
+            creation_time: now,
+            update_time: now,
+        };
+
+        log::info!("Transfer {id} is initiated: {:?}", transfer.request);
+        self.save(&transfer);
+        let _ = self.transfers.lock().insert(id, transfer);
+
+        let _ = spawn_future(
+            "Expire inventory transfer confirmation",
+            SpawnFutureFlags::STOP_BY_TOKEN,
+            self.clone().expire_confirmation(id),
+        );
+
+        Ok(id)
+    }
+
+    pub fn confirm(self: &Arc<Self>, id: TransferId) -> Result<()> {
+        self.update(id, InventoryTransfer::confirm)?;
+
+        let _ = spawn_future(
+            "Execute inventory transfer",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            self.clone().execute(id),
+        );
+
+        Ok(())
+    }
+
+    pub fn cancel(&self, id: TransferId) -> Result<()> {
+        self.update(id, |transfer, now| {
+            transfer.cancel("Cancelled by operator".to_owned(), now)
+        })
+    }
+
+    async fn expire_confirmation(self: Arc<Self>, id: TransferId) -> Result<()> {
+        sleep(self.settings.confirmation_timeout()).await;
+
+        // transfer can be already confirmed or cancelled
+        let _ = self.update(id, |transfer, now| {
+            transfer.cancel("Confirmation timeout expired".to_owned(), now)
+        });
+
+        Ok(())
+    }
+
+    async fn execute(self: Arc<Self>, id: TransferId) -> Result<()> {
+        let request = self
+            .transfers
+            .lock()
+            .get(&id)
+            .map(|x| x.request.clone())
+            .with_context(|| format!("Transfer {id} isn't found"))?;
+
+        if let Err(error) = self.run(id, &request).await {
+            log::error!("Transfer {id} failed and needs manual check: {error:?}");
+
+            let reason = format!("{error:#}");
+            self.update(id, |transfer, now| {
+                transfer.set_state(TransferState::Failed { reason }, now);
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn run(&self, id: TransferId, request: &TransferRequest) -> Result<()> {
+        let source = self.exchange(request.from_exchange_account_id)?;
+        let target = self.exchange(request.to_exchange_account_id)?;
+        let currency_code = request.currency_code;
+        let network = request.network.as_str();
+
+        let source_network = supported(
+            source
+                .exchange_client
+                .get_network_status(currency_code, network)
+                .await,
+            source.exchange_account_id,
+            "get network status",
+        )?;
+        ensure!(
+            source_network.is_withdrawal_enabled,
+            "Withdrawal of {currency_code} via {network} is disabled on {}",
+            source.exchange_account_id
+        );
+
+        let target_network = supported(
+            target
+                .exchange_client
+                .get_network_status(currency_code, network)
+                .await,
+            target.exchange_account_id,
+            "get network status",
+        )?;
+        ensure!(
+            target_network.is_deposit_enabled,
+            "Deposit of {currency_code} via {network} is disabled on {}",
+            target.exchange_account_id
+        );
+
+        let address = supported(
+            target
+                .exchange_client
+                .get_deposit_address(currency_code, network)
+                .await,
+            target.exchange_account_id,
+            "get deposit address",
+        )?;
+
+        let withdrawal_request = WithdrawalRequest {
+            currency_code,
+            amount: request.amount,
+            network: request.network.clone(),
+            address,
+        };
+        let withdrawal_id = supported(
+            source.exchange_client.withdraw(&withdrawal_request).await,
+            source.exchange_account_id,
+            "withdraw",
+        )?;
+
+        self.update(id, |transfer, now| {
+            transfer.withdrawal_requested(withdrawal_id.clone(), now)
+        })?;
+
+        let tx_id = self
+            .wait_for_withdrawal(&source, currency_code, &withdrawal_id)
+            .await?;
+
+        self.update(id, |transfer, now| {
+            transfer.withdrawal_broadcasted(tx_id.clone(), now)
+        })?;
+
+        self.wait_for_deposit(&target, currency_code, network, &tx_id)
+            .await?;
+
+        self.update(id, InventoryTransfer::deposit_credited)
+    }
+
+    /// Returns id of transaction in the network
+    async fn wait_for_withdrawal(
+        &self,
+        source: &Exchange,
+        currency_code: CurrencyCode,
+        withdrawal_id: &str,
+    ) -> Result<String> {
+        let deadline = Instant::now() + self.settings.withdrawal_timeout();
+
+        loop {
+            let result = source
+                .exchange_client
+                .get_withdrawal(currency_code, withdrawal_id)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Exchange {} doesn't support getting withdrawal",
+                        source.exchange_account_id
+                    )
+                })?;
+
+            match result {
+                Ok(info) if info.status == ExternalTransferStatus::Failed => {
+                    bail!(
+                        "Withdrawal {withdrawal_id} failed on {}",
+                        source.exchange_account_id
+                    )
+                }
+                Ok(info) => {
+                    if let Some(tx_id) = info.tx_id {
+                        return Ok(tx_id);
+                    }
+                }
+                Err(error) => log::warn!(
+                    "Failed to get withdrawal {withdrawal_id} on {}: {error:?}",
+                    source.exchange_account_id
+                ),
+            }
+
+            self.wait_next_poll(deadline, "Withdrawal").await?;
+        }
+    }
+
+    async fn wait_for_deposit(
+        &self,
+        target: &Exchange,
+        currency_code: CurrencyCode,
+        network: &str,
+        tx_id: &str,
+    ) -> Result<()> {
+        let deadline = Instant::now() + self.settings.deposit_timeout();
+
+        loop {
+            let result = target
+                .exchange_client
+                .get_deposit(currency_code, tx_id)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Exchange {} doesn't support getting deposit",
+                        target.exchange_account_id
+                    )
+                })?;
+
+            match result.map(|x| x.map(|info| info.status)) {
+                Ok(Some(ExternalTransferStatus::Completed)) => return Ok(()),
+                Ok(Some(ExternalTransferStatus::Failed)) => {
+                    bail!("Deposit {tx_id} failed on {}", target.exchange_account_id)
+                }
+                Ok(Some(ExternalTransferStatus::Pending) | None) => {
+                    self.check_deposit_network(target, currency_code, network)
+                        .await
+                }
+                Err(error) => log::warn!(
+                    "Failed to get deposit {tx_id} on {}: {error:?}",
+                    target.exchange_account_id
+                ),
+            }
+
+            self.wait_next_poll(deadline, "Deposit").await?;
+        }
+    }
+
+    /// Deposit can be delayed for long time while network is suspended on exchange, so it is only reported
+    async fn check_deposit_network(
+        &self,
+        target: &Exchange,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) {
+        let status = target
+            .exchange_client
+            .get_network_status(currency_code, network)
+            .await;
+
+        match status {
+            Some(Ok(status)) if !status.is_deposit_enabled => log::warn!(
+                "Deposit of {currency_code} via {network} is suspended on {}, transfer is delayed",
+                target.exchange_account_id
+            ),
+            None | Some(Ok(_)) => {}
+            Some(Err(error)) => log::warn!(
+                "Failed to get status of network {network} on {}: {error:?}",
+                target.exchange_account_id
+            ),
+        }
+    }
+
+    async fn wait_next_poll(&self, deadline: Instant, stage: &str) -> Result<()> {
+        if Instant::now() >= deadline {
+            bail!("{stage} isn't completed in time");
+        }
+
+        tokio::select! {
+            _ = sleep(self.settings.poll_period()) => Ok(()),
+            _ = self.cancellation_token.when_cancelled() => bail!("{stage} tracking is interrupted by engine stopping"),
+        }
+    }
+
+    fn exchange(&self, exchange_account_id: ExchangeAccountId) -> Result<Arc<Exchange>> {
+        self.exchanges
+            .get(&exchange_account_id)
+            .map(|x| x.value().clone())
+            .with_context(|| format!("Exchange {exchange_account_id} isn't found"))
+    }
+
+    fn update(
+        &self,
+        id: TransferId,
+        action: impl FnOnce(&mut InventoryTransfer, DateTime) -> Result<()>,
+    ) -> Result<()> {
+        let mut transfers = self.transfers.lock();
+        let transfer = transfers
+            .get_mut(&id)
+            .with_context(|| format!("Transfer {id} isn't found"))?;
+
+        action(transfer, time_manager::now())?;
+
+        log::info!("Transfer {id} moved to state {:?}", transfer.state);
+        self.save(transfer);
+
+        Ok(())
+    }
+
+    fn save(&self, transfer: &InventoryTransfer) {
+        if let Err(error) = self.event_recorder.save(transfer.clone()) {
+            log::error!(
+                "Failed to save inventory transfer {}: {error:?}",
+                transfer.id
+            );
+        }
+    }
+}
+
+fn supported<T>(
+    result: Option<Result<T>>,
+    exchange_account_id: ExchangeAccountId,
+    operation: &str,
+) -> Result<T> {
+    result
+        .with_context(|| format!("Exchange {exchange_account_id} doesn't support {operation}"))?
+        .with_context(|| format!("Failed to {operation} on {exchange_account_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::decimal_serialization::DecimalSerialization;
+
+    fn transfer() -> InventoryTransfer {
+        InventoryTransfer {
+            id: 1,
+            request: TransferRequest {
+                from_exchange_account_id: ExchangeAccountId::new("Binance", 0),
+                to_exchange_account_id: ExchangeAccountId::new("Bitmex", 0),
+                currency_code: "usdt".into(),
+                amount: dec!(100),
+                network: "ERC20".to_owned(),
+            },
+            state: TransferState::AwaitingConfirmation,
+            creation_time: Utc::now(),
+            update_time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn transfer_is_started_only_after_confirmation() {
+        let mut transfer = transfer();
+        transfer.confirm(Utc::now()).expect("in test");
+        assert_eq!(transfer.state, TransferState::Withdrawing);
+
+        // confirmed transfer can't be confirmed again or cancelled
+        assert!(transfer.confirm(Utc::now()).is_err());
+        assert!(transfer.cancel("test".to_owned(), Utc::now()).is_err());
+        assert_eq!(transfer.state, TransferState::Withdrawing);
+    }
+
+    #[test]
+    fn cancelled_transfer_can_not_be_confirmed() {
+        let mut transfer = transfer();
+        transfer
+            .cancel("test".to_owned(), Utc::now())
+            .expect("in test");
+
+        assert!(transfer.confirm(Utc::now()).is_err());
+        assert_eq!(
+            transfer.state,
+            TransferState::Cancelled {
+                reason: "test".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn transfer_is_completed_after_withdrawal_and_deposit_confirmation() {
+        let mut transfer = transfer();
+
+        // withdrawal can't be requested till transfer is confirmed
+        assert!(transfer
+            .withdrawal_requested("1".to_owned(), Utc::now())
+            .is_err());
+
+        transfer.confirm(Utc::now()).expect("in test");
+        transfer
+            .withdrawal_requested("1".to_owned(), Utc::now())
+            .expect("in test");
+        assert_eq!(
+            transfer.state,
+            TransferState::WithdrawalPending {
+                withdrawal_id: "1".to_owned()
+            }
+        );
+
+        // deposit can't be credited till withdrawal is broadcasted to the network
+        assert!(transfer.deposit_credited(Utc::now()).is_err());
+
+        transfer
+            .withdrawal_broadcasted("0xabc".to_owned(), Utc::now())
+            .expect("in test");
+        assert_eq!(
+            transfer.state,
+            TransferState::DepositPending {
+                withdrawal_id: "1".to_owned(),
+                tx_id: "0xabc".to_owned(),
+            }
+        );
+
+        transfer.deposit_credited(Utc::now()).expect("in test");
+        assert_eq!(
+            transfer.state,
+            TransferState::Completed {
+                withdrawal_id: "1".to_owned(),
+                tx_id: "0xabc".to_owned(),
+            }
+        );
+
+        // completed transfer can't be moved back
+        assert!(transfer
+            .withdrawal_broadcasted("0xdef".to_owned(), Utc::now())
+            .is_err());
+        assert!(transfer.deposit_credited(Utc::now()).is_err());
+    }
+
+    #[test]
+    fn amount_is_formatted_in_rpc_response_and_kept_exactly_in_database() {
+        let mut transfer = transfer();
+        transfer.request.amount = dec!(100.123456789);
+        let amount = |json: serde_json::Value| json["request"]["amount"].clone();
+
+        let rpc_json = DecimalSerialization::default()
+            .scope(|| serde_json::to_value(&transfer).expect("in test"));
+        assert_eq!(amount(rpc_json), serde_json::json!("100.12345679"));
+
+        let database_json = serde_json::to_value(&transfer).expect("in test");
+        assert_eq!(amount(database_json), serde_json::json!("100.123456789"));
+    }
+}
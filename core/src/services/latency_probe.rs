@@ -0,0 +1,318 @@
+use crate::connectivity::{measure_round_trip, WebSocketRole};
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+use crate::exchanges::general::request_type::RequestType;
+use crate::exchanges::timeouts::timeout_manager::TimeoutManager;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::LatencyProbeSettings;
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use itertools::Itertools;
+use mmb_database::impl_event;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::oneshot::Receiver;
+use tokio::time::timeout;
+
+/// Round trip times to exchange measured from host of engine.
+/// Measurement is `None` if it's failed or isn't supported by exchange
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySample {
+    pub time: DateTime,
+    pub region: String,
+    pub exchange_account_id: ExchangeAccountId,
+    /// Round trip of REST request of server time
+    pub rest_rtt_ms: Option<u64>,
+    /// Duration of TCP connection, TLS and WebSocket handshakes
+    pub ws_connect_ms: Option<u64>,
+    /// Round trip of ping over WebSocket connection
+    pub ws_rtt_ms: Option<u64>,
+}
+
+impl_event!(LatencySample, "latency_samples");
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RoundTripStats {
+    pub samples_count: usize,
+    pub failures_count: usize,
+    pub min_ms: Option<u64>,
+    pub median_ms: Option<u64>,
+    pub p90_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+    pub last_ms: Option<u64>,
+}
+
+impl RoundTripStats {
+    fn new(measurements: impl Iterator<Item = Option<u64>>) -> Self {
+        let measurements = measurements.collect_vec();
+        let last_ms = measurements.last().cloned().flatten();
+        let sorted = measurements
+            .iter()
+            .flatten()
+            .cloned()
+            .sorted()
+            .collect_vec();
+
+        RoundTripStats {
+            samples_count: measurements.len(),
+            failures_count: measurements.len() - sorted.len(),
+            min_ms: sorted.first().cloned(),
+            median_ms: percentile(&sorted, 50),
+            p90_ms: percentile(&sorted, 90),
+            max_ms: sorted.last().cloned(),
+            last_ms,
+        }
+    }
+
+    /// Last round trip exceeds median of window by more than `degradation_factor`
+    fn is_degraded(&self, degradation_factor: Decimal) -> bool {
+        match (self.last_ms, self.median_ms) {
+            (Some(last_ms), Some(median_ms)) => {
+                Decimal::from(last_ms) > Decimal::from(median_ms) * degradation_factor
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], percent: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = (sorted.len() * percent + 99) / 100;
+    Some(sorted[rank.max(1) - 1])
+}
+
+/// Round trip statistics of exchange account over window of last samples
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyReport {
+    pub exchange_account_id: ExchangeAccountId,
+    pub region: String,
+    pub rest: RoundTripStats,
+    pub ws_connect: RoundTripStats,
+    pub ws_ping: RoundTripStats,
+}
+
+impl LatencyReport {
+    fn new(
+        exchange_account_id: ExchangeAccountId,
+        region: String,
+        samples: &VecDeque<LatencySample>,
+    ) -> Self {
+        LatencyReport {
+            exchange_account_id,
+            region,
+            rest: RoundTripStats::new(samples.iter().map(|x| x.rest_rtt_ms)),
+            ws_connect: RoundTripStats::new(samples.iter().map(|x| x.ws_connect_ms)),
+            ws_ping: RoundTripStats::new(samples.iter().map(|x| x.ws_rtt_ms)),
+        }
+    }
+}
+
+/// Periodically measures REST and WebSocket round trip times to exchanges from the current host,
+/// so samples of engines deployed in different regions can be compared to choose location
+/// for every exchange, and degradations of network are noticed
+pub struct LatencyProbeService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    event_recorder: Arc<EventRecorder>,
+    timeout_manager: Arc<TimeoutManager>,
+    settings: LatencyProbeSettings,
+    samples: Mutex<HashMap<ExchangeAccountId, VecDeque<LatencySample>>>,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for LatencyProbeService {
+    fn name(&self) -> &str {
+        "LatencyProbeService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<Result<()>>> {
+        None
+    }
+}
+
+impl LatencyProbeService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        event_recorder: Arc<EventRecorder>,
+        timeout_manager: Arc<TimeoutManager>,
+        settings: LatencyProbeSettings,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        Self {
+            exchanges,
+            event_recorder,
+            timeout_manager,
+            settings,
+            samples: Mutex::new(HashMap::new()),
+            cancellation_token,
+        }
+    }
+
+    pub async fn probe(&self, exchange_account_id: ExchangeAccountId) {
+        let exchange = match self.exchanges.get(&exchange_account_id) {
+            Some(exchange) => exchange.clone(),
+            None => {
+                log::error!("Exchange {exchange_account_id} for latency probe is not found");
+                return;
+            }
+        };
+
+        let rest_rtt_ms = self
+            .measure_rest_round_trip(&exchange)
+            .await
+            .unwrap_or_else(|err| {
+                log::warn!("Failed to measure REST round trip to {exchange_account_id}: {err:?}");
+                None
+            });
+
+        let (ws_connect_ms, ws_rtt_ms) = match self.measure_websocket_round_trip(&exchange).await {
+            Ok((connect_ms, rtt_ms)) => (Some(connect_ms), Some(rtt_ms)),
+            Err(err) => {
+                log::warn!(
+                    "Failed to measure WebSocket round trip to {exchange_account_id}: {err:?}"
+                );
+                (None, None)
+            }
+        };
+
+        let sample = LatencySample {
+            time: time_manager::now(),
+            region: self.settings.region.clone(),
+            exchange_account_id,
+            rest_rtt_ms,
+            ws_connect_ms,
+            ws_rtt_ms,
+        };
+        log::trace!("Latency sample {sample:?}");
+
+        self.add_sample(sample.clone());
+
+        if let Err(err) = self.event_recorder.save(sample) {
+            log::error!("Failed to save latency sample of {exchange_account_id}: {err:?}");
+        }
+    }
+
+    /// Statistics of round trips over window of last samples of every exchange account
+    pub fn report(&self) -> Vec<LatencyReport> {
+        self.samples
+            .lock()
+            .iter()
+            .map(|(exchange_account_id, samples)| {
+                LatencyReport::new(*exchange_account_id, self.settings.region.clone(), samples)
+            })
+            .sorted_by_key(|x| x.exchange_account_id.to_string())
+            .collect()
+    }
+
+    fn add_sample(&self, sample: LatencySample) {
+        let exchange_account_id = sample.exchange_account_id;
+        let mut samples = self.samples.lock();
+        let samples = samples.entry(exchange_account_id).or_default();
+        samples.push_back(sample);
+        while samples.len() > self.settings.window_size {
+            let _ = samples.pop_front();
+        }
+
+        let report = LatencyReport::new(exchange_account_id, self.settings.region.clone(), samples);
+        let degradation_factor = self.settings.degradation_factor;
+        for (name, stats) in [
+            ("REST", &report.rest),
+            ("WebSocket connect", &report.ws_connect),
+            ("WebSocket ping", &report.ws_ping),
+        ] {
+            if stats.is_degraded(degradation_factor) {
+                log::warn!(
+                    "{name} round trip to {exchange_account_id} is degraded: last {:?} ms, median {:?} ms",
+                    stats.last_ms,
+                    stats.median_ms
+                );
+            }
+        }
+    }
+
+    /// Returns `None` if exchange doesn't support request of server time
+    async fn measure_rest_round_trip(&self, exchange: &Exchange) -> Result<Option<u64>> {
+        self.timeout_manager
+            .reserve_when_available(
+                exchange.exchange_account_id,
+                RequestType::GetServerTime,
+                None,
+                self.cancellation_token.clone(),
+            )
+            .await
+            .into_result()?;
+
+        let started = Instant::now();
+        let response = timeout(
+            self.settings.timeout(),
+            exchange.exchange_client.get_server_time(),
+        )
+        .await
+        .context("REST request timed out")?;
+
+        match response {
+            Some(Ok(_)) => Ok(Some(started.elapsed().as_millis() as u64)),
+            Some(Err(err)) => Err(err),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns durations of connection and ping in milliseconds
+    async fn measure_websocket_round_trip(&self, exchange: &Arc<Exchange>) -> Result<(u64, u64)> {
+        let role = WebSocketRole::Main;
+        let params = exchange.get_websocket_params(role).await?;
+        let round_trip = measure_round_trip(role, &params, self.settings.timeout()).await?;
+
+        Ok((
+            round_trip.connect.as_millis() as u64,
+            round_trip.ping.as_millis() as u64,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn round_trip_stats_skip_failed_measurements() {
+        let stats =
+            RoundTripStats::new([Some(30), None, Some(10), Some(20), Some(100)].into_iter());
+
+        assert_eq!(
+            stats,
+            RoundTripStats {
+                samples_count: 5,
+                failures_count: 1,
+                min_ms: Some(10),
+                median_ms: Some(20),
+                p90_ms: Some(100),
+                max_ms: Some(100),
+                last_ms: Some(100),
+            }
+        );
+        assert!(stats.is_degraded(dec!(3)));
+        assert!(!stats.is_degraded(dec!(5)));
+    }
+
+    #[test]
+    fn round_trip_stats_of_failed_measurements_are_empty() {
+        let stats = RoundTripStats::new([None, None].into_iter());
+
+        assert_eq!(stats.failures_count, 2);
+        assert_eq!(stats.median_ms, None);
+        assert!(!stats.is_degraded(dec!(3)));
+    }
+}
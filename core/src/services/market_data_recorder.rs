@@ -0,0 +1,168 @@
+use crate::database::events::recorder::EventRecorder;
+use crate::lifecycle::trading_engine::Service;
+use crate::services::heartbeat::LivenessRegistry;
+use anyhow::Result;
+use mmb_database::impl_event;
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, oneshot};
+
+/// Order book snapshot or update received from exchange as is
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderBookRecord {
+    pub time: DateTime,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub is_snapshot: bool,
+    pub asks: Vec<(Price, Amount)>,
+    pub bids: Vec<(Price, Amount)>,
+}
+
+impl_event!(OrderBookRecord, "order_book_events");
+
+impl From<&OrderBookEvent> for OrderBookRecord {
+    fn from(event: &OrderBookEvent) -> Self {
+        OrderBookRecord {
+            time: event.creation_time,
+            exchange_account_id: event.exchange_account_id,
+            currency_pair: event.currency_pair,
+            is_snapshot: matches!(event.event_type, EventType::Snapshot),
+            asks: event.data.asks.iter().map(|(&p, &a)| (p, a)).collect(),
+            bids: event.data.bids.iter().map(|(&p, &a)| (p, a)).collect(),
+        }
+    }
+}
+
+/// Saves order book events of all markets to database for building of historical datasets.
+/// Trades are saved by exchanges themselves, so they aren't handled here
+pub struct MarketDataRecorderService {
+    event_recorder: Arc<EventRecorder>,
+    liveness_registry: Arc<LivenessRegistry>,
+    work_finished_receiver: Mutex<Option<oneshot::Receiver<Result<()>>>>,
+}
+
+impl MarketDataRecorderService {
+    pub fn new(
+        event_recorder: Arc<EventRecorder>,
+        liveness_registry: Arc<LivenessRegistry>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            event_recorder,
+            liveness_registry,
+            work_finished_receiver: Default::default(),
+        })
+    }
+
+    pub async fn start(
+        self: Arc<Self>,
+        mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+        cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        let (work_finished_sender, receiver) = oneshot::channel();
+        *self.work_finished_receiver.lock() = Some(receiver);
+
+        loop {
+            let event = tokio::select! {
+                event_res = events_receiver.recv() => match event_res {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        log::warn!("MarketDataRecorderService skipped {skipped} events because of slow saving");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => {
+                        let _ = work_finished_sender.send(Ok(()));
+                        return Ok(());
+                    }
+                },
+                _ = cancellation_token.when_cancelled() => {
+                    let _ = work_finished_sender.send(Ok(()));
+                    return Ok(());
+                }
+            };
+
+            self.liveness_registry.register_activity(self.name());
+
+            if let ExchangeEvent::OrderBookEvent(order_book_event) = &event {
+                self.event_recorder
+                    .save(OrderBookRecord::from(order_book_event))
+                    .expect("Failure save order book event");
+            }
+        }
+    }
+}
+
+impl Service for MarketDataRecorderService {
+    fn name(&self) -> &str {
+        "MarketDataRecorderService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<oneshot::Receiver<Result<()>>> {
+        let work_finished_receiver = self.work_finished_receiver.lock().take();
+        if work_finished_receiver.is_none() {
+            log::warn!("'work_finished_receiver' wasn't created when started graceful shutdown in MarketDataRecorderService");
+        }
+
+        work_finished_receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::misc::time::time_manager;
+    use mmb_domain::order_book::order_book_data::OrderBookData;
+    use mmb_domain::order_book_data;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn order_book_record_from_update() {
+        let event = OrderBookEvent::new(
+            time_manager::now(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            "1".to_owned(),
+            EventType::Update,
+            Arc::new(order_book_data![
+                dec!(101) => dec!(2),
+                dec!(100) => dec!(1),
+                ;
+                dec!(99) => dec!(0),
+            ]),
+        );
+
+        let record = OrderBookRecord::from(&event);
+
+        assert!(!record.is_snapshot);
+        assert_eq!(
+            record.asks,
+            vec![(dec!(100), dec!(1)), (dec!(101), dec!(2))]
+        );
+        assert_eq!(record.bids, vec![(dec!(99), dec!(0))]);
+    }
+
+    #[test]
+    fn order_book_record_from_empty_snapshot() {
+        let event = OrderBookEvent::new(
+            time_manager::now(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            "1".to_owned(),
+            EventType::Snapshot,
+            Arc::new(OrderBookData::default()),
+        );
+
+        let record = OrderBookRecord::from(&event);
+
+        assert!(record.is_snapshot);
+        assert!(record.asks.is_empty());
+        assert!(record.bids.is_empty());
+    }
+}
@@ -1,6 +1,25 @@
+pub mod account_history_import;
+pub mod book_consistency;
 pub mod cleanup_database;
 pub mod cleanup_orders;
+pub mod compliance_export;
+pub mod dead_man_switch;
+pub mod diagnostics;
+pub mod event_stream;
 pub mod exchange_time_latency;
+pub mod fill_reconciliation;
+pub mod heartbeat;
+pub mod inventory_transfer;
+pub mod latency_probe;
 pub mod live_ranges;
+pub mod market_data_recorder;
+pub mod order_expiration;
+pub mod orders_recovery;
 pub(crate) mod market_prices;
+pub mod position_unwind;
+pub mod stuck_orders_watchdog;
+pub mod summary_report;
+pub mod support_bundle;
+pub mod symbol_search;
+pub mod trading_day_rollover;
 pub mod usd_convertion;
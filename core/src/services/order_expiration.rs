@@ -0,0 +1,203 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use dashmap::{DashMap, DashSet};
+use futures::future::join_all;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::order::event::OrderEventType;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderStatus};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+
+/// Cancels orders which aren't finished during their time-to-live (`OrderHeader::expire_after`)
+/// and raises `OrderEventType::Expired` for them after successful cancellation
+pub struct OrderExpirationService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    expiring_orders: DashSet<ClientOrderId>,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for OrderExpirationService {
+    fn name(&self) -> &str {
+        "OrderExpirationService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl OrderExpirationService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        Self {
+            exchanges,
+            expiring_orders: DashSet::new(),
+            cancellation_token,
+        }
+    }
+
+    pub async fn cancel_expired_orders(self: Arc<Self>) {
+        let now = time_manager::now();
+
+        let expired_orders = self
+            .exchanges
+            .iter()
+            .flat_map(|exchange| {
+                exchange
+                    .orders
+                    .not_finished
+                    .iter()
+                    .filter(|x| is_expired(x.value(), now))
+                    .map(|x| (exchange.clone(), x.value().clone()))
+                    .collect::<Vec<_>>()
+            })
+            // skip orders which are still cancelling since previous check
+            .filter(|(_, order)| self.expiring_orders.insert(order.client_order_id()))
+            .collect::<Vec<_>>();
+
+        let cancellations = expired_orders
+            .into_iter()
+            .map(|(exchange, order)| self.clone().cancel_expired_order(exchange, order));
+
+        join_all(cancellations).await;
+    }
+
+    async fn cancel_expired_order(self: Arc<Self>, exchange: Arc<Exchange>, order: OrderRef) {
+        let (client_order_id, exchange_order_id) = order.order_ids();
+        log::info!(
+            "Order {client_order_id} {exchange_order_id:?} on {} is expired, cancelling it",
+            exchange.exchange_account_id
+        );
+
+        let result = exchange
+            .wait_cancel_order(
+                order.clone(),
+                None,
+                true,
+                self.cancellation_token.create_linked_token(),
+            )
+            .await;
+
+        match result {
+            Ok(()) if order.status() == OrderStatus::Canceled => {
+                if let Err(error) =
+                    exchange.add_event_on_order_change(&order, OrderEventType::Expired)
+                {
+                    log::error!(
+                        "Unable to send Expired event for order {client_order_id}: {error:?}"
+                    );
+                }
+            }
+            // order is completely filled before cancellation
+            Ok(()) => {
+                let status = order.status();
+                log::info!("Expired order {client_order_id} {exchange_order_id:?} is finished with status {status:?}");
+            }
+            Err(error) => {
+                if !self.cancellation_token.is_cancellation_requested() {
+                    log::error!("Failed to cancel expired order {client_order_id} {exchange_order_id:?} on {}: {error:?}", exchange.exchange_account_id);
+                }
+            }
+        }
+
+        self.expiring_orders.remove(&client_order_id);
+    }
+}
+
+/// Order is expired if it isn't finished and its time-to-live since creation is over.
+/// Orders which are already cancelling aren't treated as expired
+pub(crate) fn is_expired(order: &OrderRef, now: DateTime) -> bool {
+    order.fn_ref(|x| {
+        let Some(expire_after) = x.header.expire_after else {
+            return false;
+        };
+
+        let Ok(expire_after) = chrono::Duration::from_std(expire_after) else {
+            return false;
+        };
+
+        !x.is_finished()
+            && x.props.status != OrderStatus::Canceling
+            && x.props.init_time + expire_after <= now
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::{OrderHeader, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    fn add_order(
+        pool: &OrdersPool,
+        init_time: DateTime,
+        expire_after: Option<std::time::Duration>,
+    ) -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            "test".into(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("a".into(), "b".into()),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        let header = match expire_after {
+            Some(expire_after) => header.with_expire_after(expire_after),
+            None => header,
+        };
+        pool.add_simple_initial(&header, init_time, None)
+    }
+
+    #[test]
+    fn order_is_expired_after_time_to_live() {
+        let pool = OrdersPool::new();
+        let now = Utc::now();
+        let order_ref = add_order(
+            &pool,
+            now - Duration::seconds(30),
+            Some(std::time::Duration::from_secs(60)),
+        );
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Created, now - Duration::seconds(29)));
+
+        assert!(!is_expired(&order_ref, now));
+        assert!(is_expired(&order_ref, now + Duration::seconds(30)));
+    }
+
+    #[test]
+    fn order_without_time_to_live_never_expires() {
+        let pool = OrdersPool::new();
+        let now = Utc::now();
+        let order_ref = add_order(&pool, now - Duration::days(30), None);
+
+        assert!(!is_expired(&order_ref, now));
+    }
+
+    #[test]
+    fn finished_or_canceling_order_is_not_expired() {
+        let pool = OrdersPool::new();
+        let now = Utc::now();
+        let order_ref = add_order(
+            &pool,
+            now - Duration::minutes(5),
+            Some(std::time::Duration::from_secs(60)),
+        );
+
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Canceling, now));
+        assert!(!is_expired(&order_ref, now));
+
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Completed, now));
+        assert!(!is_expired(&order_ref, now));
+    }
+}
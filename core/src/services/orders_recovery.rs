@@ -0,0 +1,361 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use dashmap::DashMap;
+use mmb_database::postgres_db::orders::load_not_finished_orders;
+use mmb_database::postgres_db::PgPool;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{OrderInfo, OrderSnapshot, OrderStatus};
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use tokio::time::sleep;
+
+use crate::exchanges::general::exchange::Exchange;
+use crate::infrastructure::spawn_future;
+use crate::misc::time::time_manager;
+use crate::settings::OrdersRecoverySettings;
+
+/// Restores orders which weren't finished before previous shutdown or crash into orders pools
+/// from their last snapshots saved in database and reconciles them with open orders on exchange
+/// before strategies start. Balance reservations don't survive restart, so restored orders
+/// aren't linked to reservations
+pub struct OrdersRecoveryService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    pool: PgPool,
+    settings: OrdersRecoverySettings,
+}
+
+impl OrdersRecoveryService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        pool: PgPool,
+        settings: OrdersRecoverySettings,
+    ) -> Self {
+        Self {
+            exchanges,
+            pool,
+            settings,
+        }
+    }
+
+    /// Recovered orders which state can't be received from exchange yet stay not finished
+    /// and are retried in background
+    pub async fn run(self) {
+        let mut unresolved_orders = Vec::new();
+        for exchange in self.exchanges.iter() {
+            let exchange_account_id = *exchange.key();
+            match self.recover_for_exchange(exchange.value()).await {
+                Ok(orders) => unresolved_orders.extend(
+                    orders
+                        .into_iter()
+                        .map(|order| (exchange.value().clone(), order)),
+                ),
+                Err(err) => {
+                    log::error!("Failed to recover orders for {exchange_account_id}: {err:?}")
+                }
+            }
+        }
+
+        if unresolved_orders.is_empty() {
+            return;
+        }
+
+        let _ = spawn_future(
+            "Retry finishing of recovered orders",
+            SpawnFutureFlags::STOP_BY_TOKEN,
+            retry_finish_closed_orders(unresolved_orders, self.settings.retry_period()),
+        );
+    }
+
+    /// Returns restored orders which are closed on exchange but can't be finished yet
+    async fn recover_for_exchange(&self, exchange: &Arc<Exchange>) -> Result<Vec<OrderRef>> {
+        let exchange_account_id = exchange.exchange_account_id;
+        let snapshots = load_not_finished_orders(
+            &self.pool,
+            &exchange_account_id.to_string(),
+            self.settings.depth_hours,
+        )
+        .await?;
+
+        let restored_orders = snapshots
+            .into_iter()
+            .filter_map(|json| match serde_json::from_value::<OrderSnapshot>(json) {
+                Ok(snapshot) => restore_order(exchange, snapshot),
+                Err(err) => {
+                    log::warn!("Unable to parse saved order of {exchange_account_id}: {err:?}");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let open_orders = exchange
+            .get_open_orders(false)
+            .await
+            .context("Unable to get open orders for reconciliation")?;
+
+        let mut unresolved_orders = Vec::new();
+        for order in &restored_orders {
+            match find_open_order(order, &open_orders) {
+                Some(order_info) => {
+                    order.fn_mut(|x| {
+                        x.props
+                            .exchange_order_id
+                            .get_or_insert_with(|| order_info.exchange_order_id.clone());
+                        if x.props.status == OrderStatus::Creating {
+                            x.set_status(OrderStatus::Created, time_manager::now());
+                        }
+                    });
+                    let _ = exchange
+                        .orders
+                        .cache_by_exchange_id
+                        .insert(order_info.exchange_order_id.clone(), order.clone());
+                }
+                None => {
+                    if let Err(err) = finish_closed_order(exchange, order).await {
+                        log::warn!(
+                            "Recovered order {} isn't finished yet: {err:?}",
+                            order.client_order_id()
+                        );
+                        unresolved_orders.push(order.clone());
+                    }
+                }
+            }
+        }
+
+        // open orders which are unknown after restoring are added to orders pool as missed
+        exchange.add_missing_open_orders(&open_orders);
+
+        log::info!(
+            "Recovered {} not finished orders for {exchange_account_id}, {} of them are still open",
+            restored_orders.len(),
+            restored_orders.iter().filter(|x| !x.is_finished()).count()
+        );
+
+        Ok(unresolved_orders)
+    }
+}
+
+/// Order was finished on exchange while engine was stopped, so it's finished through usual
+/// order events with fills missed during this time. Returns error if order state can't be
+/// received yet, in this case order stays not finished
+async fn finish_closed_order(exchange: &Exchange, order: &OrderRef) -> Result<()> {
+    let (client_order_id, exchange_order_id) = order.order_ids();
+    let order_info = exchange
+        .get_order_info(order)
+        .await
+        .map_err(|err| anyhow!("Unable to get order info: {err:?}"))?;
+
+    ensure!(
+        order_info.order_status.is_finished(),
+        "Order has status {:?} but it isn't in open orders",
+        order_info.order_status
+    );
+
+    exchange.finish_recovered_order(order, &order_info).await?;
+
+    log::info!(
+        "Recovered order {client_order_id} {exchange_order_id:?} on {} is finished with status {:?}",
+        exchange.exchange_account_id,
+        order.status()
+    );
+
+    Ok(())
+}
+
+async fn retry_finish_closed_orders(
+    mut orders: Vec<(Arc<Exchange>, OrderRef)>,
+    retry_period: Duration,
+) -> Result<()> {
+    while !orders.is_empty() {
+        sleep(retry_period).await;
+
+        let mut unresolved_orders = Vec::new();
+        for (exchange, order) in orders {
+            // order could be finished by exchange events meanwhile
+            if order.is_finished() {
+                continue;
+            }
+
+            if let Err(err) = finish_closed_order(&exchange, &order).await {
+                log::warn!(
+                    "Recovered order {} isn't finished yet on {}: {err:?}",
+                    order.client_order_id(),
+                    exchange.exchange_account_id
+                );
+                unresolved_orders.push((exchange, order));
+            }
+        }
+
+        orders = unresolved_orders;
+    }
+
+    Ok(())
+}
+
+fn restore_order(exchange: &Exchange, mut snapshot: OrderSnapshot) -> Option<OrderRef> {
+    let client_order_id = snapshot.header.client_order_id.clone();
+    if exchange
+        .orders
+        .cache_by_client_id
+        .contains_key(&client_order_id)
+    {
+        return None;
+    }
+
+    snapshot.header.reservation_id = None;
+    snapshot.internal_props = Default::default();
+
+    let order = exchange.orders.add_snapshot_initial(&snapshot);
+    if let Some(exchange_order_id) = snapshot.props.exchange_order_id {
+        let _ = exchange
+            .orders
+            .cache_by_exchange_id
+            .insert(exchange_order_id, order.clone());
+    }
+
+    Some(order)
+}
+
+fn find_open_order<'a>(order: &OrderRef, open_orders: &'a [OrderInfo]) -> Option<&'a OrderInfo> {
+    let (client_order_id, exchange_order_id) = order.order_ids();
+    open_orders.iter().find(|x| {
+        x.client_order_id == client_order_id
+            || exchange_order_id.as_ref() == Some(&x.exchange_order_id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchanges::general::test_helper::{get_test_client, get_test_exchange};
+    use mmb_domain::events::ExchangeEvent;
+    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::order::event::OrderEventType;
+    use mmb_domain::order::snapshot::{
+        ClientOrderId, ExchangeOrderId, OrderOptions, OrderRole, OrderSide,
+    };
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use tokio::sync::broadcast;
+
+    fn add_recovered_order(exchange: &Exchange) -> OrderRef {
+        let mut snapshot = OrderSnapshot::with_params(
+            ClientOrderId::unique_id(),
+            OrderOptions::limit(dec!(10)),
+            Some(OrderRole::Maker),
+            exchange.exchange_account_id,
+            CurrencyPair::from_codes("PHB".into(), "BTC".into()),
+            dec!(1),
+            OrderSide::Buy,
+            None,
+            "test",
+        );
+        snapshot.props.exchange_order_id = Some(ExchangeOrderId::from("1"));
+        snapshot.set_status(OrderStatus::Created, time_manager::now());
+
+        restore_order(exchange, snapshot).expect("in test")
+    }
+
+    fn set_order_info(
+        exchange: &Exchange,
+        order: &OrderRef,
+        order_status: OrderStatus,
+        filled_amount: Decimal,
+    ) {
+        let order_info = OrderInfo::new(
+            order.currency_pair(),
+            ExchangeOrderId::from("1"),
+            order.client_order_id(),
+            order.side(),
+            order_status,
+            dec!(10),
+            order.amount(),
+            dec!(10),
+            filled_amount,
+            None,
+            None,
+            None,
+        );
+        let _ = get_test_client(exchange)
+            .order_infos
+            .insert(order.client_order_id(), order_info);
+    }
+
+    fn order_event_types(rx: &mut broadcast::Receiver<ExchangeEvent>) -> Vec<OrderEventType> {
+        let mut event_types = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let ExchangeEvent::OrderEvent(order_event) = event {
+                event_types.push(order_event.event_type);
+            }
+        }
+        event_types
+    }
+
+    #[tokio::test]
+    async fn closed_order_stays_unresolved_if_its_info_is_unavailable() {
+        let (exchange, _rx) = get_test_exchange(false);
+        let order = add_recovered_order(&exchange);
+
+        assert!(finish_closed_order(&exchange, &order).await.is_err());
+        assert_eq!(order.status(), OrderStatus::Created);
+        assert!(exchange
+            .orders
+            .not_finished
+            .contains_key(&order.client_order_id()));
+
+        // order which isn't finished on exchange yet is retried too
+        set_order_info(&exchange, &order, OrderStatus::Created, dec!(0));
+        assert!(finish_closed_order(&exchange, &order).await.is_err());
+        assert_eq!(order.status(), OrderStatus::Created);
+
+        set_order_info(&exchange, &order, OrderStatus::Canceled, dec!(0));
+        finish_closed_order(&exchange, &order)
+            .await
+            .expect("in test");
+        assert_eq!(order.status(), OrderStatus::Canceled);
+    }
+
+    #[tokio::test]
+    async fn canceled_order_is_finished_with_missed_fills() {
+        let (exchange, mut rx) = get_test_exchange(false);
+        let order = add_recovered_order(&exchange);
+        set_order_info(&exchange, &order, OrderStatus::Canceled, dec!(0.4));
+
+        finish_closed_order(&exchange, &order)
+            .await
+            .expect("in test");
+
+        assert_eq!(order.status(), OrderStatus::Canceled);
+        assert_eq!(order.filled_amount(), dec!(0.4));
+        assert!(!exchange
+            .orders
+            .not_finished
+            .contains_key(&order.client_order_id()));
+
+        let event_types = order_event_types(&mut rx);
+        assert!(event_types
+            .iter()
+            .any(|x| matches!(x, OrderEventType::OrderFilled { .. })));
+        assert!(event_types
+            .iter()
+            .any(|x| matches!(x, OrderEventType::CancelOrderSucceeded)));
+    }
+
+    #[tokio::test]
+    async fn completed_order_is_finished_by_fills() {
+        let (exchange, mut rx) = get_test_exchange(false);
+        let order = add_recovered_order(&exchange);
+        set_order_info(&exchange, &order, OrderStatus::Completed, dec!(1));
+
+        finish_closed_order(&exchange, &order)
+            .await
+            .expect("in test");
+
+        assert_eq!(order.status(), OrderStatus::Completed);
+        assert_eq!(order.filled_amount(), dec!(1));
+        assert!(order_event_types(&mut rx)
+            .iter()
+            .any(|x| matches!(x, OrderEventType::OrderCompleted { .. })));
+    }
+}
@@ -0,0 +1,577 @@
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+use crate::infrastructure::spawn_future;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::PositionUnwindSettings;
+use anyhow::{bail, ensure, Context, Result};
+use dashmap::DashMap;
+use mmb_database::impl_event;
+use mmb_domain::decimal_serialization::decimal;
+use mmb_domain::exchanges::symbol::{Round, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, OrderHeader, OrderSide, Price, UserOrder,
+};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::{SpawnFutureFlags, WithExpect};
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot::Receiver;
+use tokio::time::sleep;
+
+pub type UnwindId = u64;
+
+const STRATEGY_NAME: &str = "PositionUnwind";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnwindRequest {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    /// Side of position, unwind orders have opposite side
+    pub position_side: OrderSide,
+    /// Size of position which should be reduced to zero
+    #[serde(with = "decimal")]
+    pub amount: Amount,
+    /// Duration of unwind, `PositionUnwindSettings::default_horizon_secs` is used if not specified
+    #[serde(default)]
+    pub horizon_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state")]
+pub enum UnwindState {
+    /// Position is reduced by maker only orders on own side of order book
+    Passive,
+    /// Deadline is near, so position is reduced by orders crossing the spread
+    Aggressive,
+    Completed,
+    Cancelled {
+        reason: String,
+    },
+    /// Rest of position isn't unwound and needs manual handling
+    Failed {
+        reason: String,
+    },
+}
+
+/// Progress of position unwind, saved on every step of execution
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionUnwind {
+    pub id: UnwindId,
+    pub request: UnwindRequest,
+    pub state: UnwindState,
+    #[serde(with = "decimal")]
+    pub filled_amount: Amount,
+    pub deadline: DateTime,
+    pub creation_time: DateTime,
+    pub update_time: DateTime,
+}
+
+impl_event!(PositionUnwind, "position_unwinds");
+
+impl PositionUnwind {
+    pub fn remaining_amount(&self) -> Amount {
+        (self.request.amount - self.filled_amount).max(dec!(0))
+    }
+
+    fn is_active(&self) -> bool {
+        matches!(self.state, UnwindState::Passive | UnwindState::Aggressive)
+    }
+
+    fn set_state(&mut self, state: UnwindState, now: DateTime) {
+        self.state = state;
+        self.update_time = now;
+    }
+
+    fn cancel(&mut self, reason: String, now: DateTime) -> Result<()> {
+        ensure!(
+            self.is_active(),
+            "Unwind {} can't be cancelled in state {:?}",
+            self.id,
+            self.state
+        );
+
+        self.set_state(UnwindState::Cancelled { reason }, now);
+        Ok(())
+    }
+
+    /// Register progress of active unwind, returns `false` if unwind isn't active anymore
+    fn progress(&mut self, filled_amount: Amount, phase: UnwindState, now: DateTime) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+
+        self.filled_amount = filled_amount;
+        self.set_state(phase, now);
+        true
+    }
+}
+
+/// Linear schedule of reducing position from full amount at start to zero at deadline
+#[derive(Debug, Clone, Copy)]
+struct UnwindSchedule {
+    horizon: Duration,
+    passive_part: Decimal,
+    step_period: Duration,
+}
+
+impl UnwindSchedule {
+    fn phase(&self, elapsed: Duration) -> UnwindState {
+        match part_of(elapsed, self.horizon) < self.passive_part {
+            true => UnwindState::Passive,
+            false => UnwindState::Aggressive,
+        }
+    }
+
+    /// Amount which should be unwound by order of current step to keep up with schedule.
+    /// It's zero if unwind is ahead of schedule and all remaining amount after deadline
+    fn step_amount(&self, amount: Amount, remaining: Amount, elapsed: Duration) -> Amount {
+        let next_step = elapsed + self.step_period;
+        if next_step >= self.horizon {
+            return remaining;
+        }
+
+        let target_remaining = amount * (dec!(1) - part_of(next_step, self.horizon));
+        (remaining - target_remaining).max(dec!(0))
+    }
+}
+
+fn part_of(elapsed: Duration, horizon: Duration) -> Decimal {
+    if horizon.is_zero() {
+        return dec!(1);
+    }
+
+    (Decimal::from(elapsed.as_millis() as u64) / Decimal::from(horizon.as_millis() as u64))
+        .min(dec!(1))
+}
+
+/// Reduces position to zero over configured horizon: maker only orders are used first
+/// and orders crossing the spread near the deadline. Unwind is started by operator via RPC
+/// or by risk checks via `PositionUnwindService::start`
+pub struct PositionUnwindService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    event_recorder: Arc<EventRecorder>,
+    settings: PositionUnwindSettings,
+    unwinds: Mutex<BTreeMap<UnwindId, PositionUnwind>>,
+    last_unwind_id: AtomicU64,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for PositionUnwindService {
+    fn name(&self) -> &str {
+        "PositionUnwindService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl PositionUnwindService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        event_recorder: Arc<EventRecorder>,
+        settings: PositionUnwindSettings,
+        cancellation_token: CancellationToken,
+    ) -> Arc<Self> {
+        // ids are started from current time for keeping them unique between engine restarts
+        let first_unwind_id = time_manager::now().timestamp_millis() as u64;
+
+        Arc::new(Self {
+            exchanges,
+            event_recorder,
+            settings,
+            unwinds: Mutex::new(BTreeMap::new()),
+            last_unwind_id: AtomicU64::new(first_unwind_id),
+            cancellation_token,
+        })
+    }
+
+    pub fn unwinds(&self) -> Vec<PositionUnwind> {
+        self.unwinds.lock().values().cloned().collect()
+    }
+
+    pub fn start(self: &Arc<Self>, request: UnwindRequest) -> Result<UnwindId> {
+        ensure!(
+            request.amount > dec!(0),
+            "Amount of unwind should be positive"
+        );
+        self.exchange(request.exchange_account_id)?
+            .get_symbol(request.currency_pair)?;
+
+        let market_is_unwinding = self.unwinds.lock().values().any(|x| {
+            x.is_active()
+                && x.request.exchange_account_id == request.exchange_account_id
+                && x.request.currency_pair == request.currency_pair
+        });
+        ensure!(
+            !market_is_unwinding,
+            "Position on {} {} is already unwinding",
+            request.exchange_account_id,
+            request.currency_pair
+        );
+
+        let horizon = request
+            .horizon_secs
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| self.settings.default_horizon());
+
+        let id = self.last_unwind_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let now = time_manager::now();
+        let unwind = PositionUnwind {
+            id,
+            request,
+            state: UnwindState::Passive,
+            filled_amount: dec!(0),
+            deadline: now + chrono::Duration::from_std(horizon)?,
+            creation_time: now,
+            update_time: now,
+        };
+
+        log::info!("Unwind {id} is started: {:?}", unwind.request);
+        self.save(&unwind);
+        let _ = self.unwinds.lock().insert(id, unwind);
+
+        let schedule = UnwindSchedule {
+            horizon,
+            passive_part: self.settings.passive_part,
+            step_period: self.settings.step_period(),
+        };
+        let _ = spawn_future(
+            "Execute position unwind",
+            SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+            self.clone().execute(id, schedule),
+        );
+
+        Ok(id)
+    }
+
+    /// Stop placing unwind orders, active order is cancelled on next step
+    pub fn cancel(&self, id: UnwindId) -> Result<()> {
+        self.update(id, |unwind, now| {
+            unwind.cancel("Cancelled by operator".to_owned(), now)
+        })
+    }
+
+    async fn execute(self: Arc<Self>, id: UnwindId, schedule: UnwindSchedule) -> Result<()> {
+        let (request, creation_time) = self
+            .unwinds
+            .lock()
+            .get(&id)
+            .map(|x| (x.request.clone(), x.creation_time))
+            .with_context(|| format!("Unwind {id} isn't found"))?;
+
+        if let Err(error) = self.run(id, &request, creation_time, schedule).await {
+            log::error!("Unwind {id} failed and needs manual check: {error:?}");
+
+            let reason = format!("{error:#}");
+            self.update(id, |unwind, now| {
+                unwind.set_state(UnwindState::Failed { reason }, now);
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    async fn run(
+        &self,
+        id: UnwindId,
+        request: &UnwindRequest,
+        creation_time: DateTime,
+        schedule: UnwindSchedule,
+    ) -> Result<()> {
+        let exchange = self.exchange(request.exchange_account_id)?;
+        let symbol = exchange.get_symbol(request.currency_pair)?;
+        let mut active_order: Option<OrderRef> = None;
+        let mut filled_amount = dec!(0);
+
+        loop {
+            if let Some(order) = active_order.take() {
+                if !order.is_finished() {
+                    exchange
+                        .wait_cancel_order(
+                            order.clone(),
+                            None,
+                            true,
+                            self.cancellation_token.clone(),
+                        )
+                        .await
+                        .context("Failed to cancel unwind order")?;
+                }
+                filled_amount += order.filled_amount();
+            }
+
+            let now = time_manager::now();
+            let elapsed = (now - creation_time).to_std().unwrap_or_default();
+            let phase = schedule.phase(elapsed);
+            if !self.update_progress(id, filled_amount, phase.clone())? {
+                // unwind is cancelled
+                return Ok(());
+            }
+
+            let remaining = symbol.amount_round(request.amount - filled_amount, Round::Floor);
+            let order_side = request.position_side.change_side();
+            let Some(price) = order_price(&exchange, request.currency_pair, order_side, &phase)
+            else {
+                log::warn!(
+                    "Unwind {id} is delayed: no prices in order book of {} {}",
+                    request.exchange_account_id,
+                    request.currency_pair
+                );
+                self.wait_next_step(schedule).await?;
+                continue;
+            };
+
+            let min_amount = symbol.get_min_amount(price)?;
+            if remaining < min_amount {
+                log::info!("Unwind {id} is completed with remaining amount {remaining} below min amount {min_amount}");
+                return self.update(id, |unwind, now| {
+                    unwind.set_state(UnwindState::Completed, now);
+                    Ok(())
+                });
+            }
+
+            let step_amount = schedule.step_amount(request.amount, remaining, elapsed);
+            let order_amount = symbol
+                .amount_round(step_amount, Round::Floor)
+                .max(min_amount)
+                .min(remaining);
+            if !step_amount.is_zero() {
+                active_order = self
+                    .create_order(&exchange, &symbol, order_side, order_amount, price, &phase)
+                    .await;
+            }
+
+            self.wait_next_step(schedule).await?;
+        }
+    }
+
+    async fn create_order(
+        &self,
+        exchange: &Exchange,
+        symbol: &Symbol,
+        side: OrderSide,
+        amount: Amount,
+        price: Price,
+        phase: &UnwindState,
+    ) -> Option<OrderRef> {
+        let user_order = match phase {
+            UnwindState::Passive => UserOrder::maker_only(price),
+            _ => UserOrder::limit(price),
+        };
+        let order_header = OrderHeader::with_user_order(
+            ClientOrderId::unique_id(),
+            exchange.exchange_account_id,
+            symbol.currency_pair(),
+            side,
+            amount,
+            user_order,
+            None,
+            None,
+            STRATEGY_NAME.to_owned(),
+        );
+
+        // failed order is replaced on next step, so unwind isn't stopped by it
+        match exchange
+            .create_order(&order_header, None, self.cancellation_token.clone())
+            .await
+        {
+            Ok(order) => Some(order),
+            Err(error) => {
+                log::warn!(
+                    "Failed to create unwind order {}: {error:?}",
+                    order_header.client_order_id
+                );
+                None
+            }
+        }
+    }
+
+    async fn wait_next_step(&self, schedule: UnwindSchedule) -> Result<()> {
+        tokio::select! {
+            _ = sleep(schedule.step_period) => Ok(()),
+            _ = self.cancellation_token.when_cancelled() => bail!("Unwind is interrupted by engine stopping"),
+        }
+    }
+
+    fn exchange(&self, exchange_account_id: ExchangeAccountId) -> Result<Arc<Exchange>> {
+        self.exchanges
+            .get(&exchange_account_id)
+            .map(|x| x.value().clone())
+            .with_context(|| format!("Exchange {exchange_account_id} isn't found"))
+    }
+
+    /// Returns `false` if unwind isn't active anymore
+    fn update_progress(
+        &self,
+        id: UnwindId,
+        filled_amount: Amount,
+        phase: UnwindState,
+    ) -> Result<bool> {
+        let mut unwinds = self.unwinds.lock();
+        let unwind = unwinds
+            .get_mut(&id)
+            .with_context(|| format!("Unwind {id} isn't found"))?;
+
+        let is_active = unwind.progress(filled_amount, phase, time_manager::now());
+        if is_active {
+            log::info!(
+                "Unwind {id} is in state {:?}, remaining amount {}",
+                unwind.state,
+                unwind.remaining_amount()
+            );
+            self.save(unwind);
+        }
+
+        Ok(is_active)
+    }
+
+    fn update(
+        &self,
+        id: UnwindId,
+        action: impl FnOnce(&mut PositionUnwind, DateTime) -> Result<()>,
+    ) -> Result<()> {
+        let mut unwinds = self.unwinds.lock();
+        let unwind = unwinds
+            .get_mut(&id)
+            .with_context(|| format!("Unwind {id} isn't found"))?;
+
+        action(unwind, time_manager::now())?;
+
+        log::info!("Unwind {id} moved to state {:?}", unwind.state);
+        self.save(unwind);
+
+        Ok(())
+    }
+
+    fn save(&self, unwind: &PositionUnwind) {
+        self.event_recorder
+            .save(unwind.clone())
+            .with_expect(|| format!("Failed to save position unwind {}", unwind.id));
+    }
+}
+
+/// Passive order joins top of own side of order book, aggressive one crosses the spread
+fn order_price(
+    exchange: &Exchange,
+    currency_pair: CurrencyPair,
+    order_side: OrderSide,
+    phase: &UnwindState,
+) -> Option<Price> {
+    let order_book_top = exchange.order_book_top.get(&currency_pair)?;
+    let level = match (phase, order_side) {
+        (UnwindState::Passive, OrderSide::Sell) => &order_book_top.ask,
+        (UnwindState::Passive, OrderSide::Buy) => &order_book_top.bid,
+        (_, OrderSide::Sell) => &order_book_top.bid,
+        (_, OrderSide::Buy) => &order_book_top.ask,
+    };
+
+    level.as_ref().map(|x| x.price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn schedule() -> UnwindSchedule {
+        UnwindSchedule {
+            horizon: Duration::from_secs(100),
+            passive_part: dec!(0.8),
+            step_period: Duration::from_secs(10),
+        }
+    }
+
+    fn unwind() -> PositionUnwind {
+        PositionUnwind {
+            id: 1,
+            request: UnwindRequest {
+                exchange_account_id: ExchangeAccountId::new("Binance", 0),
+                currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+                position_side: OrderSide::Buy,
+                amount: dec!(10),
+                horizon_secs: None,
+            },
+            state: UnwindState::Passive,
+            filled_amount: dec!(0),
+            deadline: Utc::now(),
+            creation_time: Utc::now(),
+            update_time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn aggressive_phase_starts_near_deadline() {
+        let schedule = schedule();
+
+        assert_eq!(schedule.phase(Duration::from_secs(0)), UnwindState::Passive);
+        assert_eq!(
+            schedule.phase(Duration::from_secs(79)),
+            UnwindState::Passive
+        );
+        assert_eq!(
+            schedule.phase(Duration::from_secs(80)),
+            UnwindState::Aggressive
+        );
+        assert_eq!(
+            schedule.phase(Duration::from_secs(200)),
+            UnwindState::Aggressive
+        );
+    }
+
+    #[test]
+    fn step_amount_follows_linear_schedule() {
+        let schedule = schedule();
+
+        assert_eq!(
+            schedule.step_amount(dec!(10), dec!(10), Duration::from_secs(0)),
+            dec!(1)
+        );
+        // behind schedule
+        assert_eq!(
+            schedule.step_amount(dec!(10), dec!(10), Duration::from_secs(50)),
+            dec!(6)
+        );
+        // ahead of schedule
+        assert_eq!(
+            schedule.step_amount(dec!(10), dec!(3), Duration::from_secs(50)),
+            dec!(0)
+        );
+        // last step
+        assert_eq!(
+            schedule.step_amount(dec!(10), dec!(3), Duration::from_secs(95)),
+            dec!(3)
+        );
+    }
+
+    #[test]
+    fn cancelled_unwind_does_not_progress() {
+        let mut unwind = unwind();
+        assert!(unwind.progress(dec!(4), UnwindState::Aggressive, Utc::now()));
+        assert_eq!(unwind.remaining_amount(), dec!(6));
+
+        unwind
+            .cancel("test".to_owned(), Utc::now())
+            .expect("in test");
+        assert!(unwind.cancel("test".to_owned(), Utc::now()).is_err());
+
+        assert!(!unwind.progress(dec!(5), UnwindState::Aggressive, Utc::now()));
+        assert_eq!(unwind.remaining_amount(), dec!(6));
+        assert_eq!(
+            unwind.state,
+            UnwindState::Cancelled {
+                reason: "test".to_owned()
+            }
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::settings::StuckOrdersWatchdogSettings;
+use dashmap::{DashMap, DashSet};
+use futures::future::join_all;
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ClientOrderId, ExchangeOrderId, OrderStatus};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+
+/// Alert about order which status wasn't resolved after staying in transitional status too long
+#[derive(Debug, Clone, Serialize)]
+pub struct StuckOrderAlert {
+    pub time: DateTime,
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub client_order_id: ClientOrderId,
+    pub exchange_order_id: Option<ExchangeOrderId>,
+    pub status: OrderStatus,
+    pub status_time: DateTime,
+    pub error: String,
+}
+
+impl_event!(StuckOrderAlert, "stuck_order_alerts");
+
+/// Looks for orders which stay in `Creating` or `Canceling` status longer than configured age
+/// (e.g. because of lost websocket events) and resolves their status via REST
+pub struct StuckOrdersWatchdogService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    settings: StuckOrdersWatchdogSettings,
+    resolving_orders: DashSet<ClientOrderId>,
+    cancellation_token: CancellationToken,
+}
+
+impl Service for StuckOrdersWatchdogService {
+    fn name(&self) -> &str {
+        "StuckOrdersWatchdogService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl StuckOrdersWatchdogService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        settings: StuckOrdersWatchdogSettings,
+        cancellation_token: CancellationToken,
+    ) -> Self {
+        Self {
+            exchanges,
+            settings,
+            resolving_orders: DashSet::new(),
+            cancellation_token,
+        }
+    }
+
+    pub async fn check_stuck_orders(self: Arc<Self>) {
+        let deadline = time_manager::now()
+            - chrono::Duration::from_std(self.settings.max_age())
+                .expect("Unable to convert max_age of stuck orders watchdog");
+
+        let stuck_orders = self
+            .exchanges
+            .iter()
+            .flat_map(|exchange| {
+                exchange
+                    .orders
+                    .cache_by_client_id
+                    .iter()
+                    .filter(|x| is_stuck(x.value(), deadline))
+                    .map(|x| (exchange.clone(), x.value().clone()))
+                    .collect::<Vec<_>>()
+            })
+            // skip orders which are still resolving since previous check
+            .filter(|(_, order)| self.resolving_orders.insert(order.client_order_id()))
+            .collect::<Vec<_>>();
+
+        let resolvings = stuck_orders
+            .into_iter()
+            .map(|(exchange, order)| self.clone().resolve(exchange, order));
+
+        join_all(resolvings).await;
+    }
+
+    async fn resolve(self: Arc<Self>, exchange: Arc<Exchange>, order: OrderRef) {
+        let client_order_id = order.client_order_id();
+        let (exchange_order_id, status, status_time) = order.fn_ref(|x| {
+            (
+                x.props.exchange_order_id.clone(),
+                x.props.status,
+                status_time(x.status_history.last_change_time(), x.props.init_time),
+            )
+        });
+
+        log::warn!("Order {client_order_id} {exchange_order_id:?} on {} is stuck in status {status:?} since {status_time}", exchange.exchange_account_id);
+
+        let result = exchange
+            .resolve_stuck_order(
+                &order,
+                self.settings.resolve_timeout(),
+                self.cancellation_token.create_linked_token(),
+            )
+            .await;
+
+        if let Err(error) = result {
+            if !self.cancellation_token.is_cancellation_requested() {
+                log::error!("Failed to resolve status of stuck order {client_order_id} {exchange_order_id:?} on {}: {error:?}", exchange.exchange_account_id);
+
+                let alert = StuckOrderAlert {
+                    time: time_manager::now(),
+                    exchange_account_id: exchange.exchange_account_id,
+                    currency_pair: order.currency_pair(),
+                    client_order_id: client_order_id.clone(),
+                    exchange_order_id,
+                    status,
+                    status_time,
+                    error: format!("{error:?}"),
+                };
+
+                exchange.event_recorder.save(alert).with_expect(|| {
+                    format!("Failed to save stuck order alert for {client_order_id}")
+                });
+            }
+        }
+
+        self.resolving_orders.remove(&client_order_id);
+    }
+}
+
+fn status_time(last_status_change_time: Option<DateTime>, init_time: DateTime) -> DateTime {
+    last_status_change_time.unwrap_or(init_time)
+}
+
+pub(crate) fn is_stuck(order: &OrderRef, deadline: DateTime) -> bool {
+    order.fn_ref(|x| {
+        matches!(
+            x.props.status,
+            OrderStatus::Creating | OrderStatus::Canceling
+        ) && status_time(x.status_history.last_change_time(), x.props.init_time) < deadline
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use mmb_domain::order::pool::OrdersPool;
+    use mmb_domain::order::snapshot::{OrderHeader, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    fn add_order(pool: &OrdersPool, init_time: DateTime) -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            "test".into(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("a".into(), "b".into()),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        pool.add_simple_initial(&header, init_time, None)
+    }
+
+    #[test]
+    fn creating_order_is_stuck_after_deadline() {
+        let pool = OrdersPool::new();
+        let now = Utc::now();
+        let order_ref = add_order(&pool, now - Duration::minutes(5));
+
+        assert!(is_stuck(&order_ref, now - Duration::minutes(1)));
+        assert!(!is_stuck(&order_ref, now - Duration::minutes(10)));
+    }
+
+    #[test]
+    fn canceling_order_age_is_counted_from_status_change() {
+        let pool = OrdersPool::new();
+        let now = Utc::now();
+        let order_ref = add_order(&pool, now - Duration::minutes(30));
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Canceling, now - Duration::seconds(10)));
+
+        assert!(!is_stuck(&order_ref, now - Duration::minutes(1)));
+
+        order_ref.fn_mut(|x| x.set_status(OrderStatus::Created, now));
+        assert!(!is_stuck(&order_ref, now + Duration::minutes(1)));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::balance::manager::balance_manager::BalanceManager;
+use crate::database::events::recorder::EventRecorder;
+use crate::exchanges::general::exchange::Exchange;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::services::stuck_orders_watchdog::is_stuck;
+use crate::settings::StuckOrdersWatchdogSettings;
+use crate::statistic_service::{CashFlowSummary, StatisticService};
+use dashmap::DashMap;
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderSide};
+use mmb_domain::reporting_precision::ReportingPrecision;
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+
+type BalancesByAccount = HashMap<ExchangeAccountId, HashMap<CurrencyCode, Decimal>>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSummary {
+    pub exchange_account_id: ExchangeAccountId,
+    pub balances: HashMap<CurrencyCode, Amount>,
+    /// Change of balances since start of the session, it's restarted on each trading day
+    pub session_pnl: HashMap<CurrencyCode, Amount>,
+    /// Net positions on derivative markets
+    pub positions: HashMap<CurrencyPair, Amount>,
+    /// Funding payments and accrued fees by markets since start of the session,
+    /// they are already included into session PnL
+    pub session_cash_flows: HashMap<CurrencyPair, CashFlowSummary>,
+    pub open_orders_count: usize,
+    /// Orders which status isn't reconciled with exchange for too long
+    pub stuck_orders_count: usize,
+}
+
+/// Periodic summary of trading state of all accounts
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryReport {
+    pub time: DateTime,
+    pub session_start_time: DateTime,
+    pub accounts: Vec<AccountSummary>,
+}
+
+impl_event!(SummaryReport, "summary_reports");
+
+struct SessionStart {
+    time: DateTime,
+    balances: BalancesByAccount,
+}
+
+/// Periodically reports balances, positions, session PnL and orders state of all accounts
+pub struct SummaryReportService {
+    exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+    balance_manager: Arc<Mutex<BalanceManager>>,
+    statistics: Arc<StatisticService>,
+    event_recorder: Arc<EventRecorder>,
+    stuck_orders_settings: Option<StuckOrdersWatchdogSettings>,
+    reporting_precision: ReportingPrecision,
+    session_start: Mutex<Option<SessionStart>>,
+}
+
+impl Service for SummaryReportService {
+    fn name(&self) -> &str {
+        "SummaryReportService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl SummaryReportService {
+    pub fn new(
+        exchanges: DashMap<ExchangeAccountId, Arc<Exchange>>,
+        balance_manager: Arc<Mutex<BalanceManager>>,
+        statistics: Arc<StatisticService>,
+        event_recorder: Arc<EventRecorder>,
+        stuck_orders_settings: Option<StuckOrdersWatchdogSettings>,
+        reporting_precision: ReportingPrecision,
+    ) -> Self {
+        Self {
+            exchanges,
+            balance_manager,
+            statistics,
+            event_recorder,
+            stuck_orders_settings,
+            reporting_precision,
+            session_start: Mutex::new(None),
+        }
+    }
+
+    pub async fn send_report(self: Arc<Self>) {
+        let report = self.create_report();
+
+        for account in &report.accounts {
+            log::info!(
+                "Summary of {}: balances {:?}, session PnL {:?}, positions {:?}, cash flows {:?}, open orders {}",
+                account.exchange_account_id,
+                account.balances,
+                account.session_pnl,
+                account.positions,
+                account.session_cash_flows,
+                account.open_orders_count,
+            );
+
+            if account.stuck_orders_count > 0 {
+                log::warn!(
+                    "Summary of {}: {} orders are not reconciled with exchange",
+                    account.exchange_account_id,
+                    account.stuck_orders_count
+                );
+            }
+        }
+
+        self.event_recorder
+            .save(report)
+            .expect("Failed to save summary report");
+    }
+
+    /// Restart session on new trading day so that session PnL is calculated for the day
+    pub fn start_session(&self, time: DateTime) {
+        let balances = self
+            .balance_manager
+            .lock()
+            .get_balances()
+            .balances_by_exchange_id
+            .unwrap_or_default();
+
+        *self.session_start.lock() =
+            (!balances.is_empty()).then_some(SessionStart { time, balances });
+    }
+
+    fn create_report(&self) -> SummaryReport {
+        let now = time_manager::now();
+        // orders are counted as stuck only when watchdog is enabled
+        let stuck_deadline = self.stuck_orders_settings.as_ref().map(|x| {
+            now - chrono::Duration::from_std(x.max_age())
+                .expect("Unable to convert max_age of stuck orders watchdog")
+        });
+
+        let balance_manager = self.balance_manager.lock();
+        let balances = balance_manager
+            .get_balances()
+            .balances_by_exchange_id
+            .unwrap_or_default();
+
+        // session is started when balances are known for the first time
+        let mut session_start = self.session_start.lock();
+        if session_start.is_none() && !balances.is_empty() {
+            *session_start = Some(SessionStart {
+                time: now,
+                balances: balances.clone(),
+            });
+        }
+
+        let mut accounts = self
+            .exchanges
+            .iter()
+            .map(|exchange| {
+                let exchange_account_id = exchange.exchange_account_id;
+
+                let account_balances = balances
+                    .get(&exchange_account_id)
+                    .cloned()
+                    .unwrap_or_default();
+
+                let session_pnl = session_start
+                    .as_ref()
+                    .and_then(|x| x.balances.get(&exchange_account_id))
+                    .map(|initial| calculate_balance_changes(initial, &account_balances))
+                    .unwrap_or_default();
+
+                let positions = exchange
+                    .symbols
+                    .iter()
+                    .filter(|x| x.is_derivative)
+                    .map(|x| {
+                        let currency_pair = *x.key();
+                        let position = balance_manager.get_position(
+                            exchange_account_id,
+                            currency_pair,
+                            OrderSide::Buy,
+                        );
+                        let base = currency_pair.to_codes().base;
+                        (
+                            currency_pair,
+                            self.reporting_precision.round_amount(base, position),
+                        )
+                    })
+                    .filter(|(_, position)| !position.is_zero())
+                    .collect();
+
+                let stuck_orders_count = stuck_deadline.map_or(0, |stuck_deadline| {
+                    exchange
+                        .orders
+                        .not_finished
+                        .iter()
+                        .filter(|x| is_stuck(x.value(), stuck_deadline))
+                        .count()
+                });
+
+                AccountSummary {
+                    exchange_account_id,
+                    balances: self.reporting_precision.round_amounts(&account_balances),
+                    session_pnl: self.reporting_precision.round_amounts(&session_pnl),
+                    positions,
+                    session_cash_flows: self.statistics.daily_cash_flows(exchange_account_id),
+                    open_orders_count: exchange.orders.not_finished.len(),
+                    stuck_orders_count,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        accounts.sort_by_key(|x| x.exchange_account_id.to_string());
+
+        SummaryReport {
+            time: now,
+            session_start_time: session_start.as_ref().map_or(now, |x| x.time),
+            accounts,
+        }
+    }
+}
+
+fn calculate_balance_changes(
+    initial: &HashMap<CurrencyCode, Decimal>,
+    current: &HashMap<CurrencyCode, Decimal>,
+) -> HashMap<CurrencyCode, Amount> {
+    initial
+        .keys()
+        .chain(current.keys())
+        .map(|currency_code| {
+            let initial_amount = initial.get(currency_code).copied().unwrap_or_default();
+            let current_amount = current.get(currency_code).copied().unwrap_or_default();
+            (*currency_code, current_amount - initial_amount)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn balance_changes_include_new_and_disappeared_currencies() {
+        let initial = HashMap::from([("btc".into(), dec!(1)), ("eth".into(), dec!(10))]);
+        let current = HashMap::from([("btc".into(), dec!(1.5)), ("usdt".into(), dec!(100))]);
+
+        let changes = calculate_balance_changes(&initial, &current);
+
+        assert_eq!(
+            changes,
+            HashMap::from([
+                ("btc".into(), dec!(0.5)),
+                ("eth".into(), dec!(-10)),
+                ("usdt".into(), dec!(100)),
+            ])
+        );
+    }
+}
@@ -0,0 +1,316 @@
+use crate::config::{API_KEY, PASSPHRASE, SECRET_KEY};
+use crate::services::diagnostics::DiagnosticsService;
+use crate::statistic_service::StatisticService;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mmb_utils::logger::get_log_file_paths;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use toml_edit::{value, Document, Item, Table, Value};
+
+pub const SUPPORT_BUNDLES_DIR: &str = "support_bundles";
+const REDACTED: &str = "<redacted>";
+const CREDENTIAL_KEYS: [&str; 3] = [API_KEY, SECRET_KEY, PASSPHRASE];
+/// Only end of log files is read, because trace logs can be very large
+const LOG_TAIL_MAX_BYTES: u64 = 16 * 1024 * 1024;
+const MAX_RECENT_ERRORS: usize = 200;
+/// Shorter values are redacted in config only, because replacing them in logs spoils the text
+const MIN_SCRUBBED_SECRET_LEN: usize = 8;
+
+#[derive(Serialize)]
+struct Versions {
+    mmb_core: &'static str,
+    target_os: &'static str,
+    target_arch: &'static str,
+    created_at: String,
+}
+
+/// Collects sanitized engine state into a single archive for attaching to bug reports
+pub struct SupportBundleService {
+    diagnostics: Arc<DiagnosticsService>,
+    statistics: Arc<StatisticService>,
+    engine_settings: String,
+}
+
+impl SupportBundleService {
+    pub(crate) fn new(
+        diagnostics: Arc<DiagnosticsService>,
+        statistics: Arc<StatisticService>,
+        engine_settings: String,
+    ) -> Arc<Self> {
+        Arc::new(SupportBundleService {
+            diagnostics,
+            statistics,
+            engine_settings,
+        })
+    }
+
+    /// Write `tar.gz` archive with engine state and last `log_lines` lines of log files
+    /// into `SUPPORT_BUNDLES_DIR` and return its path. Credentials from config are
+    /// redacted in all files of archive
+    pub fn export(&self, log_lines: usize) -> Result<PathBuf> {
+        let (config, secrets) = redact_credentials(&self.engine_settings)?;
+        let (log_tail, recent_errors) = read_logs(log_lines);
+
+        let versions = Versions {
+            mmb_core: env!("CARGO_PKG_VERSION"),
+            target_os: std::env::consts::OS,
+            target_arch: std::env::consts::ARCH,
+            created_at: Utc::now().to_rfc3339(),
+        };
+
+        let entries = [
+            ("config.toml", config),
+            ("versions.json", to_json(&versions)?),
+            (
+                "open_orders.json",
+                to_json(&self.diagnostics.open_orders())?,
+            ),
+            ("balances.json", to_json(&self.diagnostics.balances())?),
+            (
+                "reservations.json",
+                to_json(&self.diagnostics.reservations())?,
+            ),
+            (
+                "metrics.json",
+                to_json(&self.statistics.statistic_service_state)?,
+            ),
+            ("timeouts.json", to_json(&self.diagnostics.timeouts())?),
+            ("tasks.json", to_json(&self.diagnostics.tasks())?),
+            ("recent_errors.log", recent_errors),
+            ("log_tail.log", log_tail),
+        ];
+
+        fs::create_dir_all(SUPPORT_BUNDLES_DIR)
+            .with_context(|| format!("Unable to create directory {SUPPORT_BUNDLES_DIR}"))?;
+        let path = Path::new(SUPPORT_BUNDLES_DIR).join(format!(
+            "support_bundle_{}.tar.gz",
+            Utc::now().format("%Y%m%d_%H%M%S")
+        ));
+
+        write_archive(&path, &entries, &secrets)
+            .with_context(|| format!("Unable to write support bundle {}", path.display()))?;
+
+        log::info!("Support bundle is exported to {}", path.display());
+        Ok(path)
+    }
+}
+
+fn to_json<T: Serialize>(data: &T) -> Result<String> {
+    serde_json::to_string_pretty(data).context("Unable to serialize support bundle entry")
+}
+
+fn write_archive(path: &Path, entries: &[(&str, String)], secrets: &[String]) -> Result<()> {
+    let file = File::create(path)?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mtime = Utc::now().timestamp().max(0) as u64;
+    for (name, content) in entries {
+        // secrets can leak to other entries too, e.g. to logs of requests
+        let content = redact_secrets(content, secrets);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        archive.append_data(&mut header, name, content.as_bytes())?;
+    }
+
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Replace values of credentials in TOML settings and return them for redaction in other texts
+fn redact_credentials(settings: &str) -> Result<(String, Vec<String>)> {
+    let mut document: Document = settings
+        .parse()
+        .context("Unable to parse engine settings for support bundle")?;
+
+    let mut secrets = Vec::new();
+    redact_item(document.as_item_mut(), &mut secrets);
+
+    Ok((document.to_string(), secrets))
+}
+
+fn redact_item(item: &mut Item, secrets: &mut Vec<String>) {
+    match item {
+        Item::Table(table) => redact_table(table, secrets),
+        Item::ArrayOfTables(tables) => tables
+            .iter_mut()
+            .for_each(|table| redact_table(table, secrets)),
+        Item::Value(value) => redact_value(value, secrets),
+        Item::None => {}
+    }
+}
+
+fn redact_table(table: &mut Table, secrets: &mut Vec<String>) {
+    let keys: Vec<String> = table.iter().map(|(key, _)| key.to_owned()).collect();
+    for key in keys {
+        if let Some(item) = table.get_mut(&key) {
+            match CREDENTIAL_KEYS.contains(&key.as_str()) {
+                true => {
+                    take_secret(item.as_str(), secrets);
+                    *item = value(REDACTED);
+                }
+                false => redact_item(item, secrets),
+            }
+        }
+    }
+}
+
+fn redact_value(value: &mut Value, secrets: &mut Vec<String>) {
+    match value {
+        Value::InlineTable(table) => {
+            let keys: Vec<String> = table.iter().map(|(key, _)| key.to_owned()).collect();
+            for key in keys {
+                if let Some(value) = table.get_mut(&key) {
+                    match CREDENTIAL_KEYS.contains(&key.as_str()) {
+                        true => {
+                            take_secret(value.as_str(), secrets);
+                            *value = Value::from(REDACTED);
+                        }
+                        false => redact_value(value, secrets),
+                    }
+                }
+            }
+        }
+        Value::Array(array) => {
+            for index in 0..array.len() {
+                if let Some(value) = array.get_mut(index) {
+                    redact_value(value, secrets);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn take_secret(secret: Option<&str>, secrets: &mut Vec<String>) {
+    if let Some(secret) = secret.filter(|x| x.len() >= MIN_SCRUBBED_SECRET_LEN) {
+        secrets.push(secret.to_owned());
+    }
+}
+
+fn redact_secrets(text: &str, secrets: &[String]) -> String {
+    secrets.iter().fold(text.to_owned(), |text, secret| {
+        text.replace(secret, REDACTED)
+    })
+}
+
+/// Last `log_lines` lines of all log files and recent error lines from their ends
+fn read_logs(log_lines: usize) -> (String, String) {
+    let paths = match get_log_file_paths() {
+        Ok(paths) => paths,
+        Err(err) => {
+            let message = format!("Unable to get log files: {err:?}");
+            return (message.clone(), message);
+        }
+    };
+
+    let mut log_tail = String::new();
+    let mut recent_errors = String::new();
+    for path in paths {
+        let header = format!("==> {} <==\n", path.display());
+        log_tail.push_str(&header);
+        recent_errors.push_str(&header);
+
+        match read_file_tail(&path) {
+            Ok(content) => {
+                let lines: Vec<&str> = content.lines().collect();
+                for line in &lines[lines.len().saturating_sub(log_lines)..] {
+                    log_tail.push_str(line);
+                    log_tail.push('\n');
+                }
+
+                let errors: Vec<&&str> = lines
+                    .iter()
+                    .filter(|line| line.contains(" ERROR "))
+                    .collect();
+                for line in &errors[errors.len().saturating_sub(MAX_RECENT_ERRORS)..] {
+                    recent_errors.push_str(line);
+                    recent_errors.push('\n');
+                }
+            }
+            Err(err) => {
+                let message = format!("Unable to read log file: {err:?}\n");
+                log_tail.push_str(&message);
+                recent_errors.push_str(&message);
+            }
+        }
+    }
+
+    (log_tail, recent_errors)
+}
+
+fn read_file_tail(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let length = file.metadata()?.len();
+    let start = length.saturating_sub(LOG_TAIL_MAX_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+    let content = String::from_utf8_lossy(&buffer);
+
+    // first line can be cut by seeking
+    Ok(match start > 0 {
+        true => content
+            .split_once('\n')
+            .map(|(_, rest)| rest.to_owned())
+            .unwrap_or_default(),
+        false => content.into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_are_redacted_in_settings_and_texts() {
+        let settings = r#"
+[core]
+[[core.exchanges]]
+exchange_account_id = "Binance_0"
+api_key = "public_key_value"
+secret_key = "secret_key_value"
+is_margin_trading = false
+
+[strategy]
+spread = 1
+"#;
+
+        let (config, secrets) = redact_credentials(settings).expect("in test");
+
+        assert!(!config.contains("public_key_value"));
+        assert!(!config.contains("secret_key_value"));
+        assert!(config.contains("exchange_account_id = \"Binance_0\""));
+        assert!(config.contains("spread = 1"));
+        assert_eq!(secrets, vec!["public_key_value", "secret_key_value"]);
+
+        let log_line = "GET /api/v3/order?signature=1 X-MBX-APIKEY: public_key_value";
+        assert_eq!(
+            redact_secrets(log_line, &secrets),
+            "GET /api/v3/order?signature=1 X-MBX-APIKEY: <redacted>"
+        );
+    }
+
+    #[test]
+    fn credentials_are_redacted_in_inline_tables() {
+        let settings =
+            r#"core = { exchanges = [{ api_key = "public_key_value", secret_key = "s" }] }"#;
+
+        let (config, secrets) = redact_credentials(settings).expect("in test");
+
+        assert!(!config.contains("public_key_value"));
+        assert!(!config.contains("\"s\""));
+        // short secret is redacted in config, but isn't scrubbed from other texts
+        assert_eq!(secrets, vec!["public_key_value"]);
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::{bail, Result};
+use mmb_domain::decimal_serialization::{option_decimal, option_decimal_level};
+use mmb_domain::exchanges::symbol::{ContractType, Precision, Symbol};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, SpecificCurrencyPair};
+use mmb_domain::order::snapshot::{Amount, Price};
+use serde::Serialize;
+
+use crate::exchanges::general::exchange::Exchange;
+
+/// Currency codes which some exchanges use instead of common ones
+const CURRENCY_ALIASES: &[(&str, &str)] = &[("XBT", "BTC")];
+
+/// Uppercase alphanumeric form of symbol with aliased currency codes replaced,
+/// so "btc-usdt", "BTC/USDT" and "BTCUSDT" have the same form
+fn normalize(value: &str) -> String {
+    let mut normalized: String = value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|x| x.to_ascii_uppercase())
+        .collect();
+
+    for (alias, code) in CURRENCY_ALIASES {
+        normalized = normalized.replace(alias, code);
+    }
+
+    normalized
+}
+
+/// Fuzzy currency pair entered by user, e.g. "btc-usdt", "XBTUSD" or "BTC/USD"
+pub(crate) struct SymbolQuery {
+    normalized: String,
+}
+
+impl SymbolQuery {
+    pub fn new(query: &str) -> Result<Self> {
+        let normalized = normalize(query);
+        if normalized.is_empty() {
+            bail!("Symbol query '{query}' doesn't contain currency codes");
+        }
+
+        Ok(SymbolQuery { normalized })
+    }
+
+    /// Market matches if query equals to exchange specific symbol or to base and quote currency codes
+    /// of market. Instruments with expiration match by their base and quote currencies too
+    fn matches(
+        &self,
+        currency_pair: CurrencyPair,
+        specific_currency_pair: SpecificCurrencyPair,
+    ) -> bool {
+        let codes = currency_pair.to_codes();
+        normalize(specific_currency_pair.as_str()) == self.normalized
+            || normalize(&format!("{}{}", codes.base, codes.quote)) == self.normalized
+    }
+}
+
+/// Market of exchange matching symbol query
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolMatch {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub specific_currency_pair: SpecificCurrencyPair,
+    pub is_derivative: bool,
+    pub contract_type: ContractType,
+    pub price_precision: Precision,
+    pub amount_precision: Precision,
+    #[serde(with = "option_decimal")]
+    pub min_amount: Option<Amount>,
+    #[serde(with = "option_decimal")]
+    pub min_cost: Option<Amount>,
+    #[serde(with = "option_decimal_level")]
+    pub best_bid: Option<(Price, Amount)>,
+    #[serde(with = "option_decimal_level")]
+    pub best_ask: Option<(Price, Amount)>,
+}
+
+impl SymbolMatch {
+    fn new(
+        exchange: &Exchange,
+        currency_pair: CurrencyPair,
+        specific_currency_pair: SpecificCurrencyPair,
+        symbol: &Symbol,
+    ) -> Self {
+        let order_book_top = exchange.order_book_top.get(&currency_pair);
+        let order_book_top = order_book_top.as_ref().map(|x| x.value());
+
+        SymbolMatch {
+            exchange_account_id: exchange.exchange_account_id,
+            currency_pair,
+            specific_currency_pair,
+            is_derivative: symbol.is_derivative,
+            contract_type: symbol.contract_type,
+            price_precision: symbol.price_precision.clone(),
+            amount_precision: symbol.amount_precision.clone(),
+            min_amount: symbol.min_amount,
+            min_cost: symbol.min_cost,
+            best_bid: order_book_top
+                .and_then(|x| x.bid.as_ref())
+                .map(|x| (x.price, x.amount)),
+            best_ask: order_book_top
+                .and_then(|x| x.ask.as_ref())
+                .map(|x| (x.price, x.amount)),
+        }
+    }
+}
+
+/// Markets of exchange matching query sorted by currency pair
+pub(crate) fn search_symbols(exchange: &Exchange, query: &SymbolQuery) -> Vec<SymbolMatch> {
+    let mut matches: Vec<SymbolMatch> = exchange
+        .symbols
+        .iter()
+        .filter_map(|x| {
+            let currency_pair = *x.key();
+            let specific_currency_pair = exchange
+                .exchange_client
+                .get_specific_currency_pair(currency_pair);
+            query
+                .matches(currency_pair, specific_currency_pair)
+                .then(|| {
+                    SymbolMatch::new(exchange, currency_pair, specific_currency_pair, x.value())
+                })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| a.currency_pair.as_str().cmp(b.currency_pair.as_str()));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(query: &str, currency_pair: CurrencyPair, specific: &str) -> bool {
+        SymbolQuery::new(query)
+            .expect("valid query")
+            .matches(currency_pair, specific.into())
+    }
+
+    #[test]
+    fn query_matches_unified_and_specific_symbols() {
+        let btc_usdt = CurrencyPair::from_codes("btc".into(), "usdt".into());
+
+        assert!(matches("btc-usdt", btc_usdt, "BTCUSDT"));
+        assert!(matches("BTC/USDT", btc_usdt, "BTCUSDT"));
+        assert!(matches("btcusdt", btc_usdt, "BTCUSDT"));
+        assert!(!matches("BTC/USD", btc_usdt, "BTCUSDT"));
+        assert!(!matches("eth-usdt", btc_usdt, "BTCUSDT"));
+    }
+
+    #[test]
+    fn query_matches_aliased_currency_codes() {
+        let xbt_usd = CurrencyPair::from_codes("xbt".into(), "usd".into());
+        let btc_usd = CurrencyPair::from_codes("btc".into(), "usd".into());
+
+        assert!(matches("XBTUSD", xbt_usd, "XBTUSD"));
+        assert!(matches("BTC/USD", xbt_usd, "XBTUSD"));
+        assert!(matches("XBTUSD", btc_usd, "BTC-PERPETUAL"));
+    }
+
+    #[test]
+    fn query_without_codes_is_rejected() {
+        assert!(SymbolQuery::new(" / ").is_err());
+    }
+}
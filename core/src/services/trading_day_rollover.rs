@@ -0,0 +1,94 @@
+use crate::database::events::recorder::EventRecorder;
+use crate::lifecycle::trading_engine::Service;
+use crate::misc::time::time_manager;
+use crate::misc::trading_day::TradingDayBoundary;
+use crate::services::summary_report::SummaryReportService;
+use crate::settings::TradingDaySettings;
+use crate::statistic_service::{DailyPerformanceAttribution, DailyStatistics, StatisticService};
+use mmb_utils::DateTime;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::sync::oneshot::Receiver;
+
+/// Detects end of trading day, saves statistics and performance attribution of finished day,
+/// sends end-of-day summary report and starts new day for daily statistics
+/// and summary report session
+pub struct TradingDayRolloverService {
+    boundary: TradingDayBoundary,
+    statistics: Arc<StatisticService>,
+    summary_report_service: Arc<SummaryReportService>,
+    event_recorder: Arc<EventRecorder>,
+    day_start: Mutex<DateTime>,
+}
+
+impl Service for TradingDayRolloverService {
+    fn name(&self) -> &str {
+        "TradingDayRolloverService"
+    }
+
+    fn graceful_shutdown(self: Arc<Self>) -> Option<Receiver<anyhow::Result<()>>> {
+        None
+    }
+}
+
+impl TradingDayRolloverService {
+    pub fn new(
+        settings: &TradingDaySettings,
+        statistics: Arc<StatisticService>,
+        summary_report_service: Arc<SummaryReportService>,
+        event_recorder: Arc<EventRecorder>,
+    ) -> Self {
+        let boundary = TradingDayBoundary::new(settings);
+        let day_start = boundary.day_start(time_manager::now());
+        let _ = statistics.start_trading_day(day_start);
+
+        Self {
+            boundary,
+            statistics,
+            summary_report_service,
+            event_recorder,
+            day_start: Mutex::new(day_start),
+        }
+    }
+
+    pub async fn check_rollover(self: Arc<Self>) {
+        let new_day_start = self.boundary.day_start(time_manager::now());
+        let previous_day_start = {
+            let mut day_start = self.day_start.lock();
+            if *day_start >= new_day_start {
+                return;
+            }
+
+            std::mem::replace(&mut *day_start, new_day_start)
+        };
+
+        log::info!("Trading day started at {previous_day_start} is over, new trading day started at {new_day_start}");
+
+        let market_account_id_stats = self.statistics.start_trading_day(new_day_start);
+        self.event_recorder
+            .save(DailyStatistics {
+                day_start: previous_day_start,
+                day_end: new_day_start,
+                market_account_id_stats,
+            })
+            .expect("Failed to save daily statistics");
+
+        let attribution = self.statistics.start_performance_attribution_day();
+        for (strategy_name, markets) in attribution {
+            for (market_account_id, attribution) in markets {
+                self.event_recorder
+                    .save(DailyPerformanceAttribution {
+                        day_start: previous_day_start,
+                        day_end: new_day_start,
+                        strategy_name: strategy_name.clone(),
+                        market_account_id,
+                        attribution,
+                    })
+                    .expect("Failed to save daily performance attribution");
+            }
+        }
+
+        self.summary_report_service.clone().send_report().await;
+        self.summary_report_service.start_session(new_day_start);
+    }
+}
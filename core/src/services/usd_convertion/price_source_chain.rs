@@ -1,4 +1,5 @@
 use mmb_domain::market::CurrencyCode;
+use std::time::Duration;
 
 use super::rebase_price_step::RebasePriceStep;
 
@@ -7,6 +8,8 @@ pub struct PriceSourceChain {
     pub start_currency_code: CurrencyCode,
     pub end_currency_code: CurrencyCode,
     pub rebase_price_steps: Vec<RebasePriceStep>,
+    /// Prices of order books which weren't updated for longer period are treated as missing
+    pub max_price_age: Option<Duration>,
 }
 
 impl PriceSourceChain {
@@ -19,6 +22,7 @@ impl PriceSourceChain {
             start_currency_code,
             end_currency_code,
             rebase_price_steps,
+            max_price_age: None,
         }
     }
 }
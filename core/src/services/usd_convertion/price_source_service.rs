@@ -11,7 +11,7 @@ use crate::{
     infrastructure::spawn_future,
     order_book::local_snapshot_service::LocalSnapshotsService,
     services::usd_convertion::{prices_calculator, rebase_price_step::RebaseDirection},
-    settings::CurrencyPriceSourceSettings,
+    settings::{CurrencyPriceSourceSettings, PriceFallbackSettings},
 };
 
 use anyhow::{bail, Context, Result};
@@ -29,9 +29,11 @@ use rust_decimal::Decimal;
 use tokio::sync::{broadcast, mpsc, oneshot};
 
 use super::{
-    convert_currency_direction::ConvertCurrencyDirection, price_source_chain::PriceSourceChain,
-    price_sources_loader::PriceSourcesLoader, prices_sources_saver::PriceSourcesSaver,
-    rebase_price_step::RebasePriceStep,
+    convert_currency_direction::ConvertCurrencyDirection,
+    price_source_chain::PriceSourceChain,
+    price_sources_loader::PriceSourcesLoader,
+    prices_sources_saver::PriceSourcesSaver,
+    rebase_price_step::{PriceFallback, RebasePriceStep},
 };
 
 pub struct PriceSourceEventLoop {
@@ -81,7 +83,11 @@ impl PriceSourceEventLoop {
                         convert_amount.src_amount,
                         &self.local_snapshot_service,
                         &convert_amount.chain,
-                    );
+                    )
+                    .unwrap_or_else(|error| {
+                        log::error!("Failed to convert amount {}: {error:?}", convert_amount.src_amount);
+                        None
+                    });
                     convert_amount.task_finished_sender.send_expected(result);
                 },
                 core_event_res = self.rx_core.recv() => {
@@ -137,7 +143,7 @@ impl PriceSourceEventLoop {
                 price_source_chain
                     .rebase_price_steps
                     .into_iter()
-                    .map(|step| MarketId::new(step.exchange_id, step.symbol.currency_pair()))
+                    .flat_map(|step| step.all_market_ids().collect_vec())
             })
             .collect()
     }
@@ -224,16 +230,19 @@ impl PriceSourceService {
                 for pair in &setting.exchange_id_currency_pair_settings {
                     let symbol = currency_pair_to_symbol_converter
                         .get_symbol(pair.exchange_account_id, pair.currency_pair);
+                    let fallbacks = Self::prepare_fallbacks(setting, &pair.fallbacks);
                     Self::add_symbol_to_hashmap(
                         symbol.quote_currency_code(),
                         pair.exchange_account_id.exchange_id,
                         symbol.clone(),
+                        fallbacks.clone(),
                         &mut symbol_by_currency_code,
                     );
                     Self::add_symbol_to_hashmap(
                         symbol.base_currency_code(),
                         pair.exchange_account_id.exchange_id,
                         symbol.clone(),
+                        fallbacks,
                         &mut symbol_by_currency_code,
                     );
                 }
@@ -288,12 +297,44 @@ impl PriceSourceService {
                         )
                         .retain(|x| x.symbol != step_symbol);
                 }
-                PriceSourceChain::new(
+                let mut chain = PriceSourceChain::new(
                     setting.start_currency_code,
                     setting.end_currency_code,
                     rebase_price_steps,
-                )
+                );
+                chain.max_price_age = setting.max_price_age;
+                chain
+            })
+            .collect_vec()
+    }
+
+    fn prepare_fallbacks(
+        setting: &CurrencyPriceSourceSettings,
+        fallback_settings: &[PriceFallbackSettings],
+    ) -> Vec<PriceFallback> {
+        fallback_settings
+            .iter()
+            .map(|fallback| {
+                if fallback.weight <= Decimal::ZERO {
+                    panic!(
+                        "{}",
+                        Self::format_panic_message(
+                            setting,
+                            format_args!(
+                                "Weight of fallback {} should be positive",
+                                fallback.exchange_id
+                            ),
+                        )
+                    );
+                }
+
+                PriceFallback {
+                    exchange_id: fallback.exchange_id,
+                    priority: fallback.priority,
+                    weight: fallback.weight,
+                }
             })
+            .sorted_by_key(|fallback| fallback.priority)
             .collect_vec()
     }
 
@@ -310,6 +351,7 @@ impl PriceSourceService {
         currency_code: CurrencyCode,
         exchange_id: ExchangeId,
         symbol: Arc<Symbol>,
+        fallbacks: Vec<PriceFallback>,
         symbol_by_currency_code: &mut HashMap<CurrencyCode, Vec<RebasePriceStep>>,
     ) {
         let list = symbol_by_currency_code.entry(currency_code).or_default();
@@ -317,7 +359,9 @@ impl PriceSourceService {
             true => RebaseDirection::ToQuote,
             false => RebaseDirection::ToBase,
         };
-        list.push(RebasePriceStep::new(exchange_id, symbol, direction));
+        let mut step = RebasePriceStep::new(exchange_id, symbol, direction);
+        step.fallbacks = fallbacks;
+        list.push(step);
     }
 
     /// Convert amount from 'from' currency position to 'to' currency by current price
@@ -397,6 +441,10 @@ impl PriceSourceService {
             time_in_past,
             prices_source_chain,
         )
+        .unwrap_or_else(|error| {
+            log::error!("Failed to convert amount {src_amount} on time {time_in_past}: {error:?}");
+            None
+        })
     }
 }
 
@@ -505,6 +553,7 @@ pub mod test {
             vec![ExchangeIdCurrencyPairSettings {
                 exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                 currency_pair: CurrencyPair::from_codes(usdt, usdt),
+                fallbacks: Vec::new(),
             }],
         )];
 
@@ -534,6 +583,7 @@ pub mod test {
             vec![ExchangeIdCurrencyPairSettings {
                 exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                 currency_pair,
+                fallbacks: Vec::new(),
             }],
         )];
 
@@ -601,10 +651,12 @@ pub mod test {
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                     currency_pair: currency_pair_1,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_2(),
                     currency_pair: currency_pair_2,
+                    fallbacks: Vec::new(),
                 },
             ],
         )];
@@ -678,14 +730,17 @@ pub mod test {
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                     currency_pair: currency_pair_1,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_2(),
                     currency_pair: currency_pair_2,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_3(),
                     currency_pair: currency_pair_3,
+                    fallbacks: Vec::new(),
                 },
             ],
         )];
@@ -770,14 +825,17 @@ pub mod test {
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                     currency_pair: currency_pair_1,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_2(),
                     currency_pair: currency_pair_2,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_3(),
                     currency_pair: currency_pair_3,
+                    fallbacks: Vec::new(),
                 },
             ],
         )];
@@ -830,10 +888,12 @@ pub mod test {
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                     currency_pair: currency_pair_1,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_2(),
                     currency_pair: currency_pair_2,
+                    fallbacks: Vec::new(),
                 },
             ],
         )];
@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 
+use anyhow::{Context, Result};
 use mmb_domain::market::MarketId;
 use mmb_domain::order::snapshot::PriceByOrderSide;
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_utils::checked_decimal::CheckedDecimal;
 use mmb_utils::DateTime;
 use rust_decimal_macros::dec;
 
 use crate::{
+    misc::time::time_manager,
     order_book::local_snapshot_service::LocalSnapshotsService,
     services::usd_convertion::{
         price_source_chain::PriceSourceChain, rebase_price_step::RebaseDirection,
@@ -18,41 +21,56 @@ pub(crate) fn calculate(
     src_amount: Amount,
     price_source_chain: &PriceSourceChain,
     prices: &HashMap<MarketId, Price>,
-) -> Price {
+) -> Result<Option<Price>> {
     calculate_amount_for_chain(src_amount, price_source_chain, |market_id| {
         prices.get(&market_id).cloned()
     })
-    .expect("Invalid price cache")
 }
 
+/// Returns `Ok(None)` if price of some rebase step is unknown on primary and all fallback markets
+/// and error if price is invalid (e.g. zero price of `ToBase` step)
 fn calculate_amount_for_chain(
     src_amount: Amount,
     price_source_chain: &PriceSourceChain,
     calculate_price: impl Fn(MarketId) -> Option<Price>,
-) -> Option<Amount> {
+) -> Result<Option<Amount>> {
     let mut rebase_price = dec!(1);
 
     for step in &price_source_chain.rebase_price_steps {
-        let market_id = MarketId::new(step.exchange_id, step.symbol.currency_pair());
-        let calculated_price = (calculate_price)(market_id)?;
+        let market_id = step.market_id();
+        let calculated_price = match step.calculate_price(&calculate_price) {
+            Some(price) => price,
+            None => return Ok(None),
+        };
 
-        match step.direction {
-            RebaseDirection::ToQuote => rebase_price *= calculated_price,
-            RebaseDirection::ToBase => rebase_price /= calculated_price,
+        rebase_price = match step.direction {
+            RebaseDirection::ToQuote => rebase_price.try_mul(calculated_price),
+            RebaseDirection::ToBase => rebase_price.try_div(calculated_price),
         }
+        .with_context(|| format!("Failed rebase step {:?} on {market_id:?}", step.direction))?;
     }
-    Some(rebase_price * src_amount)
+
+    Ok(Some(rebase_price.try_mul(src_amount)?))
 }
 
 pub(crate) fn convert_amount(
     src_amount: Amount,
     local_snapshot_service: &LocalSnapshotsService,
     price_source_chain: &PriceSourceChain,
-) -> Option<Amount> {
+) -> Result<Option<Amount>> {
+    let stale_time = price_source_chain.max_price_age.map(|max_price_age| {
+        time_manager::now()
+            - chrono::Duration::from_std(max_price_age)
+                .expect("Unable to convert max_price_age of price source settings")
+    });
+
     calculate_amount_for_chain(src_amount, price_source_chain, |market_id| {
-        local_snapshot_service
-            .get_snapshot(market_id)?
-            .calculate_middle_price(market_id)
+        let snapshot = local_snapshot_service.get_snapshot(market_id)?;
+        if stale_time.map_or(false, |stale_time| snapshot.last_update_time < stale_time) {
+            return None;
+        }
+
+        snapshot.calculate_middle_price(market_id)
     })
 }
 
@@ -61,7 +79,7 @@ pub fn convert_amount_in_past(
     price_cache: &HashMap<MarketId, PriceByOrderSide>,
     time_in_past: DateTime,
     price_source_chain: &PriceSourceChain,
-) -> Option<Amount> {
+) -> Result<Option<Amount>> {
     calculate_amount_for_chain(src_amount, price_source_chain, |market_id| {
         let prices = match price_cache.get(&market_id) {
             Some(prices) => prices,
@@ -83,7 +101,7 @@ mod test {
     use std::sync::Arc;
 
     use chrono::Utc;
-    use mmb_domain::market::CurrencyPair;
+    use mmb_domain::market::{CurrencyPair, ExchangeId};
     use mmb_domain::order_book_data;
     use mmb_utils::hashmap;
     use mockall_double::double;
@@ -97,6 +115,7 @@ mod test {
         services::usd_convertion::{
             price_source_chain::PriceSourceChain,
             price_source_service::{test::PriceSourceServiceTestBase, PriceSourceService},
+            rebase_price_step::PriceFallback,
         },
         settings::{CurrencyPriceSourceSettings, ExchangeIdCurrencyPairSettings},
     };
@@ -118,6 +137,7 @@ mod test {
             vec![ExchangeIdCurrencyPairSettings {
                 exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                 currency_pair,
+                fallbacks: Vec::new(),
             }],
         )];
 
@@ -172,8 +192,9 @@ mod test {
         let snapshot_service = LocalSnapshotsService::new(hashmap![market_id => snapshot]);
 
         let src_amount = dec!(10);
-        let price_now =
-            convert_amount(src_amount, &snapshot_service, &price_source_chain).expect("in test");
+        let price_now = convert_amount(src_amount, &snapshot_service, &price_source_chain)
+            .expect("in test")
+            .expect("in test");
 
         assert_eq!(dec!(1) / (dec!(12) / dec!(2)) * src_amount, price_now);
     }
@@ -188,7 +209,8 @@ mod test {
         let snapshot_service = LocalSnapshotsService::new(hashmap![market_id => snapshot]);
 
         let src_amount = dec!(10);
-        let price_now = convert_amount(src_amount, &snapshot_service, &price_source_chain);
+        let price_now =
+            convert_amount(src_amount, &snapshot_service, &price_source_chain).expect("in test");
 
         assert!(price_now.is_none());
     }
@@ -205,11 +227,27 @@ mod test {
         let src_amount = dec!(10);
         let price_now =
             convert_amount_in_past(src_amount, &price_cache, time_in_past, &price_source_chain)
+                .expect("in test")
                 .expect("in test");
 
         assert_eq!(dec!(1) / (dec!(12) / dec!(2)) * src_amount, price_now);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_amount_in_past_with_zero_price_returns_error() {
+        let (currency_pair, price_source_chain, _locker) = generate_one_step_setup();
+        let time_in_past = Utc::now();
+        let market_id = MarketId::new(PriceSourceServiceTestBase::exchange_id(), currency_pair);
+        let price_cache = hashmap![
+            market_id => PriceByOrderSide::new(Some(dec!(0)), Some(dec!(0)))
+        ];
+
+        let result =
+            convert_amount_in_past(dec!(10), &price_cache, time_in_past, &price_source_chain);
+
+        assert!(result.is_err());
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn calculate_amount_in_past_using_one_step_without_price() {
         let (_, price_source_chain, _locker) = generate_one_step_setup();
@@ -217,7 +255,8 @@ mod test {
         let price_cache = HashMap::new();
         let src_amount = dec!(10);
         let price_now =
-            convert_amount_in_past(src_amount, &price_cache, time_in_past, &price_source_chain);
+            convert_amount_in_past(src_amount, &price_cache, time_in_past, &price_source_chain)
+                .expect("in test");
 
         assert!(price_now.is_none());
     }
@@ -230,19 +269,136 @@ mod test {
         let price_cache = hashmap![market_id => cached_price];
 
         let src_amount = dec!(10);
-        let price_now = calculate(src_amount, &price_source_chain, &price_cache);
+        let price_now = calculate(src_amount, &price_source_chain, &price_cache)
+            .expect("in test")
+            .expect("in test");
 
         assert_eq!(dec!(1) / cached_price * src_amount, price_now);
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    #[should_panic(expected = "Invalid price cache")]
     async fn calculate_amount_with_current_cached_prices_using_one_step_without_price() {
         let (_, price_source_chain, _locker) = generate_one_step_setup();
         let price_cache = HashMap::new();
 
         let src_amount = dec!(10);
-        let _ = calculate(src_amount, &price_source_chain, &price_cache);
+        let price_now = calculate(src_amount, &price_source_chain, &price_cache).expect("in test");
+
+        assert!(price_now.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_amount_with_zero_cached_price_returns_error() {
+        let (currency_pair, price_source_chain, _locker) = generate_one_step_setup();
+        let market_id = MarketId::new(PriceSourceServiceTestBase::exchange_id(), currency_pair);
+        let price_cache = hashmap![market_id => dec!(0)];
+
+        let result = calculate(dec!(10), &price_source_chain, &price_cache);
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_amount_using_weighted_fallbacks_without_primary_price() {
+        let (currency_pair, mut price_source_chain, _locker) = generate_one_step_setup();
+        let okx = ExchangeId::new("Okx");
+        let kraken = ExchangeId::new("Kraken");
+        let kucoin = ExchangeId::new("Kucoin");
+        price_source_chain.rebase_price_steps[0].fallbacks = vec![
+            PriceFallback {
+                exchange_id: okx,
+                priority: 0,
+                weight: dec!(3),
+            },
+            PriceFallback {
+                exchange_id: kraken,
+                priority: 0,
+                weight: dec!(1),
+            },
+            PriceFallback {
+                exchange_id: kucoin,
+                priority: 1,
+                weight: dec!(1),
+            },
+        ];
+        let price_cache = hashmap![
+            MarketId::new(okx, currency_pair) => dec!(6),
+            MarketId::new(kraken, currency_pair) => dec!(10),
+            MarketId::new(kucoin, currency_pair) => dec!(100)
+        ];
+
+        let src_amount = dec!(10);
+        let price_now = calculate(src_amount, &price_source_chain, &price_cache)
+            .expect("in test")
+            .expect("in test");
+
+        assert_eq!(dec!(1) / dec!(7) * src_amount, price_now);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_amount_using_next_priority_fallback() {
+        let (currency_pair, mut price_source_chain, _locker) = generate_one_step_setup();
+        let okx = ExchangeId::new("Okx");
+        let kucoin = ExchangeId::new("Kucoin");
+        price_source_chain.rebase_price_steps[0].fallbacks = vec![
+            PriceFallback {
+                exchange_id: okx,
+                priority: 0,
+                weight: dec!(1),
+            },
+            PriceFallback {
+                exchange_id: kucoin,
+                priority: 1,
+                weight: dec!(1),
+            },
+        ];
+        let price_cache = hashmap![MarketId::new(kucoin, currency_pair) => dec!(8)];
+
+        let src_amount = dec!(10);
+        let price_now = calculate(src_amount, &price_source_chain, &price_cache)
+            .expect("in test")
+            .expect("in test");
+
+        assert_eq!(dec!(1) / dec!(8) * src_amount, price_now);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn calculate_amount_now_skips_stale_primary_price() {
+        let (currency_pair, mut price_source_chain, _locker) = generate_one_step_setup();
+        let okx = ExchangeId::new("Okx");
+        price_source_chain.max_price_age = Some(std::time::Duration::from_secs(60));
+        price_source_chain.rebase_price_steps[0].fallbacks = vec![PriceFallback {
+            exchange_id: okx,
+            priority: 0,
+            weight: dec!(1),
+        }];
+
+        let stale_snapshot = order_book_data![
+            dec!(10) => dec!(1),
+            ;
+            dec!(2) => dec!(1),
+        ]
+        .to_orderbook_snapshot(Utc::now() - chrono::Duration::minutes(5));
+        let fresh_snapshot = order_book_data![
+            dec!(5) => dec!(1),
+            ;
+            dec!(3) => dec!(1),
+        ]
+        .to_orderbook_snapshot(Utc::now());
+
+        let primary_market_id =
+            MarketId::new(PriceSourceServiceTestBase::exchange_id(), currency_pair);
+        let snapshot_service = LocalSnapshotsService::new(hashmap![
+            primary_market_id => stale_snapshot,
+            MarketId::new(okx, currency_pair) => fresh_snapshot
+        ]);
+
+        let src_amount = dec!(10);
+        let price_now = convert_amount(src_amount, &snapshot_service, &price_source_chain)
+            .expect("in test")
+            .expect("in test");
+
+        assert_eq!(dec!(1) / dec!(4) * src_amount, price_now);
     }
 
     struct TwoStepSetup {
@@ -267,10 +423,12 @@ mod test {
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id(),
                     currency_pair: currency_pair_1,
+                    fallbacks: Vec::new(),
                 },
                 ExchangeIdCurrencyPairSettings {
                     exchange_account_id: PriceSourceServiceTestBase::exchange_account_id_2(),
                     currency_pair: currency_pair_2,
+                    fallbacks: Vec::new(),
                 },
             ],
         )];
@@ -336,7 +494,9 @@ mod test {
         ];
 
         let src_amount = dec!(10);
-        let price_now = calculate(src_amount, &setup.price_source_chain, &price_cache);
+        let price_now = calculate(src_amount, &setup.price_source_chain, &price_cache)
+            .expect("in test")
+            .expect("in test");
 
         assert_eq!(
             dec!(1) / cached_price_1 / cached_price_2 * src_amount,
@@ -345,7 +505,6 @@ mod test {
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    #[should_panic(expected = "Invalid price cache")]
     async fn calculate_amount_with_current_cached_prices_using_two_step_without_one_price() {
         let (setup, _locker) = generate_two_step_setup();
         let market_id = MarketId::new(
@@ -356,6 +515,9 @@ mod test {
         let price_cache = hashmap![market_id => cached_price];
 
         let src_amount = dec!(10);
-        let _ = calculate(src_amount, &setup.price_source_chain, &price_cache);
+        let price_now =
+            calculate(src_amount, &setup.price_source_chain, &price_cache).expect("in test");
+
+        assert!(price_now.is_none());
     }
 }
@@ -1,4 +1,7 @@
-use mmb_domain::market::ExchangeId;
+use itertools::Itertools;
+use mmb_domain::market::{ExchangeId, MarketId};
+use mmb_domain::order::snapshot::Price;
+use rust_decimal::Decimal;
 use std::sync::Arc;
 
 use mmb_domain::exchanges::symbol::Symbol;
@@ -9,11 +12,21 @@ pub enum RebaseDirection {
     ToBase,
 }
 
+/// Market with the same currency pair as rebase step on another exchange
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PriceFallback {
+    pub exchange_id: ExchangeId,
+    pub priority: u32,
+    pub weight: Decimal,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RebasePriceStep {
     pub exchange_id: ExchangeId,
     pub symbol: Arc<Symbol>,
     pub direction: RebaseDirection,
+    /// Used if price of primary market is missing or stale, sorted by priority
+    pub fallbacks: Vec<PriceFallback>,
 }
 
 impl RebasePriceStep {
@@ -22,6 +35,51 @@ impl RebasePriceStep {
             exchange_id,
             symbol,
             direction,
+            fallbacks: Vec::new(),
         }
     }
+
+    pub fn market_id(&self) -> MarketId {
+        MarketId::new(self.exchange_id, self.symbol.currency_pair())
+    }
+
+    /// Primary market and all fallback markets of step
+    pub fn all_market_ids(&self) -> impl Iterator<Item = MarketId> + '_ {
+        let currency_pair = self.symbol.currency_pair();
+        std::iter::once(self.market_id()).chain(
+            self.fallbacks
+                .iter()
+                .map(move |fallback| MarketId::new(fallback.exchange_id, currency_pair)),
+        )
+    }
+
+    /// Returns price of primary market if it's known. Otherwise returns weighted average price
+    /// of the first group of fallbacks with the same priority where at least one price is known
+    pub fn calculate_price(&self, get_price: impl Fn(MarketId) -> Option<Price>) -> Option<Price> {
+        if let Some(price) = get_price(self.market_id()) {
+            return Some(price);
+        }
+
+        let currency_pair = self.symbol.currency_pair();
+        for (priority, fallbacks) in &self.fallbacks.iter().group_by(|fallback| fallback.priority) {
+            let mut weighted_sum = Decimal::ZERO;
+            let mut total_weight = Decimal::ZERO;
+            for fallback in fallbacks {
+                if let Some(price) = get_price(MarketId::new(fallback.exchange_id, currency_pair)) {
+                    weighted_sum += price * fallback.weight;
+                    total_weight += fallback.weight;
+                }
+            }
+
+            if !total_weight.is_zero() {
+                log::debug!(
+                    "Price of {:?} is unavailable, fallbacks with priority {priority} are used",
+                    self.market_id()
+                );
+                return Some(weighted_sum / total_weight);
+            }
+        }
+
+        None
+    }
 }
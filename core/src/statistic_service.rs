@@ -1,19 +1,33 @@
 use anyhow::{Context, Result};
+use mmb_database::impl_event;
 use mmb_domain::order::event::OrderEventType;
 use mmb_utils::infrastructure::SpawnFutureFlags;
 use mmb_utils::nothing_to_do;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
-use mmb_domain::events::ExchangeEvent;
-use mmb_domain::market::MarketAccountId;
-use mmb_domain::order::snapshot::ClientOrderId;
+use mmb_domain::decimal_serialization::decimal;
+use mmb_domain::events::{CashFlowEvent, CashFlowKind, ExchangeEvent, MarkPriceEvent};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order::snapshot::{ClientOrderId, OrderSnapshot};
+use mmb_domain::order_book::event::OrderBookEvent;
+use mmb_domain::reporting_precision::ReportingPrecision;
+use mmb_utils::DateTime;
 use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
 
 use super::infrastructure::spawn_future;
+use crate::disposition_execution::warm_up::DataReadiness;
+use crate::misc::conflated_events_receiver::ConflatedEventsReceiver;
+use crate::misc::order_to_trade_ratio::OrderToTradeRatioTracker;
+use crate::misc::performance_attribution::{
+    round_attribution, PerformanceAttribution, PerformanceAttributionTracker,
+};
+use crate::misc::time::time_manager;
+use crate::settings::OrderToTradeRatioSettings;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct MarketAccountIdStatistic {
@@ -22,9 +36,21 @@ pub struct MarketAccountIdStatistic {
     partially_filled_orders_count: u64,
     fully_filled_orders_count: u64,
     // Calculated only for completely filled orders
+    #[serde(with = "decimal")]
     summary_filled_amount: Amount,
-    // Calculated only for completely filled orders
+    // Fees paid, calculated only for completely filled orders
+    #[serde(with = "decimal")]
     summary_commission: Amount,
+    // Maker rebates earned (negative fees), calculated only for completely filled orders
+    #[serde(with = "decimal")]
+    summary_rebates: Amount,
+    #[serde(with = "decimal")]
+    summary_funding: Amount,
+    #[serde(with = "decimal")]
+    summary_accrued_fees: Amount,
+    // Calculated over rolling window on the last registered order activity
+    #[serde(with = "decimal")]
+    order_to_trade_ratio: Decimal,
 }
 
 impl MarketAccountIdStatistic {
@@ -59,8 +85,78 @@ impl MarketAccountIdStatistic {
     fn add_summary_commission(&mut self, commission: Price) {
         self.summary_commission += commission;
     }
+
+    fn add_summary_rebates(&mut self, rebates: Price) {
+        self.summary_rebates += rebates;
+    }
+
+    fn set_order_to_trade_ratio(&mut self, ratio: Decimal) {
+        self.order_to_trade_ratio = ratio;
+    }
+
+    fn add_cash_flow(&mut self, kind: CashFlowKind, amount: Amount) {
+        match kind {
+            CashFlowKind::Funding => self.summary_funding += amount,
+            CashFlowKind::AccruedFee => self.summary_accrued_fees += amount,
+        }
+    }
+
+    /// Copy of statistic with amounts rounded for reporting
+    fn rounded(&self, market_account_id: MarketAccountId, precision: &ReportingPrecision) -> Self {
+        let base = market_account_id.currency_pair.to_codes().base;
+        Self {
+            summary_filled_amount: precision.round_amount(base, self.summary_filled_amount),
+            summary_commission: precision.round_default(self.summary_commission),
+            summary_rebates: precision.round_default(self.summary_rebates),
+            summary_funding: precision.round_default(self.summary_funding),
+            summary_accrued_fees: precision.round_default(self.summary_accrued_fees),
+            order_to_trade_ratio: precision.round_default(self.order_to_trade_ratio),
+            ..*self
+        }
+    }
+
+    /// Statistic for next trading day: counters are reset, current state values are kept
+    fn start_next_day(&self) -> Self {
+        Self {
+            partially_filled_orders_count: self.partially_filled_orders_count,
+            order_to_trade_ratio: self.order_to_trade_ratio,
+            ..Default::default()
+        }
+    }
 }
 
+/// Funding payments and accrued fees of market, paid ones are negative
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CashFlowSummary {
+    #[serde(with = "decimal")]
+    pub funding: Amount,
+    #[serde(with = "decimal")]
+    pub accrued_fees: Amount,
+}
+
+/// Statistics of finished trading day
+#[derive(Debug, Serialize)]
+pub struct DailyStatistics {
+    pub day_start: DateTime,
+    pub day_end: DateTime,
+    pub market_account_id_stats: HashMap<MarketAccountId, MarketAccountIdStatistic>,
+}
+
+impl_event!(DailyStatistics, "daily_statistics");
+
+/// Performance attribution of strategy on market for finished trading day
+#[derive(Debug, Serialize)]
+pub struct DailyPerformanceAttribution {
+    pub day_start: DateTime,
+    pub day_end: DateTime,
+    pub strategy_name: String,
+    pub market_account_id: MarketAccountId,
+    #[serde(flatten)]
+    pub attribution: PerformanceAttribution,
+}
+
+impl_event!(DailyPerformanceAttribution, "daily_performance_attribution");
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DispositionExecutorStatistic {
     skipped_events_amount: u64,
@@ -69,48 +165,93 @@ pub struct DispositionExecutorStatistic {
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub(crate) struct StatisticServiceState {
     market_account_id_stats: RwLock<HashMap<MarketAccountId, MarketAccountIdStatistic>>,
+    trading_day_start: RwLock<Option<DateTime>>,
+    /// Statistics since start of current trading day
+    daily_market_account_id_stats: RwLock<HashMap<MarketAccountId, MarketAccountIdStatistic>>,
     disposition_executor_stats: Mutex<DispositionExecutorStatistic>,
+    warm_up_progress: RwLock<HashMap<MarketAccountId, Vec<DataReadiness>>>,
+    performance_attribution: RwLock<PerformanceAttributionTracker>,
 }
 
 impl StatisticServiceState {
+    fn rounded(&self, precision: &ReportingPrecision) -> Self {
+        Self {
+            market_account_id_stats: RwLock::new(round_market_stats(
+                &self.market_account_id_stats.read(),
+                precision,
+            )),
+            trading_day_start: RwLock::new(*self.trading_day_start.read()),
+            daily_market_account_id_stats: RwLock::new(round_market_stats(
+                &self.daily_market_account_id_stats.read(),
+                precision,
+            )),
+            disposition_executor_stats: Mutex::new(DispositionExecutorStatistic {
+                skipped_events_amount: self.disposition_executor_stats.lock().skipped_events_amount,
+            }),
+            warm_up_progress: RwLock::new(self.warm_up_progress.read().clone()),
+            performance_attribution: RwLock::new(
+                self.performance_attribution.read().rounded(precision),
+            ),
+        }
+    }
+
+    fn update_market_stats(
+        &self,
+        market_account_id: MarketAccountId,
+        update: impl Fn(&mut MarketAccountIdStatistic),
+    ) {
+        update(
+            self.market_account_id_stats
+                .write()
+                .entry(market_account_id)
+                .or_default(),
+        );
+        update(
+            self.daily_market_account_id_stats
+                .write()
+                .entry(market_account_id)
+                .or_default(),
+        );
+    }
+
+    fn start_trading_day(
+        &self,
+        day_start: DateTime,
+    ) -> HashMap<MarketAccountId, MarketAccountIdStatistic> {
+        let mut daily_stats = self.daily_market_account_id_stats.write();
+        let next_day_stats = daily_stats
+            .iter()
+            .map(|(market_account_id, stats)| (*market_account_id, stats.start_next_day()))
+            .collect();
+
+        *self.trading_day_start.write() = Some(day_start);
+        std::mem::replace(&mut *daily_stats, next_day_stats)
+    }
+
     pub(crate) fn register_created_order(&self, market_account_id: MarketAccountId) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .register_created_order();
+        self.update_market_stats(market_account_id, |stats| stats.register_created_order());
     }
 
     pub(crate) fn register_canceled_order(&self, market_account_id: MarketAccountId) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .register_canceled_order();
+        self.update_market_stats(market_account_id, |stats| stats.register_canceled_order());
     }
 
     pub(crate) fn register_partially_filled_order(&self, market_account_id: MarketAccountId) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .increment_partially_filled_orders();
+        self.update_market_stats(market_account_id, |stats| {
+            stats.increment_partially_filled_orders()
+        });
     }
 
     fn decrement_partially_filled_orders(&self, market_account_id: MarketAccountId) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .decrement_partially_filled_orders();
+        self.update_market_stats(market_account_id, |stats| {
+            stats.decrement_partially_filled_orders()
+        });
     }
 
     pub(crate) fn register_completely_filled_order(&self, market_account_id: MarketAccountId) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .increment_completely_filled_orders();
+        self.update_market_stats(market_account_id, |stats| {
+            stats.increment_completely_filled_orders()
+        });
     }
 
     pub(crate) fn register_filled_amount(
@@ -118,11 +259,9 @@ impl StatisticServiceState {
         market_account_id: MarketAccountId,
         filled_amount: Amount,
     ) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .add_summary_filled_amount(filled_amount);
+        self.update_market_stats(market_account_id, |stats| {
+            stats.add_summary_filled_amount(filled_amount)
+        });
     }
 
     pub(crate) fn register_commission(
@@ -130,32 +269,121 @@ impl StatisticServiceState {
         market_account_id: MarketAccountId,
         commission: Price,
     ) {
-        self.market_account_id_stats
-            .write()
-            .entry(market_account_id)
-            .or_default()
-            .add_summary_commission(commission);
+        self.update_market_stats(market_account_id, |stats| {
+            stats.add_summary_commission(commission)
+        });
+    }
+
+    pub(crate) fn register_rebates(&self, market_account_id: MarketAccountId, rebates: Price) {
+        self.update_market_stats(market_account_id, |stats| {
+            stats.add_summary_rebates(rebates)
+        });
+    }
+
+    fn update_order_to_trade_ratio(&self, market_account_id: MarketAccountId, ratio: Decimal) {
+        self.update_market_stats(market_account_id, |stats| {
+            stats.set_order_to_trade_ratio(ratio)
+        });
+    }
+
+    pub(crate) fn register_cash_flow(
+        &self,
+        market_account_id: MarketAccountId,
+        kind: CashFlowKind,
+        amount: Amount,
+    ) {
+        self.update_market_stats(market_account_id, |stats| stats.add_cash_flow(kind, amount));
     }
 
     pub(crate) fn register_skipped_event(&self) {
         self.disposition_executor_stats.lock().skipped_events_amount += 1;
     }
+
+    fn daily_cash_flows(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> HashMap<CurrencyPair, CashFlowSummary> {
+        self.daily_market_account_id_stats
+            .read()
+            .iter()
+            .filter(|(market_account_id, _)| {
+                market_account_id.exchange_account_id == exchange_account_id
+            })
+            .map(|(market_account_id, stats)| {
+                let summary = CashFlowSummary {
+                    funding: stats.summary_funding,
+                    accrued_fees: stats.summary_accrued_fees,
+                };
+                (market_account_id.currency_pair, summary)
+            })
+            .filter(|(_, summary)| *summary != CashFlowSummary::default())
+            .collect()
+    }
+
+    fn update_warm_up_progress(
+        &self,
+        market_account_id: MarketAccountId,
+        progress: Vec<DataReadiness>,
+    ) {
+        let _ = self
+            .warm_up_progress
+            .write()
+            .insert(market_account_id, progress);
+    }
+}
+
+fn round_market_stats(
+    stats: &HashMap<MarketAccountId, MarketAccountIdStatistic>,
+    precision: &ReportingPrecision,
+) -> HashMap<MarketAccountId, MarketAccountIdStatistic> {
+    stats
+        .iter()
+        .map(|(market_account_id, stats)| {
+            (
+                *market_account_id,
+                stats.rounded(*market_account_id, precision),
+            )
+        })
+        .collect()
 }
 
-#[derive(Default, Debug)]
+const DEFAULT_ORDER_TO_TRADE_RATIO_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug)]
 pub struct StatisticService {
     pub(crate) statistic_service_state: StatisticServiceState,
     partially_filled_orders: Mutex<HashSet<ClientOrderId>>,
+    order_to_trade_ratio_window: chrono::Duration,
+    order_to_trade_ratios: Mutex<HashMap<MarketAccountId, OrderToTradeRatioTracker>>,
+    reporting_precision: ReportingPrecision,
 }
 
 impl StatisticService {
-    pub fn new() -> Arc<Self> {
-        Default::default()
+    pub fn new(
+        order_to_trade_ratio_settings: Option<&OrderToTradeRatioSettings>,
+        reporting_precision: ReportingPrecision,
+    ) -> Arc<Self> {
+        let window = order_to_trade_ratio_settings
+            .map(|x| Duration::from_secs(x.window_secs))
+            .unwrap_or(DEFAULT_ORDER_TO_TRADE_RATIO_WINDOW);
+
+        Arc::new(Self {
+            statistic_service_state: Default::default(),
+            partially_filled_orders: Default::default(),
+            order_to_trade_ratio_window: chrono::Duration::from_std(window)
+                .expect("Order to trade ratio window is too big"),
+            order_to_trade_ratios: Default::default(),
+            reporting_precision,
+        })
     }
 
     pub(crate) fn register_created_order(&self, market_account_id: MarketAccountId) {
         self.statistic_service_state
             .register_created_order(market_account_id);
+
+        self.register_order_activity(market_account_id, |tracker, now| {
+            tracker.register_order_request(now)
+        });
     }
 
     pub(crate) fn register_canceled_order(
@@ -166,9 +394,56 @@ impl StatisticService {
         self.statistic_service_state
             .register_canceled_order(market_account_id);
 
+        self.register_order_activity(market_account_id, |tracker, now| {
+            tracker.register_order_request(now)
+        });
+
         self.remove_filled_order_if_exist(market_account_id, client_order_id);
     }
 
+    /// Order request which doesn't change count of orders: amendment, failed creation
+    /// or failed cancellation
+    pub(crate) fn register_order_request(&self, market_account_id: MarketAccountId) {
+        self.register_order_activity(market_account_id, |tracker, now| {
+            tracker.register_order_request(now)
+        });
+    }
+
+    pub(crate) fn register_trade(&self, market_account_id: MarketAccountId) {
+        self.register_order_activity(market_account_id, |tracker, now| {
+            tracker.register_trade(now)
+        });
+    }
+
+    fn register_order_activity(
+        &self,
+        market_account_id: MarketAccountId,
+        register: impl FnOnce(&mut OrderToTradeRatioTracker, DateTime),
+    ) {
+        let now = time_manager::now();
+        let ratio = {
+            let mut trackers = self.order_to_trade_ratios.lock();
+            let tracker = trackers
+                .entry(market_account_id)
+                .or_insert_with(|| OrderToTradeRatioTracker::new(self.order_to_trade_ratio_window));
+            register(tracker, now);
+            tracker.ratio(now)
+        };
+
+        self.statistic_service_state
+            .update_order_to_trade_ratio(market_account_id, ratio);
+    }
+
+    /// Ratio of order requests (creations, amendments and cancellations, including failed ones)
+    /// to trades over the rolling window
+    pub fn order_to_trade_ratio(&self, market_account_id: MarketAccountId) -> Decimal {
+        self.order_to_trade_ratios
+            .lock()
+            .get_mut(&market_account_id)
+            .map(|tracker| tracker.ratio(time_manager::now()))
+            .unwrap_or_default()
+    }
+
     pub(crate) fn register_partially_filled_order(
         &self,
         market_account_id: MarketAccountId,
@@ -189,6 +464,7 @@ impl StatisticService {
         client_order_id: &ClientOrderId,
         filled_amount: Amount,
         commission: Amount,
+        rebates: Amount,
     ) {
         self.statistic_service_state
             .register_completely_filled_order(market_account_id);
@@ -200,6 +476,9 @@ impl StatisticService {
 
         self.statistic_service_state
             .register_commission(market_account_id, commission);
+
+        self.statistic_service_state
+            .register_rebates(market_account_id, rebates);
     }
 
     fn remove_filled_order_if_exist(
@@ -216,9 +495,147 @@ impl StatisticService {
         }
     }
 
+    pub(crate) fn register_cash_flow(&self, cash_flow: &CashFlowEvent) {
+        let market_account_id = cash_flow.market_account_id();
+        self.statistic_service_state.register_cash_flow(
+            market_account_id,
+            cash_flow.kind,
+            cash_flow.amount,
+        );
+
+        let mut attribution = self.statistic_service_state.performance_attribution.write();
+        let codes = market_account_id.currency_pair.to_codes();
+        let amount = if cash_flow.currency_code == codes.quote {
+            Some(cash_flow.amount)
+        } else if cash_flow.currency_code == codes.base {
+            attribution
+                .benchmark_price(market_account_id)
+                .map(|price| cash_flow.amount * price)
+        } else {
+            None
+        };
+
+        match amount {
+            Some(amount) => {
+                attribution.register_cash_flow(market_account_id, cash_flow.kind, amount)
+            }
+            None => log::warn!(
+                "Unable to attribute cash flow in {} on {market_account_id} to strategies",
+                cash_flow.currency_code
+            ),
+        }
+    }
+
+    /// Register the last fill of order in performance attribution of order strategy
+    pub(crate) fn register_fill_attribution(&self, order: &OrderSnapshot) {
+        let Some(fill) = order.fills.fills.last() else {
+            return;
+        };
+
+        let market_account_id = order.header.market_account_id();
+        // Commission is converted to base or quote currency
+        let fee = match fill.converted_commission_currency_code()
+            == market_account_id.currency_pair.to_codes().base
+        {
+            true => fill.converted_commission_amount() * fill.price(),
+            false => fill.converted_commission_amount(),
+        };
+
+        self.statistic_service_state
+            .performance_attribution
+            .write()
+            .register_fill(
+                &order.header.strategy_name,
+                market_account_id,
+                fill.side().unwrap_or(order.header.side),
+                fill.price(),
+                fill.amount(),
+                fee,
+                fill.book_context().and_then(|x| x.mid_price),
+            );
+    }
+
+    pub(crate) fn register_mark_price(&self, mark_price: &MarkPriceEvent) {
+        if let Some(index_price) = mark_price.index_price {
+            self.statistic_service_state
+                .performance_attribution
+                .write()
+                .update_index_price(mark_price.market_account_id(), index_price);
+        }
+    }
+
+    pub(crate) fn register_order_book(&self, order_book: &OrderBookEvent) {
+        let data = &order_book.data;
+        let top_ask = data.asks.keys().next();
+        let top_bid = data.bids.keys().next_back();
+        if let Some((ask, bid)) = top_ask.zip(top_bid) {
+            self.statistic_service_state
+                .performance_attribution
+                .write()
+                .update_mid_price(order_book.market_account_id(), (ask + bid) / Decimal::TWO);
+        }
+    }
+
     pub(crate) fn register_skipped_event(&self) {
         self.statistic_service_state.register_skipped_event();
     }
+
+    /// Reset daily counters and return statistics of previous trading day rounded for reporting
+    pub(crate) fn start_trading_day(
+        &self,
+        day_start: DateTime,
+    ) -> HashMap<MarketAccountId, MarketAccountIdStatistic> {
+        let stats = self.statistic_service_state.start_trading_day(day_start);
+        round_market_stats(&stats, &self.reporting_precision)
+    }
+
+    /// Reset daily performance attribution and return attribution of previous trading day
+    /// by strategy name and market rounded for reporting
+    pub(crate) fn start_performance_attribution_day(
+        &self,
+    ) -> HashMap<String, HashMap<MarketAccountId, PerformanceAttribution>> {
+        let attribution = self
+            .statistic_service_state
+            .performance_attribution
+            .write()
+            .start_trading_day();
+        round_attribution(attribution, &self.reporting_precision)
+    }
+
+    /// Funding and accrued fees by markets of exchange account since start of current trading day
+    /// rounded for reporting
+    pub(crate) fn daily_cash_flows(
+        &self,
+        exchange_account_id: ExchangeAccountId,
+    ) -> HashMap<CurrencyPair, CashFlowSummary> {
+        let precision = &self.reporting_precision;
+        self.statistic_service_state
+            .daily_cash_flows(exchange_account_id)
+            .into_iter()
+            .map(|(currency_pair, summary)| {
+                let summary = CashFlowSummary {
+                    funding: precision.round_default(summary.funding),
+                    accrued_fees: precision.round_default(summary.accrued_fees),
+                };
+                (currency_pair, summary)
+            })
+            .collect()
+    }
+
+    /// Current statistics with amounts rounded for reporting
+    pub(crate) fn rounded_state(&self) -> StatisticServiceState {
+        self.statistic_service_state
+            .rounded(&self.reporting_precision)
+    }
+
+    pub(crate) fn register_warm_up_progress(
+        &self,
+        market_account_id: MarketAccountId,
+        progress: Vec<DataReadiness>,
+    ) {
+        self.statistic_service_state
+            .update_warm_up_progress(market_account_id, progress);
+    }
 }
 
 pub struct StatisticEventHandler {
@@ -227,7 +644,7 @@ pub struct StatisticEventHandler {
 
 impl StatisticEventHandler {
     pub fn new(
-        events_receiver: broadcast::Receiver<ExchangeEvent>,
+        events_receiver: ConflatedEventsReceiver,
         stats: Arc<StatisticService>,
     ) -> Arc<Self> {
         let statistic_event_handler = Arc::new(Self { stats });
@@ -243,7 +660,7 @@ impl StatisticEventHandler {
 
     pub async fn start(
         self: Arc<Self>,
-        mut events_receiver: broadcast::Receiver<ExchangeEvent>,
+        mut events_receiver: ConflatedEventsReceiver,
     ) -> Result<()> {
         loop {
             let event = events_receiver
@@ -269,24 +686,38 @@ impl StatisticEventHandler {
                     OrderEventType::CreateOrderSucceeded => {
                         self.stats.register_created_order(market_account_id);
                     }
+                    OrderEventType::CreateOrderFailed
+                    | OrderEventType::CancelOrderFailed
+                    | OrderEventType::AmendOrderSucceeded
+                    | OrderEventType::AmendOrderFailed => {
+                        self.stats.register_order_request(market_account_id);
+                    }
                     OrderEventType::CancelOrderSucceeded => {
                         let client_order_id = order_event.order.client_order_id();
                         self.stats
                             .register_canceled_order(market_account_id, &client_order_id);
                     }
                     OrderEventType::OrderFilled { cloned_order } => {
+                        self.stats.register_trade(market_account_id);
+                        self.stats.register_fill_attribution(&cloned_order);
                         self.stats.register_partially_filled_order(
                             market_account_id,
                             &cloned_order.header.client_order_id,
                         );
                     }
                     OrderEventType::OrderCompleted { cloned_order } => {
-                        let commission = cloned_order
-                            .fills
-                            .fills
-                            .iter()
-                            .map(|fill| fill.commission_amount())
-                            .sum();
+                        // Negative commission of fill is a maker rebate
+                        let (commission, rebates) = cloned_order.fills.fills.iter().fold(
+                            (Amount::ZERO, Amount::ZERO),
+                            |(commission, rebates), fill| {
+                                let amount = fill.commission_amount();
+                                if amount.is_sign_negative() {
+                                    (commission, rebates - amount)
+                                } else {
+                                    (commission + amount, rebates)
+                                }
+                            },
+                        );
 
                         let filled_amount = cloned_order.fills.filled_amount;
 
@@ -295,14 +726,159 @@ impl StatisticEventHandler {
                             &cloned_order.header.client_order_id,
                             filled_amount,
                             commission,
+                            rebates,
                         );
                     }
                     _ => nothing_to_do(),
                 }
             }
+            ExchangeEvent::CashFlow(cash_flow) => self.stats.register_cash_flow(&cash_flow),
+            ExchangeEvent::MarkPrice(mark_price) => self.stats.register_mark_price(&mark_price),
+            ExchangeEvent::OrderBookEvent(order_book) => {
+                self.stats.register_order_book(&order_book)
+            }
             _ => nothing_to_do(),
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use mmb_domain::order::event::OrderEvent;
+    use mmb_domain::order::pool::{OrderRef, OrdersPool};
+    use mmb_domain::order::snapshot::{OrderHeader, OrderSide, UserOrder};
+    use rust_decimal_macros::dec;
+
+    fn order_event(order: &OrderRef, event_type: OrderEventType) -> ExchangeEvent {
+        ExchangeEvent::OrderEvent(OrderEvent::new(order.clone(), event_type))
+    }
+
+    fn create_order() -> OrderRef {
+        let header = OrderHeader::with_user_order(
+            "test".into(),
+            ExchangeAccountId::new("Binance", 0),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(0.5)),
+            None,
+            None,
+            "".to_string(),
+        );
+        OrdersPool::new().add_simple_initial(&header, Utc::now(), None)
+    }
+
+    #[test]
+    fn amendments_are_counted_as_order_requests() {
+        let statistics = StatisticService::new(None, ReportingPrecision::default());
+        let handler = StatisticEventHandler {
+            stats: statistics.clone(),
+        };
+        let order = create_order();
+        let market_account_id =
+            MarketAccountId::new(order.exchange_account_id(), order.currency_pair());
+
+        for event_type in [
+            OrderEventType::CreateOrderSucceeded,
+            OrderEventType::AmendOrderSucceeded,
+            OrderEventType::AmendOrderSucceeded,
+            OrderEventType::CancelOrderSucceeded,
+        ] {
+            handler
+                .handle_event(order_event(&order, event_type))
+                .expect("in test");
+        }
+
+        assert_eq!(statistics.order_to_trade_ratio(market_account_id), dec!(4));
+    }
+
+    #[test]
+    fn failed_order_requests_are_counted() {
+        let statistics = StatisticService::new(None, ReportingPrecision::default());
+        let handler = StatisticEventHandler {
+            stats: statistics.clone(),
+        };
+        let order = create_order();
+        let market_account_id =
+            MarketAccountId::new(order.exchange_account_id(), order.currency_pair());
+
+        for event_type in [
+            OrderEventType::CreateOrderFailed,
+            OrderEventType::AmendOrderFailed,
+            OrderEventType::CancelOrderFailed,
+        ] {
+            handler
+                .handle_event(order_event(&order, event_type))
+                .expect("in test");
+        }
+
+        assert_eq!(statistics.order_to_trade_ratio(market_account_id), dec!(3));
+    }
+
+    fn cash_flow(
+        exchange_account_id: ExchangeAccountId,
+        kind: CashFlowKind,
+        amount: Amount,
+    ) -> CashFlowEvent {
+        CashFlowEvent {
+            exchange_account_id,
+            currency_pair: CurrencyPair::from_codes("btc".into(), "usdt".into()),
+            currency_code: "usdt".into(),
+            kind,
+            amount,
+            transaction_time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn daily_cash_flows_are_broken_out_by_kind() {
+        let statistics = StatisticService::new(None, ReportingPrecision::default());
+        let exchange_account_id = ExchangeAccountId::new("Bitmex", 0);
+        let other_exchange_account_id = ExchangeAccountId::new("Bitmex", 1);
+
+        statistics.register_cash_flow(&cash_flow(
+            exchange_account_id,
+            CashFlowKind::Funding,
+            dec!(-1.5),
+        ));
+        statistics.register_cash_flow(&cash_flow(
+            exchange_account_id,
+            CashFlowKind::Funding,
+            dec!(0.5),
+        ));
+        statistics.register_cash_flow(&cash_flow(
+            exchange_account_id,
+            CashFlowKind::AccruedFee,
+            dec!(-0.1),
+        ));
+        statistics.register_cash_flow(&cash_flow(
+            other_exchange_account_id,
+            CashFlowKind::Funding,
+            dec!(7),
+        ));
+
+        let currency_pair = CurrencyPair::from_codes("btc".into(), "usdt".into());
+        let expected = CashFlowSummary {
+            funding: dec!(-1),
+            accrued_fees: dec!(-0.1),
+        };
+        assert_eq!(
+            statistics.daily_cash_flows(exchange_account_id),
+            HashMap::from([(currency_pair, expected)])
+        );
+
+        // cash flows of finished day are saved in daily statistics and aren't reported anymore
+        let daily_stats = statistics.start_trading_day(Utc::now());
+        let market_account_id = MarketAccountId::new(exchange_account_id, currency_pair);
+        assert_eq!(daily_stats[&market_account_id].summary_funding, dec!(-1));
+        assert_eq!(
+            daily_stats[&market_account_id].summary_accrued_fees,
+            dec!(-0.1)
+        );
+        assert!(statistics.daily_cash_flows(exchange_account_id).is_empty());
+    }
+}
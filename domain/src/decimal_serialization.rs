@@ -0,0 +1,374 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::fmt::{self, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalFormat {
+    /// JSON string, value is kept exactly
+    #[default]
+    String,
+    /// JSON number, value is converted to `f64`, so precision can be lost
+    Number,
+}
+
+/// Count of decimal places of values by default
+pub const DEFAULT_DECIMALS: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecimalPrecision {
+    /// Values are rounded or padded by zeros to fixed count of decimal places
+    Fixed(u32),
+    /// Scale of value is kept
+    Exact,
+}
+
+impl Default for DecimalPrecision {
+    fn default() -> Self {
+        DecimalPrecision::Fixed(DEFAULT_DECIMALS)
+    }
+}
+
+/// Format of decimal values in RPC responses and visualization API, strings with
+/// `DEFAULT_DECIMALS` decimal places by default.
+/// Values are deserialized from both strings and numbers whatever format is set,
+/// so data written before changing of format can be read
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecimalSerialization {
+    pub format: DecimalFormat,
+    /// Set as `{ fixed = 4 }` or `"exact"` in settings
+    pub precision: DecimalPrecision,
+}
+
+thread_local! {
+    static SCOPED_DECIMAL_SERIALIZATION: Cell<Option<DecimalSerialization>> = Cell::new(None);
+}
+
+/// Restores format of outer scope even if serialization panics
+struct ScopeGuard(Option<DecimalSerialization>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPED_DECIMAL_SERIALIZATION.with(|x| x.set(self.0));
+    }
+}
+
+impl DecimalSerialization {
+    /// Serialization outside of any scope, so JSON saved to database by event recorder
+    /// keeps full precision
+    pub const EXACT: DecimalSerialization = DecimalSerialization {
+        format: DecimalFormat::String,
+        precision: DecimalPrecision::Exact,
+    };
+
+    /// Apply format to decimal fields marked by helpers of this module which are
+    /// serialized inside of `serialize`. Outside of it these fields are serialized as `EXACT`
+    pub fn scope<R>(self, serialize: impl FnOnce() -> R) -> R {
+        let _guard = ScopeGuard(SCOPED_DECIMAL_SERIALIZATION.with(|x| x.replace(Some(self))));
+        serialize()
+    }
+
+    fn current() -> Self {
+        SCOPED_DECIMAL_SERIALIZATION
+            .with(|x| x.get())
+            .unwrap_or(Self::EXACT)
+    }
+
+    fn serialize_decimal<S: Serializer>(
+        &self,
+        value: &Decimal,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut value = *value;
+        if let DecimalPrecision::Fixed(decimals) = self.precision {
+            value = value.round_dp(decimals);
+            value.rescale(decimals);
+        }
+
+        match self.format {
+            DecimalFormat::String => serializer.serialize_str(&value.to_string()),
+            DecimalFormat::Number => {
+                let value = value.to_f64().ok_or_else(|| {
+                    serde::ser::Error::custom(format!("{value} can't be converted to f64"))
+                })?;
+                serializer.serialize_f64(value)
+            }
+        }
+    }
+}
+
+struct DecimalVisitor;
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("decimal as string or number")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Decimal::from_str(value)
+            .or_else(|_| Decimal::from_scientific(value))
+            .map_err(|_| E::custom(format!("invalid decimal {value}")))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Decimal::from(value))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Decimal::from(value))
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        Decimal::try_from(value).map_err(|_| E::custom(format!("invalid decimal {value}")))
+    }
+}
+
+/// Decimal serialized by format of current scope
+struct FormattedDecimal(Decimal);
+
+impl Serialize for FormattedDecimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DecimalSerialization::current().serialize_decimal(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FormattedDecimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DecimalVisitor).map(Self)
+    }
+}
+
+/// Usage: `#[serde(with = "mmb_domain::decimal_serialization::decimal")]`
+pub mod decimal {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        DecimalSerialization::current().serialize_decimal(value, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+/// Usage: `#[serde(with = "mmb_domain::decimal_serialization::option_decimal")]`
+pub mod option_decimal {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Decimal>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.map(FormattedDecimal).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Decimal>, D::Error> {
+        Option::<FormattedDecimal>::deserialize(deserializer).map(|x| x.map(|x| x.0))
+    }
+}
+
+/// Price levels as pairs of price and amount.
+/// Usage: `#[serde(with = "mmb_domain::decimal_serialization::decimal_levels")]`
+pub mod decimal_levels {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &[(Decimal, Decimal)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            value
+                .iter()
+                .map(|(price, amount)| (FormattedDecimal(*price), FormattedDecimal(*amount))),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(Decimal, Decimal)>, D::Error> {
+        Vec::<(FormattedDecimal, FormattedDecimal)>::deserialize(deserializer).map(|x| {
+            x.into_iter()
+                .map(|(price, amount)| (price.0, amount.0))
+                .collect()
+        })
+    }
+}
+
+/// Usage: `#[serde(with = "mmb_domain::decimal_serialization::option_decimal_level")]`
+pub mod option_decimal_level {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<(Decimal, Decimal)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value
+            .map(|(price, amount)| (FormattedDecimal(price), FormattedDecimal(amount)))
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<(Decimal, Decimal)>, D::Error> {
+        Option::<(FormattedDecimal, FormattedDecimal)>::deserialize(deserializer)
+            .map(|x| x.map(|(price, amount)| (price.0, amount.0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    fn serialize(policy: DecimalSerialization, value: Decimal) -> serde_json::Value {
+        policy
+            .serialize_decimal(&value, serde_json::value::Serializer)
+            .expect("in test")
+    }
+
+    #[test]
+    fn decimal_is_serialized_by_format() {
+        let string = DecimalSerialization {
+            format: DecimalFormat::String,
+            precision: DecimalPrecision::Fixed(4),
+        };
+        assert_eq!(serialize(string, dec!(1.5)), json!("1.5000"));
+        assert_eq!(serialize(string, dec!(0.123456)), json!("0.1235"));
+
+        let number = DecimalSerialization {
+            format: DecimalFormat::Number,
+            precision: DecimalPrecision::Exact,
+        };
+        assert_eq!(serialize(number, dec!(1.25)), json!(1.25));
+
+        assert_eq!(
+            serialize(DecimalSerialization::default(), dec!(1.5)),
+            json!("1.50000000")
+        );
+        assert_eq!(
+            serialize(DecimalSerialization::EXACT, dec!(1.50)),
+            json!("1.50")
+        );
+    }
+
+    #[test]
+    fn decimal_is_rounded_only_inside_of_scope() {
+        #[derive(Serialize)]
+        struct Value {
+            #[serde(with = "decimal")]
+            value: Decimal,
+            #[serde(with = "option_decimal")]
+            optional: Option<Decimal>,
+        }
+
+        let value = Value {
+            value: dec!(0.123456),
+            optional: Some(dec!(1.5)),
+        };
+        let rounded = DecimalSerialization {
+            format: DecimalFormat::String,
+            precision: DecimalPrecision::Fixed(2),
+        };
+
+        let to_json = || serde_json::to_value(&value).expect("in test");
+        assert_eq!(
+            rounded.scope(to_json),
+            json!({"value": "0.12", "optional": "1.50"})
+        );
+        assert_eq!(to_json(), json!({"value": "0.123456", "optional": "1.5"}));
+    }
+
+    #[test]
+    fn price_levels_are_serialized_by_format_of_scope() {
+        #[derive(Serialize, Deserialize)]
+        struct Value {
+            #[serde(with = "decimal_levels")]
+            levels: Vec<(Decimal, Decimal)>,
+            #[serde(with = "option_decimal_level")]
+            best: Option<(Decimal, Decimal)>,
+        }
+
+        let value = Value {
+            levels: vec![(dec!(1.5), dec!(2)), (dec!(1.25), dec!(0.5))],
+            best: Some((dec!(1.5), dec!(2))),
+        };
+        let rounded = DecimalSerialization {
+            format: DecimalFormat::String,
+            precision: DecimalPrecision::Fixed(1),
+        };
+
+        let json = rounded.scope(|| serde_json::to_value(&value).expect("in test"));
+        assert_eq!(
+            json,
+            json!({"levels": [["1.5", "2.0"], ["1.2", "0.5"]], "best": ["1.5", "2.0"]})
+        );
+
+        let parsed = serde_json::from_value::<Value>(json!({"levels": [[1.5, "2"]], "best": null}))
+            .expect("in test");
+        assert_eq!(parsed.levels, vec![(dec!(1.5), dec!(2))]);
+        assert_eq!(parsed.best, None);
+    }
+
+    #[test]
+    fn settings_are_fixed_precision_strings_by_default() {
+        let parse = |json: serde_json::Value| {
+            serde_json::from_value::<DecimalSerialization>(json).expect("in test")
+        };
+
+        assert_eq!(
+            parse(json!({})),
+            DecimalSerialization {
+                format: DecimalFormat::String,
+                precision: DecimalPrecision::Fixed(DEFAULT_DECIMALS),
+            }
+        );
+        assert_eq!(
+            parse(json!({"precision": {"fixed": 2}})).precision,
+            DecimalPrecision::Fixed(2)
+        );
+        assert_eq!(
+            parse(json!({"format": "number", "precision": "exact"})),
+            DecimalSerialization {
+                format: DecimalFormat::Number,
+                precision: DecimalPrecision::Exact,
+            }
+        );
+    }
+
+    #[test]
+    fn decimal_is_deserialized_from_string_and_number() {
+        #[derive(Deserialize)]
+        struct Value {
+            #[serde(with = "decimal")]
+            value: Decimal,
+            #[serde(default, with = "option_decimal")]
+            optional: Option<Decimal>,
+        }
+
+        let parse = |json: serde_json::Value| {
+            let value = serde_json::from_value::<Value>(json).expect("in test");
+            (value.value, value.optional)
+        };
+
+        assert_eq!(parse(json!({"value": "1.5"})), (dec!(1.5), None));
+        assert_eq!(
+            parse(json!({"value": 2, "optional": "1e-3"})),
+            (dec!(2), Some(dec!(0.001)))
+        );
+        assert_eq!(
+            parse(json!({"value": 0.25, "optional": null})),
+            (dec!(0.25), None)
+        );
+    }
+}
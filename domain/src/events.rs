@@ -11,7 +11,7 @@ use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use tokio::sync::broadcast;
 
-use crate::market::{CurrencyCode, CurrencyPair, ExchangeAccountId};
+use crate::market::{CurrencyCode, CurrencyPair, ExchangeAccountId, MarketAccountId};
 use crate::order::event::OrderEvent;
 use crate::order::snapshot::{Amount, OrderSide, OrderStatus, Price};
 use crate::order_book::event::OrderBookEvent;
@@ -86,6 +86,37 @@ impl LiquidationPriceEvent {
     }
 }
 
+/// Sensitivities of option price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Greeks {
+    pub delta: Decimal,
+    pub gamma: Decimal,
+    pub vega: Decimal,
+    pub theta: Decimal,
+    pub rho: Decimal,
+}
+
+/// Mark price of derivative instrument calculated by exchange.
+/// Greeks and implied volatility are specified only for options, funding rate - for perpetuals
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkPriceEvent {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub mark_price: Price,
+    pub index_price: Option<Price>,
+    pub implied_volatility: Option<Decimal>,
+    pub greeks: Option<Greeks>,
+    /// Current funding rate per funding period
+    pub funding_rate: Option<Decimal>,
+    pub time: DateTime,
+}
+
+impl MarkPriceEvent {
+    pub fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.exchange_account_id, self.currency_pair)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Eq)]
 pub enum TradeId {
     Number(u64),
@@ -93,10 +124,10 @@ pub enum TradeId {
 }
 
 impl TradeId {
-    pub fn number(&self) -> u64 {
+    pub fn number(&self) -> Option<u64> {
         match self {
-            TradeId::Number(number) => *number,
-            TradeId::String(_) => panic!("Unable to get number from string trade id"),
+            TradeId::Number(number) => Some(*number),
+            TradeId::String(_) => None,
         }
     }
 
@@ -212,13 +243,68 @@ pub struct TradesEvent {
 
 impl_event!(TradesEvent, "trades_events");
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum CashFlowKind {
+    /// Periodic payment between long and short sides of perpetual contract
+    Funding,
+    /// Fee charged by exchange outside of trades (e.g. borrowing interest)
+    AccruedFee,
+}
+
+/// Balance change which is not caused by order fill
+#[derive(Debug, Clone, Serialize)]
+pub struct CashFlowEvent {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPair,
+    pub currency_code: CurrencyCode,
+    pub kind: CashFlowKind,
+    /// Positive value means income, negative - expense
+    pub amount: Amount,
+    /// Transaction time received from exchange
+    pub transaction_time: DateTime,
+}
+
+impl_event!(CashFlowEvent, "cash_flow_events");
+
+impl CashFlowEvent {
+    pub fn market_account_id(&self) -> MarketAccountId {
+        MarketAccountId::new(self.exchange_account_id, self.currency_pair)
+    }
+}
+
+/// Liveness of engine subsystem on the moment of heartbeat
+#[derive(Debug, Clone, Serialize)]
+pub struct SubsystemLiveness {
+    pub name: String,
+    pub is_alive: bool,
+    /// Time of last activity of subsystem if subsystem tracks it
+    pub last_activity_time: Option<DateTime>,
+}
+
+/// Periodic event with liveness of subsystems.
+/// It is sent even if there is no market activity, so consumers can detect silently stalled subsystems
+#[derive(Debug, Clone, Serialize)]
+pub struct HeartbeatEvent {
+    pub time: DateTime,
+    pub subsystems: Vec<SubsystemLiveness>,
+}
+
+impl HeartbeatEvent {
+    pub fn stalled_subsystems(&self) -> impl Iterator<Item = &SubsystemLiveness> {
+        self.subsystems.iter().filter(|x| !x.is_alive)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ExchangeEvent {
     OrderBookEvent(OrderBookEvent),
     OrderEvent(OrderEvent),
     BalanceUpdate(BalanceUpdateEvent),
     LiquidationPrice(LiquidationPriceEvent),
+    MarkPrice(MarkPriceEvent),
     Trades(TradesEvent),
+    CashFlow(CashFlowEvent),
+    Heartbeat(HeartbeatEvent),
 }
 
 pub struct ExchangeEvents {
@@ -233,6 +319,10 @@ impl ExchangeEvents {
     pub fn get_events_channel(&self) -> broadcast::Receiver<ExchangeEvent> {
         self.events_sender.subscribe()
     }
+
+    pub fn get_events_sender(&self) -> broadcast::Sender<ExchangeEvent> {
+        self.events_sender.clone()
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Copy)]
@@ -241,6 +331,9 @@ pub enum AllowedEventSourceType {
     All,
     FallbackOnly,
     NonFallback,
+    /// Order lifecycle is tracked by websocket events only, Rest responses and fallback
+    /// can only report failures, e.g. for requests rejected before reaching exchange
+    WebSocketOnly,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Hash)]
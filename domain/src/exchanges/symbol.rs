@@ -3,7 +3,9 @@ use std::hash::{Hash, Hasher};
 use crate::market::{powi, CurrencyCode, CurrencyId, CurrencyPair};
 use crate::order::snapshot::OrderSide;
 use crate::order::snapshot::{Amount, Price};
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use mmb_utils::checked_decimal::CheckedDecimal;
+use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 use rust_decimal::MathematicalOps;
 use rust_decimal_macros::dec;
@@ -53,6 +55,85 @@ impl Precision {
     }
 }
 
+/// Kind of contract which defines how order amount is converted into balance currency
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub enum ContractType {
+    Spot,
+    /// Amount is specified in base currency, PnL is in quote currency
+    Linear,
+    /// Amount is specified in quote currency (e.g. USD for XBTUSD), PnL is in base currency
+    Inverse,
+    /// Amount is specified in contracts, PnL is in balance currency which is neither base nor quote currency
+    /// and equals to `amount_multiplier * price` per contract
+    Quanto,
+}
+
+impl ContractType {
+    fn detect(
+        is_derivative: bool,
+        base_currency_code: CurrencyCode,
+        quote_currency_code: CurrencyCode,
+        balance_currency_code: Option<CurrencyCode>,
+    ) -> Self {
+        if !is_derivative {
+            return ContractType::Spot;
+        }
+
+        match balance_currency_code {
+            Some(code) if code == base_currency_code => ContractType::Inverse,
+            None => ContractType::Linear,
+            Some(code) if code == quote_currency_code => ContractType::Linear,
+            Some(_) => ContractType::Quanto,
+        }
+    }
+}
+
+/// Band of allowed order prices relative to reference price (e.g. Binance `PERCENT_PRICE` filter).
+/// Order price should be in range `[reference_price * multiplier_down, reference_price * multiplier_up]`
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub struct PriceBandFilter {
+    pub multiplier_up: Decimal,
+    pub multiplier_down: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub struct OptionContract {
+    pub kind: OptionKind,
+    pub strike: Price,
+}
+
+/// Expiration of futures and options. Perpetual contracts and spot markets don't have it
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize)]
+pub struct InstrumentExpiry {
+    pub expiration: DateTime,
+    /// Only for options
+    pub option: Option<OptionContract>,
+}
+
+impl InstrumentExpiry {
+    /// Suffix which distinguishes currency pairs of instruments with the same base and quote currencies
+    /// in ccxt like format, e.g. `240329-60000-c` for call option with strike 60000 expiring at 2024-03-29
+    pub fn currency_pair_suffix(&self) -> String {
+        let expiration = self.expiration.format("%y%m%d");
+        match self.option {
+            None => expiration.to_string(),
+            Some(option) => {
+                let kind = match option.kind {
+                    OptionKind::Call => "c",
+                    OptionKind::Put => "p",
+                };
+                format!("{expiration}-{}-{kind}", option.strike.normalize())
+            }
+        }
+    }
+}
+
 /// Metadata for a currency pair
 #[derive(Debug, Clone, Eq, Serialize)]
 pub struct Symbol {
@@ -72,7 +153,12 @@ pub struct Symbol {
     /// Currency which is used for specifying order's amount
     pub amount_currency_code: CurrencyCode,
     pub balance_currency_code: Option<CurrencyCode>,
+    /// Contract multiplier: amount of one contract in amount currency
     pub amount_multiplier: Decimal,
+    pub contract_type: ContractType,
+    /// Allowed deviation of order price from reference price on exchange
+    pub price_band: Option<PriceBandFilter>,
+    pub expiry: Option<InstrumentExpiry>,
 
     pub price_precision: Precision,
     pub amount_precision: Precision,
@@ -118,14 +204,157 @@ impl Symbol {
             min_cost,
             balance_currency_code,
             amount_multiplier: dec!(1),
+            contract_type: ContractType::detect(
+                is_derivative,
+                base_currency_code,
+                quote_currency_code,
+                balance_currency_code,
+            ),
+            price_band: None,
+            expiry: None,
             price_precision,
             amount_precision,
         }
     }
 
+    /// Overrides contract type detected by currency codes and sets contract multiplier
+    pub fn with_contract(
+        mut self,
+        contract_type: ContractType,
+        amount_multiplier: Decimal,
+    ) -> Self {
+        self.contract_type = contract_type;
+        self.amount_multiplier = amount_multiplier;
+        self
+    }
+
+    pub fn with_price_band(mut self, price_band: PriceBandFilter) -> Self {
+        self.price_band = Some(price_band);
+        self
+    }
+
+    pub fn with_expiry(mut self, expiry: InstrumentExpiry) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    pub fn is_expired(&self, now: DateTime) -> bool {
+        self.expiry.map_or(false, |expiry| expiry.expiration <= now)
+    }
+
+    /// Range of prices allowed by exchange for placing order when current reference price
+    /// (usually mid price of order book) is `reference_price`.
+    /// Bounds are rounded inside the range by price precision.
+    pub fn allowed_price_band(
+        &self,
+        reference_price: Option<Price>,
+    ) -> (Option<Price>, Option<Price>) {
+        let (mut min_price, mut max_price) = (self.min_price, self.max_price);
+
+        if let (Some(price_band), Some(reference_price)) = (self.price_band, reference_price) {
+            let band_min =
+                self.price_round(reference_price * price_band.multiplier_down, Round::Ceiling);
+            let band_max =
+                self.price_round(reference_price * price_band.multiplier_up, Round::Floor);
+            min_price = Some(min_price.map_or(band_min, |x| x.max(band_min)));
+            max_price = Some(max_price.map_or(band_max, |x| x.min(band_max)));
+        }
+
+        // zero means that limit isn't specified on exchange
+        let not_zero = |price: Price| !price.is_zero();
+        (min_price.filter(not_zero), max_price.filter(not_zero))
+    }
+
+    /// Clamp price into range allowed by exchange, so order won't be rejected by price filters
+    pub fn clamp_price(&self, price: Price, reference_price: Option<Price>) -> Price {
+        let (min_price, max_price) = self.allowed_price_band(reference_price);
+        let price = min_price.map_or(price, |min_price| price.max(min_price));
+        max_price.map_or(price, |max_price| price.min(max_price))
+    }
+
+    /// Check that price is in range allowed by exchange
+    pub fn validate_price(&self, price: Price, reference_price: Option<Price>) -> Result<()> {
+        let (min_price, max_price) = self.allowed_price_band(reference_price);
+        let currency_pair = self.currency_pair();
+
+        if let Some(min_price) = min_price {
+            if price < min_price {
+                bail!(
+                    "Price {price} is less than min allowed price {min_price} for {currency_pair}"
+                );
+            }
+        }
+
+        if let Some(max_price) = max_price {
+            if price > max_price {
+                bail!("Price {price} is greater than max allowed price {max_price} for {currency_pair}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Value of order amount in balance currency (quote currency for spot)
+    pub fn contract_value(&self, amount: Amount, price: Price) -> Amount {
+        let amount = amount * self.amount_multiplier;
+        match self.contract_type {
+            ContractType::Spot | ContractType::Linear | ContractType::Quanto => amount * price,
+            ContractType::Inverse => amount / price,
+        }
+    }
+
+    /// Order amount (in contracts for derivatives with multiplier) which has specified value
+    /// in balance currency, i.e. inverse of `contract_value`
+    pub fn amount_by_contract_value(&self, value: Amount, price: Price) -> Result<Amount> {
+        if price.is_zero() || self.amount_multiplier.is_zero() {
+            bail!(
+                "Unable to calculate amount by contract value {value} for {} with price {price} and multiplier {}",
+                self.currency_pair(),
+                self.amount_multiplier
+            );
+        }
+
+        let amount = match self.contract_type {
+            ContractType::Spot | ContractType::Linear | ContractType::Quanto => value / price,
+            ContractType::Inverse => value * price,
+        };
+        Ok(amount / self.amount_multiplier)
+    }
+
+    /// PnL in balance currency (quote currency for spot) of position with signed amount
+    /// (positive for long) which was opened by `entry_price` and closed by `exit_price`
+    pub fn position_pnl(&self, position: Amount, entry_price: Price, exit_price: Price) -> Amount {
+        let position = position * self.amount_multiplier;
+        match self.contract_type {
+            ContractType::Spot | ContractType::Linear | ContractType::Quanto => {
+                position * (exit_price - entry_price)
+            }
+            ContractType::Inverse => {
+                position * (Decimal::ONE / entry_price - Decimal::ONE / exit_price)
+            }
+        }
+    }
+
+    /// Currency in which PnL of position is calculated
+    pub fn pnl_currency_code(&self) -> CurrencyCode {
+        match self.contract_type {
+            ContractType::Spot => self.quote_currency_code,
+            _ => self
+                .balance_currency_code
+                .unwrap_or(self.quote_currency_code),
+        }
+    }
+
     // Currency pair in unified for crate format
     pub fn currency_pair(&self) -> CurrencyPair {
-        CurrencyPair::from_codes(self.base_currency_code, self.quote_currency_code)
+        match &self.expiry {
+            None => CurrencyPair::from_codes(self.base_currency_code, self.quote_currency_code),
+            Some(expiry) => CurrencyPair::from_codes_with_suffix(
+                self.base_currency_code,
+                self.quote_currency_code,
+                &expiry.currency_pair_suffix(),
+            ),
+        }
     }
 
     pub const fn get_trade_code(&self, side: OrderSide, before_after: BeforeAfter) -> CurrencyCode {
@@ -269,41 +498,74 @@ impl Symbol {
         amount_in_amount_currency_code: Amount,
         currency_pair_price: Price,
     ) -> Amount {
+        self.try_convert_amount_from_amount_currency_code(
+            to_currency_code,
+            amount_in_amount_currency_code,
+            currency_pair_price,
+        )
+        .unwrap_or_else(|error| panic!("{error:?}"))
+    }
+
+    /// Balance currency of quanto contract is neither base nor quote currency, but value
+    /// of contract is quoted like linear one, so balance currency is converted as quote currency
+    fn pair_currency_code(&self, currency_code: CurrencyCode) -> CurrencyCode {
+        match self.contract_type {
+            ContractType::Quanto if Some(currency_code) == self.balance_currency_code => {
+                self.quote_currency_code
+            }
+            _ => currency_code,
+        }
+    }
+
+    /// Same as `convert_amount_from_amount_currency_code` but returns error on zero price or overflow
+    pub fn try_convert_amount_from_amount_currency_code(
+        &self,
+        to_currency_code: CurrencyCode,
+        amount_in_amount_currency_code: Amount,
+        currency_pair_price: Price,
+    ) -> Result<Amount> {
+        let to_currency_code = self.pair_currency_code(to_currency_code);
         if to_currency_code == self.amount_currency_code {
-            return amount_in_amount_currency_code;
+            return Ok(amount_in_amount_currency_code);
         }
 
         if to_currency_code == self.base_currency_code {
-            return amount_in_amount_currency_code / currency_pair_price;
+            return amount_in_amount_currency_code.try_div(currency_pair_price);
         }
 
         if to_currency_code == self.quote_currency_code {
-            return amount_in_amount_currency_code * currency_pair_price;
+            return amount_in_amount_currency_code.try_mul(currency_pair_price);
         }
 
         let currency_pair = self.currency_pair();
-        panic!("Currency code {to_currency_code} outside currency pair {currency_pair} is not supported");
+        bail!("Currency code {to_currency_code} outside currency pair {currency_pair} is not supported");
     }
 
-    pub fn convert_amount_from_balance_currency_code(
+    /// Returns error on zero price or overflow
+    pub fn try_convert_amount_from_balance_currency_code(
         &self,
         to_currency_code: CurrencyCode,
         amount: Amount,
         currency_pair_price: Price,
-    ) -> Amount {
-        if Some(to_currency_code) == self.balance_currency_code {
-            return amount;
+    ) -> Result<Amount> {
+        if Some(to_currency_code) == self.balance_currency_code
+            || Some(to_currency_code)
+                == self
+                    .balance_currency_code
+                    .map(|x| self.pair_currency_code(x))
+        {
+            return Ok(amount);
         }
         if to_currency_code == self.base_currency_code {
-            return amount / currency_pair_price;
+            return amount.try_div(currency_pair_price);
         }
 
         if to_currency_code == self.quote_currency_code {
-            return amount * currency_pair_price;
+            return amount.try_mul(currency_pair_price);
         }
 
         let currency_pair = self.currency_pair();
-        panic!("Currency code {to_currency_code} outside currency pair {currency_pair} is not supported");
+        bail!("Currency code {to_currency_code} outside currency pair {currency_pair} is not supported");
     }
 
     pub fn convert_amount_into_amount_currency_code(
@@ -312,19 +574,35 @@ impl Symbol {
         amount_in_from_currency_code: Decimal,
         currency_pair_price: Price,
     ) -> Decimal {
+        self.try_convert_amount_into_amount_currency_code(
+            from_currency_code,
+            amount_in_from_currency_code,
+            currency_pair_price,
+        )
+        .unwrap_or_else(|error| panic!("{error:?}"))
+    }
+
+    /// Same as `convert_amount_into_amount_currency_code` but returns error on zero price or overflow
+    pub fn try_convert_amount_into_amount_currency_code(
+        &self,
+        from_currency_code: CurrencyCode,
+        amount_in_from_currency_code: Decimal,
+        currency_pair_price: Price,
+    ) -> Result<Decimal> {
+        let from_currency_code = self.pair_currency_code(from_currency_code);
         if from_currency_code == self.amount_currency_code {
-            return amount_in_from_currency_code;
+            return Ok(amount_in_from_currency_code);
         }
 
         if from_currency_code == self.base_currency_code() {
-            return amount_in_from_currency_code * currency_pair_price;
+            return amount_in_from_currency_code.try_mul(currency_pair_price);
         }
 
         if from_currency_code == self.quote_currency_code {
-            return amount_in_from_currency_code / currency_pair_price;
+            return amount_in_from_currency_code.try_div(currency_pair_price);
         }
 
-        panic!(
+        bail!(
             "We don't currently support currency code {} outside currency pair {}",
             from_currency_code,
             self.currency_pair()
@@ -397,6 +675,7 @@ impl Hash for Symbol {
 #[cfg(test)]
 mod test {
     use super::*;
+    use chrono::{TimeZone, Utc};
     use rust_decimal_macros::dec;
 
     #[test]
@@ -521,6 +800,204 @@ mod test {
         Ok(())
     }
 
+    fn derivative_symbol(balance_currency_code: &str) -> Symbol {
+        let base_code = CurrencyCode::new("xbt");
+        let quote_code = CurrencyCode::new("usd");
+        let balance_currency_code = CurrencyCode::new(balance_currency_code);
+        let amount_currency_code = match balance_currency_code == base_code {
+            true => quote_code,
+            false => base_code,
+        };
+
+        Symbol::new(
+            true,
+            "XBT".into(),
+            base_code,
+            "USD".into(),
+            quote_code,
+            None,
+            None,
+            None,
+            None,
+            None,
+            amount_currency_code,
+            Some(balance_currency_code),
+            Precision::ByTick { tick: dec!(0.5) },
+            Precision::ByTick { tick: dec!(1) },
+        )
+    }
+
+    #[rstest]
+    #[case("xbt", ContractType::Inverse)]
+    #[case("usd", ContractType::Linear)]
+    #[case("eth", ContractType::Quanto)]
+    fn detect_contract_type(#[case] balance_currency_code: &str, #[case] expected: ContractType) {
+        let symbol = derivative_symbol(balance_currency_code);
+
+        assert_eq!(symbol.contract_type, expected);
+    }
+
+    #[test]
+    fn inverse_contract_pnl_in_base_currency() {
+        let symbol = derivative_symbol("xbt");
+
+        assert_eq!(symbol.pnl_currency_code(), CurrencyCode::new("xbt"));
+        assert_eq!(symbol.contract_value(dec!(1000), dec!(20000)), dec!(0.05));
+        // long 1000 USD from 20000 to 25000: 1000 * (1 / 20000 - 1 / 25000)
+        assert_eq!(
+            symbol.position_pnl(dec!(1000), dec!(20000), dec!(25000)),
+            dec!(0.01)
+        );
+    }
+
+    #[test]
+    fn inverse_contract_with_contract_size() {
+        // COIN-M like contract: amount in contracts of 100 USD, balance and PnL in XBT
+        let symbol = derivative_symbol("xbt").with_contract(ContractType::Inverse, dec!(100));
+
+        assert_eq!(symbol.pnl_currency_code(), CurrencyCode::new("xbt"));
+        assert_eq!(symbol.contract_value(dec!(2), dec!(20000)), dec!(0.01));
+        assert_eq!(
+            symbol
+                .amount_by_contract_value(dec!(0.01), dec!(20000))
+                .expect("in test"),
+            dec!(2)
+        );
+        // long 10 contracts (1000 USD) from 20000 to 25000
+        assert_eq!(
+            symbol.position_pnl(dec!(10), dec!(20000), dec!(25000)),
+            dec!(0.01)
+        );
+        assert!(symbol.amount_by_contract_value(dec!(1), dec!(0)).is_err());
+    }
+
+    #[test]
+    fn linear_and_quanto_contract_pnl_with_multiplier() {
+        let linear_symbol =
+            derivative_symbol("usd").with_contract(ContractType::Linear, dec!(0.001));
+        assert_eq!(
+            linear_symbol.position_pnl(dec!(-100), dec!(20000), dec!(19000)),
+            dec!(100)
+        );
+
+        let quanto_symbol =
+            derivative_symbol("eth").with_contract(ContractType::Quanto, dec!(0.000001));
+        assert_eq!(quanto_symbol.pnl_currency_code(), CurrencyCode::new("eth"));
+        assert_eq!(
+            quanto_symbol.contract_value(dec!(10), dec!(20000)),
+            dec!(0.2)
+        );
+    }
+
+    #[test]
+    fn quanto_balance_currency_is_converted_as_quote_currency() {
+        // amount in XBT contracts, value of contract is quoted in ETH
+        let symbol = derivative_symbol("eth");
+        let eth = CurrencyCode::new("eth");
+        let usd = CurrencyCode::new("usd");
+        let xbt = CurrencyCode::new("xbt");
+
+        assert_eq!(
+            symbol.convert_amount_from_amount_currency_code(eth, dec!(2), dec!(20000)),
+            dec!(40000)
+        );
+        assert_eq!(
+            symbol.convert_amount_into_amount_currency_code(eth, dec!(40000), dec!(20000)),
+            dec!(2)
+        );
+        assert_eq!(
+            symbol
+                .try_convert_amount_from_balance_currency_code(usd, dec!(40000), dec!(20000))
+                .expect("in test"),
+            dec!(40000)
+        );
+        assert_eq!(
+            symbol
+                .try_convert_amount_from_balance_currency_code(xbt, dec!(40000), dec!(20000))
+                .expect("in test"),
+            dec!(2)
+        );
+    }
+
+    #[test]
+    fn conversion_with_zero_price_returns_error() {
+        let linear_symbol = derivative_symbol("usd");
+        let xbt = CurrencyCode::new("xbt");
+        let usd = CurrencyCode::new("usd");
+
+        assert_eq!(
+            linear_symbol
+                .try_convert_amount_into_amount_currency_code(usd, dec!(100), dec!(20000))
+                .expect("in test"),
+            dec!(0.005)
+        );
+        assert!(linear_symbol
+            .try_convert_amount_into_amount_currency_code(usd, dec!(100), dec!(0))
+            .is_err());
+
+        let inverse_symbol = derivative_symbol("xbt");
+        assert!(inverse_symbol
+            .try_convert_amount_from_amount_currency_code(xbt, dec!(100), dec!(0))
+            .is_err());
+        assert!(linear_symbol
+            .try_convert_amount_from_balance_currency_code(xbt, dec!(100), dec!(0))
+            .is_err());
+    }
+
+    #[rstest]
+    #[case(dec!(100), dec!(100))]
+    #[case(dec!(30), dec!(50))]
+    #[case(dec!(1000), dec!(200))]
+    fn clamp_price_by_price_band(#[case] price: Price, #[case] expected: Price) {
+        let symbol = derivative_symbol("usd").with_price_band(PriceBandFilter {
+            multiplier_up: dec!(2),
+            multiplier_down: dec!(0.5),
+        });
+
+        assert_eq!(symbol.clamp_price(price, Some(dec!(100))), expected);
+        assert_eq!(
+            symbol.validate_price(price, Some(dec!(100))).is_ok(),
+            price == expected
+        );
+    }
+
+    #[test]
+    fn price_band_without_reference_price() {
+        let symbol = derivative_symbol("usd").with_price_band(PriceBandFilter {
+            multiplier_up: dec!(2),
+            multiplier_down: dec!(0.5),
+        });
+
+        assert_eq!(symbol.allowed_price_band(None), (None, None));
+        assert_eq!(symbol.clamp_price(dec!(1000), None), dec!(1000));
+    }
+
+    #[test]
+    fn expiring_instruments_have_separate_currency_pairs() {
+        let expiration = Utc.ymd(2024, 3, 29).and_hms(8, 0, 0);
+        let future = derivative_symbol("xbt").with_expiry(InstrumentExpiry {
+            expiration,
+            option: None,
+        });
+        let option = derivative_symbol("xbt").with_expiry(InstrumentExpiry {
+            expiration,
+            option: Some(OptionContract {
+                kind: OptionKind::Call,
+                strike: dec!(60000.0),
+            }),
+        });
+
+        assert_eq!(future.currency_pair().as_str(), "xbt/usd:240329");
+        assert_eq!(option.currency_pair().as_str(), "xbt/usd:240329-60000-c");
+        assert_eq!(
+            option.currency_pair().to_codes().to_array(),
+            [CurrencyCode::new("xbt"), CurrencyCode::new("usd")]
+        );
+        assert!(!option.is_expired(expiration - chrono::Duration::seconds(1)));
+        assert!(option.is_expired(expiration));
+        assert!(!derivative_symbol("xbt").is_expired(expiration));
+    }
+
     #[test]
     pub fn get_trade_code() {
         let base_currency = "PHB";
@@ -1,6 +1,9 @@
+pub mod decimal_serialization;
 pub mod events;
 pub mod exchanges;
 pub mod market;
 pub mod order;
 pub mod order_book;
 pub mod position;
+pub mod reporting_precision;
+pub mod transfer;
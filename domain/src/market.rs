@@ -5,6 +5,7 @@ use rust_decimal::{Decimal, MathematicalOps};
 use serde::de::{self, Deserializer, Visitor};
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::str::FromStr;
 use std::time::Duration;
@@ -311,6 +312,21 @@ mod tests {
             assert_eq!(result, "Binance_1".to_string())
         }
     }
+
+    mod currency_aliases {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        pub fn configured_alias_overrides_default() {
+            let configured = HashMap::from([("XBT".into(), "usd".into())]);
+            let aliases = CurrencyAliases::new(&[("xbt", "btc"), ("xbt2", "btc")], &configured);
+
+            assert_eq!(aliases.unify("xbt".into()), CurrencyCode::new("usd"));
+            assert_eq!(aliases.unify("XBT2".into()), CurrencyCode::new("btc"));
+            assert_eq!(aliases.unify("eth".into()), CurrencyCode::new("eth"));
+        }
+    }
 }
 
 impl CurrencyCode {
@@ -344,9 +360,19 @@ impl CurrencyPair {
         Self(SHARED_CURRENCY_PAIR.add_or_get(&[base.as_str(), quote.as_str()].join("/")))
     }
 
+    /// Currency pair of instrument which isn't the only one for base and quote currencies
+    /// (e.g. futures with different expiration dates), in ccxt like format `base/quote:suffix`
+    pub fn from_codes_with_suffix(base: CurrencyCode, quote: CurrencyCode, suffix: &str) -> Self {
+        let currency_pair = format!("{}/{}:{suffix}", base.as_str(), quote.as_str());
+        Self(SHARED_CURRENCY_PAIR.add_or_get(&currency_pair))
+    }
+
     pub fn to_codes(&self) -> CurrencyPairCodes {
-        let (base, quote) = self
-            .as_str()
+        let currency_pair = self.as_str();
+        let currency_pair = currency_pair
+            .split_once(':')
+            .map_or(currency_pair, |(codes, _suffix)| codes);
+        let (base, quote) = currency_pair
             .split_once('/')
             .with_expect(|| format!("Failed to get base and quote value from CurrencyPair {self}"));
 
@@ -357,6 +383,43 @@ impl CurrencyPair {
     }
 }
 
+/// Maps exchange specific currency codes to unified currency codes,
+/// so the same asset has the same `CurrencyCode` on all exchanges (e.g. `xbt` -> `btc`)
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyAliases(HashMap<CurrencyCode, CurrencyCode>);
+
+impl CurrencyAliases {
+    /// Connector default aliases are overridden by configured ones
+    pub fn new(
+        defaults: &[(&str, &str)],
+        configured: &HashMap<CurrencyCode, CurrencyCode>,
+    ) -> Self {
+        let defaults = defaults
+            .iter()
+            .map(|(alias, code)| (CurrencyCode::new(alias), CurrencyCode::new(code)));
+        let configured = configured.iter().map(|(alias, code)| {
+            (
+                CurrencyCode::new(alias.as_str()),
+                CurrencyCode::new(code.as_str()),
+            )
+        });
+
+        Self(defaults.chain(configured).collect())
+    }
+
+    /// Exchanges can return currency codes in any case, so aliases are matched case-insensitively
+    pub fn unify(&self, currency_code: CurrencyCode) -> CurrencyCode {
+        self.0
+            .get(&CurrencyCode::new(currency_code.as_str()))
+            .copied()
+            .unwrap_or(currency_code)
+    }
+
+    pub fn unify_currency_pair(&self, base: CurrencyCode, quote: CurrencyCode) -> CurrencyPair {
+        CurrencyPair::from_codes(self.unify(base), self.unify(quote))
+    }
+}
+
 pub fn powi(value: Decimal, degree: i8) -> Decimal {
     value.powi(degree as i64)
 }
@@ -9,10 +9,19 @@ use crate::order::snapshot::OrderSnapshot;
 pub enum OrderEventType {
     CreateOrderSucceeded,
     CreateOrderFailed,
-    OrderFilled { cloned_order: Arc<OrderSnapshot> },
-    OrderCompleted { cloned_order: Arc<OrderSnapshot> },
+    OrderFilled {
+        cloned_order: Arc<OrderSnapshot>,
+    },
+    OrderCompleted {
+        cloned_order: Arc<OrderSnapshot>,
+    },
     CancelOrderSucceeded,
     CancelOrderFailed,
+    /// Order is changed on exchange without cancellation
+    AmendOrderSucceeded,
+    AmendOrderFailed,
+    /// Order is cancelled automatically because its time-to-live is over
+    Expired,
 }
 
 #[derive(Debug, Clone)]
@@ -1,12 +1,12 @@
 use crate::events::{EventSourceType, TradeId};
 use crate::market::CurrencyCode;
-use crate::order::snapshot::{OrderFillRole, OrderSide};
+use crate::order::snapshot::{OrderFillRole, OrderSide, Price};
 use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::order::snapshot::ClientOrderFillId;
+use crate::order::snapshot::{ClientOrderFillId, IntentId};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Hash)]
 pub enum OrderFillType {
@@ -23,6 +23,28 @@ impl OrderFillType {
     }
 }
 
+/// Top of order book prevailing at the moment of fill. Stored with fill for slippage analysis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FillBookContext {
+    pub best_bid: Option<Price>,
+    pub best_ask: Option<Price>,
+    pub mid_price: Option<Price>,
+}
+
+impl FillBookContext {
+    pub fn new(best_bid: Option<Price>, best_ask: Option<Price>) -> Self {
+        let mid_price = best_bid
+            .zip(best_ask)
+            .map(|(bid, ask)| (bid + ask) / Decimal::TWO);
+
+        Self {
+            best_bid,
+            best_ask,
+            mid_price,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderFill {
     id: Uuid,
@@ -49,6 +71,11 @@ pub struct OrderFill {
     is_incremental_fill: bool,
     event_source_type: Option<EventSourceType>,
     side: Option<OrderSide>,
+    #[serde(default)]
+    book_context: Option<FillBookContext>,
+    /// Strategy decision which filled order is created by
+    #[serde(default)]
+    intent_id: Option<IntentId>,
 }
 
 impl OrderFill {
@@ -92,6 +119,8 @@ impl OrderFill {
             is_incremental_fill,
             event_source_type,
             side,
+            book_context: None,
+            intent_id: None,
         }
     }
 
@@ -149,8 +178,38 @@ impl OrderFill {
     pub fn client_order_fill_id(&self) -> &Option<ClientOrderFillId> {
         &self.client_order_fill_id
     }
+    pub fn book_context(&self) -> Option<FillBookContext> {
+        self.book_context
+    }
+    pub fn intent_id(&self) -> Option<IntentId> {
+        self.intent_id
+    }
 
     pub fn set_client_order_fill_id(&mut self, input: ClientOrderFillId) {
         self.client_order_fill_id = Some(input);
     }
+
+    pub fn set_book_context(&mut self, input: Option<FillBookContext>) {
+        self.book_context = input;
+    }
+
+    pub fn set_intent_id(&mut self, input: Option<IntentId>) {
+        self.intent_id = input;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn mid_price_requires_both_sides_of_book() {
+        let context = FillBookContext::new(Some(dec!(99)), Some(dec!(101.5)));
+        assert_eq!(context.mid_price, Some(dec!(100.25)));
+
+        let context = FillBookContext::new(Some(dec!(99)), None);
+        assert_eq!(context.mid_price, None);
+        assert_eq!(context.best_bid, Some(dec!(99)));
+    }
 }
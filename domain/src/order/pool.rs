@@ -3,7 +3,7 @@ use crate::market::ExchangeAccountId;
 use crate::order::fill::OrderFill;
 use crate::order::snapshot::{
     Amount, ClientOrderId, ExchangeOrderId, OrderHeader, OrderInfoExtensionData, OrderMut,
-    OrderSimpleProps, OrderSnapshot, OrderStatus, Price,
+    OrderOptions, OrderSimpleProps, OrderSnapshot, OrderStatus, Price, UserOrder,
 };
 use crate::order::snapshot::{OrderRole, OrderSide, OrderType};
 use dashmap::DashMap;
@@ -74,7 +74,8 @@ impl OrderRef {
 
     /// NOTE: Should be used only in cases when we sure that price specified
     pub fn price(&self) -> Price {
-        self.header().price()
+        self.fn_ref(|x| x.internal_props.modified_price)
+            .unwrap_or_else(|| self.header().price())
     }
 
     /// Price of order specified by exchange client before order creation.
@@ -85,13 +86,26 @@ impl OrderRef {
     }
 
     pub fn amount(&self) -> Amount {
-        self.header().amount
+        self.fn_ref(|x| x.internal_props.modified_amount)
+            .unwrap_or(self.header().amount)
     }
 
     pub fn order_type(&self) -> OrderType {
         self.header().order_type
     }
 
+    /// User order options taking into account amendments of conditional order on exchange
+    pub fn user_order(&self) -> Option<UserOrder> {
+        if let Some(amended) = self.fn_ref(|x| x.internal_props.amended_user_order) {
+            return Some(amended);
+        }
+
+        match self.header().options {
+            OrderOptions::User(user_order) => Some(user_order),
+            _ => None,
+        }
+    }
+
     /// Lock order for read and provide copy mutable properties or check some conditions
     pub fn fn_ref<T: 'static>(&self, f: impl FnOnce(&OrderMut) -> T) -> T {
         f(self.inner.data.read().borrow())
@@ -141,12 +155,36 @@ impl OrderRef {
     }
 }
 
+/// Limit and stop orders linked as one-cancels-other: when one of them is filled or canceled,
+/// the other one should be canceled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcoOrderGroup {
+    pub limit_order: ClientOrderId,
+    pub stop_order: ClientOrderId,
+    /// Sibling is canceled by exchange itself, otherwise it is canceled by core
+    pub is_native: bool,
+}
+
+impl OcoOrderGroup {
+    pub fn sibling(&self, client_order_id: &ClientOrderId) -> Option<&ClientOrderId> {
+        if *client_order_id == self.limit_order {
+            Some(&self.stop_order)
+        } else if *client_order_id == self.stop_order {
+            Some(&self.limit_order)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct OrdersPool {
     pub cache_by_client_id: DashMap<ClientOrderId, OrderRef>,
     pub cache_by_exchange_id: DashMap<ExchangeOrderId, OrderRef>,
     pub not_finished: DashMap<ClientOrderId, OrderRef>,
+    /// OCO groups by client order id of each order of group
+    pub oco_groups: DashMap<ClientOrderId, Arc<OcoOrderGroup>>,
 }
 
 impl OrdersPool {
@@ -157,9 +195,31 @@ impl OrdersPool {
             cache_by_client_id: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
             cache_by_exchange_id: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
             not_finished: DashMap::with_capacity(ORDERS_INIT_CAPACITY),
+            oco_groups: DashMap::new(),
         })
     }
 
+    pub fn add_oco_group(&self, group: OcoOrderGroup) -> Arc<OcoOrderGroup> {
+        let group = Arc::new(group);
+        let _ = self
+            .oco_groups
+            .insert(group.limit_order.clone(), group.clone());
+        let _ = self
+            .oco_groups
+            .insert(group.stop_order.clone(), group.clone());
+
+        group
+    }
+
+    pub fn get_oco_group(&self, client_order_id: &ClientOrderId) -> Option<Arc<OcoOrderGroup>> {
+        self.oco_groups.get(client_order_id).map(|x| x.clone())
+    }
+
+    pub fn remove_oco_group(&self, group: &OcoOrderGroup) {
+        let _ = self.oco_groups.remove(&group.limit_order);
+        let _ = self.oco_groups.remove(&group.stop_order);
+    }
+
     /// Built `OrderRef` by specified `OrderSnapshot` and Insert it in order pool.
     pub fn add_snapshot_initial(&self, snapshot: &OrderSnapshot) -> OrderRef {
         let client_order_id = snapshot.header.client_order_id.clone();
@@ -210,3 +270,29 @@ impl OrdersPool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oco_group_is_found_by_both_orders() {
+        let pool = OrdersPool::new();
+        let limit_order = ClientOrderId::unique_id();
+        let stop_order = ClientOrderId::unique_id();
+        pool.add_oco_group(OcoOrderGroup {
+            limit_order: limit_order.clone(),
+            stop_order: stop_order.clone(),
+            is_native: false,
+        });
+
+        let group = pool.get_oco_group(&stop_order).expect("in test");
+        assert_eq!(group.sibling(&stop_order), Some(&limit_order));
+        assert_eq!(group.sibling(&limit_order), Some(&stop_order));
+        assert_eq!(group.sibling(&ClientOrderId::unique_id()), None);
+
+        pool.remove_oco_group(&group);
+        assert!(pool.get_oco_group(&limit_order).is_none());
+        assert!(pool.get_oco_group(&stop_order).is_none());
+    }
+}
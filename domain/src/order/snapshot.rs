@@ -13,7 +13,7 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use smallstr::SmallString;
 use std::any::Any;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fmt::Write;
 use std::fmt::{Debug, Display, Formatter};
@@ -163,8 +163,28 @@ impl OrderStatus {
 // Id for reserved amount
 impl_u64_id!(ReservationId);
 
+// Id of strategy decision which led to order creation
+impl_u64_id!(IntentId);
+
 pub const CURRENT_ORDER_VERSION: u32 = 1;
 
+/// Price which is compared with stop price for triggering of conditional order
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Hash)]
+pub enum TriggerPriceType {
+    /// Price of last trade on the market
+    Last,
+    /// Fair price of derivative calculated by exchange, it's resistant to short-term manipulation of last price
+    Mark,
+    /// Price of underlying index
+    Index,
+}
+
+impl Default for TriggerPriceType {
+    fn default() -> Self {
+        TriggerPriceType::Last
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum UserOrder {
     // Create order with specified price or make taker order if market was crossed with specified price
@@ -178,10 +198,14 @@ pub enum UserOrder {
     StopLoss {
         /// Price for stop-loss order trigger
         stop_price: Price,
+        #[serde(default)]
+        trigger_price_type: TriggerPriceType,
     },
     TrailingStop {
         trailing_delta: Decimal,
         stop_price: Option<Price>,
+        #[serde(default)]
+        trigger_price_type: TriggerPriceType,
     },
 }
 
@@ -201,6 +225,72 @@ impl UserOrder {
             execution_type: OrderExecutionType::MakerOnly,
         }
     }
+
+    /// Stop-loss order triggered by last price
+    pub fn stop_loss(stop_price: Price) -> Self {
+        Self::StopLoss {
+            stop_price,
+            trigger_price_type: TriggerPriceType::Last,
+        }
+    }
+
+    /// Trigger price type of conditional order, `None` for other orders
+    pub fn trigger_price_type(&self) -> Option<TriggerPriceType> {
+        match self {
+            Self::StopLoss {
+                trigger_price_type, ..
+            }
+            | Self::TrailingStop {
+                trigger_price_type, ..
+            } => Some(*trigger_price_type),
+            Self::Limit { .. } | Self::Market => None,
+        }
+    }
+
+    /// Limit price of limit order or stop price of stop-loss order which balance is reserved by,
+    /// `None` for other orders
+    pub fn reservation_price(&self) -> Option<Price> {
+        match *self {
+            Self::Limit { price, .. } => Some(price),
+            Self::StopLoss { stop_price, .. } => Some(stop_price),
+            Self::Market | Self::TrailingStop { .. } => None,
+        }
+    }
+
+    /// Change limit price of limit order or stop price of stop-loss order, other orders are kept
+    pub fn with_price(self, new_price: Price) -> Self {
+        match self {
+            Self::Limit { execution_type, .. } => Self::Limit {
+                price: new_price,
+                execution_type,
+            },
+            Self::StopLoss {
+                trigger_price_type, ..
+            } => Self::StopLoss {
+                stop_price: new_price,
+                trigger_price_type,
+            },
+            user_order => user_order,
+        }
+    }
+
+    /// Order can be amended only by order of the same kind with the same execution type or trigger
+    pub fn is_amendable_to(&self, new_user_order: &UserOrder) -> bool {
+        match (self, new_user_order) {
+            (
+                Self::Limit { execution_type, .. },
+                Self::Limit {
+                    execution_type: new_execution_type,
+                    ..
+                },
+            ) => execution_type == new_execution_type,
+            (Self::StopLoss { .. }, Self::StopLoss { .. })
+            | (Self::TrailingStop { .. }, Self::TrailingStop { .. }) => {
+                self.trigger_price_type() == new_user_order.trigger_price_type()
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -272,6 +362,8 @@ impl OrderOptions {
     }
 }
 
+pub type OrderMetadata = HashMap<String, serde_json::Value>;
+
 /// Immutable part of order
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderHeader {
@@ -283,6 +375,9 @@ pub struct OrderHeader {
 
     pub side: OrderSide,
     pub amount: Amount,
+    /// Visible part of iceberg order, whole amount is visible if not specified
+    #[serde(default)]
+    pub display_amount: Option<Amount>,
 
     pub options: OrderOptions,
 
@@ -296,6 +391,19 @@ pub struct OrderHeader {
 
     pub signal_id: Option<String>,
     pub strategy_name: String,
+    /// Strategy decision which the order is created by
+    #[serde(default)]
+    pub intent_id: Option<IntentId>,
+    /// Order which is replaced by this one during emulated modification
+    #[serde(default)]
+    pub replaced_order_id: Option<ClientOrderId>,
+    /// Time-to-live of order since its creation, unfilled part of order is cancelled after that
+    #[serde(default)]
+    pub expire_after: Option<std::time::Duration>,
+    /// Custom data of strategy (e.g. snapshot of signal or version of model) which is persisted
+    /// with order and published with its events, so trades can be joined with strategy data
+    #[serde(default)]
+    pub metadata: OrderMetadata,
 }
 
 impl OrderHeader {
@@ -344,13 +452,48 @@ impl OrderHeader {
             source_price: options.get_source_price(),
             side,
             amount,
+            display_amount: None,
             options,
             reservation_id,
             signal_id,
             strategy_name,
+            intent_id: None,
+            replaced_order_id: None,
+            expire_after: None,
+            metadata: OrderMetadata::new(),
         }
     }
 
+    /// Link order to strategy decision which it is created by
+    pub fn with_intent_id(mut self, intent_id: IntentId) -> Self {
+        self.intent_id = Some(intent_id);
+        self
+    }
+
+    /// Link order to the order which it replaces, so lineage of modified order is kept
+    pub fn with_replaced_order_id(mut self, replaced_order_id: ClientOrderId) -> Self {
+        self.replaced_order_id = Some(replaced_order_id);
+        self
+    }
+
+    /// Make iceberg order which shows only specified part of amount in order book
+    pub fn with_display_amount(mut self, display_amount: Amount) -> Self {
+        self.display_amount = Some(display_amount);
+        self
+    }
+
+    /// Cancel order automatically if it isn't finished in specified time since its creation
+    pub fn with_expire_after(mut self, expire_after: std::time::Duration) -> Self {
+        self.expire_after = Some(expire_after);
+        self
+    }
+
+    /// Attach custom data of strategy to order
+    pub fn with_metadata(mut self, metadata: OrderMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     pub fn market_account_id(&self) -> MarketAccountId {
         MarketAccountId {
             exchange_account_id: self.exchange_account_id,
@@ -462,6 +605,17 @@ pub struct OrderStatusHistory {
     status_changes: Vec<OrderStatusChange>,
 }
 
+impl OrderStatusHistory {
+    pub fn last_change_time(&self) -> Option<DateTime> {
+        self.status_changes.last().map(|x| x.time)
+    }
+
+    /// Statuses of order with times of their setting in chronological order
+    pub fn changes(&self) -> impl Iterator<Item = (OrderStatus, DateTime)> + '_ {
+        self.status_changes.iter().map(|x| (x.status, x.time))
+    }
+}
+
 /// Helping properties for trading engine internal use
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SystemInternalOrderProps {
@@ -487,6 +641,15 @@ pub struct SystemInternalOrderProps {
 
     pub handled_by_balance_recovery: bool,
     pub filled_amount_after_cancellation: Option<Amount>,
+
+    /// Trigger parameters of conditional order after amendment on exchange,
+    /// header keeps the initial ones
+    pub amended_user_order: Option<UserOrder>,
+
+    /// Price and amount of limit order after modification on exchange,
+    /// header keeps the initial ones
+    pub modified_price: Option<Price>,
+    pub modified_amount: Option<Amount>,
 }
 
 /// It may be necessary for an exchange to store specific information for an order.
@@ -735,13 +898,16 @@ impl OrderSnapshot {
 
     /// NOTE: Should be used only in cases when we sure that price specified
     pub fn price(&self) -> Price {
-        self.header
-            .source_price
+        self.internal_props
+            .modified_price
+            .or(self.header.source_price)
             .unwrap_or_else(|| panic!("Cannot get price from order {}", self.client_order_id()))
     }
 
     pub fn amount(&self) -> Amount {
-        self.header.amount
+        self.internal_props
+            .modified_amount
+            .unwrap_or(self.header.amount)
     }
 
     pub fn status(&self) -> OrderStatus {
@@ -0,0 +1,184 @@
+use crate::order::snapshot::{Amount, OrderSide, Price, SortedOrderData};
+use crate::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use crate::order_book::order_book_data::OrderBookData;
+use rust_decimal_macros::dec;
+
+/// Order book with price levels aggregated into buckets of fixed price size.
+/// Ask is put to bucket with price rounded up and bid to bucket with price rounded down,
+/// so aggregated levels never look better than original ones and never cross each other
+#[derive(Clone, Debug)]
+pub struct AggregatedOrderBook {
+    bucket_size: Price,
+    asks: SortedOrderData,
+    bids: SortedOrderData,
+}
+
+impl AggregatedOrderBook {
+    pub fn new(bucket_size: Price) -> Self {
+        assert!(
+            bucket_size > dec!(0),
+            "Bucket size of aggregated order book should be positive, but it is {bucket_size}"
+        );
+
+        Self {
+            bucket_size,
+            asks: SortedOrderData::new(),
+            bids: SortedOrderData::new(),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: &LocalOrderBookSnapshot, bucket_size: Price) -> Self {
+        let mut aggregated = Self::new(bucket_size);
+        aggregated.rebuild(snapshot);
+        aggregated
+    }
+
+    pub fn bucket_size(&self) -> Price {
+        self.bucket_size
+    }
+
+    /// Fully recalculate aggregated levels from snapshot
+    pub fn rebuild(&mut self, snapshot: &LocalOrderBookSnapshot) {
+        self.asks.clear();
+        self.bids.clear();
+
+        for (&price, &amount) in &snapshot.asks {
+            self.add_amount(OrderSide::Sell, price, amount);
+        }
+
+        for (&price, &amount) in &snapshot.bids {
+            self.add_amount(OrderSide::Buy, price, amount);
+        }
+    }
+
+    /// Incrementally apply order book update.
+    /// `snapshot` should be in the state before applying the same update to it
+    pub fn apply_update(&mut self, snapshot: &LocalOrderBookSnapshot, update: &OrderBookData) {
+        self.apply_update_by_side(OrderSide::Sell, &snapshot.asks, &update.asks);
+        self.apply_update_by_side(OrderSide::Buy, &snapshot.bids, &update.bids);
+    }
+
+    fn apply_update_by_side(
+        &mut self,
+        side: OrderSide,
+        snapshot: &SortedOrderData,
+        update: &SortedOrderData,
+    ) {
+        for (&price, &new_amount) in update {
+            let old_amount = snapshot.get(&price).copied().unwrap_or_default();
+            let amount_diff = new_amount - old_amount;
+            if !amount_diff.is_zero() {
+                self.add_amount(side, price, amount_diff);
+            }
+        }
+    }
+
+    fn add_amount(&mut self, side: OrderSide, price: Price, amount: Amount) {
+        let bucket_price = self.bucket_price(side, price);
+        let levels = match side {
+            OrderSide::Buy => &mut self.bids,
+            OrderSide::Sell => &mut self.asks,
+        };
+
+        let bucket_amount = levels.entry(bucket_price).or_default();
+        *bucket_amount += amount;
+        if *bucket_amount <= dec!(0) {
+            let _ = levels.remove(&bucket_price);
+        }
+    }
+
+    /// Price of bucket which contains specified price level
+    pub fn bucket_price(&self, side: OrderSide, price: Price) -> Price {
+        let buckets_count = price / self.bucket_size;
+        let buckets_count = match side {
+            OrderSide::Buy => buckets_count.floor(),
+            OrderSide::Sell => buckets_count.ceil(),
+        };
+
+        buckets_count * self.bucket_size
+    }
+
+    /// Return all aggregated asks starting from the lowest price
+    pub fn get_asks_price_levels(&self) -> impl Iterator<Item = (&Price, &Amount)> {
+        self.asks.iter()
+    }
+
+    /// Return all aggregated bids starting from the highest price
+    pub fn get_bids_price_levels(&self) -> impl Iterator<Item = (&Price, &Amount)> {
+        self.bids.iter().rev()
+    }
+
+    /// Return top aggregated level of asks or bids
+    pub fn get_top(&self, book_side: OrderSide) -> Option<(Price, Amount)> {
+        match book_side {
+            OrderSide::Buy => self.get_bids_price_levels().next(),
+            OrderSide::Sell => self.get_asks_price_levels().next(),
+        }
+        .map(|(&price, &amount)| (price, amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order_book_data;
+    use chrono::Utc;
+
+    fn snapshot(data: OrderBookData) -> LocalOrderBookSnapshot {
+        data.to_orderbook_snapshot(Utc::now())
+    }
+
+    #[test]
+    fn aggregate_snapshot_to_buckets() {
+        let snapshot = snapshot(order_book_data![
+            dec!(10.1) => dec!(1),
+            dec!(10.5) => dec!(2),
+            dec!(10.7) => dec!(3),
+            ;
+            dec!(9.9) => dec!(4),
+            dec!(9.5) => dec!(5),
+            dec!(9.2) => dec!(6),
+        ]);
+
+        let aggregated = AggregatedOrderBook::from_snapshot(&snapshot, dec!(0.5));
+
+        let asks = aggregated.get_asks_price_levels().collect::<Vec<_>>();
+        assert_eq!(asks, vec![(&dec!(10.5), &dec!(3)), (&dec!(11.0), &dec!(3))]);
+
+        let bids = aggregated.get_bids_price_levels().collect::<Vec<_>>();
+        assert_eq!(bids, vec![(&dec!(9.5), &dec!(9)), (&dec!(9.0), &dec!(6))]);
+    }
+
+    #[test]
+    fn incremental_update_matches_rebuild() {
+        let mut snapshot = snapshot(order_book_data![
+            dec!(10.1) => dec!(1),
+            dec!(10.7) => dec!(3),
+            ;
+            dec!(9.9) => dec!(4),
+            dec!(9.5) => dec!(5),
+        ]);
+
+        let mut aggregated = AggregatedOrderBook::from_snapshot(&snapshot, dec!(0.5));
+
+        let update = order_book_data![
+            dec!(10.1) => dec!(0),
+            dec!(10.4) => dec!(2),
+            dec!(10.7) => dec!(1),
+            ;
+            dec!(9.9) => dec!(0),
+            dec!(9.5) => dec!(0),
+        ];
+        aggregated.apply_update(&snapshot, &update);
+        snapshot.apply_update(&update, Utc::now());
+
+        let rebuilt = AggregatedOrderBook::from_snapshot(&snapshot, dec!(0.5));
+        assert_eq!(aggregated.asks, rebuilt.asks);
+        assert_eq!(aggregated.bids, rebuilt.bids);
+        assert_eq!(
+            aggregated.get_top(OrderSide::Sell),
+            Some((dec!(10.5), dec!(2)))
+        );
+        assert_eq!(aggregated.get_top(OrderSide::Buy), None);
+    }
+}
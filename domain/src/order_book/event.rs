@@ -23,7 +23,8 @@ pub struct OrderBookEvent {
     pub exchange_account_id: ExchangeAccountId,
     pub currency_pair: CurrencyPair,
 
-    _event_id: String,
+    /// Exchange specific id of event, e.g. sequence number of order book update
+    event_id: String,
 
     pub event_type: EventType,
     pub data: Arc<OrderBookData>,
@@ -34,7 +35,7 @@ impl OrderBookEvent {
         creation_time: DateTime,
         exchange_account_id: ExchangeAccountId,
         currency_pair: CurrencyPair,
-        _event_id: String,
+        event_id: String,
         event_type: EventType,
         data: Arc<OrderBookData>,
     ) -> OrderBookEvent {
@@ -43,12 +44,16 @@ impl OrderBookEvent {
             creation_time,
             exchange_account_id,
             currency_pair,
-            _event_id,
+            event_id,
             event_type,
             data,
         }
     }
 
+    pub fn event_id(&self) -> &str {
+        &self.event_id
+    }
+
     pub fn market_account_id(&self) -> MarketAccountId {
         MarketAccountId::new(self.exchange_account_id, self.currency_pair)
     }
@@ -0,0 +1,127 @@
+use crate::market::CurrencyCode;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportingRounding {
+    /// Half is rounded to even number (banker's rounding)
+    #[default]
+    MidpointNearestEven,
+    /// Half is rounded away from zero
+    MidpointAwayFromZero,
+    /// Extra digits are truncated
+    ToZero,
+}
+
+impl From<ReportingRounding> for RoundingStrategy {
+    fn from(value: ReportingRounding) -> Self {
+        match value {
+            ReportingRounding::MidpointNearestEven => RoundingStrategy::MidpointNearestEven,
+            ReportingRounding::MidpointAwayFromZero => RoundingStrategy::MidpointAwayFromZero,
+            ReportingRounding::ToZero => RoundingStrategy::ToZero,
+        }
+    }
+}
+
+/// Display precision of values in statistics, reports and visualization.
+/// Values are calculated with full precision of `Decimal`, so they are rounded only when reported
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportingPrecision {
+    /// Decimal places of values converted to USD
+    pub usd_decimals: u32,
+    /// Decimal places of amounts of currencies which aren't specified in `currency_decimals`
+    pub default_decimals: u32,
+    /// Decimal places of amounts of specific currencies, e.g. `btc = 8`, `usdt = 2`
+    pub currency_decimals: HashMap<CurrencyCode, u32>,
+    pub rounding: ReportingRounding,
+}
+
+impl Default for ReportingPrecision {
+    fn default() -> Self {
+        Self {
+            usd_decimals: 2,
+            default_decimals: 8,
+            currency_decimals: HashMap::new(),
+            rounding: ReportingRounding::default(),
+        }
+    }
+}
+
+impl ReportingPrecision {
+    pub fn round_usd(&self, value: Decimal) -> Decimal {
+        self.round(value, self.usd_decimals)
+    }
+
+    pub fn round_amount(&self, currency_code: CurrencyCode, value: Decimal) -> Decimal {
+        let decimals = self
+            .currency_decimals
+            .get(&currency_code)
+            .copied()
+            .unwrap_or(self.default_decimals);
+        self.round(value, decimals)
+    }
+
+    /// Values which currency is unknown or mixed (e.g. ratios or sums over markets)
+    pub fn round_default(&self, value: Decimal) -> Decimal {
+        self.round(value, self.default_decimals)
+    }
+
+    pub fn round_amounts(
+        &self,
+        amounts: &HashMap<CurrencyCode, Decimal>,
+    ) -> HashMap<CurrencyCode, Decimal> {
+        amounts
+            .iter()
+            .map(|(currency_code, value)| {
+                (*currency_code, self.round_amount(*currency_code, *value))
+            })
+            .collect()
+    }
+
+    fn round(&self, value: Decimal, decimals: u32) -> Decimal {
+        value
+            .round_dp_with_strategy(decimals, self.rounding.into())
+            .normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn round_by_currency_decimals() {
+        let precision = ReportingPrecision {
+            currency_decimals: HashMap::from([("usdt".into(), 2)]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            precision.round_amount("usdt".into(), dec!(10.125)),
+            dec!(10.12)
+        );
+        assert_eq!(
+            precision.round_amount("btc".into(), dec!(0.1234567891)),
+            dec!(0.12345679)
+        );
+        assert_eq!(
+            precision.round_usd(dec!(1.2345678901234567890123456789)),
+            dec!(1.23)
+        );
+    }
+
+    #[test]
+    fn round_by_configured_strategy() {
+        let precision = ReportingPrecision {
+            usd_decimals: 1,
+            rounding: ReportingRounding::ToZero,
+            ..Default::default()
+        };
+
+        assert_eq!(precision.round_usd(dec!(-1.99)), dec!(-1.9));
+        assert_eq!(precision.round_usd(dec!(5)), dec!(5));
+    }
+}
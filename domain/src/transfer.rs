@@ -0,0 +1,40 @@
+use crate::market::CurrencyCode;
+use crate::order::snapshot::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Availability of currency network (chain) on exchange
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkStatus {
+    pub is_deposit_enabled: bool,
+    pub is_withdrawal_enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositAddress {
+    pub address: String,
+    /// Memo or tag required by some networks in addition to address
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WithdrawalRequest {
+    pub currency_code: CurrencyCode,
+    pub amount: Amount,
+    pub network: String,
+    pub address: DepositAddress,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExternalTransferStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// State of withdrawal or deposit reported by exchange
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalTransferInfo {
+    pub status: ExternalTransferStatus,
+    /// Id of transaction in the network, known after withdrawal is broadcasted
+    pub tx_id: Option<String>,
+}
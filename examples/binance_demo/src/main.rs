@@ -54,7 +54,7 @@ async fn main() -> Result<()> {
             engine.context(),
         );
 
-        engine.start_disposition_executor(strategy);
+        engine.start_disposition_executor(strategy)?;
 
         match engine.run().await {
             ActionAfterGracefulShutdown::Nothing => break,
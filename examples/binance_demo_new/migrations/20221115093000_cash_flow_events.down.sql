@@ -0,0 +1,3 @@
+DROP TABLE cash_flow_events;
+
+delete from public.cleanup_settings where table_name = 'cash_flow_events';
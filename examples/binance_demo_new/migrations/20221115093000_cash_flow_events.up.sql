@@ -0,0 +1,12 @@
+CREATE TABLE cash_flow_events (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX cash_flow_events__insert_time_idx ON cash_flow_events USING btree (insert_time);
+CREATE INDEX cash_flow_events__kind_idx ON cash_flow_events USING btree (((json ->> 'kind')::text));
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('cash_flow_events', '3 mons', 'insert_time');
@@ -0,0 +1,9 @@
+CREATE TABLE account_history_trades (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX account_history_trades__insert_time_idx ON account_history_trades USING btree (insert_time);
+CREATE INDEX account_history_trades__exchange_account_id_idx ON account_history_trades USING btree (((json ->> 'exchange_account_id')::text));
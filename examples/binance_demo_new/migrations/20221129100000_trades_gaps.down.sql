@@ -0,0 +1,3 @@
+DROP TABLE trades_gaps;
+
+delete from public.cleanup_settings where table_name = 'trades_gaps';
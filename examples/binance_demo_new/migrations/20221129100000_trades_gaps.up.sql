@@ -0,0 +1,11 @@
+CREATE TABLE trades_gaps (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX trades_gaps__insert_time_idx ON trades_gaps USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('trades_gaps', '3 mons', 'insert_time');
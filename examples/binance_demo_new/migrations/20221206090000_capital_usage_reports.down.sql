@@ -0,0 +1,3 @@
+DROP TABLE capital_usage_reports;
+
+delete from public.cleanup_settings where table_name = 'capital_usage_reports';
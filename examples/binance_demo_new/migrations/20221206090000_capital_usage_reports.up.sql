@@ -0,0 +1,11 @@
+CREATE TABLE capital_usage_reports (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX capital_usage_reports__insert_time_idx ON capital_usage_reports USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('capital_usage_reports', '3 mons', 'insert_time');
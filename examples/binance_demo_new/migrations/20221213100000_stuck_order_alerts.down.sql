@@ -0,0 +1,3 @@
+DROP TABLE stuck_order_alerts;
+
+delete from public.cleanup_settings where table_name = 'stuck_order_alerts';
@@ -0,0 +1,11 @@
+CREATE TABLE stuck_order_alerts (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX stuck_order_alerts__insert_time_idx ON stuck_order_alerts USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('stuck_order_alerts', '3 mons', 'insert_time');
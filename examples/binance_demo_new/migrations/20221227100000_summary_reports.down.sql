@@ -0,0 +1,3 @@
+DROP TABLE summary_reports;
+
+delete from public.cleanup_settings where table_name = 'summary_reports';
@@ -0,0 +1,11 @@
+CREATE TABLE summary_reports (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX summary_reports__insert_time_idx ON summary_reports USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('summary_reports', '3 mons', 'insert_time');
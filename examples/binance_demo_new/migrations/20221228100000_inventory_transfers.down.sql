@@ -0,0 +1,3 @@
+DROP TABLE inventory_transfers;
+
+delete from public.cleanup_settings where table_name = 'inventory_transfers';
@@ -0,0 +1,11 @@
+CREATE TABLE inventory_transfers (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX inventory_transfers__insert_time_idx ON inventory_transfers USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('inventory_transfers', '1 year', 'insert_time');
@@ -0,0 +1,3 @@
+DROP TABLE config_changes;
+
+delete from public.cleanup_settings where table_name = 'config_changes';
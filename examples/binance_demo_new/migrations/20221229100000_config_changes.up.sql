@@ -0,0 +1,11 @@
+CREATE TABLE config_changes (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX config_changes__insert_time_idx ON config_changes USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('config_changes', '1 year', 'insert_time');
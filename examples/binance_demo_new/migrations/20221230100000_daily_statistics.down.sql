@@ -0,0 +1,3 @@
+DROP TABLE daily_statistics;
+
+delete from public.cleanup_settings where table_name = 'daily_statistics';
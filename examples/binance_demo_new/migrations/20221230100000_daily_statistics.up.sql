@@ -0,0 +1,11 @@
+CREATE TABLE daily_statistics (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX daily_statistics__insert_time_idx ON daily_statistics USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('daily_statistics', '1 year', 'insert_time');
@@ -0,0 +1,3 @@
+DROP TABLE liquidity_heat_maps;
+
+delete from public.cleanup_settings where table_name = 'liquidity_heat_maps';
@@ -0,0 +1,13 @@
+CREATE TABLE liquidity_heat_maps (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX liquidity_heat_maps__insert_time_idx ON liquidity_heat_maps USING btree (insert_time);
+CREATE INDEX liquidity_heat_maps__exchange_id_idx ON liquidity_heat_maps USING btree (((json ->> 'exchange_id')::text));
+CREATE INDEX liquidity_heat_maps__currency_pair_idx ON liquidity_heat_maps USING btree (((json ->> 'currency_pair')::text));
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('liquidity_heat_maps', '1 mons', 'insert_time');
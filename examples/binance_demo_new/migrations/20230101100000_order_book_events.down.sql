@@ -0,0 +1,3 @@
+DROP TABLE order_book_events;
+
+delete from public.cleanup_settings where table_name = 'order_book_events';
@@ -0,0 +1,13 @@
+CREATE TABLE order_book_events (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX order_book_events__insert_time_idx ON order_book_events USING btree (insert_time);
+CREATE INDEX order_book_events__exchange_account_id_idx ON order_book_events USING btree (((json ->> 'exchange_account_id')::text));
+CREATE INDEX order_book_events__currency_pair_idx ON order_book_events USING btree (((json ->> 'currency_pair')::text));
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('order_book_events', '1 year', 'insert_time');
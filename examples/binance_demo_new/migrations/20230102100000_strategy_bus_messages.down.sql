@@ -0,0 +1,3 @@
+DROP TABLE strategy_bus_messages;
+
+delete from public.cleanup_settings where table_name = 'strategy_bus_messages';
@@ -0,0 +1,11 @@
+CREATE TABLE strategy_bus_messages (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX strategy_bus_messages__insert_time_idx ON strategy_bus_messages USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('strategy_bus_messages', '1 mons', 'insert_time');
@@ -0,0 +1,3 @@
+DROP TABLE position_unwinds;
+
+delete from public.cleanup_settings where table_name = 'position_unwinds';
@@ -0,0 +1,11 @@
+CREATE TABLE position_unwinds (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX position_unwinds__insert_time_idx ON position_unwinds USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('position_unwinds', '1 year', 'insert_time');
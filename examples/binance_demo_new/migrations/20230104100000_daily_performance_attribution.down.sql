@@ -0,0 +1,3 @@
+DROP TABLE daily_performance_attribution;
+
+delete from public.cleanup_settings where table_name = 'daily_performance_attribution';
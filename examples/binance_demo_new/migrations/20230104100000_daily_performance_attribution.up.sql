@@ -0,0 +1,11 @@
+CREATE TABLE daily_performance_attribution (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX daily_performance_attribution__insert_time_idx ON daily_performance_attribution USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('daily_performance_attribution', '1 year', 'insert_time');
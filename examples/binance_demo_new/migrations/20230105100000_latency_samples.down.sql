@@ -0,0 +1,3 @@
+DROP TABLE latency_samples;
+
+delete from public.cleanup_settings where table_name = 'latency_samples';
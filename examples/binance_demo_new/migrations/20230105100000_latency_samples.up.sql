@@ -0,0 +1,11 @@
+CREATE TABLE latency_samples (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX latency_samples__insert_time_idx ON latency_samples USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('latency_samples', '3 mons', 'insert_time');
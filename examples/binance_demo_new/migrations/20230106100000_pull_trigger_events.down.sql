@@ -0,0 +1,3 @@
+DROP TABLE pull_trigger_events;
+
+delete from public.cleanup_settings where table_name = 'pull_trigger_events';
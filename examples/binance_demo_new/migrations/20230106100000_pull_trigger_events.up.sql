@@ -0,0 +1,11 @@
+CREATE TABLE pull_trigger_events (
+    id bigint PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now(),
+    version int,
+    json jsonb NOT NULL
+);
+
+CREATE INDEX pull_trigger_events__insert_time_idx ON pull_trigger_events USING btree (insert_time);
+
+insert into public.cleanup_settings (table_name, period, column_name)
+values ('pull_trigger_events', '3 mons', 'insert_time');
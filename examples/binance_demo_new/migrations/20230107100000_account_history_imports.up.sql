@@ -0,0 +1,4 @@
+CREATE TABLE account_history_imports (
+    exchange_account_id text PRIMARY KEY,
+    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now()
+);
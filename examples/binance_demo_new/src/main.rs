@@ -66,7 +66,7 @@ async fn main() -> Result<()> {
             ctx.clone(),
         );
 
-        engine.start_disposition_executor(strategy);
+        engine.start_disposition_executor(strategy)?;
 
         match engine.run().await {
             ActionAfterGracefulShutdown::Nothing => break,
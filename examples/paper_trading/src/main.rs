@@ -0,0 +1,153 @@
+#![deny(
+    non_ascii_idents,
+    non_shorthand_field_patterns,
+    no_mangle_generic_items,
+    overflowing_literals,
+    path_statements,
+    unused_allocation,
+    unused_comparisons,
+    unused_parens,
+    while_true,
+    trivial_numeric_casts,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_must_use,
+    clippy::unwrap_used
+)]
+
+use anyhow::{bail, Context, Result};
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::strategy_context::StrategyContext;
+use mmb_core::lifecycle::launcher::{launch_trading_engine, EngineBuildConfig, InitSettings};
+use mmb_core::settings::DispositionStrategySettings;
+use mmb_domain::market::ExchangeAccountId;
+use mock::feed::{start_feed_server, FeedSettings};
+use mock::latency::LatencySettings;
+use mock::mock_exchange::MockExchangeBuilder;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Debug;
+use std::fs;
+use std::sync::Arc;
+use strategies::cross_venue_hedger::CrossVenueHedgerStrategy;
+use strategies::mean_reversion_maker::MeanReversionMakerStrategy;
+use strategies::momentum_taker::MomentumTakerStrategy;
+
+const CONFIGS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../strategies/configs");
+const DEFAULT_STRATEGY: &str = "momentum_taker";
+
+type CreateStrategy<Settings, Strategy> = fn(Settings, Arc<dyn StrategyContext>) -> Box<Strategy>;
+
+/// Runs strategy of the gallery against mock exchange with its settings file from
+/// `strategies/configs`, e.g. `cargo run -p paper_trading -- mean_reversion_maker`
+#[tokio::main]
+async fn main() -> Result<()> {
+    let strategy = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_STRATEGY.to_owned());
+
+    let ws_url = start_feed_server(&FeedSettings::default()).await?;
+    let engine_config = EngineBuildConfig::new(vec![Box::new(MockExchangeBuilder {
+        ws_url,
+        observer: None,
+        latency: load_latency()?,
+    })]);
+
+    match strategy.as_str() {
+        "momentum_taker" => run(&engine_config, &strategy, MomentumTakerStrategy::new).await,
+        "mean_reversion_maker" => {
+            run(&engine_config, &strategy, MeanReversionMakerStrategy::new).await
+        }
+        "cross_venue_hedger" => {
+            run(&engine_config, &strategy, CrossVenueHedgerStrategy::new).await
+        }
+        _ => bail!("Unknown strategy {strategy}, expected one of momentum_taker, mean_reversion_maker, cross_venue_hedger"),
+    }
+}
+
+async fn run<Settings, Strategy>(
+    engine_config: &EngineBuildConfig,
+    strategy_name: &str,
+    create_strategy: CreateStrategy<Settings, Strategy>,
+) -> Result<()>
+where
+    Settings: DispositionStrategySettings + Clone + Debug + DeserializeOwned + Serialize,
+    Strategy: DispositionStrategy + 'static,
+{
+    let init_settings = InitSettings::<Settings>::Load {
+        config_path: config_path(strategy_name),
+        credentials_path: credentials_path(),
+    };
+    let engine = launch_trading_engine(engine_config, init_settings).await?;
+
+    let strategy = create_strategy(engine.settings().strategy.clone(), engine.context());
+    engine.start_disposition_executor(strategy)?;
+
+    engine.run().await;
+
+    Ok(())
+}
+
+fn config_path(strategy_name: &str) -> String {
+    format!("{CONFIGS_DIR}/{strategy_name}.toml")
+}
+
+/// Simulated latencies of mock exchange accounts
+fn load_latency() -> Result<HashMap<ExchangeAccountId, LatencySettings>> {
+    let path = format!("{CONFIGS_DIR}/latency.toml");
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Unable to read file {path}"))?;
+
+    toml::from_str(&content).with_context(|| format!("Unable to parse latency settings {path}"))
+}
+
+/// Mock exchange doesn't check credentials, but engine requires them for every exchange account
+fn credentials_path() -> String {
+    format!("{CONFIGS_DIR}/credentials.toml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_core::config::try_load_settings;
+    use mock::mock_exchange::MOCK_EXCHANGE_ID;
+    use strategies::cross_venue_hedger::CrossVenueHedgerSettings;
+    use strategies::mean_reversion_maker::MeanReversionMakerSettings;
+    use strategies::momentum_taker::MomentumTakerSettings;
+
+    fn assert_runs_on_mock_exchange<Settings>(strategy_name: &str)
+    where
+        Settings: Clone + Debug + DeserializeOwned,
+    {
+        let settings =
+            try_load_settings::<Settings>(&config_path(strategy_name), &credentials_path())
+                .expect("in test");
+
+        assert!(!settings.core.exchanges.is_empty());
+        for exchange_settings in &settings.core.exchanges {
+            assert_eq!(
+                exchange_settings.exchange_account_id.exchange_id.as_str(),
+                MOCK_EXCHANGE_ID
+            );
+        }
+    }
+
+    #[test]
+    fn gallery_settings_files_point_to_mock_exchange() {
+        assert_runs_on_mock_exchange::<MomentumTakerSettings>("momentum_taker");
+        assert_runs_on_mock_exchange::<MeanReversionMakerSettings>("mean_reversion_maker");
+        assert_runs_on_mock_exchange::<CrossVenueHedgerSettings>("cross_venue_hedger");
+    }
+
+    #[test]
+    fn latency_settings_file_is_parsed() {
+        let latency = load_latency().expect("in test");
+
+        let exchange_account_id = ExchangeAccountId::new(MOCK_EXCHANGE_ID, 0);
+        assert!(latency[&exchange_account_id].order_ack.is_some());
+        assert!(latency[&exchange_account_id].fill.is_some());
+    }
+}
@@ -47,7 +47,9 @@ async fn main() {
             engine.context(),
         );
 
-        engine.start_disposition_executor(strategy);
+        engine
+            .start_disposition_executor(strategy)
+            .expect("Failed to start disposition executor");
 
         match engine.run().await {
             ActionAfterGracefulShutdown::Nothing => break,
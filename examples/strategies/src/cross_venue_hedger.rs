@@ -0,0 +1,316 @@
+use anyhow::Result;
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::strategy_context::StrategyContext;
+use mmb_core::disposition_execution::{
+    PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
+};
+use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::Round;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::{Amount, OrderRole, OrderSide, OrderSnapshot, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CrossVenueHedgerSettings {
+    /// Exchange account which quotes are placed on
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPairSetting,
+    pub max_amount: Amount,
+    /// Exchange account with more liquid market of the same currency pair, where fills are hedged
+    pub hedge_exchange_account_id: ExchangeAccountId,
+    /// Distance of quotes from top of hedge market relative to its price,
+    /// it should cover taker fee of hedge order
+    pub edge: Decimal,
+    /// Position accumulated by fills which isn't hedged yet, quoting of side
+    /// which increases it stops after reaching this limit
+    pub max_unhedged_amount: Amount,
+}
+
+impl DispositionStrategySettings for CrossVenueHedgerSettings {
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    fn currency_pair(&self) -> CurrencyPair {
+        if let CurrencyPairSetting::Ordinary { base, quote } = self.currency_pair {
+            CurrencyPair::from_codes(base, quote)
+        } else {
+            panic!(
+                "Incorrect currency pair setting enum type {:?}",
+                self.currency_pair
+            );
+        }
+    }
+
+    fn max_amount(&self) -> Amount {
+        self.max_amount
+    }
+}
+
+/// Quotes less liquid market with prices of more liquid hedge market shifted by edge.
+/// Fills on quoted market accumulate unhedged position which should be closed on hedge market:
+/// strategy API creates orders only on market of disposition executor, so required hedge
+/// is exposed by `unhedged_position` and quoting is limited by `max_unhedged_amount` until
+/// position is hedged by external executor
+pub struct CrossVenueHedgerStrategy {
+    market_account_id: MarketAccountId,
+    hedge_market_id: MarketId,
+    settings: CrossVenueHedgerSettings,
+    context: Arc<dyn StrategyContext>,
+    configuration_descriptor: ConfigurationDescriptor,
+    unhedged_position: Mutex<Amount>,
+}
+
+impl CrossVenueHedgerStrategy {
+    pub fn new(settings: CrossVenueHedgerSettings, context: Arc<dyn StrategyContext>) -> Box<Self> {
+        let currency_pair = settings.currency_pair();
+        let market_account_id = MarketAccountId::new(settings.exchange_account_id, currency_pair);
+        let hedge_market_id = MarketId::new(
+            settings.hedge_exchange_account_id.exchange_id,
+            currency_pair,
+        );
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            Self::strategy_name().into(),
+            format!(
+                "{};{};{}",
+                settings.exchange_account_id, settings.hedge_exchange_account_id, currency_pair
+            )
+            .as_str()
+            .into(),
+        );
+
+        Box::new(CrossVenueHedgerStrategy {
+            market_account_id,
+            hedge_market_id,
+            settings,
+            context,
+            configuration_descriptor,
+            unhedged_position: Mutex::new(Amount::ZERO),
+        })
+    }
+
+    fn strategy_name() -> &'static str {
+        "CrossVenueHedgerStrategy"
+    }
+
+    /// Positive position should be hedged by selling on hedge market, negative one by buying
+    pub fn unhedged_position(&self) -> Amount {
+        *self.lock_unhedged_position()
+    }
+
+    /// Register amount hedged on hedge market
+    pub fn register_hedge(&self, side: OrderSide, amount: Amount) {
+        *self.lock_unhedged_position() += signed_amount(side, amount);
+    }
+
+    fn lock_unhedged_position(&self) -> MutexGuard<'_, Amount> {
+        self.unhedged_position
+            .lock()
+            .expect("Unhedged position lock is poisoned")
+    }
+
+    fn context_by_side(
+        &self,
+        side: OrderSide,
+        hedge_price: Price,
+        explanation: Explanation,
+    ) -> Option<TradingContextBySide> {
+        let symbol = self.context.symbol(
+            self.market_account_id.exchange_account_id,
+            self.market_account_id.currency_pair,
+        )?;
+
+        let price = match side {
+            OrderSide::Buy => symbol.price_round(
+                hedge_price * (Decimal::ONE - self.settings.edge),
+                Round::Floor,
+            ),
+            OrderSide::Sell => symbol.price_round(
+                hedge_price * (Decimal::ONE + self.settings.edge),
+                Round::Ceiling,
+            ),
+        };
+
+        let mut explanation = Some(explanation);
+        let available = self.context.available_leveraged_balance(
+            self.configuration_descriptor,
+            side,
+            self.market_account_id.exchange_account_id,
+            symbol.clone(),
+            price,
+            &mut explanation,
+        )?;
+
+        // fill of side can't move unhedged position beyond limit
+        let unhedged_room = self.max_unhedged_room(side);
+        let amount = symbol.amount_round(
+            self.settings.max_amount.min(available).min(unhedged_room),
+            Round::Floor,
+        );
+
+        let explanation = explanation.unwrap_or_default();
+        if amount <= Amount::ZERO {
+            return Some(TradingContextBySide::empty(1, explanation));
+        }
+
+        Some(TradingContextBySide {
+            max_amount: self.settings.max_amount,
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role: OrderRole::Maker,
+                    strategy_name: Self::strategy_name().to_string(),
+                    metadata: Default::default(),
+                    disposition: TradeDisposition::new(self.market_account_id, side, price, amount),
+                }),
+                explanation,
+            }],
+        })
+    }
+
+    /// Amount which can be filled on side until unhedged position reaches limit
+    fn max_unhedged_room(&self, side: OrderSide) -> Amount {
+        let position = self.unhedged_position();
+        let room = self.settings.max_unhedged_amount - signed_amount(side, position);
+        room.max(Amount::ZERO)
+    }
+}
+
+fn signed_amount(side: OrderSide, amount: Amount) -> Amount {
+    match side {
+        OrderSide::Buy => amount,
+        OrderSide::Sell => -amount,
+    }
+}
+
+impl DispositionStrategy for CrossVenueHedgerStrategy {
+    fn calculate_trading_context(
+        &mut self,
+        _event: &ExchangeEvent,
+        _now: DateTime,
+        local_snapshots_service: &LocalSnapshotsService,
+        explanation: &mut Explanation,
+    ) -> Option<TradingContext> {
+        let hedge_snapshot = local_snapshots_service.get_snapshot(self.hedge_market_id)?;
+        let (hedge_bid, _) = hedge_snapshot.get_top_bid()?;
+        let (hedge_ask, _) = hedge_snapshot.get_top_ask()?;
+
+        Some(TradingContext::new(
+            self.context_by_side(OrderSide::Buy, hedge_bid, explanation.clone())?,
+            self.context_by_side(OrderSide::Sell, hedge_ask, explanation.clone())?,
+        ))
+    }
+
+    fn handle_order_fill(
+        &self,
+        cloned_order: &Arc<OrderSnapshot>,
+        _price_slot: &PriceSlot,
+        _target_eai: ExchangeAccountId,
+        _cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        // strategy is notified about every fill separately
+        if let Some(fill) = cloned_order.fills.fills.last() {
+            *self.lock_unhedged_position() += signed_amount(cloned_order.side(), fill.amount());
+        }
+
+        Ok(())
+    }
+
+    fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+        self.configuration_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_core::disposition_execution::testing::{
+        order_book_snapshot_event, OrderSnapshotBuilder, StrategyTestHarness, SymbolBuilder,
+        TestStrategyContext,
+    };
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn quotes_off_hedge_market_and_limits_unhedged_position() {
+        let exchange_account_id: ExchangeAccountId = "Bitmex_0".parse().expect("in test");
+        let hedge_exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let symbol = SymbolBuilder::new("btc", "usdt").build();
+        let currency_pair = symbol.currency_pair();
+        let market_account_id = MarketAccountId::new(exchange_account_id, currency_pair);
+
+        let context = Arc::new(TestStrategyContext::default());
+        context.add_symbol(exchange_account_id, symbol);
+        context.set_available_balance(market_account_id, OrderSide::Buy, dec!(1));
+        context.set_available_balance(market_account_id, OrderSide::Sell, dec!(1));
+
+        let settings = CrossVenueHedgerSettings {
+            exchange_account_id,
+            currency_pair: CurrencyPairSetting::Ordinary {
+                base: "btc".into(),
+                quote: "usdt".into(),
+            },
+            max_amount: dec!(0.5),
+            hedge_exchange_account_id,
+            edge: dec!(0.001),
+            max_unhedged_amount: dec!(0.8),
+        };
+        let strategy = CrossVenueHedgerStrategy::new(settings, context);
+        let mut harness = StrategyTestHarness::new(strategy, exchange_account_id);
+
+        let hedge_book = order_book_snapshot_event(
+            MarketAccountId::new(hedge_exchange_account_id, currency_pair),
+            &[(dec!(1000), dec!(5))],
+            &[(dec!(1001), dec!(5))],
+            harness.now(),
+        );
+        let _ = harness.feed_event(&hedge_book);
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Buy),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Buy,
+                dec!(999),
+                dec!(0.5)
+            )]
+        );
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Sell),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Sell,
+                dec!(1002.01),
+                dec!(0.5)
+            )]
+        );
+
+        let order =
+            OrderSnapshotBuilder::limit(market_account_id, OrderSide::Buy, dec!(999), dec!(0.5))
+                .fill(dec!(999), dec!(0.5))
+                .build();
+        harness.feed_fill(order).expect("in test");
+        assert_eq!(harness.strategy().unhedged_position(), dec!(0.5));
+
+        let _ = harness.feed_event(&hedge_book);
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Buy),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Buy,
+                dec!(999),
+                dec!(0.3)
+            )]
+        );
+
+        harness
+            .strategy()
+            .register_hedge(OrderSide::Sell, dec!(0.5));
+        assert_eq!(harness.strategy().unhedged_position(), dec!(0));
+    }
+}
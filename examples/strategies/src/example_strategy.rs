@@ -1,35 +1,53 @@
 use anyhow::Result;
-use itertools::Itertools;
-use mmb_core::balance::manager::balance_manager::BalanceManager;
 use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::strategy_context::StrategyContext;
+use mmb_core::disposition_execution::tunable_parameters::{ParameterType, TunableParameter};
 use mmb_core::disposition_execution::{
     PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
 };
 use mmb_core::explanation::{Explanation, WithExplanation};
-use mmb_core::lifecycle::trading_engine::EngineContext;
 use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
 use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
-use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_core::settings::{
+    AutoSizingSettings, CurrencyPairSetting, DegradedModeSettings, DispositionStrategySettings,
+    FeatureRecorderSettings, MaxOrderAgeSettings, ProtectiveOrdersSettings, RefreshLevelSettings,
+};
 use mmb_domain::events::ExchangeEvent;
 use mmb_domain::exchanges::symbol::Round;
-use mmb_domain::market::CurrencyPair;
-use mmb_domain::market::{ExchangeAccountId, MarketAccountId, MarketId};
-use mmb_domain::order::snapshot::Amount;
-use mmb_domain::order::snapshot::{OrderRole, OrderSide, OrderSnapshot};
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::{Amount, OrderRole, OrderSide, OrderSnapshot, Price};
 use mmb_utils::cancellation_token::CancellationToken;
 use mmb_utils::infrastructure::WithExpect;
 use mmb_utils::DateTime;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+const SPREAD_PARAMETER: &str = "spread";
+const MAX_AMOUNT_PARAMETER: &str = "max_amount";
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ExampleStrategySettings {
     pub spread: Decimal,
     pub currency_pair: CurrencyPairSetting,
     pub max_amount: Decimal,
     pub exchange_account_id: ExchangeAccountId,
+    #[serde(default)]
+    pub refresh_level_on_fill: Option<RefreshLevelSettings>,
+    #[serde(default)]
+    pub feature_recorder: Option<FeatureRecorderSettings>,
+    #[serde(default)]
+    pub protective_orders: Option<ProtectiveOrdersSettings>,
+    #[serde(default)]
+    pub order_book_price_bucket: Option<Price>,
+    #[serde(default)]
+    pub degraded_mode: Option<DegradedModeSettings>,
+    #[serde(default)]
+    pub max_order_age: Option<MaxOrderAgeSettings>,
+    #[serde(default)]
+    pub auto_sizing: Option<AutoSizingSettings>,
 }
 
 impl DispositionStrategySettings for ExampleStrategySettings {
@@ -52,13 +70,41 @@ impl DispositionStrategySettings for ExampleStrategySettings {
     fn max_amount(&self) -> Amount {
         self.max_amount
     }
+
+    fn refresh_level_on_fill(&self) -> Option<RefreshLevelSettings> {
+        self.refresh_level_on_fill
+    }
+
+    fn feature_recorder(&self) -> Option<FeatureRecorderSettings> {
+        self.feature_recorder.clone()
+    }
+
+    fn protective_orders(&self) -> Option<ProtectiveOrdersSettings> {
+        self.protective_orders
+    }
+
+    fn order_book_price_bucket(&self) -> Option<Price> {
+        self.order_book_price_bucket
+    }
+
+    fn degraded_mode(&self) -> Option<DegradedModeSettings> {
+        self.degraded_mode
+    }
+
+    fn max_order_age(&self) -> Option<MaxOrderAgeSettings> {
+        self.max_order_age
+    }
+
+    fn auto_sizing(&self) -> Option<AutoSizingSettings> {
+        self.auto_sizing
+    }
 }
 
 pub struct ExampleStrategy {
     target_eai: ExchangeAccountId,
     currency_pair: CurrencyPair,
     spread: Decimal,
-    engine_context: Arc<EngineContext>,
+    context: Arc<dyn StrategyContext>,
     configuration_descriptor: ConfigurationDescriptor,
     max_amount: Decimal,
 }
@@ -69,40 +115,48 @@ impl ExampleStrategy {
         currency_pair: CurrencyPair,
         spread: Decimal,
         max_amount: Decimal,
-        engine_context: Arc<EngineContext>,
+        context: Arc<dyn StrategyContext>,
     ) -> Box<Self> {
         let configuration_descriptor = ConfigurationDescriptor::new(
             "ExampleStrategy".into(),
             format!("{target_eai};{currency_pair}").as_str().into(),
         );
 
-        // amount_limit it's a limit for position changing for both sides
-        // it's equal to half of the max amount because an order that can change a position from
-        // a limit by sells to a limit by buys is possible
-        let amount_limit = max_amount * dec!(0.5);
-
-        let symbol = engine_context
-            .exchanges
-            .get(&target_eai)
-            .with_expect(|| format!("failed to get exchange from trading_engine for {target_eai}"))
-            .symbols
-            .get(&currency_pair)
-            .with_expect(|| format!("failed to get symbol from exchange for {currency_pair}"))
-            .clone();
-
-        engine_context
-            .balance_manager
-            .lock()
-            .set_target_amount_limit(configuration_descriptor, target_eai, symbol, amount_limit);
-
-        Box::new(ExampleStrategy {
+        let strategy = ExampleStrategy {
             target_eai,
             currency_pair,
             spread,
-            engine_context,
+            context,
             configuration_descriptor,
             max_amount,
-        })
+        };
+        strategy.set_target_amount_limit();
+
+        Box::new(strategy)
+    }
+
+    fn set_target_amount_limit(&self) {
+        // amount_limit it's a limit for position changing for both sides
+        // it's equal to half of the max amount because an order that can change a position from
+        // a limit by sells to a limit by buys is possible
+        let amount_limit = self.max_amount * dec!(0.5);
+
+        let symbol = self
+            .context
+            .symbol(self.target_eai, self.currency_pair)
+            .with_expect(|| {
+                format!(
+                    "failed to get symbol for {} and {}",
+                    self.target_eai, self.currency_pair
+                )
+            });
+
+        self.context.set_target_amount_limit(
+            self.configuration_descriptor,
+            self.target_eai,
+            symbol,
+            amount_limit,
+        );
     }
 
     fn strategy_name() -> &'static str {
@@ -130,13 +184,7 @@ impl ExampleStrategy {
 
         let current_spread = ask_min_price - bid_max_price;
 
-        let symbol = self
-            .engine_context
-            .exchanges
-            .get(&self.target_eai)?
-            .symbols
-            .get(&self.currency_pair)?
-            .clone();
+        let symbol = self.context.symbol(self.target_eai, self.currency_pair)?;
 
         let price = if current_spread < self.spread {
             let order_book_middle = (bid_max_price + ask_min_price) * dec!(0.5);
@@ -159,29 +207,9 @@ impl ExampleStrategy {
         explanation = {
             let mut explanation = Some(explanation);
 
-            // TODO: delete deep_clone
-            let orders = self
-                .engine_context
-                .exchanges
-                .iter()
-                .flat_map(|x| {
-                    x.orders
-                        .not_finished
-                        .iter()
-                        .map(|y| y.clone())
-                        .collect_vec()
-                })
-                .collect_vec();
-
-            let balance_manager = BalanceManager::clone_and_subtract_not_approved_data(
-                self.engine_context.balance_manager.clone(),
-                Some(&mut orders.iter()),
-            )
-            .expect("ExampleStrategy::calc_trading_context_by_side: failed to clone and subtract not approved data for BalanceManager");
-
-            amount = balance_manager
-                .lock()
-                .get_leveraged_balance_in_amount_currency_code(
+            amount = self
+                .context
+                .available_leveraged_balance(
                     self.configuration_descriptor,
                     side,
                     self.target_eai,
@@ -191,7 +219,7 @@ impl ExampleStrategy {
                 )
                 .with_expect(|| format!("Failed to get balance for {}", self.target_eai));
 
-            // This expect can happened if get_leveraged_balance_in_amount_currency_code() sets the explanation to None
+            // This expect can happened if available_leveraged_balance() sets the explanation to None
             explanation.expect(
                 "ExampleStrategy::calc_trading_context_by_side(): Explanation should be non None here"
             )
@@ -205,6 +233,7 @@ impl ExampleStrategy {
                 value: Some(TradeCycle {
                     order_role: OrderRole::Maker,
                     strategy_name: Self::strategy_name().to_string(),
+                    metadata: Default::default(),
                     disposition: TradeDisposition::new(
                         self.market_account_id(),
                         side,
@@ -257,4 +286,30 @@ impl DispositionStrategy for ExampleStrategy {
     fn configuration_descriptor(&self) -> ConfigurationDescriptor {
         self.configuration_descriptor
     }
+
+    fn tunable_parameters(&self) -> Vec<TunableParameter> {
+        vec![
+            TunableParameter::new(SPREAD_PARAMETER, ParameterType::Decimal, self.spread)
+                .with_range(Some(dec!(0)), None),
+            TunableParameter::new(
+                MAX_AMOUNT_PARAMETER,
+                ParameterType::Decimal,
+                self.max_amount,
+            )
+            .with_range(Some(dec!(0)), None),
+        ]
+    }
+
+    fn set_parameters(&mut self, parameters: &HashMap<String, Decimal>) -> Result<()> {
+        if let Some(spread) = parameters.get(SPREAD_PARAMETER) {
+            self.spread = *spread;
+        }
+
+        if let Some(max_amount) = parameters.get(MAX_AMOUNT_PARAMETER) {
+            self.max_amount = *max_amount;
+            self.set_target_amount_limit();
+        }
+
+        Ok(())
+    }
 }
@@ -16,4 +16,7 @@
     clippy::unwrap_used
 )]
 
+pub mod cross_venue_hedger;
 pub mod example_strategy;
+pub mod mean_reversion_maker;
+pub mod momentum_taker;
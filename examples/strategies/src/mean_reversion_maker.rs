@@ -0,0 +1,253 @@
+use anyhow::Result;
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::strategy_context::StrategyContext;
+use mmb_core::disposition_execution::{
+    PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
+};
+use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::Round;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderRole, OrderSide, OrderSnapshot, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MeanReversionMakerSettings {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPairSetting,
+    pub max_amount: Amount,
+    /// Count of last order book updates which mean mid price is calculated over
+    pub window: usize,
+    /// Distance of quotes from mean price relative to it
+    pub band: Decimal,
+}
+
+impl DispositionStrategySettings for MeanReversionMakerSettings {
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    fn currency_pair(&self) -> CurrencyPair {
+        if let CurrencyPairSetting::Ordinary { base, quote } = self.currency_pair {
+            CurrencyPair::from_codes(base, quote)
+        } else {
+            panic!(
+                "Incorrect currency pair setting enum type {:?}",
+                self.currency_pair
+            );
+        }
+    }
+
+    fn max_amount(&self) -> Amount {
+        self.max_amount
+    }
+}
+
+/// Quotes both sides around moving mean of mid price, expecting price to revert to the mean.
+/// Quotes never cross the book, so strategy always provides liquidity
+pub struct MeanReversionMakerStrategy {
+    market_account_id: MarketAccountId,
+    settings: MeanReversionMakerSettings,
+    context: Arc<dyn StrategyContext>,
+    configuration_descriptor: ConfigurationDescriptor,
+    mid_prices: VecDeque<Price>,
+}
+
+impl MeanReversionMakerStrategy {
+    pub fn new(
+        settings: MeanReversionMakerSettings,
+        context: Arc<dyn StrategyContext>,
+    ) -> Box<Self> {
+        let market_account_id =
+            MarketAccountId::new(settings.exchange_account_id(), settings.currency_pair());
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            Self::strategy_name().into(),
+            format!(
+                "{};{}",
+                market_account_id.exchange_account_id, market_account_id.currency_pair
+            )
+            .as_str()
+            .into(),
+        );
+
+        Box::new(MeanReversionMakerStrategy {
+            market_account_id,
+            settings,
+            context,
+            configuration_descriptor,
+            mid_prices: VecDeque::new(),
+        })
+    }
+
+    fn strategy_name() -> &'static str {
+        "MeanReversionMakerStrategy"
+    }
+
+    /// Add mid price and return mean of window if it's full
+    fn update_mean(&mut self, mid_price: Price) -> Option<Price> {
+        self.mid_prices.push_back(mid_price);
+        while self.mid_prices.len() > self.settings.window {
+            let _ = self.mid_prices.pop_front();
+        }
+
+        if self.mid_prices.is_empty() || self.mid_prices.len() < self.settings.window {
+            return None;
+        }
+
+        let sum: Price = self.mid_prices.iter().sum();
+        Some(sum / Decimal::from(self.mid_prices.len()))
+    }
+
+    fn maker_context(
+        &self,
+        side: OrderSide,
+        price: Price,
+        explanation: Explanation,
+    ) -> Option<TradingContextBySide> {
+        let symbol = self.context.symbol(
+            self.market_account_id.exchange_account_id,
+            self.market_account_id.currency_pair,
+        )?;
+
+        let price = match side {
+            OrderSide::Buy => symbol.price_round(price, Round::Floor),
+            OrderSide::Sell => symbol.price_round(price, Round::Ceiling),
+        };
+
+        let mut explanation = Some(explanation);
+        let available = self.context.available_leveraged_balance(
+            self.configuration_descriptor,
+            side,
+            self.market_account_id.exchange_account_id,
+            symbol.clone(),
+            price,
+            &mut explanation,
+        )?;
+        let amount = symbol.amount_round(self.settings.max_amount.min(available), Round::Floor);
+
+        Some(TradingContextBySide {
+            max_amount: self.settings.max_amount,
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role: OrderRole::Maker,
+                    strategy_name: Self::strategy_name().to_string(),
+                    metadata: Default::default(),
+                    disposition: TradeDisposition::new(self.market_account_id, side, price, amount),
+                }),
+                explanation: explanation.unwrap_or_default(),
+            }],
+        })
+    }
+}
+
+impl DispositionStrategy for MeanReversionMakerStrategy {
+    fn calculate_trading_context(
+        &mut self,
+        _event: &ExchangeEvent,
+        _now: DateTime,
+        local_snapshots_service: &LocalSnapshotsService,
+        explanation: &mut Explanation,
+    ) -> Option<TradingContext> {
+        let snapshot = local_snapshots_service.get_snapshot(self.market_account_id.market_id())?;
+        let (top_bid, _) = snapshot.get_top_bid()?;
+        let (top_ask, _) = snapshot.get_top_ask()?;
+
+        let mean = self.update_mean((top_bid + top_ask) * dec!(0.5))?;
+        let buy_price = (mean * (Decimal::ONE - self.settings.band)).min(top_bid);
+        let sell_price = (mean * (Decimal::ONE + self.settings.band)).max(top_ask);
+
+        Some(TradingContext::new(
+            self.maker_context(OrderSide::Buy, buy_price, explanation.clone())?,
+            self.maker_context(OrderSide::Sell, sell_price, explanation.clone())?,
+        ))
+    }
+
+    fn handle_order_fill(
+        &self,
+        _cloned_order: &Arc<OrderSnapshot>,
+        _price_slot: &PriceSlot,
+        _target_eai: ExchangeAccountId,
+        _cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+        self.configuration_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_core::disposition_execution::testing::{
+        StrategyTestHarness, SymbolBuilder, TestStrategyContext,
+    };
+
+    #[test]
+    fn quotes_around_mean_without_crossing_book() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let symbol = SymbolBuilder::new("btc", "usdt").build();
+        let market_account_id = MarketAccountId::new(exchange_account_id, symbol.currency_pair());
+
+        let context = Arc::new(TestStrategyContext::default());
+        context.add_symbol(exchange_account_id, symbol.clone());
+        context.set_available_balance(market_account_id, OrderSide::Buy, dec!(0.5));
+        context.set_available_balance(market_account_id, OrderSide::Sell, dec!(2));
+
+        let settings = MeanReversionMakerSettings {
+            exchange_account_id,
+            currency_pair: CurrencyPairSetting::Ordinary {
+                base: "btc".into(),
+                quote: "usdt".into(),
+            },
+            max_amount: dec!(1),
+            window: 2,
+            band: dec!(0.01),
+        };
+        let strategy = MeanReversionMakerStrategy::new(settings, context);
+        let mut harness = StrategyTestHarness::new(strategy, exchange_account_id);
+        let currency_pair = symbol.currency_pair();
+
+        let _ = harness.feed_order_book(
+            currency_pair,
+            &[(dec!(99), dec!(1))],
+            &[(dec!(101), dec!(1))],
+        );
+        assert!(harness.last_trading_context().is_none());
+
+        // mean is 101 and sell quote is moved up to top ask to not cross the book
+        let _ = harness.feed_order_book(
+            currency_pair,
+            &[(dec!(101), dec!(1))],
+            &[(dec!(103), dec!(1))],
+        );
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Buy),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Buy,
+                dec!(99.99),
+                dec!(0.5)
+            )]
+        );
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Sell),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Sell,
+                dec!(103),
+                dec!(1)
+            )]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::Result;
+use mmb_core::disposition_execution::strategy::DispositionStrategy;
+use mmb_core::disposition_execution::strategy_context::StrategyContext;
+use mmb_core::disposition_execution::{
+    PriceSlot, TradeCycle, TradeDisposition, TradingContext, TradingContextBySide,
+};
+use mmb_core::explanation::{Explanation, WithExplanation};
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_core::service_configuration::configuration_descriptor::ConfigurationDescriptor;
+use mmb_core::settings::{CurrencyPairSetting, DispositionStrategySettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::exchanges::symbol::Round;
+use mmb_domain::market::{CurrencyPair, ExchangeAccountId, MarketAccountId};
+use mmb_domain::order::snapshot::{Amount, OrderRole, OrderSide, OrderSnapshot, Price};
+use mmb_utils::cancellation_token::CancellationToken;
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MomentumTakerSettings {
+    pub exchange_account_id: ExchangeAccountId,
+    pub currency_pair: CurrencyPairSetting,
+    pub max_amount: Amount,
+    /// Count of last order book updates which price move is measured over
+    pub lookback: usize,
+    /// Relative move of mid price over lookback which starts taking in direction of the move
+    pub threshold: Decimal,
+    pub order_amount: Amount,
+}
+
+impl DispositionStrategySettings for MomentumTakerSettings {
+    fn exchange_account_id(&self) -> ExchangeAccountId {
+        self.exchange_account_id
+    }
+
+    fn currency_pair(&self) -> CurrencyPair {
+        if let CurrencyPairSetting::Ordinary { base, quote } = self.currency_pair {
+            CurrencyPair::from_codes(base, quote)
+        } else {
+            panic!(
+                "Incorrect currency pair setting enum type {:?}",
+                self.currency_pair
+            );
+        }
+    }
+
+    fn max_amount(&self) -> Amount {
+        self.max_amount
+    }
+}
+
+/// Takes liquidity in direction of price move: when mid price moves more than threshold over
+/// lookback, strategy crosses the spread with marketable limit order on the side of the move
+pub struct MomentumTakerStrategy {
+    market_account_id: MarketAccountId,
+    settings: MomentumTakerSettings,
+    context: Arc<dyn StrategyContext>,
+    configuration_descriptor: ConfigurationDescriptor,
+    mid_prices: VecDeque<Price>,
+}
+
+impl MomentumTakerStrategy {
+    pub fn new(settings: MomentumTakerSettings, context: Arc<dyn StrategyContext>) -> Box<Self> {
+        let market_account_id =
+            MarketAccountId::new(settings.exchange_account_id(), settings.currency_pair());
+        let configuration_descriptor = ConfigurationDescriptor::new(
+            Self::strategy_name().into(),
+            format!(
+                "{};{}",
+                market_account_id.exchange_account_id, market_account_id.currency_pair
+            )
+            .as_str()
+            .into(),
+        );
+
+        Box::new(MomentumTakerStrategy {
+            market_account_id,
+            settings,
+            context,
+            configuration_descriptor,
+            mid_prices: VecDeque::new(),
+        })
+    }
+
+    fn strategy_name() -> &'static str {
+        "MomentumTakerStrategy"
+    }
+
+    /// Add mid price and return its relative move since the oldest price of lookback
+    fn update_momentum(&mut self, mid_price: Price) -> Option<Decimal> {
+        self.mid_prices.push_back(mid_price);
+        while self.mid_prices.len() > self.settings.lookback + 1 {
+            let _ = self.mid_prices.pop_front();
+        }
+
+        if self.mid_prices.len() <= self.settings.lookback {
+            return None;
+        }
+
+        let oldest = *self.mid_prices.front()?;
+        (oldest > Decimal::ZERO).then(|| (mid_price - oldest) / oldest)
+    }
+
+    fn taker_context(
+        &self,
+        side: OrderSide,
+        price: Price,
+        explanation: Explanation,
+    ) -> Option<TradingContextBySide> {
+        let symbol = self.context.symbol(
+            self.market_account_id.exchange_account_id,
+            self.market_account_id.currency_pair,
+        )?;
+
+        let mut explanation = Some(explanation);
+        let available = self.context.available_leveraged_balance(
+            self.configuration_descriptor,
+            side,
+            self.market_account_id.exchange_account_id,
+            symbol.clone(),
+            price,
+            &mut explanation,
+        )?;
+        let amount = symbol.amount_round(self.settings.order_amount.min(available), Round::Floor);
+
+        Some(TradingContextBySide {
+            max_amount: self.settings.max_amount,
+            estimating: vec![WithExplanation {
+                value: Some(TradeCycle {
+                    order_role: OrderRole::Taker,
+                    strategy_name: Self::strategy_name().to_string(),
+                    metadata: Default::default(),
+                    disposition: TradeDisposition::new(self.market_account_id, side, price, amount),
+                }),
+                explanation: explanation.unwrap_or_default(),
+            }],
+        })
+    }
+}
+
+impl DispositionStrategy for MomentumTakerStrategy {
+    fn calculate_trading_context(
+        &mut self,
+        _event: &ExchangeEvent,
+        _now: DateTime,
+        local_snapshots_service: &LocalSnapshotsService,
+        explanation: &mut Explanation,
+    ) -> Option<TradingContext> {
+        let snapshot = local_snapshots_service.get_snapshot(self.market_account_id.market_id())?;
+        let (top_bid, _) = snapshot.get_top_bid()?;
+        let (top_ask, _) = snapshot.get_top_ask()?;
+
+        let momentum = self.update_momentum((top_bid + top_ask) * dec!(0.5))?;
+        let threshold = self.settings.threshold;
+
+        let empty = || TradingContextBySide::empty(1, explanation.clone());
+        let (buy_ctx, sell_ctx) = if momentum >= threshold {
+            // buy at top ask, so order is filled immediately
+            let buy_ctx = self.taker_context(OrderSide::Buy, top_ask, explanation.clone())?;
+            (buy_ctx, empty())
+        } else if momentum <= -threshold {
+            let sell_ctx = self.taker_context(OrderSide::Sell, top_bid, explanation.clone())?;
+            (empty(), sell_ctx)
+        } else {
+            (empty(), empty())
+        };
+
+        Some(TradingContext::new(buy_ctx, sell_ctx))
+    }
+
+    fn handle_order_fill(
+        &self,
+        _cloned_order: &Arc<OrderSnapshot>,
+        _price_slot: &PriceSlot,
+        _target_eai: ExchangeAccountId,
+        _cancellation_token: CancellationToken,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn configuration_descriptor(&self) -> ConfigurationDescriptor {
+        self.configuration_descriptor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_core::disposition_execution::testing::{
+        StrategyTestHarness, SymbolBuilder, TestStrategyContext,
+    };
+
+    #[test]
+    fn takes_in_direction_of_price_move() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let symbol = SymbolBuilder::new("btc", "usdt").build();
+        let market_account_id = MarketAccountId::new(exchange_account_id, symbol.currency_pair());
+
+        let context = Arc::new(TestStrategyContext::default());
+        context.add_symbol(exchange_account_id, symbol.clone());
+        context.set_available_balance(market_account_id, OrderSide::Buy, dec!(1));
+        context.set_available_balance(market_account_id, OrderSide::Sell, dec!(1));
+
+        let settings = MomentumTakerSettings {
+            exchange_account_id,
+            currency_pair: CurrencyPairSetting::Ordinary {
+                base: "btc".into(),
+                quote: "usdt".into(),
+            },
+            max_amount: dec!(1),
+            lookback: 2,
+            threshold: dec!(0.01),
+            order_amount: dec!(0.1),
+        };
+        let mut harness = StrategyTestHarness::new(
+            MomentumTakerStrategy::new(settings, context),
+            exchange_account_id,
+        );
+        let currency_pair = symbol.currency_pair();
+
+        // not enough prices for lookback yet
+        let _ = harness.feed_order_book(
+            currency_pair,
+            &[(dec!(99), dec!(1))],
+            &[(dec!(101), dec!(1))],
+        );
+        assert!(harness.last_trading_context().is_none());
+
+        let _ = harness.feed_order_book(
+            currency_pair,
+            &[(dec!(100), dec!(1))],
+            &[(dec!(102), dec!(1))],
+        );
+        let _ = harness.feed_order_book(
+            currency_pair,
+            &[(dec!(101), dec!(1))],
+            &[(dec!(103), dec!(1))],
+        );
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Buy),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Buy,
+                dec!(103),
+                dec!(0.1)
+            )]
+        );
+        assert!(harness.last_dispositions(OrderSide::Sell).is_empty());
+
+        let _ = harness.feed_order_book(
+            currency_pair,
+            &[(dec!(98), dec!(1))],
+            &[(dec!(100), dec!(1))],
+        );
+        assert_eq!(
+            harness.last_dispositions(OrderSide::Sell),
+            vec![TradeDisposition::new(
+                market_account_id,
+                OrderSide::Sell,
+                dec!(98),
+                dec!(0.1)
+            )]
+        );
+        assert!(harness.last_dispositions(OrderSide::Buy).is_empty());
+    }
+}
@@ -1,26 +1,30 @@
 use anyhow::{anyhow, bail, Context, Result};
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use dashmap::DashMap;
 use function_name::named;
-use hmac::digest::generic_array;
-use hmac::{Hmac, Mac};
 use hyper::header::CONTENT_TYPE;
 use hyper::http::request::Builder;
 use hyper::Uri;
 use itertools::Itertools;
-use mmb_utils::time::{get_current_milliseconds, u64_to_date_time};
+use mmb_utils::time::u64_to_date_time;
 use mmb_utils::DateTime;
 use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
 use serde_json::Value;
-use sha2::Sha256;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
-use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use std::time::{Duration, UNIX_EPOCH};
 use tokio::sync::broadcast;
+use url::form_urlencoded;
 
 use super::support::{
-    BinanceDerivativeAccountInfo, BinanceOrderInfo, BinancePosition, BinanceSpotAccountInfo,
+    get_order_book_side, BinanceDerivativeAccountInfo, BinanceMarginAccountInfo, BinanceOrderInfo,
+    BinancePosition,
+};
+use mmb_core::exchanges::binance_like::{
+    self, get_local_order_side, get_local_order_status, get_server_order_side, SpotAccountInfo,
+    LISTEN_KEY,
 };
 use mmb_core::exchanges::general::exchange::BoxExchangeClient;
 use mmb_core::exchanges::general::exchange::Exchange;
@@ -29,13 +33,17 @@ use mmb_core::exchanges::general::features::{
 };
 use mmb_core::exchanges::general::handlers::handle_order_filled::FillAmount;
 use mmb_core::exchanges::general::handlers::handle_order_filled::FillEvent;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::hosts::Hosts;
 use mmb_core::exchanges::rest_client::{
-    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+    ErrorHandler, ErrorHandlerData, QueryKey, RequestType, RestClient, RestHeaders, RestResponse,
+    UriBuilder,
 };
 use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
-use mmb_core::exchanges::traits::{ExchangeClientBuilder, ExchangeError, HandleMetricsCb};
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeError, HandleCashFlowCb, HandleMetricsCb,
+};
 use mmb_core::exchanges::traits::{
     ExchangeClientBuilderResult, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb,
     OrderCreatedCb, Support,
@@ -47,27 +55,77 @@ use mmb_core::exchanges::{
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use mmb_core::settings::ExchangeSettings;
 use mmb_domain::events::{AllowedEventSourceType, EventSourceType};
-use mmb_domain::events::{ExchangeBalance, ExchangeEvent, TradeId};
-use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::events::{CashFlowEvent, CashFlowKind};
+use mmb_domain::events::{ExchangeBalance, ExchangeEvent, Trade, TradeId};
+use mmb_domain::exchanges::symbol::{ContractType, Precision, PriceBandFilter, Symbol};
 use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId};
 use mmb_domain::market::{ExchangeAccountId, SpecificCurrencyPair};
 use mmb_domain::order::fill::OrderFillType;
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::*;
 use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, DerivativePosition};
+use mmb_domain::transfer::{
+    DepositAddress, ExternalTransferInfo, ExternalTransferStatus, NetworkStatus, WithdrawalRequest,
+};
 use mmb_utils::value_to_decimal::GetOrErr;
 use serde::{Deserialize, Serialize};
-use sha2::digest::generic_array::GenericArray;
 
-const LISTEN_KEY: &str = "listenKey";
+/// Binance API which is used by client
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BinanceMarket {
+    Spot,
+    /// USDⓈ-M futures: amount in base currency, margin and PnL in quote currency
+    UsdMFutures,
+    /// COIN-M futures: amount in contracts of fixed USD value, margin and PnL in base currency
+    CoinMFutures,
+    /// Cross margin account: spot symbols traded with borrowed funds
+    Margin,
+}
+
+impl BinanceMarket {
+    /// Market of `BinanceBuilder`, which works with USDⓈ-M futures when margin trading is enabled
+    pub fn from_margin_trading(is_margin_trading: bool) -> Self {
+        match is_margin_trading {
+            true => BinanceMarket::UsdMFutures,
+            false => BinanceMarket::Spot,
+        }
+    }
+
+    /// Market of `BinanceBuilder` for specified settings, margin account is used for spot only
+    pub fn from_settings(settings: &ExchangeSettings) -> Self {
+        match (settings.is_margin_trading, settings.use_margin_account) {
+            (false, true) => BinanceMarket::Margin,
+            (is_margin_trading, _) => Self::from_margin_trading(is_margin_trading),
+        }
+    }
+
+    pub fn is_futures(&self) -> bool {
+        matches!(
+            self,
+            BinanceMarket::UsdMFutures | BinanceMarket::CoinMFutures
+        )
+    }
+}
+
+/// Which positions of `positionRisk` response should be returned
+#[derive(Debug, Clone, Copy)]
+pub(super) enum BalancePositionOption {
+    /// Only opened positions, e.g. for closing them
+    NonZero,
+    /// Positions of traded currency pairs including zero ones.
+    /// `BalanceManager` knows only traded symbols and compares their positions with local ones,
+    /// so closed positions are needed too
+    TradedCurrencyPairs,
+}
 
 #[derive(Default)]
 pub struct ErrorHandlerBinance;
 
 pub struct RestHeadersBinance {
     pub api_key: String,
-    pub is_usd_m_futures: bool,
+    pub is_futures: bool,
 }
 
 impl RestHeaders for RestHeadersBinance {
@@ -77,7 +135,7 @@ impl RestHeaders for RestHeadersBinance {
         _uri: &Uri,
         _request_type: RequestType,
     ) -> Builder {
-        match self.is_usd_m_futures {
+        match self.is_futures {
             true => builder.header(CONTENT_TYPE, "application/x-www-form-urlencoded"),
             false => builder,
         }
@@ -87,32 +145,7 @@ impl RestHeaders for RestHeadersBinance {
 
 impl ErrorHandler for ErrorHandlerBinance {
     fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
-        //Binance is a little inconsistent: for failed responses sometimes they include
-        //only code or only success:false but sometimes both
-        if !(response.content.contains(r#""success":false"#)
-            || response.content.contains(r#""code""#))
-        {
-            return Ok(());
-        }
-
-        #[derive(Deserialize)]
-        struct Error {
-            msg: String,
-            code: i64,
-        }
-
-        let error: Error = serde_json::from_str(&response.content).map_err(|err| {
-            ExchangeError::parsing(format!(
-                "Unable to parse response.content: {err:?}\n{}",
-                response.content
-            ))
-        })?;
-
-        Err(ExchangeError::new(
-            ExchangeErrorType::Unknown,
-            error.msg,
-            Some(error.code),
-        ))
+        binance_like::check_rest_error(response)
     }
 
     fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
@@ -138,8 +171,23 @@ impl ErrorHandler for ErrorHandlerBinance {
 
 const EMPTY_RESPONSE_IS_OK: bool = false;
 
+/// Max count of orders in one request of futures `batchOrders`
+pub(super) const BATCH_ORDERS_MAX_COUNT: usize = 5;
+
+/// Allowed values of `limit` parameter of REST depth request
+const DEPTH_LIMITS: [usize; 7] = [5, 10, 20, 50, 100, 500, 1000];
+
+/// Progress of atomic replacement of order by amendment, see `Binance::replaced_orders`
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum ReplacedOrderState {
+    InProgress,
+    CancellationReceived,
+    Replaced,
+}
+
 pub struct Binance {
     pub settings: ExchangeSettings,
+    pub market: BinanceMarket,
     pub hosts: Hosts,
     pub id: ExchangeAccountId,
     pub order_created_callback: OrderCreatedCb,
@@ -147,6 +195,7 @@ pub struct Binance {
     pub handle_order_filled_callback: HandleOrderFilledCb,
     pub handle_trade_callback: HandleTradeCb,
     pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(super) handle_cash_flow_callback: HandleCashFlowCb,
 
     pub unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
     pub specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
@@ -172,6 +221,13 @@ pub struct Binance {
 
     // NOTE: None when websocket is disconnected
     pub(super) listen_key: RwLock<Option<String>>,
+
+    /// Unpaid interest of margin account from the last balance request
+    pub(super) margin_interest: Mutex<HashMap<CurrencyCode, Amount>>,
+
+    /// Exchange order ids of orders replaced by amendment. Cancellation of replaced order isn't
+    /// cancellation of local order, which keeps its client order id
+    pub(super) replaced_orders: DashMap<ExchangeOrderId, ReplacedOrderState>,
 }
 
 impl Binance {
@@ -205,21 +261,44 @@ impl Binance {
         lifetime_manager: Arc<AppLifetimeManager>,
         timeout_manager: Arc<TimeoutManager>,
         is_reducing_market_data: bool,
+    ) -> Self {
+        let market = BinanceMarket::from_settings(&settings);
+        Self::new_with_market(
+            id,
+            settings,
+            events_channel,
+            lifetime_manager,
+            timeout_manager,
+            is_reducing_market_data,
+            market,
+        )
+    }
+
+    pub fn new_with_market(
+        id: ExchangeAccountId,
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        timeout_manager: Arc<TimeoutManager>,
+        is_reducing_market_data: bool,
+        market: BinanceMarket,
     ) -> Self {
         let is_reducing_market_data = settings
             .is_reducing_market_data
             .unwrap_or(is_reducing_market_data);
 
-        let hosts = Self::make_hosts(settings.is_margin_trading);
+        let hosts = Self::make_hosts(market);
         let exchange_account_id = settings.exchange_account_id;
 
         Self {
             id,
+            market,
             order_created_callback: Box::new(|_, _, _| {}),
             order_cancelled_callback: Box::new(|_, _, _| {}),
             handle_order_filled_callback: Box::new(|_| {}),
             handle_trade_callback: Box::new(|_, _| {}),
             handle_metrics_callback: Box::new(|_| {}),
+            handle_cash_flow_callback: Box::new(|_| {}),
             unified_to_specific: Default::default(),
             specific_to_unified: Default::default(),
             supported_currencies: Default::default(),
@@ -227,7 +306,7 @@ impl Binance {
             traded_specific_currencies: Default::default(),
             last_trade_ids: Default::default(),
             subscribe_to_market_data: settings.subscribe_to_market_data,
-            rest_client: RestClient::new(
+            rest_client: RestClient::with_network_settings(
                 ErrorHandlerData::new(
                     EMPTY_RESPONSE_IS_OK,
                     exchange_account_id,
@@ -235,8 +314,9 @@ impl Binance {
                 ),
                 RestHeadersBinance {
                     api_key: settings.api_key.clone(),
-                    is_usd_m_futures: settings.is_margin_trading,
+                    is_futures: market.is_futures(),
                 },
+                &settings.network,
             ),
             timeout_manager,
             is_reducing_market_data,
@@ -245,29 +325,35 @@ impl Binance {
             events_channel,
             lifetime_manager,
             listen_key: Default::default(),
+            margin_interest: Default::default(),
+            replaced_orders: Default::default(),
         }
     }
 
-    pub fn make_hosts(is_margin_trading: bool) -> Hosts {
-        if is_margin_trading {
-            Hosts {
-                web_socket_host: "wss://fstream.binance.com",
-                web_socket2_host: "wss://fstream.binance.com",
-                rest_host: "https://fapi.binance.com",
-            }
-        } else {
-            Hosts {
+    pub fn make_hosts(market: BinanceMarket) -> Hosts {
+        match market {
+            BinanceMarket::Spot | BinanceMarket::Margin => Hosts {
                 web_socket_host: "wss://stream.binance.com:9443",
                 web_socket2_host: "wss://stream.binance.com:9443",
                 rest_host: "https://api.binance.com",
-            }
+            },
+            BinanceMarket::UsdMFutures => Hosts {
+                web_socket_host: "wss://fstream.binance.com",
+                web_socket2_host: "wss://fstream.binance.com",
+                rest_host: "https://fapi.binance.com",
+            },
+            BinanceMarket::CoinMFutures => Hosts {
+                web_socket_host: "wss://dstream.binance.com",
+                web_socket2_host: "wss://dstream.binance.com",
+                rest_host: "https://dapi.binance.com",
+            },
         }
     }
 
     #[named]
     pub(super) async fn request_listen_key(&self) -> Result<RestResponse, ExchangeError> {
         let path = self.get_uri_path("/fapi/v1/listenKey", "/api/v3/userDataStream");
-        let builder = UriBuilder::from_path(path);
+        let builder = UriBuilder::from_path(&path);
         let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
 
         self.rest_client
@@ -276,21 +362,14 @@ impl Binance {
     }
 
     pub(super) fn parse_listen_key(request_outcome: &RestResponse) -> Result<String> {
-        let data: Value = serde_json::from_str(&request_outcome.content)
-            .context("Unable to parse listen key response for Binance")?;
-
-        let listen_key = data[LISTEN_KEY]
-            .as_str()
-            .context("Unable to parse listen key field for Binance")?
-            .to_string();
-
-        Ok(listen_key)
+        binance_like::parse_listen_key(request_outcome)
+            .context("Unable to parse listen key for Binance")
     }
 
     #[named]
     pub async fn request_update_listen_key(&self, listen_key: &str) -> Result<(), ExchangeError> {
         let path = self.get_uri_path("/fapi/v1/listenKey", "/api/v3/userDataStream");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(&path);
         builder.add_kv(LISTEN_KEY, listen_key);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
@@ -316,34 +395,8 @@ impl Binance {
         todo!("is_websocket_reconnecting")
     }
 
-    fn write_signature_to_builder(&self, builder: &mut UriBuilder) {
-        let mut hmac = Hmac::<Sha256>::new_from_slice(self.settings.secret_key.as_bytes())
-            .expect("Unable to calculate hmac for Binance signature");
-        hmac.update(builder.query());
-
-        let hmac_bytes = hmac.finalize().into_bytes();
-
-        // hex representation of signature have double size of input data
-        builder.ensure_free_size(hmac_bytes.len() * 2);
-
-        struct HexAdapter<'a> {
-            bytes: &'a GenericArray<u8, generic_array::typenum::U32>,
-        }
-        impl<'a> Display for HexAdapter<'a> {
-            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-                write!(f, "{:x}", self.bytes)
-            }
-        }
-
-        let hexer = HexAdapter { bytes: &hmac_bytes };
-        builder.add_kv("signature", hexer);
-    }
-
     pub(super) fn add_authentification(&self, builder: &mut UriBuilder) {
-        let time_stamp = get_current_milliseconds();
-        builder.add_kv("timestamp", time_stamp);
-
-        self.write_signature_to_builder(builder);
+        binance_like::add_authentification(&self.settings.secret_key, builder);
     }
 
     pub(super) fn get_unified_currency_pair(
@@ -397,7 +450,7 @@ impl Binance {
 
         let client_order_id = {
             let field_name = match execution_type {
-                "CANCELED" | "EXPIRED" if !self.settings.is_margin_trading => "C",
+                "CANCELED" | "EXPIRED" if !self.market.is_futures() => "C",
                 _ => "c",
             };
             json_response[field_name]
@@ -417,6 +470,9 @@ impl Binance {
                 _ => log::error!("execution_type is NEW but order_status is {order_status} for message {msg_to_log}"),
             },
             "CANCELED" => match order_status {
+                "CANCELED" if self.is_replaced_order(exchange_order_id) => {
+                    log::info!("Cancellation of order {client_order_id} {exchange_order_id} replaced by amendment is skipped");
+                }
                 "CANCELED" => {
                     (self.order_cancelled_callback)(
                         client_order_id.into(),
@@ -457,6 +513,25 @@ impl Binance {
         Ok(())
     }
 
+    /// Cancellation event of order which is replaced by amendment is handled by amendment itself
+    fn is_replaced_order(&self, exchange_order_id: &str) -> bool {
+        let exchange_order_id = ExchangeOrderId::from(exchange_order_id);
+        let Some(mut state) = self.replaced_orders.get_mut(&exchange_order_id) else {
+            return false;
+        };
+
+        match *state {
+            ReplacedOrderState::InProgress => *state = ReplacedOrderState::CancellationReceived,
+            ReplacedOrderState::CancellationReceived => {}
+            ReplacedOrderState::Replaced => {
+                drop(state);
+                let _ = self.replaced_orders.remove(&exchange_order_id);
+            }
+        }
+
+        true
+    }
+
     pub(crate) fn get_currency_code(&self, currency_id: &CurrencyId) -> Option<CurrencyCode> {
         self.supported_currencies
             .get(currency_id)
@@ -551,14 +626,40 @@ impl Binance {
         Ok(ExchangeOrderId::new(order_id_str))
     }
 
-    pub(super) fn get_uri_path<'a>(
-        &self,
-        margin_trading_url: &'a str,
-        not_margin_trading_url: &'a str,
-    ) -> &'a str {
-        match self.settings.is_margin_trading {
-            true => margin_trading_url,
-            false => not_margin_trading_url,
+    pub(super) fn get_uri_path<'a>(&self, futures_url: &'a str, spot_url: &'a str) -> Cow<'a, str> {
+        match self.market {
+            BinanceMarket::UsdMFutures | BinanceMarket::CoinMFutures => {
+                self.get_futures_path(futures_url)
+            }
+            BinanceMarket::Spot => Cow::Borrowed(spot_url),
+            BinanceMarket::Margin => Self::get_margin_path(spot_url),
+        }
+    }
+
+    /// Margin account is traded by spot symbols, but its private endpoints are placed under
+    /// `/sapi/v1/margin`, e.g. `/api/v3/order` -> `/sapi/v1/margin/order`
+    fn get_margin_path(spot_url: &str) -> Cow<'_, str> {
+        match spot_url.strip_prefix("/api/v3/") {
+            Some(endpoint @ ("order" | "order/oco" | "openOrders" | "myTrades" | "account")) => {
+                Cow::Owned(format!("/sapi/v1/margin/{endpoint}"))
+            }
+            Some("userDataStream") => Cow::Borrowed("/sapi/v1/userDataStream"),
+            _ => Cow::Borrowed(spot_url),
+        }
+    }
+
+    /// COIN-M futures API has the same endpoints as USDⓈ-M futures API, but all of them are
+    /// placed under `/dapi/v1`, e.g. `/fapi/v2/positionRisk` -> `/dapi/v1/positionRisk`
+    pub(super) fn get_futures_path<'a>(&self, usd_m_futures_url: &'a str) -> Cow<'a, str> {
+        let versioned_endpoint = usd_m_futures_url
+            .strip_prefix("/fapi/")
+            .and_then(|versioned| versioned.split_once('/'));
+
+        match (self.market, versioned_endpoint) {
+            (BinanceMarket::CoinMFutures, Some((_version, endpoint))) => {
+                Cow::Owned(format!("/dapi/v1/{endpoint}"))
+            }
+            _ => Cow::Borrowed(usd_m_futures_url),
         }
     }
 
@@ -583,7 +684,7 @@ impl Binance {
         let specific_currency_pair = self.get_specific_currency_pair(order.currency_pair());
 
         let path = self.get_uri_path("/fapi/v1/order", "/api/v3/order");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(&path);
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("origClientOrderId", &client_order_id);
         self.add_authentification(&mut builder);
@@ -601,12 +702,12 @@ impl Binance {
         self.specific_order_info_to_unified(&specific_order)
     }
 
-    fn get_open_order_path(&self) -> &str {
+    fn get_open_order_path(&self) -> Cow<'_, str> {
         self.get_uri_path("/fapi/v1/openOrders", "/api/v3/openOrders")
     }
 
     pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path(self.get_open_order_path());
+        let mut builder = UriBuilder::from_path(&self.get_open_order_path());
         self.add_authentification(&mut builder);
 
         self.request_open_orders_by_http_header(builder).await
@@ -618,7 +719,7 @@ impl Binance {
     ) -> Result<RestResponse, ExchangeError> {
         let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
 
-        let mut builder = UriBuilder::from_path(self.get_open_order_path());
+        let mut builder = UriBuilder::from_path(&self.get_open_order_path());
         builder.add_kv("symbol", specific_currency_pair);
         self.add_authentification(&mut builder);
 
@@ -641,7 +742,8 @@ impl Binance {
         position: &ActivePosition,
         price: Option<Price>,
     ) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path("/fapi/v1/order");
+        let path = self.get_futures_path("/fapi/v1/order");
+        let mut builder = UriBuilder::from_path(&path);
         builder.add_kv("quantity", position.derivative.position.abs());
         let side = position.derivative.get_side().change_side();
         builder.add_kv("side", get_server_order_side(side));
@@ -671,7 +773,8 @@ impl Binance {
 
     #[named]
     pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
-        let mut builder = UriBuilder::from_path("/fapi/v2/positionRisk");
+        let path = self.get_futures_path("/fapi/v2/positionRisk");
+        let mut builder = UriBuilder::from_path(&path);
         self.add_authentification(&mut builder);
 
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
@@ -685,21 +788,38 @@ impl Binance {
         &self,
         response: &RestResponse,
     ) -> Result<Vec<ActivePosition>> {
-        self.get_active_positions(response)?.try_collect()
+        self.get_positions(response, BalancePositionOption::NonZero)?
+            .try_collect()
     }
 
-    pub(super) fn get_active_positions<'a>(
+    pub(super) fn get_positions<'a>(
         &'a self,
         response: &RestResponse,
+        option: BalancePositionOption,
     ) -> Result<impl Iterator<Item = Result<ActivePosition>> + 'a> {
         let binance_positions: Vec<BinancePosition> =
             serde_json::from_str(&response.content).context("Unable to parse Binance positions")?;
 
+        let traded_currency_pairs: HashSet<SpecificCurrencyPair> = match option {
+            BalancePositionOption::NonZero => HashSet::new(),
+            BalancePositionOption::TradedCurrencyPairs => self
+                .traded_specific_currencies
+                .lock()
+                .iter()
+                .cloned()
+                .collect(),
+        };
+
         let unified_currency_pairs = self.specific_to_unified.read();
         Ok(binance_positions
             .into_iter()
             // Binance returns all possible positions not only active
-            .filter(|position| !position.position_amount.is_zero())
+            .filter(move |position| match option {
+                BalancePositionOption::NonZero => !position.position_amount.is_zero(),
+                BalancePositionOption::TradedCurrencyPairs => {
+                    traded_currency_pairs.contains(&position.specific_currency_pair)
+                }
+            })
             .map(move |position| {
                 let currency_pair = unified_currency_pairs
                     .get(&position.specific_currency_pair)
@@ -723,10 +843,43 @@ impl Binance {
             }))
     }
 
+    /// Leverage is set for symbol and is applied to current position and new orders
+    #[named]
+    pub(super) async fn request_set_leverage(
+        &self,
+        currency_pair: CurrencyPair,
+        leverage: Decimal,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = self.get_futures_path("/fapi/v1/leverage");
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv("symbol", self.get_specific_currency_pair(currency_pair));
+        builder.add_kv("leverage", leverage);
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!("Set leverage {leverage} for {currency_pair}");
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn parse_set_leverage(&self, response: &RestResponse) -> Result<Decimal> {
+        #[derive(Deserialize)]
+        struct BinanceLeverage {
+            leverage: Decimal,
+        }
+
+        let leverage: BinanceLeverage = serde_json::from_str(&response.content)
+            .context("Unable to parse Binance leverage response")?;
+
+        Ok(leverage.leverage)
+    }
+
     #[named]
     pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
         let path = self.get_uri_path("/fapi/v2/account", "/api/v3/account");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(&path);
         self.add_authentification(&mut builder);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
 
@@ -739,7 +892,7 @@ impl Binance {
         &self,
         response: &RestResponse,
     ) -> Result<Vec<ExchangeBalance>> {
-        let binance_account_info: BinanceSpotAccountInfo =
+        let binance_account_info: SpotAccountInfo =
             serde_json::from_str(&response.content).context("Unable to parse account info")?;
 
         Ok(binance_account_info
@@ -755,6 +908,66 @@ impl Binance {
             .collect_vec())
     }
 
+    /// Borrowed funds are included in free balance, so it's available for trading like on spot.
+    /// Growth of unpaid interest since the previous request is reported as accrued fee
+    pub(super) fn parse_margin_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let margin_account_info: BinanceMarginAccountInfo = serde_json::from_str(&response.content)
+            .context("Unable to parse margin account info")?;
+
+        let mut balances = Vec::new();
+        for balance in &margin_account_info.user_assets {
+            if let Some(currency_code) = self.get_currency_code(&balance.asset.into()) {
+                self.handle_margin_interest(currency_code, balance.interest);
+                balances.push(ExchangeBalance {
+                    currency_code,
+                    balance: balance.free,
+                });
+            }
+        }
+
+        Ok(balances)
+    }
+
+    fn handle_margin_interest(&self, currency_code: CurrencyCode, interest: Amount) {
+        let previous_interest = self.margin_interest.lock().insert(currency_code, interest);
+
+        // interest accrued before launch or decreased by repayment isn't reported
+        let accrued_interest = match previous_interest {
+            Some(previous_interest) if interest > previous_interest => interest - previous_interest,
+            _ => return,
+        };
+
+        let traded_currency_pair = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .filter_map(|specific| self.specific_to_unified.read().get(specific).copied())
+            .find(|currency_pair| currency_pair.to_codes().to_array().contains(&currency_code));
+
+        let currency_pair = match traded_currency_pair {
+            Some(currency_pair) => currency_pair,
+            None => {
+                log::warn!(
+                    "Interest {accrued_interest} {currency_code} accrued on {} isn't reported because there are no traded currency pairs with it",
+                    self.settings.exchange_account_id
+                );
+                return;
+            }
+        };
+
+        (self.handle_cash_flow_callback)(CashFlowEvent {
+            exchange_account_id: self.settings.exchange_account_id,
+            currency_pair,
+            currency_code,
+            kind: CashFlowKind::AccruedFee,
+            amount: -accrued_interest,
+            transaction_time: Utc::now(),
+        });
+    }
+
     pub(super) fn parse_derivative_balance(
         &self,
         response: &RestResponse,
@@ -775,6 +988,30 @@ impl Binance {
             .collect_vec())
     }
 
+    /// Borrow or repay currency on cross margin account depending on `path`:
+    /// `/sapi/v1/margin/loan` or `/sapi/v1/margin/repay`
+    #[named]
+    pub(super) async fn request_margin_loan(
+        &self,
+        path: &str,
+        currency_code: CurrencyCode,
+        amount: Amount,
+    ) -> Result<RestResponse, ExchangeError> {
+        let currency_id = self.get_currency_id(currency_code)?;
+
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("asset", currency_id);
+        builder.add_kv("amount", amount);
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!("{path} {amount} {currency_code}");
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
     #[named]
     pub(super) async fn request_cancel_order(
         &self,
@@ -784,7 +1021,7 @@ impl Binance {
         let specific_currency_pair = self.get_specific_currency_pair(order.currency_pair());
 
         let path = self.get_uri_path("/fapi/v1/order", "/api/v3/order");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(&path);
         builder.add_kv("symbol", specific_currency_pair);
         builder.add_kv("orderId", exchange_order_id);
         self.add_authentification(&mut builder);
@@ -806,7 +1043,7 @@ impl Binance {
         let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
 
         let path = self.get_uri_path("/fapi/v1/userTrades", "/api/v3/myTrades");
-        let mut builder = UriBuilder::from_path(path);
+        let mut builder = UriBuilder::from_path(&path);
         if let Some(last_date_time_value) = last_date_time {
             builder.add_kv(
                 "startTime",
@@ -842,6 +1079,8 @@ impl Binance {
             time: u64,
             #[serde(alias = "maker")]
             is_maker: bool,
+            #[serde(alias = "buyer")]
+            is_buyer: bool,
         }
 
         impl BinanceMyTrade {
@@ -855,6 +1094,11 @@ impl Binance {
                 } else {
                     OrderRole::Taker
                 };
+                let side = if self.is_buyer {
+                    OrderSide::Buy
+                } else {
+                    OrderSide::Sell
+                };
 
                 let fee_currency_code = commission_currency_code.context("There is no suitable currency code to get specific_currency_pair for unified_order_trade converting")?;
                 Ok(OrderTrade::new(
@@ -863,6 +1107,7 @@ impl Binance {
                     datetime,
                     self.price,
                     self.amount,
+                    side,
                     order_role,
                     fee_currency_code,
                     None,
@@ -891,73 +1136,10 @@ impl Binance {
         order: &OrderRef,
     ) -> Result<RestResponse, ExchangeError> {
         let header = order.header();
-        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
-        let is_margin_trading = self.settings.is_margin_trading;
-
         let path = self.get_uri_path("/fapi/v1/order", "/api/v3/order");
-        let mut builder = UriBuilder::from_path(path);
-        builder.add_kv("symbol", specific_currency_pair);
-        builder.add_kv("side", get_server_order_side(header.side));
-        builder.add_kv("quantity", header.amount);
-        builder.add_kv("newClientOrderId", &header.client_order_id);
-
-        match (is_margin_trading, &header.options) {
-            (false, OrderOptions::User(user_order)) => match user_order {
-                UserOrder::Limit {
-                    price,
-                    execution_type,
-                } => {
-                    match execution_type {
-                        OrderExecutionType::None => {
-                            builder.add_kv("type", "LIMIT");
-                            builder.add_kv("timeInForce", "GTC");
-                        }
-                        OrderExecutionType::MakerOnly => builder.add_kv("type", "LIMIT_MAKER"),
-                    }
-                    builder.add_kv("price", price);
-                }
-                UserOrder::Market => builder.add_kv("type", "MARKET"),
-                UserOrder::StopLoss { stop_price } => {
-                    builder.add_kv("type", "STOP_LOSS");
-                    builder.add_kv("stopPrice", stop_price);
-                    builder.add_kv("timeInForce", "GTC");
-                }
-                UserOrder::TrailingStop {
-                    trailing_delta,
-                    stop_price,
-                } => {
-                    builder.add_kv("type", "STOP_LOSS");
-                    builder.add_kv("trailingDelta", trailing_delta);
-                    builder.add_kv("timeInForce", "GTC");
-
-                    if let Some(stop_price) = stop_price {
-                        builder.add_kv("stopPrice", stop_price)
-                    }
-                }
-            },
-            (true, OrderOptions::User(user_order)) => match user_order {
-                UserOrder::Limit {
-                    price,
-                    execution_type,
-                } => {
-                    builder.add_kv("type", "LIMIT");
-                    builder.add_kv("price", price);
-                    match *execution_type == OrderExecutionType::MakerOnly {
-                        true => builder.add_kv("timeInForce", "GTX"),
-                        false => builder.add_kv("timeInForce", "GTC"),
-                    }
-                }
-                UserOrder::Market => builder.add_kv("type", "MARKET"),
-                UserOrder::StopLoss { stop_price } => {
-                    builder.add_kv("type", "STOP_MARKET");
-                    builder.add_kv("stopPrice", stop_price);
-                    builder.add_kv("timeInForce", "GTC");
-                }
-                UserOrder::TrailingStop { .. } => {
-                    unimplemented!("Trailing stop order not implemented for futures now.")
-                }
-            },
-            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        let mut builder = UriBuilder::from_path(&path);
+        for (key, value) in self.get_create_order_params(header)? {
+            builder.add_kv(key, value);
         }
 
         self.add_authentification(&mut builder);
@@ -970,68 +1152,410 @@ impl Binance {
             .await
     }
 
+    /// Spot and margin API only, stop order of OCO group is created as `STOP_LOSS` order
     #[named]
-    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
-        let path = self.get_uri_path("/fapi/v1/exchangeInfo", "/api/v3/exchangeInfo");
-        let builder = UriBuilder::from_path(path);
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+    pub(super) async fn request_create_oco_order(
+        &self,
+        limit_order: &OrderRef,
+        stop_order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let limit_header = limit_order.header();
+        let stop_header = stop_order.header();
+
+        let price = match &limit_header.options {
+            OrderOptions::User(UserOrder::Limit { price, .. }) => *price,
+            options => {
+                return Err(ExchangeError::unknown(&format!(
+                    "Order {} of OCO group should be limit order, but it has options {options:?}",
+                    limit_header.client_order_id
+                )))
+            }
+        };
+        let stop_price = match &stop_header.options {
+            OrderOptions::User(UserOrder::StopLoss { stop_price, .. }) => *stop_price,
+            options => {
+                return Err(ExchangeError::unknown(&format!(
+                "Order {} of OCO group should be stop-loss order, but it has options {options:?}",
+                stop_header.client_order_id
+            )))
+            }
+        };
+
+        let specific_currency_pair = self.get_specific_currency_pair(limit_header.currency_pair);
+        let path = match self.market {
+            BinanceMarket::Margin => Self::get_margin_path("/api/v3/order/oco"),
+            _ => Cow::Borrowed("/api/v3/order/oco"),
+        };
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv("symbol", &specific_currency_pair);
+        builder.add_kv("side", get_server_order_side(limit_header.side));
+        builder.add_kv("quantity", limit_header.amount);
+        builder.add_kv("price", price);
+        builder.add_kv("stopPrice", stop_price);
+        builder.add_kv("limitClientOrderId", &limit_header.client_order_id);
+        builder.add_kv("stopClientOrderId", &stop_header.client_order_id);
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
 
+        let log_args = format!("Create OCO orders for {limit_header:?} {stop_header:?}");
         self.rest_client
-            .get(uri, function_name!(), "".to_string())
+            .post(uri, Some(query), function_name!(), log_args)
             .await
     }
 
-    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
-        let deserialized: Value = serde_json::from_str(&response.content)
-            .expect("Unable to deserialize response from Binance");
-        let symbols = deserialized
-            .get("symbols")
-            .and_then(|symbols| symbols.as_array())
-            .ok_or_else(|| anyhow!("Unable to get symbols array from Binance"))?;
+    /// Returns exchange order ids of limit and stop orders of created OCO group
+    pub(super) fn parse_create_oco_order(
+        &self,
+        response: &RestResponse,
+        limit_order: &OrderRef,
+        stop_order: &OrderRef,
+    ) -> Result<(ExchangeOrderId, ExchangeOrderId), ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OcoOrder {
+            order_id: u64,
+            client_order_id: String,
+        }
 
-        let mut supported_symbols = Vec::new();
-        for symbol in symbols {
-            if Binance::is_unsupported_symbol(symbol) {
-                continue;
-            }
+        #[derive(Deserialize)]
+        struct OcoOrderList {
+            orders: Vec<OcoOrder>,
+        }
 
-            let base_currency_id = &symbol
-                .get_as_str("baseAsset")
-                .expect("Unable to get base currency id from Binance");
-            let quote_currency_id = &symbol
-                .get_as_str("quoteAsset")
-                .expect("Unable to get quote currency id from Binance");
-            let base = base_currency_id.as_str().into();
-            let quote = quote_currency_id.as_str().into();
+        let order_list: OcoOrderList = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse OCO orders response: {err:?}"))
+        })?;
 
-            let specific_currency_pair_id = symbol
-                .get_as_str("symbol")
-                .expect("Unable to get specific currency pair");
-            let specific_currency_pair = specific_currency_pair_id.as_str().into();
-            let unified_currency_pair = CurrencyPair::from_codes(base, quote);
-            self.unified_to_specific
-                .write()
-                .insert(unified_currency_pair, specific_currency_pair);
+        let get_exchange_order_id = |order: &OrderRef| {
+            let client_order_id = order.client_order_id();
+            order_list
+                .orders
+                .iter()
+                .find(|x| x.client_order_id == client_order_id.as_str())
+                .map(|x| ExchangeOrderId::new(x.order_id.to_string().into()))
+                .ok_or_else(|| {
+                    ExchangeError::parsing(format!(
+                        "Order {client_order_id} isn't found in OCO orders response"
+                    ))
+                })
+        };
 
-            self.specific_to_unified
-                .write()
-                .insert(specific_currency_pair, unified_currency_pair);
+        Ok((
+            get_exchange_order_id(limit_order)?,
+            get_exchange_order_id(stop_order)?,
+        ))
+    }
 
-            let (amount_currency_code, balance_currency_code) =
-                match self.settings.is_margin_trading {
-                    // TODO need explicit specify type of using Binance futures markets
-                    // true => (quote, Some(base)),
-                    true => (base, Some(quote)),
-                    false => (base, None),
-                };
+    /// Spot API only. Conditional orders can't be amended in place, so the order is cancelled
+    /// and placed again with new trigger parameters and the same client order id by one request
+    #[named]
+    pub(super) async fn request_amend_conditional_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_user_order: &UserOrder,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut header = order.header().clone();
+        header.amount = order.amount();
+        header.options = OrderOptions::User(*new_user_order);
 
-            let mut min_amount = None;
+        let mut builder = UriBuilder::from_path("/api/v3/order/cancelReplace");
+        for (key, value) in self.get_create_order_params(&header)? {
+            builder.add_kv(key, value);
+        }
+        builder.add_kv("cancelReplaceMode", "STOP_ON_FAILURE");
+        builder.add_kv("cancelOrderId", exchange_order_id);
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!(
+            "Amend order {} to {new_user_order:?}",
+            header.client_order_id
+        );
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
+    /// Returns exchange order id of the order placed instead of amended one
+    pub(super) fn parse_amend_conditional_order(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct NewOrderResponse {
+            order_id: u64,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CancelReplaceResponse {
+            new_order_response: NewOrderResponse,
+        }
+
+        let response: CancelReplaceResponse =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse cancel-replace response: {err:?}"))
+            })?;
+
+        let order_id = response.new_order_response.order_id.to_string();
+        Ok(ExchangeOrderId::new(order_id.into()))
+    }
+
+    /// Futures API only, count of orders is limited by `BATCH_ORDERS_MAX_COUNT`
+    #[named]
+    pub(super) async fn request_create_orders_batch(
+        &self,
+        orders: &[OrderRef],
+    ) -> Result<RestResponse, ExchangeError> {
+        let batch_orders = orders
+            .iter()
+            .map(|order| {
+                self.get_create_order_params(order.header()).map(|params| {
+                    params
+                        .into_iter()
+                        .map(|(key, value)| (key.to_owned(), Value::String(value)))
+                        .collect::<serde_json::Map<_, _>>()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let batch_orders = serde_json::to_string(&batch_orders).map_err(|err| {
+            ExchangeError::unknown(&format!("Unable to serialize batch orders: {err:?}"))
+        })?;
+
+        let path = self.get_futures_path("/fapi/v1/batchOrders");
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv(
+            "batchOrders",
+            form_urlencoded::byte_serialize(batch_orders.as_bytes()).collect::<String>(),
+        );
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!(
+            "Create batch of orders {}",
+            orders.iter().map(|x| x.client_order_id()).join(", ")
+        );
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
+    /// Results are in the same order as orders, failed orders contain error instead of order
+    pub(super) fn parse_create_orders_batch(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<CreateOrderResult>, ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum BatchOrderResult {
+            #[serde(rename_all = "camelCase")]
+            Created {
+                order_id: u64,
+            },
+            Failed {
+                code: i64,
+                msg: String,
+            },
+        }
+
+        let batch_results: Vec<BatchOrderResult> = serde_json::from_str(&response.content)
+            .map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse batch orders response: {err:?}"))
+            })?;
+
+        Ok(batch_results
+            .into_iter()
+            .map(|batch_result| match batch_result {
+                BatchOrderResult::Created { order_id } => CreateOrderResult::succeed(
+                    &ExchangeOrderId::new(order_id.to_string().into()),
+                    EventSourceType::Rest,
+                ),
+                BatchOrderResult::Failed { code, msg } => {
+                    let mut error = ExchangeError::new(ExchangeErrorType::Unknown, msg, Some(code));
+                    error.error_type = ErrorHandlerBinance.clarify_error_type(&error);
+                    CreateOrderResult::failed(error, EventSourceType::Rest)
+                }
+            })
+            .collect())
+    }
+
+    fn get_create_order_params(
+        &self,
+        header: &OrderHeader,
+    ) -> Result<Vec<(QueryKey, String)>, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+        let is_futures = self.market.is_futures();
+
+        let mut params = vec![
+            ("symbol", specific_currency_pair.to_string()),
+            ("side", get_server_order_side(header.side).to_owned()),
+            ("quantity", header.amount.to_string()),
+            ("newClientOrderId", header.client_order_id.to_string()),
+        ];
+
+        match (is_futures, &header.options) {
+            (false, OrderOptions::User(user_order)) => match user_order {
+                UserOrder::Limit {
+                    price,
+                    execution_type,
+                } => {
+                    match execution_type {
+                        OrderExecutionType::None => {
+                            params.push(("type", "LIMIT".to_owned()));
+                            params.push(("timeInForce", "GTC".to_owned()));
+                        }
+                        OrderExecutionType::MakerOnly => {
+                            params.push(("type", "LIMIT_MAKER".to_owned()))
+                        }
+                    }
+                    params.push(("price", price.to_string()));
+                    if let Some(display_amount) = header.display_amount {
+                        params.push(("icebergQty", display_amount.to_string()));
+                    }
+                }
+                UserOrder::Market => params.push(("type", "MARKET".to_owned())),
+                UserOrder::StopLoss { stop_price, .. } => {
+                    params.push(("type", "STOP_LOSS".to_owned()));
+                    params.push(("stopPrice", stop_price.to_string()));
+                    params.push(("timeInForce", "GTC".to_owned()));
+                }
+                UserOrder::TrailingStop {
+                    trailing_delta,
+                    stop_price,
+                    ..
+                } => {
+                    params.push(("type", "STOP_LOSS".to_owned()));
+                    params.push(("trailingDelta", trailing_delta.to_string()));
+                    params.push(("timeInForce", "GTC".to_owned()));
+
+                    if let Some(stop_price) = stop_price {
+                        params.push(("stopPrice", stop_price.to_string()))
+                    }
+                }
+            },
+            (true, OrderOptions::User(user_order)) => match user_order {
+                UserOrder::Limit {
+                    price,
+                    execution_type,
+                } => {
+                    params.push(("type", "LIMIT".to_owned()));
+                    params.push(("price", price.to_string()));
+                    match *execution_type == OrderExecutionType::MakerOnly {
+                        true => params.push(("timeInForce", "GTX".to_owned())),
+                        false => params.push(("timeInForce", "GTC".to_owned())),
+                    }
+                }
+                UserOrder::Market => params.push(("type", "MARKET".to_owned())),
+                UserOrder::StopLoss {
+                    stop_price,
+                    trigger_price_type,
+                } => {
+                    params.push(("type", "STOP_MARKET".to_owned()));
+                    params.push(("stopPrice", stop_price.to_string()));
+                    params.push(("timeInForce", "GTC".to_owned()));
+                    match trigger_price_type {
+                        TriggerPriceType::Last => {
+                            params.push(("workingType", "CONTRACT_PRICE".to_owned()))
+                        }
+                        TriggerPriceType::Mark => {
+                            params.push(("workingType", "MARK_PRICE".to_owned()))
+                        }
+                        TriggerPriceType::Index => {
+                            return Err(ExchangeError::unknown(
+                                "Trigger by index price isn't supported",
+                            ))
+                        }
+                    }
+                }
+                UserOrder::TrailingStop { .. } => {
+                    unimplemented!("Trailing stop order not implemented for futures now.")
+                }
+            },
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        Ok(params)
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let path = self.get_uri_path("/fapi/v1/exchangeInfo", "/api/v3/exchangeInfo");
+        let builder = UriBuilder::from_path(&path);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let deserialized: Value = serde_json::from_str(&response.content)
+            .expect("Unable to deserialize response from Binance");
+        let symbols = deserialized
+            .get("symbols")
+            .and_then(|symbols| symbols.as_array())
+            .ok_or_else(|| anyhow!("Unable to get symbols array from Binance"))?;
+
+        let currency_aliases = self.get_currency_aliases();
+        let mut supported_symbols = Vec::new();
+        for symbol in symbols {
+            if self.is_unsupported_symbol(symbol) {
+                continue;
+            }
+
+            let base_currency_id = &symbol
+                .get_as_str("baseAsset")
+                .expect("Unable to get base currency id from Binance");
+            let quote_currency_id = &symbol
+                .get_as_str("quoteAsset")
+                .expect("Unable to get quote currency id from Binance");
+            let base = currency_aliases.unify(base_currency_id.as_str().into());
+            let quote = currency_aliases.unify(quote_currency_id.as_str().into());
+
+            let specific_currency_pair_id = symbol
+                .get_as_str("symbol")
+                .expect("Unable to get specific currency pair");
+            let specific_currency_pair = specific_currency_pair_id.as_str().into();
+            let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+            self.unified_to_specific
+                .write()
+                .insert(unified_currency_pair, specific_currency_pair);
+
+            self.specific_to_unified
+                .write()
+                .insert(specific_currency_pair, unified_currency_pair);
+
+            let (amount_currency_code, balance_currency_code) = match self.market {
+                BinanceMarket::Spot | BinanceMarket::Margin => (base, None),
+                BinanceMarket::UsdMFutures => (base, Some(quote)),
+                // amount is specified in contracts of fixed value in quote currency
+                BinanceMarket::CoinMFutures => (quote, Some(base)),
+            };
+            let contract_size = match self.market {
+                BinanceMarket::CoinMFutures => Some(
+                    symbol["contractSize"]
+                        .as_u64()
+                        .map(Decimal::from)
+                        .with_context(|| {
+                            format!("Unable to get contract size for {specific_currency_pair:?}")
+                        })?,
+                ),
+                _ => None,
+            };
+
+            let mut min_amount = None;
             let mut max_amount = None;
             let mut min_price = None;
             let mut max_price = None;
             let mut min_cost = None;
             let mut price_tick = None;
             let mut amount_tick = None;
+            let mut price_band = None;
 
             let filters = symbol
                 .get("filters")
@@ -1051,11 +1575,37 @@ impl Binance {
                         amount_tick = filter.get_as_decimal("stepSize");
                     }
                     "MIN_NOTIONAL" => {
-                        min_cost = match self.settings.is_margin_trading {
+                        min_cost = match self.market.is_futures() {
                             true => filter.get_as_decimal("notional"),
                             false => filter.get_as_decimal("minNotional"),
                         };
                     }
+                    "PERCENT_PRICE" => {
+                        price_band = filter
+                            .get_as_decimal("multiplierUp")
+                            .zip(filter.get_as_decimal("multiplierDown"))
+                            .map(|(multiplier_up, multiplier_down)| PriceBandFilter {
+                                multiplier_up,
+                                multiplier_down,
+                            });
+                    }
+                    "PERCENT_PRICE_BY_SIDE" => {
+                        // the narrowest band is used, because side-specific band isn't supported
+                        let multiplier_up = filter
+                            .get_as_decimal("bidMultiplierUp")
+                            .zip(filter.get_as_decimal("askMultiplierUp"))
+                            .map(|(bid, ask)| bid.min(ask));
+                        let multiplier_down = filter
+                            .get_as_decimal("bidMultiplierDown")
+                            .zip(filter.get_as_decimal("askMultiplierDown"))
+                            .map(|(bid, ask)| bid.max(ask));
+                        price_band = multiplier_up.zip(multiplier_down).map(
+                            |(multiplier_up, multiplier_down)| PriceBandFilter {
+                                multiplier_up,
+                                multiplier_down,
+                            },
+                        );
+                    }
                     _ => {}
                 }
             }
@@ -1077,7 +1627,7 @@ impl Binance {
             };
 
             let symbol = Symbol::new(
-                self.settings.is_margin_trading,
+                self.market.is_futures(),
                 base_currency_id.as_str().into(),
                 base,
                 quote_currency_id.as_str().into(),
@@ -1092,6 +1642,14 @@ impl Binance {
                 price_precision,
                 amount_precision,
             );
+            let symbol = match price_band {
+                Some(price_band) => symbol.with_price_band(price_band),
+                None => symbol,
+            };
+            let symbol = match contract_size {
+                Some(contract_size) => symbol.with_contract(ContractType::Inverse, contract_size),
+                None => symbol,
+            };
 
             supported_symbols.push(Arc::new(symbol))
         }
@@ -1099,7 +1657,12 @@ impl Binance {
         Ok(supported_symbols)
     }
 
-    fn is_unsupported_symbol(symbol: &Value) -> bool {
+    fn is_unsupported_symbol(&self, symbol: &Value) -> bool {
+        if self.market == BinanceMarket::CoinMFutures {
+            // Delivery contracts are listed too (e.g. BTCUSD_240329), only perpetual are supported
+            return symbol["contractType"] != "PERPETUAL" || symbol["contractStatus"] != "TRADING";
+        }
+
         let code = &symbol
             .get_as_str("symbol")
             .expect("Unable to get symbol code from Binance");
@@ -1119,7 +1682,7 @@ impl Binance {
     #[named]
     pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
         let path = self.get_uri_path("/fapi/v1/time", "/api/v3/time");
-        let builder = UriBuilder::from_path(path);
+        let builder = UriBuilder::from_path(&path);
         let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
 
         self.rest_client
@@ -1128,40 +1691,339 @@ impl Binance {
     }
 
     pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        binance_like::parse_server_time(response)
+            .context("Failed to parse Binance get time response")
+    }
+
+    #[named]
+    pub(super) async fn request_historical_trades(
+        &self,
+        currency_pair: CurrencyPair,
+        from_trade_id: u64,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+
+        let path = self.get_uri_path("/fapi/v1/historicalTrades", "/api/v3/historicalTrades");
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("fromId", from_trade_id);
+        builder.add_kv("limit", 1000);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_historical_trades(&self, response: &RestResponse) -> Result<Vec<Trade>> {
         #[derive(Deserialize)]
-        struct ServerTime {
-            #[serde(rename = "serverTime")]
+        #[serde(rename_all = "camelCase")]
+        struct BinanceHistoricalTrade {
+            id: u64,
+            price: Price,
+            qty: Amount,
             time: i64,
+            is_buyer_maker: bool,
         }
 
-        let server_time_struct: ServerTime = serde_json::from_str(&response.content)
-            .context("Failed to parse Binance get time response")?;
-        Ok(server_time_struct.time)
+        let trades: Vec<BinanceHistoricalTrade> = serde_json::from_str(&response.content)
+            .context("Failed to parse Binance historical trades response")?;
+
+        Ok(trades
+            .into_iter()
+            .map(|trade| Trade {
+                trade_id: TradeId::Number(trade.id),
+                price: trade.price,
+                quantity: trade.qty,
+                side: match trade.is_buyer_maker {
+                    true => OrderSide::Sell,
+                    false => OrderSide::Buy,
+                },
+                transaction_time: Utc.timestamp_millis(trade.time),
+            })
+            .collect())
     }
-}
 
-pub(super) fn get_server_order_side(side: OrderSide) -> &'static str {
-    match side {
-        OrderSide::Buy => "BUY",
-        OrderSide::Sell => "SELL",
+    #[named]
+    pub(super) async fn request_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: usize,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+
+        let limit = DEPTH_LIMITS
+            .into_iter()
+            .find(|&limit| limit >= depth)
+            .unwrap_or(DEPTH_LIMITS[DEPTH_LIMITS.len() - 1]);
+
+        let path = self.get_uri_path("/fapi/v1/depth", "/api/v3/depth");
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("limit", limit);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
     }
-}
 
-pub(super) fn get_local_order_side(side: &str) -> OrderSide {
-    match side {
-        "BUY" => OrderSide::Buy,
-        "SELL" => OrderSide::Sell,
-        _ => panic!("Unexpected order side"),
+    pub(super) fn parse_order_book_snapshot(
+        &self,
+        response: &RestResponse,
+    ) -> Result<OrderBookData> {
+        let data: Value = serde_json::from_str(&response.content)
+            .context("Failed to parse Binance order book snapshot response")?;
+
+        let get_side = |side: &str| {
+            data[side]
+                .as_array()
+                .with_context(|| format!("Unable to get {side} of Binance order book snapshot"))
+                .and_then(|levels| get_order_book_side(levels))
+        };
+
+        Ok(OrderBookData::new(get_side("asks")?, get_side("bids")?))
     }
-}
 
-fn get_local_order_status(status: &str) -> OrderStatus {
-    match status {
-        "NEW" | "PARTIALLY_FILLED" => OrderStatus::Created,
-        "FILLED" => OrderStatus::Completed,
-        "PENDING_CANCEL" => OrderStatus::Canceling,
-        "CANCELED" | "EXPIRED" | "REJECTED" => OrderStatus::Canceled,
-        _ => panic!("Unexpected order status"),
+    pub(super) fn get_currency_id(
+        &self,
+        currency_code: CurrencyCode,
+    ) -> Result<CurrencyId, ExchangeError> {
+        self.supported_currencies
+            .iter()
+            .find(|x| *x.value() == currency_code)
+            .map(|x| *x.key())
+            .ok_or_else(|| ExchangeError::parsing(format!("Unsupported currency {currency_code}")))
+    }
+
+    #[named]
+    pub(super) async fn request_coins_config(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/sapi/v1/capital/config/getall");
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_network_status(
+        &self,
+        response: &RestResponse,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Result<NetworkStatus> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CoinConfig {
+            coin: String,
+            network_list: Vec<CoinNetwork>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CoinNetwork {
+            network: String,
+            deposit_enable: bool,
+            withdraw_enable: bool,
+        }
+
+        let currency_id = self.get_currency_id(currency_code)?;
+        let coins: Vec<CoinConfig> = serde_json::from_str(&response.content)
+            .context("Unable to parse Binance coins config")?;
+
+        let coin_network = coins
+            .into_iter()
+            .filter(|coin| coin.coin == currency_id.as_str())
+            .flat_map(|coin| coin.network_list)
+            .find(|coin_network| coin_network.network == network)
+            .with_context(|| format!("Network {network} isn't found for {currency_code}"))?;
+
+        Ok(NetworkStatus {
+            is_deposit_enabled: coin_network.deposit_enable,
+            is_withdrawal_enabled: coin_network.withdraw_enable,
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_deposit_address(
+        &self,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Result<RestResponse, ExchangeError> {
+        let currency_id = self.get_currency_id(currency_code)?;
+
+        let mut builder = UriBuilder::from_path("/sapi/v1/capital/deposit/address");
+        builder.add_kv("coin", currency_id);
+        builder.add_kv("network", network);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("{currency_code} {network}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_deposit_address(&self, response: &RestResponse) -> Result<DepositAddress> {
+        #[derive(Deserialize)]
+        struct BinanceDepositAddress {
+            address: String,
+            #[serde(default)]
+            tag: String,
+        }
+
+        let deposit_address: BinanceDepositAddress = serde_json::from_str(&response.content)
+            .context("Unable to parse Binance deposit address")?;
+
+        Ok(DepositAddress {
+            address: deposit_address.address,
+            // empty tag is returned for networks without memo
+            tag: Some(deposit_address.tag).filter(|tag| !tag.is_empty()),
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_withdraw(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<RestResponse, ExchangeError> {
+        let currency_id = self.get_currency_id(request.currency_code)?;
+
+        let mut builder = UriBuilder::from_path("/sapi/v1/capital/withdraw/apply");
+        builder.add_kv("coin", currency_id);
+        builder.add_kv("network", &request.network);
+        builder.add_kv("address", &request.address.address);
+        if let Some(tag) = &request.address.tag {
+            builder.add_kv("addressTag", tag);
+        }
+        builder.add_kv("amount", request.amount);
+        self.add_authentification(&mut builder);
+
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!("{request:?}");
+        self.rest_client
+            .post(uri, Some(query), function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn parse_withdrawal_id(&self, response: &RestResponse) -> Result<String> {
+        #[derive(Deserialize)]
+        struct WithdrawalId {
+            id: String,
+        }
+
+        let withdrawal: WithdrawalId = serde_json::from_str(&response.content)
+            .context("Unable to parse Binance withdrawal id")?;
+
+        Ok(withdrawal.id)
+    }
+
+    #[named]
+    pub(super) async fn request_withdrawal_history(
+        &self,
+        currency_code: CurrencyCode,
+        withdrawal_id: &str,
+    ) -> Result<RestResponse, ExchangeError> {
+        let currency_id = self.get_currency_id(currency_code)?;
+
+        let mut builder = UriBuilder::from_path("/sapi/v1/capital/withdraw/history");
+        builder.add_kv("coin", currency_id);
+        builder.add_kv("idList", withdrawal_id);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("withdrawal {withdrawal_id}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    /// Withdrawal statuses: 0 - email sent, 1 - cancelled, 2 - awaiting approval, 3 - rejected,
+    /// 4 - processing, 5 - failure, 6 - completed
+    pub(super) fn parse_withdrawal(
+        &self,
+        response: &RestResponse,
+        withdrawal_id: &str,
+    ) -> Result<ExternalTransferInfo> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct BinanceWithdrawal {
+            id: String,
+            status: u8,
+            #[serde(default)]
+            tx_id: String,
+        }
+
+        let withdrawals: Vec<BinanceWithdrawal> = serde_json::from_str(&response.content)
+            .context("Unable to parse Binance withdrawal history")?;
+
+        let withdrawal = withdrawals
+            .into_iter()
+            .find(|withdrawal| withdrawal.id == withdrawal_id)
+            .with_context(|| format!("Withdrawal {withdrawal_id} isn't found"))?;
+
+        let status = match withdrawal.status {
+            1 | 3 | 5 => ExternalTransferStatus::Failed,
+            6 => ExternalTransferStatus::Completed,
+            _ => ExternalTransferStatus::Pending,
+        };
+
+        Ok(ExternalTransferInfo {
+            status,
+            tx_id: Some(withdrawal.tx_id).filter(|tx_id| !tx_id.is_empty()),
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_deposit_history(
+        &self,
+        currency_code: CurrencyCode,
+        tx_id: &str,
+    ) -> Result<RestResponse, ExchangeError> {
+        let currency_id = self.get_currency_id(currency_code)?;
+
+        let mut builder = UriBuilder::from_path("/sapi/v1/capital/deposit/hisrec");
+        builder.add_kv("coin", currency_id);
+        builder.add_kv("txId", tx_id);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("deposit {tx_id}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    /// Deposit statuses: 0 - pending, 1 - success, 6 - credited but can't be withdrawn yet,
+    /// 7 - wrong deposit, 8 - waiting user confirmation
+    pub(super) fn parse_deposit(
+        &self,
+        response: &RestResponse,
+        tx_id: &str,
+    ) -> Result<Option<ExternalTransferInfo>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct BinanceDeposit {
+            tx_id: String,
+            status: u8,
+        }
+
+        let deposits: Vec<BinanceDeposit> = serde_json::from_str(&response.content)
+            .context("Unable to parse Binance deposit history")?;
+
+        Ok(deposits
+            .into_iter()
+            .find(|deposit| deposit.tx_id == tx_id)
+            .map(|deposit| {
+                let status = match deposit.status {
+                    1 | 6 => ExternalTransferStatus::Completed,
+                    7 => ExternalTransferStatus::Failed,
+                    _ => ExternalTransferStatus::Pending,
+                };
+
+                ExternalTransferInfo {
+                    status,
+                    tx_id: Some(deposit.tx_id),
+                }
+            }))
     }
 }
 
@@ -1176,32 +2038,14 @@ impl ExchangeClientBuilder for BinanceBuilder {
         timeout_manager: Arc<TimeoutManager>,
         _orders: Arc<OrdersPool>,
     ) -> ExchangeClientBuilderResult {
-        let exchange_account_id = exchange_settings.exchange_account_id;
-
-        ExchangeClientBuilderResult {
-            client: Box::new(Binance::new(
-                exchange_account_id,
-                exchange_settings,
-                events_channel,
-                lifetime_manager,
-                timeout_manager,
-                false,
-            )) as BoxExchangeClient,
-            features: ExchangeFeatures::new(
-                OpenOrdersType::AllCurrencyPair,
-                RestFillsFeatures::new(RestFillsType::None),
-                OrderFeatures {
-                    supports_get_order_info_by_client_order_id: true,
-                    ..OrderFeatures::default()
-                },
-                OrderTradeOption::default(),
-                WebSocketOptions::default(),
-                EMPTY_RESPONSE_IS_OK,
-                AllowedEventSourceType::All,
-                AllowedEventSourceType::All,
-                AllowedEventSourceType::All,
-            ),
-        }
+        let market = BinanceMarket::from_settings(&exchange_settings);
+        create_binance_client(
+            exchange_settings,
+            events_channel,
+            lifetime_manager,
+            timeout_manager,
+            market,
+        )
     }
 
     fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
@@ -1213,18 +2057,105 @@ impl ExchangeClientBuilder for BinanceBuilder {
     }
 }
 
+/// Builder of client for Binance COIN-M perpetual futures, which are inverse contracts:
+/// amount is specified in contracts of fixed USD value, balances and PnL are in base coin
+pub struct BinanceCoinMBuilder;
+
+impl ExchangeClientBuilder for BinanceCoinMBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        assert!(
+            exchange_settings.is_margin_trading,
+            "BinanceCoinM supports derivatives only, so 'is_margin_trading' should be set for {}",
+            exchange_settings.exchange_account_id
+        );
+
+        create_binance_client(
+            exchange_settings,
+            events_channel,
+            lifetime_manager,
+            timeout_manager,
+            BinanceMarket::CoinMFutures,
+        )
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(1200)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "BinanceCoinM".into()
+    }
+}
+
+fn create_binance_client(
+    exchange_settings: ExchangeSettings,
+    events_channel: broadcast::Sender<ExchangeEvent>,
+    lifetime_manager: Arc<AppLifetimeManager>,
+    timeout_manager: Arc<TimeoutManager>,
+    market: BinanceMarket,
+) -> ExchangeClientBuilderResult {
+    let exchange_account_id = exchange_settings.exchange_account_id;
+    // mark price trigger is available for futures only
+    let supports_mark_price_trigger = market.is_futures();
+    // iceberg orders are available for spot only
+    let supports_iceberg_order = !market.is_futures();
+
+    ExchangeClientBuilderResult {
+        client: Box::new(Binance::new_with_market(
+            exchange_account_id,
+            exchange_settings,
+            events_channel,
+            lifetime_manager,
+            timeout_manager,
+            false,
+            market,
+        )) as BoxExchangeClient,
+        features: ExchangeFeatures::new(
+            OpenOrdersType::AllCurrencyPair,
+            RestFillsFeatures::new(RestFillsType::None),
+            OrderFeatures {
+                supports_get_order_info_by_client_order_id: true,
+                supports_mark_price_trigger,
+                supports_iceberg_order,
+                ..OrderFeatures::default()
+            },
+            OrderTradeOption {
+                supports_trades_gap_detection: true,
+                ..OrderTradeOption::default()
+            },
+            WebSocketOptions::default(),
+            EMPTY_RESPONSE_IS_OK,
+            AllowedEventSourceType::All,
+            AllowedEventSourceType::All,
+            AllowedEventSourceType::All,
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mmb_core::exchanges::general::exchange::RequestResult;
     use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestsTimeoutManagerFactory;
     use mmb_core::lifecycle::launcher::EngineBuildConfig;
     use mmb_utils::cancellation_token::CancellationToken;
     use mmb_utils::hashmap;
+    use rust_decimal_macros::dec;
 
     pub(crate) fn get_timeout_manager(
         exchange_account_id: ExchangeAccountId,
     ) -> Arc<TimeoutManager> {
-        let engine_build_config = EngineBuildConfig::new(vec![Box::new(BinanceBuilder)]);
+        let engine_build_config = EngineBuildConfig::new(vec![
+            Box::new(BinanceBuilder),
+            Box::new(BinanceCoinMBuilder),
+        ]);
         let timeout_arguments = engine_build_config.supported_exchange_clients
             [&exchange_account_id.exchange_id]
             .get_timeout_arguments();
@@ -1238,16 +2169,180 @@ mod tests {
     }
 
     #[test]
-    fn generate_signature() {
-        // All values and strings gotten from binanсe API example
+    fn balance_positions_contain_zero_positions_of_traded_currency_pairs() {
         let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
 
-        let settings = ExchangeSettings::new_short(
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
             exchange_account_id,
-            "vmPUZE6mv9SD5VNHk4HlWFsOr6aKE2zvsw0MuIgwCIPy6utIco14y7Ju91duEh8A".into(),
-            "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j".into(),
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
             false,
         );
+        for (specific, unified) in [("BTCUSDT", "btc/usdt"), ("ETHUSDT", "eth/usdt")] {
+            let (base, quote) = unified.split_once('/').expect("in test");
+            binance.specific_to_unified.write().insert(
+                specific.into(),
+                CurrencyPair::from_codes(base.into(), quote.into()),
+            );
+        }
+        binance.set_traded_specific_currencies(vec!["BTCUSDT".into()]);
+
+        let response = RestResponse::new(
+            r#"[
+                {"symbol":"BTCUSDT","positionAmt":"0","entryPrice":"0","liquidationPrice":"0","leverage":"20"},
+                {"symbol":"ETHUSDT","positionAmt":"1.5","entryPrice":"2000","liquidationPrice":"1500","leverage":"10"}
+            ]"#
+            .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let active_positions = binance.parse_active_positions(&response).expect("in test");
+        assert_eq!(active_positions.len(), 1);
+        assert_eq!(
+            active_positions[0].derivative.currency_pair.as_str(),
+            "eth/usdt"
+        );
+
+        let balance_positions: Vec<_> = binance
+            .get_positions(&response, BalancePositionOption::TradedCurrencyPairs)
+            .expect("in test")
+            .try_collect()
+            .expect("in test");
+        assert_eq!(balance_positions.len(), 1);
+        assert_eq!(
+            balance_positions[0].derivative.currency_pair.as_str(),
+            "btc/usdt"
+        );
+        assert!(balance_positions[0].derivative.position.is_zero());
+    }
+
+    #[test]
+    fn coin_m_symbols_are_inverse_contracts() {
+        let exchange_account_id: ExchangeAccountId = "BinanceCoinM_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
+
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new_with_market(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+            BinanceMarket::CoinMFutures,
+        );
+        assert_eq!(binance.hosts.rest_host, "https://dapi.binance.com");
+        assert_eq!(
+            binance.get_futures_path("/fapi/v2/positionRisk"),
+            "/dapi/v1/positionRisk"
+        );
+
+        let response = RestResponse::new(
+            r#"{"symbols":[
+                {"symbol":"BTCUSD_PERP","pair":"BTCUSD","contractType":"PERPETUAL","contractStatus":"TRADING","contractSize":100,"baseAsset":"BTC","quoteAsset":"USD","marginAsset":"BTC","filters":[{"filterType":"PRICE_FILTER","minPrice":"1000","maxPrice":"4520958","tickSize":"0.1"},{"filterType":"LOT_SIZE","minQty":"1","maxQty":"1000000","stepSize":"1"}]},
+                {"symbol":"BTCUSD_240329","pair":"BTCUSD","contractType":"CURRENT_QUARTER","contractStatus":"TRADING","contractSize":100,"baseAsset":"BTC","quoteAsset":"USD","marginAsset":"BTC","filters":[]}
+            ]}"#
+            .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let symbols = binance.parse_all_symbols(&response).expect("in test");
+        assert_eq!(symbols.len(), 1);
+
+        let symbol = &symbols[0];
+        assert_eq!(symbol.currency_pair().as_str(), "btc/usd");
+        assert_eq!(symbol.contract_type, ContractType::Inverse);
+        assert_eq!(symbol.amount_multiplier, dec!(100));
+        assert_eq!(symbol.amount_currency_code.as_str(), "usd");
+        assert_eq!(symbol.balance_currency_code, Some("btc".into()));
+    }
+
+    #[test]
+    fn margin_account_reports_accrued_interest() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let mut settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+        settings.use_margin_account = true;
+
+        let (tx, _) = broadcast::channel(10);
+        let mut binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+        assert_eq!(binance.market, BinanceMarket::Margin);
+        assert_eq!(binance.hosts.rest_host, "https://api.binance.com");
+        assert_eq!(
+            binance.get_uri_path("/fapi/v1/order", "/api/v3/order"),
+            "/sapi/v1/margin/order"
+        );
+        assert_eq!(
+            binance.get_uri_path("/fapi/v1/listenKey", "/api/v3/userDataStream"),
+            "/sapi/v1/userDataStream"
+        );
+        assert_eq!(
+            binance.get_uri_path("/fapi/v1/exchangeInfo", "/api/v3/exchangeInfo"),
+            "/api/v3/exchangeInfo"
+        );
+
+        for (id, code) in [("BTC", "btc"), ("USDT", "usdt")] {
+            binance.supported_currencies.insert(id.into(), code.into());
+        }
+        binance.specific_to_unified.write().insert(
+            "BTCUSDT".into(),
+            CurrencyPair::from_codes("btc".into(), "usdt".into()),
+        );
+        binance.set_traded_specific_currencies(vec!["BTCUSDT".into()]);
+
+        let cash_flows = Arc::new(Mutex::new(Vec::new()));
+        binance.set_handle_cash_flow_callback(Box::new({
+            let cash_flows = cash_flows.clone();
+            move |cash_flow| cash_flows.lock().push(cash_flow)
+        }));
+
+        let margin_account = |usdt_interest: &str| {
+            RestResponse::new(
+                format!(
+                    r#"{{"borrowEnabled":true,"marginLevel":"11.6","tradeEnabled":true,"userAssets":[
+                        {{"asset":"BTC","borrowed":"0","free":"0.5","interest":"0","locked":"0.1","netAsset":"0.6"}},
+                        {{"asset":"USDT","borrowed":"1000","free":"1500","interest":"{usdt_interest}","locked":"0","netAsset":"499"}}
+                    ]}}"#
+                ),
+                hyper::StatusCode::OK,
+            )
+        };
+
+        let balances = binance
+            .parse_margin_balance(&margin_account("1"))
+            .expect("in test");
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[1].currency_code.as_str(), "usdt");
+        assert_eq!(balances[1].balance, dec!(1500));
+        // interest accrued before launch isn't reported
+        assert!(cash_flows.lock().is_empty());
+
+        binance
+            .parse_margin_balance(&margin_account("1.25"))
+            .expect("in test");
+        let cash_flows = cash_flows.lock();
+        assert_eq!(cash_flows.len(), 1);
+        assert_eq!(cash_flows[0].kind, CashFlowKind::AccruedFee);
+        assert_eq!(cash_flows[0].currency_code.as_str(), "usdt");
+        assert_eq!(cash_flows[0].currency_pair.as_str(), "btc/usdt");
+        assert_eq!(cash_flows[0].amount, dec!(-0.25));
+    }
+
+    #[test]
+    fn batch_orders_results_are_parsed_per_order() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
 
         let (tx, _) = broadcast::channel(10);
         let binance = Binance::new(
@@ -1259,24 +2354,213 @@ mod tests {
             false,
         );
 
-        let mut builder = UriBuilder::from_path("/test");
-        builder.add_kv("symbol", "LTCBTC");
-        builder.add_kv("side", "BUY");
-        builder.add_kv("type", "LIMIT");
-        builder.add_kv("timeInForce", "GTC");
-        builder.add_kv("quantity", "1");
-        builder.add_kv("price", "0");
-        builder.add_kv("recvWindow", "5000");
-        builder.add_kv("timestamp", "1499827319559");
-        binance.write_signature_to_builder(&mut builder);
+        let response = RestResponse::new(
+            r#"[
+                {"orderId":42,"symbol":"BTCUSDT","status":"NEW","clientOrderId":"1"},
+                {"code":-2019,"msg":"Margin is insufficient."}
+            ]"#
+            .to_owned(),
+            hyper::StatusCode::OK,
+        );
 
-        let query = builder.query();
+        let results = binance
+            .parse_create_orders_batch(&response)
+            .expect("in test");
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].outcome,
+            RequestResult::Success(ExchangeOrderId::new("42".into()))
+        );
+        match &results[1].outcome {
+            RequestResult::Error(error) => assert_eq!(error.code, Some(-2019)),
+            outcome => panic!("unexpected outcome {outcome:?}"),
+        }
+    }
 
-        let expected = b"76f4fcd9c09d7969fcf97254950d690077f0fe090ea68ec7601a69ff36acd34b";
+    #[test]
+    fn wallet_responses_are_parsed() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
 
-        //expected that signature was last parameter
-        let signature_value = query.split_at(query.len() - expected.len()).1;
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+        binance
+            .supported_currencies
+            .insert("USDT".into(), "usdt".into());
+
+        let response = |content: &str| RestResponse::new(content.to_owned(), hyper::StatusCode::OK);
+
+        let coins_config = response(
+            r#"[{"coin":"USDT","networkList":[
+                {"network":"BSC","depositEnable":true,"withdrawEnable":true},
+                {"network":"ETH","depositEnable":false,"withdrawEnable":true}
+            ]}]"#,
+        );
+        let network_status = binance
+            .parse_network_status(&coins_config, "usdt".into(), "ETH")
+            .expect("in test");
+        assert_eq!(
+            network_status,
+            NetworkStatus {
+                is_deposit_enabled: false,
+                is_withdrawal_enabled: true,
+            }
+        );
+        assert!(binance
+            .parse_network_status(&coins_config, "usdt".into(), "TRX")
+            .is_err());
+
+        let deposit_address = binance
+            .parse_deposit_address(&response(
+                r#"{"address":"0xabc","coin":"USDT","tag":"","url":""}"#,
+            ))
+            .expect("in test");
+        assert_eq!(
+            deposit_address,
+            DepositAddress {
+                address: "0xabc".to_owned(),
+                tag: None,
+            }
+        );
+
+        let withdrawal_id = binance
+            .parse_withdrawal_id(&response(r#"{"id":"7213fea8e94b4a5593d507237e5a555b"}"#))
+            .expect("in test");
+        assert_eq!(withdrawal_id, "7213fea8e94b4a5593d507237e5a555b");
+
+        let withdrawals = response(
+            r#"[{"id":"1","status":4},{"id":"2","status":6,"txId":"0xdef"},{"id":"3","status":5}]"#,
+        );
+        let withdrawal = |id| binance.parse_withdrawal(&withdrawals, id).expect("in test");
+        assert_eq!(
+            withdrawal("1"),
+            ExternalTransferInfo {
+                status: ExternalTransferStatus::Pending,
+                tx_id: None,
+            }
+        );
+        assert_eq!(
+            withdrawal("2"),
+            ExternalTransferInfo {
+                status: ExternalTransferStatus::Completed,
+                tx_id: Some("0xdef".to_owned()),
+            }
+        );
+        assert_eq!(withdrawal("3").status, ExternalTransferStatus::Failed);
+
+        let deposits = response(r#"[{"txId":"0xdef","status":1}]"#);
+        let deposit = binance.parse_deposit(&deposits, "0xdef").expect("in test");
+        assert_eq!(
+            deposit.map(|x| x.status),
+            Some(ExternalTransferStatus::Completed)
+        );
+        let unknown_deposit = binance.parse_deposit(&deposits, "0x123").expect("in test");
+        assert_eq!(unknown_deposit, None);
+    }
+
+    #[test]
+    fn oco_order_response_is_parsed() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+
+        let orders = OrdersPool::new();
+        let order = |client_order_id: &str, user_order| {
+            let header = OrderHeader::with_user_order(
+                client_order_id.into(),
+                exchange_account_id,
+                CurrencyPair::from_codes("phb".into(), "btc".into()),
+                OrderSide::Sell,
+                dec!(1),
+                user_order,
+                None,
+                None,
+                "test".to_owned(),
+            );
+            orders.add_simple_initial(&header, Utc::now(), None)
+        };
+        let limit_order = order("limit", UserOrder::limit(dec!(10)));
+        let stop_order = order("stop", UserOrder::stop_loss(dec!(9)));
+
+        let response = RestResponse::new(
+            r#"{"orderListId":1,"contingencyType":"OCO","listClientOrderId":"list","orders":[
+                {"symbol":"PHBBTC","orderId":11,"clientOrderId":"stop"},
+                {"symbol":"PHBBTC","orderId":12,"clientOrderId":"limit"}
+            ]}"#
+            .to_owned(),
+            hyper::StatusCode::OK,
+        );
+        let (limit_exchange_order_id, stop_exchange_order_id) = binance
+            .parse_create_oco_order(&response, &limit_order, &stop_order)
+            .expect("in test");
+        assert_eq!(limit_exchange_order_id, ExchangeOrderId::from("12"));
+        assert_eq!(stop_exchange_order_id, ExchangeOrderId::from("11"));
+
+        let response = RestResponse::new(
+            r#"{"orders":[{"symbol":"PHBBTC","orderId":12,"clientOrderId":"limit"}]}"#.to_owned(),
+            hyper::StatusCode::OK,
+        );
+        let result = binance.parse_create_oco_order(&response, &limit_order, &stop_order);
+        assert!(result.is_err());
+    }
 
-        assert_eq!(signature_value, expected);
+    #[test]
+    fn cancel_replace_response_is_parsed_and_cancellation_of_replaced_order_is_skipped() {
+        let exchange_account_id: ExchangeAccountId = "Binance_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let binance = Binance::new(
+            exchange_account_id,
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+            get_timeout_manager(exchange_account_id),
+            false,
+        );
+
+        let response = RestResponse::new(
+            r#"{"cancelResult":"SUCCESS","newOrderResult":"SUCCESS",
+                "cancelResponse":{"symbol":"PHBBTC","orderId":11,"clientOrderId":"stop"},
+                "newOrderResponse":{"symbol":"PHBBTC","orderId":12,"clientOrderId":"stop"}}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+        let new_exchange_order_id = binance
+            .parse_amend_conditional_order(&response)
+            .expect("in test");
+        assert_eq!(new_exchange_order_id, ExchangeOrderId::from("12"));
+
+        let response = RestResponse::new(
+            r#"{"code":-2022,"msg":"Order cancel-replace failed."}"#.to_owned(),
+            hyper::StatusCode::OK,
+        );
+        assert!(binance.parse_amend_conditional_order(&response).is_err());
+
+        let _ = binance
+            .replaced_orders
+            .insert("11".into(), ReplacedOrderState::Replaced);
+        assert!(binance.is_replaced_order("11"));
+        assert!(!binance.is_replaced_order("11"));
+        assert!(!binance.is_replaced_order("12"));
     }
 }
@@ -1,4 +1,6 @@
-use super::binance::Binance;
+use super::binance::{
+    BalancePositionOption, Binance, BinanceMarket, ReplacedOrderState, BATCH_ORDERS_MAX_COUNT,
+};
 use crate::support::BinanceOrderInfo;
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
@@ -11,14 +13,19 @@ use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::general::request_type::RequestType;
 use mmb_core::exchanges::rest_client::UriBuilder;
 use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError, Support};
-use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions, Trade};
 use mmb_domain::exchanges::symbol::Symbol;
-use mmb_domain::market::CurrencyPair;
+use mmb_domain::market::{CurrencyCode, CurrencyPair};
 use mmb_domain::order::pool::OrderRef;
 use mmb_domain::order::snapshot::Price;
 use mmb_domain::order::snapshot::*;
+use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_domain::transfer::{
+    DepositAddress, ExternalTransferInfo, NetworkStatus, WithdrawalRequest,
+};
 use mmb_utils::DateTime;
+use rust_decimal::Decimal;
 use std::sync::Arc;
 
 #[async_trait]
@@ -33,6 +40,19 @@ impl ExchangeClient for Binance {
         }
     }
 
+    fn max_orders_batch_size(&self) -> Option<usize> {
+        self.market.is_futures().then_some(BATCH_ORDERS_MAX_COUNT)
+    }
+
+    async fn create_orders_batch(
+        &self,
+        orders: &[OrderRef],
+    ) -> Result<Vec<CreateOrderResult>, ExchangeError> {
+        let response = self.request_create_orders_batch(orders).await?;
+
+        self.parse_create_orders_batch(&response)
+    }
+
     async fn cancel_order(
         &self,
         order: &OrderRef,
@@ -50,7 +70,8 @@ impl ExchangeClient for Binance {
     async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
         let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
 
-        let mut builder = UriBuilder::from_path("/api/v3/openOrders");
+        let path = self.get_uri_path("/fapi/v1/allOpenOrders", "/api/v3/openOrders");
+        let mut builder = UriBuilder::from_path(&path);
         builder.add_kv("symbol", specific_currency_pair);
         self.add_authentification(&mut builder);
 
@@ -108,26 +129,78 @@ impl ExchangeClient for Binance {
         self.parse_active_positions(&response)
     }
 
+    async fn set_leverage(
+        &self,
+        currency_pair: CurrencyPair,
+        leverage: Decimal,
+    ) -> Option<Result<Decimal>> {
+        if !self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self.request_set_leverage(currency_pair, leverage).await {
+            Ok(response) => self.parse_set_leverage(&response),
+            Err(error) => Err(anyhow!("Failed to set leverage: {error:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn borrow(&self, currency_code: CurrencyCode, amount: Amount) -> Option<Result<()>> {
+        if self.market != BinanceMarket::Margin {
+            return None;
+        }
+
+        let result = self
+            .request_margin_loan("/sapi/v1/margin/loan", currency_code, amount)
+            .await
+            .map(|_| ())
+            .map_err(|error| anyhow!("Failed to borrow {amount} {currency_code}: {error:?}"));
+
+        Some(result)
+    }
+
+    async fn repay(&self, currency_code: CurrencyCode, amount: Amount) -> Option<Result<()>> {
+        if self.market != BinanceMarket::Margin {
+            return None;
+        }
+
+        let result = self
+            .request_margin_loan("/sapi/v1/margin/repay", currency_code, amount)
+            .await
+            .map(|_| ())
+            .map_err(|error| anyhow!("Failed to repay {amount} {currency_code}: {error:?}"));
+
+        Some(result)
+    }
+
     async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
         // Binance does return positions from GET request /fapi/v2/account but without liquidation_price field
         // so we have to use separate requests for balance and positions
-        Ok(match self.settings.is_margin_trading {
+        Ok(match self.market.is_futures() {
             true => {
                 let (balance_response, position_response) =
                     tokio::join!(self.request_get_balance(), self.request_get_position());
                 ExchangeBalancesAndPositions {
                     balances: self.parse_derivative_balance(&balance_response?)?,
                     positions: Some(
-                        self.get_active_positions(&position_response?)?
-                            .map(|position| Ok::<_, anyhow::Error>(position?.derivative))
-                            .try_collect()?,
+                        self.get_positions(
+                            &position_response?,
+                            BalancePositionOption::TradedCurrencyPairs,
+                        )?
+                        .map(|position| Ok::<_, anyhow::Error>(position?.derivative))
+                        .try_collect()?,
                     ),
                 }
             }
             false => {
                 let balance_response = self.request_get_balance().await?;
+                let balances = match self.market {
+                    BinanceMarket::Margin => self.parse_margin_balance(&balance_response)?,
+                    _ => self.parse_spot_balance(&balance_response)?,
+                };
                 ExchangeBalancesAndPositions {
-                    balances: self.parse_spot_balance(&balance_response)?,
+                    balances,
                     positions: None,
                 }
             }
@@ -160,6 +233,180 @@ impl ExchangeClient for Binance {
             Err(err) => Some(Err(anyhow!("Get server time request failed: {err:?}"))),
         }
     }
+
+    async fn create_oco_order(
+        &self,
+        limit_order: &OrderRef,
+        stop_order: &OrderRef,
+    ) -> Option<Result<(ExchangeOrderId, ExchangeOrderId), ExchangeError>> {
+        if self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self.request_create_oco_order(limit_order, stop_order).await {
+            Ok(response) => self.parse_create_oco_order(&response, limit_order, stop_order),
+            Err(err) => Err(err),
+        };
+
+        Some(result)
+    }
+
+    async fn amend_conditional_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_user_order: &UserOrder,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        // fills of placed order are counted from zero, so partially filled order can't be replaced
+        if self.market != BinanceMarket::Spot || !order.filled_amount().is_zero() {
+            return None;
+        }
+
+        self.replaced_orders
+            .insert(exchange_order_id.clone(), ReplacedOrderState::InProgress);
+
+        let result = match self
+            .request_amend_conditional_order(order, exchange_order_id, new_user_order)
+            .await
+        {
+            Ok(response) => self.parse_amend_conditional_order(&response),
+            Err(err) => Err(err),
+        };
+
+        let state = self.replaced_orders.remove(exchange_order_id).map(|x| x.1);
+        match (&result, state) {
+            (Ok(_), Some(ReplacedOrderState::InProgress)) => {
+                let _ = self
+                    .replaced_orders
+                    .insert(exchange_order_id.clone(), ReplacedOrderState::Replaced);
+            }
+            (Err(_), Some(ReplacedOrderState::CancellationReceived)) => {
+                // order is cancelled though it isn't replaced, so cancellation is passed on
+                (self.order_cancelled_callback)(
+                    order.client_order_id(),
+                    exchange_order_id.clone(),
+                    EventSourceType::WebSocket,
+                );
+            }
+            _ => {}
+        }
+
+        Some(result)
+    }
+
+    async fn get_trades_from_id(
+        &self,
+        currency_pair: CurrencyPair,
+        from_trade_id: u64,
+    ) -> Option<Result<Vec<Trade>>> {
+        match self
+            .request_historical_trades(currency_pair, from_trade_id)
+            .await
+        {
+            Ok(response) => Some(self.parse_historical_trades(&response)),
+            Err(err) => Some(Err(anyhow!(
+                "Get historical trades request failed: {err:?}"
+            ))),
+        }
+    }
+
+    async fn get_order_book_snapshot(
+        &self,
+        currency_pair: CurrencyPair,
+        depth: usize,
+    ) -> Option<Result<OrderBookData>> {
+        match self.request_order_book_snapshot(currency_pair, depth).await {
+            Ok(response) => Some(self.parse_order_book_snapshot(&response)),
+            Err(err) => Some(Err(anyhow!(
+                "Get order book snapshot request failed: {err:?}"
+            ))),
+        }
+    }
+
+    async fn get_network_status(
+        &self,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Option<Result<NetworkStatus>> {
+        // wallet endpoints are available on spot API only
+        if self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self.request_coins_config().await {
+            Ok(response) => self.parse_network_status(&response, currency_code, network),
+            Err(err) => Err(anyhow!("Get coins config request failed: {err:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn get_deposit_address(
+        &self,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Option<Result<DepositAddress>> {
+        if self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self.request_deposit_address(currency_code, network).await {
+            Ok(response) => self.parse_deposit_address(&response),
+            Err(err) => Err(anyhow!("Get deposit address request failed: {err:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn withdraw(&self, request: &WithdrawalRequest) -> Option<Result<String>> {
+        if self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self.request_withdraw(request).await {
+            Ok(response) => self.parse_withdrawal_id(&response),
+            Err(err) => Err(anyhow!("Withdraw request failed: {err:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn get_withdrawal(
+        &self,
+        currency_code: CurrencyCode,
+        withdrawal_id: &str,
+    ) -> Option<Result<ExternalTransferInfo>> {
+        if self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self
+            .request_withdrawal_history(currency_code, withdrawal_id)
+            .await
+        {
+            Ok(response) => self.parse_withdrawal(&response, withdrawal_id),
+            Err(err) => Err(anyhow!("Get withdrawal history request failed: {err:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn get_deposit(
+        &self,
+        currency_code: CurrencyCode,
+        tx_id: &str,
+    ) -> Option<Result<Option<ExternalTransferInfo>>> {
+        if self.market.is_futures() {
+            return None;
+        }
+
+        let result = match self.request_deposit_history(currency_code, tx_id).await {
+            Ok(response) => self.parse_deposit(&response, tx_id),
+            Err(err) => Err(anyhow!("Get deposit history request failed: {err:?}")),
+        };
+
+        Some(result)
+    }
 }
 
 impl Binance {
@@ -18,7 +18,7 @@ use super::binance::Binance;
 use mmb_core::connectivity::WebSocketRole;
 use mmb_core::exchanges::common::send_event;
 use mmb_core::exchanges::general::exchange::Exchange;
-use mmb_core::exchanges::traits::{HandleMetricsCb, Support};
+use mmb_core::exchanges::traits::{HandleCashFlowCb, HandleMetricsCb, Support};
 use mmb_core::exchanges::traits::{
     HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
 };
@@ -35,6 +35,10 @@ use mmb_domain::order_book::event::{EventType, OrderBookEvent};
 use mmb_domain::order_book::order_book_data::OrderBookData;
 use mmb_utils::time::get_current_milliseconds;
 
+/// Limits of streams which can be subscribed by single websocket connection
+const MAX_SPOT_STREAMS_PER_CONNECTION: usize = 1024;
+const MAX_FUTURES_STREAMS_PER_CONNECTION: usize = 200;
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct BinanceOrderInfo {
     #[serde(rename = "symbol")]
@@ -53,21 +57,31 @@ pub struct BinanceOrderInfo {
 }
 
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 #[serde(bound(deserialize = "'de: 'a"))]
-pub(crate) struct BinanceDerivativeAccountInfo<'a> {
-    pub(crate) assets: Vec<BinanceDerivativeBalances<'a>>,
-}
-
-#[derive(Deserialize, Debug)]
-#[serde(bound(deserialize = "'de: 'a"))]
-pub(crate) struct BinanceSpotAccountInfo<'a> {
-    pub(crate) balances: Vec<BinanceSpotBalances<'a>>,
+pub(crate) struct BinanceMarginAccountInfo<'a> {
+    pub(crate) user_assets: Vec<BinanceMarginBalance<'a>>,
 }
 
+/// Corresponds https://binance-docs.github.io/apidocs/spot/en/#query-cross-margin-account-details-user_data
+/// asset: string,       // asset name
+/// free: Decimal,       // available amount including borrowed one
+/// locked: Decimal,     // amount locked by open orders
+/// borrowed: Decimal,   // borrowed amount without interest
+/// interest: Decimal,   // unpaid interest accrued for borrowed amount
+/// net_asset: Decimal,  // free + locked - borrowed - interest
 #[derive(Debug, Deserialize)]
-pub(super) struct BinanceSpotBalances<'a> {
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BinanceMarginBalance<'a> {
     pub(super) asset: &'a str,
     pub(super) free: Decimal,
+    pub(super) interest: Decimal,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub(crate) struct BinanceDerivativeAccountInfo<'a> {
+    pub(crate) assets: Vec<BinanceDerivativeBalances<'a>>,
 }
 
 /// Corresponds https://binance-docs.github.io/apidocs/futures/en/#account-information-v2-user_data
@@ -214,6 +228,10 @@ impl Support for Binance {
         self.handle_metrics_callback = callback;
     }
 
+    fn set_handle_cash_flow_callback(&mut self, callback: HandleCashFlowCb) {
+        self.handle_cash_flow_callback = callback;
+    }
+
     fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
         *self.traded_specific_currencies.lock() = currencies;
     }
@@ -229,10 +247,10 @@ impl Support for Binance {
 
     async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
         let (host, path) = match role {
-            WebSocketRole::Main => (
-                &self.hosts.web_socket_host,
-                self.build_ws_main_path(&self.settings.websocket_channels[..]),
-            ),
+            WebSocketRole::Main => {
+                let path = self.build_ws_main_path(&self.traded_specific_currencies.lock());
+                (&self.hosts.web_socket_host, path)
+            }
             WebSocketRole::Secondary => (
                 &self.hosts.web_socket2_host,
                 self.build_ws_secondary_path().await?,
@@ -243,6 +261,25 @@ impl Support for Binance {
             .with_context(|| format!("Unable parse websocket {role:?} uri"))
     }
 
+    fn max_websocket_currency_pairs(&self) -> Option<usize> {
+        let max_streams = match self.market.is_futures() {
+            true => MAX_FUTURES_STREAMS_PER_CONNECTION,
+            false => MAX_SPOT_STREAMS_PER_CONNECTION,
+        };
+
+        Some(max_streams / self.settings.websocket_channels.len().max(1))
+    }
+
+    async fn create_market_data_ws_url(
+        &self,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<Url> {
+        let host = &self.hosts.web_socket_host;
+        let path = self.build_ws_main_path(currency_pairs);
+
+        Url::parse(&format!("{host}{path}")).context("Unable parse websocket market data uri")
+    }
+
     fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
         self.unified_to_specific.read()[&currency_pair]
     }
@@ -277,7 +314,11 @@ impl Binance {
                 format!("There are no last_trade_id for given currency_pair {currency_pair}")
             });
 
-        if self.is_reducing_market_data && trade_id_from_lasts.number() >= trade_id.number() {
+        let is_received = match (trade_id_from_lasts.number(), trade_id.number()) {
+            (Some(last_trade_id), Some(trade_id)) => last_trade_id >= trade_id,
+            _ => false,
+        };
+        if self.is_reducing_market_data && is_received {
             log::info!("Current last_trade_id for currency_pair {currency_pair} is {} >= trade_id {trade_id}", *trade_id_from_lasts);
 
             return Ok(());
@@ -325,7 +366,7 @@ impl Binance {
     }
 
     pub fn process_snapshot_update(&self, currency_pair: CurrencyPair, data: &Value) -> Result<()> {
-        let (last_update_id, raw_asks, raw_bids) = match self.settings.is_margin_trading {
+        let (last_update_id, raw_asks, raw_bids) = match self.market.is_futures() {
             true => {
                 let last_update_id = data["u"].to_string();
                 let raw_asks = data["a"]
@@ -409,10 +450,9 @@ impl Binance {
         self.get_unified_currency_pair(&specific_currency_pair)
     }
 
-    fn build_ws_main_path(&self, websocket_channels: &[String]) -> String {
-        let stream_names = self
-            .traded_specific_currencies
-            .lock()
+    fn build_ws_main_path(&self, currency_pairs: &[SpecificCurrencyPair]) -> String {
+        let websocket_channels = &self.settings.websocket_channels;
+        let stream_names = currency_pairs
             .iter()
             .flat_map(|currency_pair| {
                 let mut results = Vec::new();
@@ -466,7 +506,7 @@ fn start_updating_listen_key(exchange: &Arc<Exchange>) {
     );
 }
 
-fn get_order_book_side(levels: &[Value]) -> Result<SortedOrderData> {
+pub(super) fn get_order_book_side(levels: &[Value]) -> Result<SortedOrderData> {
     levels
         .iter()
         .map(|x| {
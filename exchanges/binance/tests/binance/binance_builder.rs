@@ -11,6 +11,7 @@ use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTime
 use mmb_core::infrastructure::init_lifetime_manager;
 use mmb_core::settings::CurrencyPairSetting;
 use mmb_core::settings::ExchangeSettings;
+use mmb_core::settings::OperationPoliciesSettings;
 use mmb_domain::events::{AllowedEventSourceType, ExchangeEvent};
 use mmb_domain::exchanges::commission::Commission;
 use mmb_domain::market::*;
@@ -192,7 +193,7 @@ impl BinanceBuilder {
 
         let exchange_blocker = ExchangeBlocker::new(vec![exchange_account_id]);
 
-        let event_recorder = EventRecorder::start(None, None)
+        let event_recorder = EventRecorder::start(None, None, None)
             .await
             .expect("Failure start EventRecorder");
 
@@ -209,6 +210,7 @@ impl BinanceBuilder {
             Arc::downgrade(&exchange_blocker),
             commission,
             event_recorder,
+            OperationPoliciesSettings::default(),
         );
         exchange.connect_ws().await.with_expect(move || {
             format!("Failed to connect to websockets on exchange {exchange_account_id}")
@@ -85,13 +85,13 @@ async fn send_request(
     uri: Uri,
     api_key: &str,
     exchange_account_id: ExchangeAccountId,
-    is_usd_m_futures: bool,
+    is_futures: bool,
 ) -> String {
     let rest_client = RestClient::new(
         ErrorHandlerData::new(false, exchange_account_id, ErrorHandlerBinance::default()),
         RestHeadersBinance {
             api_key: api_key.to_owned(),
-            is_usd_m_futures,
+            is_futures,
         },
     );
 
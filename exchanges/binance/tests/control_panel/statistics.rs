@@ -2,8 +2,8 @@
 use crate::binance::common::get_min_amount;
 use crate::binance::common::{default_currency_pair, get_prices};
 use crate::get_binance_credentials_or_exit;
-use binance::binance::Binance;
 use binance::binance::BinanceBuilder;
+use binance::binance::{Binance, BinanceMarket};
 use core_tests::order::OrderProxy;
 use jsonrpc_core::Value;
 use jsonrpc_core_client::transports::ipc;
@@ -70,7 +70,9 @@ async fn orders_cancelled() {
         let _ = exchange.cancel_all_orders(test_currency_pair).await;
         let (execution_price, min_price) = get_prices(
             get_specific_currency_pair_for_tests(&exchange, test_currency_pair),
-            &Binance::make_hosts(exchange_settings.is_margin_trading),
+            &Binance::make_hosts(BinanceMarket::from_margin_trading(
+                exchange_settings.is_margin_trading,
+            )),
             &exchange_settings,
             &symbol.price_precision,
         )
@@ -78,7 +80,9 @@ async fn orders_cancelled() {
 
         let amount = get_min_amount(
             get_specific_currency_pair_for_tests(&exchange, test_currency_pair),
-            &Binance::make_hosts(exchange_settings.is_margin_trading),
+            &Binance::make_hosts(BinanceMarket::from_margin_trading(
+                exchange_settings.is_margin_trading,
+            )),
             &exchange_settings,
             execution_price,
             &symbol,
@@ -0,0 +1,824 @@
+use crate::support::SubscribedChannel;
+use crate::types::{
+    side_by_amount, BitfinexNotification, BitfinexOrder, BitfinexPairInfo,
+    BitfinexSubmitOrderRequest, BitfinexTrade, BitfinexWallet,
+};
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, UserOrder,
+};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha384;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerBitfinex;
+
+impl ErrorHandler for ErrorHandlerBitfinex {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        /// Bitfinex returns errors as arrays instead of objects
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum BitfinexErrorResponse {
+            /// ["error", 10020, "amount: invalid"]
+            Error(String, i64, String),
+            /// Write requests return notification with status, e.g.
+            /// [MTS, "on-req", null, null, [...], null, "ERROR", "Invalid order: ..."]
+            Notification(BitfinexNotification<IgnoredAny>),
+            Other(IgnoredAny),
+        }
+
+        let response: BitfinexErrorResponse =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse Bitfinex response: {err:?}"))
+            })?;
+
+        match response {
+            BitfinexErrorResponse::Error(kind, code, message) if kind == "error" => Err(
+                ExchangeError::new(ExchangeErrorType::Unknown, message, Some(code)),
+            ),
+            BitfinexErrorResponse::Notification(BitfinexNotification(.., status, text))
+                if status == "ERROR" || status == "FAILURE" =>
+            {
+                Err(ExchangeError::new(
+                    ExchangeErrorType::Unknown,
+                    text.unwrap_or(status),
+                    None,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://docs.bitfinex.com/docs/abbreviations-glossary#error-codes
+        // Most of errors have generic code, so they are distinguished by message
+        match error.code {
+            _ if error.message.contains("not enough") => ExchangeErrorType::InsufficientFunds,
+            _ if error.message.starts_with("Order not found") => ExchangeErrorType::OrderNotFound,
+            _ if error.message.starts_with("Invalid order") => ExchangeErrorType::InvalidOrder,
+            Some(10020) => ExchangeErrorType::InvalidOrder,
+            Some(10100) | Some(10111..=10114) => ExchangeErrorType::Authentication,
+            Some(11010) => ExchangeErrorType::RateLimit,
+            Some(20051) | Some(20060) => ExchangeErrorType::ServiceUnavailable,
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersBitfinex {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersBitfinex {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersBitfinex {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        // Only public endpoints are requested without body
+        builder
+    }
+
+    /// Authenticated endpoints are always requested by POST with JSON body
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        _request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        let nonce = Bitfinex::get_nonce();
+        let signature = Bitfinex::create_signature(
+            &self.secret_key,
+            &[b"/api", uri.path().as_bytes(), nonce.as_bytes(), body],
+        );
+
+        builder
+            .header(CONTENT_TYPE, "application/json")
+            .header("bfx-nonce", nonce)
+            .header("bfx-apikey", &self.api_key)
+            .header("bfx-signature", signature)
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+const DEFAULT_CURRENCY_ALIASES: [(&str, &str); 2] = [("ust", "usdt"), ("udc", "usdc")];
+const EXCHANGE_WALLET: &str = "exchange";
+const POST_ONLY_FLAG: u32 = 4096;
+const MY_TRADES_LIMIT: u32 = 2500;
+/// Bitfinex rounds prices to 5 significant digits
+const PRICE_SIGNIFICANT_DIGITS: u8 = 5;
+const AMOUNT_DECIMAL_PLACES: i8 = 8;
+
+pub struct Bitfinex {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerBitfinex, RestHeadersBitfinex>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    // Public channels are identified by numeric ids received on subscription
+    pub(super) subscribed_channels: Mutex<HashMap<u64, SubscribedChannel>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Bitfinex {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Bitfinex {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerBitfinex::default(),
+                ),
+                RestHeadersBitfinex::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(
+                &DEFAULT_CURRENCY_ALIASES,
+                &settings.currency_aliases,
+            ),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            subscribed_channels: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://api.bitfinex.com/ws/2",
+            web_socket2_host: "wss://api-pub.bitfinex.com/ws/2",
+            rest_host: "https://api.bitfinex.com",
+        }
+    }
+
+    /// Nonce should increase with every request, so microseconds are used
+    pub(super) fn get_nonce() -> String {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_micros()
+            .to_string()
+    }
+
+    /// Hex encoded HMAC-SHA384 of concatenated message parts
+    pub(super) fn create_signature(secret_key: &str, message_parts: &[&[u8]]) -> String {
+        let mut hmac = Hmac::<Sha384>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Bitfinex signature");
+        for part in message_parts {
+            hmac.update(part);
+        }
+
+        format!("{:x}", hmac.finalize().into_bytes())
+    }
+
+    /// Bitfinex accepts only integer client order ids
+    fn get_cid(client_order_id: &ClientOrderId) -> Result<i64, ExchangeError> {
+        client_order_id.as_str().parse().map_err(|_| {
+            ExchangeError::new(
+                ExchangeErrorType::InvalidOrder,
+                format!("Bitfinex requires numeric client order id, got {client_order_id}"),
+                None,
+            )
+        })
+    }
+
+    async fn post_private(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(body).map_err(|err| {
+            ExchangeError::parsing(format!(
+                "Unable to serialize Bitfinex request body: {err:?}"
+            ))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/v2/conf/pub:info:pair")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        // Config is wrapped into one more array
+        let (pairs,): (Vec<BitfinexPairInfo>,) = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from Bitfinex")?;
+
+        Ok(pairs
+            .iter()
+            .filter_map(|pair| match self.parse_symbol(pair) {
+                Ok(symbol) => Some(symbol),
+                Err(err) => {
+                    log::warn!("Skipped Bitfinex pair {}: {err:?}", pair.pair);
+                    None
+                }
+            })
+            .collect_vec())
+    }
+
+    /// Currency codes of 3 chars are concatenated, longer ones are separated by colon
+    pub(super) fn split_pair(pair: &str) -> Result<(&str, &str)> {
+        match pair.split_once(':') {
+            Some(codes) => Ok(codes),
+            None if pair.len() == 6 => Ok(pair.split_at(3)),
+            None => anyhow::bail!("Unexpected format of Bitfinex pair {pair}"),
+        }
+    }
+
+    fn parse_symbol(&self, pair: &BitfinexPairInfo) -> Result<Arc<Symbol>> {
+        let (base_id, quote_id) = Bitfinex::split_pair(&pair.pair)?;
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        // Trading symbols have prefix "t", funding ones have prefix "f"
+        let specific_currency_pair = format!("t{}", pair.pair).as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Ok(Arc::new(Symbol::new(
+            false,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            pair.min_amount,
+            pair.max_amount,
+            None,
+            base,
+            None,
+            Precision::ByMantissa {
+                precision: PRICE_SIGNIFICANT_DIGITS,
+            },
+            Precision::tick_from_precision(AMOUNT_DECIMAL_PLACES),
+        )))
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let (order_type, price, flags) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => (
+                "EXCHANGE LIMIT",
+                Some(price),
+                match execution_type {
+                    OrderExecutionType::MakerOnly => POST_ONLY_FLAG,
+                    OrderExecutionType::None => 0,
+                },
+            ),
+            OrderOptions::User(UserOrder::Market) => ("EXCHANGE MARKET", None, 0),
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let request = BitfinexSubmitOrderRequest {
+            order_type,
+            symbol: specific_currency_pair.as_str(),
+            amount: match header.side {
+                OrderSide::Buy => header.amount,
+                OrderSide::Sell => -header.amount,
+            },
+            price,
+            cid: Bitfinex::get_cid(&header.client_order_id)?,
+            flags,
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_private(
+            "/v2/auth/w/order/submit",
+            &request,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let notification: BitfinexNotification<Vec<BitfinexOrder>> =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse order id: {err:?}"))
+            })?;
+
+        notification
+            .4
+            .first()
+            .map(|order| order.id.into())
+            .ok_or_else(|| ExchangeError::parsing("No order in Bitfinex response".to_owned()))
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let id: i64 = exchange_order_id.as_str().parse().map_err(|_| {
+            ExchangeError::unknown(&format!("Unexpected Bitfinex order id {exchange_order_id}"))
+        })?;
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_private(
+            "/v2/auth/w/order/cancel",
+            &json!({ "id": id }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    /// Cancellation of all orders isn't limited by symbol, so orders are cancelled by ids
+    #[named]
+    pub(super) async fn do_cancel_orders(
+        &self,
+        currency_pair: CurrencyPair,
+        orders: &[OrderInfo],
+    ) -> Result<RestResponse, ExchangeError> {
+        let ids: Vec<i64> = orders
+            .iter()
+            .filter_map(|order| order.exchange_order_id.as_str().parse().ok())
+            .collect();
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.post_private(
+            "/v2/auth/w/order/cancel/multi",
+            &json!({ "id": ids }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = match currency_pair {
+            Some(currency_pair) => format!(
+                "/v2/auth/r/orders/{}",
+                self.get_specific_currency_pair(currency_pair)
+            ),
+            None => "/v2/auth/r/orders".to_owned(),
+        };
+
+        self.post_private(&path, &json!({}), function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<BitfinexOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    /// Active and finished orders are requested by different endpoints
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+        is_finished: bool,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+        let exchange_order_id = order.exchange_order_id().ok_or_else(|| {
+            ExchangeError::unknown(&format!(
+                "Bitfinex can't get order info for {client_order_id} without exchange order id"
+            ))
+        })?;
+        let id: i64 = exchange_order_id.as_str().parse().map_err(|_| {
+            ExchangeError::unknown(&format!("Unexpected Bitfinex order id {exchange_order_id}"))
+        })?;
+
+        let path = match is_finished {
+            true => "/v2/auth/r/orders/hist",
+            false => "/v2/auth/r/orders",
+        };
+        let log_args = format!("order {client_order_id}");
+        self.post_private(path, &json!({ "id": [id] }), function_name!(), log_args)
+            .await
+    }
+
+    /// Returns `None` if order isn't found among requested ones
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<Option<OrderInfo>> {
+        let orders: Vec<BitfinexOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        orders
+            .into_iter()
+            .next()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .transpose()
+    }
+
+    fn specific_order_info_to_unified(&self, specific: BitfinexOrder) -> Result<OrderInfo> {
+        let filled_amount = specific.amount_orig.abs() - specific.amount.abs();
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.symbol.as_str().into())?,
+            specific.id.into(),
+            specific.cid.unwrap_or_default().into(),
+            side_by_amount(specific.amount_orig),
+            Bitfinex::get_local_order_status(&specific.status),
+            specific.price.unwrap_or_default(),
+            specific.amount_orig.abs(),
+            specific.price_avg.unwrap_or_default(),
+            filled_amount,
+            // Commission is available only in trades
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    /// Status contains details of fills, e.g. "EXECUTED @ 107.6(-0.2)" or
+    /// "CANCELED was: PARTIALLY FILLED @ 105.0(-0.1)"
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            _ if status.starts_with("ACTIVE") || status.starts_with("PARTIALLY FILLED") => {
+                OrderStatus::Created
+            }
+            _ if status.starts_with("EXECUTED") => OrderStatus::Completed,
+            // Canceled, post only canceled, insufficient balance etc.
+            _ => OrderStatus::Canceled,
+        }
+    }
+
+    pub(super) fn get_order_role(maker: i8) -> OrderRole {
+        match maker {
+            1 => OrderRole::Maker,
+            _ => OrderRole::Taker,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = format!(
+            "/v2/auth/r/trades/{}/hist",
+            self.get_specific_currency_pair(symbol.currency_pair())
+        );
+        let mut body = json!({ "limit": MY_TRADES_LIMIT, "sort": 1 });
+        if let Some(date_time) = last_date_time {
+            body["start"] = json!(date_time.timestamp_millis());
+        }
+
+        self.post_private(&path, &body, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let trades: Vec<BitfinexTrade> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        Ok(trades
+            .into_iter()
+            .map(|trade| OrderTrade {
+                exchange_order_id: trade.order_id.into(),
+                trade_id: TradeId::Number(trade.id),
+                datetime: trade.time,
+                price: trade.exec_price,
+                amount: trade.exec_amount.abs(),
+                side: side_by_amount(trade.exec_amount),
+                order_role: Bitfinex::get_order_role(trade.maker),
+                fee_currency_code: self
+                    .currency_aliases
+                    .unify(trade.fee_currency.unwrap_or_default().as_str().into()),
+                fee_rate: None,
+                // Negative fee means charged commission
+                fee_amount: trade.fee.map(|fee| -fee),
+                fill_type: OrderFillType::UserTrade,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        self.post_private(
+            "/v2/auth/r/wallets",
+            &json!({}),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    /// Only exchange wallet is used for spot trading
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let wallets: Vec<BitfinexWallet> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(wallets
+            .into_iter()
+            .filter(|wallet| wallet.wallet_type == EXCHANGE_WALLET)
+            .map(|wallet| ExchangeBalance {
+                currency_code: self.currency_aliases.unify(wallet.currency.as_str().into()),
+                balance: wallet.balance,
+            })
+            .collect_vec())
+    }
+}
+
+pub struct BitfinexBuilder;
+
+impl ExchangeClientBuilder for BitfinexBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Bitfinex::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: false,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: true,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: true,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(90)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Bitfinex".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    fn create_bitfinex() -> Bitfinex {
+        let exchange_account_id: ExchangeAccountId = "Bitfinex_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        Bitfinex::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        )
+    }
+
+    fn check_error(content: &str, expected: ExchangeErrorType) {
+        let response =
+            RestResponse::new(content.to_owned(), hyper::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let error = ErrorHandlerBitfinex
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(ErrorHandlerBitfinex.clarify_error_type(&error), expected);
+    }
+
+    #[test]
+    fn classify_array_errors() {
+        check_error(
+            r#"["error",10001,"Order not found."]"#,
+            ExchangeErrorType::OrderNotFound,
+        );
+        check_error(
+            r#"["error",10100,"apikey: invalid"]"#,
+            ExchangeErrorType::Authentication,
+        );
+        check_error(
+            r#"["error",10020,"amount: invalid"]"#,
+            ExchangeErrorType::InvalidOrder,
+        );
+        check_error(
+            r#"[1567590617442,"on-req",null,null,[[null,null,1567590617439,"tBTCUSD",null,null,-1,-1,"EXCHANGE LIMIT",null,null,null,0,null,null,null,20000,null,0,0,null,null,null,0,null,null,null,null,null,null,null,null]],null,"ERROR","Invalid order: not enough exchange balance for -1 BTCUSD at 20000"]"#,
+            ExchangeErrorType::InsufficientFunds,
+        );
+    }
+
+    #[test]
+    fn successful_responses_are_not_errors() {
+        for content in [
+            r#"[["exchange","USD",100.5,0,100.5,null,null]]"#,
+            r#"[]"#,
+            r#"[1567590617442,"on-req",null,null,[],null,"SUCCESS","Submitting 1 orders."]"#,
+        ] {
+            let response = RestResponse::new(content.to_owned(), hyper::StatusCode::OK);
+            assert!(ErrorHandlerBitfinex
+                .check_spec_rest_error(&response)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn parse_symbols_and_orders() {
+        let bitfinex = create_bitfinex();
+        let symbols = bitfinex
+            .parse_all_symbols(&RestResponse::new(
+                r#"[[["BTCUST",[null,null,null,"0.00006","2000.0",null,null,null,null,null,null,null]],["TESTBTC:TESTUSD",[null,null,null,"0.0006","200.0",null,null,null,null,null,null,null]]]]"#.to_owned(),
+                hyper::StatusCode::OK,
+            ))
+            .expect("in test");
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].quote_currency_code.as_str(), "usdt");
+        assert_eq!(symbols[1].base_currency_code.as_str(), "testbtc");
+
+        let response = RestResponse::new(
+            r#"[[54311712301,null,1701234567,"tBTCUST",1701234567000,1701234568000,-0.3,-0.5,"EXCHANGE LIMIT",null,null,null,4096,"PARTIALLY FILLED @ 40000.0(-0.2)",null,null,40000,40000,0,0,null,null,null,0,0,null,null,null,"API>BFX",null,null,{}]]"#.to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let orders = bitfinex.parse_open_orders(&response).expect("in test");
+
+        let order = &orders[0];
+        assert_eq!(order.exchange_order_id.as_str(), "54311712301");
+        assert_eq!(order.client_order_id.as_str(), "1701234567");
+        assert_eq!(order.order_side, OrderSide::Sell);
+        assert_eq!(order.order_status, OrderStatus::Created);
+        assert_eq!(order.amount, dec!(0.5));
+        assert_eq!(order.filled_amount, dec!(0.2));
+    }
+
+    #[test]
+    fn generate_signature() {
+        let signature = Bitfinex::create_signature(
+            "secret",
+            &[b"/api", b"/v2/auth/r/wallets", b"1701234567000000", b"{}"],
+        );
+
+        assert_eq!(signature, "c62d0d8755f837e72a27ff967289b9576eeeab9a1d174c4b909628ee60148dd7fac9cd6d40aff4d7b52b3031d33e6f5b");
+    }
+}
@@ -0,0 +1,444 @@
+use crate::bitfinex::Bitfinex;
+use crate::types::{
+    side_by_amount, BitfinexBookLevel, BitfinexOrder, BitfinexPublicTrade, BitfinexTrade,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+const BOOK_CHANNEL: &str = "book";
+const TRADES_CHANNEL: &str = "trades";
+/// Private events of authenticated connection are received on channel 0
+const ACCOUNT_CHANNEL_ID: u64 = 0;
+const HEARTBEAT: &str = "hb";
+const ORDER_BOOK_LEVELS: &str = "25";
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Public channel which is assigned to `chanId` on subscription
+pub(crate) struct SubscribedChannel {
+    channel: String,
+    currency_pair: CurrencyPair,
+}
+
+#[async_trait]
+impl Support for Bitfinex {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: Value = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        // Service messages are objects and channel data are arrays
+        match message {
+            Value::Object(_) => self.handle_event(&message, msg),
+            Value::Array(items) => self.handle_channel_message(items, msg),
+            _ => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg);
+                Ok(())
+            }
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        // Channel ids are assigned again after reconnection
+        self.subscribed_channels.lock().clear();
+
+        let currency_pairs = self.traded_specific_currencies.lock().clone();
+        for currency_pair in currency_pairs {
+            let subscribe_book = json!({
+                "event": "subscribe",
+                "channel": BOOK_CHANNEL,
+                "symbol": currency_pair.as_str(),
+                "prec": "P0",
+                "freq": "F0",
+                "len": ORDER_BOOK_LEVELS,
+            });
+            (self.websocket_message_callback)(
+                WebSocketRole::Secondary,
+                subscribe_book.to_string(),
+            )?;
+
+            let subscribe_trades = json!({
+                "event": "subscribe",
+                "channel": TRADES_CHANNEL,
+                "symbol": currency_pair.as_str(),
+            });
+            (self.websocket_message_callback)(
+                WebSocketRole::Secondary,
+                subscribe_trades.to_string(),
+            )?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        (self.websocket_message_callback)(WebSocketRole::Main, self.auth_message())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_websocket_keep_alive(&self, _role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        Some(WebSocketKeepAlive {
+            message: json!({ "event": "ping" }).to_string(),
+            interval: KEEP_ALIVE_INTERVAL,
+        })
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.starts_with("[0,")
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Bitfinex {
+    fn auth_message(&self) -> String {
+        let nonce = Bitfinex::get_nonce();
+        let payload = format!("AUTH{nonce}");
+
+        json!({
+            "event": "auth",
+            "apiKey": self.settings.api_key,
+            "authSig": Bitfinex::create_signature(&self.settings.secret_key, &[payload.as_bytes()]),
+            "authNonce": nonce,
+            "authPayload": payload,
+            "filter": ["trading"],
+        })
+        .to_string()
+    }
+
+    fn handle_event(&self, message: &Value, msg: &str) -> Result<()> {
+        match message["event"].as_str().unwrap_or_default() {
+            "info" | "pong" | "conf" => {}
+            "auth" if message["status"] == "OK" => {}
+            "auth" => {
+                let err = format!("Bitfinex websocket: authentication failed: {msg}");
+                log::error!("{err}");
+                bail!(err)
+            }
+            "subscribed" => {
+                let channel_id = message["chanId"]
+                    .as_u64()
+                    .with_context(|| format!("No chanId in subscription response: {msg}"))?;
+                let symbol = message["symbol"].as_str().unwrap_or_default();
+
+                self.subscribed_channels.lock().insert(
+                    channel_id,
+                    SubscribedChannel {
+                        channel: message["channel"].as_str().unwrap_or_default().to_owned(),
+                        currency_pair: self.get_unified_currency_pair(&symbol.into())?,
+                    },
+                );
+            }
+            "error" => {
+                let err = format!("Bitfinex websocket: error {msg}");
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => self.log_unknown_message(self.settings.exchange_account_id, msg),
+        }
+
+        Ok(())
+    }
+
+    /// Channel messages look like [CHANNEL_ID, EVENT?, DATA]
+    fn handle_channel_message(&self, mut items: Vec<Value>, msg: &str) -> Result<()> {
+        let channel_id = items
+            .first()
+            .and_then(Value::as_u64)
+            .with_context(|| format!("No channel id in message: {msg}"))?;
+        if items.get(1).and_then(Value::as_str) == Some(HEARTBEAT) {
+            return Ok(());
+        }
+
+        if channel_id == ACCOUNT_CHANNEL_ID {
+            let event = items[1].as_str().unwrap_or_default().to_owned();
+            return match items.get_mut(2) {
+                Some(data) => self.handle_account_event(&event, data.take()),
+                None => Ok(()),
+            };
+        }
+
+        let (channel, currency_pair) = match self.subscribed_channels.lock().get(&channel_id) {
+            Some(subscribed) => (subscribed.channel.clone(), subscribed.currency_pair),
+            None => {
+                log::warn!("Bitfinex websocket: message of unknown channel {msg}");
+                return Ok(());
+            }
+        };
+
+        let event = items[1].as_str().map(str::to_owned);
+        match (channel.as_str(), event.as_deref()) {
+            (BOOK_CHANNEL, None) => self.handle_order_book(currency_pair, items[1].take()),
+            (TRADES_CHANNEL, Some("te")) => {
+                let trade: BitfinexPublicTrade = serde_json::from_value(items[2].take())?;
+                self.handle_public_trade(currency_pair, trade)
+            }
+            // Trades snapshot and "tu" duplicate already handled "te" events
+            (TRADES_CHANNEL, _) => Ok(()),
+            _ => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg);
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_account_event(&self, event: &str, data: Value) -> Result<()> {
+        match event {
+            "on" => {
+                let order: BitfinexOrder = serde_json::from_value(data)?;
+                if let Some(cid) = order.cid {
+                    (self.order_created_callback)(
+                        cid.into(),
+                        order.id.into(),
+                        EventSourceType::WebSocket,
+                    );
+                }
+            }
+            "oc" => {
+                let order: BitfinexOrder = serde_json::from_value(data)?;
+                // Fills of executed orders are received by "tu" events
+                if order.status.contains("CANCELED") {
+                    if let Some(cid) = order.cid {
+                        (self.order_cancelled_callback)(
+                            cid.into(),
+                            order.id.into(),
+                            EventSourceType::WebSocket,
+                        );
+                    }
+                }
+            }
+            "tu" => self.handle_user_trade(serde_json::from_value(data)?)?,
+            // Wallets, positions, notifications and trades without fee info
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Snapshot is an array of levels, update is a single level
+    fn handle_order_book(&self, currency_pair: CurrencyPair, data: Value) -> Result<()> {
+        let is_snapshot = data
+            .as_array()
+            .and_then(|levels| levels.first())
+            .map_or(true, Value::is_array);
+
+        let levels: Vec<BitfinexBookLevel> = match is_snapshot {
+            true => serde_json::from_value(data)?,
+            false => vec![serde_json::from_value(data)?],
+        };
+
+        let mut order_book_data = OrderBookData::default();
+        for BitfinexBookLevel(price, count, amount) in levels {
+            let side = match amount.is_sign_positive() {
+                true => &mut order_book_data.bids,
+                false => &mut order_book_data.asks,
+            };
+            // Level without orders has to be removed
+            let amount = match count {
+                0 => Decimal::ZERO,
+                _ => amount.abs(),
+            };
+            side.insert(price, amount);
+        }
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            match is_snapshot {
+                true => EventType::Snapshot,
+                false => EventType::Update,
+            },
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_public_trade(
+        &self,
+        currency_pair: CurrencyPair,
+        trade: BitfinexPublicTrade,
+    ) -> Result<()> {
+        (self.handle_trade_callback)(
+            currency_pair,
+            Trade {
+                trade_id: TradeId::Number(trade.0),
+                price: trade.3,
+                quantity: trade.2.abs(),
+                side: side_by_amount(trade.2),
+                transaction_time: trade.time()?,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_user_trade(&self, trade: BitfinexTrade) -> Result<()> {
+        let Some(cid) = trade.cid else {
+            // Order was created outside of the bot
+            return Ok(());
+        };
+
+        // Check that currency pair is known before passing fill to engine
+        self.get_unified_currency_pair(&trade.symbol.as_str().into())?;
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::Number(trade.id)),
+            client_order_id: Some(cid.into()),
+            exchange_order_id: trade.order_id.into(),
+            fill_price: trade.exec_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.exec_amount.abs(),
+                total_filled_amount: None,
+            },
+            order_role: Some(Bitfinex::get_order_role(trade.maker)),
+            commission_currency_code: trade
+                .fee_currency
+                .map(|currency| self.currency_aliases.unify(currency.as_str().into())),
+            commission_rate: None,
+            // Negative fee means charged commission
+            commission_amount: trade.fee.map(|fee| -fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(trade.time),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::order::snapshot::OrderSide;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn parse_user_trade_event() {
+        let msg = r#"[0,"tu",[402088407,"tBTCUST",1574963975602,34938060782,-0.2,153.57,"EXCHANGE LIMIT",153.57,1,-0.0306,"UST",1574963975123]]"#;
+
+        let mut items: Vec<Value> = serde_json::from_str(msg).expect("in test");
+        assert_eq!(items[1], "tu");
+
+        let trade: BitfinexTrade = serde_json::from_value(items[2].take()).expect("in test");
+        assert_eq!(trade.id, 402088407);
+        assert_eq!(trade.order_id, 34938060782);
+        assert_eq!(side_by_amount(trade.exec_amount), OrderSide::Sell);
+        assert_eq!(trade.time.timestamp_millis(), 1574963975602);
+        assert_eq!(trade.fee, Some(dec!(-0.0306)));
+        assert_eq!(trade.cid, Some(1574963975123));
+    }
+
+    #[test]
+    fn parse_book_levels() {
+        let snapshot: Vec<BitfinexBookLevel> =
+            serde_json::from_str(r#"[[7254.7,3,3.3],[7254.8,1,-0.5]]"#).expect("in test");
+        assert_eq!(snapshot[1].0, dec!(7254.8));
+        assert_eq!(snapshot[1].2, dec!(-0.5));
+
+        let update: BitfinexBookLevel = serde_json::from_str(r#"[7254.7,0,1]"#).expect("in test");
+        assert_eq!(update.1, 0);
+    }
+}
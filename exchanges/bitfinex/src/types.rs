@@ -0,0 +1,200 @@
+use anyhow::{Context, Result};
+use chrono::{TimeZone, Utc};
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Bitfinex sends entities as arrays, so fields are taken by their positions
+fn field<T: DeserializeOwned>(fields: &[Value], index: usize, name: &str) -> Result<T> {
+    let value = fields.get(index).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value)
+        .with_context(|| format!("Unable to parse field {name} at position {index}"))
+}
+
+fn millis_to_date_time(millis: i64) -> Result<DateTime> {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .with_context(|| format!("Invalid Bitfinex time: {millis}"))
+}
+
+/// Bitfinex specifies side of orders and trades by sign of amount
+pub(crate) fn side_by_amount(amount: Amount) -> OrderSide {
+    match amount.is_sign_negative() {
+        true => OrderSide::Sell,
+        false => OrderSide::Buy,
+    }
+}
+
+/// Pair description from `/v2/conf/pub:info:pair`
+/// ["BTCUSD", [null, null, null, "0.00006", "2000.0", null, null, null, null, null, null, null]]
+#[derive(Deserialize, Debug)]
+#[serde(try_from = "Vec<Value>")]
+pub(crate) struct BitfinexPairInfo {
+    /// E.g. "BTCUSD", or "TESTBTC:TESTUSD" if any currency code is longer than 3 chars
+    pub(crate) pair: String,
+    pub(crate) min_amount: Option<Amount>,
+    pub(crate) max_amount: Option<Amount>,
+}
+
+impl TryFrom<Vec<Value>> for BitfinexPairInfo {
+    type Error = anyhow::Error;
+
+    fn try_from(fields: Vec<Value>) -> Result<Self> {
+        let info: Vec<Value> = field(&fields, 1, "INFO")?;
+        Ok(BitfinexPairInfo {
+            pair: field(&fields, 0, "PAIR")?,
+            min_amount: field(&info, 3, "MIN_ORDER_SIZE")?,
+            max_amount: field(&info, 4, "MAX_ORDER_SIZE")?,
+        })
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct BitfinexSubmitOrderRequest<'a> {
+    #[serde(rename = "type")]
+    pub(crate) order_type: &'static str,
+    pub(crate) symbol: &'a str,
+    /// Positive for buy and negative for sell
+    pub(crate) amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) price: Option<Price>,
+    pub(crate) cid: i64,
+    pub(crate) flags: u32,
+}
+
+/// Order array from `/v2/auth/r/orders`, notifications of write requests and websocket
+/// [ID, GID, CID, SYMBOL, MTS_CREATE, MTS_UPDATE, AMOUNT, AMOUNT_ORIG, TYPE, TYPE_PREV, MTS_TIF,
+///  _PLACEHOLDER, FLAGS, ORDER_STATUS, _PLACEHOLDER, _PLACEHOLDER, PRICE, PRICE_AVG, ...]
+#[derive(Deserialize, Debug)]
+#[serde(try_from = "Vec<Value>")]
+pub(crate) struct BitfinexOrder {
+    pub(crate) id: i64,
+    pub(crate) cid: Option<i64>,
+    pub(crate) symbol: String,
+    /// Remaining amount, negative for sell orders
+    pub(crate) amount: Amount,
+    /// Original amount, negative for sell orders
+    pub(crate) amount_orig: Amount,
+    /// E.g. "ACTIVE", "EXECUTED @ 107.6(-0.2)", "PARTIALLY FILLED @ 105.0(-0.1)", "CANCELED"
+    pub(crate) status: String,
+    pub(crate) price: Option<Price>,
+    pub(crate) price_avg: Option<Price>,
+}
+
+impl TryFrom<Vec<Value>> for BitfinexOrder {
+    type Error = anyhow::Error;
+
+    fn try_from(fields: Vec<Value>) -> Result<Self> {
+        Ok(BitfinexOrder {
+            id: field(&fields, 0, "ID")?,
+            cid: field(&fields, 2, "CID")?,
+            symbol: field(&fields, 3, "SYMBOL")?,
+            amount: field(&fields, 6, "AMOUNT")?,
+            amount_orig: field(&fields, 7, "AMOUNT_ORIG")?,
+            status: field(&fields, 13, "ORDER_STATUS")?,
+            price: field(&fields, 16, "PRICE")?,
+            price_avg: field(&fields, 17, "PRICE_AVG")?,
+        })
+    }
+}
+
+/// Notification which is returned on write requests, e.g. order submission
+/// [MTS, TYPE, MESSAGE_ID, _PLACEHOLDER, DATA, CODE, STATUS, TEXT]
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitfinexNotification<T>(
+    pub(crate) IgnoredAny,
+    pub(crate) String,
+    pub(crate) IgnoredAny,
+    pub(crate) IgnoredAny,
+    pub(crate) T,
+    pub(crate) IgnoredAny,
+    pub(crate) String,
+    pub(crate) Option<String>,
+);
+
+/// Trade array from `/v2/auth/r/trades/{symbol}/hist` and `tu` websocket event
+/// [ID, SYMBOL, MTS_CREATE, ORDER_ID, EXEC_AMOUNT, EXEC_PRICE, ORDER_TYPE, ORDER_PRICE, MAKER,
+///  FEE, FEE_CURRENCY, CID]
+#[derive(Deserialize, Debug)]
+#[serde(try_from = "Vec<Value>")]
+pub(crate) struct BitfinexTrade {
+    pub(crate) id: u64,
+    pub(crate) symbol: String,
+    pub(crate) time: DateTime,
+    pub(crate) order_id: i64,
+    /// Negative for sell trades
+    pub(crate) exec_amount: Amount,
+    pub(crate) exec_price: Price,
+    /// 1 for maker and -1 for taker
+    pub(crate) maker: i8,
+    /// Negative value means charged commission, it's absent in `te` websocket event
+    pub(crate) fee: Option<Amount>,
+    pub(crate) fee_currency: Option<String>,
+    pub(crate) cid: Option<i64>,
+}
+
+impl TryFrom<Vec<Value>> for BitfinexTrade {
+    type Error = anyhow::Error;
+
+    fn try_from(fields: Vec<Value>) -> Result<Self> {
+        Ok(BitfinexTrade {
+            id: field(&fields, 0, "ID")?,
+            symbol: field(&fields, 1, "SYMBOL")?,
+            time: millis_to_date_time(field(&fields, 2, "MTS_CREATE")?)?,
+            order_id: field(&fields, 3, "ORDER_ID")?,
+            exec_amount: field(&fields, 4, "EXEC_AMOUNT")?,
+            exec_price: field(&fields, 5, "EXEC_PRICE")?,
+            maker: field(&fields, 8, "MAKER")?,
+            fee: field(&fields, 9, "FEE")?,
+            fee_currency: field(&fields, 10, "FEE_CURRENCY")?,
+            cid: field(&fields, 11, "CID")?,
+        })
+    }
+}
+
+/// Wallet array from `/v2/auth/r/wallets`
+/// [WALLET_TYPE, CURRENCY, BALANCE, UNSETTLED_INTEREST, AVAILABLE_BALANCE, LAST_CHANGE, ...]
+#[derive(Deserialize, Debug)]
+#[serde(try_from = "Vec<Value>")]
+pub(crate) struct BitfinexWallet {
+    /// "exchange", "margin" or "funding"
+    pub(crate) wallet_type: String,
+    pub(crate) currency: String,
+    pub(crate) balance: Decimal,
+}
+
+impl TryFrom<Vec<Value>> for BitfinexWallet {
+    type Error = anyhow::Error;
+
+    fn try_from(fields: Vec<Value>) -> Result<Self> {
+        Ok(BitfinexWallet {
+            wallet_type: field(&fields, 0, "WALLET_TYPE")?,
+            currency: field(&fields, 1, "CURRENCY")?,
+            balance: field(&fields, 2, "BALANCE")?,
+        })
+    }
+}
+
+/// Level of public websocket `book` channel: [PRICE, COUNT, AMOUNT]
+/// Amount is positive for bids and negative for asks, count 0 means removal of level
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitfinexBookLevel(pub(crate) Price, pub(crate) u32, pub(crate) Amount);
+
+/// Trade of public websocket `trades` channel: [ID, MTS, AMOUNT, PRICE]
+/// Amount is negative for sell trades
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitfinexPublicTrade(
+    pub(crate) u64,
+    pub(crate) i64,
+    pub(crate) Amount,
+    pub(crate) Price,
+);
+
+impl BitfinexPublicTrade {
+    pub(crate) fn time(&self) -> Result<DateTime> {
+        millis_to_date_time(self.1)
+    }
+}
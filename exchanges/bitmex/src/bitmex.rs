@@ -15,29 +15,32 @@ use mmb_core::exchanges::general::features::{
     ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
     RestFillsType, WebSocketOptions,
 };
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
 use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
 use mmb_core::exchanges::hosts::Hosts;
 use mmb_core::exchanges::rest_client::{
-    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+    ErrorHandler, ErrorHandlerData, QueryKey, RequestType, RestClient, RestHeaders, RestResponse,
+    UriBuilder,
 };
 use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
 use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
 use mmb_core::exchanges::traits::{
-    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
-    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
-    Support,
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleCashFlowCb,
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
 };
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use mmb_core::settings::ExchangeSettings;
-use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent};
+use mmb_domain::events::{AllowedEventSourceType, EventSourceType, ExchangeBalance, ExchangeEvent};
 use mmb_domain::exchanges::symbol::{Precision, Symbol};
 use mmb_domain::market::{
-    CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId, SpecificCurrencyPair,
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
 };
 use mmb_domain::order::pool::{OrderRef, OrdersPool};
 use mmb_domain::order::snapshot::{
-    ExchangeOrderId, ExternalOrder, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
-    OrderSide, OrderStatus, Price, UserOrder,
+    Amount, ExchangeOrderId, ExternalOrder, OrderExecutionType, OrderHeader, OrderInfo,
+    OrderOptions, OrderRole, OrderSide, OrderStatus, Price, TriggerPriceType, UserOrder,
 };
 use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
 use mmb_utils::DateTime;
@@ -50,9 +53,10 @@ use sha2::Sha256;
 use std::collections::HashMap;
 use std::io::Write;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tinyvec::Array;
 use tokio::sync::broadcast;
+use url::form_urlencoded;
 use urlencoding_macro::encode;
 
 #[derive(Default)]
@@ -164,6 +168,10 @@ impl RestHeaders for RestHeadersBitmex {
 }
 
 const EMPTY_RESPONSE_IS_OK: bool = false;
+/// Bulk is rejected as a whole if any of its orders is invalid, so it is kept small
+pub(super) const BULK_ORDERS_MAX_COUNT: usize = 10;
+/// Parameters of order which are numbers in JSON of bulk request
+const NUMERIC_ORDER_PARAMS: &[&str] = &["orderQty", "price", "stopPx", "pegOffsetValue"];
 
 pub struct Bitmex {
     pub(crate) settings: ExchangeSettings,
@@ -181,9 +189,11 @@ pub struct Bitmex {
     pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
     pub(crate) handle_trade_callback: HandleTradeCb,
     pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(super) handle_cash_flow_callback: HandleCashFlowCb,
     pub(crate) websocket_message_callback: SendWebsocketMessageCb,
     pub(super) order_book_ids: Mutex<HashMap<(SpecificCurrencyPair, u64), Price>>,
     currency_balance_rates: Mutex<HashMap<CurrencyCode, Decimal>>,
+    pub(crate) currency_aliases: CurrencyAliases,
 }
 
 impl Bitmex {
@@ -193,14 +203,16 @@ impl Bitmex {
         lifetime_manager: Arc<AppLifetimeManager>,
     ) -> Bitmex {
         Self {
-            rest_client: RestClient::new(
+            rest_client: RestClient::with_network_settings(
                 ErrorHandlerData::new(
                     EMPTY_RESPONSE_IS_OK,
                     settings.exchange_account_id,
                     ErrorHandlerBitmex::default(),
                 ),
                 RestHeadersBitmex::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
             ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
             settings,
             hosts: Self::make_hosts(),
             unified_to_specific: Default::default(),
@@ -214,6 +226,7 @@ impl Bitmex {
             handle_order_filled_callback: Box::new(|_| {}),
             handle_trade_callback: Box::new(|_, _| {}),
             handle_metrics_callback: Box::new(|_| {}),
+            handle_cash_flow_callback: Box::new(|_| {}),
             websocket_message_callback: Box::new(|_, _| Ok(())),
             order_book_ids: Default::default(),
             currency_balance_rates: Default::default(),
@@ -246,8 +259,8 @@ impl Bitmex {
             .iter()
             .filter_map(|symbol| {
                 self.filter_symbol(symbol).map(|symbol| {
-                    let base = symbol.base_id.into();
-                    let quote = symbol.quote_id.into();
+                    let base = self.currency_aliases.unify(symbol.base_id.into());
+                    let quote = self.currency_aliases.unify(symbol.quote_id.into());
 
                     let specific_currency_pair = symbol.id.into();
                     let unified_currency_pair = CurrencyPair::from_codes(base, quote);
@@ -259,10 +272,12 @@ impl Bitmex {
                         .write()
                         .insert(specific_currency_pair, unified_currency_pair);
 
+                    // contract type of symbol is detected by amount and balance currencies
                     let (amount_currency_code, balance_currency_code) =
-                        match self.settings.is_margin_trading {
-                            true => (quote, Some(base)),
-                            false => (base, None),
+                        match (self.settings.is_margin_trading, symbol.is_inverse) {
+                            (true, true) => (quote, Some(base)),
+                            (true, false) => (base, Some(quote)),
+                            (false, _) => (base, None),
                         };
 
                     Arc::new(Symbol::new(
@@ -295,7 +310,13 @@ impl Bitmex {
 
         let is_active_symbol = symbol.state == "Open";
         let is_supported = match self.settings.is_margin_trading {
-            true => symbol_type == BitmexSymbolType::PerpetualContract && symbol.id != "ETHUSD_ETH", // ETHUSD_ETH is a ETH-margined perpetual swap. We don't support it at the moment
+            // ETHUSD_ETH is a ETH-margined perpetual swap. We don't support it at the moment.
+            // Quanto contracts aren't supported too because their settlement currency and multiplier aren't parsed
+            true => {
+                symbol_type == BitmexSymbolType::PerpetualContract
+                    && symbol.id != "ETHUSD_ETH"
+                    && !symbol.is_quanto
+            }
             false => symbol_type == BitmexSymbolType::Spot,
         };
 
@@ -312,13 +333,92 @@ impl Bitmex {
         order: &OrderRef,
     ) -> Result<RestResponse, ExchangeError> {
         let header = order.header();
+        let mut builder = UriBuilder::from_path("/api/v1/order");
+        for (key, value) in self.get_create_order_params(header)? {
+            builder.add_kv(key, value);
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("Create order for {header:?}");
+        self.rest_client
+            .post(uri, None, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn do_create_orders_bulk(
+        &self,
+        orders: &[OrderRef],
+    ) -> Result<RestResponse, ExchangeError> {
+        let bulk_orders = orders
+            .iter()
+            .map(|order| {
+                self.get_create_order_params(order.header()).map(|params| {
+                    params
+                        .into_iter()
+                        .map(|(key, value)| (key.to_owned(), bulk_order_param_value(key, value)))
+                        .collect::<serde_json::Map<_, _>>()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let bulk_orders = serde_json::to_string(&bulk_orders).map_err(|err| {
+            ExchangeError::unknown(&format!("Unable to serialize bulk orders: {err:?}"))
+        })?;
+
+        let mut builder = UriBuilder::from_path("/api/v1/order/bulk");
+        builder.add_kv(
+            "orders",
+            form_urlencoded::byte_serialize(bulk_orders.as_bytes()).collect::<String>(),
+        );
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!(
+            "Create bulk of orders {}",
+            orders.iter().map(|x| x.client_order_id()).join(", ")
+        );
+        self.rest_client
+            .post(uri, None, function_name!(), log_args)
+            .await
+    }
+
+    /// Bulk is created or rejected as a whole, so every order of successful response is created
+    pub(super) fn parse_create_orders_bulk(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<CreateOrderResult>, ExchangeError> {
+        #[derive(Deserialize)]
+        struct OrderId<'a> {
+            #[serde(rename = "orderID")]
+            order_id: &'a str,
+        }
+
+        let order_ids: Vec<OrderId> = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse bulk orders response: {err:?}"))
+        })?;
+
+        Ok(order_ids
+            .into_iter()
+            .map(|x| {
+                CreateOrderResult::succeed(
+                    &ExchangeOrderId::from(x.order_id),
+                    EventSourceType::Rest,
+                )
+            })
+            .collect())
+    }
+
+    fn get_create_order_params(
+        &self,
+        header: &OrderHeader,
+    ) -> Result<Vec<(QueryKey, String)>, ExchangeError> {
         let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
 
-        let mut builder = UriBuilder::from_path("/api/v1/order");
-        builder.add_kv("symbol", specific_currency_pair);
-        builder.add_kv("side", header.side);
-        builder.add_kv("orderQty", header.amount);
-        builder.add_kv("clOrdID", header.client_order_id.as_str());
+        let mut params = vec![
+            ("symbol", specific_currency_pair.to_string()),
+            ("side", header.side.to_string()),
+            ("orderQty", header.amount.to_string()),
+            ("clOrdID", header.client_order_id.as_str().to_owned()),
+        ];
 
         match header.options {
             OrderOptions::User(user_order) => match user_order {
@@ -326,42 +426,51 @@ impl Bitmex {
                     price,
                     execution_type,
                 } => {
-                    builder.add_kv("ordType", "Limit");
-                    builder.add_kv("price", price);
+                    params.push(("ordType", "Limit".to_owned()));
+                    params.push(("price", price.to_string()));
                     if execution_type == OrderExecutionType::MakerOnly {
-                        builder.add_kv("execInst", "ParticipateDoNotInitiate");
+                        params.push(("execInst", "ParticipateDoNotInitiate".to_owned()));
                     }
                 }
-                UserOrder::Market => builder.add_kv("ordType", "Market"),
-                UserOrder::StopLoss { stop_price } => {
-                    builder.add_kv("ordType", "Stop");
-                    builder.add_kv("stopPx", stop_price);
+                UserOrder::Market => params.push(("ordType", "Market".to_owned())),
+                UserOrder::StopLoss {
+                    stop_price,
+                    trigger_price_type,
+                } => {
+                    params.push(("ordType", "Stop".to_owned()));
+                    params.push(("stopPx", stop_price.to_string()));
+                    params.push((
+                        "execInst",
+                        get_trigger_exec_inst(trigger_price_type).to_string(),
+                    ));
                 }
                 UserOrder::TrailingStop {
-                    mut trailing_delta, ..
+                    mut trailing_delta,
+                    trigger_price_type,
+                    ..
                 } => {
-                    builder.add_kv("ordType", "Stop");
-                    builder.add_kv("pegPriceType", "TrailingStopPeg");
+                    params.push(("ordType", "Stop".to_owned()));
+                    params.push(("pegPriceType", "TrailingStopPeg".to_owned()));
                     if header.side == OrderSide::Sell {
                         trailing_delta.set_sign_negative(true);
                     }
-                    builder.add_kv("pegOffsetValue", trailing_delta);
+                    params.push(("pegOffsetValue", trailing_delta.to_string()));
+                    params.push((
+                        "execInst",
+                        get_trigger_exec_inst(trigger_price_type).to_string(),
+                    ));
                 }
             },
             // a little internal hack to not make additional variant in UserOrder enum
             OrderOptions::External(ExternalOrder::ClosePosition { .. }) => {
                 // It will cancel other active limit orders with the same side and symbol if the open quantity exceeds the current position
                 // Details: https://www.bitmex.com/api/explorer/#!/Order/Order_new
-                builder.add_kv("ordType", "Close");
+                params.push(("ordType", "Close".to_owned()));
             }
             _ => return Err(ExchangeError::unknown("Unexpected order type")),
         }
 
-        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
-        let log_args = format!("Create order for {header:?}");
-        self.rest_client
-            .post(uri, None, function_name!(), log_args)
-            .await
+        Ok(params)
     }
 
     pub(super) fn get_order_id(
@@ -521,6 +630,73 @@ impl Bitmex {
             .await
     }
 
+    /// Only trigger parameters of conditional orders are amended, order type can't be changed
+    #[named]
+    pub(super) async fn do_amend_conditional_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_user_order: &UserOrder,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/order");
+        builder.add_kv("orderID", exchange_order_id);
+
+        match *new_user_order {
+            UserOrder::StopLoss { stop_price, .. } => builder.add_kv("stopPx", stop_price),
+            UserOrder::TrailingStop {
+                mut trailing_delta, ..
+            } => {
+                if order.side() == OrderSide::Sell {
+                    trailing_delta.set_sign_negative(true);
+                }
+                builder.add_kv("pegOffsetValue", trailing_delta);
+            }
+            _ => {
+                return Err(ExchangeError::unknown(
+                    "Only conditional orders can be amended",
+                ))
+            }
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!(
+            "Amend order {} to {new_user_order:?}",
+            order.client_order_id()
+        );
+
+        self.rest_client.put(uri, function_name!(), log_args).await
+    }
+
+    /// Bitmex amends order in place, so `orderQty` is the new total amount including filled part
+    #[named]
+    pub(super) async fn do_modify_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_price: Option<Price>,
+        new_amount: Option<Amount>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/order");
+        builder.add_kv("orderID", exchange_order_id);
+        if let Some(price) = new_price {
+            match order.user_order() {
+                Some(UserOrder::StopLoss { .. }) => builder.add_kv("stopPx", price),
+                _ => builder.add_kv("price", price),
+            }
+        }
+        if let Some(amount) = new_amount {
+            builder.add_kv("orderQty", amount);
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!(
+            "Modify order {} to price {new_price:?} amount {new_amount:?}",
+            order.client_order_id()
+        );
+
+        self.rest_client.put(uri, function_name!(), log_args).await
+    }
+
     #[named]
     pub(super) async fn do_cancel_all_orders(&self) -> Result<RestResponse, ExchangeError> {
         let builder = UriBuilder::from_path("/api/v1/order/all");
@@ -533,6 +709,22 @@ impl Bitmex {
             .await
     }
 
+    /// Bitmex cancels all orders after `timeout`, zero timeout disarms the switch
+    #[named]
+    pub(super) async fn do_cancel_all_after(
+        &self,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/order/cancelAllAfter");
+        builder.add_kv("timeout", timeout.as_millis());
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("Cancel all orders after {timeout:?}");
+        self.rest_client
+            .post(uri, None, function_name!(), log_args)
+            .await
+    }
+
     pub(super) fn create_signature(secret_key: &str, message: &str, expire_time: u64) -> [u8; 64] {
         let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
             .expect("Unable to calculate hmac for Bitmex signature");
@@ -597,10 +789,11 @@ impl Bitmex {
                     datetime: trade.timestamp,
                     price: trade.fill_price,
                     amount: trade.fill_amount,
+                    side: trade.side,
                     order_role: Bitmex::get_order_role_by_commission_amount(
                         trade.commission_amount,
                     ),
-                    fee_currency_code: trade.currency.into(),
+                    fee_currency_code: self.currency_aliases.unify(trade.currency.into()),
                     fee_rate: Some(trade.commission_rate),
                     fee_amount: Some(trade.commission_amount),
                     fill_type: Self::get_order_fill_type(&trade.details).ok()?,
@@ -681,17 +874,14 @@ impl Bitmex {
         let raw_balances: Vec<BitmexBalanceInfo> =
             serde_json::from_str(&response.content).context("Failed to parse balance")?;
 
-        let currency_rates = self.currency_balance_rates.lock();
         raw_balances
             .into_iter()
             .map(|balance_info| {
                 let currency_code = balance_info.currency.into();
-                let balance_rate = currency_rates.get(&currency_code).ok_or_else(|| {
-                    anyhow!("Balance rate not found for currency {currency_code}")
-                })?;
+                let balance_rate = self.get_balance_rate(currency_code)?;
 
                 Result::<_, anyhow::Error>::Ok(ExchangeBalance {
-                    currency_code,
+                    currency_code: self.currency_aliases.unify(currency_code),
                     balance: balance_info.balance * balance_rate,
                 })
             })
@@ -755,6 +945,15 @@ impl Bitmex {
             .await
     }
 
+    /// Rate of conversion from minimal units of currency (e.g. XBt) to the currency itself
+    pub(super) fn get_balance_rate(&self, currency_code: CurrencyCode) -> Result<Decimal> {
+        self.currency_balance_rates
+            .lock()
+            .get(&currency_code)
+            .copied()
+            .ok_or_else(|| anyhow!("Balance rate not found for currency {currency_code}"))
+    }
+
     fn parse_wallet_assets(&self, response: &RestResponse) -> Result<()> {
         let assets: Vec<BitmexWalletAsset> = serde_json::from_str(&response.content)
             .context("Failed to parse wallet assets response")?;
@@ -768,6 +967,24 @@ impl Bitmex {
     }
 }
 
+fn bulk_order_param_value(key: QueryKey, value: String) -> serde_json::Value {
+    if NUMERIC_ORDER_PARAMS.contains(&key) {
+        if let Ok(number) = value.parse() {
+            return serde_json::Value::Number(number);
+        }
+    }
+
+    serde_json::Value::String(value)
+}
+
+fn get_trigger_exec_inst(trigger_price_type: TriggerPriceType) -> &'static str {
+    match trigger_price_type {
+        TriggerPriceType::Last => "LastPrice",
+        TriggerPriceType::Mark => "MarkPrice",
+        TriggerPriceType::Index => "IndexPrice",
+    }
+}
+
 pub struct BitmexBuilder;
 
 impl ExchangeClientBuilder for BitmexBuilder {
@@ -796,6 +1013,10 @@ impl ExchangeClientBuilder for BitmexBuilder {
                     order_was_completed_error_for_cancellation: true,
                     supports_already_cancelled_order: true,
                     supports_stop_loss_order: true,
+                    supports_mark_price_trigger: true,
+                    supports_index_price_trigger: true,
+                    supports_amend_order: true,
+                    supports_iceberg_order: false,
                 },
                 OrderTradeOption {
                     supports_trade_time: true,
@@ -831,6 +1052,8 @@ impl ExchangeClientBuilder for BitmexBuilder {
 mod tests {
     use super::*;
     use bstr::ByteSlice;
+    use mmb_domain::events::CashFlowKind;
+    use mmb_utils::cancellation_token::CancellationToken;
 
     #[test]
     fn generate_signature() {
@@ -857,4 +1080,107 @@ mod tests {
             "e2f422547eecb5b3cb29ade2127e21b858b235b386bfa45e1c1756eb3383919f"
         );
     }
+
+    #[test]
+    fn funding_execution_is_reported_as_cash_flow() {
+        let exchange_account_id = "Bitmex_0".parse().expect("in test");
+        let mut settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
+        settings.currency_aliases = HashMap::from([("xbt".into(), "btc".into())]);
+        let (tx, _) = broadcast::channel(10);
+        let mut bitmex = Bitmex::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let currency_pair = CurrencyPair::from_codes("btc".into(), "usd".into());
+        bitmex
+            .specific_to_unified
+            .write()
+            .insert("XBTUSD".into(), currency_pair);
+        bitmex
+            .currency_balance_rates
+            .lock()
+            .insert("XBt".into(), dec!(0.00000001));
+
+        let cash_flows = Arc::new(Mutex::new(Vec::new()));
+        bitmex.set_handle_cash_flow_callback(Box::new({
+            let cash_flows = cash_flows.clone();
+            move |cash_flow| cash_flows.lock().push(cash_flow)
+        }));
+
+        let message = r#"{"table":"execution","action":"insert","data":[{
+            "execID":"8f1a7b63-3c1e-4b7e-a1b0-2a1b3c4d5e6f","orderID":"00000000-0000-0000-0000-000000000000",
+            "clOrdID":"","symbol":"XBTUSD","side":"","lastQty":null,"lastPx":27000,
+            "ordStatus":"Filled","execType":"Funding","commission":0.0001,"execComm":2500,
+            "settlCurrency":"XBt","text":"Funding","transactTime":"2023-01-01T04:00:00.000Z",
+            "timestamp":"2023-01-01T04:00:00.000Z"}]}"#;
+        bitmex.on_websocket_message(message).expect("in test");
+
+        let cash_flows = cash_flows.lock();
+        assert_eq!(cash_flows.len(), 1);
+        assert_eq!(cash_flows[0].kind, CashFlowKind::Funding);
+        assert_eq!(cash_flows[0].currency_pair, currency_pair);
+        assert_eq!(cash_flows[0].currency_code.as_str(), "btc");
+        // paid funding decreases balance
+        assert_eq!(cash_flows[0].amount, dec!(-0.000025));
+    }
+
+    fn parse_xbtusd_symbol(currency_aliases: HashMap<CurrencyCode, CurrencyCode>) -> Arc<Symbol> {
+        let exchange_account_id = "Bitmex_0".parse().expect("in test");
+        let mut settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
+        settings.currency_aliases = currency_aliases;
+        let (tx, _) = broadcast::channel(10);
+        let bitmex = Bitmex::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let response = RestResponse::new(
+            r#"[{"typ":"FFWCSX","symbol":"XBTUSD","underlying":"XBT","quoteCurrency":"USD",
+                "state":"Open","tickSize":0.5,"lotSize":100,"maxPrice":1000000,
+                "maxOrderQty":10000000,"isInverse":true,"isQuanto":false}]"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+        let mut symbols = bitmex.parse_all_symbols(&response).expect("in test");
+        assert_eq!(symbols.len(), 1);
+        symbols.remove(0)
+    }
+
+    #[test]
+    fn xbt_is_unified_to_btc_by_configured_alias() {
+        let symbol = parse_xbtusd_symbol(HashMap::new());
+        assert_eq!(
+            symbol.currency_pair(),
+            CurrencyPair::from_codes("xbt".into(), "usd".into())
+        );
+
+        let symbol = parse_xbtusd_symbol(HashMap::from([("XBT".into(), "btc".into())]));
+        assert_eq!(
+            symbol.currency_pair(),
+            CurrencyPair::from_codes("btc".into(), "usd".into())
+        );
+        assert_eq!(symbol.base_currency_id.as_str(), "XBT");
+        assert_eq!(symbol.balance_currency_code, Some("btc".into()));
+    }
+
+    #[test]
+    fn bulk_order_quantities_are_numbers() {
+        assert_eq!(
+            bulk_order_param_value("orderQty", "100".to_owned()),
+            serde_json::json!(100)
+        );
+        assert_eq!(
+            bulk_order_param_value("pegOffsetValue", "-1.5".to_owned()),
+            serde_json::json!(-1.5)
+        );
+        assert_eq!(
+            bulk_order_param_value("clOrdID", "12345".to_owned()),
+            serde_json::json!("12345")
+        );
+    }
 }
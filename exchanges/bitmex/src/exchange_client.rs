@@ -1,5 +1,5 @@
-use crate::bitmex::Bitmex;
-use anyhow::{bail, Result};
+use crate::bitmex::{Bitmex, BULK_ORDERS_MAX_COUNT};
+use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
 use itertools::Itertools;
 use mmb_core::exchanges::general::exchange::RequestResult;
@@ -11,10 +11,11 @@ use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
 use mmb_domain::exchanges::symbol::Symbol;
 use mmb_domain::market::CurrencyPair;
 use mmb_domain::order::pool::OrderRef;
-use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, OrderInfo, Price, UserOrder};
 use mmb_domain::position::{ActivePosition, ClosedPosition};
 use mmb_utils::DateTime;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[async_trait]
 impl ExchangeClient for Bitmex {
@@ -28,6 +29,19 @@ impl ExchangeClient for Bitmex {
         }
     }
 
+    fn max_orders_batch_size(&self) -> Option<usize> {
+        Some(BULK_ORDERS_MAX_COUNT)
+    }
+
+    async fn create_orders_batch(
+        &self,
+        orders: &[OrderRef],
+    ) -> Result<Vec<CreateOrderResult>, ExchangeError> {
+        let response = self.do_create_orders_bulk(orders).await?;
+
+        self.parse_create_orders_bulk(&response)
+    }
+
     async fn cancel_order(
         &self,
         order: &OrderRef,
@@ -41,6 +55,33 @@ impl ExchangeClient for Bitmex {
         }
     }
 
+    async fn amend_conditional_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_user_order: &UserOrder,
+    ) -> Option<Result<ExchangeOrderId, ExchangeError>> {
+        Some(
+            self.do_amend_conditional_order(order, exchange_order_id, new_user_order)
+                .await
+                .map(|_| exchange_order_id.clone()),
+        )
+    }
+
+    async fn modify_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        new_price: Option<Price>,
+        new_amount: Option<Amount>,
+    ) -> Option<Result<(), ExchangeError>> {
+        Some(
+            self.do_modify_order(order, exchange_order_id, new_price, new_amount)
+                .await
+                .map(|_| ()),
+        )
+    }
+
     async fn cancel_all_orders(&self, _currency_pair: CurrencyPair) -> Result<()> {
         match self.do_cancel_all_orders().await {
             Ok(_) => Ok(()),
@@ -48,6 +89,13 @@ impl ExchangeClient for Bitmex {
         }
     }
 
+    async fn cancel_all_after(&self, timeout: Duration) -> Option<Result<()>> {
+        Some(match self.do_cancel_all_after(timeout).await {
+            Ok(_) => Ok(()),
+            Err(error) => Err(anyhow!("Failed to arm cancel all after: {error:?}")),
+        })
+    }
+
     async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
         let response = self.request_open_orders(None).await?;
 
@@ -1,6 +1,6 @@
 use crate::bitmex::Bitmex;
 use crate::types::{
-    BitmexOrderBookDelete, BitmexOrderBookInsert, BitmexOrderBookUpdate, BitmexOrderFillDummy,
+    BitmexOrderBookDelete, BitmexOrderBookInsert, BitmexOrderBookUpdate, BitmexOrderFillFunding,
     BitmexOrderFillTrade, BitmexOrderStatus, BitmexTradePayload,
 };
 use anyhow::{bail, Context, Result};
@@ -14,12 +14,14 @@ use mmb_core::exchanges::general::handlers::handle_order_filled::{
     FillAmount, FillEvent, SpecialOrderData,
 };
 use mmb_core::exchanges::traits::{
-    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
-    SendWebsocketMessageCb, Support,
+    HandleCashFlowCb, HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb,
+    OrderCreatedCb, SendWebsocketMessageCb, Support,
 };
 use mmb_core::settings::ExchangeSettings;
-use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade};
-use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
+use mmb_domain::events::{CashFlowEvent, CashFlowKind, EventSourceType, ExchangeEvent, Trade};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
 use mmb_domain::order::fill::OrderFillType;
 use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
 use mmb_domain::order_book::event::{EventType, OrderBookEvent};
@@ -122,6 +124,10 @@ impl Support for Bitmex {
         self.handle_metrics_callback = callback;
     }
 
+    fn set_handle_cash_flow_callback(&mut self, callback: HandleCashFlowCb) {
+        self.handle_cash_flow_callback = callback;
+    }
+
     fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
         *self.traded_specific_currencies.lock() = currencies;
     }
@@ -158,6 +164,10 @@ impl Support for Bitmex {
     fn get_settings(&self) -> &ExchangeSettings {
         &self.settings
     }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
 }
 
 impl Bitmex {
@@ -428,7 +438,9 @@ impl Bitmex {
                             order_role: Some(Bitmex::get_order_role_by_commission_amount(
                                 data.commission_amount,
                             )),
-                            commission_currency_code: Some(data.currency.into()),
+                            commission_currency_code: Some(
+                                self.currency_aliases.unify(data.currency.into()),
+                            ),
                             commission_rate: Some(data.commission_rate),
                             commission_amount: Some(data.commission_amount),
                             fill_type: Self::get_order_fill_type(&data.details)?,
@@ -438,7 +450,7 @@ impl Bitmex {
 
                         (self.handle_order_filled_callback)(fill_event);
                     }
-                    BitmexOrderFill::Funding(_) => (),
+                    BitmexOrderFill::Funding(data) => self.handle_funding(data)?,
                 },
             }
         }
@@ -446,6 +458,25 @@ impl Bitmex {
         Ok(())
     }
 
+    /// Funding of perpetual contracts is charged every funding interval (usually 8 hours)
+    fn handle_funding(&self, funding: BitmexOrderFillFunding) -> Result<()> {
+        let currency_code = funding.currency.into();
+        let balance_rate = self.get_balance_rate(currency_code)?;
+
+        let cash_flow = CashFlowEvent {
+            exchange_account_id: self.settings.exchange_account_id,
+            currency_pair: self.get_unified_currency_pair(&funding.symbol)?,
+            currency_code: self.currency_aliases.unify(currency_code),
+            kind: CashFlowKind::Funding,
+            amount: -funding.commission_amount * balance_rate,
+            transaction_time: funding.transaction_time,
+        };
+
+        (self.handle_cash_flow_callback)(cash_flow);
+
+        Ok(())
+    }
+
     pub(crate) fn get_order_fill_type(text: &str) -> Result<OrderFillType> {
         if text == "Liquidation" {
             Ok(OrderFillType::Liquidation)
@@ -711,5 +742,5 @@ enum BitmexOrderExecutionPayload<'a> {
 #[serde(tag = "execType")]
 pub(crate) enum BitmexOrderFill<'a> {
     Trade(BitmexOrderFillTrade<'a>),
-    Funding(BitmexOrderFillDummy),
+    Funding(BitmexOrderFillFunding<'a>),
 }
@@ -133,6 +133,10 @@ pub(crate) struct BitmexSymbol<'a> {
     pub(crate) max_price: Option<Price>,
     #[serde(rename = "maxOrderQty")]
     pub(crate) max_amount: Option<Amount>,
+    #[serde(rename = "isInverse", default)]
+    pub(crate) is_inverse: bool,
+    #[serde(rename = "isQuanto", default)]
+    pub(crate) is_quanto: bool,
 }
 
 #[derive(PartialEq)]
@@ -410,8 +414,18 @@ pub(crate) struct BitmexOrderFillTrade<'a> {
     pub(crate) commission_amount: Decimal,
 }
 
+/// Funding payment of position, it's reported as execution with execType "Funding"
 #[derive(Deserialize, Debug)]
-pub(crate) struct BitmexOrderFillDummy {}
+pub(crate) struct BitmexOrderFillFunding<'a> {
+    pub(crate) symbol: SpecificCurrencyPair,
+    #[serde(rename = "settlCurrency")]
+    pub(crate) currency: &'a str,
+    /// Paid funding in minimal units of settlement currency, received funding is negative
+    #[serde(rename = "execComm")]
+    pub(crate) commission_amount: Decimal,
+    #[serde(rename = "transactTime", deserialize_with = "deserialize_datetime")]
+    pub(crate) transaction_time: DateTime,
+}
 
 /// Bitmex Balance response description
 ///{
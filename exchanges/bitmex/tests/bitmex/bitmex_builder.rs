@@ -15,7 +15,7 @@ use mmb_core::exchanges::general::features::{
 use mmb_core::exchanges::hosts::Hosts;
 use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
 use mmb_core::infrastructure::init_lifetime_manager;
-use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings};
+use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings, OperationPoliciesSettings};
 use mmb_domain::events::{AllowedEventSourceType, ExchangeEvent};
 use mmb_domain::exchanges::commission::Commission;
 use mmb_domain::market::{CurrencyPair, ExchangeAccountId};
@@ -166,7 +166,7 @@ impl BitmexBuilder {
         let hosts = bitmex.hosts.clone();
 
         let exchange_blocker = ExchangeBlocker::new(vec![settings.exchange_account_id]);
-        let event_recorder = EventRecorder::start(None, None)
+        let event_recorder = EventRecorder::start(None, None, None)
             .await
             .expect("Failure start EventRecorder");
 
@@ -183,6 +183,7 @@ impl BitmexBuilder {
             Arc::downgrade(&exchange_blocker),
             commission,
             event_recorder,
+            OperationPoliciesSettings::default(),
         );
         exchange.build_symbols(&settings.currency_pairs).await;
         exchange.connect_ws().await.with_expect(move || {
@@ -0,0 +1,925 @@
+use crate::types::{
+    BitstampBalance, BitstampCreatedOrder, BitstampError, BitstampOpenOrder, BitstampOrderStatus,
+    BitstampPairInfo, BitstampUserTransaction, BitstampWebsocketToken,
+};
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, UserOrder,
+};
+use mmb_utils::time::{get_current_milliseconds, u64_to_date_time};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Maximum count of transactions returned by `/api/v2/user_transactions/`
+const USER_TRANSACTIONS_LIMIT: u32 = 1000;
+/// Type of user transaction which is trade
+const MARKET_TRADE_TRANSACTION_TYPE: &str = "2";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+const AUTH_VERSION: &str = "v2";
+
+#[derive(Default)]
+pub struct ErrorHandlerBitstamp;
+
+impl ErrorHandler for ErrorHandlerBitstamp {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        // Successful responses are objects or arrays without `status` or `error` fields
+        let error: BitstampError = match serde_json::from_str(&response.content) {
+            Ok(error) => error,
+            Err(_) => return Ok(()),
+        };
+
+        let message = match (error.status.as_str(), error.error) {
+            ("error", _) => {
+                // Errors of fields are flattened to single message
+                let reason = match &error.reason {
+                    Value::String(reason) => reason.clone(),
+                    Value::Object(errors) => errors
+                        .values()
+                        .flat_map(|x| x.as_array().cloned().unwrap_or_default())
+                        .filter_map(|x| x.as_str().map(str::to_owned))
+                        .join("; "),
+                    reason => reason.to_string(),
+                };
+                format!("{}: {reason}", error.code)
+            }
+            (_, Some(error)) => error,
+            _ => return Ok(()),
+        };
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            message,
+            None,
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://www.bitstamp.net/api/#section/Response-codes
+        let message = error.message.as_str();
+        if message.contains("Order not found") {
+            ExchangeErrorType::OrderNotFound
+        } else if message.contains("You have only") || message.contains("available") {
+            ExchangeErrorType::InsufficientFunds
+        } else if message.contains("Minimum order size")
+            || message.contains("Maximum order size")
+            || message.contains("Price is more than")
+            || message.contains("Invalid")
+            || message.contains("would be executed immediately")
+        {
+            ExchangeErrorType::InvalidOrder
+        } else if message.starts_with("API0001")
+            || message.starts_with("API0002")
+            || message.starts_with("API0003")
+            || message.starts_with("API0004")
+            || message.starts_with("API0005")
+            || message.starts_with("API0006")
+        {
+            ExchangeErrorType::Authentication
+        } else if message.contains("Too many requests") || message.contains("rate limit") {
+            ExchangeErrorType::RateLimit
+        } else {
+            ExchangeErrorType::Unknown
+        }
+    }
+}
+
+/// Private requests are authenticated by HMAC-SHA256 signature of method, host, path,
+/// content type, nonce, timestamp and body in `X-Auth-*` headers
+pub struct RestHeadersBitstamp {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersBitstamp {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersBitstamp {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        // Only public endpoints are requested by GET
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        _request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        let nonce = Uuid::new_v4().to_string();
+        let timestamp = get_current_milliseconds().to_string();
+        let signature = Bitstamp::create_signature(
+            &self.api_key,
+            &self.secret_key,
+            uri,
+            body,
+            &nonce,
+            &timestamp,
+        );
+
+        let builder = builder
+            .header("X-Auth", format!("BITSTAMP {}", self.api_key))
+            .header("X-Auth-Signature", signature)
+            .header("X-Auth-Nonce", nonce)
+            .header("X-Auth-Timestamp", timestamp)
+            .header("X-Auth-Version", AUTH_VERSION);
+
+        // Content type is required only for requests with parameters
+        match body.is_empty() {
+            true => builder,
+            false => builder.header(CONTENT_TYPE, FORM_CONTENT_TYPE),
+        }
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Bitstamp {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerBitstamp, RestHeadersBitstamp>,
+    /// Specific currency pairs are url symbols (e.g. "btcusd") used in paths and websocket channels
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    // Order responses return pair name (e.g. "BTC/USD") instead of url symbol (e.g. "btcusd")
+    pair_name_to_unified: RwLock<HashMap<String, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    // Token for private websocket channels which is requested before connection
+    pub(crate) websocket_token: Mutex<Option<BitstampWebsocketToken>>,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Bitstamp {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Bitstamp {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerBitstamp::default(),
+                ),
+                RestHeadersBitstamp::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            pair_name_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+            websocket_token: Default::default(),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://ws.bitstamp.net",
+            web_socket2_host: "wss://ws.bitstamp.net",
+            rest_host: "https://www.bitstamp.net",
+        }
+    }
+
+    /// Upper case hex encoded HMAC-SHA256 of request description
+    pub(super) fn create_signature(
+        api_key: &str,
+        secret_key: &str,
+        uri: &Uri,
+        body: &[u8],
+        nonce: &str,
+        timestamp: &str,
+    ) -> String {
+        let content_type = match body.is_empty() {
+            true => "",
+            false => FORM_CONTENT_TYPE,
+        };
+
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Bitstamp signature");
+        hmac.update(format!("BITSTAMP {api_key}POST").as_bytes());
+        hmac.update(uri.host().unwrap_or_default().as_bytes());
+        hmac.update(uri.path().as_bytes());
+        hmac.update(uri.query().unwrap_or_default().as_bytes());
+        hmac.update(content_type.as_bytes());
+        hmac.update(nonce.as_bytes());
+        hmac.update(timestamp.as_bytes());
+        hmac.update(AUTH_VERSION.as_bytes());
+        hmac.update(body);
+
+        format!("{:X}", hmac.finalize().into_bytes())
+    }
+
+    async fn post_private(
+        &self,
+        builder: UriBuilder,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+        self.rest_client
+            .post(uri, Some(query), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/api/v2/trading-pairs-info/")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let pairs: Vec<BitstampPairInfo> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize trading pairs from Bitstamp")?;
+
+        pairs
+            .into_iter()
+            .filter(|pair| pair.trading == "Enabled")
+            .map(|pair| self.parse_pair_info(pair))
+            .try_collect()
+    }
+
+    fn parse_pair_info(&self, pair: BitstampPairInfo) -> Result<Arc<Symbol>> {
+        let (base_id, quote_id) = pair
+            .name
+            .split('/')
+            .collect_tuple()
+            .with_context(|| format!("Unexpected Bitstamp pair name {}", pair.name))?;
+        let base_id = base_id.to_lowercase();
+        let quote_id = quote_id.to_lowercase();
+        let base = self.currency_aliases.unify(base_id.as_str().into());
+        let quote = self.currency_aliases.unify(quote_id.as_str().into());
+
+        let specific_currency_pair = pair.url_symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+        self.pair_name_to_unified
+            .write()
+            .insert(pair.name.clone(), unified_currency_pair);
+
+        self.supported_currencies
+            .insert(base_id.as_str().into(), base);
+        self.supported_currencies
+            .insert(quote_id.as_str().into(), quote);
+
+        // Minimum order is cost in quote currency, e.g. "10 USD"
+        let min_cost = pair
+            .minimum_order
+            .split_whitespace()
+            .next()
+            .and_then(|x| x.parse::<Decimal>().ok());
+
+        Ok(Arc::new(Symbol::new(
+            false,
+            base_id.as_str().into(),
+            base,
+            quote_id.as_str().into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            min_cost,
+            base,
+            None,
+            Precision::ByTick {
+                tick: Decimal::new(1, pair.counter_decimals),
+            },
+            Precision::ByTick {
+                tick: Decimal::new(1, pair.base_decimals),
+            },
+        )))
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+        let side = Self::get_server_order_side(header.side);
+
+        let mut builder = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                let mut builder =
+                    UriBuilder::from_path(&format!("/api/v2/{side}/{specific_currency_pair}/"));
+                builder.add_kv("price", price);
+                if execution_type == OrderExecutionType::MakerOnly {
+                    builder.add_kv("moc_order", "True");
+                }
+                builder
+            }
+            OrderOptions::User(UserOrder::Market) => {
+                UriBuilder::from_path(&format!("/api/v2/{side}/market/{specific_currency_pair}/"))
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+        builder.add_kv("amount", header.amount);
+        builder.add_kv("client_order_id", header.client_order_id.as_str());
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let order: BitstampCreatedOrder = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order id: {err:?}")))?;
+
+        Ok(order.id)
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v2/cancel_order/");
+        builder.add_kv("id", exchange_order_id);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+        let builder = UriBuilder::from_path(&format!(
+            "/api/v2/cancel_all_orders/{specific_currency_pair}/"
+        ));
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = match currency_pair {
+            Some(currency_pair) => format!(
+                "/api/v2/open_orders/{}/",
+                self.get_specific_currency_pair(currency_pair)
+            ),
+            None => "/api/v2/open_orders/all/".to_owned(),
+        };
+
+        self.post_private(
+            UriBuilder::from_path(&path),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<BitstampOpenOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .into_iter()
+            .map(|order| {
+                Ok(OrderInfo::new(
+                    self.get_unified_currency_pair_by_name(&order.currency_pair)?,
+                    order.id,
+                    ClientOrderId::from(order.client_order_id.unwrap_or_default().as_str()),
+                    Self::get_local_order_side(&order.side)?,
+                    OrderStatus::Created,
+                    order.price,
+                    order.amount_at_create,
+                    // Average price of fills isn't returned for open orders
+                    dec!(0),
+                    order.amount_at_create - order.amount,
+                    None,
+                    None,
+                    None,
+                ))
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let mut builder = UriBuilder::from_path("/api/v2/order_status/");
+        builder.add_kv("client_order_id", client_order_id.as_str());
+
+        let log_args = format!("order {client_order_id}");
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    /// Order status contains neither price nor initial amount of order,
+    /// so they are calculated from fills of order
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let order: BitstampOrderStatus = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        let currency_pair = self.get_unified_currency_pair_by_name(&order.market)?;
+        let base_id = self.get_base_currency_id(&order.market)?;
+
+        let mut filled_amount = dec!(0);
+        let mut filled_cost = dec!(0);
+        for transaction in &order.transactions {
+            let amount = get_amount(&transaction.amounts, &base_id)?.abs();
+            filled_amount += amount;
+            filled_cost += amount * transaction.price;
+        }
+        let average_fill_price = match filled_amount.is_zero() {
+            true => dec!(0),
+            false => filled_cost / filled_amount,
+        };
+
+        Ok(OrderInfo::new(
+            currency_pair,
+            order.id,
+            ClientOrderId::from(order.client_order_id.unwrap_or_default().as_str()),
+            Self::get_local_order_side(&order.side)?,
+            Self::get_local_order_status(&order.status)?,
+            average_fill_price,
+            filled_amount + order.amount_remaining,
+            average_fill_price,
+            filled_amount,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    fn get_unified_currency_pair_by_name(&self, pair_name: &str) -> Result<CurrencyPair> {
+        self.pair_name_to_unified
+            .read()
+            .get(pair_name)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found pair name '{pair_name}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    /// Amounts in responses are keyed by lower case currency ids from pair name
+    fn get_base_currency_id(&self, pair_name: &str) -> Result<String> {
+        pair_name
+            .split('/')
+            .next()
+            .map(str::to_lowercase)
+            .with_context(|| format!("Unexpected Bitstamp pair name {pair_name}"))
+    }
+
+    fn get_server_order_side(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    /// Side is "0" for buy and "1" for sell
+    pub(super) fn get_local_order_side(side: &str) -> Result<OrderSide> {
+        match side {
+            "0" => Ok(OrderSide::Buy),
+            "1" => Ok(OrderSide::Sell),
+            _ => bail!("Unknown Bitstamp order side {side}"),
+        }
+    }
+
+    fn get_local_order_status(status: &str) -> Result<OrderStatus> {
+        match status {
+            "Open" => Ok(OrderStatus::Created),
+            "Finished" => Ok(OrderStatus::Completed),
+            "Canceled" | "Expired" => Ok(OrderStatus::Canceled),
+            _ => bail!("Unknown Bitstamp order status {status}"),
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
+
+        let mut builder = UriBuilder::from_path(&format!(
+            "/api/v2/user_transactions/{specific_currency_pair}/"
+        ));
+        builder.add_kv("limit", USER_TRANSACTIONS_LIMIT);
+        builder.add_kv("sort", "asc");
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("since_timestamp", date_time.timestamp());
+        }
+
+        self.post_private(builder, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Amounts of transactions are keyed by currency ids of symbol,
+    /// sign of base amount defines side of trade
+    pub(super) fn parse_my_trades(
+        &self,
+        symbol: &Symbol,
+        response: &RestResponse,
+    ) -> Result<Vec<OrderTrade>> {
+        let transactions: Vec<BitstampUserTransaction> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        let base_id = symbol.base_currency_id.as_str().to_lowercase();
+        let quote_id = symbol.quote_currency_id.as_str().to_lowercase();
+        let price_key = format!("{base_id}_{quote_id}");
+
+        transactions
+            .into_iter()
+            .filter(|x| x.transaction_type == MARKET_TRADE_TRANSACTION_TYPE)
+            .map(|transaction| {
+                let amount = get_amount(&transaction.amounts, &base_id)?;
+                let side = match amount.is_sign_negative() {
+                    true => OrderSide::Sell,
+                    false => OrderSide::Buy,
+                };
+
+                Ok(OrderTrade {
+                    exchange_order_id: transaction.order_id.context("Missing order id of trade")?,
+                    trade_id: TradeId::Number(transaction.id),
+                    datetime: parse_date_time(&transaction.datetime)?,
+                    price: get_amount(&transaction.amounts, &price_key)?,
+                    amount: amount.abs(),
+                    side,
+                    // Liquidity of trade isn't returned by Bitstamp
+                    order_role: OrderRole::Taker,
+                    fee_currency_code: symbol.quote_currency_code(),
+                    fee_rate: None,
+                    fee_amount: Some(transaction.fee),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        self.post_private(
+            UriBuilder::from_path("/api/v2/account_balances/"),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let balances: Vec<BitstampBalance> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(balances
+            .into_iter()
+            .map(|balance| ExchangeBalance {
+                currency_code: self
+                    .currency_aliases
+                    .unify(balance.currency.as_str().into()),
+                balance: balance.total,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_websocket_token(&self) -> Result<RestResponse, ExchangeError> {
+        self.post_private(
+            UriBuilder::from_path("/api/v2/websockets_token/"),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_websocket_token(response: &RestResponse) -> Result<BitstampWebsocketToken> {
+        serde_json::from_str(&response.content)
+            .context("Unable to parse websocket token response for Bitstamp")
+    }
+}
+
+/// Amounts are strings in most responses but numbers for some currencies
+fn get_amount(amounts: &HashMap<String, Value>, key: &str) -> Result<Amount> {
+    let value = amounts
+        .get(key)
+        .with_context(|| format!("Missing amount of {key}"))?;
+
+    match value {
+        Value::String(amount) => amount.parse::<Decimal>().map_err(Into::into),
+        Value::Number(amount) => amount.to_string().parse::<Decimal>().map_err(Into::into),
+        _ => bail!("Unexpected amount of {key}: {value}"),
+    }
+    .with_context(|| format!("Unable to parse amount of {key}"))
+}
+
+/// Date time of REST responses in UTC, e.g. "2022-01-31 14:43:15.796000"
+fn parse_date_time(date_time: &str) -> Result<DateTime> {
+    let date_time = NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M:%S%.f")
+        .with_context(|| format!("Unable to parse Bitstamp date time {date_time}"))?;
+
+    Ok(Utc.from_utc_datetime(&date_time))
+}
+
+/// Microseconds of websocket messages, e.g. "1643640195123456"
+pub(crate) fn parse_microtimestamp(microtimestamp: &str) -> Result<DateTime> {
+    let microseconds: u64 = microtimestamp
+        .parse()
+        .with_context(|| format!("Unable to parse Bitstamp microtimestamp {microtimestamp}"))?;
+
+    Ok(u64_to_date_time(microseconds / 1000))
+}
+
+pub struct BitstampBuilder;
+
+impl ExchangeClientBuilder for BitstampBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Bitstamp::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: false,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Default limit is 10000 requests per 10 minutes
+        RequestTimeoutArguments::from_requests_per_minute(1000)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Bitstamp".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+
+    fn create_bitstamp() -> Bitstamp {
+        let exchange_account_id: ExchangeAccountId = "Bitstamp_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let bitstamp = Bitstamp::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let response = RestResponse::new(
+            r#"[{"name":"BTC/USD","url_symbol":"btcusd","base_decimals":8,"counter_decimals":0,"instant_order_counter_decimals":2,"minimum_order":"10 USD","trading":"Enabled","instant_and_market_orders":"Enabled","description":"Bitcoin / U.S. dollar"},{"name":"OLD/USD","url_symbol":"oldusd","base_decimals":2,"counter_decimals":5,"instant_order_counter_decimals":5,"minimum_order":"10 USD","trading":"Disabled","instant_and_market_orders":"Disabled","description":"Old / U.S. dollar"}]"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        bitstamp.parse_all_symbols(&response).expect("in test");
+
+        bitstamp
+    }
+
+    #[test]
+    fn parse_trading_pairs() {
+        let bitstamp = create_bitstamp();
+        let btc_usd = CurrencyPair::from_codes("btc".into(), "usd".into());
+
+        assert_eq!(
+            bitstamp.get_specific_currency_pair(btc_usd).as_str(),
+            "btcusd"
+        );
+        assert_eq!(
+            bitstamp
+                .get_unified_currency_pair_by_name("BTC/USD")
+                .expect("in test"),
+            btc_usd
+        );
+        assert!(bitstamp
+            .get_unified_currency_pair_by_name("OLD/USD")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_order_status() {
+        let bitstamp = create_bitstamp();
+        let response = RestResponse::new(
+            r#"{"id":1234123412341234,"datetime":"2022-01-31 14:43:15","type":"1","status":"Open","market":"BTC/USD","transactions":[{"tid":2,"price":"100.00","btc":"0.10000000","usd":"10.00","fee":"0.05","datetime":"2022-01-31 14:43:16","type":2},{"tid":3,"price":"110.00","btc":"0.10000000","usd":"11.00","fee":"0.05","datetime":"2022-01-31 14:43:17","type":2}],"amount_remaining":"0.30000000","client_order_id":"123123"}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let order = bitstamp.parse_order_info(&response).expect("in test");
+
+        assert_eq!(order.exchange_order_id.as_str(), "1234123412341234");
+        assert_eq!(order.client_order_id.as_str(), "123123");
+        assert_eq!(order.order_side, OrderSide::Sell);
+        assert_eq!(order.order_status, OrderStatus::Created);
+        assert_eq!(order.amount, dec!(0.5));
+        assert_eq!(order.filled_amount, dec!(0.2));
+        assert_eq!(order.average_fill_price, dec!(105));
+    }
+
+    #[test]
+    fn parse_my_trades() {
+        let bitstamp = create_bitstamp();
+        let symbol = bitstamp
+            .parse_all_symbols(&RestResponse::new(
+                r#"[{"name":"BTC/USD","url_symbol":"btcusd","base_decimals":8,"counter_decimals":0,"minimum_order":"10 USD","trading":"Enabled"}]"#.to_owned(),
+                StatusCode::OK,
+            ))
+            .expect("in test")
+            .remove(0);
+        let response = RestResponse::new(
+            r#"[{"id":257041873,"datetime":"2022-01-31 14:43:15.796000","type":"2","fee":"0.05000","btc":"-0.10000000","usd":"10.00","btc_usd":"100.00","eur":0.0,"order_id":1234123412341234},{"id":257041874,"datetime":"2022-01-31 14:50:00.000000","type":"0","fee":"0.00000","btc":"0","usd":"500.00","eur":0.0}]"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let trades = bitstamp
+            .parse_my_trades(&symbol, &response)
+            .expect("in test");
+
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!(trade.exchange_order_id.as_str(), "1234123412341234");
+        assert_eq!(trade.side, OrderSide::Sell);
+        assert_eq!(trade.amount, dec!(0.1));
+        assert_eq!(trade.price, dec!(100));
+        assert_eq!(trade.fee_amount, Some(dec!(0.05)));
+    }
+
+    #[test]
+    fn clarify_error_type_by_reason() {
+        let response = RestResponse::new(
+            r#"{"status":"error","reason":{"__all__":["You have only 1.00 USD available. Check your account balance for details."]},"code":"API0011"}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let error = ErrorHandlerBitstamp
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(
+            ErrorHandlerBitstamp.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+    }
+}
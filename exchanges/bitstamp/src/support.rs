@@ -0,0 +1,349 @@
+use crate::bitstamp::{parse_microtimestamp, Bitstamp};
+use crate::types::{
+    BitstampMyOrder, BitstampMyTrade, BitstampOrderBook, BitstampPublicTrade, BitstampWsMessage,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderSide};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde_json::json;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+const ORDER_BOOK_CHANNEL: &str = "order_book_";
+const LIVE_TRADES_CHANNEL: &str = "live_trades_";
+const MY_ORDERS_CHANNEL: &str = "private-my_orders_";
+const MY_TRADES_CHANNEL: &str = "private-my_trades_";
+
+#[async_trait]
+impl Support for Bitstamp {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: BitstampWsMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        let channel = message.channel.as_str();
+        match message.event.as_str() {
+            "data" if channel.starts_with(ORDER_BOOK_CHANNEL) => {
+                self.handle_order_book(channel, serde_json::from_value(message.data)?)?
+            }
+            "trade" if channel.starts_with(LIVE_TRADES_CHANNEL) => {
+                self.handle_trade(channel, serde_json::from_value(message.data)?)?
+            }
+            "trade" if channel.starts_with(MY_TRADES_CHANNEL) => {
+                self.handle_my_trade(serde_json::from_value(message.data)?)
+            }
+            "order_created" | "order_changed" | "order_deleted" => self.handle_my_order(
+                message.event.as_str(),
+                serde_json::from_value(message.data)?,
+            ),
+            "bts:subscription_succeeded" | "bts:heartbeat" => {}
+            // Server is going to be restarted, connection will be reestablished after closing
+            "bts:request_reconnect" => log::warn!(
+                "Reconnection is requested by Bitstamp for {}",
+                self.settings.exchange_account_id
+            ),
+            "bts:error" => bail!("Bitstamp websocket error: {msg}"),
+            _ => self.log_unknown_message(self.settings.exchange_account_id, msg),
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let currency_pairs = self.traded_specific_currencies.lock().clone();
+        for currency_pair in &currency_pairs {
+            for channel in [ORDER_BOOK_CHANNEL, LIVE_TRADES_CHANNEL] {
+                let subscribe = json!({
+                    "event": "bts:subscribe",
+                    "data": { "channel": format!("{channel}{currency_pair}") },
+                });
+                (self.websocket_message_callback)(WebSocketRole::Main, subscribe.to_string())?;
+            }
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Secondary) {
+            return Ok(());
+        }
+
+        let token = self.websocket_token.lock().clone();
+        let token =
+            token.context("Websocket token for Bitstamp private channels wasn't received")?;
+        for currency_pair in &currency_pairs {
+            for channel in [MY_ORDERS_CHANNEL, MY_TRADES_CHANNEL] {
+                let subscribe = json!({
+                    "event": "bts:subscribe",
+                    "data": {
+                        "channel": format!("{channel}{currency_pair}-{}", token.user_id),
+                        "auth": token.token,
+                    },
+                });
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        *self.websocket_token.lock() = None;
+
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => {
+                // Private channels require token which is valid for 60 seconds after receiving
+                let response = self.request_websocket_token().await?;
+                *self.websocket_token.lock() = Some(Bitstamp::parse_websocket_token(&response)?);
+
+                self.hosts.web_socket2_host
+            }
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains("private-my_")
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Bitstamp {
+    /// Every message of order book channel contains top 100 levels of order book
+    fn handle_order_book(&self, channel: &str, order_book: BitstampOrderBook) -> Result<()> {
+        let specific_currency_pair = channel.trim_start_matches(ORDER_BOOK_CHANNEL).into();
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(OrderBookData::new(
+                order_book.asks.into_iter().collect(),
+                order_book.bids.into_iter().collect(),
+            )),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, channel: &str, trade: BitstampPublicTrade) -> Result<()> {
+        let specific_currency_pair = channel.trim_start_matches(LIVE_TRADES_CHANNEL).into();
+
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&specific_currency_pair)?,
+            Trade {
+                trade_id: TradeId::Number(trade.id),
+                price: trade.price,
+                quantity: trade.amount,
+                side: match trade.side {
+                    0 => OrderSide::Buy,
+                    _ => OrderSide::Sell,
+                },
+                transaction_time: parse_microtimestamp(&trade.microtimestamp)?,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_my_order(&self, event: &str, order: BitstampMyOrder) {
+        if order.client_order_id.is_empty() {
+            // Order was created outside of the bot
+            return;
+        }
+        let client_order_id = ClientOrderId::from(order.client_order_id.as_str());
+
+        match event {
+            "order_created" => (self.order_created_callback)(
+                client_order_id,
+                order.id_str,
+                EventSourceType::WebSocket,
+            ),
+            // Fully filled orders are deleted too, their fills are handled from trades channel
+            "order_deleted" if !order.amount.is_zero() => (self.order_cancelled_callback)(
+                client_order_id,
+                order.id_str,
+                EventSourceType::WebSocket,
+            ),
+            // Changes of orders are partial fills
+            _ => {}
+        }
+    }
+
+    fn handle_my_trade(&self, trade: BitstampMyTrade) {
+        if trade.client_order_id.is_empty() {
+            // Order was created outside of the bot
+            return;
+        }
+
+        let fill_date = match parse_microtimestamp(&trade.microtimestamp) {
+            Ok(fill_date) => Some(fill_date),
+            Err(err) => {
+                log::warn!("{err:?}");
+                None
+            }
+        };
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::Number(trade.id)),
+            client_order_id: Some(ClientOrderId::from(trade.client_order_id.as_str())),
+            exchange_order_id: trade.order_id,
+            fill_price: trade.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.amount,
+                total_filled_amount: None,
+            },
+            // Liquidity of trade isn't sent by Bitstamp
+            order_role: None,
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: Some(trade.fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date,
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn parse_market_data_messages() {
+        let message: BitstampWsMessage = serde_json::from_str(
+            r#"{"data":{"timestamp":"1643640195","microtimestamp":"1643640195123456","bids":[["37000","0.10000000"],["36999","0.20000000"]],"asks":[["37001","0.30000000"]]},"channel":"order_book_btcusd","event":"data"}"#,
+        )
+        .expect("in test");
+        assert_eq!(message.event, "data");
+        let order_book: BitstampOrderBook = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(order_book.bids.len(), 2);
+        assert_eq!(order_book.asks[0], (dec!(37001), dec!(0.3)));
+
+        let message: BitstampWsMessage = serde_json::from_str(
+            r#"{"data":{"id":217005617,"timestamp":"1643640195","amount":0.01,"amount_str":"0.01000000","price":37000,"price_str":"37000","type":1,"microtimestamp":"1643640195123456","buy_order_id":1450000000000000,"sell_order_id":1450000000000001},"channel":"live_trades_btcusd","event":"trade"}"#,
+        )
+        .expect("in test");
+        let trade: BitstampPublicTrade = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(trade.id, 217005617);
+        assert_eq!(trade.amount, dec!(0.01));
+        assert_eq!(trade.side, 1);
+        assert_eq!(
+            parse_microtimestamp(&trade.microtimestamp)
+                .expect("in test")
+                .timestamp_millis(),
+            1643640195123
+        );
+    }
+
+    #[test]
+    fn parse_private_messages() {
+        let message: BitstampWsMessage = serde_json::from_str(
+            r#"{"data":{"id":1450000000000000,"id_str":"1450000000000000","client_order_id":"123123","order_type":0,"datetime":"1643640195","microtimestamp":"1643640195123456","amount":0.05,"amount_str":"0.05000000","price":37000,"price_str":"37000"},"channel":"private-my_orders_btcusd-123","event":"order_deleted"}"#,
+        )
+        .expect("in test");
+        let order: BitstampMyOrder = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(order.id_str.as_str(), "1450000000000000");
+        assert_eq!(order.amount, dec!(0.05));
+
+        let message: BitstampWsMessage = serde_json::from_str(
+            r#"{"data":{"id":217005617,"order_id":1450000000000000,"client_order_id":"123123","amount":"0.05000000","price":"37000","fee":"0.92500","side":"buy","microtimestamp":"1643640195123456"},"channel":"private-my_trades_btcusd-123","event":"trade"}"#,
+        )
+        .expect("in test");
+        let trade: BitstampMyTrade = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(trade.order_id.as_str(), "1450000000000000");
+        assert_eq!(trade.fee, dec!(0.925));
+    }
+}
@@ -0,0 +1,298 @@
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, Price};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Order ids are strings in some responses and numbers in others
+fn deserialize_order_id<'de, D>(deserializer: D) -> Result<ExchangeOrderId, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(id) => Ok(id.as_str().into()),
+        Value::Number(id) => Ok(id.to_string().as_str().into()),
+        value => Err(serde::de::Error::custom(format!(
+            "Unexpected Bitstamp order id {value}"
+        ))),
+    }
+}
+
+/// Currency pair from `/api/v2/trading-pairs-info/`
+/// {
+///   "name": "BTC/USD",
+///   "url_symbol": "btcusd",
+///   "base_decimals": 8,
+///   "counter_decimals": 0,
+///   "instant_order_counter_decimals": 2,
+///   "minimum_order": "10 USD",
+///   "trading": "Enabled",
+///   "instant_and_market_orders": "Enabled",
+///   "description": "Bitcoin / U.S. dollar"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampPairInfo {
+    /// "BTC/USD", used by order responses
+    pub(crate) name: String,
+    /// "btcusd", used in paths of requests and names of websocket channels
+    pub(crate) url_symbol: String,
+    pub(crate) base_decimals: u32,
+    pub(crate) counter_decimals: u32,
+    /// Minimum cost of order in quote currency, e.g. "10 USD"
+    pub(crate) minimum_order: String,
+    /// "Enabled" or "Disabled"
+    pub(crate) trading: String,
+}
+
+/// Response of `/api/v2/buy/{pair}/` and `/api/v2/sell/{pair}/`
+/// {
+///   "id": "1234123412341234",
+///   "market": "BTC/USD",
+///   "datetime": "2022-01-31 14:43:15.796000",
+///   "type": "0",
+///   "price": "100.00",
+///   "amount": "0.10000000",
+///   "client_order_id": "123123"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampCreatedOrder {
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) id: ExchangeOrderId,
+}
+
+/// Order from `/api/v2/open_orders/all/`
+/// {
+///   "id": "1234123412341234",
+///   "datetime": "2022-01-31 14:43:15",
+///   "type": "0",
+///   "price": "100.00",
+///   "amount": "0.05000000",
+///   "amount_at_create": "0.10000000",
+///   "currency_pair": "BTC/USD",
+///   "market": "BTC/USD",
+///   "client_order_id": "123123"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampOpenOrder {
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) id: ExchangeOrderId,
+    /// "0" for buy and "1" for sell
+    #[serde(rename = "type")]
+    pub(crate) side: String,
+    pub(crate) price: Price,
+    /// Remaining amount
+    pub(crate) amount: Amount,
+    pub(crate) amount_at_create: Amount,
+    pub(crate) currency_pair: String,
+    /// Absent for orders created outside of the bot
+    #[serde(default)]
+    pub(crate) client_order_id: Option<String>,
+}
+
+/// Response of `/api/v2/order_status/`, amounts of transactions are keyed by currency ids
+/// {
+///   "id": 1234123412341234,
+///   "datetime": "2022-01-31 14:43:15",
+///   "type": "0",
+///   "status": "Finished",
+///   "market": "BTC/USD",
+///   "transactions": [
+///     {"tid": 2, "price": "100.00", "btc": "0.10000000", "usd": "10.00", "fee": "0.05", "datetime": "2022-01-31 14:43:16", "type": 2}
+///   ],
+///   "amount_remaining": "0.00000000",
+///   "client_order_id": "123123"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampOrderStatus {
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) id: ExchangeOrderId,
+    #[serde(rename = "type")]
+    pub(crate) side: String,
+    /// "Open", "Finished", "Expired" or "Canceled"
+    pub(crate) status: String,
+    pub(crate) market: String,
+    #[serde(default)]
+    pub(crate) transactions: Vec<BitstampOrderTransaction>,
+    pub(crate) amount_remaining: Amount,
+    #[serde(default)]
+    pub(crate) client_order_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampOrderTransaction {
+    pub(crate) price: Price,
+    /// Amounts by currency ids, e.g. "btc" and "usd"
+    #[serde(flatten)]
+    pub(crate) amounts: HashMap<String, Value>,
+}
+
+/// Trade from `/api/v2/user_transactions/{pair}/`, amounts are keyed by currency ids
+/// and price is keyed by pair, e.g. "btc_usd". Amount of base currency is negative for sells
+/// {
+///   "id": 257041873,
+///   "datetime": "2022-01-31 14:43:15.796000",
+///   "type": "2",
+///   "fee": "0.05000",
+///   "btc": "-0.10000000",
+///   "usd": "10.00",
+///   "btc_usd": "100.00",
+///   "eur": 0.0,
+///   "order_id": 1234123412341234
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampUserTransaction {
+    pub(crate) id: u64,
+    pub(crate) datetime: String,
+    /// "0" deposit, "1" withdrawal, "2" market trade and others
+    #[serde(rename = "type")]
+    pub(crate) transaction_type: String,
+    pub(crate) fee: Amount,
+    #[serde(default, deserialize_with = "deserialize_optional_order_id")]
+    pub(crate) order_id: Option<ExchangeOrderId>,
+    #[serde(flatten)]
+    pub(crate) amounts: HashMap<String, Value>,
+}
+
+fn deserialize_optional_order_id<'de, D>(
+    deserializer: D,
+) -> Result<Option<ExchangeOrderId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_order_id(deserializer).map(Some)
+}
+
+/// Balance of currency from `/api/v2/account_balances/`
+/// {
+///   "currency": "btc",
+///   "total": "1.00000000",
+///   "available": "0.90000000",
+///   "reserved": "0.10000000"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampBalance {
+    pub(crate) currency: String,
+    /// Total balance including amount reserved by open orders
+    pub(crate) total: Amount,
+}
+
+/// Response of `/api/v2/websockets_token/`
+/// {"token": "...", "valid_sec": 60, "user_id": 123}
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct BitstampWebsocketToken {
+    pub(crate) token: String,
+    pub(crate) user_id: u64,
+}
+
+/// Every websocket message has event and channel
+/// {"event": "data", "channel": "order_book_btcusd", "data": {...}}
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampWsMessage {
+    pub(crate) event: String,
+    #[serde(default)]
+    pub(crate) channel: String,
+    #[serde(default)]
+    pub(crate) data: Value,
+}
+
+/// Data of `order_book_{pair}` channel with top 100 levels of order book
+/// {
+///   "timestamp": "1643640195",
+///   "microtimestamp": "1643640195123456",
+///   "bids": [["37000", "0.1"]],
+///   "asks": [["37001", "0.2"]]
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampOrderBook {
+    pub(crate) bids: Vec<(Price, Amount)>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
+/// Data of `live_trades_{pair}` channel
+/// {
+///   "id": 217005617,
+///   "timestamp": "1643640195",
+///   "amount": 0.01,
+///   "amount_str": "0.01000000",
+///   "price": 37000,
+///   "price_str": "37000",
+///   "type": 0,
+///   "microtimestamp": "1643640195123456",
+///   "buy_order_id": 1450000000000000,
+///   "sell_order_id": 1450000000000001
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampPublicTrade {
+    pub(crate) id: u64,
+    #[serde(rename = "amount_str")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "price_str")]
+    pub(crate) price: Price,
+    /// 0 for buy and 1 for sell
+    #[serde(rename = "type")]
+    pub(crate) side: u8,
+    pub(crate) microtimestamp: String,
+}
+
+/// Data of `private-my_orders_{pair}-{user_id}` channel,
+/// events are "order_created", "order_changed" and "order_deleted"
+/// {
+///   "id": 1450000000000000,
+///   "id_str": "1450000000000000",
+///   "client_order_id": "123123",
+///   "order_type": 0,
+///   "datetime": "1643640195",
+///   "microtimestamp": "1643640195123456",
+///   "amount": 0.05,
+///   "amount_str": "0.05000000",
+///   "price": 37000,
+///   "price_str": "37000"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampMyOrder {
+    pub(crate) id_str: ExchangeOrderId,
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+    /// Remaining amount
+    #[serde(rename = "amount_str")]
+    pub(crate) amount: Amount,
+}
+
+/// Data of `private-my_trades_{pair}-{user_id}` channel
+/// {
+///   "id": 217005617,
+///   "order_id": 1450000000000000,
+///   "client_order_id": "123123",
+///   "amount": "0.05000000",
+///   "price": "37000",
+///   "fee": "0.92500",
+///   "side": "buy",
+///   "microtimestamp": "1643640195123456"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampMyTrade {
+    pub(crate) id: u64,
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+    pub(crate) amount: Amount,
+    pub(crate) price: Price,
+    pub(crate) fee: Amount,
+    pub(crate) microtimestamp: String,
+}
+
+/// Error response of REST API, `reason` is string or object with errors by fields.
+/// Some endpoints return legacy format with `error` field only
+/// {"status": "error", "reason": "Order not found", "code": "API0010"}
+/// {"status": "error", "reason": {"__all__": ["You have only 1 USD available. Check your account balance for details."]}, "code": "API0011"}
+#[derive(Deserialize, Debug)]
+pub(crate) struct BitstampError {
+    #[serde(default)]
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) reason: Value,
+    #[serde(default)]
+    pub(crate) code: String,
+    #[serde(default)]
+    pub(crate) error: Option<String>,
+}
@@ -0,0 +1,813 @@
+use crate::types::{
+    BybitCancelOrderRequest, BybitExecution, BybitInstrument, BybitList, BybitOrderId,
+    BybitOrderInfo, BybitPlaceOrderRequest, BybitPosition, BybitResponse, BybitWallet,
+};
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole, OrderSide,
+    OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerBybit;
+
+impl ErrorHandler for ErrorHandlerBybit {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct BybitError {
+            ret_code: i64,
+            ret_msg: String,
+        }
+
+        let bybit_error: BybitError = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse Bybit response: {err:?}"))
+        })?;
+
+        match bybit_error.ret_code {
+            0 => Ok(()),
+            code => Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                bybit_error.ret_msg,
+                Some(code),
+            )),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://bybit-exchange.github.io/docs/v5/error
+        match error.code {
+            Some(110001) => ExchangeErrorType::OrderNotFound,
+            Some(110008) | Some(110010) => ExchangeErrorType::OrderCompleted,
+            Some(110004) | Some(110007) | Some(110012) => ExchangeErrorType::InsufficientFunds,
+            Some(10001) | Some(110003) | Some(110017) | Some(110094) => {
+                ExchangeErrorType::InvalidOrder
+            }
+            Some(10006) | Some(10018) => ExchangeErrorType::RateLimit,
+            Some(10002) | Some(10003) | Some(10004) | Some(10005) | Some(33004) => {
+                ExchangeErrorType::Authentication
+            }
+            Some(10016) => ExchangeErrorType::ServiceUnavailable,
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersBybit {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersBybit {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+
+    fn add_auth_headers(&self, builder: Builder, payload: &[u8]) -> Builder {
+        if self.api_key.is_empty() {
+            // Public endpoints don't require authentication
+            return builder;
+        }
+
+        let timestamp = Bybit::get_unix_time_millis().to_string();
+        let signature = Bybit::create_signature(
+            &self.secret_key,
+            &[
+                timestamp.as_bytes(),
+                self.api_key.as_bytes(),
+                RECV_WINDOW.as_bytes(),
+                payload,
+            ],
+        );
+
+        builder
+            .header("X-BAPI-API-KEY", &self.api_key)
+            .header("X-BAPI-TIMESTAMP", timestamp)
+            .header("X-BAPI-RECV-WINDOW", RECV_WINDOW)
+            .header("X-BAPI-SIGN", signature)
+    }
+}
+
+impl RestHeaders for RestHeadersBybit {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        // GET requests are signed with query string
+        let query = uri.query().unwrap_or_default();
+        self.add_auth_headers(builder, query.as_bytes())
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        self.add_auth_headers(builder, body)
+            .header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+// Milliseconds during which the signed request is valid on Bybit side
+const RECV_WINDOW: &str = "5000";
+// Only USDT linear perpetual contracts are supported for now
+const CATEGORY: &str = "linear";
+const SETTLE_COIN: &str = "USDT";
+
+pub struct Bybit {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerBybit, RestHeadersBybit>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Bybit {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Bybit {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerBybit::default(),
+                ),
+                RestHeadersBybit::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://stream.bybit.com/v5/private",
+            web_socket2_host: "wss://stream.bybit.com/v5/public/linear",
+            rest_host: "https://api.bybit.com",
+        }
+    }
+
+    pub(super) fn get_unix_time_millis() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_millis()
+    }
+
+    /// Hex encoded HMAC-SHA256 of concatenated message parts
+    pub(super) fn create_signature(secret_key: &str, message_parts: &[&[u8]]) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Bybit signature");
+        for part in message_parts {
+            hmac.update(part);
+        }
+
+        format!("{:x}", hmac.finalize().into_bytes())
+    }
+
+    async fn post_json(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(body).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to serialize Bybit request body: {err:?}"))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v5/market/instruments-info");
+        builder.add_kv("category", CATEGORY);
+        builder.add_kv("limit", 1000);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let instruments: BybitResponse<BybitList<BybitInstrument>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize response from Bybit")?;
+
+        Ok(instruments
+            .result
+            .list
+            .iter()
+            .filter(|instrument| {
+                instrument.contract_type == "LinearPerpetual"
+                    && instrument.status == "Trading"
+                    && instrument.settle_coin == SETTLE_COIN
+            })
+            .map(|instrument| self.parse_symbol(instrument))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, instrument: &BybitInstrument) -> Arc<Symbol> {
+        let base_id = instrument.base_coin.as_str();
+        let quote_id = instrument.quote_coin.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = instrument.symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        // Quantity of linear contracts is specified in base currency, margin is in settle currency
+        let settle = self
+            .currency_aliases
+            .unify(instrument.settle_coin.as_str().into());
+
+        Arc::new(Symbol::new(
+            true,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            Some(instrument.price_filter.min_price),
+            Some(instrument.price_filter.max_price),
+            Some(instrument.lot_size_filter.min_order_qty),
+            Some(instrument.lot_size_filter.max_order_qty),
+            None,
+            base,
+            Some(settle),
+            Precision::ByTick {
+                tick: instrument.price_filter.tick_size,
+            },
+            Precision::ByTick {
+                tick: instrument.lot_size_filter.qty_step,
+            },
+        ))
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let (order_type, price, time_in_force) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => match execution_type {
+                OrderExecutionType::MakerOnly => ("Limit", Some(price), "PostOnly"),
+                OrderExecutionType::None => ("Limit", Some(price), "GTC"),
+            },
+            OrderOptions::User(UserOrder::Market) => ("Market", None, "IOC"),
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let request = BybitPlaceOrderRequest {
+            category: CATEGORY,
+            symbol: specific_currency_pair.as_str(),
+            side: header.side,
+            order_type,
+            qty: header.amount,
+            price,
+            time_in_force,
+            order_link_id: header.client_order_id.as_str(),
+            reduce_only: false,
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_json("/v5/order/create", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let deserialized: BybitResponse<BybitOrderId> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse orderId: {err:?}")))?;
+
+        Ok(deserialized.result.order_id)
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(order.currency_pair());
+        let request = BybitCancelOrderRequest {
+            category: CATEGORY,
+            symbol: specific_currency_pair.as_str(),
+            order_id: Some(exchange_order_id.as_str()),
+        };
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_json("/v5/order/cancel", &request, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+        let request = BybitCancelOrderRequest {
+            category: CATEGORY,
+            symbol: specific_currency_pair.as_str(),
+            order_id: None,
+        };
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.post_json("/v5/order/cancel-all", &request, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v5/order/realtime");
+        builder.add_kv("category", CATEGORY);
+        match currency_pair {
+            Some(pair) => builder.add_kv("symbol", self.get_specific_currency_pair(pair)),
+            // Symbol or settle coin is required for linear category
+            None => builder.add_kv("settleCoin", SETTLE_COIN),
+        }
+        builder.add_kv("openOnly", 0);
+        builder.add_kv("limit", 50);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: BybitResponse<BybitList<BybitOrderInfo>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .result
+            .list
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        // Realtime orders contain open orders and recently closed ones
+        let mut builder = UriBuilder::from_path("/v5/order/realtime");
+        builder.add_kv("category", CATEGORY);
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("orderLinkId", &client_order_id);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: BybitResponse<BybitList<BybitOrderInfo>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_order_info request")?;
+
+        let order = orders
+            .result
+            .list
+            .into_iter()
+            .next()
+            .context("No one order info received")?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: BybitOrderInfo) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.symbol.as_str().into())?,
+            specific.order_id,
+            specific.order_link_id,
+            specific.side,
+            Bybit::get_local_order_status(&specific.order_status),
+            specific.price.unwrap_or_default(),
+            specific.qty,
+            specific.avg_price.unwrap_or_default(),
+            specific.cum_exec_qty.unwrap_or_default(),
+            Some(self.get_settle_currency_code().to_string()),
+            None,
+            specific.cum_exec_fee,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    /// Commission of linear contracts is always charged in settle currency
+    pub(super) fn get_settle_currency_code(&self) -> CurrencyCode {
+        self.currency_aliases.unify(SETTLE_COIN.into())
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "Created" | "New" | "PartiallyFilled" | "Untriggered" | "Triggered" => {
+                OrderStatus::Created
+            }
+            "Filled" => OrderStatus::Completed,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => OrderStatus::Canceled,
+            "Rejected" => OrderStatus::FailedToCreate,
+            _ => panic!("Bybit: unexpected order status {}", status),
+        }
+    }
+
+    pub(super) fn get_order_role(is_maker: bool) -> OrderRole {
+        match is_maker {
+            true => OrderRole::Maker,
+            false => OrderRole::Taker,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v5/execution/list");
+        builder.add_kv("category", CATEGORY);
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        builder.add_kv("execType", "Trade");
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("startTime", date_time.timestamp_millis());
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let executions: BybitResponse<BybitList<BybitExecution>> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        let fee_currency_code = self.get_settle_currency_code();
+        Ok(executions
+            .result
+            .list
+            .into_iter()
+            .filter(|execution| execution.exec_type == "Trade")
+            .map(|execution| OrderTrade {
+                exchange_order_id: execution.order_id,
+                trade_id: TradeId::from(execution.exec_id),
+                datetime: execution.exec_time,
+                price: execution.exec_price,
+                amount: execution.exec_qty,
+                side: execution.side,
+                order_role: Bybit::get_order_role(execution.is_maker),
+                fee_currency_code,
+                fee_rate: execution.fee_rate,
+                fee_amount: Some(execution.exec_fee),
+                fill_type: OrderFillType::UserTrade,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v5/account/wallet-balance");
+        builder.add_kv("accountType", "UNIFIED");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let wallets: BybitResponse<BybitList<BybitWallet>> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(wallets
+            .result
+            .list
+            .into_iter()
+            .flat_map(|wallet| wallet.coin)
+            .map(|coin| ExchangeBalance {
+                currency_code: self.currency_aliases.unify(coin.coin.as_str().into()),
+                balance: coin.wallet_balance,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v5/position/list");
+        builder.add_kv("category", CATEGORY);
+        builder.add_kv("settleCoin", SETTLE_COIN);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_position(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ActivePosition>> {
+        let positions: BybitResponse<BybitList<BybitPosition>> =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        positions
+            .result
+            .list
+            .into_iter()
+            .filter(|position| !position.size.is_zero())
+            .map(|position| {
+                let derivative_position = DerivativePosition {
+                    currency_pair: self
+                        .get_unified_currency_pair(&position.symbol.as_str().into())?,
+                    // Size is always positive, short position has "Sell" side
+                    position: match position.side.as_str() {
+                        "Sell" => -position.size,
+                        _ => position.size,
+                    },
+                    average_entry_price: position.avg_price.unwrap_or_default(),
+                    liquidation_price: position.liq_price.unwrap_or_default(),
+                    leverage: position.leverage.unwrap_or(Decimal::ONE),
+                };
+
+                Ok(ActivePosition::new(
+                    derivative_position,
+                    position.updated_time,
+                ))
+            })
+            .try_collect()
+    }
+
+    /// Position is closed by reduce-only order with opposite side
+    #[named]
+    pub(super) async fn request_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair =
+            self.get_specific_currency_pair(position.derivative.currency_pair);
+        let side = match position.derivative.position.is_sign_positive() {
+            true => OrderSide::Sell,
+            false => OrderSide::Buy,
+        };
+        let client_order_id = position.id.to_string();
+
+        let (order_type, time_in_force) = match price {
+            Some(_) => ("Limit", "GTC"),
+            None => ("Market", "IOC"),
+        };
+        let request = BybitPlaceOrderRequest {
+            category: CATEGORY,
+            symbol: specific_currency_pair.as_str(),
+            side,
+            order_type,
+            qty: position.derivative.position.abs(),
+            price,
+            time_in_force,
+            order_link_id: &client_order_id,
+            reduce_only: true,
+        };
+
+        let log_args = format!("Close position response for {position:?} {price:?}");
+        self.post_json("/v5/order/create", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn parse_close_position(
+        &self,
+        position: &ActivePosition,
+        response: &RestResponse,
+    ) -> Result<ClosedPosition> {
+        let exchange_order_id = self
+            .get_order_id(response)
+            .map_err(|err| anyhow!("Failed to get order id of closing position: {err:?}"))?;
+
+        Ok(ClosedPosition::new(
+            exchange_order_id,
+            position.derivative.position.abs(),
+        ))
+    }
+}
+
+pub struct BybitBuilder;
+
+impl ExchangeClientBuilder for BybitBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Bybit::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: true,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Bybit".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_signature() {
+        let signature = Bybit::create_signature(
+            "secret",
+            &[
+                b"1658384314791",
+                b"api_key",
+                RECV_WINDOW.as_bytes(),
+                b"category=linear&symbol=BTCUSDT",
+            ],
+        );
+
+        assert_eq!(
+            signature,
+            "57e921dd19ddd6c57f0ac2925294ad407fc0fe05aa55ba6131adc9d5eda66e32"
+        );
+    }
+}
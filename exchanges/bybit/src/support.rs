@@ -0,0 +1,423 @@
+use crate::bybit::Bybit;
+use crate::types::{BybitBookPayload, BybitExecution, BybitOrderEvent, BybitTradePayload};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{
+    FillAmount, FillEvent, SpecialOrderData,
+};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+// Bybit supports 1, 50, 200 and 500 levels of order book for linear contracts
+const ORDER_BOOK_DEPTH: u32 = 50;
+// Milliseconds during which websocket authentication request is valid
+const AUTH_EXPIRATION: u128 = 10_000;
+
+#[async_trait]
+impl Support for Bybit {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Topic(topic_message) => self.handle_topic_message(topic_message)?,
+            WebsocketMessage::OpResponse(response) => self.handle_op_response(response)?,
+            WebsocketMessage::Unknown(_) => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let topics = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .flat_map(|currency_pair| {
+                [
+                    format!("orderbook.{ORDER_BOOK_DEPTH}.{currency_pair}"),
+                    format!("publicTrade.{currency_pair}"),
+                ]
+            })
+            .collect::<Vec<_>>();
+        if !topics.is_empty() {
+            let subscribe = Request::to_message("subscribe", topics);
+            (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        // Private topics are subscribed after successful authentication
+        let expires = Bybit::get_unix_time_millis() + AUTH_EXPIRATION;
+        let signature = Bybit::create_signature(
+            &self.settings.secret_key,
+            &[b"GET/realtime", expires.to_string().as_bytes()],
+        );
+        let auth = Request::to_message("auth", (&self.settings.api_key, expires, signature));
+        (self.websocket_message_callback)(WebSocketRole::Main, auth)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""topic":"order""#) || message.contains(r#""topic":"execution""#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Bybit {
+    fn handle_op_response(&self, response: OpResponse) -> Result<()> {
+        match (response.op.as_str(), response.success) {
+            (_, Some(false)) => {
+                let err = format!(
+                    "Bybit websocket: failed {} request: {:?}",
+                    response.op, response.ret_msg
+                );
+                log::error!("{err}");
+                bail!(err)
+            }
+            ("auth", _) => {
+                let subscribe = Request::to_message("subscribe", ["order", "execution"]);
+                (self.websocket_message_callback)(WebSocketRole::Main, subscribe)
+            }
+            ("ping" | "pong", _) => Ok(()),
+            _ => {
+                log::info!("Bybit websocket: {} request succeeded", response.op);
+
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_topic_message(&self, message: TopicMessage) -> Result<()> {
+        let topic = message.topic.split('.').next().unwrap_or_default();
+        match topic {
+            "orderbook" => {
+                let event_type = match message.data_type.as_deref() {
+                    Some("snapshot") => EventType::Snapshot,
+                    _ => EventType::Update,
+                };
+                self.handle_order_book(serde_json::from_value(message.data)?, event_type)?;
+            }
+            "publicTrade" => {
+                for trade in serde_json::from_value::<Vec<BybitTradePayload>>(message.data)? {
+                    self.handle_trade(trade)?;
+                }
+            }
+            "order" => {
+                for order_event in serde_json::from_value::<Vec<BybitOrderEvent>>(message.data)? {
+                    self.handle_order_event(order_event);
+                }
+            }
+            "execution" => {
+                for execution in serde_json::from_value::<Vec<BybitExecution>>(message.data)? {
+                    self.handle_execution(execution)?;
+                }
+            }
+            _ => log::warn!("Bybit websocket: unexpected topic {}", message.topic),
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_book(&self, book: BybitBookPayload, event_type: EventType) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&book.symbol.as_str().into())?;
+
+        // Level with zero quantity should be removed from order book
+        let mut order_book_data = OrderBookData::default();
+        for (price, amount) in book.bids {
+            order_book_data.bids.insert(price, amount);
+        }
+        for (price, amount) in book.asks {
+            order_book_data.asks.insert(price, amount);
+        }
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: BybitTradePayload) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.symbol.as_str().into())?,
+            Trade {
+                trade_id: TradeId::from(trade.trade_id),
+                price: trade.price,
+                quantity: trade.amount,
+                side: trade.side,
+                transaction_time: trade.timestamp,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, order_event: BybitOrderEvent) {
+        // Orders created outside of the bot aren't tracked
+        let Some(client_order_id) = order_event.order_link_id else {
+            return;
+        };
+
+        match order_event.order_status.as_str() {
+            "New" => (self.order_created_callback)(
+                client_order_id,
+                order_event.order_id,
+                EventSourceType::WebSocket,
+            ),
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => (self
+                .order_cancelled_callback)(
+                client_order_id,
+                order_event.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are handled from execution topic
+            _ => (),
+        }
+    }
+
+    fn handle_execution(&self, execution: BybitExecution) -> Result<()> {
+        if execution.exec_type != "Trade" {
+            // Funding and other executions don't fill orders
+            return Ok(());
+        }
+
+        let total_filled_amount = execution
+            .leaves_qty
+            .map(|leaves_qty| execution.order_qty - leaves_qty);
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(execution.exec_id)),
+            client_order_id: execution.order_link_id,
+            exchange_order_id: execution.order_id,
+            fill_price: execution.exec_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: execution.exec_qty,
+                total_filled_amount,
+            },
+            order_role: Some(Bybit::get_order_role(execution.is_maker)),
+            commission_currency_code: Some(self.get_settle_currency_code()),
+            commission_rate: execution.fee_rate,
+            commission_amount: Some(execution.exec_fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: Some(SpecialOrderData {
+                currency_pair: self.get_unified_currency_pair(&execution.symbol.as_str().into())?,
+                order_side: execution.side,
+                order_amount: execution.order_qty,
+            }),
+            fill_date: Some(execution.exec_time),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    Topic(TopicMessage),
+    OpResponse(OpResponse),
+    Unknown(Value),
+}
+
+/// Response on operation sent via websocket, e.g. auth, subscription or ping
+#[derive(Deserialize, Debug)]
+struct OpResponse {
+    op: String,
+    success: Option<bool>,
+    ret_msg: Option<String>,
+}
+
+/// Data pushed by subscribed topic
+#[derive(Deserialize, Debug)]
+struct TopicMessage {
+    topic: String,
+    #[serde(rename = "type")]
+    data_type: Option<String>,
+    data: Value,
+}
+
+#[derive(Serialize)]
+struct Request<T> {
+    op: &'static str,
+    args: T,
+}
+
+impl<T: Serialize> Request<T> {
+    fn to_message(op: &'static str, args: T) -> String {
+        serde_json::to_string(&Request { op, args })
+            .expect("Failed to serialize Bybit websocket message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_auth_request() {
+        let message = Request::to_message("auth", ("api_key", 1662350400000u128, "signature"));
+
+        assert_eq!(
+            message,
+            r#"{"op":"auth","args":["api_key",1662350400000,"signature"]}"#
+        );
+    }
+
+    #[test]
+    fn parse_execution() {
+        let msg = r#"{"id":"592324803b2785-26fa-4214-9963-bdd4727f07be","topic":"execution","creationTime":1672364174455,"data":[{"category":"linear","symbol":"BTCUSDT","execFee":"0.005061","execId":"7e2ae69c-4edf-5800-a352-893d52b446aa","execPrice":"0.3374","execQty":"25","execType":"Trade","execValue":"8.435","isMaker":false,"feeRate":"0.0006","tradeIv":"","markIv":"","blockTradeId":"","markPrice":"0.3391","indexPrice":"","underlyingPrice":"","leavesQty":"0","orderId":"f6e324ff-99c2-4e89-9739-3086e47f9381","orderLinkId":"1234567","orderPrice":"0.3207","orderQty":"25","orderType":"Market","stopOrderType":"UNKNOWN","side":"Sell","execTime":"1672364174443","isLeverage":"0","closedSize":""}]}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Topic(topic_message) = message else {
+            panic!("Unexpected message {message:?}");
+        };
+        assert_eq!(topic_message.topic, "execution");
+
+        let executions: Vec<BybitExecution> =
+            serde_json::from_value(topic_message.data).expect("in test");
+        let execution = &executions[0];
+        assert_eq!(
+            execution.order_id.as_str(),
+            "f6e324ff-99c2-4e89-9739-3086e47f9381"
+        );
+        assert_eq!(execution.exec_qty, dec!(25));
+        assert_eq!(execution.leaves_qty, Some(dec!(0)));
+        assert!(!execution.is_maker);
+    }
+
+    #[test]
+    fn parse_service_messages() {
+        let auth: WebsocketMessage = serde_json::from_str(
+            r#"{"success":true,"ret_msg":"","op":"auth","conn_id":"cejreaspqfh3sjdnldmg-p"}"#,
+        )
+        .expect("in test");
+        assert!(matches!(
+            auth,
+            WebsocketMessage::OpResponse(OpResponse {
+                success: Some(true),
+                ..
+            })
+        ));
+
+        let book: WebsocketMessage = serde_json::from_str(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":1672304484978,"data":{"s":"BTCUSDT","b":[["16493.50","0.006"]],"a":[["16611.00","0"]],"u":18521288,"seq":7961638724},"cts":1672304484976}"#,
+        )
+        .expect("in test");
+        let WebsocketMessage::Topic(topic_message) = book else {
+            panic!("Unexpected message {book:?}");
+        };
+        let book: BybitBookPayload = serde_json::from_value(topic_message.data).expect("in test");
+        assert_eq!(book.bids[0], (dec!(16493.50), dec!(0.006)));
+        assert!(book.asks[0].1.is_zero());
+    }
+}
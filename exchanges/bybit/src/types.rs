@@ -0,0 +1,281 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// Common envelope of Bybit v5 REST responses. Errors are checked in `ErrorHandlerBybit`
+/// so only the result part is needed here
+#[derive(Deserialize, Debug)]
+pub(crate) struct BybitResponse<T> {
+    pub(crate) result: T,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct BybitList<T> {
+    pub(crate) list: Vec<T>,
+}
+
+/// Instrument description from `/v5/market/instruments-info`
+/// {
+///   "symbol": "BTCUSDT",
+///   "contractType": "LinearPerpetual",
+///   "status": "Trading",
+///   "baseCoin": "BTC",
+///   "quoteCoin": "USDT",
+///   "settleCoin": "USDT",
+///   "priceFilter": { "minPrice": "0.10", "maxPrice": "199999.80", "tickSize": "0.10" },
+///   "lotSizeFilter": { "maxOrderQty": "100.000", "minOrderQty": "0.001", "qtyStep": "0.001" }
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitInstrument {
+    pub(crate) symbol: String,
+    pub(crate) contract_type: String,
+    pub(crate) status: String,
+    pub(crate) base_coin: String,
+    pub(crate) quote_coin: String,
+    pub(crate) settle_coin: String,
+    pub(crate) price_filter: BybitPriceFilter,
+    pub(crate) lot_size_filter: BybitLotSizeFilter,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitPriceFilter {
+    pub(crate) min_price: Price,
+    pub(crate) max_price: Price,
+    pub(crate) tick_size: Price,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitLotSizeFilter {
+    pub(crate) min_order_qty: Amount,
+    pub(crate) max_order_qty: Amount,
+    pub(crate) qty_step: Amount,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitPlaceOrderRequest<'a> {
+    pub(crate) category: &'static str,
+    pub(crate) symbol: &'a str,
+    #[serde(serialize_with = "serialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) order_type: &'static str,
+    pub(crate) qty: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) price: Option<Price>,
+    pub(crate) time_in_force: &'static str,
+    pub(crate) order_link_id: &'a str,
+    pub(crate) reduce_only: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitCancelOrderRequest<'a> {
+    pub(crate) category: &'static str,
+    pub(crate) symbol: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) order_id: Option<&'a str>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitOrderId {
+    pub(crate) order_id: ExchangeOrderId,
+}
+
+/// Order from `/v5/order/realtime`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitOrderInfo {
+    pub(crate) symbol: String,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) order_link_id: ClientOrderId,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) order_status: String,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) price: Option<Price>,
+    pub(crate) qty: Amount,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) avg_price: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) cum_exec_qty: Option<Amount>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) cum_exec_fee: Option<Amount>,
+}
+
+/// Execution from `/v5/execution/list` and private websocket `execution` topic
+/// {
+///   "symbol": "BTCUSDT",
+///   "orderId": "fd4300ae-7847-404e-b947-b46980a4d140",
+///   "orderLinkId": "1234567",
+///   "side": "Sell",
+///   "orderQty": "0.002",
+///   "leavesQty": "0.001",
+///   "execFee": "0.0123",
+///   "execId": "e0cbe81d-0f18-5866-9415-cf319b5dab3b",
+///   "execPrice": "26000",
+///   "execQty": "0.001",
+///   "execType": "Trade",
+///   "execTime": "1672364174443",
+///   "isMaker": false
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitExecution {
+    pub(crate) symbol: String,
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(deserialize_with = "deserialize_optional_client_order_id")]
+    pub(crate) order_link_id: Option<ClientOrderId>,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) order_qty: Amount,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) leaves_qty: Option<Amount>,
+    /// Positive fee means charged commission, negative fee means rebate
+    pub(crate) exec_fee: Amount,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) fee_rate: Option<Decimal>,
+    pub(crate) exec_id: String,
+    pub(crate) exec_price: Price,
+    pub(crate) exec_qty: Amount,
+    /// "Trade" for trades, other types (e.g. "Funding") don't fill orders
+    pub(crate) exec_type: String,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) exec_time: DateTime,
+    pub(crate) is_maker: bool,
+}
+
+/// Wallet balance from `/v5/account/wallet-balance`
+#[derive(Deserialize, Debug)]
+pub(crate) struct BybitWallet {
+    pub(crate) coin: Vec<BybitCoinBalance>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitCoinBalance {
+    pub(crate) coin: String,
+    pub(crate) wallet_balance: Decimal,
+}
+
+/// Position from `/v5/position/list` in one-way mode, side is empty if there is no position
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitPosition {
+    pub(crate) symbol: String,
+    pub(crate) side: String,
+    pub(crate) size: Amount,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) avg_price: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) liq_price: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) leverage: Option<Decimal>,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) updated_time: DateTime,
+}
+
+/// Order of private websocket `order` topic
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BybitOrderEvent {
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(deserialize_with = "deserialize_optional_client_order_id")]
+    pub(crate) order_link_id: Option<ClientOrderId>,
+    pub(crate) order_status: String,
+}
+
+/// Order book data of public websocket `orderbook.50` topic
+#[derive(Deserialize, Debug)]
+pub(crate) struct BybitBookPayload {
+    #[serde(rename = "s")]
+    pub(crate) symbol: String,
+    #[serde(rename = "b")]
+    pub(crate) bids: Vec<(Price, Amount)>,
+    #[serde(rename = "a")]
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
+/// Trade of public websocket `publicTrade` topic
+#[derive(Deserialize, Debug)]
+pub(crate) struct BybitTradePayload {
+    #[serde(rename = "s")]
+    pub(crate) symbol: String,
+    #[serde(rename = "i")]
+    pub(crate) trade_id: String,
+    #[serde(rename = "p")]
+    pub(crate) price: Price,
+    #[serde(rename = "v")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "S", deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    #[serde(rename = "T", with = "chrono::serde::ts_milliseconds")]
+    pub(crate) timestamp: DateTime,
+}
+
+fn serialize_side<S>(side: &OrderSide, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(match side {
+        OrderSide::Buy => "Buy",
+        OrderSide::Sell => "Sell",
+    })
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "Buy" => Ok(OrderSide::Buy),
+        "Sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown Bybit order side: {side}"
+        ))),
+    }
+}
+
+/// Bybit returns empty string instead of null for missing numeric values
+fn deserialize_optional_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(None),
+        false => value.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+/// Orders created outside of the bot have empty `orderLinkId`
+fn deserialize_optional_client_order_id<'de, D>(
+    deserializer: D,
+) -> Result<Option<ClientOrderId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(value.as_str().into())),
+    }
+}
+
+/// Bybit returns time as unix timestamp in milliseconds in string
+fn deserialize_millis<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    let millis: i64 = value.parse().map_err(de::Error::custom)?;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| de::Error::custom(format!("Invalid Bybit time: {value}")))
+}
@@ -0,0 +1,810 @@
+use crate::types::{
+    CryptoComData, CryptoComInstrument, CryptoComOrder, CryptoComRequest, CryptoComResponse,
+    CryptoComTrade, CryptoComUserBalance,
+};
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, UserOrder,
+};
+use mmb_utils::time::{get_current_milliseconds, u64_to_date_time};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Prefix of paths of all REST methods, e.g. `/exchange/v1/private/create-order`
+const API_PATH: &str = "/exchange/v1/";
+const SPOT_INSTRUMENT_TYPE: &str = "CCY_PAIR";
+/// Maximum count of trades returned by `private/get-trades`
+const MY_TRADES_LIMIT: u32 = 100;
+
+#[derive(Default)]
+pub struct ErrorHandlerCryptoCom;
+
+impl ErrorHandler for ErrorHandlerCryptoCom {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct CryptoComError {
+            code: i64,
+            #[serde(default)]
+            message: String,
+        }
+
+        // Successful responses contain `"code":0` too, so error is determined by its value
+        let error: CryptoComError = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse Crypto.com response: {err:?}"))
+        })?;
+
+        if error.code == 0 {
+            return Ok(());
+        }
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            error.message,
+            Some(error.code),
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        use ExchangeErrorType::*;
+        // Details: https://exchange-docs.crypto.com/exchange/v1/rest-ws/index.html#response-and-reason-codes
+        match error.code {
+            // 212 is invalid order id, 316 is order which isn't open anymore
+            Some(212 | 316) => OrderNotFound,
+            // 306 is insufficient available balance, 20002 is negative balance after order
+            Some(306 | 20002) => InsufficientFunds,
+            // 308 and 309 are invalid price and quantity, 415 is rejected post only order,
+            // 30003..=30025 are violated limits and precisions of instrument
+            Some(308 | 309 | 415 | 30003..=30025) => InvalidOrder,
+            Some(10006) => RateLimit,
+            // 10002 is unauthorized, 10003 is IP not in whitelist, 10007 is invalid nonce
+            Some(10002 | 10003 | 10007) => Authentication,
+            Some(10001 | 50001) => ServiceUnavailable,
+            _ => Unknown,
+        }
+    }
+}
+
+/// Private requests are signed in body, so only content type should be specified
+#[derive(Default)]
+pub struct RestHeadersCryptoCom;
+
+impl RestHeaders for RestHeadersCryptoCom {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        builder.header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct CryptoCom {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerCryptoCom, RestHeadersCryptoCom>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl CryptoCom {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> CryptoCom {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerCryptoCom::default(),
+                ),
+                RestHeadersCryptoCom::default(),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://stream.crypto.com/exchange/v1/market",
+            web_socket2_host: "wss://stream.crypto.com/exchange/v1/user",
+            rest_host: "https://api.crypto.com",
+        }
+    }
+
+    /// Parameters are concatenated as keys and values sorted by keys without separators,
+    /// values of lists are concatenated one by one
+    fn params_to_string(params: &Value) -> String {
+        match params {
+            Value::Null => String::new(),
+            Value::String(value) => value.clone(),
+            Value::Array(items) => items.iter().map(Self::params_to_string).join(""),
+            Value::Object(map) => map
+                .iter()
+                .sorted_by(|(left, _), (right, _)| left.cmp(right))
+                .map(|(key, value)| format!("{key}{}", Self::params_to_string(value)))
+                .join(""),
+            value => value.to_string(),
+        }
+    }
+
+    /// Hex encoded HMAC-SHA256 of method, id, api key, parameters and nonce
+    fn create_signature(&self, method: &str, id: i64, params: &Value, nonce: i64) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(self.settings.secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Crypto.com signature");
+        hmac.update(method.as_bytes());
+        hmac.update(id.to_string().as_bytes());
+        hmac.update(self.settings.api_key.as_bytes());
+        hmac.update(Self::params_to_string(params).as_bytes());
+        hmac.update(nonce.to_string().as_bytes());
+
+        format!("{:x}", hmac.finalize().into_bytes())
+    }
+
+    /// Signed request used by private REST methods and websocket authentication
+    pub(super) fn create_signed_request<'a>(
+        &'a self,
+        method: &'a str,
+        params: Value,
+    ) -> CryptoComRequest<'a> {
+        let nonce = get_current_milliseconds();
+        // Ids are only echoed in responses, so nonce is unique enough for them
+        let id = nonce;
+
+        CryptoComRequest {
+            id,
+            method,
+            api_key: &self.settings.api_key,
+            sig: self.create_signature(method, id, &params, nonce),
+            params,
+            nonce,
+        }
+    }
+
+    async fn post_private(
+        &self,
+        method: &'static str,
+        params: Value,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let request = self.create_signed_request(method, params);
+        let body = serde_json::to_vec(&request).map_err(|err| {
+            ExchangeError::parsing(format!(
+                "Unable to serialize Crypto.com request body: {err:?}"
+            ))
+        })?;
+        let uri = UriBuilder::from_path(&format!("{API_PATH}{method}"))
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path(&format!("{API_PATH}public/get-instruments"))
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let response: CryptoComResponse<CryptoComData<CryptoComInstrument>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize response from Crypto.com")?;
+
+        Ok(response
+            .result
+            .data
+            .iter()
+            .filter(|instrument| {
+                instrument.tradable && instrument.inst_type == SPOT_INSTRUMENT_TYPE
+            })
+            .map(|instrument| self.parse_symbol(instrument))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, instrument: &CryptoComInstrument) -> Arc<Symbol> {
+        let base_id = instrument.base_ccy.as_str();
+        let quote_id = instrument.quote_ccy.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = instrument.symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Arc::new(Symbol::new(
+            false,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            None,
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick {
+                tick: instrument.price_tick_size,
+            },
+            Precision::ByTick {
+                tick: instrument.qty_tick_size,
+            },
+        ))
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        // Numbers are sent as strings to avoid loss of precision
+        let mut params = json!({
+            "instrument_name": specific_currency_pair.as_str(),
+            "side": Self::get_server_order_side(header.side),
+            "quantity": header.amount.to_string(),
+            "client_oid": header.client_order_id.as_str(),
+        });
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                params["type"] = json!("LIMIT");
+                params["price"] = json!(price.to_string());
+                if execution_type == OrderExecutionType::MakerOnly {
+                    params["exec_inst"] = json!(["POST_ONLY"]);
+                }
+            }
+            OrderOptions::User(UserOrder::Market) => params["type"] = json!("MARKET"),
+            // Stop orders of Crypto.com are created by separate advanced order methods
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_private("private/create-order", params, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        struct OrderId {
+            order_id: ExchangeOrderId,
+        }
+
+        let deserialized: CryptoComResponse<OrderId> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order_id: {err:?}")))?;
+
+        Ok(deserialized.result.order_id)
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_private(
+            "private/cancel-order",
+            json!({ "order_id": exchange_order_id.as_str() }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.post_private(
+            "private/cancel-all-orders",
+            json!({ "instrument_name": specific_currency_pair.as_str() }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let params = match currency_pair {
+            Some(currency_pair) => {
+                let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+                json!({ "instrument_name": specific_currency_pair.as_str() })
+            }
+            None => json!({}),
+        };
+
+        let log_args = format!("Open orders for {currency_pair:?}");
+        self.post_private(
+            "private/get-open-orders",
+            params,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let response: CryptoComResponse<CryptoComData<CryptoComOrder>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_open_orders request")?;
+
+        response
+            .result
+            .data
+            .iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let log_args = format!("order {client_order_id}");
+        self.post_private(
+            "private/get-order-detail",
+            json!({ "client_oid": client_order_id.as_str() }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let response: CryptoComResponse<CryptoComOrder> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_order_info request")?;
+
+        self.specific_order_info_to_unified(&response.result)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: &CryptoComOrder) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.instrument_name.as_str().into())?,
+            specific.order_id.clone(),
+            ClientOrderId::from(specific.client_oid.as_str()),
+            Self::get_local_order_side(&specific.side)?,
+            Self::get_local_order_status(&specific.status)?,
+            specific.limit_price.unwrap_or_default(),
+            specific.quantity,
+            specific.avg_price,
+            specific.cumulative_quantity,
+            specific.fee_instrument_name.as_ref().map(|fee_currency| {
+                self.currency_aliases
+                    .unify(fee_currency.as_str().into())
+                    .to_string()
+            }),
+            None,
+            Some(specific.cumulative_fee),
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    fn get_server_order_side(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        }
+    }
+
+    pub(super) fn get_local_order_side(side: &str) -> Result<OrderSide> {
+        match side {
+            "BUY" => Ok(OrderSide::Buy),
+            "SELL" => Ok(OrderSide::Sell),
+            _ => bail!("Unknown Crypto.com order side {side}"),
+        }
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> Result<OrderStatus> {
+        match status {
+            "NEW" | "PENDING" | "ACTIVE" => Ok(OrderStatus::Created),
+            "FILLED" => Ok(OrderStatus::Completed),
+            "CANCELED" | "REJECTED" | "EXPIRED" => Ok(OrderStatus::Canceled),
+            _ => bail!("Unknown Crypto.com order status {status}"),
+        }
+    }
+
+    pub(super) fn get_order_role(taker_side: &str) -> OrderRole {
+        match taker_side {
+            "MAKER" => OrderRole::Maker,
+            _ => OrderRole::Taker,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
+
+        let mut params = json!({
+            "instrument_name": specific_currency_pair.as_str(),
+            "limit": MY_TRADES_LIMIT,
+        });
+        if let Some(date_time) = last_date_time {
+            params["start_time"] = json!(date_time.timestamp_millis());
+        }
+
+        self.post_private(
+            "private/get-trades",
+            params,
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let response: CryptoComResponse<CryptoComData<CryptoComTrade>> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        response
+            .result
+            .data
+            .into_iter()
+            .map(|trade| {
+                Ok(OrderTrade {
+                    exchange_order_id: trade.order_id,
+                    trade_id: TradeId::from(trade.trade_id),
+                    datetime: u64_to_date_time(trade.create_time),
+                    price: trade.traded_price,
+                    amount: trade.traded_quantity,
+                    side: Self::get_local_order_side(&trade.side)?,
+                    order_role: Self::get_order_role(&trade.taker_side),
+                    fee_currency_code: self
+                        .currency_aliases
+                        .unify(trade.fee_instrument_name.as_str().into()),
+                    fee_rate: None,
+                    // Fees are returned as negative values
+                    fee_amount: Some(-trade.fees),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        self.post_private(
+            "private/user-balance",
+            json!({}),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let response: CryptoComResponse<CryptoComData<CryptoComUserBalance>> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(response
+            .result
+            .data
+            .into_iter()
+            .flat_map(|user_balance| user_balance.position_balances)
+            .map(|balance| ExchangeBalance {
+                currency_code: self
+                    .currency_aliases
+                    .unify(balance.instrument_name.as_str().into()),
+                balance: balance.quantity,
+            })
+            .collect_vec())
+    }
+}
+
+pub struct CryptoComBuilder;
+
+impl ExchangeClientBuilder for CryptoComBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(CryptoCom::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Crypto.com limits are set per method: 15 requests per 100ms for creation and
+        // cancellation of orders and 3 requests per 100ms for other private methods,
+        // so the strictest of them is used for all requests
+        RequestTimeoutArguments::from_requests_per_second(30)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "CryptoCom".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    fn create_crypto_com() -> CryptoCom {
+        let exchange_account_id: ExchangeAccountId = "CryptoCom_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let crypto_com = CryptoCom::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let instruments = RestResponse::new(
+            r#"{"id":1,"method":"public/get-instruments","code":0,"result":{"data":[
+                {"symbol":"BTC_USDT","inst_type":"CCY_PAIR","display_name":"BTC/USDT","base_ccy":"BTC","quote_ccy":"USDT","quote_decimals":2,"quantity_decimals":5,"price_tick_size":"0.01","qty_tick_size":"0.00001","max_leverage":"50","tradable":true,"expiry_timestamp_ms":0},
+                {"symbol":"BTCUSD-PERP","inst_type":"PERPETUAL_SWAP","display_name":"BTCUSD Perpetual","base_ccy":"BTC","quote_ccy":"USD","quote_decimals":1,"quantity_decimals":4,"price_tick_size":"0.1","qty_tick_size":"0.0001","max_leverage":"100","tradable":true,"expiry_timestamp_ms":0},
+                {"symbol":"OLD_USDT","inst_type":"CCY_PAIR","display_name":"OLD/USDT","base_ccy":"OLD","quote_ccy":"USDT","quote_decimals":4,"quantity_decimals":2,"price_tick_size":"0.0001","qty_tick_size":"0.01","max_leverage":"1","tradable":false,"expiry_timestamp_ms":0}
+            ]}}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        crypto_com.parse_all_symbols(&instruments).expect("in test");
+
+        crypto_com
+    }
+
+    #[test]
+    fn parse_symbols() {
+        let crypto_com = create_crypto_com();
+
+        assert!(crypto_com
+            .get_unified_currency_pair(&"BTC_USDT".into())
+            .is_ok());
+        assert!(crypto_com
+            .get_unified_currency_pair(&"BTCUSD-PERP".into())
+            .is_err());
+        assert!(crypto_com
+            .get_unified_currency_pair(&"OLD_USDT".into())
+            .is_err());
+    }
+
+    #[test]
+    fn params_to_string() {
+        let params = json!({
+            "side": "BUY",
+            "instrument_name": "BTC_USDT",
+            "exec_inst": ["POST_ONLY"],
+            "limit": 100,
+        });
+
+        assert_eq!(
+            CryptoCom::params_to_string(&params),
+            "exec_instPOST_ONLYinstrument_nameBTC_USDTlimit100sideBUY"
+        );
+        assert_eq!(CryptoCom::params_to_string(&Value::Null), "");
+    }
+
+    #[test]
+    fn parse_open_orders() {
+        let crypto_com = create_crypto_com();
+        let response = RestResponse::new(
+            r#"{"id":1,"method":"private/get-open-orders","code":0,"result":{"data":[{"account_id":"52e7c00f-1324-5a6z-bfgt-de445bde21a5","order_id":"19848525","client_oid":"1613571154900","order_type":"LIMIT","time_in_force":"GOOD_TILL_CANCEL","side":"BUY","exec_inst":["POST_ONLY"],"quantity":"0.0100","limit_price":"50000.0","order_value":"500.000000","maker_fee_rate":"0.000250","taker_fee_rate":"0.000400","avg_price":"49800.0","cumulative_quantity":"0.0040","cumulative_value":"199.200000","cumulative_fee":"0.00000400","status":"ACTIVE","order_date":"2021-02-17","instrument_name":"BTC_USDT","fee_instrument_name":"BTC","create_time":1613575617173,"create_time_ns":"1613575617173123456","update_time":1613575617173}]}}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let orders = crypto_com.parse_open_orders(&response).expect("in test");
+
+        assert_eq!(orders.len(), 1);
+        let order = &orders[0];
+        assert_eq!(order.exchange_order_id.as_str(), "19848525");
+        assert_eq!(order.client_order_id.as_str(), "1613571154900");
+        assert_eq!(order.order_side, OrderSide::Buy);
+        assert_eq!(order.order_status, OrderStatus::Created);
+        assert_eq!(order.price, dec!(50000));
+        assert_eq!(order.filled_amount, dec!(0.004));
+        assert_eq!(order.commission_amount, Some(dec!(0.000004)));
+    }
+
+    #[test]
+    fn parse_negative_trade_fee() {
+        let crypto_com = create_crypto_com();
+        let response = RestResponse::new(
+            r#"{"id":1,"method":"private/get-trades","code":0,"result":{"data":[{"account_id":"52e7c00f-1324-5a6z-bfgt-de445bde21a5","event_date":"2021-02-17","journal_type":"TRADING","side":"SELL","instrument_name":"BTC_USDT","fees":"-0.013620","trade_id":"28457","trade_match_id":"976523","create_time":1613640738192,"traded_price":"50000.00","traded_quantity":"0.0020","fee_instrument_name":"USDT","client_oid":"1613640737632","taker_side":"MAKER","order_id":"19848524","create_time_ns":"1613640738192123456"}]}}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let trades = crypto_com.parse_my_trades(&response).expect("in test");
+
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!(trade.side, OrderSide::Sell);
+        assert_eq!(trade.order_role, OrderRole::Maker);
+        assert_eq!(trade.fee_amount, Some(dec!(0.013620)));
+    }
+
+    #[test]
+    fn clarify_error_type_by_code() {
+        let response = RestResponse::new(
+            r#"{"id":1,"method":"private/create-order","code":306,"message":"INSUFFICIENT_AVAILABLE_BALANCE"}"#.to_owned(),
+            StatusCode::BAD_REQUEST,
+        );
+
+        let error = ErrorHandlerCryptoCom
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(error.code, Some(306));
+        assert_eq!(
+            ErrorHandlerCryptoCom.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+    }
+}
@@ -0,0 +1,452 @@
+use crate::crypto_com::CryptoCom;
+use crate::types::{CryptoComBook, CryptoComOrder, CryptoComPublicTrade, CryptoComTrade};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::ClientOrderId;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::time::{get_current_milliseconds, u64_to_date_time};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+const BOOK_CHANNEL: &str = "book";
+const TRADE_CHANNEL: &str = "trade";
+const USER_ORDER_CHANNEL: &str = "user.order";
+const USER_TRADE_CHANNEL: &str = "user.trade";
+const SUBSCRIBE_METHOD: &str = "subscribe";
+const AUTH_METHOD: &str = "public/auth";
+const HEARTBEAT_METHOD: &str = "public/heartbeat";
+const RESPOND_HEARTBEAT_METHOD: &str = "public/respond-heartbeat";
+/// Allowed values are 10 and 50
+const ORDER_BOOK_LEVELS: u8 = 10;
+
+#[async_trait]
+impl Support for CryptoCom {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        if message.code != 0 {
+            let err = format!(
+                "Crypto.com websocket: error on {} {} {}",
+                message.method, message.code, message.message
+            );
+            log::error!("{err}");
+            bail!(err)
+        }
+
+        match message.method.as_str() {
+            HEARTBEAT_METHOD => self.respond_heartbeat(message.id)?,
+            AUTH_METHOD => self.subscribe_private_channels()?,
+            SUBSCRIBE_METHOD => match message.result {
+                Some(result) => self.handle_subscription_data(result)?,
+                // Confirmation of subscription
+                None => {}
+            },
+            _ => self.log_unknown_message(self.settings.exchange_account_id, msg),
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let channels = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .flat_map(|currency_pair| {
+                [
+                    format!("{BOOK_CHANNEL}.{currency_pair}.{ORDER_BOOK_LEVELS}"),
+                    format!("{TRADE_CHANNEL}.{currency_pair}"),
+                ]
+            })
+            .collect::<Vec<_>>();
+        if !channels.is_empty() {
+            // Snapshots of limited levels are requested instead of default snapshots and deltas
+            let params = json!({ "channels": channels, "book_subscription_type": "SNAPSHOT" });
+            let subscribe = Request::new(SUBSCRIBE_METHOD, params).to_message();
+            (self.websocket_message_callback)(WebSocketRole::Main, subscribe)?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Secondary) {
+            return Ok(());
+        }
+
+        // Private channels are subscribed after response on authentication
+        let auth = self.create_signed_request(AUTH_METHOD, Value::Null);
+        let auth = serde_json::to_string(&auth).context("Unable to serialize auth request")?;
+        (self.websocket_message_callback)(WebSocketRole::Secondary, auth)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(USER_ORDER_CHANNEL) || message.contains(USER_TRADE_CHANNEL)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl CryptoCom {
+    /// Crypto.com sends heartbeat to every connection and closes it if there is no response
+    /// with the same id. Role of connection isn't known here, so response is sent to all of them
+    fn respond_heartbeat(&self, id: i64) -> Result<()> {
+        let response = Request {
+            id,
+            method: RESPOND_HEARTBEAT_METHOD,
+            params: Value::Null,
+            nonce: None,
+        }
+        .to_message();
+
+        for role in [WebSocketRole::Main, WebSocketRole::Secondary] {
+            if self.is_websocket_enabled(role) {
+                (self.websocket_message_callback)(role, response.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn subscribe_private_channels(&self) -> Result<()> {
+        let params = json!({ "channels": [USER_ORDER_CHANNEL, USER_TRADE_CHANNEL] });
+        let subscribe = Request::new(SUBSCRIBE_METHOD, params).to_message();
+        (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)
+    }
+
+    fn handle_subscription_data(&self, result: SubscriptionResult) -> Result<()> {
+        match result.channel.as_str() {
+            BOOK_CHANNEL => {
+                for book in serde_json::from_value::<Vec<CryptoComBook>>(result.data)? {
+                    self.handle_book(&result.instrument_name, book)?;
+                }
+            }
+            TRADE_CHANNEL => {
+                for trade in serde_json::from_value::<Vec<CryptoComPublicTrade>>(result.data)? {
+                    self.handle_trade(&result.instrument_name, trade)?;
+                }
+            }
+            USER_ORDER_CHANNEL => {
+                for order in serde_json::from_value::<Vec<CryptoComOrder>>(result.data)? {
+                    self.handle_order_event(order);
+                }
+            }
+            USER_TRADE_CHANNEL => {
+                for trade in serde_json::from_value::<Vec<CryptoComTrade>>(result.data)? {
+                    self.handle_user_trade(trade)?;
+                }
+            }
+            _ => log::warn!("Unknown Crypto.com websocket channel {}", result.channel),
+        }
+
+        Ok(())
+    }
+
+    fn handle_book(&self, instrument_name: &str, book: CryptoComBook) -> Result<()> {
+        let mut order_book_data = OrderBookData::default();
+        for (price, amount, _) in book.bids {
+            order_book_data.bids.insert(price, amount);
+        }
+        for (price, amount, _) in book.asks {
+            order_book_data.asks.insert(price, amount);
+        }
+
+        // Book is subscribed in snapshot mode, so every message contains all limited levels
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            self.get_unified_currency_pair(&instrument_name.into())?,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, instrument_name: &str, trade: CryptoComPublicTrade) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&instrument_name.into())?,
+            Trade {
+                trade_id: TradeId::from(trade.trade_id),
+                price: trade.price,
+                quantity: trade.amount,
+                side: CryptoCom::get_local_order_side(&trade.side)?,
+                transaction_time: u64_to_date_time(trade.time),
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, order: CryptoComOrder) {
+        if order.client_oid.is_empty() {
+            // Order was created outside of the bot
+            return;
+        }
+        let client_order_id = ClientOrderId::from(order.client_oid.as_str());
+
+        match order.status.as_str() {
+            "ACTIVE" => (self.order_created_callback)(
+                client_order_id,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Post only orders which would be filled immediately are rejected
+            "CANCELED" | "REJECTED" | "EXPIRED" => (self.order_cancelled_callback)(
+                client_order_id,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are received from user.trade channel
+            _ => {}
+        }
+    }
+
+    fn handle_user_trade(&self, trade: CryptoComTrade) -> Result<()> {
+        if trade.client_oid.is_empty() {
+            // Order was created outside of the bot
+            return Ok(());
+        }
+
+        // Check that currency pair is known before passing fill to engine
+        self.get_unified_currency_pair(&trade.instrument_name.as_str().into())?;
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(trade.trade_id)),
+            client_order_id: Some(trade.client_oid.as_str().into()),
+            exchange_order_id: trade.order_id,
+            fill_price: trade.traded_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.traded_quantity,
+                total_filled_amount: None,
+            },
+            order_role: Some(CryptoCom::get_order_role(&trade.taker_side)),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(trade.fee_instrument_name.as_str().into()),
+            ),
+            commission_rate: None,
+            // Fees are sent as negative values
+            commission_amount: Some(-trade.fees),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(u64_to_date_time(trade.create_time)),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+/// Responses, heartbeats and subscription data have the same envelope
+#[derive(Deserialize, Debug)]
+struct WebsocketMessage {
+    #[serde(default)]
+    id: i64,
+    method: String,
+    #[serde(default)]
+    code: i64,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    result: Option<SubscriptionResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SubscriptionResult {
+    channel: String,
+    /// Empty for private channels
+    #[serde(default)]
+    instrument_name: String,
+    #[serde(default)]
+    data: Value,
+}
+
+#[derive(Serialize)]
+struct Request {
+    id: i64,
+    method: &'static str,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    params: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<i64>,
+}
+
+impl Request {
+    fn new(method: &'static str, params: Value) -> Self {
+        let nonce = get_current_milliseconds();
+        Request {
+            id: nonce,
+            method,
+            params,
+            nonce: Some(nonce),
+        }
+    }
+
+    fn to_message(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize Crypto.com websocket message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_heartbeat_response() {
+        let response = Request {
+            id: 1587523073344,
+            method: RESPOND_HEARTBEAT_METHOD,
+            params: Value::Null,
+            nonce: None,
+        };
+
+        assert_eq!(
+            response.to_message(),
+            r#"{"id":1587523073344,"method":"public/respond-heartbeat"}"#
+        );
+    }
+
+    #[test]
+    fn parse_public_messages() {
+        let book: WebsocketMessage = serde_json::from_str(
+            r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"book.BTC_USDT.10","channel":"book","depth":10,"data":[{"asks":[["30082.5","0.1689","1"]],"bids":[["30079.4","0.0015","1"],["30079.3","1.2","3"]],"t":1654780033786,"tt":1654780033755,"u":542048017824}]}}"#,
+        )
+        .expect("in test");
+        let result = book.result.expect("in test");
+        assert_eq!(result.channel, BOOK_CHANNEL);
+        assert_eq!(result.instrument_name, "BTC_USDT");
+        let books: Vec<CryptoComBook> = serde_json::from_value(result.data).expect("in test");
+        assert_eq!(books[0].asks[0].0, dec!(30082.5));
+        assert_eq!(books[0].bids[1].1, dec!(1.2));
+
+        let trades: WebsocketMessage = serde_json::from_str(
+            r#"{"id":-1,"method":"subscribe","code":0,"result":{"instrument_name":"BTC_USDT","subscription":"trade.BTC_USDT","channel":"trade","data":[{"d":"2030407068121133824","t":1613581138462,"p":"51327.5","q":"0.0001","s":"BUY","i":"BTC_USDT","m":"76423"}]}}"#,
+        )
+        .expect("in test");
+        let result = trades.result.expect("in test");
+        let trades: Vec<CryptoComPublicTrade> =
+            serde_json::from_value(result.data).expect("in test");
+        assert_eq!(trades[0].trade_id, "2030407068121133824");
+        assert_eq!(trades[0].amount, dec!(0.0001));
+
+        let heartbeat: WebsocketMessage =
+            serde_json::from_str(r#"{"id":1587523073344,"method":"public/heartbeat","code":0}"#)
+                .expect("in test");
+        assert_eq!(heartbeat.method, HEARTBEAT_METHOD);
+        assert_eq!(heartbeat.id, 1587523073344);
+        assert!(heartbeat.result.is_none());
+    }
+
+    #[test]
+    fn parse_user_order_event() {
+        let msg = r#"{"id":-1,"method":"subscribe","code":0,"result":{"subscription":"user.order","channel":"user.order","data":[{"account_id":"52e7c00f-1324-5a6z-bfgt-de445bde21a5","order_id":"19848525","client_oid":"1613571154900","order_type":"LIMIT","time_in_force":"GOOD_TILL_CANCEL","side":"BUY","exec_inst":[],"quantity":"0.0100","limit_price":"50000.0","order_value":"500.000000","avg_price":"0","cumulative_quantity":"0","cumulative_value":"0","cumulative_fee":"0","status":"ACTIVE","order_date":"2021-02-17","instrument_name":"BTC_USDT","fee_instrument_name":"BTC","create_time":1613575617173,"update_time":1613575617173}]}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let result = message.result.expect("in test");
+        assert_eq!(result.channel, USER_ORDER_CHANNEL);
+        assert!(result.instrument_name.is_empty());
+
+        let orders: Vec<CryptoComOrder> = serde_json::from_value(result.data).expect("in test");
+        assert_eq!(orders[0].client_oid, "1613571154900");
+        assert_eq!(orders[0].order_id.as_str(), "19848525");
+        assert_eq!(orders[0].status, "ACTIVE");
+    }
+}
@@ -0,0 +1,193 @@
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, Price};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Every REST response is wrapped into envelope with method and code
+/// {
+///   "id": 1,
+///   "method": "private/get-open-orders",
+///   "code": 0,
+///   "result": {}
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComResponse<T> {
+    pub(crate) result: T,
+}
+
+/// Most of lists are returned in `data` field of `result`
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComData<T> {
+    pub(crate) data: Vec<T>,
+}
+
+/// Body of private REST requests, the same envelope is used by websocket requests
+#[derive(Serialize, Debug)]
+pub(crate) struct CryptoComRequest<'a> {
+    pub(crate) id: i64,
+    pub(crate) method: &'a str,
+    pub(crate) api_key: &'a str,
+    /// Absent for websocket authentication
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub(crate) params: Value,
+    pub(crate) nonce: i64,
+    pub(crate) sig: String,
+}
+
+/// Instrument description from `public/get-instruments`
+/// {
+///   "symbol": "BTC_USDT",
+///   "inst_type": "CCY_PAIR",
+///   "display_name": "BTC/USDT",
+///   "base_ccy": "BTC",
+///   "quote_ccy": "USDT",
+///   "quote_decimals": 2,
+///   "quantity_decimals": 5,
+///   "price_tick_size": "0.01",
+///   "qty_tick_size": "0.00001",
+///   "max_leverage": "50",
+///   "tradable": true
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComInstrument {
+    pub(crate) symbol: String,
+    /// "CCY_PAIR" for spot, "PERPETUAL_SWAP" and "FUTURE" for derivatives
+    pub(crate) inst_type: String,
+    pub(crate) base_ccy: String,
+    pub(crate) quote_ccy: String,
+    pub(crate) price_tick_size: Price,
+    pub(crate) qty_tick_size: Amount,
+    pub(crate) tradable: bool,
+}
+
+/// Order from `private/get-open-orders`, `private/get-order-detail` and `user.order` channel
+/// {
+///   "account_id": "52e7c00f-1324-5a6z-bfgt-de445bde21a5",
+///   "order_id": "19848525",
+///   "client_oid": "1613571154900",
+///   "order_type": "LIMIT",
+///   "time_in_force": "GOOD_TILL_CANCEL",
+///   "side": "BUY",
+///   "exec_inst": [],
+///   "quantity": "0.0100",
+///   "limit_price": "50000.0",
+///   "order_value": "500.000000",
+///   "avg_price": "49800.0",
+///   "cumulative_quantity": "0.0040",
+///   "cumulative_value": "199.200000",
+///   "cumulative_fee": "0.00000400",
+///   "status": "ACTIVE",
+///   "instrument_name": "BTC_USDT",
+///   "fee_instrument_name": "BTC",
+///   "create_time": 1613575617173,
+///   "update_time": 1613575617173
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComOrder {
+    pub(crate) order_id: ExchangeOrderId,
+    /// Empty for orders created outside of the bot
+    #[serde(default)]
+    pub(crate) client_oid: String,
+    pub(crate) side: String,
+    pub(crate) quantity: Amount,
+    /// Absent for market orders
+    #[serde(default)]
+    pub(crate) limit_price: Option<Price>,
+    #[serde(default)]
+    pub(crate) avg_price: Price,
+    #[serde(default)]
+    pub(crate) cumulative_quantity: Amount,
+    #[serde(default)]
+    pub(crate) cumulative_fee: Amount,
+    pub(crate) status: String,
+    pub(crate) instrument_name: String,
+    #[serde(default)]
+    pub(crate) fee_instrument_name: Option<String>,
+}
+
+/// Trade from `private/get-trades` and `user.trade` channel, fee is negative
+/// {
+///   "account_id": "52e7c00f-1324-5a6z-bfgt-de445bde21a5",
+///   "event_date": "2021-02-17",
+///   "journal_type": "TRADING",
+///   "side": "SELL",
+///   "instrument_name": "BTC_USDT",
+///   "fees": "-0.013620",
+///   "trade_id": "28457",
+///   "trade_match_id": "976523",
+///   "create_time": 1613640738192,
+///   "traded_price": "50000.00",
+///   "traded_quantity": "0.0020",
+///   "fee_instrument_name": "USDT",
+///   "client_oid": "1613640737632",
+///   "taker_side": "MAKER",
+///   "order_id": "19848524"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComTrade {
+    pub(crate) side: String,
+    pub(crate) instrument_name: String,
+    pub(crate) fees: Decimal,
+    pub(crate) trade_id: String,
+    pub(crate) create_time: u64,
+    pub(crate) traded_price: Price,
+    pub(crate) traded_quantity: Amount,
+    pub(crate) fee_instrument_name: String,
+    #[serde(default)]
+    pub(crate) client_oid: String,
+    /// "MAKER" or "TAKER"
+    pub(crate) taker_side: String,
+    pub(crate) order_id: ExchangeOrderId,
+}
+
+/// Balance of currency from `private/user-balance`
+/// {
+///   "instrument_name": "BTC",
+///   "quantity": "0.0012",
+///   "market_value": "60.00",
+///   "reserved_qty": "0.0002",
+///   "max_withdrawal_balance": "0.0010"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComPositionBalance {
+    pub(crate) instrument_name: String,
+    /// Total balance including amount reserved by open orders
+    pub(crate) quantity: Amount,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComUserBalance {
+    pub(crate) position_balances: Vec<CryptoComPositionBalance>,
+}
+
+/// Data of websocket `book.{instrument_name}.{depth}` channel,
+/// levels are `[price, amount, count of orders]`
+/// {
+///   "asks": [["30082.5", "0.1689", "1"]],
+///   "bids": [["30079.4", "0.0015", "1"]],
+///   "t": 1654780033786,
+///   "u": 542048017824
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComBook {
+    #[serde(default)]
+    pub(crate) asks: Vec<(Price, Amount, Decimal)>,
+    #[serde(default)]
+    pub(crate) bids: Vec<(Price, Amount, Decimal)>,
+}
+
+/// Trade of websocket `trade.{instrument_name}` channel
+/// {"d": "2030407068121133824", "t": 1613581138462, "p": "51327.5", "q": "0.0001", "s": "BUY"}
+#[derive(Deserialize, Debug)]
+pub(crate) struct CryptoComPublicTrade {
+    #[serde(rename = "d")]
+    pub(crate) trade_id: String,
+    #[serde(rename = "t")]
+    pub(crate) time: u64,
+    #[serde(rename = "p")]
+    pub(crate) price: Price,
+    #[serde(rename = "q")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "s")]
+    pub(crate) side: String,
+}
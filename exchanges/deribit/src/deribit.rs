@@ -0,0 +1,877 @@
+use crate::types::{
+    DeribitAccountSummaries, DeribitInstrument, DeribitOrderInfo, DeribitPlaceOrderResult,
+    DeribitPosition, DeribitResponse, DeribitUserTrades,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::misc::time::time_manager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{
+    ContractType, InstrumentExpiry, OptionContract, OptionKind, Precision, Symbol,
+};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole, OrderSide,
+    OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerDeribit;
+
+impl ErrorHandler for ErrorHandlerDeribit {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct DeribitErrorResponse {
+            error: Option<DeribitError>,
+        }
+
+        #[derive(Deserialize)]
+        struct DeribitError {
+            code: i64,
+            message: String,
+        }
+
+        let response: DeribitErrorResponse =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse Deribit response: {err:?}"))
+            })?;
+
+        match response.error {
+            None => Ok(()),
+            Some(error) => Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                error.message,
+                Some(error.code),
+            )),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://docs.deribit.com/#rpc-error-codes
+        match error.code {
+            Some(10004) => ExchangeErrorType::OrderNotFound,
+            Some(10010) | Some(10011) | Some(11044) => ExchangeErrorType::OrderCompleted,
+            Some(10009) => ExchangeErrorType::InsufficientFunds,
+            Some(10002) | Some(10005) | Some(10007) | Some(11029) | Some(11050) => {
+                ExchangeErrorType::InvalidOrder
+            }
+            Some(10028) => ExchangeErrorType::RateLimit,
+            Some(13004) | Some(13009) | Some(13021) => ExchangeErrorType::Authentication,
+            Some(10041) | Some(13028) => ExchangeErrorType::ServiceUnavailable,
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersDeribit {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersDeribit {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersDeribit {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+    ) -> Builder {
+        if self.api_key.is_empty() {
+            // Public endpoints don't require authentication
+            return builder;
+        }
+
+        let path_and_query = match uri.path_and_query() {
+            Some(path_and_query) => path_and_query.as_str(),
+            None => uri.path(),
+        };
+        let timestamp = Deribit::get_unix_time_millis().to_string();
+        let nonce = Deribit::get_nonce();
+        // All requests are sent with GET method, so body is always empty
+        let signature = Deribit::create_signature(
+            &self.secret_key,
+            &[
+                timestamp.as_bytes(),
+                nonce.as_bytes(),
+                request_type.as_str().as_bytes(),
+                path_and_query.as_bytes(),
+                b"",
+            ],
+        );
+
+        builder.header(
+            "Authorization",
+            format!(
+                "deri-hmac-sha256 id={},ts={timestamp},sig={signature},nonce={nonce}",
+                self.api_key
+            ),
+        )
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Deribit {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerDeribit, RestHeadersDeribit>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Deribit {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Deribit {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerDeribit::default(),
+                ),
+                RestHeadersDeribit::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Private and public channels are available via the same JSON-RPC websocket endpoint
+        Hosts {
+            web_socket_host: "wss://www.deribit.com/ws/api/v2",
+            web_socket2_host: "wss://www.deribit.com/ws/api/v2",
+            rest_host: "https://www.deribit.com",
+        }
+    }
+
+    pub(super) fn get_unix_time_millis() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_millis()
+    }
+
+    /// Unique string for every signed request
+    pub(super) fn get_nonce() -> String {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_nanos()
+            .to_string()
+    }
+
+    /// Hex encoded HMAC-SHA256 of message parts separated by new line
+    pub(super) fn create_signature(secret_key: &str, message_parts: &[&[u8]]) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Deribit signature");
+        for part in message_parts {
+            hmac.update(part);
+            hmac.update(b"\n");
+        }
+
+        format!("{:x}", hmac.finalize().into_bytes())
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v2/public/get_instruments");
+        builder.add_kv("currency", "any");
+        builder.add_kv("expired", false);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let instruments: DeribitResponse<Vec<DeribitInstrument>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize response from Deribit")?;
+
+        instruments
+            .result
+            .iter()
+            .filter(|instrument| {
+                instrument.is_active && matches!(instrument.kind.as_str(), "future" | "option")
+            })
+            .map(|instrument| self.parse_symbol(instrument))
+            .try_collect()
+    }
+
+    fn parse_symbol(&self, instrument: &DeribitInstrument) -> Result<Arc<Symbol>> {
+        let base_id = instrument.base_currency.as_str();
+        let quote_id = instrument.quote_currency.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+        let settle = instrument
+            .settlement_currency
+            .as_deref()
+            .map(|settle| self.currency_aliases.unify(settle.into()));
+        let is_option = instrument.kind == "option";
+
+        // Amount of inverse futures is specified in USD,
+        // amount of options and linear futures is in base currency
+        let is_inverse = settle == Some(base);
+        let amount_currency_code = match is_inverse && !is_option {
+            true => quote,
+            false => base,
+        };
+
+        let mut symbol = Symbol::new(
+            true,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            Some(instrument.min_trade_amount),
+            None,
+            None,
+            amount_currency_code,
+            settle,
+            Precision::ByTick {
+                tick: instrument.tick_size,
+            },
+            Precision::ByTick {
+                tick: instrument.min_trade_amount,
+            },
+        );
+
+        if is_option && is_inverse {
+            // Premium of inverse options is quoted in base currency,
+            // so its value is `amount * price`
+            // in base currency instead of `amount / price` for inverse futures
+            symbol = symbol.with_contract(ContractType::Quanto, Decimal::ONE);
+        }
+
+        if instrument.settlement_period.as_deref() != Some("perpetual") {
+            let option = match is_option {
+                false => None,
+                true => Some(OptionContract {
+                    kind: match instrument.option_type.as_deref() {
+                        Some("call") => OptionKind::Call,
+                        Some("put") => OptionKind::Put,
+                        option_type => {
+                            bail!("Unknown Deribit option type {option_type:?}")
+                        }
+                    },
+                    strike: instrument.strike.with_context(|| {
+                        format!("No strike of option {}", instrument.instrument_name)
+                    })?,
+                }),
+            };
+
+            symbol = symbol.with_expiry(InstrumentExpiry {
+                expiration: instrument.expiration_timestamp,
+                option,
+            });
+        }
+
+        let specific_currency_pair = instrument.instrument_name.as_str().into();
+        let unified_currency_pair = symbol.currency_pair();
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Ok(Arc::new(symbol))
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let path = match header.side {
+            OrderSide::Buy => "/api/v2/private/buy",
+            OrderSide::Sell => "/api/v2/private/sell",
+        };
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("instrument_name", specific_currency_pair);
+        builder.add_kv("amount", header.amount);
+        builder.add_kv("label", &header.client_order_id);
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                builder.add_kv("type", "limit");
+                builder.add_kv("price", price);
+                if execution_type == OrderExecutionType::MakerOnly {
+                    builder.add_kv("post_only", true);
+                    // Otherwise price of post only order is changed to be just below the spread
+                    builder.add_kv("reject_post_only", true);
+                }
+            }
+            OrderOptions::User(UserOrder::Market) => builder.add_kv("type", "market"),
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("Create order for {header:?}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let deserialized: DeribitResponse<DeribitPlaceOrderResult> =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse order_id: {err:?}"))
+            })?;
+
+        Ok(deserialized.result.order.order_id)
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v2/private/cancel");
+        builder.add_kv("order_id", exchange_order_id);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v2/private/cancel_all_by_instrument");
+        builder.add_kv(
+            "instrument_name",
+            self.get_specific_currency_pair(currency_pair),
+        );
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let builder = match currency_pair {
+            Some(pair) => {
+                let mut builder =
+                    UriBuilder::from_path("/api/v2/private/get_open_orders_by_instrument");
+                builder.add_kv("instrument_name", self.get_specific_currency_pair(pair));
+                builder
+            }
+            None => UriBuilder::from_path("/api/v2/private/get_open_orders"),
+        };
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: DeribitResponse<Vec<DeribitOrderInfo>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .result
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        // Client order id is sent as order label
+        let mut builder = UriBuilder::from_path("/api/v2/private/get_order_state_by_label");
+        builder.add_kv("currency", "any");
+        builder.add_kv("label", &client_order_id);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: DeribitResponse<Vec<DeribitOrderInfo>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_order_info request")?;
+
+        let order = orders
+            .result
+            .into_iter()
+            .next()
+            .context("No one order info received")?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: DeribitOrderInfo) -> Result<OrderInfo> {
+        let currency_pair =
+            self.get_unified_currency_pair(&specific.instrument_name.as_str().into())?;
+        Ok(OrderInfo::new(
+            currency_pair,
+            specific.order_id,
+            specific.label,
+            specific.direction,
+            Deribit::get_local_order_status(&specific.order_state),
+            specific.price,
+            specific.amount,
+            specific.average_price.unwrap_or_default(),
+            specific.filled_amount,
+            None,
+            None,
+            specific.commission,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "open" | "untriggered" | "triggered" => OrderStatus::Created,
+            "filled" => OrderStatus::Completed,
+            "cancelled" => OrderStatus::Canceled,
+            "rejected" => OrderStatus::FailedToCreate,
+            _ => panic!("Deribit: unexpected order state {}", status),
+        }
+    }
+
+    pub(super) fn get_order_role(liquidity: &str) -> Option<OrderRole> {
+        match liquidity {
+            "M" => Some(OrderRole::Maker),
+            "T" => Some(OrderRole::Taker),
+            _ => None,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder =
+            UriBuilder::from_path("/api/v2/private/get_user_trades_by_instrument_and_time");
+        builder.add_kv(
+            "instrument_name",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        builder.add_kv(
+            "start_timestamp",
+            last_date_time.map_or(0, |date_time| date_time.timestamp_millis()),
+        );
+        builder.add_kv("end_timestamp", Deribit::get_unix_time_millis());
+        builder.add_kv("count", 1000);
+        builder.add_kv("sorting", "asc");
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let user_trades: DeribitResponse<DeribitUserTrades> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        user_trades
+            .result
+            .trades
+            .into_iter()
+            .map(|trade| {
+                Ok(OrderTrade {
+                    exchange_order_id: trade.order_id,
+                    trade_id: TradeId::from(trade.trade_id),
+                    datetime: trade.timestamp,
+                    price: trade.price,
+                    amount: trade.amount,
+                    side: trade.direction,
+                    order_role: Deribit::get_order_role(&trade.liquidity).with_context(|| {
+                        format!("Unknown Deribit liquidity {}", trade.liquidity)
+                    })?,
+                    fee_currency_code: self
+                        .currency_aliases
+                        .unify(trade.fee_currency.as_str().into()),
+                    fee_rate: None,
+                    fee_amount: Some(trade.fee),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v2/private/get_account_summaries");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let accounts: DeribitResponse<DeribitAccountSummaries> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(accounts
+            .result
+            .summaries
+            .into_iter()
+            .map(|summary| ExchangeBalance {
+                currency_code: self
+                    .currency_aliases
+                    .unify(summary.currency.as_str().into()),
+                balance: summary.balance,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v2/private/get_positions");
+        builder.add_kv("currency", "any");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_position(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ActivePosition>> {
+        let positions: DeribitResponse<Vec<DeribitPosition>> =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        positions
+            .result
+            .into_iter()
+            .filter(|position| !position.size.is_zero())
+            .map(|position| {
+                let derivative_position = DerivativePosition {
+                    currency_pair: self
+                        .get_unified_currency_pair(&position.instrument_name.as_str().into())?,
+                    position: position.size,
+                    average_entry_price: position.average_price,
+                    liquidation_price: position.estimated_liquidation_price.unwrap_or_default(),
+                    leverage: position.leverage.unwrap_or(Decimal::ONE),
+                };
+
+                // Deribit doesn't send time of position update
+                Ok(ActivePosition::new(
+                    derivative_position,
+                    time_manager::now(),
+                ))
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v2/private/close_position");
+        builder.add_kv(
+            "instrument_name",
+            self.get_specific_currency_pair(position.derivative.currency_pair),
+        );
+        match price {
+            Some(price) => {
+                builder.add_kv("type", "limit");
+                builder.add_kv("price", price);
+            }
+            None => builder.add_kv("type", "market"),
+        }
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Close position response for {position:?} {price:?}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_close_position(
+        &self,
+        position: &ActivePosition,
+        response: &RestResponse,
+    ) -> Result<ClosedPosition> {
+        let exchange_order_id = self
+            .get_order_id(response)
+            .map_err(|err| anyhow!("Failed to get order id of closing position: {err:?}"))?;
+
+        Ok(ClosedPosition::new(
+            exchange_order_id,
+            position.derivative.position.abs(),
+        ))
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/api/v2/public/get_time")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        let server_time: DeribitResponse<i64> =
+            serde_json::from_str(&response.content).context("Failed to parse server time")?;
+
+        Ok(server_time.result)
+    }
+}
+
+pub struct DeribitBuilder;
+
+impl ExchangeClientBuilder for DeribitBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Deribit::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: true,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Deribit limits are based on credits:
+        // 20 requests per second are allowed for non matching engine requests
+        RequestTimeoutArguments::from_requests_per_minute(1200)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Deribit".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn generate_signature() {
+        let signature = Deribit::create_signature(
+            "secret",
+            &[
+                b"1576074319000",
+                b"1iqt2wls",
+                b"GET",
+                b"/api/v2/private/get_account_summary?currency=BTC",
+                b"",
+            ],
+        );
+
+        assert_eq!(
+            signature,
+            "21a7bf65e0002ab3d2526bb9ba6efb8ee675e812ad62740983f82fcca9bf4438"
+        );
+    }
+
+    #[test]
+    fn parse_expiring_instruments() {
+        let exchange_account_id: ExchangeAccountId = "Deribit_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
+
+        let (tx, _) = broadcast::channel(10);
+        let deribit = Deribit::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+        let response = RestResponse::new(
+            r#"{"jsonrpc":"2.0","result":[
+                {"instrument_name":"BTC-PERPETUAL","kind":"future","base_currency":"BTC","quote_currency":"USD","settlement_currency":"BTC","tick_size":0.5,"min_trade_amount":10,"contract_size":10,"expiration_timestamp":32503708800000,"settlement_period":"perpetual","is_active":true},
+                {"instrument_name":"BTC-29MAR24","kind":"future","base_currency":"BTC","quote_currency":"USD","settlement_currency":"BTC","tick_size":2.5,"min_trade_amount":10,"contract_size":10,"expiration_timestamp":1711699200000,"settlement_period":"month","is_active":true},
+                {"instrument_name":"BTC-29MAR24-60000-C","kind":"option","base_currency":"BTC","quote_currency":"USD","settlement_currency":"BTC","tick_size":0.0005,"min_trade_amount":0.1,"contract_size":1,"expiration_timestamp":1711699200000,"settlement_period":"month","strike":60000.0,"option_type":"call","is_active":true}
+            ],"usIn":1,"usOut":2,"usDiff":1,"testnet":false}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let symbols = deribit.parse_all_symbols(&response).expect("in test");
+
+        assert_eq!(symbols[0].currency_pair().as_str(), "btc/usd");
+        assert_eq!(symbols[0].contract_type, ContractType::Inverse);
+        assert_eq!(symbols[0].amount_currency_code, CurrencyCode::new("usd"));
+        assert_eq!(symbols[1].currency_pair().as_str(), "btc/usd:240329");
+
+        let option = &symbols[2];
+        assert_eq!(option.currency_pair().as_str(), "btc/usd:240329-60000-c");
+        assert_eq!(option.contract_type, ContractType::Quanto);
+        assert_eq!(option.amount_currency_code, CurrencyCode::new("btc"));
+        assert_eq!(option.contract_value(dec!(2), dec!(0.05)), dec!(0.1));
+        assert_eq!(
+            deribit
+                .get_specific_currency_pair(option.currency_pair())
+                .as_str(),
+            "BTC-29MAR24-60000-C"
+        );
+    }
+}
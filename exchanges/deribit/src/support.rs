@@ -0,0 +1,466 @@
+use crate::deribit::Deribit;
+use crate::types::{
+    DeribitBookPayload, DeribitOrderInfo, DeribitTickerPayload, DeribitTrade, DeribitTradePayload,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Greeks, MarkPriceEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Id of JSON-RPC request for websocket authentication, so its response can be recognized
+const AUTH_REQUEST_ID: u64 = 1;
+const SUBSCRIBE_REQUEST_ID: u64 = 2;
+/// Deribit closes websocket connection without any requests after some time
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[async_trait]
+impl Support for Deribit {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Notification(notification) => {
+                self.handle_notification(notification)?
+            }
+            WebsocketMessage::Response(response) => self.handle_response(response)?,
+            WebsocketMessage::Unknown(_) => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let channels = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .flat_map(|instrument_name| {
+                [
+                    format!("book.{instrument_name}.none.10.100ms"),
+                    format!("trades.{instrument_name}.100ms"),
+                    format!("ticker.{instrument_name}.100ms"),
+                ]
+            })
+            .collect::<Vec<_>>();
+        if !channels.is_empty() {
+            let subscribe = Request::to_message(
+                SUBSCRIBE_REQUEST_ID,
+                "public/subscribe",
+                json!({ "channels": channels }),
+            );
+            (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        // Private channels are subscribed after successful authentication
+        let timestamp = Deribit::get_unix_time_millis().to_string();
+        let nonce = Deribit::get_nonce();
+        let signature = Deribit::create_signature(
+            &self.settings.secret_key,
+            &[timestamp.as_bytes(), nonce.as_bytes()],
+        );
+        let auth = Request::to_message(
+            AUTH_REQUEST_ID,
+            "public/auth",
+            json!({
+                "grant_type": "client_signature",
+                "client_id": self.settings.api_key,
+                "timestamp": timestamp.parse::<u64>()?,
+                "signature": signature,
+                "nonce": nonce,
+                "data": "",
+            }),
+        );
+        (self.websocket_message_callback)(WebSocketRole::Main, auth)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_websocket_keep_alive(&self, _role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        Some(WebSocketKeepAlive {
+            message: r#"{"jsonrpc":"2.0","method":"public/test","params":{}}"#.to_owned(),
+            interval: KEEP_ALIVE_INTERVAL,
+        })
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""channel":"user."#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Deribit {
+    fn handle_response(&self, response: ResponseMessage) -> Result<()> {
+        if let Some(error) = response.error {
+            let err = format!(
+                "Deribit websocket: error in response on request {:?}: {error}",
+                response.id
+            );
+            log::error!("{err}");
+            bail!(err)
+        }
+
+        if response.id == Some(AUTH_REQUEST_ID) {
+            let subscribe = Request::to_message(
+                SUBSCRIBE_REQUEST_ID,
+                "private/subscribe",
+                json!({ "channels": ["user.orders.any.any.raw", "user.trades.any.any.raw"] }),
+            );
+            return (self.websocket_message_callback)(WebSocketRole::Main, subscribe);
+        }
+
+        Ok(())
+    }
+
+    fn handle_notification(&self, notification: NotificationMessage) -> Result<()> {
+        let params = notification.params;
+        let channel = params.channel.as_str();
+        match channel.split('.').next() {
+            Some("book") => self.handle_order_book(serde_json::from_value(params.data)?),
+            Some("trades") => {
+                for trade in serde_json::from_value::<Vec<DeribitTradePayload>>(params.data)? {
+                    self.handle_trade(trade)?;
+                }
+                Ok(())
+            }
+            Some("ticker") => self.handle_ticker(serde_json::from_value(params.data)?),
+            Some("user") if channel.starts_with("user.orders") => {
+                self.handle_order_update(serde_json::from_value(params.data)?);
+                Ok(())
+            }
+            Some("user") if channel.starts_with("user.trades") => {
+                for trade in serde_json::from_value::<Vec<DeribitTrade>>(params.data)? {
+                    self.handle_order_fill(trade);
+                }
+                Ok(())
+            }
+            _ => {
+                log::info!("Deribit websocket: notification of unknown channel {channel}");
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_order_book(&self, book: DeribitBookPayload) -> Result<()> {
+        let currency_pair =
+            self.get_unified_currency_pair(&book.instrument_name.as_str().into())?;
+
+        let mut order_book_data = OrderBookData::default();
+        for (price, amount) in book.bids {
+            order_book_data.bids.insert(price, amount);
+        }
+        for (price, amount) in book.asks {
+            order_book_data.asks.insert(price, amount);
+        }
+
+        // Grouped book channel always sends full snapshot of 10 levels
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: DeribitTradePayload) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.instrument_name.as_str().into())?,
+            Trade {
+                trade_id: TradeId::from(trade.trade_id),
+                price: trade.price,
+                quantity: trade.amount,
+                side: trade.direction,
+                transaction_time: trade.timestamp,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_ticker(&self, ticker: DeribitTickerPayload) -> Result<()> {
+        let mark_price_event = MarkPriceEvent {
+            exchange_account_id: self.settings.exchange_account_id,
+            currency_pair: self
+                .get_unified_currency_pair(&ticker.instrument_name.as_str().into())?,
+            mark_price: ticker.mark_price,
+            index_price: ticker.index_price,
+            implied_volatility: ticker.mark_iv,
+            greeks: ticker.greeks.map(|greeks| Greeks {
+                delta: greeks.delta,
+                gamma: greeks.gamma,
+                vega: greeks.vega,
+                theta: greeks.theta,
+                rho: greeks.rho,
+            }),
+            funding_rate: None,
+            time: ticker.timestamp,
+        };
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::MarkPrice(mark_price_event),
+        )
+    }
+
+    fn handle_order_update(&self, order: DeribitOrderInfo) {
+        match order.order_state.as_str() {
+            "open" => (self.order_created_callback)(
+                order.label,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            "cancelled" => (self.order_cancelled_callback)(
+                order.label,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are handled by user trades channel
+            _ => (),
+        }
+    }
+
+    fn handle_order_fill(&self, trade: DeribitTrade) {
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(trade.trade_id)),
+            client_order_id: trade.label,
+            exchange_order_id: trade.order_id,
+            fill_price: trade.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.amount,
+                total_filled_amount: None,
+            },
+            order_role: Deribit::get_order_role(&trade.liquidity),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(trade.fee_currency.as_str().into()),
+            ),
+            commission_rate: None,
+            commission_amount: Some(trade.fee),
+            fill_type: OrderFillType::UserTrade,
+            // Trades don't contain order amount, so fills of unknown orders can't be handled
+            special_order_data: None,
+            fill_date: Some(trade.timestamp),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    Notification(NotificationMessage),
+    Response(ResponseMessage),
+    Unknown(Value),
+}
+
+/// Data pushed by subscribed channel
+#[derive(Deserialize, Debug)]
+struct NotificationMessage {
+    params: NotificationParams,
+}
+
+#[derive(Deserialize, Debug)]
+struct NotificationParams {
+    channel: String,
+    data: Value,
+}
+
+/// Response on JSON-RPC request sent via websocket, e.g. authentication or subscription
+#[derive(Deserialize, Debug)]
+struct ResponseMessage {
+    id: Option<u64>,
+    error: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+impl<'a> Request<'a> {
+    fn to_message(id: u64, method: &'a str, params: Value) -> String {
+        let request = Request {
+            jsonrpc: "2.0",
+            id,
+            method,
+            params,
+        };
+
+        serde_json::to_string(&request).expect("Failed to serialize Deribit websocket message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_subscription() {
+        let message = Request::to_message(
+            SUBSCRIBE_REQUEST_ID,
+            "public/subscribe",
+            json!({ "channels": ["ticker.BTC-PERPETUAL.100ms"] }),
+        );
+
+        assert_eq!(
+            message,
+            r#"{"jsonrpc":"2.0","id":2,"method":"public/subscribe","params":{"channels":["ticker.BTC-PERPETUAL.100ms"]}}"#
+        );
+    }
+
+    #[test]
+    fn parse_option_ticker() {
+        let msg = r#"{"jsonrpc":"2.0","method":"subscription","params":{"channel":"ticker.BTC-29MAR24-60000-C.100ms","data":{"timestamp":1700000000000,"instrument_name":"BTC-29MAR24-60000-C","mark_price":0.0265,"index_price":37000.5,"mark_iv":52.3,"greeks":{"delta":0.21,"gamma":0.00002,"vega":41.2,"theta":-20.1,"rho":7.5},"best_bid_price":0.026,"best_ask_price":0.027}}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Notification(notification) = message else {
+            panic!("Unexpected message {message:?}");
+        };
+        let params = notification.params;
+        assert_eq!(params.channel, "ticker.BTC-29MAR24-60000-C.100ms");
+
+        let ticker: DeribitTickerPayload = serde_json::from_value(params.data).expect("in test");
+        assert_eq!(ticker.mark_price, dec!(0.0265));
+        assert_eq!(ticker.mark_iv, Some(dec!(52.3)));
+        assert_eq!(ticker.greeks.expect("in test").delta, dec!(0.21));
+    }
+
+    #[test]
+    fn parse_responses() {
+        let auth: WebsocketMessage = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","id":1,"result":{"access_token":"token","expires_in":31536000,"refresh_token":"token","scope":"connection","token_type":"bearer"},"usIn":1,"usOut":2,"usDiff":1,"testnet":false}"#,
+        )
+        .expect("in test");
+        assert!(matches!(
+            auth,
+            WebsocketMessage::Response(ResponseMessage {
+                id: Some(AUTH_REQUEST_ID),
+                error: None
+            })
+        ));
+
+        let error: WebsocketMessage = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","id":2,"error":{"message":"unauthorized","code":13009}}"#,
+        )
+        .expect("in test");
+        assert!(matches!(
+            error,
+            WebsocketMessage::Response(ResponseMessage { error: Some(_), .. })
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer};
+
+/// JSON-RPC response of Deribit API. Errors are checked in `ErrorHandlerDeribit`
+/// so only the result part is needed here
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitResponse<T> {
+    pub(crate) result: T,
+}
+
+/// Instrument from `/public/get_instruments`
+/// {
+///   "instrument_name": "BTC-29MAR24-60000-C",
+///   "kind": "option",
+///   "base_currency": "BTC",
+///   "quote_currency": "USD",
+///   "settlement_currency": "BTC",
+///   "tick_size": 0.0005,
+///   "min_trade_amount": 0.1,
+///   "contract_size": 1,
+///   "expiration_timestamp": 1711699200000,
+///   "settlement_period": "month",
+///   "strike": 60000,
+///   "option_type": "call",
+///   "is_active": true
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitInstrument {
+    pub(crate) instrument_name: String,
+    pub(crate) kind: String,
+    pub(crate) base_currency: String,
+    pub(crate) quote_currency: String,
+    pub(crate) settlement_currency: Option<String>,
+    pub(crate) tick_size: Price,
+    pub(crate) min_trade_amount: Amount,
+    pub(crate) contract_size: Decimal,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) expiration_timestamp: DateTime,
+    pub(crate) settlement_period: Option<String>,
+    pub(crate) strike: Option<Price>,
+    pub(crate) option_type: Option<String>,
+    pub(crate) is_active: bool,
+}
+
+/// Result of `/private/buy` and `/private/sell`
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitPlaceOrderResult {
+    pub(crate) order: DeribitOrderInfo,
+}
+
+/// Order from private requests and `user.orders` channel
+/// {
+///   "order_id": "ETH-584849853",
+///   "instrument_name": "ETH-PERPETUAL",
+///   "direction": "buy",
+///   "price": 1500.0,
+///   "amount": 40,
+///   "filled_amount": 10,
+///   "average_price": 1500.0,
+///   "order_state": "open",
+///   "label": "1234567",
+///   "commission": 0.0000012
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitOrderInfo {
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) instrument_name: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) direction: OrderSide,
+    /// Price of market order is specified as "market_price"
+    #[serde(deserialize_with = "deserialize_price")]
+    pub(crate) price: Price,
+    pub(crate) amount: Amount,
+    pub(crate) filled_amount: Amount,
+    pub(crate) average_price: Option<Price>,
+    pub(crate) order_state: String,
+    pub(crate) label: ClientOrderId,
+    pub(crate) commission: Option<Decimal>,
+}
+
+/// Trade from `/private/get_user_trades_by_instrument_and_time` and `user.trades` channel
+/// {
+///   "trade_id": "ETH-2696097",
+///   "order_id": "ETH-584849853",
+///   "instrument_name": "ETH-PERPETUAL",
+///   "direction": "buy",
+///   "price": 1500.0,
+///   "amount": 10,
+///   "liquidity": "M",
+///   "fee": 0.0000012,
+///   "fee_currency": "ETH",
+///   "label": "1234567",
+///   "timestamp": 1590484255886
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitTrade {
+    pub(crate) trade_id: String,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) instrument_name: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) direction: OrderSide,
+    pub(crate) price: Price,
+    pub(crate) amount: Amount,
+    pub(crate) liquidity: String,
+    pub(crate) fee: Decimal,
+    pub(crate) fee_currency: String,
+    #[serde(default)]
+    pub(crate) label: Option<ClientOrderId>,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) timestamp: DateTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitUserTrades {
+    pub(crate) trades: Vec<DeribitTrade>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitAccountSummaries {
+    pub(crate) summaries: Vec<DeribitAccountSummary>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitAccountSummary {
+    pub(crate) currency: String,
+    pub(crate) balance: Decimal,
+}
+
+/// Position from `/private/get_positions`. Size is negative for short positions
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitPosition {
+    pub(crate) instrument_name: String,
+    pub(crate) size: Amount,
+    pub(crate) average_price: Price,
+    pub(crate) estimated_liquidation_price: Option<Price>,
+    pub(crate) leverage: Option<Decimal>,
+}
+
+/// Data of public websocket `book.{instrument_name}.none.10.100ms` channel
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitBookPayload {
+    pub(crate) instrument_name: String,
+    pub(crate) bids: Vec<(Price, Amount)>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
+/// Trade of public websocket `trades.{instrument_name}.100ms` channel
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitTradePayload {
+    pub(crate) trade_id: String,
+    pub(crate) instrument_name: String,
+    pub(crate) price: Price,
+    pub(crate) amount: Amount,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) direction: OrderSide,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) timestamp: DateTime,
+}
+
+/// Data of public websocket `ticker.{instrument_name}.100ms` channel.
+/// Implied volatility and greeks are sent only for options
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitTickerPayload {
+    pub(crate) instrument_name: String,
+    pub(crate) mark_price: Price,
+    pub(crate) index_price: Option<Price>,
+    pub(crate) mark_iv: Option<Decimal>,
+    pub(crate) greeks: Option<DeribitGreeks>,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) timestamp: DateTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DeribitGreeks {
+    pub(crate) delta: Decimal,
+    pub(crate) gamma: Decimal,
+    pub(crate) vega: Decimal,
+    pub(crate) theta: Decimal,
+    pub(crate) rho: Decimal,
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown Deribit order direction: {side}"
+        ))),
+    }
+}
+
+fn deserialize_price<'de, D>(deserializer: D) -> Result<Price, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OrderPrice {
+        Number(Price),
+        Market(String),
+    }
+
+    match OrderPrice::deserialize(deserializer)? {
+        OrderPrice::Number(price) => Ok(price),
+        OrderPrice::Market(_) => Ok(Price::ZERO),
+    }
+}
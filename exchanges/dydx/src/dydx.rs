@@ -0,0 +1,1147 @@
+use crate::proto::{
+    CancelGoodTil, MsgCancelOrder, MsgPlaceOrder, Order, OrderGoodTil, OrderId, Side, SubaccountId,
+    TimeInForce, MSG_CANCEL_ORDER_TYPE_URL, MSG_PLACE_ORDER_TYPE_URL,
+};
+use crate::types::{
+    CosmosAccountResponse, CosmosBroadcastResponse, DydxFills, DydxHeight, DydxOrder, DydxOrderId,
+    DydxPerpetualMarket, DydxPerpetualMarkets, DydxSubaccountResponse, DydxTime,
+    SHORT_TERM_ORDER_FLAGS,
+};
+use crate::wallet::Wallet;
+use anyhow::{anyhow, bail, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::misc::time::time_manager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerDydx;
+
+impl ErrorHandler for ErrorHandlerDydx {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        /// Indexer returns list of errors, node returns result of transaction check
+        #[derive(Deserialize)]
+        struct DydxErrorResponse {
+            #[serde(default)]
+            errors: Vec<IndexerError>,
+            tx_response: Option<TxResult>,
+        }
+
+        #[derive(Deserialize)]
+        struct IndexerError {
+            msg: String,
+        }
+
+        #[derive(Deserialize)]
+        struct TxResult {
+            code: i64,
+            #[serde(default)]
+            raw_log: String,
+        }
+
+        let response: DydxErrorResponse =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse dYdX response: {err:?}"))
+            })?;
+
+        if let Some(error) = response.errors.into_iter().next() {
+            return Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                error.msg,
+                None,
+            ));
+        }
+
+        match response.tx_response {
+            Some(tx) if tx.code != 0 => Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                tx.raw_log,
+                Some(tx.code),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Transaction errors contain only message of module where the check failed
+        // Details: https://github.com/dydxprotocol/v4-chain/tree/main/protocol/x/clob/types
+        let message = error.message.to_lowercase();
+        if message.contains("does not exist") {
+            ExchangeErrorType::OrderNotFound
+        } else if message.contains("fully filled") || message.contains("already canceled") {
+            ExchangeErrorType::OrderCompleted
+        } else if message.contains("insufficient") || message.contains("undercollateralized") {
+            ExchangeErrorType::InsufficientFunds
+        } else if message.contains("post-only")
+            || message.contains("invalid")
+            || message.contains("must be a multiple")
+            || message.contains("goodtilblock")
+        {
+            ExchangeErrorType::InvalidOrder
+        } else if message.contains("rate limit") {
+            ExchangeErrorType::RateLimit
+        } else if message.contains("signature verification failed") {
+            ExchangeErrorType::Authentication
+        } else {
+            ExchangeErrorType::Unknown
+        }
+    }
+}
+
+/// Indexer requests are public, so only body of node requests needs headers
+#[derive(Default)]
+pub struct RestHeadersDydx;
+
+impl RestHeaders for RestHeadersDydx {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+        _body: &[u8],
+    ) -> Builder {
+        builder.header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+const CHAIN_ID: &str = "dydx-mainnet-1";
+/// Any full node with enabled REST (LCD) API can be used for transactions broadcasting
+const NODE_REST_HOST: &str = "dydx-rest.publicnode.com";
+/// Only default subaccount is used for trading
+pub(crate) const SUBACCOUNT_NUMBER: u32 = 0;
+/// Maximum lifetime of short-term orders in blocks
+const SHORT_TERM_ORDER_BLOCKS: u32 = 20;
+/// Quote quantums of USDC have 6 decimals
+const QUOTE_ATOMIC_RESOLUTION: i32 = -6;
+/// Deviation from oracle price for market orders, which are sent as IOC limit orders
+const MARKET_ORDER_SLIPPAGE: Decimal = dec!(0.05);
+/// Cosmos SDK error of account sequence mismatch
+const WRONG_SEQUENCE_CODE: i64 = 32;
+
+pub struct Dydx {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerDydx, RestHeadersDydx>,
+    pub(crate) wallet: Wallet,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    markets: RwLock<HashMap<CurrencyPair, DydxPerpetualMarket>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    /// On-chain client ids are `u32`, so they are generated for every `ClientOrderId`
+    client_ids: DashMap<u32, ClientOrderId>,
+    client_ids_by_client_order_id: DashMap<ClientOrderId, u32>,
+    client_id_seed: AtomicU32,
+    /// Fills contain order id assigned by indexer, so it's mapped to on-chain order id
+    pub(crate) order_ids_by_indexer_id: DashMap<String, DydxOrderId>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Dydx {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Dydx {
+        let wallet =
+            Wallet::new(&settings.secret_key, &settings.api_key, CHAIN_ID).with_expect(|| {
+                format!(
+                    "Failed to create wallet for {}",
+                    settings.exchange_account_id
+                )
+            });
+
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerDydx::default(),
+                ),
+                RestHeadersDydx::default(),
+                &settings.network,
+            ),
+            // Perpetuals are quoted in USD and margined in USDC
+            currency_aliases: CurrencyAliases::new(&[("usdc", "usd")], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            wallet,
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            markets: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            client_ids: Default::default(),
+            client_ids_by_client_order_id: Default::default(),
+            client_id_seed: AtomicU32::new(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .expect("System Time before UNIX EPOCH!")
+                    .as_secs() as u32,
+            ),
+            order_ids_by_indexer_id: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Public and subaccount channels are available via the same indexer websocket endpoint
+        Hosts {
+            web_socket_host: "wss://indexer.dydx.trade/v4/ws",
+            web_socket2_host: "wss://indexer.dydx.trade/v4/ws",
+            rest_host: "https://indexer.dydx.trade",
+        }
+    }
+
+    fn add_subaccount_kv(&self, builder: &mut UriBuilder) {
+        builder.add_kv("address", self.wallet.address());
+        builder.add_kv("subaccountNumber", SUBACCOUNT_NUMBER);
+    }
+
+    /// Client id of new order. Mapping is kept to restore `ClientOrderId` from order updates
+    fn register_client_id(&self, client_order_id: &ClientOrderId) -> u32 {
+        if let Some(client_id) = self.client_ids_by_client_order_id.get(client_order_id) {
+            return *client_id;
+        }
+
+        let client_id = self.client_id_seed.fetch_add(1, Ordering::Relaxed);
+        self.client_ids.insert(client_id, client_order_id.clone());
+        self.client_ids_by_client_order_id
+            .insert(client_order_id.clone(), client_id);
+
+        client_id
+    }
+
+    /// Orders created outside of the engine get `ClientOrderId` equal to on-chain client id
+    pub(crate) fn get_client_order_id(&self, client_id: u32) -> ClientOrderId {
+        match self.client_ids.get(&client_id) {
+            Some(client_order_id) => client_order_id.clone(),
+            None => ClientOrderId::from(u64::from(client_id)),
+        }
+    }
+
+    pub(crate) fn subaccount_id(&self) -> SubaccountId {
+        SubaccountId {
+            owner: self.wallet.address().to_owned(),
+            number: SUBACCOUNT_NUMBER,
+        }
+    }
+
+    fn get_market(&self, currency_pair: CurrencyPair) -> Result<DydxPerpetualMarket> {
+        self.markets
+            .read()
+            .get(&currency_pair)
+            .cloned()
+            .with_context(|| format!("Unknown dYdX market for {currency_pair}"))
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/v4/perpetualMarkets")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let markets: DydxPerpetualMarkets = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from dYdX")?;
+
+        markets
+            .markets
+            .into_values()
+            .filter(|market| market.status == "ACTIVE")
+            .map(|market| self.parse_symbol(market))
+            .try_collect()
+    }
+
+    fn parse_symbol(&self, market: DydxPerpetualMarket) -> Result<Arc<Symbol>> {
+        let (base_id, quote_id) = market
+            .ticker
+            .split_once('-')
+            .with_context(|| format!("Unexpected dYdX market ticker {}", market.ticker))?;
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let symbol = Symbol::new(
+            true,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            Some(market.step_size),
+            None,
+            None,
+            base,
+            Some(quote),
+            Precision::ByTick {
+                tick: market.tick_size,
+            },
+            Precision::ByTick {
+                tick: market.step_size,
+            },
+        );
+
+        let specific_currency_pair = market.ticker.as_str().into();
+        let unified_currency_pair = symbol.currency_pair();
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+        self.markets.write().insert(unified_currency_pair, market);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Ok(Arc::new(symbol))
+    }
+
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let header = order.header();
+        let client_id = self.register_client_id(&header.client_order_id);
+
+        let (price, time_in_force) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => match execution_type {
+                OrderExecutionType::MakerOnly => (price, TimeInForce::PostOnly),
+                _ => (price, TimeInForce::Unspecified),
+            },
+            OrderOptions::User(UserOrder::Market) => {
+                let price = self
+                    .get_market_order_price(header.currency_pair, header.side)
+                    .await?;
+                (price, TimeInForce::Ioc)
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        let order_id = self
+            .place_order(
+                header.currency_pair,
+                client_id,
+                header.side,
+                price,
+                header.amount,
+                time_in_force,
+                false,
+                log_args,
+            )
+            .await?;
+
+        Ok(order_id.to_exchange_order_id())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn place_order(
+        &self,
+        currency_pair: CurrencyPair,
+        client_id: u32,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        time_in_force: TimeInForce,
+        reduce_only: bool,
+        log_args: String,
+    ) -> Result<DydxOrderId, ExchangeError> {
+        let market = self
+            .get_market(currency_pair)
+            .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+        let order_id = DydxOrderId::short_term(client_id, market.clob_pair_id);
+        let good_til_block = self.request_block_height().await? + SHORT_TERM_ORDER_BLOCKS;
+
+        let message = MsgPlaceOrder {
+            order: Some(Order {
+                order_id: Some(self.to_proto_order_id(order_id)),
+                side: match side {
+                    OrderSide::Buy => Side::Buy,
+                    OrderSide::Sell => Side::Sell,
+                } as i32,
+                quantums: Dydx::to_quantums(amount, &market)
+                    .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?,
+                subticks: Dydx::to_subticks(price, &market)
+                    .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?,
+                good_til: Some(OrderGoodTil::GoodTilBlock(good_til_block)),
+                time_in_force: time_in_force as i32,
+                reduce_only,
+                client_metadata: 0,
+            }),
+        };
+
+        self.broadcast_message(MSG_PLACE_ORDER_TYPE_URL, &message, log_args)
+            .await?;
+
+        Ok(order_id)
+    }
+
+    fn to_proto_order_id(&self, order_id: DydxOrderId) -> OrderId {
+        OrderId {
+            subaccount_id: Some(self.subaccount_id()),
+            client_id: order_id.client_id,
+            order_flags: order_id.order_flags,
+            clob_pair_id: order_id.clob_pair_id,
+        }
+    }
+
+    /// Amount in base quantums: `amount * 10^(-atomic_resolution)`
+    pub(crate) fn to_quantums(amount: Amount, market: &DydxPerpetualMarket) -> Result<u64> {
+        let raw_quantums = amount * Decimal::TEN.powi(-market.atomic_resolution as i64);
+        let step = Decimal::from(market.step_base_quantums);
+        let quantums = (raw_quantums / step).round() * step;
+
+        quantums
+            .to_u64()
+            .with_context(|| format!("Invalid dYdX order amount {amount}"))
+    }
+
+    /// Price in subticks:
+    /// `price * 10^(atomic_resolution - quantum_conversion_exponent - quote_atomic_resolution)`
+    pub(crate) fn to_subticks(price: Price, market: &DydxPerpetualMarket) -> Result<u64> {
+        let exponent =
+            market.atomic_resolution - market.quantum_conversion_exponent - QUOTE_ATOMIC_RESOLUTION;
+        let raw_subticks = price * Decimal::TEN.powi(exponent as i64);
+        let step = Decimal::from(market.subticks_per_tick);
+        let subticks = (raw_subticks / step).round() * step;
+
+        subticks
+            .to_u64()
+            .with_context(|| format!("Invalid dYdX order price {price}"))
+    }
+
+    /// Market orders are IOC orders with price worse than oracle price
+    async fn get_market_order_price(
+        &self,
+        currency_pair: CurrencyPair,
+        side: OrderSide,
+    ) -> Result<Price, ExchangeError> {
+        let response = self.request_market(currency_pair).await?;
+        let markets: DydxPerpetualMarkets = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse market: {err:?}")))?;
+        let market = markets
+            .markets
+            .into_values()
+            .next()
+            .ok_or_else(|| ExchangeError::unknown("No market in dYdX response"))?;
+        let oracle_price = market
+            .oracle_price
+            .ok_or_else(|| ExchangeError::unknown("No oracle price of dYdX market"))?;
+
+        let price = match side {
+            OrderSide::Buy => oracle_price * (Decimal::ONE + MARKET_ORDER_SLIPPAGE),
+            OrderSide::Sell => oracle_price * (Decimal::ONE - MARKET_ORDER_SLIPPAGE),
+        };
+        // Price should be multiple of tick
+        Ok((price / market.tick_size).round() * market.tick_size)
+    }
+
+    #[named]
+    async fn request_market(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v4/perpetualMarkets");
+        builder.add_kv("ticker", self.get_specific_currency_pair(currency_pair));
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    #[named]
+    async fn request_block_height(&self) -> Result<u32, ExchangeError> {
+        let uri = UriBuilder::from_path("/v4/height").build_uri(self.hosts.rest_uri_host(), false);
+        let response = self
+            .rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await?;
+
+        let height: DydxHeight = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse block height: {err:?}"))
+        })?;
+
+        Ok(height.height)
+    }
+
+    #[named]
+    async fn request_account(&self) -> Result<RestResponse, ExchangeError> {
+        let path = format!("/cosmos/auth/v1beta1/accounts/{}", self.wallet.address());
+        let uri = UriBuilder::from_path(&path).build_uri(NODE_REST_HOST, false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Signs transaction with single message and sends it to node.
+    /// Response is received after transaction is checked, but before it's included into block
+    #[named]
+    async fn broadcast_message(
+        &self,
+        type_url: &str,
+        message: &impl prost::Message,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let account = match self.wallet.account() {
+            Some(account) => account,
+            None => {
+                let response = self.request_account().await?;
+                let account = serde_json::from_str::<CosmosAccountResponse>(&response.content)
+                    .map_err(|err| {
+                        ExchangeError::parsing(format!("Unable to parse dYdX account: {err:?}"))
+                    })?
+                    .account;
+                self.wallet.set_account(account);
+                account
+            }
+        };
+
+        let tx_bytes = self
+            .wallet
+            .sign_tx(type_url, message, account)
+            .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+        let body = json!({
+            "tx_bytes": base64::encode(tx_bytes),
+            "mode": "BROADCAST_MODE_SYNC",
+        })
+        .to_string();
+
+        let uri = UriBuilder::from_path("/cosmos/tx/v1beta1/txs").build_uri(NODE_REST_HOST, false);
+        let result = self
+            .rest_client
+            .post(uri, Some(Bytes::from(body)), function_name!(), log_args)
+            .await;
+
+        if let Err(error) = &result {
+            if error.code == Some(WRONG_SEQUENCE_CODE) {
+                // Sequence is requested again before next transaction
+                self.wallet.reset_account();
+            }
+        }
+
+        let response = result?;
+        if let Ok(broadcast) = serde_json::from_str::<CosmosBroadcastResponse>(&response.content) {
+            let tx_response = broadcast.tx_response;
+            log::trace!(
+                "dYdX transaction {} is accepted with code {} {} {}",
+                tx_response.txhash,
+                tx_response.code,
+                tx_response.codespace,
+                tx_response.raw_log
+            );
+        }
+
+        Ok(response)
+    }
+
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let order_id = DydxOrderId::from_exchange_order_id(exchange_order_id)
+            .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.cancel_order_by_id(order_id, log_args).await
+    }
+
+    async fn cancel_order_by_id(
+        &self,
+        order_id: DydxOrderId,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        if order_id.order_flags != SHORT_TERM_ORDER_FLAGS {
+            return Err(ExchangeError::new(
+                ExchangeErrorType::InvalidOrder,
+                format!("Only short-term dYdX orders can be cancelled, but got {order_id:?}"),
+                None,
+            ));
+        }
+
+        let good_til_block = self.request_block_height().await? + SHORT_TERM_ORDER_BLOCKS;
+        let message = MsgCancelOrder {
+            order_id: Some(self.to_proto_order_id(order_id)),
+            good_til: Some(CancelGoodTil::GoodTilBlock(good_til_block)),
+        };
+
+        self.broadcast_message(MSG_CANCEL_ORDER_TYPE_URL, &message, log_args)
+            .await
+    }
+
+    /// There is no batch cancellation on dYdX chain, so open orders are cancelled one by one
+    pub(super) async fn do_cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let response = self.request_open_orders(Some(currency_pair)).await?;
+        let orders: Vec<DydxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        let mut errors = Vec::new();
+        for order in orders {
+            let log_args = format!("Cancel all orders for {currency_pair}");
+            if let Err(error) = self.cancel_order_by_id(order.order_id(), log_args).await {
+                errors.push(format!("{:?}: {error:?}", order.order_id()));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => bail!("Failed to cancel orders: {}", errors.join(", ")),
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v4/orders");
+        self.add_subaccount_kv(&mut builder);
+        builder.add_kv("status", "OPEN");
+        if let Some(currency_pair) = currency_pair {
+            builder.add_kv("ticker", self.get_specific_currency_pair(currency_pair));
+        }
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<DydxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    /// Indexer doesn't allow to filter orders by client id,
+    /// so latest orders of market are requested
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v4/orders");
+        self.add_subaccount_kv(&mut builder);
+        builder.add_kv(
+            "ticker",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("returnLatestOrders", true);
+        builder.add_kv("limit", 100);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("order {}", order.client_order_id());
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(
+        &self,
+        order: &OrderRef,
+        response: &RestResponse,
+    ) -> Result<OrderInfo> {
+        let client_order_id = order.client_order_id();
+        let client_id = self
+            .client_ids_by_client_order_id
+            .get(&client_order_id)
+            .map(|client_id| *client_id)
+            .with_context(|| format!("Order {client_order_id} wasn't created by dYdX client"))?;
+
+        let orders: Vec<DydxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        let order = orders
+            .into_iter()
+            .find(|order| order.client_id == client_id)
+            .with_context(|| format!("No order info received for {client_order_id}"))?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: DydxOrder) -> Result<OrderInfo> {
+        let order_id = specific.order_id();
+        self.order_ids_by_indexer_id
+            .insert(specific.id.clone(), order_id);
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.ticker.as_str().into())?,
+            order_id.to_exchange_order_id(),
+            self.get_client_order_id(specific.client_id),
+            specific.side.context("No side in dYdX order")?,
+            Dydx::get_local_order_status(&specific.status),
+            specific.price.unwrap_or_default(),
+            specific.size.unwrap_or_default(),
+            // Indexer doesn't send average fill price
+            Decimal::ZERO,
+            specific.total_filled.unwrap_or_default(),
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "OPEN" | "BEST_EFFORT_OPENED" | "UNTRIGGERED" => OrderStatus::Created,
+            "FILLED" => OrderStatus::Completed,
+            "CANCELED" | "BEST_EFFORT_CANCELED" => OrderStatus::Canceled,
+            _ => panic!("dYdX: unexpected order status {}", status),
+        }
+    }
+
+    pub(super) fn get_order_role(liquidity: &str) -> Option<OrderRole> {
+        match liquidity {
+            "MAKER" => Some(OrderRole::Maker),
+            "TAKER" => Some(OrderRole::Taker),
+            _ => None,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/v4/fills");
+        self.add_subaccount_kv(&mut builder);
+        builder.add_kv(
+            "market",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        builder.add_kv("marketType", "PERPETUAL");
+        builder.add_kv("limit", 100);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Indexer returns latest fills first and doesn't filter them by start time
+    pub(super) fn parse_my_trades(
+        &self,
+        response: &RestResponse,
+        last_date_time: Option<DateTime>,
+    ) -> Result<Vec<OrderTrade>> {
+        let fills: DydxFills =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        fills
+            .fills
+            .into_iter()
+            .rev()
+            .filter(|fill| last_date_time.map_or(true, |time| fill.created_at > time))
+            .filter_map(|fill| {
+                // Only fills of orders known by indexer id can be matched with orders
+                let order_id = fill
+                    .order_id
+                    .as_ref()
+                    .and_then(|id| self.order_ids_by_indexer_id.get(id))
+                    .map(|order_id| *order_id)?;
+
+                Some(Ok(OrderTrade {
+                    exchange_order_id: order_id.to_exchange_order_id(),
+                    trade_id: TradeId::from(fill.id),
+                    datetime: fill.created_at,
+                    price: fill.price,
+                    amount: fill.size,
+                    side: fill.side,
+                    order_role: match Dydx::get_order_role(&fill.liquidity) {
+                        Some(order_role) => order_role,
+                        None => return Some(Err(anyhow!("Unknown liquidity {}", fill.liquidity))),
+                    },
+                    fee_currency_code: self.currency_aliases.unify("usdc".into()),
+                    fee_rate: None,
+                    fee_amount: Some(fill.fee),
+                    fill_type: OrderFillType::UserTrade,
+                }))
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_subaccount(&self) -> Result<RestResponse, ExchangeError> {
+        let path = format!(
+            "/v4/addresses/{}/subaccountNumber/{SUBACCOUNT_NUMBER}",
+            self.wallet.address()
+        );
+        let uri = UriBuilder::from_path(&path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let response: DydxSubaccountResponse =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(response
+            .subaccount
+            .asset_positions
+            .values()
+            .map(|asset| ExchangeBalance {
+                currency_code: self.currency_aliases.unify(asset.symbol.as_str().into()),
+                balance: asset.signed_size(),
+            })
+            .collect_vec())
+    }
+
+    pub(super) fn parse_get_position(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ActivePosition>> {
+        let response: DydxSubaccountResponse =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        response
+            .subaccount
+            .open_perpetual_positions
+            .into_values()
+            .filter(|position| !position.size.is_zero())
+            .map(|position| {
+                let derivative_position = DerivativePosition {
+                    currency_pair: self
+                        .get_unified_currency_pair(&position.market.as_str().into())?,
+                    position: position.size,
+                    average_entry_price: position.entry_price,
+                    // Indexer doesn't calculate liquidation price
+                    liquidation_price: Price::ZERO,
+                    leverage: Decimal::ONE,
+                };
+
+                // dYdX doesn't send time of position update
+                Ok(ActivePosition::new(
+                    derivative_position,
+                    time_manager::now(),
+                ))
+            })
+            .try_collect()
+    }
+
+    /// Position is closed by reduce-only IOC order
+    pub(super) async fn do_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        let derivative = &position.derivative;
+        let side = match derivative.position.is_sign_positive() {
+            true => OrderSide::Sell,
+            false => OrderSide::Buy,
+        };
+        let price = match price {
+            Some(price) => price,
+            None => self
+                .get_market_order_price(derivative.currency_pair, side)
+                .await
+                .map_err(|err| anyhow!("Failed to get price for closing position: {err:?}"))?,
+        };
+
+        let client_id = self.register_client_id(&ClientOrderId::unique_id());
+        let amount = derivative.position.abs();
+        let log_args = format!("Close position for {position:?} {price:?}");
+        let order_id = self
+            .place_order(
+                derivative.currency_pair,
+                client_id,
+                side,
+                price,
+                amount,
+                TimeInForce::Ioc,
+                true,
+                log_args,
+            )
+            .await
+            .map_err(|err| anyhow!("Failed to close position: {err:?}"))?;
+
+        Ok(ClosedPosition::new(order_id.to_exchange_order_id(), amount))
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/v4/time").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        let server_time: DydxTime =
+            serde_json::from_str(&response.content).context("Failed to parse server time")?;
+
+        Ok(server_time.iso.timestamp_millis())
+    }
+}
+
+pub struct DydxBuilder;
+
+impl ExchangeClientBuilder for DydxBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Dydx::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: false,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Indexer allows 100 requests per 10 seconds from one IP
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Dydx".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+
+    fn create_dydx() -> Dydx {
+        let exchange_account_id: ExchangeAccountId = "Dydx_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(
+            exchange_account_id,
+            "dydx1ttk2rhslczcsgcd0l8j7ylw9a7k5xfuc7gkrsk".into(),
+            "".into(),
+            true,
+        );
+
+        let (tx, _) = broadcast::channel(10);
+        Dydx::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        )
+    }
+
+    fn btc_market() -> DydxPerpetualMarket {
+        serde_json::from_str(
+            r#"{"clobPairId":"0","ticker":"BTC-USD","status":"ACTIVE","oraclePrice":"37000.52","tickSize":"1","stepSize":"0.0001","atomicResolution":-10,"quantumConversionExponent":-9,"subticksPerTick":100000,"stepBaseQuantums":1000000}"#,
+        )
+        .expect("in test")
+    }
+
+    #[test]
+    fn convert_order_to_quantums_and_subticks() {
+        let market = btc_market();
+
+        assert_eq!(
+            Dydx::to_quantums(dec!(0.0123), &market).expect("in test"),
+            123_000_000
+        );
+        assert_eq!(
+            Dydx::to_subticks(dec!(30001), &market).expect("in test"),
+            3_000_100_000
+        );
+    }
+
+    #[test]
+    fn parse_perpetual_markets() {
+        let dydx = create_dydx();
+        let response = RestResponse::new(
+            r#"{"markets":{
+                "BTC-USD":{"clobPairId":"0","ticker":"BTC-USD","status":"ACTIVE","oraclePrice":"37000.52","tickSize":"1","stepSize":"0.0001","atomicResolution":-10,"quantumConversionExponent":-9,"subticksPerTick":100000,"stepBaseQuantums":1000000},
+                "LUNA-USD":{"clobPairId":"35","ticker":"LUNA-USD","status":"FINAL_SETTLEMENT","oraclePrice":null,"tickSize":"0.0001","stepSize":"1","atomicResolution":-6,"quantumConversionExponent":-9,"subticksPerTick":1000000,"stepBaseQuantums":1000000}
+            }}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let symbols = dydx.parse_all_symbols(&response).expect("in test");
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].currency_pair().as_str(), "btc/usd");
+        assert!(symbols[0].is_derivative);
+        assert_eq!(
+            dydx.get_specific_currency_pair(symbols[0].currency_pair())
+                .as_str(),
+            "BTC-USD"
+        );
+    }
+
+    #[test]
+    fn exchange_order_id_contains_on_chain_order_id() {
+        let order_id = DydxOrderId::short_term(1700000001, 7);
+        let exchange_order_id = order_id.to_exchange_order_id();
+
+        assert_eq!(exchange_order_id.as_str(), "1700000001-0-7");
+        assert_eq!(
+            DydxOrderId::from_exchange_order_id(&exchange_order_id).expect("in test"),
+            order_id
+        );
+    }
+
+    #[test]
+    fn broadcast_error_is_detected() {
+        let response = RestResponse::new(
+            r#"{"tx_response":{"height":"0","txhash":"ABC","codespace":"clob","code":2000,"raw_log":"Order does not exist"}}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let error = ErrorHandlerDydx
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(error.code, Some(2000));
+        assert_eq!(
+            ErrorHandlerDydx.clarify_error_type(&error),
+            ExchangeErrorType::OrderNotFound
+        );
+    }
+}
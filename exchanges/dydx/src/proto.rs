@@ -0,0 +1,102 @@
+//! Protobuf messages of `dydxprotocol.clob` module which are needed for orders placement.
+//! Definitions are taken from https://github.com/dydxprotocol/v4-chain/tree/main/proto/dydxprotocol
+
+use prost::{Enumeration, Message, Oneof};
+
+pub(crate) const MSG_PLACE_ORDER_TYPE_URL: &str = "/dydxprotocol.clob.MsgPlaceOrder";
+pub(crate) const MSG_CANCEL_ORDER_TYPE_URL: &str = "/dydxprotocol.clob.MsgCancelOrder";
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub(crate) struct SubaccountId {
+    /// Address of wallet that owns subaccount
+    #[prost(string, tag = "1")]
+    pub(crate) owner: String,
+    #[prost(uint32, tag = "2")]
+    pub(crate) number: u32,
+}
+
+/// On-chain order id. Client id is chosen by the order owner,
+/// so id is known before order placement
+#[derive(Clone, PartialEq, Eq, Message)]
+pub(crate) struct OrderId {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) subaccount_id: Option<SubaccountId>,
+    #[prost(fixed32, tag = "2")]
+    pub(crate) client_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub(crate) order_flags: u32,
+    #[prost(uint32, tag = "4")]
+    pub(crate) clob_pair_id: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
+#[repr(i32)]
+pub(crate) enum Side {
+    Unspecified = 0,
+    Buy = 1,
+    Sell = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Enumeration)]
+#[repr(i32)]
+pub(crate) enum TimeInForce {
+    /// Good till `good_til_block`
+    Unspecified = 0,
+    Ioc = 1,
+    PostOnly = 2,
+    FillOrKill = 3,
+}
+
+#[derive(Clone, PartialEq, Eq, Oneof)]
+pub(crate) enum OrderGoodTil {
+    /// Last block in which short-term order can be executed
+    #[prost(uint32, tag = "5")]
+    GoodTilBlock(u32),
+    /// Expiration time of stateful order in unix seconds
+    #[prost(fixed32, tag = "6")]
+    GoodTilBlockTime(u32),
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub(crate) struct Order {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) order_id: Option<OrderId>,
+    #[prost(enumeration = "Side", tag = "2")]
+    pub(crate) side: i32,
+    /// Amount in base quantums of perpetual
+    #[prost(uint64, tag = "3")]
+    pub(crate) quantums: u64,
+    /// Price in subticks of CLOB pair
+    #[prost(uint64, tag = "4")]
+    pub(crate) subticks: u64,
+    #[prost(oneof = "OrderGoodTil", tags = "5, 6")]
+    pub(crate) good_til: Option<OrderGoodTil>,
+    #[prost(enumeration = "TimeInForce", tag = "7")]
+    pub(crate) time_in_force: i32,
+    #[prost(bool, tag = "8")]
+    pub(crate) reduce_only: bool,
+    #[prost(uint32, tag = "9")]
+    pub(crate) client_metadata: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub(crate) struct MsgPlaceOrder {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) order: Option<Order>,
+}
+
+#[derive(Clone, PartialEq, Eq, Oneof)]
+pub(crate) enum CancelGoodTil {
+    #[prost(uint32, tag = "2")]
+    GoodTilBlock(u32),
+    #[prost(fixed32, tag = "3")]
+    GoodTilBlockTime(u32),
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub(crate) struct MsgCancelOrder {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) order_id: Option<OrderId>,
+    #[prost(oneof = "CancelGoodTil", tags = "2, 3")]
+    pub(crate) good_til: Option<CancelGoodTil>,
+}
@@ -0,0 +1,383 @@
+use crate::dydx::{Dydx, SUBACCOUNT_NUMBER};
+use crate::types::{
+    DydxBookContents, DydxFill, DydxOrder, DydxSubaccountContents, DydxTradesContents,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::OrderStatus;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+const ORDER_BOOK_CHANNEL: &str = "v4_orderbook";
+const TRADES_CHANNEL: &str = "v4_trades";
+const SUBACCOUNTS_CHANNEL: &str = "v4_subaccounts";
+
+#[async_trait]
+impl Support for Dydx {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message.message_type.as_str() {
+            "connected" => Ok(()),
+            "subscribed" => self.handle_channel_data(message, EventType::Snapshot),
+            "channel_data" => self.handle_channel_data(message, EventType::Update),
+            "error" => {
+                let err = format!("dYdX websocket: error message {msg}");
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg);
+                Ok(())
+            }
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        // Indexer accepts one channel per subscription message
+        for ticker in self.traded_specific_currencies.lock().iter() {
+            for channel in [ORDER_BOOK_CHANNEL, TRADES_CHANNEL] {
+                let subscribe = subscribe_message(channel, ticker.as_str());
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+            }
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        // Subaccount channel is public, it's enough to know address of wallet
+        let subaccount = format!("{}/{SUBACCOUNT_NUMBER}", self.wallet.address());
+        let subscribe = subscribe_message(SUBACCOUNTS_CHANNEL, &subaccount);
+        (self.websocket_message_callback)(WebSocketRole::Main, subscribe)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => !self.wallet.address().is_empty(),
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(SUBACCOUNTS_CHANNEL)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Dydx {
+    fn handle_channel_data(&self, message: WebsocketMessage, event_type: EventType) -> Result<()> {
+        let (Some(channel), Some(contents)) = (message.channel, message.contents) else {
+            return Ok(());
+        };
+
+        match channel.as_str() {
+            ORDER_BOOK_CHANNEL => {
+                let ticker = message.id.context("No market id in order book message")?;
+                self.handle_order_book(&ticker, serde_json::from_value(contents)?, event_type)
+            }
+            // Snapshot of trades contains trades made before subscription
+            TRADES_CHANNEL if matches!(event_type, EventType::Snapshot) => Ok(()),
+            TRADES_CHANNEL => {
+                let ticker = message.id.context("No market id in trades message")?;
+                self.handle_trades(&ticker, serde_json::from_value(contents)?)
+            }
+            SUBACCOUNTS_CHANNEL => {
+                // Initial message contains current state of subaccount instead of updates
+                if matches!(event_type, EventType::Update) {
+                    self.handle_subaccount_update(serde_json::from_value(contents)?)?;
+                }
+                Ok(())
+            }
+            _ => {
+                log::info!("dYdX websocket: message of unknown channel {channel}");
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_order_book(
+        &self,
+        ticker: &str,
+        book: DydxBookContents,
+        event_type: EventType,
+    ) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&ticker.into())?;
+
+        // Size of removed levels is zero in updates
+        let mut order_book_data = OrderBookData::default();
+        for level in book.bids {
+            let (price, size) = level.price_and_size();
+            order_book_data.bids.insert(price, size);
+        }
+        for level in book.asks {
+            let (price, size) = level.price_and_size();
+            order_book_data.asks.insert(price, size);
+        }
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trades(&self, ticker: &str, contents: DydxTradesContents) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&ticker.into())?;
+        for trade in contents.trades {
+            (self.handle_trade_callback)(
+                currency_pair,
+                Trade {
+                    trade_id: TradeId::from(trade.id),
+                    price: trade.price,
+                    quantity: trade.size,
+                    side: trade.side,
+                    transaction_time: trade.created_at,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Orders are handled before fills, so fills can be matched by indexer order id
+    fn handle_subaccount_update(&self, contents: DydxSubaccountContents) -> Result<()> {
+        for order in contents.orders {
+            self.handle_order_update(order);
+        }
+
+        for fill in contents.fills {
+            self.handle_order_fill(fill);
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_update(&self, order: DydxOrder) {
+        let order_id = order.order_id();
+        self.order_ids_by_indexer_id
+            .insert(order.id.clone(), order_id);
+
+        let client_order_id = self.get_client_order_id(order.client_id);
+        let exchange_order_id = order_id.to_exchange_order_id();
+        match Dydx::get_local_order_status(&order.status) {
+            OrderStatus::Created => (self.order_created_callback)(
+                client_order_id,
+                exchange_order_id,
+                EventSourceType::WebSocket,
+            ),
+            OrderStatus::Canceled => (self.order_cancelled_callback)(
+                client_order_id,
+                exchange_order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are handled separately
+            _ => (),
+        }
+    }
+
+    fn handle_order_fill(&self, fill: DydxFill) {
+        let Some(order_id) = fill
+            .order_id
+            .as_ref()
+            .and_then(|id| self.order_ids_by_indexer_id.get(id))
+            .map(|order_id| *order_id)
+        else {
+            log::warn!("dYdX websocket: fill {} of unknown order", fill.id);
+            return;
+        };
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(fill.id)),
+            client_order_id: Some(self.get_client_order_id(order_id.client_id)),
+            exchange_order_id: order_id.to_exchange_order_id(),
+            fill_price: fill.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: fill.size,
+                total_filled_amount: None,
+            },
+            order_role: Dydx::get_order_role(&fill.liquidity),
+            commission_currency_code: Some(self.currency_aliases.unify("usdc".into())),
+            commission_rate: None,
+            commission_amount: Some(fill.fee),
+            fill_type: OrderFillType::UserTrade,
+            // Fills don't contain order amount, so fills of unknown orders can't be handled
+            special_order_data: None,
+            fill_date: Some(fill.created_at),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+    }
+}
+
+fn subscribe_message(channel: &str, id: &str) -> String {
+    json!({
+        "type": "subscribe",
+        "channel": channel,
+        "id": id,
+    })
+    .to_string()
+}
+
+/// Common format of all indexer websocket messages
+#[derive(Deserialize, Debug)]
+struct WebsocketMessage {
+    #[serde(rename = "type")]
+    message_type: String,
+    channel: Option<String>,
+    /// Market ticker or subaccount id
+    id: Option<String>,
+    contents: Option<Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_subscription() {
+        let message = subscribe_message(ORDER_BOOK_CHANNEL, "BTC-USD");
+
+        assert_eq!(
+            message,
+            r#"{"channel":"v4_orderbook","id":"BTC-USD","type":"subscribe"}"#
+        );
+    }
+
+    #[test]
+    fn parse_order_book_update() {
+        let msg = r#"{"type":"channel_data","connection_id":"1","message_id":5,"id":"BTC-USD","channel":"v4_orderbook","version":"1.0.0","contents":{"bids":[["37000","0.5"]],"asks":[["37001","0"]]}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        assert_eq!(message.message_type, "channel_data");
+        assert_eq!(message.id.as_deref(), Some("BTC-USD"));
+
+        let book: DydxBookContents =
+            serde_json::from_value(message.contents.expect("in test")).expect("in test");
+        let (price, size) = book
+            .bids
+            .into_iter()
+            .next()
+            .expect("in test")
+            .price_and_size();
+        assert_eq!(price, dec!(37000));
+        assert_eq!(size, dec!(0.5));
+    }
+
+    #[test]
+    fn parse_subaccount_update() {
+        let msg = r#"{"type":"channel_data","connection_id":"1","message_id":7,"id":"dydx1abc/0","channel":"v4_subaccounts","version":"3.0.0","contents":{
+            "orders":[{"id":"b8d1a0c8","clientId":"1700000001","clobPairId":"0","side":"BUY","size":"0.01","totalFilled":"0.01","price":"30000","type":"LIMIT","status":"FILLED","orderFlags":"0","ticker":"BTC-USD"}],
+            "fills":[{"id":"6ac3b3ee","side":"BUY","liquidity":"MAKER","type":"LIMIT","market":"BTC-USD","marketType":"PERPETUAL","price":"30000","size":"0.01","fee":"-0.033","createdAt":"2023-11-14T22:13:20.000Z","orderId":"b8d1a0c8"}]
+        }}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let contents: DydxSubaccountContents =
+            serde_json::from_value(message.contents.expect("in test")).expect("in test");
+
+        let order = &contents.orders[0];
+        assert_eq!(
+            order.order_id().to_exchange_order_id().as_str(),
+            "1700000001-0-0"
+        );
+        assert_eq!(contents.fills[0].order_id.as_deref(), Some("b8d1a0c8"));
+        assert_eq!(contents.fills[0].fee, dec!(-0.033));
+    }
+}
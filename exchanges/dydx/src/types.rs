@@ -0,0 +1,348 @@
+use anyhow::{Context, Result};
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Flags of short-term orders, which are stored in memory of validators and expire by block height
+pub(crate) const SHORT_TERM_ORDER_FLAGS: u32 = 0;
+
+/// Part of on-chain order id which identifies order inside subaccount.
+/// Subaccount is always the one of current exchange account,
+/// so it isn't stored in `ExchangeOrderId`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct DydxOrderId {
+    pub(crate) client_id: u32,
+    pub(crate) order_flags: u32,
+    pub(crate) clob_pair_id: u32,
+}
+
+impl DydxOrderId {
+    pub(crate) fn short_term(client_id: u32, clob_pair_id: u32) -> Self {
+        DydxOrderId {
+            client_id,
+            order_flags: SHORT_TERM_ORDER_FLAGS,
+            clob_pair_id,
+        }
+    }
+
+    /// Format is `{client_id}-{order_flags}-{clob_pair_id}`
+    pub(crate) fn to_exchange_order_id(self) -> ExchangeOrderId {
+        format!(
+            "{}-{}-{}",
+            self.client_id, self.order_flags, self.clob_pair_id
+        )
+        .as_str()
+        .into()
+    }
+
+    pub(crate) fn from_exchange_order_id(exchange_order_id: &ExchangeOrderId) -> Result<Self> {
+        let mut parts = exchange_order_id.as_str().split('-');
+        let mut next_part = |name: &str| -> Result<u32> {
+            parts
+                .next()
+                .with_context(|| format!("No {name} in dYdX order id {exchange_order_id}"))?
+                .parse()
+                .with_context(|| format!("Invalid {name} in dYdX order id {exchange_order_id}"))
+        };
+
+        Ok(DydxOrderId {
+            client_id: next_part("client id")?,
+            order_flags: next_part("order flags")?,
+            clob_pair_id: next_part("clob pair id")?,
+        })
+    }
+}
+
+/// Response of indexer `/v4/perpetualMarkets`
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxPerpetualMarkets {
+    pub(crate) markets: HashMap<String, DydxPerpetualMarket>,
+}
+
+/// {
+///   "clobPairId": "0",
+///   "ticker": "BTC-USD",
+///   "status": "ACTIVE",
+///   "oraclePrice": "37000.52",
+///   "tickSize": "1",
+///   "stepSize": "0.0001",
+///   "atomicResolution": -10,
+///   "quantumConversionExponent": -9,
+///   "subticksPerTick": 100000,
+///   "stepBaseQuantums": 1000000
+/// }
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DydxPerpetualMarket {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) clob_pair_id: u32,
+    pub(crate) ticker: String,
+    pub(crate) status: String,
+    pub(crate) oracle_price: Option<Price>,
+    pub(crate) tick_size: Price,
+    pub(crate) step_size: Amount,
+    pub(crate) atomic_resolution: i32,
+    pub(crate) quantum_conversion_exponent: i32,
+    pub(crate) subticks_per_tick: u64,
+    pub(crate) step_base_quantums: u64,
+}
+
+/// Response of indexer `/v4/height`
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxHeight {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) height: u32,
+}
+
+/// Response of indexer `/v4/time`
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxTime {
+    pub(crate) iso: DateTime,
+}
+
+/// Order from indexer `/v4/orders` and `v4_subaccounts` channel
+/// {
+///   "id": "b8d1a0c8-5f6d-5a52-9a53-5d8a1fa2c1e1",
+///   "clientId": "1700000001",
+///   "clobPairId": "0",
+///   "side": "BUY",
+///   "size": "0.01",
+///   "totalFilled": "0",
+///   "price": "30000",
+///   "type": "LIMIT",
+///   "status": "OPEN",
+///   "timeInForce": "GTT",
+///   "reduceOnly": false,
+///   "orderFlags": "0",
+///   "goodTilBlock": "12345678",
+///   "ticker": "BTC-USD"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DydxOrder {
+    /// Order id assigned by indexer, which is used in fills
+    pub(crate) id: String,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) client_id: u32,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) clob_pair_id: u32,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) order_flags: u32,
+    pub(crate) ticker: String,
+    pub(crate) status: String,
+    #[serde(default, deserialize_with = "deserialize_optional_side")]
+    pub(crate) side: Option<OrderSide>,
+    pub(crate) size: Option<Amount>,
+    pub(crate) total_filled: Option<Amount>,
+    pub(crate) price: Option<Price>,
+}
+
+impl DydxOrder {
+    pub(crate) fn order_id(&self) -> DydxOrderId {
+        DydxOrderId {
+            client_id: self.client_id,
+            order_flags: self.order_flags,
+            clob_pair_id: self.clob_pair_id,
+        }
+    }
+}
+
+/// Fill from indexer `/v4/fills` and `v4_subaccounts` channel
+/// {
+///   "id": "6ac3b3ee-2a5b-5c05-8b31-d8fd4cb0e1f3",
+///   "side": "BUY",
+///   "liquidity": "MAKER",
+///   "type": "LIMIT",
+///   "market": "BTC-USD",
+///   "marketType": "PERPETUAL",
+///   "price": "30000",
+///   "size": "0.01",
+///   "fee": "-0.033",
+///   "createdAt": "2023-11-14T22:13:20.000Z",
+///   "createdAtHeight": "12345670",
+///   "orderId": "b8d1a0c8-5f6d-5a52-9a53-5d8a1fa2c1e1"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DydxFill {
+    pub(crate) id: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) liquidity: String,
+    pub(crate) market: String,
+    pub(crate) price: Price,
+    pub(crate) size: Amount,
+    pub(crate) fee: Decimal,
+    pub(crate) created_at: DateTime,
+    /// Absent for liquidations and deleveraging
+    pub(crate) order_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxFills {
+    pub(crate) fills: Vec<DydxFill>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxSubaccountResponse {
+    pub(crate) subaccount: DydxSubaccount,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DydxSubaccount {
+    #[serde(default)]
+    pub(crate) open_perpetual_positions: HashMap<String, DydxPerpetualPosition>,
+    #[serde(default)]
+    pub(crate) asset_positions: HashMap<String, DydxAssetPosition>,
+}
+
+/// Size is negative for short positions
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DydxPerpetualPosition {
+    pub(crate) market: String,
+    pub(crate) size: Amount,
+    pub(crate) entry_price: Price,
+}
+
+/// Collateral of subaccount, e.g. USDC
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxAssetPosition {
+    pub(crate) symbol: String,
+    pub(crate) side: String,
+    pub(crate) size: Amount,
+}
+
+impl DydxAssetPosition {
+    pub(crate) fn signed_size(&self) -> Amount {
+        match self.side.as_str() {
+            "SHORT" => -self.size,
+            _ => self.size,
+        }
+    }
+}
+
+/// Response of node `/cosmos/auth/v1beta1/accounts/{address}`
+#[derive(Deserialize, Debug)]
+pub(crate) struct CosmosAccountResponse {
+    pub(crate) account: CosmosAccount,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub(crate) struct CosmosAccount {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) account_number: u64,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub(crate) sequence: u64,
+}
+
+/// Response of node `/cosmos/tx/v1beta1/txs`. Errors are checked in `ErrorHandlerDydx`
+#[derive(Deserialize, Debug)]
+pub(crate) struct CosmosBroadcastResponse {
+    pub(crate) tx_response: CosmosTxResponse,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct CosmosTxResponse {
+    pub(crate) txhash: String,
+    pub(crate) code: u32,
+    #[serde(default)]
+    pub(crate) codespace: String,
+    #[serde(default)]
+    pub(crate) raw_log: String,
+}
+
+/// Order book level of `v4_orderbook` channel.
+/// Levels of snapshot are objects, levels of updates are arrays `[price, size]`
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum DydxBookLevel {
+    Object { price: Price, size: Amount },
+    Array(Price, Amount),
+}
+
+impl DydxBookLevel {
+    pub(crate) fn price_and_size(self) -> (Price, Amount) {
+        match self {
+            DydxBookLevel::Object { price, size } => (price, size),
+            DydxBookLevel::Array(price, size) => (price, size),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxBookContents {
+    #[serde(default)]
+    pub(crate) bids: Vec<DydxBookLevel>,
+    #[serde(default)]
+    pub(crate) asks: Vec<DydxBookLevel>,
+}
+
+/// Trade of public `v4_trades` channel
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DydxTrade {
+    pub(crate) id: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) size: Amount,
+    pub(crate) price: Price,
+    pub(crate) created_at: DateTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxTradesContents {
+    pub(crate) trades: Vec<DydxTrade>,
+}
+
+/// Contents of `v4_subaccounts` channel. Orders and fills are absent if they aren't changed
+#[derive(Deserialize, Debug)]
+pub(crate) struct DydxSubaccountContents {
+    #[serde(default)]
+    pub(crate) orders: Vec<DydxOrder>,
+    #[serde(default)]
+    pub(crate) fills: Vec<DydxFill>,
+}
+
+fn parse_side<E: de::Error>(side: &str) -> Result<OrderSide, E> {
+    match side {
+        "BUY" => Ok(OrderSide::Buy),
+        "SELL" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown dYdX order side: {side}"
+        ))),
+    }
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse_side(&String::deserialize(deserializer)?)
+}
+
+fn deserialize_optional_side<'de, D>(deserializer: D) -> Result<Option<OrderSide>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|side| parse_side(&side))
+        .transpose()
+}
+
+/// Indexer and node send integer values as strings
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
@@ -0,0 +1,149 @@
+use crate::types::CosmosAccount;
+use anyhow::{anyhow, Context, Result};
+use cosmrs::crypto::secp256k1::SigningKey;
+use cosmrs::tendermint::chain;
+use cosmrs::tx::{Body, Fee, SignDoc, SignerInfo};
+use cosmrs::Any;
+use parking_lot::Mutex;
+use prost::Message;
+
+const ADDRESS_PREFIX: &str = "dydx";
+
+/// Cosmos wallet which signs transactions of dYdX chain
+pub(crate) struct Wallet {
+    signing_key: Option<SigningKey>,
+    address: String,
+    chain_id: chain::Id,
+    /// Account number and sequence are requested from node before first transaction
+    account: Mutex<Option<CosmosAccount>>,
+}
+
+impl Wallet {
+    /// `secret_key` is hex encoded secp256k1 private key. If it's empty, only public data and
+    /// data of `address` are available
+    pub(crate) fn new(secret_key: &str, address: &str, chain_id: &str) -> Result<Self> {
+        let chain_id = chain_id
+            .parse()
+            .with_context(|| format!("Invalid chain id {chain_id}"))?;
+
+        if secret_key.is_empty() {
+            return Ok(Wallet {
+                signing_key: None,
+                address: address.to_owned(),
+                chain_id,
+                account: Mutex::new(None),
+            });
+        }
+
+        let key_bytes = hex::decode(secret_key.trim_start_matches("0x"))
+            .context("dYdX secret key should be hex encoded")?;
+        let signing_key = SigningKey::from_slice(&key_bytes)
+            .map_err(|err| anyhow!("Invalid dYdX secret key: {err}"))?;
+        let address = signing_key
+            .public_key()
+            .account_id(ADDRESS_PREFIX)
+            .map_err(|err| anyhow!("Failed to derive dYdX address: {err}"))?
+            .to_string();
+
+        Ok(Wallet {
+            signing_key: Some(signing_key),
+            address,
+            chain_id,
+            account: Mutex::new(None),
+        })
+    }
+
+    pub(crate) fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub(crate) fn account(&self) -> Option<CosmosAccount> {
+        *self.account.lock()
+    }
+
+    pub(crate) fn set_account(&self, account: CosmosAccount) {
+        *self.account.lock() = Some(account);
+    }
+
+    pub(crate) fn reset_account(&self) {
+        *self.account.lock() = None;
+    }
+
+    /// Signed transaction with single message ready for broadcasting.
+    /// Short-term orders messages are free, so transaction is sent without fee and gas
+    pub(crate) fn sign_tx(
+        &self,
+        type_url: &str,
+        message: &impl Message,
+        account: CosmosAccount,
+    ) -> Result<Vec<u8>> {
+        let signing_key = self
+            .signing_key
+            .as_ref()
+            .context("dYdX secret key isn't specified")?;
+
+        let message = Any {
+            type_url: type_url.to_owned(),
+            value: message.encode_to_vec(),
+        };
+        let body = Body::new(vec![message], "", 0u32);
+        let auth_info = SignerInfo::single_direct(Some(signing_key.public_key()), account.sequence)
+            .auth_info(Fee {
+                amount: vec![],
+                gas_limit: 0,
+                payer: None,
+                granter: None,
+            });
+
+        let sign_doc = SignDoc::new(&body, &auth_info, &self.chain_id, account.account_number)
+            .map_err(|err| anyhow!("Failed to create sign doc: {err}"))?;
+        let tx = sign_doc
+            .sign(signing_key)
+            .map_err(|err| anyhow!("Failed to sign dYdX transaction: {err}"))?;
+
+        tx.to_bytes()
+            .map_err(|err| anyhow!("Failed to serialize dYdX transaction: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{MsgCancelOrder, OrderId, SubaccountId, MSG_CANCEL_ORDER_TYPE_URL};
+
+    const SECRET_KEY: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn address_is_derived_from_secret_key() {
+        let wallet = Wallet::new(SECRET_KEY, "", "dydx-mainnet-1").expect("in test");
+
+        assert!(wallet.address().starts_with("dydx1"));
+    }
+
+    #[test]
+    fn sign_transaction() {
+        let wallet = Wallet::new(SECRET_KEY, "", "dydx-mainnet-1").expect("in test");
+        let message = MsgCancelOrder {
+            order_id: Some(OrderId {
+                subaccount_id: Some(SubaccountId {
+                    owner: wallet.address().to_owned(),
+                    number: 0,
+                }),
+                client_id: 1,
+                order_flags: 0,
+                clob_pair_id: 0,
+            }),
+            good_til: None,
+        };
+        let account = CosmosAccount {
+            account_number: 1,
+            sequence: 0,
+        };
+
+        let tx = wallet
+            .sign_tx(MSG_CANCEL_ORDER_TYPE_URL, &message, account)
+            .expect("in test");
+
+        assert!(!tx.is_empty());
+    }
+}
@@ -0,0 +1,820 @@
+use crate::types::{
+    GateioAccount, GateioCurrencyPair, GateioOpenOrders, GateioOrderInfo, GateioPlaceOrderRequest,
+    GateioServerTime, GateioTrade,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, UserOrder,
+};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerGateio;
+
+impl ErrorHandler for ErrorHandlerGateio {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct GateioError {
+            label: String,
+            #[serde(default)]
+            message: String,
+        }
+
+        if response.status.is_success() {
+            return Ok(());
+        }
+
+        let gateio_error: GateioError = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse Gate.io response: {err:?}"))
+        })?;
+
+        // Gate.io identifies errors by string labels instead of numeric codes
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            format!("{}: {}", gateio_error.label, gateio_error.message),
+            None,
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://www.gate.io/docs/developers/apiv4/en/#label-list
+        let label = error.message.split(':').next().unwrap_or_default();
+        match label {
+            "ORDER_NOT_FOUND" => ExchangeErrorType::OrderNotFound,
+            "ORDER_CLOSED" => ExchangeErrorType::OrderCompleted,
+            "BALANCE_NOT_ENOUGH" => ExchangeErrorType::InsufficientFunds,
+            "INVALID_PARAM_VALUE"
+            | "INVALID_PRECISION"
+            | "INVALID_CURRENCY_PAIR"
+            | "AMOUNT_TOO_LITTLE"
+            | "AMOUNT_TOO_MUCH"
+            | "POC_FILL_IMMEDIATELY" => ExchangeErrorType::InvalidOrder,
+            "TOO_MANY_REQUESTS" => ExchangeErrorType::RateLimit,
+            "INVALID_KEY" | "INVALID_SIGNATURE" | "REQUEST_EXPIRED" | "FORBIDDEN"
+            | "IP_FORBIDDEN" => ExchangeErrorType::Authentication,
+            "SERVER_ERROR" | "TOO_BUSY" => ExchangeErrorType::ServiceUnavailable,
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersGateio {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersGateio {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+
+    fn add_auth_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        if self.api_key.is_empty() {
+            // Public endpoints don't require authentication
+            return builder;
+        }
+
+        let timestamp = Utc::now().timestamp().to_string();
+        let body_hash = format!("{:x}", Sha512::digest(body));
+        let signature = Gateio::create_signature(
+            &self.secret_key,
+            &[
+                request_type.as_str(),
+                uri.path(),
+                uri.query().unwrap_or_default(),
+                &body_hash,
+                &timestamp,
+            ]
+            .join("\n"),
+        );
+
+        builder
+            .header("KEY", &self.api_key)
+            .header("Timestamp", timestamp)
+            .header("SIGN", signature)
+    }
+}
+
+impl RestHeaders for RestHeadersGateio {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+    ) -> Builder {
+        self.add_auth_headers(builder, uri, request_type, &[])
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        self.add_auth_headers(builder, uri, request_type, body)
+            .header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+/// Gate.io requires custom order id to start with this prefix
+const CLIENT_ORDER_ID_PREFIX: &str = "t-";
+const OPEN_ORDERS_LIMIT: u32 = 100;
+const MY_TRADES_LIMIT: u32 = 1000;
+
+pub struct Gateio {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerGateio, RestHeadersGateio>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Gateio {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Gateio {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerGateio::default(),
+                ),
+                RestHeadersGateio::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Public and private channels are served by the same websocket endpoint
+        Hosts {
+            web_socket_host: "wss://api.gateio.ws/ws/v4/",
+            web_socket2_host: "wss://api.gateio.ws/ws/v4/",
+            rest_host: "https://api.gateio.ws",
+        }
+    }
+
+    /// Hex encoded HMAC-SHA512 of message
+    pub(super) fn create_signature(secret_key: &str, message: &str) -> String {
+        let mut hmac = Hmac::<Sha512>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Gate.io signature");
+        hmac.update(message.as_bytes());
+
+        format!("{:x}", hmac.finalize().into_bytes())
+    }
+
+    pub(super) fn to_gateio_text(client_order_id: &ClientOrderId) -> String {
+        format!("{CLIENT_ORDER_ID_PREFIX}{client_order_id}")
+    }
+
+    /// Returns `None` for orders created outside of the bot, e.g. via web interface
+    pub(super) fn parse_client_order_id(text: &str) -> Option<ClientOrderId> {
+        text.strip_prefix(CLIENT_ORDER_ID_PREFIX)
+            .map(ClientOrderId::from)
+    }
+
+    async fn post_json(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(body).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to serialize Gate.io request body: {err:?}"))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/api/v4/spot/currency_pairs")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let currency_pairs: Vec<GateioCurrencyPair> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from Gate.io")?;
+
+        Ok(currency_pairs
+            .iter()
+            .filter(|currency_pair| currency_pair.trade_status == "tradable")
+            .map(|currency_pair| self.parse_symbol(currency_pair))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, currency_pair: &GateioCurrencyPair) -> Arc<Symbol> {
+        let base_id = currency_pair.base.as_str();
+        let quote_id = currency_pair.quote.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = currency_pair.id.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Arc::new(Symbol::new(
+            false,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            currency_pair.min_base_amount,
+            currency_pair.max_base_amount,
+            currency_pair.min_quote_amount,
+            base,
+            None,
+            Precision::tick_from_precision(currency_pair.precision),
+            Precision::tick_from_precision(currency_pair.amount_precision),
+        ))
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let (order_type, price, time_in_force) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => (
+                "limit",
+                Some(price),
+                match execution_type {
+                    // "poc" means pending or cancelled, i.e. post only
+                    OrderExecutionType::MakerOnly => "poc",
+                    OrderExecutionType::None => "gtc",
+                },
+            ),
+            // Amount of market buy order is specified in quote currency on Gate.io,
+            // so it can't be placed without knowing price
+            OrderOptions::User(UserOrder::Market) if header.side == OrderSide::Sell => {
+                ("market", None, "ioc")
+            }
+            OrderOptions::User(UserOrder::Market) => {
+                return Err(ExchangeError::new(
+                    ExchangeErrorType::InvalidOrder,
+                    "Market buy orders aren't supported by Gate.io client".to_owned(),
+                    None,
+                ))
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let request = GateioPlaceOrderRequest {
+            text: Gateio::to_gateio_text(&header.client_order_id),
+            currency_pair: specific_currency_pair.as_str(),
+            order_type,
+            account: "spot",
+            side: header.side,
+            amount: header.amount,
+            price,
+            time_in_force,
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_json("/api/v4/spot/orders", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        struct OrderId {
+            id: ExchangeOrderId,
+        }
+
+        let deserialized: OrderId = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order id: {err:?}")))?;
+
+        Ok(deserialized.id)
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = format!("/api/v4/spot/orders/{}", exchange_order_id.as_str());
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv(
+            "currency_pair",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.rest_client
+            .delete(uri, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v4/spot/orders");
+        builder.add_kv(
+            "currency_pair",
+            self.get_specific_currency_pair(currency_pair),
+        );
+        builder.add_kv("account", "spot");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.rest_client
+            .delete(uri, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v4/spot/open_orders");
+        builder.add_kv("limit", OPEN_ORDERS_LIMIT);
+        builder.add_kv("account", "spot");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Open orders of all currency pairs are grouped by currency pair
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let open_orders: Vec<GateioOpenOrders> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        open_orders
+            .into_iter()
+            .flat_map(|currency_pair_orders| currency_pair_orders.orders)
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v4/spot/orders");
+        builder.add_kv(
+            "currency_pair",
+            self.get_specific_currency_pair(currency_pair),
+        );
+        builder.add_kv("status", "open");
+        builder.add_kv("limit", OPEN_ORDERS_LIMIT);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Open orders for {currency_pair}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_open_orders_by_currency_pair(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<GateioOrderInfo> = serde_json::from_str(&response.content).context(
+            "Unable to parse response content for get_open_orders_by_currency_pair request",
+        )?;
+
+        orders
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    /// Gate.io allows to request order by custom id instead of exchange order id
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let path = format!(
+            "/api/v4/spot/orders/{}",
+            Gateio::to_gateio_text(&client_order_id)
+        );
+        let mut builder = UriBuilder::from_path(&path);
+        builder.add_kv(
+            "currency_pair",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let order: GateioOrderInfo = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: GateioOrderInfo) -> Result<OrderInfo> {
+        let client_order_id = Gateio::parse_client_order_id(&specific.text)
+            .unwrap_or_else(|| specific.text.as_str().into());
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.currency_pair.as_str().into())?,
+            specific.id,
+            client_order_id,
+            specific.side,
+            Gateio::get_local_order_status(&specific.status),
+            specific.price,
+            specific.amount,
+            specific.avg_deal_price.unwrap_or_default(),
+            specific.amount - specific.left,
+            Some(
+                self.currency_aliases
+                    .unify(specific.fee_currency.as_str().into())
+                    .to_string(),
+            ),
+            None,
+            Some(specific.fee),
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "open" => OrderStatus::Created,
+            "cancelled" => OrderStatus::Canceled,
+            // "closed" means fully filled
+            _ => OrderStatus::Completed,
+        }
+    }
+
+    pub(super) fn get_order_role(role: &str) -> Option<OrderRole> {
+        match role {
+            "maker" => Some(OrderRole::Maker),
+            "taker" => Some(OrderRole::Taker),
+            _ => None,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v4/spot/my_trades");
+        builder.add_kv(
+            "currency_pair",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("from", date_time.timestamp());
+        }
+        builder.add_kv("limit", MY_TRADES_LIMIT);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let trades: Vec<GateioTrade> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        trades
+            .into_iter()
+            .map(|trade| {
+                Ok(OrderTrade {
+                    exchange_order_id: trade.order_id,
+                    trade_id: TradeId::from(trade.id),
+                    datetime: trade.create_time_ms,
+                    price: trade.price,
+                    amount: trade.amount,
+                    side: trade.side,
+                    order_role: Gateio::get_order_role(&trade.role)
+                        .with_context(|| format!("Unknown Gate.io trade role {}", trade.role))?,
+                    fee_currency_code: self
+                        .currency_aliases
+                        .unify(trade.fee_currency.as_str().into()),
+                    fee_rate: None,
+                    fee_amount: Some(trade.fee),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/api/v4/spot/accounts")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let accounts: Vec<GateioAccount> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| ExchangeBalance {
+                currency_code: self
+                    .currency_aliases
+                    .unify(account.currency.as_str().into()),
+                balance: account.available + account.locked,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let uri =
+            UriBuilder::from_path("/api/v4/spot/time").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        let server_time: GateioServerTime =
+            serde_json::from_str(&response.content).context("Failed to parse server time")?;
+
+        Ok(server_time.server_time)
+    }
+}
+
+pub struct GateioBuilder;
+
+impl ExchangeClientBuilder for GateioBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Gateio::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    // Label ORDER_CLOSED is returned on cancellation of finished order
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: true,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Gateio".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    fn create_gateio() -> Gateio {
+        let exchange_account_id: ExchangeAccountId = "Gateio_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        Gateio::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        )
+    }
+
+    #[test]
+    fn generate_signature() {
+        let body_hash = format!("{:x}", Sha512::digest(b""));
+        let message = [
+            "GET",
+            "/api/v4/spot/orders",
+            "currency_pair=BTC_USDT&status=open",
+            &body_hash,
+            "1541993715",
+        ]
+        .join("\n");
+
+        let signature = Gateio::create_signature("secret", &message);
+
+        assert_eq!(signature, "93fd0852e183795350a1b58b5866854c2d30b0ffbad41303eff94206844cb4abadd3a24988d478388f37f70710a633e793e4c78607441cac1bedb60d945c2300");
+    }
+
+    #[test]
+    fn parse_open_orders_of_all_currency_pairs() {
+        let gateio = create_gateio();
+        gateio.parse_all_symbols(&RestResponse::new(
+            r#"[{"id":"BTC_USDT","base":"BTC","quote":"USDT","fee":"0.2","min_base_amount":"0.0001","min_quote_amount":"1","amount_precision":4,"precision":1,"trade_status":"tradable"},{"id":"OLD_USDT","base":"OLD","quote":"USDT","fee":"0.2","amount_precision":2,"precision":2,"trade_status":"untradable"}]"#.to_owned(),
+            hyper::StatusCode::OK,
+        ))
+        .expect("in test");
+
+        let response = RestResponse::new(
+            r#"[{"currency_pair":"BTC_USDT","total":1,"orders":[{"id":"12332324","text":"t-123456","create_time_ms":1548000000123,"currency_pair":"BTC_USDT","status":"open","type":"limit","account":"spot","side":"buy","amount":"1","price":"5000.1","time_in_force":"poc","left":"0.25","filled_total":"3750.075","avg_deal_price":"5000.1","fee":"0.0015","fee_currency":"BTC"}]}]"#.to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let orders = gateio.parse_open_orders(&response).expect("in test");
+
+        assert_eq!(orders.len(), 1);
+        let order = &orders[0];
+        assert_eq!(order.exchange_order_id.as_str(), "12332324");
+        assert_eq!(order.client_order_id.as_str(), "123456");
+        assert_eq!(order.order_status, OrderStatus::Created);
+        assert_eq!(order.filled_amount, dec!(0.75));
+        assert_eq!(order.commission_amount, Some(dec!(0.0015)));
+    }
+
+    #[test]
+    fn clarify_error_type_by_label() {
+        let response = RestResponse::new(
+            r#"{"label":"ORDER_NOT_FOUND","message":"Order not found"}"#.to_owned(),
+            hyper::StatusCode::BAD_REQUEST,
+        );
+
+        let error = ErrorHandlerGateio
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(
+            ErrorHandlerGateio.clarify_error_type(&error),
+            ExchangeErrorType::OrderNotFound
+        );
+    }
+}
@@ -0,0 +1,447 @@
+use crate::gateio::Gateio;
+use crate::types::{GateioBookPayload, GateioOrderEvent, GateioTradePayload, GateioUserTrade};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+const ORDER_BOOK_CHANNEL: &str = "spot.order_book";
+const TRADES_CHANNEL: &str = "spot.trades";
+const ORDERS_CHANNEL: &str = "spot.orders";
+const USER_TRADES_CHANNEL: &str = "spot.usertrades";
+const PING_CHANNEL: &str = "spot.ping";
+const PONG_CHANNEL: &str = "spot.pong";
+const ORDER_BOOK_LEVELS: &str = "20";
+const ORDER_BOOK_UPDATE_INTERVAL: &str = "100ms";
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+#[async_trait]
+impl Support for Gateio {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        if let Some(error) = message.error {
+            let err = format!(
+                "Gate.io websocket: error on {} {}: {error}",
+                message.event, message.channel
+            );
+            log::error!("{err}");
+            bail!(err)
+        }
+
+        match (message.event.as_str(), message.channel.as_str()) {
+            (_, PONG_CHANNEL) | ("subscribe", _) => {}
+            ("update", ORDER_BOOK_CHANNEL) => {
+                self.handle_order_book(serde_json::from_value(message.result)?)?
+            }
+            ("update", TRADES_CHANNEL) => {
+                self.handle_trade(serde_json::from_value(message.result)?)?
+            }
+            ("update", ORDERS_CHANNEL) => {
+                for order_event in serde_json::from_value::<Vec<GateioOrderEvent>>(message.result)?
+                {
+                    self.handle_order_event(order_event);
+                }
+            }
+            ("update", USER_TRADES_CHANNEL) => {
+                for trade in serde_json::from_value::<Vec<GateioUserTrade>>(message.result)? {
+                    self.handle_user_trade(trade)?;
+                }
+            }
+            _ => self.log_unknown_message(self.settings.exchange_account_id, msg),
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let currency_pairs = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .map(|currency_pair| currency_pair.to_string())
+            .collect::<Vec<_>>();
+        if !currency_pairs.is_empty() {
+            // Order book channel accepts only one currency pair per subscription
+            for currency_pair in &currency_pairs {
+                let subscribe = Request::subscribe(
+                    ORDER_BOOK_CHANNEL,
+                    vec![
+                        currency_pair.as_str(),
+                        ORDER_BOOK_LEVELS,
+                        ORDER_BOOK_UPDATE_INTERVAL,
+                    ],
+                    None,
+                );
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+            }
+
+            let payload = currency_pairs.iter().map(String::as_str).collect();
+            let subscribe = Request::subscribe(TRADES_CHANNEL, payload, None);
+            (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        // Private channels are authenticated by signature of every subscription request
+        for channel in [ORDERS_CHANNEL, USER_TRADES_CHANNEL] {
+            let subscribe = Request::subscribe(channel, vec!["!all"], Some(&self.settings));
+            (self.websocket_message_callback)(WebSocketRole::Main, subscribe)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_websocket_keep_alive(&self, _role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        Some(WebSocketKeepAlive {
+            message: Request::ping(),
+            interval: KEEP_ALIVE_INTERVAL,
+        })
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(ORDERS_CHANNEL) || message.contains(USER_TRADES_CHANNEL)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Gateio {
+    fn handle_order_book(&self, book: GateioBookPayload) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&book.currency_pair.as_str().into())?;
+
+        let mut order_book_data = OrderBookData::default();
+        for (price, amount) in book.bids {
+            order_book_data.bids.insert(price, amount);
+        }
+        for (price, amount) in book.asks {
+            order_book_data.asks.insert(price, amount);
+        }
+
+        // Channel spot.order_book always sends full snapshot of limited levels
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: GateioTradePayload) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.currency_pair.as_str().into())?,
+            Trade {
+                trade_id: TradeId::from(trade.id),
+                price: trade.price,
+                quantity: trade.amount,
+                side: trade.side,
+                transaction_time: trade.create_time_ms,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, order_event: GateioOrderEvent) {
+        let Some(client_order_id) = Gateio::parse_client_order_id(&order_event.text) else {
+            // Order was created outside of the bot
+            return;
+        };
+
+        match (order_event.event.as_str(), order_event.finish_as.as_str()) {
+            ("put", _) => (self.order_created_callback)(
+                client_order_id,
+                order_event.id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are received from spot.usertrades channel
+            ("finish", "filled") => {}
+            // Post only, IOC and other orders which were finished without full filling
+            ("finish", _) => (self.order_cancelled_callback)(
+                client_order_id,
+                order_event.id,
+                EventSourceType::WebSocket,
+            ),
+            _ => {}
+        }
+    }
+
+    fn handle_user_trade(&self, trade: GateioUserTrade) -> Result<()> {
+        let Some(client_order_id) = Gateio::parse_client_order_id(&trade.text) else {
+            // Order was created outside of the bot
+            return Ok(());
+        };
+
+        // Check that currency pair is known before passing fill to engine
+        self.get_unified_currency_pair(&trade.currency_pair.as_str().into())?;
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(trade.id)),
+            client_order_id: Some(client_order_id),
+            exchange_order_id: trade.order_id,
+            fill_price: trade.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.amount,
+                total_filled_amount: None,
+            },
+            order_role: Gateio::get_order_role(&trade.role),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(trade.fee_currency.as_str().into()),
+            ),
+            commission_rate: None,
+            commission_amount: Some(trade.fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(trade.create_time_ms),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+/// Every websocket message has the same envelope, `result` depends on channel
+#[derive(Deserialize, Debug)]
+struct WebsocketMessage {
+    channel: String,
+    #[serde(default)]
+    event: String,
+    #[serde(default)]
+    error: Option<Value>,
+    #[serde(default)]
+    result: Value,
+}
+
+#[derive(Serialize)]
+struct Auth {
+    method: &'static str,
+    #[serde(rename = "KEY")]
+    key: String,
+    #[serde(rename = "SIGN")]
+    sign: String,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    time: i64,
+    channel: &'static str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    event: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    payload: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth: Option<Auth>,
+}
+
+impl<'a> Request<'a> {
+    fn to_message(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize Gate.io websocket message")
+    }
+
+    /// Private channels require `settings` with credentials for signing the request
+    fn subscribe(
+        channel: &'static str,
+        payload: Vec<&'a str>,
+        settings: Option<&ExchangeSettings>,
+    ) -> String {
+        let time = Utc::now().timestamp();
+        let event = "subscribe";
+        let auth = settings.map(|settings| Auth {
+            method: "api_key",
+            key: settings.api_key.clone(),
+            sign: Gateio::create_signature(
+                &settings.secret_key,
+                &format!("channel={channel}&event={event}&time={time}"),
+            ),
+        });
+
+        Request {
+            time,
+            channel,
+            event,
+            payload,
+            auth,
+        }
+        .to_message()
+    }
+
+    fn ping() -> String {
+        Request {
+            time: Utc::now().timestamp(),
+            channel: PING_CHANNEL,
+            event: "",
+            payload: Vec::new(),
+            auth: None,
+        }
+        .to_message()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_subscription() {
+        let message = Request::subscribe(TRADES_CHANNEL, vec!["BTC_USDT", "ETH_USDT"], None);
+        let message: Value = serde_json::from_str(&message).expect("in test");
+
+        assert_eq!(message["channel"], TRADES_CHANNEL);
+        assert_eq!(message["event"], "subscribe");
+        assert_eq!(message["payload"][1], "ETH_USDT");
+        assert!(message["time"].is_i64());
+        assert!(message.get("auth").is_none());
+    }
+
+    #[test]
+    fn parse_user_trade_event() {
+        let msg = r#"{"time":1605176741,"time_ms":1605176741123,"channel":"spot.usertrades","event":"update","result":[{"id":5736713,"user_id":1000001,"order_id":"30784428","currency_pair":"BTC_USDT","create_time":1605176741,"create_time_ms":"1605176741123.456","side":"sell","amount":"1.00000000","role":"taker","price":"10000.00000000","fee":"0.00200000000000","point_fee":"0","gt_fee":"0","text":"t-123456","fee_currency":"USDT"}]}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        assert_eq!(message.channel, USER_TRADES_CHANNEL);
+
+        let trades: Vec<GateioUserTrade> = serde_json::from_value(message.result).expect("in test");
+        let trade = &trades[0];
+        assert_eq!(trade.id, "5736713");
+        assert_eq!(trade.order_id.as_str(), "30784428");
+        assert_eq!(trade.amount, dec!(1));
+        assert_eq!(trade.create_time_ms.timestamp_millis(), 1605176741123);
+        assert_eq!(
+            Gateio::parse_client_order_id(&trade.text).map(|x| x.to_string()),
+            Some("123456".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_service_messages() {
+        let subscribed: WebsocketMessage = serde_json::from_str(
+            r#"{"time":1606292218,"channel":"spot.trades","event":"subscribe","error":null,"result":{"status":"success"}}"#,
+        )
+        .expect("in test");
+        assert!(subscribed.error.is_none());
+
+        let failed: WebsocketMessage = serde_json::from_str(
+            r#"{"time":1606292218,"channel":"spot.orders","event":"subscribe","error":{"code":2,"message":"Invalid signature"},"result":null}"#,
+        )
+        .expect("in test");
+        assert!(failed.error.is_some());
+
+        let book: WebsocketMessage = serde_json::from_str(
+            r#"{"time":1606295412,"channel":"spot.order_book","event":"update","result":{"t":1606295412123,"lastUpdateId":48791820,"s":"BTC_USDT","bids":[["19079.55","0.0195"]],"asks":[["19080.24","0.1638"],["19080.25","0.5"]]}}"#,
+        )
+        .expect("in test");
+        let book: GateioBookPayload = serde_json::from_value(book.result).expect("in test");
+        assert_eq!(book.currency_pair, "BTC_USDT");
+        assert_eq!(book.asks[1].0, dec!(19080.25));
+        assert_eq!(book.bids[0].1, dec!(0.0195));
+    }
+}
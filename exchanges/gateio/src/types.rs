@@ -0,0 +1,279 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// Currency pair description from `/api/v4/spot/currency_pairs`
+/// {
+///   "id": "ETH_USDT",
+///   "base": "ETH",
+///   "quote": "USDT",
+///   "fee": "0.2",
+///   "min_base_amount": "0.001",
+///   "min_quote_amount": "1.0",
+///   "max_base_amount": "10000",
+///   "amount_precision": 3,
+///   "precision": 6,
+///   "trade_status": "tradable"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioCurrencyPair {
+    pub(crate) id: String,
+    pub(crate) base: String,
+    pub(crate) quote: String,
+    #[serde(default)]
+    pub(crate) min_base_amount: Option<Amount>,
+    #[serde(default)]
+    pub(crate) min_quote_amount: Option<Price>,
+    #[serde(default)]
+    pub(crate) max_base_amount: Option<Amount>,
+    pub(crate) amount_precision: i8,
+    /// Count of decimal places of price
+    pub(crate) precision: i8,
+    pub(crate) trade_status: String,
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct GateioPlaceOrderRequest<'a> {
+    /// Client order id with mandatory `t-` prefix
+    pub(crate) text: String,
+    pub(crate) currency_pair: &'a str,
+    #[serde(rename = "type")]
+    pub(crate) order_type: &'static str,
+    pub(crate) account: &'static str,
+    #[serde(serialize_with = "serialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) price: Option<Price>,
+    pub(crate) time_in_force: &'static str,
+}
+
+/// Order from `/api/v4/spot/orders` and `/api/v4/spot/open_orders`
+/// {
+///   "id": "12332324",
+///   "text": "t-123456",
+///   "create_time_ms": 1548000000123,
+///   "currency_pair": "ETH_BTC",
+///   "status": "open",
+///   "type": "limit",
+///   "account": "spot",
+///   "side": "buy",
+///   "amount": "1",
+///   "price": "5.00032",
+///   "time_in_force": "gtc",
+///   "left": "0.5",
+///   "filled_total": "2.50016",
+///   "avg_deal_price": "5.00032",
+///   "fee": "0.005",
+///   "fee_currency": "ETH"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioOrderInfo {
+    pub(crate) id: ExchangeOrderId,
+    pub(crate) text: String,
+    pub(crate) currency_pair: String,
+    /// "open", "closed" or "cancelled"
+    pub(crate) status: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) amount: Amount,
+    pub(crate) price: Price,
+    pub(crate) left: Amount,
+    #[serde(default)]
+    pub(crate) avg_deal_price: Option<Price>,
+    pub(crate) fee: Amount,
+    pub(crate) fee_currency: String,
+}
+
+/// Open orders of one currency pair from `/api/v4/spot/open_orders`
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioOpenOrders {
+    pub(crate) orders: Vec<GateioOrderInfo>,
+}
+
+/// Trade from `/api/v4/spot/my_trades`
+/// {
+///   "id": "1232893232",
+///   "create_time_ms": "1618846794692.000",
+///   "currency_pair": "BTC_USDT",
+///   "side": "sell",
+///   "role": "taker",
+///   "amount": "0.0001",
+///   "price": "58793.1",
+///   "order_id": "4128442423",
+///   "fee": "0.0117586",
+///   "fee_currency": "USDT",
+///   "text": "t-123456"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioTrade {
+    pub(crate) id: String,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) create_time_ms: DateTime,
+    pub(crate) currency_pair: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) role: String,
+    pub(crate) amount: Amount,
+    pub(crate) price: Price,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) fee: Amount,
+    pub(crate) fee_currency: String,
+    #[serde(default)]
+    pub(crate) text: String,
+}
+
+/// Account from `/api/v4/spot/accounts`
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioAccount {
+    pub(crate) currency: String,
+    pub(crate) available: Decimal,
+    pub(crate) locked: Decimal,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioServerTime {
+    pub(crate) server_time: i64,
+}
+
+/// Order update of private websocket `spot.orders` channel
+/// {
+///   "id": "12332324",
+///   "text": "t-123456",
+///   "create_time_ms": "1694508233000",
+///   "update_time_ms": "1694508233000",
+///   "currency_pair": "BTC_USDT",
+///   "type": "limit",
+///   "side": "buy",
+///   "amount": "0.001",
+///   "price": "26000",
+///   "left": "0.001",
+///   "event": "put",
+///   "finish_as": "open"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioOrderEvent {
+    pub(crate) id: ExchangeOrderId,
+    pub(crate) text: String,
+    /// "put" on creation, "update" on fill and "finish" on completion or cancellation
+    pub(crate) event: String,
+    #[serde(default)]
+    pub(crate) finish_as: String,
+}
+
+/// Trade of private websocket `spot.usertrades` channel
+/// {
+///   "id": 5736713,
+///   "order_id": "30784428",
+///   "currency_pair": "BTC_USDT",
+///   "create_time_ms": "1605176741123.456",
+///   "side": "sell",
+///   "amount": "1.00000000",
+///   "role": "taker",
+///   "price": "10000.00000000",
+///   "fee": "0.00200000000000",
+///   "fee_currency": "USDT",
+///   "text": "t-123456"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioUserTrade {
+    #[serde(deserialize_with = "deserialize_string_or_number")]
+    pub(crate) id: String,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) currency_pair: String,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) create_time_ms: DateTime,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) amount: Amount,
+    pub(crate) role: String,
+    pub(crate) price: Price,
+    pub(crate) fee: Amount,
+    pub(crate) fee_currency: String,
+    #[serde(default)]
+    pub(crate) text: String,
+}
+
+/// Order book snapshot of public websocket `spot.order_book` channel
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioBookPayload {
+    #[serde(rename = "s")]
+    pub(crate) currency_pair: String,
+    pub(crate) bids: Vec<(Price, Amount)>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
+/// Trade of public websocket `spot.trades` channel
+#[derive(Deserialize, Debug)]
+pub(crate) struct GateioTradePayload {
+    #[serde(deserialize_with = "deserialize_string_or_number")]
+    pub(crate) id: String,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) create_time_ms: DateTime,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) currency_pair: String,
+    pub(crate) amount: Amount,
+    pub(crate) price: Price,
+}
+
+fn serialize_side<S>(side: &OrderSide, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(match side {
+        OrderSide::Buy => "buy",
+        OrderSide::Sell => "sell",
+    })
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown Gate.io order side: {side}"
+        ))),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    Number(Decimal),
+    String(String),
+}
+
+/// Gate.io returns ids either as numbers or as strings depending on channel
+fn deserialize_string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::Number(number) => number.to_string(),
+        StringOrNumber::String(value) => value,
+    })
+}
+
+/// Gate.io returns time in milliseconds either as number or as string,
+/// sometimes with fractional part of microseconds
+fn deserialize_millis<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = deserialize_string_or_number(deserializer)?;
+    let millis: Decimal = value.parse().map_err(de::Error::custom)?;
+    let nanos = (millis * Decimal::from(1_000_000))
+        .trunc()
+        .to_i64()
+        .ok_or_else(|| de::Error::custom(format!("Invalid Gate.io time: {value}")))?;
+
+    Ok(Utc.timestamp_nanos(nanos))
+}
@@ -0,0 +1,782 @@
+use crate::types::{GeminiBalance, GeminiError, GeminiOrder, GeminiSymbolDetails, GeminiTrade};
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::{CACHE_CONTROL, CONTENT_TYPE};
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, UserOrder,
+};
+use mmb_utils::time::{get_current_milliseconds, u64_to_date_time};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use serde_json::{json, Value};
+use sha2::Sha384;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Public endpoints are limited by 120 requests per minute
+const SYMBOL_DETAILS_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+/// Maximum count of trades returned by `/v1/mytrades`
+const MY_TRADES_LIMIT: u32 = 500;
+/// Symbols with other statuses don't accept new orders
+const OPEN_SYMBOL_STATUS: &str = "open";
+
+#[derive(Default)]
+pub struct ErrorHandlerGemini;
+
+impl ErrorHandler for ErrorHandlerGemini {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        // Successful responses are objects or arrays without `result` field
+        let error: GeminiError = match serde_json::from_str(&response.content) {
+            Ok(error) => error,
+            Err(_) => return Ok(()),
+        };
+
+        if error.result != "error" {
+            return Ok(());
+        }
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            format!("{}: {}", error.reason, error.message),
+            None,
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        use ExchangeErrorType::*;
+        // Message has format "<reason>: <description>"
+        // Details: https://docs.gemini.com/rest-api/#error-codes
+        let reason = error.message.split(':').next().unwrap_or_default();
+        match reason {
+            "OrderNotFound" => OrderNotFound,
+            "InsufficientFunds" => InsufficientFunds,
+            "InvalidPrice"
+            | "InvalidQuantity"
+            | "InvalidSide"
+            | "InvalidOrderType"
+            | "InvalidSymbol"
+            | "ClientOrderIdTooLong"
+            | "ClientOrderIdMustBeString" => InvalidOrder,
+            "RateLimit" => RateLimit,
+            "InvalidNonce"
+            | "InvalidSignature"
+            | "MissingApikeyHeader"
+            | "InvalidApiKey"
+            | "MissingRole" => Authentication,
+            "Maintenance" | "System" => ServiceUnavailable,
+            _ => Unknown,
+        }
+    }
+}
+
+/// Private requests are authenticated by payload and its signature in headers,
+/// payload is taken from body of request
+pub struct RestHeadersGemini {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersGemini {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersGemini {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        // Only public endpoints are requested by GET
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        Gemini::create_auth_headers(&self.api_key, &self.secret_key, body)
+            .into_iter()
+            .fold(builder, |builder, (name, value)| {
+                builder.header(name, value)
+            })
+            .header(CONTENT_TYPE, "text/plain")
+            .header(CACHE_CONTROL, "no-cache")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Gemini {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerGemini, RestHeadersGemini>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    /// Currency pairs which order book snapshot is received by current connection,
+    /// next `l2_updates` messages for them are updates
+    pub(super) order_book_snapshots: Mutex<HashSet<SpecificCurrencyPair>>,
+    /// Nonce should increase with every private request and websocket connection
+    last_nonce: AtomicI64,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Gemini {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Gemini {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerGemini::default(),
+                ),
+                RestHeadersGemini::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            order_book_snapshots: Default::default(),
+            last_nonce: AtomicI64::new(0),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://api.gemini.com/v2/marketdata",
+            web_socket2_host: "wss://api.gemini.com/v1/order/events",
+            rest_host: "https://api.gemini.com",
+        }
+    }
+
+    /// Milliseconds of current time or next value if several nonces are requested
+    /// within the same millisecond
+    pub(super) fn next_nonce(&self) -> i64 {
+        let now = get_current_milliseconds();
+        let previous = self
+            .last_nonce
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(now.max(last + 1))
+            })
+            .expect("Nonce is always updated");
+
+        now.max(previous + 1)
+    }
+
+    /// Headers with base64 encoded payload and hex encoded HMAC-SHA384 of it
+    pub(super) fn create_auth_headers(
+        api_key: &str,
+        secret_key: &str,
+        payload: &[u8],
+    ) -> [(&'static str, String); 3] {
+        let payload = base64::encode(payload);
+
+        let mut hmac = Hmac::<Sha384>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for Gemini signature");
+        hmac.update(payload.as_bytes());
+        let signature = format!("{:x}", hmac.finalize().into_bytes());
+
+        [
+            ("X-GEMINI-APIKEY", api_key.to_owned()),
+            ("X-GEMINI-PAYLOAD", payload),
+            ("X-GEMINI-SIGNATURE", signature),
+        ]
+    }
+
+    /// Payload of private request is JSON object with path of request, nonce and parameters
+    pub(super) fn create_payload(&self, path: &str, mut params: Value) -> Value {
+        params["request"] = json!(path);
+        params["nonce"] = json!(self.next_nonce().to_string());
+        params
+    }
+
+    async fn post_private(
+        &self,
+        path: &'static str,
+        params: Value,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let payload = self.create_payload(path, params);
+        let body = serde_json::to_vec(&payload).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to serialize Gemini payload: {err:?}"))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/v1/symbols").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_symbol_details(
+        &self,
+        symbol: &str,
+    ) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path(&format!("/v1/symbols/details/{symbol}"))
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), format!("symbol {symbol}"))
+            .await
+    }
+
+    /// There is no endpoint with details of all symbols,
+    /// so they are requested one by one within limit of public endpoints
+    pub(super) async fn build_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        let response = self.request_all_symbols().await?;
+        let names: Vec<String> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize symbols from Gemini")?;
+
+        let mut symbols = Vec::with_capacity(names.len());
+        for name in names {
+            let response = self.request_symbol_details(&name).await?;
+            if let Some(symbol) = self.parse_symbol_details(&response)? {
+                symbols.push(symbol);
+            }
+
+            tokio::time::sleep(SYMBOL_DETAILS_REQUEST_INTERVAL).await;
+        }
+
+        Ok(symbols)
+    }
+
+    pub(super) fn parse_symbol_details(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Option<Arc<Symbol>>> {
+        let details: GeminiSymbolDetails = serde_json::from_str(&response.content)
+            .context("Unable to deserialize symbol details from Gemini")?;
+
+        if details.status != OPEN_SYMBOL_STATUS {
+            return Ok(None);
+        }
+
+        let base_id = details.base_currency.as_str();
+        let quote_id = details.quote_currency.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = details.symbol.to_uppercase().as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Ok(Some(Arc::new(Symbol::new(
+            false,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            Some(details.min_order_size),
+            None,
+            None,
+            base,
+            None,
+            Precision::ByTick {
+                tick: details.quote_increment,
+            },
+            Precision::ByTick {
+                tick: details.tick_size,
+            },
+        ))))
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        // Numbers are sent as strings to avoid loss of precision
+        let mut params = json!({
+            "client_order_id": header.client_order_id.as_str(),
+            "symbol": specific_currency_pair.as_str(),
+            "amount": header.amount.to_string(),
+            "side": Self::get_server_order_side(header.side),
+            "type": "exchange limit",
+        });
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                params["price"] = json!(price.to_string());
+                if execution_type == OrderExecutionType::MakerOnly {
+                    params["options"] = json!(["maker-or-cancel"]);
+                }
+            }
+            // Gemini supports only limit orders, market orders should be emulated
+            // by immediate-or-cancel limit orders with crossing price
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_private("/v1/order/new", params, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let order: GeminiOrder = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order_id: {err:?}")))?;
+
+        Ok(order.order_id)
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let order_id: u64 = exchange_order_id.as_str().parse().map_err(|err| {
+            ExchangeError::parsing(format!("Unexpected Gemini order id: {err:?}"))
+        })?;
+
+        let log_args = format!("Cancel order {exchange_order_id}");
+        self.post_private(
+            "/v1/order/cancel",
+            json!({ "order_id": order_id }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
+        self.post_private("/v1/orders", json!({}), function_name!(), "".to_string())
+            .await
+    }
+
+    /// Gemini returns open orders of all symbols, so they are filtered by currency pair here
+    pub(super) fn parse_open_orders(
+        &self,
+        response: &RestResponse,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<GeminiOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .filter_ok(|order| currency_pair.map_or(true, |x| x == order.currency_pair))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let log_args = format!("order {client_order_id}");
+        self.post_private(
+            "/v1/order/status",
+            json!({ "client_order_id": client_order_id.as_str() }),
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let order: GeminiOrder = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        self.specific_order_info_to_unified(&order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: &GeminiOrder) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.symbol)?,
+            specific.order_id.clone(),
+            ClientOrderId::from(specific.client_order_id.as_str()),
+            Self::get_local_order_side(&specific.side)?,
+            Self::get_local_order_status(specific),
+            specific.price,
+            specific.original_amount,
+            specific.avg_execution_price,
+            specific.executed_amount,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    /// Symbols are returned in lower case by some endpoints and in upper case by others
+    pub(super) fn get_unified_currency_pair(&self, symbol: &str) -> Result<CurrencyPair> {
+        let currency_pair = SpecificCurrencyPair::from(symbol.to_uppercase().as_str());
+        self.specific_to_unified
+            .read()
+            .get(&currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    fn get_server_order_side(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    /// Side is in lower case for orders and capitalized for trades
+    pub(super) fn get_local_order_side(side: &str) -> Result<OrderSide> {
+        match side {
+            "buy" | "Buy" => Ok(OrderSide::Buy),
+            "sell" | "Sell" => Ok(OrderSide::Sell),
+            _ => bail!("Unknown Gemini order side {side}"),
+        }
+    }
+
+    /// Gemini has no status field, order is completed if it's neither live nor cancelled
+    fn get_local_order_status(order: &GeminiOrder) -> OrderStatus {
+        match (order.is_live, order.is_cancelled) {
+            (true, _) => OrderStatus::Created,
+            (false, true) => OrderStatus::Canceled,
+            (false, false) => OrderStatus::Completed,
+        }
+    }
+
+    pub(super) fn get_order_role(is_taker: bool) -> OrderRole {
+        match is_taker {
+            true => OrderRole::Taker,
+            false => OrderRole::Maker,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
+
+        let mut params = json!({
+            "symbol": specific_currency_pair.as_str(),
+            "limit_trades": MY_TRADES_LIMIT,
+        });
+        if let Some(date_time) = last_date_time {
+            params["timestamp"] = json!(date_time.timestamp_millis());
+        }
+
+        self.post_private("/v1/mytrades", params, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let trades: Vec<GeminiTrade> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        trades
+            .into_iter()
+            .map(|trade| {
+                Ok(OrderTrade {
+                    exchange_order_id: trade.order_id,
+                    trade_id: TradeId::Number(trade.tid),
+                    datetime: u64_to_date_time(trade.timestampms),
+                    price: trade.price,
+                    amount: trade.amount,
+                    side: Self::get_local_order_side(&trade.side)?,
+                    order_role: Self::get_order_role(trade.aggressor),
+                    fee_currency_code: self
+                        .currency_aliases
+                        .unify(trade.fee_currency.as_str().into()),
+                    fee_rate: None,
+                    fee_amount: Some(trade.fee_amount),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        self.post_private("/v1/balances", json!({}), function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let balances: Vec<GeminiBalance> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(balances
+            .into_iter()
+            .map(|balance| ExchangeBalance {
+                currency_code: self
+                    .currency_aliases
+                    .unify(balance.currency.as_str().into()),
+                balance: balance.amount,
+            })
+            .collect_vec())
+    }
+}
+
+pub struct GeminiBuilder;
+
+impl ExchangeClientBuilder for GeminiBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Gemini::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: false,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                // Order events websocket reports every step of order lifecycle,
+                // so creation and cancellation are accepted from it only for auditing
+                AllowedEventSourceType::WebSocketOnly,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::WebSocketOnly,
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Private endpoints are limited by 600 requests per minute
+        RequestTimeoutArguments::from_requests_per_second(10)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Gemini".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    fn create_gemini() -> Gemini {
+        let exchange_account_id: ExchangeAccountId = "Gemini_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let gemini = Gemini::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let details = RestResponse::new(
+            r#"{"symbol":"BTCUSD","base_currency":"BTC","quote_currency":"USD","tick_size":1E-8,"quote_increment":0.01,"min_order_size":"0.00001","status":"open","wrap_enabled":false}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        gemini.parse_symbol_details(&details).expect("in test");
+
+        gemini
+    }
+
+    #[test]
+    fn parse_symbol_details() {
+        let gemini = create_gemini();
+
+        assert!(gemini.get_unified_currency_pair("btcusd").is_ok());
+        assert!(gemini.get_unified_currency_pair("BTCUSD").is_ok());
+
+        let closed = RestResponse::new(
+            r#"{"symbol":"OLDUSD","base_currency":"OLD","quote_currency":"USD","tick_size":0.01,"quote_increment":0.0001,"min_order_size":"1","status":"closed","wrap_enabled":false}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        assert!(gemini
+            .parse_symbol_details(&closed)
+            .expect("in test")
+            .is_none());
+        assert!(gemini.get_unified_currency_pair("OLDUSD").is_err());
+    }
+
+    #[test]
+    fn parse_open_orders() {
+        let gemini = create_gemini();
+        let response = RestResponse::new(
+            r#"[{"order_id":"106817811","id":"106817811","symbol":"btcusd","exchange":"gemini","avg_execution_price":"3632.85","side":"buy","type":"exchange limit","timestamp":"1547220404","timestampms":1547220404836,"is_live":true,"is_cancelled":false,"is_hidden":false,"was_forced":false,"executed_amount":"3.75","remaining_amount":"1.25","client_order_id":"20190110-4738721","options":[],"price":"3633.00","original_amount":"5"}]"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let orders = gemini.parse_open_orders(&response, None).expect("in test");
+
+        assert_eq!(orders.len(), 1);
+        let order = &orders[0];
+        assert_eq!(order.exchange_order_id.as_str(), "106817811");
+        assert_eq!(order.client_order_id.as_str(), "20190110-4738721");
+        assert_eq!(order.order_side, OrderSide::Buy);
+        assert_eq!(order.order_status, OrderStatus::Created);
+        assert_eq!(order.price, dec!(3633));
+        assert_eq!(order.filled_amount, dec!(3.75));
+    }
+
+    #[test]
+    fn next_nonce_increases() {
+        let gemini = create_gemini();
+
+        let first = gemini.next_nonce();
+        let second = gemini.next_nonce();
+
+        assert!(second > first);
+    }
+
+    #[test]
+    fn clarify_error_type_by_reason() {
+        let response = RestResponse::new(
+            r#"{"result":"error","reason":"InsufficientFunds","message":"Failed to place buy order on symbol 'BTCUSD' for price $7,225.00 and quantity 1 BTC due to insufficient funds"}"#.to_owned(),
+            StatusCode::BAD_REQUEST,
+        );
+
+        let error = ErrorHandlerGemini
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(
+            ErrorHandlerGemini.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+    }
+}
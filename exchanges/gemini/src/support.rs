@@ -0,0 +1,353 @@
+use crate::gemini::Gemini;
+use crate::types::{GeminiL2Updates, GeminiOrderEvent, GeminiPublicTrade};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderRole};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::time::u64_to_date_time;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+const L2_SUBSCRIPTION: &str = "l2";
+const L2_UPDATES_TYPE: &str = "l2_updates";
+const TRADE_TYPE: &str = "trade";
+const ORDER_EVENTS_PATH: &str = "/v1/order/events";
+
+#[async_trait]
+impl Support for Gemini {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: Value = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        // Order events are sent in arrays, other messages are sent as single objects
+        if message.is_array() {
+            for event in serde_json::from_value::<Vec<GeminiOrderEvent>>(message)? {
+                self.handle_order_event(event)?;
+            }
+            return Ok(());
+        }
+
+        let message_type = message["type"].as_str().unwrap_or_default();
+        match message_type {
+            L2_UPDATES_TYPE => self.handle_l2_updates(serde_json::from_value(message)?)?,
+            TRADE_TYPE => self.handle_trade(serde_json::from_value(message)?)?,
+            // Order events websocket sends single events in objects too
+            "accepted" | "rejected" | "booked" | "fill" | "cancelled" | "cancel_rejected"
+            | "closed" => self.handle_order_event(serde_json::from_value(message)?)?,
+            "heartbeat" | "subscription_ack" | "auction_result" | "auction_indicative" => {}
+            _ => self.log_unknown_message(self.settings.exchange_account_id, msg),
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        // New connection starts from snapshots of order books
+        self.order_book_snapshots.lock().clear();
+
+        let symbols = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .map(|currency_pair| currency_pair.as_str().to_owned())
+            .collect::<Vec<_>>();
+        if symbols.is_empty() {
+            return Ok(());
+        }
+
+        // Order events websocket is authenticated by headers and doesn't need subscription
+        let subscribe = json!({
+            "type": "subscribe",
+            "subscriptions": [{ "name": L2_SUBSCRIPTION, "symbols": symbols }],
+        });
+        (self.websocket_message_callback)(WebSocketRole::Main, subscribe.to_string())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_websocket_headers(&self, role: WebSocketRole) -> Result<Vec<(String, String)>> {
+        if role == WebSocketRole::Main {
+            return Ok(Vec::new());
+        }
+
+        let payload = self.create_payload(ORDER_EVENTS_PATH, json!({}));
+        let payload = serde_json::to_vec(&payload).context("Unable to serialize payload")?;
+
+        Ok(
+            Gemini::create_auth_headers(
+                &self.settings.api_key,
+                &self.settings.secret_key,
+                &payload,
+            )
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect(),
+        )
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains("order_id")
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Gemini {
+    /// The first message of currency pair after subscription contains full order book,
+    /// next ones contain only changed levels
+    fn handle_l2_updates(&self, updates: GeminiL2Updates) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&updates.symbol)?;
+
+        let mut order_book_data = OrderBookData::default();
+        for (side, price, amount) in updates.changes {
+            match side.as_str() {
+                "buy" => order_book_data.bids.insert(price, amount),
+                "sell" => order_book_data.asks.insert(price, amount),
+                _ => {
+                    log::warn!("Unknown side of Gemini order book change {side}");
+                    continue;
+                }
+            };
+        }
+
+        let is_snapshot = self
+            .order_book_snapshots
+            .lock()
+            .insert(updates.symbol.to_uppercase().as_str().into());
+        let event_type = match is_snapshot {
+            true => EventType::Snapshot,
+            false => EventType::Update,
+        };
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: GeminiPublicTrade) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.symbol)?,
+            Trade {
+                trade_id: TradeId::Number(trade.tid),
+                price: trade.price,
+                quantity: trade.quantity,
+                side: Gemini::get_local_order_side(&trade.side)?,
+                transaction_time: u64_to_date_time(trade.timestamp),
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, event: GeminiOrderEvent) -> Result<()> {
+        if event.client_order_id.is_empty() {
+            // Order was created outside of the bot
+            return Ok(());
+        }
+        let client_order_id = ClientOrderId::from(event.client_order_id.as_str());
+
+        match event.event_type.as_str() {
+            "accepted" => (self.order_created_callback)(
+                client_order_id,
+                event.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Maker-or-cancel orders which would be filled immediately are cancelled too
+            "cancelled" => (self.order_cancelled_callback)(
+                client_order_id,
+                event.order_id,
+                EventSourceType::WebSocket,
+            ),
+            "fill" => self.handle_fill(client_order_id, event)?,
+            // Failures of requests are reported by Rest responses
+            "rejected" | "cancel_rejected" => log::warn!(
+                "Gemini order event {} for {client_order_id} {:?}",
+                event.event_type,
+                event.order_id
+            ),
+            // Initial state of open orders, booking and closing of orders
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_fill(&self, client_order_id: ClientOrderId, event: GeminiOrderEvent) -> Result<()> {
+        let fill = event
+            .fill
+            .with_context(|| format!("Missing fill in fill event of {client_order_id}"))?;
+
+        // Check that currency pair is known before passing fill to engine
+        self.get_unified_currency_pair(&event.symbol)?;
+
+        let order_role = match fill.liquidity.as_str() {
+            "Maker" => OrderRole::Maker,
+            _ => OrderRole::Taker,
+        };
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(fill.trade_id)),
+            client_order_id: Some(client_order_id),
+            exchange_order_id: event.order_id,
+            fill_price: fill.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: fill.amount,
+                total_filled_amount: Some(event.executed_amount),
+            },
+            order_role: Some(order_role),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(fill.fee_currency.as_str().into()),
+            ),
+            commission_rate: None,
+            commission_amount: Some(fill.fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(u64_to_date_time(event.timestampms)),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn parse_market_data_messages() {
+        let msg = r#"{"type":"l2_updates","symbol":"BTCUSD","changes":[["buy","9122.04","0.00121425"],["sell","9122.07","0"]],"trades":[],"auction_events":[]}"#;
+        let message: Value = serde_json::from_str(msg).expect("in test");
+        assert_eq!(message["type"], L2_UPDATES_TYPE);
+        let updates: GeminiL2Updates = serde_json::from_str(msg).expect("in test");
+        assert_eq!(updates.symbol, "BTCUSD");
+        assert_eq!(updates.changes[0].1, dec!(9122.04));
+        assert_eq!(updates.changes[1].2, dec!(0));
+
+        let trade: GeminiPublicTrade = serde_json::from_str(
+            r#"{"type":"trade","symbol":"BTCUSD","event_id":169841458,"timestamp":1560976400428,"price":"9122.04","quantity":"0.0073173","side":"sell","tid":2840140800042677}"#,
+        )
+        .expect("in test");
+        assert_eq!(trade.tid, 2840140800042677);
+        assert_eq!(trade.quantity, dec!(0.0073173));
+    }
+
+    #[test]
+    fn parse_order_events() {
+        let msg = r#"[{"type":"fill","order_id":"109535951","client_order_id":"20190110-4738721","api_session":"account-key","symbol":"btcusd","side":"buy","order_type":"exchange limit","timestamp":"1547743216","timestampms":1547743216580,"is_live":false,"is_cancelled":false,"is_hidden":false,"avg_execution_price":"3592.00","executed_amount":"1","remaining_amount":"0","original_amount":"1","price":"3592.00","fill":{"trade_id":"109535970","liquidity":"Maker","price":"3592.00","amount":"1","fee":"0.0898","fee_currency":"USD"},"socket_sequence":81},{"type":"closed","order_id":"109535951","client_order_id":"20190110-4738721","api_session":"account-key","symbol":"btcusd","side":"buy","order_type":"exchange limit","timestamp":"1547743216","timestampms":1547743216580,"is_live":false,"is_cancelled":false,"is_hidden":false,"avg_execution_price":"3592.00","executed_amount":"1","remaining_amount":"0","original_amount":"1","price":"3592.00","socket_sequence":82}]"#;
+
+        let events: Vec<GeminiOrderEvent> = serde_json::from_str(msg).expect("in test");
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "fill");
+        assert_eq!(events[0].executed_amount, dec!(1));
+        let fill = events[0].fill.as_ref().expect("in test");
+        assert_eq!(fill.liquidity, "Maker");
+        assert_eq!(fill.fee, dec!(0.0898));
+        assert!(events[1].fill.is_none());
+    }
+}
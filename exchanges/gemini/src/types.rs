@@ -0,0 +1,218 @@
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, Price};
+use serde::Deserialize;
+
+/// Symbol description from `/v1/symbols/details/{symbol}`
+/// {
+///   "symbol": "BTCUSD",
+///   "base_currency": "BTC",
+///   "quote_currency": "USD",
+///   "tick_size": 1E-8,
+///   "quote_increment": 0.01,
+///   "min_order_size": "0.00001",
+///   "status": "open",
+///   "wrap_enabled": false
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiSymbolDetails {
+    pub(crate) symbol: String,
+    pub(crate) base_currency: String,
+    pub(crate) quote_currency: String,
+    /// Step of amount
+    pub(crate) tick_size: Amount,
+    /// Step of price
+    pub(crate) quote_increment: Price,
+    pub(crate) min_order_size: Amount,
+    /// "open", "closed", "cancel_only", "post_only" or "limit_only"
+    pub(crate) status: String,
+}
+
+/// Order status from `/v1/order/new`, `/v1/order/status` and `/v1/orders`
+/// {
+///   "order_id": "106817811",
+///   "id": "106817811",
+///   "symbol": "btcusd",
+///   "exchange": "gemini",
+///   "avg_execution_price": "3632.8508430064554",
+///   "side": "buy",
+///   "type": "exchange limit",
+///   "timestamp": "1547220404",
+///   "timestampms": 1547220404836,
+///   "is_live": true,
+///   "is_cancelled": false,
+///   "is_hidden": false,
+///   "was_forced": false,
+///   "executed_amount": "3.7567928949",
+///   "remaining_amount": "1.2432071051",
+///   "client_order_id": "20190110-4738721",
+///   "options": [],
+///   "price": "3633.00",
+///   "original_amount": "5"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiOrder {
+    pub(crate) order_id: ExchangeOrderId,
+    /// Absent for orders created outside of the bot
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+    pub(crate) symbol: String,
+    pub(crate) side: String,
+    pub(crate) is_live: bool,
+    pub(crate) is_cancelled: bool,
+    #[serde(default)]
+    pub(crate) avg_execution_price: Price,
+    #[serde(default)]
+    pub(crate) executed_amount: Amount,
+    pub(crate) original_amount: Amount,
+    #[serde(default)]
+    pub(crate) price: Price,
+}
+
+/// Trade from `/v1/mytrades`
+/// {
+///   "price": "3648.09",
+///   "amount": "0.0027343246",
+///   "timestamp": 1547232911,
+///   "timestampms": 1547232911021,
+///   "type": "Buy",
+///   "aggressor": true,
+///   "fee_currency": "USD",
+///   "fee_amount": "0.024937655575035",
+///   "tid": 107317526,
+///   "order_id": "107317524",
+///   "exchange": "gemini",
+///   "is_clearing_fill": false,
+///   "symbol": "BTCUSD",
+///   "client_order_id": "20190110-4738721"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiTrade {
+    pub(crate) price: Price,
+    pub(crate) amount: Amount,
+    pub(crate) timestampms: u64,
+    /// "Buy" or "Sell"
+    #[serde(rename = "type")]
+    pub(crate) side: String,
+    /// Taker if true
+    pub(crate) aggressor: bool,
+    pub(crate) fee_currency: String,
+    pub(crate) fee_amount: Amount,
+    pub(crate) tid: u64,
+    pub(crate) order_id: ExchangeOrderId,
+}
+
+/// Balance of currency from `/v1/balances`
+/// {
+///   "type": "exchange",
+///   "currency": "BTC",
+///   "amount": "1154.62034001",
+///   "available": "1129.10517279",
+///   "availableForWithdrawal": "1129.10517279"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiBalance {
+    pub(crate) currency: String,
+    /// Total balance including amount reserved by open orders
+    pub(crate) amount: Amount,
+}
+
+/// Event of `/v1/order/events` websocket, `fill` is present only for events of `fill` type
+/// {
+///   "type": "fill",
+///   "order_id": "109535951",
+///   "client_order_id": "20190110-4738721",
+///   "api_session": "UI",
+///   "symbol": "btcusd",
+///   "side": "buy",
+///   "order_type": "exchange limit",
+///   "timestamp": "1547743216",
+///   "timestampms": 1547743216580,
+///   "is_live": false,
+///   "is_cancelled": false,
+///   "is_hidden": false,
+///   "avg_execution_price": "3592.00",
+///   "executed_amount": "1",
+///   "remaining_amount": "0",
+///   "original_amount": "1",
+///   "price": "3592.00",
+///   "fill": {
+///     "trade_id": "109535970",
+///     "liquidity": "Maker",
+///     "price": "3592.00",
+///     "amount": "1",
+///     "fee": "0.0898",
+///     "fee_currency": "USD"
+///   },
+///   "socket_sequence": 81
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiOrderEvent {
+    /// "initial", "accepted", "rejected", "booked", "fill", "cancelled", "cancel_rejected"
+    /// or "closed"
+    #[serde(rename = "type")]
+    pub(crate) event_type: String,
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+    pub(crate) symbol: String,
+    #[serde(default)]
+    pub(crate) executed_amount: Amount,
+    pub(crate) timestampms: u64,
+    #[serde(default)]
+    pub(crate) fill: Option<GeminiOrderEventFill>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiOrderEventFill {
+    pub(crate) trade_id: String,
+    /// "Maker" or "Taker"
+    pub(crate) liquidity: String,
+    pub(crate) price: Price,
+    pub(crate) amount: Amount,
+    pub(crate) fee: Amount,
+    pub(crate) fee_currency: String,
+}
+
+/// Message of `l2` subscription of `/v2/marketdata` websocket,
+/// changes are `[side, price, amount]` and zero amount means removal of level
+/// {
+///   "type": "l2_updates",
+///   "symbol": "BTCUSD",
+///   "changes": [["buy", "9122.04", "0.00121425"], ["sell", "9122.07", "0.98942292"]]
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiL2Updates {
+    pub(crate) symbol: String,
+    pub(crate) changes: Vec<(String, Price, Amount)>,
+}
+
+/// Trade of `l2` subscription of `/v2/marketdata` websocket
+/// {
+///   "type": "trade",
+///   "symbol": "BTCUSD",
+///   "event_id": 169841458,
+///   "timestamp": 1560976400428,
+///   "price": "9122.04",
+///   "quantity": "0.0073173",
+///   "side": "sell",
+///   "tid": 2840140800042677
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiPublicTrade {
+    pub(crate) symbol: String,
+    pub(crate) timestamp: u64,
+    pub(crate) price: Price,
+    pub(crate) quantity: Amount,
+    pub(crate) side: String,
+    pub(crate) tid: u64,
+}
+
+/// Error response of REST API
+/// {"result": "error", "reason": "InsufficientFunds", "message": "Insufficient funds"}
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeminiError {
+    pub(crate) result: String,
+    #[serde(default)]
+    pub(crate) reason: String,
+    #[serde(default)]
+    pub(crate) message: String,
+}
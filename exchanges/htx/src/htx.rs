@@ -0,0 +1,869 @@
+use crate::types::{
+    HtxAccount, HtxAccountBalance, HtxError, HtxMatchResult, HtxOrder, HtxResponse, HtxSymbol,
+};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, QueryKey, RequestType, RestClient, RestHeaders, RestResponse,
+    UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, UserOrder,
+};
+use mmb_utils::time::u64_to_date_time;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use url::form_urlencoded;
+
+/// Maximum count of trades returned by `/v1/order/matchresults`
+const MATCH_RESULTS_LIMIT: u32 = 500;
+const SPOT_ACCOUNT_TYPE: &str = "spot";
+const JSON_CONTENT_TYPE: &str = "application/json";
+const SIGNATURE_METHOD: &str = "HmacSHA256";
+/// Format of timestamp in signed requests, e.g. "2017-05-11T15:19:30"
+pub(crate) const SIGNATURE_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Default)]
+pub struct ErrorHandlerHtx;
+
+impl ErrorHandler for ErrorHandlerHtx {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        // Errors are returned with status 200 and "error" status of response
+        let error: HtxError = match serde_json::from_str(&response.content) {
+            Ok(error) => error,
+            Err(_) => return Ok(()),
+        };
+
+        if error.status != "error" {
+            return Ok(());
+        }
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            format!("{}: {}", error.err_code, error.err_msg),
+            None,
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://huobiapi.github.io/docs/spot/v1/en/#error-code
+        let message = error.message.as_str();
+        if message.starts_with("base-record-invalid") || message.starts_with("order-not-found") {
+            ExchangeErrorType::OrderNotFound
+        } else if message.starts_with("order-orderstate-error") {
+            ExchangeErrorType::OrderCompleted
+        } else if message.contains("balance-insufficient") || message.contains("insufficient") {
+            ExchangeErrorType::InsufficientFunds
+        } else if message.starts_with("order-value-min-error")
+            || message.starts_with("order-limitorder-amount")
+            || message.starts_with("order-orderprice-precision-error")
+            || message.starts_with("order-orderamount-precision-error")
+            || message.starts_with("order-price-")
+            || message.starts_with("invalid-amount")
+            || message.starts_with("invalid-price")
+        {
+            ExchangeErrorType::InvalidOrder
+        } else if message.starts_with("api-signature-not-valid")
+            || message.starts_with("api-key-invalid")
+            || message.starts_with("login-required")
+            || message.starts_with("api-not-support-temp-addr")
+        {
+            ExchangeErrorType::Authentication
+        } else if message.contains("too-many-request") || message.contains("rate-limit") {
+            ExchangeErrorType::RateLimit
+        } else {
+            ExchangeErrorType::Unknown
+        }
+    }
+}
+
+/// Private requests are signed by parameters of query, so only content type is added to headers
+#[derive(Default)]
+pub struct RestHeadersHtx;
+
+impl RestHeaders for RestHeadersHtx {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+        _body: &[u8],
+    ) -> Builder {
+        builder.header(CONTENT_TYPE, JSON_CONTENT_TYPE)
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Htx {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerHtx, RestHeadersHtx>,
+    /// Specific currency pairs are symbols (e.g. "btcusdt") used in requests and websocket channels
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    // Id of spot account which is requested on first usage
+    account_id: Mutex<Option<u64>>,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Htx {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Htx {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerHtx::default(),
+                ),
+                RestHeadersHtx::default(),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+            account_id: Default::default(),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://api.huobi.pro/ws",
+            web_socket2_host: "wss://api.huobi.pro/ws/v2",
+            rest_host: "https://api.huobi.pro",
+        }
+    }
+
+    /// Base64 encoded HMAC-SHA256 of method, host, path and sorted query
+    pub(super) fn create_signature(
+        secret_key: &str,
+        method: &str,
+        host: &str,
+        path: &str,
+        query: &[u8],
+    ) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for HTX signature");
+        hmac.update(format!("{method}\n{host}\n{path}\n").as_bytes());
+        hmac.update(query);
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+
+    /// Parameters of signed request are sorted by keys, signature is added to the end of query
+    fn signed_uri(&self, method: &str, path: &str, mut params: Vec<(QueryKey, String)>) -> Uri {
+        let timestamp = Utc::now().format(SIGNATURE_TIMESTAMP_FORMAT).to_string();
+        params.extend([
+            ("AccessKeyId", self.settings.api_key.clone()),
+            ("SignatureMethod", SIGNATURE_METHOD.to_owned()),
+            ("SignatureVersion", "2".to_owned()),
+            ("Timestamp", timestamp),
+        ]);
+        params.sort_by_key(|(key, _)| *key);
+
+        let mut builder = UriBuilder::from_path(path);
+        for (key, value) in params {
+            builder.add_kv(key, url_encode(&value));
+        }
+
+        let host = self.hosts.rest_uri_host();
+        let signature = Self::create_signature(
+            &self.settings.secret_key,
+            method,
+            host,
+            path,
+            builder.query(),
+        );
+        builder.add_kv("Signature", url_encode(&signature));
+
+        builder.build_uri(host, true)
+    }
+
+    async fn get_private(
+        &self,
+        path: &str,
+        params: Vec<(QueryKey, String)>,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let uri = self.signed_uri("GET", path, params);
+        self.rest_client.get(uri, action_name, log_args).await
+    }
+
+    async fn post_private(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(body).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to serialize HTX request body: {err:?}"))
+        })?;
+        let uri = self.signed_uri("POST", path, Vec::new());
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    /// Spot account id is required by most of private requests
+    async fn get_account_id(&self) -> Result<u64, ExchangeError> {
+        if let Some(account_id) = *self.account_id.lock() {
+            return Ok(account_id);
+        }
+
+        let response = self.request_accounts().await?;
+        let account_id = Self::parse_spot_account_id(&response)
+            .map_err(|err| ExchangeError::parsing(format!("{err:?}")))?;
+        *self.account_id.lock() = Some(account_id);
+
+        Ok(account_id)
+    }
+
+    #[named]
+    async fn request_accounts(&self) -> Result<RestResponse, ExchangeError> {
+        self.get_private(
+            "/v1/account/accounts",
+            Vec::new(),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    fn parse_spot_account_id(response: &RestResponse) -> Result<u64> {
+        let accounts: HtxResponse<Vec<HtxAccount>> =
+            serde_json::from_str(&response.content).context("Unable to parse HTX accounts")?;
+
+        accounts
+            .data
+            .into_iter()
+            .find(|x| x.account_type == SPOT_ACCOUNT_TYPE)
+            .map(|x| x.id)
+            .context("Spot account wasn't found on HTX")
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/v1/common/symbols")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let symbols: HtxResponse<Vec<HtxSymbol>> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize symbols from HTX")?;
+
+        Ok(symbols
+            .data
+            .into_iter()
+            .filter(|symbol| symbol.state == "online")
+            .map(|symbol| self.parse_symbol(symbol))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, symbol: HtxSymbol) -> Arc<Symbol> {
+        let base = self
+            .currency_aliases
+            .unify(symbol.base_currency.as_str().into());
+        let quote = self
+            .currency_aliases
+            .unify(symbol.quote_currency.as_str().into());
+
+        let specific_currency_pair = symbol.symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies
+            .insert(symbol.base_currency.as_str().into(), base);
+        self.supported_currencies
+            .insert(symbol.quote_currency.as_str().into(), quote);
+
+        Arc::new(Symbol::new(
+            false,
+            symbol.base_currency.as_str().into(),
+            base,
+            symbol.quote_currency.as_str().into(),
+            quote,
+            None,
+            None,
+            symbol.min_order_amt,
+            symbol.max_order_amt,
+            symbol.min_order_value,
+            base,
+            None,
+            Precision::ByTick {
+                tick: Decimal::new(1, symbol.price_precision),
+            },
+            Precision::ByTick {
+                tick: Decimal::new(1, symbol.amount_precision),
+            },
+        ))
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/v1/common/timestamp")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        let server_time: HtxResponse<i64> =
+            serde_json::from_str(&response.content).context("Unable to parse HTX server time")?;
+
+        Ok(server_time.data)
+    }
+
+    /// Only limit orders are supported: market buy orders of HTX take cost
+    /// in quote currency instead of amount
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let (price, execution_type) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => (price, execution_type),
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let side = Self::get_server_order_side(header.side);
+        let order_type = match execution_type {
+            OrderExecutionType::MakerOnly => format!("{side}-limit-maker"),
+            OrderExecutionType::None => format!("{side}-limit"),
+        };
+
+        let body = json!({
+            "account-id": self.get_account_id().await?.to_string(),
+            "symbol": self.get_specific_currency_pair(header.currency_pair).as_str(),
+            "type": order_type,
+            "amount": header.amount.to_string(),
+            "price": price.to_string(),
+            "source": "spot-api",
+            "client-order-id": header.client_order_id.as_str(),
+        });
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_private("/v1/order/orders/place", &body, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let order_id: HtxResponse<String> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order id: {err:?}")))?;
+
+        Ok(order_id.data.as_str().into())
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = format!("/v1/order/orders/{exchange_order_id}/submitcancel");
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_private(&path, &json!({}), function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = json!({
+            "account-id": self.get_account_id().await?.to_string(),
+            "symbol": self.get_specific_currency_pair(currency_pair).as_str(),
+        });
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.post_private(
+            "/v1/order/orders/batchCancelOpenOrders",
+            &body,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut params = vec![("account-id", self.get_account_id().await?.to_string())];
+        if let Some(currency_pair) = currency_pair {
+            let specific_currency_pair = self.get_specific_currency_pair(currency_pair);
+            params.push(("symbol", specific_currency_pair.as_str().to_owned()));
+        }
+
+        self.get_private(
+            "/v1/order/openOrders",
+            params,
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: HtxResponse<Vec<HtxOrder>> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .data
+            .into_iter()
+            .map(|order| self.order_to_order_info(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let log_args = format!("order {client_order_id}");
+        self.get_private(
+            "/v1/order/orders/getClientOrder",
+            vec![("clientOrderId", client_order_id.as_str().to_owned())],
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let order: HtxResponse<HtxOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        self.order_to_order_info(order.data)
+    }
+
+    fn order_to_order_info(&self, order: HtxOrder) -> Result<OrderInfo> {
+        let average_fill_price = match order.filled_amount.is_zero() {
+            true => dec!(0),
+            false => order.filled_cash_amount / order.filled_amount,
+        };
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&order.symbol.as_str().into())?,
+            order.id,
+            ClientOrderId::from(order.client_order_id.as_str()),
+            Self::get_local_order_side(&order.order_type)?,
+            Self::get_local_order_status(&order.state)?,
+            order.price,
+            order.amount,
+            average_fill_price,
+            order.filled_amount,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    fn get_server_order_side(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    /// Side is prefix of order type, e.g. "buy-limit" or "sell-limit-maker"
+    pub(super) fn get_local_order_side(order_type: &str) -> Result<OrderSide> {
+        match order_type.split('-').next() {
+            Some("buy") => Ok(OrderSide::Buy),
+            Some("sell") => Ok(OrderSide::Sell),
+            _ => bail!("Unknown HTX order type {order_type}"),
+        }
+    }
+
+    fn get_local_order_status(state: &str) -> Result<OrderStatus> {
+        match state {
+            "created" | "submitted" | "partial-filled" => Ok(OrderStatus::Created),
+            "canceling" => Ok(OrderStatus::Canceling),
+            "filled" => Ok(OrderStatus::Completed),
+            "partial-canceled" | "canceled" => Ok(OrderStatus::Canceled),
+            _ => bail!("Unknown HTX order state {state}"),
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
+
+        let mut params = vec![
+            ("symbol", specific_currency_pair.as_str().to_owned()),
+            ("size", MATCH_RESULTS_LIMIT.to_string()),
+        ];
+        if let Some(date_time) = last_date_time {
+            params.push(("start-time", date_time.timestamp_millis().to_string()));
+        }
+
+        self.get_private(
+            "/v1/order/matchresults",
+            params,
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let trades: HtxResponse<Vec<HtxMatchResult>> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        trades
+            .data
+            .into_iter()
+            .map(|trade| {
+                Ok(OrderTrade {
+                    exchange_order_id: trade.order_id,
+                    trade_id: TradeId::Number(trade.trade_id),
+                    datetime: u64_to_date_time(trade.created_at),
+                    price: trade.price,
+                    amount: trade.filled_amount,
+                    side: Self::get_local_order_side(&trade.order_type)?,
+                    order_role: Self::get_local_order_role(&trade.role)?,
+                    fee_currency_code: self
+                        .currency_aliases
+                        .unify(trade.fee_currency.as_str().into()),
+                    fee_rate: None,
+                    fee_amount: Some(trade.filled_fees),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    fn get_local_order_role(role: &str) -> Result<OrderRole> {
+        match role {
+            "maker" => Ok(OrderRole::Maker),
+            "taker" => Ok(OrderRole::Taker),
+            _ => bail!("Unknown HTX trade role {role}"),
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let account_id = self.get_account_id().await?;
+
+        self.get_private(
+            &format!("/v1/account/accounts/{account_id}/balance"),
+            Vec::new(),
+            function_name!(),
+            "".to_string(),
+        )
+        .await
+    }
+
+    /// Available and frozen balances of currency are returned separately
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let account: HtxResponse<HtxAccountBalance> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        let mut balances: HashMap<CurrencyCode, Amount> = HashMap::new();
+        for balance in account.data.list {
+            if balance.balance_type != "trade" && balance.balance_type != "frozen" {
+                continue;
+            }
+
+            let currency_code = self
+                .currency_aliases
+                .unify(balance.currency.as_str().into());
+            *balances.entry(currency_code).or_default() += balance.balance;
+        }
+
+        Ok(balances
+            .into_iter()
+            .map(|(currency_code, balance)| ExchangeBalance {
+                currency_code,
+                balance,
+            })
+            .collect_vec())
+    }
+}
+
+/// Percent-encoding of query values, e.g. colons of timestamp and symbols of base64 signature
+pub(crate) fn url_encode(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+pub struct HtxBuilder;
+
+impl ExchangeClientBuilder for HtxBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Htx::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Most of private endpoints are limited by 50 requests per 2 seconds
+        RequestTimeoutArguments::from_requests_per_minute(1500)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Htx".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+
+    fn create_htx() -> Htx {
+        let exchange_account_id: ExchangeAccountId = "Htx_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let htx = Htx::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let response = RestResponse::new(
+            r#"{"status":"ok","data":[{"base-currency":"btc","quote-currency":"usdt","price-precision":2,"amount-precision":6,"symbol-partition":"main","symbol":"btcusdt","state":"online","value-precision":8,"min-order-amt":0.0001,"max-order-amt":1000,"min-order-value":5},{"base-currency":"old","quote-currency":"usdt","price-precision":4,"amount-precision":2,"symbol-partition":"main","symbol":"oldusdt","state":"offline","value-precision":8}]}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        let symbols = htx.parse_all_symbols(&response).expect("in test");
+        assert_eq!(symbols.len(), 1);
+
+        htx
+    }
+
+    #[test]
+    fn parse_symbols() {
+        let htx = create_htx();
+        let btc_usdt = CurrencyPair::from_codes("btc".into(), "usdt".into());
+
+        assert_eq!(htx.get_specific_currency_pair(btc_usdt).as_str(), "btcusdt");
+        assert!(htx.get_unified_currency_pair(&"oldusdt".into()).is_err());
+    }
+
+    #[test]
+    fn parse_order_info() {
+        let htx = create_htx();
+        let response = RestResponse::new(
+            r#"{"status":"ok","data":{"id":357632718898331,"symbol":"btcusdt","account-id":13496526,"client-order-id":"23456","amount":"0.500000000000000000","price":"100.000000000000000000","created-at":1630649406687,"type":"sell-limit-maker","field-amount":"0.2","field-cash-amount":"21.0","field-fees":"0.0","finished-at":0,"source":"spot-api","state":"partial-filled","canceled-at":0}}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let order = htx.parse_order_info(&response).expect("in test");
+
+        assert_eq!(order.exchange_order_id.as_str(), "357632718898331");
+        assert_eq!(order.client_order_id.as_str(), "23456");
+        assert_eq!(order.order_side, OrderSide::Sell);
+        assert_eq!(order.order_status, OrderStatus::Created);
+        assert_eq!(order.amount, dec!(0.5));
+        assert_eq!(order.filled_amount, dec!(0.2));
+        assert_eq!(order.average_fill_price, dec!(105));
+    }
+
+    #[test]
+    fn parse_balance_sums_available_and_frozen() {
+        let htx = create_htx();
+        let response = RestResponse::new(
+            r#"{"status":"ok","data":{"id":1000001,"type":"spot","state":"working","list":[{"currency":"usdt","type":"trade","balance":"91.5"},{"currency":"usdt","type":"frozen","balance":"5.5"},{"currency":"btc","type":"trade","balance":"0.1"}]}}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let balances = htx.parse_get_balance(&response).expect("in test");
+
+        let usdt = balances
+            .iter()
+            .find(|x| x.currency_code == "usdt".into())
+            .expect("in test");
+        assert_eq!(usdt.balance, dec!(97));
+        assert_eq!(balances.len(), 2);
+    }
+
+    #[test]
+    fn clarify_error_type_by_code() {
+        let response = RestResponse::new(
+            r#"{"status":"error","err-code":"account-frozen-balance-insufficient-error","err-msg":"trade account balance is not enough, left: `1.0`","data":null}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let error = ErrorHandlerHtx
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(
+            ErrorHandlerHtx.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+    }
+}
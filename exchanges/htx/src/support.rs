@@ -0,0 +1,444 @@
+use crate::htx::{url_encode, Htx, SIGNATURE_TIMESTAMP_FORMAT};
+use crate::types::{
+    HtxMarketMessage, HtxOrderBook, HtxOrderUpdate, HtxPrivateMessage, HtxTradeClearing,
+    HtxTradeDetail,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketCompression, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::{ClientOrderId, OrderRole, OrderSide};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::time::u64_to_date_time;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+const MARKET_CHANNEL_PREFIX: &str = "market.";
+const ORDER_BOOK_CHANNEL_SUFFIX: &str = ".mbp.refresh.20";
+const TRADE_DETAIL_CHANNEL_SUFFIX: &str = ".trade.detail";
+const ORDERS_CHANNEL: &str = "orders#";
+const TRADE_CLEARING_CHANNEL: &str = "trade.clearing#";
+/// Code of successful responses of private websocket
+const SUCCESS_CODE: u32 = 200;
+
+#[async_trait]
+impl Support for Htx {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    /// Messages of private websocket (API v2) have `action`, messages of market data don't
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: Value = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message.get("action") {
+            Some(_) => self.handle_private_message(serde_json::from_value(message)?, msg),
+            None => self.handle_market_message(serde_json::from_value(message)?, msg),
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let currency_pairs = self.traded_specific_currencies.lock().clone();
+        for currency_pair in &currency_pairs {
+            for channel_suffix in [ORDER_BOOK_CHANNEL_SUFFIX, TRADE_DETAIL_CHANNEL_SUFFIX] {
+                let channel = format!("{MARKET_CHANNEL_PREFIX}{currency_pair}{channel_suffix}");
+                let subscribe = json!({ "sub": channel, "id": channel });
+                (self.websocket_message_callback)(WebSocketRole::Main, subscribe.to_string())?;
+            }
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Secondary) {
+            return Ok(());
+        }
+
+        // Private channels are subscribed after successful authentication
+        let auth = self.create_websocket_auth_message()?;
+        (self.websocket_message_callback)(WebSocketRole::Secondary, auth.to_string())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    /// Only messages of market data websocket are gzip compressed
+    fn get_websocket_compression(&self, role: WebSocketRole) -> Option<WebSocketCompression> {
+        match role {
+            WebSocketRole::Main => Some(WebSocketCompression::Gzip),
+            WebSocketRole::Secondary => None,
+        }
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(ORDERS_CHANNEL) || message.contains(TRADE_CLEARING_CHANNEL)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Htx {
+    /// Authentication of private websocket is signed like REST requests
+    /// but with signature version 2.1 and camel case parameters
+    fn create_websocket_auth_message(&self) -> Result<Value> {
+        let url = Url::parse(self.hosts.web_socket2_host)
+            .context("Unable parse HTX private websocket uri")?;
+        let host = url.host_str().unwrap_or_default();
+        let timestamp = Utc::now().format(SIGNATURE_TIMESTAMP_FORMAT).to_string();
+
+        let query = format!(
+            "accessKey={}&signatureMethod=HmacSHA256&signatureVersion=2.1&timestamp={}",
+            url_encode(&self.settings.api_key),
+            url_encode(&timestamp)
+        );
+        let signature = Htx::create_signature(
+            &self.settings.secret_key,
+            "GET",
+            host,
+            url.path(),
+            query.as_bytes(),
+        );
+
+        Ok(json!({
+            "action": "req",
+            "ch": "auth",
+            "params": {
+                "authType": "api",
+                "accessKey": self.settings.api_key,
+                "signatureMethod": "HmacSHA256",
+                "signatureVersion": "2.1",
+                "timestamp": timestamp,
+                "signature": signature,
+            },
+        }))
+    }
+
+    fn handle_market_message(&self, message: HtxMarketMessage, msg: &str) -> Result<()> {
+        if let Some(ping) = message.ping {
+            let pong = json!({ "pong": ping });
+            return (self.websocket_message_callback)(WebSocketRole::Main, pong.to_string());
+        }
+
+        if message.status.as_deref() == Some("error") {
+            bail!("HTX websocket error: {msg}");
+        }
+
+        let channel = match &message.ch {
+            Some(channel) => channel.as_str(),
+            // Responses of subscriptions
+            None => return Ok(()),
+        };
+
+        if channel.ends_with(ORDER_BOOK_CHANNEL_SUFFIX) {
+            let specific_currency_pair =
+                get_channel_symbol(channel, ORDER_BOOK_CHANNEL_SUFFIX).into();
+            self.handle_order_book(
+                specific_currency_pair,
+                serde_json::from_value(message.tick)?,
+            )
+        } else if channel.ends_with(TRADE_DETAIL_CHANNEL_SUFFIX) {
+            let specific_currency_pair =
+                get_channel_symbol(channel, TRADE_DETAIL_CHANNEL_SUFFIX).into();
+            self.handle_trades(
+                specific_currency_pair,
+                serde_json::from_value(message.tick)?,
+            )
+        } else {
+            self.log_unknown_message(self.settings.exchange_account_id, msg);
+            Ok(())
+        }
+    }
+
+    fn handle_private_message(&self, message: HtxPrivateMessage, msg: &str) -> Result<()> {
+        match message.action.as_str() {
+            "ping" => {
+                let pong = json!({ "action": "pong", "data": message.data });
+                (self.websocket_message_callback)(WebSocketRole::Secondary, pong.to_string())?;
+            }
+            "req" | "sub" if message.code != Some(SUCCESS_CODE) => bail!(
+                "HTX private websocket error: {}",
+                message.message.unwrap_or_else(|| msg.to_owned())
+            ),
+            "req" if message.ch == "auth" => self.subscribe_private_channels()?,
+            "push" if message.ch.starts_with(ORDERS_CHANNEL) => {
+                self.handle_order_update(serde_json::from_value(message.data)?)
+            }
+            "push" if message.ch.starts_with(TRADE_CLEARING_CHANNEL) => {
+                self.handle_trade_clearing(serde_json::from_value(message.data)?)
+            }
+            "sub" => {}
+            _ => self.log_unknown_message(self.settings.exchange_account_id, msg),
+        }
+
+        Ok(())
+    }
+
+    fn subscribe_private_channels(&self) -> Result<()> {
+        let currency_pairs = self.traded_specific_currencies.lock().clone();
+        for currency_pair in &currency_pairs {
+            for channel in [
+                format!("{ORDERS_CHANNEL}{currency_pair}"),
+                format!("{TRADE_CLEARING_CHANNEL}{currency_pair}#0"),
+            ] {
+                let subscribe = json!({ "action": "sub", "ch": channel });
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Every message of order book channel contains top 20 levels of order book,
+    /// sequence number is used as event id
+    fn handle_order_book(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        order_book: HtxOrderBook,
+    ) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            order_book.seq_num.to_string(),
+            EventType::Snapshot,
+            Arc::new(OrderBookData::new(
+                order_book.asks.into_iter().collect(),
+                order_book.bids.into_iter().collect(),
+            )),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trades(
+        &self,
+        specific_currency_pair: SpecificCurrencyPair,
+        trades: HtxTradeDetail,
+    ) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+
+        for trade in trades.data {
+            (self.handle_trade_callback)(
+                currency_pair,
+                Trade {
+                    trade_id: TradeId::Number(trade.trade_id),
+                    price: trade.price,
+                    quantity: trade.amount,
+                    side: match trade.direction.as_str() {
+                        "buy" => OrderSide::Buy,
+                        _ => OrderSide::Sell,
+                    },
+                    transaction_time: u64_to_date_time(trade.ts),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_update(&self, order: HtxOrderUpdate) {
+        if order.client_order_id.is_empty() {
+            // Order was created outside of the bot
+            return;
+        }
+        let client_order_id = ClientOrderId::from(order.client_order_id.as_str());
+
+        match order.event_type.as_str() {
+            "creation" => (self.order_created_callback)(
+                client_order_id,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            "cancellation" => (self.order_cancelled_callback)(
+                client_order_id,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are handled from trade clearing channel because it contains fees
+            _ => {}
+        }
+    }
+
+    fn handle_trade_clearing(&self, trade: HtxTradeClearing) {
+        if trade.event_type != "trade" || trade.client_order_id.is_empty() {
+            // Cancellation of trade or order created outside of the bot
+            return;
+        }
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::Number(trade.trade_id)),
+            client_order_id: Some(ClientOrderId::from(trade.client_order_id.as_str())),
+            exchange_order_id: trade.order_id,
+            fill_price: trade.trade_price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: trade.trade_volume,
+                total_filled_amount: None,
+            },
+            order_role: Some(match trade.aggressor {
+                true => OrderRole::Taker,
+                false => OrderRole::Maker,
+            }),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(trade.fee_currency.as_str().into()),
+            ),
+            commission_rate: None,
+            commission_amount: Some(trade.transact_fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(u64_to_date_time(trade.trade_time)),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+    }
+}
+
+/// Symbol of market data channel, e.g. "btcusdt" of "market.btcusdt.trade.detail"
+fn get_channel_symbol<'a>(channel: &'a str, suffix: &str) -> &'a str {
+    channel
+        .trim_start_matches(MARKET_CHANNEL_PREFIX)
+        .trim_end_matches(suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn parse_market_data_messages() {
+        let message: HtxMarketMessage = serde_json::from_str(
+            r#"{"ch":"market.btcusdt.mbp.refresh.20","ts":1573199608679,"tick":{"seqNum":100020146795,"bids":[[37000.0,0.1],[36999.5,0.2]],"asks":[[37001.0,0.3]]}}"#,
+        )
+        .expect("in test");
+        let channel = message.ch.expect("in test");
+        assert_eq!(
+            get_channel_symbol(&channel, ORDER_BOOK_CHANNEL_SUFFIX),
+            "btcusdt"
+        );
+        let order_book: HtxOrderBook = serde_json::from_value(message.tick).expect("in test");
+        assert_eq!(order_book.seq_num, 100020146795);
+        assert_eq!(order_book.bids[1], (dec!(36999.5), dec!(0.2)));
+
+        let message: HtxMarketMessage = serde_json::from_str(
+            r#"{"ch":"market.btcusdt.trade.detail","ts":1630994963175,"tick":{"id":137005445109,"ts":1630994963173,"data":[{"id":137005445109359286410323766,"ts":1630994963173,"tradeId":102523573486,"amount":0.006754,"price":52648.62,"direction":"buy"}]}}"#,
+        )
+        .expect("in test");
+        let trades: HtxTradeDetail = serde_json::from_value(message.tick).expect("in test");
+        assert_eq!(trades.data[0].trade_id, 102523573486);
+        assert_eq!(trades.data[0].price, dec!(52648.62));
+
+        let message: HtxMarketMessage =
+            serde_json::from_str(r#"{"ping":1492420473027}"#).expect("in test");
+        assert_eq!(message.ping, Some(1492420473027));
+    }
+
+    #[test]
+    fn parse_private_messages() {
+        let message: HtxPrivateMessage = serde_json::from_str(
+            r#"{"action":"push","ch":"trade.clearing#btcusdt#0","data":{"eventType":"trade","symbol":"btcusdt","orderId":99998888,"tradePrice":"37000","tradeVolume":"0.05","orderSide":"buy","orderType":"buy-limit","aggressor":false,"tradeId":919219323232,"tradeTime":1583853365586,"transactFee":"0.0001","feeCurrency":"btc","feeDeduct":"0","feeDeductType":"","clientOrderId":"123123","orderPrice":"37000","orderSize":"0.1","orderStatus":"partial-filled"}}"#,
+        )
+        .expect("in test");
+        assert_eq!(message.action, "push");
+        let trade: HtxTradeClearing = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(trade.order_id.as_str(), "99998888");
+        assert_eq!(trade.trade_volume, dec!(0.05));
+        assert_eq!(trade.transact_fee, dec!(0.0001));
+        assert!(!trade.aggressor);
+
+        let message: HtxPrivateMessage = serde_json::from_str(
+            r#"{"action":"push","ch":"orders#btcusdt","data":{"eventType":"cancellation","symbol":"btcusdt","orderId":2039486,"clientOrderId":"123123","orderSide":"buy","orderStatus":"canceled","remainAmt":"0.5","lastActTime":1583853365586}}"#,
+        )
+        .expect("in test");
+        let order: HtxOrderUpdate = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(order.event_type, "cancellation");
+        assert_eq!(order.client_order_id, "123123");
+    }
+}
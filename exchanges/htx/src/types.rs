@@ -0,0 +1,311 @@
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, Price};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Order ids are numbers in most responses and strings in others
+fn deserialize_order_id<'de, D>(deserializer: D) -> Result<ExchangeOrderId, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(id) => Ok(id.as_str().into()),
+        Value::Number(id) => Ok(id.to_string().as_str().into()),
+        value => Err(serde::de::Error::custom(format!(
+            "Unexpected HTX order id {value}"
+        ))),
+    }
+}
+
+/// Successful responses of REST API wrap result in `data`
+/// {"status": "ok", "data": ...}
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxResponse<T> {
+    pub(crate) data: T,
+}
+
+/// Symbol from `/v1/common/symbols`
+/// {
+///   "base-currency": "btc",
+///   "quote-currency": "usdt",
+///   "price-precision": 2,
+///   "amount-precision": 6,
+///   "symbol-partition": "main",
+///   "symbol": "btcusdt",
+///   "state": "online",
+///   "value-precision": 8,
+///   "min-order-amt": 0.0001,
+///   "max-order-amt": 1000,
+///   "min-order-value": 5
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct HtxSymbol {
+    pub(crate) base_currency: String,
+    pub(crate) quote_currency: String,
+    pub(crate) price_precision: u32,
+    pub(crate) amount_precision: u32,
+    /// "btcusdt", used in requests and names of websocket channels
+    pub(crate) symbol: String,
+    /// "online", "offline", "pre-online" or "suspend"
+    pub(crate) state: String,
+    #[serde(default)]
+    pub(crate) min_order_amt: Option<Amount>,
+    #[serde(default)]
+    pub(crate) max_order_amt: Option<Amount>,
+    /// Minimum cost of order in quote currency
+    #[serde(default)]
+    pub(crate) min_order_value: Option<Price>,
+}
+
+/// Account from `/v1/account/accounts`
+/// {"id": 10000001, "type": "spot", "subtype": "", "state": "working"}
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxAccount {
+    pub(crate) id: u64,
+    /// "spot", "margin", "otc" and others
+    #[serde(rename = "type")]
+    pub(crate) account_type: String,
+}
+
+/// Order from `/v1/order/openOrders` and `/v1/order/orders/getClientOrder`.
+/// Filled amounts are named `filled-*` in open orders and `field-*` in order details
+/// {
+///   "id": 357632718898331,
+///   "symbol": "btcusdt",
+///   "account-id": 13496526,
+///   "client-order-id": "23456",
+///   "amount": "5.000000000000000000",
+///   "price": "1.000000000000000000",
+///   "created-at": 1630649406687,
+///   "type": "buy-limit-maker",
+///   "field-amount": "0.0",
+///   "field-cash-amount": "0.0",
+///   "field-fees": "0.0",
+///   "source": "spot-api",
+///   "state": "submitted"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct HtxOrder {
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) id: ExchangeOrderId,
+    pub(crate) symbol: String,
+    /// Empty for orders created outside of the bot
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+    pub(crate) amount: Amount,
+    pub(crate) price: Price,
+    /// "buy-limit", "sell-limit-maker", "buy-market" and others
+    #[serde(rename = "type")]
+    pub(crate) order_type: String,
+    #[serde(alias = "field-amount")]
+    pub(crate) filled_amount: Amount,
+    /// Filled cost in quote currency
+    #[serde(alias = "field-cash-amount")]
+    pub(crate) filled_cash_amount: Amount,
+    /// "created", "submitted", "partial-filled", "filled", "partial-canceled",
+    /// "canceling" or "canceled"
+    pub(crate) state: String,
+}
+
+/// Trade from `/v1/order/matchresults`
+/// {
+///   "symbol": "btcusdt",
+///   "fee-currency": "btc",
+///   "source": "spot-api",
+///   "price": "37000.0",
+///   "created-at": 1629443051839,
+///   "role": "taker",
+///   "order-id": 345487249132375,
+///   "match-id": 5014,
+///   "trade-id": 1085,
+///   "filled-amount": "0.01",
+///   "filled-fees": "0.00002",
+///   "id": 313288753120940,
+///   "type": "buy-limit"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct HtxMatchResult {
+    pub(crate) fee_currency: String,
+    pub(crate) price: Price,
+    pub(crate) created_at: u64,
+    /// "maker" or "taker"
+    pub(crate) role: String,
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) trade_id: u64,
+    pub(crate) filled_amount: Amount,
+    pub(crate) filled_fees: Amount,
+    #[serde(rename = "type")]
+    pub(crate) order_type: String,
+}
+
+/// Response of `/v1/account/accounts/{account-id}/balance`
+/// {
+///   "id": 1000001,
+///   "type": "spot",
+///   "state": "working",
+///   "list": [
+///     {"currency": "usdt", "type": "trade", "balance": "91.850043797676510303"},
+///     {"currency": "usdt", "type": "frozen", "balance": "5.160000000000000015"}
+///   ]
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxAccountBalance {
+    pub(crate) list: Vec<HtxBalance>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxBalance {
+    pub(crate) currency: String,
+    /// "trade" for available balance and "frozen" for balance reserved by orders
+    #[serde(rename = "type")]
+    pub(crate) balance_type: String,
+    pub(crate) balance: Amount,
+}
+
+/// Error response of REST API, returned with status 200
+/// {"status": "error", "err-code": "order-value-min-error", "err-msg": "Order total cannot be lower than: `5`", "data": null}
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct HtxError {
+    #[serde(default)]
+    pub(crate) status: String,
+    #[serde(default)]
+    pub(crate) err_code: String,
+    #[serde(default)]
+    pub(crate) err_msg: String,
+}
+
+/// Messages of market data websocket, they are sent gzip compressed
+/// {"ch": "market.btcusdt.trade.detail", "ts": 1630994963175, "tick": {...}}
+/// {"id": "1", "status": "ok", "subbed": "market.btcusdt.trade.detail", "ts": 1489474081631}
+/// {"status": "error", "err-code": "bad-request", "err-msg": "invalid topic", "ts": 1494301904959}
+/// {"ping": 1492420473027}
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxMarketMessage {
+    #[serde(default)]
+    pub(crate) ch: Option<String>,
+    #[serde(default)]
+    pub(crate) tick: Value,
+    #[serde(default)]
+    pub(crate) ping: Option<u64>,
+    #[serde(default)]
+    pub(crate) status: Option<String>,
+}
+
+/// Data of `market.{symbol}.mbp.refresh.20` channel with top 20 levels of order book
+/// {
+///   "ch": "market.btcusdt.mbp.refresh.20",
+///   "ts": 1573199608679,
+///   "tick": {
+///     "seqNum": 100020146795,
+///     "bids": [[37000.0, 0.1]],
+///     "asks": [[37001.0, 0.2]]
+///   }
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HtxOrderBook {
+    pub(crate) seq_num: u64,
+    pub(crate) bids: Vec<(Price, Amount)>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
+/// Data of `market.{symbol}.trade.detail` channel
+/// {
+///   "ch": "market.btcusdt.trade.detail",
+///   "ts": 1630994963175,
+///   "tick": {
+///     "id": 137005445109,
+///     "ts": 1630994963173,
+///     "data": [{"id": 137005445109359286410323766, "ts": 1630994963173, "tradeId": 102523573486, "amount": 0.006754, "price": 52648.62, "direction": "buy"}]
+///   }
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxTradeDetail {
+    pub(crate) data: Vec<HtxPublicTrade>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HtxPublicTrade {
+    pub(crate) trade_id: u64,
+    pub(crate) ts: u64,
+    pub(crate) amount: Amount,
+    pub(crate) price: Price,
+    /// "buy" or "sell"
+    pub(crate) direction: String,
+}
+
+/// Messages of private websocket (API v2)
+/// {"action": "push", "ch": "orders#btcusdt", "data": {...}}
+/// {"action": "req", "code": 200, "ch": "auth", "data": {}}
+/// {"action": "ping", "data": {"ts": 1575537778295}}
+#[derive(Deserialize, Debug)]
+pub(crate) struct HtxPrivateMessage {
+    pub(crate) action: String,
+    #[serde(default)]
+    pub(crate) ch: String,
+    #[serde(default)]
+    pub(crate) code: Option<u32>,
+    #[serde(default)]
+    pub(crate) message: Option<String>,
+    #[serde(default)]
+    pub(crate) data: Value,
+}
+
+/// Data of `orders#{symbol}` channel, events are "creation", "trade" and "cancellation"
+/// {
+///   "eventType": "cancellation",
+///   "symbol": "btcusdt",
+///   "orderId": 2039486,
+///   "clientOrderId": "123123",
+///   "orderSide": "buy",
+///   "orderStatus": "canceled",
+///   "remainAmt": "0.5",
+///   "lastActTime": 1583853365586
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HtxOrderUpdate {
+    pub(crate) event_type: String,
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+}
+
+/// Data of `trade.clearing#{symbol}#0` channel
+/// {
+///   "eventType": "trade",
+///   "symbol": "btcusdt",
+///   "orderId": 99998888,
+///   "tradePrice": "37000",
+///   "tradeVolume": "0.05",
+///   "orderSide": "buy",
+///   "aggressor": true,
+///   "tradeId": 919219323232,
+///   "tradeTime": 998787897878,
+///   "transactFee": "19.88",
+///   "feeCurrency": "btc",
+///   "clientOrderId": "123123"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HtxTradeClearing {
+    pub(crate) event_type: String,
+    #[serde(deserialize_with = "deserialize_order_id")]
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) trade_price: Price,
+    pub(crate) trade_volume: Amount,
+    /// Taker if true
+    pub(crate) aggressor: bool,
+    pub(crate) trade_id: u64,
+    pub(crate) trade_time: u64,
+    pub(crate) transact_fee: Amount,
+    pub(crate) fee_currency: String,
+    #[serde(default)]
+    pub(crate) client_order_id: String,
+}
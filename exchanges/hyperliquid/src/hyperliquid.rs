@@ -0,0 +1,1068 @@
+use crate::types::{
+    to_wire, Action, CancelWire, HyperliquidClearinghouseState, HyperliquidExchangeResponse,
+    HyperliquidFill, HyperliquidMarket, HyperliquidMeta, HyperliquidOrder, HyperliquidOrderStatus,
+    HyperliquidOrderStatusResponse, OrderTypeWire, OrderWire, TimeInForce,
+};
+use crate::wallet::Wallet;
+use anyhow::{anyhow, bail, Context, Result};
+use dashmap::DashMap;
+use ethers_core::types::H128;
+use ethers_core::utils::keccak256;
+use function_name::named;
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleCashFlowCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::misc::time::time_manager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_utils::infrastructure::WithExpect;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerHyperliquid;
+
+impl ErrorHandler for ErrorHandlerHyperliquid {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        // Info responses have different formats, so only common fields of exchange responses
+        // are checked: status of request and statuses of orders in the action
+        let response: Value = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse Hyperliquid response: {err:?}"))
+        })?;
+
+        if response["status"] == "err" {
+            let message = match &response["response"] {
+                Value::String(message) => message.clone(),
+                other => other.to_string(),
+            };
+            return Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                message,
+                None,
+            ));
+        }
+
+        let order_error = response["response"]["data"]["statuses"]
+            .as_array()
+            .and_then(|statuses| statuses.iter().find_map(|status| status["error"].as_str()));
+        match order_error {
+            Some(message) => Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                message.to_owned(),
+                None,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Hyperliquid errors don't have codes, so they are recognized by message
+        let message = error.message.to_lowercase();
+        if message.contains("never placed, already canceled, or filled") {
+            ExchangeErrorType::OrderNotFound
+        } else if message.contains("insufficient") {
+            ExchangeErrorType::InsufficientFunds
+        } else if message.contains("post only")
+            || message.contains("invalid")
+            || message.contains("minimum value")
+            || message.contains("tick size")
+            || message.contains("could not immediately match")
+        {
+            ExchangeErrorType::InvalidOrder
+        } else if message.contains("too many") || message.contains("rate limit") {
+            ExchangeErrorType::RateLimit
+        } else if message.contains("does not exist") {
+            ExchangeErrorType::Authentication
+        } else {
+            ExchangeErrorType::Unknown
+        }
+    }
+}
+
+/// Requests are authenticated by signature in body, so only content type is specified
+#[derive(Default)]
+pub struct RestHeadersHyperliquid;
+
+impl RestHeaders for RestHeadersHyperliquid {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+        _body: &[u8],
+    ) -> Builder {
+        builder.header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+/// Prices can have up to 5 significant figures and up to `6 - sz_decimals` decimal places
+const PRICE_SIGNIFICANT_FIGURES: u32 = 5;
+const MAX_PRICE_DECIMALS: u32 = 6;
+/// Minimum notional value of order in USD
+const MIN_ORDER_VALUE: Decimal = dec!(10);
+/// Deviation from mid price for market orders, which are sent as IOC limit orders
+const MARKET_ORDER_SLIPPAGE: Decimal = dec!(0.05);
+
+pub struct Hyperliquid {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerHyperliquid, RestHeadersHyperliquid>,
+    pub(crate) wallet: Wallet,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    markets: RwLock<HashMap<CurrencyPair, HyperliquidMarket>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    /// Client order ids are 128 bit hex strings, so `ClientOrderId` is restored by them
+    client_order_ids: DashMap<String, ClientOrderId>,
+    /// Nonce of signed action should be unique, so the last one is kept
+    last_nonce: AtomicU64,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(crate) handle_cash_flow_callback: HandleCashFlowCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Hyperliquid {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Hyperliquid {
+        let wallet = Wallet::new(&settings.secret_key, &settings.api_key).with_expect(|| {
+            format!(
+                "Failed to create wallet for {}",
+                settings.exchange_account_id
+            )
+        });
+
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerHyperliquid::default(),
+                ),
+                RestHeadersHyperliquid::default(),
+                &settings.network,
+            ),
+            // Perpetuals are quoted in USD and margined in USDC
+            currency_aliases: CurrencyAliases::new(&[("usdc", "usd")], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            wallet,
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            markets: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            client_order_ids: Default::default(),
+            last_nonce: AtomicU64::new(0),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_cash_flow_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Public and user channels are available via the same websocket endpoint
+        Hosts {
+            web_socket_host: "wss://api.hyperliquid.xyz/ws",
+            web_socket2_host: "wss://api.hyperliquid.xyz/ws",
+            rest_host: "https://api.hyperliquid.xyz",
+        }
+    }
+
+    /// Client order id of Hyperliquid is derived from `ClientOrderId`,
+    /// mapping is kept to restore `ClientOrderId` from order updates
+    fn register_cloid(&self, client_order_id: &ClientOrderId) -> String {
+        let hash = keccak256(client_order_id.as_str());
+        let cloid = format!("{:?}", H128::from_slice(&hash[..16]));
+        self.client_order_ids
+            .insert(cloid.clone(), client_order_id.clone());
+
+        cloid
+    }
+
+    /// Orders created outside of the engine get `ClientOrderId` equal to order id
+    pub(crate) fn get_client_order_id(&self, order: &HyperliquidOrder) -> ClientOrderId {
+        order
+            .cloid
+            .as_ref()
+            .and_then(|cloid| self.client_order_ids.get(cloid))
+            .map(|client_order_id| client_order_id.clone())
+            .unwrap_or_else(|| ClientOrderId::from(order.oid))
+    }
+
+    pub(crate) fn to_exchange_order_id(oid: u64) -> ExchangeOrderId {
+        ExchangeOrderId::from(oid.to_string().as_str())
+    }
+
+    fn parse_oid(exchange_order_id: &ExchangeOrderId) -> Result<u64, ExchangeError> {
+        exchange_order_id.as_str().parse().map_err(|_| {
+            ExchangeError::unknown(&format!(
+                "Unexpected Hyperliquid order id {exchange_order_id}"
+            ))
+        })
+    }
+
+    fn next_nonce(&self) -> u64 {
+        let now = time_manager::now().timestamp_millis() as u64;
+        let previous = self
+            .last_nonce
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(now.max(last + 1))
+            })
+            .expect("Nonce update function always returns value");
+
+        now.max(previous + 1)
+    }
+
+    fn get_market(&self, currency_pair: CurrencyPair) -> Result<HyperliquidMarket> {
+        self.markets
+            .read()
+            .get(&currency_pair)
+            .cloned()
+            .with_context(|| format!("Unknown Hyperliquid market for {currency_pair}"))
+    }
+
+    /// All info requests are sent to the same endpoint, request type is specified in body
+    async fn request_info(
+        &self,
+        body: Value,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/info").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(
+                uri,
+                Some(Bytes::from(body.to_string())),
+                action_name,
+                log_args,
+            )
+            .await
+    }
+
+    #[named]
+    async fn send_action(
+        &self,
+        action: Action,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = self
+            .wallet
+            .sign_action(&action, self.next_nonce())
+            .map_err(|err| ExchangeError::authentication(format!("{err:?}")))?;
+        let uri = UriBuilder::from_path("/exchange").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(
+                uri,
+                Some(Bytes::from(body.to_string())),
+                function_name!(),
+                log_args,
+            )
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        self.request_info(json!({ "type": "meta" }), function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let meta: HyperliquidMeta = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from Hyperliquid")?;
+
+        meta.universe
+            .into_iter()
+            .enumerate()
+            .filter(|(_, asset)| !asset.is_delisted)
+            .map(|(asset_id, asset)| {
+                let market = HyperliquidMarket {
+                    asset_id: asset_id as u32,
+                    sz_decimals: asset.sz_decimals,
+                };
+                self.parse_symbol(&asset.name, market)
+            })
+            .try_collect()
+    }
+
+    fn parse_symbol(&self, coin: &str, market: HyperliquidMarket) -> Result<Arc<Symbol>> {
+        let quote_id = "USDC";
+        let base = self.currency_aliases.unify(coin.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+        let amount_tick = Decimal::new(1, market.sz_decimals);
+
+        let symbol = Symbol::new(
+            true,
+            coin.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            Some(amount_tick),
+            None,
+            Some(MIN_ORDER_VALUE),
+            base,
+            Some(quote),
+            Precision::ByMantissa {
+                precision: PRICE_SIGNIFICANT_FIGURES as u8,
+            },
+            Precision::ByTick { tick: amount_tick },
+        );
+
+        let specific_currency_pair = coin.into();
+        let unified_currency_pair = symbol.currency_pair();
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+        self.markets.write().insert(unified_currency_pair, market);
+
+        self.supported_currencies.insert(coin.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Ok(Arc::new(symbol))
+    }
+
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let header = order.header();
+        let cloid = self.register_cloid(&header.client_order_id);
+
+        let (price, time_in_force) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => match execution_type {
+                OrderExecutionType::MakerOnly => (price, TimeInForce::Alo),
+                _ => (price, TimeInForce::Gtc),
+            },
+            OrderOptions::User(UserOrder::Market) => {
+                let price = self
+                    .get_market_order_price(header.currency_pair, header.side)
+                    .await?;
+                (price, TimeInForce::Ioc)
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        let oid = self
+            .place_order(
+                header.currency_pair,
+                cloid,
+                header.side,
+                price,
+                header.amount,
+                time_in_force,
+                false,
+                log_args,
+            )
+            .await?;
+
+        Ok(Hyperliquid::to_exchange_order_id(oid))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn place_order(
+        &self,
+        currency_pair: CurrencyPair,
+        cloid: String,
+        side: OrderSide,
+        price: Price,
+        amount: Amount,
+        time_in_force: TimeInForce,
+        reduce_only: bool,
+        log_args: String,
+    ) -> Result<u64, ExchangeError> {
+        let market = self
+            .get_market(currency_pair)
+            .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+
+        let action = Action::Order {
+            orders: vec![OrderWire {
+                asset: market.asset_id,
+                is_buy: side == OrderSide::Buy,
+                price: to_wire(Hyperliquid::round_price(price, market.sz_decimals)),
+                size: to_wire(amount.round_dp(market.sz_decimals)),
+                reduce_only,
+                order_type: OrderTypeWire::Limit { tif: time_in_force },
+                cloid,
+            }],
+            grouping: "na",
+        };
+
+        let response = self.send_action(action, log_args).await?;
+        let statuses = Hyperliquid::parse_statuses(&response)?;
+        match statuses.into_iter().next() {
+            Some(HyperliquidOrderStatus::Resting { oid })
+            | Some(HyperliquidOrderStatus::Filled { oid }) => Ok(oid),
+            status => Err(ExchangeError::unknown(&format!(
+                "Unexpected status of created order {status:?}"
+            ))),
+        }
+    }
+
+    fn parse_statuses(
+        response: &RestResponse,
+    ) -> Result<Vec<HyperliquidOrderStatus>, ExchangeError> {
+        let response: HyperliquidExchangeResponse = serde_json::from_str(&response.content)
+            .map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse Hyperliquid response: {err:?}"))
+            })?;
+
+        Ok(response
+            .response
+            .data
+            .map(|data| data.statuses)
+            .unwrap_or_default())
+    }
+
+    /// Price is rounded to 5 significant figures and allowed count of decimal places.
+    /// Integer prices are allowed regardless of significant figures
+    pub(crate) fn round_price(price: Price, sz_decimals: u32) -> Price {
+        let max_decimals = MAX_PRICE_DECIMALS.saturating_sub(sz_decimals);
+        let price = match price.trunc() == price {
+            true => price,
+            false => price.round_sf(PRICE_SIGNIFICANT_FIGURES).unwrap_or(price),
+        };
+
+        price.round_dp(max_decimals)
+    }
+
+    /// Market orders are IOC orders with price worse than mid price
+    #[named]
+    async fn get_market_order_price(
+        &self,
+        currency_pair: CurrencyPair,
+        side: OrderSide,
+    ) -> Result<Price, ExchangeError> {
+        let response = self
+            .request_info(
+                json!({ "type": "allMids" }),
+                function_name!(),
+                format!("{currency_pair}"),
+            )
+            .await?;
+        let mids: HashMap<String, Price> =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse mid prices: {err:?}"))
+            })?;
+
+        let coin = self.get_specific_currency_pair(currency_pair);
+        let mid_price = *mids.get(coin.as_str()).ok_or_else(|| {
+            ExchangeError::unknown(&format!("No mid price of Hyperliquid market {coin}"))
+        })?;
+
+        Ok(match side {
+            OrderSide::Buy => mid_price * (Decimal::ONE + MARKET_ORDER_SLIPPAGE),
+            OrderSide::Sell => mid_price * (Decimal::ONE - MARKET_ORDER_SLIPPAGE),
+        })
+    }
+
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let market = self
+            .get_market(order.currency_pair())
+            .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+        let action = Action::Cancel {
+            cancels: vec![CancelWire {
+                asset: market.asset_id,
+                oid: Hyperliquid::parse_oid(exchange_order_id)?,
+            }],
+        };
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.send_action(action, log_args).await
+    }
+
+    /// Open orders of market are cancelled by single action
+    pub(super) async fn do_cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let response = self.request_open_orders().await?;
+        let orders = self.parse_open_orders(&response, Some(currency_pair))?;
+        if orders.is_empty() {
+            return Ok(());
+        }
+
+        let market = self.get_market(currency_pair)?;
+        let cancels = orders
+            .iter()
+            .map(|order| {
+                Ok(CancelWire {
+                    asset: market.asset_id,
+                    oid: Hyperliquid::parse_oid(&order.exchange_order_id)?,
+                })
+            })
+            .collect::<Result<Vec<_>, ExchangeError>>()?;
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.send_action(Action::Cancel { cancels }, log_args)
+            .await?;
+
+        Ok(())
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
+        let body = json!({ "type": "openOrders", "user": self.wallet.user() });
+        self.request_info(body, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Open orders are requested for all markets, so they are filtered by currency pair
+    pub(super) fn parse_open_orders(
+        &self,
+        response: &RestResponse,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<HyperliquidOrder> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(&order, OrderStatus::Created))
+            .filter_ok(|order| currency_pair.map_or(true, |x| x == order.currency_pair))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        // Order status is requested by client order id, so it's available before creation response
+        let cloid = self.register_cloid(&order.client_order_id());
+        let body = json!({ "type": "orderStatus", "user": self.wallet.user(), "oid": cloid });
+
+        let log_args = format!("order {}", order.client_order_id());
+        self.request_info(body, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let response: HyperliquidOrderStatusResponse = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        let update = response
+            .order
+            .with_context(|| format!("No order info received: {}", response.status))?;
+
+        self.specific_order_info_to_unified(
+            &update.order,
+            Hyperliquid::get_local_order_status(&update.status),
+        )
+    }
+
+    pub(crate) fn specific_order_info_to_unified(
+        &self,
+        specific: &HyperliquidOrder,
+        status: OrderStatus,
+    ) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.coin.as_str().into())?,
+            Hyperliquid::to_exchange_order_id(specific.oid),
+            self.get_client_order_id(specific),
+            specific.side,
+            status,
+            specific.limit_px,
+            specific.orig_sz,
+            // Average fill price isn't sent with order
+            Decimal::ZERO,
+            specific.filled_amount(),
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    /// Orders can be cancelled or rejected for different reasons, e.g. `marginCanceled`
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "open" | "triggered" => OrderStatus::Created,
+            "filled" => OrderStatus::Completed,
+            "rejected" => OrderStatus::FailedToCreate,
+            _ if status.ends_with("Canceled") || status == "canceled" => OrderStatus::Canceled,
+            _ if status.ends_with("Rejected") => OrderStatus::FailedToCreate,
+            _ => panic!("Hyperliquid: unexpected order status {status}"),
+        }
+    }
+
+    pub(super) fn get_order_role(crossed: bool) -> OrderRole {
+        match crossed {
+            true => OrderRole::Taker,
+            false => OrderRole::Maker,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = match last_date_time {
+            Some(time) => json!({
+                "type": "userFillsByTime",
+                "user": self.wallet.user(),
+                "startTime": time.timestamp_millis(),
+            }),
+            // Latest fills are returned without start time
+            None => json!({ "type": "userFills", "user": self.wallet.user() }),
+        };
+
+        self.request_info(body, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Fills of all markets are returned, so they are filtered by symbol
+    pub(super) fn parse_my_trades(
+        &self,
+        response: &RestResponse,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<Vec<OrderTrade>> {
+        let fills: Vec<HyperliquidFill> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+        let coin = self.get_specific_currency_pair(symbol.currency_pair());
+
+        Ok(fills
+            .into_iter()
+            .filter(|fill| fill.coin == coin.as_str())
+            .filter(|fill| last_date_time.map_or(true, |time| fill.time > time))
+            .sorted_by_key(|fill| fill.time)
+            .map(|fill| OrderTrade {
+                exchange_order_id: Hyperliquid::to_exchange_order_id(fill.oid),
+                trade_id: TradeId::Number(fill.tid),
+                datetime: fill.time,
+                price: fill.px,
+                amount: fill.sz,
+                side: fill.side,
+                order_role: Hyperliquid::get_order_role(fill.crossed),
+                fee_currency_code: self.currency_aliases.unify(fill.fee_token.as_str().into()),
+                fee_rate: None,
+                fee_amount: Some(fill.fee),
+                fill_type: OrderFillType::UserTrade,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_clearinghouse_state(&self) -> Result<RestResponse, ExchangeError> {
+        let body = json!({ "type": "clearinghouseState", "user": self.wallet.user() });
+        self.request_info(body, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let state: HyperliquidClearinghouseState =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        // Perpetuals account has only USDC collateral
+        Ok(vec![ExchangeBalance {
+            currency_code: self.currency_aliases.unify("usdc".into()),
+            balance: state.margin_summary.account_value,
+        }])
+    }
+
+    pub(super) fn parse_get_position(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ActivePosition>> {
+        let state: HyperliquidClearinghouseState =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        state
+            .asset_positions
+            .into_iter()
+            .map(|asset_position| asset_position.position)
+            .filter(|position| !position.szi.is_zero())
+            .map(|position| {
+                let derivative_position = DerivativePosition {
+                    currency_pair: self
+                        .get_unified_currency_pair(&position.coin.as_str().into())?,
+                    position: position.szi,
+                    average_entry_price: position.entry_px.unwrap_or_default(),
+                    liquidation_price: position.liquidation_px.unwrap_or_default(),
+                    leverage: position.leverage.value,
+                };
+
+                // Hyperliquid doesn't send time of position update
+                Ok(ActivePosition::new(
+                    derivative_position,
+                    time_manager::now(),
+                ))
+            })
+            .try_collect()
+    }
+
+    /// Position is closed by reduce-only IOC order
+    pub(super) async fn do_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        let derivative = &position.derivative;
+        let side = match derivative.position.is_sign_positive() {
+            true => OrderSide::Sell,
+            false => OrderSide::Buy,
+        };
+        let price = match price {
+            Some(price) => price,
+            None => self
+                .get_market_order_price(derivative.currency_pair, side)
+                .await
+                .map_err(|err| anyhow!("Failed to get price for closing position: {err:?}"))?,
+        };
+
+        let cloid = self.register_cloid(&ClientOrderId::unique_id());
+        let amount = derivative.position.abs();
+        let log_args = format!("Close position for {position:?} {price:?}");
+        let oid = self
+            .place_order(
+                derivative.currency_pair,
+                cloid,
+                side,
+                price,
+                amount,
+                TimeInForce::Ioc,
+                true,
+                log_args,
+            )
+            .await
+            .map_err(|err| anyhow!("Failed to close position: {err:?}"))?;
+
+        Ok(ClosedPosition::new(
+            Hyperliquid::to_exchange_order_id(oid),
+            amount,
+        ))
+    }
+
+    /// Leverage is set for cross margin mode and should be integer
+    pub(super) async fn do_set_leverage(
+        &self,
+        currency_pair: CurrencyPair,
+        leverage: Decimal,
+    ) -> Result<Decimal> {
+        let market = self.get_market(currency_pair)?;
+        let Some(leverage) = leverage.round().to_u32().filter(|x| *x > 0) else {
+            bail!("Invalid Hyperliquid leverage {leverage}");
+        };
+
+        let action = Action::UpdateLeverage {
+            asset: market.asset_id,
+            is_cross: true,
+            leverage,
+        };
+        let log_args = format!("Set leverage {leverage} for {currency_pair}");
+        self.send_action(action, log_args)
+            .await
+            .map_err(|err| anyhow!("Failed to set leverage: {err:?}"))?;
+
+        Ok(Decimal::from(leverage))
+    }
+}
+
+pub struct HyperliquidBuilder;
+
+impl ExchangeClientBuilder for HyperliquidBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Hyperliquid::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // REST requests have weight limit of 1200 per minute from one IP,
+        // most info requests have weight 2 or 20
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Hyperliquid".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+
+    fn create_hyperliquid() -> Hyperliquid {
+        let exchange_account_id: ExchangeAccountId = "Hyperliquid_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(
+            exchange_account_id,
+            "0x14791697260e4c9a71f18484c9f997b308e59325".into(),
+            "".into(),
+            true,
+        );
+
+        let (tx, _) = broadcast::channel(10);
+        Hyperliquid::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::new()),
+        )
+    }
+
+    fn meta_response() -> RestResponse {
+        RestResponse::new(
+            r#"{"universe":[
+                {"name":"BTC","szDecimals":5,"maxLeverage":50},
+                {"name":"ETH","szDecimals":4,"maxLeverage":50},
+                {"name":"LUNA","szDecimals":1,"maxLeverage":3,"isDelisted":true}
+            ]}"#
+            .to_owned(),
+            hyper::StatusCode::OK,
+        )
+    }
+
+    #[test]
+    fn parse_meta() {
+        let hyperliquid = create_hyperliquid();
+
+        let symbols = hyperliquid
+            .parse_all_symbols(&meta_response())
+            .expect("in test");
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[1].currency_pair().as_str(), "eth/usd");
+        assert!(symbols[1].is_derivative);
+        assert_eq!(
+            hyperliquid
+                .get_specific_currency_pair(symbols[1].currency_pair())
+                .as_str(),
+            "ETH"
+        );
+        assert_eq!(
+            hyperliquid
+                .get_market(symbols[1].currency_pair())
+                .expect("in test")
+                .asset_id,
+            1
+        );
+    }
+
+    #[test]
+    fn round_price_to_significant_figures() {
+        assert_eq!(Hyperliquid::round_price(dec!(37123.456), 5), dec!(37123));
+        assert_eq!(Hyperliquid::round_price(dec!(123456), 5), dec!(123456));
+        assert_eq!(Hyperliquid::round_price(dec!(1.234567), 0), dec!(1.2346));
+        assert_eq!(Hyperliquid::round_price(dec!(0.0123456), 2), dec!(0.0123));
+    }
+
+    #[test]
+    fn cloid_is_restored_to_client_order_id() {
+        let hyperliquid = create_hyperliquid();
+        let client_order_id = ClientOrderId::unique_id();
+
+        let cloid = hyperliquid.register_cloid(&client_order_id);
+
+        assert_eq!(cloid.len(), 34);
+        let order: HyperliquidOrder = serde_json::from_value(json!({
+            "coin": "BTC",
+            "side": "B",
+            "limitPx": "30000",
+            "sz": "0.01",
+            "oid": 77738308,
+            "origSz": "0.02",
+            "cloid": cloid,
+        }))
+        .expect("in test");
+        assert_eq!(hyperliquid.get_client_order_id(&order), client_order_id);
+        assert_eq!(order.filled_amount(), dec!(0.01));
+    }
+
+    #[test]
+    fn parse_order_statuses() {
+        let response = RestResponse::new(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":77738308}}]}}}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let statuses = Hyperliquid::parse_statuses(&response).expect("in test");
+
+        assert!(matches!(
+            statuses[..],
+            [HyperliquidOrderStatus::Resting { oid: 77738308 }]
+        ));
+    }
+
+    #[test]
+    fn order_error_is_detected() {
+        let response = RestResponse::new(
+            r#"{"status":"ok","response":{"type":"cancel","data":{"statuses":[{"error":"Order was never placed, already canceled, or filled."}]}}}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let error = ErrorHandlerHyperliquid
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(
+            ErrorHandlerHyperliquid.clarify_error_type(&error),
+            ExchangeErrorType::OrderNotFound
+        );
+    }
+
+    #[test]
+    fn parse_positions() {
+        let hyperliquid = create_hyperliquid();
+        hyperliquid
+            .parse_all_symbols(&meta_response())
+            .expect("in test");
+        let response = RestResponse::new(
+            r#"{"marginSummary":{"accountValue":"13109.48","totalNtlPos":"3000","totalRawUsd":"10109.48","totalMarginUsed":"300"},
+                "withdrawable":"12809.48",
+                "assetPositions":[{"type":"oneWay","position":{"coin":"ETH","szi":"-1.5","entryPx":"2000.5","liquidationPx":"9000","leverage":{"type":"cross","value":10},"positionValue":"3000","unrealizedPnl":"0.75"}}]}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let balances = hyperliquid.parse_get_balance(&response).expect("in test");
+        let positions = hyperliquid.parse_get_position(&response).expect("in test");
+
+        assert_eq!(balances[0].currency_code.as_str(), "usd");
+        assert_eq!(balances[0].balance, dec!(13109.48));
+        assert_eq!(positions[0].derivative.currency_pair.as_str(), "eth/usd");
+        assert_eq!(positions[0].derivative.position, dec!(-1.5));
+        assert_eq!(positions[0].derivative.leverage, dec!(10));
+    }
+}
@@ -0,0 +1,429 @@
+use crate::hyperliquid::Hyperliquid;
+use crate::types::{
+    HyperliquidActiveAssetCtx, HyperliquidBook, HyperliquidFill, HyperliquidOrderUpdate,
+    HyperliquidTrade, HyperliquidUserFills, HyperliquidUserFundings,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleCashFlowCb, HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb,
+    OrderCreatedCb, SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{
+    CashFlowEvent, CashFlowKind, EventSourceType, ExchangeEvent, MarkPriceEvent, Trade, TradeId,
+};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::OrderStatus;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+const L2_BOOK_CHANNEL: &str = "l2Book";
+const TRADES_CHANNEL: &str = "trades";
+const ACTIVE_ASSET_CTX_CHANNEL: &str = "activeAssetCtx";
+const ORDER_UPDATES_CHANNEL: &str = "orderUpdates";
+const USER_FILLS_CHANNEL: &str = "userFills";
+const USER_FUNDINGS_CHANNEL: &str = "userFundings";
+/// Connection is closed by server if there are no messages for 60 seconds
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[async_trait]
+impl Support for Hyperliquid {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message.channel.as_str() {
+            "subscriptionResponse" | "pong" => Ok(()),
+            L2_BOOK_CHANNEL => self.handle_order_book(serde_json::from_value(message.data)?),
+            TRADES_CHANNEL => self.handle_trades(serde_json::from_value(message.data)?),
+            ACTIVE_ASSET_CTX_CHANNEL => {
+                self.handle_asset_ctx(serde_json::from_value(message.data)?)
+            }
+            ORDER_UPDATES_CHANNEL => {
+                self.handle_order_updates(serde_json::from_value(message.data)?);
+                Ok(())
+            }
+            USER_FILLS_CHANNEL => {
+                self.handle_user_fills(serde_json::from_value(message.data)?);
+                Ok(())
+            }
+            USER_FUNDINGS_CHANNEL => {
+                self.handle_user_fundings(serde_json::from_value(message.data)?)
+            }
+            "error" => {
+                let err = format!("Hyperliquid websocket: error message {msg}");
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg);
+                Ok(())
+            }
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        for coin in self.traded_specific_currencies.lock().iter() {
+            for channel in [L2_BOOK_CHANNEL, TRADES_CHANNEL, ACTIVE_ASSET_CTX_CHANNEL] {
+                let subscribe = subscribe_message(json!({ "type": channel, "coin": coin }));
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+            }
+        }
+
+        // User channels don't require authentication, it's enough to know address of wallet
+        let user = self.wallet.user();
+        for channel in [
+            ORDER_UPDATES_CHANNEL,
+            USER_FILLS_CHANNEL,
+            USER_FUNDINGS_CHANNEL,
+        ] {
+            let subscribe = subscribe_message(json!({ "type": channel, "user": user }));
+            (self.websocket_message_callback)(WebSocketRole::Main, subscribe)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, _callback: HandleMetricsCb) {}
+
+    fn set_handle_cash_flow_callback(&mut self, callback: HandleCashFlowCb) {
+        self.handle_cash_flow_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, _role: WebSocketRole) -> bool {
+        true
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_websocket_keep_alive(&self, _role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        Some(WebSocketKeepAlive {
+            message: json!({ "method": "ping" }).to_string(),
+            interval: KEEP_ALIVE_INTERVAL,
+        })
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(ORDER_UPDATES_CHANNEL)
+            || message.contains(USER_FILLS_CHANNEL)
+            || message.contains(USER_FUNDINGS_CHANNEL)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Hyperliquid {
+    /// Every message of `l2Book` channel contains snapshot of order book
+    fn handle_order_book(&self, book: HyperliquidBook) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&book.coin.as_str().into())?;
+
+        let (bids, asks) = book.levels;
+        let mut order_book_data = OrderBookData::default();
+        for level in bids {
+            order_book_data.bids.insert(level.px, level.sz);
+        }
+        for level in asks {
+            order_book_data.asks.insert(level.px, level.sz);
+        }
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trades(&self, trades: Vec<HyperliquidTrade>) -> Result<()> {
+        for trade in trades {
+            let currency_pair = self.get_unified_currency_pair(&trade.coin.as_str().into())?;
+            (self.handle_trade_callback)(
+                currency_pair,
+                Trade {
+                    trade_id: TradeId::Number(trade.tid),
+                    price: trade.px,
+                    quantity: trade.sz,
+                    side: trade.side,
+                    transaction_time: trade.time,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Mark price, oracle price and current funding rate of perpetual
+    fn handle_asset_ctx(&self, asset_ctx: HyperliquidActiveAssetCtx) -> Result<()> {
+        let mark_price_event = MarkPriceEvent {
+            exchange_account_id: self.settings.exchange_account_id,
+            currency_pair: self.get_unified_currency_pair(&asset_ctx.coin.as_str().into())?,
+            mark_price: asset_ctx.ctx.mark_px,
+            index_price: Some(asset_ctx.ctx.oracle_px),
+            implied_volatility: None,
+            greeks: None,
+            funding_rate: Some(asset_ctx.ctx.funding),
+            time: Utc::now(),
+        };
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::MarkPrice(mark_price_event),
+        )
+    }
+
+    fn handle_order_updates(&self, updates: Vec<HyperliquidOrderUpdate>) {
+        for update in updates {
+            let client_order_id = self.get_client_order_id(&update.order);
+            let exchange_order_id = Hyperliquid::to_exchange_order_id(update.order.oid);
+            match Hyperliquid::get_local_order_status(&update.status) {
+                OrderStatus::Created => (self.order_created_callback)(
+                    client_order_id,
+                    exchange_order_id,
+                    EventSourceType::WebSocket,
+                ),
+                OrderStatus::Canceled => (self.order_cancelled_callback)(
+                    client_order_id,
+                    exchange_order_id,
+                    EventSourceType::WebSocket,
+                ),
+                // Fills are received by separate channel
+                _ => (),
+            }
+        }
+    }
+
+    /// Snapshot contains fills made before subscription, they are requested by REST if needed
+    fn handle_user_fills(&self, user_fills: HyperliquidUserFills) {
+        if user_fills.is_snapshot {
+            return;
+        }
+
+        for fill in user_fills.fills {
+            self.handle_order_fill(fill);
+        }
+    }
+
+    fn handle_order_fill(&self, fill: HyperliquidFill) {
+        let client_order_id = fill
+            .cloid
+            .as_ref()
+            .and_then(|cloid| self.client_order_ids.get(cloid))
+            .map(|client_order_id| client_order_id.clone());
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::Number(fill.tid)),
+            client_order_id,
+            exchange_order_id: Hyperliquid::to_exchange_order_id(fill.oid),
+            fill_price: fill.px,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: fill.sz,
+                total_filled_amount: None,
+            },
+            order_role: Some(Hyperliquid::get_order_role(fill.crossed)),
+            commission_currency_code: Some(
+                self.currency_aliases.unify(fill.fee_token.as_str().into()),
+            ),
+            commission_rate: None,
+            commission_amount: Some(fill.fee),
+            fill_type: OrderFillType::UserTrade,
+            // Fills don't contain order amount, so fills of unknown orders can't be handled
+            special_order_data: None,
+            fill_date: Some(fill.time),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+    }
+
+    /// Funding payments of positions are reported hourly
+    fn handle_user_fundings(&self, user_fundings: HyperliquidUserFundings) -> Result<()> {
+        if user_fundings.is_snapshot {
+            return Ok(());
+        }
+
+        for funding in user_fundings.fundings {
+            let cash_flow = CashFlowEvent {
+                exchange_account_id: self.settings.exchange_account_id,
+                currency_pair: self.get_unified_currency_pair(&funding.coin.as_str().into())?,
+                currency_code: self.currency_aliases.unify("usdc".into()),
+                kind: CashFlowKind::Funding,
+                amount: funding.usdc,
+                transaction_time: funding.time,
+            };
+
+            (self.handle_cash_flow_callback)(cash_flow);
+        }
+
+        Ok(())
+    }
+}
+
+fn subscribe_message(subscription: Value) -> String {
+    json!({
+        "method": "subscribe",
+        "subscription": subscription,
+    })
+    .to_string()
+}
+
+/// Common format of all websocket messages
+#[derive(Deserialize, Debug)]
+struct WebsocketMessage {
+    channel: String,
+    #[serde(default)]
+    data: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_subscription() {
+        let message = subscribe_message(json!({ "type": L2_BOOK_CHANNEL, "coin": "BTC" }));
+
+        assert_eq!(
+            message,
+            r#"{"method":"subscribe","subscription":{"coin":"BTC","type":"l2Book"}}"#
+        );
+    }
+
+    #[test]
+    fn parse_order_book() {
+        let msg = r#"{"channel":"l2Book","data":{"coin":"BTC","time":1700000000000,"levels":[[{"px":"37000","sz":"0.5","n":2}],[{"px":"37001","sz":"1.25","n":1}]]}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let book: HyperliquidBook = serde_json::from_value(message.data).expect("in test");
+
+        assert_eq!(book.coin, "BTC");
+        assert_eq!(book.levels.0[0].px, dec!(37000));
+        assert_eq!(book.levels.1[0].sz, dec!(1.25));
+    }
+
+    #[test]
+    fn parse_asset_ctx() {
+        let msg = r#"{"channel":"activeAssetCtx","data":{"coin":"BTC","ctx":{"funding":"0.0000125","openInterest":"8000.5","prevDayPx":"36000","dayNtlVlm":"1000000000","premium":"0.0001","oraclePx":"37000.5","markPx":"37001","midPx":"37000.5","impactPxs":["37000","37002"]}}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let asset_ctx: HyperliquidActiveAssetCtx =
+            serde_json::from_value(message.data).expect("in test");
+
+        assert_eq!(asset_ctx.ctx.funding, dec!(0.0000125));
+        assert_eq!(asset_ctx.ctx.mark_px, dec!(37001));
+        assert_eq!(asset_ctx.ctx.oracle_px, dec!(37000.5));
+    }
+
+    #[test]
+    fn parse_user_fills() {
+        let msg = r#"{"channel":"userFills","data":{"user":"0x14791697260e4c9a71f18484c9f997b308e59325","fills":[{"coin":"BTC","px":"37000","sz":"0.01","side":"B","time":1700000000000,"startPosition":"0","dir":"Open Long","closedPnl":"0","hash":"0xabc","oid":77738308,"crossed":false,"fee":"-0.0074","tid":118906512037719,"feeToken":"USDC"}]}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let user_fills: HyperliquidUserFills =
+            serde_json::from_value(message.data).expect("in test");
+
+        assert!(!user_fills.is_snapshot);
+        let fill = &user_fills.fills[0];
+        assert_eq!(fill.oid, 77738308);
+        assert_eq!(fill.fee, dec!(-0.0074));
+        assert_eq!(
+            Hyperliquid::get_order_role(fill.crossed),
+            mmb_domain::order::snapshot::OrderRole::Maker
+        );
+    }
+
+    #[test]
+    fn parse_user_fundings() {
+        let msg = r#"{"channel":"userFundings","data":{"user":"0x14791697260e4c9a71f18484c9f997b308e59325","fundings":[{"time":1700002800000,"coin":"ETH","usdc":"-0.375","szi":"1.5","fundingRate":"0.0000125"}]}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let user_fundings: HyperliquidUserFundings =
+            serde_json::from_value(message.data).expect("in test");
+
+        assert_eq!(user_fundings.fundings[0].coin, "ETH");
+        assert_eq!(user_fundings.fundings[0].usdc, dec!(-0.375));
+    }
+}
@@ -0,0 +1,293 @@
+use mmb_domain::order::snapshot::{Amount, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Side in Hyperliquid responses: `B` is bid (buy) and `A` is ask (sell)
+pub(crate) fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "B" => Ok(OrderSide::Buy),
+        "A" => Ok(OrderSide::Sell),
+        _ => Err(serde::de::Error::custom(format!(
+            "Unknown Hyperliquid side {side}"
+        ))),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidMeta {
+    /// Index of asset in universe is used as asset id in orders
+    pub(crate) universe: Vec<HyperliquidAsset>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidAsset {
+    pub(crate) name: String,
+    pub(crate) sz_decimals: u32,
+    #[serde(default)]
+    pub(crate) is_delisted: bool,
+}
+
+/// Perpetual asset with its id in order actions
+#[derive(Debug, Clone)]
+pub(crate) struct HyperliquidMarket {
+    pub(crate) asset_id: u32,
+    pub(crate) sz_decimals: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidOrder {
+    pub(crate) coin: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) limit_px: Price,
+    /// Remaining amount of order
+    pub(crate) sz: Amount,
+    pub(crate) oid: u64,
+    pub(crate) orig_sz: Amount,
+    pub(crate) cloid: Option<String>,
+}
+
+impl HyperliquidOrder {
+    pub(crate) fn filled_amount(&self) -> Amount {
+        self.orig_sz - self.sz
+    }
+}
+
+/// Response of `orderStatus` request
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidOrderStatusResponse {
+    /// `order` or `unknownOid`
+    pub(crate) status: String,
+    pub(crate) order: Option<HyperliquidOrderUpdate>,
+}
+
+/// Order with its status, the same format is used by `orderUpdates` websocket channel
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidOrderUpdate {
+    pub(crate) order: HyperliquidOrder,
+    pub(crate) status: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidFill {
+    pub(crate) coin: String,
+    pub(crate) px: Price,
+    pub(crate) sz: Amount,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) time: DateTime,
+    pub(crate) oid: u64,
+    pub(crate) tid: u64,
+    /// True if fill is taker
+    pub(crate) crossed: bool,
+    pub(crate) fee: Amount,
+    pub(crate) fee_token: String,
+    pub(crate) cloid: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidClearinghouseState {
+    pub(crate) margin_summary: HyperliquidMarginSummary,
+    pub(crate) asset_positions: Vec<HyperliquidAssetPosition>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidMarginSummary {
+    /// USDC balance including unrealized PnL of positions
+    pub(crate) account_value: Amount,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidAssetPosition {
+    pub(crate) position: HyperliquidPosition,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidPosition {
+    pub(crate) coin: String,
+    /// Signed size of position
+    pub(crate) szi: Amount,
+    pub(crate) entry_px: Option<Price>,
+    pub(crate) liquidation_px: Option<Price>,
+    pub(crate) leverage: HyperliquidLeverage,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidLeverage {
+    pub(crate) value: Decimal,
+}
+
+/// Response of `/exchange` endpoint
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidExchangeResponse {
+    pub(crate) response: HyperliquidExchangeResponseData,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidExchangeResponseData {
+    pub(crate) data: Option<HyperliquidStatuses>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidStatuses {
+    pub(crate) statuses: Vec<HyperliquidOrderStatus>,
+}
+
+/// Status of single order in response of order or cancel action
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum HyperliquidOrderStatus {
+    Resting {
+        oid: u64,
+    },
+    Filled {
+        oid: u64,
+    },
+    Error(String),
+    /// Order is cancelled successfully
+    Success,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidBookLevel {
+    pub(crate) px: Price,
+    pub(crate) sz: Amount,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidBook {
+    pub(crate) coin: String,
+    /// Bids and asks
+    pub(crate) levels: (Vec<HyperliquidBookLevel>, Vec<HyperliquidBookLevel>),
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidTrade {
+    pub(crate) coin: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) px: Price,
+    pub(crate) sz: Amount,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) time: DateTime,
+    pub(crate) tid: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct HyperliquidActiveAssetCtx {
+    pub(crate) coin: String,
+    pub(crate) ctx: HyperliquidAssetCtx,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidAssetCtx {
+    /// Hourly funding rate
+    pub(crate) funding: Decimal,
+    pub(crate) mark_px: Price,
+    pub(crate) oracle_px: Price,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidUserFills {
+    #[serde(default)]
+    pub(crate) is_snapshot: bool,
+    pub(crate) fills: Vec<HyperliquidFill>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidUserFundings {
+    #[serde(default)]
+    pub(crate) is_snapshot: bool,
+    pub(crate) fundings: Vec<HyperliquidFunding>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HyperliquidFunding {
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) time: DateTime,
+    pub(crate) coin: String,
+    /// Paid (negative) or received (positive) USDC
+    pub(crate) usdc: Amount,
+}
+
+/// Actions are serialized with MessagePack for signing,
+/// so order of fields should be the same as in official SDK
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub(crate) enum Action {
+    Order {
+        orders: Vec<OrderWire>,
+        grouping: &'static str,
+    },
+    Cancel {
+        cancels: Vec<CancelWire>,
+    },
+    UpdateLeverage {
+        asset: u32,
+        #[serde(rename = "isCross")]
+        is_cross: bool,
+        leverage: u32,
+    },
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct OrderWire {
+    #[serde(rename = "a")]
+    pub(crate) asset: u32,
+    #[serde(rename = "b")]
+    pub(crate) is_buy: bool,
+    #[serde(rename = "p")]
+    pub(crate) price: String,
+    #[serde(rename = "s")]
+    pub(crate) size: String,
+    #[serde(rename = "r")]
+    pub(crate) reduce_only: bool,
+    #[serde(rename = "t")]
+    pub(crate) order_type: OrderTypeWire,
+    #[serde(rename = "c")]
+    pub(crate) cloid: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OrderTypeWire {
+    Limit { tif: TimeInForce },
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub(crate) enum TimeInForce {
+    /// Good till cancel
+    Gtc,
+    /// Add liquidity only, i.e. post-only
+    Alo,
+    Ioc,
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct CancelWire {
+    #[serde(rename = "a")]
+    pub(crate) asset: u32,
+    #[serde(rename = "o")]
+    pub(crate) oid: u64,
+}
+
+/// Decimals are sent as strings without trailing zeros
+pub(crate) fn to_wire(value: Decimal) -> String {
+    value.normalize().to_string()
+}
@@ -0,0 +1,183 @@
+use crate::types::Action;
+use anyhow::{anyhow, Context, Result};
+use ethers_core::abi::{encode, Token};
+use ethers_core::types::{Address, BigEndianHash, Signature, H256, U256};
+use ethers_core::utils::keccak256;
+use ethers_signers::{LocalWallet, Signer};
+use serde_json::{json, Value};
+
+/// Actions are signed by "phantom agent" with chain id which isn't related to any real chain
+const SIGNATURE_CHAIN_ID: u64 = 1337;
+/// Source of phantom agent for mainnet, testnet uses `b`
+const MAINNET_SOURCE: &str = "a";
+
+/// Ethereum wallet which signs actions of Hyperliquid L1
+pub(crate) struct Wallet {
+    signing_key: Option<LocalWallet>,
+    address: Address,
+}
+
+impl Wallet {
+    /// `secret_key` is hex encoded private key of wallet. If it's empty, `address` is used
+    /// to receive data of account without trading
+    pub(crate) fn new(secret_key: &str, address: &str) -> Result<Self> {
+        if secret_key.is_empty() {
+            let address = address
+                .parse()
+                .with_context(|| format!("Invalid Hyperliquid address {address}"))?;
+            return Ok(Wallet {
+                signing_key: None,
+                address,
+            });
+        }
+
+        let signing_key = secret_key
+            .trim_start_matches("0x")
+            .parse::<LocalWallet>()
+            .map_err(|err| anyhow!("Invalid Hyperliquid secret key: {err}"))?;
+
+        Ok(Wallet {
+            address: signing_key.address(),
+            signing_key: Some(signing_key),
+        })
+    }
+
+    pub(crate) fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Address in format of info requests and websocket subscriptions
+    pub(crate) fn user(&self) -> String {
+        format!("{:?}", self.address)
+    }
+
+    /// Body of `/exchange` request with signed action
+    pub(crate) fn sign_action(&self, action: &Action, nonce: u64) -> Result<Value> {
+        let signing_key = self
+            .signing_key
+            .as_ref()
+            .context("Hyperliquid secret key isn't specified")?;
+
+        let connection_id = action_hash(action, nonce)?;
+        let signature = signing_key
+            .sign_hash(agent_signing_hash(connection_id))
+            .map_err(|err| anyhow!("Failed to sign Hyperliquid action: {err}"))?;
+
+        Ok(json!({
+            "action": action,
+            "nonce": nonce,
+            "signature": signature_to_json(&signature),
+            "vaultAddress": null,
+        }))
+    }
+}
+
+/// Hash of MessagePack encoded action with nonce and without vault address
+pub(crate) fn action_hash(action: &Action, nonce: u64) -> Result<H256> {
+    let mut bytes = rmp_serde::to_vec_named(action).context("Failed to serialize action")?;
+    bytes.extend(nonce.to_be_bytes());
+    bytes.push(0);
+
+    Ok(H256::from(keccak256(bytes)))
+}
+
+/// EIP-712 hash of `Agent { source, connectionId }` in domain `Exchange`
+pub(crate) fn agent_signing_hash(connection_id: H256) -> H256 {
+    let domain_type = keccak256(
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+    );
+    let domain_separator = keccak256(encode(&[
+        Token::FixedBytes(domain_type.to_vec()),
+        Token::FixedBytes(keccak256("Exchange").to_vec()),
+        Token::FixedBytes(keccak256("1").to_vec()),
+        Token::Uint(U256::from(SIGNATURE_CHAIN_ID)),
+        Token::Address(Address::zero()),
+    ]));
+
+    let agent_type = keccak256("Agent(string source,bytes32 connectionId)");
+    let struct_hash = keccak256(encode(&[
+        Token::FixedBytes(agent_type.to_vec()),
+        Token::FixedBytes(keccak256(MAINNET_SOURCE).to_vec()),
+        Token::FixedBytes(connection_id.as_bytes().to_vec()),
+    ]));
+
+    let mut digest = vec![0x19, 0x01];
+    digest.extend(domain_separator);
+    digest.extend(struct_hash);
+    H256::from(keccak256(digest))
+}
+
+fn signature_to_json(signature: &Signature) -> Value {
+    json!({
+        "r": format!("{:?}", H256::from_uint(&signature.r)),
+        "s": format!("{:?}", H256::from_uint(&signature.s)),
+        "v": signature.v,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CancelWire;
+
+    const SECRET_KEY: &str = "0x0123456789012345678901234567890123456789012345678901234567890123";
+
+    fn cancel_action() -> Action {
+        Action::Cancel {
+            cancels: vec![CancelWire {
+                asset: 0,
+                oid: 77738308,
+            }],
+        }
+    }
+
+    #[test]
+    fn address_is_derived_from_secret_key() {
+        let wallet = Wallet::new(SECRET_KEY, "").expect("in test");
+
+        assert_eq!(wallet.user(), "0x14791697260e4c9a71f18484c9f997b308e59325");
+    }
+
+    #[test]
+    fn action_hash_depends_on_nonce() {
+        let hash = action_hash(&cancel_action(), 1700000000000).expect("in test");
+
+        assert_ne!(
+            hash,
+            action_hash(&cancel_action(), 1700000000001).expect("in test")
+        );
+    }
+
+    #[test]
+    fn signed_action_is_recovered_to_wallet_address() {
+        let wallet = Wallet::new(SECRET_KEY, "").expect("in test");
+        let nonce = 1700000000000;
+
+        let body = wallet
+            .sign_action(&cancel_action(), nonce)
+            .expect("in test");
+
+        assert_eq!(body["action"]["type"], "cancel");
+        assert_eq!(body["action"]["cancels"][0]["o"], 77738308);
+
+        let parse_u256 = |value: &Value| {
+            let hash: H256 = value.as_str().expect("in test").parse().expect("in test");
+            hash.into_uint()
+        };
+        let signature = Signature {
+            r: parse_u256(&body["signature"]["r"]),
+            s: parse_u256(&body["signature"]["s"]),
+            v: body["signature"]["v"].as_u64().expect("in test"),
+        };
+        let hash = agent_signing_hash(action_hash(&cancel_action(), nonce).expect("in test"));
+        assert_eq!(signature.recover(hash).expect("in test"), wallet.address());
+    }
+
+    #[test]
+    fn wallet_without_secret_key_cannot_sign() {
+        let wallet =
+            Wallet::new("", "0x14791697260e4c9a71f18484c9f997b308e59325").expect("in test");
+
+        assert!(wallet.sign_action(&cancel_action(), 1).is_err());
+    }
+}
@@ -597,6 +597,10 @@ impl InteractiveBrokers {
         } = msg
         {
             let order_id = order.order_id;
+            let order_side: MmbOrderSide = IbOrderSide::from_str(&order.action)
+                .map_err(|e| anyhow!(e))?
+                .try_into()
+                .map_err(|e: String| anyhow!(e))?;
             // TODO: Check if right value used
             let price = Decimal::from_f64_retain(order.lmt_price).context(anyhow!(
                 "fn {f_n}: Order price: Decimal::from_f64_retain error.",
@@ -614,6 +618,7 @@ impl InteractiveBrokers {
                 Self::parse_datetime(&order_state.completed_time)?,
                 price,
                 amount,
+                order_side,
                 OrderRole::Maker,
                 CurrencyCode::from(order_state.commission_currency.as_str()),
                 None,
@@ -13,7 +13,9 @@ use mmb_core::exchanges::traits::{
 };
 use mmb_core::infrastructure::spawn_future_standalone;
 use mmb_core::settings::ExchangeSettings;
-use mmb_domain::market::{CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
 use mmb_utils::infrastructure::SpawnFutureFlags;
 use std::any::Any;
 use std::sync::Arc;
@@ -122,4 +124,8 @@ impl Support for InteractiveBrokers {
     fn get_settings(&self) -> &ExchangeSettings {
         todo!()
     }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        CurrencyAliases::default()
+    }
 }
@@ -0,0 +1,854 @@
+use crate::types::{
+    KrakenAddOrderResult, KrakenAsset, KrakenOpenOrders, KrakenOrderInfo, KrakenResult,
+    KrakenSymbol, KrakenTradesHistory,
+};
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::{StatusCode, Uri};
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, TriggerPriceType, UserOrder,
+};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerKraken;
+
+impl ErrorHandler for ErrorHandlerKraken {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct KrakenErrors {
+            #[serde(default)]
+            error: Vec<String>,
+        }
+
+        match response.status {
+            // Kraken reports most of errors with status 200 and non-empty "error" array
+            StatusCode::OK => {
+                let errors: KrakenErrors =
+                    serde_json::from_str(&response.content).map_err(|err| {
+                        ExchangeError::parsing(format!("Unable to parse response: {err:?}"))
+                    })?;
+                match errors.error.is_empty() {
+                    true => Ok(()),
+                    false => Err(ExchangeError::new(
+                        ExchangeErrorType::Unknown,
+                        errors.error.join("; "),
+                        None,
+                    )),
+                }
+            }
+            StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN | StatusCode::NOT_FOUND => Err(
+                ExchangeError::new(ExchangeErrorType::SendError, response.content.clone(), None),
+            ),
+            _ => Err(ExchangeError::unknown(&response.content)),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Kraken error message has format "<severity><category>:<description>"
+        // Details: https://docs.kraken.com/rest/#section/General-Usage/Requests-Responses-and-Errors
+        let message = error.message.as_str();
+        if message.contains("EOrder:Unknown order") {
+            ExchangeErrorType::OrderNotFound
+        } else if message.contains("EOrder:Insufficient funds")
+            || message.contains("EOrder:Insufficient margin")
+        {
+            ExchangeErrorType::InsufficientFunds
+        } else if message.contains("EOrder:Invalid price")
+            || message.contains("EOrder:Order minimum not met")
+            || message.contains("EOrder:Cost minimum not met")
+            || message.contains("EOrder:Tick size check failed")
+            || message.contains("EOrder:Post only order")
+            || message.contains("EGeneral:Invalid arguments")
+        {
+            ExchangeErrorType::InvalidOrder
+        } else if message.contains("EAPI:Rate limit exceeded")
+            || message.contains("EOrder:Rate limit exceeded")
+            || message.contains("EGeneral:Too many requests")
+        {
+            ExchangeErrorType::RateLimit
+        } else if message.contains("EAPI:Invalid key")
+            || message.contains("EAPI:Invalid signature")
+            || message.contains("EAPI:Invalid nonce")
+            || message.contains("EGeneral:Permission denied")
+        {
+            ExchangeErrorType::Authentication
+        } else if message.contains("EService:Unavailable")
+            || message.contains("EService:Busy")
+            || message.contains("EService:Market in cancel_only mode")
+        {
+            ExchangeErrorType::ServiceUnavailable
+        } else {
+            ExchangeErrorType::Unknown
+        }
+    }
+}
+
+pub struct RestHeadersKraken {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersKraken {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersKraken {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        // Only public endpoints are requested without body
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        _request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        builder
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header("API-Key", &self.api_key)
+            .header(
+                "API-Sign",
+                Kraken::create_signature(&self.secret_key, uri.path(), body),
+            )
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+const DEFAULT_CURRENCY_ALIASES: &[(&str, &str)] = &[("xbt", "btc"), ("xdg", "doge")];
+/// Websocket API v2 uses common asset names instead of Kraken specific ones
+const WS_ASSET_NAMES: &[(&str, &str)] = &[("XBT", "BTC"), ("XDG", "DOGE")];
+
+pub struct Kraken {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerKraken, RestHeadersKraken>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    // Trades history returns pair name (e.g. "XXBTZUSD") instead of altname (e.g. "XBTUSD")
+    pair_name_to_unified: RwLock<HashMap<String, CurrencyPair>>,
+    pub(crate) specific_to_ws_symbol: RwLock<HashMap<SpecificCurrencyPair, String>>,
+    pub(crate) ws_symbol_to_unified: RwLock<HashMap<String, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    // Token for private websocket channels which is requested before connection
+    pub(crate) websocket_token: Mutex<Option<String>>,
+    // Kraken requires nonce to be increased on every private request
+    last_nonce: AtomicU64,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Kraken {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Kraken {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerKraken::default(),
+                ),
+                RestHeadersKraken::new(settings.api_key.clone(), settings.secret_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(
+                DEFAULT_CURRENCY_ALIASES,
+                &settings.currency_aliases,
+            ),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            pair_name_to_unified: Default::default(),
+            specific_to_ws_symbol: Default::default(),
+            ws_symbol_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+            websocket_token: Default::default(),
+            last_nonce: AtomicU64::new(0),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://ws.kraken.com/v2",
+            web_socket2_host: "wss://ws-auth.kraken.com/v2",
+            rest_host: "https://api.kraken.com",
+        }
+    }
+
+    pub(super) fn create_signature(secret_key: &str, path: &str, body: &[u8]) -> String {
+        // Nonce is always the first parameter of private request, see `private_uri_builder()`
+        let nonce = body
+            .split(|byte| *byte == b'&')
+            .find_map(|pair| pair.strip_prefix(b"nonce="))
+            .unwrap_or_default();
+
+        let mut sha256 = Sha256::new();
+        sha256.update(nonce);
+        sha256.update(body);
+
+        let secret_key =
+            base64::decode(secret_key).expect("Kraken secret key should be base64 encoded");
+        let mut hmac = Hmac::<Sha512>::new_from_slice(&secret_key)
+            .expect("Unable to calculate hmac for Kraken signature");
+        hmac.update(path.as_bytes());
+        hmac.update(&sha256.finalize());
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+
+    fn next_nonce(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_millis() as u64;
+
+        let previous = self
+            .last_nonce
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(now.max(last + 1))
+            })
+            .expect("Nonce update closure always returns value");
+
+        now.max(previous + 1)
+    }
+
+    fn private_uri_builder(&self, path: &str) -> UriBuilder {
+        let mut builder = UriBuilder::from_path(path);
+        builder.add_kv("nonce", self.next_nonce());
+        builder
+    }
+
+    async fn post_private(
+        &self,
+        builder: UriBuilder,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let (uri, query) = builder.build_uri_and_query(self.hosts.rest_uri_host(), false);
+        self.rest_client
+            .post(uri, Some(query), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    async fn request_assets(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/0/public/Assets");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    fn parse_assets(&self, response: &RestResponse) -> Result<()> {
+        let assets: KrakenResult<HashMap<String, KrakenAsset>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize assets response from Kraken")?;
+
+        for (asset_id, asset) in assets.result {
+            let currency_code = self.currency_aliases.unify(asset.altname.as_str().into());
+            self.supported_currencies
+                .insert(asset_id.as_str().into(), currency_code);
+        }
+
+        Ok(())
+    }
+
+    pub(super) async fn update_assets(&self) -> Result<()> {
+        let response = self.request_assets().await?;
+
+        self.parse_assets(&response)
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/0/public/AssetPairs");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let symbols: KrakenResult<HashMap<String, KrakenSymbol>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to deserialize response from Kraken")?;
+
+        Ok(symbols
+            .result
+            .into_iter()
+            .filter(|(_, symbol)| Self::is_supported_symbol(symbol))
+            .filter_map(|(pair_name, symbol)| {
+                let base_id: CurrencyId = symbol.base.as_str().into();
+                let quote_id: CurrencyId = symbol.quote.as_str().into();
+                let base = *self.supported_currencies.get(&base_id)?;
+                let quote = *self.supported_currencies.get(&quote_id)?;
+
+                let specific_currency_pair = symbol.altname.as_str().into();
+                let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+                self.unified_to_specific
+                    .write()
+                    .insert(unified_currency_pair, specific_currency_pair);
+                self.specific_to_unified
+                    .write()
+                    .insert(specific_currency_pair, unified_currency_pair);
+                self.pair_name_to_unified
+                    .write()
+                    .insert(pair_name, unified_currency_pair);
+
+                if let Some(ws_name) = &symbol.wsname {
+                    let ws_symbol = Self::get_ws_symbol(ws_name);
+                    self.ws_symbol_to_unified
+                        .write()
+                        .insert(ws_symbol.clone(), unified_currency_pair);
+                    self.specific_to_ws_symbol
+                        .write()
+                        .insert(specific_currency_pair, ws_symbol);
+                }
+
+                let price_tick = symbol
+                    .price_tick
+                    .unwrap_or_else(|| Decimal::new(1, symbol.pair_decimals));
+
+                Some(Arc::new(Symbol::new(
+                    false,
+                    base_id,
+                    base,
+                    quote_id,
+                    quote,
+                    None,
+                    None,
+                    symbol.min_amount,
+                    None,
+                    symbol.min_cost,
+                    base,
+                    None,
+                    Precision::ByTick { tick: price_tick },
+                    Precision::ByTick {
+                        tick: Decimal::new(1, symbol.lot_decimals),
+                    },
+                )))
+            })
+            .collect_vec())
+    }
+
+    fn is_supported_symbol(symbol: &KrakenSymbol) -> bool {
+        // Dark pool pairs have ".d" suffix and can't be traded via websocket
+        let is_dark_pool = symbol.altname.ends_with(".d");
+        let is_online = symbol
+            .status
+            .as_ref()
+            .map_or(true, |status| status == "online");
+
+        !is_dark_pool && is_online
+    }
+
+    /// Converts websocket API v1 symbol (e.g. "XBT/USD") to websocket API v2 symbol (e.g. "BTC/USD")
+    pub(crate) fn get_ws_symbol(ws_name: &str) -> String {
+        ws_name
+            .split('/')
+            .map(|asset| {
+                WS_ASSET_NAMES
+                    .iter()
+                    .find(|(kraken_name, _)| *kraken_name == asset)
+                    .map_or(asset, |(_, common_name)| common_name)
+            })
+            .join("/")
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let mut builder = self.private_uri_builder("/0/private/AddOrder");
+        builder.add_kv("pair", specific_currency_pair);
+        builder.add_kv("type", Self::get_side_str(header.side));
+        builder.add_kv("volume", header.amount);
+        builder.add_kv("cl_ord_id", header.client_order_id.as_str());
+
+        // Fee is always charged in quote currency to simplify fee currency detection for trades
+        let mut order_flags = "fciq";
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                builder.add_kv("ordertype", "limit");
+                builder.add_kv("price", price);
+                if execution_type == OrderExecutionType::MakerOnly {
+                    order_flags = "post,fciq";
+                }
+            }
+            OrderOptions::User(UserOrder::Market) => builder.add_kv("ordertype", "market"),
+            OrderOptions::User(UserOrder::StopLoss {
+                stop_price,
+                trigger_price_type,
+            }) => {
+                builder.add_kv("ordertype", "stop-loss");
+                builder.add_kv("price", stop_price);
+                builder.add_kv("trigger", get_trigger(trigger_price_type)?);
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+        builder.add_kv("oflags", order_flags);
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let deserialized: KrakenResult<KrakenAddOrderResult> =
+            serde_json::from_str(&response.content)
+                .map_err(|err| ExchangeError::parsing(format!("Unable to parse txid: {err:?}")))?;
+
+        deserialized
+            .result
+            .exchange_order_ids
+            .into_iter()
+            .next()
+            .ok_or_else(|| ExchangeError::parsing("No one txid received".to_owned()))
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = self.private_uri_builder("/0/private/CancelOrder");
+        // Order may be canceled passing either txid, userref or cl_ord_id via "txid" key
+        builder.add_kv("txid", exchange_order_id);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_all_orders(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = self.private_uri_builder("/0/private/CancelAll");
+
+        let log_args = "Cancel all orders".to_owned();
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    /// Timeout is in seconds, zero timeout disarms the switch
+    #[named]
+    pub(super) async fn do_cancel_all_after(
+        &self,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = self.private_uri_builder("/0/private/CancelAllOrdersAfter");
+        builder.add_kv("timeout", timeout.as_secs());
+
+        let log_args = format!("Cancel all orders after {timeout:?}");
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = self.private_uri_builder("/0/private/OpenOrders");
+
+        self.post_private(builder, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(
+        &self,
+        response: &RestResponse,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<Vec<OrderInfo>> {
+        let orders: KrakenResult<KrakenOpenOrders> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        // Kraken doesn't support filtering of open orders by pair
+        orders
+            .result
+            .open
+            .into_iter()
+            .map(|(exchange_order_id, order)| {
+                self.specific_order_info_to_unified(exchange_order_id, order)
+            })
+            .filter_ok(|order| currency_pair.map_or(true, |pair| order.currency_pair == pair))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+        let exchange_order_id = order.exchange_order_id().ok_or_else(|| {
+            ExchangeError::unknown(&format!(
+                "Kraken can't get order info for {client_order_id} without exchange order id"
+            ))
+        })?;
+
+        let mut builder = self.private_uri_builder("/0/private/QueryOrders");
+        builder.add_kv("txid", &exchange_order_id);
+
+        let log_args = format!("order {client_order_id}");
+        self.post_private(builder, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: KrakenResult<HashMap<ExchangeOrderId, KrakenOrderInfo>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_order_info request")?;
+
+        let (exchange_order_id, order) = orders
+            .result
+            .into_iter()
+            .next()
+            .context("No one order info received")?;
+
+        self.specific_order_info_to_unified(exchange_order_id, order)
+    }
+
+    fn specific_order_info_to_unified(
+        &self,
+        exchange_order_id: ExchangeOrderId,
+        specific: KrakenOrderInfo,
+    ) -> Result<OrderInfo> {
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.descr.pair.as_str().into())?,
+            exchange_order_id,
+            specific
+                .cl_ord_id
+                .unwrap_or_else(|| ClientOrderId::new(Default::default())),
+            specific.descr.side,
+            Kraken::get_local_order_status(&specific.status),
+            specific.descr.price,
+            specific.amount,
+            specific.average_fill_price,
+            specific.filled_amount,
+            // Fee currency isn't returned by Kraken on order requests
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "pending" | "open" => OrderStatus::Created,
+            "closed" => OrderStatus::Completed,
+            "canceled" | "expired" => OrderStatus::Canceled,
+            _ => panic!("Kraken: unexpected order status {}", status),
+        }
+    }
+
+    pub(super) fn get_side_str(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = self.private_uri_builder("/0/private/TradesHistory");
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("start", date_time.timestamp());
+        }
+
+        self.post_private(builder, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(
+        &self,
+        symbol: &Symbol,
+        response: &RestResponse,
+    ) -> Result<Vec<OrderTrade>> {
+        let trades: KrakenResult<KrakenTradesHistory> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        let pair_name_to_unified = self.pair_name_to_unified.read();
+        // Kraken doesn't support filtering of trades history by pair
+        Ok(trades
+            .result
+            .trades
+            .into_iter()
+            .filter(|(_, trade)| {
+                pair_name_to_unified.get(&trade.pair) == Some(&symbol.currency_pair())
+            })
+            .map(|(trade_id, trade)| OrderTrade {
+                exchange_order_id: trade.exchange_order_id,
+                trade_id: TradeId::from(trade_id),
+                datetime: trade.time,
+                price: trade.price,
+                amount: trade.amount,
+                side: trade.side,
+                order_role: match trade.maker {
+                    true => OrderRole::Maker,
+                    false => OrderRole::Taker,
+                },
+                // All orders are created with "fciq" flag so fee is charged in quote currency
+                fee_currency_code: symbol.quote_currency_code(),
+                fee_rate: None,
+                fee_amount: Some(trade.fee),
+                fill_type: OrderFillType::UserTrade,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = self.private_uri_builder("/0/private/Balance");
+
+        self.post_private(builder, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let balances: KrakenResult<HashMap<String, Decimal>> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(balances
+            .result
+            .into_iter()
+            .map(|(asset_id, balance)| {
+                let currency_id: CurrencyId = asset_id.as_str().into();
+                let currency_code = match self.supported_currencies.get(&currency_id) {
+                    Some(currency_code) => *currency_code,
+                    None => self.currency_aliases.unify(asset_id.as_str().into()),
+                };
+
+                ExchangeBalance {
+                    currency_code,
+                    balance,
+                }
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_websocket_token(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = self.private_uri_builder("/0/private/GetWebSocketsToken");
+
+        self.post_private(builder, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_websocket_token(response: &RestResponse) -> Result<String> {
+        #[derive(Deserialize)]
+        struct WebsocketToken {
+            token: String,
+        }
+
+        let token: KrakenResult<WebsocketToken> = serde_json::from_str(&response.content)
+            .context("Unable to parse websocket token response for Kraken")?;
+
+        Ok(token.result.token)
+    }
+}
+
+fn get_trigger(trigger_price_type: TriggerPriceType) -> Result<&'static str, ExchangeError> {
+    match trigger_price_type {
+        TriggerPriceType::Last => Ok("last"),
+        TriggerPriceType::Index => Ok("index"),
+        TriggerPriceType::Mark => Err(ExchangeError::new(
+            ExchangeErrorType::InvalidOrder,
+            "Kraken doesn't support mark price trigger".to_owned(),
+            None,
+        )),
+    }
+}
+
+pub struct KrakenBuilder;
+
+impl ExchangeClientBuilder for KrakenBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Kraken::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: false,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: true,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: true,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(60)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Kraken".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_signature() {
+        // Test data from https://docs.kraken.com/rest/#section/Authentication/Headers-and-Signature
+        let secret_key = "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==";
+        let path = "/0/private/AddOrder";
+        let body =
+            "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25";
+
+        let signature = Kraken::create_signature(secret_key, path, body.as_bytes());
+
+        assert_eq!(
+            signature,
+            "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ=="
+        );
+    }
+
+    #[test]
+    fn convert_ws_symbol() {
+        assert_eq!(Kraken::get_ws_symbol("XBT/USD"), "BTC/USD");
+        assert_eq!(Kraken::get_ws_symbol("XDG/EUR"), "DOGE/EUR");
+        assert_eq!(Kraken::get_ws_symbol("ETH/XBT"), "ETH/BTC");
+        assert_eq!(Kraken::get_ws_symbol("DOT/USD"), "DOT/USD");
+    }
+}
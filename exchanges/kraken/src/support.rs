@@ -0,0 +1,528 @@
+use crate::kraken::Kraken;
+use crate::types::{KrakenBookPayload, KrakenExecType, KrakenExecution, KrakenTradePayload};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{
+    FillAmount, FillEvent, SpecialOrderData,
+};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::OrderRole;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+// Kraken supports 10, 25, 100, 500 and 1000 levels of order book
+const ORDER_BOOK_DEPTH: u32 = 25;
+
+#[async_trait]
+impl Support for Kraken {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Channel(channel_message) => {
+                self.handle_channel_message(channel_message)?
+            }
+            WebsocketMessage::MethodResponse(response) => self.handle_method_response(response)?,
+            WebsocketMessage::Unknown(_) => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let ws_symbols = self.get_traded_ws_symbols();
+        if !ws_symbols.is_empty() {
+            let book = Request::subscribe(SubscriptionParams {
+                channel: Channel::Book,
+                symbol: Some(&ws_symbols),
+                depth: Some(ORDER_BOOK_DEPTH),
+                ..Default::default()
+            });
+            (self.websocket_message_callback)(WebSocketRole::Main, book)?;
+
+            let trade = Request::subscribe(SubscriptionParams {
+                channel: Channel::Trade,
+                symbol: Some(&ws_symbols),
+                snapshot: Some(false),
+                ..Default::default()
+            });
+            (self.websocket_message_callback)(WebSocketRole::Main, trade)?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Secondary) {
+            return Ok(());
+        }
+
+        let token = self.websocket_token.lock().clone();
+        let token = token.context("Websocket token for Kraken private channels wasn't received")?;
+        // Open orders are requested via REST, so only updates are needed
+        let executions = Request::subscribe(SubscriptionParams {
+            channel: Channel::Executions,
+            token: Some(&token),
+            snap_orders: Some(false),
+            snap_trades: Some(false),
+            ..Default::default()
+        });
+        (self.websocket_message_callback)(WebSocketRole::Secondary, executions)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        *self.websocket_token.lock() = None;
+
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => {
+                // Private channels require token which should be used within 15 minutes after receiving
+                let response = self.request_websocket_token().await?;
+                *self.websocket_token.lock() = Some(Kraken::parse_websocket_token(&response)?);
+
+                self.hosts.web_socket2_host
+            }
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""channel":"executions""#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Kraken {
+    fn get_traded_ws_symbols(&self) -> Vec<String> {
+        let ws_symbols = self.specific_to_ws_symbol.read();
+        self.traded_specific_currencies
+            .lock()
+            .iter()
+            .filter_map(|currency_pair| ws_symbols.get(currency_pair).cloned())
+            .collect()
+    }
+
+    fn get_unified_currency_pair_by_ws_symbol(&self, ws_symbol: &str) -> Result<CurrencyPair> {
+        self.ws_symbol_to_unified
+            .read()
+            .get(ws_symbol)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found websocket symbol '{ws_symbol}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    fn handle_method_response(&self, response: MethodResponse) -> Result<()> {
+        match response.success {
+            Some(false) => {
+                let err = format!(
+                    "Kraken websocket: failed {} request: {:?}",
+                    response.method, response.error
+                );
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => {
+                if response.method != "pong" {
+                    log::info!(
+                        "Kraken websocket: {} request succeeded: {:?}",
+                        response.method,
+                        response.result
+                    );
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_channel_message(&self, message: ChannelMessage) -> Result<()> {
+        match message {
+            ChannelMessage::Book { data_type, data } => {
+                let event_type = match data_type {
+                    DataType::Snapshot => EventType::Snapshot,
+                    DataType::Update => EventType::Update,
+                };
+                for book in data {
+                    self.handle_order_book(book, event_type)?;
+                }
+            }
+            ChannelMessage::Trade { data } => self.handle_trades(data)?,
+            ChannelMessage::Executions { data_type, data } => {
+                if data_type == DataType::Snapshot {
+                    // We're not interested in executions snapshot
+                    return Ok(());
+                }
+
+                for execution in data {
+                    self.handle_execution(execution)?;
+                }
+            }
+            ChannelMessage::Heartbeat | ChannelMessage::Status => (),
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_book(&self, book: KrakenBookPayload, event_type: EventType) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair_by_ws_symbol(&book.symbol)?;
+
+        // Level with zero quantity should be removed from order book
+        let mut order_book_data = OrderBookData::default();
+        for level in book.bids {
+            order_book_data.bids.insert(level.price, level.qty);
+        }
+        for level in book.asks {
+            order_book_data.asks.insert(level.price, level.qty);
+        }
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trades(&self, trades: Vec<KrakenTradePayload>) -> Result<()> {
+        for trade in trades {
+            (self.handle_trade_callback)(
+                self.get_unified_currency_pair_by_ws_symbol(&trade.symbol)?,
+                Trade {
+                    trade_id: TradeId::Number(trade.trade_id),
+                    price: trade.price,
+                    quantity: trade.qty,
+                    side: trade.side,
+                    transaction_time: trade.timestamp,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn handle_execution(&self, execution: KrakenExecution) -> Result<()> {
+        match execution.exec_type {
+            KrakenExecType::New => {
+                if let Some(client_order_id) = execution.cl_ord_id {
+                    (self.order_created_callback)(
+                        client_order_id,
+                        execution.order_id,
+                        EventSourceType::WebSocket,
+                    );
+                }
+            }
+            KrakenExecType::Canceled | KrakenExecType::Expired => {
+                if let Some(client_order_id) = execution.cl_ord_id {
+                    (self.order_cancelled_callback)(
+                        client_order_id,
+                        execution.order_id,
+                        EventSourceType::WebSocket,
+                    );
+                }
+            }
+            KrakenExecType::Trade => self.handle_order_fill(execution)?,
+            // Other execution types don't change order state we are tracking
+            KrakenExecType::Other => (),
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_fill(&self, execution: KrakenExecution) -> Result<()> {
+        let special_order_data = match (&execution.symbol, execution.side, execution.order_qty) {
+            (Some(symbol), Some(order_side), Some(order_amount)) => Some(SpecialOrderData {
+                currency_pair: self.get_unified_currency_pair_by_ws_symbol(symbol)?,
+                order_side,
+                order_amount,
+            }),
+            _ => None,
+        };
+
+        let order_role = match execution.liquidity_ind.as_deref() {
+            Some("m") => Some(OrderRole::Maker),
+            Some("t") => Some(OrderRole::Taker),
+            _ => None,
+        };
+
+        // All orders are created with "fciq" flag so commission is charged in a single currency
+        let commission_currency_code = execution
+            .fees
+            .first()
+            .map(|fee| self.currency_aliases.unify(CurrencyCode::new(&fee.asset)));
+        let commission_amount = match execution.fees.is_empty() {
+            true => None,
+            false => Some(execution.fees.iter().map(|fee| fee.qty).sum()),
+        };
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: execution.exec_id.map(TradeId::from),
+            client_order_id: execution.cl_ord_id,
+            exchange_order_id: execution.order_id,
+            fill_price: execution
+                .last_price
+                .context("No last_price in Kraken trade execution")?,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: execution
+                    .last_qty
+                    .context("No last_qty in Kraken trade execution")?,
+                total_filled_amount: execution.cum_qty,
+            },
+            order_role,
+            commission_currency_code,
+            commission_rate: None,
+            commission_amount,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data,
+            fill_date: execution.timestamp,
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    Channel(ChannelMessage),
+    MethodResponse(MethodResponse),
+    Unknown(Value),
+}
+
+/// Response on request sent via websocket, e.g. subscription or ping
+#[derive(Deserialize, Debug)]
+struct MethodResponse {
+    method: String,
+    success: Option<bool>,
+    error: Option<String>,
+    result: Option<Value>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum DataType {
+    Snapshot,
+    Update,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "channel")]
+enum ChannelMessage {
+    Book {
+        #[serde(rename = "type")]
+        data_type: DataType,
+        data: Vec<KrakenBookPayload>,
+    },
+    Trade {
+        data: Vec<KrakenTradePayload>,
+    },
+    Executions {
+        #[serde(rename = "type")]
+        data_type: DataType,
+        data: Vec<KrakenExecution>,
+    },
+    Heartbeat,
+    Status,
+}
+
+#[derive(Serialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Channel {
+    #[default]
+    Book,
+    Trade,
+    Executions,
+}
+
+#[derive(Serialize, Default)]
+struct SubscriptionParams<'a> {
+    channel: Channel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snap_orders: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snap_trades: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    method: &'static str,
+    params: SubscriptionParams<'a>,
+}
+
+impl<'a> Request<'a> {
+    fn subscribe(params: SubscriptionParams<'a>) -> String {
+        serde_json::to_string(&Request {
+            method: "subscribe",
+            params,
+        })
+        .expect("Failed to serialize Kraken subscription message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_executions_subscription() {
+        let message = Request::subscribe(SubscriptionParams {
+            channel: Channel::Executions,
+            token: Some("token"),
+            snap_orders: Some(false),
+            snap_trades: Some(false),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            message,
+            r#"{"method":"subscribe","params":{"channel":"executions","token":"token","snap_orders":false,"snap_trades":false}}"#
+        );
+    }
+
+    #[test]
+    fn parse_execution_trade() {
+        let msg = r#"{"channel":"executions","type":"update","data":[{"order_id":"OK4GJX-KSTLS-7DZZO5","cl_ord_id":"1234567","symbol":"BTC/USD","side":"sell","order_qty":0.1,"exec_type":"trade","exec_id":"TT5ZBM-NHGTW-JCSSRJ","trade_id":123,"last_qty":0.05,"last_price":26637.5,"liquidity_ind":"m","cum_qty":0.05,"fees":[{"asset":"USD","qty":1.2}],"order_status":"partially_filled","timestamp":"2023-09-22T10:33:05.709950Z"}]}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Channel(ChannelMessage::Executions { data_type, data }) = message
+        else {
+            panic!("Unexpected message {message:?}");
+        };
+
+        assert_eq!(data_type, DataType::Update);
+        let execution = &data[0];
+        assert_eq!(execution.exec_type, KrakenExecType::Trade);
+        assert_eq!(execution.order_id.as_str(), "OK4GJX-KSTLS-7DZZO5");
+        assert_eq!(execution.last_qty, Some(dec!(0.05)));
+        assert_eq!(execution.fees[0].asset, "USD");
+    }
+
+    #[test]
+    fn parse_service_messages() {
+        let heartbeat: WebsocketMessage =
+            serde_json::from_str(r#"{"channel":"heartbeat"}"#).expect("in test");
+        assert!(matches!(
+            heartbeat,
+            WebsocketMessage::Channel(ChannelMessage::Heartbeat)
+        ));
+
+        let subscribed: WebsocketMessage = serde_json::from_str(
+            r#"{"method":"subscribe","result":{"channel":"book","symbol":"BTC/USD","depth":25},"success":true,"time_in":"2023-09-25T09:04:31.742599Z","time_out":"2023-09-25T09:04:31.742648Z"}"#,
+        )
+        .expect("in test");
+        assert!(matches!(
+            subscribed,
+            WebsocketMessage::MethodResponse(MethodResponse {
+                success: Some(true),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::HashMap;
+
+/// Common envelope of Kraken REST responses. Errors are checked in `ErrorHandlerKraken`
+/// so only the result part is needed here
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenResult<T> {
+    pub(crate) result: T,
+}
+
+/// Kraken asset description from `/0/public/Assets`
+/// "XXBT": {
+///   "aclass": "currency",
+///   "altname": "XBT",
+///   "decimals": 10,
+///   "display_decimals": 5,
+///   "status": "enabled"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenAsset {
+    pub(crate) altname: String,
+}
+
+/// Kraken symbol description from `/0/public/AssetPairs`
+/// "XXBTZUSD": {
+///   "altname": "XBTUSD",
+///   "wsname": "XBT/USD",
+///   "aclass_base": "currency",
+///   "base": "XXBT",
+///   "aclass_quote": "currency",
+///   "quote": "ZUSD",
+///   "cost_decimals": 5,
+///   "pair_decimals": 1,
+///   "lot_decimals": 8,
+///   "ordermin": "0.0001",
+///   "costmin": "0.5",
+///   "tick_size": "0.1",
+///   "status": "online"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenSymbol {
+    pub(crate) altname: String,
+    pub(crate) wsname: Option<String>,
+    pub(crate) base: String,
+    pub(crate) quote: String,
+    pub(crate) pair_decimals: u32,
+    pub(crate) lot_decimals: u32,
+    #[serde(rename = "ordermin")]
+    pub(crate) min_amount: Option<Amount>,
+    #[serde(rename = "costmin")]
+    pub(crate) min_cost: Option<Price>,
+    #[serde(rename = "tick_size")]
+    pub(crate) price_tick: Option<Price>,
+    pub(crate) status: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenAddOrderResult {
+    #[serde(rename = "txid")]
+    pub(crate) exchange_order_ids: Vec<ExchangeOrderId>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenOpenOrders {
+    pub(crate) open: HashMap<ExchangeOrderId, KrakenOrderInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenOrderDescription {
+    /// Pair altname, e.g. "XBTUSD"
+    pub(crate) pair: String,
+    #[serde(rename = "type", deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) price: Price,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenOrderInfo {
+    #[serde(default)]
+    pub(crate) cl_ord_id: Option<ClientOrderId>,
+    pub(crate) status: String,
+    pub(crate) descr: KrakenOrderDescription,
+    #[serde(rename = "vol")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "vol_exec")]
+    pub(crate) filled_amount: Amount,
+    #[serde(rename = "price")]
+    pub(crate) average_fill_price: Price,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenTradesHistory {
+    pub(crate) trades: HashMap<String, KrakenTrade>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenTrade {
+    #[serde(rename = "ordertxid")]
+    pub(crate) exchange_order_id: ExchangeOrderId,
+    /// Pair name as key of `/0/public/AssetPairs` response, e.g. "XXBTZUSD"
+    pub(crate) pair: String,
+    #[serde(deserialize_with = "deserialize_unix_time")]
+    pub(crate) time: DateTime,
+    #[serde(rename = "type", deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) price: Price,
+    pub(crate) fee: Amount,
+    #[serde(rename = "vol")]
+    pub(crate) amount: Amount,
+    #[serde(default)]
+    pub(crate) maker: bool,
+}
+
+/// Book level of websocket `book` channel
+/// { "price": 45283.5, "qty": 0.10000000 }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenBookLevel {
+    pub(crate) price: Price,
+    pub(crate) qty: Amount,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenBookPayload {
+    pub(crate) symbol: String,
+    #[serde(default)]
+    pub(crate) bids: Vec<KrakenBookLevel>,
+    #[serde(default)]
+    pub(crate) asks: Vec<KrakenBookLevel>,
+}
+
+/// Public trade of websocket `trade` channel
+/// {
+///   "symbol": "BTC/USD",
+///   "side": "sell",
+///   "price": 26637.5,
+///   "qty": 0.0219,
+///   "ord_type": "limit",
+///   "trade_id": 4665846,
+///   "timestamp": "2023-09-25T07:48:36.925533Z"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenTradePayload {
+    pub(crate) symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) price: Price,
+    pub(crate) qty: Amount,
+    pub(crate) trade_id: u64,
+    pub(crate) timestamp: DateTime,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum KrakenExecType {
+    New,
+    Trade,
+    Canceled,
+    Expired,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFee {
+    pub(crate) asset: String,
+    pub(crate) qty: Amount,
+}
+
+/// Order event of private websocket `executions` channel
+/// {
+///   "order_id": "OK4GJX-KSTLS-7DZZO5",
+///   "cl_ord_id": "1234567",
+///   "symbol": "BTC/USD",
+///   "side": "sell",
+///   "order_qty": 0.1,
+///   "exec_type": "trade",
+///   "exec_id": "TT5ZBM-NHGTW-JCSSRJ",
+///   "last_qty": 0.1,
+///   "last_price": 26637.5,
+///   "liquidity_ind": "t",
+///   "cum_qty": 0.1,
+///   "fees": [{ "asset": "USD", "qty": 6.92575 }],
+///   "order_status": "filled",
+///   "timestamp": "2023-09-22T10:33:05.709950Z"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenExecution {
+    pub(crate) exec_type: KrakenExecType,
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(default)]
+    pub(crate) cl_ord_id: Option<ClientOrderId>,
+    pub(crate) symbol: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_side")]
+    pub(crate) side: Option<OrderSide>,
+    pub(crate) order_qty: Option<Amount>,
+    pub(crate) exec_id: Option<String>,
+    pub(crate) last_qty: Option<Amount>,
+    pub(crate) last_price: Option<Price>,
+    pub(crate) cum_qty: Option<Amount>,
+    pub(crate) liquidity_ind: Option<String>,
+    #[serde(default)]
+    pub(crate) fees: Vec<KrakenFee>,
+    pub(crate) timestamp: Option<DateTime>,
+}
+
+fn parse_side<E: de::Error>(side: &str) -> Result<OrderSide, E> {
+    match side {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown Kraken order side: {side}"
+        ))),
+    }
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse_side(&String::deserialize(deserializer)?)
+}
+
+fn deserialize_optional_side<'de, D>(deserializer: D) -> Result<Option<OrderSide>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|side| parse_side(&side))
+        .transpose()
+}
+
+/// Kraken REST API returns time as unix timestamp in seconds with fractional part
+fn deserialize_unix_time<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let time = Decimal::deserialize(deserializer)?;
+    let secs = time.trunc().to_i64();
+    let nanos = (time.fract() * dec!(1_000_000_000)).to_u32();
+
+    secs.zip(nanos)
+        .and_then(|(secs, nanos)| Utc.timestamp_opt(secs, nanos).single())
+        .ok_or_else(|| de::Error::custom(format!("Invalid Kraken time: {time}")))
+}
@@ -0,0 +1,927 @@
+use crate::types::{
+    KrakenFuturesAccounts, KrakenFuturesFills, KrakenFuturesInstrument, KrakenFuturesInstruments,
+    KrakenFuturesOpenOrders, KrakenFuturesOpenPositions, KrakenFuturesOrdersStatus,
+    KrakenFuturesSendOrder,
+};
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::http::request::Builder;
+use hyper::{StatusCode, Uri};
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleOrderFilledCb,
+    HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb, Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, OrderStatus, Price, TriggerPriceType, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Statuses of `sendStatus` and `cancelStatus` which mean successful request
+const SUCCESS_STATUSES: &[&str] = &["placed", "cancelled", "noOrdersToCancel"];
+
+#[derive(Default)]
+pub struct ErrorHandlerKrakenFutures;
+
+impl ErrorHandler for ErrorHandlerKrakenFutures {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        match response.status {
+            // Kraken Futures reports most of errors with status 200 and "result": "error"
+            StatusCode::OK => {
+                let value: Value = serde_json::from_str(&response.content).map_err(|err| {
+                    ExchangeError::parsing(format!("Unable to parse response: {err:?}"))
+                })?;
+
+                if value["result"] == "error" {
+                    let message = value["error"].as_str().unwrap_or(&response.content);
+                    return Err(ExchangeError::new(
+                        ExchangeErrorType::Unknown,
+                        message.to_owned(),
+                        None,
+                    ));
+                }
+
+                // Rejected orders and cancellations are returned with "result": "success"
+                let status = value["sendStatus"]["status"]
+                    .as_str()
+                    .or_else(|| value["cancelStatus"]["status"].as_str());
+                match status {
+                    Some(status) if !SUCCESS_STATUSES.contains(&status) => Err(ExchangeError::new(
+                        ExchangeErrorType::Unknown,
+                        status.to_owned(),
+                        None,
+                    )),
+                    _ => Ok(()),
+                }
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(ExchangeError::new(
+                ExchangeErrorType::Authentication,
+                response.content.clone(),
+                None,
+            )),
+            StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND => Err(ExchangeError::new(
+                ExchangeErrorType::SendError,
+                response.content.clone(),
+                None,
+            )),
+            _ => Err(ExchangeError::unknown(&response.content)),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://docs.futures.kraken.com/#http-api-trading-v3-api-order-management-send-order
+        match error.message.as_str() {
+            "notFound" => ExchangeErrorType::OrderNotFound,
+            "filled" => ExchangeErrorType::OrderCompleted,
+            "insufficientAvailableFunds" | "wouldCauseLiquidation" => {
+                ExchangeErrorType::InsufficientFunds
+            }
+            "invalidOrderType"
+            | "invalidSide"
+            | "invalidSize"
+            | "invalidPrice"
+            | "postWouldExecute"
+            | "iocWouldNotExecute"
+            | "outsidePriceCollar"
+            | "selfFill"
+            | "tooManySmallOrders"
+            | "maxPositionViolation"
+            | "wouldNotReducePosition"
+            | "clientOrderIdAlreadyExist"
+            | "clientOrderIdTooLong" => ExchangeErrorType::InvalidOrder,
+            "apiLimitExceeded" => ExchangeErrorType::RateLimit,
+            "authenticationError" | "nonceBelowThreshold" | "nonceDuplicate" => {
+                ExchangeErrorType::Authentication
+            }
+            "marketSuspended" | "marketInactive" | "marketUnavailable" | "Unavailable" => {
+                ExchangeErrorType::ServiceUnavailable
+            }
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersKrakenFutures {
+    api_key: String,
+    secret_key: String,
+}
+
+impl RestHeadersKrakenFutures {
+    pub fn new(api_key: String, secret_key: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+        }
+    }
+}
+
+impl RestHeaders for RestHeadersKrakenFutures {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        if self.api_key.is_empty() {
+            return builder;
+        }
+
+        // Parameters of all requests are sent in query, so only query and path are signed
+        let post_data = uri.query().unwrap_or_default();
+        let endpoint_path = uri.path().trim_start_matches("/derivatives");
+        let message = format!("{post_data}{endpoint_path}");
+
+        builder.header("APIKey", &self.api_key).header(
+            "Authent",
+            KrakenFutures::create_signature(&self.secret_key, &message),
+        )
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+const DEFAULT_CURRENCY_ALIASES: &[(&str, &str)] = &[("xbt", "btc")];
+/// Only multi-collateral futures are supported
+const FLEXIBLE_FUTURES: &str = "flexible_futures";
+
+pub struct KrakenFutures {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerKrakenFutures, RestHeadersKrakenFutures>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl KrakenFutures {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> KrakenFutures {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerKrakenFutures::default(),
+                ),
+                RestHeadersKrakenFutures::new(
+                    settings.api_key.clone(),
+                    settings.secret_key.clone(),
+                ),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(
+                DEFAULT_CURRENCY_ALIASES,
+                &settings.currency_aliases,
+            ),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Public and private feeds are available via the same websocket endpoint
+        Hosts {
+            web_socket_host: "wss://futures.kraken.com/ws/v1",
+            web_socket2_host: "wss://futures.kraken.com/ws/v1",
+            rest_host: "https://futures.kraken.com",
+        }
+    }
+
+    /// Signature of REST request or websocket challenge:
+    /// base64(HMAC-SHA512(base64 decoded secret, SHA256(message)))
+    pub(super) fn create_signature(secret_key: &str, message: &str) -> String {
+        let secret_key =
+            base64::decode(secret_key).expect("Kraken Futures secret key should be base64 encoded");
+        let mut hmac = Hmac::<Sha512>::new_from_slice(&secret_key)
+            .expect("Unable to calculate hmac for Kraken Futures signature");
+        hmac.update(&Sha256::digest(message.as_bytes()));
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+
+    async fn get(
+        &self,
+        builder: UriBuilder,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client.get(uri, action_name, log_args).await
+    }
+
+    async fn post(
+        &self,
+        builder: UriBuilder,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .post(uri, None, action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/derivatives/api/v3/instruments");
+
+        self.get(builder, function_name!(), "".to_string()).await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let instruments: KrakenFuturesInstruments = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from Kraken Futures")?;
+
+        Ok(instruments
+            .instruments
+            .into_iter()
+            .filter(|instrument| {
+                instrument.tradeable && instrument.instrument_type == FLEXIBLE_FUTURES
+            })
+            .filter_map(|instrument| self.parse_symbol(instrument))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, instrument: KrakenFuturesInstrument) -> Option<Arc<Symbol>> {
+        let base_id: CurrencyId = instrument.base?.as_str().into();
+        let quote_id: CurrencyId = instrument.quote?.as_str().into();
+        let base = self.currency_aliases.unify(base_id.as_str().into());
+        let quote = self.currency_aliases.unify(quote_id.as_str().into());
+
+        let specific_currency_pair = instrument.symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+        self.supported_currencies.insert(base_id, base);
+        self.supported_currencies.insert(quote_id, quote);
+
+        let amount_tick = Decimal::TEN.powi(
+            -instrument
+                .contract_value_trade_precision
+                .unwrap_or_default(),
+        );
+
+        Some(Arc::new(Symbol::new(
+            true,
+            base_id,
+            base,
+            quote_id,
+            quote,
+            None,
+            None,
+            Some(amount_tick),
+            None,
+            None,
+            base,
+            // Balances of multi-collateral account are evaluated in USD
+            Some(quote),
+            Precision::ByTick {
+                tick: instrument.tick_size?,
+            },
+            Precision::ByTick { tick: amount_tick },
+        )))
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let mut builder = UriBuilder::from_path("/derivatives/api/v3/sendorder");
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                let order_type = match execution_type {
+                    OrderExecutionType::MakerOnly => "post",
+                    OrderExecutionType::None => "lmt",
+                };
+                builder.add_kv("orderType", order_type);
+                builder.add_kv("limitPrice", price);
+            }
+            OrderOptions::User(UserOrder::Market) => builder.add_kv("orderType", "mkt"),
+            OrderOptions::User(UserOrder::StopLoss {
+                stop_price,
+                trigger_price_type,
+            }) => {
+                // Stop order without limit price triggers market order
+                builder.add_kv("orderType", "stp");
+                builder.add_kv("stopPrice", stop_price);
+                builder.add_kv("triggerSignal", get_trigger_signal(trigger_price_type));
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("side", Self::get_side_str(header.side));
+        builder.add_kv("size", header.amount);
+        builder.add_kv("cliOrdId", header.client_order_id.as_str());
+
+        let log_args = format!("Create order for {header:?}");
+        self.post(builder, function_name!(), log_args).await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let deserialized: KrakenFuturesSendOrder = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse order id: {err:?}")))?;
+
+        Ok(deserialized.send_status.order_id)
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/derivatives/api/v3/cancelorder");
+        builder.add_kv("order_id", exchange_order_id);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post(builder, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/derivatives/api/v3/cancelallorders");
+        builder.add_kv("symbol", self.get_specific_currency_pair(currency_pair));
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.post(builder, function_name!(), log_args).await
+    }
+
+    /// Timeout is in seconds, zero timeout disarms the switch
+    #[named]
+    pub(super) async fn do_cancel_all_after(
+        &self,
+        timeout: Duration,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/derivatives/api/v3/cancelallordersafter");
+        builder.add_kv("timeout", timeout.as_secs());
+
+        let log_args = format!("Cancel all orders after {timeout:?}");
+        self.post(builder, function_name!(), log_args).await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/derivatives/api/v3/openorders");
+
+        self.get(builder, function_name!(), "".to_string()).await
+    }
+
+    pub(super) fn parse_open_orders(
+        &self,
+        response: &RestResponse,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<Vec<OrderInfo>> {
+        let orders: KrakenFuturesOpenOrders = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        // Kraken Futures doesn't support filtering of open orders by symbol
+        orders
+            .open_orders
+            .into_iter()
+            .map(|order| {
+                Ok(OrderInfo::new(
+                    self.get_unified_currency_pair(&order.symbol.as_str().into())?,
+                    order.order_id,
+                    order
+                        .cli_ord_id
+                        .unwrap_or_else(|| ClientOrderId::new(Default::default())),
+                    order.side,
+                    OrderStatus::Created,
+                    order.limit_price.unwrap_or_default(),
+                    order.filled_size + order.unfilled_size,
+                    // Average fill price isn't returned for open orders
+                    Decimal::ZERO,
+                    order.filled_size,
+                    None,
+                    None,
+                    None,
+                ))
+            })
+            .filter_ok(|order| currency_pair.map_or(true, |pair| order.currency_pair == pair))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+        let mut builder = UriBuilder::from_path("/derivatives/api/v3/orders/status");
+        builder.add_kv("cliOrdIds", client_order_id.as_str());
+
+        let log_args = format!("order {client_order_id}");
+        self.post(builder, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: KrakenFuturesOrdersStatus = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        let order_status = orders
+            .orders
+            .into_iter()
+            .next()
+            .context("No one order info received")?;
+        let order = order_status.order;
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&order.symbol.as_str().into())?,
+            order.order_id,
+            order
+                .cli_ord_id
+                .unwrap_or_else(|| ClientOrderId::new(Default::default())),
+            order.side,
+            KrakenFutures::get_local_order_status(&order_status.status),
+            order.limit_price.unwrap_or_default(),
+            order.quantity,
+            // Average fill price isn't returned by order status request
+            Decimal::ZERO,
+            order.filled,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "ENTERED_BOOK" | "TRIGGER_PLACED" | "TRIGGER_ACTIVATED" => OrderStatus::Created,
+            "FULLY_EXECUTED" => OrderStatus::Completed,
+            "REJECTED" => OrderStatus::FailedToCreate,
+            "CANCELLED" => OrderStatus::Canceled,
+            _ => panic!("Kraken Futures: unexpected order status {}", status),
+        }
+    }
+
+    pub(super) fn get_order_role(fill_type: &str) -> OrderRole {
+        match fill_type {
+            "maker" => OrderRole::Maker,
+            // Liquidation and assignment fills are taken from the book
+            _ => OrderRole::Taker,
+        }
+    }
+
+    pub(super) fn get_side_str(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    /// Only the latest 100 fills are returned, `lastFillTime` is used to request older ones
+    #[named]
+    pub(super) async fn request_my_trades(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/derivatives/api/v3/fills");
+
+        self.get(builder, function_name!(), "".to_string()).await
+    }
+
+    pub(super) fn parse_my_trades(
+        &self,
+        symbol: &Symbol,
+        response: &RestResponse,
+        last_date_time: Option<DateTime>,
+    ) -> Result<Vec<OrderTrade>> {
+        let fills: KrakenFuturesFills =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+        let specific_currency_pair = self.get_specific_currency_pair(symbol.currency_pair());
+
+        // Kraken Futures doesn't support filtering of fills by symbol
+        Ok(fills
+            .fills
+            .into_iter()
+            .filter(|fill| fill.symbol == specific_currency_pair.as_str())
+            .filter(|fill| last_date_time.map_or(true, |time| fill.fill_time > time))
+            .sorted_by_key(|fill| fill.fill_time)
+            .map(|fill| OrderTrade {
+                exchange_order_id: fill.order_id,
+                trade_id: TradeId::from(fill.fill_id),
+                datetime: fill.fill_time,
+                price: fill.price,
+                amount: fill.size,
+                side: fill.side,
+                order_role: KrakenFutures::get_order_role(&fill.fill_type),
+                // Fees aren't returned with fills, multi-collateral futures are charged in USD
+                fee_currency_code: symbol.quote_currency_code(),
+                fee_rate: None,
+                fee_amount: None,
+                fill_type: OrderFillType::UserTrade,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/derivatives/api/v3/accounts");
+
+        self.get(builder, function_name!(), "".to_string()).await
+    }
+
+    /// Every collateral currency of multi-collateral account is reported as separate balance
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let accounts: KrakenFuturesAccounts =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        let flex = accounts
+            .accounts
+            .flex
+            .context("Kraken Futures multi-collateral account not found")?;
+
+        Ok(flex
+            .currencies
+            .into_iter()
+            .map(|(currency, collateral)| ExchangeBalance {
+                currency_code: self.currency_aliases.unify(currency.as_str().into()),
+                balance: collateral.quantity,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_open_positions(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/derivatives/api/v3/openpositions");
+
+        self.get(builder, function_name!(), "".to_string()).await
+    }
+
+    pub(super) fn parse_get_position(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ActivePosition>> {
+        let positions: KrakenFuturesOpenPositions =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        positions
+            .open_positions
+            .into_iter()
+            // Only positions of multi-collateral futures are tracked
+            .filter(|position| {
+                self.specific_to_unified
+                    .read()
+                    .contains_key(&position.symbol.as_str().into())
+            })
+            .map(|position| {
+                let derivative_position = DerivativePosition {
+                    currency_pair: self
+                        .get_unified_currency_pair(&position.symbol.as_str().into())?,
+                    position: position.signed_size(),
+                    average_entry_price: position.price,
+                    // Liquidation price depends on the whole multi-collateral account
+                    liquidation_price: Price::ZERO,
+                    leverage: position.max_fixed_leverage.unwrap_or(Decimal::ONE),
+                };
+
+                Ok(ActivePosition::new(derivative_position, position.fill_time))
+            })
+            .try_collect()
+    }
+
+    /// Position is closed by reduce-only order, market one if price isn't specified
+    #[named]
+    pub(super) async fn do_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        let derivative = &position.derivative;
+        let side = match derivative.position.is_sign_positive() {
+            true => OrderSide::Sell,
+            false => OrderSide::Buy,
+        };
+        let amount: Amount = derivative.position.abs();
+
+        let mut builder = UriBuilder::from_path("/derivatives/api/v3/sendorder");
+        match price {
+            Some(price) => {
+                builder.add_kv("orderType", "ioc");
+                builder.add_kv("limitPrice", price);
+            }
+            None => builder.add_kv("orderType", "mkt"),
+        }
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(derivative.currency_pair),
+        );
+        builder.add_kv("side", Self::get_side_str(side));
+        builder.add_kv("size", amount);
+        builder.add_kv("reduceOnly", true);
+
+        let log_args = format!("Close position for {position:?} {price:?}");
+        let response = self
+            .post(builder, function_name!(), log_args)
+            .await
+            .map_err(|err| anyhow!("Failed to close position: {err:?}"))?;
+        let exchange_order_id = self
+            .get_order_id(&response)
+            .map_err(|err| anyhow!("Failed to get order id of closing position: {err:?}"))?;
+
+        Ok(ClosedPosition::new(exchange_order_id, amount))
+    }
+}
+
+fn get_trigger_signal(trigger_price_type: TriggerPriceType) -> &'static str {
+    match trigger_price_type {
+        TriggerPriceType::Last => "last",
+        TriggerPriceType::Index => "index",
+        TriggerPriceType::Mark => "mark",
+    }
+}
+
+pub struct KrakenFuturesBuilder;
+
+impl ExchangeClientBuilder for KrakenFuturesBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(KrakenFutures::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: true,
+                    supports_mark_price_trigger: true,
+                    supports_index_price_trigger: true,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: false,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: false,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(300)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "KrakenFutures".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    const SECRET_KEY: &str =
+        "GS4mL1XvzHEVzN++LDR2awG1lKEz3CAmxsUlhbGT5r3HxbaD6l745Y0KYq+npU/1mESFwKvbxgbthryNOeBXIA==";
+
+    fn create_kraken_futures() -> KrakenFutures {
+        let exchange_account_id: ExchangeAccountId = "KrakenFutures_0".parse().expect("in test");
+        let settings = ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), true);
+
+        let (tx, _) = broadcast::channel(10);
+        let kraken_futures = KrakenFutures::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::new()),
+        );
+
+        let instruments = RestResponse::new(
+            r#"{"result":"success","instruments":[
+                {"symbol":"PF_XBTUSD","type":"flexible_futures","tradeable":true,"tickSize":1,"contractSize":1,"contractValueTradePrecision":4,"base":"BTC","quote":"USD","tag":"perpetual"},
+                {"symbol":"PF_ETHUSD","type":"flexible_futures","tradeable":true,"tickSize":0.1,"contractSize":1,"contractValueTradePrecision":-1,"base":"ETH","quote":"USD","tag":"perpetual"},
+                {"symbol":"PI_XBTUSD","type":"futures_inverse","tradeable":true,"tickSize":0.5,"contractSize":1,"tag":"perpetual"}
+            ],"serverTime":"2023-09-22T10:33:05.712Z"}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        kraken_futures
+            .parse_all_symbols(&instruments)
+            .expect("in test");
+
+        kraken_futures
+    }
+
+    #[test]
+    fn generate_signature() {
+        let message = "orderType=lmt&symbol=PF_XBTUSD&side=buy&size=0.01&limitPrice=27000&cliOrdId=1/api/v3/sendorder";
+
+        assert_eq!(
+            KrakenFutures::create_signature(SECRET_KEY, message),
+            "uAgwMT3cjvASH34u26OhnrVpZ46aOluAyaHsO1+827sFyhFpTXMdbgtPPWfFYzlsl99JwhwpiPqztmOLGYmKaQ=="
+        );
+    }
+
+    #[test]
+    fn rejected_order_is_error() {
+        let response = RestResponse::new(
+            r#"{"result":"success","sendStatus":{"status":"insufficientAvailableFunds","receivedTime":"2023-09-22T10:33:05.709Z"},"serverTime":"2023-09-22T10:33:05.712Z"}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let error = ErrorHandlerKrakenFutures
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+        assert_eq!(
+            ErrorHandlerKrakenFutures.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+
+        let response = RestResponse::new(
+            r#"{"result":"success","cancelStatus":{"status":"noOrdersToCancel","cancelledOrders":[]},"serverTime":"2023-09-22T10:33:05.712Z"}"#.to_owned(),
+            StatusCode::OK,
+        );
+        assert!(ErrorHandlerKrakenFutures
+            .check_spec_rest_error(&response)
+            .is_ok());
+    }
+
+    #[test]
+    fn parse_instruments() {
+        let kraken_futures = create_kraken_futures();
+
+        let btc_usd = CurrencyPair::from_codes("btc".into(), "usd".into());
+        let eth_usd = CurrencyPair::from_codes("eth".into(), "usd".into());
+        assert_eq!(
+            kraken_futures.get_specific_currency_pair(btc_usd).as_str(),
+            "PF_XBTUSD"
+        );
+        assert_eq!(
+            kraken_futures.get_specific_currency_pair(eth_usd).as_str(),
+            "PF_ETHUSD"
+        );
+        assert!(kraken_futures
+            .get_unified_currency_pair(&"PI_XBTUSD".into())
+            .is_err());
+    }
+
+    #[test]
+    fn parse_multi_collateral_balance() {
+        let kraken_futures = create_kraken_futures();
+        let response = RestResponse::new(
+            r#"{"result":"success","accounts":{
+                "cash":{"type":"cashAccount","balances":{"xbt":0.5}},
+                "flex":{"type":"multiCollateralMarginAccount","currencies":{
+                    "XBT":{"quantity":0.1,"value":2700.5,"collateral":2673.5,"available":0.1},
+                    "USD":{"quantity":1000.25,"value":1000.25,"collateral":1000.25,"available":900}
+                },"balanceValue":3700.75,"portfolioValue":3710.5,"collateralValue":3673.75,"availableMargin":3500}
+            },"serverTime":"2023-09-22T10:33:05.712Z"}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+
+        let balances = kraken_futures
+            .parse_get_balance(&response)
+            .expect("in test")
+            .into_iter()
+            .map(|balance| (balance.currency_code, balance.balance))
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[&CurrencyCode::new("btc")], dec!(0.1));
+        assert_eq!(balances[&CurrencyCode::new("usd")], dec!(1000.25));
+    }
+
+    #[test]
+    fn parse_open_positions() {
+        let kraken_futures = create_kraken_futures();
+        let response = RestResponse::new(
+            r#"{"result":"success","openPositions":[
+                {"side":"short","symbol":"PF_XBTUSD","price":27000.5,"fillTime":"2023-09-22T10:33:05.709Z","size":0.05,"unrealizedFunding":-0.0012,"pnlCurrency":"USD","maxFixedLeverage":null},
+                {"side":"long","symbol":"PF_ETHUSD","price":1600,"fillTime":"2023-09-22T11:00:00.000Z","size":2,"unrealizedFunding":0.01,"pnlCurrency":"USD","maxFixedLeverage":5}
+            ],"serverTime":"2023-09-22T10:33:05.712Z"}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+
+        let positions = kraken_futures
+            .parse_get_position(&response)
+            .expect("in test");
+
+        assert_eq!(positions.len(), 2);
+        let btc = &positions[0].derivative;
+        assert_eq!(btc.position, dec!(-0.05));
+        assert_eq!(btc.average_entry_price, dec!(27000.5));
+        let eth = &positions[1].derivative;
+        assert_eq!(eth.position, dec!(2));
+        assert_eq!(eth.leverage, dec!(5));
+    }
+}
@@ -0,0 +1,537 @@
+use crate::kraken_futures::KrakenFutures;
+use crate::types::{
+    KrakenFuturesBookSnapshot, KrakenFuturesBookUpdate, KrakenFuturesTrade, KrakenFuturesWsFill,
+    KrakenFuturesWsOrderEvent,
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::OrderSide;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+/// Order event reason which means that order was filled, not cancelled
+const FULL_FILL_REASON: &str = "full_fill";
+const NEW_ORDER_REASON: &str = "new_placed_order_by_user";
+
+#[async_trait]
+impl Support for KrakenFutures {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Feed(feed_message) => self.handle_feed_message(feed_message)?,
+            WebsocketMessage::Event(event) => self.handle_event(event)?,
+            WebsocketMessage::Unknown(_) => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let product_ids = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .map(|currency_pair| currency_pair.as_str().to_owned())
+            .collect::<Vec<_>>();
+        if !product_ids.is_empty() {
+            for feed in [Feed::Book, Feed::Trade] {
+                let subscribe = Request::subscribe(Subscription {
+                    feed: Some(feed),
+                    product_ids: Some(&product_ids),
+                    ..Default::default()
+                });
+                (self.websocket_message_callback)(WebSocketRole::Main, subscribe)?;
+            }
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Secondary) {
+            return Ok(());
+        }
+
+        // Private feeds require signed challenge which is received in response
+        let challenge = Request {
+            event: "challenge",
+            api_key: Some(&self.settings.api_key),
+            subscription: Default::default(),
+        }
+        .to_json();
+        (self.websocket_message_callback)(WebSocketRole::Secondary, challenge)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, _callback: HandleMetricsCb) {}
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""feed":"fills""#) || message.contains(r#""feed":"open_orders""#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl KrakenFutures {
+    fn handle_event(&self, event: EventMessage) -> Result<()> {
+        match event.event.as_str() {
+            "challenge" => {
+                let challenge = event
+                    .message
+                    .context("No message in Kraken Futures challenge response")?;
+                self.subscribe_private_feeds(&challenge)
+            }
+            "error" => {
+                let err = format!("Kraken Futures websocket: error {:?}", event.message);
+                log::error!("{err}");
+                bail!(err)
+            }
+            "alert" => {
+                log::warn!("Kraken Futures websocket: alert {:?}", event.message);
+                Ok(())
+            }
+            // "info", "subscribed" and "unsubscribed" events
+            _ => Ok(()),
+        }
+    }
+
+    fn subscribe_private_feeds(&self, challenge: &str) -> Result<()> {
+        let signed_challenge =
+            KrakenFutures::create_signature(&self.settings.secret_key, challenge);
+
+        // Open orders and fills are requested via REST, so snapshots are skipped
+        for feed in [Feed::OpenOrders, Feed::Fills] {
+            let subscribe = Request {
+                event: "subscribe",
+                api_key: Some(&self.settings.api_key),
+                subscription: Subscription {
+                    feed: Some(feed),
+                    original_challenge: Some(challenge),
+                    signed_challenge: Some(&signed_challenge),
+                    ..Default::default()
+                },
+            }
+            .to_json();
+            (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_feed_message(&self, message: FeedMessage) -> Result<()> {
+        match message {
+            FeedMessage::BookSnapshot(snapshot) => self.handle_book_snapshot(snapshot),
+            FeedMessage::Book(update) => self.handle_book_update(update),
+            FeedMessage::Trade(trade) => self.handle_trade(trade),
+            FeedMessage::Fills { fills } => {
+                for fill in fills {
+                    self.handle_order_fill(fill)?;
+                }
+                Ok(())
+            }
+            FeedMessage::OpenOrders(order_event) => {
+                self.handle_order_event(order_event);
+                Ok(())
+            }
+            FeedMessage::Other => Ok(()),
+        }
+    }
+
+    fn handle_book_snapshot(&self, snapshot: KrakenFuturesBookSnapshot) -> Result<()> {
+        let mut order_book_data = OrderBookData::default();
+        for level in snapshot.bids {
+            order_book_data.bids.insert(level.price, level.qty);
+        }
+        for level in snapshot.asks {
+            order_book_data.asks.insert(level.price, level.qty);
+        }
+
+        self.send_order_book_event(&snapshot.product_id, EventType::Snapshot, order_book_data)
+    }
+
+    fn handle_book_update(&self, update: KrakenFuturesBookUpdate) -> Result<()> {
+        // Level with zero quantity should be removed from order book
+        let mut order_book_data = OrderBookData::default();
+        match update.side {
+            OrderSide::Buy => order_book_data.bids.insert(update.price, update.qty),
+            OrderSide::Sell => order_book_data.asks.insert(update.price, update.qty),
+        };
+
+        self.send_order_book_event(&update.product_id, EventType::Update, order_book_data)
+    }
+
+    fn send_order_book_event(
+        &self,
+        product_id: &str,
+        event_type: EventType,
+        order_book_data: OrderBookData,
+    ) -> Result<()> {
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            self.get_unified_currency_pair(&product_id.into())?,
+            String::default(),
+            event_type,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: KrakenFuturesTrade) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.product_id.as_str().into())?,
+            Trade {
+                trade_id: TradeId::from(trade.uid),
+                price: trade.price,
+                quantity: trade.qty,
+                side: trade.side,
+                transaction_time: trade.time,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, order_event: KrakenFuturesWsOrderEvent) {
+        let (exchange_order_id, client_order_id) = match order_event.order {
+            Some(order) => (order.order_id, order.cli_ord_id),
+            None => match order_event.order_id {
+                Some(order_id) => (order_id, order_event.cli_ord_id),
+                None => return,
+            },
+        };
+        // Orders created outside of the engine aren't tracked
+        let Some(client_order_id) = client_order_id else {
+            return;
+        };
+
+        match (order_event.is_cancel, order_event.reason.as_str()) {
+            // Fully filled orders are removed from open orders too, fills are handled separately
+            (true, FULL_FILL_REASON) => (),
+            (true, _) => (self.order_cancelled_callback)(
+                client_order_id,
+                exchange_order_id,
+                EventSourceType::WebSocket,
+            ),
+            (false, NEW_ORDER_REASON) => (self.order_created_callback)(
+                client_order_id,
+                exchange_order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Partial fills and edits don't change order state we are tracking
+            (false, _) => (),
+        }
+    }
+
+    fn handle_order_fill(&self, fill: KrakenFuturesWsFill) -> Result<()> {
+        // Check that fill belongs to supported instrument
+        self.get_unified_currency_pair(&fill.instrument.as_str().into())?;
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(fill.fill_id)),
+            client_order_id: fill.cli_ord_id,
+            exchange_order_id: fill.order_id,
+            fill_price: fill.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: fill.qty,
+                total_filled_amount: None,
+            },
+            order_role: Some(KrakenFutures::get_order_role(&fill.fill_type)),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(CurrencyCode::new(&fill.fee_currency)),
+            ),
+            commission_rate: None,
+            commission_amount: Some(fill.fee_paid),
+            fill_type: OrderFillType::UserTrade,
+            // Fills don't contain order amount, so fills of unknown orders can't be handled
+            special_order_data: None,
+            fill_date: Some(fill.time),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    /// Subscription events contain "feed" too, so they should be checked first
+    Event(EventMessage),
+    Feed(FeedMessage),
+    Unknown(Value),
+}
+
+/// Response on request sent via websocket, e.g. subscription or challenge
+#[derive(Deserialize, Debug)]
+struct EventMessage {
+    event: String,
+    message: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "feed")]
+enum FeedMessage {
+    BookSnapshot(KrakenFuturesBookSnapshot),
+    Book(KrakenFuturesBookUpdate),
+    Trade(KrakenFuturesTrade),
+    Fills {
+        fills: Vec<KrakenFuturesWsFill>,
+    },
+    OpenOrders(KrakenFuturesWsOrderEvent),
+    /// Snapshots of trades, fills and open orders aren't needed
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Feed {
+    Book,
+    Trade,
+    Fills,
+    OpenOrders,
+}
+
+#[derive(Serialize, Default)]
+struct Subscription<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feed: Option<Feed>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    product_ids: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_challenge: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signed_challenge: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+    /// Challenge request doesn't contain subscription parameters
+    #[serde(flatten)]
+    subscription: Subscription<'a>,
+}
+
+impl<'a> Request<'a> {
+    fn subscribe(subscription: Subscription<'a>) -> String {
+        Request {
+            event: "subscribe",
+            api_key: None,
+            subscription,
+        }
+        .to_json()
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize Kraken Futures request")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_requests() {
+        let product_ids = ["PF_XBTUSD".to_owned()];
+        let book = Request::subscribe(Subscription {
+            feed: Some(Feed::Book),
+            product_ids: Some(&product_ids),
+            ..Default::default()
+        });
+        assert_eq!(
+            book,
+            r#"{"event":"subscribe","feed":"book","product_ids":["PF_XBTUSD"]}"#
+        );
+
+        let challenge = Request {
+            event: "challenge",
+            api_key: Some("key"),
+            subscription: Default::default(),
+        }
+        .to_json();
+        assert_eq!(challenge, r#"{"event":"challenge","api_key":"key"}"#);
+
+        let fills = Request {
+            event: "subscribe",
+            api_key: Some("key"),
+            subscription: Subscription {
+                feed: Some(Feed::Fills),
+                original_challenge: Some("challenge"),
+                signed_challenge: Some("signed"),
+                ..Default::default()
+            },
+        }
+        .to_json();
+        assert_eq!(
+            fills,
+            r#"{"event":"subscribe","api_key":"key","feed":"fills","original_challenge":"challenge","signed_challenge":"signed"}"#
+        );
+    }
+
+    #[test]
+    fn sign_challenge() {
+        let secret_key =
+            "GS4mL1XvzHEVzN++LDR2awG1lKEz3CAmxsUlhbGT5r3HxbaD6l745Y0KYq+npU/1mESFwKvbxgbthryNOeBXIA==";
+
+        assert_eq!(
+            KrakenFutures::create_signature(secret_key, "c100b894-1729-464d-ae1c-0cdfc6bd8a2c"),
+            "x9kKvkN/jEeG/Vht4pXq5IebUqC/Fq4DvRsd1d1IBH9p9jqji/br+dP2iJmEkIuJXGpAhU6KhiMNJXwvq4waeA=="
+        );
+    }
+
+    #[test]
+    fn parse_fills() {
+        let msg = r#"{"feed":"fills","username":"user","fills":[{"instrument":"PF_XBTUSD","time":1695378785709,"price":26637.5,"seq":100,"buy":false,"qty":0.05,"remaining_order_qty":0.05,"order_id":"3696d19b-3226-46bd-993d-a9a7aacc8fbc","cli_ord_id":"1234567","fill_id":"c14ee7cb-ad8c-4dd7-a4cc-4b7e3b7f6b9d","fill_type":"maker","fee_paid":0.27,"fee_currency":"USD","taker_order_type":"ioc","order_type":"limit"}]}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Feed(FeedMessage::Fills { fills }) = message else {
+            panic!("Unexpected message {message:?}");
+        };
+
+        let fill = &fills[0];
+        assert_eq!(
+            fill.order_id.as_str(),
+            "3696d19b-3226-46bd-993d-a9a7aacc8fbc"
+        );
+        assert_eq!(fill.qty, dec!(0.05));
+        assert_eq!(fill.fee_paid, dec!(0.27));
+        assert!(!fill.buy);
+    }
+
+    #[test]
+    fn parse_service_messages() {
+        let snapshot: WebsocketMessage =
+            serde_json::from_str(r#"{"feed":"fills_snapshot","username":"user","fills":[]}"#)
+                .expect("in test");
+        assert!(matches!(
+            snapshot,
+            WebsocketMessage::Feed(FeedMessage::Other)
+        ));
+
+        let challenge: WebsocketMessage = serde_json::from_str(
+            r#"{"event":"challenge","message":"c100b894-1729-464d-ae1c-0cdfc6bd8a2c"}"#,
+        )
+        .expect("in test");
+        assert!(matches!(
+            challenge,
+            WebsocketMessage::Event(EventMessage {
+                message: Some(_),
+                ..
+            })
+        ));
+
+        let cancelled: WebsocketMessage = serde_json::from_str(
+            r#"{"feed":"open_orders","order_id":"59302619-41d2-4f0b-941f-7e7914760ad3","cli_ord_id":"1234567","is_cancel":true,"reason":"cancelled_by_user"}"#,
+        )
+        .expect("in test");
+        assert!(matches!(
+            cancelled,
+            WebsocketMessage::Feed(FeedMessage::OpenOrders(KrakenFuturesWsOrderEvent {
+                is_cancel: true,
+                ..
+            }))
+        ));
+    }
+}
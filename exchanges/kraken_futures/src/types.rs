@@ -0,0 +1,332 @@
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::HashMap;
+
+/// Instrument description from `/derivatives/api/v3/instruments`
+/// {
+///   "symbol": "PF_XBTUSD",
+///   "type": "flexible_futures",
+///   "tradeable": true,
+///   "tickSize": 0.5,
+///   "contractSize": 1,
+///   "contractValueTradePrecision": 4,
+///   "base": "BTC",
+///   "quote": "USD",
+///   "tag": "perpetual"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesInstrument {
+    pub(crate) symbol: String,
+    #[serde(rename = "type")]
+    pub(crate) instrument_type: String,
+    pub(crate) tradeable: bool,
+    pub(crate) tick_size: Option<Price>,
+    /// Number of decimals of order size, can be negative
+    pub(crate) contract_value_trade_precision: Option<i64>,
+    pub(crate) base: Option<String>,
+    pub(crate) quote: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesInstruments {
+    pub(crate) instruments: Vec<KrakenFuturesInstrument>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesSendStatus {
+    pub(crate) order_id: ExchangeOrderId,
+}
+
+/// Response of `sendorder`. Statuses except `placed` are reported as errors by `ErrorHandlerKrakenFutures`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesSendOrder {
+    pub(crate) send_status: KrakenFuturesSendStatus,
+}
+
+/// Open order from `/derivatives/api/v3/openorders`
+/// {
+///   "order_id": "59302619-41d2-4f0b-941f-7e7914760ad3",
+///   "cliOrdId": "1234567",
+///   "symbol": "PF_XBTUSD",
+///   "side": "buy",
+///   "orderType": "lmt",
+///   "limitPrice": 27000.5,
+///   "unfilledSize": 0.1,
+///   "filledSize": 0.05,
+///   "status": "partiallyFilled",
+///   "reduceOnly": false
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesOpenOrder {
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) cli_ord_id: Option<ClientOrderId>,
+    pub(crate) symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    /// Stop orders without limit price are executed as market orders
+    pub(crate) limit_price: Option<Price>,
+    pub(crate) unfilled_size: Amount,
+    pub(crate) filled_size: Amount,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesOpenOrders {
+    pub(crate) open_orders: Vec<KrakenFuturesOpenOrder>,
+}
+
+/// Order from `/derivatives/api/v3/orders/status`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesOrder {
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) cli_ord_id: Option<ClientOrderId>,
+    pub(crate) symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) quantity: Amount,
+    pub(crate) filled: Amount,
+    pub(crate) limit_price: Option<Price>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesOrderStatus {
+    pub(crate) order: KrakenFuturesOrder,
+    /// `ENTERED_BOOK`, `FULLY_EXECUTED`, `REJECTED`, `CANCELLED`, `TRIGGER_PLACED`
+    /// or `TRIGGER_ACTIVATED`
+    pub(crate) status: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesOrdersStatus {
+    pub(crate) orders: Vec<KrakenFuturesOrderStatus>,
+}
+
+/// Fill from `/derivatives/api/v3/fills`
+/// {
+///   "fill_id": "3d57ed09-fbd6-44f1-8e8b-b10e551c5e73",
+///   "symbol": "PF_XBTUSD",
+///   "side": "buy",
+///   "order_id": "693af756-055e-47ef-99d5-bcf4c456ebc5",
+///   "cliOrdId": "1234567",
+///   "size": 0.05,
+///   "price": 27000.5,
+///   "fillTime": "2023-09-22T10:33:05.709Z",
+///   "fillType": "maker"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesFill {
+    pub(crate) fill_id: String,
+    pub(crate) symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) size: Amount,
+    pub(crate) price: Price,
+    pub(crate) fill_time: DateTime,
+    pub(crate) fill_type: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesFills {
+    pub(crate) fills: Vec<KrakenFuturesFill>,
+}
+
+/// Collateral currency of multi-collateral account
+/// "XBT": { "quantity": 0.1, "value": 2700.5, "collateral": 2673.5, "available": 0.1 }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesCollateral {
+    pub(crate) quantity: Amount,
+}
+
+/// Multi-collateral (flex) account which is used for trading of `flexible_futures`
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesFlexAccount {
+    pub(crate) currencies: HashMap<String, KrakenFuturesCollateral>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesAccountsList {
+    /// Cash and single-collateral accounts aren't used for trading
+    pub(crate) flex: Option<KrakenFuturesFlexAccount>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesAccounts {
+    pub(crate) accounts: KrakenFuturesAccountsList,
+}
+
+/// Position from `/derivatives/api/v3/openpositions`
+/// {
+///   "side": "short",
+///   "symbol": "PF_XBTUSD",
+///   "price": 27000.5,
+///   "fillTime": "2023-09-22T10:33:05.709Z",
+///   "size": 0.05,
+///   "unrealizedFunding": -0.0012,
+///   "pnlCurrency": "USD",
+///   "maxFixedLeverage": null
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesPosition {
+    pub(crate) side: String,
+    pub(crate) symbol: String,
+    /// Average entry price
+    pub(crate) price: Price,
+    pub(crate) fill_time: DateTime,
+    pub(crate) size: Amount,
+    /// Specified only for isolated margin positions
+    pub(crate) max_fixed_leverage: Option<Decimal>,
+}
+
+impl KrakenFuturesPosition {
+    /// Short position has negative size
+    pub(crate) fn signed_size(&self) -> Amount {
+        match self.side.as_str() {
+            "short" => -self.size,
+            _ => self.size,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KrakenFuturesOpenPositions {
+    pub(crate) open_positions: Vec<KrakenFuturesPosition>,
+}
+
+/// Book level of websocket `book_snapshot` feed
+/// { "price": 27000.5, "qty": 0.5 }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesBookLevel {
+    pub(crate) price: Price,
+    pub(crate) qty: Amount,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesBookSnapshot {
+    pub(crate) product_id: String,
+    #[serde(default)]
+    pub(crate) bids: Vec<KrakenFuturesBookLevel>,
+    #[serde(default)]
+    pub(crate) asks: Vec<KrakenFuturesBookLevel>,
+}
+
+/// Single level update of websocket `book` feed, zero quantity means removed level
+/// {
+///   "feed": "book",
+///   "product_id": "PF_XBTUSD",
+///   "side": "sell",
+///   "seq": 30007489,
+///   "price": 27001.0,
+///   "qty": 0.0,
+///   "timestamp": 1695378785709
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesBookUpdate {
+    pub(crate) product_id: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) price: Price,
+    pub(crate) qty: Amount,
+}
+
+/// Public trade of websocket `trade` feed
+/// {
+///   "feed": "trade",
+///   "product_id": "PF_XBTUSD",
+///   "uid": "05af78ac-a774-478c-a50c-8b9c234e071e",
+///   "side": "sell",
+///   "type": "fill",
+///   "seq": 653355,
+///   "time": 1695378785709,
+///   "qty": 0.0219,
+///   "price": 26637.5
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesTrade {
+    pub(crate) product_id: String,
+    pub(crate) uid: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) time: DateTime,
+    pub(crate) qty: Amount,
+    pub(crate) price: Price,
+}
+
+/// Fill of private websocket `fills` feed
+/// {
+///   "instrument": "PF_XBTUSD",
+///   "time": 1695378785709,
+///   "price": 26637.5,
+///   "buy": false,
+///   "qty": 0.05,
+///   "remaining_order_qty": 0.05,
+///   "order_id": "3696d19b-3226-46bd-993d-a9a7aacc8fbc",
+///   "cli_ord_id": "1234567",
+///   "fill_id": "c14ee7cb-ad8c-4dd7-a4cc-4b7e3b7f6b9d",
+///   "fill_type": "maker",
+///   "fee_paid": 0.27,
+///   "fee_currency": "USD"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesWsFill {
+    pub(crate) instrument: String,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) time: DateTime,
+    pub(crate) price: Price,
+    pub(crate) buy: bool,
+    pub(crate) qty: Amount,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) cli_ord_id: Option<ClientOrderId>,
+    pub(crate) fill_id: String,
+    pub(crate) fill_type: String,
+    pub(crate) fee_paid: Amount,
+    pub(crate) fee_currency: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesWsOrder {
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) cli_ord_id: Option<ClientOrderId>,
+}
+
+/// Order event of private websocket `open_orders` feed. New and updated orders contain
+/// `order` object while cancelled ones contain only ids
+/// {
+///   "feed": "open_orders",
+///   "order_id": "59302619-41d2-4f0b-941f-7e7914760ad3",
+///   "cli_ord_id": "1234567",
+///   "is_cancel": true,
+///   "reason": "cancelled_by_user"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct KrakenFuturesWsOrderEvent {
+    pub(crate) order: Option<KrakenFuturesWsOrder>,
+    pub(crate) order_id: Option<ExchangeOrderId>,
+    pub(crate) cli_ord_id: Option<ClientOrderId>,
+    pub(crate) is_cancel: bool,
+    pub(crate) reason: String,
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown Kraken Futures order side: {side}"
+        ))),
+    }
+}
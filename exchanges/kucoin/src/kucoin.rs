@@ -0,0 +1,805 @@
+use crate::types::{
+    KucoinAccount, KucoinFill, KucoinOrderId, KucoinOrderInfo, KucoinPage, KucoinPlaceOrderRequest,
+    KucoinResponse, KucoinSymbol, KucoinWebSocketToken,
+};
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole, OrderStatus, UserOrder,
+};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use url::Url;
+
+#[derive(Default)]
+pub struct ErrorHandlerKucoin;
+
+impl ErrorHandler for ErrorHandlerKucoin {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        struct KucoinError {
+            code: String,
+            #[serde(default)]
+            msg: String,
+        }
+
+        let kucoin_error: KucoinError = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse KuCoin response: {err:?}"))
+        })?;
+
+        match kucoin_error.code.as_str() {
+            SUCCESS_CODE => Ok(()),
+            code => Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                kucoin_error.msg,
+                code.parse().ok(),
+            )),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://docs.kucoin.com/#request
+        match error.code {
+            // The same code is returned for all request parameters errors
+            Some(400100) if error.message.contains("order_not_exist") => {
+                ExchangeErrorType::OrderNotFound
+            }
+            Some(400100) if error.message.contains("nsufficient") => {
+                ExchangeErrorType::InsufficientFunds
+            }
+            Some(200004) => ExchangeErrorType::InsufficientFunds,
+            Some(400100) | Some(400200) | Some(400350) => ExchangeErrorType::InvalidOrder,
+            Some(429000) | Some(200002) => ExchangeErrorType::RateLimit,
+            Some(400001..=400007) | Some(411100) => ExchangeErrorType::Authentication,
+            Some(500000) => ExchangeErrorType::ServiceUnavailable,
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersKucoin {
+    api_key: String,
+    secret_key: String,
+    /// Passphrase encrypted by secret key as required by API key version 2
+    signed_passphrase: String,
+}
+
+impl RestHeadersKucoin {
+    pub fn new(api_key: String, secret_key: String, passphrase: &str) -> Self {
+        Self {
+            signed_passphrase: Kucoin::create_signature(&secret_key, &[passphrase.as_bytes()]),
+            api_key,
+            secret_key,
+        }
+    }
+
+    fn add_auth_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        if self.api_key.is_empty() {
+            // Public endpoints don't require authentication
+            return builder;
+        }
+
+        let path_and_query = match uri.path_and_query() {
+            Some(path_and_query) => path_and_query.as_str(),
+            None => uri.path(),
+        };
+        let timestamp = Kucoin::get_unix_time_millis().to_string();
+        let signature = Kucoin::create_signature(
+            &self.secret_key,
+            &[
+                timestamp.as_bytes(),
+                request_type.as_str().as_bytes(),
+                path_and_query.as_bytes(),
+                body,
+            ],
+        );
+
+        builder
+            .header("KC-API-KEY", &self.api_key)
+            .header("KC-API-SIGN", signature)
+            .header("KC-API-TIMESTAMP", timestamp)
+            .header("KC-API-PASSPHRASE", &self.signed_passphrase)
+            .header("KC-API-KEY-VERSION", API_KEY_VERSION)
+    }
+}
+
+impl RestHeaders for RestHeadersKucoin {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+    ) -> Builder {
+        self.add_auth_headers(builder, uri, request_type, &[])
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        self.add_auth_headers(builder, uri, request_type, body)
+            .header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+const SUCCESS_CODE: &str = "200000";
+const API_KEY_VERSION: &str = "2";
+
+pub struct Kucoin {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerKucoin, RestHeadersKucoin>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    // Ping intervals are received together with websocket connection tokens
+    pub(super) websocket_keep_alive: Mutex<HashMap<WebSocketRole, WebSocketKeepAlive>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Kucoin {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Kucoin {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerKucoin::default(),
+                ),
+                RestHeadersKucoin::new(
+                    settings.api_key.clone(),
+                    settings.secret_key.clone(),
+                    &settings.passphrase,
+                ),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            websocket_keep_alive: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Websocket endpoints are received together with connection tokens, see `create_ws_url`
+        Hosts {
+            web_socket_host: "wss://ws-api-spot.kucoin.com",
+            web_socket2_host: "wss://ws-api-spot.kucoin.com",
+            rest_host: "https://api.kucoin.com",
+        }
+    }
+
+    pub(super) fn get_unix_time_millis() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System Time before UNIX EPOCH!")
+            .as_millis()
+    }
+
+    /// Base64 encoded HMAC-SHA256 of concatenated message parts
+    pub(super) fn create_signature(secret_key: &str, message_parts: &[&[u8]]) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for KuCoin signature");
+        for part in message_parts {
+            hmac.update(part);
+        }
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+
+    async fn post_json(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(body).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to serialize KuCoin request body: {err:?}"))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    /// Private channels require token of private bullet, public ones are available with any token
+    #[named]
+    pub(super) async fn request_websocket_token(
+        &self,
+        role: WebSocketRole,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = match role {
+            WebSocketRole::Main => "/api/v1/bullet-private",
+            WebSocketRole::Secondary => "/api/v1/bullet-public",
+        };
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, None, function_name!(), format!("{role} websocket"))
+            .await
+    }
+
+    /// Websocket URL contains one-time connection token, so it's requested before every connection.
+    /// Keep-alive parameters of the connection are saved for `get_websocket_keep_alive`
+    pub(super) fn parse_websocket_token(
+        &self,
+        role: WebSocketRole,
+        response: &RestResponse,
+    ) -> Result<Url> {
+        let token: KucoinResponse<KucoinWebSocketToken> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse KuCoin websocket token")?;
+        let server = token
+            .data
+            .instance_servers
+            .into_iter()
+            .next()
+            .context("No one KuCoin websocket server received")?;
+
+        let connect_id = Self::get_unix_time_millis().to_string();
+        let url = Url::parse_with_params(
+            &server.endpoint,
+            [
+                ("token", token.data.token.as_str()),
+                ("connectId", connect_id.as_str()),
+            ],
+        )
+        .with_context(|| format!("Unable parse websocket {role:?} uri"))?;
+
+        let keep_alive = WebSocketKeepAlive {
+            message: format!(r#"{{"id":"{connect_id}","type":"ping"}}"#),
+            interval: Duration::from_millis(server.ping_interval),
+        };
+        self.websocket_keep_alive.lock().insert(role, keep_alive);
+
+        Ok(url)
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri =
+            UriBuilder::from_path("/api/v2/symbols").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let symbols: KucoinResponse<Vec<KucoinSymbol>> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from KuCoin")?;
+
+        Ok(symbols
+            .data
+            .iter()
+            .filter(|symbol| symbol.enable_trading)
+            .map(|symbol| self.parse_symbol(symbol))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, symbol: &KucoinSymbol) -> Arc<Symbol> {
+        let base_id = symbol.base_currency.as_str();
+        let quote_id = symbol.quote_currency.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = symbol.symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Arc::new(Symbol::new(
+            false,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            Some(symbol.base_min_size),
+            Some(symbol.base_max_size),
+            Some(symbol.quote_min_size),
+            base,
+            None,
+            Precision::ByTick {
+                tick: symbol.price_increment,
+            },
+            Precision::ByTick {
+                tick: symbol.base_increment,
+            },
+        ))
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let (order_type, price, post_only) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => (
+                "limit",
+                Some(price),
+                execution_type == OrderExecutionType::MakerOnly,
+            ),
+            OrderOptions::User(UserOrder::Market) => ("market", None, false),
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let request = KucoinPlaceOrderRequest {
+            client_oid: header.client_order_id.as_str(),
+            side: header.side,
+            symbol: specific_currency_pair.as_str(),
+            order_type,
+            price,
+            size: header.amount,
+            // Post only orders can't be used with IOC or FOK
+            time_in_force: price.map(|_| "GTC"),
+            post_only,
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_json("/api/v1/orders", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let deserialized: KucoinResponse<KucoinOrderId> = serde_json::from_str(&response.content)
+            .map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse orderId: {err:?}"))
+        })?;
+
+        Ok(deserialized.data.order_id)
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let path = format!("/api/v1/orders/{}", exchange_order_id.as_str());
+        let uri = UriBuilder::from_path(&path).build_uri(self.hosts.rest_uri_host(), false);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.rest_client
+            .delete(uri, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/orders");
+        builder.add_kv("symbol", self.get_specific_currency_pair(currency_pair));
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.rest_client
+            .delete(uri, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/orders");
+        builder.add_kv("status", "active");
+        if let Some(pair) = currency_pair {
+            builder.add_kv("symbol", self.get_specific_currency_pair(pair));
+        }
+        builder.add_kv("pageSize", 500);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: KucoinResponse<KucoinPage<KucoinOrderInfo>> =
+            serde_json::from_str(&response.content)
+                .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .data
+            .items
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let path = format!("/api/v1/order/client-order/{}", client_order_id.as_str());
+        let uri = UriBuilder::from_path(&path).build_uri(self.hosts.rest_uri_host(), false);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let order: KucoinResponse<KucoinOrderInfo> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        self.specific_order_info_to_unified(order.data)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: KucoinOrderInfo) -> Result<OrderInfo> {
+        let average_fill_price = match specific.deal_size.is_zero() {
+            true => Decimal::ZERO,
+            false => specific.deal_funds / specific.deal_size,
+        };
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.symbol.as_str().into())?,
+            specific.id,
+            specific.client_oid,
+            specific.side,
+            Kucoin::get_local_order_status(specific.is_active, specific.cancel_exist),
+            specific.price,
+            specific.size,
+            average_fill_price,
+            specific.deal_size,
+            Some(
+                self.currency_aliases
+                    .unify(specific.fee_currency.as_str().into())
+                    .to_string(),
+            ),
+            None,
+            Some(specific.fee),
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    /// KuCoin doesn't return order status, it's derived from activity and cancellation flags
+    pub(super) fn get_local_order_status(is_active: bool, cancel_exist: bool) -> OrderStatus {
+        match (is_active, cancel_exist) {
+            (true, _) => OrderStatus::Created,
+            (false, true) => OrderStatus::Canceled,
+            (false, false) => OrderStatus::Completed,
+        }
+    }
+
+    pub(super) fn get_order_role(liquidity: &str) -> Option<OrderRole> {
+        match liquidity {
+            "maker" => Some(OrderRole::Maker),
+            "taker" => Some(OrderRole::Taker),
+            _ => None,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/fills");
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("startAt", date_time.timestamp_millis());
+        }
+        builder.add_kv("pageSize", 500);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let fills: KucoinResponse<KucoinPage<KucoinFill>> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        fills
+            .data
+            .items
+            .into_iter()
+            .map(|fill| {
+                Ok(OrderTrade {
+                    exchange_order_id: fill.order_id,
+                    trade_id: TradeId::from(fill.trade_id),
+                    datetime: fill.created_at,
+                    price: fill.price,
+                    amount: fill.size,
+                    side: fill.side,
+                    order_role: Kucoin::get_order_role(&fill.liquidity)
+                        .with_context(|| format!("Unknown KuCoin liquidity {}", fill.liquidity))?,
+                    fee_currency_code: self
+                        .currency_aliases
+                        .unify(fill.fee_currency.as_str().into()),
+                    fee_rate: Some(fill.fee_rate),
+                    fee_amount: Some(fill.fee),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v1/accounts");
+        builder.add_kv("type", "trade");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let accounts: KucoinResponse<Vec<KucoinAccount>> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(accounts
+            .data
+            .into_iter()
+            .map(|account| ExchangeBalance {
+                currency_code: self
+                    .currency_aliases
+                    .unify(account.currency.as_str().into()),
+                balance: account.balance,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let uri =
+            UriBuilder::from_path("/api/v1/timestamp").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        let server_time: KucoinResponse<i64> =
+            serde_json::from_str(&response.content).context("Failed to parse server time")?;
+
+        Ok(server_time.data)
+    }
+}
+
+pub struct KucoinBuilder;
+
+impl ExchangeClientBuilder for KucoinBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Kucoin::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    // Cancellation of completed order returns the same error as for unknown order
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: true,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Kucoin".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+
+    #[test]
+    fn parse_websocket_token() {
+        let exchange_account_id: ExchangeAccountId = "Kucoin_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let kucoin = Kucoin::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+        let response = RestResponse::new(
+            r#"{"code":"200000","data":{"token":"token123","instanceServers":[{"endpoint":"wss://ws-api-spot.kucoin.com/","encrypt":true,"protocol":"websocket","pingInterval":18000,"pingTimeout":10000}]}}"#.to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let url = kucoin
+            .parse_websocket_token(WebSocketRole::Secondary, &response)
+            .expect("in test");
+
+        assert_eq!(url.host_str(), Some("ws-api-spot.kucoin.com"));
+        assert!(url
+            .query()
+            .expect("in test")
+            .starts_with("token=token123&connectId="));
+        let keep_alive = kucoin.websocket_keep_alive.lock()[&WebSocketRole::Secondary].clone();
+        assert_eq!(keep_alive.interval, Duration::from_secs(18));
+        assert!(keep_alive.message.contains(r#""type":"ping""#));
+    }
+
+    #[test]
+    fn generate_signature() {
+        let signature = Kucoin::create_signature(
+            "secret",
+            &[
+                b"1547015186532",
+                b"POST",
+                b"/api/v1/orders",
+                br#"{"clientOid":"1234567","side":"buy","symbol":"BTC-USDT","type":"limit","price":"10000","size":"0.01","timeInForce":"GTC","postOnly":false}"#,
+            ],
+        );
+
+        assert_eq!(signature, "o0et1q42dyJu4laKlLUhjm+EP5+P03agAJONNubJCB0=");
+    }
+}
@@ -0,0 +1,390 @@
+use crate::kucoin::Kucoin;
+use crate::types::{KucoinBookPayload, KucoinOrderChange, KucoinTradePayload};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{
+    FillAmount, FillEvent, SpecialOrderData,
+};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+const ORDER_BOOK_TOPIC: &str = "/spotMarket/level2Depth50";
+const TRADES_TOPIC: &str = "/market/match";
+const ORDERS_TOPIC: &str = "/spotMarket/tradeOrdersV2";
+
+#[async_trait]
+impl Support for Kucoin {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Welcome | WebsocketMessage::Ack | WebsocketMessage::Pong => {}
+            WebsocketMessage::Message(push) => self.handle_push(push)?,
+            WebsocketMessage::Error { code, data } => {
+                let err = format!("KuCoin websocket: error with code {code:?}: {data:?}");
+                log::error!("{err}");
+                bail!(err)
+            }
+            WebsocketMessage::Unknown => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let symbols = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .map(|currency_pair| currency_pair.to_string())
+            .collect::<Vec<_>>();
+        if !symbols.is_empty() {
+            let symbols = symbols.join(",");
+            for topic in [ORDER_BOOK_TOPIC, TRADES_TOPIC] {
+                let subscribe = Request::subscribe(&format!("{topic}:{symbols}"), false);
+                (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+            }
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        // Main websocket is authenticated by token of private bullet, so no login is needed
+        let subscribe = Request::subscribe(ORDERS_TOPIC, true);
+        (self.websocket_message_callback)(WebSocketRole::Main, subscribe)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let response = self
+            .request_websocket_token(role)
+            .await
+            .with_context(|| format!("Unable to get KuCoin websocket {role:?} token"))?;
+
+        self.parse_websocket_token(role, &response)
+    }
+
+    fn get_websocket_keep_alive(&self, role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        self.websocket_keep_alive.lock().get(&role).cloned()
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(ORDERS_TOPIC)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Kucoin {
+    fn handle_push(&self, push: PushMessage) -> Result<()> {
+        // Topic of public channels has format `/topic/name:SYMBOL`
+        let (topic, symbol) = push.topic.split_once(':').unwrap_or((&push.topic, ""));
+        match topic {
+            ORDER_BOOK_TOPIC => self.handle_order_book(symbol, serde_json::from_value(push.data)?),
+            TRADES_TOPIC => self.handle_trade(serde_json::from_value(push.data)?),
+            ORDERS_TOPIC => self.handle_order_change(serde_json::from_value(push.data)?),
+            _ => {
+                log::info!("KuCoin websocket: message of unknown topic {}", push.topic);
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_order_book(&self, symbol: &str, book: KucoinBookPayload) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&symbol.into())?;
+
+        let mut order_book_data = OrderBookData::default();
+        for (price, amount) in book.bids {
+            order_book_data.bids.insert(price, amount);
+        }
+        for (price, amount) in book.asks {
+            order_book_data.asks.insert(price, amount);
+        }
+
+        // Channel level2Depth50 always sends full snapshot of 50 levels
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: KucoinTradePayload) -> Result<()> {
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&trade.symbol.as_str().into())?,
+            Trade {
+                trade_id: TradeId::from(trade.trade_id),
+                price: trade.price,
+                quantity: trade.size,
+                side: trade.side,
+                transaction_time: trade.time,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_change(&self, order_change: KucoinOrderChange) -> Result<()> {
+        let Some(client_order_id) = order_change.client_oid.clone() else {
+            // Order was created outside of the bot
+            return Ok(());
+        };
+
+        match order_change.change_type.as_str() {
+            // Market orders are never opened in order book, but all orders are received
+            "received" => (self.order_created_callback)(
+                client_order_id,
+                order_change.order_id,
+                EventSourceType::WebSocket,
+            ),
+            "canceled" => (self.order_cancelled_callback)(
+                client_order_id,
+                order_change.order_id,
+                EventSourceType::WebSocket,
+            ),
+            "match" => self.handle_order_fill(order_change)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_fill(&self, order_change: KucoinOrderChange) -> Result<()> {
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: order_change.trade_id.map(TradeId::from),
+            client_order_id: order_change.client_oid,
+            exchange_order_id: order_change.order_id,
+            fill_price: order_change
+                .match_price
+                .context("No matchPrice in KuCoin order match event")?,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: order_change
+                    .match_size
+                    .context("No matchSize in KuCoin order match event")?,
+                total_filled_amount: order_change.filled_size,
+            },
+            order_role: order_change
+                .liquidity
+                .as_deref()
+                .and_then(Kucoin::get_order_role),
+            // Commission isn't sent in order change events, so it's calculated by engine
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: Some(SpecialOrderData {
+                currency_pair: self
+                    .get_unified_currency_pair(&order_change.symbol.as_str().into())?,
+                order_side: order_change.side,
+                order_amount: order_change.size.unwrap_or_default(),
+            }),
+            fill_date: Some(order_change.ts),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum WebsocketMessage {
+    Welcome,
+    Ack,
+    Pong,
+    Message(PushMessage),
+    Error {
+        code: Option<Value>,
+        data: Option<Value>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+/// Data pushed by subscribed topic
+#[derive(Deserialize, Debug)]
+struct PushMessage {
+    topic: String,
+    data: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request<'a> {
+    id: String,
+    #[serde(rename = "type")]
+    request_type: &'static str,
+    topic: &'a str,
+    private_channel: bool,
+    response: bool,
+}
+
+impl<'a> Request<'a> {
+    fn subscribe(topic: &'a str, private_channel: bool) -> String {
+        let request = Request {
+            id: Kucoin::get_unix_time_millis().to_string(),
+            request_type: "subscribe",
+            topic,
+            private_channel,
+            response: true,
+        };
+
+        serde_json::to_string(&request).expect("Failed to serialize KuCoin websocket message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_subscription() {
+        let message = Request::subscribe("/market/match:BTC-USDT,ETH-USDT", false);
+        let message: Value = serde_json::from_str(&message).expect("in test");
+
+        assert_eq!(message["type"], "subscribe");
+        assert_eq!(message["topic"], "/market/match:BTC-USDT,ETH-USDT");
+        assert_eq!(message["privateChannel"], false);
+        assert_eq!(message["response"], true);
+        assert!(message["id"].is_string());
+    }
+
+    #[test]
+    fn parse_order_match_event() {
+        let msg = r#"{"type":"message","topic":"/spotMarket/tradeOrdersV2","subject":"orderChange","channelType":"private","data":{"symbol":"BTC-USDT","orderType":"limit","side":"buy","orderId":"5efab07953bdea00089965d2","type":"match","orderTime":1593487481683297666,"size":"0.1","filledSize":"0.04","price":"0.1","matchPrice":"0.1","matchSize":"0.04","tradeId":"5efab07a4ee4c7000a82d6d9","clientOid":"1593487481000313","remainSize":"0.06","status":"match","liquidity":"maker","ts":1593487482038606180}}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Message(push) = message else {
+            panic!("Unexpected message {message:?}");
+        };
+        assert_eq!(push.topic, ORDERS_TOPIC);
+
+        let order_change: KucoinOrderChange = serde_json::from_value(push.data).expect("in test");
+        assert_eq!(order_change.order_id.as_str(), "5efab07953bdea00089965d2");
+        assert_eq!(order_change.change_type, "match");
+        assert_eq!(order_change.match_size, Some(dec!(0.04)));
+        assert_eq!(order_change.filled_size, Some(dec!(0.04)));
+        assert_eq!(order_change.liquidity.as_deref(), Some("maker"));
+        assert_eq!(order_change.ts.timestamp_millis(), 1593487482038);
+    }
+
+    #[test]
+    fn parse_service_messages() {
+        let welcome: WebsocketMessage =
+            serde_json::from_str(r#"{"id":"hQvf8jkno","type":"welcome"}"#).expect("in test");
+        assert!(matches!(welcome, WebsocketMessage::Welcome));
+
+        let pong: WebsocketMessage =
+            serde_json::from_str(r#"{"id":"1545910590801","type":"pong"}"#).expect("in test");
+        assert!(matches!(pong, WebsocketMessage::Pong));
+
+        let book: WebsocketMessage = serde_json::from_str(
+            r#"{"type":"message","topic":"/spotMarket/level2Depth50:BTC-USDT","subject":"level2","data":{"asks":[["9989","8"],["9990","32"]],"bids":[["9988","56"]],"timestamp":1586948108193}}"#,
+        )
+        .expect("in test");
+        let WebsocketMessage::Message(push) = book else {
+            panic!("Unexpected message {book:?}");
+        };
+        let book: KucoinBookPayload = serde_json::from_value(push.data).expect("in test");
+        assert_eq!(book.asks[1].0, dec!(9990));
+        assert_eq!(book.bids[0].1, dec!(56));
+    }
+}
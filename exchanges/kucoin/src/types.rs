@@ -0,0 +1,271 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// Common envelope of KuCoin REST responses. Errors are checked in `ErrorHandlerKucoin`
+/// so only the data part is needed here
+#[derive(Deserialize, Debug)]
+pub(crate) struct KucoinResponse<T> {
+    pub(crate) data: T,
+}
+
+/// Page of paginated KuCoin REST responses
+#[derive(Deserialize, Debug)]
+pub(crate) struct KucoinPage<T> {
+    pub(crate) items: Vec<T>,
+}
+
+/// Websocket connection token from `/api/v1/bullet-public` and `/api/v1/bullet-private`
+/// {
+///   "token": "2neAiuYvAU61ZDXANAGAsiL4-iAExhsBXZxftpOeh_55i3Ysy2q2LEsEWU64mdzUOPusi34M_wGoSf7iNyEWJ4aBZXpWhrmY9jKtqkdWoFa75w3istPvPtiYB9J6i9GjsxUuhPw3Blrzaz",
+///   "instanceServers": [
+///     {
+///       "endpoint": "wss://ws-api-spot.kucoin.com/",
+///       "encrypt": true,
+///       "protocol": "websocket",
+///       "pingInterval": 18000,
+///       "pingTimeout": 10000
+///     }
+///   ]
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinWebSocketToken {
+    pub(crate) token: String,
+    pub(crate) instance_servers: Vec<KucoinInstanceServer>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinInstanceServer {
+    pub(crate) endpoint: String,
+    /// Milliseconds between ping messages which client should send to keep connection alive
+    pub(crate) ping_interval: u64,
+}
+
+/// Symbol description from `/api/v2/symbols`
+/// {
+///   "symbol": "BTC-USDT",
+///   "baseCurrency": "BTC",
+///   "quoteCurrency": "USDT",
+///   "baseMinSize": "0.00001",
+///   "quoteMinSize": "0.1",
+///   "baseMaxSize": "10000000000",
+///   "baseIncrement": "0.00000001",
+///   "priceIncrement": "0.1",
+///   "enableTrading": true
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinSymbol {
+    pub(crate) symbol: String,
+    pub(crate) base_currency: String,
+    pub(crate) quote_currency: String,
+    pub(crate) base_min_size: Amount,
+    pub(crate) quote_min_size: Price,
+    pub(crate) base_max_size: Amount,
+    pub(crate) base_increment: Amount,
+    pub(crate) price_increment: Price,
+    pub(crate) enable_trading: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinPlaceOrderRequest<'a> {
+    pub(crate) client_oid: &'a str,
+    #[serde(serialize_with = "serialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) symbol: &'a str,
+    #[serde(rename = "type")]
+    pub(crate) order_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) price: Option<Price>,
+    pub(crate) size: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) time_in_force: Option<&'static str>,
+    pub(crate) post_only: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinOrderId {
+    pub(crate) order_id: ExchangeOrderId,
+}
+
+/// Order from `/api/v1/orders` and `/api/v1/order/client-order/{clientOid}`
+/// {
+///   "id": "5c35c02703aa673ceec2a168",
+///   "symbol": "BTC-USDT",
+///   "side": "buy",
+///   "price": "10",
+///   "size": "2",
+///   "dealFunds": "0.166",
+///   "dealSize": "2",
+///   "fee": "0",
+///   "feeCurrency": "USDT",
+///   "isActive": false,
+///   "cancelExist": false,
+///   "clientOid": "1234567"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinOrderInfo {
+    pub(crate) id: ExchangeOrderId,
+    pub(crate) symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) price: Price,
+    pub(crate) size: Amount,
+    pub(crate) deal_funds: Decimal,
+    pub(crate) deal_size: Amount,
+    pub(crate) fee: Amount,
+    pub(crate) fee_currency: String,
+    pub(crate) is_active: bool,
+    pub(crate) cancel_exist: bool,
+    pub(crate) client_oid: ClientOrderId,
+}
+
+/// Fill from `/api/v1/fills`
+/// {
+///   "symbol": "BTC-USDT",
+///   "tradeId": "5c35c02709e4f67d5266954e",
+///   "orderId": "5c35c02703aa673ceec2a168",
+///   "side": "buy",
+///   "liquidity": "taker",
+///   "price": "0.083",
+///   "size": "0.8424304",
+///   "fee": "0",
+///   "feeRate": "0",
+///   "feeCurrency": "USDT",
+///   "createdAt": 1547026472000
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinFill {
+    pub(crate) trade_id: String,
+    pub(crate) order_id: ExchangeOrderId,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) liquidity: String,
+    pub(crate) price: Price,
+    pub(crate) size: Amount,
+    pub(crate) fee: Amount,
+    pub(crate) fee_rate: Decimal,
+    pub(crate) fee_currency: String,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub(crate) created_at: DateTime,
+}
+
+/// Account from `/api/v1/accounts`
+#[derive(Deserialize, Debug)]
+pub(crate) struct KucoinAccount {
+    pub(crate) currency: String,
+    pub(crate) balance: Decimal,
+}
+
+/// Order change of private websocket `/spotMarket/tradeOrdersV2` topic
+/// {
+///   "symbol": "BTC-USDT",
+///   "orderType": "limit",
+///   "side": "buy",
+///   "orderId": "5efab07953bdea00089965d2",
+///   "type": "match",
+///   "size": "0.1",
+///   "filledSize": "0.1",
+///   "price": "0.1",
+///   "matchPrice": "0.1",
+///   "matchSize": "0.1",
+///   "tradeId": "5efab07a4ee4c7000a82d6d9",
+///   "clientOid": "1234567",
+///   "remainSize": "0",
+///   "status": "match",
+///   "liquidity": "maker",
+///   "ts": 1593487482038606180
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinOrderChange {
+    pub(crate) symbol: String,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) order_id: ExchangeOrderId,
+    /// Type of change: "received", "open", "match", "update", "filled" or "canceled"
+    #[serde(rename = "type")]
+    pub(crate) change_type: String,
+    pub(crate) size: Option<Amount>,
+    pub(crate) filled_size: Option<Amount>,
+    pub(crate) match_price: Option<Price>,
+    pub(crate) match_size: Option<Amount>,
+    pub(crate) trade_id: Option<String>,
+    pub(crate) client_oid: Option<ClientOrderId>,
+    pub(crate) liquidity: Option<String>,
+    #[serde(deserialize_with = "deserialize_nanos")]
+    pub(crate) ts: DateTime,
+}
+
+/// Order book data of public websocket `/spotMarket/level2Depth50` topic
+#[derive(Deserialize, Debug)]
+pub(crate) struct KucoinBookPayload {
+    pub(crate) bids: Vec<(Price, Amount)>,
+    pub(crate) asks: Vec<(Price, Amount)>,
+}
+
+/// Trade of public websocket `/market/match` topic
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KucoinTradePayload {
+    pub(crate) symbol: String,
+    pub(crate) trade_id: String,
+    pub(crate) price: Price,
+    pub(crate) size: Amount,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    #[serde(deserialize_with = "deserialize_nanos")]
+    pub(crate) time: DateTime,
+}
+
+fn serialize_side<S>(side: &OrderSide, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(match side {
+        OrderSide::Buy => "buy",
+        OrderSide::Sell => "sell",
+    })
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!(
+            "Unknown KuCoin order side: {side}"
+        ))),
+    }
+}
+
+/// KuCoin returns time of websocket events as unix timestamp in nanoseconds
+/// either as number or as string
+fn deserialize_nanos<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Nanos {
+        Number(i64),
+        String(String),
+    }
+
+    let nanos = match Nanos::deserialize(deserializer)? {
+        Nanos::Number(nanos) => nanos,
+        Nanos::String(value) => value.parse().map_err(de::Error::custom)?,
+    };
+    Ok(Utc.timestamp_nanos(nanos))
+}
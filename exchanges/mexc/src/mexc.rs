@@ -0,0 +1,726 @@
+use crate::types::{MexcExchangeInfo, MexcMyTrade, MexcOrderInfo, MexcSymbol};
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use function_name::named;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::binance_like::{
+    self, get_local_order_side, get_local_order_status, get_server_order_side, SpotAccountInfo,
+    LISTEN_KEY,
+};
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    ClientOrderId, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole,
+    OrderSide, UserOrder,
+};
+use mmb_utils::time::u64_to_date_time;
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Statuses of enabled symbols, the old API version returns "ENABLED" instead of "1"
+const ENABLED_SYMBOL_STATUSES: &[&str] = &["1", "ENABLED"];
+/// Maximum count of trades returned by `/api/v3/myTrades`
+const MY_TRADES_LIMIT: u32 = 100;
+
+#[derive(Default)]
+pub struct ErrorHandlerMexc;
+
+impl ErrorHandler for ErrorHandlerMexc {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        binance_like::check_rest_error(response)
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        use ExchangeErrorType::*;
+        // Details: https://mexcdevelop.github.io/apidocs/spot_v3_en/#error-code
+        match (error.code, error.message.as_str()) {
+            (Some(-2013), _) | (_, "Unknown order sent." | "Order does not exist.") => {
+                OrderNotFound
+            }
+            // 10101 insufficient balance, 30004 insufficient position, 30005 oversold
+            (Some(10101 | 30004 | 30005), _) => InsufficientFunds,
+            // 30002 and 30003 are limits of amount, 30010 is price out of range,
+            // 30029 is limit of open orders count
+            (Some(30002 | 30003 | 30010 | 30029), _) => InvalidOrder,
+            (Some(429), _) => RateLimit,
+            // 602 and 700002 are invalid signature, 10072 is invalid api key
+            (Some(602 | 700002 | 10072 | 10073), _) => Authentication,
+            // Trading of symbol is suspended
+            (Some(30000 | 30016), _) => ServiceUnavailable,
+            _ => Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersMexc {
+    api_key: String,
+}
+
+impl RestHeadersMexc {
+    pub fn new(api_key: String) -> Self {
+        Self { api_key }
+    }
+}
+
+impl RestHeaders for RestHeadersMexc {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        builder
+            .header(CONTENT_TYPE, "application/json")
+            .header("X-MEXC-APIKEY", &self.api_key)
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub struct Mexc {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerMexc, RestHeadersMexc>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) listen_key: RwLock<Option<String>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Mexc {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Mexc {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerMexc::default(),
+                ),
+                RestHeadersMexc::new(settings.api_key.clone()),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            listen_key: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        // Private channels are served by the same endpoint, but listen key is added to URL
+        Hosts {
+            web_socket_host: "wss://wbs.mexc.com/ws",
+            web_socket2_host: "wss://wbs.mexc.com/ws",
+            rest_host: "https://api.mexc.com",
+        }
+    }
+
+    pub(super) fn add_authentification(&self, builder: &mut UriBuilder) {
+        binance_like::add_authentification(&self.settings.secret_key, builder);
+    }
+
+    #[named]
+    pub(super) async fn request_listen_key(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/userDataStream");
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .post(uri, None, function_name!(), "".to_string())
+            .await
+    }
+
+    /// Listen key is valid for 60 minutes after creation or the last update
+    #[named]
+    pub(super) async fn request_update_listen_key(
+        &self,
+        listen_key: &str,
+    ) -> Result<(), ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/userDataStream");
+        builder.add_kv(LISTEN_KEY, listen_key);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .put(uri, function_name!(), "".to_string())
+            .await
+            .map(|_| ())
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let uri = UriBuilder::from_path("/api/v3/exchangeInfo")
+            .build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let exchange_info: MexcExchangeInfo = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from MEXC")?;
+
+        Ok(exchange_info
+            .symbols
+            .iter()
+            .filter(|symbol| {
+                symbol.is_spot_trading_allowed
+                    && ENABLED_SYMBOL_STATUSES.contains(&symbol.status.as_str())
+            })
+            .map(|symbol| self.parse_symbol(symbol))
+            .collect_vec())
+    }
+
+    /// Unlike Binance, filters of symbols are empty and limits are returned as separate fields
+    fn parse_symbol(&self, symbol: &MexcSymbol) -> Arc<Symbol> {
+        let base_id = symbol.base_asset.as_str();
+        let quote_id = symbol.quote_asset.as_str();
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = symbol.symbol.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        Arc::new(Symbol::new(
+            false,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            symbol.base_size_precision.filter(|x| !x.is_zero()),
+            None,
+            symbol.quote_amount_precision.filter(|x| !x.is_zero()),
+            base,
+            None,
+            Precision::tick_from_precision(symbol.quote_precision),
+            Precision::tick_from_precision(symbol.base_asset_precision),
+        ))
+    }
+
+    #[named]
+    pub(super) async fn request_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let mut builder = UriBuilder::from_path("/api/v3/order");
+        builder.add_kv("symbol", specific_currency_pair);
+        builder.add_kv("side", get_server_order_side(header.side));
+        builder.add_kv("quantity", header.amount);
+        builder.add_kv("newClientOrderId", &header.client_order_id);
+
+        match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => {
+                match execution_type {
+                    OrderExecutionType::None => builder.add_kv("type", "LIMIT"),
+                    OrderExecutionType::MakerOnly => builder.add_kv("type", "LIMIT_MAKER"),
+                }
+                builder.add_kv("price", price);
+            }
+            OrderOptions::User(UserOrder::Market) => builder.add_kv("type", "MARKET"),
+            // MEXC spot API has no stop orders
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        }
+
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Create order for {header:?}");
+        self.rest_client
+            .post(uri, None, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OrderId {
+            order_id: ExchangeOrderId,
+        }
+
+        let deserialized: OrderId = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse orderId: {err:?}")))?;
+
+        Ok(deserialized.order_id)
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/order");
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("orderId", exchange_order_id);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.rest_client
+            .delete(uri, function_name!(), log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_cancel_all_orders(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/openOrders");
+        builder.add_kv("symbol", self.get_specific_currency_pair(currency_pair));
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Cancel all orders for {currency_pair}");
+        self.rest_client
+            .delete(uri, function_name!(), log_args)
+            .await
+    }
+
+    /// MEXC requires symbol for open orders request
+    #[named]
+    pub(super) async fn request_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/openOrders");
+        builder.add_kv("symbol", self.get_specific_currency_pair(currency_pair));
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("Open orders for {currency_pair}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: Vec<MexcOrderInfo> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let mut builder = UriBuilder::from_path("/api/v3/order");
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("origClientOrderId", &client_order_id);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("order {client_order_id}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let order: MexcOrderInfo = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        self.specific_order_info_to_unified(&order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: &MexcOrderInfo) -> Result<OrderInfo> {
+        // Average price isn't returned, but it can be calculated by filled cost
+        let average_fill_price = match specific.executed_qty.is_zero() {
+            true => Decimal::ZERO,
+            false => specific.cummulative_quote_qty / specific.executed_qty,
+        };
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific.symbol.as_str().into())?,
+            specific.order_id.clone(),
+            ClientOrderId::from(specific.client_order_id.as_deref().unwrap_or_default()),
+            get_local_order_side(&specific.side),
+            get_local_order_status(&specific.status),
+            specific.price,
+            specific.orig_qty,
+            average_fill_price,
+            specific.executed_qty,
+            None,
+            None,
+            None,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    /// Sides of websocket events are numbers: 1 for buy and 2 for sell
+    pub(super) fn get_ws_order_side(side: u8) -> Result<OrderSide> {
+        match side {
+            1 => Ok(OrderSide::Buy),
+            2 => Ok(OrderSide::Sell),
+            _ => bail!("Unknown MEXC order side {side}"),
+        }
+    }
+
+    pub(super) fn get_order_role(is_maker: bool) -> OrderRole {
+        match is_maker {
+            true => OrderRole::Maker,
+            false => OrderRole::Taker,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/myTrades");
+        builder.add_kv(
+            "symbol",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("startTime", date_time.timestamp_millis());
+        }
+        builder.add_kv("limit", MY_TRADES_LIMIT);
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let trades: Vec<MexcMyTrade> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        Ok(trades
+            .into_iter()
+            .map(|trade| OrderTrade {
+                exchange_order_id: trade.order_id,
+                trade_id: TradeId::from(trade.id),
+                datetime: u64_to_date_time(trade.time),
+                price: trade.price,
+                amount: trade.qty,
+                side: match trade.is_buyer {
+                    true => OrderSide::Buy,
+                    false => OrderSide::Sell,
+                },
+                order_role: Mexc::get_order_role(trade.is_maker),
+                fee_currency_code: self
+                    .currency_aliases
+                    .unify(trade.commission_asset.as_str().into()),
+                fee_rate: None,
+                fee_amount: Some(trade.commission),
+                fill_type: OrderFillType::UserTrade,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v3/account");
+        self.add_authentification(&mut builder);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let account_info: SpotAccountInfo =
+            serde_json::from_str(&response.content).context("Unable to parse account info")?;
+
+        Ok(account_info
+            .balances
+            .iter()
+            .map(|balance| ExchangeBalance {
+                currency_code: self.currency_aliases.unify(balance.asset.into()),
+                balance: balance.free,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_server_time(&self) -> Result<RestResponse, ExchangeError> {
+        let uri =
+            UriBuilder::from_path("/api/v3/time").build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_server_time(&self, response: &RestResponse) -> Result<i64> {
+        binance_like::parse_server_time(response).context("Failed to parse MEXC get time response")
+    }
+}
+
+pub struct MexcBuilder;
+
+impl ExchangeClientBuilder for MexcBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Mexc::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::OneCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(1200)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Mexc".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::StatusCode;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::OrderStatus;
+    use mmb_utils::cancellation_token::CancellationToken;
+    use rust_decimal_macros::dec;
+
+    fn create_mexc() -> Mexc {
+        let exchange_account_id: ExchangeAccountId = "Mexc_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+
+        let (tx, _) = broadcast::channel(10);
+        let mexc = Mexc::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let exchange_info = RestResponse::new(
+            r#"{"timezone":"CST","serverTime":1678680698000,"symbols":[
+                {"symbol":"MXUSDT","status":"1","baseAsset":"MX","baseAssetPrecision":2,"quoteAsset":"USDT","quotePrecision":4,"quoteAssetPrecision":4,"isSpotTradingAllowed":true,"maxQuoteAmount":"5000000","quoteAmountPrecision":"5","baseSizePrecision":"0","filters":[]},
+                {"symbol":"BTCUSDT","status":"ENABLED","baseAsset":"BTC","baseAssetPrecision":6,"quoteAsset":"USDT","quotePrecision":2,"quoteAssetPrecision":2,"isSpotTradingAllowed":true,"quoteAmountPrecision":"1","baseSizePrecision":"0.0001","filters":[]},
+                {"symbol":"OLDUSDT","status":"2","baseAsset":"OLD","baseAssetPrecision":2,"quoteAsset":"USDT","quotePrecision":4,"isSpotTradingAllowed":true,"filters":[]}
+            ]}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+        mexc.parse_all_symbols(&exchange_info).expect("in test");
+
+        mexc
+    }
+
+    #[test]
+    fn parse_symbols() {
+        let mexc = create_mexc();
+        let exchange_info = RestResponse::new(
+            r#"{"symbols":[{"symbol":"BTCUSDT","status":"1","baseAsset":"BTC","baseAssetPrecision":6,"quoteAsset":"USDT","quotePrecision":2,"isSpotTradingAllowed":true,"quoteAmountPrecision":"1","baseSizePrecision":"0.0001","filters":[]}]}"#
+                .to_owned(),
+            StatusCode::OK,
+        );
+
+        let symbols = mexc.parse_all_symbols(&exchange_info).expect("in test");
+
+        assert_eq!(symbols.len(), 1);
+        let symbol = &symbols[0];
+        assert_eq!(symbol.min_amount, Some(dec!(0.0001)));
+        assert_eq!(symbol.min_cost, Some(dec!(1)));
+        assert_eq!(
+            symbol.price_precision,
+            Precision::ByTick { tick: dec!(0.01) }
+        );
+        assert_eq!(
+            symbol.amount_precision,
+            Precision::ByTick {
+                tick: dec!(0.000001)
+            }
+        );
+        assert!(mexc.get_unified_currency_pair(&"OLDUSDT".into()).is_err());
+    }
+
+    #[test]
+    fn parse_partially_canceled_order() {
+        let mexc = create_mexc();
+        let response = RestResponse::new(
+            r#"{"symbol":"MXUSDT","orderId":"C02__443776347957968896088","orderListId":-1,"clientOrderId":"123456","price":"3.5","origQty":"10","executedQty":"2.5","cummulativeQuoteQty":"8.7","status":"PARTIALLY_CANCELED","timeInForce":null,"type":"LIMIT","side":"BUY","stopPrice":null,"time":1666676533741,"updateTime":1666676533741,"isWorking":true}"#.to_owned(),
+            StatusCode::OK,
+        );
+
+        let order = mexc.parse_order_info(&response).expect("in test");
+
+        assert_eq!(
+            order.exchange_order_id.as_str(),
+            "C02__443776347957968896088"
+        );
+        assert_eq!(order.client_order_id.as_str(), "123456");
+        assert_eq!(order.order_side, OrderSide::Buy);
+        assert_eq!(order.order_status, OrderStatus::Canceled);
+        assert_eq!(order.filled_amount, dec!(2.5));
+        assert_eq!(order.average_fill_price, dec!(3.48));
+    }
+
+    #[test]
+    fn clarify_error_type_by_code() {
+        let response = RestResponse::new(
+            r#"{"code":30004,"msg":"Insufficient position"}"#.to_owned(),
+            StatusCode::BAD_REQUEST,
+        );
+
+        let error = ErrorHandlerMexc
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(
+            ErrorHandlerMexc.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+    }
+}
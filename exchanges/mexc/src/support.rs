@@ -0,0 +1,509 @@
+use crate::mexc::Mexc;
+use crate::types::{MexcDeals, MexcDepth, MexcWsFill, MexcWsOrder};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::{WebSocketKeepAlive, WebSocketRole};
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::infrastructure::spawn_by_timer;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::snapshot::ClientOrderId;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::time::u64_to_date_time;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+const DEALS_CHANNEL: &str = "spot@public.deals.v3.api";
+const LIMIT_DEPTH_CHANNEL: &str = "spot@public.limit.depth.v3.api";
+const ORDERS_CHANNEL: &str = "spot@private.orders.v3.api";
+const PRIVATE_DEALS_CHANNEL: &str = "spot@private.deals.v3.api";
+/// Allowed values are 5, 10 and 20
+const ORDER_BOOK_LEVELS: u8 = 20;
+/// Connection without messages from client is closed after 1 minute
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(20);
+const LISTEN_KEY_UPDATE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// MEXC allows at most 30 subscriptions per connection and every currency pair is subscribed
+/// to deals and order book, so currency pairs exceeding the limit are sharded across connections
+const MAX_CURRENCY_PAIRS_PER_CONNECTION: usize = 15;
+
+#[async_trait]
+impl Support for Mexc {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    async fn initialized(&self, exchange: Arc<Exchange>) {
+        start_updating_listen_key(&exchange);
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Data(data) => self.handle_data_message(data)?,
+            WebsocketMessage::Response(response) if response.code != 0 => {
+                let err = format!("MEXC websocket: error {} {}", response.code, response.msg);
+                log::error!("{err}");
+                bail!(err)
+            }
+            // Successful subscriptions and pongs
+            WebsocketMessage::Response(_) => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let traded_specific_currencies = self.traded_specific_currencies.lock().clone();
+        // otherwise market data is subscribed by every connection of shards separately
+        if !traded_specific_currencies.is_empty()
+            && traded_specific_currencies.len() <= MAX_CURRENCY_PAIRS_PER_CONNECTION
+        {
+            let params = market_data_params(&traded_specific_currencies);
+            (self.websocket_message_callback)(WebSocketRole::Main, Request::subscribe(params))?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Secondary) {
+            return Ok(());
+        }
+
+        // Private channels are authenticated by listen key in websocket URL
+        let params = vec![ORDERS_CHANNEL.to_owned(), PRIVATE_DEALS_CHANNEL.to_owned()];
+        (self.websocket_message_callback)(WebSocketRole::Secondary, Request::subscribe(params))
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        *self.listen_key.write() = None;
+
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => true,
+            WebSocketRole::Secondary => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let url = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host.to_owned(),
+            WebSocketRole::Secondary => {
+                let listen_key = self.receive_listen_key().await?;
+                let url = format!("{}?listenKey={listen_key}", self.hosts.web_socket2_host);
+                *self.listen_key.write() = Some(listen_key);
+                url
+            }
+        };
+
+        Url::parse(&url).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_websocket_keep_alive(&self, _role: WebSocketRole) -> Option<WebSocketKeepAlive> {
+        Some(WebSocketKeepAlive {
+            message: Request::ping(),
+            interval: KEEP_ALIVE_INTERVAL,
+        })
+    }
+
+    fn max_websocket_currency_pairs(&self) -> Option<usize> {
+        Some(MAX_CURRENCY_PAIRS_PER_CONNECTION)
+    }
+
+    fn create_market_data_subscribe_messages(
+        &self,
+        currency_pairs: &[SpecificCurrencyPair],
+    ) -> Result<Vec<String>> {
+        let params = market_data_params(currency_pairs);
+        Ok(vec![Request::subscribe(params)])
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(ORDERS_CHANNEL) || message.contains(PRIVATE_DEALS_CHANNEL)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Mexc {
+    fn handle_data_message(&self, message: DataMessage) -> Result<()> {
+        // Public channels contain symbol in the name, e.g. "spot@public.deals.v3.api@BTCUSDT"
+        let channel = message
+            .channel
+            .split('@')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join("@");
+        match channel.as_str() {
+            DEALS_CHANNEL => {
+                self.handle_deals(&message.symbol, serde_json::from_value(message.data)?)
+            }
+            LIMIT_DEPTH_CHANNEL => {
+                self.handle_depth(&message.symbol, serde_json::from_value(message.data)?)
+            }
+            ORDERS_CHANNEL => {
+                self.handle_order_event(serde_json::from_value(message.data)?);
+                Ok(())
+            }
+            PRIVATE_DEALS_CHANNEL => {
+                self.handle_order_fill(&message.symbol, serde_json::from_value(message.data)?)
+            }
+            _ => {
+                log::warn!("Unknown MEXC websocket channel {}", message.channel);
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_depth(&self, symbol: &str, depth: MexcDepth) -> Result<()> {
+        let mut order_book_data = OrderBookData::default();
+        for level in depth.bids {
+            order_book_data.bids.insert(level.price, level.amount);
+        }
+        for level in depth.asks {
+            order_book_data.asks.insert(level.price, level.amount);
+        }
+
+        // Limit depth channel always sends full snapshot of limited levels
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            self.get_unified_currency_pair(&symbol.into())?,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_deals(&self, symbol: &str, deals: MexcDeals) -> Result<()> {
+        let currency_pair = self.get_unified_currency_pair(&symbol.into())?;
+
+        for deal in deals.deals {
+            (self.handle_trade_callback)(
+                currency_pair,
+                Trade {
+                    // Public deals have no ids
+                    trade_id: TradeId::from(format!(
+                        "{}-{}-{}",
+                        deal.time, deal.price, deal.amount
+                    )),
+                    price: deal.price,
+                    quantity: deal.amount,
+                    side: Mexc::get_ws_order_side(deal.side)?,
+                    transaction_time: u64_to_date_time(deal.time),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, order: MexcWsOrder) {
+        if order.client_order_id.is_empty() {
+            // Order was created outside of the bot
+            return;
+        }
+        let client_order_id = ClientOrderId::from(order.client_order_id.as_str());
+
+        match order.status {
+            1 => (self.order_created_callback)(
+                client_order_id,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            4 | 5 => (self.order_cancelled_callback)(
+                client_order_id,
+                order.order_id,
+                EventSourceType::WebSocket,
+            ),
+            // Fills are received from private deals channel
+            _ => {}
+        }
+    }
+
+    fn handle_order_fill(&self, symbol: &str, fill: MexcWsFill) -> Result<()> {
+        if fill.client_order_id.is_empty() {
+            // Order was created outside of the bot
+            return Ok(());
+        }
+
+        // Check that currency pair is known before passing fill to engine
+        self.get_unified_currency_pair(&symbol.into())?;
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(fill.trade_id)),
+            client_order_id: Some(fill.client_order_id.as_str().into()),
+            exchange_order_id: fill.order_id,
+            fill_price: fill.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: fill.amount,
+                total_filled_amount: None,
+            },
+            order_role: Some(Mexc::get_order_role(fill.is_maker == 1)),
+            commission_currency_code: Some(
+                self.currency_aliases
+                    .unify(fill.commission_asset.as_str().into()),
+            ),
+            commission_rate: None,
+            commission_amount: Some(fill.commission),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(u64_to_date_time(fill.time)),
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+fn start_updating_listen_key(exchange: &Arc<Exchange>) {
+    let exchange_wk = Arc::downgrade(exchange);
+    spawn_by_timer(
+        "Update listen key",
+        LISTEN_KEY_UPDATE_INTERVAL,
+        LISTEN_KEY_UPDATE_INTERVAL,
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let exchange_wk = exchange_wk.clone();
+            async move {
+                let exchange = match exchange_wk.upgrade() {
+                    None => return,
+                    Some(v) => v,
+                };
+
+                exchange
+                    .exchange_client
+                    .as_any()
+                    .downcast_ref::<Mexc>()
+                    .expect("received non MEXC exchange client in method of updating listen keys by timer")
+                    .ping_listen_key()
+                    .await;
+            }
+        },
+    );
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    Data(DataMessage),
+    /// Response on subscription or ping, e.g. `{"id":0,"code":0,"msg":"PONG"}`
+    Response(ResponseMessage),
+}
+
+#[derive(Deserialize, Debug)]
+struct DataMessage {
+    #[serde(rename = "c")]
+    channel: String,
+    #[serde(rename = "d")]
+    data: Value,
+    #[serde(rename = "s", default)]
+    symbol: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ResponseMessage {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Serialize)]
+struct Request {
+    method: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    params: Vec<String>,
+}
+
+impl Request {
+    fn to_message(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize MEXC websocket message")
+    }
+
+    fn subscribe(params: Vec<String>) -> String {
+        Request {
+            method: "SUBSCRIPTION",
+            params,
+        }
+        .to_message()
+    }
+
+    fn ping() -> String {
+        Request {
+            method: "PING",
+            params: Vec::new(),
+        }
+        .to_message()
+    }
+}
+
+fn market_data_params(currency_pairs: &[SpecificCurrencyPair]) -> Vec<String> {
+    currency_pairs
+        .iter()
+        .flat_map(|currency_pair| {
+            [
+                format!("{DEALS_CHANNEL}@{currency_pair}"),
+                format!("{LIMIT_DEPTH_CHANNEL}@{currency_pair}@{ORDER_BOOK_LEVELS}"),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_requests() {
+        let subscribe = Request::subscribe(vec![
+            format!("{DEALS_CHANNEL}@BTCUSDT"),
+            format!("{LIMIT_DEPTH_CHANNEL}@BTCUSDT@{ORDER_BOOK_LEVELS}"),
+        ]);
+        assert_eq!(
+            subscribe,
+            r#"{"method":"SUBSCRIPTION","params":["spot@public.deals.v3.api@BTCUSDT","spot@public.limit.depth.v3.api@BTCUSDT@20"]}"#
+        );
+
+        assert_eq!(Request::ping(), r#"{"method":"PING"}"#);
+    }
+
+    #[test]
+    fn market_data_of_max_currency_pairs_fits_subscriptions_limit() {
+        let currency_pairs = (0..MAX_CURRENCY_PAIRS_PER_CONNECTION)
+            .map(|index| SpecificCurrencyPair::new(&format!("COIN{index}USDT")))
+            .collect::<Vec<_>>();
+
+        let params = market_data_params(&currency_pairs);
+
+        assert_eq!(params.len(), 30);
+        assert_eq!(params[0], "spot@public.deals.v3.api@COIN0USDT");
+        assert_eq!(params[1], "spot@public.limit.depth.v3.api@COIN0USDT@20");
+    }
+
+    #[test]
+    fn parse_public_messages() {
+        let deals: WebsocketMessage = serde_json::from_str(
+            r#"{"c":"spot@public.deals.v3.api@BTCUSDT","d":{"deals":[{"S":2,"p":"20233.84","t":1678670940682,"v":"0.001028"}],"e":"spot@public.deals.v3.api"},"s":"BTCUSDT","t":1678670940682}"#,
+        )
+        .expect("in test");
+        let WebsocketMessage::Data(deals) = deals else {
+            panic!("Unexpected message {deals:?}");
+        };
+        assert_eq!(deals.symbol, "BTCUSDT");
+        let deals: MexcDeals = serde_json::from_value(deals.data).expect("in test");
+        assert_eq!(deals.deals[0].side, 2);
+        assert_eq!(deals.deals[0].amount, dec!(0.001028));
+
+        let depth: WebsocketMessage = serde_json::from_str(
+            r#"{"c":"spot@public.limit.depth.v3.api@BTCUSDT@20","d":{"asks":[{"p":"20290.89","v":"0.001787"}],"bids":[{"p":"20290.86","v":"0.000271"},{"p":"20290.5","v":"1.2"}],"e":"spot@public.limit.depth.v3.api","r":"3407459756"},"s":"BTCUSDT","t":1678670942000}"#,
+        )
+        .expect("in test");
+        let WebsocketMessage::Data(depth) = depth else {
+            panic!("Unexpected message {depth:?}");
+        };
+        let depth: MexcDepth = serde_json::from_value(depth.data).expect("in test");
+        assert_eq!(depth.asks[0].price, dec!(20290.89));
+        assert_eq!(depth.bids[1].amount, dec!(1.2));
+
+        let pong: WebsocketMessage =
+            serde_json::from_str(r#"{"id":0,"code":0,"msg":"PONG"}"#).expect("in test");
+        assert!(matches!(
+            pong,
+            WebsocketMessage::Response(ResponseMessage { code: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn parse_private_fill() {
+        let msg = r#"{"c":"spot@private.deals.v3.api","d":{"p":"1.804","v":"0.31","a":"0.55924","S":1,"T":1678670940682,"t":"5bbb6ad8b4474570b155610e3960cd4f","c":"123456","i":"2dd9655f9fa2438fa1709510d7c1afd9","m":0,"st":0,"n":"0.000248","N":"USDT"},"s":"MXUSDT","t":1678670940682}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Data(message) = message else {
+            panic!("Unexpected message {message:?}");
+        };
+        assert_eq!(message.channel, PRIVATE_DEALS_CHANNEL);
+
+        let fill: MexcWsFill = serde_json::from_value(message.data).expect("in test");
+        assert_eq!(fill.client_order_id, "123456");
+        assert_eq!(fill.order_id.as_str(), "2dd9655f9fa2438fa1709510d7c1afd9");
+        assert_eq!(fill.amount, dec!(0.31));
+        assert_eq!(fill.commission, dec!(0.000248));
+        assert_eq!(fill.is_maker, 0);
+    }
+}
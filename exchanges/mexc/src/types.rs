@@ -0,0 +1,201 @@
+use mmb_domain::order::snapshot::{Amount, ExchangeOrderId, Price};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+/// Symbol description from `/api/v3/exchangeInfo`
+/// {
+///   "symbol": "MXUSDT",
+///   "status": "1",
+///   "baseAsset": "MX",
+///   "baseAssetPrecision": 2,
+///   "quoteAsset": "USDT",
+///   "quotePrecision": 4,
+///   "isSpotTradingAllowed": true,
+///   "maxQuoteAmount": "5000000",
+///   "quoteAmountPrecision": "5",
+///   "baseSizePrecision": "0.01",
+///   "filters": []
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MexcSymbol {
+    pub(crate) symbol: String,
+    /// "1" or "ENABLED" for enabled symbols depending on API version
+    pub(crate) status: String,
+    pub(crate) base_asset: String,
+    /// Count of decimal places of amount
+    pub(crate) base_asset_precision: i8,
+    pub(crate) quote_asset: String,
+    /// Count of decimal places of price
+    pub(crate) quote_precision: i8,
+    #[serde(default)]
+    pub(crate) is_spot_trading_allowed: bool,
+    /// Minimal amount of order, zero if there is no limit
+    #[serde(default)]
+    pub(crate) base_size_precision: Option<Amount>,
+    /// Minimal cost of order in quote currency despite the name
+    #[serde(default)]
+    pub(crate) quote_amount_precision: Option<Price>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcExchangeInfo {
+    pub(crate) symbols: Vec<MexcSymbol>,
+}
+
+/// Order from `/api/v3/order` and `/api/v3/openOrders`, ids of orders are strings unlike Binance
+/// {
+///   "symbol": "MXUSDT",
+///   "orderId": "C02__443776347957968896088",
+///   "clientOrderId": "123456",
+///   "price": "3.5",
+///   "origQty": "10",
+///   "executedQty": "2.5",
+///   "cummulativeQuoteQty": "8.75",
+///   "status": "PARTIALLY_FILLED",
+///   "type": "LIMIT",
+///   "side": "BUY",
+///   "time": 1666676533741
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MexcOrderInfo {
+    pub(crate) symbol: String,
+    pub(crate) order_id: ExchangeOrderId,
+    /// Empty or null for orders created outside of the bot
+    #[serde(default)]
+    pub(crate) client_order_id: Option<String>,
+    pub(crate) price: Price,
+    pub(crate) orig_qty: Amount,
+    pub(crate) executed_qty: Amount,
+    pub(crate) cummulative_quote_qty: Decimal,
+    pub(crate) status: String,
+    pub(crate) side: String,
+}
+
+/// Trade from `/api/v3/myTrades`
+/// {
+///   "symbol": "MXUSDT",
+///   "id": "fad2af9e942049b6adbda1a271f990c6",
+///   "orderId": "bb41e5663e124046bd9497a3f5692f39",
+///   "price": "3.5",
+///   "qty": "2.5",
+///   "commission": "0.0043",
+///   "commissionAsset": "USDT",
+///   "time": 1499865549590,
+///   "isBuyer": true,
+///   "isMaker": false
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MexcMyTrade {
+    pub(crate) id: String,
+    pub(crate) order_id: ExchangeOrderId,
+    pub(crate) price: Price,
+    pub(crate) qty: Amount,
+    pub(crate) commission: Amount,
+    pub(crate) commission_asset: String,
+    pub(crate) time: u64,
+    pub(crate) is_buyer: bool,
+    pub(crate) is_maker: bool,
+}
+
+/// Price level of websocket depth, e.g. `{"p": "3.5", "v": "10"}`
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcLevel {
+    #[serde(rename = "p")]
+    pub(crate) price: Price,
+    #[serde(rename = "v")]
+    pub(crate) amount: Amount,
+}
+
+/// Data of public websocket `spot@public.limit.depth.v3.api` channel,
+/// which always contains full snapshot of limited levels
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcDepth {
+    #[serde(default)]
+    pub(crate) asks: Vec<MexcLevel>,
+    #[serde(default)]
+    pub(crate) bids: Vec<MexcLevel>,
+}
+
+/// Trade of public websocket `spot@public.deals.v3.api` channel
+/// {"S": 1, "p": "3.5", "t": 1678680698000, "v": "2.5"}
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcDeal {
+    /// 1 for buy and 2 for sell
+    #[serde(rename = "S")]
+    pub(crate) side: u8,
+    #[serde(rename = "p")]
+    pub(crate) price: Price,
+    #[serde(rename = "v")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "t")]
+    pub(crate) time: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcDeals {
+    pub(crate) deals: Vec<MexcDeal>,
+}
+
+/// Order update of private websocket `spot@private.orders.v3.api` channel
+/// {
+///   "c": "123456",
+///   "i": "e03a5c7441e44ed899466a7140b71391",
+///   "o": 1,
+///   "p": "3.5",
+///   "v": "10",
+///   "S": 1,
+///   "s": 1,
+///   "O": 1661938138000
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcWsOrder {
+    #[serde(rename = "c", default)]
+    pub(crate) client_order_id: String,
+    #[serde(rename = "i")]
+    pub(crate) order_id: ExchangeOrderId,
+    /// 1 new, 2 filled, 3 partially filled, 4 canceled, 5 partially filled and canceled
+    #[serde(rename = "s")]
+    pub(crate) status: u8,
+}
+
+/// Fill of private websocket `spot@private.deals.v3.api` channel
+/// {
+///   "S": 1,
+///   "T": 1678670940682,
+///   "c": "123456",
+///   "i": "5d6bd0d6c2a54a05b1f6a3f0c1d9a6a1",
+///   "m": 0,
+///   "p": "3.5",
+///   "t": "4a4e4a4b4b4c4c4d4d4e4e4f4f505051",
+///   "v": "2.5",
+///   "n": "0.0043",
+///   "N": "USDT"
+/// }
+#[derive(Deserialize, Debug)]
+pub(crate) struct MexcWsFill {
+    /// 1 for buy and 2 for sell
+    #[serde(rename = "S")]
+    pub(crate) side: u8,
+    #[serde(rename = "T")]
+    pub(crate) time: u64,
+    #[serde(rename = "c", default)]
+    pub(crate) client_order_id: String,
+    #[serde(rename = "i")]
+    pub(crate) order_id: ExchangeOrderId,
+    /// 1 if order was maker
+    #[serde(rename = "m")]
+    pub(crate) is_maker: u8,
+    #[serde(rename = "p")]
+    pub(crate) price: Price,
+    #[serde(rename = "t")]
+    pub(crate) trade_id: String,
+    #[serde(rename = "v")]
+    pub(crate) amount: Amount,
+    #[serde(rename = "n")]
+    pub(crate) commission: Amount,
+    #[serde(rename = "N")]
+    pub(crate) commission_asset: String,
+}
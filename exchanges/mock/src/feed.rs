@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_utils::DateTime;
+use rand::Rng;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{self, Interval};
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+const PRICE_TICK: Decimal = dec!(0.01);
+
+/// Market data and fills generated by websocket server of mock exchange.
+/// All fields are optional, so empty settings run default profile
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FeedSettings {
+    /// Order book snapshots pushed by mock exchange per second
+    pub book_updates_per_sec: u32,
+    /// Fills of open orders pushed by mock exchange per second
+    pub fills_per_sec: u32,
+    /// Price levels on each side of generated order book snapshots
+    pub book_depth: usize,
+    /// Port of local websocket server of mock exchange, any free port is used if `0`
+    pub ws_port: u16,
+    pub initial_price: Decimal,
+}
+
+impl Default for FeedSettings {
+    fn default() -> Self {
+        FeedSettings {
+            book_updates_per_sec: 100,
+            fills_per_sec: 5,
+            book_depth: 20,
+            ws_port: 0,
+            initial_price: dec!(20000),
+        }
+    }
+}
+
+/// Messages pushed by websocket server of mock exchange
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum FeedMessage {
+    Book {
+        sent_at: DateTime,
+        bids: Vec<(Price, Amount)>,
+        asks: Vec<(Price, Amount)>,
+    },
+    /// Request to fill any open order of client
+    Fill { sent_at: DateTime },
+}
+
+/// Start local websocket server which pushes generated market data and fills to every connection
+pub async fn start_feed_server(settings: &FeedSettings) -> Result<Url> {
+    let listener = TcpListener::bind(("127.0.0.1", settings.ws_port))
+        .await
+        .context("Unable to bind websocket server of mock exchange")?;
+    let address = listener.local_addr()?;
+
+    let settings = settings.clone();
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let settings = settings.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = serve_connection(stream, settings).await {
+                            log::warn!("Mock exchange websocket connection closed: {err:?}");
+                        }
+                    });
+                }
+                Err(err) => log::error!("Failed to accept mock exchange connection: {err:?}"),
+            }
+        }
+    });
+
+    Url::parse(&format!("ws://{address}")).context("Invalid websocket url of mock exchange")
+}
+
+async fn serve_connection(stream: TcpStream, settings: FeedSettings) -> Result<()> {
+    let mut websocket = tokio_tungstenite::accept_async(stream).await?;
+
+    let mut book_interval = rate_interval(settings.book_updates_per_sec);
+    let mut fill_interval = rate_interval(settings.fills_per_sec);
+    let mut mid_price = settings.initial_price;
+
+    loop {
+        let message = tokio::select! {
+            _ = book_interval.tick(), if settings.book_updates_per_sec > 0 => {
+                mid_price = next_mid_price(mid_price);
+                create_book(mid_price, settings.book_depth)
+            }
+            _ = fill_interval.tick(), if settings.fills_per_sec > 0 => {
+                FeedMessage::Fill { sent_at: Utc::now() }
+            }
+            incoming = websocket.next() => match incoming {
+                // Pings are answered by tungstenite while reading, other messages are ignored
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Err(err.into()),
+                None => return Ok(()),
+            }
+        };
+
+        websocket
+            .send(Message::Text(serde_json::to_string(&message)?))
+            .await?;
+    }
+}
+
+fn rate_interval(per_sec: u32) -> Interval {
+    time::interval(Duration::from_secs_f64(1.0 / per_sec.max(1) as f64))
+}
+
+/// Random walk of mid price, so strategy has to move its orders from time to time
+fn next_mid_price(mid_price: Price) -> Price {
+    let step_ticks = rand::thread_rng().gen_range(-5..=5);
+    (mid_price + PRICE_TICK * Decimal::from(step_ticks)).max(PRICE_TICK * dec!(100))
+}
+
+fn create_book(mid_price: Price, depth: usize) -> FeedMessage {
+    let mut rng = rand::thread_rng();
+    let mut level_amount = || Decimal::from(rng.gen_range(1..=1000)) * dec!(0.001);
+
+    let bids = (1..=depth)
+        .map(|level| {
+            (
+                mid_price - PRICE_TICK * Decimal::from(level),
+                level_amount(),
+            )
+        })
+        .collect();
+    let asks = (1..=depth)
+        .map(|level| {
+            (
+                mid_price + PRICE_TICK * Decimal::from(level),
+                level_amount(),
+            )
+        })
+        .collect();
+
+    FeedMessage::Book {
+        sent_at: Utc::now(),
+        bids,
+        asks,
+    }
+}
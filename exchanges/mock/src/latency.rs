@@ -0,0 +1,107 @@
+use rand::Rng;
+use serde::Deserialize;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+/// Distribution of simulated network latency of mock exchange
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LatencyProfile {
+    Fixed {
+        millis: u64,
+    },
+    /// Negative samples are clamped to zero
+    Normal {
+        mean_millis: f64,
+        std_dev_millis: f64,
+    },
+    /// Random choice from latencies recorded on live exchange, e.g. from metrics of requests
+    Empirical {
+        samples_millis: Vec<u64>,
+    },
+}
+
+impl LatencyProfile {
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        let millis = match self {
+            LatencyProfile::Fixed { millis } => *millis as f64,
+            LatencyProfile::Normal {
+                mean_millis,
+                std_dev_millis,
+            } => mean_millis + std_dev_millis * standard_normal(rng),
+            LatencyProfile::Empirical { samples_millis } => match samples_millis.is_empty() {
+                true => 0.0,
+                false => samples_millis[rng.gen_range(0..samples_millis.len())] as f64,
+            },
+        };
+
+        Duration::from_secs_f64(millis.max(0.0) / 1000.0)
+    }
+}
+
+/// Box-Muller transform
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+/// Latencies of mock exchange account, there is no delay for not set ones
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LatencySettings {
+    /// Delay of responses on order creation and cancellation
+    pub order_ack: Option<LatencyProfile>,
+    /// Delay of fill notifications after fill message of websocket server
+    pub fill: Option<LatencyProfile>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_latency_is_constant() {
+        let profile = LatencyProfile::Fixed { millis: 15 };
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            assert_eq!(profile.sample(&mut rng), Duration::from_millis(15));
+        }
+    }
+
+    #[test]
+    fn empirical_latency_is_one_of_samples() {
+        let samples_millis = vec![3, 8, 120];
+        let profile = LatencyProfile::Empirical {
+            samples_millis: samples_millis.clone(),
+        };
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let latency = profile.sample(&mut rng).as_millis() as u64;
+            assert!(samples_millis.contains(&latency), "{latency}");
+        }
+    }
+
+    #[test]
+    fn normal_latency_is_spread_around_mean_and_never_negative() {
+        let profile = LatencyProfile::Normal {
+            mean_millis: 10.0,
+            std_dev_millis: 10.0,
+        };
+
+        let mut rng = rand::thread_rng();
+        let samples: Vec<f64> = (0..10_000)
+            .map(|_| profile.sample(&mut rng).as_secs_f64() * 1000.0)
+            .collect();
+
+        assert!(samples.iter().all(|x| *x >= 0.0));
+        assert!(samples.iter().any(|x| *x == 0.0));
+        assert!(samples.iter().any(|x| *x > 20.0));
+
+        // clamping of negative samples moves mean up a little
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        assert!((10.0..12.0).contains(&mean), "{mean}");
+    }
+}
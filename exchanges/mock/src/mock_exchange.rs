@@ -0,0 +1,585 @@
+use crate::feed::FeedMessage;
+use crate::latency::{LatencyProfile, LatencySettings};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClient, ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError,
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::infrastructure::spawn_future_ok;
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{
+    AllowedEventSourceType, EventSourceType, ExchangeBalance, ExchangeBalancesAndPositions,
+    ExchangeEvent, TradeId,
+};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyCode, CurrencyId, CurrencyPair, ExchangeAccountId, ExchangeId, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ClientOrderId, ExchangeOrderId, OrderInfo, OrderRole, OrderSide, OrderStatus, Price,
+};
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mmb_utils::DateTime;
+use rust_decimal_macros::dec;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+use url::Url;
+
+pub const MOCK_EXCHANGE_ID: &str = "Mock";
+const BASE_CURRENCY: &str = "BTC";
+const QUOTE_CURRENCY: &str = "USDT";
+const SPECIFIC_CURRENCY_PAIR: &str = "BTCUSDT";
+const EMPTY_RESPONSE_IS_OK: bool = false;
+
+pub fn mock_currency_pair() -> CurrencyPair {
+    CurrencyPair::from_codes(BASE_CURRENCY.into(), QUOTE_CURRENCY.into())
+}
+
+struct MockOrder {
+    exchange_order_id: ExchangeOrderId,
+    side: OrderSide,
+    price: Price,
+    amount: Amount,
+}
+
+/// Receives notifications about activity of mock exchange, e.g. for measurements of load tests.
+/// `sent_at` is the time when message was sent by websocket server
+pub trait MockExchangeObserver: Send + Sync {
+    fn book_received(&self, _sent_at: DateTime) {}
+
+    fn fill_received(&self, _sent_at: DateTime) {}
+
+    /// Fill is handled by engine
+    fn fill_handled(&self, _sent_at: DateTime) {}
+
+    fn order_created(&self) {}
+
+    fn order_cancelled(&self) {}
+}
+
+/// Exchange which accepts every order immediately and fills open orders on request of websocket
+/// server, so engine can be run without network, credentials and exchange limits
+pub struct MockExchange {
+    settings: ExchangeSettings,
+    ws_url: Url,
+    observer: Option<Arc<dyn MockExchangeObserver>>,
+    events_channel: broadcast::Sender<ExchangeEvent>,
+    lifetime_manager: Arc<AppLifetimeManager>,
+    supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    open_orders: DashMap<ClientOrderId, MockOrder>,
+    last_order_id: AtomicU64,
+    last_trade_id: AtomicU64,
+    latency: LatencySettings,
+    handle_order_filled_callback: Arc<HandleOrderFilledCb>,
+}
+
+impl MockExchange {
+    pub fn new(
+        settings: ExchangeSettings,
+        ws_url: Url,
+        observer: Option<Arc<dyn MockExchangeObserver>>,
+        latency: LatencySettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Self {
+        let supported_currencies = DashMap::new();
+        for currency in [BASE_CURRENCY, QUOTE_CURRENCY] {
+            supported_currencies.insert(currency.into(), currency.into());
+        }
+
+        MockExchange {
+            settings,
+            ws_url,
+            observer,
+            events_channel,
+            lifetime_manager,
+            supported_currencies,
+            open_orders: DashMap::new(),
+            last_order_id: AtomicU64::new(0),
+            last_trade_id: AtomicU64::new(0),
+            latency,
+            handle_order_filled_callback: Arc::new(Box::new(|_| {})),
+        }
+    }
+
+    fn handle_book(&self, sent_at: DateTime, order_book_data: OrderBookData) -> Result<()> {
+        self.notify(|observer| observer.book_received(sent_at));
+
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            mock_currency_pair(),
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_fill(&self, sent_at: DateTime) {
+        self.notify(|observer| observer.fill_received(sent_at));
+
+        // Whole order is filled, so orders are completed and don't pile up in orders pool
+        let Some(client_order_id) = self.open_orders.iter().next().map(|x| x.key().clone()) else {
+            return;
+        };
+        let Some((client_order_id, order)) = self.open_orders.remove(&client_order_id) else {
+            return;
+        };
+
+        let trade_id = self.last_trade_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::Number(trade_id)),
+            client_order_id: Some(client_order_id),
+            exchange_order_id: order.exchange_order_id,
+            fill_price: order.price,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: order.amount,
+                total_filled_amount: Some(order.amount),
+            },
+            order_role: Some(OrderRole::Maker),
+            commission_currency_code: None,
+            commission_rate: None,
+            commission_amount: None,
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: Some(Utc::now()),
+        };
+
+        let Some(delay) = Self::sample_latency(&self.latency.fill) else {
+            (self.handle_order_filled_callback)(fill_event);
+            self.notify(|observer| observer.fill_handled(sent_at));
+            return;
+        };
+
+        let callback = self.handle_order_filled_callback.clone();
+        let observer = self.observer.clone();
+        let action = async move {
+            sleep(delay).await;
+            callback(fill_event);
+            if let Some(observer) = observer {
+                observer.fill_handled(sent_at);
+            }
+        };
+        spawn_future_ok(
+            "Delayed fill of mock exchange",
+            SpawnFutureFlags::STOP_BY_TOKEN,
+            action,
+        );
+    }
+
+    fn sample_latency(profile: &Option<LatencyProfile>) -> Option<Duration> {
+        profile
+            .as_ref()
+            .map(|profile| profile.sample(&mut rand::thread_rng()))
+    }
+
+    /// Simulates network round trip of order request
+    async fn delay_order_ack(&self) {
+        if let Some(delay) = Self::sample_latency(&self.latency.order_ack) {
+            sleep(delay).await;
+        }
+    }
+
+    fn notify(&self, action: impl FnOnce(&dyn MockExchangeObserver)) {
+        if let Some(observer) = &self.observer {
+            action(observer.as_ref());
+        }
+    }
+
+    fn order_info(&self, client_order_id: &ClientOrderId, order: &MockOrder) -> OrderInfo {
+        OrderInfo::new(
+            mock_currency_pair(),
+            order.exchange_order_id.clone(),
+            client_order_id.clone(),
+            order.side,
+            OrderStatus::Created,
+            order.price,
+            order.amount,
+            dec!(0),
+            dec!(0),
+            None,
+            None,
+            None,
+        )
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for MockExchange {
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        self.delay_order_ack().await;
+
+        let order_id = self.last_order_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let exchange_order_id = ExchangeOrderId::from(order_id);
+
+        self.open_orders.insert(
+            order.client_order_id(),
+            MockOrder {
+                exchange_order_id: exchange_order_id.clone(),
+                side: order.side(),
+                price: order.price(),
+                amount: order.amount(),
+            },
+        );
+        self.notify(|observer| observer.order_created());
+
+        CreateOrderResult::succeed(&exchange_order_id, EventSourceType::Rest)
+    }
+
+    async fn cancel_order(
+        &self,
+        order: &OrderRef,
+        _exchange_order_id: &ExchangeOrderId,
+    ) -> CancelOrderResult {
+        self.delay_order_ack().await;
+
+        self.open_orders.remove(&order.client_order_id());
+        self.notify(|observer| observer.order_cancelled());
+
+        CancelOrderResult::succeed(order.client_order_id(), EventSourceType::Rest, None)
+    }
+
+    async fn cancel_all_orders(&self, _currency_pair: CurrencyPair) -> Result<()> {
+        self.open_orders.clear();
+        Ok(())
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        Ok(self
+            .open_orders
+            .iter()
+            .map(|x| self.order_info(x.key(), x.value()))
+            .collect())
+    }
+
+    async fn get_open_orders_by_currency_pair(
+        &self,
+        _currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderInfo>> {
+        self.get_open_orders().await
+    }
+
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        let client_order_id = order.client_order_id();
+        match self.open_orders.get(&client_order_id) {
+            Some(mock_order) => Ok(self.order_info(&client_order_id, &mock_order)),
+            None => Err(ExchangeError::unknown(&format!(
+                "Order {client_order_id} isn't open on mock exchange"
+            ))),
+        }
+    }
+
+    async fn close_position(
+        &self,
+        _position: &ActivePosition,
+        _price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        bail!("Mock exchange supports spot trading only")
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        // There are no positions in spot trading
+        Ok(Vec::new())
+    }
+
+    async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        Ok(ExchangeBalancesAndPositions {
+            balances: vec![
+                ExchangeBalance {
+                    currency_code: BASE_CURRENCY.into(),
+                    balance: dec!(1000),
+                },
+                ExchangeBalance {
+                    currency_code: QUOTE_CURRENCY.into(),
+                    balance: dec!(100000000),
+                },
+            ],
+            positions: None,
+        })
+    }
+
+    async fn get_my_trades(
+        &self,
+        _symbol: &Symbol,
+        _last_date_time: Option<DateTime>,
+    ) -> RequestResult<Vec<OrderTrade>> {
+        // Fills are delivered by websocket only
+        RequestResult::Success(Vec::new())
+    }
+
+    async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        Ok(vec![Arc::new(Symbol::new(
+            false,
+            BASE_CURRENCY.into(),
+            BASE_CURRENCY.into(),
+            QUOTE_CURRENCY.into(),
+            QUOTE_CURRENCY.into(),
+            None,
+            None,
+            Some(dec!(0.001)),
+            None,
+            None,
+            BASE_CURRENCY.into(),
+            None,
+            Precision::ByTick { tick: dec!(0.01) },
+            Precision::ByTick { tick: dec!(0.001) },
+        ))])
+    }
+
+    async fn get_server_time(&self) -> Option<Result<i64>> {
+        Some(Ok(Utc::now().timestamp_millis()))
+    }
+}
+
+#[async_trait]
+impl Support for MockExchange {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        let message: FeedMessage =
+            serde_json::from_str(msg).context("Unable to parse mock exchange message")?;
+
+        match message {
+            FeedMessage::Book {
+                sent_at,
+                bids,
+                asks,
+            } => {
+                let mut order_book_data = OrderBookData::default();
+                order_book_data.bids.extend(bids);
+                order_book_data.asks.extend(asks);
+
+                self.handle_book(sent_at, order_book_data)
+            }
+            FeedMessage::Fill { sent_at } => {
+                self.handle_fill(sent_at);
+                Ok(())
+            }
+        }
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    // Nothing is sent to mock exchange and orders are created and cancelled by REST responses only
+    fn set_send_websocket_message_callback(&mut self, _callback: SendWebsocketMessageCb) {}
+
+    fn set_order_created_callback(&mut self, _callback: OrderCreatedCb) {}
+
+    fn set_order_cancelled_callback(&mut self, _callback: OrderCancelledCb) {}
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = Arc::new(callback);
+    }
+
+    fn set_handle_trade_callback(&mut self, _callback: HandleTradeCb) {}
+
+    fn set_handle_metrics_callback(&mut self, _callback: HandleMetricsCb) {}
+
+    fn set_traded_specific_currencies(&self, _currencies: Vec<SpecificCurrencyPair>) {}
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        // Market data and fills are pushed through the same connection
+        matches!(role, WebSocketRole::Main)
+    }
+
+    async fn create_ws_url(&self, _role: WebSocketRole) -> Result<Url> {
+        Ok(self.ws_url.clone())
+    }
+
+    fn get_specific_currency_pair(&self, _currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        SPECIFIC_CURRENCY_PAIR.into()
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, _message: &str) -> bool {
+        // Logging of every message would distort measured latencies
+        false
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+}
+
+pub struct MockExchangeBuilder {
+    /// Url of websocket server started by `start_feed_server`
+    pub ws_url: Url,
+    pub observer: Option<Arc<dyn MockExchangeObserver>>,
+    /// Simulated latencies by exchange account, accounts which aren't listed have no delays
+    pub latency: HashMap<ExchangeAccountId, LatencySettings>,
+}
+
+impl ExchangeClientBuilder for MockExchangeBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        let latency = self
+            .latency
+            .get(&exchange_settings.exchange_account_id)
+            .cloned()
+            .unwrap_or_default();
+
+        ExchangeClientBuilderResult {
+            client: Box::new(MockExchange::new(
+                exchange_settings,
+                self.ws_url.clone(),
+                self.observer.clone(),
+                latency,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: false,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: false,
+                    creation_response_from_rest_only_for_errors: false,
+                    order_was_completed_error_for_cancellation: false,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: true,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: false,
+                    supports_ping_pong: false,
+                    supports_subscription_response: false,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Requests aren't limited by mock exchange, so timeout manager isn't a bottleneck
+        RequestTimeoutArguments::from_requests_per_minute(1_000_000)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        MOCK_EXCHANGE_ID.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::order::snapshot::{OrderHeader, UserOrder};
+    use mmb_utils::cancellation_token::CancellationToken;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn order_ack_is_delayed_by_latency_profile() {
+        let exchange_account_id = ExchangeAccountId::new(MOCK_EXCHANGE_ID, 0);
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+        let latency = LatencySettings {
+            order_ack: Some(LatencyProfile::Fixed { millis: 50 }),
+            fill: None,
+        };
+        let (events_channel, _) = broadcast::channel(10);
+        let exchange = MockExchange::new(
+            settings,
+            Url::parse("ws://127.0.0.1").expect("in test"),
+            None,
+            latency,
+            events_channel,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+
+        let header = OrderHeader::with_user_order(
+            "test".into(),
+            exchange_account_id,
+            mock_currency_pair(),
+            OrderSide::Buy,
+            dec!(1),
+            UserOrder::limit(dec!(100)),
+            None,
+            None,
+            "test".to_owned(),
+        );
+        let order = OrdersPool::new().add_simple_initial(&header, Utc::now(), None);
+
+        let started = Instant::now();
+        let result = exchange.create_order(&order).await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert!(matches!(result.outcome, RequestResult::Success(_)));
+        assert_eq!(exchange.open_orders.len(), 1);
+
+        let started = Instant::now();
+        let exchange_order_id = ExchangeOrderId::from(1u64);
+        let _ = exchange.cancel_order(&order, &exchange_order_id).await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert!(exchange.open_orders.is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::okx::Okx;
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::{CurrencyCode, CurrencyPair};
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_domain::transfer::{
+    DepositAddress, ExternalTransferInfo, NetworkStatus, WithdrawalRequest,
+};
+use mmb_utils::DateTime;
+use std::sync::Arc;
+
+#[async_trait]
+impl ExchangeClient for Okx {
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        match self.do_create_order(order).await {
+            Ok(request_outcome) => match self.get_order_id(&request_outcome) {
+                Ok(order_id) => CreateOrderResult::succeed(&order_id, EventSourceType::Rest),
+                Err(error) => CreateOrderResult::failed(error, EventSourceType::Rest),
+            },
+            Err(err) => CreateOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> CancelOrderResult {
+        match self.do_cancel_order(order, exchange_order_id).await {
+            Ok(_) => {
+                CancelOrderResult::succeed(order.client_order_id(), EventSourceType::Rest, None)
+            }
+            Err(err) => CancelOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        match self.do_cancel_all_orders(currency_pair).await {
+            Ok(_) => Ok(()),
+            Err(error) => bail!("Failed to cancel all orders: {error:?}"),
+        }
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        let response = self.request_open_orders(None).await?;
+
+        self.parse_open_orders(&response)
+    }
+
+    async fn get_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderInfo>> {
+        let response = self.request_open_orders(Some(currency_pair)).await?;
+
+        self.parse_open_orders(&response)
+    }
+
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        match self.request_order_info(order).await {
+            Ok(request_outcome) => self.parse_order_info(&request_outcome).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse order info: {err:?}"))
+            }),
+            Err(error) => Err(ExchangeError::unknown(
+                format!("Failed to get order info: {:?}", error).as_str(),
+            )),
+        }
+    }
+
+    async fn close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        if !self.settings.is_margin_trading {
+            bail!("OKX client supports positions only in margin trading mode")
+        }
+
+        let response = self.request_close_position(position, price).await?;
+
+        self.parse_close_position(position, &response)
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        if !self.settings.is_margin_trading {
+            // There are no positions in spot trading
+            return Ok(Vec::new());
+        }
+
+        let response = self.request_get_position().await?;
+
+        self.parse_get_position(&response)
+    }
+
+    async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        let balance_response = self.request_get_balance().await?;
+        let balances = self.parse_get_balance(&balance_response)?;
+
+        if !self.settings.is_margin_trading {
+            return Ok(ExchangeBalancesAndPositions {
+                balances,
+                positions: None,
+            });
+        }
+
+        let positions = self
+            .get_active_positions()
+            .await?
+            .into_iter()
+            .map(|active_position| active_position.derivative)
+            .collect::<Vec<DerivativePosition>>();
+
+        Ok(ExchangeBalancesAndPositions {
+            balances,
+            positions: Some(positions),
+        })
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> RequestResult<Vec<OrderTrade>> {
+        match self.request_my_trades(symbol, last_date_time).await {
+            Ok(response) => match self.parse_my_trades(&response) {
+                Ok(data) => RequestResult::Success(data),
+                Err(err) => RequestResult::Error(ExchangeError::parsing(format!(
+                    "Unable to parse trades: {err:?}"
+                ))),
+            },
+            Err(err) => RequestResult::Error(ExchangeError::unknown(
+                format!("Failed to get trades: {err:?}").as_str(),
+            )),
+        }
+    }
+
+    async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        let response = self.request_all_symbols().await?;
+
+        self.parse_all_symbols(&response)
+    }
+
+    async fn get_server_time(&self) -> Option<Result<i64>> {
+        // TODO Need to receive OKX server time
+        None
+    }
+
+    async fn get_network_status(
+        &self,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Option<Result<NetworkStatus>> {
+        let result = match self.request_currency_chains(currency_code).await {
+            Ok(response) => self.parse_network_status(&response, currency_code, network),
+            Err(error) => Err(anyhow!("Failed to get currency chains: {error:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn get_deposit_address(
+        &self,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Option<Result<DepositAddress>> {
+        let result = match self.request_deposit_address(currency_code).await {
+            Ok(response) => self.parse_deposit_address(&response, currency_code, network),
+            Err(error) => Err(anyhow!("Failed to get deposit address: {error:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn withdraw(&self, request: &WithdrawalRequest) -> Option<Result<String>> {
+        let result = match self.request_withdraw(request).await {
+            Ok(response) => self.parse_withdrawal_id(&response),
+            Err(error) => Err(anyhow!("Failed to withdraw: {error:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn get_withdrawal(
+        &self,
+        currency_code: CurrencyCode,
+        withdrawal_id: &str,
+    ) -> Option<Result<ExternalTransferInfo>> {
+        let result = match self
+            .request_withdrawal_history(currency_code, withdrawal_id)
+            .await
+        {
+            Ok(response) => self.parse_withdrawal(&response, withdrawal_id),
+            Err(error) => Err(anyhow!("Failed to get withdrawal history: {error:?}")),
+        };
+
+        Some(result)
+    }
+
+    async fn get_deposit(
+        &self,
+        currency_code: CurrencyCode,
+        tx_id: &str,
+    ) -> Option<Result<Option<ExternalTransferInfo>>> {
+        let result = match self.request_deposit_history(currency_code, tx_id).await {
+            Ok(response) => self.parse_deposit(&response, tx_id),
+            Err(error) => Err(anyhow!("Failed to get deposit history: {error:?}")),
+        };
+
+        Some(result)
+    }
+}
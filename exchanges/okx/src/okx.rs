@@ -0,0 +1,1228 @@
+use crate::types::{
+    OkxBalance, OkxCancelOrderRequest, OkxCurrencyChain, OkxDeposit, OkxDepositAddress, OkxFill,
+    OkxInstrument, OkxOrderId, OkxOrderInfo, OkxPlaceOrderRequest, OkxPosition, OkxResponse,
+    OkxWithdrawal, OkxWithdrawalId, OkxWithdrawalRequest,
+};
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use dashmap::DashMap;
+use function_name::named;
+use hmac::{Hmac, Mac};
+use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleMetricsCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, SendWebsocketMessageCb,
+    Support,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{AllowedEventSourceType, ExchangeBalance, ExchangeEvent, TradeId};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole, OrderSide,
+    OrderStatus, Price, UserOrder,
+};
+use mmb_domain::position::{ActivePosition, ClosedPosition, DerivativePosition};
+use mmb_domain::transfer::{
+    DepositAddress, ExternalTransferInfo, ExternalTransferStatus, NetworkStatus, WithdrawalRequest,
+};
+use mmb_utils::DateTime;
+use parking_lot::{Mutex, RwLock};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerOkx;
+
+impl ErrorHandler for ErrorHandlerOkx {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OperationResult {
+            #[serde(default)]
+            s_code: String,
+            #[serde(default)]
+            s_msg: String,
+        }
+        #[derive(Deserialize)]
+        struct OkxError {
+            code: String,
+            #[serde(default)]
+            msg: String,
+            #[serde(default)]
+            data: Vec<OperationResult>,
+        }
+
+        let okx_error: OkxError = serde_json::from_str(&response.content).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to parse OKX response: {err:?}"))
+        })?;
+        if okx_error.code == "0" {
+            return Ok(());
+        }
+
+        // Trade endpoints return general code "1" and specific error code for every operation
+        let (code, message) = match okx_error
+            .data
+            .iter()
+            .find(|result| !result.s_code.is_empty() && result.s_code != "0")
+        {
+            Some(result) => (result.s_code.as_str(), result.s_msg.as_str()),
+            None => (okx_error.code.as_str(), okx_error.msg.as_str()),
+        };
+
+        Err(ExchangeError::new(
+            ExchangeErrorType::Unknown,
+            message.to_owned(),
+            code.parse().ok(),
+        ))
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Details: https://www.okx.com/docs-v5/en/#error-code
+        match error.code {
+            Some(51400) | Some(51401) | Some(51603) => ExchangeErrorType::OrderNotFound,
+            Some(51402) => ExchangeErrorType::OrderCompleted,
+            Some(51008) | Some(51131) => ExchangeErrorType::InsufficientFunds,
+            Some(51000) | Some(51006) | Some(51020) | Some(51121) | Some(51124) => {
+                ExchangeErrorType::InvalidOrder
+            }
+            Some(50011) | Some(50061) => ExchangeErrorType::RateLimit,
+            Some(50105) | Some(50111) | Some(50113) => ExchangeErrorType::Authentication,
+            Some(50001) | Some(50013) => ExchangeErrorType::ServiceUnavailable,
+            _ => ExchangeErrorType::Unknown,
+        }
+    }
+}
+
+pub struct RestHeadersOkx {
+    api_key: String,
+    secret_key: String,
+    passphrase: String,
+}
+
+impl RestHeadersOkx {
+    pub fn new(api_key: String, secret_key: String, passphrase: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+            passphrase,
+        }
+    }
+
+    fn add_auth_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        if self.api_key.is_empty() {
+            // Public endpoints don't require authentication
+            return builder;
+        }
+
+        let path_and_query = match uri.path_and_query() {
+            Some(path_and_query) => path_and_query.as_str(),
+            None => uri.path(),
+        };
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        let signature = Okx::create_signature(
+            &self.secret_key,
+            &timestamp,
+            request_type.as_str(),
+            path_and_query,
+            body,
+        );
+
+        builder
+            .header("OK-ACCESS-KEY", &self.api_key)
+            .header("OK-ACCESS-SIGN", signature)
+            .header("OK-ACCESS-TIMESTAMP", timestamp)
+            .header("OK-ACCESS-PASSPHRASE", &self.passphrase)
+    }
+}
+
+impl RestHeaders for RestHeadersOkx {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+    ) -> Builder {
+        self.add_auth_headers(builder, uri, request_type, &[])
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        uri: &Uri,
+        request_type: RequestType,
+        body: &[u8],
+    ) -> Builder {
+        self.add_auth_headers(builder, uri, request_type, body)
+            .header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+// OKX allows to cancel up to 20 orders in a single batch request
+const CANCEL_BATCH_SIZE: usize = 20;
+
+pub struct Okx {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerOkx, RestHeadersOkx>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    specific_to_unified: RwLock<HashMap<SpecificCurrencyPair, CurrencyPair>>,
+    // Amounts of SWAP instruments are specified in contracts on OKX side
+    contract_values: RwLock<HashMap<SpecificCurrencyPair, Decimal>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    // Currencies used for trading according to user settings
+    pub(super) traded_specific_currencies: Mutex<Vec<SpecificCurrencyPair>>,
+    pub(super) lifetime_manager: Arc<AppLifetimeManager>,
+    pub(super) events_channel: broadcast::Sender<ExchangeEvent>,
+    pub(crate) order_created_callback: OrderCreatedCb,
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) handle_trade_callback: HandleTradeCb,
+    pub(super) handle_metrics_callback: HandleMetricsCb,
+    pub(crate) websocket_message_callback: SendWebsocketMessageCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Okx {
+    pub fn new(
+        settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+    ) -> Okx {
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerOkx::default(),
+                ),
+                RestHeadersOkx::new(
+                    settings.api_key.clone(),
+                    settings.secret_key.clone(),
+                    settings.passphrase.clone(),
+                ),
+                &settings.network,
+            ),
+            currency_aliases: CurrencyAliases::new(&[], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            unified_to_specific: Default::default(),
+            specific_to_unified: Default::default(),
+            contract_values: Default::default(),
+            supported_currencies: Default::default(),
+            traded_specific_currencies: Default::default(),
+            events_channel,
+            lifetime_manager,
+            order_created_callback: Box::new(|_, _, _| {}),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+            handle_trade_callback: Box::new(|_, _| {}),
+            handle_metrics_callback: Box::new(|_| {}),
+            websocket_message_callback: Box::new(|_, _| Ok(())),
+        }
+    }
+
+    fn make_hosts() -> Hosts {
+        Hosts {
+            web_socket_host: "wss://ws.okx.com:8443/ws/v5/private",
+            web_socket2_host: "wss://ws.okx.com:8443/ws/v5/public",
+            rest_host: "https://www.okx.com",
+        }
+    }
+
+    /// OKX instrument type according to trading mode: SWAP for margin trading and SPOT otherwise
+    pub(crate) fn get_instrument_type(&self) -> &'static str {
+        match self.settings.is_margin_trading {
+            true => "SWAP",
+            false => "SPOT",
+        }
+    }
+
+    fn get_trade_mode(&self) -> &'static str {
+        match self.settings.is_margin_trading {
+            true => "cross",
+            false => "cash",
+        }
+    }
+
+    pub(super) fn create_signature(
+        secret_key: &str,
+        timestamp: &str,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
+    ) -> String {
+        let mut hmac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes())
+            .expect("Unable to calculate hmac for OKX signature");
+        hmac.update(timestamp.as_bytes());
+        hmac.update(method.as_bytes());
+        hmac.update(path_and_query.as_bytes());
+        hmac.update(body);
+
+        base64::encode(hmac.finalize().into_bytes())
+    }
+
+    async fn post_json(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+        action_name: &'static str,
+        log_args: String,
+    ) -> Result<RestResponse, ExchangeError> {
+        let body = serde_json::to_vec(body).map_err(|err| {
+            ExchangeError::parsing(format!("Unable to serialize OKX request body: {err:?}"))
+        })?;
+        let uri = UriBuilder::from_path(path).build_uri(self.hosts.rest_uri_host(), false);
+
+        self.rest_client
+            .post(uri, Some(Bytes::from(body)), action_name, log_args)
+            .await
+    }
+
+    #[named]
+    pub(super) async fn request_all_symbols(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/public/instruments");
+        builder.add_kv("instType", self.get_instrument_type());
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_all_symbols(&self, response: &RestResponse) -> Result<Vec<Arc<Symbol>>> {
+        let instruments: OkxResponse<OkxInstrument> = serde_json::from_str(&response.content)
+            .context("Unable to deserialize response from OKX")?;
+
+        Ok(instruments
+            .data
+            .iter()
+            .filter(|instrument| instrument.state == "live")
+            .filter_map(|instrument| self.parse_symbol(instrument))
+            .collect_vec())
+    }
+
+    fn parse_symbol(&self, instrument: &OkxInstrument) -> Option<Arc<Symbol>> {
+        let (base_id, quote_id) = match self.settings.is_margin_trading {
+            // Underlying of SWAP instrument has format "BASE-QUOTE"
+            true => instrument.uly.split_once('-')?,
+            false => (instrument.base_ccy.as_str(), instrument.quote_ccy.as_str()),
+        };
+        let base = self.currency_aliases.unify(base_id.into());
+        let quote = self.currency_aliases.unify(quote_id.into());
+
+        let specific_currency_pair = instrument.inst_id.as_str().into();
+        let unified_currency_pair = CurrencyPair::from_codes(base, quote);
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair);
+        self.specific_to_unified
+            .write()
+            .insert(specific_currency_pair, unified_currency_pair);
+
+        self.supported_currencies.insert(base_id.into(), base);
+        self.supported_currencies.insert(quote_id.into(), quote);
+
+        // Amounts of symbol are converted from contracts to contract value currency
+        let (contract_value, amount_currency_code, balance_currency_code) =
+            match self.settings.is_margin_trading {
+                true => {
+                    let contract_value = instrument.ct_val?;
+                    self.contract_values
+                        .write()
+                        .insert(specific_currency_pair, contract_value);
+
+                    let settlement = self
+                        .currency_aliases
+                        .unify(instrument.settle_ccy.as_str().into());
+                    match instrument.ct_type.as_str() {
+                        "inverse" => (contract_value, quote, Some(settlement)),
+                        _ => (contract_value, base, Some(settlement)),
+                    }
+                }
+                false => (Decimal::ONE, base, None),
+            };
+
+        Some(Arc::new(Symbol::new(
+            self.settings.is_margin_trading,
+            base_id.into(),
+            base,
+            quote_id.into(),
+            quote,
+            None,
+            None,
+            instrument.min_amount.map(|amount| amount * contract_value),
+            instrument.max_amount.map(|amount| amount * contract_value),
+            None,
+            amount_currency_code,
+            balance_currency_code,
+            Precision::ByTick {
+                tick: instrument.price_tick,
+            },
+            Precision::ByTick {
+                tick: instrument.amount_tick * contract_value,
+            },
+        )))
+    }
+
+    fn get_contract_value(&self, specific_currency_pair: &SpecificCurrencyPair) -> Decimal {
+        self.contract_values
+            .read()
+            .get(specific_currency_pair)
+            .copied()
+            .unwrap_or(Decimal::ONE)
+    }
+
+    /// Converts amount in contracts of SWAP instrument to amount in contract value currency
+    pub(crate) fn from_contracts(
+        &self,
+        specific_currency_pair: &SpecificCurrencyPair,
+        contracts: Amount,
+    ) -> Amount {
+        contracts * self.get_contract_value(specific_currency_pair)
+    }
+
+    /// Converts amount in contract value currency to amount in contracts of SWAP instrument
+    fn to_contracts(
+        &self,
+        specific_currency_pair: &SpecificCurrencyPair,
+        amount: Amount,
+    ) -> Amount {
+        (amount / self.get_contract_value(specific_currency_pair)).normalize()
+    }
+
+    #[named]
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let header = order.header();
+        let specific_currency_pair = self.get_specific_currency_pair(header.currency_pair);
+
+        let (ord_type, px) = match header.options {
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => match execution_type {
+                OrderExecutionType::MakerOnly => ("post_only", Some(price)),
+                OrderExecutionType::None => ("limit", Some(price)),
+            },
+            OrderOptions::User(UserOrder::Market) => ("market", None),
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let request = OkxPlaceOrderRequest {
+            inst_id: specific_currency_pair.as_str(),
+            td_mode: self.get_trade_mode(),
+            cl_ord_id: header.client_order_id.as_str(),
+            side: Self::get_side_str(header.side),
+            ord_type,
+            sz: self.to_contracts(&specific_currency_pair, header.amount),
+            px,
+            // By default amount of SPOT market buy order is specified in quote currency
+            tgt_ccy: (ord_type == "market" && !self.settings.is_margin_trading)
+                .then_some("base_ccy"),
+            reduce_only: None,
+        };
+
+        let log_args = format!("Create order for {header:?}");
+        self.post_json("/api/v5/trade/order", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn get_order_id(
+        &self,
+        response: &RestResponse,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let deserialized: OkxResponse<OkxOrderId> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse ordId: {err:?}")))?;
+
+        deserialized
+            .data
+            .into_iter()
+            .next()
+            .map(|order| order.ord_id)
+            .ok_or_else(|| ExchangeError::parsing("No one ordId received".to_owned()))
+    }
+
+    #[named]
+    pub(super) async fn do_cancel_order(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair = self.get_specific_currency_pair(order.currency_pair());
+        let request = OkxCancelOrderRequest {
+            inst_id: specific_currency_pair.as_str(),
+            ord_id: exchange_order_id.as_str(),
+        };
+
+        let log_args = format!("Cancel order for {}", order.client_order_id());
+        self.post_json(
+            "/api/v5/trade/cancel-order",
+            &request,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    /// OKX doesn't support cancellation of all orders, so they are canceled by batches
+    #[named]
+    pub(super) async fn do_cancel_orders_batch(
+        &self,
+        orders: &[OrderInfo],
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pairs = orders
+            .iter()
+            .map(|order| self.get_specific_currency_pair(order.currency_pair))
+            .collect_vec();
+        let request = orders
+            .iter()
+            .zip(&specific_currency_pairs)
+            .map(|(order, specific_currency_pair)| OkxCancelOrderRequest {
+                inst_id: specific_currency_pair.as_str(),
+                ord_id: order.exchange_order_id.as_str(),
+            })
+            .collect_vec();
+
+        let log_args = format!("Cancel orders batch of {} orders", orders.len());
+        self.post_json(
+            "/api/v5/trade/cancel-batch-orders",
+            &request,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) async fn do_cancel_all_orders(&self, currency_pair: CurrencyPair) -> Result<()> {
+        let response = self.request_open_orders(Some(currency_pair)).await?;
+        let orders = self.parse_open_orders(&response)?;
+
+        for batch in orders.chunks(CANCEL_BATCH_SIZE) {
+            self.do_cancel_orders_batch(batch).await?;
+        }
+
+        Ok(())
+    }
+
+    #[named]
+    pub(super) async fn request_open_orders(
+        &self,
+        currency_pair: Option<CurrencyPair>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/trade/orders-pending");
+        builder.add_kv("instType", self.get_instrument_type());
+        if let Some(pair) = currency_pair {
+            builder.add_kv("instId", self.get_specific_currency_pair(pair));
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_open_orders(&self, response: &RestResponse) -> Result<Vec<OrderInfo>> {
+        let orders: OkxResponse<OkxOrderInfo> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_open_orders request")?;
+
+        orders
+            .data
+            .into_iter()
+            .map(|order| self.specific_order_info_to_unified(order))
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_order_info(
+        &self,
+        order: &OrderRef,
+    ) -> Result<RestResponse, ExchangeError> {
+        let client_order_id = order.client_order_id();
+
+        let mut builder = UriBuilder::from_path("/api/v5/trade/order");
+        builder.add_kv(
+            "instId",
+            self.get_specific_currency_pair(order.currency_pair()),
+        );
+        builder.add_kv("clOrdId", &client_order_id);
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+        let log_args = format!("order {client_order_id}");
+
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    pub(super) fn parse_order_info(&self, response: &RestResponse) -> Result<OrderInfo> {
+        let orders: OkxResponse<OkxOrderInfo> = serde_json::from_str(&response.content)
+            .context("Unable to parse response content for get_order_info request")?;
+
+        let order = orders
+            .data
+            .into_iter()
+            .next()
+            .context("No one order info received")?;
+
+        self.specific_order_info_to_unified(order)
+    }
+
+    fn specific_order_info_to_unified(&self, specific: OkxOrderInfo) -> Result<OrderInfo> {
+        let specific_currency_pair = specific.inst_id.as_str().into();
+        let filled_amount = specific.acc_fill_sz.unwrap_or_default();
+        // Negative fee means charged commission
+        let commission_amount = specific.fee.map(|fee| -fee);
+        let commission_currency_code = match specific.fee_ccy.is_empty() {
+            true => None,
+            false => Some(
+                self.currency_aliases
+                    .unify(specific.fee_ccy.as_str().into())
+                    .to_string(),
+            ),
+        };
+
+        Ok(OrderInfo::new(
+            self.get_unified_currency_pair(&specific_currency_pair)?,
+            specific.ord_id,
+            specific.cl_ord_id,
+            specific.side,
+            Okx::get_local_order_status(&specific.state),
+            specific.px.unwrap_or_default(),
+            self.from_contracts(&specific_currency_pair, specific.sz),
+            specific.avg_px.unwrap_or_default(),
+            self.from_contracts(&specific_currency_pair, filled_amount),
+            commission_currency_code,
+            None,
+            commission_amount,
+        ))
+    }
+
+    pub(super) fn get_unified_currency_pair(
+        &self,
+        currency_pair: &SpecificCurrencyPair,
+    ) -> Result<CurrencyPair> {
+        self.specific_to_unified
+            .read()
+            .get(currency_pair)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "Not found currency pair '{currency_pair:?}' in {}",
+                    self.settings.exchange_account_id
+                )
+            })
+    }
+
+    pub(super) fn get_local_order_status(status: &str) -> OrderStatus {
+        match status {
+            "live" | "partially_filled" => OrderStatus::Created,
+            "filled" => OrderStatus::Completed,
+            "canceled" | "mmp_canceled" => OrderStatus::Canceled,
+            _ => panic!("OKX: unexpected order status {}", status),
+        }
+    }
+
+    pub(super) fn get_side_str(side: OrderSide) -> &'static str {
+        match side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        }
+    }
+
+    pub(super) fn get_order_role(exec_type: &str) -> Option<OrderRole> {
+        match exec_type {
+            "M" => Some(OrderRole::Maker),
+            "T" => Some(OrderRole::Taker),
+            _ => None,
+        }
+    }
+
+    #[named]
+    pub(super) async fn request_my_trades(
+        &self,
+        symbol: &Symbol,
+        last_date_time: Option<DateTime>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/trade/fills");
+        builder.add_kv("instType", self.get_instrument_type());
+        builder.add_kv(
+            "instId",
+            self.get_specific_currency_pair(symbol.currency_pair()),
+        );
+        if let Some(date_time) = last_date_time {
+            builder.add_kv("begin", date_time.timestamp_millis());
+        }
+
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_my_trades(&self, response: &RestResponse) -> Result<Vec<OrderTrade>> {
+        let fills: OkxResponse<OkxFill> =
+            serde_json::from_str(&response.content).context("Failed to parse trade data")?;
+
+        fills
+            .data
+            .into_iter()
+            .map(|fill| {
+                let specific_currency_pair = fill.inst_id.as_str().into();
+                Ok(OrderTrade {
+                    exchange_order_id: fill.ord_id,
+                    trade_id: TradeId::from(fill.trade_id),
+                    datetime: fill.ts,
+                    price: fill.fill_px,
+                    amount: self.from_contracts(&specific_currency_pair, fill.fill_sz),
+                    side: fill.side,
+                    order_role: Okx::get_order_role(&fill.exec_type)
+                        .with_context(|| format!("Unknown OKX exec type {}", fill.exec_type))?,
+                    fee_currency_code: self.currency_aliases.unify(fill.fee_ccy.as_str().into()),
+                    fee_rate: None,
+                    // Negative fee means charged commission
+                    fee_amount: Some(-fill.fee),
+                    fill_type: OrderFillType::UserTrade,
+                })
+            })
+            .try_collect()
+    }
+
+    #[named]
+    pub(super) async fn request_get_balance(&self) -> Result<RestResponse, ExchangeError> {
+        let builder = UriBuilder::from_path("/api/v5/account/balance");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_balance(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ExchangeBalance>> {
+        let balances: OkxResponse<OkxBalance> =
+            serde_json::from_str(&response.content).context("Failed to parse balance")?;
+
+        Ok(balances
+            .data
+            .into_iter()
+            .flat_map(|balance| balance.details)
+            .map(|details| ExchangeBalance {
+                currency_code: self.currency_aliases.unify(details.ccy.as_str().into()),
+                balance: details.cash_bal,
+            })
+            .collect_vec())
+    }
+
+    #[named]
+    pub(super) async fn request_get_position(&self) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/account/positions");
+        builder.add_kv("instType", "SWAP");
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), "".to_string())
+            .await
+    }
+
+    pub(super) fn parse_get_position(
+        &self,
+        response: &RestResponse,
+    ) -> Result<Vec<ActivePosition>> {
+        let positions: OkxResponse<OkxPosition> =
+            serde_json::from_str(&response.content).context("Failed to parse positions")?;
+
+        positions
+            .data
+            .into_iter()
+            .filter(|position| position.pos.map_or(false, |pos| !pos.is_zero()))
+            .map(|position| {
+                let specific_currency_pair = position.inst_id.as_str().into();
+                let derivative_position = DerivativePosition {
+                    currency_pair: self.get_unified_currency_pair(&specific_currency_pair)?,
+                    position: self
+                        .from_contracts(&specific_currency_pair, position.pos.unwrap_or_default()),
+                    average_entry_price: position.avg_px.unwrap_or_default(),
+                    liquidation_price: position.liq_px.unwrap_or_default(),
+                    leverage: position.lever.unwrap_or(Decimal::ONE),
+                };
+
+                Ok(ActivePosition::new(derivative_position, position.u_time))
+            })
+            .try_collect()
+    }
+
+    /// Position is closed by reduce-only order with opposite side
+    #[named]
+    pub(super) async fn request_close_position(
+        &self,
+        position: &ActivePosition,
+        price: Option<Price>,
+    ) -> Result<RestResponse, ExchangeError> {
+        let specific_currency_pair =
+            self.get_specific_currency_pair(position.derivative.currency_pair);
+        let side = match position.derivative.position.is_sign_positive() {
+            true => OrderSide::Sell,
+            false => OrderSide::Buy,
+        };
+        let client_order_id = position.id.to_string();
+
+        let request = OkxPlaceOrderRequest {
+            inst_id: specific_currency_pair.as_str(),
+            td_mode: self.get_trade_mode(),
+            cl_ord_id: &client_order_id,
+            side: Self::get_side_str(side),
+            ord_type: price.map_or("market", |_| "limit"),
+            sz: self.to_contracts(&specific_currency_pair, position.derivative.position.abs()),
+            px: price,
+            tgt_ccy: None,
+            reduce_only: Some(true),
+        };
+
+        let log_args = format!("Close position response for {position:?} {price:?}");
+        self.post_json("/api/v5/trade/order", &request, function_name!(), log_args)
+            .await
+    }
+
+    pub(super) fn parse_close_position(
+        &self,
+        position: &ActivePosition,
+        response: &RestResponse,
+    ) -> Result<ClosedPosition> {
+        let exchange_order_id = self.get_order_id(response).map_err(|err| {
+            anyhow::anyhow!("Failed to get order id of closing position: {err:?}")
+        })?;
+
+        Ok(ClosedPosition::new(
+            exchange_order_id,
+            position.derivative.position.abs(),
+        ))
+    }
+
+    fn get_currency_id(&self, currency_code: CurrencyCode) -> Result<CurrencyId, ExchangeError> {
+        self.supported_currencies
+            .iter()
+            .find(|x| *x.value() == currency_code)
+            .map(|x| *x.key())
+            .ok_or_else(|| ExchangeError::parsing(format!("Unsupported currency {currency_code}")))
+    }
+
+    /// OKX chain name consists of currency and network, e.g. "USDT-ERC20"
+    fn get_chain(&self, currency_code: CurrencyCode, network: &str) -> Result<String> {
+        let currency_id = self.get_currency_id(currency_code)?;
+        Ok(format!("{currency_id}-{network}"))
+    }
+
+    #[named]
+    pub(super) async fn request_currency_chains(
+        &self,
+        currency_code: CurrencyCode,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/asset/currencies");
+        builder.add_kv("ccy", self.get_currency_id(currency_code)?);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), currency_code.to_string())
+            .await
+    }
+
+    pub(super) fn parse_network_status(
+        &self,
+        response: &RestResponse,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Result<NetworkStatus> {
+        let chains: OkxResponse<OkxCurrencyChain> = serde_json::from_str(&response.content)
+            .context("Unable to parse OKX currency chains")?;
+
+        let chain_name = self.get_chain(currency_code, network)?;
+        let chain = chains
+            .data
+            .into_iter()
+            .find(|chain| chain.chain == chain_name)
+            .with_context(|| format!("Chain {chain_name} isn't found"))?;
+
+        Ok(NetworkStatus {
+            is_deposit_enabled: chain.can_dep,
+            is_withdrawal_enabled: chain.can_wd,
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_deposit_address(
+        &self,
+        currency_code: CurrencyCode,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/asset/deposit-address");
+        builder.add_kv("ccy", self.get_currency_id(currency_code)?);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        self.rest_client
+            .get(uri, function_name!(), currency_code.to_string())
+            .await
+    }
+
+    pub(super) fn parse_deposit_address(
+        &self,
+        response: &RestResponse,
+        currency_code: CurrencyCode,
+        network: &str,
+    ) -> Result<DepositAddress> {
+        let addresses: OkxResponse<OkxDepositAddress> = serde_json::from_str(&response.content)
+            .context("Unable to parse OKX deposit addresses")?;
+
+        let chain_name = self.get_chain(currency_code, network)?;
+        let address = addresses
+            .data
+            .into_iter()
+            .find(|address| address.chain == chain_name)
+            .with_context(|| format!("Deposit address for chain {chain_name} isn't found"))?;
+
+        let tag = [address.tag, address.memo]
+            .into_iter()
+            .find(|tag| !tag.is_empty());
+
+        Ok(DepositAddress {
+            address: address.addr,
+            tag,
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_withdraw(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<RestResponse, ExchangeError> {
+        let currency_id = self.get_currency_id(request.currency_code)?;
+        let to_addr = match &request.address.tag {
+            Some(tag) => format!("{}:{tag}", request.address.address),
+            None => request.address.address.clone(),
+        };
+
+        let withdrawal_request = OkxWithdrawalRequest {
+            ccy: currency_id.as_str(),
+            amt: request.amount,
+            // on-chain withdrawal
+            dest: "4",
+            to_addr,
+            chain: self.get_chain(request.currency_code, &request.network)?,
+        };
+
+        let log_args = format!("{request:?}");
+        self.post_json(
+            "/api/v5/asset/withdrawal",
+            &withdrawal_request,
+            function_name!(),
+            log_args,
+        )
+        .await
+    }
+
+    pub(super) fn parse_withdrawal_id(&self, response: &RestResponse) -> Result<String> {
+        let withdrawals: OkxResponse<OkxWithdrawalId> =
+            serde_json::from_str(&response.content).context("Unable to parse OKX withdrawal id")?;
+
+        withdrawals
+            .data
+            .into_iter()
+            .next()
+            .map(|withdrawal| withdrawal.wd_id)
+            .context("No withdrawal id received")
+    }
+
+    #[named]
+    pub(super) async fn request_withdrawal_history(
+        &self,
+        currency_code: CurrencyCode,
+        withdrawal_id: &str,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/asset/withdrawal-history");
+        builder.add_kv("ccy", self.get_currency_id(currency_code)?);
+        builder.add_kv("wdId", withdrawal_id);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("withdrawal {withdrawal_id}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    /// Withdrawal states: -2 - cancelled, -1 - failed, 2 - succeeded,
+    /// others are waiting for review or processing
+    pub(super) fn parse_withdrawal(
+        &self,
+        response: &RestResponse,
+        withdrawal_id: &str,
+    ) -> Result<ExternalTransferInfo> {
+        let withdrawals: OkxResponse<OkxWithdrawal> = serde_json::from_str(&response.content)
+            .context("Unable to parse OKX withdrawal history")?;
+
+        let withdrawal = withdrawals
+            .data
+            .into_iter()
+            .find(|withdrawal| withdrawal.wd_id == withdrawal_id)
+            .with_context(|| format!("Withdrawal {withdrawal_id} isn't found"))?;
+
+        let status = match withdrawal.state.as_str() {
+            "-2" | "-1" => ExternalTransferStatus::Failed,
+            "2" => ExternalTransferStatus::Completed,
+            _ => ExternalTransferStatus::Pending,
+        };
+
+        Ok(ExternalTransferInfo {
+            status,
+            tx_id: Some(withdrawal.tx_id).filter(|tx_id| !tx_id.is_empty()),
+        })
+    }
+
+    #[named]
+    pub(super) async fn request_deposit_history(
+        &self,
+        currency_code: CurrencyCode,
+        tx_id: &str,
+    ) -> Result<RestResponse, ExchangeError> {
+        let mut builder = UriBuilder::from_path("/api/v5/asset/deposit-history");
+        builder.add_kv("ccy", self.get_currency_id(currency_code)?);
+        builder.add_kv("txId", tx_id);
+        let uri = builder.build_uri(self.hosts.rest_uri_host(), true);
+
+        let log_args = format!("deposit {tx_id}");
+        self.rest_client.get(uri, function_name!(), log_args).await
+    }
+
+    /// Deposit states: 0 - waiting for confirmations, 1 - credited, 2 - succeeded,
+    /// 8 - delayed by suspended network, 11..14 - blocked by exchange and need manual handling
+    pub(super) fn parse_deposit(
+        &self,
+        response: &RestResponse,
+        tx_id: &str,
+    ) -> Result<Option<ExternalTransferInfo>> {
+        let deposits: OkxResponse<OkxDeposit> = serde_json::from_str(&response.content)
+            .context("Unable to parse OKX deposit history")?;
+
+        Ok(deposits
+            .data
+            .into_iter()
+            .find(|deposit| deposit.tx_id == tx_id)
+            .map(|deposit| {
+                let status = match deposit.state.as_str() {
+                    "1" | "2" => ExternalTransferStatus::Completed,
+                    "11" | "12" | "13" | "14" => ExternalTransferStatus::Failed,
+                    _ => ExternalTransferStatus::Pending,
+                };
+
+                ExternalTransferInfo {
+                    status,
+                    tx_id: Some(deposit.tx_id),
+                }
+            }))
+    }
+}
+
+pub struct OkxBuilder;
+
+impl ExchangeClientBuilder for OkxBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        events_channel: broadcast::Sender<ExchangeEvent>,
+        lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Okx::new(
+                exchange_settings,
+                events_channel,
+                lifetime_manager,
+            )),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::MyTrades),
+                OrderFeatures {
+                    maker_only: true,
+                    supports_get_order_info_by_client_order_id: true,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: true,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: true,
+                    // Stop orders are placed via separate algo orders API which isn't supported yet
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: true,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: true,
+                },
+                WebSocketOptions {
+                    execution_notification: true,
+                    cancellation_notification: true,
+                    supports_ping_pong: true,
+                    supports_subscription_response: true,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Okx".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_utils::cancellation_token::CancellationToken;
+
+    #[test]
+    fn generate_signature() {
+        let signature = Okx::create_signature(
+            "22582BD0CFF14C41EDBF1AB98506286D",
+            "2020-12-08T09:08:57.715Z",
+            "POST",
+            "/api/v5/trade/order",
+            br#"{"instId":"BTC-USDT","tdMode":"cash","side":"buy","ordType":"limit","sz":"0.01","px":"18000"}"#,
+        );
+
+        assert_eq!(signature, "uYoSTGgTX8O/FrHmKun7WKWymGS4RWbxG6mT7SDk0cs=");
+    }
+
+    #[test]
+    fn wallet_responses_are_parsed() {
+        let exchange_account_id = ExchangeAccountId::new("OKX", 0);
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, "".into(), "".into(), false);
+        let (tx, _) = broadcast::channel(10);
+        let okx = Okx::new(
+            settings,
+            tx,
+            AppLifetimeManager::new(CancellationToken::default()),
+        );
+        okx.supported_currencies.insert("EOS".into(), "eos".into());
+
+        let response = |content: &str| RestResponse::new(content.to_owned(), hyper::StatusCode::OK);
+
+        let chains = response(
+            r#"{"code":"0","msg":"","data":[
+                {"ccy":"EOS","chain":"EOS-EOS","canDep":true,"canWd":false},
+                {"ccy":"EOS","chain":"EOS-ERC20","canDep":false,"canWd":true}
+            ]}"#,
+        );
+        let network_status = okx
+            .parse_network_status(&chains, "eos".into(), "EOS")
+            .expect("in test");
+        assert_eq!(
+            network_status,
+            NetworkStatus {
+                is_deposit_enabled: true,
+                is_withdrawal_enabled: false,
+            }
+        );
+
+        let addresses = response(
+            r#"{"code":"0","msg":"","data":[
+                {"chain":"EOS-EOS","addr":"okbtothemoon","memo":"123","ccy":"EOS"},
+                {"chain":"EOS-ERC20","addr":"0xabc","ccy":"EOS"}
+            ]}"#,
+        );
+        let deposit_address = okx
+            .parse_deposit_address(&addresses, "eos".into(), "EOS")
+            .expect("in test");
+        assert_eq!(
+            deposit_address,
+            DepositAddress {
+                address: "okbtothemoon".to_owned(),
+                tag: Some("123".to_owned()),
+            }
+        );
+
+        let withdrawal_id = okx
+            .parse_withdrawal_id(&response(
+                r#"{"code":"0","msg":"","data":[{"ccy":"EOS","wdId":"67485","amt":"1"}]}"#,
+            ))
+            .expect("in test");
+        assert_eq!(withdrawal_id, "67485");
+
+        let withdrawals = response(
+            r#"{"code":"0","msg":"","data":[
+                {"wdId":"1","txId":"","state":"1"},
+                {"wdId":"2","txId":"0xdef","state":"2"},
+                {"wdId":"3","txId":"","state":"-2"}
+            ]}"#,
+        );
+        let withdrawal = |id| okx.parse_withdrawal(&withdrawals, id).expect("in test");
+        assert_eq!(
+            withdrawal("1"),
+            ExternalTransferInfo {
+                status: ExternalTransferStatus::Pending,
+                tx_id: None,
+            }
+        );
+        assert_eq!(
+            withdrawal("2"),
+            ExternalTransferInfo {
+                status: ExternalTransferStatus::Completed,
+                tx_id: Some("0xdef".to_owned()),
+            }
+        );
+        assert_eq!(withdrawal("3").status, ExternalTransferStatus::Failed);
+
+        let deposits = response(r#"{"code":"0","msg":"","data":[{"txId":"0xdef","state":"0"}]}"#);
+        let deposit = okx.parse_deposit(&deposits, "0xdef").expect("in test");
+        assert_eq!(
+            deposit.map(|x| x.status),
+            Some(ExternalTransferStatus::Pending)
+        );
+        let unknown_deposit = okx.parse_deposit(&deposits, "0x123").expect("in test");
+        assert_eq!(unknown_deposit, None);
+    }
+}
@@ -0,0 +1,488 @@
+use crate::okx::Okx;
+use crate::types::{OkxBookPayload, OkxOrderEvent, OkxTradePayload};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::common::send_event;
+use mmb_core::exchanges::general::handlers::handle_order_filled::{
+    FillAmount, FillEvent, SpecialOrderData,
+};
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::events::{EventSourceType, ExchangeEvent, Trade, TradeId};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order_book::event::{EventType, OrderBookEvent};
+use mmb_domain::order_book::order_book_data::OrderBookData;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::Any;
+use std::sync::Arc;
+use url::Url;
+
+#[async_trait]
+impl Support for Okx {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        if msg == "pong" {
+            return Ok(());
+        }
+
+        let message: WebsocketMessage = serde_json::from_str(msg)
+            .with_context(|| format!("Unable to parse websocket message:\n{msg}"))?;
+
+        match message {
+            WebsocketMessage::Event(event) => self.handle_event(event)?,
+            WebsocketMessage::Push(push) => self.handle_push(push)?,
+            WebsocketMessage::Unknown(_) => {
+                self.log_unknown_message(self.settings.exchange_account_id, msg)
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        let inst_ids = self
+            .traded_specific_currencies
+            .lock()
+            .iter()
+            .map(|currency_pair| currency_pair.to_string())
+            .collect::<Vec<_>>();
+        if !inst_ids.is_empty() {
+            let args = inst_ids
+                .iter()
+                .flat_map(|inst_id| {
+                    [Channel::Books5, Channel::Trades].map(|channel| SubscriptionArg {
+                        channel,
+                        inst_id: Some(inst_id),
+                        inst_type: None,
+                    })
+                })
+                .collect();
+            let subscribe = Request::subscribe(args);
+            (self.websocket_message_callback)(WebSocketRole::Secondary, subscribe)?;
+        }
+
+        if !self.is_websocket_enabled(WebSocketRole::Main) {
+            return Ok(());
+        }
+
+        // Private channels are subscribed after successful login
+        let timestamp = Utc::now().timestamp().to_string();
+        let login = Request::login(LoginArg {
+            api_key: &self.settings.api_key,
+            passphrase: &self.settings.passphrase,
+            sign: Okx::create_signature(
+                &self.settings.secret_key,
+                &timestamp,
+                "GET",
+                "/users/self/verify",
+                &[],
+            ),
+            timestamp,
+        });
+        (self.websocket_message_callback)(WebSocketRole::Main, login)
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, callback: SendWebsocketMessageCb) {
+        self.websocket_message_callback = callback;
+    }
+
+    fn set_order_created_callback(&mut self, callback: OrderCreatedCb) {
+        self.order_created_callback = callback;
+    }
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    fn set_handle_trade_callback(&mut self, callback: HandleTradeCb) {
+        self.handle_trade_callback = callback;
+    }
+
+    fn set_handle_metrics_callback(&mut self, callback: HandleMetricsCb) {
+        self.handle_metrics_callback = callback;
+    }
+
+    fn set_traded_specific_currencies(&self, currencies: Vec<SpecificCurrencyPair>) {
+        *self.traded_specific_currencies.lock() = currencies;
+    }
+
+    fn is_websocket_enabled(&self, role: WebSocketRole) -> bool {
+        match role {
+            WebSocketRole::Main => {
+                !self.settings.api_key.is_empty() && !self.settings.secret_key.is_empty()
+            }
+            WebSocketRole::Secondary => true,
+        }
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        let host = match role {
+            WebSocketRole::Main => self.hosts.web_socket_host,
+            WebSocketRole::Secondary => self.hosts.web_socket2_host,
+        };
+
+        Url::parse(host).with_context(|| format!("Unable parse websocket {role:?} uri"))
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, message: &str) -> bool {
+        message.contains(r#""channel":"orders""#)
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+impl Okx {
+    fn handle_event(&self, event: EventMessage) -> Result<()> {
+        match event.event.as_str() {
+            "login" if event.code.as_deref() == Some("0") => {
+                let subscribe = Request::subscribe(vec![SubscriptionArg {
+                    channel: Channel::Orders,
+                    inst_id: None,
+                    inst_type: Some(self.get_instrument_type()),
+                }]);
+                (self.websocket_message_callback)(WebSocketRole::Main, subscribe)
+            }
+            "login" | "error" => {
+                let err = format!(
+                    "OKX websocket: {} event with code {:?}: {:?}",
+                    event.event, event.code, event.msg
+                );
+                log::error!("{err}");
+                bail!(err)
+            }
+            _ => {
+                log::info!(
+                    "OKX websocket: {} event received: {:?}",
+                    event.event,
+                    event.arg
+                );
+
+                Ok(())
+            }
+        }
+    }
+
+    fn handle_push(&self, push: PushMessage) -> Result<()> {
+        match push.arg.channel {
+            Channel::Books5 => {
+                for book in serde_json::from_value::<Vec<OkxBookPayload>>(push.data)? {
+                    self.handle_order_book(book)?;
+                }
+            }
+            Channel::Trades => {
+                for trade in serde_json::from_value::<Vec<OkxTradePayload>>(push.data)? {
+                    self.handle_trade(trade)?;
+                }
+            }
+            Channel::Orders => {
+                for order_event in serde_json::from_value::<Vec<OkxOrderEvent>>(push.data)? {
+                    self.handle_order_event(order_event)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_book(&self, book: OkxBookPayload) -> Result<()> {
+        let specific_currency_pair = book.inst_id.as_str().into();
+        let currency_pair = self.get_unified_currency_pair(&specific_currency_pair)?;
+
+        let mut order_book_data = OrderBookData::default();
+        for level in book.bids {
+            let amount = self.from_contracts(&specific_currency_pair, level.1);
+            order_book_data.bids.insert(level.0, amount);
+        }
+        for level in book.asks {
+            let amount = self.from_contracts(&specific_currency_pair, level.1);
+            order_book_data.asks.insert(level.0, amount);
+        }
+
+        // Channel books5 always sends full snapshot of 5 levels
+        let order_book_event = OrderBookEvent::new(
+            Utc::now(),
+            self.settings.exchange_account_id,
+            currency_pair,
+            String::default(),
+            EventType::Snapshot,
+            Arc::new(order_book_data),
+        );
+
+        send_event(
+            &self.events_channel,
+            self.lifetime_manager.clone(),
+            self.settings.exchange_account_id,
+            ExchangeEvent::OrderBookEvent(order_book_event),
+        )
+    }
+
+    fn handle_trade(&self, trade: OkxTradePayload) -> Result<()> {
+        let specific_currency_pair = trade.inst_id.as_str().into();
+        (self.handle_trade_callback)(
+            self.get_unified_currency_pair(&specific_currency_pair)?,
+            Trade {
+                trade_id: TradeId::from(trade.trade_id),
+                price: trade.px,
+                quantity: self.from_contracts(&specific_currency_pair, trade.sz),
+                side: trade.side,
+                transaction_time: trade.ts,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn handle_order_event(&self, order_event: OkxOrderEvent) -> Result<()> {
+        match order_event.state.as_str() {
+            "live" => (self.order_created_callback)(
+                order_event.cl_ord_id,
+                order_event.ord_id,
+                EventSourceType::WebSocket,
+            ),
+            "canceled" | "mmp_canceled" => (self.order_cancelled_callback)(
+                order_event.cl_ord_id,
+                order_event.ord_id,
+                EventSourceType::WebSocket,
+            ),
+            "partially_filled" | "filled" => self.handle_order_fill(order_event)?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn handle_order_fill(&self, order_event: OkxOrderEvent) -> Result<()> {
+        let fill_amount = order_event.fill_sz.unwrap_or_default();
+        if order_event.trade_id.is_empty() || fill_amount.is_zero() {
+            // Order was updated without a new trade
+            return Ok(());
+        }
+
+        let specific_currency_pair = order_event.inst_id.as_str().into();
+        let commission_currency_code = match order_event.fill_fee_ccy.is_empty() {
+            true => None,
+            false => Some(
+                self.currency_aliases
+                    .unify(order_event.fill_fee_ccy.as_str().into()),
+            ),
+        };
+
+        let fill_event = FillEvent {
+            source_type: EventSourceType::WebSocket,
+            trade_id: Some(TradeId::from(order_event.trade_id)),
+            client_order_id: Some(order_event.cl_ord_id),
+            exchange_order_id: order_event.ord_id,
+            fill_price: order_event
+                .fill_px
+                .context("No fillPx in OKX order fill event")?,
+            fill_amount: FillAmount::Incremental {
+                fill_amount: self.from_contracts(&specific_currency_pair, fill_amount),
+                total_filled_amount: order_event
+                    .acc_fill_sz
+                    .map(|amount| self.from_contracts(&specific_currency_pair, amount)),
+            },
+            order_role: Okx::get_order_role(&order_event.exec_type),
+            commission_currency_code,
+            commission_rate: None,
+            // Negative fee means charged commission
+            commission_amount: order_event.fill_fee.map(|fee| -fee),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: Some(SpecialOrderData {
+                currency_pair: self.get_unified_currency_pair(&specific_currency_pair)?,
+                order_side: order_event.side,
+                order_amount: self.from_contracts(&specific_currency_pair, order_event.sz),
+            }),
+            fill_date: order_event.fill_time,
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum WebsocketMessage {
+    Event(EventMessage),
+    Push(PushMessage),
+    Unknown(Value),
+}
+
+/// Response on operation sent via websocket, e.g. login or subscription
+#[derive(Deserialize, Debug)]
+struct EventMessage {
+    event: String,
+    code: Option<String>,
+    msg: Option<String>,
+    arg: Option<Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PushArg {
+    channel: Channel,
+}
+
+/// Data pushed by subscribed channel
+#[derive(Deserialize, Debug)]
+struct PushMessage {
+    arg: PushArg,
+    data: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Channel {
+    Books5,
+    Trades,
+    Orders,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionArg<'a> {
+    channel: Channel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inst_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inst_type: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginArg<'a> {
+    api_key: &'a str,
+    passphrase: &'a str,
+    timestamp: String,
+    sign: String,
+}
+
+#[derive(Serialize)]
+struct Request<T> {
+    op: &'static str,
+    args: Vec<T>,
+}
+
+impl<T: Serialize> Request<T> {
+    fn to_message(op: &'static str, args: Vec<T>) -> String {
+        serde_json::to_string(&Request { op, args })
+            .expect("Failed to serialize OKX websocket message")
+    }
+}
+
+impl<'a> Request<SubscriptionArg<'a>> {
+    fn subscribe(args: Vec<SubscriptionArg<'a>>) -> String {
+        Self::to_message("subscribe", args)
+    }
+}
+
+impl<'a> Request<LoginArg<'a>> {
+    fn login(arg: LoginArg<'a>) -> String {
+        Self::to_message("login", vec![arg])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn serialize_subscription() {
+        let message = Request::subscribe(vec![
+            SubscriptionArg {
+                channel: Channel::Books5,
+                inst_id: Some("BTC-USDT"),
+                inst_type: None,
+            },
+            SubscriptionArg {
+                channel: Channel::Orders,
+                inst_id: None,
+                inst_type: Some("SPOT"),
+            },
+        ]);
+
+        assert_eq!(
+            message,
+            r#"{"op":"subscribe","args":[{"channel":"books5","instId":"BTC-USDT"},{"channel":"orders","instType":"SPOT"}]}"#
+        );
+    }
+
+    #[test]
+    fn parse_order_fill_event() {
+        let msg = r#"{"arg":{"channel":"orders","instType":"SPOT","uid":"77982378738415879"},"data":[{"instType":"SPOT","instId":"BTC-USDT","ordId":"312269865356374016","clOrdId":"1234567","side":"buy","sz":"0.001","px":"46000","state":"partially_filled","tradeId":"242589207","fillPx":"46000","fillSz":"0.0005","accFillSz":"0.0005","fillFee":"-0.0000005","fillFeeCcy":"BTC","execType":"M","fillTime":"1597026383085","uTime":"1597026383085"}]}"#;
+
+        let message: WebsocketMessage = serde_json::from_str(msg).expect("in test");
+        let WebsocketMessage::Push(push) = message else {
+            panic!("Unexpected message {message:?}");
+        };
+        assert_eq!(push.arg.channel, Channel::Orders);
+
+        let order_events: Vec<OkxOrderEvent> = serde_json::from_value(push.data).expect("in test");
+        let order_event = &order_events[0];
+        assert_eq!(order_event.ord_id.as_str(), "312269865356374016");
+        assert_eq!(order_event.fill_sz, Some(dec!(0.0005)));
+        assert_eq!(order_event.fill_fee, Some(dec!(-0.0000005)));
+        assert_eq!(order_event.exec_type, "M");
+    }
+
+    #[test]
+    fn parse_events() {
+        let login: WebsocketMessage =
+            serde_json::from_str(r#"{"event":"login","code":"0","msg":""}"#).expect("in test");
+        assert!(matches!(
+            login,
+            WebsocketMessage::Event(EventMessage { ref event, .. }) if event == "login"
+        ));
+
+        let book: WebsocketMessage = serde_json::from_str(
+            r#"{"arg":{"channel":"books5","instId":"BTC-USDT"},"data":[{"asks":[["8476.98","415","0","13"]],"bids":[["8476.97","256","0","12"]],"instId":"BTC-USDT","ts":"1597026383085"}]}"#,
+        )
+        .expect("in test");
+        let WebsocketMessage::Push(push) = book else {
+            panic!("Unexpected message {book:?}");
+        };
+        let books: Vec<OkxBookPayload> = serde_json::from_value(push.data).expect("in test");
+        assert_eq!(books[0].asks[0].0, dec!(8476.98));
+        assert_eq!(books[0].bids[0].1, dec!(256));
+    }
+}
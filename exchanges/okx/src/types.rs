@@ -0,0 +1,354 @@
+use chrono::{TimeZone, Utc};
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, ExchangeOrderId, OrderSide, Price};
+use mmb_utils::DateTime;
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// Common envelope of OKX REST responses. Errors are checked in `ErrorHandlerOkx`
+/// so only the data part is needed here
+#[derive(Deserialize, Debug)]
+pub(crate) struct OkxResponse<T> {
+    pub(crate) data: Vec<T>,
+}
+
+/// OKX instrument description from `/api/v5/public/instruments`
+/// {
+///   "instType": "SWAP",
+///   "instId": "BTC-USDT-SWAP",
+///   "uly": "BTC-USDT",
+///   "baseCcy": "", // only for SPOT
+///   "quoteCcy": "", // only for SPOT
+///   "settleCcy": "USDT", // only for SWAP
+///   "ctVal": "0.01", // contract value, only for SWAP
+///   "ctValCcy": "BTC", // contract value currency, only for SWAP
+///   "ctType": "linear", // linear or inverse, only for SWAP
+///   "tickSz": "0.1",
+///   "lotSz": "1",
+///   "minSz": "1",
+///   "maxLmtSz": "100000000",
+///   "state": "live"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxInstrument {
+    pub(crate) inst_id: String,
+    #[serde(default)]
+    pub(crate) uly: String,
+    #[serde(default)]
+    pub(crate) base_ccy: String,
+    #[serde(default)]
+    pub(crate) quote_ccy: String,
+    #[serde(default)]
+    pub(crate) settle_ccy: String,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) ct_val: Option<Decimal>,
+    #[serde(default)]
+    pub(crate) ct_type: String,
+    #[serde(rename = "tickSz")]
+    pub(crate) price_tick: Price,
+    #[serde(rename = "lotSz")]
+    pub(crate) amount_tick: Amount,
+    #[serde(
+        rename = "minSz",
+        default,
+        deserialize_with = "deserialize_optional_decimal"
+    )]
+    pub(crate) min_amount: Option<Amount>,
+    #[serde(
+        rename = "maxLmtSz",
+        default,
+        deserialize_with = "deserialize_optional_decimal"
+    )]
+    pub(crate) max_amount: Option<Amount>,
+    pub(crate) state: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxPlaceOrderRequest<'a> {
+    pub(crate) inst_id: &'a str,
+    pub(crate) td_mode: &'static str,
+    pub(crate) cl_ord_id: &'a str,
+    pub(crate) side: &'static str,
+    pub(crate) ord_type: &'static str,
+    pub(crate) sz: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) px: Option<Price>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tgt_ccy: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reduce_only: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxCancelOrderRequest<'a> {
+    pub(crate) inst_id: &'a str,
+    pub(crate) ord_id: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxOrderId {
+    pub(crate) ord_id: ExchangeOrderId,
+}
+
+/// OKX order from `/api/v5/trade/order` and `/api/v5/trade/orders-pending`
+/// Amounts are specified in contracts for SWAP instruments
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxOrderInfo {
+    pub(crate) inst_id: String,
+    pub(crate) ord_id: ExchangeOrderId,
+    pub(crate) cl_ord_id: ClientOrderId,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) state: String,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) px: Option<Price>,
+    pub(crate) sz: Amount,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) avg_px: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) acc_fill_sz: Option<Amount>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) fee: Option<Amount>,
+    #[serde(default)]
+    pub(crate) fee_ccy: String,
+}
+
+/// OKX fill from `/api/v5/trade/fills`
+/// Negative fee means charged commission, positive fee means rebate
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxFill {
+    pub(crate) inst_id: String,
+    pub(crate) trade_id: String,
+    pub(crate) ord_id: ExchangeOrderId,
+    pub(crate) fill_px: Price,
+    pub(crate) fill_sz: Amount,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    /// "T" for taker and "M" for maker
+    pub(crate) exec_type: String,
+    pub(crate) fee: Amount,
+    pub(crate) fee_ccy: String,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) ts: DateTime,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct OkxBalance {
+    pub(crate) details: Vec<OkxBalanceDetails>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxBalanceDetails {
+    pub(crate) ccy: String,
+    pub(crate) cash_bal: Decimal,
+}
+
+/// Position in net mode, so `pos` is negative for short positions
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxPosition {
+    pub(crate) inst_id: String,
+    #[serde(deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) pos: Option<Amount>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) avg_px: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) liq_px: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) lever: Option<Decimal>,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) u_time: DateTime,
+}
+
+/// Network of currency from `/api/v5/asset/currencies`, `chain` has format "USDT-ERC20"
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxCurrencyChain {
+    pub(crate) chain: String,
+    pub(crate) can_dep: bool,
+    pub(crate) can_wd: bool,
+}
+
+/// Deposit address from `/api/v5/asset/deposit-address`
+/// Memo required by some networks is returned as `tag` or `memo` depending on currency
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxDepositAddress {
+    pub(crate) chain: String,
+    pub(crate) addr: String,
+    #[serde(default)]
+    pub(crate) tag: String,
+    #[serde(default)]
+    pub(crate) memo: String,
+}
+
+/// On-chain withdrawal request for `/api/v5/asset/withdrawal`
+/// Memo is specified in `to_addr` in format "address:memo"
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxWithdrawalRequest<'a> {
+    pub(crate) ccy: &'a str,
+    pub(crate) amt: Amount,
+    pub(crate) dest: &'static str,
+    pub(crate) to_addr: String,
+    pub(crate) chain: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxWithdrawalId {
+    pub(crate) wd_id: String,
+}
+
+/// Withdrawal from `/api/v5/asset/withdrawal-history`, `tx_id` is empty till it's broadcasted
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxWithdrawal {
+    pub(crate) wd_id: String,
+    #[serde(default)]
+    pub(crate) tx_id: String,
+    pub(crate) state: String,
+}
+
+/// Deposit from `/api/v5/asset/deposit-history`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxDeposit {
+    pub(crate) tx_id: String,
+    pub(crate) state: String,
+}
+
+/// Order event of private websocket `orders` channel
+/// {
+///   "instId": "BTC-USDT",
+///   "ordId": "312269865356374016",
+///   "clOrdId": "1234567",
+///   "side": "buy",
+///   "sz": "0.001",
+///   "state": "partially_filled",
+///   "tradeId": "242589207",
+///   "fillPx": "46000",
+///   "fillSz": "0.0005",
+///   "accFillSz": "0.0005",
+///   "fillFee": "-0.0000005",
+///   "fillFeeCcy": "BTC",
+///   "execType": "M",
+///   "fillTime": "1597026383085",
+///   "uTime": "1597026383085"
+/// }
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxOrderEvent {
+    pub(crate) inst_id: String,
+    pub(crate) ord_id: ExchangeOrderId,
+    pub(crate) cl_ord_id: ClientOrderId,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    pub(crate) sz: Amount,
+    pub(crate) state: String,
+    #[serde(default)]
+    pub(crate) trade_id: String,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) fill_px: Option<Price>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) fill_sz: Option<Amount>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) acc_fill_sz: Option<Amount>,
+    #[serde(default, deserialize_with = "deserialize_optional_decimal")]
+    pub(crate) fill_fee: Option<Amount>,
+    #[serde(default)]
+    pub(crate) fill_fee_ccy: String,
+    #[serde(default)]
+    pub(crate) exec_type: String,
+    #[serde(default, deserialize_with = "deserialize_optional_millis")]
+    pub(crate) fill_time: Option<DateTime>,
+}
+
+fn deserialize_side<'de, D>(deserializer: D) -> Result<OrderSide, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let side = String::deserialize(deserializer)?;
+    match side.as_str() {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        _ => Err(de::Error::custom(format!("Unknown OKX order side: {side}"))),
+    }
+}
+
+/// OKX returns empty string instead of null for missing numeric values
+fn deserialize_optional_decimal<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(None),
+        false => value.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+fn parse_millis<E: de::Error>(value: &str) -> Result<DateTime, E> {
+    let millis: i64 = value.parse().map_err(de::Error::custom)?;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| de::Error::custom(format!("Invalid OKX time: {value}")))
+}
+
+/// OKX returns time as unix timestamp in milliseconds in string
+fn deserialize_millis<'de, D>(deserializer: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse_millis(&String::deserialize(deserializer)?)
+}
+
+fn deserialize_optional_millis<'de, D>(deserializer: D) -> Result<Option<DateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.is_empty() {
+        true => Ok(None),
+        false => parse_millis(&value).map(Some),
+    }
+}
+
+/// Order book level of public websocket `books5` channel: [price, size, deprecated, orders count]
+/// Size is specified in contracts for SWAP instruments
+#[derive(Deserialize, Debug)]
+pub(crate) struct OkxBookLevel(
+    pub(crate) Price,
+    pub(crate) Amount,
+    de::IgnoredAny,
+    de::IgnoredAny,
+);
+
+/// Order book snapshot of public websocket `books5` channel
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxBookPayload {
+    pub(crate) inst_id: String,
+    pub(crate) asks: Vec<OkxBookLevel>,
+    pub(crate) bids: Vec<OkxBookLevel>,
+}
+
+/// Trade of public websocket `trades` channel
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OkxTradePayload {
+    pub(crate) inst_id: String,
+    pub(crate) trade_id: String,
+    pub(crate) px: Price,
+    pub(crate) sz: Amount,
+    #[serde(deserialize_with = "deserialize_side")]
+    pub(crate) side: OrderSide,
+    #[serde(deserialize_with = "deserialize_millis")]
+    pub(crate) ts: DateTime,
+}
@@ -159,7 +159,9 @@ impl ExchangeClient for Serum {
         _symbol: &Symbol,
         _last_date_time: Option<DateTime>,
     ) -> RequestResult<Vec<OrderTrade>> {
-        todo!()
+        RequestResult::Error(ExchangeError::unknown(
+            "get_my_trades isn't supported on Serum",
+        ))
     }
 
     async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
@@ -57,7 +57,7 @@ use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTime
 use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
 use mmb_core::exchanges::traits::{
     ExchangeClient, ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError,
-    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb, Support,
 };
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
 use mmb_core::settings::ExchangeSettings;
@@ -169,6 +169,15 @@ impl Serum {
     ) -> Self {
         let payer = Keypair::from_base58_string(&settings.secret_key);
         let exchange_account_id = settings.exchange_account_id;
+        let rest_client = RestClient::with_network_settings(
+            ErrorHandlerData::new(
+                empty_response_is_ok,
+                exchange_account_id,
+                ErrorHandlerEmpty::default(),
+            ),
+            RestHeadersEmpty::default(),
+            &settings.network,
+        );
 
         Self {
             id,
@@ -182,14 +191,7 @@ impl Serum {
             unified_to_specific: Default::default(),
             supported_currencies: Default::default(),
             traded_specific_currencies: Default::default(),
-            rest_client: RestClient::new(
-                ErrorHandlerData::new(
-                    empty_response_is_ok,
-                    exchange_account_id,
-                    ErrorHandlerEmpty::default(),
-                ),
-                RestHeadersEmpty::default(),
-            ),
+            rest_client,
             rpc_client: Arc::new(SolanaClient::new(&network_type)),
             markets_data: Default::default(),
             network_type,
@@ -874,8 +876,9 @@ impl Serum {
             market_name.rsplit_once('/').with_context(|| {
                 format!("Unable to get currency pair from market name {market_name}")
             })?;
-        let base_currency_code = base_currency_id.into();
-        let quote_currency_code = quote_currency_id.into();
+        let currency_aliases = self.get_currency_aliases();
+        let base_currency_code = currency_aliases.unify(base_currency_id.into());
+        let quote_currency_code = currency_aliases.unify(quote_currency_id.into());
 
         let is_derivative = false;
 
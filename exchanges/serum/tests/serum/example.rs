@@ -79,7 +79,9 @@ async fn example() {
             ctx.clone(),
         );
 
-        engine.start_disposition_executor(strategy);
+        engine
+            .start_disposition_executor(strategy)
+            .expect("Failed to start disposition executor");
 
         match engine.run().await {
             ActionAfterGracefulShutdown::Nothing => break,
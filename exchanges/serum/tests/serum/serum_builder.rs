@@ -19,7 +19,7 @@ use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
 use mmb_core::exchanges::traits::{ExchangeClientBuilder, ExchangeClientBuilderResult};
 use mmb_core::infrastructure::init_lifetime_manager;
 use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
-use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings};
+use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings, OperationPoliciesSettings};
 use mmb_domain::events::{AllowedEventSourceType, ExchangeEvent};
 use mmb_domain::exchanges::commission::Commission;
 use mmb_domain::market::{ExchangeAccountId, ExchangeId};
@@ -136,6 +136,7 @@ impl SerumBuilder {
             Arc::downgrade(&exchange_blocker),
             commission,
             event_recorder,
+            OperationPoliciesSettings::default(),
         );
         exchange.connect_ws().await?;
         exchange.build_symbols(&settings.currency_pairs).await;
@@ -0,0 +1,110 @@
+use crate::uniswap::Uniswap;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use mmb_core::exchanges::general::exchange::RequestResult;
+use mmb_core::exchanges::general::order::cancel::CancelOrderResult;
+use mmb_core::exchanges::general::order::create::CreateOrderResult;
+use mmb_core::exchanges::general::order::get_order_trades::OrderTrade;
+use mmb_core::exchanges::traits::{ExchangeClient, ExchangeError};
+use mmb_domain::events::{EventSourceType, ExchangeBalancesAndPositions};
+use mmb_domain::exchanges::symbol::Symbol;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::pool::OrderRef;
+use mmb_domain::order::snapshot::{ExchangeOrderId, OrderInfo, Price};
+use mmb_domain::position::{ActivePosition, ClosedPosition};
+use mmb_utils::DateTime;
+use std::sync::Arc;
+
+#[async_trait]
+impl ExchangeClient for Uniswap {
+    async fn create_order(&self, order: &OrderRef) -> CreateOrderResult {
+        // Hash of swap transaction is known right after node accepts it
+        match self.do_create_order(order).await {
+            Ok(order_id) => CreateOrderResult::succeed(&order_id, EventSourceType::Rest),
+            Err(err) => CreateOrderResult::failed(err, EventSourceType::Rest),
+        }
+    }
+
+    async fn cancel_order(
+        &self,
+        _order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> CancelOrderResult {
+        let error = self.cancellation_error(exchange_order_id);
+        CancelOrderResult::failed(error, EventSourceType::Rest)
+    }
+
+    /// Swaps don't rest in order book, so there is nothing to cancel
+    async fn cancel_all_orders(&self, _currency_pair: CurrencyPair) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_open_orders(&self) -> Result<Vec<OrderInfo>> {
+        Ok(self.get_pending_orders(None))
+    }
+
+    async fn get_open_orders_by_currency_pair(
+        &self,
+        currency_pair: CurrencyPair,
+    ) -> Result<Vec<OrderInfo>> {
+        Ok(self.get_pending_orders(Some(currency_pair)))
+    }
+
+    async fn get_order_info(&self, order: &OrderRef) -> Result<OrderInfo, ExchangeError> {
+        let exchange_order_id = order.exchange_order_id().ok_or_else(|| {
+            ExchangeError::unknown("Swap transaction of order wasn't sent to node")
+        })?;
+
+        match self.request_receipt(&exchange_order_id).await {
+            Ok(receipt) => self
+                .parse_order_info(order, &exchange_order_id, receipt)
+                .map_err(|err| {
+                    ExchangeError::parsing(format!("Unable to parse order info: {err:?}"))
+                }),
+            Err(error) => Err(ExchangeError::unknown(
+                format!("Failed to get order info: {:?}", error).as_str(),
+            )),
+        }
+    }
+
+    async fn close_position(
+        &self,
+        _position: &ActivePosition,
+        _price: Option<Price>,
+    ) -> Result<ClosedPosition> {
+        bail!("Uniswap doesn't support derivatives")
+    }
+
+    async fn get_active_positions(&self) -> Result<Vec<ActivePosition>> {
+        // There are no positions on Uniswap
+        Ok(Vec::new())
+    }
+
+    async fn get_balance_and_positions(&self) -> Result<ExchangeBalancesAndPositions> {
+        Ok(ExchangeBalancesAndPositions {
+            balances: self.get_balances().await?,
+            positions: None,
+        })
+    }
+
+    /// Fills are taken from receipts of swap transactions
+    async fn get_my_trades(
+        &self,
+        _symbol: &Symbol,
+        _last_date_time: Option<DateTime>,
+    ) -> RequestResult<Vec<OrderTrade>> {
+        RequestResult::Success(Vec::new())
+    }
+
+    async fn build_all_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        match self.build_symbols().await {
+            Ok(symbols) => Ok(symbols),
+            Err(error) => bail!("Failed to build Uniswap pools: {error:?}"),
+        }
+    }
+
+    /// There is no server time on DEX
+    async fn get_server_time(&self) -> Option<Result<i64>> {
+        None
+    }
+}
@@ -0,0 +1,126 @@
+use crate::uniswap::Uniswap;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use mmb_core::connectivity::WebSocketRole;
+use mmb_core::exchanges::general::exchange::Exchange;
+use mmb_core::exchanges::traits::{
+    HandleMetricsCb, HandleOrderFilledCb, HandleTradeCb, OrderCancelledCb, OrderCreatedCb,
+    SendWebsocketMessageCb, Support,
+};
+use mmb_core::infrastructure::spawn_by_timer;
+use mmb_core::settings::ExchangeSettings;
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, SpecificCurrencyPair,
+};
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// Block time of Ethereum is 12 seconds, so receipts are checked a few times per block
+const CHECK_SWAPS_PERIOD: Duration = Duration::from_secs(3);
+
+#[async_trait]
+impl Support for Uniswap {
+    fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+        self
+    }
+
+    async fn initialized(&self, exchange: Arc<Exchange>) {
+        start_checking_pending_swaps(&exchange);
+    }
+
+    fn on_websocket_message(&self, msg: &str) -> Result<()> {
+        self.log_unknown_message(self.settings.exchange_account_id, msg);
+        Ok(())
+    }
+
+    fn on_connecting(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_connected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_disconnected(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_send_websocket_message_callback(&mut self, _callback: SendWebsocketMessageCb) {}
+
+    /// Swap is created when node accepts transaction, so creation is known from REST response
+    fn set_order_created_callback(&mut self, _callback: OrderCreatedCb) {}
+
+    fn set_order_cancelled_callback(&mut self, callback: OrderCancelledCb) {
+        self.order_cancelled_callback = callback;
+    }
+
+    fn set_handle_order_filled_callback(&mut self, callback: HandleOrderFilledCb) {
+        self.handle_order_filled_callback = callback;
+    }
+
+    /// There is no market data stream, prices are requested from quoter contract
+    fn set_handle_trade_callback(&mut self, _callback: HandleTradeCb) {}
+
+    fn set_handle_metrics_callback(&mut self, _callback: HandleMetricsCb) {}
+
+    fn set_traded_specific_currencies(&self, _currencies: Vec<SpecificCurrencyPair>) {}
+
+    fn is_websocket_enabled(&self, _role: WebSocketRole) -> bool {
+        false
+    }
+
+    async fn create_ws_url(&self, role: WebSocketRole) -> Result<Url> {
+        bail!("Uniswap doesn't have {role:?} websocket")
+    }
+
+    fn get_specific_currency_pair(&self, currency_pair: CurrencyPair) -> SpecificCurrencyPair {
+        self.unified_to_specific.read()[&currency_pair]
+    }
+
+    fn get_supported_currencies(&self) -> &DashMap<CurrencyId, CurrencyCode> {
+        &self.supported_currencies
+    }
+
+    fn should_log_message(&self, _message: &str) -> bool {
+        false
+    }
+
+    fn get_settings(&self) -> &ExchangeSettings {
+        &self.settings
+    }
+
+    fn get_currency_aliases(&self) -> CurrencyAliases {
+        self.currency_aliases.clone()
+    }
+}
+
+fn start_checking_pending_swaps(exchange: &Arc<Exchange>) {
+    let exchange_wk = Arc::downgrade(exchange);
+    spawn_by_timer(
+        "Check Uniswap swaps",
+        CHECK_SWAPS_PERIOD,
+        CHECK_SWAPS_PERIOD,
+        SpawnFutureFlags::STOP_BY_TOKEN | SpawnFutureFlags::DENY_CANCELLATION,
+        move || {
+            let exchange_wk = exchange_wk.clone();
+            async move {
+                let exchange = match exchange_wk.upgrade() {
+                    None => return,
+                    Some(v) => v,
+                };
+
+                exchange
+                    .exchange_client
+                    .as_any()
+                    .downcast_ref::<Uniswap>()
+                    .expect("received non Uniswap exchange client in method of checking swaps")
+                    .check_pending_swaps()
+                    .await;
+            }
+        },
+    );
+}
@@ -0,0 +1,167 @@
+use anyhow::{bail, Context, Result};
+use ethers_core::abi::{decode, ParamType, Token};
+use ethers_core::types::{Address, Bytes, Log, H256, U256};
+use ethers_core::utils::keccak256;
+use mmb_domain::market::CurrencyPair;
+use mmb_domain::order::snapshot::{Amount, ClientOrderId, OrderSide, Price};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::Deserialize;
+
+/// Pool is configured by specific currency pair `{base_token}/{quote_token}/{fee}`,
+/// e.g. `0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2/0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48/500`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PoolId {
+    pub(crate) base_token: Address,
+    pub(crate) quote_token: Address,
+    /// Fee tier in hundredths of a bip, e.g. 500 for 0.05%
+    pub(crate) fee: u32,
+}
+
+impl PoolId {
+    pub(crate) fn parse(specific_currency_pair: &str) -> Result<Self> {
+        let parts: Vec<&str> = specific_currency_pair.split('/').collect();
+        let [base_token, quote_token, fee] = parts[..] else {
+            bail!("Uniswap pool should be specified as `{{base_token}}/{{quote_token}}/{{fee}}`, but got {specific_currency_pair}");
+        };
+
+        Ok(PoolId {
+            base_token: base_token
+                .parse()
+                .with_context(|| format!("Invalid base token address {base_token}"))?,
+            quote_token: quote_token
+                .parse()
+                .with_context(|| format!("Invalid quote token address {quote_token}"))?,
+            fee: fee
+                .parse()
+                .with_context(|| format!("Invalid pool fee {fee}"))?,
+        })
+    }
+}
+
+/// Pool with decimals of its tokens requested from token contracts
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct UniswapPool {
+    pub(crate) id: PoolId,
+    pub(crate) base_decimals: u32,
+    pub(crate) quote_decimals: u32,
+}
+
+impl UniswapPool {
+    /// Tokens in order of swap: (token_in, token_out)
+    pub(crate) fn swap_tokens(&self, side: OrderSide) -> (Address, Address) {
+        match side {
+            OrderSide::Buy => (self.id.quote_token, self.id.base_token),
+            OrderSide::Sell => (self.id.base_token, self.id.quote_token),
+        }
+    }
+}
+
+/// Swap transaction which is sent to node, but isn't mined yet
+#[derive(Debug, Clone)]
+pub(crate) struct PendingSwap {
+    pub(crate) client_order_id: ClientOrderId,
+    pub(crate) currency_pair: CurrencyPair,
+    pub(crate) side: OrderSide,
+    pub(crate) amount: Amount,
+    /// Worst price accepted by transaction
+    pub(crate) price: Price,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct JsonRpcResponse<T> {
+    pub(crate) result: T,
+}
+
+/// Receipt of mined transaction from `eth_getTransactionReceipt`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionReceipt {
+    /// 1 for success and 0 for reverted transaction
+    pub(crate) status: U256,
+    pub(crate) gas_used: U256,
+    pub(crate) effective_gas_price: U256,
+    pub(crate) logs: Vec<Log>,
+}
+
+impl TransactionReceipt {
+    pub(crate) fn is_succeed(&self) -> bool {
+        self.status == U256::one()
+    }
+
+    /// Paid gas in native currency
+    pub(crate) fn gas_fee(&self) -> Result<Decimal> {
+        from_token_units(self.gas_used * self.effective_gas_price, NATIVE_DECIMALS)
+    }
+
+    /// Sum of ERC20 transfers of `token` from `from` address to `to` address
+    pub(crate) fn transferred(
+        &self,
+        token: Address,
+        from: Option<Address>,
+        to: Option<Address>,
+    ) -> U256 {
+        let transfer_topic = H256::from(keccak256(b"Transfer(address,address,uint256)"));
+        let matches = |topic: Option<&H256>, address: Option<Address>| match address {
+            Some(address) => topic.map_or(false, |topic| Address::from(*topic) == address),
+            None => true,
+        };
+
+        self.logs
+            .iter()
+            .filter(|log| log.address == token && log.topics.first() == Some(&transfer_topic))
+            .filter(|log| matches(log.topics.get(1), from) && matches(log.topics.get(2), to))
+            .fold(U256::zero(), |sum, log| {
+                sum + U256::from_big_endian(&log.data)
+            })
+    }
+}
+
+/// Decimals of ETH
+pub(crate) const NATIVE_DECIMALS: u32 = 18;
+
+/// Amount in smallest units of token: `amount * 10^decimals`
+pub(crate) fn to_token_units(amount: Decimal, decimals: u32) -> Result<U256> {
+    let units = amount
+        .checked_mul(Decimal::TEN.powi(decimals.into()))
+        .with_context(|| format!("Amount {amount} is too big for token units"))?
+        .trunc()
+        .to_u128()
+        .with_context(|| format!("Invalid token amount {amount}"))?;
+
+    Ok(U256::from(units))
+}
+
+pub(crate) fn from_token_units(units: U256, decimals: u32) -> Result<Decimal> {
+    // Mantissa of decimal is 96 bits
+    if units.bits() > 96 {
+        bail!("Token amount {units} is too big for decimal");
+    }
+
+    Decimal::try_from_i128_with_scale(units.as_u128() as i128, decimals)
+        .with_context(|| format!("Unable to convert token amount {units} with {decimals} decimals"))
+}
+
+/// Result of `eth_call` is ABI encoded return value of contract function
+pub(crate) fn parse_u256(result: &Bytes) -> Result<U256> {
+    match result.len() < 32 {
+        true => bail!("Unexpected length of uint256 result: {result}"),
+        false => Ok(U256::from_big_endian(&result[..32])),
+    }
+}
+
+/// Token symbol is ABI encoded string, but some old tokens (e.g. MKR) return `bytes32`
+pub(crate) fn parse_symbol(result: &Bytes) -> Result<String> {
+    if let Ok(tokens) = decode(&[ParamType::String], result) {
+        if let Some(Token::String(symbol)) = tokens.into_iter().next() {
+            return Ok(symbol);
+        }
+    }
+
+    let bytes = result
+        .get(..32)
+        .context("Unexpected length of token symbol")?;
+    let symbol = String::from_utf8(bytes.iter().copied().take_while(|x| *x != 0).collect())
+        .context("Token symbol isn't utf-8 string")?;
+    Ok(symbol)
+}
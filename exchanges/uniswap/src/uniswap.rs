@@ -0,0 +1,976 @@
+use crate::types::{
+    from_token_units, parse_symbol, parse_u256, to_token_units, JsonRpcResponse, PendingSwap,
+    PoolId, TransactionReceipt, UniswapPool, NATIVE_DECIMALS,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use dashmap::DashMap;
+use ethers_core::abi::{encode, Token};
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, Bytes, Eip1559TransactionRequest, H256, U256};
+use ethers_core::utils::id;
+use ethers_signers::{LocalWallet, Signer};
+use hyper::header::CONTENT_TYPE;
+use hyper::http::request::Builder;
+use hyper::Uri;
+use itertools::Itertools;
+use mmb_core::exchanges::general::features::{
+    ExchangeFeatures, OpenOrdersType, OrderFeatures, OrderTradeOption, RestFillsFeatures,
+    RestFillsType, WebSocketOptions,
+};
+use mmb_core::exchanges::general::handlers::handle_order_filled::{FillAmount, FillEvent};
+use mmb_core::exchanges::hosts::Hosts;
+use mmb_core::exchanges::rest_client::{
+    ErrorHandler, ErrorHandlerData, RequestType, RestClient, RestHeaders, RestResponse, UriBuilder,
+};
+use mmb_core::exchanges::timeouts::requests_timeout_manager_factory::RequestTimeoutArguments;
+use mmb_core::exchanges::timeouts::timeout_manager::TimeoutManager;
+use mmb_core::exchanges::traits::{
+    ExchangeClientBuilder, ExchangeClientBuilderResult, ExchangeError, HandleOrderFilledCb,
+    OrderCancelledCb,
+};
+use mmb_core::lifecycle::app_lifetime_manager::AppLifetimeManager;
+use mmb_core::settings::{CurrencyPairSetting, ExchangeSettings};
+use mmb_domain::events::{
+    AllowedEventSourceType, EventSourceType, ExchangeBalance, ExchangeEvent, TradeId,
+};
+use mmb_domain::exchanges::symbol::{Precision, Symbol};
+use mmb_domain::market::{
+    CurrencyAliases, CurrencyCode, CurrencyId, CurrencyPair, ExchangeErrorType, ExchangeId,
+    SpecificCurrencyPair,
+};
+use mmb_domain::order::fill::OrderFillType;
+use mmb_domain::order::pool::{OrderRef, OrdersPool};
+use mmb_domain::order::snapshot::{
+    Amount, ExchangeOrderId, OrderExecutionType, OrderInfo, OrderOptions, OrderRole, OrderSide,
+    OrderStatus, Price, UserOrder,
+};
+use mmb_utils::infrastructure::WithExpect;
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+#[derive(Default)]
+pub struct ErrorHandlerUniswap;
+
+impl ErrorHandler for ErrorHandlerUniswap {
+    fn check_spec_rest_error(&self, response: &RestResponse) -> Result<(), ExchangeError> {
+        /// JSON-RPC error object, e.g. `{"code":-32000,"message":"nonce too low"}`
+        #[derive(Deserialize)]
+        struct JsonRpcErrorResponse {
+            error: Option<JsonRpcError>,
+        }
+
+        #[derive(Deserialize)]
+        struct JsonRpcError {
+            code: i64,
+            message: String,
+        }
+
+        let response: JsonRpcErrorResponse =
+            serde_json::from_str(&response.content).map_err(|err| {
+                ExchangeError::parsing(format!("Unable to parse JSON-RPC response: {err:?}"))
+            })?;
+
+        match response.error {
+            Some(error) => Err(ExchangeError::new(
+                ExchangeErrorType::Unknown,
+                error.message,
+                Some(error.code),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn clarify_error_type(&self, error: &ExchangeError) -> ExchangeErrorType {
+        // Node returns only messages of geth and revert reasons of contracts
+        // Details: https://github.com/Uniswap/v3-periphery/blob/main/contracts/libraries/TransferHelper.sol
+        let message = error.message.to_lowercase();
+        if message.contains("insufficient funds") || message.contains("revert: stf") {
+            ExchangeErrorType::InsufficientFunds
+        } else if message.contains("too little received") || message.contains("too much requested")
+        {
+            ExchangeErrorType::InvalidOrder
+        } else if message.contains("rate limit") || message.contains("too many requests") {
+            ExchangeErrorType::RateLimit
+        } else {
+            ExchangeErrorType::Unknown
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RestHeadersUniswap;
+
+impl RestHeaders for RestHeadersUniswap {
+    fn add_specific_headers(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+    ) -> Builder {
+        builder
+    }
+
+    fn add_specific_headers_with_body(
+        &self,
+        builder: Builder,
+        _uri: &Uri,
+        _request_type: RequestType,
+        _body: &[u8],
+    ) -> Builder {
+        builder.header(CONTENT_TYPE, "application/json")
+    }
+}
+
+const EMPTY_RESPONSE_IS_OK: bool = false;
+/// Ethereum mainnet
+const CHAIN_ID: u64 = 1;
+/// Uniswap v3 `QuoterV2` contract
+const QUOTER_ADDRESS: &str = "0x61fFE014bA17989E743c5F6cB21bF9697530B21e";
+/// Uniswap v3 `SwapRouter02` contract
+const ROUTER_ADDRESS: &str = "0x68b3465833fb72A70ecDF485E0e4C7bD8665Fc45";
+/// Deviation from quoted price for market orders, which are sent as swaps with price limit
+const MARKET_ORDER_SLIPPAGE: Decimal = dec!(0.005);
+/// Gas estimation is increased to be sure the swap isn't reverted because of price movement
+const GAS_LIMIT_MULTIPLIER: u64 = 2;
+/// Geth error of already used nonce
+const NONCE_TOO_LOW: &str = "nonce too low";
+
+pub struct Uniswap {
+    pub(crate) settings: ExchangeSettings,
+    pub hosts: Hosts,
+    rest_client: RestClient<ErrorHandlerUniswap, RestHeadersUniswap>,
+    /// Only balances and swap receipts are available without wallet
+    wallet: Option<LocalWallet>,
+    pub(crate) address: Address,
+    /// Nonce of next transaction, it's requested from node before first transaction.
+    /// Lock is held while transaction is sent, so swaps are sent one by one
+    nonce: tokio::sync::Mutex<Option<U256>>,
+    pub(crate) unified_to_specific: RwLock<HashMap<CurrencyPair, SpecificCurrencyPair>>,
+    pools: RwLock<HashMap<CurrencyPair, UniswapPool>>,
+    pub(crate) supported_currencies: DashMap<CurrencyId, CurrencyCode>,
+    /// Swaps sent by the engine by transaction hash
+    pub(crate) pending_swaps: DashMap<ExchangeOrderId, PendingSwap>,
+    /// Swaps are created by REST response, so only cancellation and fill callbacks are used
+    pub(crate) order_cancelled_callback: OrderCancelledCb,
+    pub(crate) handle_order_filled_callback: HandleOrderFilledCb,
+    pub(crate) currency_aliases: CurrencyAliases,
+}
+
+impl Uniswap {
+    pub fn new(settings: ExchangeSettings) -> Uniswap {
+        let (wallet, address) = Self::create_wallet(&settings).with_expect(|| {
+            format!(
+                "Failed to create wallet for {}",
+                settings.exchange_account_id
+            )
+        });
+
+        Self {
+            rest_client: RestClient::with_network_settings(
+                ErrorHandlerData::new(
+                    EMPTY_RESPONSE_IS_OK,
+                    settings.exchange_account_id,
+                    ErrorHandlerUniswap::default(),
+                ),
+                RestHeadersUniswap::default(),
+                &settings.network,
+            ),
+            // Pools usually contain wrapped ETH instead of native one
+            currency_aliases: CurrencyAliases::new(&[("weth", "eth")], &settings.currency_aliases),
+            settings,
+            hosts: Self::make_hosts(),
+            wallet,
+            address,
+            nonce: Default::default(),
+            unified_to_specific: Default::default(),
+            pools: Default::default(),
+            supported_currencies: Default::default(),
+            pending_swaps: Default::default(),
+            order_cancelled_callback: Box::new(|_, _, _| {}),
+            handle_order_filled_callback: Box::new(|_| {}),
+        }
+    }
+
+    /// `secret_key` is hex encoded private key of wallet. If it's empty, `api_key` is used as
+    /// address of wallet without trading
+    fn create_wallet(settings: &ExchangeSettings) -> Result<(Option<LocalWallet>, Address)> {
+        if settings.secret_key.is_empty() {
+            let address = settings
+                .api_key
+                .parse()
+                .with_context(|| format!("Invalid wallet address {}", settings.api_key))?;
+            return Ok((None, address));
+        }
+
+        let wallet = settings
+            .secret_key
+            .trim_start_matches("0x")
+            .parse::<LocalWallet>()
+            .map_err(|err| anyhow!("Invalid Uniswap secret key: {err}"))?
+            .with_chain_id(CHAIN_ID);
+        let address = wallet.address();
+
+        Ok((Some(wallet), address))
+    }
+
+    fn make_hosts() -> Hosts {
+        // Any Ethereum node with JSON-RPC API can be used, there is no websocket API
+        Hosts {
+            web_socket_host: "",
+            web_socket2_host: "",
+            rest_host: "https://ethereum-rpc.publicnode.com",
+        }
+    }
+
+    fn get_pool(&self, currency_pair: CurrencyPair) -> Result<UniswapPool> {
+        self.pools
+            .read()
+            .get(&currency_pair)
+            .cloned()
+            .with_context(|| format!("Unknown Uniswap pool for {currency_pair}"))
+    }
+
+    /// Every JSON-RPC method is sent as separate POST request
+    async fn rpc_request<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: Value,
+        log_args: String,
+    ) -> Result<T, ExchangeError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        })
+        .to_string();
+
+        let uri = UriBuilder::from_path("/").build_uri(self.hosts.rest_uri_host(), false);
+        let response = self
+            .rest_client
+            .post(uri, Some(body.into()), method, log_args)
+            .await?;
+
+        let response: JsonRpcResponse<T> = serde_json::from_str(&response.content)
+            .map_err(|err| ExchangeError::parsing(format!("Unable to parse {method}: {err:?}")))?;
+
+        Ok(response.result)
+    }
+
+    async fn eth_call(&self, to: Address, data: Vec<u8>) -> Result<Bytes, ExchangeError> {
+        let params = json!([{ "to": to, "data": Bytes::from(data) }, "latest"]);
+        self.rpc_request("eth_call", params, "".to_owned()).await
+    }
+
+    async fn request_token_info(&self, token: Address) -> Result<(String, u32)> {
+        let symbol = self.eth_call(token, id("symbol()").to_vec()).await?;
+        let decimals = self.eth_call(token, id("decimals()").to_vec()).await?;
+
+        Ok((parse_symbol(&symbol)?, parse_u256(&decimals)?.low_u32()))
+    }
+
+    /// There is no list of markets on DEX, so pools are taken from specific currency pairs
+    /// of settings
+    pub(super) async fn build_symbols(&self) -> Result<Vec<Arc<Symbol>>> {
+        let currency_pairs = self.settings.currency_pairs.clone().unwrap_or_default();
+
+        let mut symbols = Vec::with_capacity(currency_pairs.len());
+        for currency_pair in currency_pairs {
+            let specific_currency_pair = match currency_pair {
+                CurrencyPairSetting::Specific(specific) => specific,
+                CurrencyPairSetting::Ordinary { base, quote } => bail!(
+                    "Uniswap pool for {base}/{quote} should be specified as `{{base_token}}/{{quote_token}}/{{fee}}`"
+                ),
+            };
+
+            symbols.push(self.build_symbol(&specific_currency_pair).await?);
+        }
+
+        Ok(symbols)
+    }
+
+    async fn build_symbol(&self, specific_currency_pair: &str) -> Result<Arc<Symbol>> {
+        let pool_id = PoolId::parse(specific_currency_pair)?;
+        let (base_id, base_decimals) = self.request_token_info(pool_id.base_token).await?;
+        let (quote_id, quote_decimals) = self.request_token_info(pool_id.quote_token).await?;
+
+        let base = self.currency_aliases.unify(base_id.as_str().into());
+        let quote = self.currency_aliases.unify(quote_id.as_str().into());
+        let amount_tick = Decimal::new(1, base_decimals);
+        let symbol = Symbol::new(
+            false,
+            base_id.as_str().into(),
+            base,
+            quote_id.as_str().into(),
+            quote,
+            None,
+            None,
+            Some(amount_tick),
+            None,
+            None,
+            base,
+            None,
+            // Price of swaps isn't limited by ticks
+            Precision::ByMantissa { precision: 8 },
+            Precision::ByTick { tick: amount_tick },
+        );
+
+        let unified_currency_pair = symbol.currency_pair();
+        self.unified_to_specific
+            .write()
+            .insert(unified_currency_pair, specific_currency_pair.into());
+        self.pools.write().insert(
+            unified_currency_pair,
+            UniswapPool {
+                id: pool_id,
+                base_decimals,
+                quote_decimals,
+            },
+        );
+
+        self.supported_currencies
+            .insert(base_id.as_str().into(), base);
+        self.supported_currencies
+            .insert(quote_id.as_str().into(), quote);
+
+        Ok(Arc::new(symbol))
+    }
+
+    /// Average price of swap of specified base amount according to current state of pool.
+    /// Buy is quoted by exact output and sell by exact input, so amount is always in base currency
+    pub async fn quote_price(
+        &self,
+        currency_pair: CurrencyPair,
+        side: OrderSide,
+        amount: Amount,
+    ) -> Result<Price> {
+        let pool = self.get_pool(currency_pair)?;
+        let (token_in, token_out) = pool.swap_tokens(side);
+        let base_units = to_token_units(amount, pool.base_decimals)?;
+
+        let function = match side {
+            OrderSide::Buy => "quoteExactOutputSingle((address,address,uint256,uint24,uint160))",
+            OrderSide::Sell => "quoteExactInputSingle((address,address,uint256,uint24,uint160))",
+        };
+        let mut data = id(function).to_vec();
+        data.extend(encode(&[Token::Tuple(vec![
+            Token::Address(token_in),
+            Token::Address(token_out),
+            Token::Uint(base_units),
+            Token::Uint(pool.id.fee.into()),
+            Token::Uint(U256::zero()),
+        ])]));
+
+        let quoter = QUOTER_ADDRESS.parse().expect("Invalid quoter address");
+        let result = self.eth_call(quoter, data).await?;
+        let quote_amount = from_token_units(parse_u256(&result)?, pool.quote_decimals)?;
+
+        Ok(quote_amount / amount)
+    }
+
+    pub(super) async fn do_create_order(
+        &self,
+        order: &OrderRef,
+    ) -> Result<ExchangeOrderId, ExchangeError> {
+        let header = order.header();
+
+        let price = match header.options {
+            // Limit price is the worst price of swap, so it works like fill-or-kill order
+            OrderOptions::User(UserOrder::Limit {
+                price,
+                execution_type,
+            }) => match execution_type {
+                OrderExecutionType::MakerOnly => {
+                    return Err(ExchangeError::new(
+                        ExchangeErrorType::InvalidOrder,
+                        "Swaps are always taker orders".to_owned(),
+                        None,
+                    ))
+                }
+                _ => price,
+            },
+            OrderOptions::User(UserOrder::Market) => {
+                let price = self
+                    .quote_price(header.currency_pair, header.side, header.amount)
+                    .await
+                    .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+                match header.side {
+                    OrderSide::Buy => price * (Decimal::ONE + MARKET_ORDER_SLIPPAGE),
+                    OrderSide::Sell => price * (Decimal::ONE - MARKET_ORDER_SLIPPAGE),
+                }
+            }
+            _ => return Err(ExchangeError::unknown("Unexpected order type")),
+        };
+
+        let data = self
+            .encode_swap(header.currency_pair, header.side, header.amount, price)
+            .map_err(|err| ExchangeError::unknown(&format!("{err:?}")))?;
+        let router = ROUTER_ADDRESS.parse().expect("Invalid router address");
+
+        let log_args = format!("Create order for {header:?}");
+        let tx_hash = self.send_transaction(router, data, log_args).await?;
+
+        let exchange_order_id = ExchangeOrderId::from(format!("{tx_hash:?}").as_str());
+        self.pending_swaps.insert(
+            exchange_order_id.clone(),
+            PendingSwap {
+                client_order_id: header.client_order_id.clone(),
+                currency_pair: header.currency_pair,
+                side: header.side,
+                amount: header.amount,
+                price,
+            },
+        );
+
+        Ok(exchange_order_id)
+    }
+
+    /// Call of `SwapRouter02`. Buy is swap of exact output with maximum input,
+    /// sell is swap of exact input with minimum output
+    pub(crate) fn encode_swap(
+        &self,
+        currency_pair: CurrencyPair,
+        side: OrderSide,
+        amount: Amount,
+        price: Price,
+    ) -> Result<Vec<u8>> {
+        let pool = self.get_pool(currency_pair)?;
+        let (token_in, token_out) = pool.swap_tokens(side);
+        let base_units = to_token_units(amount, pool.base_decimals)?;
+        let quote_units = to_token_units(amount * price, pool.quote_decimals)?;
+
+        let function = match side {
+            OrderSide::Buy => {
+                "exactOutputSingle((address,address,uint24,address,uint256,uint256,uint160))"
+            }
+            OrderSide::Sell => {
+                "exactInputSingle((address,address,uint24,address,uint256,uint256,uint160))"
+            }
+        };
+        let mut data = id(function).to_vec();
+        data.extend(encode(&[Token::Tuple(vec![
+            Token::Address(token_in),
+            Token::Address(token_out),
+            Token::Uint(pool.id.fee.into()),
+            Token::Address(self.address),
+            Token::Uint(base_units),
+            Token::Uint(quote_units),
+            Token::Uint(U256::zero()),
+        ])]));
+
+        Ok(data)
+    }
+
+    /// Signs EIP-1559 transaction and sends it to node.
+    /// Response is received before transaction is included into block
+    async fn send_transaction(
+        &self,
+        to: Address,
+        data: Vec<u8>,
+        log_args: String,
+    ) -> Result<H256, ExchangeError> {
+        let wallet = self.wallet.as_ref().ok_or_else(|| {
+            ExchangeError::authentication("Secret key is required for swaps".to_owned())
+        })?;
+
+        let mut nonce_guard = self.nonce.lock().await;
+        let nonce = match *nonce_guard {
+            Some(nonce) => nonce,
+            None => {
+                let params = json!([self.address, "pending"]);
+                self.rpc_request("eth_getTransactionCount", params, "".to_owned())
+                    .await?
+            }
+        };
+
+        let mut request = Eip1559TransactionRequest::new()
+            .from(self.address)
+            .to(to)
+            .data(data)
+            .nonce(nonce)
+            .chain_id(CHAIN_ID);
+
+        let estimate_params = json!([request]);
+        let gas: U256 = self
+            .rpc_request("eth_estimateGas", estimate_params, log_args.clone())
+            .await?;
+        let gas_price: U256 = self
+            .rpc_request("eth_gasPrice", json!([]), "".to_owned())
+            .await?;
+        let priority_fee: U256 = self
+            .rpc_request("eth_maxPriorityFeePerGas", json!([]), "".to_owned())
+            .await?;
+        // Base fee can grow until transaction is included into block
+        request = request
+            .gas(gas * GAS_LIMIT_MULTIPLIER)
+            .max_fee_per_gas(gas_price * 2 + priority_fee)
+            .max_priority_fee_per_gas(priority_fee);
+
+        let tx = TypedTransaction::Eip1559(request);
+        let signature = wallet
+            .sign_transaction_sync(&tx)
+            .map_err(|err| ExchangeError::unknown(&format!("Failed to sign transaction: {err}")))?;
+        let raw_tx = tx.rlp_signed(&signature);
+
+        let result = self
+            .rpc_request("eth_sendRawTransaction", json!([raw_tx]), log_args)
+            .await;
+
+        *nonce_guard = match &result {
+            Ok(_) => Some(nonce + 1),
+            // Nonce is requested again before next transaction
+            Err(error) if error.message.contains(NONCE_TOO_LOW) => None,
+            Err(_) => Some(nonce),
+        };
+
+        result
+    }
+
+    /// Transaction can't be cancelled after it's sent, only replaced by another transaction
+    /// with the same nonce, but it's usually mined earlier than replacement
+    pub(super) fn cancellation_error(&self, exchange_order_id: &ExchangeOrderId) -> ExchangeError {
+        let error_type = match self.pending_swaps.contains_key(exchange_order_id) {
+            true => ExchangeErrorType::Unknown,
+            false => ExchangeErrorType::OrderCompleted,
+        };
+
+        ExchangeError::new(
+            error_type,
+            format!("Swap {exchange_order_id} can't be cancelled"),
+            None,
+        )
+    }
+
+    pub(super) async fn request_receipt(
+        &self,
+        exchange_order_id: &ExchangeOrderId,
+    ) -> Result<Option<TransactionReceipt>, ExchangeError> {
+        let params = json!([exchange_order_id.as_str()]);
+        let log_args = format!("Receipt of {exchange_order_id}");
+
+        self.rpc_request("eth_getTransactionReceipt", params, log_args)
+            .await
+    }
+
+    /// Executed amount and average price of mined swap
+    pub(crate) fn parse_swap_result(
+        &self,
+        swap: &PendingSwap,
+        receipt: &TransactionReceipt,
+    ) -> Result<(Amount, Price)> {
+        let pool = self.get_pool(swap.currency_pair)?;
+        let (base_from, base_to) = match swap.side {
+            OrderSide::Buy => (None, Some(self.address)),
+            OrderSide::Sell => (Some(self.address), None),
+        };
+
+        let base_units = receipt.transferred(pool.id.base_token, base_from, base_to);
+        let quote_units = receipt.transferred(pool.id.quote_token, base_to, base_from);
+        let base_amount = from_token_units(base_units, pool.base_decimals)?;
+        let quote_amount = from_token_units(quote_units, pool.quote_decimals)?;
+
+        if base_amount.is_zero() {
+            bail!("There are no transfers of base token in swap {swap:?}");
+        }
+
+        Ok((base_amount, quote_amount / base_amount))
+    }
+
+    pub(super) fn parse_order_info(
+        &self,
+        order: &OrderRef,
+        exchange_order_id: &ExchangeOrderId,
+        receipt: Option<TransactionReceipt>,
+    ) -> Result<OrderInfo> {
+        let header = order.header();
+        let swap = PendingSwap {
+            client_order_id: header.client_order_id.clone(),
+            currency_pair: header.currency_pair,
+            side: header.side,
+            amount: header.amount,
+            price: order.price(),
+        };
+
+        let (status, filled_amount, average_price, gas_fee) = match receipt {
+            None => (OrderStatus::Created, Amount::ZERO, Price::ZERO, None),
+            Some(receipt) if receipt.is_succeed() => {
+                let (amount, price) = self.parse_swap_result(&swap, &receipt)?;
+                (
+                    OrderStatus::Completed,
+                    amount,
+                    price,
+                    Some(receipt.gas_fee()?),
+                )
+            }
+            Some(receipt) => (
+                OrderStatus::Canceled,
+                Amount::ZERO,
+                Price::ZERO,
+                Some(receipt.gas_fee()?),
+            ),
+        };
+
+        Ok(OrderInfo::new(
+            swap.currency_pair,
+            exchange_order_id.clone(),
+            swap.client_order_id,
+            swap.side,
+            status,
+            swap.price,
+            swap.amount,
+            average_price,
+            filled_amount,
+            gas_fee.map(|_| self.native_currency_code().to_string()),
+            None,
+            gas_fee,
+        ))
+    }
+
+    fn native_currency_code(&self) -> CurrencyCode {
+        self.currency_aliases.unify("eth".into())
+    }
+
+    /// Pending swaps are checked periodically because there are no websocket notifications.
+    /// Reverted swap (e.g. because of price movement) is considered cancelled
+    pub(crate) async fn check_pending_swaps(&self) {
+        let exchange_order_ids = self
+            .pending_swaps
+            .iter()
+            .map(|x| x.key().clone())
+            .collect_vec();
+
+        for exchange_order_id in exchange_order_ids {
+            let receipt = match self.request_receipt(&exchange_order_id).await {
+                Ok(Some(receipt)) => receipt,
+                Ok(None) => continue,
+                Err(error) => {
+                    log::warn!("Failed to get receipt of swap {exchange_order_id}: {error:?}");
+                    continue;
+                }
+            };
+
+            let Some((_, swap)) = self.pending_swaps.remove(&exchange_order_id) else {
+                continue;
+            };
+
+            if let Err(error) = self.handle_receipt(exchange_order_id.clone(), swap, receipt) {
+                log::error!("Failed to handle receipt of swap {exchange_order_id}: {error:?}");
+            }
+        }
+    }
+
+    fn handle_receipt(
+        &self,
+        exchange_order_id: ExchangeOrderId,
+        swap: PendingSwap,
+        receipt: TransactionReceipt,
+    ) -> Result<()> {
+        if !receipt.is_succeed() {
+            log::warn!(
+                "Swap {exchange_order_id} of {} is reverted",
+                swap.client_order_id
+            );
+            (self.order_cancelled_callback)(
+                swap.client_order_id,
+                exchange_order_id,
+                EventSourceType::Rest,
+            );
+            return Ok(());
+        }
+
+        let (amount, price) = self.parse_swap_result(&swap, &receipt)?;
+        // Pool fee is already included into price of swap, so only gas is a commission
+        let fill_event = FillEvent {
+            source_type: EventSourceType::Rest,
+            trade_id: Some(TradeId::from(exchange_order_id.as_str().to_owned())),
+            client_order_id: Some(swap.client_order_id),
+            exchange_order_id,
+            fill_price: price,
+            fill_amount: FillAmount::Total {
+                total_filled_amount: amount,
+            },
+            order_role: Some(OrderRole::Taker),
+            commission_currency_code: Some(self.native_currency_code()),
+            commission_rate: None,
+            commission_amount: Some(receipt.gas_fee()?),
+            fill_type: OrderFillType::UserTrade,
+            special_order_data: None,
+            fill_date: None,
+        };
+
+        (self.handle_order_filled_callback)(fill_event);
+
+        Ok(())
+    }
+
+    pub(super) fn get_pending_orders(&self, currency_pair: Option<CurrencyPair>) -> Vec<OrderInfo> {
+        self.pending_swaps
+            .iter()
+            .filter(|x| currency_pair.map_or(true, |pair| x.currency_pair == pair))
+            .map(|x| {
+                let swap = x.value();
+                OrderInfo::new(
+                    swap.currency_pair,
+                    x.key().clone(),
+                    swap.client_order_id.clone(),
+                    swap.side,
+                    OrderStatus::Created,
+                    swap.price,
+                    swap.amount,
+                    Decimal::ZERO,
+                    Decimal::ZERO,
+                    None,
+                    None,
+                    None,
+                )
+            })
+            .collect_vec()
+    }
+
+    /// Native balance and balances of tokens of configured pools
+    pub(super) async fn get_balances(&self) -> Result<Vec<ExchangeBalance>> {
+        let native_balance: U256 = self
+            .rpc_request(
+                "eth_getBalance",
+                json!([self.address, "latest"]),
+                "".to_owned(),
+            )
+            .await?;
+
+        let mut balances = vec![ExchangeBalance {
+            currency_code: self.native_currency_code(),
+            balance: from_token_units(native_balance, NATIVE_DECIMALS)?,
+        }];
+
+        let pools = self.pools.read().clone();
+        let tokens = pools
+            .iter()
+            .flat_map(|(currency_pair, pool)| {
+                let codes = currency_pair.to_codes();
+                [
+                    (pool.id.base_token, codes.base, pool.base_decimals),
+                    (pool.id.quote_token, codes.quote, pool.quote_decimals),
+                ]
+            })
+            .unique_by(|(token, _, _)| *token)
+            .collect_vec();
+
+        for (token, currency_code, decimals) in tokens {
+            let mut data = id("balanceOf(address)").to_vec();
+            data.extend(encode(&[Token::Address(self.address)]));
+            let result = self.eth_call(token, data).await?;
+
+            balances.push(ExchangeBalance {
+                currency_code,
+                balance: from_token_units(parse_u256(&result)?, decimals)?,
+            });
+        }
+
+        // Wrapped and native ETH are unified to the same currency
+        Ok(balances
+            .into_iter()
+            .into_group_map_by(|x| x.currency_code)
+            .into_iter()
+            .map(|(currency_code, balances)| ExchangeBalance {
+                currency_code,
+                balance: balances.iter().map(|x| x.balance).sum(),
+            })
+            .collect_vec())
+    }
+}
+
+pub struct UniswapBuilder;
+
+impl ExchangeClientBuilder for UniswapBuilder {
+    fn create_exchange_client(
+        &self,
+        exchange_settings: ExchangeSettings,
+        _events_channel: broadcast::Sender<ExchangeEvent>,
+        _lifetime_manager: Arc<AppLifetimeManager>,
+        _timeout_manager: Arc<TimeoutManager>,
+        _orders: Arc<OrdersPool>,
+    ) -> ExchangeClientBuilderResult {
+        ExchangeClientBuilderResult {
+            client: Box::new(Uniswap::new(exchange_settings)),
+            features: ExchangeFeatures::new(
+                OpenOrdersType::AllCurrencyPair,
+                RestFillsFeatures::new(RestFillsType::GetOrderInfo),
+                OrderFeatures {
+                    maker_only: false,
+                    supports_get_order_info_by_client_order_id: false,
+                    cancellation_response_from_rest_only_for_errors: true,
+                    creation_response_from_rest_only_for_errors: false,
+                    order_was_completed_error_for_cancellation: true,
+                    supports_already_cancelled_order: false,
+                    supports_stop_loss_order: false,
+                    supports_mark_price_trigger: false,
+                    supports_index_price_trigger: false,
+                    supports_amend_order: false,
+                    supports_iceberg_order: false,
+                },
+                OrderTradeOption {
+                    supports_trade_time: false,
+                    supports_trade_incremented_id: false,
+                    supports_get_prints: false,
+                    supports_tick_direction: false,
+                    supports_my_trades_from_time: false,
+                },
+                WebSocketOptions {
+                    execution_notification: false,
+                    cancellation_notification: false,
+                    supports_ping_pong: false,
+                    supports_subscription_response: false,
+                },
+                EMPTY_RESPONSE_IS_OK,
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+                AllowedEventSourceType::default(),
+            ),
+        }
+    }
+
+    fn get_timeout_arguments(&self) -> RequestTimeoutArguments {
+        // Public nodes usually allow tens of requests per second
+        RequestTimeoutArguments::from_requests_per_minute(600)
+    }
+
+    fn get_exchange_id(&self) -> ExchangeId {
+        "Uniswap".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::types::Log;
+    use mmb_domain::market::ExchangeAccountId;
+    use mmb_domain::order::snapshot::ClientOrderId;
+
+    const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    const WALLET: &str = "0x00000000000000000000000000000000000000aa";
+
+    fn create_uniswap() -> Uniswap {
+        let exchange_account_id: ExchangeAccountId = "Uniswap_0".parse().expect("in test");
+        let settings =
+            ExchangeSettings::new_short(exchange_account_id, WALLET.into(), "".into(), false);
+
+        let uniswap = Uniswap::new(settings);
+
+        uniswap.pools.write().insert(
+            CurrencyPair::from_codes("eth".into(), "usdc".into()),
+            UniswapPool {
+                id: PoolId::parse(&format!("{WETH}/{USDC}/500")).expect("in test"),
+                base_decimals: 18,
+                quote_decimals: 6,
+            },
+        );
+
+        uniswap
+    }
+
+    fn transfer_log(token: &str, from: &str, to: &str, value: u64) -> Log {
+        let topic = |address: &str| H256::from(address.parse::<Address>().expect("in test"));
+
+        Log {
+            address: token.parse().expect("in test"),
+            topics: vec![
+                H256::from(ethers_core::utils::keccak256(
+                    b"Transfer(address,address,uint256)",
+                )),
+                topic(from),
+                topic(to),
+            ],
+            data: encode(&[Token::Uint(value.into())]).into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_pool_id() {
+        let pool_id = PoolId::parse(&format!("{WETH}/{USDC}/3000")).expect("in test");
+
+        assert_eq!(pool_id.base_token, WETH.parse().expect("in test"));
+        assert_eq!(pool_id.quote_token, USDC.parse().expect("in test"));
+        assert_eq!(pool_id.fee, 3000);
+
+        assert!(PoolId::parse("WETH/USDC").is_err());
+    }
+
+    #[test]
+    fn encode_sell_swap() {
+        let uniswap = create_uniswap();
+        let currency_pair = CurrencyPair::from_codes("eth".into(), "usdc".into());
+
+        let data = uniswap
+            .encode_swap(currency_pair, OrderSide::Sell, dec!(1.5), dec!(2000))
+            .expect("in test");
+
+        // Selector of `exactInputSingle` and 7 words of parameters
+        assert_eq!(data[..4], [0x04, 0xe4, 0x5a, 0xaf]);
+        assert_eq!(data.len(), 4 + 7 * 32);
+        // Amount in is 1.5 WETH and minimum amount out is 3000 USDC
+        assert_eq!(
+            U256::from_big_endian(&data[4 + 4 * 32..4 + 5 * 32]),
+            U256::from(1_500_000_000_000_000_000u64)
+        );
+        assert_eq!(
+            U256::from_big_endian(&data[4 + 5 * 32..4 + 6 * 32]),
+            U256::from(3_000_000_000u64)
+        );
+    }
+
+    #[test]
+    fn parse_swap_result_from_transfer_logs() {
+        let uniswap = create_uniswap();
+        let pool_address = "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640";
+        let receipt = TransactionReceipt {
+            status: U256::one(),
+            gas_used: U256::from(150_000),
+            effective_gas_price: U256::from(20_000_000_000u64),
+            logs: vec![
+                transfer_log(USDC, pool_address, WALLET, 2_990_000_000),
+                transfer_log(WETH, WALLET, pool_address, 1_500_000_000_000_000_000),
+            ],
+        };
+        let swap = PendingSwap {
+            client_order_id: ClientOrderId::unique_id(),
+            currency_pair: CurrencyPair::from_codes("eth".into(), "usdc".into()),
+            side: OrderSide::Sell,
+            amount: dec!(1.5),
+            price: dec!(1990),
+        };
+
+        let (amount, price) = uniswap.parse_swap_result(&swap, &receipt).expect("in test");
+
+        assert_eq!(amount, dec!(1.5));
+        assert_eq!(price.round_dp(6), dec!(1993.333333));
+        assert_eq!(receipt.gas_fee().expect("in test"), dec!(0.003));
+    }
+
+    #[test]
+    fn json_rpc_error_is_detected() {
+        let response = RestResponse::new(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"insufficient funds for gas * price + value"}}"#
+                .to_owned(),
+            hyper::StatusCode::OK,
+        );
+
+        let error = ErrorHandlerUniswap
+            .check_spec_rest_error(&response)
+            .expect_err("in test");
+
+        assert_eq!(error.code, Some(-32000));
+        assert_eq!(
+            ErrorHandlerUniswap.clarify_error_type(&error),
+            ExchangeErrorType::InsufficientFunds
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use crate::postgres_db::PgPool;
+use anyhow::{Context, Result};
+
+pub async fn is_account_history_imported(pool: &PgPool, exchange_account_id: &str) -> Result<bool> {
+    let sql = "select exists(select 1 from account_history_imports where exchange_account_id = $1)";
+    let row = pool
+        .0
+        .get()
+        .await?
+        .query_one(sql, &[&exchange_account_id])
+        .await
+        .context("checking imported account history")?;
+    Ok(row.get(0))
+}
+
+/// Marks account history as imported, so it isn't requested from exchange on next launches
+pub async fn save_account_history_imported(pool: &PgPool, exchange_account_id: &str) -> Result<()> {
+    let sql = "insert into account_history_imports(exchange_account_id) values ($1)
+                         on conflict (exchange_account_id) do nothing";
+    pool.0
+        .get()
+        .await?
+        .execute(sql, &[&exchange_account_id])
+        .await
+        .context("saving account history import marker")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::postgres_db::account_history::{
+        is_account_history_imported, save_account_history_imported,
+    };
+    use crate::postgres_db::tests::{get_database_url, PgPoolMutex};
+
+    const EXCHANGE_ACCOUNT_ID: &str = "Binance_0";
+
+    async fn init_test() -> PgPoolMutex {
+        let pool_mutex = PgPoolMutex::create(&get_database_url(), 1).await;
+        let connection = pool_mutex.pool.get_connection_expected().await;
+        connection
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS account_history_imports (
+                    exchange_account_id text PRIMARY KEY,
+                    insert_time timestamp WITH TIME ZONE NOT NULL DEFAULT now()
+                );
+                TRUNCATE account_history_imports;",
+            )
+            .await
+            .expect("TRUNCATE account_history_imports");
+
+        drop(connection);
+        pool_mutex
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn account_history_is_imported_only_after_marker_saved() {
+        let pool_mutex = init_test().await;
+        let pool = &pool_mutex.pool;
+
+        let imported = is_account_history_imported(pool, EXCHANGE_ACCOUNT_ID)
+            .await
+            .expect("in test");
+        assert!(!imported);
+
+        save_account_history_imported(pool, EXCHANGE_ACCOUNT_ID)
+            .await
+            .expect("in test");
+        // repeated marker doesn't fail
+        save_account_history_imported(pool, EXCHANGE_ACCOUNT_ID)
+            .await
+            .expect("in test");
+
+        let imported = is_account_history_imported(pool, EXCHANGE_ACCOUNT_ID)
+            .await
+            .expect("in test");
+        assert!(imported);
+
+        let other_imported = is_account_history_imported(pool, "Binance_1")
+            .await
+            .expect("in test");
+        assert!(!other_imported);
+    }
+}
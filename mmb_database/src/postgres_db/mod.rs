@@ -1,7 +1,9 @@
+pub mod account_history;
 pub mod cleanup_database;
 pub mod events;
 pub mod live_ranges;
 pub mod migrator;
+pub mod orders;
 pub mod tests;
 
 use anyhow::{Context, Result};
@@ -0,0 +1,31 @@
+use crate::postgres_db::PgPool;
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+
+/// Last saved snapshots of orders of exchange account which weren't finished at the moment
+/// of saving. Only snapshots saved during last `depth_hours` are considered
+pub async fn load_not_finished_orders(
+    pool: &PgPool,
+    exchange_account_id: &str,
+    depth_hours: u64,
+) -> Result<Vec<JsonValue>> {
+    let sql = "select json from (
+                   select distinct on (json #>> '{header, client_order_id}') json
+                   from orders
+                   where json #>> '{header, exchange_account_id}' = $1
+                       and insert_time > now() - make_interval(hours => $2)
+                   order by json #>> '{header, client_order_id}', id desc
+               ) as last_snapshots
+               where json #>> '{props, finished_time}' is null";
+
+    let depth_hours = i32::try_from(depth_hours).context("depth_hours is too big")?;
+    let rows = pool
+        .0
+        .get()
+        .await?
+        .query(sql, &[&exchange_account_id, &depth_hours])
+        .await
+        .context("loading not finished orders")?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
@@ -1,4 +1,4 @@
-use jsonrpc_core::{Error, Result};
+use jsonrpc_core::{BoxFuture, Error, Result};
 use jsonrpc_derive::rpc;
 
 #[cfg(unix)]
@@ -22,20 +22,144 @@ pub trait MmbRpc {
 
     #[rpc(name = "stats")]
     fn stats(&self) -> Result<String>;
+
+    #[rpc(name = "transfers")]
+    fn transfers(&self) -> Result<String>;
+
+    /// Register transfer of inventory between exchanges, which is started only after confirmation
+    #[rpc(name = "initiate_transfer")]
+    fn initiate_transfer(&self, request: String) -> Result<String>;
+
+    #[rpc(name = "confirm_transfer")]
+    fn confirm_transfer(&self, transfer_id: u64) -> Result<String>;
+
+    #[rpc(name = "cancel_transfer")]
+    fn cancel_transfer(&self, transfer_id: u64) -> Result<String>;
+
+    #[rpc(name = "unwinds")]
+    fn unwinds(&self) -> Result<String>;
+
+    /// Start reducing position to zero over horizon, passive orders are escalated to aggressive ones
+    /// near deadline
+    #[rpc(name = "start_unwind")]
+    fn start_unwind(&self, request: String) -> Result<String>;
+
+    #[rpc(name = "cancel_unwind")]
+    fn cancel_unwind(&self, unwind_id: u64) -> Result<String>;
+
+    #[rpc(name = "strategy_parameters")]
+    fn strategy_parameters(&self) -> Result<String>;
+
+    /// Change tunable parameters of strategy. Values are applied between decision cycles
+    #[rpc(name = "set_strategy_parameters")]
+    fn set_strategy_parameters(&self, parameters: String) -> Result<String>;
+
+    /// Balance reservations of all exchange accounts
+    #[rpc(name = "diagnostics.dump_reservations")]
+    fn dump_reservations(&self) -> Result<String>;
+
+    /// Top `depth` levels of local order book of market
+    #[rpc(name = "diagnostics.dump_book")]
+    fn dump_book(
+        &self,
+        exchange_account_id: String,
+        currency_pair: String,
+        depth: usize,
+    ) -> Result<String>;
+
+    /// Compare top `depth` levels of local order book of market with REST depth snapshot
+    #[rpc(name = "diagnostics.probe_book")]
+    fn probe_book(
+        &self,
+        exchange_account_id: String,
+        currency_pair: String,
+        depth: usize,
+    ) -> BoxFuture<Result<String>>;
+
+    /// Last reports of periodic sampling of order books consistency
+    #[rpc(name = "diagnostics.book_consistency")]
+    fn book_consistency(&self) -> Result<String>;
+
+    /// Statistics of REST and WebSocket round trips to exchanges over last samples of latency probe
+    #[rpc(name = "diagnostics.latency_report")]
+    fn latency_report(&self) -> Result<String>;
+
+    /// Markets of connected exchanges matching fuzzy currency pair, e.g. "btc-usdt" or "XBTUSD",
+    /// with their unified and specific symbols, precisions and top of order book
+    #[rpc(name = "search_symbols")]
+    fn search_symbols(&self, query: String) -> Result<String>;
+
+    /// Cancel all quotes of market and hold quoting for cool-down period of pull triggers,
+    /// all markets of exchange account are affected if currency pair isn't specified
+    #[rpc(name = "pull_quotes")]
+    fn pull_quotes(
+        &self,
+        exchange_account_id: String,
+        currency_pair: Option<String>,
+        reason: String,
+    ) -> Result<String>;
+
+    /// Last fired pull triggers with their causes and hold times
+    #[rpc(name = "pull_triggers_report")]
+    fn pull_triggers_report(&self) -> Result<String>;
+
+    /// Requests and pre-reserved groups of timeout managers of all exchange accounts
+    #[rpc(name = "diagnostics.dump_timeouts")]
+    fn dump_timeouts(&self) -> Result<String>;
+
+    /// Running and recently finished spawned futures
+    #[rpc(name = "diagnostics.dump_tasks")]
+    fn dump_tasks(&self) -> Result<String>;
+
+    /// Write archive with sanitized engine state and last `log_lines` lines of logs
+    /// for attaching to bug reports, returns path of archive
+    #[rpc(name = "export_support_bundle")]
+    fn export_support_bundle(&self, log_lines: usize) -> Result<String>;
+
+    /// Write hash chained audit file of order events in range `[from, to)` of RFC 3339 times
+    /// for compliance reviews, returns path, records count and hash of the last record
+    #[rpc(name = "export_audit")]
+    fn export_audit(&self, from: String, to: String) -> Result<String>;
 }
 
 pub enum ErrorCode {
     StopperIsNone = 1,
     UnableToSendSignal = 2,
     FailedToSaveNewConfig = 3,
+    TransferRequestFailed = 4,
+    StrategyParametersRejected = 5,
+    DiagnosticsFailed = 6,
+    UnwindRequestFailed = 7,
+    AuditExportFailed = 8,
+    PullQuotesFailed = 9,
 }
 
-pub fn server_side_error(code: ErrorCode) -> Error {
-    let reason = match code {
+fn error_reason(code: &ErrorCode) -> &'static str {
+    match code {
         ErrorCode::StopperIsNone => "Server stopper is none",
         ErrorCode::UnableToSendSignal => "Unable to send signal",
         ErrorCode::FailedToSaveNewConfig => "Failed to save new config",
-    };
+        ErrorCode::TransferRequestFailed => "Failed to handle transfer request",
+        ErrorCode::StrategyParametersRejected => "Strategy parameters are rejected",
+        ErrorCode::DiagnosticsFailed => "Failed to collect diagnostics",
+        ErrorCode::UnwindRequestFailed => "Failed to handle unwind request",
+        ErrorCode::AuditExportFailed => "Failed to export audit file",
+        ErrorCode::PullQuotesFailed => "Failed to pull quotes",
+    }
+}
+
+pub fn server_side_error(code: ErrorCode) -> Error {
+    let reason = error_reason(&code);
     log::error!("Rest API error: {}", reason);
     Error::new(jsonrpc_core::ErrorCode::ServerError(code as i64))
 }
+
+pub fn server_side_error_with_details(code: ErrorCode, details: &str) -> Error {
+    let reason = error_reason(&code);
+    log::error!("Rest API error: {reason}: {details}");
+    Error {
+        code: jsonrpc_core::ErrorCode::ServerError(code as i64),
+        message: format!("{reason}: {details}"),
+        data: None,
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+
+/// Decimal arithmetic which returns error with operands in context instead of panic
+/// on division by zero or overflow
+pub trait CheckedDecimal: Sized {
+    fn try_add(self, rhs: Self) -> Result<Self>;
+    fn try_sub(self, rhs: Self) -> Result<Self>;
+    fn try_mul(self, rhs: Self) -> Result<Self>;
+    fn try_div(self, rhs: Self) -> Result<Self>;
+}
+
+impl CheckedDecimal for Decimal {
+    fn try_add(self, rhs: Self) -> Result<Self> {
+        self.checked_add(rhs)
+            .ok_or_else(|| anyhow!("Overflow in addition {self} + {rhs}"))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self> {
+        self.checked_sub(rhs)
+            .ok_or_else(|| anyhow!("Overflow in subtraction {self} - {rhs}"))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self> {
+        self.checked_mul(rhs)
+            .ok_or_else(|| anyhow!("Overflow in multiplication {self} * {rhs}"))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self> {
+        if rhs.is_zero() {
+            return Err(anyhow!("Division by zero {self} / {rhs}"));
+        }
+
+        self.checked_div(rhs)
+            .ok_or_else(|| anyhow!("Overflow in division {self} / {rhs}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn division_by_zero_is_error() {
+        assert_eq!(dec!(3).try_div(dec!(2)).expect("in test"), dec!(1.5));
+
+        let error = dec!(3).try_div(dec!(0)).expect_err("in test");
+        assert_eq!(error.to_string(), "Division by zero 3 / 0");
+    }
+
+    #[test]
+    fn overflow_is_error() {
+        assert!(Decimal::MAX.try_mul(dec!(2)).is_err());
+        assert!(Decimal::MAX.try_add(Decimal::MAX).is_err());
+        assert!(Decimal::MIN.try_sub(Decimal::MAX).is_err());
+    }
+}
@@ -3,6 +3,7 @@ use bitflags::bitflags;
 use futures::executor::block_on;
 use futures::Future;
 use futures::FutureExt;
+use serde::Serialize;
 use std::fmt::Arguments;
 use std::fmt::{Debug, Display};
 use std::panic;
@@ -15,6 +16,7 @@ use crate::logger::init_logger;
 use crate::logger::print_info;
 use crate::panic::handle_future_panic;
 use crate::panic::set_panic_hook;
+use crate::spawned_futures::SpawnedFutureRegistration;
 use crate::OPERATION_CANCELED_MSG;
 
 bitflags! {
@@ -64,7 +66,7 @@ impl FutureOutcome {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
 pub enum CompletionReason {
     CompletedSuccessfully,
     Canceled,
@@ -95,12 +97,16 @@ pub fn spawn_future_timed(
     );
 
     log::info!("Future {action_name} with id {future_id} started");
+    let registration = SpawnedFutureRegistration::new(&action_name, future_id);
 
     tokio::spawn(async move {
-        timeout(duration, action).await.unwrap_or_else(|_| {
+        let future_outcome = timeout(duration, action).await.unwrap_or_else(|_| {
             log::error!("Time in form of {duration:?} is over, but future {action_name} is not completed yet");
             FutureOutcome::new(action_name, future_id, CompletionReason::TimeExpired)
-        })
+        });
+        registration.finish(future_outcome.completion_reason);
+
+        future_outcome
     })
 }
 
@@ -117,15 +123,22 @@ pub fn spawn_future(
     let future_id = Uuid::new_v4();
 
     log::info!("Future '{action_name}' with id '{future_id}' started");
+    let registration = SpawnedFutureRegistration::new(&action_name, future_id);
 
-    tokio::spawn(handle_action_outcome(
-        action_name,
-        future_id,
-        flags,
-        action,
-        graceful_shutdown_spawner,
-        cancellation_token,
-    ))
+    tokio::spawn(async move {
+        let future_outcome = handle_action_outcome(
+            action_name,
+            future_id,
+            flags,
+            action,
+            graceful_shutdown_spawner,
+            cancellation_token,
+        )
+        .await;
+        registration.finish(future_outcome.completion_reason);
+
+        future_outcome
+    })
 }
 
 /// Spawn standalone future with logging and error, panic and cancellation handling.
@@ -144,16 +157,20 @@ pub fn spawn_future_standalone(
     let thread_id = Uuid::new_v4();
 
     log::info!("Thread {action_name} with id {thread_id} started");
+    let registration = SpawnedFutureRegistration::new(&action_name, thread_id);
 
     std::thread::spawn(move || {
-        block_on(handle_action_outcome(
+        let future_outcome = block_on(handle_action_outcome(
             action_name,
             thread_id,
             flags,
             action,
             graceful_shutdown_spawner,
             cancellation_token,
-        ))
+        ));
+        registration.finish(future_outcome.completion_reason);
+
+        future_outcome
     })
 }
 
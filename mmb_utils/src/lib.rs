@@ -17,6 +17,7 @@
 )]
 
 pub mod cancellation_token;
+pub mod checked_decimal;
 pub mod decimal_inverse_sign;
 pub mod impl_id;
 pub mod impl_mocks;
@@ -25,6 +26,7 @@ pub mod infrastructure;
 pub mod logger;
 pub mod panic;
 pub mod send_expected;
+pub mod spawned_futures;
 pub mod time;
 pub mod value_to_decimal;
 
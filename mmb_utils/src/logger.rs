@@ -65,6 +65,20 @@ impl Debug for LoggerType {
     }
 }
 
+/// Paths of files which are written by configured file appenders
+pub fn get_log_file_paths() -> Result<Vec<PathBuf>> {
+    let loggers = get_loggers()?;
+
+    Ok(loggers
+        .info
+        .into_iter()
+        .filter_map(|logger| match logger {
+            LoggerType::File(path) => Some(PathBuf::from(path)),
+            _ => None,
+        })
+        .collect())
+}
+
 fn get_loggers() -> Result<Loggers> {
     let file = fs::File::open(get_log_config_path()).context("Failed to open log config file")?;
     let mut config: BTreeMap<String, Value> =
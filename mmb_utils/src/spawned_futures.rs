@@ -0,0 +1,133 @@
+use crate::infrastructure::CompletionReason;
+use crate::DateTime;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use uuid::Uuid;
+
+/// Count of finished futures kept for diagnostics, older ones are forgotten
+const MAX_FINISHED_FUTURES_COUNT: usize = 200;
+
+static SPAWNED_FUTURES: Lazy<Mutex<SpawnedFutures>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub enum SpawnedFutureState {
+    Running,
+    Finished(CompletionReason),
+    /// Future was dropped before completion, e.g. its task was aborted
+    Dropped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpawnedFutureInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub state: SpawnedFutureState,
+    pub start_time: DateTime,
+    pub finish_time: Option<DateTime>,
+}
+
+/// Registry of futures spawned through `spawn_future*` functions with their states
+#[derive(Default)]
+struct SpawnedFutures {
+    running: HashMap<Uuid, SpawnedFutureInfo>,
+    finished: VecDeque<SpawnedFutureInfo>,
+}
+
+impl SpawnedFutures {
+    fn finish(&mut self, id: Uuid, state: SpawnedFutureState) {
+        let Some(mut info) = self.running.remove(&id) else { return; };
+
+        info.state = state;
+        info.finish_time = Some(Utc::now());
+
+        if self.finished.len() == MAX_FINISHED_FUTURES_COUNT {
+            let _ = self.finished.pop_front();
+        }
+        self.finished.push_back(info);
+    }
+}
+
+/// Running futures ordered by start time followed by recently finished ones
+pub fn spawned_futures() -> Vec<SpawnedFutureInfo> {
+    let spawned_futures = SPAWNED_FUTURES.lock();
+
+    let mut running = spawned_futures
+        .running
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    running.sort_by_key(|x| x.start_time);
+
+    running
+        .into_iter()
+        .chain(spawned_futures.finished.iter().cloned())
+        .collect()
+}
+
+/// Keeps future registered as running until it's finished or dropped
+pub(crate) struct SpawnedFutureRegistration {
+    id: Uuid,
+}
+
+impl SpawnedFutureRegistration {
+    pub(crate) fn new(name: &str, id: Uuid) -> Self {
+        let info = SpawnedFutureInfo {
+            id,
+            name: name.to_owned(),
+            state: SpawnedFutureState::Running,
+            start_time: Utc::now(),
+            finish_time: None,
+        };
+        let _ = SPAWNED_FUTURES.lock().running.insert(id, info);
+
+        SpawnedFutureRegistration { id }
+    }
+
+    pub(crate) fn finish(self, completion_reason: CompletionReason) {
+        SPAWNED_FUTURES
+            .lock()
+            .finish(self.id, SpawnedFutureState::Finished(completion_reason));
+    }
+}
+
+impl Drop for SpawnedFutureRegistration {
+    fn drop(&mut self) {
+        // Does nothing if future is already finished
+        SPAWNED_FUTURES
+            .lock()
+            .finish(self.id, SpawnedFutureState::Dropped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn future_state_is_updated_on_finish_and_drop() {
+        let finished_id = Uuid::new_v4();
+        let finished = SpawnedFutureRegistration::new("finished", finished_id);
+        let dropped_id = Uuid::new_v4();
+        let dropped = SpawnedFutureRegistration::new("dropped", dropped_id);
+
+        let state = |id| {
+            spawned_futures()
+                .into_iter()
+                .find(|x| x.id == id)
+                .map(|x| x.state)
+        };
+        assert_eq!(state(finished_id), Some(SpawnedFutureState::Running));
+
+        finished.finish(CompletionReason::Error);
+        drop(dropped);
+
+        assert_eq!(
+            state(finished_id),
+            Some(SpawnedFutureState::Finished(CompletionReason::Error))
+        );
+        assert_eq!(state(dropped_id), Some(SpawnedFutureState::Dropped));
+    }
+}
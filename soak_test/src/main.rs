@@ -0,0 +1,184 @@
+#![deny(
+    non_ascii_idents,
+    non_shorthand_field_patterns,
+    no_mangle_generic_items,
+    overflowing_literals,
+    path_statements,
+    unused_allocation,
+    unused_comparisons,
+    unused_parens,
+    while_true,
+    trivial_numeric_casts,
+    unused_extern_crates,
+    unused_import_braces,
+    unused_qualifications,
+    unused_must_use,
+    clippy::unwrap_used
+)]
+
+mod settings;
+mod stats;
+
+use crate::settings::SoakSettings;
+use crate::stats::SoakStats;
+use anyhow::Result;
+use chrono::Utc;
+use mmb_core::infrastructure::{spawn_by_timer, spawn_future_ok};
+use mmb_core::lifecycle::launcher::{launch_trading_engine, EngineBuildConfig, InitSettings};
+use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_core::settings::{AppSettings, CoreSettings, CurrencyPairSetting, ExchangeSettings};
+use mmb_domain::events::ExchangeEvent;
+use mmb_domain::market::ExchangeAccountId;
+use mmb_utils::infrastructure::SpawnFutureFlags;
+use mock::feed::start_feed_server;
+use mock::mock_exchange::{mock_currency_pair, MockExchangeBuilder, MOCK_EXCHANGE_ID};
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use strategies::example_strategy::{ExampleStrategy, ExampleStrategySettings};
+use tokio::sync::broadcast::error::RecvError;
+
+const DEFAULT_SETTINGS_PATH: &str = "soak_test.toml";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let settings_path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_SETTINGS_PATH.to_owned());
+    let soak_settings = SoakSettings::load(&settings_path)?;
+
+    let stats = Arc::new(SoakStats::default());
+    let ws_url = start_feed_server(&soak_settings.feed).await?;
+
+    let exchange_account_id = ExchangeAccountId::new(MOCK_EXCHANGE_ID, 0);
+    let engine_config = EngineBuildConfig::new(vec![Box::new(MockExchangeBuilder {
+        ws_url,
+        observer: Some(stats.clone()),
+        latency: HashMap::from([(exchange_account_id, soak_settings.latency.clone())]),
+    })]);
+    let init_settings =
+        InitSettings::Directly(create_app_settings(&soak_settings, exchange_account_id));
+
+    let engine = launch_trading_engine(&engine_config, init_settings).await?;
+    let context = engine.context();
+
+    let strategy_settings = &engine.settings().strategy;
+    let strategy = ExampleStrategy::new(
+        strategy_settings.exchange_account_id,
+        mock_currency_pair(),
+        strategy_settings.spread,
+        strategy_settings.max_amount,
+        context.clone(),
+    );
+    engine.start_disposition_executor(strategy)?;
+
+    start_events_subscriber(&context, stats.clone());
+    start_reporter(&context, stats, &soak_settings);
+    if soak_settings.duration_secs > 0 {
+        start_stop_timer(&context, soak_settings.duration_secs);
+    }
+
+    engine.run().await;
+
+    Ok(())
+}
+
+fn create_app_settings(
+    soak_settings: &SoakSettings,
+    exchange_account_id: ExchangeAccountId,
+) -> AppSettings<ExampleStrategySettings> {
+    let currency_pair = mock_currency_pair().to_codes();
+
+    let mut exchange_settings =
+        ExchangeSettings::new_short(exchange_account_id, String::new(), String::new(), false);
+    exchange_settings.currency_pairs = Some(vec![CurrencyPairSetting::Ordinary {
+        base: currency_pair.base,
+        quote: currency_pair.quote,
+    }]);
+
+    AppSettings {
+        strategy: ExampleStrategySettings {
+            spread: soak_settings.spread,
+            currency_pair: CurrencyPairSetting::Ordinary {
+                base: currency_pair.base,
+                quote: currency_pair.quote,
+            },
+            max_amount: soak_settings.max_amount,
+            exchange_account_id,
+            refresh_level_on_fill: None,
+            feature_recorder: None,
+            protective_orders: None,
+            order_book_price_bucket: None,
+            degraded_mode: None,
+            max_order_age: None,
+            auto_sizing: None,
+        },
+        core: CoreSettings {
+            exchanges: vec![exchange_settings],
+            ..Default::default()
+        },
+    }
+}
+
+/// Consumer of engine events channel like strategies, it measures delivery latency of events
+/// and counts events lost because of channel overflow
+fn start_events_subscriber(context: &Arc<EngineContext>, stats: Arc<SoakStats>) {
+    let mut events_receiver = context.get_events_channel();
+
+    let action = async move {
+        loop {
+            match events_receiver.recv().await {
+                Ok(ExchangeEvent::OrderBookEvent(order_book_event)) => stats
+                    .events_latency
+                    .record_since(order_book_event.creation_time, Utc::now()),
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    stats.lagged_events.fetch_add(skipped, Ordering::Relaxed);
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    };
+    spawn_future_ok(
+        "Soak test events subscriber",
+        SpawnFutureFlags::STOP_BY_TOKEN,
+        action,
+    );
+}
+
+fn start_reporter(context: &Arc<EngineContext>, stats: Arc<SoakStats>, settings: &SoakSettings) {
+    let context = Arc::downgrade(context);
+    let period = Duration::from_secs(settings.report_interval_secs.max(1));
+
+    spawn_by_timer(
+        "Soak test report",
+        Duration::ZERO,
+        period,
+        SpawnFutureFlags::STOP_BY_TOKEN,
+        move || {
+            let context = context.upgrade();
+            let stats = stats.clone();
+            async move {
+                if let Some(context) = context {
+                    stats.report(&context);
+                }
+            }
+        },
+    );
+}
+
+fn start_stop_timer(context: &Arc<EngineContext>, duration_secs: u64) {
+    let lifetime_manager = context.lifetime_manager.clone();
+
+    let action = async move {
+        tokio::time::sleep(Duration::from_secs(duration_secs)).await;
+        lifetime_manager.spawn_graceful_shutdown("Soak test duration elapsed");
+    };
+    spawn_future_ok(
+        "Soak test stop timer",
+        SpawnFutureFlags::STOP_BY_TOKEN,
+        action,
+    );
+}
@@ -0,0 +1,44 @@
+use anyhow::{Context, Result};
+use mock::feed::FeedSettings;
+use mock::latency::LatencySettings;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use std::fs;
+
+/// Load profile of soak test. All fields are optional, so empty file runs default profile
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SoakSettings {
+    #[serde(flatten)]
+    pub feed: FeedSettings,
+    /// Total test duration, test runs until interrupted if `0`
+    pub duration_secs: u64,
+    pub report_interval_secs: u64,
+    pub spread: Decimal,
+    pub max_amount: Decimal,
+    /// Simulated network latencies of mock exchange, there are no delays by default
+    pub latency: LatencySettings,
+}
+
+impl Default for SoakSettings {
+    fn default() -> Self {
+        SoakSettings {
+            feed: FeedSettings::default(),
+            duration_secs: 3600,
+            report_interval_secs: 60,
+            spread: dec!(0.001),
+            max_amount: dec!(0.01),
+            latency: LatencySettings::default(),
+        }
+    }
+}
+
+impl SoakSettings {
+    pub fn load(path: &str) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("Unable to read file {path}"))?;
+
+        toml::from_str(&content).with_context(|| format!("Unable to parse soak settings {path}"))
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::Utc;
+use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_utils::DateTime;
+use mock::mock_exchange::MockExchangeObserver;
+use parking_lot::Mutex;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Latency samples in microseconds collected between reports
+#[derive(Default)]
+pub struct LatencyRecorder {
+    samples: Mutex<Vec<u64>>,
+}
+
+impl LatencyRecorder {
+    pub fn record_since(&self, start: DateTime, end: DateTime) {
+        let micros = (end - start).num_microseconds().unwrap_or(i64::MAX).max(0);
+        self.samples.lock().push(micros as u64);
+    }
+
+    fn take_summary(&self) -> LatencySummary {
+        let mut samples = std::mem::take(&mut *self.samples.lock());
+        samples.sort_unstable();
+
+        let percentile = |p: usize| match samples.is_empty() {
+            true => 0,
+            false => samples[(samples.len() - 1) * p / 100],
+        };
+
+        LatencySummary {
+            count: samples.len(),
+            p50: percentile(50),
+            p99: percentile(99),
+            max: samples.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+struct LatencySummary {
+    count: usize,
+    p50: u64,
+    p99: u64,
+    max: u64,
+}
+
+impl Display for LatencySummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "count {} p50 {}us p99 {}us max {}us",
+            self.count, self.p50, self.p99, self.max
+        )
+    }
+}
+
+/// Measurements shared between mock exchange, events subscriber and reporter
+#[derive(Default)]
+pub struct SoakStats {
+    /// From sending message by websocket server to handling it by exchange client
+    pub websocket_latency: LatencyRecorder,
+    /// From creation of order book event to receiving it from engine events channel
+    pub events_latency: LatencyRecorder,
+    /// From sending fill by websocket server to completion of fill handling by engine
+    pub fill_latency: LatencyRecorder,
+    pub book_messages: AtomicU64,
+    pub fills: AtomicU64,
+    pub created_orders: AtomicU64,
+    pub cancelled_orders: AtomicU64,
+    /// Events skipped by subscriber because engine events channel was overflowed
+    pub lagged_events: AtomicU64,
+    initial_rss_kb: Mutex<Option<u64>>,
+}
+
+impl SoakStats {
+    pub fn report(&self, context: &EngineContext) {
+        let rss_kb = read_rss_kb();
+        let initial_rss_kb = *self.initial_rss_kb.lock().get_or_insert(rss_kb);
+
+        log::info!(
+            "Soak report: rss {rss_kb}kB, growth {}kB",
+            rss_kb as i64 - initial_rss_kb as i64
+        );
+        log::info!(
+            "Book messages {}, fills {}, created orders {}, cancelled orders {}, lagged events {}",
+            self.book_messages.load(Ordering::Relaxed),
+            self.fills.load(Ordering::Relaxed),
+            self.created_orders.load(Ordering::Relaxed),
+            self.cancelled_orders.load(Ordering::Relaxed),
+            self.lagged_events.load(Ordering::Relaxed),
+        );
+        log::info!(
+            "Websocket latency: {}",
+            self.websocket_latency.take_summary()
+        );
+        log::info!("Events latency: {}", self.events_latency.take_summary());
+        log::info!("Fill latency: {}", self.fill_latency.take_summary());
+
+        // Sizes of orders caches should stay bounded while orders are completed
+        for exchange in context.exchanges.iter() {
+            let orders = &exchange.orders;
+            log::info!(
+                "Orders pool of {}: by client id {}, by exchange id {}, not finished {}",
+                exchange.key(),
+                orders.cache_by_client_id.len(),
+                orders.cache_by_exchange_id.len(),
+                orders.not_finished.len(),
+            );
+        }
+    }
+}
+
+impl MockExchangeObserver for SoakStats {
+    fn book_received(&self, sent_at: DateTime) {
+        self.websocket_latency.record_since(sent_at, Utc::now());
+        self.book_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn fill_received(&self, sent_at: DateTime) {
+        self.websocket_latency.record_since(sent_at, Utc::now());
+    }
+
+    fn fill_handled(&self, sent_at: DateTime) {
+        self.fill_latency.record_since(sent_at, Utc::now());
+        self.fills.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn order_created(&self) {
+        self.created_orders.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn order_cancelled(&self) {
+        self.cancelled_orders.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Resident set size of current process, `0` if it isn't available (e.g. not on Linux)
+fn read_rss_kb() -> u64 {
+    fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmRSS:"))
+                .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+        })
+        .unwrap_or_default()
+}
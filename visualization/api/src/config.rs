@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use mmb_domain::decimal_serialization::DecimalSerialization;
 use mmb_domain::order::snapshot::Amount;
+use mmb_domain::reporting_precision::ReportingPrecision;
 
 use crate::types::ExchangeId;
 
@@ -10,6 +12,11 @@ pub struct AppConfig {
     pub database_url: String,
     pub refresh_data_interval_ms: u64,
     pub markets: Vec<Market>,
+    #[serde(default)]
+    pub reporting_precision: ReportingPrecision,
+    /// Format of decimal values in API responses
+    #[serde(default)]
+    pub decimal_serialization: DecimalSerialization,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
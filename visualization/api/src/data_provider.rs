@@ -115,6 +115,12 @@ impl DataProvider {
                         }
                         Some(desired_amount) => {
                             liquidity_data.desired_amount = desired_amount;
+                            let price_bucket = sub
+                                .price_bucket
+                                .filter(|x| x.is_sign_positive() && !x.is_zero());
+                            if let Some(bucket_size) = price_bucket {
+                                liquidity_data.order_book.snapshot.aggregate(bucket_size);
+                            }
                             let message = NewLiquidityDataMessage {
                                 subscription: sub,
                                 data: liquidity_data,
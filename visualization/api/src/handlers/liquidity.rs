@@ -1,14 +1,70 @@
 use std::sync::Arc;
 
 use actix_web::web::Data;
-use paperclip::actix::{api_v2_operation, web::Json};
+use chrono::{DateTime, Duration, Utc};
+use paperclip::actix::{
+    api_v2_operation,
+    web::{self, Json},
+    Apiv2Schema,
+};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::error::AppError;
+use crate::services::data_provider::heat_map::{HeatMapSample, HeatMapService};
 use crate::services::market_settings::MarketSettingsService;
 
+const HEAT_MAP_SAMPLES_LIMIT: i32 = 2000;
+
 #[api_v2_operation(tags(Liquidity), summary = "Get supported exchanges")]
 pub async fn supported_exchanges(
     market_settings_service: Data<Arc<MarketSettingsService>>,
 ) -> Json<Value> {
     Json(json!({ "supportedExchanges": &market_settings_service.supported_exchanges }))
 }
+
+#[derive(Deserialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatMapQuery {
+    exchange_name: String,
+    currency_code_pair: String,
+    /// Start of depth history, last hour by default
+    from: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatMapGetResponse {
+    exchange_name: String,
+    currency_code_pair: String,
+    samples: Vec<HeatMapSample>,
+}
+
+#[api_v2_operation(tags(Liquidity), summary = "Get liquidity heat map")]
+pub async fn heat_map(
+    query: web::Query<HeatMapQuery>,
+    heat_map_service: Data<Arc<HeatMapService>>,
+) -> Result<Json<HeatMapGetResponse>, AppError> {
+    let from = query
+        .from
+        .unwrap_or_else(|| Utc::now() - Duration::hours(1));
+    let samples = heat_map_service
+        .list(
+            &query.exchange_name,
+            &query.currency_code_pair,
+            from,
+            HEAT_MAP_SAMPLES_LIMIT,
+        )
+        .await;
+    match samples {
+        Ok(samples) => Ok(Json(HeatMapGetResponse {
+            exchange_name: query.exchange_name.clone(),
+            currency_code_pair: query.currency_code_pair.clone(),
+            samples,
+        })),
+        Err(e) => {
+            log::error!("list liquidity heat map {e:?}");
+            Err(AppError::InternalServerError)
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use actix_web::{web, Error, HttpRequest, Responder};
 use actix_web_actors::ws::start;
+use mmb_domain::decimal_serialization::DecimalSerialization;
 
 use crate::services::token::TokenService;
 use crate::ws::actors::ws_client_session::WsClientSession;
@@ -8,6 +9,11 @@ pub async fn ws_client(
     req: HttpRequest,
     stream: web::Payload,
     token_service: web::Data<TokenService>,
+    decimal_serialization: web::Data<DecimalSerialization>,
 ) -> Result<impl Responder, Error> {
-    start(WsClientSession::new(token_service), &req, stream)
+    start(
+        WsClientSession::new(token_service, **decimal_serialization),
+        &req,
+        stream,
+    )
 }
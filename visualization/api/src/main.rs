@@ -55,6 +55,8 @@ async fn main() -> std::io::Result<()> {
         enforcer,
         config.markets,
         config.refresh_data_interval_ms,
+        config.reporting_precision,
+        config.decimal_serialization,
     )
     .await
 }
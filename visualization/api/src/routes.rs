@@ -34,9 +34,13 @@ pub(crate) fn http_routes(app: &mut web::ServiceConfig) {
                     .route("/validate", post().to(handlers::configuration::validate)),
             )
             .route("/explanations", get().to(handlers::explanation::get))
-            .service(web::scope("/liquidity").route(
-                "/supported-exchanges",
-                get().to(handlers::liquidity::supported_exchanges),
-            )),
+            .service(
+                web::scope("/liquidity")
+                    .route(
+                        "/supported-exchanges",
+                        get().to(handlers::liquidity::supported_exchanges),
+                    )
+                    .route("/heat-map", get().to(handlers::liquidity::heat_map)),
+            ),
     );
 }
@@ -7,6 +7,8 @@ use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::{App, HttpServer};
 use casbin::Enforcer;
+use mmb_domain::decimal_serialization::DecimalSerialization;
+use mmb_domain::reporting_precision::ReportingPrecision;
 use paperclip::actix::OpenApiExt;
 use paperclip::v2::models::DefaultApiRaw;
 use sqlx::postgres::PgPoolOptions;
@@ -20,6 +22,7 @@ use crate::services::account::AccountService;
 use crate::services::auth::AuthService;
 use crate::services::data_provider::balances::BalancesService;
 use crate::services::data_provider::explanation::ExplanationService;
+use crate::services::data_provider::heat_map::HeatMapService;
 use crate::services::market_settings::MarketSettingsService;
 use crate::services::settings::SettingsService;
 use crate::services::token::TokenService;
@@ -39,6 +42,8 @@ pub async fn start(
     enforcer: Enforcer,
     markets: Vec<Market>,
     refresh_data_interval_ms: u64,
+    reporting_precision: ReportingPrecision,
+    decimal_serialization: DecimalSerialization,
 ) -> std::io::Result<()> {
     log::info!("Starting server at {address}");
     let connection_pool = PgPoolOptions::new()
@@ -48,7 +53,7 @@ pub async fn start(
         .expect("Unable to connect to DB");
 
     let liquidity_service = LiquidityService::new(connection_pool.clone());
-    let balances_service = BalancesService::new(connection_pool.clone());
+    let balances_service = BalancesService::new(connection_pool.clone(), reporting_precision);
     let new_data_listener = NewDataListener::default().start();
     let error_listener = ErrorListener::default().start();
     let account_service = AccountService::default();
@@ -62,7 +67,8 @@ pub async fn start(
     let auth_service = Arc::new(AuthService::new(enforcer));
     let market_settings_service = Arc::new(MarketSettingsService::from(markets));
     let settings_service = Arc::new(SettingsService::new(connection_pool.clone()));
-    let explanation_service = Arc::new(ExplanationService::new(connection_pool));
+    let explanation_service = Arc::new(ExplanationService::new(connection_pool.clone()));
+    let heat_map_service = Arc::new(HeatMapService::new(connection_pool));
 
     let data_provider = DataProvider::new(
         subscription_manager,
@@ -99,6 +105,8 @@ pub async fn start(
             .app_data(Data::new(market_settings_service.clone()))
             .app_data(Data::new(settings_service.clone()))
             .app_data(Data::new(explanation_service.clone()))
+            .app_data(Data::new(heat_map_service.clone()))
+            .app_data(Data::new(decimal_serialization))
             .with_json_spec_at("/swagger-spec")
             .with_swagger_ui_at("/swagger-ui")
             .build()
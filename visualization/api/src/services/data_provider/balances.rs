@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 
 use mmb_domain::order::snapshot::Amount;
+use mmb_domain::reporting_precision::ReportingPrecision;
 
 use crate::services::data_provider::model::EventRecord;
 use crate::types::{CurrencyCode, ExchangeId};
@@ -13,6 +14,7 @@ use crate::types::{CurrencyCode, ExchangeId};
 #[derive(Clone)]
 pub struct BalancesService {
     pool: Pool<Postgres>,
+    reporting_precision: ReportingPrecision,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -34,8 +36,11 @@ pub struct BalancesData {
 }
 
 impl BalancesService {
-    pub fn new(pool: Pool<Postgres>) -> Self {
-        Self { pool }
+    pub fn new(pool: Pool<Postgres>, reporting_precision: ReportingPrecision) -> Self {
+        Self {
+            pool,
+            reporting_precision,
+        }
     }
 
     pub async fn get_balances(&self) -> Result<BalancesData, sqlx::Error> {
@@ -54,8 +59,10 @@ impl BalancesService {
                 it.1.into_iter().for_each(|it2| {
                     let balance_data = BalanceData {
                         exchange_id: it.0.clone(),
+                        value: self
+                            .reporting_precision
+                            .round_amount(it2.0.as_str().into(), it2.1),
                         currency_code: it2.0,
-                        value: it2.1,
                     };
                     exchange_balances.push(balance_data);
                 })
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use paperclip::actix::Apiv2Schema;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+
+use mmb_domain::order::snapshot::{Amount, Price};
+
+use crate::services::data_provider::model::EventTimedRecord;
+use crate::types::{CurrencyPair, ExchangeId};
+
+/// Data Provider for liquidity heat maps
+#[derive(Clone)]
+pub struct HeatMapService {
+    pool: Pool<Postgres>,
+}
+
+/// Resting amounts by price buckets relative to middle price: `i`-th bucket of asks starts from
+/// `mid_price * (1 + i * bucket_step)`, `i`-th bucket of bids starts from `mid_price * (1 - i * bucket_step)`
+#[derive(Serialize, Deserialize, Apiv2Schema)]
+#[serde(rename_all(deserialize = "snake_case", serialize = "camelCase"))]
+pub struct HeatMapSample {
+    pub time: DateTime<Utc>,
+    pub mid_price: Price,
+    pub bucket_step: Decimal,
+    pub asks: Vec<Amount>,
+    pub bids: Vec<Amount>,
+}
+
+impl HeatMapService {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(
+        &self,
+        exchange_id: &ExchangeId,
+        currency_pair: &CurrencyPair,
+        from: DateTime<Utc>,
+        limit: i32,
+    ) -> anyhow::Result<Vec<HeatMapSample>> {
+        let sql = include_str!("../sql/get_liquidity_heat_map.sql");
+        let records = sqlx::query_as::<Postgres, EventTimedRecord>(sql)
+            .bind(exchange_id)
+            .bind(currency_pair)
+            .bind(from)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let list = records
+            .into_iter()
+            .map(|it| {
+                serde_json::from_value(it.json).unwrap_or_else(|_| {
+                    panic!(
+                        "Incorrect database liquidity heat map json data. ID: {:?}",
+                        it.id
+                    )
+                })
+            })
+            .collect_vec();
+        Ok(list)
+    }
+}
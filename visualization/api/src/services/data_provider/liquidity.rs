@@ -1,11 +1,14 @@
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 
-use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::decimal_serialization::decimal;
+use mmb_domain::order::snapshot::{Amount, Price, SortedOrderData};
+use mmb_domain::order_book::aggregated_order_book::AggregatedOrderBook;
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
 
 use crate::services::data_provider::model::EventRecord;
 use crate::types::{CurrencyPair, ExchangeId};
-use serde_aux::prelude::deserialize_number_from_string;
 
 /// Data Provider for Liquidity
 #[derive(Clone)]
@@ -31,11 +34,11 @@ pub struct OrderBookRecord {
 #[derive(Debug, Clone, Deserialize)]
 pub struct LiquidityOrderRecord {
     pub client_order_id: String,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub price: Price,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub amount: Amount,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub remaining_amount: Amount,
     pub side: LiquidityOrderSide,
 }
@@ -52,23 +55,50 @@ pub struct OrderBookSnapshotRecord {
     pub bids: Vec<PriceLevelRecord>,
 }
 
+impl OrderBookSnapshotRecord {
+    /// Aggregate price levels into price buckets of specified size
+    pub fn aggregate(&mut self, bucket_size: Price) {
+        fn to_sorted_data(levels: &[PriceLevelRecord]) -> SortedOrderData {
+            levels.iter().map(|x| (x.price, x.amount)).collect()
+        }
+
+        fn to_records<'a>(
+            levels: impl Iterator<Item = (&'a Price, &'a Amount)>,
+        ) -> Vec<PriceLevelRecord> {
+            levels
+                .map(|(&price, &amount)| PriceLevelRecord { price, amount })
+                .collect()
+        }
+
+        let snapshot = LocalOrderBookSnapshot::new(
+            to_sorted_data(&self.asks),
+            to_sorted_data(&self.bids),
+            Utc::now(),
+        );
+        let aggregated = AggregatedOrderBook::from_snapshot(&snapshot, bucket_size);
+
+        self.asks = to_records(aggregated.get_asks_price_levels());
+        self.bids = to_records(aggregated.get_bids_price_levels());
+    }
+}
+
 #[derive(Deserialize, Clone)]
 pub struct OrderBookOrderRecord;
 
 #[derive(Deserialize, Clone)]
 pub struct PriceLevelRecord {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub price: Price,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub amount: Amount,
 }
 
 #[derive(Deserialize, Clone)]
 pub struct TransactionRecord {
     pub side: TransactionOrderSide,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub price: Price,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub amount: Amount,
     pub hedged: Option<String>,
     pub status: String,
@@ -95,9 +125,9 @@ pub struct MarketIdRecord {
 
 #[derive(Deserialize, Clone)]
 pub struct TransactionTradesRecord {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub price: Price,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
+    #[serde(deserialize_with = "decimal::deserialize")]
     pub amount: Amount,
     pub exchange_id: ExchangeId,
     pub exchange_order_id: String,
@@ -1,4 +1,5 @@
 pub mod balances;
 pub mod explanation;
+pub mod heat_map;
 pub mod liquidity;
 pub(crate) mod model;
@@ -0,0 +1,6 @@
+SELECT id, insert_time, json FROM liquidity_heat_maps
+WHERE ((json ->> 'exchange_id')::text = $1)
+  AND ((json ->> 'currency_pair')::text = $2)
+  AND insert_time >= $3
+ORDER BY insert_time
+limit $4
@@ -8,6 +8,8 @@ use actix_web_actors::ws::{Message, ProtocolError, WebsocketContext};
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use mmb_domain::decimal_serialization::DecimalSerialization;
+
 use crate::services::token::TokenService;
 use crate::ws::broker_messages::{
     BalancesResponseMessage, ClientConnected, ClientDisconnected, ClientErrorResponseMessage,
@@ -22,6 +24,8 @@ pub struct WsClientSession {
     subscribed_liquidity: Option<LiquiditySubscription>,
     subscribed_balances: Option<BalancesSubscription>,
     token_service: Data<TokenService>,
+    /// Format of decimal values in responses
+    decimal_serialization: DecimalSerialization,
     is_auth: bool,
     hb: Instant,
 }
@@ -30,12 +34,16 @@ const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(3);
 
 impl WsClientSession {
-    pub fn new(token_service: Data<TokenService>) -> Self {
+    pub fn new(
+        token_service: Data<TokenService>,
+        decimal_serialization: DecimalSerialization,
+    ) -> Self {
         Self {
             subscriptions: HashSet::new(),
             subscribed_liquidity: None,
             subscribed_balances: None,
             token_service,
+            decimal_serialization,
             is_auth: false,
             hb: Instant::now(),
         }
@@ -97,7 +105,10 @@ impl Handler<LiquidityResponseMessage> for WsClientSession {
             }
         };
 
-        match serde_json::to_value(&msg.body) {
+        match self
+            .decimal_serialization
+            .scope(|| serde_json::to_value(&msg.body))
+        {
             Ok(body) => {
                 send_message(ctx, msg.command, body);
             }
@@ -127,7 +138,10 @@ impl Handler<BalancesResponseMessage> for WsClientSession {
             }
         };
 
-        match serde_json::to_value(&msg.body) {
+        match self
+            .decimal_serialization
+            .scope(|| serde_json::to_value(&msg.body))
+        {
             Ok(body) => {
                 send_message(ctx, msg.command, body);
             }
@@ -7,6 +7,7 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 
+use mmb_domain::decimal_serialization::{decimal, option_decimal};
 use mmb_domain::order::snapshot::{Amount, Price};
 
 use crate::services::data_provider::liquidity::{
@@ -25,6 +26,7 @@ pub struct LiquidityResponseBody {
 pub struct OrderStateAndTransactions {
     pub exchange_name: String,
     pub currency_code_pair: String,
+    #[serde(with = "decimal")]
     pub desired_amount: Amount,
     pub sell: Orders,
     pub buy: Orders,
@@ -40,7 +42,9 @@ pub struct Orders {
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Order {
+    #[serde(with = "decimal")]
     pub amount: Amount,
+    #[serde(with = "decimal")]
     pub price: Price,
 }
 
@@ -49,7 +53,9 @@ pub struct Order {
 pub struct Transaction {
     pub id: String,
     pub date_time: String,
+    #[serde(with = "decimal")]
     pub price: Price,
+    #[serde(with = "decimal")]
     pub amount: Amount,
     pub hedged: Option<String>,
     pub profit_loss_pct: Option<String>,
@@ -63,7 +69,9 @@ pub struct Transaction {
 pub struct Trade {
     pub exchange_name: String,
     pub date_time: String,
+    #[serde(with = "decimal")]
     pub price: Price,
+    #[serde(with = "decimal")]
     pub amount: Amount,
     pub exchange_order_id: String,
     pub side: Option<TransactionTradeSide>,
@@ -72,12 +80,19 @@ pub struct Trade {
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Indicators {
+    #[serde(with = "decimal")]
     pub volume_pct: Decimal,
+    #[serde(with = "decimal")]
     pub bid_pct: Decimal,
+    #[serde(with = "decimal")]
     pub ask_pct: Decimal,
+    #[serde(with = "option_decimal")]
     pub spread: Option<Decimal>,
+    #[serde(with = "option_decimal")]
     pub total_volume: Option<Amount>,
+    #[serde(with = "option_decimal")]
     pub total_bid: Option<Amount>,
+    #[serde(with = "option_decimal")]
     pub total_ask: Option<Amount>,
 }
 
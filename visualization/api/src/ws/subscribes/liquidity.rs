@@ -3,6 +3,8 @@ use std::hash::{Hash, Hasher};
 
 use serde::Deserialize;
 
+use mmb_domain::order::snapshot::Price;
+
 use crate::types::{CurrencyPair, ExchangeId};
 use crate::ws::subscribes::Subscription;
 
@@ -11,6 +13,9 @@ use crate::ws::subscribes::Subscription;
 pub struct LiquiditySubscription {
     pub exchange_id: ExchangeId,
     pub currency_pair: CurrencyPair,
+    /// If set, order book levels are aggregated into price buckets of this size
+    #[serde(default)]
+    pub price_bucket: Option<Price>,
 }
 
 impl Subscription for LiquiditySubscription {
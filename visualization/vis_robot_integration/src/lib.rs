@@ -16,9 +16,11 @@
     clippy::unwrap_used
 )]
 
+mod liquidity_heat_map;
 mod liquidity_order_book;
 mod transaction;
 
+use crate::liquidity_heat_map::LiquidityHeatMapSampler;
 use crate::transaction::{
     transaction_service, TransactionSnapshot, TransactionStatus, TransactionTrade,
 };
@@ -37,7 +39,8 @@ pub async fn start_visualization_data_saving(
     strategy_name: &'static str,
 ) -> Result<(), Error> {
     let mut snapshots_service = LocalSnapshotsService::default();
-    let mut events_rx = ctx.get_events_channel();
+    let mut heat_map_sampler = LiquidityHeatMapSampler::default();
+    let mut events_rx = ctx.get_conflated_events_receiver();
 
     let stop_token = ctx.lifetime_manager.stop_token();
     while !stop_token.is_cancellation_requested() {
@@ -83,6 +86,10 @@ pub async fn start_visualization_data_saving(
                     market_account_id,
                 )
                 .context("in start_visualization_data_saving")?;
+
+                heat_map_sampler
+                    .save_sample_if_needed(&ctx, &snapshots_service, market_account_id)
+                    .context("in start_visualization_data_saving")?;
             }
         }
     }
@@ -0,0 +1,124 @@
+use anyhow::Context;
+use mmb_core::lifecycle::trading_engine::EngineContext;
+use mmb_core::misc::time::time_manager;
+use mmb_core::order_book::local_snapshot_service::LocalSnapshotsService;
+use mmb_database::impl_event;
+use mmb_domain::market::{CurrencyPair, ExchangeId, MarketAccountId, MarketId};
+use mmb_domain::order::snapshot::{Amount, Price};
+use mmb_domain::order_book::local_order_book_snapshot::LocalOrderBookSnapshot;
+use mmb_utils::DateTime;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SAMPLING_PERIOD_SEC: i64 = 5;
+/// Size of price bucket relative to middle price
+const BUCKET_STEP: Decimal = dec!(0.001);
+const BUCKETS_COUNT: usize = 50;
+
+/// Resting amount in order book aggregated by price buckets relative to middle price.
+/// Amount of `i`-th bucket of asks is in range `[mid * (1 + i * step), mid * (1 + (i + 1) * step))`,
+/// for bids it's `(mid * (1 - (i + 1) * step), mid * (1 - i * step)]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiquidityHeatMapSample {
+    exchange_id: ExchangeId,
+    currency_pair: CurrencyPair,
+    time: DateTime,
+    mid_price: Price,
+    bucket_step: Decimal,
+    asks: Vec<Amount>,
+    bids: Vec<Amount>,
+}
+
+impl_event!(LiquidityHeatMapSample, "liquidity_heat_maps");
+
+/// Samples order books for depth history heat maps not more often than `SAMPLING_PERIOD_SEC` per market
+#[derive(Default)]
+pub(crate) struct LiquidityHeatMapSampler {
+    last_sample_time: HashMap<MarketId, DateTime>,
+}
+
+impl LiquidityHeatMapSampler {
+    pub(crate) fn save_sample_if_needed(
+        &mut self,
+        ctx: &EngineContext,
+        snapshots_service: &LocalSnapshotsService,
+        market_account_id: Option<MarketAccountId>,
+    ) -> anyhow::Result<()> {
+        let market_id = match market_account_id {
+            Some(market_account_id) => market_account_id.market_id(),
+            None => return Ok(()),
+        };
+
+        let now = time_manager::now();
+        if let Some(last_sample_time) = self.last_sample_time.get(&market_id) {
+            if now - *last_sample_time < chrono::Duration::seconds(SAMPLING_PERIOD_SEC) {
+                return Ok(());
+            }
+        }
+
+        let sample = match snapshots_service
+            .get_snapshot(market_id)
+            .and_then(|snapshot| create_heat_map_sample(snapshot, market_id, now))
+        {
+            Some(sample) => sample,
+            None => return Ok(()),
+        };
+
+        self.last_sample_time.insert(market_id, now);
+        ctx.event_recorder
+            .save(sample)
+            .context("failed saving liquidity heat map sample")
+    }
+}
+
+fn create_heat_map_sample(
+    order_book_snapshot: &LocalOrderBookSnapshot,
+    market_id: MarketId,
+    time: DateTime,
+) -> Option<LiquidityHeatMapSample> {
+    let (top_ask, _) = order_book_snapshot.get_top_ask()?;
+    let (top_bid, _) = order_book_snapshot.get_top_bid()?;
+    let mid_price = (top_ask + top_bid) / dec!(2);
+    if mid_price.is_zero() {
+        return None;
+    }
+
+    let bucket_index = |deviation: Decimal| (deviation / BUCKET_STEP).floor().to_usize();
+    let ask_bucket = |(&price, &amount): (&Price, &Amount)| {
+        (bucket_index(price / mid_price - Decimal::ONE), amount)
+    };
+    let bid_bucket = |(&price, &amount): (&Price, &Amount)| {
+        (bucket_index(Decimal::ONE - price / mid_price), amount)
+    };
+
+    Some(LiquidityHeatMapSample {
+        exchange_id: market_id.exchange_id,
+        currency_pair: market_id.currency_pair,
+        time,
+        mid_price,
+        bucket_step: BUCKET_STEP,
+        asks: aggregate_by_buckets(order_book_snapshot.get_asks_price_levels().map(ask_bucket)),
+        bids: aggregate_by_buckets(order_book_snapshot.get_bids_price_levels().map(bid_bucket)),
+    })
+}
+
+/// Price levels should be sorted from top of order book
+fn aggregate_by_buckets(levels: impl Iterator<Item = (Option<usize>, Amount)>) -> Vec<Amount> {
+    let mut buckets = Vec::new();
+    for (index, amount) in levels {
+        let index = match index {
+            Some(index) if index < BUCKETS_COUNT => index,
+            _ => break,
+        };
+
+        if buckets.len() <= index {
+            buckets.resize(index + 1, Amount::ZERO);
+        }
+        buckets[index] += amount;
+    }
+
+    buckets
+}